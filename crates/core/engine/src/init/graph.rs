@@ -79,6 +79,7 @@ pub mod task_ids {
     pub const URI_REGISTRATION: TaskId = TaskId::new("uri_registration");
     pub const DEV_DETECT:       TaskId = TaskId::new("dev_detect");
     pub const FILE_ASSOCIATION: TaskId = TaskId::new("file_association");
+    pub const RECENT_PROJECTS:  TaskId = TaskId::new("recent_projects");
 }
 
 /// Errors that can occur during initialization
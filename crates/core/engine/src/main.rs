@@ -224,7 +224,16 @@ fn main() {
         steps::uri_registration::run
     );
 
-    // Task 11: Project file association prompt (depends on global context)
+    // Task 11: Recent projects (depends on engine context and runtime)
+    init_task!(
+        graph,
+        RECENT_PROJECTS,
+        "Recent Projects",
+        [ENGINE_CONTEXT, RUNTIME],
+        steps::recent_projects::run
+    );
+
+    // Task 12: Project file association prompt (depends on global context)
     // (disabled — handled by the OS on first launch)
 
     // Execute the initialization graph
@@ -1,4 +1,4 @@
-//! App data step: config directory initialization.
+//! App data step: config directory initialization and crash reports.
 
 use crate::appdata;
 use crate::init::{InitContext, InitError};
@@ -9,5 +9,7 @@ pub fn run(_ctx: &mut InitContext) -> Result<(), InitError> {
     tracing::debug!("Themes directory: {:?}", appdata.themes_dir);
     tracing::debug!("Config directory: {:?}", appdata.config_dir);
     tracing::debug!("Config file: {:?}", appdata.config_file);
+
+    engine_state::install_crash_reporter(appdata.appdata_dir.join("crashes"));
     Ok(())
 }
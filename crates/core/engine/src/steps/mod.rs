@@ -11,6 +11,7 @@ pub mod discord;
 pub mod engine_context;
 pub mod file_association;
 pub mod logging;
+pub mod recent_projects;
 pub mod runtime;
 pub mod set_global;
 pub mod settings;
@@ -0,0 +1,23 @@
+//! Recent projects step: record every opened project in the recent list.
+
+use crate::init::{InitContext, InitError};
+use engine_state::ProjectOpened;
+
+pub fn run(ctx: &mut InitContext) -> Result<(), InitError> {
+    let engine_context = ctx
+        .engine_context
+        .clone()
+        .ok_or(InitError::MissingContext("Engine context not initialized"))?;
+    let rt = ctx
+        .runtime
+        .as_ref()
+        .ok_or(InitError::MissingContext("Runtime not initialized"))?;
+
+    let opened = engine_context.subscribe::<ProjectOpened>();
+    rt.spawn(async move {
+        while let Ok(event) = opened.recv().await {
+            engine_context.record_project_opened(&event.path);
+        }
+    });
+    Ok(())
+}
@@ -5,7 +5,7 @@
 //! WgpuSurface is available.
 
 use crate::scene::SceneDb;
-use crate::subsystems::render::{EditorCameraState, HelioRenderer, RenderMetrics};
+use crate::subsystems::render::{CaptureView, EditorCameraState, HelioRenderer, RenderMetrics};
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
@@ -68,6 +68,39 @@ impl GpuRenderer {
             r.render_frame(device, queue, view, width, height, format);
         }
         self.frame_count += 1;
+        self.publish_render_metrics();
+    }
+
+    /// Share this frame's statistics through the engine context so tools
+    /// like Mission Control can read them without locking the renderer.
+    fn publish_render_metrics(&self) {
+        let (Some(context), Some(metrics)) = (
+            engine_state::EngineContext::global(),
+            self.get_render_metrics(),
+        ) else {
+            return;
+        };
+        context.set_render_metrics(engine_state::RenderMetrics {
+            frame_time_ms: metrics.frame_time_ms,
+            draw_calls: metrics.draw_calls,
+            triangles: metrics.triangles_drawn,
+            gpu_mem_used: (metrics.memory_usage_mb as f64 * 1024.0 * 1024.0) as u64,
+            vram_total: 0,
+        });
+    }
+
+    /// Render `capture` into an offscreen `view`. Returns `false` until the
+    /// viewport has rendered its first frame.
+    pub fn render_capture(
+        &mut self,
+        view: &wgpu::TextureView,
+        width: u32,
+        height: u32,
+        capture: &CaptureView,
+    ) -> bool {
+        self.helio_renderer
+            .as_mut()
+            .is_some_and(|r| r.render_capture(view, width, height, capture))
     }
 
     pub fn get_fps(&self) -> f32 {
@@ -240,3 +273,11 @@ impl GpuRenderer {
 
 unsafe impl Send for GpuRenderer {}
 unsafe impl Sync for GpuRenderer {}
+
+impl Drop for GpuRenderer {
+    fn drop(&mut self) {
+        if let Some(context) = engine_state::EngineContext::global() {
+            context.clear_render_metrics();
+        }
+    }
+}
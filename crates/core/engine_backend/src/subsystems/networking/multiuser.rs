@@ -195,6 +195,26 @@ pub enum ClientMessage {
         peer_id: String,
         data: String, // serialized ReplicationMessage
     },
+    // Session roles. Clients without role support fail to parse these and
+    // ignore them, so they keep working as Editors.
+    RoleAnnounce {
+        session_id: String,
+        peer_id: String,
+        protocol_version: u32,
+    },
+    SetRole {
+        session_id: String,
+        peer_id: String,        // Peer making the change
+        target_peer_id: String, // Peer whose role changes
+        role: String,           // SessionRole id
+    },
+    PermissionDenied {
+        session_id: String,
+        peer_id: String,
+        target_peer_id: String,
+        operation: String,
+        reason: String,
+    },
     Ping,
 }
 
@@ -210,6 +230,8 @@ pub enum ServerMessage {
         join_token: Option<String>,
         #[serde(default)]
         participant_profiles: Option<Vec<PeerProfile>>,
+        #[serde(default)]
+        host_peer_id: Option<String>, // Absent from relays that predate it
     },
     PeerJoined {
         session_id: String,
@@ -314,6 +336,25 @@ pub enum ServerMessage {
         from_peer_id: String,
         data: String, // serialized ReplicationMessage
     },
+    // Session roles (relayed)
+    RoleAnnounce {
+        session_id: String,
+        from_peer_id: String,
+        protocol_version: u32,
+    },
+    RoleChanged {
+        session_id: String,
+        from_peer_id: String,
+        target_peer_id: String,
+        role: String,
+    },
+    PermissionDenied {
+        session_id: String,
+        from_peer_id: String,
+        target_peer_id: String,
+        operation: String,
+        reason: String,
+    },
     Pong,
     Error {
         message: String,
@@ -827,6 +868,7 @@ impl MultiuserClient {
                                                                 participants: users,
                                                                 join_token: None,
                                                                 participant_profiles: None,
+                                                                host_peer_id: None,
                                                             });
                                                         }
                                                     }
@@ -8,8 +8,10 @@ pub struct RenderMetrics {
     pub fps: f32,
     pub frame_time_ms: f32,
     pub draw_calls: u32,
+    /// GPU memory allocated by the renderer
     pub memory_usage_mb: f32,
     pub vertices_drawn: u64,
+    pub triangles_drawn: u64,
     pub frames_rendered: u64,
     pub pipeline_time_us: f32,
 }
@@ -4,7 +4,7 @@ pub mod core;
 pub mod renderer;
 
 pub use core::{CameraInput, DiagnosticMetric, GpuProfilerData, RenderMetrics};
-pub use renderer::{EditorCameraState, HelioRenderer, RendererCommand};
+pub use renderer::{CaptureView, EditorCameraState, HelioRenderer, RendererCommand};
 
 pub const RENDER_WIDTH: u32 = 1600;
 pub const RENDER_HEIGHT: u32 = 900;
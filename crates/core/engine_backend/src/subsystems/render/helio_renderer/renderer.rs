@@ -4,7 +4,7 @@ use glam::{EulerRot, Mat4, Quat, Vec3};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex, mpsc};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use engine_fs::virtual_fs;
 use helio::{
@@ -17,7 +17,7 @@ use pulsar_reflection::{
     ComponentRuntimeContext, LiveKeySet, RuntimeComponentOwner, Subsystems,
     apply_runtime_behavior_for_class, scene_id_to_tag,
 };
-use pulsar_rendering::subsystems::{MeshCache, SceneObjectCache};
+use pulsar_rendering::subsystems::{MeshCache, MeshStats, SceneObjectCache};
 use pulsar_scene::{build_transform_parts, component_instances_from_props};
 
 use crate::scene::{ObjectType, SceneObjectSnapshot};
@@ -38,6 +38,50 @@ pub struct EditorCameraState {
     pub pitch: f32,
 }
 
+impl EditorCameraState {
+    /// Unit view direction for this yaw and pitch.
+    pub fn forward(&self) -> Vec3 {
+        let (sy, cy) = self.yaw.sin_cos();
+        let (sp, cp) = self.pitch.sin_cos();
+        Vec3::new(sy * cp, sp, -cy * cp)
+    }
+}
+
+/// An offscreen capture: the camera to render from and which editor-only
+/// overlays to keep. See [`HelioRenderer::render_capture`].
+#[derive(Clone, Copy, Debug)]
+pub struct CaptureView {
+    pub camera: EditorCameraState,
+    pub show_grid: bool,
+    pub show_gizmos: bool,
+    /// Keep the selected actor highlighted. Only drawn with `show_gizmos`.
+    pub show_selection: bool,
+    /// Clear to transparent black instead of the editor background; only
+    /// visible where no sky or geometry covers it.
+    pub transparent_background: bool,
+}
+
+/// Background behind the scene in the editor viewport.
+const EDITOR_CLEAR_COLOR: [f32; 4] = [0.15, 0.18, 0.25, 1.0];
+
+/// How often GPU memory usage is refreshed in the render metrics.
+const MEMORY_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Perspective camera matching the editor viewport's projection.
+fn editor_camera(state: &EditorCameraState, width: u32, height: u32) -> Camera {
+    let position = Vec3::from_array(state.position);
+    let aspect = width as f32 / height.max(1) as f32;
+    Camera::perspective_look_at(
+        position,
+        position + state.forward(),
+        Vec3::Y,
+        std::f32::consts::FRAC_PI_4,
+        aspect,
+        0.1,
+        10_000.0,
+    )
+}
+
 /// Delegates to the shared implementation in `pulsar_scene`.
 fn build_transform(snap: &SceneObjectSnapshot) -> Mat4 {
     build_transform_parts(snap.position, snap.rotation, snap.scale)
@@ -81,6 +125,7 @@ pub struct HelioRenderer {
     pub metrics: Arc<Mutex<RenderMetrics>>,
     pub gpu_profiler: Arc<Mutex<GpuProfilerData>>,
     last_frame: Instant,
+    last_memory_report: Instant,
     frame_count: u64,
 }
 
@@ -121,6 +166,7 @@ impl HelioRenderer {
             metrics: Arc::new(Mutex::new(RenderMetrics::default())),
             gpu_profiler: Arc::new(Mutex::new(GpuProfilerData::default())),
             last_frame: Instant::now(),
+            last_memory_report: Instant::now(),
             frame_count: 0,
         }
     }
@@ -211,7 +257,7 @@ impl HelioRenderer {
                 cull_stats_buffer,
             );
             r.set_editor_mode(true);
-            r.set_clear_color([0.15, 0.18, 0.25, 1.0]);
+            r.set_clear_color(EDITOR_CLEAR_COLOR);
             // Keep ambient disabled so scene illumination comes only from explicit light actors.
             r.set_ambient([0.0, 0.0, 0.0], 0.0);
 
@@ -264,6 +310,7 @@ impl HelioRenderer {
         }
 
         let mut sync_ms = 0.0;
+        let mut scene_stats = None;
         let scene_revision = self.scene_db.render_revision();
         if scene_revision != inner.last_scene_revision && !inner.editor_state.is_dragging() {
             profiling::profile_scope!("helio_scene_sync");
@@ -271,24 +318,24 @@ impl HelioRenderer {
             Self::sync_scene(&self.scene_db, inner, &self.pending_errors);
             sync_ms = t_sync.elapsed().as_secs_f64() * 1000.0;
             inner.last_scene_revision = scene_revision;
+            scene_stats = Some(Self::scene_stats(inner));
         }
 
+        // Allocator reports walk every allocation, so only sample once a second
+        let gpu_memory_mb = if self.last_memory_report.elapsed() >= MEMORY_REPORT_INTERVAL {
+            self.last_memory_report = Instant::now();
+            inner
+                .device
+                .generate_allocator_report()
+                .map(|report| report.total_allocated_bytes as f32 / (1024.0 * 1024.0))
+        } else {
+            None
+        };
+
         let t_prepare = Instant::now();
         let camera = {
             profiling::profile_scope!("helio_frame_prepare");
-            let (sy, cy) = self.cam_yaw.sin_cos();
-            let (sp, cp) = self.cam_pitch.sin_cos();
-            let fwd = Vec3::new(sy * cp, sp, -cy * cp);
-            let aspect = width as f32 / height.max(1) as f32;
-            let camera = Camera::perspective_look_at(
-                self.cam_pos,
-                self.cam_pos + fwd,
-                Vec3::Y,
-                std::f32::consts::FRAC_PI_4,
-                aspect,
-                0.1,
-                10_000.0,
-            );
+            let camera = editor_camera(&self.editor_camera_state(), width, height);
 
             // Mirror Helio editor demo exactly: clear debug geometry first, then draw gizmos.
             // Without debug_clear(), each frame's gizmo lines accumulate, making it look like
@@ -323,6 +370,90 @@ impl HelioRenderer {
             m.fps = if dt > 0.0 { 1.0 / dt } else { 0.0 };
             m.frame_time_ms = dt * 1000.0;
             m.frames_rendered = self.frame_count;
+            if let Some((draw_calls, stats)) = scene_stats {
+                m.draw_calls = draw_calls;
+                m.vertices_drawn = stats.vertices;
+                m.triangles_drawn = stats.triangles;
+            }
+            if let Some(memory_mb) = gpu_memory_mb {
+                m.memory_usage_mb = memory_mb;
+            }
+        }
+    }
+
+    /// Objects submitted and their combined geometry, before GPU culling
+    fn scene_stats(inner: &HelioInner) -> (u32, MeshStats) {
+        inner.object_cache.map.values().fold(
+            (0, MeshStats::default()),
+            |(draw_calls, total), (_, mesh_asset)| {
+                let mesh = inner.mesh_cache.stats(mesh_asset);
+                (
+                    draw_calls + 1,
+                    MeshStats {
+                        vertices: total.vertices + mesh.vertices,
+                        triangles: total.triangles + mesh.triangles,
+                    },
+                )
+            },
+        )
+    }
+
+    /// Render one frame from `capture.camera` into `view`, independent of the
+    /// editor camera and its input. Used for screenshots and turntables; the
+    /// render size is restored by the next [`render_frame`](Self::render_frame).
+    ///
+    /// Returns `false` if the renderer hasn't drawn its first frame yet.
+    pub fn render_capture(
+        &mut self,
+        view: &wgpu::TextureView,
+        width: u32,
+        height: u32,
+        capture: &CaptureView,
+    ) -> bool {
+        profiling::profile_scope!("helio_capture");
+        let Some(inner) = self.inner.as_mut() else {
+            return false;
+        };
+
+        if self.viewport_size != (width, height) {
+            inner.renderer.set_render_size(width, height);
+            self.viewport_size = (width, height);
+        }
+
+        let scene_revision = self.scene_db.render_revision();
+        if scene_revision != inner.last_scene_revision && !inner.editor_state.is_dragging() {
+            Self::sync_scene(&self.scene_db, inner, &self.pending_errors);
+            inner.last_scene_revision = scene_revision;
+        }
+
+        let camera = editor_camera(&capture.camera, width, height);
+        inner.renderer.debug_clear();
+        if capture.show_gizmos {
+            let selected = inner.editor_state.selected();
+            if !capture.show_selection {
+                inner.editor_state.deselect();
+            }
+            inner.renderer.set_gizmo_camera(&camera, height as f32);
+            inner.editor_state.draw_gizmos(&mut inner.renderer);
+            if let (false, Some(actor)) = (capture.show_selection, selected) {
+                inner.editor_state.select(actor);
+            }
+        }
+        inner.renderer.set_editor_mode(capture.show_grid);
+        if capture.transparent_background {
+            inner.renderer.set_clear_color([0.0; 4]);
+        }
+
+        let result = inner.renderer.render(&camera, view);
+
+        inner.renderer.set_editor_mode(true);
+        inner.renderer.set_clear_color(EDITOR_CLEAR_COLOR);
+        match result {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::error!("Helio capture render error: {:?}", e);
+                false
+            }
         }
     }
 
@@ -0,0 +1,201 @@
+//! Generation-guarded object handles.
+//!
+//! Gameplay code refers to world objects through an [`ObjectHandle`]: a slot
+//! index plus the generation the slot had when the handle was issued.
+//! Despawning bumps the slot's generation, so every outstanding handle to the
+//! object stops resolving that same tick. The slot itself is only recycled
+//! once the object's teardown has finished, and the new occupant's handles
+//! carry the new generation — a stale handle can never reach a recycled
+//! object.
+
+/// Handle to an object in an [`ObjectSlots`] table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObjectHandle {
+    index: u32,
+    generation: u32,
+}
+
+impl ObjectHandle {
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+enum Slot<T> {
+    Free,
+    Alive(T),
+    /// Despawned; teardown is still queued.
+    Dead(T),
+}
+
+/// Slot table mapping handles to objects.
+pub struct ObjectSlots<T> {
+    slots: Vec<Slot<T>>,
+    generations: Vec<u32>,
+    free: Vec<u32>,
+    alive: usize,
+}
+
+impl<T> Default for ObjectSlots<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ObjectSlots<T> {
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            generations: Vec::new(),
+            free: Vec::new(),
+            alive: 0,
+        }
+    }
+
+    /// Store `value` in a free slot, growing the table if there is none.
+    pub fn insert(&mut self, value: T) -> ObjectHandle {
+        self.alive += 1;
+        if let Some(index) = self.free.pop() {
+            self.slots[index as usize] = Slot::Alive(value);
+            return ObjectHandle {
+                index,
+                generation: self.generations[index as usize],
+            };
+        }
+        let index = self.slots.len() as u32;
+        self.slots.push(Slot::Alive(value));
+        self.generations.push(0);
+        ObjectHandle {
+            index,
+            generation: 0,
+        }
+    }
+
+    /// The object `handle` refers to, if it is still alive.
+    pub fn get(&self, handle: ObjectHandle) -> Option<&T> {
+        if self.generations.get(handle.index as usize) != Some(&handle.generation) {
+            return None;
+        }
+        match &self.slots[handle.index as usize] {
+            Slot::Alive(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, handle: ObjectHandle) -> bool {
+        self.get(handle).is_some()
+    }
+
+    /// Mark the object dead and invalidate every handle to it.
+    ///
+    /// Returns the handle to pass to [`free`](Self::free) once the object's
+    /// teardown is done, or `None` if `handle` was already stale.
+    pub fn kill(&mut self, handle: ObjectHandle) -> Option<ObjectHandle> {
+        if !self.contains(handle) {
+            return None;
+        }
+        let index = handle.index as usize;
+        if let Slot::Alive(value) = std::mem::replace(&mut self.slots[index], Slot::Free) {
+            self.slots[index] = Slot::Dead(value);
+        }
+        self.generations[index] = self.generations[index].wrapping_add(1);
+        self.alive -= 1;
+        Some(ObjectHandle {
+            index: handle.index,
+            generation: self.generations[index],
+        })
+    }
+
+    /// Recycle the slot of a dead object, returning the object.
+    ///
+    /// `dead` is the handle returned by [`kill`](Self::kill).
+    pub fn free(&mut self, dead: ObjectHandle) -> Option<T> {
+        let index = dead.index as usize;
+        if self.generations.get(index) != Some(&dead.generation) {
+            return None;
+        }
+        match std::mem::replace(&mut self.slots[index], Slot::Free) {
+            Slot::Dead(value) => {
+                // Bump again so `dead` itself never resolves to the next occupant
+                self.generations[index] = self.generations[index].wrapping_add(1);
+                self.free.push(dead.index);
+                Some(value)
+            }
+            other => {
+                self.slots[index] = other;
+                None
+            }
+        }
+    }
+
+    /// Live objects; despawned ones are skipped from the tick they die.
+    pub fn iter(&self) -> impl Iterator<Item = (ObjectHandle, &T)> {
+        self.slots
+            .iter()
+            .zip(&self.generations)
+            .enumerate()
+            .filter_map(|(index, (slot, generation))| match slot {
+                Slot::Alive(value) => Some((
+                    ObjectHandle {
+                        index: index as u32,
+                        generation: *generation,
+                    },
+                    value,
+                )),
+                _ => None,
+            })
+    }
+
+    /// Number of live objects.
+    pub fn len(&self) -> usize {
+        self.alive
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.alive == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_handle_detects_reuse() {
+        let mut slots = ObjectSlots::new();
+        let first = slots.insert("crate");
+        let dead = slots.kill(first).unwrap();
+
+        // Invalid from the moment of despawn, before teardown finishes
+        assert_eq!(slots.get(first), None);
+        assert_eq!(slots.kill(first), None);
+
+        // The slot is not reused while teardown is pending
+        let second = slots.insert("barrel");
+        assert_ne!(second.index(), first.index());
+
+        assert_eq!(slots.free(dead), Some("crate"));
+        assert_eq!(slots.free(dead), None);
+        let third = slots.insert("lamp");
+        assert_eq!(third.index(), first.index());
+        assert_ne!(third.generation(), first.generation());
+        assert_eq!(slots.get(first), None);
+        assert_eq!(slots.get(dead), None);
+        assert_eq!(slots.get(third), Some(&"lamp"));
+    }
+
+    #[test]
+    fn test_iteration_skips_dead_objects() {
+        let mut slots = ObjectSlots::new();
+        let handles: Vec<_> = (0..4).map(|i| slots.insert(i)).collect();
+        slots.kill(handles[1]).unwrap();
+
+        let live: Vec<_> = slots.iter().map(|(_, value)| *value).collect();
+        assert_eq!(live, vec![0, 2, 3]);
+        assert_eq!(slots.len(), 3);
+    }
+}
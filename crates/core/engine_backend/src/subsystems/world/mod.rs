@@ -13,6 +13,7 @@
 //! # Features
 //! - Load and manage game levels
 //! - Add, remove, and query actors
+//! - Deferred, time-sliced teardown of despawned actors (see [`reclamation`])
 //! - Spatial partitioning of the world for performance optimization
 //! - Future support for dynamic world generation and environmental effects
 //! - Integration with other subsystems like rendering and physics
@@ -20,8 +21,17 @@
 //! - Error handling and logging for robust operation
 //! - Unit tests for core functionalities
 
+pub mod handles;
+pub mod reclamation;
+
+pub use handles::{ObjectHandle, ObjectSlots};
+pub use reclamation::{
+    ReclamationConfig, ReclamationCounters, ReclamationQueue, Release, TeardownStage,
+};
+
 use pebble::spacial_store::sqlite_backend::SqliteDatabase;
 use pebble::SpatialObject;
+use std::collections::HashSet;
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -37,9 +47,17 @@ pub struct Actor {
 // not implementing Send + Sync (it contains Box<dyn PersistenceBackend> without bounds).
 // This is a limitation of the PebbleVault crate.
 
+/// Spatial storage of a world's actors; the context handed to teardown steps.
+pub type WorldStorage = pebble::VaultManager<Actor>;
+
 pub struct World {
     // World data and methods
-    vault: pebble::VaultManager<Actor>,
+    vault: WorldStorage,
+    /// Gameplay handles to actors in the vault.
+    objects: ObjectSlots<Uuid>,
+    /// Despawned actors still in the vault, hidden from lookups and queries.
+    dead: HashSet<Uuid>,
+    reclamation: ReclamationQueue<WorldStorage>,
 }
 impl World {
     pub fn new(level_name: &str) -> Self {
@@ -47,7 +65,85 @@ impl World {
             .expect("Failed to create SqliteDatabase, probably FS permission issues");
         let vault = pebble::VaultManager::new(Box::new(db)).expect("Failed to create VaultManager");
 
-        Self { vault }
+        Self {
+            vault,
+            objects: ObjectSlots::new(),
+            dead: HashSet::new(),
+            reclamation: ReclamationQueue::default(),
+        }
+    }
+
+    /// Issues a gameplay handle for an actor already stored in the world.
+    pub fn track_actor(&mut self, id: Uuid) -> ObjectHandle {
+        self.objects.insert(id)
+    }
+
+    /// The actor `handle` refers to, or `None` once it has been despawned.
+    pub fn resolve(&self, handle: ObjectHandle) -> Option<Uuid> {
+        self.objects.get(handle).copied()
+    }
+
+    /// Despawns an actor.
+    ///
+    /// The actor disappears from lookups and queries immediately and every
+    /// handle to it stops resolving. Its teardown is deferred to
+    /// [`reclaim`](Self::reclaim): `releases` hold the physics bodies and
+    /// renderer handles other subsystems need released, and removal from the
+    /// spatial store is appended as the final component step.
+    ///
+    /// Returns `false` if `handle` was stale.
+    pub fn despawn(
+        &mut self,
+        handle: ObjectHandle,
+        mut releases: Vec<Release<WorldStorage>>,
+    ) -> bool {
+        let Some(id) = self.objects.get(handle).copied() else {
+            return false;
+        };
+        let Some(dead) = self.objects.kill(handle) else {
+            return false;
+        };
+        self.dead.insert(id);
+        releases.push(Release::new(
+            TeardownStage::Components,
+            move |vault: &mut WorldStorage| {
+                if let Err(e) = vault.remove_object(id) {
+                    tracing::warn!("Failed to remove despawned actor {}: {}", id, e);
+                }
+            },
+        ));
+        self.reclamation.push(dead, releases);
+        true
+    }
+
+    /// Runs this tick's share of deferred teardown. Call once per tick.
+    pub fn reclaim(&mut self) {
+        let finished = self.reclamation.sweep(&mut self.vault);
+        self.free(finished);
+    }
+
+    /// Tears down every despawned actor now, ignoring the budget.
+    ///
+    /// Used on level unload, where the spike is hidden behind the transition.
+    pub fn force_drain(&mut self) {
+        let finished = self.reclamation.drain(&mut self.vault);
+        self.free(finished);
+    }
+
+    fn free(&mut self, finished: Vec<ObjectHandle>) {
+        for dead in finished {
+            if let Some(id) = self.objects.free(dead) {
+                self.dead.remove(&id);
+            }
+        }
+    }
+
+    pub fn reclamation_counters(&self) -> ReclamationCounters {
+        self.reclamation.counters()
+    }
+
+    pub fn set_reclamation_config(&mut self, config: ReclamationConfig) {
+        self.reclamation.set_config(config);
     }
 
     /// Adds an object to a specific region.
@@ -149,6 +245,9 @@ impl World {
     /// - This method returns a clone of the `SpatialObject`, including the `Arc<T>` custom data.
     /// - The search is performed across all regions, which may be slow for a large number of regions or objects.
     fn get_actor_private(&self, object_id: Uuid) -> Option<Arc<Actor>> {
+        if self.dead.contains(&object_id) {
+            return None;
+        }
         match self.vault.get_object(object_id) {
             Ok(Some(spatial_obj)) => Some(spatial_obj.custom_data.clone()),
             Ok(None) => None,
@@ -297,8 +396,11 @@ impl World {
         max_y: f64,
         max_z: f64,
     ) -> Result<Vec<SpatialObject<Actor>>, String> {
-        self.vault
-            .query_region(region_id, min_x, min_y, min_z, max_x, max_y, max_z)
+        let mut objects = self
+            .vault
+            .query_region(region_id, min_x, min_y, min_z, max_x, max_y, max_z)?;
+        objects.retain(|object| !self.dead.contains(&object.uuid));
+        Ok(objects)
     }
 
     /// Persists all in-memory databases to disk.
@@ -0,0 +1,434 @@
+//! Time-sliced teardown of despawned world objects.
+//!
+//! Despawning a streamed-out chunk used to release every component, physics
+//! body and renderer handle of its objects in one tick. Now a despawn only
+//! marks the object dead (see [`ObjectSlots::kill`](super::ObjectSlots::kill))
+//! and queues its teardown here as a list of [`Release`] steps. Each tick the
+//! sweeper runs steps until the next one is expected to overrun the
+//! configured budget.
+//!
+//! # Guarantees
+//! - **Ordering**: an object's steps run in [`TeardownStage`] order —
+//!   physics bodies, then renderer handles, then the component storage both
+//!   may still point into — even when the steps are spread over several
+//!   ticks.
+//! - **Starvation bound**: an object still queued `max_ticks` sweeps after its
+//!   despawn is torn down completely in that sweep, whatever the budget.
+//! - **Force drain**: [`ReclamationQueue::drain`] empties the queue
+//!   immediately, for level unload.
+
+use super::handles::ObjectHandle;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Teardown phases, in the order they run for each object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TeardownStage {
+    /// Rigid bodies and colliders.
+    Physics,
+    /// Renderer residency handles (meshes, lights, materials).
+    Renderer,
+    /// Component data and the object's spatial storage entry.
+    Components,
+}
+
+/// One teardown step. `C` is the context the sweeper hands to each step.
+pub struct Release<C> {
+    stage: TeardownStage,
+    action: Box<dyn FnOnce(&mut C)>,
+}
+
+impl<C> Release<C> {
+    pub fn new(stage: TeardownStage, action: impl FnOnce(&mut C) + 'static) -> Self {
+        Self {
+            stage,
+            action: Box::new(action),
+        }
+    }
+
+    pub fn stage(&self) -> TeardownStage {
+        self.stage
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReclamationConfig {
+    /// Time each sweep may spend on teardown, in microseconds.
+    pub budget_us: u64,
+    /// Sweeps after which a dead object is reclaimed regardless of budget.
+    pub max_ticks: u32,
+}
+
+impl Default for ReclamationConfig {
+    fn default() -> Self {
+        Self {
+            budget_us: 500,
+            max_ticks: 30,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReclamationCounters {
+    /// Dead objects waiting for teardown.
+    pub queue_depth: usize,
+    /// Teardown steps waiting to run.
+    pub pending_releases: usize,
+    pub queue_high_water: usize,
+    /// Objects fully torn down.
+    pub reclaimed: u64,
+    /// Objects torn down past the budget because of the starvation bound.
+    pub forced: u64,
+    /// Objects torn down by a force drain.
+    pub drained: u64,
+    /// Time the last sweep spent on teardown.
+    pub last_sweep_us: u64,
+    /// Steps run by the last sweep.
+    pub last_sweep_releases: usize,
+}
+
+struct Pending<C> {
+    handle: ObjectHandle,
+    despawn_tick: u64,
+    releases: VecDeque<Release<C>>,
+}
+
+/// Queue of dead objects and their outstanding teardown steps.
+pub struct ReclamationQueue<C> {
+    config: ReclamationConfig,
+    queue: VecDeque<Pending<C>>,
+    /// Sweeps run so far.
+    tick: u64,
+    /// Smoothed cost of one step, used to stop before overrunning the budget.
+    step_estimate: Option<Duration>,
+    counters: ReclamationCounters,
+}
+
+impl<C> Default for ReclamationQueue<C> {
+    fn default() -> Self {
+        Self::new(ReclamationConfig::default())
+    }
+}
+
+impl<C> ReclamationQueue<C> {
+    pub fn new(config: ReclamationConfig) -> Self {
+        Self {
+            config,
+            queue: VecDeque::new(),
+            tick: 0,
+            step_estimate: None,
+            counters: ReclamationCounters::default(),
+        }
+    }
+
+    pub fn config(&self) -> ReclamationConfig {
+        self.config
+    }
+
+    pub fn set_config(&mut self, config: ReclamationConfig) {
+        self.config = config;
+    }
+
+    /// Queue the teardown of a dead object.
+    ///
+    /// `handle` is returned by a later sweep once every step has run.
+    pub fn push(&mut self, handle: ObjectHandle, mut releases: Vec<Release<C>>) {
+        // Stable, so steps within a stage keep the caller's order
+        releases.sort_by_key(Release::stage);
+        self.counters.pending_releases += releases.len();
+        self.queue.push_back(Pending {
+            handle,
+            despawn_tick: self.tick,
+            releases: releases.into(),
+        });
+        self.counters.queue_high_water = self.counters.queue_high_water.max(self.queue.len());
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    pub fn counters(&self) -> ReclamationCounters {
+        ReclamationCounters {
+            queue_depth: self.queue.len(),
+            ..self.counters
+        }
+    }
+
+    /// Run one tick's worth of teardown, returning the objects finished.
+    pub fn sweep(&mut self, context: &mut C) -> Vec<ObjectHandle> {
+        profiling::profile_scope!("World::Reclamation::Sweep");
+        let start = Instant::now();
+        self.sweep_with_clock(context, || start.elapsed())
+    }
+
+    /// [`sweep`](Self::sweep) against an injected clock.
+    fn sweep_with_clock(
+        &mut self,
+        context: &mut C,
+        mut clock: impl FnMut() -> Duration,
+    ) -> Vec<ObjectHandle> {
+        self.tick += 1;
+        let budget = Duration::from_micros(self.config.budget_us);
+        let max_ticks = u64::from(self.config.max_ticks.max(1));
+        let start = clock();
+        let mut reclaimed = Vec::new();
+        let mut releases = 0;
+
+        while let Some(front) = self.queue.front_mut() {
+            let overdue = self.tick - front.despawn_tick >= max_ticks;
+            if !overdue {
+                let elapsed = clock().saturating_sub(start);
+                let expected = self.step_estimate.unwrap_or_default();
+                if elapsed + expected > budget {
+                    break;
+                }
+            }
+
+            if let Some(release) = front.releases.pop_front() {
+                let before = clock();
+                (release.action)(context);
+                let cost = clock().saturating_sub(before);
+                self.step_estimate = Some(match self.step_estimate {
+                    Some(estimate) => (estimate * 3 + cost) / 4,
+                    None => cost,
+                });
+                self.counters.pending_releases -= 1;
+                releases += 1;
+            }
+
+            if front.releases.is_empty() {
+                let handle = front.handle;
+                self.queue.pop_front();
+                self.counters.reclaimed += 1;
+                if overdue {
+                    self.counters.forced += 1;
+                }
+                reclaimed.push(handle);
+            }
+        }
+
+        self.counters.last_sweep_us = clock().saturating_sub(start).as_micros() as u64;
+        self.counters.last_sweep_releases = releases;
+        reclaimed
+    }
+
+    /// Tear down every queued object now, ignoring the budget.
+    pub fn drain(&mut self, context: &mut C) -> Vec<ObjectHandle> {
+        profiling::profile_scope!("World::Reclamation::Drain");
+        let mut reclaimed = Vec::with_capacity(self.queue.len());
+        while let Some(pending) = self.queue.pop_front() {
+            for release in pending.releases {
+                (release.action)(context);
+                self.counters.pending_releases -= 1;
+            }
+            self.counters.reclaimed += 1;
+            self.counters.drained += 1;
+            reclaimed.push(pending.handle);
+        }
+        reclaimed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::handles::ObjectSlots;
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Objects, their pending teardown, and a log of released resources.
+    /// Every step advances a fake clock by the object's scripted cost.
+    struct ScriptedWorld {
+        objects: ObjectSlots<&'static str>,
+        queue: ReclamationQueue<Vec<String>>,
+        released: Vec<String>,
+        clock: Rc<Cell<Duration>>,
+    }
+
+    impl ScriptedWorld {
+        fn new(config: ReclamationConfig) -> Self {
+            Self {
+                objects: ObjectSlots::new(),
+                queue: ReclamationQueue::new(config),
+                released: Vec::new(),
+                clock: Rc::new(Cell::new(Duration::ZERO)),
+            }
+        }
+
+        fn spawn(&mut self, name: &'static str) -> ObjectHandle {
+            self.objects.insert(name)
+        }
+
+        /// Despawn with one step per stage, each costing `cost_us`.
+        fn despawn(&mut self, handle: ObjectHandle, cost_us: u64) {
+            let name = *self.objects.get(handle).unwrap();
+            let stages = [
+                TeardownStage::Components,
+                TeardownStage::Renderer,
+                TeardownStage::Physics,
+            ];
+            let releases = stages
+                .into_iter()
+                .map(|stage| {
+                    let clock = self.clock.clone();
+                    Release::new(stage, move |log: &mut Vec<String>| {
+                        clock.set(clock.get() + Duration::from_micros(cost_us));
+                        log.push(format!("{name}:{stage:?}"));
+                    })
+                })
+                .collect();
+            let dead = self.objects.kill(handle).unwrap();
+            self.queue.push(dead, releases);
+        }
+
+        /// Sweep, returning the fake time spent.
+        fn tick(&mut self) -> Duration {
+            let clock = self.clock.clone();
+            let start = clock.get();
+            for dead in self
+                .queue
+                .sweep_with_clock(&mut self.released, || clock.get())
+            {
+                self.objects.free(dead).unwrap();
+            }
+            self.clock.get() - start
+        }
+
+        fn live(&self) -> Vec<&'static str> {
+            self.objects.iter().map(|(_, name)| *name).collect()
+        }
+    }
+
+    fn config(budget_us: u64, max_ticks: u32) -> ReclamationConfig {
+        ReclamationConfig {
+            budget_us,
+            max_ticks,
+        }
+    }
+
+    #[test]
+    fn test_despawned_objects_leave_queries_immediately() {
+        let mut world = ScriptedWorld::new(config(0, 10));
+        let crate_ = world.spawn("crate");
+        world.spawn("barrel");
+        world.despawn(crate_, 10);
+
+        assert_eq!(world.live(), vec!["barrel"]);
+        assert!(world.objects.get(crate_).is_none());
+        // Nothing has been torn down yet
+        assert!(world.released.is_empty());
+        assert_eq!(world.queue.counters().queue_depth, 1);
+        assert_eq!(world.queue.counters().pending_releases, 3);
+    }
+
+    #[test]
+    fn test_sweep_stays_within_budget() {
+        let mut world = ScriptedWorld::new(config(35, 1_000));
+        let handles: Vec<_> = (0..10).map(|_| world.spawn("rock")).collect();
+        for handle in handles {
+            world.despawn(handle, 10);
+        }
+
+        let mut ticks = 0;
+        while !world.queue.is_empty() {
+            let spent = world.tick();
+            ticks += 1;
+            assert!(
+                spent <= Duration::from_micros(35),
+                "tick {ticks} spent {spent:?}"
+            );
+            assert!(world.queue.counters().last_sweep_releases > 0);
+        }
+        assert_eq!(ticks, 10);
+        assert_eq!(world.released.len(), 30);
+        let counters = world.queue.counters();
+        assert_eq!(counters.reclaimed, 10);
+        assert_eq!(counters.forced, 0);
+        assert_eq!(counters.queue_high_water, 10);
+    }
+
+    #[test]
+    fn test_starvation_bound() {
+        // A budget too small for any step: only the bound makes progress
+        let mut world = ScriptedWorld::new(config(5, 4));
+        let first: Vec<_> = (0..20).map(|_| world.spawn("tree")).collect();
+        for handle in first {
+            world.despawn(handle, 10);
+        }
+        for _ in 0..2 {
+            world.tick();
+        }
+        let second: Vec<_> = (0..5).map(|_| world.spawn("bush")).collect();
+        for handle in second {
+            world.despawn(handle, 10);
+        }
+
+        let queued: Vec<usize> = (0..6)
+            .map(|_| {
+                world.tick();
+                world.queue.len()
+            })
+            .collect();
+        // Trees are due on sweep 4, bushes (despawned after sweep 2) on sweep 6
+        assert_eq!(queued, vec![25, 5, 5, 0, 0, 0]);
+        assert_eq!(world.released.len(), 75);
+        assert_eq!(world.queue.counters().forced, 25);
+        assert_eq!(world.objects.len(), 0);
+    }
+
+    #[test]
+    fn test_teardown_order_across_ticks() {
+        // Room for two steps per sweep, so objects split across ticks
+        let mut world = ScriptedWorld::new(config(25, 100));
+        let a = world.spawn("a");
+        let b = world.spawn("b");
+        world.despawn(a, 10);
+        world.despawn(b, 10);
+
+        world.tick();
+        assert_eq!(world.released, vec!["a:Physics", "a:Renderer"]);
+        world.tick();
+        world.tick();
+        assert_eq!(
+            world.released,
+            vec![
+                "a:Physics",
+                "a:Renderer",
+                "a:Components",
+                "b:Physics",
+                "b:Renderer",
+                "b:Components",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_drain_ignores_budget() {
+        let mut world = ScriptedWorld::new(config(0, 100));
+        let handles: Vec<_> = (0..3).map(|_| world.spawn("prop")).collect();
+        for handle in &handles {
+            world.despawn(*handle, 1_000);
+        }
+
+        let drained = world.queue.drain(&mut world.released);
+        assert_eq!(drained.len(), 3);
+        for dead in drained {
+            world.objects.free(dead).unwrap();
+        }
+        assert_eq!(world.released.len(), 9);
+        let counters = world.queue.counters();
+        assert_eq!(counters.queue_depth, 0);
+        assert_eq!(counters.pending_releases, 0);
+        assert_eq!(counters.drained, 3);
+
+        // Freed slots are reused under new generations
+        let reused = world.spawn("prop");
+        assert!(handles.iter().all(|old| world.objects.get(*old).is_none()));
+        assert_eq!(world.objects.get(reused), Some(&"prop"));
+    }
+}
@@ -0,0 +1,75 @@
+use engine_fs::{AssetIndex, AssetRegistration};
+use plugin_editor_api::FileTypeId;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Best of a few runs, so a busy machine doesn't skew the numbers.
+fn fastest(runs: usize, mut run: impl FnMut() -> Duration) -> Duration {
+    (0..runs).map(|_| run()).min().unwrap()
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1_000.0
+}
+
+fn main() {
+    const ASSETS: usize = 10_000;
+    let entries: Vec<AssetRegistration> = (0..ASSETS)
+        .map(|i| {
+            let mut entry =
+                AssetRegistration::new(format!("Type_{}", i % 100), FileTypeId::new("struct"));
+            entry.category = Some(format!("Category_{}", i % 10));
+            entry.file_path = Some(PathBuf::from(format!("types/{}.json", i)));
+            entry
+        })
+        .collect();
+
+    let looped = fastest(3, || {
+        let index = AssetIndex::new();
+        let entries = entries.clone();
+        let started = Instant::now();
+        for entry in entries {
+            index.register(
+                entry.name,
+                entry.category,
+                None,
+                entry.file_path,
+                entry.file_type_id,
+                None,
+                None,
+            );
+        }
+        started.elapsed()
+    });
+    let batched = fastest(3, || {
+        let index = AssetIndex::new();
+        let entries = entries.clone();
+        let started = Instant::now();
+        index.register_batch(entries);
+        started.elapsed()
+    });
+
+    const SEARCHED: usize = 50_000;
+    let index = AssetIndex::new();
+    for i in 0..SEARCHED {
+        index.register_simple(format!("Type_{}_{}", i % 97, i), FileTypeId::new("struct"));
+    }
+    let paged = fastest(3, || {
+        let started = Instant::now();
+        std::hint::black_box(index.search_paged("type_42", 0, 50));
+        started.elapsed()
+    });
+    let ranked = fastest(3, || {
+        let started = Instant::now();
+        std::hint::black_box(index.search_fuzzy_ranked("t42", 50));
+        started.elapsed()
+    });
+
+    println!(
+        "asset_index assets={ASSETS} register_looped_ms={:.3} register_batched_ms={:.3} searched_assets={SEARCHED} search_paged_ms={:.3} search_ranked_ms={:.3}",
+        ms(looped),
+        ms(batched),
+        ms(paged),
+        ms(ranked),
+    );
+}
@@ -0,0 +1,523 @@
+//! Catalog of every file in the project
+//!
+//! The asset index only holds files the plugin registry has a file type for.
+//! The catalog lists every file a scan walks, with its [`AssetKind`], size and
+//! modification time, so the file manager, new-file dialogs and the level
+//! editor can find assets of a kind without walking the project themselves.
+//! Scans fill it and the file system watcher keeps it current.
+//!
+//! With hashing turned on, files are also hashed on a small background pool
+//! so identical copies can be found. Only new or changed files are hashed
+//! again.
+
+use anyhow::{Context, Result};
+use ignore::gitignore::GitignoreBuilder;
+use parking_lot::{Mutex, RwLock};
+use plugin_editor_api::FileTypeId;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::Hasher;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::SystemTime;
+use twox_hash::XxHash64;
+
+use crate::asset_index::AssetIndex;
+use crate::templates::AssetKind;
+
+/// A file in the project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetEntry {
+    pub path: PathBuf,
+    /// The template kind its extension belongs to, if any
+    pub kind: Option<AssetKind>,
+    /// The file type the plugin registry gave it, if it is indexed
+    pub file_type_id: Option<FileTypeId>,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    /// Hash of its contents, once hashed
+    pub content_hash: Option<u64>,
+}
+
+/// Threads hashing file contents; reading files is most of the work
+const HASH_THREADS: usize = 2;
+
+/// How content hashing treats files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HashSettings {
+    /// Files larger than this, in bytes, are not hashed
+    pub max_file_size: u64,
+}
+
+impl Default for HashSettings {
+    fn default() -> Self {
+        Self {
+            max_file_size: 256 * 1024 * 1024,
+        }
+    }
+}
+
+type Entries = RwLock<BTreeMap<PathBuf, CatalogEntry>>;
+
+/// What the catalog keeps per file; the file type comes from the asset index
+#[derive(Debug, Clone)]
+struct CatalogEntry {
+    kind: Option<AssetKind>,
+    size: u64,
+    modified: Option<SystemTime>,
+    hash: Option<u64>,
+}
+
+impl CatalogEntry {
+    /// Reads the entry of the file at `path`, or `None` if it isn't a file
+    fn read(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok().filter(|m| m.is_file())?;
+        Some(Self {
+            kind: AssetKind::for_path(path),
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+            hash: None,
+        })
+    }
+
+    /// Takes the hash of `old` if it was read from the same version of the
+    /// file, judging by size and modification time
+    fn keep_hash(&mut self, old: Option<&CatalogEntry>) {
+        if let Some(old) = old.filter(|old| old.is_same_file(self)) {
+            self.hash = old.hash;
+        }
+    }
+
+    fn is_same_file(&self, other: &CatalogEntry) -> bool {
+        self.size == other.size && self.modified == other.modified
+    }
+}
+
+/// Every file in the project, by path
+pub struct AssetCatalog {
+    project_root: PathBuf,
+    asset_index: Arc<AssetIndex>,
+    entries: Arc<Entries>,
+    hashing: Arc<RwLock<Option<HashSettings>>>,
+    /// Files waiting for or being hashed
+    queued: Arc<Mutex<HashSet<PathBuf>>>,
+    hash_pool: OnceLock<rayon::ThreadPool>,
+}
+
+impl AssetCatalog {
+    pub fn new(project_root: PathBuf, asset_index: Arc<AssetIndex>) -> Self {
+        Self {
+            project_root,
+            asset_index,
+            entries: Arc::default(),
+            hashing: Arc::default(),
+            queued: Arc::default(),
+            hash_pool: OnceLock::new(),
+        }
+    }
+
+    /// Replaces the catalog with the files a scan found. Files unchanged
+    /// since the last scan keep their hashes.
+    pub fn replace(&self, files: &[PathBuf]) {
+        let mut entries = self.entries.write();
+        let scanned: BTreeMap<PathBuf, CatalogEntry> = files
+            .iter()
+            .filter_map(|path| {
+                let mut entry = CatalogEntry::read(path)?;
+                entry.keep_hash(entries.get(path));
+                Some((path.clone(), entry))
+            })
+            .collect();
+        *entries = scanned;
+        let unhashed = unhashed_paths(&entries);
+        drop(entries);
+        self.hash_in_background(unhashed);
+    }
+
+    /// Reads the file at `path` again, adding it if it is new and dropping
+    /// it if it is gone. Its hash is only recomputed if the file changed.
+    pub fn refresh(&self, path: &Path) {
+        let Some(mut entry) = CatalogEntry::read(path) else {
+            self.remove(path);
+            return;
+        };
+        let mut entries = self.entries.write();
+        entry.keep_hash(entries.get(path));
+        let unhashed = entry.hash.is_none();
+        entries.insert(path.to_path_buf(), entry);
+        drop(entries);
+        if unhashed {
+            self.hash_in_background(vec![path.to_path_buf()]);
+        }
+    }
+
+    /// Drops the file at `path`, or every file under it if it was a folder
+    pub fn remove(&self, path: &Path) {
+        self.entries
+            .write()
+            .retain(|entry_path, _| !entry_path.starts_with(path));
+    }
+
+    /// Moves the entries at or under `from` to the same place under `to`,
+    /// with their hashes
+    pub fn rename(&self, from: &Path, to: &Path) {
+        let mut entries = self.entries.write();
+        let moved: Vec<PathBuf> = entries
+            .keys()
+            .filter(|path| path.starts_with(from))
+            .cloned()
+            .collect();
+        let mut unhashed = Vec::new();
+        for old_path in moved {
+            let new_path = match old_path.strip_prefix(from) {
+                Ok(rest) if !rest.as_os_str().is_empty() => to.join(rest),
+                _ => to.to_path_buf(),
+            };
+            let old = entries.remove(&old_path);
+            if let Some(mut entry) = CatalogEntry::read(&new_path) {
+                entry.keep_hash(old.as_ref());
+                if entry.hash.is_none() {
+                    unhashed.push(new_path.clone());
+                }
+                entries.insert(new_path, entry);
+            }
+        }
+        if !entries.keys().any(|path| path.starts_with(to)) {
+            if let Some(entry) = CatalogEntry::read(to) {
+                entries.insert(to.to_path_buf(), entry);
+                unhashed.push(to.to_path_buf());
+            }
+        }
+        drop(entries);
+        self.hash_in_background(unhashed);
+    }
+
+    /// Files of `kind`, or all files, by path
+    pub fn list(&self, kind: Option<AssetKind>) -> Vec<AssetEntry> {
+        self.collect(|_, entry| kind.is_none() || entry.kind == kind)
+    }
+
+    /// Files matching a gitignore-style glob relative to the project root,
+    /// e.g. `*.png` anywhere or `/textures/**/*.png` under one folder
+    pub fn matching(&self, glob: &str) -> Result<Vec<AssetEntry>> {
+        let mut builder = GitignoreBuilder::new(&self.project_root);
+        builder
+            .add_line(None, glob)
+            .with_context(|| format!("Invalid glob '{}'", glob))?;
+        let matcher = builder.build()?;
+        Ok(self.collect(|path, _| {
+            path.strip_prefix(&self.project_root)
+                .is_ok_and(|relative| matcher.matched(relative, false).is_ignore())
+        }))
+    }
+
+    /// Number of files of each kind with any, in [`AssetKind::all`] order
+    pub fn count_by_kind(&self) -> Vec<(AssetKind, usize)> {
+        let entries = self.entries.read();
+        AssetKind::all()
+            .into_iter()
+            .map(|kind| {
+                let count = entries
+                    .values()
+                    .filter(|entry| entry.kind == Some(kind))
+                    .count();
+                (kind, count)
+            })
+            .filter(|&(_, count)| count > 0)
+            .collect()
+    }
+
+    /// Turns content hashing on, hashing every file not hashed yet, or off,
+    /// forgetting all hashes
+    pub fn set_hashing(&self, settings: Option<HashSettings>) {
+        *self.hashing.write() = settings;
+        let mut entries = self.entries.write();
+        for entry in entries.values_mut() {
+            if settings.is_none_or(|settings| entry.size > settings.max_file_size) {
+                entry.hash = None;
+            }
+        }
+        let unhashed = unhashed_paths(&entries);
+        drop(entries);
+        self.hash_in_background(unhashed);
+    }
+
+    /// The hash of the contents of the file at `path`, if it was hashed
+    pub fn hash_of(&self, path: &Path) -> Option<u64> {
+        self.entries.read().get(path)?.hash
+    }
+
+    /// Files still waiting to be hashed
+    pub fn pending_hashes(&self) -> usize {
+        self.queued.lock().len()
+    }
+
+    /// Groups of files with identical contents, largest files first, each
+    /// sorted by path. Empty files aren't counted as copies of each other.
+    /// Only hashed files are compared, so groups may be missing while
+    /// [`Self::pending_hashes`] is above zero.
+    pub fn find_duplicates(&self) -> Vec<Vec<PathBuf>> {
+        let mut by_content: HashMap<(u64, u64), Vec<PathBuf>> = HashMap::new();
+        for (path, entry) in self.entries.read().iter() {
+            if let Some(hash) = entry.hash.filter(|_| entry.size > 0) {
+                by_content
+                    .entry((entry.size, hash))
+                    .or_default()
+                    .push(path.clone());
+            }
+        }
+
+        let mut groups: Vec<(u64, Vec<PathBuf>)> = by_content
+            .into_iter()
+            .filter(|(_, paths)| paths.len() > 1)
+            .map(|((size, _), paths)| (size, paths))
+            .collect();
+        groups.sort_by(|(a_size, a), (b_size, b)| b_size.cmp(a_size).then_with(|| a.cmp(b)));
+        groups.into_iter().map(|(_, paths)| paths).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.read().is_empty()
+    }
+
+    fn collect(&self, keep: impl Fn(&Path, &CatalogEntry) -> bool) -> Vec<AssetEntry> {
+        self.entries
+            .read()
+            .iter()
+            .filter(|(path, entry)| keep(path, entry))
+            .map(|(path, entry)| AssetEntry {
+                path: path.clone(),
+                kind: entry.kind,
+                file_type_id: self
+                    .asset_index
+                    .get_by_path(path)
+                    .map(|asset| asset.file_type_id),
+                size: entry.size,
+                modified: entry.modified,
+                content_hash: entry.hash,
+            })
+            .collect()
+    }
+
+    /// Hashes the files at `paths` on the hashing pool, if hashing is on
+    /// and they aren't queued already
+    fn hash_in_background(&self, paths: Vec<PathBuf>) {
+        if paths.is_empty() || self.hashing.read().is_none() {
+            return;
+        }
+        let paths: Vec<PathBuf> = {
+            let mut queued = self.queued.lock();
+            paths
+                .into_iter()
+                .filter(|path| queued.insert(path.clone()))
+                .collect()
+        };
+        let pool = self.hash_pool.get_or_init(|| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(HASH_THREADS)
+                .thread_name(|i| format!("asset-hash-{}", i))
+                .build()
+                .expect("failed to start asset hashing threads")
+        });
+        for path in paths {
+            let entries = self.entries.clone();
+            let hashing = self.hashing.clone();
+            let queued = self.queued.clone();
+            pool.spawn(move || {
+                hash_entry(&entries, &hashing, &path);
+                queued.lock().remove(&path);
+            });
+        }
+    }
+}
+
+/// Files without a hash
+fn unhashed_paths(entries: &BTreeMap<PathBuf, CatalogEntry>) -> Vec<PathBuf> {
+    entries
+        .iter()
+        .filter(|(_, entry)| entry.hash.is_none())
+        .map(|(path, _)| path.clone())
+        .collect()
+}
+
+/// Times a file is hashed again for changing while it was read
+const HASH_ATTEMPTS: usize = 3;
+
+/// Hashes the file at `path` and stores the hash, unless hashing was turned
+/// off, the file is too large, or it kept changing while it was read
+fn hash_entry(entries: &Entries, hashing: &RwLock<Option<HashSettings>>, path: &Path) {
+    for _ in 0..HASH_ATTEMPTS {
+        let Some(settings) = *hashing.read() else {
+            return;
+        };
+        let Some(before) = entries.read().get(path).cloned() else {
+            return;
+        };
+        if before.hash.is_some() || before.size > settings.max_file_size {
+            return;
+        }
+
+        let hash = match hash_file(path) {
+            Ok(hash) => hash,
+            Err(e) => {
+                tracing::debug!("Failed to hash {:?}: {}", path, e);
+                return;
+            }
+        };
+        let unchanged = CatalogEntry::read(path).is_some_and(|after| after.is_same_file(&before));
+        let mut entries = entries.write();
+        match entries.get_mut(path) {
+            Some(entry) if unchanged && entry.is_same_file(&before) => {
+                entry.hash = Some(hash);
+                return;
+            }
+            Some(_) => {}
+            None => return,
+        }
+    }
+}
+
+/// xxHash64 of the contents of the file at `path`
+fn hash_file(path: &Path) -> std::io::Result<u64> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = XxHash64::with_seed(0);
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(hasher.finish());
+        }
+        hasher.write(&buffer[..read]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let files = [
+            "types/structs/Item.struct.json",
+            "data/loot.json",
+            "main.rs",
+        ];
+        for file in files {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, "{}").unwrap();
+        }
+        let paths: Vec<PathBuf> = files.iter().map(|file| root.join(file)).collect();
+
+        let asset_index = Arc::new(AssetIndex::new());
+        asset_index
+            .register_with_path(
+                "Item",
+                paths[0].clone(),
+                FileTypeId::new("struct"),
+                None,
+                None,
+            )
+            .unwrap();
+        let catalog = AssetCatalog::new(root.to_path_buf(), asset_index);
+        catalog.replace(&paths);
+
+        let structs = catalog.list(Some(AssetKind::Struct));
+        assert_eq!(structs.len(), 1);
+        assert_eq!(structs[0].file_type_id, Some(FileTypeId::new("struct")));
+        assert_eq!(structs[0].size, 2);
+        assert_eq!(catalog.list(None).len(), 3);
+        assert_eq!(
+            catalog.count_by_kind(),
+            [
+                (AssetKind::Struct, 1),
+                (AssetKind::RustScript, 1),
+                (AssetKind::JsonData, 1)
+            ]
+        );
+
+        let json: Vec<PathBuf> = catalog
+            .matching("*.json")
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect();
+        assert_eq!(json, [paths[1].clone(), paths[0].clone()]);
+        assert_eq!(catalog.matching("/types/**").unwrap().len(), 1);
+
+        std::fs::rename(root.join("types"), root.join("defs")).unwrap();
+        catalog.rename(&root.join("types"), &root.join("defs"));
+        assert_eq!(
+            catalog.list(Some(AssetKind::Struct))[0].path,
+            root.join("defs/structs/Item.struct.json")
+        );
+        catalog.remove(&root.join("defs"));
+        std::fs::write(root.join("notes.txt"), "").unwrap();
+        catalog.refresh(&root.join("notes.txt"));
+        assert_eq!(catalog.len(), 3);
+        assert_eq!(catalog.list(None)[2].kind, None);
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let write = |name: &str, contents: &str| {
+            std::fs::write(root.join(name), contents).unwrap();
+            root.join(name)
+        };
+        let paths = [
+            write("rock.png", "pixels"),
+            write("rock_copy.png", "pixels"),
+            write("moss.png", "other pixels"),
+            write("rock_large.png", "many more pixels"),
+            write("a.gitkeep", ""),
+            write("b.gitkeep", ""),
+        ];
+
+        let catalog = AssetCatalog::new(root.to_path_buf(), Arc::new(AssetIndex::new()));
+        catalog.replace(&paths);
+        assert_eq!(catalog.hash_of(&paths[0]), None);
+
+        catalog.set_hashing(Some(HashSettings { max_file_size: 12 }));
+        let wait = || {
+            let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+            while catalog.pending_hashes() > 0 {
+                assert!(
+                    std::time::Instant::now() < deadline,
+                    "{} files still waiting to be hashed after 5s",
+                    catalog.pending_hashes()
+                );
+                std::thread::sleep(std::time::Duration::from_millis(5));
+            }
+        };
+        wait();
+        assert_eq!(catalog.hash_of(&paths[0]), catalog.hash_of(&paths[1]));
+        assert_ne!(catalog.hash_of(&paths[0]), catalog.hash_of(&paths[2]));
+        assert_eq!(catalog.hash_of(&paths[3]), None);
+        assert_eq!(
+            catalog.find_duplicates(),
+            [vec![paths[0].clone(), paths[1].clone()]]
+        );
+
+        let rock_hash = catalog.hash_of(&paths[0]);
+        std::fs::rename(&paths[1], root.join("rock_2.png")).unwrap();
+        catalog.rename(&paths[1], &root.join("rock_2.png"));
+        assert_eq!(catalog.hash_of(&root.join("rock_2.png")), rock_hash);
+
+        write("rock.png", "new pixels");
+        catalog.refresh(&paths[0]);
+        wait();
+        assert_ne!(catalog.hash_of(&paths[0]), rock_hash);
+        assert!(catalog.find_duplicates().is_empty());
+
+        catalog.set_hashing(None);
+        assert_eq!(catalog.hash_of(&paths[0]), None);
+    }
+}
@@ -1,22 +1,47 @@
 //! # Asset Index
 //!
 //! In-memory, thread-safe index of project assets discovered by [`crate::scanner::ProjectScanner`].
-//! Supports fast registration and lookup by ID, name, category, file path, or file type.
+//! Supports fast registration and lookup by ID, name, category, tag, file path, or file type.
+//!
+//! The index is cached in the project at [`CACHE_FILE`] so that opening a
+//! project only rescans files changed since the last session.
+//!
+//! [`AssetIndex::subscribe`] hands out a receiver of [`AssetIndexEvent`]s so
+//! views can follow changes instead of polling.
 
-use dashmap::DashMap;
+use crate::type_definition::TypeDefinition;
+use anyhow::{Context, Result};
+use dashmap::{DashMap, SharedValue};
+use parking_lot::Mutex;
 use plugin_editor_api::FileTypeId;
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::BuildHasher;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::SystemTime;
+use tokio::sync::broadcast;
+
+/// Project-relative location of the asset index cache.
+pub const CACHE_FILE: &str = ".pulsar/asset_index.json";
+
+/// Bumped whenever the cache format changes; older caches are discarded.
+const CACHE_FORMAT_VERSION: u32 = 2;
+
+/// Events a subscriber holds on to before it starts missing them, see
+/// [`AssetIndex::subscribe`].
+const EVENT_CAPACITY: usize = 256;
 
 /// Information about a single project asset file.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct AssetInfo {
     /// Unique identifier for the asset
     pub id: u64,
     /// Display name of the asset
     pub name: String,
-    /// Optional category for grouping assets
+    /// Optional category, the primary grouping of assets. An asset has at
+    /// most one; use `tags` to put it in more groups.
     pub category: Option<String>,
     /// Optional description of the asset
     pub description: Option<String>,
@@ -28,6 +53,150 @@ pub struct AssetInfo {
     pub display_name: String,
     /// Last modified timestamp
     pub last_modified: Option<SystemTime>,
+    /// Fields, variants or methods, for assets that define a type
+    #[serde(default)]
+    pub definition: Option<TypeDefinition>,
+    /// Free-form tags (e.g. "Networking", "Gameplay"), matched
+    /// case-insensitively
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Names of the types the asset's file refers to, linked to the assets
+    /// defining them by [`AssetIndex::link_references`]
+    #[serde(default)]
+    pub references: Vec<String>,
+}
+
+impl AssetInfo {
+    /// Whether the asset's file changed after it was indexed. False for
+    /// assets without a file, or whose file is gone.
+    pub fn is_stale(&self) -> bool {
+        let Some(path) = &self.file_path else {
+            return false;
+        };
+        match std::fs::metadata(path).and_then(|m| m.modified()) {
+            Ok(modified) => self.last_modified.is_none_or(|indexed| modified > indexed),
+            Err(_) => false,
+        }
+    }
+
+    /// Whether the asset has a file that no longer exists.
+    pub fn is_missing(&self) -> bool {
+        self.file_path.as_ref().is_some_and(|path| !path.exists())
+    }
+
+    /// Takes what was read from the asset's file from a new registration of
+    /// it. The ID, path, category and tags are kept.
+    pub(crate) fn reparsed(&mut self, registration: AssetRegistration) {
+        self.display_name = registration
+            .display_name
+            .unwrap_or_else(|| registration.name.clone());
+        self.name = registration.name;
+        self.description = registration.description;
+        self.file_type_id = registration.file_type_id;
+        self.definition = registration.definition;
+        self.references = registration.references;
+    }
+
+    /// Whether the asset has `tag`, ignoring case.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = tag.to_lowercase();
+        self.tags.iter().any(|t| t.to_lowercase() == tag)
+    }
+}
+
+/// Part of the results of a search, see [`AssetIndex::search_paged`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchPage {
+    pub results: Vec<AssetInfo>,
+    /// Number of assets matching the query, returned or not
+    pub total: usize,
+}
+
+/// An asset to add with [`AssetIndex::register_batch`].
+#[derive(Debug, Clone)]
+pub struct AssetRegistration {
+    pub name: String,
+    pub category: Option<String>,
+    pub description: Option<String>,
+    pub file_path: Option<PathBuf>,
+    pub file_type_id: FileTypeId,
+    /// Defaults to `name`
+    pub display_name: Option<String>,
+    pub last_modified: Option<SystemTime>,
+    pub definition: Option<TypeDefinition>,
+    pub tags: Vec<String>,
+    /// Names of the types the file refers to
+    pub references: Vec<String>,
+}
+
+impl AssetRegistration {
+    pub fn new(name: impl Into<String>, file_type_id: FileTypeId) -> Self {
+        Self {
+            name: name.into(),
+            category: None,
+            description: None,
+            file_path: None,
+            file_type_id,
+            display_name: None,
+            last_modified: None,
+            definition: None,
+            tags: Vec::new(),
+            references: Vec::new(),
+        }
+    }
+
+    /// An asset backed by the file at `file_path`, reading last_modified from
+    /// the file system like [`AssetIndex::register_with_path`].
+    pub fn for_file(name: impl Into<String>, file_path: PathBuf, file_type_id: FileTypeId) -> Self {
+        let last_modified = std::fs::metadata(&file_path)
+            .ok()
+            .and_then(|m| m.modified().ok());
+        Self {
+            file_path: Some(file_path),
+            last_modified,
+            ..Self::new(name, file_type_id)
+        }
+    }
+}
+
+/// Outcome of [`AssetIndex::refresh_stale`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StaleRefresh {
+    /// Assets updated from their changed files
+    pub refreshed: Vec<u64>,
+    /// Stale assets whose file couldn't be read again, left as they were
+    pub unreadable: Vec<AssetInfo>,
+    /// Assets whose file is gone, left for the caller to unregister
+    pub missing: Vec<AssetInfo>,
+}
+
+/// A change to an [`AssetIndex`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssetIndexEvent {
+    /// An asset was registered
+    Registered(AssetInfo),
+    /// Assets were registered together by
+    /// [`register_batch`](AssetIndex::register_batch), in the order given
+    BatchRegistered(Vec<AssetInfo>),
+    /// The asset was changed in place, keeping its ID
+    Updated(AssetInfo),
+    /// The asset with this ID was removed
+    Unregistered(u64),
+    /// The assets with these IDs were removed together by
+    /// [`unregister_batch`](AssetIndex::unregister_batch)
+    BatchUnregistered(Vec<u64>),
+    /// Every asset was removed
+    Cleared,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedIndex {
+    version: u32,
+    next_id: u64,
+    assets: Vec<AssetInfo>,
+    /// `(from, to)` pairs, see [`AssetIndex::add_dependency`]
+    #[serde(default)]
+    dependencies: Vec<(u64, u64)>,
 }
 
 /// An in-memory, thread-safe index of project asset files.
@@ -42,10 +211,21 @@ pub struct AssetIndex {
     name_index: DashMap<String, Vec<u64>>,
     /// Index for category-based lookups
     category_index: DashMap<String, Vec<u64>>,
+    /// Index for tag-based lookups (lowercase tag -> asset IDs)
+    tag_index: DashMap<String, Vec<u64>>,
     /// Index for file path-based lookups (file path -> asset ID)
     file_path_index: DashMap<PathBuf, u64>,
     /// Next available asset ID (atomic for interior mutability)
     next_id: AtomicU64,
+    /// Assets each asset references (from -> to)
+    dependencies: DashMap<u64, HashSet<u64>>,
+    /// The same edges reversed (to -> from)
+    dependents: DashMap<u64, HashSet<u64>>,
+    /// Serializes changes to the indexes, so an update racing another update
+    /// or a removal of the same asset can't leave them out of step. Lookups
+    /// don't take it.
+    index_lock: Mutex<()>,
+    events: broadcast::Sender<AssetIndexEvent>,
 }
 
 impl Default for AssetIndex {
@@ -54,8 +234,13 @@ impl Default for AssetIndex {
             assets: DashMap::new(),
             name_index: DashMap::new(),
             category_index: DashMap::new(),
+            tag_index: DashMap::new(),
             file_path_index: DashMap::new(),
             next_id: AtomicU64::new(0),
+            dependencies: DashMap::new(),
+            dependents: DashMap::new(),
+            index_lock: Mutex::new(()),
+            events: broadcast::channel(EVENT_CAPACITY).0,
         }
     }
 }
@@ -66,6 +251,21 @@ impl AssetIndex {
         Self::default()
     }
 
+    /// Receives every change made from now on.
+    ///
+    /// Each subscriber gets its own copy of every event, and changes never
+    /// wait on subscribers: one that falls more than [`EVENT_CAPACITY`] events
+    /// behind gets [`RecvError::Lagged`](broadcast::error::RecvError::Lagged)
+    /// and should reload from [`all`](Self::all).
+    pub fn subscribe(&self) -> broadcast::Receiver<AssetIndexEvent> {
+        self.events.subscribe()
+    }
+
+    fn emit(&self, event: AssetIndexEvent) {
+        // Fails only when nobody is subscribed
+        let _ = self.events.send(event);
+    }
+
     /// Registers a new asset with all available fields and returns its assigned unique ID.
     pub fn register(
         &self,
@@ -82,38 +282,128 @@ impl AssetIndex {
         let name = name.into();
         let display_name = display_name.unwrap_or_else(|| name.clone());
 
-        let asset_info = AssetInfo {
+        self.insert(AssetInfo {
             id,
-            name: name.clone(),
-            category: category.clone(),
+            name,
+            category,
             description,
-            file_path: file_path.clone(),
+            file_path,
             file_type_id,
             display_name,
             last_modified,
-        };
+            definition: None,
+            tags: Vec::new(),
+            references: Vec::new(),
+        });
+        id
+    }
+
+    /// Adds an asset under its own ID.
+    fn insert(&self, asset_info: AssetInfo) {
+        let id = asset_info.id;
+        let _indexes = self.index_lock.lock();
 
         // Add to name index
         self.name_index
-            .entry(name.to_lowercase())
+            .entry(asset_info.name.to_lowercase())
             .or_insert_with(Vec::new)
             .push(id);
 
         // Add to category index
-        if let Some(cat) = &category {
+        if let Some(cat) = &asset_info.category {
             self.category_index
                 .entry(cat.to_lowercase())
                 .or_insert_with(Vec::new)
                 .push(id);
         }
 
+        // Add to tag index
+        for tag in tag_keys(&asset_info.tags) {
+            self.tag_index.entry(tag).or_default().push(id);
+        }
+
         // Add to file path index
-        if let Some(path) = &file_path {
+        if let Some(path) = &asset_info.file_path {
             self.file_path_index.insert(path.clone(), id);
         }
 
+        let event = self.registered_event(&asset_info);
         self.assets.insert(id, asset_info);
-        id
+        if let Some(event) = event {
+            self.emit(event);
+        }
+    }
+
+    /// The event for a new asset, or `None` when nobody is subscribed, so
+    /// scans don't clone every asset they register.
+    fn registered_event(&self, asset_info: &AssetInfo) -> Option<AssetIndexEvent> {
+        (self.events.receiver_count() > 0).then(|| AssetIndexEvent::Registered(asset_info.clone()))
+    }
+
+    /// Registers many assets at once, returning their IDs in the order given.
+    ///
+    /// Cheaper than calling [`register`](Self::register) in a loop: the IDs
+    /// are allocated as one contiguous range, the index lock is taken once,
+    /// index entries are grouped by shard so each shard is locked once, and
+    /// subscribers get one [`AssetIndexEvent::BatchRegistered`].
+    pub fn register_batch(&self, entries: Vec<AssetRegistration>) -> Vec<u64> {
+        if entries.is_empty() {
+            return Vec::new();
+        }
+        let start = self
+            .next_id
+            .fetch_add(entries.len() as u64, Ordering::SeqCst);
+
+        let mut names: HashMap<String, Vec<u64>> = HashMap::new();
+        let mut categories: HashMap<String, Vec<u64>> = HashMap::new();
+        let mut tags: HashMap<String, Vec<u64>> = HashMap::new();
+        let assets: Vec<AssetInfo> = entries
+            .into_iter()
+            .zip(start..)
+            .map(|(entry, id)| {
+                names.entry(entry.name.to_lowercase()).or_default().push(id);
+                if let Some(cat) = &entry.category {
+                    categories.entry(cat.to_lowercase()).or_default().push(id);
+                }
+                for tag in tag_keys(&entry.tags) {
+                    tags.entry(tag).or_default().push(id);
+                }
+                AssetInfo {
+                    id,
+                    display_name: entry.display_name.unwrap_or_else(|| entry.name.clone()),
+                    name: entry.name,
+                    category: entry.category,
+                    description: entry.description,
+                    file_path: entry.file_path,
+                    file_type_id: entry.file_type_id,
+                    last_modified: entry.last_modified,
+                    definition: entry.definition,
+                    tags: entry.tags,
+                    references: entry.references,
+                }
+            })
+            .collect();
+        let ids = (start..start + assets.len() as u64).collect();
+
+        let _indexes = self.index_lock.lock();
+        for (index, grouped) in [
+            (&self.name_index, names),
+            (&self.category_index, categories),
+            (&self.tag_index, tags),
+        ] {
+            extend_by_shard(index, grouped);
+        }
+        let registered = (self.events.receiver_count() > 0).then(|| assets.clone());
+        for asset_info in assets {
+            if let Some(path) = &asset_info.file_path {
+                self.file_path_index.insert(path.clone(), asset_info.id);
+            }
+            self.assets.insert(asset_info.id, asset_info);
+        }
+        if let Some(registered) = registered {
+            self.emit(AssetIndexEvent::BatchRegistered(registered));
+        }
+        ids
     }
 
     /// Registers an asset without all optional fields.
@@ -148,32 +438,412 @@ impl AssetIndex {
         ))
     }
 
+    /// Registers a type asset with its definition, reading last_modified
+    /// from the file system like [`register_with_path`](Self::register_with_path).
+    pub fn register_with_definition(
+        &self,
+        name: impl Into<String>,
+        file_path: PathBuf,
+        file_type_id: FileTypeId,
+        display_name: Option<String>,
+        description: Option<String>,
+        definition: TypeDefinition,
+    ) -> Result<u64, String> {
+        let last_modified = std::fs::metadata(&file_path)
+            .ok()
+            .and_then(|m| m.modified().ok());
+
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let name = name.into();
+        self.insert(AssetInfo {
+            id,
+            display_name: display_name.unwrap_or_else(|| name.clone()),
+            name,
+            category: None,
+            description,
+            file_path: Some(file_path),
+            file_type_id,
+            last_modified,
+            references: definition.referenced_types(),
+            definition: Some(definition),
+            tags: Vec::new(),
+        });
+        Ok(id)
+    }
+
+    /// Fills in the definition of type assets registered without one by
+    /// parsing their files. Returns how many were filled in.
+    pub fn migrate_definitions(&self) -> usize {
+        let missing: Vec<(u64, TypeDefinition)> = self
+            .assets
+            .iter()
+            .filter(|t| t.definition.is_none())
+            .filter_map(|t| {
+                let path = t.file_path.as_ref()?;
+                Some((t.id, TypeDefinition::for_file(path, &t.file_type_id)?))
+            })
+            .collect();
+
+        missing
+            .into_iter()
+            .filter(|(id, definition)| {
+                let updated = match self.assets.get_mut(id) {
+                    Some(mut asset_info) => {
+                        asset_info.definition = Some(definition.clone());
+                        asset_info.clone()
+                    }
+                    None => return false,
+                };
+                self.emit(AssetIndexEvent::Updated(updated));
+                true
+            })
+            .count()
+    }
+
+    /// Assets whose file changed after they were indexed, e.g. while the
+    /// editor was closed or when a watcher event was missed.
+    pub fn find_stale(&self) -> Vec<AssetInfo> {
+        self.filtered(AssetInfo::is_stale)
+    }
+
+    /// Assets whose file no longer exists.
+    pub fn find_missing(&self) -> Vec<AssetInfo> {
+        self.filtered(AssetInfo::is_missing)
+    }
+
+    /// Brings stale assets up to date in place, each keeping its ID, with
+    /// `reparser` reading a changed file again. Cheaper than a rescan when
+    /// few files changed. Missing files are reported, not unregistered.
+    pub fn refresh_stale(
+        &self,
+        reparser: impl Fn(&Path) -> Option<AssetRegistration>,
+    ) -> StaleRefresh {
+        let mut refresh = StaleRefresh {
+            missing: self.find_missing(),
+            ..StaleRefresh::default()
+        };
+        for asset in self.find_stale() {
+            let Some(path) = &asset.file_path else {
+                continue;
+            };
+            match reparser(path) {
+                Some(registration) => {
+                    if self
+                        .update(asset.id, |asset| asset.reparsed(registration))
+                        .is_some()
+                    {
+                        refresh.refreshed.push(asset.id);
+                    }
+                }
+                None => refresh.unreadable.push(asset),
+            }
+        }
+        refresh
+    }
+
+    fn filtered(&self, f: impl Fn(&AssetInfo) -> bool) -> Vec<AssetInfo> {
+        let mut assets: Vec<AssetInfo> = self
+            .assets
+            .iter()
+            .filter(|t| f(t))
+            .map(|t| t.clone())
+            .collect();
+        assets.sort_by_key(|t| t.id);
+        assets
+    }
+
     /// Removes an asset by its ID.
     pub fn unregister(&self, id: u64) -> Option<AssetInfo> {
+        self.unregister_with_dependents(id)
+            .map(|(asset_info, _)| asset_info)
+    }
+
+    /// Removes an asset by its ID, also returning the assets that referenced
+    /// it, so the caller can warn that they now point at nothing.
+    pub fn unregister_with_dependents(&self, id: u64) -> Option<(AssetInfo, Vec<u64>)> {
+        let _indexes = self.index_lock.lock();
+        let removed = self.remove_locked(id)?;
+        self.emit(AssetIndexEvent::Unregistered(id));
+        Some(removed)
+    }
+
+    /// Removes many assets at once, taking the index lock once. Returns the
+    /// assets that were registered; unknown IDs are skipped. Subscribers get
+    /// one [`AssetIndexEvent::BatchUnregistered`].
+    pub fn unregister_batch(&self, ids: &[u64]) -> Vec<AssetInfo> {
+        let _indexes = self.index_lock.lock();
+        let removed: Vec<AssetInfo> = ids
+            .iter()
+            .filter_map(|&id| self.remove_locked(id))
+            .map(|(asset_info, _)| asset_info)
+            .collect();
+        if !removed.is_empty() {
+            self.emit(AssetIndexEvent::BatchUnregistered(
+                removed.iter().map(|asset_info| asset_info.id).collect(),
+            ));
+        }
+        removed
+    }
+
+    /// Removes an asset and its edges. The caller holds `index_lock`.
+    fn remove_locked(&self, id: u64) -> Option<(AssetInfo, Vec<u64>)> {
         if let Some((_, asset_info)) = self.assets.remove(&id) {
             // Remove from name index
-            if let Some(mut ids) = self.name_index.get_mut(&asset_info.name.to_lowercase()) {
-                ids.retain(|&i| i != id);
-            }
+            remove_id(&self.name_index, &asset_info.name.to_lowercase(), id);
 
             // Remove from category index
             if let Some(cat) = &asset_info.category {
-                if let Some(mut ids) = self.category_index.get_mut(&cat.to_lowercase()) {
-                    ids.retain(|&i| i != id);
-                }
+                remove_id(&self.category_index, &cat.to_lowercase(), id);
+            }
+
+            // Remove from tag index
+            for tag in tag_keys(&asset_info.tags) {
+                remove_id(&self.tag_index, &tag, id);
             }
 
             // Remove from file path index
             if let Some(path) = &asset_info.file_path {
-                self.file_path_index.remove(path);
+                self.file_path_index.remove_if(path, |_, &i| i == id);
             }
 
-            Some(asset_info)
+            // Remove its edges from the dependency graph
+            if let Some((_, dependencies)) = self.dependencies.remove(&id) {
+                for to in dependencies {
+                    remove_edge(&self.dependents, to, id);
+                }
+            }
+            let mut dependents: Vec<u64> = self
+                .dependents
+                .remove(&id)
+                .map(|(_, dependents)| dependents.into_iter().collect())
+                .unwrap_or_default();
+            for &from in &dependents {
+                remove_edge(&self.dependencies, from, id);
+            }
+            dependents.sort_unstable();
+
+            Some((asset_info, dependents))
         } else {
             None
         }
     }
 
+    /// Tags an asset. Returns false if there's no such asset or it already
+    /// has the tag.
+    pub fn add_tag(&self, id: u64, tag: &str) -> bool {
+        let _indexes = self.index_lock.lock();
+        let updated = {
+            let Some(mut asset_info) = self.assets.get_mut(&id) else {
+                return false;
+            };
+            if asset_info.has_tag(tag) {
+                return false;
+            }
+            asset_info.tags.push(tag.to_string());
+            asset_info.clone()
+        };
+        self.tag_index
+            .entry(tag.to_lowercase())
+            .or_default()
+            .push(id);
+        self.emit(AssetIndexEvent::Updated(updated));
+        true
+    }
+
+    /// Removes a tag from an asset. Returns false if the asset didn't have it.
+    pub fn remove_tag(&self, id: u64, tag: &str) -> bool {
+        let _indexes = self.index_lock.lock();
+        let updated = {
+            let Some(mut asset_info) = self.assets.get_mut(&id) else {
+                return false;
+            };
+            if !asset_info.has_tag(tag) {
+                return false;
+            }
+            let key = tag.to_lowercase();
+            asset_info.tags.retain(|t| t.to_lowercase() != key);
+            asset_info.clone()
+        };
+        remove_id(&self.tag_index, &tag.to_lowercase(), id);
+        self.emit(AssetIndexEvent::Updated(updated));
+        true
+    }
+
+    /// Records that asset `from` references asset `to`. Returns false if
+    /// either isn't registered, they're the same asset, or the edge exists.
+    pub fn add_dependency(&self, from: u64, to: u64) -> bool {
+        let _indexes = self.index_lock.lock();
+        if from == to || !self.assets.contains_key(&from) || !self.assets.contains_key(&to) {
+            return false;
+        }
+        self.dependents.entry(to).or_default().insert(from);
+        self.dependencies.entry(from).or_default().insert(to)
+    }
+
+    /// Removes the edge `from -> to`. Returns false if there was none.
+    pub fn remove_dependency(&self, from: u64, to: u64) -> bool {
+        let _indexes = self.index_lock.lock();
+        remove_edge(&self.dependents, to, from);
+        remove_edge(&self.dependencies, from, to)
+    }
+
+    /// Assets that `id` references, in ID order.
+    pub fn dependencies_of(&self, id: u64) -> Vec<u64> {
+        sorted_ids(&self.dependencies, id)
+    }
+
+    /// Assets that reference `id`, in ID order.
+    pub fn dependents_of(&self, id: u64) -> Vec<u64> {
+        sorted_ids(&self.dependents, id)
+    }
+
+    /// Files of the assets that the asset at `file_path` references, in ID
+    /// order.
+    pub fn dependency_paths(&self, file_path: &Path) -> Vec<PathBuf> {
+        self.paths_along(&self.dependencies, file_path)
+    }
+
+    /// Files of the assets that reference the asset at `file_path`, in ID
+    /// order.
+    pub fn dependent_paths(&self, file_path: &Path) -> Vec<PathBuf> {
+        self.paths_along(&self.dependents, file_path)
+    }
+
+    fn paths_along(&self, edges: &DashMap<u64, HashSet<u64>>, file_path: &Path) -> Vec<PathBuf> {
+        let Some(id) = self.file_path_index.get(file_path).map(|id| *id) else {
+            return Vec::new();
+        };
+        sorted_ids(edges, id)
+            .into_iter()
+            .filter_map(|id| self.assets.get(&id)?.file_path.clone())
+            .collect()
+    }
+
+    /// Rebuilds the edges of every asset backed by a file from its
+    /// `references`, each linked to the asset defining a type of that name.
+    /// Names matching no type are skipped. Edges between assets without
+    /// files, added with [`add_dependency`](Self::add_dependency), are kept.
+    pub fn link_references(&self) {
+        let _indexes = self.index_lock.lock();
+        let mut types: HashMap<String, u64> = HashMap::new();
+        let mut references: Vec<(u64, Vec<String>)> = Vec::new();
+        for asset in self.assets.iter() {
+            if asset.definition.is_some() {
+                types
+                    .entry(asset.name.to_lowercase())
+                    .and_modify(|id| *id = (*id).min(asset.id))
+                    .or_insert(asset.id);
+            }
+            if asset.file_path.is_some() {
+                references.push((asset.id, asset.references.clone()));
+            }
+        }
+
+        for (from, names) in references {
+            if let Some((_, old)) = self.dependencies.remove(&from) {
+                for to in old {
+                    remove_edge(&self.dependents, to, from);
+                }
+            }
+            for name in names {
+                let Some(&to) = types.get(&name.to_lowercase()) else {
+                    continue;
+                };
+                if to != from {
+                    self.dependents.entry(to).or_default().insert(from);
+                    self.dependencies.entry(from).or_default().insert(to);
+                }
+            }
+        }
+    }
+
+    /// Assets that reference `id` directly or through other assets, nearest
+    /// first. Cycles are followed only once, and `id` itself is never
+    /// included.
+    pub fn transitive_dependents(&self, id: u64) -> Vec<u64> {
+        let mut visited = HashSet::from([id]);
+        let mut queue = VecDeque::from([id]);
+        let mut dependents = Vec::new();
+        while let Some(next) = queue.pop_front() {
+            for dependent in self.dependents_of(next) {
+                if visited.insert(dependent) {
+                    dependents.push(dependent);
+                    queue.push_back(dependent);
+                }
+            }
+        }
+        dependents
+    }
+
+    /// Changes an asset in place, keeping its ID, and returns the updated
+    /// asset.
+    ///
+    /// The name, category, tag and file path indexes follow the change and
+    /// `last_modified` is set to now. Returns `None` if there is no asset
+    /// with this ID.
+    pub fn update(&self, id: u64, f: impl FnOnce(&mut AssetInfo)) -> Option<AssetInfo> {
+        let _indexes = self.index_lock.lock();
+
+        // The asset's entry is released before the indexes are touched:
+        // lookups hold an index entry while reading assets
+        let (old, new) = {
+            let mut asset_info = self.assets.get_mut(&id)?;
+            let old = asset_info.clone();
+            f(&mut asset_info);
+            asset_info.id = id;
+            asset_info.last_modified = Some(SystemTime::now());
+            (old, asset_info.clone())
+        };
+
+        let (old_name, new_name) = (old.name.to_lowercase(), new.name.to_lowercase());
+        if old_name != new_name {
+            remove_id(&self.name_index, &old_name, id);
+            self.name_index.entry(new_name).or_default().push(id);
+        }
+
+        let old_category = old.category.as_ref().map(|cat| cat.to_lowercase());
+        let new_category = new.category.as_ref().map(|cat| cat.to_lowercase());
+        if old_category != new_category {
+            if let Some(cat) = &old_category {
+                remove_id(&self.category_index, cat, id);
+            }
+            if let Some(cat) = new_category {
+                self.category_index.entry(cat).or_default().push(id);
+            }
+        }
+
+        let (old_tags, new_tags) = (tag_keys(&old.tags), tag_keys(&new.tags));
+        for tag in old_tags.difference(&new_tags) {
+            remove_id(&self.tag_index, tag, id);
+        }
+        for tag in new_tags.difference(&old_tags) {
+            self.tag_index.entry(tag.clone()).or_default().push(id);
+        }
+
+        if old.file_path != new.file_path {
+            if let Some(path) = &old.file_path {
+                self.file_path_index.remove_if(path, |_, &i| i == id);
+            }
+            if let Some(path) = &new.file_path {
+                self.file_path_index.insert(path.clone(), id);
+            }
+        }
+
+        self.emit(AssetIndexEvent::Updated(new.clone()));
+        Some(new)
+    }
+
+    /// Renames an asset, keeping its ID. A display name that was the old
+    /// name follows the rename.
+    pub fn rename(&self, id: u64, new_name: &str) -> Option<AssetInfo> {
+        self.update(id, |asset_info| {
+            if asset_info.display_name == asset_info.name {
+                asset_info.display_name = new_name.to_string();
+            }
+            asset_info.name = new_name.to_string();
+        })
+    }
+
     /// Gets an asset by file path.
     pub fn get_by_path(&self, file_path: &PathBuf) -> Option<AssetInfo> {
         self.file_path_index
@@ -214,11 +884,14 @@ impl AssetIndex {
 
     /// Gets all assets with the given exact name (case-insensitive).
     pub fn get_by_name(&self, name: &str) -> Vec<AssetInfo> {
+        let name = name.to_lowercase();
         self.name_index
-            .get(&name.to_lowercase())
+            .get(&name)
             .map(|ids| {
                 ids.iter()
                     .filter_map(|id| self.assets.get(id).map(|v| v.clone()))
+                    // Skip an asset renamed since the index was read
+                    .filter(|t| t.name.to_lowercase() == name)
                     .collect()
             })
             .unwrap_or_default()
@@ -226,14 +899,110 @@ impl AssetIndex {
 
     /// Searches for assets whose names contain the query string (case-insensitive substring match).
     pub fn search(&self, query: &str) -> Vec<AssetInfo> {
+        self.search_with_tags(query, &[])
+    }
+
+    /// Like [`search`](Self::search), but only among assets carrying every one of `tags`.
+    pub fn search_with_tags(&self, query: &str, tags: &[&str]) -> Vec<AssetInfo> {
         let query_lower = query.to_lowercase();
         self.assets
             .iter()
+            .filter(|t| tags.iter().all(|tag| t.has_tag(tag)))
             .filter(|t| t.name.to_lowercase().contains(&query_lower))
             .map(|t| t.clone())
             .collect()
     }
 
+    /// Like [`search`](Self::search), but returns at most `limit` matches
+    /// starting at `offset`, in ID order so pages don't overlap. Only the
+    /// returned assets are cloned.
+    pub fn search_paged(&self, query: &str, offset: usize, limit: usize) -> SearchPage {
+        self.search_paged_with_tags(query, &[], offset, limit)
+    }
+
+    /// Like [`search_paged`](Self::search_paged), but only among assets
+    /// carrying every one of `tags`.
+    pub fn search_paged_with_tags(
+        &self,
+        query: &str,
+        tags: &[&str],
+        offset: usize,
+        limit: usize,
+    ) -> SearchPage {
+        let query_lower = query.to_lowercase();
+        let tagged = self.ids_with_tags(tags);
+        let mut ids: Vec<u64> = self
+            .name_index
+            .iter()
+            .filter(|entry| entry.key().contains(&query_lower))
+            .flat_map(|entry| entry.value().clone())
+            .filter(|id| tagged.as_ref().is_none_or(|tagged| tagged.contains(id)))
+            .collect();
+        ids.sort_unstable();
+
+        SearchPage {
+            total: ids.len(),
+            results: ids
+                .into_iter()
+                .skip(offset)
+                .take(limit)
+                .filter_map(|id| self.get(id))
+                .collect(),
+        }
+    }
+
+    /// IDs of the assets carrying every one of `tags`, or `None` when there
+    /// is nothing to filter on.
+    fn ids_with_tags(&self, tags: &[&str]) -> Option<HashSet<u64>> {
+        let ids_with = |tag: &str| -> HashSet<u64> {
+            self.tag_index
+                .get(&tag.to_lowercase())
+                .map(|ids| ids.iter().copied().collect())
+                .unwrap_or_default()
+        };
+        let (first, rest) = tags.split_first()?;
+        let mut ids = ids_with(first);
+        for tag in rest {
+            let tagged = ids_with(tag);
+            ids.retain(|id| tagged.contains(id));
+        }
+        Some(ids)
+    }
+
+    /// The `limit` best fuzzy matches on the name, best first, with ties in
+    /// ID order. Only the returned assets are cloned.
+    pub fn search_fuzzy_ranked(&self, query: &str, limit: usize) -> SearchPage {
+        let query_lower = query.to_lowercase();
+        let query_chars: Vec<char> = query_lower.chars().collect();
+
+        // Min-heap of the best matches so far: the worst is dropped as soon as
+        // there are more than `limit`, rather than sorting every match
+        let mut best: BinaryHeap<Reverse<(i32, Reverse<u64>)>> = BinaryHeap::new();
+        let mut total = 0;
+        for entry in self.name_index.iter() {
+            let score = fuzzy_match(&query_chars, entry.key());
+            if score <= 0 {
+                continue;
+            }
+            for &id in entry.value() {
+                total += 1;
+                best.push(Reverse((score, Reverse(id))));
+                if best.len() > limit {
+                    best.pop();
+                }
+            }
+        }
+
+        SearchPage {
+            total,
+            results: best
+                .into_sorted_vec()
+                .into_iter()
+                .filter_map(|Reverse((_, Reverse(id)))| self.get(id))
+                .collect(),
+        }
+    }
+
     /// Searches for assets with fuzzy matching on the name.
     pub fn search_fuzzy(&self, query: &str) -> Vec<AssetInfo> {
         let query_lower = query.to_lowercase();
@@ -257,13 +1026,68 @@ impl AssetIndex {
         results.into_iter().map(|(t, _)| t).collect()
     }
 
+    /// Like [`search_fuzzy`](Self::search_fuzzy), but an asset also matches
+    /// on the names of the members in its [`TypeDefinition`], scoring as its
+    /// best match.
+    pub fn search_fuzzy_with_members(&self, query: &str) -> Vec<AssetInfo> {
+        let query_lower = query.to_lowercase();
+        let query_chars: Vec<char> = query_lower.chars().collect();
+
+        let mut results: Vec<(AssetInfo, i32)> = self
+            .assets
+            .iter()
+            .filter_map(|t| {
+                let members = t
+                    .definition
+                    .as_ref()
+                    .map(TypeDefinition::member_names)
+                    .unwrap_or_default();
+                let score = std::iter::once(t.name.as_str())
+                    .chain(members)
+                    .map(|name| fuzzy_match(&query_chars, &name.to_lowercase()))
+                    .max()
+                    .unwrap_or(0);
+                if score > 0 {
+                    Some((t.clone(), score))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        // Sort by score descending
+        results.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+        results.into_iter().map(|(t, _)| t).collect()
+    }
+
+    /// Gets all assets with a given tag (case-insensitive).
+    pub fn get_by_tag(&self, tag: &str) -> Vec<AssetInfo> {
+        self.tag_index
+            .get(&tag.to_lowercase())
+            .map(|ids| {
+                ids.iter()
+                    .filter_map(|id| self.assets.get(id).map(|v| v.clone()))
+                    // Skip an asset untagged since the index was read
+                    .filter(|t| t.has_tag(tag))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Gets all assets in a given category (case-insensitive).
     pub fn get_by_category(&self, category: &str) -> Vec<AssetInfo> {
+        let category = category.to_lowercase();
         self.category_index
-            .get(&category.to_lowercase())
+            .get(&category)
             .map(|ids| {
                 ids.iter()
                     .filter_map(|id| self.assets.get(id).map(|v| v.clone()))
+                    // Skip an asset moved since the index was read
+                    .filter(|t| {
+                        t.category
+                            .as_deref()
+                            .is_some_and(|cat| cat.to_lowercase() == category)
+                    })
                     .collect()
             })
             .unwrap_or_default()
@@ -286,27 +1110,159 @@ impl AssetIndex {
 
     /// Clears all registered assets from the index.
     pub fn clear(&self) {
+        let _indexes = self.index_lock.lock();
         self.assets.clear();
         self.name_index.clear();
         self.category_index.clear();
+        self.tag_index.clear();
         self.file_path_index.clear();
+        self.dependencies.clear();
+        self.dependents.clear();
         self.next_id.store(0, Ordering::SeqCst);
+        self.emit(AssetIndexEvent::Cleared);
+    }
+
+    /// Writes the index to `path` as JSON, creating its directory if needed.
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        let mut assets = self.all();
+        assets.sort_by_key(|asset| asset.id);
+        let mut dependencies: Vec<(u64, u64)> = self
+            .dependencies
+            .iter()
+            .flat_map(|entry| {
+                let from = *entry.key();
+                entry
+                    .value()
+                    .iter()
+                    .map(move |&to| (from, to))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        dependencies.sort_unstable();
+        let persisted = PersistedIndex {
+            version: CACHE_FORMAT_VERSION,
+            next_id: self.next_id.load(Ordering::SeqCst),
+            assets,
+            dependencies,
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {:?}", parent))?;
+        }
+        let json = serde_json::to_string(&persisted)?;
+        std::fs::write(path, json).with_context(|| format!("Failed to write {:?}", path))
+    }
+
+    /// Reads an index written by [`save_to`](Self::save_to). Assets keep
+    /// their IDs, and new assets continue from where the saved index left off.
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let json =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+        let persisted: PersistedIndex = serde_json::from_str(&json)?;
+        if persisted.version != CACHE_FORMAT_VERSION {
+            anyhow::bail!(
+                "Unsupported asset index version {} (expected {})",
+                persisted.version,
+                CACHE_FORMAT_VERSION
+            );
+        }
+
+        let index = Self::new();
+        let mut next_id = persisted.next_id;
+        for asset_info in persisted.assets {
+            if index.assets.contains_key(&asset_info.id) {
+                anyhow::bail!("Duplicate asset ID {}", asset_info.id);
+            }
+            next_id = next_id.max(asset_info.id + 1);
+            index.insert(asset_info);
+        }
+        for (from, to) in persisted.dependencies {
+            index.add_dependency(from, to);
+        }
+        index.next_id.store(next_id, Ordering::SeqCst);
+        Ok(index)
+    }
+}
+
+/// Appends each key's IDs to those already indexed under it, taking each
+/// shard's lock once for all of its keys rather than once per key.
+fn extend_by_shard(index: &DashMap<String, Vec<u64>>, grouped: HashMap<String, Vec<u64>>) {
+    let mut by_shard: HashMap<usize, Vec<(String, Vec<u64>)>> = HashMap::new();
+    for (key, ids) in grouped {
+        by_shard
+            .entry(index.determine_map(&key))
+            .or_default()
+            .push((key, ids));
+    }
+
+    // Hashed the way DashMap hashes its keys, so entries land where lookups
+    // look for them
+    let hasher = index.hasher();
+    for (shard, entries) in by_shard {
+        let mut shard = index.shards()[shard].write();
+        for (key, ids) in entries {
+            let hash = hasher.hash_one(&key);
+            match shard.get_mut(hash, |(k, _)| *k == key) {
+                Some((_, indexed)) => indexed.get_mut().extend(ids),
+                None => {
+                    shard.insert(hash, (key, SharedValue::new(ids)), |(k, _)| {
+                        hasher.hash_one(k)
+                    });
+                }
+            }
+        }
     }
 }
 
+/// Remove `id` from the IDs indexed under `key`, dropping the key once none
+/// are left.
+fn remove_id(index: &DashMap<String, Vec<u64>>, key: &str, id: u64) {
+    if let Some(mut ids) = index.get_mut(key) {
+        ids.retain(|&i| i != id);
+    }
+    index.remove_if(key, |_, ids| ids.is_empty());
+}
+
+/// Lowercase tags, without duplicates.
+fn tag_keys(tags: &[String]) -> HashSet<String> {
+    tags.iter().map(|tag| tag.to_lowercase()).collect()
+}
+
+/// Remove `id` from the set under `key`, dropping the key once the set is
+/// empty. Returns whether `id` was there.
+fn remove_edge(edges: &DashMap<u64, HashSet<u64>>, key: u64, id: u64) -> bool {
+    let removed = edges.get_mut(&key).is_some_and(|mut ids| ids.remove(&id));
+    edges.remove_if(&key, |_, ids| ids.is_empty());
+    removed
+}
+
+fn sorted_ids(edges: &DashMap<u64, HashSet<u64>>, key: u64) -> Vec<u64> {
+    let mut ids: Vec<u64> = edges
+        .get(&key)
+        .map(|ids| ids.iter().copied().collect())
+        .unwrap_or_default();
+    ids.sort_unstable();
+    ids
+}
+
 /// Simple fuzzy matching algorithm that returns a score.
 ///
 /// Returns a positive score if all pattern characters are found in order in the text.
 /// Higher scores are given for consecutive matches and matches at word/segment boundaries.
 /// Returns 0 if the pattern is not fully matched.
 pub(crate) fn fuzzy_match(pattern: &[char], text: &str) -> i32 {
-    let text_chars: Vec<char> = text.chars().collect();
     let mut pattern_idx = 0;
     let mut score = 0;
     let mut prev_match = false;
+    let mut prev_char = None;
 
-    for (i, &c) in text_chars.iter().enumerate() {
-        if pattern_idx < pattern.len() && c == pattern[pattern_idx] {
+    for c in text.chars() {
+        // The rest of the text can't change the score
+        if pattern_idx == pattern.len() {
+            break;
+        }
+        if c == pattern[pattern_idx] {
             pattern_idx += 1;
             score += 1;
 
@@ -316,7 +1272,7 @@ pub(crate) fn fuzzy_match(pattern: &[char], text: &str) -> i32 {
             }
 
             // Bonus for matching at start or after separator
-            if i == 0 || matches!(text_chars.get(i.wrapping_sub(1)), Some('_' | ' ' | '-')) {
+            if matches!(prev_char, None | Some('_' | ' ' | '-')) {
                 score += 3;
             }
 
@@ -324,6 +1280,7 @@ pub(crate) fn fuzzy_match(pattern: &[char], text: &str) -> i32 {
         } else {
             prev_match = false;
         }
+        prev_char = Some(c);
     }
 
     // Only return score if all pattern characters were matched
@@ -333,3 +1290,593 @@ pub(crate) fn fuzzy_match(pattern: &[char], text: &str) -> i32 {
         0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn register(index: &AssetIndex, name: &str, category: &str, path: &str) -> u64 {
+        index.register(
+            name,
+            Some(category.to_string()),
+            None,
+            Some(PathBuf::from(path)),
+            FileTypeId::new("material"),
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_rename_updates_indexes() {
+        let index = AssetIndex::new();
+        let id = register(&index, "Brick", "Materials", "materials/brick.mat");
+
+        let renamed = index.rename(id, "Stone").unwrap();
+        assert_eq!(renamed.id, id);
+        assert_eq!(renamed.display_name, "Stone");
+        assert!(renamed.last_modified.is_some());
+
+        assert!(index.get_by_name("brick").is_empty());
+        assert_eq!(index.get_by_name("stone")[0].id, id);
+        assert!(!index.name_index.contains_key("brick"));
+        assert_eq!(index.get_by_category("materials").len(), 1);
+        assert_eq!(
+            index
+                .get_by_path(&PathBuf::from("materials/brick.mat"))
+                .unwrap()
+                .name,
+            "Stone"
+        );
+        assert!(index.rename(id + 1, "Missing").is_none());
+    }
+
+    #[test]
+    fn test_update_moves_category_and_path() {
+        let index = AssetIndex::new();
+        let id = register(&index, "Brick", "Materials", "materials/brick.mat");
+
+        let updated = index
+            .update(id, |asset| {
+                asset.id = 999;
+                asset.category = Some("Walls".to_string());
+                asset.file_path = Some(PathBuf::from("walls/brick.mat"));
+            })
+            .unwrap();
+        assert_eq!(updated.id, id);
+
+        assert!(index.get_by_category("materials").is_empty());
+        assert!(!index.category_index.contains_key("materials"));
+        assert_eq!(index.get_by_category("walls")[0].id, id);
+        assert!(index
+            .get_by_path(&PathBuf::from("materials/brick.mat"))
+            .is_none());
+        assert_eq!(
+            index
+                .get_by_path(&PathBuf::from("walls/brick.mat"))
+                .unwrap()
+                .id,
+            id
+        );
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CACHE_FILE);
+
+        let index = AssetIndex::new();
+        let brick = register(&index, "Brick", "Materials", "materials/brick.mat");
+        let stone = register(&index, "Stone", "Materials", "materials/stone.mat");
+        let marble = register(&index, "Marble", "Materials", "materials/marble.mat");
+        index.add_dependency(marble, brick);
+        index.rename(brick, "Clay");
+        index.unregister(stone);
+        index.save_to(&path).unwrap();
+
+        let loaded = AssetIndex::load_from(&path).unwrap();
+        assert_eq!(loaded.get(brick), index.get(brick));
+        assert!(loaded.get(stone).is_none());
+        assert_eq!(loaded.dependents_of(brick), [marble]);
+        assert_eq!(loaded.get_by_name("clay")[0].id, brick);
+        assert_eq!(loaded.get_by_category("materials").len(), 2);
+        assert_eq!(
+            loaded
+                .get_by_path(&PathBuf::from("materials/brick.mat"))
+                .unwrap()
+                .id,
+            brick
+        );
+
+        // IDs of removed assets are not handed out again
+        let next = register(&loaded, "Granite", "Materials", "materials/granite.mat");
+        assert!(next > marble);
+    }
+
+    #[test]
+    fn test_load_rejects_bad_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("asset_index.json");
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert!(AssetIndex::load_from(&path).is_err());
+
+        std::fs::write(&path, r#"{"version": 999, "next_id": 0, "assets": []}"#).unwrap();
+        assert!(AssetIndex::load_from(&path).is_err());
+
+        assert!(AssetIndex::load_from(&dir.path().join("missing.json")).is_err());
+    }
+
+    #[test]
+    fn test_search_fuzzy_with_members() {
+        let index = AssetIndex::new();
+        let definition = TypeDefinition::Struct {
+            fields: vec![crate::type_definition::FieldDef {
+                name: "health_points".to_string(),
+                type_name: "f32".to_string(),
+                doc: None,
+            }],
+        };
+        let player = index
+            .register_with_definition(
+                "Player",
+                PathBuf::from("types/structs/Player/struct.json"),
+                FileTypeId::new("struct"),
+                None,
+                None,
+                definition.clone(),
+            )
+            .unwrap();
+        register(&index, "Healer", "Materials", "materials/healer.mat");
+
+        assert_eq!(index.get(player).unwrap().definition, Some(definition));
+        assert_eq!(index.search_fuzzy("health").len(), 0);
+        let found: Vec<u64> = index
+            .search_fuzzy_with_members("health")
+            .iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(found, [player]);
+        assert_eq!(index.search_fuzzy_with_members("heal").len(), 2);
+    }
+
+    #[test]
+    fn test_paged_and_ranked_search() {
+        let index = AssetIndex::new();
+        for i in 0..10 {
+            register(
+                &index,
+                &format!("Rock {}", i),
+                "Props",
+                &format!("props/rock_{}.prop", i),
+            );
+        }
+        let rocket = register(&index, "Rocket_Launcher", "Weapons", "weapons/rocket.prop");
+
+        let page = index.search_paged("rock", 4, 4);
+        assert_eq!(page.total, 11);
+        let ids: Vec<u64> = page.results.iter().map(|t| t.id).collect();
+        assert_eq!(ids, [4, 5, 6, 7]);
+        assert_eq!(index.search_paged("rock", 8, 4).results.len(), 3);
+        assert_eq!(index.search_paged("rock", 20, 4).results.len(), 0);
+
+        // "rl" matches the start of both segments of "rocket_launcher" best
+        let ranked = index.search_fuzzy_ranked("rl", 3);
+        assert_eq!(ranked.total, 1);
+        assert_eq!(ranked.results[0].id, rocket);
+
+        let ranked = index.search_fuzzy_ranked("rock", 3);
+        assert_eq!(ranked.total, 11);
+        let ids: Vec<u64> = ranked.results.iter().map(|t| t.id).collect();
+        assert_eq!(ids, [0, 1, 2]);
+        assert_eq!(index.search_fuzzy_ranked("rock", 0).results.len(), 0);
+    }
+
+    #[test]
+    fn test_large_index_search() {
+        let index = AssetIndex::new();
+        for i in 0..50_000 {
+            index.register_simple(format!("Type_{}_{}", i % 97, i), FileTypeId::new("struct"));
+        }
+
+        // Names are Type_{i % 97}_{i}: type_42 matches i % 97 == 42 and the
+        // i that start with 42, from its own bucket or another
+        let paged = index.search_paged("type_42", 0, 50);
+        assert_eq!(paged.results.len(), 50);
+        assert_eq!(
+            paged.total,
+            (0..50_000)
+                .filter(|i| format!("type_{}_{}", i % 97, i).contains("type_42"))
+                .count()
+        );
+        assert!(paged.results.windows(2).all(|w| w[0].id < w[1].id));
+        assert!(paged
+            .results
+            .iter()
+            .all(|asset| asset.name.to_lowercase().contains("type_42")));
+
+        let ranked = index.search_fuzzy_ranked("t42", 50);
+        assert_eq!(ranked.results.len(), 50);
+        assert!(ranked.total > 50);
+        let scores: Vec<i32> = ranked
+            .results
+            .iter()
+            .map(|asset| fuzzy_match(&['t', '4', '2'], &asset.name.to_lowercase()))
+            .collect();
+        assert!(scores.windows(2).all(|w| w[0] >= w[1]));
+    }
+
+    #[test]
+    fn test_register_batch() {
+        let index = AssetIndex::new();
+        let first = register(&index, "Rock", "Materials", "materials/rock.mat");
+
+        let mut sand = AssetRegistration::new("Sand", FileTypeId::new("material"));
+        sand.category = Some("Materials".to_string());
+        sand.tags = vec!["Terrain".to_string()];
+        let mut rock = AssetRegistration::new("rock", FileTypeId::new("material"));
+        rock.file_path = Some(PathBuf::from("terrain/rock.mat"));
+        let ids = index.register_batch(vec![sand, rock]);
+        assert_eq!(ids, [first + 1, first + 2]);
+
+        assert_eq!(index.get_by_name("ROCK").len(), 2);
+        assert_eq!(index.get_by_category("materials").len(), 2);
+        assert_eq!(index.get_by_tag("terrain")[0].name, "Sand");
+        assert_eq!(
+            index
+                .get_by_path(&PathBuf::from("terrain/rock.mat"))
+                .unwrap()
+                .id,
+            ids[1]
+        );
+        assert_eq!(index.get(ids[0]).unwrap().display_name, "Sand");
+        assert_eq!(register(&index, "Dirt", "Materials", "d.mat"), first + 3);
+
+        let removed = index.unregister_batch(&[first, ids[1], 999]);
+        assert_eq!(removed.len(), 2);
+        assert_eq!(index.get_by_name("rock").len(), 0);
+        assert!(!index.name_index.contains_key("rock"));
+        assert!(index
+            .get_by_path(&PathBuf::from("terrain/rock.mat"))
+            .is_none());
+        assert_eq!(index.len(), 2);
+    }
+
+    #[test]
+    fn test_register_batch_indexes_everything_and_notifies_once() {
+        let index = AssetIndex::new();
+        register(&index, "Type_0", "Category_0", "existing.json");
+        let mut events = index.subscribe();
+
+        let entries: Vec<AssetRegistration> = (0..10_000)
+            .map(|i| {
+                let mut entry =
+                    AssetRegistration::new(format!("Type_{}", i % 100), FileTypeId::new("struct"));
+                entry.category = Some(format!("Category_{}", i % 10));
+                entry.tags = vec![format!("Tag_{}", i % 7)];
+                entry.file_path = Some(PathBuf::from(format!("types/{}.json", i)));
+                entry
+            })
+            .collect();
+        let ids = index.register_batch(entries);
+
+        assert_eq!(index.len(), 10_001);
+        // Keys spread over every shard, one of them already indexed
+        assert_eq!(index.get_by_name("type_0").len(), 101);
+        for i in 1..100 {
+            assert_eq!(index.get_by_name(&format!("type_{}", i)).len(), 100);
+        }
+        assert_eq!(index.get_by_category("category_0").len(), 1_001);
+        assert_eq!(index.get_by_category("category_9").len(), 1_000);
+        assert_eq!(index.get_by_tag("tag_6").len(), 1_428);
+        assert_eq!(
+            index
+                .get_by_path(&PathBuf::from("types/9999.json"))
+                .unwrap()
+                .id,
+            ids[9_999]
+        );
+
+        match events.try_recv().unwrap() {
+            AssetIndexEvent::BatchRegistered(assets) => {
+                let registered: Vec<u64> = assets.iter().map(|asset| asset.id).collect();
+                assert_eq!(registered, ids);
+            }
+            other => panic!("expected one batch event, got {:?}", other),
+        }
+        assert!(events.try_recv().is_err());
+
+        index.unregister_batch(&ids[..3]);
+        assert_eq!(
+            events.try_recv().unwrap(),
+            AssetIndexEvent::BatchUnregistered(ids[..3].to_vec())
+        );
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_refresh_stale() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = |name: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, "{}").unwrap();
+            path
+        };
+        let material = || FileTypeId::new("material");
+
+        let index = AssetIndex::new();
+        let fresh = index
+            .register_with_path("Fresh", file("fresh.mat"), material(), None, None)
+            .unwrap();
+        let indexed_long_ago = Some(SystemTime::UNIX_EPOCH);
+        let changed = index.register(
+            "Changed",
+            None,
+            None,
+            Some(file("changed.mat")),
+            material(),
+            None,
+            indexed_long_ago,
+        );
+        let broken = index.register(
+            "Broken",
+            None,
+            None,
+            Some(file("broken.mat")),
+            material(),
+            None,
+            indexed_long_ago,
+        );
+        let gone = index.register(
+            "Gone",
+            None,
+            None,
+            Some(dir.path().join("gone.mat")),
+            material(),
+            None,
+            indexed_long_ago,
+        );
+        index.add_tag(changed, "Terrain");
+
+        let stale: Vec<u64> = index.find_stale().iter().map(|t| t.id).collect();
+        assert_eq!(stale, [changed, broken]);
+        assert_eq!(index.find_missing()[0].id, gone);
+
+        let refresh = index.refresh_stale(|path| {
+            (!path.ends_with("broken.mat"))
+                .then(|| AssetRegistration::new("Reparsed", FileTypeId::new("texture")))
+        });
+        assert_eq!(refresh.refreshed, [changed]);
+        assert_eq!(refresh.unreadable[0].id, broken);
+        assert_eq!(refresh.missing[0].id, gone);
+
+        let reparsed = index.get(changed).unwrap();
+        assert_eq!(reparsed.name, "Reparsed");
+        assert_eq!(reparsed.file_type_id, FileTypeId::new("texture"));
+        assert_eq!(reparsed.tags, ["Terrain"]);
+        assert!(reparsed.file_path.unwrap().ends_with("changed.mat"));
+        assert_eq!(index.get_by_name("reparsed")[0].id, changed);
+        assert!(!index.get(fresh).unwrap().is_stale());
+        assert_eq!(index.find_stale().len(), 1);
+        assert!(index.get(gone).is_some());
+    }
+
+    #[test]
+    fn test_tags() {
+        let index = AssetIndex::new();
+        let socket = register(&index, "Socket", "Types", "types/socket.json");
+        let player = register(&index, "Player", "Types", "types/player.json");
+
+        assert!(index.add_tag(player, "Networking"));
+        assert!(index.add_tag(player, "Gameplay"));
+        assert!(!index.add_tag(player, "gameplay"));
+        assert!(index.add_tag(socket, "networking"));
+        assert!(!index.add_tag(999, "Gameplay"));
+
+        let mut networked: Vec<u64> = index
+            .get_by_tag("NETWORKING")
+            .iter()
+            .map(|t| t.id)
+            .collect();
+        networked.sort();
+        assert_eq!(networked, [socket, player]);
+        assert_eq!(index.search("p").len(), 1);
+        assert_eq!(index.search_with_tags("p", &["networking"]).len(), 1);
+        assert_eq!(index.search_with_tags("p", &["physics"]).len(), 0);
+        assert_eq!(index.search_with_tags("", &["networking"]).len(), 2);
+        assert_eq!(
+            index.search_with_tags("", &["Networking", "gameplay"])[0].id,
+            player
+        );
+        assert_eq!(
+            index.search_with_tags("", &["networking", "physics"]).len(),
+            0
+        );
+        assert_eq!(
+            index.search_paged_with_tags("", &["gameplay"], 0, 10).total,
+            1
+        );
+        assert_eq!(
+            index
+                .search_paged_with_tags("", &["networking", "gameplay"], 0, 10)
+                .total,
+            1
+        );
+
+        assert!(index.remove_tag(player, "networking"));
+        assert!(!index.remove_tag(player, "networking"));
+        assert_eq!(index.get(player).unwrap().tags, ["Gameplay"]);
+        assert_eq!(index.get_by_tag("networking").len(), 1);
+
+        index.update(player, |asset| asset.tags = vec!["Audio".to_string()]);
+        assert!(index.get_by_tag("gameplay").is_empty());
+        assert!(!index.tag_index.contains_key("gameplay"));
+        assert_eq!(index.get_by_tag("audio")[0].id, player);
+
+        index.unregister(socket);
+        assert!(!index.tag_index.contains_key("networking"));
+        index.clear();
+        assert!(index.tag_index.is_empty());
+    }
+
+    #[test]
+    fn test_dependencies() {
+        let index = AssetIndex::new();
+        let vector = register(&index, "Vector", "Types", "types/vector.json");
+        let transform = register(&index, "Transform", "Types", "types/transform.json");
+        let player = register(&index, "Player", "Types", "types/player.json");
+        let camera = register(&index, "Camera", "Types", "types/camera.json");
+
+        assert!(index.add_dependency(transform, vector));
+        assert!(index.add_dependency(player, transform));
+        assert!(index.add_dependency(camera, transform));
+        // Cycle back to the start
+        assert!(index.add_dependency(vector, player));
+        assert!(!index.add_dependency(player, transform));
+        assert!(!index.add_dependency(player, player));
+        assert!(!index.add_dependency(player, 999));
+
+        assert_eq!(index.dependencies_of(player), [transform]);
+        assert_eq!(index.dependents_of(transform), [player, camera]);
+        assert_eq!(
+            index.transitive_dependents(vector),
+            [transform, player, camera]
+        );
+
+        let (removed, dangling) = index.unregister_with_dependents(transform).unwrap();
+        assert_eq!(removed.id, transform);
+        assert_eq!(dangling, [player, camera]);
+        assert!(index.dependencies_of(player).is_empty());
+        assert!(index.dependents_of(vector).is_empty());
+        assert!(!index.dependents.contains_key(&transform));
+        assert!(!index.dependencies.contains_key(&camera));
+        assert_eq!(index.dependents_of(player), [vector]);
+
+        assert!(index.remove_dependency(vector, player));
+        assert!(!index.remove_dependency(vector, player));
+        assert!(index.dependencies.is_empty() && index.dependents.is_empty());
+    }
+
+    #[test]
+    fn test_link_references() {
+        let index = AssetIndex::new();
+        let field = |type_name: &str| crate::type_definition::FieldDef {
+            name: "field".to_string(),
+            type_name: type_name.to_string(),
+            doc: None,
+        };
+        let define = |name: &str, path: &str, type_names: &[&str]| {
+            let definition = TypeDefinition::Struct {
+                fields: type_names.iter().map(|t| field(t)).collect(),
+            };
+            index
+                .register_with_definition(
+                    name,
+                    PathBuf::from(path),
+                    FileTypeId::new("struct"),
+                    None,
+                    None,
+                    definition,
+                )
+                .unwrap()
+        };
+        let item = define("Item", "types/item.json", &["u32"]);
+        let player = define("Player", "types/player.json", &["Vec<Item>", "Pet"]);
+        let mut class = AssetRegistration::for_file(
+            "Hero",
+            PathBuf::from("classes/Hero.class"),
+            FileTypeId::new("class"),
+        );
+        class.references = vec!["player".to_string(), "f32".to_string()];
+        let hero = index.register_batch(vec![class])[0];
+
+        index.link_references();
+        assert_eq!(index.dependencies_of(player), [item]);
+        assert_eq!(index.dependents_of(player), [hero]);
+        assert_eq!(
+            index.dependent_paths(Path::new("types/item.json")),
+            [PathBuf::from("types/player.json")]
+        );
+        assert_eq!(
+            index.dependency_paths(Path::new("classes/Hero.class")),
+            [PathBuf::from("types/player.json")]
+        );
+        assert!(index
+            .dependent_paths(Path::new("types/none.json"))
+            .is_empty());
+
+        // A type appearing later is linked, one no longer referenced isn't
+        let pet = define("Pet", "types/pet.json", &[]);
+        index.update(player, |asset| asset.references = vec!["Pet".to_string()]);
+        index.link_references();
+        assert_eq!(index.dependencies_of(player), [pet]);
+        assert!(index.dependents_of(item).is_empty());
+    }
+
+    #[test]
+    fn test_events() {
+        let index = AssetIndex::new();
+        let mut first = index.subscribe();
+        let mut second = index.subscribe();
+        drop(index.subscribe());
+
+        let id = register(&index, "Rock", "Materials", "materials/rock.mat");
+        index.rename(id, "Stone");
+        index.add_tag(id, "Terrain");
+        index.unregister(id);
+        assert!(index.unregister(id).is_none());
+        index.clear();
+
+        for receiver in [&mut first, &mut second] {
+            let registered = receiver.try_recv().unwrap();
+            assert!(matches!(registered, AssetIndexEvent::Registered(info) if info.id == id));
+            let renamed = receiver.try_recv().unwrap();
+            assert!(matches!(renamed, AssetIndexEvent::Updated(info) if info.name == "Stone"));
+            let tagged = receiver.try_recv().unwrap();
+            assert!(matches!(tagged, AssetIndexEvent::Updated(info) if info.has_tag("terrain")));
+            assert_eq!(
+                receiver.try_recv().unwrap(),
+                AssetIndexEvent::Unregistered(id)
+            );
+            assert_eq!(receiver.try_recv().unwrap(), AssetIndexEvent::Cleared);
+            assert!(receiver.try_recv().is_err());
+        }
+    }
+
+    #[test]
+    fn test_concurrent_renames_keep_indexes_consistent() {
+        let index = AssetIndex::new();
+        let id = register(&index, "Asset", "Props", "props/asset.prop");
+
+        std::thread::scope(|s| {
+            for thread in 0..4 {
+                let index = &index;
+                s.spawn(move || {
+                    for i in 0..250 {
+                        index.rename(id, &format!("Asset {}-{}", thread, i));
+                    }
+                });
+            }
+            for _ in 0..2 {
+                let index = &index;
+                s.spawn(move || {
+                    for _ in 0..250 {
+                        for asset in index.search("asset") {
+                            assert_eq!(asset.id, id);
+                        }
+                        for asset in index.get_by_category("props") {
+                            assert_eq!(asset.id, id);
+                        }
+                    }
+                });
+            }
+        });
+
+        let name = index.get(id).unwrap().name;
+        assert_eq!(index.name_index.len(), 1);
+        assert_eq!(*index.name_index.get(&name.to_lowercase()).unwrap(), [id]);
+        assert_eq!(index.get_by_name(&name).len(), 1);
+        assert_eq!(*index.category_index.get("props").unwrap(), [id]);
+        assert_eq!(index.file_path_index.len(), 1);
+    }
+}
@@ -0,0 +1,232 @@
+//! Asset parsers contributed by plugins
+//!
+//! The scanner reads type definitions from the file types it knows about,
+//! such as `.struct.json` or `.alias.json`. An [`AssetParser`] registered for
+//! an extension reads other formats: files whose name ends with the
+//! extension, or files directly in a folder whose name does, like the
+//! `graph_save.json` of a `Player.class` blueprint folder.
+//!
+//! A parser that declines a file, fails or panics leaves it to the built-in
+//! handling. Failures are logged and kept per file as [`ParseProblem`]s for
+//! the problems panel, so one bad file never aborts a scan.
+
+use anyhow::{Context, Result};
+use parking_lot::RwLock;
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::derived::blueprints;
+use crate::type_definition::TypeDefinition;
+
+/// Reads the type a file defines
+pub trait AssetParser: Send + Sync {
+    /// The type defined by the file at `path`, or `None` to leave the file
+    /// to the built-in file types
+    fn parse(&self, path: &Path) -> Result<Option<TypeRegistration>>;
+}
+
+/// A type read by an [`AssetParser`]. The asset index holds one asset per
+/// file, so a parser reports at most one type for each.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeRegistration {
+    pub name: String,
+    /// Fields, variants or methods, if the format has them
+    pub definition: Option<TypeDefinition>,
+    /// Names of the types the file refers to
+    pub references: Vec<String>,
+}
+
+impl TypeRegistration {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            definition: None,
+            references: Vec::new(),
+        }
+    }
+}
+
+/// A file a parser failed on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseProblem {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+/// A parser's type for a file, with the extension it is registered for
+pub(crate) struct Parsed {
+    pub extension: String,
+    pub registration: TypeRegistration,
+}
+
+/// The registered parsers, by extension, and the problems they reported
+#[derive(Default)]
+pub struct AssetParsers {
+    parsers: RwLock<Vec<(String, Arc<dyn AssetParser>)>>,
+    problems: RwLock<BTreeMap<PathBuf, String>>,
+}
+
+impl AssetParsers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse files with `extension`, with or without its leading dot, using
+    /// `parser`, replacing any parser registered for it before
+    pub fn register(&self, extension: &str, parser: Box<dyn AssetParser>) {
+        let extension = extension.trim_start_matches('.').to_string();
+        let mut parsers = self.parsers.write();
+        parsers.retain(|(registered, _)| *registered != extension);
+        parsers.push((extension, Arc::from(parser)));
+        // Longest first, so `bp.json` wins over `json`
+        parsers.sort_by_key(|(extension, _)| std::cmp::Reverse(extension.len()));
+    }
+
+    /// The extension of the parser registered for `path`, if any
+    pub fn extension_for(&self, path: &Path) -> Option<String> {
+        self.parser_for(path).map(|(extension, _)| extension)
+    }
+
+    /// The type the parser for `path` reads from it. `None` if no parser
+    /// handles the file, it declined it, or it failed, which is recorded as
+    /// a problem.
+    pub(crate) fn parse(&self, path: &Path) -> Option<Parsed> {
+        let (extension, parser) = self.parser_for(path)?;
+        let message = match std::panic::catch_unwind(AssertUnwindSafe(|| parser.parse(path))) {
+            Ok(Ok(registration)) => {
+                self.problems.write().remove(path);
+                return registration.map(|registration| Parsed {
+                    extension,
+                    registration,
+                });
+            }
+            Ok(Err(e)) => format!("{:#}", e),
+            Err(panic) => format!("Parser panicked: {}", panic_message(panic.as_ref())),
+        };
+        tracing::warn!("Failed to parse {:?} as .{}: {}", path, extension, message);
+        self.problems.write().insert(path.to_path_buf(), message);
+        None
+    }
+
+    /// Drop the problems of files at or under `path`, e.g. once removed
+    pub fn forget(&self, path: &Path) {
+        self.problems
+            .write()
+            .retain(|problem_path, _| !problem_path.starts_with(path));
+    }
+
+    /// Files the parsers failed on, by path
+    pub fn problems(&self) -> Vec<ParseProblem> {
+        self.problems
+            .read()
+            .iter()
+            .map(|(path, message)| ParseProblem {
+                path: path.clone(),
+                message: message.clone(),
+            })
+            .collect()
+    }
+
+    /// The parser whose extension ends the file name of `path`, or failing
+    /// that the name of the folder it is in
+    fn parser_for(&self, path: &Path) -> Option<(String, Arc<dyn AssetParser>)> {
+        let parsers = self.parsers.read();
+        let matching = |name: Option<&OsStr>| {
+            let name = name?.to_str()?;
+            parsers
+                .iter()
+                .find(|(extension, _)| {
+                    name.strip_suffix(extension.as_str())
+                        .is_some_and(|stem| stem.ends_with('.') && stem.len() > 1)
+                })
+                .map(|(extension, parser)| (extension.clone(), parser.clone()))
+        };
+        matching(path.file_name()).or_else(|| matching(path.parent()?.file_name()))
+    }
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown cause")
+}
+
+/// Reads blueprint class folders (`<Name>.class/`) as types named after the
+/// folder, referring to the data types their graph uses
+pub struct BlueprintClassParser;
+
+impl AssetParser for BlueprintClassParser {
+    fn parse(&self, path: &Path) -> Result<Option<TypeRegistration>> {
+        if path.file_name() != Some(OsStr::new(blueprints::CLASS_MARKER)) {
+            return Ok(None);
+        }
+        let Some(name) = path
+            .parent()
+            .and_then(|class_dir| class_dir.file_stem())
+            .and_then(|name| name.to_str())
+        else {
+            return Ok(None);
+        };
+
+        let json = std::fs::read_to_string(path)?;
+        let graph: serde_json::Value =
+            serde_json::from_str(&json).context("Invalid blueprint graph")?;
+        let mut registration = TypeRegistration::new(name);
+        registration.references = blueprints::graph_types(&graph);
+        Ok(Some(registration))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Panicking;
+
+    impl AssetParser for Panicking {
+        fn parse(&self, _path: &Path) -> Result<Option<TypeRegistration>> {
+            panic!("unsupported version");
+        }
+    }
+
+    #[test]
+    fn test_dispatch_and_problems() {
+        let dir = tempfile::tempdir().unwrap();
+        let class_dir = dir.path().join("Player.class");
+        std::fs::create_dir(&class_dir).unwrap();
+        let graph = class_dir.join(blueprints::CLASS_MARKER);
+        std::fs::write(&graph, r#"{"variables": [{"data_type": {"Data": {"base_type": "Inventory", "wrappers": []}}}]}"#).unwrap();
+
+        let parsers = AssetParsers::new();
+        parsers.register(".class", Box::new(BlueprintClassParser));
+        parsers.register("mesh", Box::new(Panicking));
+        assert_eq!(parsers.extension_for(&graph).as_deref(), Some("class"));
+        assert_eq!(parsers.extension_for(&dir.path().join("class")), None);
+
+        let parsed = parsers.parse(&graph).unwrap();
+        assert_eq!(parsed.extension, "class");
+        assert_eq!(parsed.registration.name, "Player");
+        assert_eq!(parsed.registration.references, ["Inventory"]);
+        assert!(parsers.parse(&class_dir.join("notes.txt")).is_none());
+        assert!(parsers.problems().is_empty());
+
+        std::fs::write(&graph, "{").unwrap();
+        assert!(parsers.parse(&graph).is_none());
+        let rock = dir.path().join("rock.mesh");
+        assert!(parsers.parse(&rock).is_none());
+        let problems = parsers.problems();
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].message.starts_with("Invalid blueprint graph"));
+        assert_eq!(problems[1].path, rock);
+        assert!(problems[1].message.contains("unsupported version"));
+
+        parsers.forget(&class_dir);
+        assert_eq!(parsers.problems().len(), 1);
+    }
+}
@@ -0,0 +1,181 @@
+//! Blueprint artifact registration
+//!
+//! A blueprint class folder (`<Name>.class/`) holds `graph_save.json` and an
+//! `events/` folder. Compiling it writes `events/.build/bytecode.json`, which the
+//! game project loads at startup. That artifact is registered here as derived
+//! from the class sources so edits mark it stale. The compiler lives in the
+//! blueprint editor, which provides the [`BLUEPRINT_REBUILDER`] callback;
+//! without it stale artifacts are only flagged.
+//!
+//! [`referenced_types`] reads the types a class's graph uses, for the asset
+//! index to link the class to the type assets it depends on.
+
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use super::{AssetKey, DerivedAssets, RebuilderId};
+
+pub const BLUEPRINT_REBUILDER: &str = "blueprint";
+/// File marking a folder as a blueprint class.
+pub const CLASS_MARKER: &str = "graph_save.json";
+
+/// Path of the compiled bytecode for the class folder `class_dir`.
+pub fn bytecode_path(class_dir: &Path) -> PathBuf {
+    class_dir
+        .join("events")
+        .join(".build")
+        .join("bytecode.json")
+}
+
+/// Source files the bytecode of `class_dir` is compiled from.
+pub fn class_inputs(class_dir: &Path) -> Vec<PathBuf> {
+    let mut inputs = vec![class_dir.join(CLASS_MARKER)];
+    if let Ok(entries) = std::fs::read_dir(class_dir.join("events")) {
+        let mut events: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.is_file()
+                    && !path
+                        .file_name()
+                        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+            })
+            .collect();
+        events.sort();
+        inputs.extend(events);
+    }
+    inputs
+}
+
+/// Register the bytecode artifact of one blueprint class.
+pub fn register_class(derived: &DerivedAssets, class_dir: &Path) {
+    let inputs = class_inputs(class_dir)
+        .into_iter()
+        .map(AssetKey::Path)
+        .collect();
+    if let Err(e) = derived.register_derivation(
+        AssetKey::Path(bytecode_path(class_dir)),
+        inputs,
+        RebuilderId::new(BLUEPRINT_REBUILDER),
+    ) {
+        tracing::warn!(
+            "Failed to register blueprint artifact for {:?}: {}",
+            class_dir,
+            e
+        );
+    }
+}
+
+/// Register every blueprint class found under `project_root`.
+pub fn register_project(derived: &DerivedAssets, project_root: &Path) {
+    for entry in walkdir::WalkDir::new(project_root)
+        .into_iter()
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            entry.depth() == 0 || (!name.starts_with('.') && name != "target")
+        })
+        .flatten()
+    {
+        if entry.file_type().is_file() && entry.file_name() == CLASS_MARKER {
+            if let Some(class_dir) = entry.path().parent() {
+                register_class(derived, class_dir);
+            }
+        }
+    }
+}
+
+/// Names of the data types of the pins and variables in a blueprint class,
+/// given its folder or its `graph_save.json`, each named once. Empty if the
+/// graph can't be read.
+pub fn referenced_types(class_path: &Path) -> Vec<String> {
+    let graph_file = if class_path.is_dir() {
+        class_path.join(CLASS_MARKER)
+    } else {
+        class_path.to_path_buf()
+    };
+    std::fs::read_to_string(&graph_file)
+        .ok()
+        .and_then(|json| serde_json::from_str::<Value>(&json).ok())
+        .map(|graph| graph_types(&graph))
+        .unwrap_or_default()
+}
+
+/// Names of the data types used in a class graph, each named once.
+pub fn graph_types(graph: &Value) -> Vec<String> {
+    let mut names = Vec::new();
+    collect_base_types(graph, &mut names);
+    names
+}
+
+/// Data types are `{ "Data": { "base_type": .., "wrappers": [..] } }`
+/// wherever they appear in the graph.
+fn collect_base_types(value: &Value, names: &mut Vec<String>) {
+    match value {
+        Value::Object(fields) => {
+            if let Some(Value::String(base_type)) = fields.get("base_type") {
+                if !names.contains(base_type) {
+                    names.push(base_type.clone());
+                }
+            }
+            for field in fields.values() {
+                collect_base_types(field, names);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_base_types(item, names);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::derived::RebuildMode;
+
+    #[test]
+    fn test_event_edit_marks_bytecode_stale() {
+        let dir = tempfile::tempdir().unwrap();
+        let class_dir = dir.path().join("src").join("classes").join("Player.class");
+        std::fs::create_dir_all(class_dir.join("events").join(".build")).unwrap();
+        std::fs::write(class_dir.join(CLASS_MARKER), "{}").unwrap();
+        std::fs::write(class_dir.join("events").join("tick.json"), "{}").unwrap();
+        std::fs::write(bytecode_path(&class_dir), "{}").unwrap();
+
+        let derived = DerivedAssets::new();
+        derived.set_mode(RebuildMode::Manual);
+        register_project(&derived, dir.path());
+
+        derived.on_input_changed(&class_dir.join("events").join("tick.json"));
+        assert_eq!(
+            derived.stale_outputs(),
+            vec![AssetKey::Path(bytecode_path(&class_dir))]
+        );
+    }
+
+    #[test]
+    fn test_referenced_types() {
+        let dir = tempfile::tempdir().unwrap();
+        let class_dir = dir.path().join("Player.class");
+        std::fs::create_dir_all(&class_dir).unwrap();
+        let data = |base_type: &str| serde_json::json!({ "Data": { "base_type": base_type, "wrappers": [] } });
+        let graph = serde_json::json!({
+            "main_graph": {
+                "nodes": {
+                    "a": { "inputs": [{ "pin": { "data_type": data("Inventory") } }] },
+                    "b": { "outputs": [{ "pin": { "data_type": "Execution" } }] }
+                }
+            },
+            "variables": [{ "name": "health", "data_type": data("f32") }, { "data_type": data("Inventory") }]
+        });
+        std::fs::write(class_dir.join(CLASS_MARKER), graph.to_string()).unwrap();
+
+        let mut names = referenced_types(&class_dir);
+        names.sort();
+        assert_eq!(names, ["Inventory", "f32"]);
+        assert_eq!(referenced_types(&class_dir.join(CLASS_MARKER)).len(), 2);
+        assert!(referenced_types(&dir.path().join("Missing.class")).is_empty());
+    }
+}
@@ -0,0 +1,224 @@
+//! Rebuild dispatcher: runs registered rebuilder callbacks for stale outputs.
+//!
+//! Levels from [`DependencyGraph::rebuild_order`] run one after another, so an
+//! output is never rebuilt before the outputs it reads from. Inside a level each
+//! rebuilder gets its own lane and runs at most `max_concurrency` jobs at once.
+//! Jobs run as tasks on the rayon pool.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use super::graph::{AssetKey, DependencyGraph, RebuilderId};
+
+/// Rebuilds one output from its inputs. Called on a background thread.
+pub type RebuildFn = Arc<dyn Fn(&AssetKey, &[AssetKey]) -> Result<(), String> + Send + Sync>;
+
+#[derive(Clone)]
+pub struct Rebuilder {
+    pub callback: RebuildFn,
+    /// Maximum number of outputs this rebuilder processes in parallel (at least 1).
+    pub max_concurrency: usize,
+}
+
+impl Rebuilder {
+    pub fn new(
+        max_concurrency: usize,
+        callback: impl Fn(&AssetKey, &[AssetKey]) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            callback: Arc::new(callback),
+            max_concurrency: max_concurrency.max(1),
+        }
+    }
+}
+
+/// Outcome of a dispatch run.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RebuildReport {
+    pub rebuilt: Vec<AssetKey>,
+    pub failed: Vec<(AssetKey, String)>,
+    /// Outputs with no registered rebuilder, or whose inputs failed to rebuild.
+    pub skipped: Vec<AssetKey>,
+}
+
+impl RebuildReport {
+    pub fn is_empty(&self) -> bool {
+        self.rebuilt.is_empty() && self.failed.is_empty() && self.skipped.is_empty()
+    }
+}
+
+/// Rebuilds `outputs` in topological order using `rebuilders`.
+///
+/// Blocks until every level has finished. The graph is only read; callers apply
+/// the report (e.g. [`DependencyGraph::mark_rebuilt`]) afterwards.
+pub fn dispatch(
+    graph: &DependencyGraph,
+    rebuilders: &HashMap<RebuilderId, Rebuilder>,
+    outputs: &[AssetKey],
+) -> RebuildReport {
+    let mut report = RebuildReport::default();
+
+    for level in graph.rebuild_order(outputs) {
+        let mut lanes: BTreeMap<&RebuilderId, Vec<(AssetKey, Vec<AssetKey>)>> = BTreeMap::new();
+        for output in level {
+            let Some(derivation) = graph.derivation(&output) else {
+                report.skipped.push(output);
+                continue;
+            };
+            // Don't rebuild on top of an input that just failed
+            let blocked = derivation.inputs.iter().any(|input| {
+                report.failed.iter().any(|(key, _)| key == input) || report.skipped.contains(input)
+            });
+            if blocked || !rebuilders.contains_key(&derivation.rebuilder) {
+                report.skipped.push(output);
+                continue;
+            }
+            lanes
+                .entry(&derivation.rebuilder)
+                .or_default()
+                .push((output, derivation.inputs.clone()));
+        }
+
+        let mut results: Vec<Vec<(AssetKey, Result<(), String>)>> = Vec::new();
+        results.resize_with(lanes.len(), Vec::new);
+        rayon::scope(|scope| {
+            for ((id, jobs), lane_results) in lanes.into_iter().zip(results.iter_mut()) {
+                let rebuilder = &rebuilders[id];
+                scope.spawn(move |_| *lane_results = run_lane(rebuilder, jobs));
+            }
+        });
+
+        for (output, result) in results.into_iter().flatten() {
+            match result {
+                Ok(()) => report.rebuilt.push(output),
+                Err(e) => report.failed.push((output, e)),
+            }
+        }
+    }
+
+    report
+}
+
+/// Runs one rebuilder's jobs, at most `max_concurrency` at a time.
+fn run_lane(
+    rebuilder: &Rebuilder,
+    jobs: Vec<(AssetKey, Vec<AssetKey>)>,
+) -> Vec<(AssetKey, Result<(), String>)> {
+    let mut results = Vec::with_capacity(jobs.len());
+    for chunk in jobs.chunks(rebuilder.max_concurrency) {
+        let mut chunk_results: Vec<Option<(AssetKey, Result<(), String>)>> = Vec::new();
+        chunk_results.resize_with(chunk.len(), || None);
+        rayon::scope(|scope| {
+            for ((output, inputs), slot) in chunk.iter().zip(chunk_results.iter_mut()) {
+                let callback = &rebuilder.callback;
+                scope.spawn(move |_| {
+                    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                        callback(output, inputs)
+                    }))
+                    .unwrap_or_else(|_| Err("rebuilder panicked".to_string()));
+                    *slot = Some((output.clone(), result));
+                });
+            }
+        });
+        results.extend(chunk_results.into_iter().flatten());
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    fn file(name: &str) -> AssetKey {
+        AssetKey::Path(PathBuf::from(name))
+    }
+
+    #[test]
+    fn test_respects_concurrency_limit() {
+        let mut graph = DependencyGraph::new();
+        let outputs: Vec<AssetKey> = (0..8)
+            .map(|i| AssetKey::derived(format!("thumb:{i}")))
+            .collect();
+        for (i, output) in outputs.iter().enumerate() {
+            graph
+                .register_derivation(
+                    output.clone(),
+                    vec![file(&format!("{i}.png"))],
+                    RebuilderId::new("thumb"),
+                )
+                .unwrap();
+        }
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (running_cb, peak_cb) = (Arc::clone(&running), Arc::clone(&peak));
+        let rebuilders = HashMap::from([(
+            RebuilderId::new("thumb"),
+            Rebuilder::new(2, move |_, _| {
+                let now = running_cb.fetch_add(1, Ordering::SeqCst) + 1;
+                peak_cb.fetch_max(now, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(10));
+                running_cb.fetch_sub(1, Ordering::SeqCst);
+                Ok(())
+            }),
+        )]);
+
+        let report = dispatch(&graph, &rebuilders, &outputs);
+        assert_eq!(report.rebuilt.len(), 8);
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[test]
+    fn test_runs_levels_in_order_and_skips_after_failure() {
+        let mut graph = DependencyGraph::new();
+        let a = AssetKey::derived("a");
+        let b = AssetKey::derived("b");
+        let c = AssetKey::derived("c");
+        graph
+            .register_derivation(a.clone(), vec![file("src")], RebuilderId::new("ok"))
+            .unwrap();
+        graph
+            .register_derivation(b.clone(), vec![a.clone()], RebuilderId::new("fail"))
+            .unwrap();
+        graph
+            .register_derivation(c.clone(), vec![b.clone()], RebuilderId::new("ok"))
+            .unwrap();
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let order_cb = Arc::clone(&order);
+        let rebuilders = HashMap::from([
+            (
+                RebuilderId::new("ok"),
+                Rebuilder::new(4, move |output, _| {
+                    order_cb.lock().push(output.clone());
+                    Ok(())
+                }),
+            ),
+            (
+                RebuilderId::new("fail"),
+                Rebuilder::new(1, |_, _| Err("boom".to_string())),
+            ),
+        ]);
+
+        let report = dispatch(&graph, &rebuilders, &[c.clone(), b.clone(), a.clone()]);
+        assert_eq!(report.rebuilt, vec![a.clone()]);
+        assert_eq!(report.failed, vec![(b, "boom".to_string())]);
+        assert_eq!(report.skipped, vec![c]);
+        assert_eq!(*order.lock(), vec![a]);
+    }
+
+    #[test]
+    fn test_missing_rebuilder_is_skipped() {
+        let mut graph = DependencyGraph::new();
+        let out = AssetKey::derived("x");
+        graph
+            .register_derivation(out.clone(), vec![file("src")], RebuilderId::new("none"))
+            .unwrap();
+        let report = dispatch(&graph, &HashMap::new(), std::slice::from_ref(&out));
+        assert_eq!(report.skipped, vec![out]);
+    }
+}
@@ -0,0 +1,579 @@
+//! Derived-asset dependency graph.
+//!
+//! Pure bookkeeping: which outputs are derived from which inputs, which outputs
+//! are stale, and in which order stale outputs must be rebuilt. No I/O besides
+//! the explicit [`DependencyGraph::save`]/[`DependencyGraph::load`] helpers.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Version of the on-disk graph format.
+const GRAPH_FORMAT_VERSION: u32 = 1;
+
+/// Node in the dependency graph: a project file or a named derived artifact.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "key", rename_all = "lowercase")]
+pub enum AssetKey {
+    Path(PathBuf),
+    Derived(String),
+}
+
+impl AssetKey {
+    pub fn derived(key: impl Into<String>) -> Self {
+        AssetKey::Derived(key.into())
+    }
+
+    pub fn as_path(&self) -> Option<&Path> {
+        match self {
+            AssetKey::Path(path) => Some(path),
+            AssetKey::Derived(_) => None,
+        }
+    }
+}
+
+impl From<PathBuf> for AssetKey {
+    fn from(path: PathBuf) -> Self {
+        AssetKey::Path(path)
+    }
+}
+
+impl From<&Path> for AssetKey {
+    fn from(path: &Path) -> Self {
+        AssetKey::Path(path.to_path_buf())
+    }
+}
+
+impl fmt::Display for AssetKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetKey::Path(path) => write!(f, "{}", path.display()),
+            AssetKey::Derived(key) => write!(f, "{}", key),
+        }
+    }
+}
+
+/// Identifies the callback that knows how to rebuild an output.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct RebuilderId(pub String);
+
+impl RebuilderId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for RebuilderId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// One registered derivation: `output` is produced from `inputs` by `rebuilder`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Derivation {
+    pub output: AssetKey,
+    pub inputs: Vec<AssetKey>,
+    pub rebuilder: RebuilderId,
+}
+
+#[derive(Debug)]
+pub enum DerivationError {
+    /// Registering the derivation would make `cycle[0]` depend on itself.
+    Cycle {
+        cycle: Vec<AssetKey>,
+    },
+    Io(std::io::Error),
+    Parse(serde_json::Error),
+    UnsupportedVersion {
+        found: u32,
+    },
+}
+
+impl fmt::Display for DerivationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DerivationError::Cycle { cycle } => {
+                let path: Vec<String> = cycle.iter().map(ToString::to_string).collect();
+                write!(f, "Derivation would create a cycle: {}", path.join(" -> "))
+            }
+            DerivationError::Io(e) => write!(f, "Failed to access dependency graph: {}", e),
+            DerivationError::Parse(e) => write!(f, "Invalid dependency graph: {}", e),
+            DerivationError::UnsupportedVersion { found } => write!(
+                f,
+                "Unsupported dependency graph version {} (expected {})",
+                found, GRAPH_FORMAT_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DerivationError {}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedGraph {
+    version: u32,
+    derivations: Vec<Derivation>,
+    stale: Vec<AssetKey>,
+}
+
+/// Graph of derivations plus the current stale set.
+#[derive(Debug, Clone, Default)]
+pub struct DependencyGraph {
+    /// Derivations keyed by output
+    derivations: BTreeMap<AssetKey, Derivation>,
+    /// Reverse edges: input -> outputs that read it directly
+    dependents: HashMap<AssetKey, BTreeSet<AssetKey>>,
+    stale: BTreeSet<AssetKey>,
+}
+
+impl DependencyGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers (or replaces) the derivation of `output`.
+    ///
+    /// Rejects the derivation if any input is `output` itself or is derived,
+    /// directly or transitively, from `output`.
+    pub fn register_derivation(
+        &mut self,
+        output: AssetKey,
+        inputs: Vec<AssetKey>,
+        rebuilder: RebuilderId,
+    ) -> Result<(), DerivationError> {
+        if let Some(mut cycle) = self.path_to_any(&output, &inputs) {
+            cycle.push(output);
+            return Err(DerivationError::Cycle { cycle });
+        }
+
+        self.unregister_derivation(&output);
+        for input in &inputs {
+            self.dependents
+                .entry(input.clone())
+                .or_default()
+                .insert(output.clone());
+        }
+        self.derivations.insert(
+            output.clone(),
+            Derivation {
+                output,
+                inputs,
+                rebuilder,
+            },
+        );
+        Ok(())
+    }
+
+    /// Removes the derivation of `output`. Outputs derived from it keep their
+    /// registration; `output` simply becomes a plain input for them.
+    pub fn unregister_derivation(&mut self, output: &AssetKey) -> Option<Derivation> {
+        let derivation = self.derivations.remove(output)?;
+        for input in &derivation.inputs {
+            if let Some(outputs) = self.dependents.get_mut(input) {
+                outputs.remove(output);
+                if outputs.is_empty() {
+                    self.dependents.remove(input);
+                }
+            }
+        }
+        self.stale.remove(output);
+        Some(derivation)
+    }
+
+    /// Breadth-first search along reverse edges from `start` to any of `targets`.
+    /// Returns the path `[start, ..., target]` if one exists.
+    fn path_to_any(&self, start: &AssetKey, targets: &[AssetKey]) -> Option<Vec<AssetKey>> {
+        let targets: BTreeSet<&AssetKey> = targets.iter().collect();
+        let mut parents: HashMap<&AssetKey, &AssetKey> = HashMap::new();
+        let mut queue = VecDeque::from([start]);
+        let mut seen = BTreeSet::from([start]);
+
+        while let Some(node) = queue.pop_front() {
+            if targets.contains(node) {
+                let mut path = vec![node.clone()];
+                let mut current = node;
+                while let Some(parent) = parents.get(current) {
+                    path.push((*parent).clone());
+                    current = parent;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for next in self.dependents.get(node).into_iter().flatten() {
+                if seen.insert(next) {
+                    parents.insert(next, node);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn derivation(&self, output: &AssetKey) -> Option<&Derivation> {
+        self.derivations.get(output)
+    }
+
+    pub fn derivations(&self) -> impl Iterator<Item = &Derivation> {
+        self.derivations.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.derivations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.derivations.is_empty()
+    }
+
+    /// Outputs that read `input` directly.
+    pub fn direct_dependents(&self, input: &AssetKey) -> Vec<AssetKey> {
+        self.dependents
+            .get(input)
+            .map(|outputs| outputs.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Marks every output transitively derived from `changed` as stale.
+    ///
+    /// Returns the outputs that were not already stale, in breadth-first order.
+    pub fn mark_changed(&mut self, changed: &AssetKey) -> Vec<AssetKey> {
+        let mut newly_stale = Vec::new();
+        let mut queue = VecDeque::from([changed.clone()]);
+        let mut seen = BTreeSet::from([changed.clone()]);
+
+        while let Some(node) = queue.pop_front() {
+            for output in self.dependents.get(&node).into_iter().flatten() {
+                if !seen.insert(output.clone()) {
+                    continue;
+                }
+                if self.stale.insert(output.clone()) {
+                    newly_stale.push(output.clone());
+                }
+                queue.push_back(output.clone());
+            }
+        }
+        newly_stale
+    }
+
+    /// Clears the stale flag after a successful rebuild.
+    pub fn mark_rebuilt(&mut self, output: &AssetKey) {
+        self.stale.remove(output);
+    }
+
+    pub fn is_stale(&self, output: &AssetKey) -> bool {
+        self.stale.contains(output)
+    }
+
+    /// Whether any output read directly from `input` is stale (file manager badges).
+    pub fn has_stale_dependents(&self, input: &AssetKey) -> bool {
+        self.dependents
+            .get(input)
+            .is_some_and(|outputs| outputs.iter().any(|o| self.stale.contains(o)))
+    }
+
+    pub fn stale_outputs(&self) -> Vec<AssetKey> {
+        self.stale.iter().cloned().collect()
+    }
+
+    /// Depth of a key: 0 for plain inputs, otherwise one more than its deepest input.
+    fn depth(&self, key: &AssetKey, memo: &mut HashMap<AssetKey, usize>) -> usize {
+        if let Some(depth) = memo.get(key) {
+            return *depth;
+        }
+        let depth = match self.derivations.get(key) {
+            None => 0,
+            Some(derivation) => {
+                1 + derivation
+                    .inputs
+                    .iter()
+                    .map(|input| self.depth(input, memo))
+                    .max()
+                    .unwrap_or(0)
+            }
+        };
+        memo.insert(key.clone(), depth);
+        depth
+    }
+
+    /// Groups `outputs` into levels that can be rebuilt in order: every output
+    /// comes after everything it is (transitively) derived from. Outputs within a
+    /// level are independent of each other.
+    pub fn rebuild_order(&self, outputs: &[AssetKey]) -> Vec<Vec<AssetKey>> {
+        let mut memo = HashMap::new();
+        let mut levels: BTreeMap<usize, BTreeSet<AssetKey>> = BTreeMap::new();
+        for output in outputs {
+            let depth = self.depth(output, &mut memo);
+            levels.entry(depth).or_default().insert(output.clone());
+        }
+        levels
+            .into_values()
+            .map(|level| level.into_iter().collect())
+            .collect()
+    }
+
+    pub fn to_json(&self) -> Result<String, DerivationError> {
+        let persisted = PersistedGraph {
+            version: GRAPH_FORMAT_VERSION,
+            derivations: self.derivations.values().cloned().collect(),
+            stale: self.stale.iter().cloned().collect(),
+        };
+        serde_json::to_string_pretty(&persisted).map_err(DerivationError::Parse)
+    }
+
+    /// Rebuilds a graph from JSON, re-validating every derivation so a hand-edited
+    /// file can't smuggle in a cycle.
+    pub fn from_json(json: &str) -> Result<Self, DerivationError> {
+        let persisted: PersistedGraph =
+            serde_json::from_str(json).map_err(DerivationError::Parse)?;
+        if persisted.version != GRAPH_FORMAT_VERSION {
+            return Err(DerivationError::UnsupportedVersion {
+                found: persisted.version,
+            });
+        }
+
+        let mut graph = Self::new();
+        for derivation in persisted.derivations {
+            graph.register_derivation(
+                derivation.output,
+                derivation.inputs,
+                derivation.rebuilder,
+            )?;
+        }
+        graph.stale = persisted
+            .stale
+            .into_iter()
+            .filter(|key| graph.derivations.contains_key(key))
+            .collect();
+        Ok(graph)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), DerivationError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(DerivationError::Io)?;
+        }
+        std::fs::write(path, self.to_json()?).map_err(DerivationError::Io)
+    }
+
+    /// Loads a graph from `path`, returning an empty graph if the file doesn't exist.
+    pub fn load(path: &Path) -> Result<Self, DerivationError> {
+        match std::fs::read_to_string(path) {
+            Ok(json) => Self::from_json(&json),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::new()),
+            Err(e) => Err(DerivationError::Io(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str) -> AssetKey {
+        AssetKey::Path(PathBuf::from(name))
+    }
+
+    fn rb(id: &str) -> RebuilderId {
+        RebuilderId::new(id)
+    }
+
+    /// texture.png -> material.preview, texture.png -> mesh.preview,
+    /// both previews -> level.bake (a diamond)
+    fn diamond() -> DependencyGraph {
+        let mut graph = DependencyGraph::new();
+        graph
+            .register_derivation(
+                AssetKey::derived("material.preview"),
+                vec![file("texture.png"), file("material.json")],
+                rb("preview"),
+            )
+            .unwrap();
+        graph
+            .register_derivation(
+                AssetKey::derived("mesh.preview"),
+                vec![file("texture.png"), file("mesh.glb")],
+                rb("preview"),
+            )
+            .unwrap();
+        graph
+            .register_derivation(
+                AssetKey::derived("level.bake"),
+                vec![
+                    AssetKey::derived("material.preview"),
+                    AssetKey::derived("mesh.preview"),
+                ],
+                rb("bake"),
+            )
+            .unwrap();
+        graph
+    }
+
+    #[test]
+    fn test_diamond_marks_all_transitive_outputs_once() {
+        let mut graph = diamond();
+        let stale = graph.mark_changed(&file("texture.png"));
+        assert_eq!(stale.len(), 3);
+        assert_eq!(stale.last(), Some(&AssetKey::derived("level.bake")));
+
+        // Already stale: nothing new
+        assert!(graph.mark_changed(&file("texture.png")).is_empty());
+        assert!(graph.has_stale_dependents(&file("texture.png")));
+    }
+
+    #[test]
+    fn test_unrelated_input_only_marks_its_branch() {
+        let mut graph = diamond();
+        let stale = graph.mark_changed(&file("mesh.glb"));
+        assert_eq!(
+            stale,
+            vec![
+                AssetKey::derived("mesh.preview"),
+                AssetKey::derived("level.bake")
+            ]
+        );
+        assert!(!graph.is_stale(&AssetKey::derived("material.preview")));
+    }
+
+    #[test]
+    fn test_derived_output_as_input() {
+        let mut graph = diamond();
+        // Changing a derived output directly (e.g. it was rebuilt) invalidates what reads it
+        let stale = graph.mark_changed(&AssetKey::derived("material.preview"));
+        assert_eq!(stale, vec![AssetKey::derived("level.bake")]);
+    }
+
+    #[test]
+    fn test_rebuild_order_respects_topology() {
+        let mut graph = diamond();
+        graph.mark_changed(&file("texture.png"));
+        let order = graph.rebuild_order(&graph.stale_outputs());
+        assert_eq!(
+            order,
+            vec![
+                vec![
+                    AssetKey::derived("material.preview"),
+                    AssetKey::derived("mesh.preview")
+                ],
+                vec![AssetKey::derived("level.bake")],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rebuild_order_for_partial_set() {
+        let graph = diamond();
+        let order = graph.rebuild_order(&[
+            AssetKey::derived("level.bake"),
+            AssetKey::derived("mesh.preview"),
+        ]);
+        assert_eq!(
+            order,
+            vec![
+                vec![AssetKey::derived("mesh.preview")],
+                vec![AssetKey::derived("level.bake")],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cycle_rejected() {
+        let mut graph = diamond();
+        let err = graph
+            .register_derivation(
+                AssetKey::derived("material.preview"),
+                vec![AssetKey::derived("level.bake")],
+                rb("preview"),
+            )
+            .unwrap_err();
+        match err {
+            DerivationError::Cycle { cycle } => {
+                assert_eq!(cycle.first(), Some(&AssetKey::derived("material.preview")));
+                assert_eq!(cycle.last(), Some(&AssetKey::derived("material.preview")));
+                assert!(cycle.contains(&AssetKey::derived("level.bake")));
+            }
+            other => panic!("expected cycle, got {:?}", other),
+        }
+        // The original derivation is untouched
+        assert_eq!(
+            graph
+                .derivation(&AssetKey::derived("material.preview"))
+                .unwrap()
+                .inputs
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_self_dependency_rejected() {
+        let mut graph = DependencyGraph::new();
+        let err = graph.register_derivation(
+            AssetKey::derived("a"),
+            vec![AssetKey::derived("a")],
+            rb("x"),
+        );
+        assert!(matches!(err, Err(DerivationError::Cycle { .. })));
+    }
+
+    #[test]
+    fn test_reregistration_replaces_edges() {
+        let mut graph = diamond();
+        graph
+            .register_derivation(
+                AssetKey::derived("mesh.preview"),
+                vec![file("mesh.glb")],
+                rb("preview"),
+            )
+            .unwrap();
+        let stale = graph.mark_changed(&file("texture.png"));
+        assert!(!stale.contains(&AssetKey::derived("mesh.preview")));
+    }
+
+    #[test]
+    fn test_persistence_round_trip() {
+        let mut graph = diamond();
+        graph.mark_changed(&file("mesh.glb"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".pulsar").join("derived_graph.json");
+        graph.save(&path).unwrap();
+
+        let loaded = DependencyGraph::load(&path).unwrap();
+        assert_eq!(loaded.len(), 3);
+        assert_eq!(loaded.stale_outputs(), graph.stale_outputs());
+        assert_eq!(
+            loaded.direct_dependents(&file("texture.png")),
+            graph.direct_dependents(&file("texture.png"))
+        );
+    }
+
+    #[test]
+    fn test_load_missing_file_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let graph = DependencyGraph::load(&dir.path().join("nope.json")).unwrap();
+        assert!(graph.is_empty());
+    }
+
+    #[test]
+    fn test_persisted_cycle_rejected() {
+        let json = r#"{
+            "version": 1,
+            "derivations": [
+                { "output": { "kind": "derived", "key": "a" },
+                  "inputs": [{ "kind": "derived", "key": "b" }], "rebuilder": "x" },
+                { "output": { "kind": "derived", "key": "b" },
+                  "inputs": [{ "kind": "derived", "key": "a" }], "rebuilder": "x" }
+            ],
+            "stale": []
+        }"#;
+        assert!(matches!(
+            DependencyGraph::from_json(json),
+            Err(DerivationError::Cycle { .. })
+        ));
+    }
+}
@@ -0,0 +1,375 @@
+//! Derived-asset tracking
+//!
+//! Producers of derived data (thumbnails, compiled blueprints, previews, ...)
+//! register which inputs each output is built from. When the watcher reports a
+//! change to any input, every output transitively derived from it is marked
+//! stale and, in [`RebuildMode::Automatic`], queued for rebuild on the
+//! background thread pool. Changes to the graph are saved on the same pool,
+//! once per burst of changes.
+//!
+//! - [`graph`] - Dependency graph, staleness propagation and persistence
+//! - [`dispatcher`] - Topologically ordered rebuilds with per-rebuilder limits
+//! - [`blueprints`] - Blueprint bytecode artifact registration
+
+#[cfg(feature = "editor")]
+pub mod blueprints;
+pub mod dispatcher;
+pub mod graph;
+
+pub use dispatcher::{RebuildFn, RebuildReport, Rebuilder};
+pub use graph::{AssetKey, DependencyGraph, Derivation, DerivationError, RebuilderId};
+
+use parking_lot::{Mutex, RwLock};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+/// Location of the persisted graph relative to the project root.
+pub const GRAPH_FILE: &str = ".pulsar/derived_graph.json";
+
+/// Whether stale outputs are rebuilt as soon as they're detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RebuildMode {
+    #[default]
+    Automatic,
+    /// Only flag outputs as stale; rebuilds happen on [`DerivedAssets::request_rebuild_all`].
+    Manual,
+}
+
+static GLOBAL_DERIVED: OnceLock<DerivedAssets> = OnceLock::new();
+
+/// Access the process-wide derived-asset tracker.
+pub fn global() -> &'static DerivedAssets {
+    GLOBAL_DERIVED.get_or_init(DerivedAssets::new)
+}
+
+pub struct DerivedAssets {
+    graph: Arc<Mutex<DependencyGraph>>,
+    rebuilders: Arc<RwLock<HashMap<RebuilderId, Rebuilder>>>,
+    mode: RwLock<RebuildMode>,
+    store: Arc<GraphStore>,
+    rebuilds: Arc<RebuildQueue>,
+}
+
+/// Where the graph is saved. Saves run on the background pool, and changes
+/// made while one is queued are written by it.
+#[derive(Default)]
+struct GraphStore {
+    path: RwLock<Option<PathBuf>>,
+    /// Set while a save is queued and hasn't taken its snapshot yet
+    scheduled: AtomicBool,
+    /// Held from snapshot to write, so saves land in order
+    writing: Mutex<()>,
+}
+
+impl GraphStore {
+    fn save_later(self: &Arc<Self>, graph: &Arc<Mutex<DependencyGraph>>) {
+        if self.path.read().is_none() || self.scheduled.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let (store, graph) = (Arc::clone(self), Arc::clone(graph));
+        rayon::spawn(move || store.save(&graph));
+    }
+
+    fn save(&self, graph: &Mutex<DependencyGraph>) {
+        let _writing = self.writing.lock();
+        // Changes made from here on queue another save
+        self.scheduled.store(false, Ordering::Release);
+        let Some(path) = self.path.read().clone() else {
+            return;
+        };
+        let snapshot = graph.lock().clone();
+        if let Err(e) = snapshot.save(&path) {
+            tracing::warn!("Failed to save derived-asset graph: {}", e);
+        }
+    }
+}
+
+/// Outputs waiting to be rebuilt on the background pool
+#[derive(Default)]
+struct RebuildQueue {
+    pending: Mutex<Vec<AssetKey>>,
+    /// Set while a task draining `pending` is queued or running
+    draining: AtomicBool,
+}
+
+impl Default for DerivedAssets {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DerivedAssets {
+    pub fn new() -> Self {
+        Self {
+            graph: Arc::new(Mutex::new(DependencyGraph::new())),
+            rebuilders: Arc::new(RwLock::new(HashMap::new())),
+            mode: RwLock::new(RebuildMode::default()),
+            store: Arc::default(),
+            rebuilds: Arc::default(),
+        }
+    }
+
+    /// Load the persisted graph for `project_root` and save future changes there.
+    pub fn open_project(&self, project_root: &Path) {
+        // Changes to the previous project still go to its file
+        self.flush();
+        let path = project_root.join(GRAPH_FILE);
+        let graph = DependencyGraph::load(&path).unwrap_or_else(|e| {
+            tracing::warn!("Discarding derived-asset graph at {:?}: {}", path, e);
+            DependencyGraph::new()
+        });
+        tracing::debug!(
+            "Loaded derived-asset graph: {} derivations, {} stale",
+            graph.len(),
+            graph.stale_outputs().len()
+        );
+        *self.graph.lock() = graph;
+        *self.store.path.write() = Some(path);
+    }
+
+    /// Save the graph now if it has changes waiting to be saved.
+    pub fn flush(&self) {
+        if self.store.scheduled.load(Ordering::Acquire) {
+            self.store.save(&self.graph);
+        }
+    }
+
+    pub fn mode(&self) -> RebuildMode {
+        *self.mode.read()
+    }
+
+    pub fn set_mode(&self, mode: RebuildMode) {
+        *self.mode.write() = mode;
+    }
+
+    /// Register (or replace) how `output` is derived.
+    pub fn register_derivation(
+        &self,
+        output: AssetKey,
+        inputs: Vec<AssetKey>,
+        rebuilder: RebuilderId,
+    ) -> Result<(), DerivationError> {
+        let mut graph = self.graph.lock();
+        if graph
+            .derivation(&output)
+            .is_some_and(|d| d.inputs == inputs && d.rebuilder == rebuilder)
+        {
+            return Ok(());
+        }
+        graph.register_derivation(output, inputs, rebuilder)?;
+        self.store.save_later(&self.graph);
+        Ok(())
+    }
+
+    pub fn unregister_derivation(&self, output: &AssetKey) {
+        let mut graph = self.graph.lock();
+        if graph.unregister_derivation(output).is_some() {
+            self.store.save_later(&self.graph);
+        }
+    }
+
+    /// Register the callback that rebuilds outputs tagged with `id`.
+    pub fn register_rebuilder(&self, id: RebuilderId, rebuilder: Rebuilder) {
+        self.rebuilders.write().insert(id, rebuilder);
+    }
+
+    /// Called by the watcher for every created, modified or removed file.
+    pub fn on_input_changed(&self, path: &Path) {
+        let newly_stale = {
+            let mut graph = self.graph.lock();
+            let newly_stale = graph.mark_changed(&AssetKey::from(path));
+            if !newly_stale.is_empty() {
+                self.store.save_later(&self.graph);
+            }
+            newly_stale
+        };
+        if newly_stale.is_empty() {
+            return;
+        }
+
+        tracing::debug!(
+            "{:?} changed: {} derived output(s) now stale",
+            path,
+            newly_stale.len()
+        );
+        if self.mode() == RebuildMode::Automatic {
+            self.queue(newly_stale);
+        }
+    }
+
+    pub fn stale_outputs(&self) -> Vec<AssetKey> {
+        self.graph.lock().stale_outputs()
+    }
+
+    pub fn is_stale(&self, output: &AssetKey) -> bool {
+        self.graph.lock().is_stale(output)
+    }
+
+    /// Whether anything built directly from the file at `path` is stale.
+    pub fn has_stale_dependents(&self, path: &Path) -> bool {
+        self.graph
+            .lock()
+            .has_stale_dependents(&AssetKey::from(path))
+    }
+
+    /// Clear the stale flag of an output its producer rebuilt on its own.
+    pub fn mark_rebuilt(&self, output: &AssetKey) {
+        let mut graph = self.graph.lock();
+        if graph.is_stale(output) {
+            graph.mark_rebuilt(output);
+            self.store.save_later(&self.graph);
+        }
+    }
+
+    /// Queue every stale output for rebuild on the background worker.
+    pub fn request_rebuild_all(&self) {
+        let stale = self.stale_outputs();
+        if !stale.is_empty() {
+            self.queue(stale);
+        }
+    }
+
+    /// Rebuild every stale output on the calling thread.
+    pub fn rebuild_stale_blocking(&self) -> RebuildReport {
+        Self::rebuild(
+            &self.graph,
+            &self.rebuilders,
+            &self.store,
+            self.stale_outputs(),
+        )
+    }
+
+    fn queue(&self, outputs: Vec<AssetKey>) {
+        self.rebuilds.pending.lock().extend(outputs);
+        if self.rebuilds.draining.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let graph = Arc::clone(&self.graph);
+        let rebuilders = Arc::clone(&self.rebuilders);
+        let store = Arc::clone(&self.store);
+        let rebuilds = Arc::clone(&self.rebuilds);
+        rayon::spawn(move || Self::drain_rebuilds(&rebuilds, &graph, &rebuilders, &store));
+    }
+
+    fn drain_rebuilds(
+        rebuilds: &RebuildQueue,
+        graph: &Arc<Mutex<DependencyGraph>>,
+        rebuilders: &RwLock<HashMap<RebuilderId, Rebuilder>>,
+        store: &Arc<GraphStore>,
+    ) {
+        loop {
+            // Coalesce bursts (e.g. a save touching several files) into one run
+            let mut outputs = std::mem::take(&mut *rebuilds.pending.lock());
+            if outputs.is_empty() {
+                rebuilds.draining.store(false, Ordering::Release);
+                // Outputs queued since the take found the queue still draining
+                if rebuilds.pending.lock().is_empty()
+                    || rebuilds.draining.swap(true, Ordering::AcqRel)
+                {
+                    return;
+                }
+                continue;
+            }
+            outputs.sort();
+            outputs.dedup();
+
+            let report = Self::rebuild(graph, rebuilders, store, outputs);
+            if !report.is_empty() {
+                tracing::info!(
+                    "Derived rebuild: {} rebuilt, {} failed, {} left stale",
+                    report.rebuilt.len(),
+                    report.failed.len(),
+                    report.skipped.len()
+                );
+            }
+            for (output, e) in &report.failed {
+                tracing::warn!("Failed to rebuild {}: {}", output, e);
+            }
+        }
+    }
+
+    fn rebuild(
+        graph: &Arc<Mutex<DependencyGraph>>,
+        rebuilders: &RwLock<HashMap<RebuilderId, Rebuilder>>,
+        store: &Arc<GraphStore>,
+        outputs: Vec<AssetKey>,
+    ) -> RebuildReport {
+        // Rebuild from a snapshot so callbacks never run under the graph lock
+        let (snapshot, outputs) = {
+            let graph = graph.lock();
+            let outputs: Vec<AssetKey> = outputs
+                .into_iter()
+                .filter(|output| graph.is_stale(output))
+                .collect();
+            (graph.clone(), outputs)
+        };
+        if outputs.is_empty() {
+            return RebuildReport::default();
+        }
+
+        let rebuilders = rebuilders.read().clone();
+        let report = dispatcher::dispatch(&snapshot, &rebuilders, &outputs);
+
+        {
+            let mut graph = graph.lock();
+            for output in &report.rebuilt {
+                graph.mark_rebuilt(output);
+            }
+        }
+        store.save_later(graph);
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_mode_only_flags() {
+        let derived = DerivedAssets::new();
+        derived.set_mode(RebuildMode::Manual);
+        derived
+            .register_derivation(
+                AssetKey::derived("thumb"),
+                vec![AssetKey::Path(PathBuf::from("/p/a.png"))],
+                RebuilderId::new("thumb"),
+            )
+            .unwrap();
+
+        derived.on_input_changed(Path::new("/p/a.png"));
+        assert!(derived.has_stale_dependents(Path::new("/p/a.png")));
+        assert!(derived.rebuilds.pending.lock().is_empty());
+        assert!(!derived.rebuilds.draining.load(Ordering::Acquire));
+
+        derived.register_rebuilder(RebuilderId::new("thumb"), Rebuilder::new(1, |_, _| Ok(())));
+        let report = derived.rebuild_stale_blocking();
+        assert_eq!(report.rebuilt, vec![AssetKey::derived("thumb")]);
+        assert!(derived.stale_outputs().is_empty());
+    }
+
+    #[test]
+    fn test_open_project_persists_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("a.png");
+
+        let derived = DerivedAssets::new();
+        derived.set_mode(RebuildMode::Manual);
+        derived.open_project(dir.path());
+        derived
+            .register_derivation(
+                AssetKey::derived("thumb"),
+                vec![AssetKey::from(input.as_path())],
+                RebuilderId::new("thumb"),
+            )
+            .unwrap();
+        derived.on_input_changed(&input);
+        derived.flush();
+
+        let reopened = DerivedAssets::new();
+        reopened.open_project(dir.path());
+        assert_eq!(reopened.stale_outputs(), vec![AssetKey::derived("thumb")]);
+    }
+}
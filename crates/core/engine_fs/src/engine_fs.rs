@@ -3,14 +3,20 @@
 //! Coordinates all asset operations and maintains type database.
 
 use anyhow::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
 use std::sync::Arc;
 
-use crate::asset_index::AssetIndex;
+use crate::asset_catalog::{AssetCatalog, AssetEntry, HashSettings};
+use crate::asset_index::{self, AssetIndex, StaleRefresh};
+use crate::asset_parsers::{AssetParser, AssetParsers, BlueprintClassParser, ParseProblem};
+use crate::derived;
 use crate::operations::AssetOperations;
-use crate::scanner::ProjectScanner;
+use crate::project_ignore::ProjectIgnore;
+use crate::scanner::{ProjectScanner, ScanProgress, ScanReport};
+use crate::templates::AssetKind;
 use crate::user_types::UserTypeRegistry;
-use crate::watchers;
+use crate::watchers::{self, WatcherCounters, WatcherStats};
 
 /// The main engine filesystem manager
 pub struct EngineFs {
@@ -19,22 +25,54 @@ pub struct EngineFs {
     user_types: Arc<UserTypeRegistry>,
     operations: AssetOperations,
     scanner: ProjectScanner,
+    ignore: Arc<ProjectIgnore>,
+    catalog: Arc<AssetCatalog>,
+    parsers: Arc<AssetParsers>,
+    watcher_counters: Arc<WatcherCounters>,
 }
 
 impl EngineFs {
     /// Create a new EngineFs instance for a project
+    ///
+    /// The asset index is loaded from the project's cache when there is a
+    /// usable one, and then only files changed since it was saved are
+    /// rescanned. Otherwise the whole project is scanned.
     pub fn new(project_root: PathBuf) -> Result<Self> {
-        let asset_index = Arc::new(AssetIndex::new());
+        Self::open(project_root, None)
+    }
+
+    /// Like [`Self::new`], sending the progress of the initial scan to
+    /// `progress` at a throttled rate. The last update is marked finished.
+    pub fn new_with_progress(
+        project_root: PathBuf,
+        progress: Sender<ScanProgress>,
+    ) -> Result<Self> {
+        Self::open(project_root, Some(progress))
+    }
+
+    fn open(project_root: PathBuf, progress: Option<Sender<ScanProgress>>) -> Result<Self> {
+        derived::global().open_project(&project_root);
+
+        let cached_index = Self::load_index_cache(&project_root);
+        let incremental = cached_index.is_some();
+        let asset_index = Arc::new(cached_index.unwrap_or_default());
         let user_types = Arc::new(UserTypeRegistry::new());
         let operations = AssetOperations::new(
             project_root.clone(),
             asset_index.clone(),
             user_types.clone(),
         );
+        let ignore = Arc::new(ProjectIgnore::load(&project_root));
+        let catalog = Arc::new(AssetCatalog::new(project_root.clone(), asset_index.clone()));
+        let parsers = Arc::new(AssetParsers::new());
+        parsers.register("class", Box::new(BlueprintClassParser));
         let scanner = ProjectScanner::new(
             project_root.clone(),
             asset_index.clone(),
             user_types.clone(),
+            ignore.clone(),
+            catalog.clone(),
+            parsers.clone(),
         );
 
         let mut fs = Self {
@@ -43,14 +81,49 @@ impl EngineFs {
             user_types,
             operations,
             scanner,
+            ignore,
+            catalog,
+            parsers,
+            watcher_counters: Arc::default(),
         };
 
         // Initial scan of the project
-        fs.scan_project()?;
+        if let Some(progress) = progress {
+            fs.scanner.report_progress(progress);
+        }
+        if incremental {
+            fs.rescan_incremental()?;
+        } else {
+            fs.scan_project_full()?;
+        }
+        fs.scanner.finish_progress();
 
         Ok(fs)
     }
 
+    /// The cached asset index of the project, if there is one that can be
+    /// read. An unreadable one is logged and ignored.
+    fn load_index_cache(project_root: &Path) -> Option<AssetIndex> {
+        let path = project_root.join(asset_index::CACHE_FILE);
+        if !path.exists() {
+            return None;
+        }
+        AssetIndex::load_from(&path)
+            .map_err(|e| tracing::warn!("Discarding asset index cache at {:?}: {:#}", path, e))
+            .ok()
+    }
+
+    fn save_index_cache(&self) {
+        let path = self.project_root.join(asset_index::CACHE_FILE);
+        if let Err(e) = self.asset_index.save_to(&path) {
+            tracing::warn!("Failed to save asset index cache to {:?}: {:#}", path, e);
+        }
+    }
+
+    fn register_derived(&self) {
+        derived::blueprints::register_project(derived::global(), &self.project_root);
+    }
+
     /// Get the project root path
     pub fn project_root(&self) -> &PathBuf {
         &self.project_root
@@ -71,18 +144,152 @@ impl EngineFs {
         &self.operations
     }
 
-    /// Scan the entire project and build the asset index and user type registry
-    pub fn scan_project(&mut self) -> Result<()> {
-        self.scanner.scan_project()
+    /// Files in the project of `kind`, or all of them, by path
+    pub fn list_assets(&self, kind: Option<AssetKind>) -> Vec<AssetEntry> {
+        self.catalog.list(kind)
+    }
+
+    /// Files in the project matching a gitignore-style glob, e.g. `*.png`
+    pub fn find_assets_matching(&self, glob: &str) -> Result<Vec<AssetEntry>> {
+        self.catalog.matching(glob)
+    }
+
+    /// Number of files of each asset kind in the project, for the project
+    /// dashboard
+    pub fn asset_counts(&self) -> Vec<(AssetKind, usize)> {
+        self.catalog.count_by_kind()
+    }
+
+    /// Turns hashing of file contents on or off. Off by default; hashing
+    /// runs in the background and only changed files are hashed again.
+    pub fn set_content_hashing(&self, settings: Option<HashSettings>) {
+        self.catalog.set_hashing(settings);
+    }
+
+    /// Groups of project files with identical contents, for cleaning up
+    /// repeated imports
+    pub fn find_duplicates(&self) -> Vec<Vec<PathBuf>> {
+        self.catalog.find_duplicates()
+    }
+
+    /// The hash of the contents of the file at `path`, once it is hashed
+    pub fn hash_of(&self, path: &Path) -> Option<u64> {
+        self.catalog.hash_of(path)
+    }
+
+    /// Files still waiting to be hashed
+    pub fn pending_content_hashes(&self) -> usize {
+        self.catalog.pending_hashes()
+    }
+
+    /// Read files with `extension`, or in folders with it, through `parser`
+    /// instead of the built-in file types. Files already indexed are parsed
+    /// again by the next full scan.
+    pub fn register_asset_parser(&self, extension: &str, parser: Box<dyn AssetParser>) {
+        self.parsers.register(extension, parser);
+    }
+
+    /// Files a registered parser failed on, for the problems panel
+    pub fn parse_problems(&self) -> Vec<ParseProblem> {
+        self.parsers.problems()
+    }
+
+    /// Whether `path` is left out of scans by the defaults or the project's
+    /// `.pulsarignore`
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        self.ignore.is_ignored(path, path.is_dir())
+    }
+
+    /// Clear the asset index and user type registry and build them again
+    /// from the whole project. Every asset gets a new ID, so prefer
+    /// [`rescan_incremental`](Self::rescan_incremental) unless the index
+    /// can't be trusted.
+    pub fn scan_project_full(&mut self) -> Result<()> {
+        self.ignore.reload();
+        self.scanner.scan_project()?;
+        self.register_derived();
+        self.save_index_cache();
+        Ok(())
+    }
+
+    /// Bring the asset index up to date with the project in place.
+    ///
+    /// Files modified since their `last_modified` are read again keeping
+    /// their asset's ID, new files are registered and assets whose file is
+    /// gone are unregistered, so IDs held elsewhere stay valid.
+    pub fn rescan_incremental(&mut self) -> Result<ScanReport> {
+        self.ignore.reload();
+        let report = self.scanner.scan_changed()?;
+        for asset in &report.removed {
+            if asset.file_type_id.as_str() != "alias" {
+                continue;
+            }
+            if let Some(path) = &asset.file_path {
+                self.user_types
+                    .history()
+                    .record_removed(path, std::time::SystemTime::now());
+            }
+        }
+        tracing::debug!(
+            "Rescanned project: {} added, {} updated, {} removed, {} unchanged",
+            report.added.len(),
+            report.updated.len(),
+            report.removed.len(),
+            report.unchanged
+        );
+        self.register_derived();
+        self.save_index_cache();
+        Ok(report)
+    }
+
+    /// Bring the asset index up to date with files changed or removed
+    /// without the watcher noticing, e.g. while the editor was closed.
+    /// Cheaper than [`rescan_incremental`](Self::rescan_incremental), so it
+    /// can be called periodically; new files are only picked up by a scan.
+    pub fn refresh_stale_assets(&self) -> StaleRefresh {
+        let refresh = self
+            .asset_index
+            .refresh_stale(|path| self.scanner.registration_for(path.to_path_buf()));
+
+        for id in &refresh.refreshed {
+            if let Some(asset) = self.asset_index.get(*id) {
+                if let Some(path) = &asset.file_path {
+                    self.scanner.register_user_type(path, &asset.file_type_id);
+                }
+            }
+        }
+        let missing: Vec<u64> = refresh.missing.iter().map(|asset| asset.id).collect();
+        for asset in self.asset_index.unregister_batch(&missing) {
+            if let Some(path) = &asset.file_path {
+                if self.user_types.unregister_by_path(path).is_some() {
+                    self.user_types
+                        .history()
+                        .record_removed(path, std::time::SystemTime::now());
+                }
+            }
+        }
+
+        if !refresh.refreshed.is_empty() || !missing.is_empty() {
+            tracing::debug!(
+                "Refreshed {} stale asset(s), removed {} missing",
+                refresh.refreshed.len(),
+                missing.len()
+            );
+            self.save_index_cache();
+        }
+        refresh
     }
 
     /// Start file system watching for automatic updates
-    /// Note: Currently only watches for file removals. Rescan project to detect new/modified files.
     pub fn start_watching(&self) -> Result<()> {
         watchers::start_watcher(
             self.project_root.clone(),
             self.asset_index.clone(),
             self.user_types.clone(),
+            self.ignore.clone(),
+            self.catalog.clone(),
+            self.parsers.clone(),
+            self.watcher_counters.clone(),
         )?;
 
         tracing::trace!(
@@ -92,4 +299,9 @@ impl EngineFs {
 
         Ok(())
     }
+
+    /// What the file system watcher has handled so far
+    pub fn watcher_stats(&self) -> WatcherStats {
+        self.watcher_counters.snapshot()
+    }
 }
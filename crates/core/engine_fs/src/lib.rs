@@ -13,6 +13,12 @@
 //! - [`watchers`] - File system watching for automatic updates
 //! - [`engine_fs`] - Main coordinator struct
 //! - [`scanner`] - Project scanning and indexing
+//! - [`asset_catalog`] - Every project file by kind, size and modification time
+//! - [`asset_parsers`] - Plugin-registered parsers for asset formats
+//! - [`project_ignore`] - `.pulsarignore` rules for paths left out of scans
+//! - [`type_definition`] - Structured fields, variants and methods of user types
+//! - [`type_history`] - Per-type snapshot history and structural diffs
+//! - [`derived`] - Derived-asset dependency graph and rebuild dispatch
 //!
 //! ## Remote file editing
 //!
@@ -24,13 +30,20 @@
 
 // Module declarations
 #[cfg(feature = "editor")]
+pub mod asset_catalog;
+#[cfg(feature = "editor")]
 pub mod asset_index;
+#[cfg(feature = "editor")]
+pub mod asset_parsers;
+pub mod derived;
 pub mod import_options;
 #[cfg(feature = "editor")]
 mod engine_fs;
 pub mod events;
 #[cfg(feature = "editor")]
 pub mod operations;
+#[cfg(feature = "editor")]
+pub mod project_ignore;
 pub mod providers;
 #[cfg(feature = "editor")]
 mod scanner;
@@ -41,6 +54,9 @@ pub mod thumbnails;
 #[cfg(feature = "editor")]
 pub mod tooling;
 #[cfg(feature = "editor")]
+pub mod type_definition;
+pub mod type_history;
+#[cfg(feature = "editor")]
 pub mod user_types;
 pub mod virtual_fs;
 #[cfg(feature = "editor")]
@@ -48,11 +64,22 @@ pub mod watchers;
 
 // Re-export main types
 #[cfg(feature = "editor")]
-pub use asset_index::{AssetIndex, AssetInfo};
+pub use asset_catalog::{AssetCatalog, AssetEntry, HashSettings};
+#[cfg(feature = "editor")]
+pub use asset_index::{
+    AssetIndex, AssetIndexEvent, AssetInfo, AssetRegistration, SearchPage, StaleRefresh,
+};
+#[cfg(feature = "editor")]
+pub use asset_parsers::{AssetParser, ParseProblem, TypeRegistration};
 #[cfg(feature = "editor")]
 pub use engine_fs::EngineFs;
 #[cfg(feature = "editor")]
-pub use user_types::{UserTypeInfo, UserTypeRegistry};
+pub use scanner::{ScanProgress, ScanReport};
+#[cfg(feature = "editor")]
+pub use type_definition::{FieldDef, MethodDef, TypeDefinition, VariantDef};
+pub use type_history::{TypeChange, TypeChangeKind, TypeDiff, TypeHistory, TypeSnapshot};
+#[cfg(feature = "editor")]
+pub use user_types::{UserTypeEvent, UserTypeInfo, UserTypeRegistry};
 
 // Re-export provider types
 #[cfg(feature = "p2p")]
@@ -4,24 +4,82 @@
 
 use anyhow::{Context, Result};
 use plugin_editor_api::FileTypeId;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use crate::asset_index::AssetIndex;
+use crate::asset_index::{AssetIndex, AssetInfo};
 use crate::templates::AssetKind;
+use crate::type_definition::TypeDefinition;
+use crate::user_types::UserTypeRegistry;
 use crate::{events, FsChangeKind};
 
+/// Assets referencing one that is deleted or moved, which will no longer
+/// find it. Returned so the UI can ask for confirmation or list them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyWarning {
+    /// The asset deleted or moved
+    pub path: PathBuf,
+    /// Files of the assets referencing it
+    pub dependents: Vec<PathBuf>,
+}
+
+/// Why an asset couldn't be moved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveError {
+    /// Nothing exists at the source path
+    SourceMissing(PathBuf),
+    /// Something already exists at the destination
+    DestinationExists(PathBuf),
+    /// The destination is on another device, so the move can't be a rename
+    CrossDevice { from: PathBuf, to: PathBuf },
+}
+
+impl std::fmt::Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::SourceMissing(path) => write!(f, "Nothing to move at {:?}", path),
+            MoveError::DestinationExists(path) => write!(f, "{:?} already exists", path),
+            MoveError::CrossDevice { from, to } => write!(
+                f,
+                "Can't move {:?} to {:?}: they are on different devices",
+                from, to
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// What [`GeneralOperations::move_asset`] did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MoveReport {
+    /// Assets now indexed at the new location, under their old IDs
+    pub moved: Vec<u64>,
+    /// Old and new type name, when the move renamed the asset
+    pub renamed: Option<(String, String)>,
+    /// Type files whose references to the old name were rewritten
+    pub rewritten: Vec<PathBuf>,
+    /// Files still referencing the old name, to update by hand
+    pub follow_ups: Vec<PathBuf>,
+}
+
 /// General asset operations handler
 pub struct GeneralOperations {
     project_root: PathBuf,
     asset_index: Arc<AssetIndex>,
+    user_types: Arc<UserTypeRegistry>,
 }
 
 impl GeneralOperations {
-    pub fn new(project_root: PathBuf, asset_index: Arc<AssetIndex>) -> Self {
+    pub fn new(
+        project_root: PathBuf,
+        asset_index: Arc<AssetIndex>,
+        user_types: Arc<UserTypeRegistry>,
+    ) -> Self {
         Self {
             project_root,
             asset_index,
+            user_types,
         }
     }
 
@@ -62,8 +120,10 @@ impl GeneralOperations {
                 .context("Failed to write asset file")?;
         }
 
-        // Register in appropriate index
+        // Register in appropriate index, linking assets that were waiting for
+        // this type
         self.register_asset(&file_path, kind)?;
+        self.asset_index.link_references();
         events::emit(file_path.clone(), FsChangeKind::Created);
 
         Ok(file_path)
@@ -86,75 +146,251 @@ impl GeneralOperations {
             _ => return Ok(()), // Other asset types don't need indexing yet
         };
 
-        if let Err(e) = self.asset_index.register_with_path(
-            name.clone(),
-            file_path.to_path_buf(),
-            file_type_id.clone(),
-            None,
-            Some(format!("{:?}: {}", file_type_id, name)),
-        ) {
+        let description = Some(format!("{:?}: {}", file_type_id, name));
+        let registered = match TypeDefinition::for_file(file_path, &file_type_id) {
+            Some(definition) => self.asset_index.register_with_definition(
+                name.clone(),
+                file_path.to_path_buf(),
+                file_type_id.clone(),
+                None,
+                description,
+                definition,
+            ),
+            None => self.asset_index.register_with_path(
+                name.clone(),
+                file_path.to_path_buf(),
+                file_type_id.clone(),
+                None,
+                description,
+            ),
+        };
+        if let Err(e) = registered {
             tracing::warn!("Failed to register asset '{}': {:?}", name, e);
         }
 
         Ok(())
     }
 
-    /// Delete any asset file
-    pub fn delete_asset(&self, file_path: &PathBuf) -> Result<()> {
-        // Unregister from asset index
-        self.asset_index.unregister_by_path(file_path);
+    /// What deleting or moving the asset at `file_path` would leave
+    /// dangling, or `None` if nothing references it
+    pub fn dependency_warning(&self, file_path: &Path) -> Option<DependencyWarning> {
+        let dependents = self.asset_index.dependent_paths(file_path);
+        (!dependents.is_empty()).then(|| DependencyWarning {
+            path: file_path.to_path_buf(),
+            dependents,
+        })
+    }
+
+    /// Delete any asset file, returning the assets that still reference it
+    pub fn delete_asset(&self, file_path: &PathBuf) -> Result<Option<DependencyWarning>> {
+        let warning = self.dependency_warning(file_path);
+        if let Some(warning) = &warning {
+            tracing::warn!(
+                "Deleting {:?}, which is still referenced by: {:?}",
+                file_path,
+                warning.dependents
+            );
+        }
 
         // Delete file
         crate::virtual_fs::delete_path(file_path).context("Failed to delete asset file")?;
+        self.asset_index.unregister_by_path(file_path);
         events::emit(file_path.clone(), FsChangeKind::Deleted);
 
-        Ok(())
+        Ok(warning)
     }
 
-    /// Rename/move any asset file
-    pub fn move_asset(&self, old_path: &PathBuf, new_path: &PathBuf) -> Result<()> {
-        // Unregister from asset index
-        self.asset_index.unregister_by_path(old_path);
+    /// Move or rename an asset, keeping its ID.
+    ///
+    /// A folder, such as a folder-based asset, is moved in one rename along
+    /// with everything in it. Assets indexed at or under `from` are re-pathed
+    /// in place. When the move renames a type, references to it in type
+    /// files are rewritten; other assets still naming it are reported as
+    /// follow-ups.
+    pub fn move_asset(&self, from: &Path, to: &Path) -> Result<MoveReport> {
+        if !crate::virtual_fs::exists(from)? {
+            return Err(MoveError::SourceMissing(from.to_path_buf()).into());
+        }
+        if crate::virtual_fs::exists(to)? {
+            return Err(MoveError::DestinationExists(to.to_path_buf()).into());
+        }
 
-        // Create parent directory for new path
-        if let Some(parent) = new_path.parent() {
+        let moved: Vec<AssetInfo> = self
+            .asset_index
+            .all()
+            .into_iter()
+            .filter(|asset| {
+                asset
+                    .file_path
+                    .as_ref()
+                    .is_some_and(|path| path.starts_with(from))
+            })
+            .collect();
+        let mut dependents: Vec<PathBuf> = moved
+            .iter()
+            .filter_map(|asset| asset.file_path.as_deref())
+            .flat_map(|path| self.asset_index.dependent_paths(path))
+            .filter(|path| !path.starts_with(from))
+            .collect();
+        dependents.sort();
+        dependents.dedup();
+
+        if let Some(parent) = to.parent() {
             crate::virtual_fs::create_dir_all(parent)?;
         }
+        crate::virtual_fs::rename(from, to).map_err(|e| {
+            let crosses_devices = e
+                .downcast_ref::<std::io::Error>()
+                .is_some_and(|e| e.kind() == std::io::ErrorKind::CrossesDevices);
+            if crosses_devices {
+                anyhow::Error::from(MoveError::CrossDevice {
+                    from: from.to_path_buf(),
+                    to: to.to_path_buf(),
+                })
+            } else {
+                e.context("Failed to move asset file")
+            }
+        })?;
+
+        let old_name = asset_stem(from);
+        let new_name = asset_stem(to);
+        let mut report = MoveReport::default();
+        if old_name != new_name {
+            report.renamed = Some((old_name.to_string(), new_name.to_string()));
+        }
+
+        for asset in moved {
+            let Some(old_path) = asset.file_path else {
+                continue;
+            };
+            let new_path = match old_path.strip_prefix(from) {
+                Ok(rest) if !rest.as_os_str().is_empty() => to.join(rest),
+                _ => to.to_path_buf(),
+            };
+            let alias = self.user_types.unregister_by_path(&old_path).is_some();
+            self.user_types.history().rekey(&old_path, &new_path);
+
+            self.asset_index.update(asset.id, |asset| {
+                asset.file_path = Some(new_path.clone());
+                if asset.name == old_name {
+                    asset.name = new_name.to_string();
+                }
+                if asset.display_name == old_name {
+                    asset.display_name = new_name.to_string();
+                }
+            });
+            if alias {
+                if let Err(e) = self.user_types.register_alias_file(&new_path) {
+                    tracing::warn!(
+                        "Failed to register moved type alias {:?}: {:?}",
+                        new_path,
+                        e
+                    );
+                }
+            }
+            report.moved.push(asset.id);
+        }
 
-        // Move file
-        crate::virtual_fs::rename(old_path, new_path).context("Failed to move asset file")?;
-
-        // Re-register at new location using registry
-        if let Some(plugin_manager) = plugin_manager::global() {
-            {
-                let pm = plugin_manager.read();
-                if let Some(file_type_id) = pm.file_type_registry().get_file_type_for_path(new_path)
-                {
-                    if let Some(file_type_def) =
-                        pm.file_type_registry().get_file_type(&file_type_id)
-                    {
-                        let name = new_path
-                            .file_stem()
-                            .and_then(|s| s.to_str())
-                            .unwrap_or("unknown")
-                            .to_string();
-
-                        if let Err(e) = self.asset_index.register_with_path(
-                            name.clone(),
-                            new_path.clone(),
-                            file_type_id,
-                            None,
-                            Some(format!("{}: {}", file_type_def.display_name, name)),
-                        ) {
-                            tracing::warn!("Failed to register renamed asset '{}': {:?}", name, e);
-                        }
+        if let Some((old_name, new_name)) = &report.renamed {
+            for path in dependents {
+                match self.rewrite_references(&path, old_name, new_name) {
+                    Ok(true) => report.rewritten.push(path),
+                    Ok(false) => report.follow_ups.push(path),
+                    Err(e) => {
+                        tracing::warn!("Failed to update references in {:?}: {:?}", path, e);
+                        report.follow_ups.push(path);
                     }
                 }
             }
         }
-        events::emit(old_path.clone(), FsChangeKind::Deleted);
-        events::emit(new_path.clone(), FsChangeKind::Created);
+        self.asset_index.link_references();
 
-        Ok(())
+        Ok(report)
+    }
+
+    /// Replaces `old_name` with `new_name` where it is a whole string in the
+    /// type file at `path`. Returns false for files that aren't type files,
+    /// or where the name only appears inside other text.
+    fn rewrite_references(&self, path: &Path, old_name: &str, new_name: &str) -> Result<bool> {
+        let Some(asset) = self.asset_index.get_by_path(&path.to_path_buf()) else {
+            return Ok(false);
+        };
+        if asset.definition.is_none() || !path.is_file() {
+            return Ok(false);
+        }
+
+        let json = crate::virtual_fs::read_file(path)?;
+        let mut value: serde_json::Value =
+            serde_json::from_slice(&json).context("Invalid type JSON")?;
+        if !rename_strings(&mut value, old_name, new_name) {
+            return Ok(false);
+        }
+        crate::virtual_fs::write_file(path, serde_json::to_string_pretty(&value)?.as_bytes())?;
+
+        let definition = TypeDefinition::for_file(path, &asset.file_type_id);
+        self.asset_index.update(asset.id, |asset| {
+            asset.references = match &definition {
+                Some(definition) => definition.referenced_types(),
+                None => Vec::new(),
+            };
+            asset.definition = definition;
+        });
+        if self.user_types.get_by_path(path).is_some() {
+            self.user_types.register_alias_file(path)?;
+        }
+        Ok(true)
+    }
+}
+
+/// The name an asset at `path` goes by: its file or folder name up to the
+/// first dot, so `Player.alias.json` and `Player.class` are both `Player`.
+fn asset_stem(path: &Path) -> &str {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.split('.').next())
+        .unwrap_or_default()
+}
+
+/// Replaces every string in `value` that is exactly `from` with `to`.
+/// Returns whether any was replaced.
+fn rename_strings(value: &mut serde_json::Value, from: &str, to: &str) -> bool {
+    match value {
+        serde_json::Value::String(s) if s == from => {
+            *s = to.to_string();
+            true
+        }
+        serde_json::Value::Array(items) => items.iter_mut().fold(false, |renamed, item| {
+            rename_strings(item, from, to) | renamed
+        }),
+        serde_json::Value::Object(fields) => fields.values_mut().fold(false, |renamed, field| {
+            rename_strings(field, from, to) | renamed
+        }),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_asset_stem() {
+        assert_eq!(asset_stem(Path::new("types/Player.alias.json")), "Player");
+        assert_eq!(asset_stem(Path::new("classes/Hero.class")), "Hero");
+        assert_eq!(asset_stem(Path::new("types/structs/Item")), "Item");
+    }
+
+    #[test]
+    fn test_rename_strings() {
+        let mut alias = json!({
+            "name": "Inventory",
+            "ast": { "kind": "Generic", "args": [{ "kind": "AliasRef", "alias": "Item" }] },
+            "doc": "A bag of Item"
+        });
+        assert!(rename_strings(&mut alias, "Item", "Loot"));
+        assert_eq!(alias["ast"]["args"][0]["alias"], "Loot");
+        assert_eq!(alias["doc"], "A bag of Item");
+        assert!(!rename_strings(&mut alias, "Item", "Loot"));
     }
 }
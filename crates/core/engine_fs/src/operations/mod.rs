@@ -7,7 +7,7 @@ mod general_ops;
 mod type_ops;
 
 use anyhow::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use crate::asset_index::AssetIndex;
@@ -15,7 +15,7 @@ use crate::templates::AssetKind;
 use crate::user_types::UserTypeRegistry;
 
 // Re-export operation handlers
-pub use general_ops::GeneralOperations;
+pub use general_ops::{DependencyWarning, GeneralOperations, MoveError, MoveReport};
 pub use type_ops::TypeOperations;
 
 /// Main asset operations coordinator
@@ -31,8 +31,8 @@ impl AssetOperations {
         user_types: Arc<UserTypeRegistry>,
     ) -> Self {
         Self {
-            type_ops: TypeOperations::new(project_root.clone(), user_types),
-            general_ops: GeneralOperations::new(project_root, asset_index),
+            type_ops: TypeOperations::new(project_root.clone(), user_types.clone()),
+            general_ops: GeneralOperations::new(project_root, asset_index, user_types),
         }
     }
 
@@ -75,13 +75,20 @@ impl AssetOperations {
         self.general_ops.create_asset(kind, name, custom_dir)
     }
 
-    /// Delete any asset file
-    pub fn delete_asset(&self, file_path: &PathBuf) -> Result<()> {
+    /// What deleting or moving an asset would leave dangling, to confirm
+    /// with the user first
+    pub fn dependency_warning(&self, file_path: &Path) -> Option<DependencyWarning> {
+        self.general_ops.dependency_warning(file_path)
+    }
+
+    /// Delete any asset file, returning the assets that still reference it
+    pub fn delete_asset(&self, file_path: &PathBuf) -> Result<Option<DependencyWarning>> {
         self.general_ops.delete_asset(file_path)
     }
 
-    /// Rename/move any asset file
-    pub fn move_asset(&self, old_path: &PathBuf, new_path: &PathBuf) -> Result<()> {
-        self.general_ops.move_asset(old_path, new_path)
+    /// Move or rename an asset, keeping its ID and updating references to
+    /// it where possible
+    pub fn move_asset(&self, from: &Path, to: &Path) -> Result<MoveReport> {
+        self.general_ops.move_asset(from, to)
     }
 }
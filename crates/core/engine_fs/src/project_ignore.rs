@@ -0,0 +1,118 @@
+//! Paths excluded from scanning and watching
+//!
+//! A `.pulsarignore` file at the project root lists gitignore-style patterns
+//! for paths the asset index should never see, e.g. raw source art or vendored
+//! code. Hidden files and `target/` directories are ignored by default; the
+//! file's patterns come after the defaults, so `!target/` brings them back.
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use parking_lot::RwLock;
+use std::path::{Path, PathBuf};
+
+/// Name of the ignore file, at the project root
+pub const IGNORE_FILE: &str = ".pulsarignore";
+
+/// Patterns ignored before those of the ignore file
+const DEFAULT_PATTERNS: &[&str] = &[".*", "target/"];
+
+/// The ignore rules of a project, shared by the scanner and the watcher
+#[derive(Debug)]
+pub struct ProjectIgnore {
+    project_root: PathBuf,
+    rules: RwLock<Gitignore>,
+}
+
+impl ProjectIgnore {
+    /// Load the rules of the project at `project_root`
+    pub fn load(project_root: &Path) -> Self {
+        Self {
+            project_root: project_root.to_path_buf(),
+            rules: RwLock::new(build_rules(project_root)),
+        }
+    }
+
+    /// Read the ignore file again
+    pub fn reload(&self) {
+        *self.rules.write() = build_rules(&self.project_root);
+    }
+
+    /// The project's ignore file
+    pub fn file_path(&self) -> PathBuf {
+        self.project_root.join(IGNORE_FILE)
+    }
+
+    /// Whether `path` is the project's ignore file
+    pub fn is_ignore_file(&self, path: &Path) -> bool {
+        path == self.file_path()
+    }
+
+    /// Whether `path`, or a directory it is in, is ignored. Paths outside the
+    /// project are never ignored.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.project_root) else {
+            return false;
+        };
+        if relative.as_os_str().is_empty() {
+            return false;
+        }
+        self.rules
+            .read()
+            .matched_path_or_any_parents(relative, is_dir)
+            .is_ignore()
+    }
+}
+
+/// The default patterns followed by those of the ignore file. Invalid lines
+/// are logged and skipped.
+fn build_rules(project_root: &Path) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(project_root);
+    for pattern in DEFAULT_PATTERNS {
+        builder
+            .add_line(None, pattern)
+            .expect("default ignore patterns are valid");
+    }
+
+    let path = project_root.join(IGNORE_FILE);
+    if path.is_file() {
+        if let Some(e) = builder.add(&path) {
+            tracing::warn!("Invalid patterns in {:?}: {}", path, e);
+        }
+    }
+
+    builder.build().unwrap_or_else(|e| {
+        tracing::warn!("Failed to build ignore rules from {:?}: {}", path, e);
+        Gitignore::empty()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_and_ignore_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        let ignore = ProjectIgnore::load(root);
+        assert!(ignore.is_ignored(&root.join("target"), true));
+        assert!(ignore.is_ignored(&root.join("game/target/debug/app"), false));
+        assert!(ignore.is_ignored(&root.join(".pulsar/cache.json"), false));
+        assert!(!ignore.is_ignored(&root.join("assets_raw/rock.blend"), false));
+        assert!(!ignore.is_ignored(root, true));
+        assert!(!ignore.is_ignored(Path::new("/elsewhere/.hidden"), false));
+
+        std::fs::write(
+            root.join(IGNORE_FILE),
+            "# Raw art\nassets_raw/\n*.blend\n!target/\n",
+        )
+        .unwrap();
+        assert!(!ignore.is_ignored(&root.join("assets_raw"), true));
+        ignore.reload();
+        assert!(ignore.is_ignored(&root.join("assets_raw/rock.fbx"), false));
+        assert!(ignore.is_ignored(&root.join("models/rock.blend"), false));
+        assert!(!ignore.is_ignored(&root.join("target"), true));
+        assert!(ignore.is_ignored(&root.join(".git"), true));
+        assert!(ignore.is_ignore_file(&root.join(IGNORE_FILE)));
+    }
+}
@@ -4,17 +4,99 @@
 //! and registering user-defined type aliases in the user type registry.
 
 use anyhow::Result;
-use std::path::PathBuf;
+use parking_lot::Mutex;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use crate::asset_index::AssetIndex;
+use crate::asset_catalog::AssetCatalog;
+use crate::asset_index::{AssetIndex, AssetInfo, AssetRegistration};
+use crate::asset_parsers::{AssetParsers, TypeRegistration};
+use crate::project_ignore::ProjectIgnore;
+use crate::type_definition::TypeDefinition;
 use crate::user_types::UserTypeRegistry;
+use plugin_editor_api::FileTypeId;
+
+/// Outcome of an incremental scan, see [`EngineFs::rescan_incremental`].
+///
+/// [`EngineFs::rescan_incremental`]: crate::EngineFs::rescan_incremental
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanReport {
+    /// Assets registered for files new to the index
+    pub added: Vec<u64>,
+    /// Assets updated in place from their changed files
+    pub updated: Vec<u64>,
+    /// Assets unregistered because their file is gone or has no file type
+    pub removed: Vec<AssetInfo>,
+    /// Files whose entry was already up to date
+    pub unchanged: usize,
+}
+
+/// Least time between two progress updates of a scan
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How far a scan has come, for the project splash screen's progress bar
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanProgress {
+    /// Files found in the project so far
+    pub files_discovered: usize,
+    /// Files looked at for registration so far
+    pub files_processed: usize,
+    /// The file found or looked at last
+    pub current_path: Option<PathBuf>,
+    /// Set on the last update, once the scan is done
+    pub finished: bool,
+}
+
+/// Sends [`ScanProgress`] updates, at most one per [`PROGRESS_INTERVAL`]
+struct ProgressReporter {
+    sender: Sender<ScanProgress>,
+    progress: ScanProgress,
+    last_sent: Option<Instant>,
+}
+
+impl ProgressReporter {
+    fn discovered(&mut self, path: &Path) {
+        self.progress.files_discovered += 1;
+        self.send_throttled(path);
+    }
+
+    fn processed(&mut self, path: &Path) {
+        self.progress.files_processed += 1;
+        self.send_throttled(path);
+    }
+
+    fn send_throttled(&mut self, path: &Path) {
+        if self
+            .last_sent
+            .is_some_and(|sent| sent.elapsed() < PROGRESS_INTERVAL)
+        {
+            return;
+        }
+        self.last_sent = Some(Instant::now());
+        self.progress.current_path = Some(path.to_path_buf());
+        // A dropped receiver only means nobody is watching any more
+        let _ = self.sender.send(self.progress.clone());
+    }
+
+    fn finish(mut self) {
+        self.progress.current_path = None;
+        self.progress.finished = true;
+        let _ = self.sender.send(self.progress);
+    }
+}
 
 /// Project scanner for indexing assets
 pub struct ProjectScanner {
     project_root: PathBuf,
     asset_index: Arc<AssetIndex>,
     user_types: Arc<UserTypeRegistry>,
+    ignore: Arc<ProjectIgnore>,
+    catalog: Arc<AssetCatalog>,
+    parsers: Arc<AssetParsers>,
+    progress: Mutex<Option<ProgressReporter>>,
 }
 
 impl ProjectScanner {
@@ -22,92 +104,432 @@ impl ProjectScanner {
         project_root: PathBuf,
         asset_index: Arc<AssetIndex>,
         user_types: Arc<UserTypeRegistry>,
+        ignore: Arc<ProjectIgnore>,
+        catalog: Arc<AssetCatalog>,
+        parsers: Arc<AssetParsers>,
     ) -> Self {
         Self {
             project_root,
             asset_index,
             user_types,
+            ignore,
+            catalog,
+            parsers,
+            progress: Mutex::new(None),
+        }
+    }
+
+    /// Send the progress of the scans that follow to `sender`, until
+    /// [`Self::finish_progress`]
+    pub fn report_progress(&self, sender: Sender<ScanProgress>) {
+        *self.progress.lock() = Some(ProgressReporter {
+            sender,
+            progress: ScanProgress::default(),
+            last_sent: None,
+        });
+    }
+
+    /// Send the last progress update, marked finished, and stop reporting
+    pub fn finish_progress(&self) {
+        if let Some(reporter) = self.progress.lock().take() {
+            reporter.finish();
+        }
+    }
+
+    fn on_progress(&self, update: impl FnOnce(&mut ProgressReporter)) {
+        if let Some(reporter) = self.progress.lock().as_mut() {
+            update(reporter);
         }
     }
 
     /// Scan the entire project and build the asset index and user type registry
     pub fn scan_project(&mut self) -> Result<()> {
-        use walkdir::WalkDir;
-
         // Clear existing indexes
         self.asset_index.clear();
         self.user_types.clear();
 
-        // Walk the project directory
-        for entry in WalkDir::new(&self.project_root)
-            .follow_links(true)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
+        let files = self.project_files();
+        self.catalog.replace(&files);
 
-            // Skip hidden files and target directory
-            if path.components().any(|c| {
-                c.as_os_str().to_string_lossy().starts_with('.') || c.as_os_str() == "target"
-            }) {
-                continue;
-            }
-
-            // Register based on file extension
-            if path.is_file() {
-                self.register_asset(path.to_path_buf())?;
+        // Register based on file extension, all in one batch
+        let registrations: Vec<AssetRegistration> = files
+            .into_iter()
+            .inspect(|path| self.on_progress(|reporter| reporter.processed(path)))
+            .filter_map(|path| self.registration_for(path))
+            .collect();
+        for registration in &registrations {
+            if let Some(path) = &registration.file_path {
+                self.register_user_type(path, &registration.file_type_id);
             }
         }
+        self.asset_index.register_batch(registrations);
+        self.asset_index.link_references();
 
         Ok(())
     }
 
-    /// Register a single asset file using the plugin registry
-    fn register_asset(&self, path: PathBuf) -> Result<()> {
-        // Use the global registry to determine file type
-        if let Some(plugin_manager) = plugin_manager::global() {
-            {
-                let pm = plugin_manager.read();
-                if let Some(file_type_id) = pm.file_type_registry().get_file_type_for_path(&path) {
-                    if let Some(file_type_def) =
-                        pm.file_type_registry().get_file_type(&file_type_id)
+    /// Bring the index up to date with the project without clearing it.
+    ///
+    /// Files modified since their indexed entry, or that now map to another
+    /// file type, are registered again in place, so every file keeps its
+    /// asset ID and user type UUID. New files are registered, and entries
+    /// whose file is gone, or no longer has a file type, are removed.
+    pub fn scan_changed(&mut self) -> Result<ScanReport> {
+        let mut report = ScanReport::default();
+        let mut seen = HashSet::new();
+        let files = self.project_files();
+        self.catalog.replace(&files);
+        for path in files {
+            self.on_progress(|reporter| reporter.processed(&path));
+            let existing = self.asset_index.get_by_path(&path);
+            if let Some(asset) = &existing {
+                match self.file_type_for(&path) {
+                    Some(file_type_id)
+                        if file_type_id == asset.file_type_id && !asset.is_stale() =>
                     {
-                        // Get the type name from the parent folder or file stem
-                        let type_name = path
-                            .parent()
-                            .and_then(|p| p.file_name())
-                            .and_then(|n| n.to_str())
-                            .or_else(|| path.file_stem().and_then(|n| n.to_str()))
-                            .unwrap_or("unknown")
-                            .to_string();
-
-                        // Register with FileTypeId from registry
-                        if let Err(e) = self.asset_index.register_with_path(
-                            type_name.clone(),
-                            path.clone(),
-                            file_type_id.clone(),
-                            None,
-                            Some(format!("{}: {}", file_type_def.display_name, type_name)),
-                        ) {
-                            tracing::warn!("Failed to register asset '{}': {:?}", type_name, e);
-                        }
-
-                        // Additionally register user-defined type aliases in the
-                        // dynamic type registry
-                        if file_type_id.as_str() == "alias" {
-                            if let Err(e) = self.user_types.register_alias_file(&path) {
-                                tracing::warn!(
-                                    "Failed to register type alias at {:?}: {:?}",
-                                    path,
-                                    e
-                                );
-                            }
+                        // Only missing when the index came from its cache
+                        if self.user_types.get_by_path(&path).is_none() {
+                            self.register_user_type(&path, &asset.file_type_id);
                         }
+                        seen.insert(path);
+                        report.unchanged += 1;
+                        continue;
+                    }
+                    // Registered again below, keeping its ID
+                    Some(_) => {
+                        self.user_types.unregister_by_path(&path);
+                    }
+                    None => {
+                        report.removed.extend(self.asset_index.unregister(asset.id));
+                        continue;
                     }
                 }
             }
+            seen.insert(path.clone());
+            match (existing, self.register_asset(path)?) {
+                (Some(asset), Some(_)) => report.updated.push(asset.id),
+                (None, Some(id)) => report.added.push(id),
+                // No longer readable, left as it was
+                (Some(_), None) => report.unchanged += 1,
+                (None, None) => {}
+            }
         }
 
+        let gone: Vec<u64> = self
+            .asset_index
+            .all()
+            .into_iter()
+            .filter(|asset| {
+                asset
+                    .file_path
+                    .as_ref()
+                    .is_some_and(|path| !seen.contains(path))
+            })
+            .map(|asset| asset.id)
+            .collect();
+        report
+            .removed
+            .extend(self.asset_index.unregister_batch(&gone));
+        for asset in &report.removed {
+            if let Some(path) = &asset.file_path {
+                self.user_types.unregister_by_path(path);
+            }
+        }
+        self.asset_index.link_references();
+
+        Ok(report)
+    }
+
+    /// Files in the project, skipping ignored ones. Ignored directories
+    /// aren't walked at all.
+    fn project_files(&self) -> Vec<PathBuf> {
+        use walkdir::WalkDir;
+
+        WalkDir::new(&self.project_root)
+            .follow_links(true)
+            .into_iter()
+            .filter_entry(|entry| {
+                !self
+                    .ignore
+                    .is_ignored(entry.path(), entry.file_type().is_dir())
+            })
+            .filter_map(|e| e.ok())
+            .map(|entry| entry.into_path())
+            .filter(|path| path.is_file())
+            .inspect(|path| self.on_progress(|reporter| reporter.discovered(path)))
+            .collect()
+    }
+
+    /// Register a single asset file using the plugin registry, returning its
+    /// ID, or `None` if the registry doesn't know its file type
+    pub(crate) fn register_asset(&self, path: PathBuf) -> Result<Option<u64>> {
+        let Some(registration) = self.registration_for(path.clone()) else {
+            return Ok(None);
+        };
+        self.register_user_type(&path, &registration.file_type_id);
+
+        // An asset already indexed at this path is updated in place so it
+        // keeps its ID
+        let id = if let Some(existing) = self.asset_index.get_by_path(&path) {
+            self.asset_index
+                .update(existing.id, |asset| asset.reparsed(registration));
+            existing.id
+        } else {
+            self.asset_index.register_batch(vec![registration])[0]
+        };
+
+        Ok(Some(id))
+    }
+
+    /// Move the assets indexed at or under `from` to the same place under
+    /// `to`, keeping their IDs, and read them again. Moved files that no
+    /// longer have a file type are unregistered. If nothing was indexed at
+    /// `from`, `to` is registered as a new file.
+    pub(crate) fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        let moved: Vec<AssetInfo> = self
+            .asset_index
+            .all()
+            .into_iter()
+            .filter(|asset| {
+                asset
+                    .file_path
+                    .as_ref()
+                    .is_some_and(|path| path.starts_with(from))
+            })
+            .collect();
+        if moved.is_empty() {
+            if to.is_file() {
+                self.register_asset(to.to_path_buf())?;
+            }
+            return Ok(());
+        }
+
+        for asset in moved {
+            let Some(old_path) = asset.file_path else {
+                continue;
+            };
+            let new_path = match old_path.strip_prefix(from) {
+                Ok(rest) if !rest.as_os_str().is_empty() => to.join(rest),
+                _ => to.to_path_buf(),
+            };
+            self.user_types.unregister_by_path(&old_path);
+            self.user_types.history().rekey(&old_path, &new_path);
+            self.asset_index.update(asset.id, |asset| {
+                asset.file_path = Some(new_path.clone());
+            });
+            if self.register_asset(new_path)?.is_none() {
+                self.asset_index.unregister(asset.id);
+            }
+        }
         Ok(())
     }
+
+    /// What to index for the file at `path`: the type a registered parser
+    /// reads from it, or failing that what the plugin registry's file type
+    /// gives
+    pub(crate) fn registration_for(&self, path: PathBuf) -> Option<AssetRegistration> {
+        let file_type = plugin_manager::global().and_then(|plugin_manager| {
+            let pm = plugin_manager.read();
+            let file_type_id = pm.file_type_registry().get_file_type_for_path(&path)?;
+            let file_type_def = pm.file_type_registry().get_file_type(&file_type_id)?;
+            Some((file_type_id, file_type_def.display_name.clone()))
+        });
+
+        if let Some(parsed) = self.parsers.parse(&path) {
+            let (file_type_id, display_name) = file_type
+                .unwrap_or_else(|| (FileTypeId::new(parsed.extension.as_str()), parsed.extension));
+            return Some(asset_registration(
+                path,
+                file_type_id,
+                &display_name,
+                parsed.registration,
+            ));
+        }
+
+        let (file_type_id, display_name) = file_type?;
+        // Get the type name from the parent folder or file stem
+        let type_name = path
+            .parent()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str())
+            .or_else(|| path.file_stem().and_then(|n| n.to_str()))
+            .unwrap_or("unknown")
+            .to_string();
+        let definition = TypeDefinition::for_file(&path, &file_type_id);
+        let references = definition
+            .as_ref()
+            .map(TypeDefinition::referenced_types)
+            .unwrap_or_default();
+
+        Some(asset_registration(
+            path,
+            file_type_id,
+            &display_name,
+            TypeRegistration {
+                name: type_name,
+                definition,
+                references,
+            },
+        ))
+    }
+
+    /// The file type `path` is indexed under: the plugin registry's, or the
+    /// extension of the parser registered for it
+    fn file_type_for(&self, path: &Path) -> Option<FileTypeId> {
+        registry_file_type(path).or_else(|| self.parsers.extension_for(path).map(FileTypeId::new))
+    }
+
+    /// Drop the parse problems of files at or under `path`
+    pub(crate) fn forget_problems(&self, path: &Path) {
+        self.parsers.forget(path);
+    }
+
+    /// Additionally register user-defined type aliases in the dynamic type
+    /// registry
+    pub(crate) fn register_user_type(&self, path: &Path, file_type_id: &FileTypeId) {
+        if file_type_id.as_str() == "alias" {
+            if let Err(e) = self.user_types.register_alias_file(path) {
+                tracing::warn!("Failed to register type alias at {:?}: {:?}", path, e);
+            }
+        }
+    }
+}
+
+fn asset_registration(
+    path: PathBuf,
+    file_type_id: FileTypeId,
+    file_type_name: &str,
+    parsed: TypeRegistration,
+) -> AssetRegistration {
+    let description = format!("{}: {}", file_type_name, parsed.name);
+    let mut registration = AssetRegistration::for_file(parsed.name, path, file_type_id);
+    registration.description = Some(description);
+    registration.definition = parsed.definition;
+    registration.references = parsed.references;
+    registration
+}
+
+/// The file type the plugin registry gives `path`
+fn registry_file_type(path: &Path) -> Option<FileTypeId> {
+    plugin_manager::global()?
+        .read()
+        .file_type_registry()
+        .get_file_type_for_path(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_is_monotonic() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for folder in 0..5 {
+            let folder = root.join(format!("folder_{}", folder));
+            std::fs::create_dir(&folder).unwrap();
+            for file in 0..40 {
+                std::fs::write(folder.join(format!("data_{}.json", file)), "{}").unwrap();
+            }
+        }
+
+        let asset_index = Arc::new(AssetIndex::new());
+        let mut scanner = ProjectScanner::new(
+            root.to_path_buf(),
+            asset_index.clone(),
+            Arc::new(UserTypeRegistry::new()),
+            Arc::new(ProjectIgnore::load(root)),
+            Arc::new(AssetCatalog::new(root.to_path_buf(), asset_index)),
+            Arc::new(AssetParsers::new()),
+        );
+        let (sender, receiver) = std::sync::mpsc::channel();
+        scanner.report_progress(sender);
+        scanner.scan_project().unwrap();
+        scanner.finish_progress();
+
+        let updates: Vec<ScanProgress> = receiver.try_iter().collect();
+        assert!(updates.windows(2).all(|pair| {
+            pair[0].files_discovered <= pair[1].files_discovered
+                && pair[0].files_processed <= pair[1].files_processed
+        }));
+        let last = updates.last().unwrap();
+        assert!(last.finished);
+        assert_eq!((last.files_discovered, last.files_processed), (200, 200));
+        assert!(updates[..updates.len() - 1].iter().all(|u| !u.finished));
+    }
+
+    /// Reads `<Name>.alias` files as types named after the file, so the
+    /// scanner registers them as user types without the plugin registry
+    struct AliasParser;
+
+    impl crate::asset_parsers::AssetParser for AliasParser {
+        fn parse(&self, path: &Path) -> Result<Option<TypeRegistration>> {
+            let name = path.file_stem().and_then(|stem| stem.to_str()).unwrap();
+            Ok(Some(TypeRegistration::new(name)))
+        }
+    }
+
+    fn write_alias(dir: &Path, name: &str, target: &str) -> PathBuf {
+        let path = dir.join(format!("{}.alias", name));
+        let json = serde_json::json!({
+            "schemaVersion": 1,
+            "typeKind": "alias",
+            "name": name,
+            "displayName": name,
+            "ast": { "nodeKind": "Primitive", "name": target },
+        });
+        std::fs::write(&path, json.to_string()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_rescan_keeps_user_type_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let kept = write_alias(root, "RescanKeptId", "u64");
+        let touched = write_alias(root, "RescanTouchedId", "u64");
+        let deleted = write_alias(root, "RescanDeletedId", "u64");
+
+        let asset_index = Arc::new(AssetIndex::new());
+        let user_types = Arc::new(UserTypeRegistry::new());
+        let parsers = Arc::new(AssetParsers::new());
+        parsers.register("alias", Box::new(AliasParser));
+        let mut scanner = ProjectScanner::new(
+            root.to_path_buf(),
+            asset_index.clone(),
+            user_types.clone(),
+            Arc::new(ProjectIgnore::load(root)),
+            Arc::new(AssetCatalog::new(root.to_path_buf(), asset_index.clone())),
+            parsers,
+        );
+        scanner.scan_project().unwrap();
+        let uuid_of = |path: &Path| user_types.get_by_path(path).unwrap().uuid;
+        let (kept_uuid, touched_uuid) = (uuid_of(&kept), uuid_of(&touched));
+        let asset_id = |path: &Path| asset_index.get_by_path(path).unwrap().id;
+        let (touched_id, deleted_id) = (asset_id(&touched), asset_id(&deleted));
+
+        write_alias(root, "RescanTouchedId", "u32");
+        let modified = std::time::SystemTime::now() + Duration::from_secs(60);
+        std::fs::File::options()
+            .write(true)
+            .open(&touched)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        std::fs::remove_file(&deleted).unwrap();
+        let added = write_alias(root, "RescanAddedId", "u64");
+        let report = scanner.scan_changed().unwrap();
+
+        assert_eq!(report.added, [asset_id(&added)]);
+        assert_eq!(report.updated, [touched_id]);
+        let removed: Vec<u64> = report.removed.iter().map(|asset| asset.id).collect();
+        assert_eq!(removed, [deleted_id]);
+        assert_eq!(report.unchanged, 1);
+
+        assert_eq!(uuid_of(&kept), kept_uuid);
+        assert_eq!(uuid_of(&touched), touched_uuid);
+        assert!(user_types.get_by_path(&deleted).is_none());
+        assert!(user_types.get_by_path(&added).is_some());
+        assert_eq!(user_types.len(), 3);
+    }
 }
@@ -126,6 +126,20 @@ impl AssetKind {
         TemplateGenerator::generate(*self, name)
     }
 
+    /// The kind of the file at `path`, by the longest extension it ends
+    /// with, so `Item.struct.json` is a struct rather than JSON data
+    pub fn for_path(path: &std::path::Path) -> Option<AssetKind> {
+        let file_name = path.file_name()?.to_str()?;
+        AssetKind::all()
+            .into_iter()
+            .filter(|kind| {
+                file_name
+                    .strip_suffix(kind.extension())
+                    .is_some_and(|stem| stem.ends_with('.') && stem.len() > 1)
+            })
+            .max_by_key(|kind| kind.extension().len())
+    }
+
     /// Get all available asset kinds
     pub fn all() -> Vec<AssetKind> {
         vec![
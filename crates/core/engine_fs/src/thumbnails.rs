@@ -19,7 +19,14 @@
 //!
 //! 2. **Disk cache** — `{cache_root}/.pulsar/thumbnails/{hash}.png`.
 //!    Hash encodes path + mtime, so stale entries regenerate automatically.
+//!
+//! ## Derived-asset graph
+//!
+//! The worker registers every requested thumbnail in [`crate::derived`] as
+//! `thumbnail:<path>` derived from its source file, so edits to the source mark
+//! it stale and the `thumbnail` rebuilder regenerates it in the background.
 
+use crate::derived::{self, AssetKey, Rebuilder, RebuilderId};
 use parking_lot::Mutex;
 use std::collections::{hash_map::DefaultHasher, HashMap, HashSet};
 use std::hash::{Hash, Hasher};
@@ -35,6 +42,8 @@ const MEM_CACHE_MAX: usize = 512;
 const MEM_CACHE_TTL: Duration = Duration::from_secs(300); // 5 min
 /// How often the background eviction thread wakes.
 const EVICTION_INTERVAL: Duration = Duration::from_secs(60); // 1 min
+/// Rebuilder id for thumbnails in the derived-asset graph.
+pub const THUMBNAIL_REBUILDER: &str = "thumbnail";
 
 // ─────────────────────────────────────────────────────────────────────────────
 // In-memory LRU cache
@@ -141,12 +150,17 @@ impl ThumbnailService {
         let (tx, rx) = std::sync::mpsc::sync_channel::<ThumbnailJob>(128);
         let pending = Arc::new(Mutex::new(HashSet::<PathBuf>::new()));
         let mem_cache = Arc::new(Mutex::new(MemCache::new()));
+        // Cache root each requested asset was last rendered into — used by the
+        // derived-asset rebuilder, which only knows the source path.
+        let cache_roots = Arc::new(Mutex::new(HashMap::<PathBuf, PathBuf>::new()));
 
         // ── Worker thread ────────────────────────────────────────────────────
+        let worker_roots = Arc::clone(&cache_roots);
         std::thread::Builder::new()
             .name("thumbnail-worker".into())
             .spawn(move || {
                 while let Ok(job) = rx.recv() {
+                    track_derivation(&worker_roots, &job.abs_path, &job.cache_root);
                     let cache_key = compute_cache_key(&job.abs_path);
 
                     // 1. Memory cache hit — no disk I/O needed.
@@ -173,6 +187,7 @@ impl ThumbnailService {
                     }
 
                     job.pending.lock().remove(&job.abs_path);
+                    derived::global().mark_rebuilt(&thumbnail_key(&job.abs_path));
                     (job.on_done)(rgba);
                 }
             })
@@ -201,6 +216,29 @@ impl ThumbnailService {
             })
             .expect("failed to spawn thumbnail-evictor thread");
 
+        // ── Derived-asset rebuilder ──────────────────────────────────────────
+        // Limited to one at a time, same as the worker, to keep renders serial.
+        let rebuild_cache = Arc::clone(&mem_cache);
+        derived::global().register_rebuilder(
+            RebuilderId::new(THUMBNAIL_REBUILDER),
+            Rebuilder::new(1, move |_, inputs| {
+                let Some(abs_path) = inputs.first().and_then(AssetKey::as_path) else {
+                    return Err("thumbnail derivation has no source file".to_string());
+                };
+                let Some(cache_root) = cache_roots.lock().get(abs_path).cloned() else {
+                    return Err(format!("no thumbnail cache root for {:?}", abs_path));
+                };
+                let disk_path = get_or_generate_thumbnail_sync(abs_path, &cache_root)
+                    .ok_or_else(|| format!("could not generate thumbnail for {:?}", abs_path))?;
+                if let Ok(img) = image::open(&disk_path) {
+                    rebuild_cache
+                        .lock()
+                        .insert(compute_cache_key(abs_path), Arc::new(img.into_rgba8()));
+                }
+                Ok(())
+            }),
+        );
+
         Self {
             sender: tx,
             pending,
@@ -299,6 +337,38 @@ fn get_or_generate_thumbnail_sync(abs_asset_path: &Path, cache_root: &Path) -> O
 // Internal helpers
 // ─────────────────────────────────────────────────────────────────────────────
 
+/// Record `abs_path` in the derived-asset graph so source edits invalidate it.
+/// Runs on the worker thread.
+fn track_derivation(
+    cache_roots: &Mutex<HashMap<PathBuf, PathBuf>>,
+    abs_path: &Path,
+    cache_root: &Path,
+) {
+    let supported = abs_path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| is_supported_ext(&e.to_ascii_lowercase()));
+    if !supported {
+        return;
+    }
+
+    cache_roots
+        .lock()
+        .insert(abs_path.to_path_buf(), cache_root.to_path_buf());
+    if let Err(e) = derived::global().register_derivation(
+        thumbnail_key(abs_path),
+        vec![AssetKey::from(abs_path)],
+        RebuilderId::new(THUMBNAIL_REBUILDER),
+    ) {
+        tracing::debug!("thumbnail derivation for {:?} rejected: {}", abs_path, e);
+    }
+}
+
+/// Key of the thumbnail of `abs_path` in the derived-asset graph.
+pub fn thumbnail_key(abs_path: &Path) -> AssetKey {
+    AssetKey::derived(format!("thumbnail:{}", abs_path.display()))
+}
+
 fn is_supported_ext(ext: &str) -> bool {
     matches!(
         ext,
@@ -0,0 +1,333 @@
+//! # Type Definitions
+//!
+//! Structured members of a user-defined type (struct fields, enum variants,
+//! trait methods, alias target), kept on [`crate::AssetInfo`] so editors and
+//! pickers don't each have to re-parse the type's JSON file.
+//!
+//! Type files are the [`ui_types_common`] assets (`StructAsset`, `EnumAsset`,
+//! `TraitAsset`, `AliasAsset`); [`TypeDefinition::parse`] turns one into a
+//! definition, with member types rendered as Rust source.
+
+use anyhow::{Context, Result};
+use plugin_editor_api::FileTypeId;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use ui_types_common::codegen::{render_ast_node, render_type_ref};
+use ui_types_common::{
+    AliasAsset, EnumAsset, EnumVariant, StructAsset, StructField, TraitAsset, TraitMethod,
+    VariantPayload,
+};
+
+/// File types whose files are type definitions.
+const TYPE_FILE_TYPES: [&str; 4] = ["struct", "enum", "trait", "alias"];
+
+/// A named, typed member: a struct field, a field of an enum variant or a
+/// method parameter.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FieldDef {
+    /// Field name; the position ("0", "1", ...) for tuple variant fields
+    pub name: String,
+    /// Rendered Rust type
+    pub type_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct VariantDef {
+    pub name: String,
+    /// Empty for unit variants
+    #[serde(default)]
+    pub fields: Vec<FieldDef>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MethodDef {
+    pub name: String,
+    pub params: Vec<FieldDef>,
+    /// Rendered Rust type, `()` when the method returns nothing
+    pub return_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+}
+
+/// Members of a user-defined type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum TypeDefinition {
+    Struct { fields: Vec<FieldDef> },
+    Enum { variants: Vec<VariantDef> },
+    Trait { methods: Vec<MethodDef> },
+    Alias { target: String },
+}
+
+impl TypeDefinition {
+    /// Parses the JSON of a type file, whichever kind of type it holds.
+    pub fn parse(json: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(json).context("Invalid type JSON")?;
+        let kind = value
+            .get("typeKind")
+            .and_then(Value::as_str)
+            .context("Type JSON has no typeKind")?
+            .to_string();
+        Ok(match kind.as_str() {
+            "struct" => Self::from(&serde_json::from_value::<StructAsset>(value)?),
+            "enum" => Self::from(&serde_json::from_value::<EnumAsset>(value)?),
+            "trait" => Self::from(&serde_json::from_value::<TraitAsset>(value)?),
+            "alias" => Self::from(&serde_json::from_value::<AliasAsset>(value)?),
+            other => anyhow::bail!("Unknown type kind '{}'", other),
+        })
+    }
+
+    /// Reads and parses a type file.
+    pub fn read(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read type file {:?}", path))?;
+        Self::parse(&json)
+    }
+
+    /// The definition in the file at `path` if `file_type_id` is a type file
+    /// type. A type file that can't be parsed is logged and skipped.
+    pub fn for_file(path: &Path, file_type_id: &FileTypeId) -> Option<Self> {
+        if !TYPE_FILE_TYPES.contains(&file_type_id.as_str()) {
+            return None;
+        }
+        Self::read(path)
+            .map_err(|e| tracing::debug!("No type definition for {:?}: {:#}", path, e))
+            .ok()
+    }
+
+    /// Names of the fields, variants or methods, and of the fields of enum
+    /// variants. Empty for aliases.
+    pub fn member_names(&self) -> Vec<&str> {
+        match self {
+            TypeDefinition::Struct { fields } => fields.iter().map(|f| f.name.as_str()).collect(),
+            TypeDefinition::Enum { variants } => variants
+                .iter()
+                .flat_map(|v| {
+                    std::iter::once(v.name.as_str()).chain(v.fields.iter().map(|f| f.name.as_str()))
+                })
+                .collect(),
+            TypeDefinition::Trait { methods } => methods.iter().map(|m| m.name.as_str()).collect(),
+            TypeDefinition::Alias { .. } => Vec::new(),
+        }
+    }
+
+    /// Names in the types of the members, or in the alias target, in the
+    /// order they first appear. `Vec<Player>` names `Vec` and `Player`.
+    pub fn referenced_types(&self) -> Vec<String> {
+        let types: Vec<&str> = match self {
+            TypeDefinition::Struct { fields } => {
+                fields.iter().map(|f| f.type_name.as_str()).collect()
+            }
+            TypeDefinition::Enum { variants } => variants
+                .iter()
+                .flat_map(|v| v.fields.iter().map(|f| f.type_name.as_str()))
+                .collect(),
+            TypeDefinition::Trait { methods } => methods
+                .iter()
+                .flat_map(|m| {
+                    m.params
+                        .iter()
+                        .map(|p| p.type_name.as_str())
+                        .chain(std::iter::once(m.return_type.as_str()))
+                })
+                .collect(),
+            TypeDefinition::Alias { target } => vec![target.as_str()],
+        };
+
+        let mut names: Vec<String> = Vec::new();
+        for name in types
+            .iter()
+            .flat_map(|t| t.split(|c: char| !c.is_alphanumeric() && c != '_'))
+            .filter(|name| name.starts_with(|c: char| c.is_alphabetic() || c == '_'))
+        {
+            if !names.iter().any(|known| known == name) {
+                names.push(name.to_string());
+            }
+        }
+        names
+    }
+}
+
+impl From<&StructField> for FieldDef {
+    fn from(field: &StructField) -> Self {
+        Self {
+            name: field.name.clone(),
+            type_name: render_type_ref(&field.type_ref),
+            doc: field.doc.clone(),
+        }
+    }
+}
+
+impl From<&EnumVariant> for VariantDef {
+    fn from(variant: &EnumVariant) -> Self {
+        let fields = match &variant.payload {
+            VariantPayload::Unit => Vec::new(),
+            VariantPayload::Single(type_ref) => vec![FieldDef {
+                name: "0".to_string(),
+                type_name: render_type_ref(type_ref),
+                doc: None,
+            }],
+            VariantPayload::Struct(fields) => fields.iter().map(FieldDef::from).collect(),
+        };
+        Self {
+            name: variant.name.clone(),
+            fields,
+            doc: variant.doc.clone(),
+        }
+    }
+}
+
+impl From<&TraitMethod> for MethodDef {
+    fn from(method: &TraitMethod) -> Self {
+        Self {
+            name: method.name.clone(),
+            params: method
+                .signature
+                .params
+                .iter()
+                .map(|param| FieldDef {
+                    name: param.name.clone(),
+                    type_name: render_type_ref(&param.type_ref),
+                    doc: None,
+                })
+                .collect(),
+            return_type: render_type_ref(&method.signature.return_type),
+            doc: method.doc.clone(),
+        }
+    }
+}
+
+impl From<&StructAsset> for TypeDefinition {
+    fn from(asset: &StructAsset) -> Self {
+        TypeDefinition::Struct {
+            fields: asset.fields.iter().map(FieldDef::from).collect(),
+        }
+    }
+}
+
+impl From<&EnumAsset> for TypeDefinition {
+    fn from(asset: &EnumAsset) -> Self {
+        TypeDefinition::Enum {
+            variants: asset.variants.iter().map(VariantDef::from).collect(),
+        }
+    }
+}
+
+impl From<&TraitAsset> for TypeDefinition {
+    fn from(asset: &TraitAsset) -> Self {
+        TypeDefinition::Trait {
+            methods: asset.methods.iter().map(MethodDef::from).collect(),
+        }
+    }
+}
+
+impl From<&AliasAsset> for TypeDefinition {
+    fn from(asset: &AliasAsset) -> Self {
+        TypeDefinition::Alias {
+            target: render_ast_node(&asset.ast),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_struct() {
+        let definition = TypeDefinition::parse(
+            r#"{
+                "schemaVersion": 1,
+                "typeKind": "struct",
+                "name": "Player",
+                "displayName": "Player",
+                "fields": [
+                    { "name": "health", "type": { "kind": "Primitive", "name": "f32" } },
+                    { "name": "spawn", "type": { "kind": "AliasRef", "alias": "Position" }, "doc": "Where it starts" }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let TypeDefinition::Struct { fields } = &definition else {
+            panic!("expected a struct, got {:?}", definition);
+        };
+        assert_eq!(fields[0].name, "health");
+        assert_eq!(fields[0].type_name, "f32");
+        assert_eq!(fields[1].doc.as_deref(), Some("Where it starts"));
+        assert_eq!(definition.member_names(), ["health", "spawn"]);
+
+        let json = serde_json::to_string(&definition).unwrap();
+        assert_eq!(
+            serde_json::from_str::<TypeDefinition>(&json).unwrap(),
+            definition
+        );
+    }
+
+    #[test]
+    fn test_referenced_types() {
+        let field = |name: &str, type_name: &str| FieldDef {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            doc: None,
+        };
+        let definition = TypeDefinition::Struct {
+            fields: vec![
+                field("health", "f32"),
+                field("inventory", "Vec<Item>"),
+                field("home", "Option<(Item, map::Position)>"),
+            ],
+        };
+        assert_eq!(
+            definition.referenced_types(),
+            ["f32", "Vec", "Item", "Option", "map", "Position"]
+        );
+
+        let alias = TypeDefinition::Alias {
+            target: "HashMap<String, Player>".to_string(),
+        };
+        assert_eq!(alias.referenced_types(), ["HashMap", "String", "Player"]);
+    }
+
+    #[test]
+    fn test_parse_enum_and_alias() {
+        let definition = TypeDefinition::parse(
+            r#"{
+                "schemaVersion": 1,
+                "typeKind": "enum",
+                "name": "Shape",
+                "displayName": "Shape",
+                "variants": [
+                    { "name": "Empty" },
+                    { "name": "Circle", "payload": { "kind": "Primitive", "name": "f32" } }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(definition.member_names(), ["Empty", "Circle", "0"]);
+
+        let definition = TypeDefinition::parse(
+            r#"{
+                "schemaVersion": 1,
+                "typeKind": "alias",
+                "name": "Id",
+                "displayName": "Id",
+                "ast": { "nodeKind": "Primitive", "name": "u64" }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            definition,
+            TypeDefinition::Alias {
+                target: "u64".to_string()
+            }
+        );
+
+        assert!(TypeDefinition::parse(r#"{ "typeKind": "union" }"#).is_err());
+    }
+}
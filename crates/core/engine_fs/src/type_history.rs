@@ -0,0 +1,757 @@
+//! # Type History
+//!
+//! Session-local change tracking for user types: a bounded per-type snapshot
+//! history, a structural diff between two snapshots, and a folded
+//! "recent changes" feed.
+//!
+//! Snapshots are keyed by file path rather than UUID because
+//! [`crate::UserTypeRegistry`] hands out a fresh UUID every time a type is
+//! re-registered. Nothing in here touches the reflection registries, so the
+//! diffing and folding logic can be tested with hand-built shapes.
+
+use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Number of snapshots retained per type by default.
+pub const DEFAULT_HISTORY_DEPTH: usize = 8;
+/// Minimum confidence for a removed/added member pair to be reported as a rename.
+pub const DEFAULT_RENAME_THRESHOLD: f32 = 0.6;
+/// Updates to the same type closer together than this collapse into one feed entry.
+pub const DEFAULT_FOLD_WINDOW: Duration = Duration::from_secs(2);
+/// Maximum number of entries kept in the recent changes feed.
+pub const DEFAULT_FEED_CAPACITY: usize = 200;
+
+/// A single named member of a shaped type (struct field, enum variant, alias target).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShapeMember {
+    pub name: String,
+    /// Rendered type of the member; empty for unit enum variants
+    pub type_name: String,
+}
+
+impl ShapeMember {
+    pub fn new(name: impl Into<String>, type_name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            type_name: type_name.into(),
+        }
+    }
+}
+
+/// Structural description of a type at the time it was registered.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TypeShape {
+    /// Kind of type ("alias", "struct", "enum", ...)
+    pub kind: String,
+    pub members: Vec<ShapeMember>,
+}
+
+/// What a type looked like at one registration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeSnapshot {
+    pub name: String,
+    pub display_name: String,
+    /// File type / category the type was registered under
+    pub category: String,
+    pub file_path: PathBuf,
+    pub shape: Option<TypeShape>,
+    pub recorded_at: SystemTime,
+}
+
+impl TypeSnapshot {
+    /// Whether two snapshots describe the same definition, ignoring when they were taken.
+    fn same_definition(&self, other: &TypeSnapshot) -> bool {
+        self.name == other.name
+            && self.display_name == other.display_name
+            && self.category == other.category
+            && self.file_path == other.file_path
+            && self.shape == other.shape
+    }
+}
+
+/// Change to a single member between two shapes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MemberChange {
+    Added { name: String, type_name: String },
+    Removed { name: String, type_name: String },
+    /// Best guess that a removed member was renamed; `confidence` is in `0.0..=1.0`
+    Renamed {
+        from: String,
+        to: String,
+        confidence: f32,
+    },
+    TypeChanged {
+        name: String,
+        from: String,
+        to: String,
+    },
+}
+
+/// Structured difference between two snapshots of the same type.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TypeDiff {
+    pub name: Option<(String, String)>,
+    pub display_name: Option<(String, String)>,
+    pub category: Option<(String, String)>,
+    pub file_path: Option<(PathBuf, PathBuf)>,
+    pub kind: Option<(String, String)>,
+    pub members: Vec<MemberChange>,
+    pub before_at: Option<SystemTime>,
+    pub after_at: Option<SystemTime>,
+}
+
+impl TypeDiff {
+    /// True if nothing about the definition changed.
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.display_name.is_none()
+            && self.category.is_none()
+            && self.file_path.is_none()
+            && self.kind.is_none()
+            && self.members.is_empty()
+    }
+}
+
+fn changed<T: PartialEq + Clone>(before: &T, after: &T) -> Option<(T, T)> {
+    (before != after).then(|| (before.clone(), after.clone()))
+}
+
+/// Computes the structured diff between two snapshots.
+pub fn diff_snapshots(
+    before: &TypeSnapshot,
+    after: &TypeSnapshot,
+    rename_threshold: f32,
+) -> TypeDiff {
+    let empty = TypeShape::default();
+    let before_shape = before.shape.as_ref().unwrap_or(&empty);
+    let after_shape = after.shape.as_ref().unwrap_or(&empty);
+
+    TypeDiff {
+        name: changed(&before.name, &after.name),
+        display_name: changed(&before.display_name, &after.display_name),
+        category: changed(&before.category, &after.category),
+        file_path: changed(&before.file_path, &after.file_path),
+        kind: match (&before.shape, &after.shape) {
+            (Some(b), Some(a)) => changed(&b.kind, &a.kind),
+            _ => None,
+        },
+        members: diff_shapes(before_shape, after_shape, rename_threshold),
+        before_at: Some(before.recorded_at),
+        after_at: Some(after.recorded_at),
+    }
+}
+
+/// Member-level diff between two shapes.
+///
+/// Members are matched by name first. Whatever is left over on both sides is
+/// paired up as rename guesses, best confidence first, as long as the pair
+/// clears `rename_threshold`. Confidence is an even blend of name similarity
+/// and whether the member type stayed the same.
+pub fn diff_shapes(
+    before: &TypeShape,
+    after: &TypeShape,
+    rename_threshold: f32,
+) -> Vec<MemberChange> {
+    let before_by_name: HashMap<&str, &ShapeMember> =
+        before.members.iter().map(|m| (m.name.as_str(), m)).collect();
+    let after_names: std::collections::HashSet<&str> =
+        after.members.iter().map(|m| m.name.as_str()).collect();
+
+    let removed: Vec<&ShapeMember> = before
+        .members
+        .iter()
+        .filter(|m| !after_names.contains(m.name.as_str()))
+        .collect();
+    let added: Vec<&ShapeMember> = after
+        .members
+        .iter()
+        .filter(|m| !before_by_name.contains_key(m.name.as_str()))
+        .collect();
+
+    // Score every removed/added pair, then take the best ones greedily
+    let mut candidates: Vec<(usize, usize, f32)> = Vec::new();
+    for (ri, r) in removed.iter().enumerate() {
+        for (ai, a) in added.iter().enumerate() {
+            let confidence = rename_confidence(r, a);
+            if confidence >= rename_threshold {
+                candidates.push((ri, ai, confidence));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+    let mut renamed_from: HashMap<usize, (usize, f32)> = HashMap::new();
+    let mut taken_removed = vec![false; removed.len()];
+    for (ri, ai, confidence) in candidates {
+        if taken_removed[ri] || renamed_from.contains_key(&ai) {
+            continue;
+        }
+        taken_removed[ri] = true;
+        renamed_from.insert(ai, (ri, confidence));
+    }
+
+    let mut changes = Vec::new();
+    let mut added_index = 0;
+    for member in &after.members {
+        if let Some(old) = before_by_name.get(member.name.as_str()) {
+            if old.type_name != member.type_name {
+                changes.push(MemberChange::TypeChanged {
+                    name: member.name.clone(),
+                    from: old.type_name.clone(),
+                    to: member.type_name.clone(),
+                });
+            }
+            continue;
+        }
+
+        match renamed_from.get(&added_index) {
+            Some(&(ri, confidence)) => {
+                let old = removed[ri];
+                changes.push(MemberChange::Renamed {
+                    from: old.name.clone(),
+                    to: member.name.clone(),
+                    confidence,
+                });
+                if old.type_name != member.type_name {
+                    changes.push(MemberChange::TypeChanged {
+                        name: member.name.clone(),
+                        from: old.type_name.clone(),
+                        to: member.type_name.clone(),
+                    });
+                }
+            }
+            None => changes.push(MemberChange::Added {
+                name: member.name.clone(),
+                type_name: member.type_name.clone(),
+            }),
+        }
+        added_index += 1;
+    }
+
+    for (ri, member) in removed.iter().enumerate() {
+        if !taken_removed[ri] {
+            changes.push(MemberChange::Removed {
+                name: member.name.clone(),
+                type_name: member.type_name.clone(),
+            });
+        }
+    }
+
+    changes
+}
+
+fn rename_confidence(removed: &ShapeMember, added: &ShapeMember) -> f32 {
+    let type_score = if removed.type_name == added.type_name {
+        1.0
+    } else {
+        0.0
+    };
+    0.5 * name_similarity(&removed.name, &added.name) + 0.5 * type_score
+}
+
+/// Normalized Levenshtein similarity in `0.0..=1.0`, case-insensitive.
+pub fn name_similarity(a: &str, b: &str) -> f32 {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    1.0 - prev[b.len()] as f32 / longest as f32
+}
+
+/// Kind of entry in the recent changes feed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeChangeKind {
+    Modified,
+    Removed,
+}
+
+/// One (possibly folded) entry in the recent changes feed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeChange {
+    pub file_path: PathBuf,
+    pub name: String,
+    pub kind: TypeChangeKind,
+    pub first_at: SystemTime,
+    pub last_at: SystemTime,
+    /// Number of updates folded into this entry
+    pub update_count: u32,
+}
+
+/// Chronological feed of type changes, folding rapid updates to the same type.
+#[derive(Debug, Clone)]
+pub struct ChangeFeed {
+    entries: VecDeque<TypeChange>,
+    capacity: usize,
+    fold_window: Duration,
+}
+
+impl Default for ChangeFeed {
+    fn default() -> Self {
+        Self::new(DEFAULT_FEED_CAPACITY, DEFAULT_FOLD_WINDOW)
+    }
+}
+
+impl ChangeFeed {
+    pub fn new(capacity: usize, fold_window: Duration) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity: capacity.max(1),
+            fold_window,
+        }
+    }
+
+    /// Appends a change, folding it into the latest entry for the same path when
+    /// both are modifications within the fold window.
+    pub fn push(
+        &mut self,
+        file_path: PathBuf,
+        name: String,
+        kind: TypeChangeKind,
+        at: SystemTime,
+    ) {
+        if let Some(pos) = self.entries.iter().rposition(|e| e.file_path == file_path) {
+            let entry = &self.entries[pos];
+            let within_window = at
+                .duration_since(entry.last_at)
+                .map(|elapsed| elapsed <= self.fold_window)
+                .unwrap_or(true);
+
+            if entry.kind == TypeChangeKind::Modified
+                && kind == TypeChangeKind::Modified
+                && within_window
+            {
+                let mut entry = self.entries.remove(pos).expect("position is in bounds");
+                entry.name = name;
+                entry.last_at = at;
+                entry.update_count += 1;
+                self.entries.push_back(entry);
+                return;
+            }
+        }
+
+        self.entries.push_back(TypeChange {
+            file_path,
+            name,
+            kind,
+            first_at: at,
+            last_at: at,
+            update_count: 1,
+        });
+        while self.entries.len() > self.capacity {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Entries, newest first.
+    pub fn entries(&self) -> Vec<TypeChange> {
+        self.entries.iter().rev().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Bounded per-type snapshot history plus the session change feed.
+#[derive(Debug)]
+pub struct TypeHistory {
+    depth: usize,
+    snapshots: Mutex<HashMap<PathBuf, VecDeque<TypeSnapshot>>>,
+    feed: Mutex<ChangeFeed>,
+}
+
+impl Default for TypeHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_DEPTH)
+    }
+}
+
+impl TypeHistory {
+    /// Creates a history keeping at most `depth` snapshots per type.
+    pub fn new(depth: usize) -> Self {
+        Self {
+            depth: depth.max(2),
+            snapshots: Mutex::new(HashMap::new()),
+            feed: Mutex::new(ChangeFeed::default()),
+        }
+    }
+
+    /// Records a snapshot taken at registration/update time.
+    ///
+    /// Re-registering an identical definition (e.g. a project rescan) is a no-op.
+    /// Returns the diff against the previous snapshot if the type actually changed.
+    pub fn record(&self, snapshot: TypeSnapshot) -> Option<TypeDiff> {
+        let mut snapshots = self.snapshots.lock();
+        let history = snapshots.entry(snapshot.file_path.clone()).or_default();
+
+        let diff = match history.back() {
+            Some(previous) if previous.same_definition(&snapshot) => return None,
+            Some(previous) => Some(diff_snapshots(
+                previous,
+                &snapshot,
+                DEFAULT_RENAME_THRESHOLD,
+            )),
+            None => None,
+        };
+
+        if diff.is_some() {
+            self.feed.lock().push(
+                snapshot.file_path.clone(),
+                snapshot.name.clone(),
+                TypeChangeKind::Modified,
+                snapshot.recorded_at,
+            );
+        }
+
+        history.push_back(snapshot);
+        while history.len() > self.depth {
+            history.pop_front();
+        }
+
+        diff
+    }
+
+    /// Notes that the type at `file_path` was removed. Its history is kept so the
+    /// debugger can still show what it looked like.
+    pub fn record_removed(&self, file_path: &Path, at: SystemTime) {
+        let name = self
+            .latest(file_path)
+            .map(|s| s.name)
+            .unwrap_or_else(|| file_path.to_string_lossy().into_owned());
+        self.feed
+            .lock()
+            .push(file_path.to_path_buf(), name, TypeChangeKind::Removed, at);
+    }
+
+    /// Moves the history of a type to a new path, so a later snapshot at the new
+    /// location diffs against the old one.
+    pub fn rekey(&self, old_path: &Path, new_path: &Path) {
+        let mut snapshots = self.snapshots.lock();
+        if let Some(history) = snapshots.remove(old_path) {
+            snapshots.insert(new_path.to_path_buf(), history);
+        }
+    }
+
+    /// All retained snapshots for a type, oldest first.
+    pub fn history(&self, file_path: &Path) -> Vec<TypeSnapshot> {
+        self.snapshots
+            .lock()
+            .get(file_path)
+            .map(|h| h.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn latest(&self, file_path: &Path) -> Option<TypeSnapshot> {
+        self.snapshots.lock().get(file_path)?.back().cloned()
+    }
+
+    /// Whether the type has more than one recorded version this session.
+    pub fn has_changed(&self, file_path: &Path) -> bool {
+        self.snapshots
+            .lock()
+            .get(file_path)
+            .is_some_and(|h| h.len() > 1)
+    }
+
+    /// Diff between each consecutive pair of retained snapshots, newest first.
+    pub fn diffs(&self, file_path: &Path, rename_threshold: f32) -> Vec<TypeDiff> {
+        let history = self.history(file_path);
+        history
+            .windows(2)
+            .rev()
+            .map(|pair| diff_snapshots(&pair[0], &pair[1], rename_threshold))
+            .collect()
+    }
+
+    /// Recent changes feed, newest first.
+    pub fn recent_changes(&self) -> Vec<TypeChange> {
+        self.feed.lock().entries()
+    }
+
+    pub fn clear(&self) {
+        self.snapshots.lock().clear();
+        self.feed.lock().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape(kind: &str, members: &[(&str, &str)]) -> TypeShape {
+        TypeShape {
+            kind: kind.to_string(),
+            members: members
+                .iter()
+                .map(|(name, ty)| ShapeMember::new(*name, *ty))
+                .collect(),
+        }
+    }
+
+    fn snapshot(name: &str, shape: TypeShape, secs: u64) -> TypeSnapshot {
+        TypeSnapshot {
+            name: name.to_string(),
+            display_name: name.to_string(),
+            category: shape.kind.clone(),
+            file_path: PathBuf::from(format!("types/{}.json", name.to_lowercase())),
+            shape: Some(shape),
+            recorded_at: SystemTime::UNIX_EPOCH + Duration::from_secs(secs),
+        }
+    }
+
+    fn at(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn test_added_removed_and_type_changed_fields() {
+        let before = shape("struct", &[("x", "f32"), ("y", "f32"), ("label", "String")]);
+        let after = shape("struct", &[("x", "f64"), ("y", "f32"), ("velocity", "Vec3")]);
+
+        let changes = diff_shapes(&before, &after, DEFAULT_RENAME_THRESHOLD);
+        assert_eq!(
+            changes,
+            vec![
+                MemberChange::TypeChanged {
+                    name: "x".into(),
+                    from: "f32".into(),
+                    to: "f64".into(),
+                },
+                MemberChange::Added {
+                    name: "velocity".into(),
+                    type_name: "Vec3".into(),
+                },
+                MemberChange::Removed {
+                    name: "label".into(),
+                    type_name: "String".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rename_guess_above_threshold() {
+        let before = shape("struct", &[("health", "u32"), ("armor", "u32")]);
+        let after = shape("struct", &[("max_health", "u32"), ("armor", "u32")]);
+
+        let changes = diff_shapes(&before, &after, DEFAULT_RENAME_THRESHOLD);
+        assert_eq!(changes.len(), 1);
+        match &changes[0] {
+            MemberChange::Renamed {
+                from,
+                to,
+                confidence,
+            } => {
+                assert_eq!(from, "health");
+                assert_eq!(to, "max_health");
+                assert!(*confidence >= DEFAULT_RENAME_THRESHOLD);
+            }
+            other => panic!("expected rename, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_rename_guess_below_threshold_is_add_and_remove() {
+        let before = shape("struct", &[("health", "u32")]);
+        let after = shape("struct", &[("name", "String")]);
+
+        let changes = diff_shapes(&before, &after, DEFAULT_RENAME_THRESHOLD);
+        assert_eq!(
+            changes,
+            vec![
+                MemberChange::Added {
+                    name: "name".into(),
+                    type_name: "String".into(),
+                },
+                MemberChange::Removed {
+                    name: "health".into(),
+                    type_name: "u32".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rename_picks_best_candidate() {
+        let before = shape("enum", &[("Idle", ""), ("Running", "")]);
+        let after = shape("enum", &[("Idling", ""), ("Run", "")]);
+
+        let changes = diff_shapes(&before, &after, DEFAULT_RENAME_THRESHOLD);
+        let renames: Vec<(String, String)> = changes
+            .iter()
+            .filter_map(|c| match c {
+                MemberChange::Renamed { from, to, .. } => Some((from.clone(), to.clone())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            renames,
+            vec![
+                ("Idle".to_string(), "Idling".to_string()),
+                ("Running".to_string(), "Run".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_snapshot_diff_reports_metadata_changes() {
+        let before = snapshot("Meters", shape("alias", &[("value", "f32")]), 1);
+        let mut after = snapshot("Distance", shape("alias", &[("value", "f64")]), 5);
+        after.file_path = before.file_path.clone();
+
+        let diff = diff_snapshots(&before, &after, DEFAULT_RENAME_THRESHOLD);
+        assert_eq!(diff.name, Some(("Meters".into(), "Distance".into())));
+        assert!(diff.category.is_none());
+        assert!(diff.file_path.is_none());
+        assert_eq!(diff.before_at, Some(at(1)));
+        assert_eq!(diff.after_at, Some(at(5)));
+        assert_eq!(diff.members.len(), 1);
+    }
+
+    #[test]
+    fn test_history_is_bounded_and_skips_identical() {
+        let history = TypeHistory::new(3);
+        let path = PathBuf::from("types/meters.json");
+
+        assert!(history
+            .record(snapshot("Meters", shape("alias", &[("value", "f32")]), 0))
+            .is_none());
+        // Identical definition, e.g. from a rescan
+        assert!(history
+            .record(snapshot("Meters", shape("alias", &[("value", "f32")]), 1))
+            .is_none());
+        assert_eq!(history.history(&path).len(), 1);
+        assert!(!history.has_changed(&path));
+
+        for (i, ty) in ["f64", "u32", "i64"].iter().enumerate() {
+            let diff = history.record(snapshot(
+                "Meters",
+                shape("alias", &[("value", ty)]),
+                10 + i as u64,
+            ));
+            assert!(diff.is_some());
+        }
+
+        let retained = history.history(&path);
+        assert_eq!(retained.len(), 3);
+        assert_eq!(retained[0].recorded_at, at(10));
+        assert!(history.has_changed(&path));
+        assert_eq!(history.diffs(&path, DEFAULT_RENAME_THRESHOLD).len(), 2);
+    }
+
+    #[test]
+    fn test_rekey_carries_history_to_new_path() {
+        let history = TypeHistory::default();
+        let old = snapshot("Meters", shape("alias", &[("value", "f32")]), 0);
+        let old_path = old.file_path.clone();
+        history.record(old);
+
+        let new_path = PathBuf::from("types/units/meters.json");
+        history.rekey(&old_path, &new_path);
+        let mut moved = snapshot("Meters", shape("alias", &[("value", "f32")]), 1);
+        moved.file_path = new_path.clone();
+
+        let diff = history.record(moved).expect("path change is a change");
+        assert_eq!(diff.file_path, Some((old_path, new_path)));
+        assert!(diff.members.is_empty());
+    }
+
+    #[test]
+    fn test_feed_folds_rapid_updates() {
+        let mut feed = ChangeFeed::new(10, Duration::from_secs(2));
+        let path = PathBuf::from("types/a.json");
+
+        feed.push(path.clone(), "A".into(), TypeChangeKind::Modified, at(0));
+        feed.push(path.clone(), "A".into(), TypeChangeKind::Modified, at(1));
+        feed.push(path.clone(), "A".into(), TypeChangeKind::Modified, at(3));
+
+        let entries = feed.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].update_count, 3);
+        assert_eq!(entries[0].first_at, at(0));
+        assert_eq!(entries[0].last_at, at(3));
+    }
+
+    #[test]
+    fn test_feed_does_not_fold_across_window_or_kinds() {
+        let mut feed = ChangeFeed::new(10, Duration::from_secs(2));
+        let a = PathBuf::from("types/a.json");
+        let b = PathBuf::from("types/b.json");
+
+        feed.push(a.clone(), "A".into(), TypeChangeKind::Modified, at(0));
+        feed.push(a.clone(), "A".into(), TypeChangeKind::Modified, at(10));
+        feed.push(b.clone(), "B".into(), TypeChangeKind::Modified, at(11));
+        feed.push(b.clone(), "B".into(), TypeChangeKind::Removed, at(12));
+
+        let entries = feed.entries();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].kind, TypeChangeKind::Removed);
+        assert_eq!(entries[0].file_path, b);
+        assert_eq!(entries[3].file_path, a);
+    }
+
+    #[test]
+    fn test_feed_fold_moves_entry_to_front() {
+        let mut feed = ChangeFeed::new(10, Duration::from_secs(5));
+        let a = PathBuf::from("types/a.json");
+        let b = PathBuf::from("types/b.json");
+
+        feed.push(a.clone(), "A".into(), TypeChangeKind::Modified, at(0));
+        feed.push(b.clone(), "B".into(), TypeChangeKind::Modified, at(1));
+        feed.push(a.clone(), "A".into(), TypeChangeKind::Modified, at(2));
+
+        let entries = feed.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].file_path, a);
+        assert_eq!(entries[0].update_count, 2);
+    }
+
+    #[test]
+    fn test_feed_capacity() {
+        let mut feed = ChangeFeed::new(2, Duration::ZERO);
+        for i in 0..5 {
+            feed.push(
+                PathBuf::from(format!("types/{}.json", i)),
+                i.to_string(),
+                TypeChangeKind::Modified,
+                at(i),
+            );
+        }
+        let entries = feed.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "4");
+        assert_eq!(entries[1].name, "3");
+    }
+
+    #[test]
+    fn test_name_similarity() {
+        assert_eq!(name_similarity("abc", "abc"), 1.0);
+        assert_eq!(name_similarity("", ""), 1.0);
+        assert_eq!(name_similarity("abc", "xyz"), 0.0);
+        assert!((name_similarity("Speed", "speed") - 1.0).abs() < f32::EPSILON);
+    }
+}
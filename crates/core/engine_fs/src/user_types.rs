@@ -9,8 +9,12 @@
 //! wrapping the resolved base type, which lets `pulsar_reflection` remain the sole
 //! source of truth for type information while this module just maintains the
 //! file-path/name <-> type bookkeeping needed by the project filesystem.
+//!
+//! [`UserTypeRegistry::subscribe`] hands out a receiver of [`UserTypeEvent`]s
+//! so views like the type debugger can follow changes instead of polling.
 
 use crate::asset_index::fuzzy_match;
+use crate::type_history::{ShapeMember, TypeHistory, TypeShape, TypeSnapshot};
 use crate::{events, FsChangeKind};
 use anyhow::{Context, Result};
 use dashmap::DashMap;
@@ -22,6 +26,7 @@ use pulsar_reflection::{
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::SystemTime;
+use tokio::sync::broadcast;
 use ui_types_common::types::TypeAstNode;
 use uuid::Uuid;
 
@@ -29,7 +34,9 @@ use uuid::Uuid;
 /// [`DynamicTypeInfo`] registered in [`DYNAMIC_TYPE_REGISTRY`].
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct UserTypeInfo {
-    /// UUID of the registered [`DynamicTypeInfo`] in [`DYNAMIC_TYPE_REGISTRY`]
+    /// ID of the type, kept when its file is registered again. It starts out
+    /// as the UUID of its [`DynamicTypeInfo`] in [`DYNAMIC_TYPE_REGISTRY`];
+    /// use [`UserTypeRegistry::dynamic_type`] to look that up.
     pub uuid: Uuid,
     /// Name of the type alias
     pub name: String,
@@ -45,15 +52,49 @@ pub struct UserTypeInfo {
     pub last_modified: Option<SystemTime>,
 }
 
+/// Events a subscriber holds on to before it starts missing them, see
+/// [`UserTypeRegistry::subscribe`].
+const EVENT_CAPACITY: usize = 256;
+
+/// A change to a [`UserTypeRegistry`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UserTypeEvent {
+    /// A type was registered at a path that had none
+    Registered(UserTypeInfo),
+    /// The type at this path was registered again, keeping its UUID
+    Updated(UserTypeInfo),
+    /// The type with this UUID was removed
+    Unregistered(Uuid),
+    /// Every type was removed
+    Cleared,
+}
+
 /// Registry of user-defined type aliases, indexed by UUID, name, and file path.
 ///
 /// The actual type information lives in [`DYNAMIC_TYPE_REGISTRY`]; this registry
 /// just tracks the file-system-facing metadata and keeps it in sync.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct UserTypeRegistry {
     by_uuid: DashMap<Uuid, UserTypeInfo>,
     by_path: DashMap<PathBuf, Uuid>,
     by_name: DashMap<String, Uuid>,
+    /// UUID of each type's current [`DynamicTypeInfo`], by the type's UUID
+    dynamic_ids: DashMap<Uuid, Uuid>,
+    history: Arc<TypeHistory>,
+    events: broadcast::Sender<UserTypeEvent>,
+}
+
+impl Default for UserTypeRegistry {
+    fn default() -> Self {
+        Self {
+            by_uuid: DashMap::new(),
+            by_path: DashMap::new(),
+            by_name: DashMap::new(),
+            dynamic_ids: DashMap::new(),
+            history: Arc::default(),
+            events: broadcast::channel(EVENT_CAPACITY).0,
+        }
+    }
 }
 
 impl UserTypeRegistry {
@@ -61,6 +102,26 @@ impl UserTypeRegistry {
         Self::default()
     }
 
+    /// Receives every change made from now on.
+    ///
+    /// Each subscriber gets its own copy of every event, and changes never
+    /// wait on subscribers: one that falls more than [`EVENT_CAPACITY`] events
+    /// behind gets [`RecvError::Lagged`](broadcast::error::RecvError::Lagged)
+    /// and should reload from [`all`](Self::all).
+    pub fn subscribe(&self) -> broadcast::Receiver<UserTypeEvent> {
+        self.events.subscribe()
+    }
+
+    fn emit(&self, event: UserTypeEvent) {
+        // Fails only when nobody is subscribed
+        let _ = self.events.send(event);
+    }
+
+    /// Snapshot history of every registration this session, shared with the type debugger.
+    pub fn history(&self) -> Arc<TypeHistory> {
+        self.history.clone()
+    }
+
     /// Returns all registered user types.
     pub fn all(&self) -> Vec<UserTypeInfo> {
         self.by_uuid.iter().map(|e| e.value().clone()).collect()
@@ -78,6 +139,12 @@ impl UserTypeRegistry {
         self.by_uuid.get(uuid.value()).map(|e| e.value().clone())
     }
 
+    /// Looks up the current [`DynamicTypeInfo`] of a user type by its UUID.
+    pub fn dynamic_type(&self, uuid: &Uuid) -> Option<Arc<DynamicTypeInfo>> {
+        let dynamic_id = self.dynamic_ids.get(uuid)?;
+        DYNAMIC_TYPE_REGISTRY.get(dynamic_id.value())
+    }
+
     /// Returns all user types with the given file type ID.
     pub fn get_by_file_type(&self, file_type_id: &FileTypeId) -> Vec<UserTypeInfo> {
         self.by_uuid
@@ -130,27 +197,39 @@ impl UserTypeRegistry {
 
     /// Removes all user types from this registry and from [`DYNAMIC_TYPE_REGISTRY`].
     pub fn clear(&self) {
-        for entry in self.by_uuid.iter() {
-            DYNAMIC_TYPE_REGISTRY.unregister(&entry.value().uuid);
+        for entry in self.dynamic_ids.iter() {
+            DYNAMIC_TYPE_REGISTRY.unregister(entry.value());
         }
+        self.dynamic_ids.clear();
         self.by_uuid.clear();
         self.by_path.clear();
         self.by_name.clear();
+        self.emit(UserTypeEvent::Cleared);
     }
 
     /// Removes a user type by file path, unregistering it from [`DYNAMIC_TYPE_REGISTRY`] too.
     pub fn unregister_by_path(&self, file_path: &Path) -> Option<UserTypeInfo> {
+        let info = self.remove_by_path(file_path)?;
+        self.emit(UserTypeEvent::Unregistered(info.uuid));
+        Some(info)
+    }
+
+    /// [`unregister_by_path`](Self::unregister_by_path) without the event.
+    fn remove_by_path(&self, file_path: &Path) -> Option<UserTypeInfo> {
         let (_, uuid) = self.by_path.remove(file_path)?;
         let (_, info) = self.by_uuid.remove(&uuid)?;
         self.by_name.remove(&info.name.to_lowercase());
-        DYNAMIC_TYPE_REGISTRY.unregister(&uuid);
+        if let Some((_, dynamic_id)) = self.dynamic_ids.remove(&uuid) {
+            DYNAMIC_TYPE_REGISTRY.unregister(&dynamic_id);
+        }
         Some(info)
     }
 
     /// Reads, parses, and registers a `.alias.json` file, building a [`DynamicTypeInfo`]
     /// for it and registering it in [`DYNAMIC_TYPE_REGISTRY`].
     ///
-    /// If a type was already registered for this path, it is replaced.
+    /// If a type was already registered for this path, it is replaced and
+    /// keeps its UUID.
     pub fn register_alias_file(&self, file_path: &Path) -> Result<Uuid> {
         let content = std::fs::read_to_string(file_path).context("Failed to read alias file")?;
         let asset: ui_types_common::AliasAsset =
@@ -177,18 +256,29 @@ impl UserTypeRegistry {
         }
 
         // Replace any previous registration for this path
-        self.unregister_by_path(&file_path);
+        let previous = self.remove_by_path(&file_path);
 
         let base_type = self.resolve_ast(&asset.ast);
         let dynamic_type = DynamicTypeBuilder::new(asset.name.clone())
             .add_field("value", base_type)
             .build();
-        let uuid = DYNAMIC_TYPE_REGISTRY.register(dynamic_type);
+        let dynamic_id = DYNAMIC_TYPE_REGISTRY.register(dynamic_type);
+        let uuid = previous.as_ref().map_or(dynamic_id, |info| info.uuid);
+        self.dynamic_ids.insert(uuid, dynamic_id);
 
         let last_modified = std::fs::metadata(&file_path)
             .ok()
             .and_then(|m| m.modified().ok());
 
+        self.history.record(TypeSnapshot {
+            name: asset.name.clone(),
+            display_name: asset.display_name.clone(),
+            category: "alias".to_string(),
+            file_path: file_path.clone(),
+            shape: Some(alias_shape(&asset)),
+            recorded_at: last_modified.unwrap_or_else(SystemTime::now),
+        });
+
         let info = UserTypeInfo {
             uuid,
             name: asset.name.clone(),
@@ -201,7 +291,13 @@ impl UserTypeRegistry {
 
         self.by_name.insert(asset.name.to_lowercase(), uuid);
         self.by_path.insert(file_path, uuid);
-        self.by_uuid.insert(uuid, info);
+        self.by_uuid.insert(uuid, info.clone());
+
+        self.emit(if previous.is_some() {
+            UserTypeEvent::Updated(info)
+        } else {
+            UserTypeEvent::Registered(info)
+        });
 
         Ok(uuid)
     }
@@ -221,7 +317,7 @@ impl UserTypeRegistry {
             }
             TypeAstNode::AliasRef { alias } => self
                 .get_by_name(alias)
-                .and_then(|info| DYNAMIC_TYPE_REGISTRY.get(&info.uuid))
+                .and_then(|info| self.dynamic_type(&info.uuid))
                 .and_then(|dynamic_type| {
                     dynamic_type.get_field("value").map(|field| field.base_type)
                 })
@@ -286,6 +382,7 @@ impl UserTypeRegistry {
     /// Deletes a type alias file and unregisters it.
     pub fn delete_type_alias(&self, file_path: &Path) -> Result<()> {
         self.unregister_by_path(file_path);
+        self.history.record_removed(file_path, SystemTime::now());
 
         std::fs::remove_file(file_path).context("Failed to delete alias file")?;
         events::emit(file_path.to_path_buf(), FsChangeKind::Deleted);
@@ -296,6 +393,7 @@ impl UserTypeRegistry {
     /// Moves/renames a type alias file and re-registers it at the new location.
     pub fn move_type_alias(&self, old_path: &Path, new_path: &Path) -> Result<()> {
         self.unregister_by_path(old_path);
+        self.history.rekey(old_path, new_path);
 
         std::fs::rename(old_path, new_path).context("Failed to move alias file")?;
 
@@ -313,11 +411,73 @@ impl UserTypeRegistry {
     }
 }
 
+/// Shape of an alias for [`TypeHistory`]: a single `value` member holding the
+/// rendered target type.
+fn alias_shape(asset: &ui_types_common::AliasAsset) -> TypeShape {
+    TypeShape {
+        kind: asset.type_kind.as_str().to_string(),
+        members: vec![ShapeMember::new(
+            "value",
+            ui_types_common::codegen::render_ast_node(&asset.ast),
+        )],
+    }
+}
+
 // Re-exported for convenience so callers don't need to depend on pulsar_reflection directly
 // just to reference the underlying dynamic type info.
 pub use pulsar_reflection::DynamicTypeInfo as UserDynamicTypeInfo;
 
-/// Looks up the [`DynamicTypeInfo`] for a registered user type by UUID.
+/// Looks up a [`DynamicTypeInfo`] by its UUID in [`DYNAMIC_TYPE_REGISTRY`].
+/// For a user type's UUID, use [`UserTypeRegistry::dynamic_type`].
 pub fn get_dynamic_type(uuid: &Uuid) -> Option<Arc<DynamicTypeInfo>> {
     DYNAMIC_TYPE_REGISTRY.get(uuid)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_alias(dir: &Path, name: &str, target: &str) -> PathBuf {
+        let path = dir.join(format!("{}.alias.json", name));
+        let json = serde_json::json!({
+            "schemaVersion": 1,
+            "typeKind": "alias",
+            "name": name,
+            "displayName": name,
+            "ast": { "nodeKind": "Primitive", "name": target },
+        });
+        std::fs::write(&path, json.to_string()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_every_subscriber_receives_every_event() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = UserTypeRegistry::new();
+        let mut first = registry.subscribe();
+        let mut second = registry.subscribe();
+        drop(registry.subscribe());
+
+        let path = write_alias(dir.path(), "EventsTestId", "u64");
+        let uuid = registry.register_alias_file(&path).unwrap();
+        write_alias(dir.path(), "EventsTestId", "u32");
+        let new_uuid = registry.register_alias_file(&path).unwrap();
+        assert_eq!(new_uuid, uuid);
+        assert!(registry.dynamic_type(&uuid).is_some());
+        registry.unregister_by_path(&path);
+        registry.clear();
+
+        for receiver in [&mut first, &mut second] {
+            let registered = receiver.try_recv().unwrap();
+            assert!(matches!(registered, UserTypeEvent::Registered(info) if info.uuid == uuid));
+            let updated = receiver.try_recv().unwrap();
+            assert!(matches!(updated, UserTypeEvent::Updated(info) if info.uuid == new_uuid));
+            assert_eq!(
+                receiver.try_recv().unwrap(),
+                UserTypeEvent::Unregistered(new_uuid)
+            );
+            assert_eq!(receiver.try_recv().unwrap(), UserTypeEvent::Cleared);
+            assert!(receiver.try_recv().is_err());
+        }
+    }
+}
@@ -2,25 +2,73 @@
 //!
 //! Monitors file changes and automatically updates indexes
 //!
-//! Note: Currently disabled registry-based type detection in watchers due to Send trait limitations.
-//! Type detection is still handled during project scans and manual operations.
+//! Events are debounced per path and coalesced, so a build or checkout touching
+//! a file many times registers it once. Settled changes are handled in batches
+//! on the watcher thread, through the same registration path as an incremental
+//! scan.
 
 use anyhow::Result;
+use notify::event::{ModifyKind, RenameMode};
 use notify::{Event, EventKind, RecursiveMode, Watcher};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use crate::asset_catalog::AssetCatalog;
 use crate::asset_index::AssetIndex;
+use crate::asset_parsers::AssetParsers;
+use crate::derived;
+use crate::project_ignore::ProjectIgnore;
+use crate::scanner::ProjectScanner;
 use crate::user_types::UserTypeRegistry;
 
+/// How long a path must go without events before its change is handled
+pub const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Counts of what the watcher has handled, see [`WatcherCounters::snapshot`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WatcherStats {
+    /// Filesystem events received
+    pub events_seen: u64,
+    /// Batches of settled changes handled
+    pub batches_processed: u64,
+    /// Changed paths in the latest batch
+    pub last_batch_size: usize,
+}
+
+/// Live counters updated by the watcher thread
+#[derive(Debug, Default)]
+pub struct WatcherCounters {
+    events_seen: AtomicU64,
+    batches_processed: AtomicU64,
+    last_batch_size: AtomicU64,
+}
+
+impl WatcherCounters {
+    pub fn snapshot(&self) -> WatcherStats {
+        WatcherStats {
+            events_seen: self.events_seen.load(Ordering::Relaxed),
+            batches_processed: self.batches_processed.load(Ordering::Relaxed),
+            last_batch_size: self.last_batch_size.load(Ordering::Relaxed) as usize,
+        }
+    }
+}
+
 /// Start watching the project directory for changes
 ///
-/// Note: Currently only handles file removal events. File creation/modification detection
-/// requires plugin registry access which isn't thread-safe yet.
+/// Events for paths `ignore` excludes are dropped. When the `.pulsarignore` file
+/// changes, the rules are read again and assets it now excludes are unregistered.
 pub fn start_watcher(
     project_root: PathBuf,
     asset_index: Arc<AssetIndex>,
     user_types: Arc<UserTypeRegistry>,
+    ignore: Arc<ProjectIgnore>,
+    catalog: Arc<AssetCatalog>,
+    parsers: Arc<AssetParsers>,
+    counters: Arc<WatcherCounters>,
 ) -> Result<()> {
     let (tx, rx) = std::sync::mpsc::channel();
 
@@ -33,14 +81,53 @@ pub fn start_watcher(
     // Watch the project root
     watcher.watch(&project_root, RecursiveMode::Recursive)?;
 
+    let scanner = ProjectScanner::new(
+        project_root,
+        asset_index.clone(),
+        user_types.clone(),
+        ignore.clone(),
+        catalog.clone(),
+        parsers,
+    );
+
     // Spawn thread to handle events
     std::thread::Builder::new()
         .name("FS Watcher".to_string())
         .spawn(move || {
             profiling::set_thread_name("FS Watcher");
-            while let Ok(event) = rx.recv() {
-                profiling::profile_scope!("fs_event_handle");
-                handle_fs_event(&event, &asset_index, &user_types);
+            let mut pending = PendingChanges::default();
+            loop {
+                let received = match pending.next_due(Instant::now()) {
+                    None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                    Some(wait) => rx.recv_timeout(wait),
+                };
+                match received {
+                    Ok(event) => {
+                        tracing::debug!("Filesystem event: {:?}", event);
+                        counters.events_seen.fetch_add(1, Ordering::Relaxed);
+                        pending.push(&event, Instant::now());
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+
+                let batch = pending.take_settled(Instant::now());
+                if batch.is_empty() {
+                    continue;
+                }
+                profiling::profile_scope!("fs_event_batch");
+                counters
+                    .last_batch_size
+                    .store(batch.len() as u64, Ordering::Relaxed);
+                handle_batch(
+                    batch,
+                    &scanner,
+                    &asset_index,
+                    &user_types,
+                    &ignore,
+                    &catalog,
+                );
+                counters.batches_processed.fetch_add(1, Ordering::Relaxed);
             }
             // Keep watcher alive
             drop(watcher);
@@ -49,25 +136,335 @@ pub fn start_watcher(
     Ok(())
 }
 
-fn handle_fs_event(event: &Event, asset_index: &AssetIndex, user_types: &UserTypeRegistry) {
-    profiling::profile_scope!("handle_fs_event");
-    tracing::debug!("Filesystem event: {:?}", event);
+/// What happened to a path, once its events are coalesced
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Change {
+    /// Created or modified
+    Changed,
+    Removed,
+    /// Moved here from `from`, and maybe modified since
+    Renamed {
+        from: PathBuf,
+    },
+}
+
+/// Changes waiting for their path to settle
+#[derive(Debug, Default)]
+struct PendingChanges {
+    changes: HashMap<PathBuf, (Change, Instant)>,
+    /// Sources of renames reported as two events, by tracker ID
+    rename_sources: HashMap<usize, PathBuf>,
+}
 
-    match &event.kind {
-        EventKind::Remove(_) => {
-            // File removed - we can safely unregister
-            for path in &event.paths {
-                asset_index.unregister_by_path(path);
-                user_types.unregister_by_path(path);
+impl PendingChanges {
+    fn push(&mut self, event: &Event, now: Instant) {
+        match &event.kind {
+            EventKind::Remove(_) => {
+                for path in &event.paths {
+                    self.record(path.clone(), Change::Removed, now);
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if event.paths.len() == 2 => {
+                self.rename(&event.paths[0], &event.paths[1], now);
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                for path in &event.paths {
+                    if let Some(tracker) = event.tracker() {
+                        self.rename_sources.insert(tracker, path.clone());
+                    }
+                    self.record(path.clone(), Change::Removed, now);
+                }
             }
+            EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+                let from = event
+                    .tracker()
+                    .and_then(|tracker| self.rename_sources.remove(&tracker));
+                for path in &event.paths {
+                    match &from {
+                        Some(from) => self.rename(from, path, now),
+                        None => self.record(path.clone(), Change::Changed, now),
+                    }
+                }
+            }
+            // Renames reported without saying which side a path is on
+            EventKind::Modify(ModifyKind::Name(_)) => {
+                for path in &event.paths {
+                    let change = if path.exists() {
+                        Change::Changed
+                    } else {
+                        Change::Removed
+                    };
+                    self.record(path.clone(), change, now);
+                }
+            }
+            EventKind::Create(_) | EventKind::Modify(_) => {
+                for path in &event.paths {
+                    self.record(path.clone(), Change::Changed, now);
+                }
+            }
+            _ => {}
         }
-        EventKind::Create(_) | EventKind::Modify(_) => {
-            // File created/modified - log for now, user needs to rescan
-            // TODO: Once PluginManager is Send-safe, integrate registry-based detection here
-            for path in &event.paths {
-                tracing::info!("File changed: {:?} - rescan project to update index", path);
+    }
+
+    /// Merge `change` into what is pending for `path`, restarting its window
+    fn record(&mut self, path: PathBuf, change: Change, now: Instant) {
+        let merged = match (self.changes.remove(&path), change) {
+            // A renamed file that is then modified still needs moving
+            (Some((Change::Renamed { from }, _)), Change::Changed) => Change::Renamed { from },
+            // A renamed file that is then removed takes its source with it
+            (Some((Change::Renamed { from }, _)), Change::Removed) => {
+                self.changes.insert(from, (Change::Removed, now));
+                Change::Removed
             }
+            (_, change) => change,
+        };
+        self.changes.insert(path, (merged, now));
+    }
+
+    fn rename(&mut self, from: &Path, to: &Path, now: Instant) {
+        let source = match self.changes.remove(from) {
+            // Renamed again, from where it was first
+            Some((Change::Renamed { from }, _)) => from,
+            _ => from.to_path_buf(),
+        };
+        self.record(to.to_path_buf(), Change::Renamed { from: source }, now);
+    }
+
+    /// How long until the next pending path settles, or `None` if there are
+    /// none
+    fn next_due(&self, now: Instant) -> Option<Duration> {
+        self.changes
+            .values()
+            .map(|(_, last)| (*last + DEBOUNCE).saturating_duration_since(now))
+            .min()
+    }
+
+    /// Remove and return the changes whose path has had no events for
+    /// [`DEBOUNCE`]. Rename sources whose other half never came settle as
+    /// removals.
+    fn take_settled(&mut self, now: Instant) -> Vec<(PathBuf, Change)> {
+        let settled: Vec<PathBuf> = self
+            .changes
+            .iter()
+            .filter(|(_, (_, last))| now.duration_since(*last) >= DEBOUNCE)
+            .map(|(path, _)| path.clone())
+            .collect();
+        let mut batch: Vec<(PathBuf, Change)> = settled
+            .into_iter()
+            .filter_map(|path| {
+                let (change, _) = self.changes.remove(&path)?;
+                Some((path, change))
+            })
+            .collect();
+        // Their path was recorded as removed when the source was reported
+        self.rename_sources.retain(|_, from| self.changes.contains_key(from));
+        // Removals first, so a path reused by a rename or a new file is free
+        batch.sort_by_key(|(path, change)| (!matches!(change, Change::Removed), path.clone()));
+        batch
+    }
+}
+
+fn handle_batch(
+    batch: Vec<(PathBuf, Change)>,
+    scanner: &ProjectScanner,
+    asset_index: &AssetIndex,
+    user_types: &UserTypeRegistry,
+    ignore: &ProjectIgnore,
+    catalog: &AssetCatalog,
+) {
+    if batch.iter().any(|(path, _)| ignore.is_ignore_file(path)) {
+        reload_ignore(asset_index, user_types, ignore);
+    }
+
+    for (path, change) in batch {
+        let ignored = ignore.is_ignored(&path, path.is_dir());
+        match change {
+            Change::Removed if !ignored => {
+                catalog.remove(&path);
+                scanner.forget_problems(&path);
+                remove(&path, asset_index, user_types);
+            }
+            Change::Changed if !ignored => {
+                derived::global().on_input_changed(&path);
+                catalog.refresh(&path);
+                if !path.is_file() {
+                    continue;
+                }
+                match scanner.register_asset(path.clone()) {
+                    Ok(Some(_)) => {}
+                    // Already-known aliases don't need the plugin registry to be re-read
+                    Ok(None) if user_types.get_by_path(&path).is_some() => {
+                        if let Err(e) = user_types.register_alias_file(&path) {
+                            tracing::warn!(
+                                "Failed to re-register type alias at {:?}: {:?}",
+                                path,
+                                e
+                            );
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!("Failed to register changed file {:?}: {:?}", path, e),
+                }
+            }
+            Change::Renamed { from } => {
+                derived::global().on_input_changed(&from);
+                derived::global().on_input_changed(&path);
+                if ignored {
+                    // Moved out of sight, as if removed
+                    catalog.remove(&from);
+                    scanner.forget_problems(&from);
+                    remove(&from, asset_index, user_types);
+                } else {
+                    catalog.rename(&from, &path);
+                    scanner.forget_problems(&from);
+                    if let Err(e) = scanner.rename(&from, &path) {
+                        tracing::warn!("Failed to move {:?} to {:?}: {:?}", from, path, e);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    asset_index.link_references();
+}
+
+fn remove(path: &Path, asset_index: &AssetIndex, user_types: &UserTypeRegistry) {
+    derived::global().on_input_changed(path);
+    asset_index.unregister_by_path(&path.to_path_buf());
+    if user_types.unregister_by_path(path).is_some() {
+        user_types
+            .history()
+            .record_removed(path, std::time::SystemTime::now());
+    }
+}
+
+/// Read the ignore rules again and unregister the assets they now exclude.
+/// Files no longer excluded are picked up by the next rescan.
+fn reload_ignore(asset_index: &AssetIndex, user_types: &UserTypeRegistry, ignore: &ProjectIgnore) {
+    ignore.reload();
+
+    let excluded: Vec<u64> = asset_index
+        .all()
+        .into_iter()
+        .filter(|asset| {
+            asset
+                .file_path
+                .as_ref()
+                .is_some_and(|path| ignore.is_ignored(path, false))
+        })
+        .map(|asset| asset.id)
+        .collect();
+    for asset in asset_index.unregister_batch(&excluded) {
+        if let Some(path) = &asset.file_path {
+            user_types.unregister_by_path(path);
+        }
+    }
+    tracing::info!(
+        "Reloaded {:?}, unregistered {} now ignored asset(s) - rescan project to index files no longer ignored",
+        ignore.file_path(),
+        excluded.len()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, DataChange, RemoveKind};
+
+    fn event(kind: EventKind, paths: &[&str]) -> Event {
+        paths.iter().fold(Event::new(kind), |event, path| {
+            event.add_path(PathBuf::from(path))
+        })
+    }
+
+    fn modify() -> EventKind {
+        EventKind::Modify(ModifyKind::Data(DataChange::Content))
+    }
+
+    #[test]
+    fn test_events_coalesce_until_settled() {
+        let start = Instant::now();
+        let mut pending = PendingChanges::default();
+        pending.push(
+            &event(EventKind::Create(CreateKind::File), &["/p/a.rs"]),
+            start,
+        );
+        for i in 1..5 {
+            pending.push(&event(modify(), &["/p/a.rs"]), start + DEBOUNCE / 4 * i);
         }
-        _ => {}
+        pending.push(&event(modify(), &["/p/b.rs"]), start);
+        pending.push(
+            &event(EventKind::Remove(RemoveKind::File), &["/p/b.rs"]),
+            start,
+        );
+
+        // b.rs settles first; a.rs kept getting events
+        let now = start + DEBOUNCE;
+        assert_eq!(
+            pending.take_settled(now),
+            [(PathBuf::from("/p/b.rs"), Change::Removed)]
+        );
+        assert_eq!(pending.next_due(now), Some(DEBOUNCE));
+        assert_eq!(
+            pending.take_settled(now + DEBOUNCE),
+            [(PathBuf::from("/p/a.rs"), Change::Changed)]
+        );
+        assert_eq!(pending.next_due(now), None);
+    }
+
+    #[test]
+    fn test_renames() {
+        let now = Instant::now();
+        let mut pending = PendingChanges::default();
+        let rename = EventKind::Modify(ModifyKind::Name(RenameMode::Both));
+        pending.push(&event(rename, &["/p/a.rs", "/p/b.rs"]), now);
+        pending.push(&event(rename, &["/p/b.rs", "/p/c.rs"]), now);
+        pending.push(&event(modify(), &["/p/c.rs"]), now);
+
+        let from = EventKind::Modify(ModifyKind::Name(RenameMode::From));
+        let to = EventKind::Modify(ModifyKind::Name(RenameMode::To));
+        pending.push(&event(from, &["/p/x.rs"]).set_tracker(7), now);
+        pending.push(&event(to, &["/p/y.rs"]).set_tracker(7), now);
+
+        assert_eq!(
+            pending.take_settled(now + DEBOUNCE),
+            [
+                (
+                    PathBuf::from("/p/c.rs"),
+                    Change::Renamed {
+                        from: PathBuf::from("/p/a.rs")
+                    }
+                ),
+                (
+                    PathBuf::from("/p/y.rs"),
+                    Change::Renamed {
+                        from: PathBuf::from("/p/x.rs")
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unmatched_rename_source_settles_as_removal() {
+        let now = Instant::now();
+        let mut pending = PendingChanges::default();
+        let from = EventKind::Modify(ModifyKind::Name(RenameMode::From));
+        let to = EventKind::Modify(ModifyKind::Name(RenameMode::To));
+        pending.push(&event(from, &["/p/x.rs"]).set_tracker(7), now);
+
+        assert_eq!(
+            pending.take_settled(now + DEBOUNCE),
+            [(PathBuf::from("/p/x.rs"), Change::Removed)]
+        );
+        assert!(pending.rename_sources.is_empty());
+
+        // A late other half is just a new file
+        let later = now + DEBOUNCE * 2;
+        pending.push(&event(to, &["/p/y.rs"]).set_tracker(7), later);
+        assert_eq!(
+            pending.take_settled(later + DEBOUNCE),
+            [(PathBuf::from("/p/y.rs"), Change::Changed)]
+        );
     }
 }
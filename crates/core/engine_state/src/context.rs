@@ -4,11 +4,14 @@
 //! Each context represents a specific domain (windows, projects, etc.) with
 //! proper types instead of string key-value pairs.
 
+use crate::event_bus::{EngineEvent, EventBus, ProjectClosed, ProjectOpened};
+use crate::multiuser::{participant_events, ParticipantEvent};
+use crate::recent_projects::{RecentProject, RecentProjects};
 use crate::DiscordPresence;
 use dashmap::DashMap;
 use engine_fs::UserTypeRegistry;
 use pulsar_auth::AuthProfile;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::OnceLock;
 use ui_types_common::window_types::{WindowId, WindowRequest};
@@ -178,6 +181,12 @@ pub struct EngineContext {
     /// The extension point for new per-window state (replaces ad-hoc
     /// per-window registries):
     pub window_state: crate::keyed_store::KeyedStore<WindowId>,
+
+    /// Typed engine-wide events (project opened, build finished, ...).
+    ///
+    /// Use [`Self::publish`] / [`Self::subscribe`] instead of broadcasting
+    /// through shared state.
+    pub events: EventBus,
 }
 
 impl EngineContext {
@@ -192,6 +201,7 @@ impl EngineContext {
             renderers: crate::renderers_typed::TypedRendererRegistry::new(),
             window_state: crate::keyed_store::KeyedStore::new(),
             store,
+            events: EventBus::new(),
         }
     }
 
@@ -226,11 +236,73 @@ impl EngineContext {
         self.windows.len()
     }
 
-    /// Set current project
+    /// Set current project, publishing [`ProjectClosed`] for the one it
+    /// replaces and [`ProjectOpened`] if the path changed
     pub fn set_project(&self, project: ProjectContext) {
-        self.store
+        let path = project.path.clone();
+        let previous = self
+            .store
             .get_or_init::<Option<ProjectContext>>()
-            .set(Some(project));
+            .update(|current| current.replace(project).map(|p| p.path));
+        if previous.as_ref() == Some(&path) {
+            return;
+        }
+        if let Some(previous) = previous {
+            self.publish(ProjectClosed { path: previous });
+        }
+        self.publish(ProjectOpened { path });
+    }
+
+    /// Send `event` to every current subscriber of its type
+    pub fn publish<T: EngineEvent>(&self, event: T) -> usize {
+        self.events.publish(event)
+    }
+
+    /// Receive every `T` published from now on
+    pub fn subscribe<T: EngineEvent>(&self) -> smol::channel::Receiver<T> {
+        self.events.subscribe()
+    }
+
+    /// Recently opened projects, pinned ones first, then most recent first.
+    /// Projects whose directory is gone are kept and marked `missing`.
+    pub fn recent_projects(&self) -> Vec<RecentProject> {
+        self.store.get_or_init::<RecentProjects>().read().list()
+    }
+
+    /// Move `path` to the top of the recent projects list
+    pub fn record_project_opened(&self, path: &Path) {
+        self.store
+            .get_or_init::<RecentProjects>()
+            .update(|recent| recent.record_opened(path));
+    }
+
+    /// Keep `path` at the top of the recent projects list. Returns false if
+    /// it isn't in the list.
+    pub fn pin_project(&self, path: &Path) -> bool {
+        self.store
+            .get_or_init::<RecentProjects>()
+            .update(|recent| recent.set_pinned(path, true))
+    }
+
+    /// Undo [`Self::pin_project`]
+    pub fn unpin_project(&self, path: &Path) -> bool {
+        self.store
+            .get_or_init::<RecentProjects>()
+            .update(|recent| recent.set_pinned(path, false))
+    }
+
+    /// Remove `path` from the recent projects list
+    pub fn forget_recent_project(&self, path: &Path) -> bool {
+        self.store
+            .get_or_init::<RecentProjects>()
+            .update(|recent| recent.remove(path))
+    }
+
+    /// Empty the recent projects list, except for pinned projects
+    pub fn clear_recent_projects(&self) {
+        self.store
+            .get_or_init::<RecentProjects>()
+            .update(|recent| recent.clear_unpinned());
     }
 
     /// Initialize Discord Rich Presence
@@ -317,7 +389,7 @@ impl EngineContext {
     /// engine_context.set_multiuser(multiuser_ctx);
     /// ```
     pub fn set_multiuser(&self, context: crate::multiuser::MultiuserContext) {
-        self.multiuser.set(Some(context));
+        self.change_multiuser(|session| *session = Some(context));
     }
 
     /// Mutate multiuser context in place if active.
@@ -332,19 +404,53 @@ impl EngineContext {
         if self.multiuser.read().is_none() {
             return false;
         }
-        self.multiuser.update(|guard| {
-            if let Some(ctx) = guard.as_mut() {
+        self.change_multiuser(|session| {
+            if let Some(ctx) = session.as_mut() {
                 update(ctx);
             }
         });
         true
     }
 
+    /// Edit one participant's presence in the active session, publishing
+    /// [`ParticipantEvent::Updated`]. Returns `false` without a session or
+    /// if `peer_id` isn't in it.
+    pub fn update_participant<F>(&self, peer_id: &str, update: F) -> bool
+    where
+        F: FnOnce(&mut crate::multiuser::ParticipantInfo),
+    {
+        let mut updated = false;
+        self.update_multiuser(|ctx| updated = ctx.update_participant(peer_id, update));
+        updated
+    }
+
     /// Clear multiuser session context
     ///
     /// Call this when disconnecting from a session.
     pub fn clear_multiuser(&self) {
-        self.multiuser.set(None);
+        self.change_multiuser(|session| *session = None);
+    }
+
+    /// Apply `change` to the session, then publish a [`ParticipantEvent`] for
+    /// every participant it added, removed or changed.
+    fn change_multiuser(
+        &self,
+        change: impl FnOnce(&mut Option<crate::multiuser::MultiuserContext>),
+    ) {
+        let participants = |session: &Option<crate::multiuser::MultiuserContext>| {
+            session
+                .as_ref()
+                .map(|ctx| ctx.participant_info.clone())
+                .unwrap_or_default()
+        };
+        let events = self.multiuser.update(|session| {
+            let before = participants(session);
+            change(session);
+            participant_events(&before, &participants(session))
+        });
+        for event in events {
+            self.publish(event);
+        }
     }
 
     /// Get multiuser session context (if active)
@@ -371,7 +477,7 @@ impl EngineContext {
         participants: Vec<crate::multiuser::MultiuserParticipant>,
     ) {
         let _ = self.update_multiuser(|ctx| {
-            ctx.participant_profiles = participants;
+            ctx.set_participant_profiles(participants);
         });
     }
 
@@ -386,6 +492,34 @@ impl EngineContext {
         self.multiuser.update(|_| {});
     }
 
+    /// Publish the latest frame statistics of the active renderer
+    pub fn set_render_metrics(&self, metrics: crate::renderers_typed::RenderMetrics) {
+        self.store
+            .get_or_init::<Option<crate::renderers_typed::RenderMetrics>>()
+            .set(Some(metrics));
+    }
+
+    /// Forget the render metrics, e.g. when the last renderer is dropped
+    pub fn clear_render_metrics(&self) {
+        self.store
+            .get_or_init::<Option<crate::renderers_typed::RenderMetrics>>()
+            .set(None);
+    }
+
+    /// Latest frame statistics, or `None` without an active renderer
+    pub fn render_metrics(&self) -> Option<crate::renderers_typed::RenderMetrics> {
+        *self
+            .store
+            .get_or_init::<Option<crate::renderers_typed::RenderMetrics>>()
+            .read()
+    }
+
+    /// Non-sensitive state for crash reports: project, windows, launch
+    /// parameters, multiuser status and renderer kinds
+    pub fn snapshot(&self) -> crate::crash_report::EngineSnapshot {
+        crate::crash_report::EngineSnapshot::capture(self)
+    }
+
     /// Set as global instance (for GPUI views that need global access)
     pub fn set_global(self) {
         GLOBAL_CONTEXT.set(self);
@@ -461,4 +595,80 @@ mod tests {
         project_handle.set(None);
         assert!(project_handle.read().is_none());
     }
+
+    #[test]
+    fn test_render_metrics_cleared_without_renderer() {
+        use crate::renderers_typed::RenderMetrics;
+
+        let context = EngineContext::new();
+        assert_eq!(context.render_metrics(), None);
+
+        let metrics = RenderMetrics {
+            frame_time_ms: 16.6,
+            draw_calls: 12,
+            triangles: 3_000,
+            ..Default::default()
+        };
+        context.set_render_metrics(metrics);
+        assert_eq!(context.render_metrics(), Some(metrics));
+
+        context.clear_render_metrics();
+        assert_eq!(context.render_metrics(), None);
+    }
+
+    #[test]
+    fn test_set_project_publishes_events() {
+        let context = EngineContext::new();
+        let opened = context.subscribe::<ProjectOpened>();
+        let closed = context.subscribe::<ProjectClosed>();
+
+        context.set_project(ProjectContext::new(PathBuf::from("/a")));
+        context.set_project(ProjectContext::new(PathBuf::from("/a")).with_window_id(7));
+        context.set_project(ProjectContext::new(PathBuf::from("/b")));
+
+        let opened: Vec<_> = std::iter::from_fn(|| opened.try_recv().ok()).collect();
+        let closed: Vec<_> = std::iter::from_fn(|| closed.try_recv().ok()).collect();
+        assert_eq!(
+            opened,
+            [
+                ProjectOpened { path: "/a".into() },
+                ProjectOpened { path: "/b".into() }
+            ]
+        );
+        assert_eq!(closed, [ProjectClosed { path: "/a".into() }]);
+    }
+
+    #[test]
+    fn test_participant_changes_publish_events() {
+        use crate::multiuser::MultiuserContext;
+
+        let context = EngineContext::new();
+        let events = context.subscribe::<ParticipantEvent>();
+        let mut next = || events.try_recv().ok();
+
+        context.set_multiuser(
+            MultiuserContext::new("ws://relay", "s", "me", "me")
+                .with_participants(vec!["me".to_string()]),
+        );
+        assert!(matches!(next(), Some(ParticipantEvent::Joined(p)) if p.peer_id == "me"));
+
+        context.update_multiuser(|mu| mu.add_participant("them"));
+        assert!(matches!(next(), Some(ParticipantEvent::Joined(p)) if p.peer_id == "them"));
+
+        assert!(context.update_participant("them", |p| {
+            p.focused_path = Some(PathBuf::from("scenes/main.scene"));
+        }));
+        assert!(matches!(
+            next(),
+            Some(ParticipantEvent::Updated(p)) if p.focused_path.is_some()
+        ));
+        assert!(!context.update_participant("nobody", |_| {}));
+
+        context.clear_multiuser();
+        let left: Vec<_> = std::iter::from_fn(&mut next).collect();
+        assert_eq!(left.len(), 2);
+        assert!(left
+            .iter()
+            .all(|e| matches!(e, ParticipantEvent::Left { .. })));
+    }
 }
@@ -0,0 +1,317 @@
+//! Crash reports
+//!
+//! [`install_crash_reporter`] adds a panic hook that writes
+//! `crash_{timestamp}.json` next to the logs support already asks for: the
+//! panic message and location, a backtrace, and an [`EngineSnapshot`] of the
+//! typed engine context — open project, windows, launch parameters,
+//! multiuser status and renderer kinds. Session tokens, server URLs and peer
+//! IDs are never included.
+//!
+//! The panicking thread may be holding any of the context's locks, so it
+//! never reads the context itself: the snapshot is taken on a helper thread
+//! and left out of the report if it isn't ready within
+//! [`SNAPSHOT_TIMEOUT`].
+//!
+//! Paths name the user's home directory. Call
+//! [`set_crash_report_redaction`] to replace it with `~` in reports.
+
+use crate::context::{EngineContext, LaunchContext, ProjectContext};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use ui_types_common::window_types::WindowId;
+
+/// How long the panic hook waits for the engine snapshot
+pub const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(2);
+
+static REDACT_USER_PATHS: AtomicBool = AtomicBool::new(false);
+static REPORTING: AtomicBool = AtomicBool::new(false);
+
+/// Non-sensitive engine state, for crash reports
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineSnapshot {
+    pub project_path: Option<PathBuf>,
+    pub windows: Vec<WindowSnapshot>,
+    pub launch: Option<LaunchSnapshot>,
+    pub multiuser: Option<MultiuserSnapshot>,
+    pub renderers: Vec<RendererSnapshot>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowSnapshot {
+    pub id: WindowId,
+    pub kind: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchSnapshot {
+    pub uri_project_path: Option<PathBuf>,
+    pub verbose: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MultiuserSnapshot {
+    pub mode: String,
+    pub status: String,
+    pub is_host: bool,
+    pub participants: usize,
+    pub latency_ms: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RendererSnapshot {
+    pub window_id: WindowId,
+    pub kind: String,
+}
+
+impl EngineSnapshot {
+    pub(crate) fn capture(ctx: &EngineContext) -> Self {
+        let project_path = ctx
+            .store
+            .get::<Option<ProjectContext>>()
+            .and_then(|project| project.read().as_ref().map(|p| p.path.clone()));
+
+        let mut windows: Vec<WindowSnapshot> = ctx
+            .windows
+            .iter()
+            .map(|entry| WindowSnapshot {
+                id: *entry.key(),
+                kind: entry.window_type.kind_name().to_string(),
+            })
+            .collect();
+        windows.sort_by_key(|w| w.id);
+
+        let launch = ctx.store.get::<LaunchContext>().map(|launch| {
+            let launch = launch.read();
+            LaunchSnapshot {
+                uri_project_path: launch.uri_project_path.clone(),
+                verbose: launch.verbose,
+            }
+        });
+
+        let multiuser = ctx
+            .multiuser
+            .read()
+            .as_ref()
+            .map(|session| MultiuserSnapshot {
+                mode: format!("{:?}", session.mode),
+                status: format!("{:?}", session.status),
+                is_host: session.is_host,
+                participants: session.participants.len(),
+                latency_ms: session.latency_ms,
+            });
+
+        let mut renderers: Vec<RendererSnapshot> = ctx
+            .renderers
+            .kinds()
+            .into_iter()
+            .map(|(window_id, kind)| RendererSnapshot { window_id, kind })
+            .collect();
+        renderers.sort_by_key(|r| r.window_id);
+
+        Self {
+            project_path,
+            windows,
+            launch,
+            multiuser,
+            renderers,
+        }
+    }
+
+    fn redact_user_paths(&mut self, home: &Path) {
+        let redact = |path: &mut PathBuf| {
+            if let Ok(rest) = path.strip_prefix(home) {
+                *path = Path::new("~").join(rest);
+            }
+        };
+        if let Some(path) = &mut self.project_path {
+            redact(path);
+        }
+        if let Some(path) = self
+            .launch
+            .as_mut()
+            .and_then(|l| l.uri_project_path.as_mut())
+        {
+            redact(path);
+        }
+    }
+}
+
+/// Everything written to a crash file
+#[derive(Debug, Serialize)]
+struct CrashReport {
+    time: DateTime<Utc>,
+    os: &'static str,
+    arch: &'static str,
+    thread: Option<String>,
+    message: String,
+    location: Option<String>,
+    backtrace: String,
+    snapshot: Option<EngineSnapshot>,
+}
+
+impl CrashReport {
+    fn redact_user_paths(&mut self, home: &Path) {
+        // A home of `/` would redact every path
+        if home.parent().is_none() {
+            return;
+        }
+        let home_text = home.to_string_lossy();
+        let redact = |text: &mut String| *text = text.replace(home_text.as_ref(), "~");
+        redact(&mut self.message);
+        redact(&mut self.backtrace);
+        if let Some(location) = &mut self.location {
+            redact(location);
+        }
+        if let Some(snapshot) = &mut self.snapshot {
+            snapshot.redact_user_paths(home);
+        }
+    }
+}
+
+/// Replace the user's home directory with `~` in crash reports. Off by
+/// default.
+pub fn set_crash_report_redaction(enabled: bool) {
+    REDACT_USER_PATHS.store(enabled, Ordering::Relaxed);
+}
+
+/// Write a crash report to `output_dir` whenever a thread panics, then run
+/// the previously installed hook.
+pub fn install_crash_reporter(output_dir: impl Into<PathBuf>) {
+    let output_dir = output_dir.into();
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // A panic while reporting (e.g. in the snapshot thread) is only
+        // passed on.
+        if !REPORTING.swap(true, Ordering::AcqRel) {
+            match write_report(&output_dir, build_report(info)) {
+                Ok(path) => eprintln!("Crash report written to {}", path.display()),
+                Err(e) => eprintln!("Failed to write crash report: {e}"),
+            }
+            REPORTING.store(false, Ordering::Release);
+        }
+        previous(info);
+    }));
+}
+
+fn build_report(info: &PanicHookInfo<'_>) -> CrashReport {
+    let payload = info.payload();
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "<non-string panic payload>".to_string());
+
+    let mut report = CrashReport {
+        time: Utc::now(),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        thread: std::thread::current().name().map(str::to_string),
+        message,
+        location: info.location().map(|l| l.to_string()),
+        backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+        snapshot: snapshot_from_helper_thread(),
+    };
+    if REDACT_USER_PATHS.load(Ordering::Relaxed) {
+        if let Some(dirs) = directories::BaseDirs::new() {
+            report.redact_user_paths(dirs.home_dir());
+        }
+    }
+    report
+}
+
+/// Take the snapshot on another thread, so a lock held by the panicking
+/// thread can only cost the snapshot, not deadlock the hook.
+fn snapshot_from_helper_thread() -> Option<EngineSnapshot> {
+    let ctx = EngineContext::global()?;
+    let (sender, receiver) = std::sync::mpsc::sync_channel(1);
+    std::thread::Builder::new()
+        .name("crash-snapshot".to_string())
+        .spawn(move || {
+            let _ = sender.send(ctx.snapshot());
+        })
+        .ok()?;
+    receiver.recv_timeout(SNAPSHOT_TIMEOUT).ok()
+}
+
+fn write_report(output_dir: &Path, report: CrashReport) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(output_dir)?;
+    let name = format!("crash_{}.json", report.time.format("%Y%m%d_%H%M%S_%3f"));
+    let path = output_dir.join(name);
+    let json = serde_json::to_string_pretty(&report).map_err(std::io::Error::other)?;
+    std::fs::write(&path, json)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::WindowContext;
+    use crate::multiuser::{MultiuserContext, MultiuserStatus};
+    use crate::renderers_typed::TypedRendererHandle;
+    use std::sync::Arc;
+    use ui_types_common::window_types::WindowRequest;
+
+    fn context() -> EngineContext {
+        let ctx = EngineContext::new();
+        ctx.set_project(ProjectContext::new(PathBuf::from("/home/dev/games/Rocket")));
+        ctx.windows
+            .insert(2, WindowContext::new(2, WindowRequest::Entry));
+        ctx.windows.insert(
+            1,
+            WindowContext::new(
+                1,
+                WindowRequest::ProjectEditor {
+                    project_path: "/home/dev/games/Rocket".to_string(),
+                },
+            ),
+        );
+        ctx.renderers
+            .register(1, TypedRendererHandle::helio(1, Arc::new(())));
+        ctx.set_multiuser(
+            MultiuserContext::new_peer_to_peer("ws://relay", "session", "me", "host")
+                .with_status(MultiuserStatus::Connecting),
+        );
+        ctx
+    }
+
+    #[test]
+    fn test_snapshot_leaves_out_secrets() {
+        let snapshot = context().snapshot();
+        let kinds: Vec<&str> = snapshot.windows.iter().map(|w| w.kind.as_str()).collect();
+        assert_eq!(kinds, ["ProjectEditor", "Entry"]);
+        assert_eq!(snapshot.renderers[0].kind, "Helio");
+        assert_eq!(snapshot.multiuser.as_ref().unwrap().status, "Connecting");
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert!(json.contains("/home/dev/games/Rocket"));
+        assert!(!json.contains("ws://relay") && !json.contains("session"));
+    }
+
+    #[test]
+    fn test_redacted_report_file() {
+        let mut report = CrashReport {
+            time: Utc::now(),
+            os: "linux",
+            arch: "x86_64",
+            thread: Some("main".to_string()),
+            message: "missing /home/dev/games/Rocket/Pulsar.toml".to_string(),
+            location: Some("/home/dev/src/main.rs:3:1".to_string()),
+            backtrace: String::new(),
+            snapshot: Some(context().snapshot()),
+        };
+        report.redact_user_paths(Path::new("/home/dev"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_report(dir.path(), report).unwrap();
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        assert!(name.starts_with("crash_") && name.ends_with(".json"));
+
+        let json = std::fs::read_to_string(path).unwrap();
+        assert!(!json.contains("/home/dev"));
+        assert!(json.contains("~/games/Rocket"));
+    }
+}
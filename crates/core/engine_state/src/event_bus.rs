@@ -0,0 +1,231 @@
+//! Typed, engine-wide event broadcasting.
+//!
+//! [`EventBus`] is the publish/subscribe sibling of
+//! [`crate::store::StateStore`]: where the store holds the *current* value of
+//! some state, the bus announces that something *happened* — a project was
+//! opened, a build finished, a plugin loaded. Any type implementing
+//! [`EngineEvent`] can be published; there is no registration step.
+//!
+//! Every subscriber gets its own unbounded channel, so a slow subscriber never
+//! holds up the publisher or other subscribers. Dropping the [`Receiver`] is
+//! all it takes to unsubscribe: its sender is pruned on the next publish.
+//!
+//! # Example
+//!
+//! ```
+//! use engine_state::{EngineEvent, EventBus};
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! struct AnalyzerReady;
+//! impl EngineEvent for AnalyzerReady {}
+//!
+//! let bus = EventBus::new();
+//! let ready = bus.subscribe::<AnalyzerReady>();
+//!
+//! assert_eq!(bus.publish(AnalyzerReady), 1);
+//! assert_eq!(ready.try_recv(), Ok(AnalyzerReady));
+//!
+//! drop(ready);
+//! assert_eq!(bus.publish(AnalyzerReady), 0);
+//! ```
+
+use dashmap::DashMap;
+use smol::channel::{Receiver, Sender};
+use std::any::{Any, TypeId};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// An event that can be sent over the [`EventBus`]
+pub trait EngineEvent: Clone + Send + Sync + 'static {}
+
+/// Broadcasts events to every live subscriber of their type.
+///
+/// Cheap to clone; clones share the same subscribers.
+#[derive(Clone, Default)]
+pub struct EventBus {
+    // TypeId -> Vec<Sender<T>>, type-erased.
+    subscribers: Arc<DashMap<TypeId, Box<dyn Any + Send + Sync>>>,
+}
+
+impl EventBus {
+    /// Create a bus with no subscribers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Receive every `T` published from now on.
+    pub fn subscribe<T: EngineEvent>(&self) -> Receiver<T> {
+        let (sender, receiver) = smol::channel::unbounded();
+        let mut slot = self
+            .subscribers
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(Vec::<Sender<T>>::new()));
+        let senders = downcast::<T>(&mut slot);
+        senders.retain(|s| !s.is_closed());
+        senders.push(sender);
+        receiver
+    }
+
+    /// Send `event` to every subscriber of `T`, dropping subscribers whose
+    /// receiver is gone. Returns how many subscribers it reached.
+    pub fn publish<T: EngineEvent>(&self, event: T) -> usize {
+        let Some(mut slot) = self.subscribers.get_mut(&TypeId::of::<T>()) else {
+            return 0;
+        };
+        let senders = downcast::<T>(&mut slot);
+        senders.retain(|s| s.try_send(event.clone()).is_ok());
+        senders.len()
+    }
+
+    /// How many live subscribers `T` has.
+    pub fn subscriber_count<T: EngineEvent>(&self) -> usize {
+        self.subscribers
+            .get_mut(&TypeId::of::<T>())
+            .map_or(0, |mut slot| {
+                let senders = downcast::<T>(&mut slot);
+                senders.retain(|s| !s.is_closed());
+                senders.len()
+            })
+    }
+}
+
+fn downcast<T: EngineEvent>(slot: &mut Box<dyn Any + Send + Sync>) -> &mut Vec<Sender<T>> {
+    slot.downcast_mut::<Vec<Sender<T>>>()
+        .expect("EventBus: TypeId collision")
+}
+
+// ── Engine events ────────────────────────────────────────────────────────────
+
+/// A project became the current project
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProjectOpened {
+    pub path: PathBuf,
+}
+
+/// The current project was replaced or closed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProjectClosed {
+    pub path: PathBuf,
+}
+
+/// A build of the project's game crate started
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BuildStarted {
+    pub project_root: PathBuf,
+}
+
+/// A build of the project's game crate ended
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BuildFinished {
+    pub project_root: PathBuf,
+    pub succeeded: bool,
+}
+
+/// An editor plugin finished loading
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PluginLoaded {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+}
+
+impl EngineEvent for ProjectOpened {}
+impl EngineEvent for ProjectClosed {}
+impl EngineEvent for BuildStarted {}
+impl EngineEvent for BuildFinished {}
+impl EngineEvent for PluginLoaded {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::sync::Barrier;
+
+    fn opened(i: usize) -> ProjectOpened {
+        ProjectOpened {
+            path: PathBuf::from(format!("/p/{i}")),
+        }
+    }
+
+    #[test]
+    fn test_events_are_routed_by_type() {
+        let bus = EventBus::new();
+        let opened_rx = bus.subscribe::<ProjectOpened>();
+        let closed_rx = bus.subscribe::<ProjectClosed>();
+
+        assert_eq!(bus.publish(opened(1)), 1);
+        assert_eq!(
+            bus.publish(BuildStarted {
+                project_root: "/p".into()
+            }),
+            0
+        );
+
+        assert_eq!(opened_rx.try_recv(), Ok(opened(1)));
+        assert!(opened_rx.try_recv().is_err());
+        assert!(closed_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_dropped_subscribers_are_pruned() {
+        let bus = EventBus::new();
+        let kept = bus.subscribe::<ProjectOpened>();
+        let dropped = bus.subscribe::<ProjectOpened>();
+        assert_eq!(bus.subscriber_count::<ProjectOpened>(), 2);
+
+        drop(dropped);
+        assert_eq!(bus.subscriber_count::<ProjectOpened>(), 1);
+        assert_eq!(bus.publish(opened(1)), 1);
+        assert_eq!(kept.try_recv(), Ok(opened(1)));
+    }
+
+    #[test]
+    fn test_concurrent_publishers_and_subscribers() {
+        const PUBLISHERS: usize = 4;
+        const SUBSCRIBERS: usize = 4;
+        const EVENTS: usize = 500;
+
+        let bus = EventBus::new();
+        let barrier = Arc::new(Barrier::new(PUBLISHERS + SUBSCRIBERS));
+
+        let subscribers: Vec<_> = (0..SUBSCRIBERS)
+            .map(|_| {
+                let bus = bus.clone();
+                let barrier = barrier.clone();
+                let receiver = bus.subscribe::<ProjectOpened>();
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    // Churn: short-lived subscriptions alongside the real one
+                    for _ in 0..EVENTS {
+                        drop(bus.subscribe::<ProjectOpened>());
+                    }
+                    (0..PUBLISHERS * EVENTS)
+                        .map(|_| receiver.recv_blocking().unwrap().path)
+                        .collect::<HashSet<_>>()
+                })
+            })
+            .collect();
+
+        let publishers: Vec<_> = (0..PUBLISHERS)
+            .map(|p| {
+                let bus = bus.clone();
+                let barrier = barrier.clone();
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    for i in 0..EVENTS {
+                        bus.publish(opened(p * EVENTS + i));
+                    }
+                })
+            })
+            .collect();
+
+        for publisher in publishers {
+            publisher.join().unwrap();
+        }
+        for subscriber in subscribers {
+            // Every event arrives exactly once
+            assert_eq!(subscriber.join().unwrap().len(), PUBLISHERS * EVENTS);
+        }
+        assert_eq!(bus.subscriber_count::<ProjectOpened>(), 0);
+    }
+}
@@ -71,12 +71,27 @@
 //! See `EngineContext::multiuser` for a real migration of an existing field
 //! onto this system (it replaced a single-consumer `smol::channel` bus with
 //! multi-listener [`ResourceHandle::changed`]).
+//!
+//! ## Events
+//!
+//! Things that *happen* rather than state that *is* — a project opened, a
+//! build finished — go over the typed [`EventBus`] on `EngineContext::events`:
+//!
+//! ```ignore
+//! let opened = ctx.subscribe::<ProjectOpened>();
+//! ctx.publish(BuildStarted { project_root });
+//! while let Ok(event) = opened.recv().await { /* ... */ }
+//! ```
 
 mod discord;
 mod multiuser;
+pub mod session_roles;
 
 // Typed systems (primary API)
 pub mod context;
+pub mod crash_report;
+pub mod event_bus;
+pub mod recent_projects;
 pub mod renderers_typed;
 
 // Generic, type-safe arbitrary state system
@@ -93,13 +108,22 @@ pub use pulsar_auth::AuthProfile;
 
 // Re-export multiuser types
 pub use multiuser::{
-    MultiuserContext, MultiuserMode, MultiuserParticipant, MultiuserStatus, RelayConnectionMode,
+    participant_color, MultiuserContext, MultiuserMode, MultiuserParticipant, MultiuserStatus,
+    ParticipantEvent, ParticipantInfo, RelayConnectionMode,
 };
+pub use session_roles::{Capability, SessionRole, SessionRoles, TeamRoster};
 
 // Re-export typed systems as primary API
 pub use context::{DevContext, EngineContext, LaunchContext, ProjectContext, WindowContext};
+pub use crash_report::{install_crash_reporter, set_crash_report_redaction, EngineSnapshot};
+pub use event_bus::{
+    BuildFinished, BuildStarted, EngineEvent, EventBus, PluginLoaded, ProjectClosed, ProjectOpened,
+};
 pub use keyed_store::KeyedStore;
-pub use renderers_typed::{RendererType, TypedRendererHandle, TypedRendererRegistry};
+pub use recent_projects::RecentProject;
+pub use renderers_typed::{
+    RenderMetrics, RendererType, TypedRendererHandle, TypedRendererRegistry,
+};
 pub use resource::{Resource, ResourceHandle, WriteGuard};
 pub use store::StateStore;
 
@@ -3,6 +3,15 @@
 //! Types for collaborative editing sessions.  The live session state is stored
 //! exclusively in `EngineContext::multiuser` — there is no separate global
 //! static.  Use `EngineContext::global()` to read or mutate session state.
+//!
+//! Every change to the participant list — a join, a leave, or new presence
+//! metadata — is published as a [`ParticipantEvent`] on the engine event bus,
+//! so presence UI can subscribe instead of polling.
+
+use crate::event_bus::EngineEvent;
+use crate::session_roles::{Capability, RoleTable, SessionRole};
+use std::path::PathBuf;
+use std::time::SystemTime;
 
 /// Relay/data connection mode
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -78,6 +87,8 @@ pub struct MultiuserContext {
     pub is_host: bool,
     /// List of other participants (peer IDs)
     pub participants: Vec<String>,
+    /// Presence metadata for each entry of `participants`, in the same order.
+    pub participant_info: Vec<ParticipantInfo>,
     /// Rich participant metadata when available.
     pub participant_profiles: Vec<MultiuserParticipant>,
     /// Last measured latency to signaling server in milliseconds.
@@ -88,6 +99,10 @@ pub struct MultiuserContext {
     pub auth_token: Option<String>,
     /// The workspace UUID on the `pulsar-studio` server.
     pub workspace_id: Option<String>,
+    /// Our role in the session (see [`crate::session_roles`]).
+    pub local_role: SessionRole,
+    /// Capabilities of every role in this session.
+    pub role_table: RoleTable,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
@@ -99,6 +114,83 @@ pub struct MultiuserParticipant {
     pub ping_ms: Option<u32>,
 }
 
+impl MultiuserParticipant {
+    fn label(&self) -> Option<&str> {
+        self.display_name
+            .as_deref()
+            .or(self.github_login.as_deref())
+    }
+}
+
+/// Presence of one session participant
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParticipantInfo {
+    pub peer_id: String,
+    /// Profile name when known, otherwise the peer ID
+    pub display_name: String,
+    /// RGB in `0.0..=1.0`, stable for a peer ID
+    pub color: [f32; 3],
+    /// File the participant is looking at, relative to the project root
+    pub focused_path: Option<PathBuf>,
+    pub last_seen: SystemTime,
+}
+
+impl ParticipantInfo {
+    pub fn new(peer_id: impl Into<String>) -> Self {
+        let peer_id = peer_id.into();
+        Self {
+            display_name: peer_id.clone(),
+            color: participant_color(&peer_id),
+            peer_id,
+            focused_path: None,
+            last_seen: SystemTime::now(),
+        }
+    }
+}
+
+/// Color for a peer ID, the same on every client
+pub fn participant_color(peer_id: &str) -> [f32; 3] {
+    let hash = peer_id
+        .bytes()
+        .fold(0u32, |acc, b| acc.wrapping_mul(31).wrapping_add(b as u32));
+    let r = ((hash & 0xFF) as f32) / 255.0;
+    let g = (((hash >> 8) & 0xFF) as f32) / 255.0;
+    let b = (((hash >> 16) & 0xFF) as f32) / 255.0;
+    [r, g, b]
+}
+
+/// A change to a session's participants, published on the engine event bus
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParticipantEvent {
+    Joined(ParticipantInfo),
+    Left { peer_id: String },
+    Updated(ParticipantInfo),
+}
+
+impl EngineEvent for ParticipantEvent {}
+
+/// Events that turn the `before` participants into `after`
+pub(crate) fn participant_events(
+    before: &[ParticipantInfo],
+    after: &[ParticipantInfo],
+) -> Vec<ParticipantEvent> {
+    let mut events: Vec<ParticipantEvent> = before
+        .iter()
+        .filter(|old| !after.iter().any(|new| new.peer_id == old.peer_id))
+        .map(|old| ParticipantEvent::Left {
+            peer_id: old.peer_id.clone(),
+        })
+        .collect();
+    for new in after {
+        match before.iter().find(|old| old.peer_id == new.peer_id) {
+            None => events.push(ParticipantEvent::Joined(new.clone())),
+            Some(old) if old != new => events.push(ParticipantEvent::Updated(new.clone())),
+            Some(_) => {}
+        }
+    }
+    events
+}
+
 impl MultiuserContext {
     /// Construct a P2P session context.
     pub fn new_peer_to_peer(
@@ -133,6 +225,11 @@ impl MultiuserContext {
         let peer_id_str = peer_id.into();
         let host_peer_id_str = host_peer_id.into();
         let is_host = peer_id_str == host_peer_id_str;
+        let local_role = if is_host {
+            SessionRole::Host
+        } else {
+            SessionRole::Editor
+        };
         Self {
             mode: MultiuserMode::PeerToPeer,
             server_url: server_url.into(),
@@ -142,11 +239,14 @@ impl MultiuserContext {
             status: MultiuserStatus::Disconnected,
             is_host,
             participants: Vec::new(),
+            participant_info: Vec::new(),
             participant_profiles: Vec::new(),
             latency_ms: None,
             join_token: None,
             auth_token: None,
             workspace_id: None,
+            local_role,
+            role_table: RoleTable::new(),
         }
     }
 
@@ -166,12 +266,12 @@ impl MultiuserContext {
     }
 
     pub fn with_participants(mut self, participants: Vec<String>) -> Self {
-        self.participants = participants;
+        self.merge_participants(&participants);
         self
     }
 
     pub fn with_participant_profiles(mut self, participants: Vec<MultiuserParticipant>) -> Self {
-        self.participant_profiles = participants;
+        self.set_participant_profiles(participants);
         self
     }
 
@@ -197,12 +297,103 @@ impl MultiuserContext {
     pub fn add_participant(&mut self, peer_id: impl Into<String>) {
         let peer_id = peer_id.into();
         if !self.participants.contains(&peer_id) {
+            self.participant_info
+                .push(self.new_participant_info(&peer_id));
             self.participants.push(peer_id);
         }
     }
 
     pub fn remove_participant(&mut self, peer_id: &str) {
         self.participants.retain(|p| p != peer_id);
+        self.participant_info.retain(|p| p.peer_id != peer_id);
+    }
+
+    /// Make `peer_ids` the participant list, keeping the presence metadata
+    /// of peers that were already in the session.
+    pub fn merge_participants(&mut self, peer_ids: &[String]) {
+        let mut previous = std::mem::take(&mut self.participant_info);
+        self.participant_info = peer_ids
+            .iter()
+            .map(
+                |peer_id| match previous.iter().position(|p| &p.peer_id == peer_id) {
+                    Some(index) => previous.swap_remove(index),
+                    None => self.new_participant_info(peer_id),
+                },
+            )
+            .collect();
+        self.participants = peer_ids.to_vec();
+    }
+
+    pub fn participant(&self, peer_id: &str) -> Option<&ParticipantInfo> {
+        self.participant_info.iter().find(|p| p.peer_id == peer_id)
+    }
+
+    /// Edit a participant's presence, marking them as seen now. Returns
+    /// `false` if `peer_id` isn't in the session.
+    pub fn update_participant(
+        &mut self,
+        peer_id: &str,
+        update: impl FnOnce(&mut ParticipantInfo),
+    ) -> bool {
+        let Some(info) = self
+            .participant_info
+            .iter_mut()
+            .find(|p| p.peer_id == peer_id)
+        else {
+            return false;
+        };
+        info.last_seen = SystemTime::now();
+        update(info);
+        true
+    }
+
+    /// Replace the participant profiles, renaming participants to match.
+    pub fn set_participant_profiles(&mut self, profiles: Vec<MultiuserParticipant>) {
+        self.participant_profiles = profiles;
+        for info in &mut self.participant_info {
+            if let Some(label) = self
+                .participant_profiles
+                .iter()
+                .find(|p| p.peer_id == info.peer_id)
+                .and_then(MultiuserParticipant::label)
+            {
+                info.display_name = label.to_string();
+            }
+        }
+    }
+
+    /// Add or replace one participant's profile.
+    pub fn upsert_participant_profile(&mut self, profile: MultiuserParticipant) {
+        if let Some(label) = profile.label() {
+            let _ = self.update_participant(&profile.peer_id, |info| {
+                info.display_name = label.to_string();
+            });
+        }
+        match self
+            .participant_profiles
+            .iter_mut()
+            .find(|p| p.peer_id == profile.peer_id)
+        {
+            Some(existing) => {
+                existing.display_name = profile.display_name;
+                existing.avatar_url = profile.avatar_url;
+                existing.github_login = profile.github_login;
+            }
+            None => self.participant_profiles.push(profile),
+        }
+    }
+
+    fn new_participant_info(&self, peer_id: &str) -> ParticipantInfo {
+        let mut info = ParticipantInfo::new(peer_id);
+        if let Some(label) = self
+            .participant_profiles
+            .iter()
+            .find(|p| p.peer_id == peer_id)
+            .and_then(MultiuserParticipant::label)
+        {
+            info.display_name = label.to_string();
+        }
+        info
     }
 
     pub fn is_connected(&self) -> bool {
@@ -220,6 +411,16 @@ impl MultiuserContext {
         }
     }
 
+    /// Whether our role allows `capability`.
+    pub fn can(&self, capability: Capability) -> bool {
+        self.role_table.can(&self.local_role, capability)
+    }
+
+    /// Editors should open files read-only when our role can't edit.
+    pub fn is_read_only(&self) -> bool {
+        !self.can(Capability::EditFiles)
+    }
+
     pub fn mode_label(&self) -> &'static str {
         match self.mode {
             MultiuserMode::CloudProject => "Cloud",
@@ -231,6 +432,7 @@ impl MultiuserContext {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::path::Path;
 
     #[test]
     fn test_multiuser_context_creation() {
@@ -248,6 +450,18 @@ mod tests {
         assert!(ctx.is_host);
     }
 
+    #[test]
+    fn test_local_role_defaults() {
+        let host =
+            MultiuserContext::new("ws://localhost:8080", "session-123", "peer-abc", "peer-abc");
+        assert_eq!(host.local_role, SessionRole::Host);
+        let mut guest =
+            MultiuserContext::new("ws://localhost:8080", "session-123", "peer-abc", "peer-xyz");
+        assert!(!guest.is_read_only());
+        guest.local_role = SessionRole::Reviewer;
+        assert!(guest.is_read_only());
+    }
+
     #[test]
     fn test_participant_management() {
         let mut ctx =
@@ -257,5 +471,43 @@ mod tests {
         assert_eq!(ctx.participant_count(), 2);
         ctx.remove_participant("peer-def");
         assert_eq!(ctx.participants, vec!["peer-ghi"]);
+        assert_eq!(ctx.participant_info.len(), 1);
+        assert_eq!(ctx.participant_info[0].peer_id, "peer-ghi");
+    }
+
+    #[test]
+    fn test_merge_keeps_participant_metadata() {
+        let mut ctx =
+            MultiuserContext::new("ws://localhost:8080", "session-123", "peer-abc", "peer-abc")
+                .with_participants(vec!["peer-abc".to_string(), "peer-def".to_string()]);
+        ctx.upsert_participant_profile(MultiuserParticipant {
+            peer_id: "peer-def".to_string(),
+            github_login: Some("octocat".to_string()),
+            ..Default::default()
+        });
+        assert!(ctx.update_participant("peer-def", |p| {
+            p.focused_path = Some(PathBuf::from("src/main.rs"));
+        }));
+        let before = ctx.participant_info.clone();
+
+        ctx.merge_participants(&["peer-def".to_string(), "peer-ghi".to_string()]);
+        let def = ctx.participant("peer-def").unwrap();
+        assert_eq!(def.display_name, "octocat");
+        assert_eq!(def.focused_path.as_deref(), Some(Path::new("src/main.rs")));
+        assert_eq!(
+            ctx.participant("peer-ghi").unwrap().display_name,
+            "peer-ghi"
+        );
+
+        let events = participant_events(&before, &ctx.participant_info);
+        assert_eq!(
+            events,
+            vec![
+                ParticipantEvent::Left {
+                    peer_id: "peer-abc".to_string()
+                },
+                ParticipantEvent::Joined(ctx.participant("peer-ghi").unwrap().clone()),
+            ]
+        );
     }
 }
@@ -0,0 +1,305 @@
+//! Recently opened projects
+//!
+//! The list is kept in `recent_projects.json` in the app data directory and
+//! shared by everything that opens projects: the entry screen, the loading
+//! screen and the editor's project switcher. Read and change it through
+//! [`EngineContext`](crate::EngineContext) rather than the file.
+//!
+//! A project whose directory has gone away stays in the list, marked
+//! [`missing`](RecentProject::missing), until it is removed explicitly.
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use directories::ProjectDirs;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Name of the list file, in the app data directory
+pub const RECENT_PROJECTS_FILE: &str = "recent_projects.json";
+
+/// How many unpinned projects are remembered; pinned ones are never dropped
+const MAX_UNPINNED: usize = 20;
+
+/// A project in the recent projects list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentProject {
+    pub path: PathBuf,
+    pub name: String,
+    /// When the project was last opened. Older lists stored local times
+    /// without an offset; those are read as local time.
+    #[serde(default, deserialize_with = "deserialize_timestamp")]
+    pub last_opened: Option<DateTime<Utc>>,
+    /// Pinned projects are listed first and never fall off the list
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub is_git: bool,
+    /// The project directory no longer exists. Worked out when the list is
+    /// read, never saved.
+    #[serde(skip)]
+    pub missing: bool,
+}
+
+impl RecentProject {
+    fn opened_now(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            name: project_name(path),
+            last_opened: Some(Utc::now()),
+            pinned: false,
+            is_git: path.join(".git").exists(),
+            missing: false,
+        }
+    }
+}
+
+/// The on-disk list, most recently opened first
+#[derive(Debug)]
+pub struct RecentProjects {
+    file: Option<PathBuf>,
+    projects: Vec<RecentProject>,
+}
+
+impl Default for RecentProjects {
+    /// The list in the app data directory
+    fn default() -> Self {
+        match default_file() {
+            Some(file) => Self::load(file),
+            None => Self {
+                file: None,
+                projects: Vec::new(),
+            },
+        }
+    }
+}
+
+impl RecentProjects {
+    /// Read the list at `file`. A missing file is an empty list; unreadable
+    /// entries are skipped, and an unreadable file is set aside as
+    /// `<file>.bak` so the next save doesn't destroy it.
+    pub fn load(file: PathBuf) -> Self {
+        let projects = read_projects(&file);
+        Self {
+            file: Some(file),
+            projects,
+        }
+    }
+
+    /// Every project, pinned ones first, then most recently opened first
+    pub fn list(&self) -> Vec<RecentProject> {
+        let mut projects = self.projects.clone();
+        projects.sort_by_key(|p| !p.pinned);
+        for project in &mut projects {
+            project.missing = !project.path.is_dir();
+        }
+        projects
+    }
+
+    /// Move `path` to the top of the list, adding it if needed
+    pub fn record_opened(&mut self, path: &Path) {
+        let mut project = RecentProject::opened_now(path);
+        if let Some(index) = self.position(path) {
+            project.pinned = self.projects.remove(index).pinned;
+        }
+        self.projects.insert(0, project);
+
+        let mut unpinned = 0;
+        self.projects.retain(|p| {
+            unpinned += usize::from(!p.pinned);
+            p.pinned || unpinned <= MAX_UNPINNED
+        });
+        self.save();
+    }
+
+    /// Pin or unpin `path`. Returns false if it isn't in the list.
+    pub fn set_pinned(&mut self, path: &Path, pinned: bool) -> bool {
+        let Some(index) = self.position(path) else {
+            return false;
+        };
+        self.projects[index].pinned = pinned;
+        self.save();
+        true
+    }
+
+    /// Remove `path` from the list. Returns false if it wasn't in it.
+    pub fn remove(&mut self, path: &Path) -> bool {
+        let Some(index) = self.position(path) else {
+            return false;
+        };
+        self.projects.remove(index);
+        self.save();
+        true
+    }
+
+    /// Forget every project that isn't pinned
+    pub fn clear_unpinned(&mut self) {
+        self.projects.retain(|p| p.pinned);
+        self.save();
+    }
+
+    fn position(&self, path: &Path) -> Option<usize> {
+        self.projects.iter().position(|p| p.path == path)
+    }
+
+    fn save(&self) {
+        let Some(file) = &self.file else {
+            return;
+        };
+        if let Err(e) = write_projects(file, &self.projects) {
+            tracing::warn!("Failed to save recent projects to {:?}: {}", file, e);
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ProjectsFileRef<'a> {
+    projects: &'a [RecentProject],
+}
+
+#[derive(Deserialize)]
+struct ProjectsFile {
+    projects: Vec<serde_json::Value>,
+}
+
+fn default_file() -> Option<PathBuf> {
+    ProjectDirs::from("com", "Pulsar", "Pulsar_Engine")
+        .map(|dirs| dirs.data_dir().join(RECENT_PROJECTS_FILE))
+}
+
+fn project_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string_lossy().into_owned())
+}
+
+fn read_projects(file: &Path) -> Vec<RecentProject> {
+    let text = match std::fs::read_to_string(file) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+        Err(e) => {
+            tracing::warn!("Failed to read recent projects from {:?}: {}", file, e);
+            return Vec::new();
+        }
+    };
+
+    let entries = match serde_json::from_str::<ProjectsFile>(&text) {
+        Ok(parsed) => parsed.projects,
+        Err(e) => {
+            let backup = file.with_extension("json.bak");
+            tracing::warn!(
+                "Recent projects file {:?} is unreadable ({}); moving it to {:?}",
+                file,
+                e,
+                backup
+            );
+            let _ = std::fs::rename(file, &backup);
+            return Vec::new();
+        }
+    };
+
+    let mut projects: Vec<RecentProject> = Vec::with_capacity(entries.len());
+    for entry in entries {
+        match serde_json::from_value::<RecentProject>(entry) {
+            Ok(project) if !projects.iter().any(|p| p.path == project.path) => {
+                projects.push(project)
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Skipping unreadable recent project entry: {}", e),
+        }
+    }
+    projects
+}
+
+fn write_projects(file: &Path, projects: &[RecentProject]) -> anyhow::Result<()> {
+    if let Some(parent) = file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(&ProjectsFileRef { projects })?;
+    let temp = file.with_extension("json.tmp");
+    std::fs::write(&temp, json)?;
+    std::fs::rename(&temp, file)?;
+    Ok(())
+}
+
+/// RFC 3339, or the `YYYY-MM-DD HH:MM` local time older lists used. Anything
+/// else reads as never opened rather than failing the entry.
+fn deserialize_timestamp<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(text) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    if let Ok(time) = DateTime::parse_from_rfc3339(&text) {
+        return Ok(Some(time.with_timezone(&Utc)));
+    }
+    Ok(NaiveDateTime::parse_from_str(&text, "%Y-%m-%d %H:%M")
+        .ok()
+        .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+        .map(|time| time.with_timezone(&Utc)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_pin_and_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("data").join(RECENT_PROJECTS_FILE);
+        let alpha = dir.path().join("alpha");
+        let beta = dir.path().join("beta");
+        std::fs::create_dir_all(&alpha).unwrap();
+        std::fs::create_dir_all(&beta).unwrap();
+
+        let mut recent = RecentProjects::load(file.clone());
+        recent.record_opened(&alpha);
+        recent.record_opened(&beta);
+        assert!(recent.set_pinned(&alpha, true));
+        assert!(!recent.set_pinned(&dir.path().join("gamma"), true));
+
+        std::fs::remove_dir_all(&beta).unwrap();
+        let list = RecentProjects::load(file.clone()).list();
+        let names: Vec<&str> = list.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["alpha", "beta"]);
+        assert!(list[0].pinned && !list[0].missing);
+        assert!(list[1].missing);
+
+        for i in 0..MAX_UNPINNED + 5 {
+            recent.record_opened(&dir.path().join(format!("p{i}")));
+        }
+        let list = recent.list();
+        assert_eq!(list.len(), MAX_UNPINNED + 1);
+        assert_eq!(list[0].path, alpha);
+
+        recent.clear_unpinned();
+        assert_eq!(recent.list().len(), 1);
+    }
+
+    #[test]
+    fn test_tolerant_loading() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join(RECENT_PROJECTS_FILE);
+        std::fs::write(
+            &file,
+            r#"{"projects": [
+                {"name": "old", "path": "/p/old", "last_opened": "2024-05-01 09:30", "is_git": true},
+                {"name": "new", "path": "/p/new", "last_opened": "2024-05-02T10:00:00+02:00", "is_git": false},
+                {"name": "bad"},
+                {"name": "odd", "path": "/p/odd", "last_opened": "yesterday"}
+            ]}"#,
+        )
+        .unwrap();
+        let list = RecentProjects::load(file.clone()).list();
+        assert_eq!(list.len(), 3);
+        assert!(list[0].last_opened.is_some() && list[0].is_git);
+        assert_eq!(
+            list[1].last_opened.unwrap().to_rfc3339(),
+            "2024-05-02T08:00:00+00:00"
+        );
+        assert_eq!(list[2].last_opened, None);
+
+        std::fs::write(&file, "{ not json").unwrap();
+        assert!(RecentProjects::load(file.clone()).list().is_empty());
+        assert!(dir.path().join("recent_projects.json.bak").exists());
+    }
+}
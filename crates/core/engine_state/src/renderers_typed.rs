@@ -171,6 +171,14 @@ impl TypedRendererRegistry {
         self.renderers.iter().map(|entry| *entry.key()).collect()
     }
 
+    /// The renderer kind registered for each window
+    pub fn kinds(&self) -> Vec<(u64, String)> {
+        self.renderers
+            .iter()
+            .map(|entry| (*entry.key(), entry.renderer_type.name().to_string()))
+            .collect()
+    }
+
     /// Clear all renderers
     pub fn clear(&self) {
         self.renderers.clear();
@@ -182,3 +190,19 @@ impl Default for TypedRendererRegistry {
         Self::new()
     }
 }
+
+/// Statistics from the most recent frame of the active renderer.
+///
+/// Renderers publish these every frame through
+/// [`crate::EngineContext::set_render_metrics`] so diagnostics tools can read
+/// them without downcasting a [`TypedRendererHandle`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RenderMetrics {
+    pub frame_time_ms: f32,
+    pub draw_calls: u32,
+    pub triangles: u64,
+    /// GPU memory allocated by the renderer, in bytes
+    pub gpu_mem_used: u64,
+    /// Total VRAM of the adapter in bytes, or 0 if the backend can't tell
+    pub vram_total: u64,
+}
@@ -0,0 +1,984 @@
+//! Multiuser Session Roles
+//!
+//! Role model, capability checks and default role assignment for collaborative
+//! sessions. Everything here is pure: the multiplayer UI calls these decision
+//! functions on both ends (to disable affordances locally, and on the host to
+//! reject operations from peers that lack the capability).
+//!
+//! ## Host migration
+//!
+//! The host role is never inferred from participant order and never assigned
+//! through [`SessionRoles::assign`]. It only moves through an explicit
+//! [`SessionRoles::transfer_host`]; if the host leaves, the session has no host
+//! until someone is deliberately given the role.
+//!
+//! ## Legacy clients
+//!
+//! Clients that predate roles never send a role announcement. They are treated
+//! as [`SessionRole::Editor`] regardless of their assigned role, since they can't
+//! enforce anything locally, and the UI shows a warning for them.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Version of the role protocol announced by role-aware clients.
+pub const ROLE_PROTOCOL_VERSION: u32 = 1;
+
+/// Per-project team file, relative to the project root.
+pub const TEAM_ROSTER_FILE: &str = ".pulsar/team_roles.toml";
+
+/// Something a participant may be allowed to do in a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    EditFiles,
+    AcquireLocks,
+    /// Force-release locks held by others
+    ManageLocks,
+    ChangeRoles,
+    KickParticipants,
+    ToggleRecording,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 6] = [
+        Capability::EditFiles,
+        Capability::AcquireLocks,
+        Capability::ManageLocks,
+        Capability::ChangeRoles,
+        Capability::KickParticipants,
+        Capability::ToggleRecording,
+    ];
+
+    fn bit(self) -> u8 {
+        1 << (self as u8)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Capability::EditFiles => "Edit files",
+            Capability::AcquireLocks => "Acquire locks",
+            Capability::ManageLocks => "Manage locks",
+            Capability::ChangeRoles => "Change roles",
+            Capability::KickParticipants => "Kick participants",
+            Capability::ToggleRecording => "Toggle session recording",
+        }
+    }
+}
+
+/// Set of [`Capability`] values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(from = "Vec<Capability>", into = "Vec<Capability>")]
+pub struct CapabilitySet(u8);
+
+impl CapabilitySet {
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub fn all() -> Self {
+        Capability::ALL.into_iter().collect()
+    }
+
+    pub fn with(mut self, capability: Capability) -> Self {
+        self.0 |= capability.bit();
+        self
+    }
+
+    pub fn contains(self, capability: Capability) -> bool {
+        self.0 & capability.bit() != 0
+    }
+
+    /// Whether every capability in `other` is also in `self`.
+    pub fn is_superset_of(self, other: CapabilitySet) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = Capability> {
+        Capability::ALL
+            .into_iter()
+            .filter(move |c| self.contains(*c))
+    }
+}
+
+impl FromIterator<Capability> for CapabilitySet {
+    fn from_iter<I: IntoIterator<Item = Capability>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(CapabilitySet::empty(), CapabilitySet::with)
+    }
+}
+
+impl From<Vec<Capability>> for CapabilitySet {
+    fn from(capabilities: Vec<Capability>) -> Self {
+        capabilities.into_iter().collect()
+    }
+}
+
+impl From<CapabilitySet> for Vec<Capability> {
+    fn from(set: CapabilitySet) -> Self {
+        set.iter().collect()
+    }
+}
+
+/// A participant's role. Built-in roles have fixed capabilities; custom roles
+/// are looked up in the project's [`RoleTable`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum SessionRole {
+    Host,
+    #[default]
+    Editor,
+    Reviewer,
+    Custom(String),
+}
+
+impl SessionRole {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SessionRole::Host => "host",
+            SessionRole::Editor => "editor",
+            SessionRole::Reviewer => "reviewer",
+            SessionRole::Custom(id) => id,
+        }
+    }
+
+    /// Parse a role id as sent on the wire. Unknown ids become custom roles.
+    pub fn parse(id: &str) -> Self {
+        match id.trim().to_ascii_lowercase().as_str() {
+            "host" => SessionRole::Host,
+            "editor" => SessionRole::Editor,
+            "reviewer" => SessionRole::Reviewer,
+            _ => SessionRole::Custom(id.trim().to_string()),
+        }
+    }
+}
+
+impl From<String> for SessionRole {
+    fn from(id: String) -> Self {
+        SessionRole::parse(&id)
+    }
+}
+
+impl From<SessionRole> for String {
+    fn from(role: SessionRole) -> Self {
+        role.as_str().to_string()
+    }
+}
+
+impl fmt::Display for SessionRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionRole::Host => write!(f, "Host"),
+            SessionRole::Editor => write!(f, "Editor"),
+            SessionRole::Reviewer => write!(f, "Reviewer"),
+            SessionRole::Custom(id) => write!(f, "{}", id),
+        }
+    }
+}
+
+/// A project-defined role.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleDefinition {
+    pub id: String,
+    pub label: String,
+    #[serde(default)]
+    pub capabilities: CapabilitySet,
+}
+
+/// Capabilities of every role known to a session.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RoleTable {
+    custom: BTreeMap<String, RoleDefinition>,
+}
+
+impl RoleTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_custom_roles(roles: impl IntoIterator<Item = RoleDefinition>) -> Self {
+        let mut table = Self::new();
+        for role in roles {
+            table.define(role);
+        }
+        table
+    }
+
+    /// Add or replace a custom role. Built-in ids can't be redefined.
+    pub fn define(&mut self, role: RoleDefinition) -> bool {
+        if !matches!(SessionRole::parse(&role.id), SessionRole::Custom(_)) {
+            return false;
+        }
+        self.custom.insert(role.id.clone(), role);
+        true
+    }
+
+    pub fn capabilities(&self, role: &SessionRole) -> CapabilitySet {
+        match role {
+            SessionRole::Host => CapabilitySet::all(),
+            SessionRole::Editor => CapabilitySet::empty()
+                .with(Capability::EditFiles)
+                .with(Capability::AcquireLocks),
+            SessionRole::Reviewer => CapabilitySet::empty(),
+            // Unknown custom roles get nothing rather than guessing
+            SessionRole::Custom(id) => self
+                .custom
+                .get(id)
+                .map(|role| role.capabilities)
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn can(&self, role: &SessionRole, capability: Capability) -> bool {
+        self.capabilities(role).contains(capability)
+    }
+
+    /// Roles that can be picked in the participant list (everything but Host).
+    pub fn assignable_roles(&self) -> Vec<SessionRole> {
+        let mut roles = vec![SessionRole::Editor, SessionRole::Reviewer];
+        roles.extend(self.custom.keys().cloned().map(SessionRole::Custom));
+        roles
+    }
+}
+
+/// An operation a participant attempts, checked with [`check_action`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionAction {
+    /// Any change to project files, including replicated edits.
+    EditFiles,
+    AcquireLock,
+    ForceReleaseLock,
+    Kick {
+        target_role: SessionRole,
+    },
+    ToggleRecording,
+}
+
+impl SessionAction {
+    pub fn required_capability(&self) -> Capability {
+        match self {
+            SessionAction::EditFiles => Capability::EditFiles,
+            SessionAction::AcquireLock => Capability::AcquireLocks,
+            SessionAction::ForceReleaseLock => Capability::ManageLocks,
+            SessionAction::Kick { .. } => Capability::KickParticipants,
+            SessionAction::ToggleRecording => Capability::ToggleRecording,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PermissionError {
+    MissingCapability {
+        role: SessionRole,
+        capability: Capability,
+    },
+    /// The host role moves only through [`SessionRoles::transfer_host`].
+    HostRoleNotAssignable,
+    /// The host's role can't be changed and the host can't be kicked.
+    TargetIsHost,
+    /// Managers can't hand out capabilities they don't have themselves.
+    RoleExceedsActor {
+        role: SessionRole,
+    },
+    OnlyHostCanTransfer,
+    UnknownParticipant {
+        peer_id: String,
+    },
+}
+
+impl fmt::Display for PermissionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PermissionError::MissingCapability { role, capability } => write!(
+                f,
+                "{} role is not allowed to {}",
+                role,
+                capability.label().to_lowercase()
+            ),
+            PermissionError::HostRoleNotAssignable => {
+                write!(f, "The host role can only be transferred by the host")
+            }
+            PermissionError::TargetIsHost => write!(f, "The host cannot be changed or removed"),
+            PermissionError::RoleExceedsActor { role } => {
+                write!(
+                    f,
+                    "Cannot assign {} with more permissions than your own",
+                    role
+                )
+            }
+            PermissionError::OnlyHostCanTransfer => {
+                write!(f, "Only the current host can transfer the host role")
+            }
+            PermissionError::UnknownParticipant { peer_id } => {
+                write!(f, "Unknown participant {}", peer_id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PermissionError {}
+
+/// Can `actor` perform `action`?
+pub fn check_action(
+    table: &RoleTable,
+    actor: &SessionRole,
+    action: &SessionAction,
+) -> Result<(), PermissionError> {
+    let capability = action.required_capability();
+    if !table.can(actor, capability) {
+        return Err(PermissionError::MissingCapability {
+            role: actor.clone(),
+            capability,
+        });
+    }
+    if let SessionAction::Kick { target_role } = action {
+        if *target_role == SessionRole::Host {
+            return Err(PermissionError::TargetIsHost);
+        }
+    }
+    Ok(())
+}
+
+/// Can `actor` change a participant from `target_current` to `new_role`?
+pub fn check_role_change(
+    table: &RoleTable,
+    actor: &SessionRole,
+    target_current: &SessionRole,
+    new_role: &SessionRole,
+) -> Result<(), PermissionError> {
+    if !table.can(actor, Capability::ChangeRoles) {
+        return Err(PermissionError::MissingCapability {
+            role: actor.clone(),
+            capability: Capability::ChangeRoles,
+        });
+    }
+    if *new_role == SessionRole::Host {
+        return Err(PermissionError::HostRoleNotAssignable);
+    }
+    if *target_current == SessionRole::Host {
+        return Err(PermissionError::TargetIsHost);
+    }
+    if !table
+        .capabilities(actor)
+        .is_superset_of(table.capabilities(new_role))
+    {
+        return Err(PermissionError::RoleExceedsActor {
+            role: new_role.clone(),
+        });
+    }
+    Ok(())
+}
+
+/// Role state of one participant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParticipantRole {
+    pub role: SessionRole,
+    /// Has the client announced role support? `false` means a legacy client.
+    pub role_aware: bool,
+}
+
+impl ParticipantRole {
+    /// Role used for enforcement: legacy clients are always Editors.
+    pub fn effective(&self) -> SessionRole {
+        if self.role_aware || self.role == SessionRole::Host {
+            self.role.clone()
+        } else {
+            SessionRole::Editor
+        }
+    }
+
+    /// A legacy client whose assigned role isn't what it's treated as.
+    pub fn is_legacy(&self) -> bool {
+        !self.role_aware && self.role != SessionRole::Host
+    }
+}
+
+/// A role change applied to the session, for the timeline and broadcast.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoleChange {
+    pub peer_id: String,
+    pub from: SessionRole,
+    pub to: SessionRole,
+    pub changed_by: String,
+}
+
+/// Roles of everyone in a session.
+#[derive(Debug, Clone, Default)]
+pub struct SessionRoles {
+    pub table: RoleTable,
+    participants: HashMap<String, ParticipantRole>,
+    host_peer_id: Option<String>,
+}
+
+impl SessionRoles {
+    pub fn new(table: RoleTable, host_peer_id: impl Into<String>) -> Self {
+        let host_peer_id = host_peer_id.into();
+        let mut participants = HashMap::new();
+        participants.insert(
+            host_peer_id.clone(),
+            ParticipantRole {
+                role: SessionRole::Host,
+                role_aware: true,
+            },
+        );
+        Self {
+            table,
+            participants,
+            host_peer_id: Some(host_peer_id),
+        }
+    }
+
+    /// Roles for a session whose host the server didn't report. Nobody holds
+    /// Host, and nobody is promoted to it by joining.
+    pub fn without_host(table: RoleTable) -> Self {
+        Self {
+            table,
+            ..Self::default()
+        }
+    }
+
+    pub fn host_peer_id(&self) -> Option<&str> {
+        self.host_peer_id.as_deref()
+    }
+
+    /// Add a participant with their default role. Joining never grants Host,
+    /// even if the joining peer is first in the server's participant list.
+    pub fn join(&mut self, peer_id: impl Into<String>, default_role: SessionRole) {
+        let peer_id = peer_id.into();
+        if self.participants.contains_key(&peer_id) {
+            return;
+        }
+        let role = if default_role == SessionRole::Host {
+            SessionRole::Editor
+        } else {
+            default_role
+        };
+        self.participants.insert(
+            peer_id,
+            ParticipantRole {
+                role,
+                role_aware: false,
+            },
+        );
+    }
+
+    /// Remove a participant. If they were the host, the session is left without
+    /// one — nobody is promoted implicitly.
+    pub fn leave(&mut self, peer_id: &str) {
+        self.participants.remove(peer_id);
+        if self.host_peer_id.as_deref() == Some(peer_id) {
+            self.host_peer_id = None;
+        }
+    }
+
+    /// Record that `peer_id` runs a role-aware client.
+    pub fn mark_role_aware(&mut self, peer_id: &str) {
+        if let Some(participant) = self.participants.get_mut(peer_id) {
+            participant.role_aware = true;
+        }
+    }
+
+    pub fn participant(&self, peer_id: &str) -> Option<&ParticipantRole> {
+        self.participants.get(peer_id)
+    }
+
+    /// Effective role of `peer_id` (unknown peers are treated as Reviewers).
+    pub fn role_of(&self, peer_id: &str) -> SessionRole {
+        self.participants
+            .get(peer_id)
+            .map(ParticipantRole::effective)
+            .unwrap_or(SessionRole::Reviewer)
+    }
+
+    pub fn can(&self, peer_id: &str, capability: Capability) -> bool {
+        self.table.can(&self.role_of(peer_id), capability)
+    }
+
+    pub fn check(&self, peer_id: &str, action: &SessionAction) -> Result<(), PermissionError> {
+        check_action(&self.table, &self.role_of(peer_id), action)
+    }
+
+    /// Peers treated as Editors because their client doesn't support roles.
+    pub fn legacy_participants(&self) -> Vec<String> {
+        let mut peers: Vec<String> = self
+            .participants
+            .iter()
+            .filter(|(_, p)| p.is_legacy())
+            .map(|(peer_id, _)| peer_id.clone())
+            .collect();
+        peers.sort();
+        peers
+    }
+
+    /// Change `target`'s role on behalf of `actor`.
+    pub fn assign(
+        &mut self,
+        actor: &str,
+        target: &str,
+        role: SessionRole,
+    ) -> Result<RoleChange, PermissionError> {
+        let current = self
+            .participants
+            .get(target)
+            .ok_or_else(|| PermissionError::UnknownParticipant {
+                peer_id: target.to_string(),
+            })?
+            .role
+            .clone();
+        check_role_change(&self.table, &self.role_of(actor), &current, &role)?;
+
+        if let Some(participant) = self.participants.get_mut(target) {
+            participant.role = role.clone();
+        }
+        Ok(RoleChange {
+            peer_id: target.to_string(),
+            from: current,
+            to: role,
+            changed_by: actor.to_string(),
+        })
+    }
+
+    /// Hand the host role to `new_host`. Only the current host can do this; the
+    /// previous host becomes an Editor.
+    pub fn transfer_host(
+        &mut self,
+        actor: &str,
+        new_host: &str,
+    ) -> Result<Vec<RoleChange>, PermissionError> {
+        if self.host_peer_id.as_deref() != Some(actor) {
+            return Err(PermissionError::OnlyHostCanTransfer);
+        }
+        let previous = self
+            .participants
+            .get(new_host)
+            .ok_or_else(|| PermissionError::UnknownParticipant {
+                peer_id: new_host.to_string(),
+            })?
+            .role
+            .clone();
+
+        let mut changes = Vec::new();
+        if let Some(old) = self.participants.get_mut(actor) {
+            old.role = SessionRole::Editor;
+            changes.push(RoleChange {
+                peer_id: actor.to_string(),
+                from: SessionRole::Host,
+                to: SessionRole::Editor,
+                changed_by: actor.to_string(),
+            });
+        }
+        if let Some(new) = self.participants.get_mut(new_host) {
+            new.role = SessionRole::Host;
+        }
+        changes.push(RoleChange {
+            peer_id: new_host.to_string(),
+            from: previous,
+            to: SessionRole::Host,
+            changed_by: actor.to_string(),
+        });
+        self.host_peer_id = Some(new_host.to_string());
+        Ok(changes)
+    }
+
+    /// Apply a role change received from the network after validating it.
+    pub fn apply_remote(
+        &mut self,
+        actor: &str,
+        target: &str,
+        role: SessionRole,
+    ) -> Result<RoleChange, PermissionError> {
+        if role == SessionRole::Host {
+            return self
+                .transfer_host(actor, target)
+                .map(|mut changes| changes.pop().expect("transfer always changes the new host"));
+        }
+        self.assign(actor, target, role)
+    }
+}
+
+/// A known team member and the role they should get when joining.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TeamMember {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github_login: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    pub role: SessionRole,
+}
+
+/// Per-project team list with preferred roles, stored in [`TEAM_ROSTER_FILE`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TeamRoster {
+    /// Role for participants not on the list.
+    #[serde(default)]
+    pub default_role: SessionRole,
+    #[serde(default)]
+    pub members: Vec<TeamMember>,
+    #[serde(default)]
+    pub roles: Vec<RoleDefinition>,
+}
+
+impl TeamRoster {
+    pub fn role_table(&self) -> RoleTable {
+        RoleTable::with_custom_roles(self.roles.iter().cloned())
+    }
+
+    fn find(&self, github_login: Option<&str>, display_name: Option<&str>) -> Option<usize> {
+        // GitHub login is the stable identity; display names are a fallback
+        github_login
+            .and_then(|login| {
+                self.members.iter().position(|m| {
+                    m.github_login
+                        .as_deref()
+                        .is_some_and(|l| l.eq_ignore_ascii_case(login))
+                })
+            })
+            .or_else(|| {
+                display_name.and_then(|name| {
+                    self.members.iter().position(|m| {
+                        m.github_login.is_none() && m.display_name.as_deref() == Some(name)
+                    })
+                })
+            })
+    }
+
+    /// Role a joining participant should get. Never Host: the host role is
+    /// only ever transferred deliberately.
+    pub fn resolve(&self, github_login: Option<&str>, display_name: Option<&str>) -> SessionRole {
+        let role = self
+            .find(github_login, display_name)
+            .map(|index| self.members[index].role.clone())
+            .unwrap_or_else(|| self.default_role.clone());
+        if role == SessionRole::Host {
+            SessionRole::Editor
+        } else {
+            role
+        }
+    }
+
+    /// Remember `role` as the preferred role of this participant.
+    pub fn remember(
+        &mut self,
+        github_login: Option<&str>,
+        display_name: Option<&str>,
+        role: SessionRole,
+    ) {
+        if github_login.is_none() && display_name.is_none() {
+            return;
+        }
+        match self.find(github_login, display_name) {
+            Some(index) => self.members[index].role = role,
+            None => self.members.push(TeamMember {
+                github_login: github_login.map(str::to_owned),
+                display_name: display_name.map(str::to_owned),
+                role,
+            }),
+        }
+    }
+
+    pub fn path_for(project_root: &Path) -> PathBuf {
+        project_root.join(TEAM_ROSTER_FILE)
+    }
+
+    /// Load the roster of `project_root`, or an empty one if there is none.
+    pub fn load(project_root: &Path) -> anyhow::Result<Self> {
+        let path = Self::path_for(project_root);
+        match std::fs::read_to_string(&path) {
+            Ok(text) => Ok(toml::from_str(&text)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, project_root: &Path) -> anyhow::Result<()> {
+        let path = Self::path_for(project_root);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lead_role() -> RoleDefinition {
+        RoleDefinition {
+            id: "lead".to_string(),
+            label: "Lead".to_string(),
+            capabilities: CapabilitySet::empty()
+                .with(Capability::EditFiles)
+                .with(Capability::AcquireLocks)
+                .with(Capability::ManageLocks)
+                .with(Capability::ChangeRoles),
+        }
+    }
+
+    fn session() -> SessionRoles {
+        let mut roles = SessionRoles::new(RoleTable::with_custom_roles([lead_role()]), "host");
+        for (peer, role) in [
+            ("editor", SessionRole::Editor),
+            ("reviewer", SessionRole::Reviewer),
+            ("lead", SessionRole::Custom("lead".to_string())),
+        ] {
+            roles.join(peer, role);
+            roles.mark_role_aware(peer);
+        }
+        roles
+    }
+
+    #[test]
+    fn test_builtin_capability_matrix() {
+        let table = RoleTable::new();
+        for capability in Capability::ALL {
+            assert!(table.can(&SessionRole::Host, capability));
+            assert!(!table.can(&SessionRole::Reviewer, capability));
+            let editor_can = matches!(capability, Capability::EditFiles | Capability::AcquireLocks);
+            assert_eq!(table.can(&SessionRole::Editor, capability), editor_can);
+        }
+    }
+
+    #[test]
+    fn test_every_action_check() {
+        let roles = session();
+        let actions = [
+            (
+                SessionAction::EditFiles,
+                ["host", "editor", "lead"].as_slice(),
+            ),
+            (SessionAction::AcquireLock, &["host", "editor", "lead"]),
+            (SessionAction::ForceReleaseLock, &["host", "lead"]),
+            (
+                SessionAction::Kick {
+                    target_role: SessionRole::Editor,
+                },
+                &["host"],
+            ),
+            (SessionAction::ToggleRecording, &["host"]),
+        ];
+        for (action, allowed) in actions {
+            for peer in ["host", "editor", "reviewer", "lead"] {
+                assert_eq!(
+                    roles.check(peer, &action).is_ok(),
+                    allowed.contains(&peer),
+                    "{peer} / {action:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_cannot_kick_host() {
+        let roles = session();
+        assert_eq!(
+            roles.check(
+                "host",
+                &SessionAction::Kick {
+                    target_role: SessionRole::Host
+                }
+            ),
+            Err(PermissionError::TargetIsHost)
+        );
+    }
+
+    #[test]
+    fn test_unknown_custom_role_has_no_capabilities() {
+        let mut table = RoleTable::new();
+        assert_eq!(
+            table.capabilities(&SessionRole::Custom("ghost".to_string())),
+            CapabilitySet::empty()
+        );
+        assert!(!table.define(RoleDefinition {
+            id: "Editor".to_string(),
+            label: "Sneaky".to_string(),
+            capabilities: CapabilitySet::all(),
+        }));
+    }
+
+    #[test]
+    fn test_role_changes() {
+        let mut roles = session();
+        let change = roles
+            .assign("host", "editor", SessionRole::Reviewer)
+            .unwrap();
+        assert_eq!(change.from, SessionRole::Editor);
+        assert_eq!(roles.role_of("editor"), SessionRole::Reviewer);
+
+        // Lead can manage roles but not hand out more than it has
+        assert!(roles.assign("lead", "editor", SessionRole::Editor).is_ok());
+        assert_eq!(
+            roles.assign("lead", "reviewer", SessionRole::Custom("lead".to_string())),
+            Ok(RoleChange {
+                peer_id: "reviewer".to_string(),
+                from: SessionRole::Reviewer,
+                to: SessionRole::Custom("lead".to_string()),
+                changed_by: "lead".to_string(),
+            })
+        );
+
+        assert!(matches!(
+            roles.assign("editor", "reviewer", SessionRole::Editor),
+            Err(PermissionError::MissingCapability {
+                capability: Capability::ChangeRoles,
+                ..
+            })
+        ));
+        assert_eq!(
+            roles.assign("lead", "host", SessionRole::Reviewer),
+            Err(PermissionError::TargetIsHost)
+        );
+        assert_eq!(
+            roles.assign("host", "lead", SessionRole::Host),
+            Err(PermissionError::HostRoleNotAssignable)
+        );
+    }
+
+    #[test]
+    fn test_custom_role_cannot_exceed_actor() {
+        let mut table = RoleTable::with_custom_roles([lead_role()]);
+        table.define(RoleDefinition {
+            id: "moderator".to_string(),
+            label: "Moderator".to_string(),
+            capabilities: CapabilitySet::empty().with(Capability::KickParticipants),
+        });
+        assert_eq!(
+            check_role_change(
+                &table,
+                &SessionRole::Custom("lead".to_string()),
+                &SessionRole::Editor,
+                &SessionRole::Custom("moderator".to_string()),
+            ),
+            Err(PermissionError::RoleExceedsActor {
+                role: SessionRole::Custom("moderator".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn test_host_leaving_promotes_nobody() {
+        let mut roles = session();
+        roles.leave("host");
+        assert_eq!(roles.host_peer_id(), None);
+        // The next participant is not implicitly the host
+        assert_eq!(roles.role_of("editor"), SessionRole::Editor);
+        assert!(!roles.can("lead", Capability::KickParticipants));
+        // A late joiner doesn't become host either, even when offered the role
+        roles.join("newcomer", SessionRole::Host);
+        assert_eq!(roles.role_of("newcomer"), SessionRole::Editor);
+    }
+
+    #[test]
+    fn test_unknown_host_is_nobody() {
+        let mut roles = SessionRoles::without_host(RoleTable::default());
+        roles.join("first", SessionRole::Host);
+        roles.join("second", SessionRole::Editor);
+        assert_eq!(roles.host_peer_id(), None);
+        assert_eq!(roles.role_of("first"), SessionRole::Editor);
+        assert_eq!(
+            roles.transfer_host("first", "second"),
+            Err(PermissionError::OnlyHostCanTransfer)
+        );
+    }
+
+    #[test]
+    fn test_host_transfer_is_explicit() {
+        let mut roles = session();
+        assert_eq!(
+            roles.transfer_host("lead", "editor"),
+            Err(PermissionError::OnlyHostCanTransfer)
+        );
+
+        let changes = roles.transfer_host("host", "editor").unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(roles.host_peer_id(), Some("editor"));
+        assert_eq!(roles.role_of("editor"), SessionRole::Host);
+        assert_eq!(roles.role_of("host"), SessionRole::Editor);
+
+        // Remote role messages carrying Host go through the same path
+        let change = roles
+            .apply_remote("editor", "host", SessionRole::Host)
+            .unwrap();
+        assert_eq!(change.to, SessionRole::Host);
+        assert_eq!(roles.host_peer_id(), Some("host"));
+    }
+
+    #[test]
+    fn test_legacy_client_is_editor() {
+        let mut roles = session();
+        roles.join("old-client", SessionRole::Reviewer);
+        assert_eq!(roles.role_of("old-client"), SessionRole::Editor);
+        assert!(roles.can("old-client", Capability::EditFiles));
+        assert_eq!(roles.legacy_participants(), vec!["old-client".to_string()]);
+
+        roles.mark_role_aware("old-client");
+        assert_eq!(roles.role_of("old-client"), SessionRole::Reviewer);
+        assert!(roles.legacy_participants().is_empty());
+    }
+
+    #[test]
+    fn test_unknown_peer_is_reviewer() {
+        let roles = session();
+        assert_eq!(roles.role_of("stranger"), SessionRole::Reviewer);
+        assert!(roles.check("stranger", &SessionAction::EditFiles).is_err());
+    }
+
+    #[test]
+    fn test_wire_role_parsing() {
+        assert_eq!(SessionRole::parse("Reviewer"), SessionRole::Reviewer);
+        assert_eq!(
+            SessionRole::parse("lead"),
+            SessionRole::Custom("lead".to_string())
+        );
+        assert_eq!(String::from(SessionRole::Host), "host");
+    }
+
+    #[test]
+    fn test_roster_resolution() {
+        let mut roster = TeamRoster::default();
+        roster.remember(Some("Alice"), Some("Alice A."), SessionRole::Reviewer);
+        roster.remember(None, Some("Bob"), SessionRole::Custom("lead".to_string()));
+        roster.remember(Some("carol"), None, SessionRole::Host);
+
+        assert_eq!(roster.resolve(Some("alice"), None), SessionRole::Reviewer);
+        assert_eq!(
+            roster.resolve(None, Some("Bob")),
+            SessionRole::Custom("lead".to_string())
+        );
+        // Host is never handed out automatically
+        assert_eq!(roster.resolve(Some("carol"), None), SessionRole::Editor);
+        assert_eq!(
+            roster.resolve(Some("dave"), Some("Dave")),
+            SessionRole::Editor
+        );
+
+        roster.default_role = SessionRole::Reviewer;
+        assert_eq!(roster.resolve(None, None), SessionRole::Reviewer);
+
+        // Updating a remembered member doesn't duplicate it
+        roster.remember(Some("ALICE"), None, SessionRole::Editor);
+        assert_eq!(roster.members.len(), 3);
+        assert_eq!(roster.resolve(Some("alice"), None), SessionRole::Editor);
+    }
+
+    #[test]
+    fn test_roster_persistence() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut roster = TeamRoster {
+            roles: vec![lead_role()],
+            ..TeamRoster::default()
+        };
+        roster.remember(Some("alice"), None, SessionRole::Reviewer);
+        roster.save(dir.path()).unwrap();
+
+        let loaded = TeamRoster::load(dir.path()).unwrap();
+        assert_eq!(loaded, roster);
+        assert!(loaded.role_table().can(
+            &SessionRole::Custom("lead".to_string()),
+            Capability::ManageLocks
+        ));
+    }
+}
@@ -0,0 +1,324 @@
+//! Example plugin: a Wavefront `.obj` preview built on embedded viewports.
+//!
+//! Opens `.obj` files in a panel that shows the mesh on a grid, slowly
+//! turning, with its bounding box. Everything on the GPU is owned by the host;
+//! the plugin only uploads a [`MeshData`] and draws through the render API.
+//!
+//! Build and install:
+//!
+//! ```text
+//! cargo build -p plugin_editor_api --example obj_preview
+//! cp target/debug/examples/libobj_preview.so plugins/editor/
+//! ```
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use gpui::*;
+use plugin_editor_api::*;
+use ui::dock::{Panel, PanelEvent};
+use ui::{v_flex, ActiveTheme as _};
+
+const EDITOR_ID: &str = "obj-preview";
+const FILE_TYPE_ID: &str = "wavefront-obj";
+const RENDER_CALLBACK: &str = "obj-preview";
+
+/// Degrees per second the preview turns.
+const TURN_SPEED: f32 = 20.0;
+
+// ============================================================================
+// Plugin
+// ============================================================================
+
+#[derive(Default)]
+pub struct ObjPreviewPlugin;
+
+impl EditorPlugin for ObjPreviewPlugin {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            id: PluginId::new("com.pulsar.obj-preview"),
+            name: "OBJ Preview".into(),
+            version: "0.1.0".into(),
+            author: "Pulsar".into(),
+            description: "Previews Wavefront .obj meshes in an embedded viewport".into(),
+        }
+    }
+
+    fn file_types(&self) -> Vec<FileTypeDefinition> {
+        vec![standalone_file_type(
+            FILE_TYPE_ID,
+            "obj",
+            "Wavefront Mesh",
+            ui::IconName::Cube,
+            gpui::rgb(0x8BC34A).into(),
+            serde_json::json!({}),
+        )]
+    }
+
+    fn editors(&self) -> Vec<EditorMetadata> {
+        vec![EditorMetadata {
+            id: EditorId::new(EDITOR_ID),
+            display_name: "OBJ Preview".into(),
+            supported_file_types: vec![FileTypeId::new(FILE_TYPE_ID)],
+        }]
+    }
+
+    fn on_load(&mut self) {
+        if let Err(e) = register_render_callback(
+            RenderCallbackId::new(RENDER_CALLBACK),
+            Arc::new(render_preview),
+        ) {
+            tracing::warn!("OBJ preview disabled: {}", e);
+        }
+    }
+}
+
+impl EditorPluginEditor for ObjPreviewPlugin {
+    fn register_editors(&'static self, registry: &mut EditorFactoryRegistry) {
+        registry.register_fn(EditorId::new(EDITOR_ID), |file_path, window, cx| {
+            let panel = cx.new(|cx| ObjPreviewPanel::new(file_path, window, cx));
+            Ok(Arc::new(panel) as Arc<dyn PanelView>)
+        });
+    }
+}
+
+impl EditorPluginStatusbar for ObjPreviewPlugin {}
+impl EditorPluginAi for ObjPreviewPlugin {}
+impl EditorPluginComponents for ObjPreviewPlugin {
+    fn component_definitions(&self) -> Vec<ComponentDefinition> {
+        Vec::new()
+    }
+}
+impl EditorPluginSubsystems for ObjPreviewPlugin {
+    fn subsystems(&self) -> Vec<Box<dyn Subsystem>> {
+        Vec::new()
+    }
+}
+
+export_plugin!(ObjPreviewPlugin);
+
+// ============================================================================
+// Scene
+// ============================================================================
+
+/// What the render callback draws for one viewport.
+struct PreviewScene {
+    mesh: MeshHandle,
+    bounds: ([f32; 3], [f32; 3]),
+    yaw_degrees: f32,
+}
+
+/// Scenes by viewport; one callback serves every open preview.
+fn scenes() -> &'static Mutex<HashMap<EmbeddedViewportId, PreviewScene>> {
+    static SCENES: OnceLock<Mutex<HashMap<EmbeddedViewportId, PreviewScene>>> = OnceLock::new();
+    SCENES.get_or_init(Default::default)
+}
+
+fn render_preview(api: &mut dyn ViewportRenderApi) {
+    let mut scenes = scenes().lock().unwrap();
+    let Some(scene) = scenes.get_mut(&api.viewport_id()) else {
+        return;
+    };
+    scene.yaw_degrees = (scene.yaw_degrees + TURN_SPEED * api.delta_time().as_secs_f32()) % 360.0;
+
+    let (min, max) = scene.bounds;
+    let center = [0, 1, 2].map(|a| (min[a] + max[a]) * 0.5);
+    let half_extents = [0, 1, 2].map(|a| (max[a] - min[a]) * 0.5);
+    let radius = half_extents
+        .iter()
+        .map(|h| h * h)
+        .sum::<f32>()
+        .sqrt()
+        .max(0.01);
+
+    api.set_camera(ViewportCamera {
+        far: radius * 20.0,
+        near: radius * 0.01,
+        ..ViewportCamera::orbit(center, radius * 2.5, scene.yaw_degrees.to_radians(), 0.35)
+    });
+    api.draw_debug(DebugPrimitive::Grid {
+        half_extent: radius * 2.0,
+        spacing: radius / 4.0,
+        color: [0.35, 0.35, 0.38, 1.0],
+    });
+    api.draw_mesh(scene.mesh, IDENTITY_TRANSFORM, [0.75, 0.78, 0.82, 1.0]);
+    api.draw_debug(DebugPrimitive::Box {
+        center,
+        half_extents,
+        color: [0.55, 0.76, 0.29, 1.0],
+    });
+    api.draw_debug(DebugPrimitive::Axes {
+        origin: [0.0; 3],
+        length: radius * 0.5,
+    });
+}
+
+// ============================================================================
+// Panel
+// ============================================================================
+
+pub struct ObjPreviewPanel {
+    focus_handle: FocusHandle,
+    file_path: PathBuf,
+    /// Dropped with the panel, which releases the viewport's GPU resources.
+    viewport: Option<EmbeddedViewportHandle>,
+    status: String,
+}
+
+impl ObjPreviewPanel {
+    fn new(file_path: PathBuf, _window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let (viewport, status) = match open_preview(&file_path) {
+            Ok((viewport, status)) => (Some(viewport), status),
+            Err(e) => (None, e.to_string()),
+        };
+        Self {
+            focus_handle: cx.focus_handle(),
+            file_path,
+            viewport,
+            status,
+        }
+    }
+}
+
+fn open_preview(path: &Path) -> Result<(EmbeddedViewportHandle, String), PluginError> {
+    let text = std::fs::read_to_string(path).map_err(|e| PluginError::FileLoadError {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+    let mesh = parse_obj(&text)?;
+    let bounds = mesh.bounds().unwrap_or(([0.0; 3], [0.0; 3]));
+    let status = format!(
+        "{} vertices, {} triangles",
+        mesh.positions.len(),
+        mesh.indices.len() / 3
+    );
+
+    let viewport = create_embedded_viewport(ViewportConfig::new(RENDER_CALLBACK))?;
+    let handle = viewport.upload_mesh(mesh)?;
+    scenes().lock().unwrap().insert(
+        viewport.id(),
+        PreviewScene {
+            mesh: handle,
+            bounds,
+            yaw_degrees: 0.0,
+        },
+    );
+    Ok((viewport, status))
+}
+
+impl Drop for ObjPreviewPanel {
+    fn drop(&mut self) {
+        if let Some(viewport) = &self.viewport {
+            scenes().lock().unwrap().remove(&viewport.id());
+        }
+    }
+}
+
+impl Render for ObjPreviewPanel {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let body = match &self.viewport {
+            Some(viewport) => viewport.element(window, cx),
+            None => div()
+                .size_full()
+                .flex()
+                .items_center()
+                .justify_center()
+                .text_color(cx.theme().muted_foreground)
+                .child("Preview unavailable")
+                .into_any_element(),
+        };
+
+        v_flex()
+            .size_full()
+            .bg(cx.theme().background)
+            .track_focus(&self.focus_handle)
+            .child(div().flex_1().relative().child(body))
+            .child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(self.status.clone()),
+            )
+    }
+}
+
+impl Focusable for ObjPreviewPanel {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for ObjPreviewPanel {}
+
+impl Panel for ObjPreviewPanel {
+    fn panel_name(&self) -> &'static str {
+        "obj_preview"
+    }
+
+    fn title(&self, _window: &Window, _cx: &App) -> AnyElement {
+        let name = self
+            .file_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "OBJ Preview".to_string());
+        div().child(name).into_any_element()
+    }
+}
+
+// ============================================================================
+// OBJ Parsing
+// ============================================================================
+
+/// Reads positions and faces; polygons are fan-triangulated. Normals are left
+/// to the host, which shades faces flat.
+fn parse_obj(text: &str) -> Result<MeshData, PluginError> {
+    let invalid = |line: usize, message: &str| PluginError::InvalidFormat {
+        expected: "Wavefront OBJ".to_string(),
+        message: format!("line {}: {}", line + 1, message),
+    };
+
+    let mut mesh = MeshData::default();
+    for (number, line) in text.lines().enumerate() {
+        let mut parts = line.split_whitespace();
+        match parts.next() {
+            Some("v") => {
+                let coords: Vec<f32> = parts.take(3).filter_map(|p| p.parse().ok()).collect();
+                let [x, y, z] = coords[..] else {
+                    return Err(invalid(number, "vertex needs three coordinates"));
+                };
+                mesh.positions.push([x, y, z]);
+            }
+            Some("f") => {
+                // Indices are 1-based; negative ones count back from the latest vertex
+                let corners = parts
+                    .map(|corner| {
+                        let index: i64 = corner
+                            .split('/')
+                            .next()
+                            .and_then(|i| i.parse().ok())
+                            .ok_or_else(|| invalid(number, "malformed face"))?;
+                        let resolved = if index < 0 {
+                            mesh.positions.len() as i64 + index
+                        } else {
+                            index - 1
+                        };
+                        u32::try_from(resolved).map_err(|_| invalid(number, "bad vertex index"))
+                    })
+                    .collect::<Result<Vec<u32>, _>>()?;
+                if corners.len() < 3 {
+                    return Err(invalid(number, "face needs at least three vertices"));
+                }
+                for i in 1..corners.len() - 1 {
+                    mesh.indices
+                        .extend([corners[0], corners[i], corners[i + 1]]);
+                }
+            }
+            _ => {}
+        }
+    }
+    mesh.validate()?;
+    Ok(mesh)
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// Command Palette Commands
+// ============================================================================
+
+/// A command a plugin adds to the command palette.
+///
+/// Returned from [`EditorPlugin::commands`](crate::plugin::EditorPlugin::commands)
+/// and run through
+/// [`EditorPlugin::execute_command`](crate::plugin::EditorPlugin::execute_command).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandDefinition {
+    /// Identifier passed to `execute_command`, unique within the plugin
+    /// (e.g., "format-blueprint")
+    pub id: String,
+
+    /// Name shown in the palette
+    pub display_name: String,
+
+    /// Keybinding shown next to the command (e.g., "Ctrl+Shift+F").
+    /// Only a hint: the plugin is responsible for binding it.
+    pub keybinding: Option<String>,
+
+    /// Category the palette groups the command under (e.g., "Blueprint")
+    pub category: String,
+}
+
+impl CommandDefinition {
+    /// Create a new command definition
+    pub fn new(
+        id: impl Into<String>,
+        display_name: impl Into<String>,
+        category: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            display_name: display_name.into(),
+            keybinding: None,
+            category: category.into(),
+        }
+    }
+
+    /// Add a keybinding hint to this command definition
+    pub fn with_keybinding(mut self, keybinding: impl Into<String>) -> Self {
+        self.keybinding = Some(keybinding.into());
+        self
+    }
+}
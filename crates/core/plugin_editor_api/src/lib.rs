@@ -33,18 +33,20 @@
 //! | Module | Contents |
 //! |--------|----------|
 //! | [`identifiers`] | `PluginId`, `FileTypeId`, `EditorId` |
-//! | [`version`] | `VersionInfo`, compile-time version hashing |
-//! | [`metadata`] | `PluginMetadata`, `EditorMetadata` |
+//! | [`version`] | `VersionInfo`, `PLUGIN_API_VERSION`, compatibility policy |
+//! | [`metadata`] | `PluginMetadata`, `PluginDependency`, `EditorMetadata` |
 //! | [`file_types`] | `FileTypeDefinition`, `FileStructure`, `PathTemplate` |
 //! | [`error`] | `PluginError` type |
 //! | [`statusbar`] | Statusbar button definitions |
 //! | [`actions`] | `OpenAsset` action |
+//! | [`commands`] | `CommandDefinition` for the command palette |
 //! | [`ai`] | `AiToolDefinition`, `FsContext` |
 //! | [`components`] | `ComponentDefinition`, `EditorPluginComponents` |
 //! | [`subsystems`] | `EditorPluginSubsystems`, `Subsystem` re-exports |
 //! | [`plugin`] | `EditorPlugin` trait, `export_plugin!` macro |
 //! | [`editor_element`] | `EditorHandle`, `EditorElement` — init vs render lifecycle |
 //! | [`helpers`] | `standalone_file_type()`, `folder_file_type()` |
+//! | [`viewport`] | `create_embedded_viewport`, `ViewportRenderApi`, `ViewportHost` |
 //!
 //! ## Creating a Plugin
 //!
@@ -80,6 +82,7 @@
 pub mod actions;
 pub mod ai;
 pub mod asset_payload;
+pub mod commands;
 pub mod components;
 pub mod editor_element;
 pub mod error;
@@ -91,6 +94,7 @@ pub mod plugin;
 pub mod statusbar;
 pub mod subsystems;
 pub mod version;
+pub mod viewport;
 
 // ── Re-exports for plugin convenience ────────────────────────────────────────
 //
@@ -103,6 +107,7 @@ pub mod version;
 pub use actions::*;
 pub use ai::*;
 pub use asset_payload::*;
+pub use commands::*;
 pub use components::*;
 pub use editor_element::*;
 pub use error::*;
@@ -114,6 +119,11 @@ pub use plugin::*;
 pub use statusbar::*;
 pub use subsystems::*;
 pub use version::*;
+pub use viewport::*;
+
+/// Re-export the shared UI scale so plugins can read the host's zoom level.
+/// The `export_plugin!` macro references `$crate::ui_scale`.
+pub use window_manager::ui_scale;
 
 /// Re-export GPUI's core types for plugin use.
 /// The `export_plugin!` macro references these via `$crate::Window`, `$crate::App`.
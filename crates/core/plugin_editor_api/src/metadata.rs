@@ -7,7 +7,7 @@ use crate::identifiers::{EditorId, FileTypeId, PluginId};
 // ============================================================================
 
 /// Metadata describing a plugin.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PluginMetadata {
     /// Unique plugin identifier (reverse domain notation)
     pub id: PluginId,
@@ -21,6 +21,24 @@ pub struct PluginMetadata {
     pub description: String,
 }
 
+/// Another plugin that must be loaded before the declaring one.
+///
+/// Returned from [`EditorPlugin::dependencies`](crate::plugin::EditorPlugin::dependencies).
+/// If it is missing or fails to load, the declaring plugin is not loaded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginDependency {
+    /// ID of the plugin depended on
+    pub id: PluginId,
+}
+
+impl PluginDependency {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: PluginId::new(id),
+        }
+    }
+}
+
 // ============================================================================
 // Editor Metadata
 // ============================================================================
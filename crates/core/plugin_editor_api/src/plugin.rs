@@ -1,8 +1,10 @@
 use std::sync::Arc;
 
+use crate::commands::CommandDefinition;
+use crate::error::PluginError;
 use crate::file_types::FileTypeDefinition;
 use crate::identifiers::{EditorId, FileTypeId};
-use crate::metadata::{EditorMetadata, PluginMetadata};
+use crate::metadata::{EditorMetadata, PluginDependency, PluginMetadata};
 use crate::version::VersionInfo;
 
 // ============================================================================
@@ -37,12 +39,92 @@ pub trait EditorPlugin: Send + Sync {
     /// Get all file types this plugin supports.
     fn file_types(&self) -> Vec<FileTypeDefinition>;
 
+    /// Content for a new file of one of this plugin's file types about to be
+    /// created at `target_path`, for content that depends on where it goes
+    /// (e.g. a class named after the file).
+    ///
+    /// Return `None` to use the file type's static
+    /// [`default_content`](FileTypeDefinition::default_content).
+    fn default_content_for(
+        &self,
+        file_type_id: &FileTypeId,
+        target_path: &std::path::Path,
+    ) -> Option<serde_json::Value> {
+        let _ = (file_type_id, target_path);
+        None
+    }
+
+    /// Custom file drawer icon for one of this plugin's file types: a
+    /// base64-encoded PNG or SVG of at most 256 KB, shown instead of the
+    /// file type's [`icon`](FileTypeDefinition::icon).
+    ///
+    /// Asked once per file type when the plugin is loaded. An icon that
+    /// can't be decoded or is too large is skipped with a warning.
+    fn file_type_icon(&self, file_type_id: &FileTypeId) -> Option<String> {
+        let _ = file_type_id;
+        None
+    }
+
     /// Get all editor types this plugin provides.
     fn editors(&self) -> Vec<EditorMetadata>;
 
+    /// Plugins that must be registered before this one, e.g. the plugin
+    /// providing a file type whose editor this one extends.
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        Vec::new()
+    }
+
     /// Called when the plugin is loaded.  Override to perform
     /// one-time initialisation.
     fn on_load(&mut self) {}
+
+    /// Called when this build of the plugin is retired, by a reload or by
+    /// disabling the plugin. Stop background threads and timers here.
+    ///
+    /// The library stays loaded and editors it created stay open until
+    /// closed, so this must not invalidate anything they use.
+    fn on_unload(&self) {}
+
+    /// Describe this plugin's settings, to get a settings file kept by the
+    /// plugin manager and a generated form in the settings window.
+    ///
+    /// The schema is an object with a `properties` map from setting key to
+    /// `{ "type", "default", "title", "description" }`. `type` is one of
+    /// `boolean`, `integer`, `number`, `string`, `array` or `object`;
+    /// numbers may add `minimum` / `maximum` and strings an `enum` of
+    /// allowed values.
+    fn settings_schema(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Called with the plugin's settings after it is loaded, and again
+    /// whenever they are changed. The settings are a JSON object holding
+    /// every key declared in [`settings_schema`](Self::settings_schema),
+    /// already validated against it.
+    ///
+    /// Takes `&self` because loaded plugins are shared; keep settings
+    /// behind a lock or other interior mutability.
+    fn on_settings_changed(&self, settings: &serde_json::Value) {
+        let _ = settings;
+    }
+
+    /// Commands this plugin adds to the command palette.
+    fn commands(&self) -> Vec<CommandDefinition> {
+        Vec::new()
+    }
+
+    /// Run one of the commands from [`commands`](Self::commands), by ID.
+    fn execute_command(
+        &self,
+        command_id: &str,
+        window: &mut gpui::Window,
+        cx: &mut gpui::App,
+    ) -> Result<(), PluginError> {
+        let _ = (window, cx);
+        Err(PluginError::Other {
+            message: format!("Unknown command: {}", command_id),
+        })
+    }
 }
 
 // ============================================================================
@@ -68,17 +150,81 @@ pub trait EditorPluginFull:
 // Plugin Constructor
 // ============================================================================
 
+/// Host state passed to a plugin's constructor.
+///
+/// The layout only ever grows at the end. `size` is the size of the struct the
+/// host built, so a plugin built against a newer API can tell which fields an
+/// older host filled in before reading them.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PluginGlobals {
+    /// `size_of::<PluginGlobals>()` in the host
+    pub size: usize,
+    /// The host's `ui::theme::Theme`, valid for the process lifetime
+    pub theme: *const std::ffi::c_void,
+}
+
+impl PluginGlobals {
+    pub fn new(theme: *const std::ffi::c_void) -> Self {
+        Self {
+            size: std::mem::size_of::<Self>(),
+            theme,
+        }
+    }
+
+    /// The host's theme pointer, or `None` if it is null or the host's struct
+    /// predates the field.
+    pub fn theme(&self) -> Option<*const std::ffi::c_void> {
+        let end =
+            std::mem::offset_of!(Self, theme) + std::mem::size_of::<*const std::ffi::c_void>();
+        (self.size >= end && !self.theme.is_null()).then_some(self.theme)
+    }
+}
+
 /// Type alias for the plugin constructor function.
 ///
-/// Plugins must export a function with this signature named `_plugin_create`.
+/// Plugins must export a function with this signature named
+/// `_plugin_create_with_globals`; [`export_plugin!`](crate::export_plugin)
+/// generates it. The constructor returns null instead of unwinding across the
+/// FFI boundary when `globals` is unusable.
 ///
 /// # Safety
 ///
-/// The returned reference has `'static` lifetime because the plugin is never unloaded.
-/// This is safe because the plugin code remains valid for the process lifetime.
+/// `globals` must point to a valid [`PluginGlobals`] for the duration of the
+/// call. The returned pointer is leaked and stays valid for the process
+/// lifetime because the plugin is never unloaded.
+pub type PluginCreateWithGlobals =
+    unsafe extern "C" fn(globals: *const PluginGlobals) -> *mut dyn EditorPluginFull;
+
+/// Constructor exported as `_plugin_create` by plugins built before
+/// [`PluginCreateWithGlobals`]. The host still loads them, with a warning.
+#[deprecated(note = "export `_plugin_create_with_globals` (`PluginCreateWithGlobals`) instead")]
 pub type PluginCreate =
     unsafe extern "C" fn(theme_ptr: *const std::ffi::c_void) -> &'static mut dyn EditorPluginFull;
 
+/// Type alias for the optional `_plugin_init_ui_scale` export.
+///
+/// The host passes the address of its [`UiScaleCell`](crate::ui_scale::UiScaleCell) so
+/// the plugin's copy of [`crate::ui_scale`] reads the live scale instead of its own.
+pub type PluginInitUiScale = unsafe extern "C" fn(cell: *const crate::ui_scale::UiScaleCell);
+
+/// Type alias for the optional `_plugin_init_viewport_host` export.
+///
+/// The host passes the address of its [`ViewportHostCell`](crate::viewport::ViewportHostCell)
+/// so [`create_embedded_viewport`](crate::viewport::create_embedded_viewport) inside the
+/// plugin reaches the engine's renderer.
+pub type PluginInitViewportHost =
+    unsafe extern "C" fn(cell: *const crate::viewport::ViewportHostCell);
+
+/// Type alias for the optional `_plugin_supported_extensions` export.
+///
+/// Returns a static, NUL-terminated list of the file extensions the plugin
+/// edits, each followed by `;` (e.g. `"obj;fbx;\0"`). The host reads it from
+/// libraries it has not loaded to suggest them for files no editor handles,
+/// so the function must not depend on any globals the host installs.
+/// Generated by `export_plugin!(MyPlugin, extensions = [...])`.
+pub type PluginSupportedExtensions = extern "C" fn() -> *const std::ffi::c_char;
+
 // ============================================================================
 // Plugin Declaration and Export Macro
 // ============================================================================
@@ -92,8 +238,8 @@ pub type PluginCreate =
 ///
 /// This macro generates `unsafe extern "C"` functions. The safety contract is:
 ///
-/// 1. **Plugin is never unloaded**: The returned `&'static mut dyn EditorPlugin` reference
-///    is valid forever because we never call dlclose/FreeLibrary.
+/// 1. **Plugin is never unloaded**: The leaked `*mut dyn EditorPluginFull` returned by
+///    `_plugin_create_with_globals` is valid forever because we never call dlclose/FreeLibrary.
 ///
 /// 2. **Theme pointer validity**: The engine must ensure the Theme pointer remains valid.
 ///    This is guaranteed by the engine keeping Theme in stable storage.
@@ -108,8 +254,25 @@ pub type PluginCreate =
 ///
 /// export_plugin!(MyPlugin);
 /// ```
+///
+/// Listing the extensions the plugin edits also exports
+/// `_plugin_supported_extensions` (see [`PluginSupportedExtensions`]), so the
+/// editor can point users at the plugin while it isn't loaded:
+///
+/// ```rust,ignore
+/// export_plugin!(MyPlugin, extensions = ["obj", "fbx"]);
+/// ```
 #[macro_export]
 macro_rules! export_plugin {
+    ($plugin_type:ty, extensions = [$($extension:literal),* $(,)?]) => {
+        $crate::export_plugin!($plugin_type);
+
+        /// File extensions this plugin edits, each followed by `;`.
+        #[no_mangle]
+        pub extern "C" fn _plugin_supported_extensions() -> *const std::ffi::c_char {
+            concat!($($extension, ";",)* "\0").as_ptr().cast()
+        }
+    };
     ($plugin_type:ty) => {
         // Static storage for synced Theme data from main app
         static SYNCED_THEME: std::sync::OnceLock<usize> = std::sync::OnceLock::new();
@@ -129,12 +292,52 @@ macro_rules! export_plugin {
             fn file_types(&self) -> Vec<$crate::file_types::FileTypeDefinition> {
                 $crate::plugin::EditorPlugin::file_types(&self.0)
             }
+            fn default_content_for(
+                &self,
+                file_type_id: &$crate::identifiers::FileTypeId,
+                target_path: &::std::path::Path,
+            ) -> Option<$crate::JsonValue> {
+                $crate::plugin::EditorPlugin::default_content_for(
+                    &self.0,
+                    file_type_id,
+                    target_path,
+                )
+            }
+            fn file_type_icon(
+                &self,
+                file_type_id: &$crate::identifiers::FileTypeId,
+            ) -> Option<String> {
+                $crate::plugin::EditorPlugin::file_type_icon(&self.0, file_type_id)
+            }
             fn editors(&self) -> Vec<$crate::metadata::EditorMetadata> {
                 $crate::plugin::EditorPlugin::editors(&self.0)
             }
+            fn dependencies(&self) -> Vec<$crate::metadata::PluginDependency> {
+                $crate::plugin::EditorPlugin::dependencies(&self.0)
+            }
             fn on_load(&mut self) {
                 $crate::plugin::EditorPlugin::on_load(&mut self.0)
             }
+            fn on_unload(&self) {
+                $crate::plugin::EditorPlugin::on_unload(&self.0)
+            }
+            fn settings_schema(&self) -> Option<$crate::JsonValue> {
+                $crate::plugin::EditorPlugin::settings_schema(&self.0)
+            }
+            fn on_settings_changed(&self, settings: &$crate::JsonValue) {
+                $crate::plugin::EditorPlugin::on_settings_changed(&self.0, settings)
+            }
+            fn commands(&self) -> Vec<$crate::commands::CommandDefinition> {
+                $crate::plugin::EditorPlugin::commands(&self.0)
+            }
+            fn execute_command(
+                &self,
+                command_id: &str,
+                window: &mut $crate::Window,
+                cx: &mut $crate::App,
+            ) -> std::result::Result<(), $crate::error::PluginError> {
+                $crate::plugin::EditorPlugin::execute_command(&self.0, command_id, window, cx)
+            }
         }
 
         impl $crate::editor_element::EditorPluginEditor for __PluginExport {
@@ -203,27 +406,36 @@ macro_rules! export_plugin {
 
         /// Create the plugin instance.
         ///
+        /// Returns null if `globals` carries no theme or the plugin was
+        /// already created, rather than panicking across the FFI boundary.
+        ///
         /// # Safety
         ///
-        /// This function returns a `&'static mut dyn EditorPluginFull` which is safe because:
-        /// 1. The plugin is never unloaded (PermanentLibrary prevents dlclose/FreeLibrary)
-        /// 2. The returned reference is leaked intentionally
-        /// 3. All plugin code remains valid for process lifetime
+        /// `globals` must be null or point to a valid `PluginGlobals` whose
+        /// theme pointer stays valid for the process lifetime. This is the
+        /// caller's (engine's) responsibility.
         ///
-        /// The theme_ptr must point to valid Theme data that remains stable for the process
-        /// lifetime. This is the caller's (engine's) responsibility.
+        /// The returned instance is leaked intentionally; it stays valid
+        /// because the plugin is never unloaded (PermanentLibrary prevents
+        /// dlclose/FreeLibrary).
+        // Host and plugin are both Rust and agree on the trait object layout
+        #[allow(improper_ctypes_definitions)]
         #[no_mangle]
-        pub unsafe extern "C" fn _plugin_create(
-            theme_ptr: *const std::ffi::c_void,
-        ) -> &'static mut dyn $crate::plugin::EditorPluginFull {
-            // Validate theme pointer
-            if theme_ptr.is_null() {
-                panic!("[Plugin] ERROR: Received null theme pointer from host!");
-            }
+        pub unsafe extern "C" fn _plugin_create_with_globals(
+            globals: *const $crate::plugin::PluginGlobals,
+        ) -> *mut dyn $crate::plugin::EditorPluginFull {
+            let null =
+                std::ptr::null_mut::<__PluginExport>() as *mut dyn $crate::plugin::EditorPluginFull;
+
+            let Some(theme_ptr) = globals.as_ref().and_then(|g| g.theme()) else {
+                tracing::error!("[Plugin] ERROR: Received no theme pointer from host!");
+                return null;
+            };
 
             // Store theme pointer (fail if already set)
             if SYNCED_THEME.set(theme_ptr as usize).is_err() {
-                panic!("[Plugin] ERROR: Theme pointer already initialized!");
+                tracing::error!("[Plugin] ERROR: Theme pointer already initialized!");
+                return null;
             }
 
             // Register our theme accessor with the ui crate
@@ -234,8 +446,8 @@ macro_rules! export_plugin {
             let wrapper = __PluginExport(plugin);
             let boxed: Box<dyn $crate::plugin::EditorPluginFull> = Box::new(wrapper);
 
-            // Leak the box to get a 'static reference
-            Box::leak(boxed)
+            // Leak the box; the host keeps it for the process lifetime
+            Box::into_raw(boxed)
         }
 
         /// Internal accessor for plugin theme (called by ui crate).
@@ -273,5 +485,27 @@ macro_rules! export_plugin {
                 SYNCED_THEME.set(theme_ptr as usize);
             }
         }
+
+        /// Point the plugin's UI scale reads at the host's shared cell.
+        #[no_mangle]
+        pub unsafe extern "C" fn _plugin_init_ui_scale(
+            cell: *const $crate::ui_scale::UiScaleCell,
+        ) {
+            $crate::ui_scale::install_shared(cell);
+        }
+
+        /// Point the plugin's embedded viewport calls at the host's renderer.
+        #[no_mangle]
+        pub unsafe extern "C" fn _plugin_init_viewport_host(
+            cell: *const $crate::viewport::ViewportHostCell,
+        ) {
+            $crate::viewport::install_shared_viewport_host(cell);
+        }
+
+        /// UI scale as seen from inside the plugin (used by host-side diagnostics and tests).
+        #[no_mangle]
+        pub extern "C" fn _plugin_ui_scale() -> f32 {
+            $crate::ui_scale::current()
+        }
     };
 }
@@ -1,44 +1,111 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 // ============================================================================
 // Version Information
 // ============================================================================
 
+/// Version of the `plugin_editor_api` surface plugins are compiled against.
+///
+/// Bump this whenever a change to this crate's public types or traits would
+/// make a plugin built against the previous surface misbehave: a changed trait
+/// method, a reordered `#[repr(C)]` struct, a new required export. Purely
+/// additive changes with defaults don't need a bump.
+pub const PLUGIN_API_VERSION: u32 = 1;
+
 /// Version information for compatibility checking across the DLL boundary.
 ///
-/// This struct ensures that plugins are loaded only if they were compiled with
-/// compatible versions of the engine and Rust compiler.
+/// This struct ensures that plugins are loaded only if they were compiled
+/// against a compatible engine and plugin API. See
+/// [`check_compatible`](Self::check_compatible) for the policy.
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct VersionInfo {
     /// Engine version (major, minor, patch)
     pub engine_version: (u32, u32, u32),
+    /// [`PLUGIN_API_VERSION`] at build time
+    pub api_version: u32,
     /// Rustc version hash (hash of semver part only)
     pub rustc_version_hash: u64,
 }
 
+/// The part of a [`VersionInfo`] that made a plugin incompatible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VersionComponent {
+    ApiVersion,
+    EngineMajor,
+    EngineMinor,
+    Rustc,
+}
+
+impl fmt::Display for VersionComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ApiVersion => write!(f, "plugin API version"),
+            Self::EngineMajor => write!(f, "engine major version"),
+            Self::EngineMinor => write!(f, "engine minor version"),
+            Self::Rustc => write!(f, "rustc version"),
+        }
+    }
+}
+
 impl VersionInfo {
     /// Get the current version info for this build
     pub const fn current() -> Self {
         Self {
             engine_version: parse_engine_version(),
+            api_version: PLUGIN_API_VERSION,
             rustc_version_hash: rustc_version_hash(),
         }
     }
 
-    /// Check if two versions are compatible
-    pub fn is_compatible(&self, other: &Self) -> bool {
-        // Engine major version must match
-        if self.engine_version.0 != other.engine_version.0 {
-            return false;
+    /// Check whether a plugin built with `plugin` can be loaded by an engine
+    /// built with `self`, reporting the first component that rules it out.
+    ///
+    /// The policy, in order:
+    ///
+    /// 1. The plugin API versions must be equal.
+    /// 2. The engine major versions must be equal.
+    /// 3. The plugin must not be built against a newer engine minor version
+    ///    than the engine's; it may use additions the engine lacks. Before
+    ///    1.0, minor versions are breaking, so they must be equal.
+    /// 4. With `strict_rustc`, the rustc versions must be equal. Rustc makes
+    ///    no ABI promises between versions, but in practice a toolchain bump
+    ///    rarely changes the layouts the plugin API exchanges, so this is
+    ///    left to builds that want the guarantee.
+    ///
+    /// Patch versions never matter.
+    pub fn check_compatible(
+        &self,
+        plugin: &Self,
+        strict_rustc: bool,
+    ) -> Result<(), VersionComponent> {
+        let (major, minor, _) = self.engine_version;
+        let (plugin_major, plugin_minor, _) = plugin.engine_version;
+        if self.api_version != plugin.api_version {
+            return Err(VersionComponent::ApiVersion);
         }
-
-        // Rustc version must match exactly (ABI not stable across versions)
-        if self.rustc_version_hash != other.rustc_version_hash {
-            return false;
+        if major != plugin_major {
+            return Err(VersionComponent::EngineMajor);
         }
+        let minor_compatible = if major == 0 {
+            plugin_minor == minor
+        } else {
+            plugin_minor <= minor
+        };
+        if !minor_compatible {
+            return Err(VersionComponent::EngineMinor);
+        }
+        if strict_rustc && self.rustc_version_hash != plugin.rustc_version_hash {
+            return Err(VersionComponent::Rustc);
+        }
+        Ok(())
+    }
 
-        true
+    /// Check if a plugin built with `plugin` is compatible with this engine,
+    /// without the strict rustc check.
+    pub fn is_compatible(&self, plugin: &Self) -> bool {
+        self.check_compatible(plugin, false).is_ok()
     }
 }
 
@@ -114,3 +181,69 @@ const fn parse_engine_version() -> (u32, u32, u32) {
 
     (major, minor, patch)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(engine_version: (u32, u32, u32), api_version: u32, rustc: u64) -> VersionInfo {
+        VersionInfo {
+            engine_version,
+            api_version,
+            rustc_version_hash: rustc,
+        }
+    }
+
+    #[test]
+    fn test_minor_and_patch_policy() {
+        let engine = version((1, 4, 2), 3, 7);
+        assert_eq!(
+            engine.check_compatible(&version((1, 4, 9), 3, 7), true),
+            Ok(())
+        );
+        assert_eq!(
+            engine.check_compatible(&version((1, 2, 0), 3, 7), true),
+            Ok(())
+        );
+        assert_eq!(
+            engine.check_compatible(&version((1, 5, 0), 3, 7), true),
+            Err(VersionComponent::EngineMinor)
+        );
+        assert_eq!(
+            engine.check_compatible(&version((2, 0, 0), 3, 7), true),
+            Err(VersionComponent::EngineMajor)
+        );
+
+        // Before 1.0 every minor release is breaking
+        let engine = version((0, 4, 2), 3, 7);
+        assert_eq!(
+            engine.check_compatible(&version((0, 4, 0), 3, 7), true),
+            Ok(())
+        );
+        assert_eq!(
+            engine.check_compatible(&version((0, 3, 0), 3, 7), true),
+            Err(VersionComponent::EngineMinor)
+        );
+    }
+
+    #[test]
+    fn test_api_version_checked_first() {
+        let engine = version((1, 4, 2), 3, 7);
+        assert_eq!(
+            engine.check_compatible(&version((2, 0, 0), 2, 8), true),
+            Err(VersionComponent::ApiVersion)
+        );
+        assert!(!engine.is_compatible(&version((1, 4, 2), 4, 7)));
+    }
+
+    #[test]
+    fn test_rustc_only_checked_when_strict() {
+        let engine = version((1, 4, 2), 3, 7);
+        let nightly_bump = version((1, 4, 2), 3, 8);
+        assert!(engine.is_compatible(&nightly_bump));
+        assert_eq!(
+            engine.check_compatible(&nightly_bump, true),
+            Err(VersionComponent::Rustc)
+        );
+    }
+}
@@ -0,0 +1,1195 @@
+//! # Embedded Viewports
+//!
+//! Lets a plugin editor show a GPU-rendered 3D view inside its panel without
+//! touching the renderer directly.
+//!
+//! The host owns everything on the GPU side: it allocates the render target,
+//! uploads meshes, composites the result into the panel and decides when a
+//! frame is drawn. The plugin only sees handles and a restricted
+//! [`ViewportRenderApi`], so no `wgpu` types ever cross the DLL boundary.
+//!
+//! ## Flow
+//!
+//! 1. [`register_render_callback`] once, under a [`RenderCallbackId`].
+//! 2. [`create_embedded_viewport`] with a [`ViewportConfig`] naming that callback.
+//! 3. Upload geometry through [`EmbeddedViewportHandle::upload_mesh`] (retained
+//!    until released or the viewport is destroyed).
+//! 4. Put [`EmbeddedViewportHandle::element`] in the editor's element tree.
+//! 5. The host invokes the callback each frame the viewport is visible and
+//!    active, subject to the viewport frame-rate cap.
+//! 6. Dropping the handle (e.g. when the editor panel closes) releases the
+//!    viewport's GPU resources immediately.
+//!
+//! ```rust,ignore
+//! register_render_callback(RenderCallbackId::new("mesh-preview"), Arc::new(|api| {
+//!     api.set_camera(ViewportCamera::orbit([0.0; 3], 3.0, 0.4, 0.3));
+//!     api.draw_mesh(MESH.get(), IDENTITY_TRANSFORM, [0.8, 0.8, 0.8, 1.0]);
+//!     api.draw_debug(DebugPrimitive::Grid { half_extent: 5.0, spacing: 1.0, color: GRID });
+//! }));
+//!
+//! let viewport = create_embedded_viewport(ViewportConfig::new("mesh-preview"))?;
+//! let mesh = viewport.upload_mesh(mesh_data)?;
+//! // in render_frame:
+//! div().size_full().child(viewport.element(ctx.window, ctx.cx))
+//! ```
+//!
+//! ## Host side
+//!
+//! The host implements [`ViewportHost`] and shares it with each plugin through
+//! the `_plugin_init_viewport_host` export generated by
+//! [`export_plugin!`](crate::export_plugin). [`ViewportRegistry`] holds the
+//! bookkeeping every host needs (handle allocation, resize debouncing,
+//! visibility/pacing gating, idle eviction and release ordering) so it can be
+//! tested without a GPU.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use gpui::{AnyElement, App, Window};
+use serde::{Deserialize, Serialize};
+
+use crate::error::PluginError;
+
+// ============================================================================
+// Identifiers
+// ============================================================================
+
+/// Identifies one embedded viewport. Allocated by the host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EmbeddedViewportId(u64);
+
+impl EmbeddedViewportId {
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for EmbeddedViewportId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "viewport#{}", self.0)
+    }
+}
+
+/// Handle to a mesh uploaded into a viewport's retained scene.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MeshHandle(u64);
+
+impl MeshHandle {
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Name under which a plugin registers its render callback.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RenderCallbackId(String);
+
+impl RenderCallbackId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for RenderCallbackId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+// ============================================================================
+// Configuration and Scene Data
+// ============================================================================
+
+/// Column-major 4x4 identity matrix, for meshes drawn at the origin.
+pub const IDENTITY_TRANSFORM: [[f32; 4]; 4] = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+/// Parameters for [`create_embedded_viewport`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewportConfig {
+    /// Render target size in physical pixels until the first layout arrives.
+    pub initial_size: (u32, u32),
+    /// Linear RGBA clear colour.
+    pub clear_color: [f32; 4],
+    /// Callback invoked to draw each frame.
+    pub render_callback_id: RenderCallbackId,
+}
+
+impl ViewportConfig {
+    pub fn new(render_callback_id: impl Into<String>) -> Self {
+        Self {
+            initial_size: (640, 480),
+            clear_color: [0.05, 0.05, 0.06, 1.0],
+            render_callback_id: RenderCallbackId::new(render_callback_id),
+        }
+    }
+
+    pub fn with_initial_size(mut self, width: u32, height: u32) -> Self {
+        self.initial_size = (width, height);
+        self
+    }
+
+    pub fn with_clear_color(mut self, clear_color: [f32; 4]) -> Self {
+        self.clear_color = clear_color;
+        self
+    }
+}
+
+/// Indexed triangle mesh uploaded into a viewport's retained scene.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeshData {
+    pub positions: Vec<[f32; 3]>,
+    /// Per-vertex normals. Left empty, the host uses flat face normals.
+    pub normals: Vec<[f32; 3]>,
+    pub indices: Vec<u32>,
+}
+
+impl MeshData {
+    /// Checks the mesh is drawable, so errors surface at upload time rather than on the GPU.
+    pub fn validate(&self) -> Result<(), PluginError> {
+        let invalid = |message: String| PluginError::InvalidFormat {
+            expected: "triangle mesh".to_string(),
+            message,
+        };
+        if self.positions.is_empty() || self.indices.is_empty() {
+            return Err(invalid("mesh has no geometry".to_string()));
+        }
+        if !self.indices.len().is_multiple_of(3) {
+            return Err(invalid(format!(
+                "index count {} is not a multiple of 3",
+                self.indices.len()
+            )));
+        }
+        if !self.normals.is_empty() && self.normals.len() != self.positions.len() {
+            return Err(invalid(format!(
+                "{} normals for {} positions",
+                self.normals.len(),
+                self.positions.len()
+            )));
+        }
+        if let Some(index) = self
+            .indices
+            .iter()
+            .find(|&&i| i as usize >= self.positions.len())
+        {
+            return Err(invalid(format!("index {} is out of range", index)));
+        }
+        Ok(())
+    }
+
+    /// Axis-aligned bounds as `(min, max)`, useful for framing the camera.
+    pub fn bounds(&self) -> Option<([f32; 3], [f32; 3])> {
+        let first = *self.positions.first()?;
+        Some(
+            self.positions
+                .iter()
+                .fold((first, first), |(mut min, mut max), p| {
+                    for axis in 0..3 {
+                        min[axis] = min[axis].min(p[axis]);
+                        max[axis] = max[axis].max(p[axis]);
+                    }
+                    (min, max)
+                }),
+        )
+    }
+}
+
+/// Perspective camera for a viewport.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewportCamera {
+    pub eye: [f32; 3],
+    pub target: [f32; 3],
+    pub up: [f32; 3],
+    pub vertical_fov_degrees: f32,
+    pub near: f32,
+    pub far: f32,
+}
+
+impl Default for ViewportCamera {
+    fn default() -> Self {
+        Self {
+            eye: [3.0, 2.0, 3.0],
+            target: [0.0, 0.0, 0.0],
+            up: [0.0, 1.0, 0.0],
+            vertical_fov_degrees: 60.0,
+            near: 0.05,
+            far: 1000.0,
+        }
+    }
+}
+
+impl ViewportCamera {
+    /// Camera orbiting `target` at `distance`, with yaw/pitch in radians.
+    pub fn orbit(target: [f32; 3], distance: f32, yaw: f32, pitch: f32) -> Self {
+        let (sin_yaw, cos_yaw) = yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = pitch.sin_cos();
+        Self {
+            eye: [
+                target[0] + distance * cos_pitch * sin_yaw,
+                target[1] + distance * sin_pitch,
+                target[2] + distance * cos_pitch * cos_yaw,
+            ],
+            target,
+            ..Self::default()
+        }
+    }
+}
+
+/// A single coloured line segment.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DebugLine {
+    pub from: [f32; 3],
+    pub to: [f32; 3],
+    pub color: [f32; 4],
+}
+
+/// Debug shapes the host expands into line segments.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DebugPrimitive {
+    Line(DebugLine),
+    /// Wireframe axis-aligned box.
+    Box {
+        center: [f32; 3],
+        half_extents: [f32; 3],
+        color: [f32; 4],
+    },
+    /// Three great circles, one per axis plane.
+    Sphere {
+        center: [f32; 3],
+        radius: f32,
+        color: [f32; 4],
+    },
+    /// Red/green/blue X/Y/Z axes.
+    Axes {
+        origin: [f32; 3],
+        length: f32,
+    },
+    /// Square grid on the XZ plane, centred on the origin.
+    Grid {
+        half_extent: f32,
+        spacing: f32,
+        color: [f32; 4],
+    },
+}
+
+const SPHERE_SEGMENTS: usize = 24;
+const MAX_GRID_LINES_PER_AXIS: usize = 201;
+
+impl DebugPrimitive {
+    /// Expands the primitive into line segments.
+    pub fn lines(&self) -> Vec<DebugLine> {
+        match *self {
+            Self::Line(line) => vec![line],
+            Self::Box {
+                center,
+                half_extents,
+                color,
+            } => {
+                let corner = |i: usize| {
+                    let sign = |bit: usize| if i & bit == 0 { -1.0 } else { 1.0 };
+                    [
+                        center[0] + sign(1) * half_extents[0],
+                        center[1] + sign(2) * half_extents[1],
+                        center[2] + sign(4) * half_extents[2],
+                    ]
+                };
+                // Corners differing in exactly one bit share an edge
+                let mut lines = Vec::with_capacity(12);
+                for a in 0..8 {
+                    for bit in [1, 2, 4] {
+                        if a & bit == 0 {
+                            lines.push(DebugLine {
+                                from: corner(a),
+                                to: corner(a | bit),
+                                color,
+                            });
+                        }
+                    }
+                }
+                lines
+            }
+            Self::Sphere {
+                center,
+                radius,
+                color,
+            } => {
+                let mut lines = Vec::with_capacity(SPHERE_SEGMENTS * 3);
+                for (u, v) in [(0, 1), (1, 2), (0, 2)] {
+                    let point = |step: usize| {
+                        let angle = step as f32 / SPHERE_SEGMENTS as f32 * std::f32::consts::TAU;
+                        let mut p = center;
+                        p[u] += radius * angle.cos();
+                        p[v] += radius * angle.sin();
+                        p
+                    };
+                    for step in 0..SPHERE_SEGMENTS {
+                        lines.push(DebugLine {
+                            from: point(step),
+                            to: point(step + 1),
+                            color,
+                        });
+                    }
+                }
+                lines
+            }
+            Self::Axes { origin, length } => (0..3)
+                .map(|axis| {
+                    let mut to = origin;
+                    to[axis] += length;
+                    let mut color = [0.0, 0.0, 0.0, 1.0];
+                    color[axis] = 1.0;
+                    DebugLine {
+                        from: origin,
+                        to,
+                        color,
+                    }
+                })
+                .collect(),
+            Self::Grid {
+                half_extent,
+                spacing,
+                color,
+            } => {
+                if spacing <= 0.0 || half_extent <= 0.0 {
+                    return Vec::new();
+                }
+                let steps =
+                    ((half_extent / spacing).floor() as usize).min(MAX_GRID_LINES_PER_AXIS / 2);
+                let mut lines = Vec::with_capacity((steps * 2 + 1) * 2);
+                for i in 0..=steps * 2 {
+                    let offset = (i as f32 - steps as f32) * spacing;
+                    lines.push(DebugLine {
+                        from: [offset, 0.0, -half_extent],
+                        to: [offset, 0.0, half_extent],
+                        color,
+                    });
+                    lines.push(DebugLine {
+                        from: [-half_extent, 0.0, offset],
+                        to: [half_extent, 0.0, offset],
+                        color,
+                    });
+                }
+                lines
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Render API
+// ============================================================================
+
+/// Drawing interface passed to a viewport's render callback.
+///
+/// Calls record into the current frame; the host draws everything after the
+/// callback returns. Meshes must belong to the viewport being drawn — unknown
+/// handles are skipped.
+pub trait ViewportRenderApi {
+    /// Which viewport is being drawn, for plugins sharing one callback across several.
+    fn viewport_id(&self) -> EmbeddedViewportId;
+
+    /// Render target size in physical pixels.
+    fn size(&self) -> (u32, u32);
+
+    /// Time since this viewport's previous frame.
+    fn delta_time(&self) -> Duration;
+
+    fn set_camera(&mut self, camera: ViewportCamera);
+
+    /// Draws a retained mesh with a column-major model transform and a flat colour.
+    fn draw_mesh(&mut self, mesh: MeshHandle, transform: [[f32; 4]; 4], color: [f32; 4]);
+
+    fn draw_lines(&mut self, lines: &[DebugLine]);
+
+    fn draw_debug(&mut self, primitive: DebugPrimitive) {
+        self.draw_lines(&primitive.lines());
+    }
+}
+
+/// A plugin's per-frame draw function.
+pub type ViewportRenderCallback = Arc<dyn Fn(&mut dyn ViewportRenderApi) + Send + Sync>;
+
+// ============================================================================
+// Host Services
+// ============================================================================
+
+/// Services the host provides for embedded viewports.
+///
+/// Implemented by the engine; plugins use it through [`create_embedded_viewport`]
+/// and [`EmbeddedViewportHandle`] rather than calling it directly.
+pub trait ViewportHost: Send + Sync {
+    fn register_render_callback(&self, id: RenderCallbackId, callback: ViewportRenderCallback);
+
+    fn unregister_render_callback(&self, id: &RenderCallbackId);
+
+    fn create_viewport(&self, config: ViewportConfig) -> Result<EmbeddedViewportId, PluginError>;
+
+    /// Releases the viewport's callback binding, meshes and render target, in that order.
+    fn destroy_viewport(&self, id: EmbeddedViewportId);
+
+    fn upload_mesh(
+        &self,
+        id: EmbeddedViewportId,
+        mesh: MeshData,
+    ) -> Result<MeshHandle, PluginError>;
+
+    fn release_mesh(&self, id: EmbeddedViewportId, mesh: MeshHandle);
+
+    /// Inactive viewports stay composited but their callback isn't invoked.
+    fn set_viewport_active(&self, id: EmbeddedViewportId, active: bool);
+
+    /// Element that displays the viewport and reports its size back to the host.
+    fn viewport_element(
+        &self,
+        id: EmbeddedViewportId,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> AnyElement;
+}
+
+/// Holds the host's [`ViewportHost`]; plugins read it through a raw pointer.
+pub struct ViewportHostCell {
+    host: OnceLock<Arc<dyn ViewportHost>>,
+}
+
+impl ViewportHostCell {
+    pub const fn new() -> Self {
+        Self {
+            host: OnceLock::new(),
+        }
+    }
+
+    pub fn get(&self) -> Option<Arc<dyn ViewportHost>> {
+        self.host.get().cloned()
+    }
+}
+
+impl Default for ViewportHostCell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static HOST_CELL: ViewportHostCell = ViewportHostCell::new();
+static SHARED_CELL: AtomicPtr<ViewportHostCell> = AtomicPtr::new(std::ptr::null_mut());
+
+/// Installs the host implementation. Called once by the engine at startup.
+pub fn set_viewport_host(host: Arc<dyn ViewportHost>) {
+    if HOST_CELL.host.set(host).is_err() {
+        tracing::warn!("Viewport host already installed");
+    }
+}
+
+/// The host's cell; pass this to plugins via `_plugin_init_viewport_host`.
+pub fn viewport_host_cell() -> *const ViewportHostCell {
+    &HOST_CELL
+}
+
+/// Points this copy of the module at the host's cell. Called from the plugin side.
+///
+/// # Safety
+///
+/// `cell` must be null or point to a [`ViewportHostCell`] that lives for the rest
+/// of the process (the host's static cell satisfies this).
+pub unsafe fn install_shared_viewport_host(cell: *const ViewportHostCell) {
+    SHARED_CELL.store(cell as *mut ViewportHostCell, Ordering::Release);
+}
+
+/// The viewport host as seen from this module (host or plugin).
+pub fn viewport_host() -> Option<Arc<dyn ViewportHost>> {
+    let shared = SHARED_CELL.load(Ordering::Acquire);
+    if shared.is_null() {
+        HOST_CELL.get()
+    } else {
+        // SAFETY: install_shared_viewport_host's contract guarantees the pointer outlives the process.
+        unsafe { &*shared }.get()
+    }
+}
+
+fn require_host() -> Result<Arc<dyn ViewportHost>, PluginError> {
+    viewport_host().ok_or_else(|| PluginError::Other {
+        message: "This editor host does not provide embedded viewports".to_string(),
+    })
+}
+
+/// Registers the draw function viewports refer to by `id`.
+pub fn register_render_callback(
+    id: RenderCallbackId,
+    callback: ViewportRenderCallback,
+) -> Result<(), PluginError> {
+    require_host()?.register_render_callback(id, callback);
+    Ok(())
+}
+
+/// Asks the host for a new viewport. Keep the handle for as long as the viewport is shown.
+pub fn create_embedded_viewport(
+    config: ViewportConfig,
+) -> Result<EmbeddedViewportHandle, PluginError> {
+    let host = require_host()?;
+    EmbeddedViewportHandle::create(host, config)
+}
+
+// ============================================================================
+// Viewport Handle
+// ============================================================================
+
+/// Owning handle to an embedded viewport.
+///
+/// Dropping it destroys the viewport and frees its GPU resources, so storing
+/// it on the editor ties the viewport's lifetime to the panel's.
+pub struct EmbeddedViewportHandle {
+    id: EmbeddedViewportId,
+    host: Arc<dyn ViewportHost>,
+}
+
+impl EmbeddedViewportHandle {
+    /// Creates a viewport on a specific host. Plugins normally use [`create_embedded_viewport`].
+    pub fn create(
+        host: Arc<dyn ViewportHost>,
+        config: ViewportConfig,
+    ) -> Result<Self, PluginError> {
+        let id = host.create_viewport(config)?;
+        Ok(Self { id, host })
+    }
+
+    pub fn id(&self) -> EmbeddedViewportId {
+        self.id
+    }
+
+    pub fn upload_mesh(&self, mesh: MeshData) -> Result<MeshHandle, PluginError> {
+        mesh.validate()?;
+        self.host.upload_mesh(self.id, mesh)
+    }
+
+    pub fn release_mesh(&self, mesh: MeshHandle) {
+        self.host.release_mesh(self.id, mesh);
+    }
+
+    pub fn set_active(&self, active: bool) {
+        self.host.set_viewport_active(self.id, active);
+    }
+
+    /// The element to place in the editor's layout. It fills its parent.
+    pub fn element(&self, window: &mut Window, cx: &mut App) -> AnyElement {
+        self.host.viewport_element(self.id, window, cx)
+    }
+}
+
+impl Drop for EmbeddedViewportHandle {
+    fn drop(&mut self) {
+        self.host.destroy_viewport(self.id);
+    }
+}
+
+impl fmt::Debug for EmbeddedViewportHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmbeddedViewportHandle")
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+// ============================================================================
+// Host Bookkeeping
+// ============================================================================
+
+/// Default delay before a layout size change resizes the render target.
+pub const DEFAULT_RESIZE_DEBOUNCE: Duration = Duration::from_millis(120);
+
+/// What the host should do for a viewport this frame, from [`ViewportRegistry::begin_paint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ViewportFramePlan {
+    /// (Re)allocate the render target at this size — first paint or after eviction.
+    pub allocate_target: Option<(u32, u32)>,
+    /// Resize the existing render target; the debounce period has elapsed.
+    pub resize_target: Option<(u32, u32)>,
+    /// Invoke this callback and draw a new frame.
+    pub render: Option<RenderCallbackId>,
+    /// Time since the viewport last drew, for [`ViewportRenderApi::delta_time`].
+    pub delta_time: Duration,
+}
+
+/// One step of tearing down a viewport, in the order the host must perform them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReleaseStep {
+    /// Stop invoking the callback so nothing draws into freed resources.
+    DetachCallback(RenderCallbackId),
+    /// Free a mesh's GPU buffers. Newest first.
+    Mesh(MeshHandle),
+    /// Free the render target, last since draws reference it.
+    RenderTarget,
+}
+
+#[derive(Debug)]
+struct ViewportRecord {
+    config: ViewportConfig,
+    size: (u32, u32),
+    pending_resize: Option<((u32, u32), Instant)>,
+    active: bool,
+    target_resident: bool,
+    last_painted: Option<Instant>,
+    last_rendered: Option<Instant>,
+    meshes: Vec<MeshHandle>,
+}
+
+/// Host-side bookkeeping for embedded viewports, independent of the GPU.
+#[derive(Debug)]
+pub struct ViewportRegistry {
+    viewports: HashMap<EmbeddedViewportId, ViewportRecord>,
+    next_viewport: u64,
+    next_mesh: u64,
+    resize_debounce: Duration,
+    min_frame_interval: Option<Duration>,
+}
+
+impl Default for ViewportRegistry {
+    fn default() -> Self {
+        Self::new(DEFAULT_RESIZE_DEBOUNCE)
+    }
+}
+
+impl ViewportRegistry {
+    pub fn new(resize_debounce: Duration) -> Self {
+        Self {
+            viewports: HashMap::new(),
+            next_viewport: 1,
+            next_mesh: 1,
+            resize_debounce,
+            min_frame_interval: None,
+        }
+    }
+
+    /// Caps how often callbacks run, from the viewport FPS setting (0 = unlimited).
+    pub fn set_max_fps(&mut self, max_fps: u32) {
+        self.min_frame_interval =
+            (max_fps > 0).then(|| Duration::from_secs_f64(1.0 / max_fps as f64));
+    }
+
+    pub fn create(&mut self, config: ViewportConfig) -> EmbeddedViewportId {
+        let id = EmbeddedViewportId(self.next_viewport);
+        self.next_viewport += 1;
+        let size = clamp_size(config.initial_size);
+        self.viewports.insert(
+            id,
+            ViewportRecord {
+                config,
+                size,
+                pending_resize: None,
+                active: true,
+                target_resident: false,
+                last_painted: None,
+                last_rendered: None,
+                meshes: Vec::new(),
+            },
+        );
+        id
+    }
+
+    pub fn contains(&self, id: EmbeddedViewportId) -> bool {
+        self.viewports.contains_key(&id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.viewports.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.viewports.is_empty()
+    }
+
+    pub fn ids(&self) -> Vec<EmbeddedViewportId> {
+        let mut ids: Vec<_> = self.viewports.keys().copied().collect();
+        ids.sort();
+        ids
+    }
+
+    pub fn config(&self, id: EmbeddedViewportId) -> Option<&ViewportConfig> {
+        self.viewports.get(&id).map(|v| &v.config)
+    }
+
+    /// Current render target size (not the pending one).
+    pub fn size(&self, id: EmbeddedViewportId) -> Option<(u32, u32)> {
+        self.viewports.get(&id).map(|v| v.size)
+    }
+
+    pub fn is_resident(&self, id: EmbeddedViewportId) -> bool {
+        self.viewports.get(&id).is_some_and(|v| v.target_resident)
+    }
+
+    pub fn set_active(&mut self, id: EmbeddedViewportId, active: bool) {
+        if let Some(viewport) = self.viewports.get_mut(&id) {
+            viewport.active = active;
+        }
+    }
+
+    /// Allocates a mesh handle owned by `id`.
+    pub fn add_mesh(&mut self, id: EmbeddedViewportId) -> Option<MeshHandle> {
+        let viewport = self.viewports.get_mut(&id)?;
+        let mesh = MeshHandle(self.next_mesh);
+        self.next_mesh += 1;
+        viewport.meshes.push(mesh);
+        Some(mesh)
+    }
+
+    /// Forgets a mesh. Returns false if `id` doesn't own it.
+    pub fn remove_mesh(&mut self, id: EmbeddedViewportId, mesh: MeshHandle) -> bool {
+        let Some(viewport) = self.viewports.get_mut(&id) else {
+            return false;
+        };
+        let before = viewport.meshes.len();
+        viewport.meshes.retain(|m| *m != mesh);
+        viewport.meshes.len() != before
+    }
+
+    pub fn owns_mesh(&self, id: EmbeddedViewportId, mesh: MeshHandle) -> bool {
+        self.viewports
+            .get(&id)
+            .is_some_and(|v| v.meshes.contains(&mesh))
+    }
+
+    /// Called when the viewport's element paints with `observed_size` physical pixels.
+    ///
+    /// Painting is what makes a viewport visible; a zero-sized layout counts as
+    /// hidden. Size changes only reach the render target once they have held
+    /// steady for the debounce period, so dragging a splitter doesn't reallocate
+    /// every frame.
+    pub fn begin_paint(
+        &mut self,
+        id: EmbeddedViewportId,
+        observed_size: (u32, u32),
+        now: Instant,
+    ) -> Option<ViewportFramePlan> {
+        let debounce = self.resize_debounce;
+        let min_frame_interval = self.min_frame_interval;
+        let viewport = self.viewports.get_mut(&id)?;
+        let visible = observed_size.0 > 0 && observed_size.1 > 0;
+        viewport.last_painted = Some(now);
+
+        let mut plan = ViewportFramePlan {
+            allocate_target: None,
+            resize_target: None,
+            render: None,
+            delta_time: viewport
+                .last_rendered
+                .map(|last| now.saturating_duration_since(last))
+                .unwrap_or_default(),
+        };
+
+        if visible {
+            match viewport.pending_resize {
+                _ if observed_size == viewport.size => viewport.pending_resize = None,
+                Some((pending, _)) if pending == observed_size => {}
+                _ => viewport.pending_resize = Some((observed_size, now)),
+            }
+            if let Some((pending, since)) = viewport.pending_resize {
+                if now.saturating_duration_since(since) >= debounce {
+                    viewport.size = pending;
+                    viewport.pending_resize = None;
+                    if viewport.target_resident {
+                        plan.resize_target = Some(pending);
+                    }
+                }
+            }
+        }
+
+        if !viewport.target_resident {
+            viewport.target_resident = true;
+            plan.allocate_target = Some(viewport.size);
+        }
+
+        let paced = match (viewport.last_rendered, min_frame_interval) {
+            (Some(last), Some(interval)) => now.saturating_duration_since(last) >= interval,
+            _ => true,
+        };
+        if visible && viewport.active && paced {
+            viewport.last_rendered = Some(now);
+            plan.render = Some(viewport.config.render_callback_id.clone());
+        }
+        Some(plan)
+    }
+
+    /// Releases render targets of viewports that haven't painted for `idle_after`.
+    ///
+    /// Meshes stay retained; the target is reallocated on the next paint.
+    pub fn evict_idle(&mut self, now: Instant, idle_after: Duration) -> Vec<EmbeddedViewportId> {
+        let mut evicted: Vec<_> = self
+            .viewports
+            .iter_mut()
+            .filter(|(_, v)| {
+                v.target_resident
+                    && v.last_painted
+                        .is_none_or(|last| now.saturating_duration_since(last) >= idle_after)
+            })
+            .map(|(id, v)| {
+                v.target_resident = false;
+                v.pending_resize = None;
+                *id
+            })
+            .collect();
+        evicted.sort();
+        evicted
+    }
+
+    /// Removes a viewport, returning what to release in order.
+    pub fn destroy(&mut self, id: EmbeddedViewportId) -> Option<Vec<ReleaseStep>> {
+        let viewport = self.viewports.remove(&id)?;
+        let mut steps = vec![ReleaseStep::DetachCallback(
+            viewport.config.render_callback_id,
+        )];
+        steps.extend(viewport.meshes.into_iter().rev().map(ReleaseStep::Mesh));
+        if viewport.target_resident {
+            steps.push(ReleaseStep::RenderTarget);
+        }
+        Some(steps)
+    }
+}
+
+fn clamp_size((width, height): (u32, u32)) -> (u32, u32) {
+    (width.max(1), height.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    const DEBOUNCE: Duration = Duration::from_millis(100);
+
+    fn registry() -> (ViewportRegistry, EmbeddedViewportId, Instant) {
+        let mut registry = ViewportRegistry::new(DEBOUNCE);
+        let id = registry.create(ViewportConfig::new("preview").with_initial_size(320, 240));
+        (registry, id, Instant::now())
+    }
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_create_and_destroy_bookkeeping() {
+        let mut registry = ViewportRegistry::default();
+        let a = registry.create(ViewportConfig::new("a"));
+        let b = registry.create(ViewportConfig::new("b"));
+        assert_ne!(a, b);
+        assert_eq!(registry.ids(), vec![a, b]);
+
+        let mesh = registry.add_mesh(a).unwrap();
+        assert!(registry.owns_mesh(a, mesh));
+        assert!(!registry.owns_mesh(b, mesh));
+        assert!(!registry.remove_mesh(b, mesh));
+
+        assert!(registry.destroy(a).is_some());
+        assert!(!registry.contains(a));
+        assert!(registry.destroy(a).is_none(), "double destroy is a no-op");
+        assert!(registry.add_mesh(a).is_none());
+        assert_eq!(registry.len(), 1);
+
+        // Ids aren't reused, so a stale handle can't reach a newer viewport
+        let c = registry.create(ViewportConfig::new("c"));
+        assert!(c != a && c != b);
+    }
+
+    #[test]
+    fn test_first_paint_allocates_target() {
+        let (mut registry, id, t0) = registry();
+        assert!(!registry.is_resident(id));
+
+        let plan = registry.begin_paint(id, (320, 240), t0).unwrap();
+        assert_eq!(plan.allocate_target, Some((320, 240)));
+        assert_eq!(plan.render, Some(RenderCallbackId::new("preview")));
+        assert!(registry.is_resident(id));
+
+        let plan = registry.begin_paint(id, (320, 240), t0 + ms(16)).unwrap();
+        assert_eq!(plan.allocate_target, None);
+        assert_eq!(plan.delta_time, ms(16));
+    }
+
+    #[test]
+    fn test_resize_is_debounced() {
+        let (mut registry, id, t0) = registry();
+        registry.begin_paint(id, (320, 240), t0);
+
+        // A drag: the size changes every frame, and the target stays put
+        for (i, width) in [400, 480, 560, 640].into_iter().enumerate() {
+            let t = t0 + ms(20 * (i as u64 + 1));
+            let plan = registry.begin_paint(id, (width, 240), t).unwrap();
+            assert_eq!(plan.resize_target, None);
+            assert_eq!(registry.size(id), Some((320, 240)));
+        }
+
+        // Settled at 640 since t0+80ms: not yet due at +150, due at +180
+        let plan = registry.begin_paint(id, (640, 240), t0 + ms(150)).unwrap();
+        assert_eq!(plan.resize_target, None);
+        let plan = registry.begin_paint(id, (640, 240), t0 + ms(180)).unwrap();
+        assert_eq!(plan.resize_target, Some((640, 240)));
+        assert_eq!(registry.size(id), Some((640, 240)));
+
+        let plan = registry.begin_paint(id, (640, 240), t0 + ms(400)).unwrap();
+        assert_eq!(plan.resize_target, None);
+    }
+
+    #[test]
+    fn test_resize_back_to_current_size_cancels() {
+        let (mut registry, id, t0) = registry();
+        registry.begin_paint(id, (320, 240), t0);
+        registry.begin_paint(id, (500, 240), t0 + ms(10));
+        registry.begin_paint(id, (320, 240), t0 + ms(20));
+        let plan = registry.begin_paint(id, (320, 240), t0 + ms(500)).unwrap();
+        assert_eq!(plan.resize_target, None);
+    }
+
+    #[test]
+    fn test_callback_gated_by_visibility_and_activity() {
+        let (mut registry, id, t0) = registry();
+
+        let hidden = registry.begin_paint(id, (0, 0), t0).unwrap();
+        assert_eq!(hidden.render, None);
+
+        registry.set_active(id, false);
+        let inactive = registry.begin_paint(id, (320, 240), t0 + ms(16)).unwrap();
+        assert_eq!(inactive.render, None);
+
+        registry.set_active(id, true);
+        let visible = registry.begin_paint(id, (320, 240), t0 + ms(32)).unwrap();
+        assert!(visible.render.is_some());
+
+        assert!(registry
+            .begin_paint(EmbeddedViewportId(999), (1, 1), t0)
+            .is_none());
+    }
+
+    #[test]
+    fn test_callback_respects_frame_cap() {
+        let (mut registry, id, t0) = registry();
+        registry.set_max_fps(30);
+
+        assert!(registry
+            .begin_paint(id, (320, 240), t0)
+            .unwrap()
+            .render
+            .is_some());
+        assert!(registry
+            .begin_paint(id, (320, 240), t0 + ms(16))
+            .unwrap()
+            .render
+            .is_none());
+        let plan = registry.begin_paint(id, (320, 240), t0 + ms(34)).unwrap();
+        assert!(plan.render.is_some());
+        assert_eq!(plan.delta_time, ms(34));
+
+        registry.set_max_fps(0);
+        assert!(registry
+            .begin_paint(id, (320, 240), t0 + ms(35))
+            .unwrap()
+            .render
+            .is_some());
+    }
+
+    #[test]
+    fn test_idle_viewports_are_evicted_and_reallocated() {
+        let (mut registry, id, t0) = registry();
+        let other = registry.create(ViewportConfig::new("other"));
+        let mesh = registry.add_mesh(id).unwrap();
+        registry.begin_paint(id, (320, 240), t0);
+        registry.begin_paint(other, (320, 240), t0 + ms(900));
+
+        assert_eq!(registry.evict_idle(t0 + ms(1000), ms(500)), vec![id]);
+        assert!(!registry.is_resident(id));
+        assert!(registry.is_resident(other));
+        assert!(registry.owns_mesh(id, mesh), "meshes survive eviction");
+
+        let plan = registry.begin_paint(id, (320, 240), t0 + ms(1100)).unwrap();
+        assert_eq!(plan.allocate_target, Some((320, 240)));
+    }
+
+    #[test]
+    fn test_destroy_release_order() {
+        let (mut registry, id, t0) = registry();
+        let first = registry.add_mesh(id).unwrap();
+        let second = registry.add_mesh(id).unwrap();
+        let third = registry.add_mesh(id).unwrap();
+        registry.remove_mesh(id, second);
+        registry.begin_paint(id, (320, 240), t0);
+
+        assert_eq!(
+            registry.destroy(id).unwrap(),
+            vec![
+                ReleaseStep::DetachCallback(RenderCallbackId::new("preview")),
+                ReleaseStep::Mesh(third),
+                ReleaseStep::Mesh(first),
+                ReleaseStep::RenderTarget,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_destroy_never_painted_skips_target() {
+        let (mut registry, id, _) = registry();
+        assert_eq!(
+            registry.destroy(id).unwrap(),
+            vec![ReleaseStep::DetachCallback(RenderCallbackId::new(
+                "preview"
+            ))]
+        );
+    }
+
+    #[test]
+    fn test_mesh_validation() {
+        let triangle = MeshData {
+            positions: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+            normals: Vec::new(),
+            indices: vec![0, 1, 2],
+        };
+        assert!(triangle.validate().is_ok());
+        assert_eq!(triangle.bounds(), Some(([0.0, 0.0, 0.0], [1.0, 1.0, 0.0])));
+
+        let mut bad = triangle.clone();
+        bad.indices = vec![0, 1, 3];
+        assert!(bad.validate().is_err());
+        bad.indices = vec![0, 1];
+        assert!(bad.validate().is_err());
+        let mut bad = triangle;
+        bad.normals = vec![[0.0, 0.0, 1.0]];
+        assert!(bad.validate().is_err());
+        assert!(MeshData::default().validate().is_err());
+    }
+
+    #[test]
+    fn test_debug_primitive_expansion() {
+        let white = [1.0; 4];
+        let cube = DebugPrimitive::Box {
+            center: [0.0; 3],
+            half_extents: [1.0; 3],
+            color: white,
+        };
+        let edges = cube.lines();
+        assert_eq!(edges.len(), 12);
+        for edge in &edges {
+            let differing = (0..3).filter(|&a| edge.from[a] != edge.to[a]).count();
+            assert_eq!(differing, 1, "box edges are axis-aligned");
+        }
+
+        let axes = DebugPrimitive::Axes {
+            origin: [0.0; 3],
+            length: 2.0,
+        }
+        .lines();
+        assert_eq!(axes[1].to, [0.0, 2.0, 0.0]);
+        assert_eq!(axes[1].color, [0.0, 1.0, 0.0, 1.0]);
+
+        let sphere = DebugPrimitive::Sphere {
+            center: [0.0; 3],
+            radius: 1.0,
+            color: white,
+        };
+        assert_eq!(sphere.lines().len(), SPHERE_SEGMENTS * 3);
+
+        let grid = DebugPrimitive::Grid {
+            half_extent: 2.0,
+            spacing: 1.0,
+            color: white,
+        };
+        assert_eq!(grid.lines().len(), 10);
+        let degenerate = DebugPrimitive::Grid {
+            half_extent: 2.0,
+            spacing: 0.0,
+            color: white,
+        };
+        assert!(degenerate.lines().is_empty());
+    }
+
+    /// Host stand-in that records calls, for handle lifecycle tests.
+    #[derive(Default)]
+    struct RecordingHost {
+        registry: Mutex<ViewportRegistry>,
+        released: Mutex<Vec<ReleaseStep>>,
+    }
+
+    impl ViewportHost for RecordingHost {
+        fn register_render_callback(&self, _: RenderCallbackId, _: ViewportRenderCallback) {}
+
+        fn unregister_render_callback(&self, _: &RenderCallbackId) {}
+
+        fn create_viewport(
+            &self,
+            config: ViewportConfig,
+        ) -> Result<EmbeddedViewportId, PluginError> {
+            Ok(self.registry.lock().unwrap().create(config))
+        }
+
+        fn destroy_viewport(&self, id: EmbeddedViewportId) {
+            if let Some(steps) = self.registry.lock().unwrap().destroy(id) {
+                self.released.lock().unwrap().extend(steps);
+            }
+        }
+
+        fn upload_mesh(
+            &self,
+            id: EmbeddedViewportId,
+            _: MeshData,
+        ) -> Result<MeshHandle, PluginError> {
+            self.registry
+                .lock()
+                .unwrap()
+                .add_mesh(id)
+                .ok_or_else(|| PluginError::Other {
+                    message: "unknown viewport".to_string(),
+                })
+        }
+
+        fn release_mesh(&self, id: EmbeddedViewportId, mesh: MeshHandle) {
+            self.registry.lock().unwrap().remove_mesh(id, mesh);
+        }
+
+        fn set_viewport_active(&self, id: EmbeddedViewportId, active: bool) {
+            self.registry.lock().unwrap().set_active(id, active);
+        }
+
+        fn viewport_element(
+            &self,
+            _: EmbeddedViewportId,
+            _: &mut Window,
+            _: &mut App,
+        ) -> AnyElement {
+            unreachable!("not rendered in tests")
+        }
+    }
+
+    #[test]
+    fn test_dropping_handle_releases_viewport() {
+        let host = Arc::new(RecordingHost::default());
+        let handle =
+            EmbeddedViewportHandle::create(host.clone(), ViewportConfig::new("preview")).unwrap();
+        let id = handle.id();
+        let mesh = handle
+            .upload_mesh(MeshData {
+                positions: vec![[0.0; 3]; 3],
+                normals: Vec::new(),
+                indices: vec![0, 1, 2],
+            })
+            .unwrap();
+        assert!(handle.upload_mesh(MeshData::default()).is_err());
+        assert!(host.registry.lock().unwrap().owns_mesh(id, mesh));
+
+        drop(handle);
+        assert!(!host.registry.lock().unwrap().contains(id));
+        assert_eq!(
+            *host.released.lock().unwrap(),
+            vec![
+                ReleaseStep::DetachCallback(RenderCallbackId::new("preview")),
+                ReleaseStep::Mesh(mesh),
+            ]
+        );
+    }
+}
@@ -9,7 +9,7 @@
 use plugin_editor_api::editor_element::{EditorFactoryRegistry, EditorPluginEditor};
 use plugin_editor_api::identifiers::EditorId;
 use plugin_editor_api::plugin::EditorPlugin;
-use plugin_editor_api::version::VersionInfo;
+use plugin_editor_api::version::{VersionInfo, PLUGIN_API_VERSION};
 use plugin_editor_api::PluginMetadata;
 
 /// A minimal test plugin that implements the full EditorPlugin + EditorPluginEditor
@@ -163,6 +163,7 @@ fn version_info_abi() {
     // VersionInfo is #[repr(C)] — verify it crosses the FFI boundary correctly
     let v = VersionInfo::current();
     assert_eq!(v.engine_version.0, 0);
+    assert_eq!(v.api_version, PLUGIN_API_VERSION);
     // rustc_version_hash should be non-zero
     assert!(
         v.rustc_version_hash != 0,
@@ -173,3 +174,23 @@ fn version_info_abi() {
         v.engine_version.0, v.engine_version.1, v.engine_version.2, v.rustc_version_hash
     );
 }
+
+#[test]
+fn plugin_globals_abi() {
+    use plugin_editor_api::plugin::PluginGlobals;
+
+    let theme = 1u8;
+    let theme_ptr = &theme as *const u8 as *const std::ffi::c_void;
+    let globals = PluginGlobals::new(theme_ptr);
+    assert_eq!(globals.size, std::mem::size_of::<PluginGlobals>());
+    assert_eq!(globals.theme(), Some(theme_ptr));
+
+    // A host whose struct ends before the theme field
+    let truncated = PluginGlobals {
+        size: std::mem::size_of::<usize>(),
+        ..globals
+    };
+    assert_eq!(truncated.theme(), None);
+
+    assert_eq!(PluginGlobals::new(std::ptr::null()).theme(), None);
+}
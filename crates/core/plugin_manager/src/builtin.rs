@@ -15,15 +15,38 @@ use std::sync::Arc;
 use gpui::{App, Window};
 use ui::dock::PanelView;
 
+/// Whether an editor may modify the file it opens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EditorOpenMode {
+    #[default]
+    Editable,
+    /// The user can't edit in the current session (e.g. a Reviewer in a
+    /// multiuser session). Editors should disable saving and mutation.
+    ReadOnly,
+}
+
 /// Context provided to editors during creation, containing engine-level information.
 pub struct EditorContext {
     /// The current project root path, if any.
     pub project_root: Option<PathBuf>,
+    pub open_mode: EditorOpenMode,
 }
 
 impl EditorContext {
     pub fn new(project_root: Option<PathBuf>) -> Self {
-        Self { project_root }
+        Self {
+            project_root,
+            open_mode: EditorOpenMode::default(),
+        }
+    }
+
+    pub fn with_open_mode(mut self, open_mode: EditorOpenMode) -> Self {
+        self.open_mode = open_mode;
+        self
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.open_mode == EditorOpenMode::ReadOnly
     }
 }
 
@@ -0,0 +1,158 @@
+//! Per-project default editors.
+//!
+//! When more than one editor can open a file type, the editor the user picked
+//! as the default is stored in `<project>/.pulsar/default_editors.json`, a JSON
+//! object mapping file type IDs to editor IDs. Without a default (or when the
+//! default's plugin isn't loaded), opening such a file fails with
+//! [`PluginManagerError::MultipleEditorsAvailable`] so the UI can ask.
+
+use plugin_editor_api::{EditorId, FileTypeId};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::PluginManagerError;
+
+const DIR: &str = ".pulsar";
+const FILE: &str = "default_editors.json";
+
+fn store_path(project_root: &Path) -> PathBuf {
+    project_root.join(DIR).join(FILE)
+}
+
+/// Default editor by file type.
+#[derive(Debug, Default)]
+pub(crate) struct DefaultEditors {
+    editors: HashMap<FileTypeId, EditorId>,
+}
+
+impl DefaultEditors {
+    /// Read the project's defaults. A missing or unparseable file gives no
+    /// defaults rather than failing.
+    pub(crate) fn load(project_root: &Path) -> Self {
+        let path = store_path(project_root);
+        let editors = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid default editors in {:?}: {}", path, e);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+        Self { editors }
+    }
+
+    /// Make `editor_id` the default for `file_type_id`, saving it under
+    /// `project_root` if there is one.
+    ///
+    /// The default applies for the rest of the session even if saving fails.
+    pub(crate) fn set(
+        &mut self,
+        project_root: Option<&Path>,
+        file_type_id: FileTypeId,
+        editor_id: EditorId,
+    ) -> Result<(), PluginManagerError> {
+        self.editors.insert(file_type_id, editor_id);
+        match project_root {
+            Some(root) => self.save(root),
+            None => Ok(()),
+        }
+    }
+
+    fn save(&self, project_root: &Path) -> Result<(), PluginManagerError> {
+        let path = store_path(project_root);
+        let write = || -> std::io::Result<()> {
+            std::fs::create_dir_all(project_root.join(DIR))?;
+            let bytes = serde_json::to_vec_pretty(&self.editors)?;
+            std::fs::write(&path, bytes)
+        };
+        write().map_err(|e| PluginManagerError::FileCreationError {
+            path: path.clone(),
+            message: e.to_string(),
+        })
+    }
+
+    /// Pick which of `candidates` opens files of `file_type_id`.
+    pub(crate) fn choose(
+        &self,
+        file_type_id: &FileTypeId,
+        candidates: Vec<EditorId>,
+    ) -> Result<EditorId, PluginManagerError> {
+        match candidates.as_slice() {
+            [] => Err(PluginManagerError::NoEditorForFileType {
+                file_type_id: file_type_id.clone(),
+            }),
+            [only] => Ok(only.clone()),
+            _ => match self.editors.get(file_type_id) {
+                Some(default) if candidates.contains(default) => Ok(default.clone()),
+                _ => Err(PluginManagerError::MultipleEditorsAvailable {
+                    file_type_id: file_type_id.clone(),
+                    editors: candidates,
+                }),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editors(ids: &[&str]) -> Vec<EditorId> {
+        ids.iter().map(|id| EditorId::new(*id)).collect()
+    }
+
+    #[test]
+    fn test_choose_editor() {
+        let rust = FileTypeId::new("rust-script");
+        let mut defaults = DefaultEditors::default();
+
+        assert!(matches!(
+            defaults.choose(&rust, Vec::new()),
+            Err(PluginManagerError::NoEditorForFileType { .. })
+        ));
+        assert_eq!(
+            defaults.choose(&rust, editors(&["code"])).unwrap(),
+            EditorId::new("code")
+        );
+        assert!(matches!(
+            defaults.choose(&rust, editors(&["code", "graph"])),
+            Err(PluginManagerError::MultipleEditorsAvailable { editors: ref candidates, .. })
+                if *candidates == editors(&["code", "graph"])
+        ));
+
+        defaults
+            .set(None, rust.clone(), EditorId::new("graph"))
+            .unwrap();
+        assert_eq!(
+            defaults.choose(&rust, editors(&["code", "graph"])).unwrap(),
+            EditorId::new("graph")
+        );
+        // A default whose plugin isn't loaded doesn't count
+        assert!(matches!(
+            defaults.choose(&rust, editors(&["code", "other"])),
+            Err(PluginManagerError::MultipleEditorsAvailable { .. })
+        ));
+    }
+
+    #[test]
+    fn test_defaults_are_saved_per_project() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut defaults = DefaultEditors::load(dir.path());
+        defaults
+            .set(
+                Some(dir.path()),
+                FileTypeId::new("rust-script"),
+                EditorId::new("graph"),
+            )
+            .unwrap();
+
+        let reloaded = DefaultEditors::load(dir.path());
+        assert_eq!(
+            reloaded.editors.get(&FileTypeId::new("rust-script")),
+            Some(&EditorId::new("graph"))
+        );
+
+        std::fs::write(store_path(dir.path()), "not json").unwrap();
+        assert!(DefaultEditors::load(dir.path()).editors.is_empty());
+    }
+}
@@ -0,0 +1,789 @@
+//! Host implementation of the embedded viewport API.
+//!
+//! Each viewport gets its own GPUI wgpu surface as render target, composited
+//! into the plugin's panel through the element returned by
+//! [`ViewportHost::viewport_element`]. Scheduling decisions (resize debouncing,
+//! visibility and frame-cap gating, idle eviction, release order) come from
+//! [`ViewportRegistry`]; this module only turns them into GPU work.
+//!
+//! Meshes are kept on the CPU as well as the GPU so an evicted viewport can be
+//! rebuilt when it becomes visible again.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use once_cell::sync::OnceCell;
+use parking_lot::Mutex;
+use plugin_editor_api::*;
+
+/// Matches the default `max_viewport_fps` engine setting.
+const DEFAULT_MAX_FPS: u32 = 60;
+
+/// Render targets of viewports that haven't painted for this long are released.
+const IDLE_EVICTION: Duration = Duration::from_secs(30);
+
+const SURFACE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Bgra8UnormSrgb;
+const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+/// Uniform stride; `min_uniform_buffer_offset_alignment` is at most 256.
+const DRAW_UNIFORM_STRIDE: u64 = 256;
+
+static SERVICE: OnceCell<Arc<EmbeddedViewportService>> = OnceCell::new();
+
+/// The engine's [`ViewportHost`], installed with [`set_viewport_host`] at startup.
+pub struct EmbeddedViewportService {
+    state: Mutex<ServiceState>,
+}
+
+#[derive(Default)]
+struct ServiceState {
+    registry: ViewportRegistry,
+    callbacks: HashMap<RenderCallbackId, ViewportRenderCallback>,
+    viewports: HashMap<EmbeddedViewportId, Arc<Mutex<ViewportGpu>>>,
+    meshes: HashMap<MeshHandle, Arc<MeshData>>,
+}
+
+/// GPU-side state of one viewport. Shared with its element so painting
+/// doesn't hold the service lock.
+struct ViewportGpu {
+    clear_color: [f32; 4],
+    surface: Option<WgpuSurfaceHandle>,
+    renderer: Option<ViewportRenderer>,
+    destroyed: bool,
+}
+
+impl EmbeddedViewportService {
+    pub fn new(max_fps: u32) -> Self {
+        let mut registry = ViewportRegistry::default();
+        registry.set_max_fps(max_fps);
+        Self {
+            state: Mutex::new(ServiceState {
+                registry,
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Applies the viewport FPS cap to embedded viewports (0 = unlimited).
+    pub fn set_max_fps(&self, max_fps: u32) {
+        self.state.lock().registry.set_max_fps(max_fps);
+    }
+
+    /// Number of live viewports, for diagnostics.
+    pub fn viewport_count(&self) -> usize {
+        self.state.lock().registry.len()
+    }
+
+    /// Releases render targets of viewports that haven't been shown recently.
+    pub fn evict_idle(&self, now: Instant) {
+        let mut state = self.state.lock();
+        for id in state.registry.evict_idle(now, IDLE_EVICTION) {
+            if let Some(gpu) = state.viewports.get(&id) {
+                let mut gpu = gpu.lock();
+                gpu.renderer = None;
+                gpu.surface = None;
+                tracing::debug!("Evicted render target of idle {}", id);
+            }
+        }
+    }
+
+    /// Frame setup for a viewport whose element is painting at `size` physical pixels.
+    fn prepare_frame(
+        &self,
+        id: EmbeddedViewportId,
+        size: (u32, u32),
+        window: &mut Window,
+    ) -> Option<PreparedFrame> {
+        let now = Instant::now();
+        self.evict_idle(now);
+
+        let mut state = self.state.lock();
+        let plan = state.registry.begin_paint(id, size, now)?;
+        let gpu = state.viewports.get(&id)?.clone();
+        let callback = plan
+            .render
+            .as_ref()
+            .and_then(|callback_id| state.callbacks.get(callback_id).cloned());
+        let meshes: HashMap<MeshHandle, Arc<MeshData>> = state
+            .meshes
+            .iter()
+            .filter(|(mesh, _)| state.registry.owns_mesh(id, **mesh))
+            .map(|(mesh, data)| (*mesh, data.clone()))
+            .collect();
+        let target_size = state.registry.size(id)?;
+        drop(state);
+
+        if let Some((width, height)) = plan.allocate_target.or(plan.resize_target) {
+            let mut gpu = gpu.lock();
+            gpu.surface = window.create_wgpu_surface(width, height, SURFACE_FORMAT);
+            if gpu.surface.is_none() {
+                tracing::warn!("Failed to allocate render target for {}", id);
+            }
+        }
+
+        Some(PreparedFrame {
+            id,
+            gpu,
+            callback,
+            meshes,
+            target_size,
+            delta_time: plan.delta_time,
+        })
+    }
+}
+
+impl ViewportHost for EmbeddedViewportService {
+    fn register_render_callback(&self, id: RenderCallbackId, callback: ViewportRenderCallback) {
+        self.state.lock().callbacks.insert(id, callback);
+    }
+
+    fn unregister_render_callback(&self, id: &RenderCallbackId) {
+        self.state.lock().callbacks.remove(id);
+    }
+
+    fn create_viewport(&self, config: ViewportConfig) -> Result<EmbeddedViewportId, PluginError> {
+        let mut state = self.state.lock();
+        if !state.callbacks.contains_key(&config.render_callback_id) {
+            tracing::warn!(
+                "Embedded viewport created before render callback '{}' was registered",
+                config.render_callback_id
+            );
+        }
+        let clear_color = config.clear_color;
+        let id = state.registry.create(config);
+        state.viewports.insert(
+            id,
+            Arc::new(Mutex::new(ViewportGpu {
+                clear_color,
+                surface: None,
+                renderer: None,
+                destroyed: false,
+            })),
+        );
+        Ok(id)
+    }
+
+    fn destroy_viewport(&self, id: EmbeddedViewportId) {
+        let mut state = self.state.lock();
+        let Some(steps) = state.registry.destroy(id) else {
+            return;
+        };
+        let Some(gpu) = state.viewports.remove(&id) else {
+            return;
+        };
+        let mut gpu = gpu.lock();
+        gpu.destroyed = true;
+        for step in steps {
+            match step {
+                // The callback stays registered for other viewports; this one
+                // is marked destroyed so its element stops invoking it.
+                ReleaseStep::DetachCallback(_) => {}
+                ReleaseStep::Mesh(mesh) => {
+                    state.meshes.remove(&mesh);
+                    if let Some(renderer) = gpu.renderer.as_mut() {
+                        renderer.meshes.remove(&mesh);
+                    }
+                }
+                ReleaseStep::RenderTarget => {
+                    gpu.renderer = None;
+                    gpu.surface = None;
+                }
+            }
+        }
+        tracing::debug!("Destroyed embedded {}", id);
+    }
+
+    fn upload_mesh(
+        &self,
+        id: EmbeddedViewportId,
+        mesh: MeshData,
+    ) -> Result<MeshHandle, PluginError> {
+        mesh.validate()?;
+        let mut state = self.state.lock();
+        let handle = state
+            .registry
+            .add_mesh(id)
+            .ok_or_else(|| PluginError::Other {
+                message: format!("Unknown embedded {}", id),
+            })?;
+        state.meshes.insert(handle, Arc::new(mesh));
+        Ok(handle)
+    }
+
+    fn release_mesh(&self, id: EmbeddedViewportId, mesh: MeshHandle) {
+        let mut state = self.state.lock();
+        if !state.registry.remove_mesh(id, mesh) {
+            return;
+        }
+        state.meshes.remove(&mesh);
+        if let Some(gpu) = state.viewports.get(&id) {
+            if let Some(renderer) = gpu.lock().renderer.as_mut() {
+                renderer.meshes.remove(&mesh);
+            }
+        }
+    }
+
+    fn set_viewport_active(&self, id: EmbeddedViewportId, active: bool) {
+        self.state.lock().registry.set_active(id, active);
+    }
+
+    fn viewport_element(
+        &self,
+        id: EmbeddedViewportId,
+        _window: &mut Window,
+        _cx: &mut App,
+    ) -> AnyElement {
+        let surface = self
+            .state
+            .lock()
+            .viewports
+            .get(&id)
+            .and_then(|gpu| gpu.lock().surface.clone());
+
+        div()
+            .relative()
+            .size_full()
+            .child(
+                canvas(
+                    move |bounds, window, _cx| {
+                        let scale = window.scale_factor();
+                        let size = (
+                            (f32::from(bounds.size.width) * scale).round() as u32,
+                            (f32::from(bounds.size.height) * scale).round() as u32,
+                        );
+                        service().prepare_frame(id, size, window)
+                    },
+                    move |_bounds, frame, window, _cx| {
+                        if let Some(frame) = frame {
+                            frame.render();
+                            window.request_animation_frame();
+                        }
+                    },
+                )
+                .absolute()
+                .inset_0()
+                .size_full(),
+            )
+            .when_some(surface, |this, surface| {
+                this.child(wgpu_surface(surface).absolute().inset_0())
+            })
+            .into_any_element()
+    }
+}
+
+/// The engine's viewport service, installed as the [`ViewportHost`] on first use.
+///
+/// Plugins reach it through `_plugin_init_viewport_host`, which the plugin
+/// manager calls at load time.
+pub fn service() -> &'static Arc<EmbeddedViewportService> {
+    SERVICE.get_or_init(|| {
+        let service = Arc::new(EmbeddedViewportService::new(DEFAULT_MAX_FPS));
+        set_viewport_host(service.clone());
+        service
+    })
+}
+
+// ============================================================================
+// Frame Rendering
+// ============================================================================
+
+struct PreparedFrame {
+    id: EmbeddedViewportId,
+    gpu: Arc<Mutex<ViewportGpu>>,
+    callback: Option<ViewportRenderCallback>,
+    meshes: HashMap<MeshHandle, Arc<MeshData>>,
+    target_size: (u32, u32),
+    delta_time: Duration,
+}
+
+impl PreparedFrame {
+    fn render(self) {
+        let Some(callback) = self.callback else {
+            return;
+        };
+
+        // Run plugin code without holding any host lock, so the callback may
+        // upload or release meshes itself.
+        let mut recorder = FrameRecorder {
+            id: self.id,
+            size: self.target_size,
+            delta_time: self.delta_time,
+            camera: ViewportCamera::default(),
+            meshes: &self.meshes,
+            mesh_draws: Vec::new(),
+            lines: Vec::new(),
+        };
+        callback(&mut recorder);
+        let FrameRecorder {
+            camera,
+            mesh_draws,
+            lines,
+            ..
+        } = recorder;
+
+        let mut gpu = self.gpu.lock();
+        if gpu.destroyed {
+            return;
+        }
+        let Some(surface) = gpu.surface.clone() else {
+            return;
+        };
+        if surface.is_resize_pending() {
+            return;
+        }
+        let Some((view, (width, height))) = surface.back_view_with_size() else {
+            return;
+        };
+        let clear_color = gpu.clear_color;
+        let renderer = gpu
+            .renderer
+            .get_or_insert_with(|| ViewportRenderer::new(surface.device(), surface.format()));
+        renderer.render(
+            surface.device(),
+            surface.queue(),
+            &view,
+            (width, height),
+            clear_color,
+            &camera,
+            &mesh_draws,
+            &self.meshes,
+            &lines,
+        );
+        drop(view);
+        surface.swap_buffers();
+    }
+}
+
+struct MeshDraw {
+    mesh: MeshHandle,
+    transform: [[f32; 4]; 4],
+    color: [f32; 4],
+}
+
+/// [`ViewportRenderApi`] implementation that records one frame's draws.
+struct FrameRecorder<'a> {
+    id: EmbeddedViewportId,
+    size: (u32, u32),
+    delta_time: Duration,
+    camera: ViewportCamera,
+    meshes: &'a HashMap<MeshHandle, Arc<MeshData>>,
+    mesh_draws: Vec<MeshDraw>,
+    lines: Vec<DebugLine>,
+}
+
+impl ViewportRenderApi for FrameRecorder<'_> {
+    fn viewport_id(&self) -> EmbeddedViewportId {
+        self.id
+    }
+
+    fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    fn delta_time(&self) -> Duration {
+        self.delta_time
+    }
+
+    fn set_camera(&mut self, camera: ViewportCamera) {
+        self.camera = camera;
+    }
+
+    fn draw_mesh(&mut self, mesh: MeshHandle, transform: [[f32; 4]; 4], color: [f32; 4]) {
+        if !self.meshes.contains_key(&mesh) {
+            tracing::trace!("Skipping mesh {:?} not owned by {}", mesh, self.id);
+            return;
+        }
+        self.mesh_draws.push(MeshDraw {
+            mesh,
+            transform,
+            color,
+        });
+    }
+
+    fn draw_lines(&mut self, lines: &[DebugLine]) {
+        self.lines.extend_from_slice(lines);
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct DrawUniforms {
+    view_proj: [[f32; 4]; 4],
+    model: [[f32; 4]; 4],
+    color: [f32; 4],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct MeshVertex {
+    position: [f32; 3],
+    normal: [f32; 3],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct LineVertex {
+    position: [f32; 3],
+    color: [f32; 4],
+}
+
+struct GpuMesh {
+    vertices: wgpu::Buffer,
+    indices: wgpu::Buffer,
+    index_count: u32,
+}
+
+struct ViewportRenderer {
+    mesh_pipeline: wgpu::RenderPipeline,
+    line_pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    uniforms: wgpu::Buffer,
+    uniform_capacity: u64,
+    line_vertices: wgpu::Buffer,
+    line_capacity: u64,
+    depth: Option<(wgpu::TextureView, (u32, u32))>,
+    meshes: HashMap<MeshHandle, GpuMesh>,
+}
+
+impl ViewportRenderer {
+    fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("embedded_viewport"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/embedded_viewport.wgsl").into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("embedded_viewport_bgl"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: wgpu::BufferSize::new(
+                        std::mem::size_of::<DrawUniforms>() as u64
+                    ),
+                },
+                count: None,
+            }],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("embedded_viewport_layout"),
+            bind_group_layouts: &[Some(&bind_group_layout)],
+            immediate_size: 0,
+        });
+
+        let pipeline = |label: &str,
+                        vs: &str,
+                        fs: &str,
+                        buffer: wgpu::VertexBufferLayout,
+                        topology: wgpu::PrimitiveTopology| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some(vs),
+                    buffers: &[Some(buffer)],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some(fs),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology,
+                    ..Default::default()
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: DEPTH_FORMAT,
+                    depth_write_enabled: Some(true),
+                    depth_compare: Some(wgpu::CompareFunction::LessEqual),
+                    stencil: Default::default(),
+                    bias: Default::default(),
+                }),
+                multisample: wgpu::MultisampleState::default(),
+                multiview_mask: None,
+                cache: None,
+            })
+        };
+
+        let mesh_pipeline = pipeline(
+            "embedded_viewport_mesh",
+            "vs_mesh",
+            "fs_mesh",
+            wgpu::VertexBufferLayout {
+                array_stride: std::mem::size_of::<MeshVertex>() as wgpu::BufferAddress,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3],
+            },
+            wgpu::PrimitiveTopology::TriangleList,
+        );
+        let line_pipeline = pipeline(
+            "embedded_viewport_lines",
+            "vs_line",
+            "fs_line",
+            wgpu::VertexBufferLayout {
+                array_stride: std::mem::size_of::<LineVertex>() as wgpu::BufferAddress,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x4],
+            },
+            wgpu::PrimitiveTopology::LineList,
+        );
+
+        let uniform_capacity = DRAW_UNIFORM_STRIDE * 16;
+        let line_capacity = 4096;
+        Self {
+            mesh_pipeline,
+            line_pipeline,
+            bind_group_layout,
+            uniforms: Self::create_buffer(device, uniform_capacity, wgpu::BufferUsages::UNIFORM),
+            uniform_capacity,
+            line_vertices: Self::create_buffer(device, line_capacity, wgpu::BufferUsages::VERTEX),
+            line_capacity,
+            depth: None,
+            meshes: HashMap::new(),
+        }
+    }
+
+    fn create_buffer(device: &wgpu::Device, size: u64, usage: wgpu::BufferUsages) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("embedded_viewport_buffer"),
+            size,
+            usage: usage | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn render(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        view: &wgpu::TextureView,
+        (width, height): (u32, u32),
+        clear_color: [f32; 4],
+        camera: &ViewportCamera,
+        mesh_draws: &[MeshDraw],
+        meshes: &HashMap<MeshHandle, Arc<MeshData>>,
+        lines: &[DebugLine],
+    ) {
+        // Upload meshes on first use; released ones were already removed
+        for draw in mesh_draws {
+            if self.meshes.contains_key(&draw.mesh) {
+                continue;
+            }
+            if let Some(data) = meshes.get(&draw.mesh) {
+                self.meshes
+                    .insert(draw.mesh, Self::upload_mesh(device, data));
+            }
+        }
+
+        if self.depth.as_ref().map(|(_, size)| *size) != Some((width, height)) {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("embedded_viewport_depth"),
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: DEPTH_FORMAT,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            });
+            let depth_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            self.depth = Some((depth_view, (width, height)));
+        }
+
+        // Slot 0 holds the line uniforms, one slot per mesh draw after that
+        let view_proj = view_projection(camera, width, height).to_cols_array_2d();
+        let mut uniforms = vec![0u8; DRAW_UNIFORM_STRIDE as usize * (mesh_draws.len() + 1)];
+        let slots = std::iter::once(DrawUniforms {
+            view_proj,
+            model: IDENTITY_TRANSFORM,
+            color: [1.0; 4],
+        })
+        .chain(mesh_draws.iter().map(|draw| DrawUniforms {
+            view_proj,
+            model: draw.transform,
+            color: draw.color,
+        }));
+        for (i, slot) in slots.enumerate() {
+            let offset = i * DRAW_UNIFORM_STRIDE as usize;
+            let bytes = bytemuck::bytes_of(&slot);
+            uniforms[offset..offset + bytes.len()].copy_from_slice(bytes);
+        }
+        if uniforms.len() as u64 > self.uniform_capacity {
+            self.uniform_capacity = (uniforms.len() as u64).next_power_of_two();
+            self.uniforms =
+                Self::create_buffer(device, self.uniform_capacity, wgpu::BufferUsages::UNIFORM);
+        }
+        queue.write_buffer(&self.uniforms, 0, &uniforms);
+
+        let line_vertices: Vec<LineVertex> = lines
+            .iter()
+            .flat_map(|line| {
+                [
+                    LineVertex {
+                        position: line.from,
+                        color: line.color,
+                    },
+                    LineVertex {
+                        position: line.to,
+                        color: line.color,
+                    },
+                ]
+            })
+            .collect();
+        let line_bytes: &[u8] = bytemuck::cast_slice(&line_vertices);
+        if line_bytes.len() as u64 > self.line_capacity {
+            self.line_capacity = (line_bytes.len() as u64).next_power_of_two();
+            self.line_vertices =
+                Self::create_buffer(device, self.line_capacity, wgpu::BufferUsages::VERTEX);
+        }
+        if !line_bytes.is_empty() {
+            queue.write_buffer(&self.line_vertices, 0, line_bytes);
+        }
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("embedded_viewport_bg"),
+            layout: &self.bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer: &self.uniforms,
+                    offset: 0,
+                    size: wgpu::BufferSize::new(std::mem::size_of::<DrawUniforms>() as u64),
+                }),
+            }],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("embedded_viewport_encoder"),
+        });
+        let depth_view = self.depth.as_ref().map(|(view, _)| view);
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("embedded_viewport_pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                depth_slice: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color {
+                        r: clear_color[0] as f64,
+                        g: clear_color[1] as f64,
+                        b: clear_color[2] as f64,
+                        a: clear_color[3] as f64,
+                    }),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: depth_view.map(|view| {
+                wgpu::RenderPassDepthStencilAttachment {
+                    view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Discard,
+                    }),
+                    stencil_ops: None,
+                }
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+            multiview_mask: None,
+        });
+
+        pass.set_pipeline(&self.mesh_pipeline);
+        for (i, draw) in mesh_draws.iter().enumerate() {
+            let Some(mesh) = self.meshes.get(&draw.mesh) else {
+                continue;
+            };
+            let offset = (i as u64 + 1) * DRAW_UNIFORM_STRIDE;
+            pass.set_bind_group(0, &bind_group, &[offset as u32]);
+            pass.set_vertex_buffer(0, mesh.vertices.slice(..));
+            pass.set_index_buffer(mesh.indices.slice(..), wgpu::IndexFormat::Uint32);
+            pass.draw_indexed(0..mesh.index_count, 0, 0..1);
+        }
+
+        if !line_vertices.is_empty() {
+            pass.set_pipeline(&self.line_pipeline);
+            pass.set_bind_group(0, &bind_group, &[0]);
+            pass.set_vertex_buffer(0, self.line_vertices.slice(..line_bytes.len() as u64));
+            pass.draw(0..line_vertices.len() as u32, 0..1);
+        }
+
+        drop(pass);
+        queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    fn upload_mesh(device: &wgpu::Device, data: &MeshData) -> GpuMesh {
+        use wgpu::util::DeviceExt;
+
+        let (vertices, indices) = mesh_vertices(data);
+        GpuMesh {
+            vertices: device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("embedded_viewport_mesh_vertices"),
+                contents: bytemuck::cast_slice(&vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            }),
+            indices: device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("embedded_viewport_mesh_indices"),
+                contents: bytemuck::cast_slice(&indices),
+                usage: wgpu::BufferUsages::INDEX,
+            }),
+            index_count: indices.len() as u32,
+        }
+    }
+}
+
+/// Interleaves positions and normals. Meshes without normals are unwelded so
+/// each triangle gets its face normal.
+fn mesh_vertices(data: &MeshData) -> (Vec<MeshVertex>, Vec<u32>) {
+    if data.normals.len() == data.positions.len() {
+        let vertices = data
+            .positions
+            .iter()
+            .zip(&data.normals)
+            .map(|(&position, &normal)| MeshVertex { position, normal })
+            .collect();
+        return (vertices, data.indices.clone());
+    }
+
+    let mut vertices = Vec::with_capacity(data.indices.len());
+    for triangle in data.indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| glam::Vec3::from(data.positions[triangle[i] as usize]));
+        let normal = (b - a).cross(c - a).normalize_or_zero().to_array();
+        for position in [a, b, c] {
+            vertices.push(MeshVertex {
+                position: position.to_array(),
+                normal,
+            });
+        }
+    }
+    let indices = (0..vertices.len() as u32).collect();
+    (vertices, indices)
+}
+
+fn view_projection(camera: &ViewportCamera, width: u32, height: u32) -> glam::Mat4 {
+    let aspect = width.max(1) as f32 / height.max(1) as f32;
+    let projection = glam::Mat4::perspective_rh(
+        camera.vertical_fov_degrees.to_radians(),
+        aspect,
+        camera.near,
+        camera.far,
+    );
+    let view = glam::Mat4::look_at_rh(camera.eye.into(), camera.target.into(), camera.up.into());
+    projection * view
+}
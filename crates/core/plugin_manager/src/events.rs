@@ -0,0 +1,99 @@
+//! Plugin manager events.
+//!
+//! [`PluginManager::subscribe`](crate::PluginManager::subscribe) hands out a
+//! receiver that gets every event from then on, so windows showing plugin
+//! state can follow it without polling. Receivers that are dropped are
+//! forgotten on the next event.
+//!
+//! Plugin libraries are never unloaded (see the crate docs). A plugin is
+//! "unloaded" when a reload retires its previous build: once its file types
+//! and editors are unregistered and, if editors from that build were still
+//! open, once the last of them closes.
+
+use crossbeam_channel::{Receiver, Sender};
+use parking_lot::RwLock;
+use plugin_editor_api::{PluginId, PluginMetadata};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::QuarantinedPlugin;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PluginManagerEvent {
+    /// A plugin was registered, on first load or as a rebuilt build
+    PluginLoaded(PluginMetadata),
+    /// A plugin's build was retired while editors it created are still open
+    PluginUnloadPending(PluginId),
+    /// A retired build has no open editors left
+    PluginUnloaded(PluginId),
+    /// A plugin panicked and is no longer called into
+    PluginQuarantined(QuarantinedPlugin),
+    /// A plugin created an editor
+    EditorOpened { plugin_id: PluginId, path: PathBuf },
+    /// An editor created by a plugin was closed
+    EditorClosed { plugin_id: PluginId },
+}
+
+/// The subscribers' senders. Cheap to clone; clones share the subscribers.
+#[derive(Clone, Default)]
+pub(crate) struct EventBus(Arc<RwLock<Vec<Sender<PluginManagerEvent>>>>);
+
+impl EventBus {
+    pub(crate) fn subscribe(&self) -> Receiver<PluginManagerEvent> {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        self.0.write().push(sender);
+        receiver
+    }
+
+    pub(crate) fn emit(&self, event: PluginManagerEvent) {
+        let disconnected: Vec<Sender<PluginManagerEvent>> = self
+            .0
+            .read()
+            .iter()
+            .filter(|sender| sender.send(event.clone()).is_err())
+            .cloned()
+            .collect();
+        if !disconnected.is_empty() {
+            self.0
+                .write()
+                .retain(|sender| !disconnected.iter().any(|gone| gone.same_channel(sender)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn closed(id: &str) -> PluginManagerEvent {
+        PluginManagerEvent::EditorClosed {
+            plugin_id: PluginId::new(id),
+        }
+    }
+
+    #[test]
+    fn test_every_subscriber_gets_events() {
+        let bus = EventBus::default();
+        let first = bus.subscribe();
+        bus.emit(closed("a"));
+        let second = bus.subscribe();
+        bus.emit(closed("b"));
+
+        assert_eq!(
+            first.try_iter().collect::<Vec<_>>(),
+            [closed("a"), closed("b")]
+        );
+        // Only events from after subscribing
+        assert_eq!(second.try_iter().collect::<Vec<_>>(), [closed("b")]);
+    }
+
+    #[test]
+    fn test_dropped_subscribers_are_forgotten() {
+        let bus = EventBus::default();
+        let kept = bus.subscribe();
+        drop(bus.subscribe());
+        bus.emit(closed("a"));
+        assert_eq!(bus.0.read().len(), 1);
+        assert_eq!(kept.try_recv(), Ok(closed("a")));
+    }
+}
@@ -0,0 +1,95 @@
+//! Custom file type icons.
+//!
+//! Plugins can give their file types an image icon through
+//! [`EditorPlugin::file_type_icon`](plugin_editor_api::EditorPlugin::file_type_icon):
+//! a base64-encoded PNG or SVG. It is decoded once when the plugin loads and
+//! cached in the [`FileTypeRegistry`](crate::FileTypeRegistry). An icon that
+//! can't be used is dropped with a warning, leaving the file type's
+//! [`IconName`](ui::IconName) icon.
+
+use base64::Engine as _;
+use gpui::{Image, ImageFormat};
+use plugin_editor_api::FileTypeId;
+use std::sync::Arc;
+
+/// Largest decoded icon accepted, in bytes.
+pub(crate) const MAX_ICON_BYTES: usize = 256 * 1024;
+
+/// Decode a plugin's icon payload, or log why it can't be used.
+pub(crate) fn decode_icon(file_type_id: &FileTypeId, payload: &str) -> Option<Arc<Image>> {
+    match decode_icon_bytes(payload) {
+        Ok((format, bytes)) => Some(Arc::new(Image::from_bytes(format, bytes))),
+        Err(reason) => {
+            tracing::warn!(
+                "Ignoring custom icon for file type {}: {}",
+                file_type_id,
+                reason
+            );
+            None
+        }
+    }
+}
+
+fn decode_icon_bytes(payload: &str) -> Result<(ImageFormat, Vec<u8>), String> {
+    // Base64 grows data by a third, so an oversized payload is rejected
+    // before decoding it
+    if payload.len() / 4 * 3 > MAX_ICON_BYTES + 3 {
+        return Err(format!("larger than {} KB", MAX_ICON_BYTES / 1024));
+    }
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(payload.trim())
+        .map_err(|e| format!("invalid base64: {}", e))?;
+    if bytes.len() > MAX_ICON_BYTES {
+        return Err(format!("larger than {} KB", MAX_ICON_BYTES / 1024));
+    }
+    let format = image_format(&bytes).ok_or("not a PNG or SVG image")?;
+    Ok((format, bytes))
+}
+
+fn image_format(bytes: &[u8]) -> Option<ImageFormat> {
+    const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    if bytes.starts_with(PNG_SIGNATURE) {
+        return Some(ImageFormat::Png);
+    }
+    let text = std::str::from_utf8(bytes)
+        .ok()?
+        .trim_start_matches('\u{feff}')
+        .trim_start();
+    (text.starts_with("<svg") || (text.starts_with("<?xml") && text.contains("<svg")))
+        .then_some(ImageFormat::Svg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(bytes: &[u8]) -> String {
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    }
+
+    #[test]
+    fn test_decode_png_and_svg() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        let (format, bytes) = decode_icon_bytes(&encode(png)).unwrap();
+        assert!(matches!(format, ImageFormat::Png));
+        assert_eq!(bytes, png);
+
+        let svg = br#"<?xml version="1.0"?><svg xmlns="http://www.w3.org/2000/svg"/>"#;
+        let (format, _) = decode_icon_bytes(&encode(svg)).unwrap();
+        assert!(matches!(format, ImageFormat::Svg));
+    }
+
+    #[test]
+    fn test_reject_bad_icons() {
+        assert!(decode_icon_bytes("not base64!").is_err());
+        assert!(decode_icon_bytes(&encode(b"GIF89a")).is_err());
+
+        let mut oversized = b"\x89PNG\r\n\x1a\n".to_vec();
+        oversized.resize(MAX_ICON_BYTES + 1, 0);
+        assert!(decode_icon_bytes(&encode(&oversized)).is_err());
+
+        let mut largest = b"\x89PNG\r\n\x1a\n".to_vec();
+        largest.resize(MAX_ICON_BYTES, 0);
+        assert!(decode_icon_bytes(&encode(&largest)).is_ok());
+    }
+}
@@ -4,9 +4,13 @@
 //! in the Pulsar engine. It handles:
 //!
 //! - Dynamic library loading from `plugins/editor/`
-//! - Version compatibility checking
+//! - Version compatibility checking, from a `.plugin.toml` sidecar before the
+//!   library is opened when there is one
+//! - Load ordering by declared plugin dependencies
 //! - File type and editor registration
 //! - Editor instance creation
+//! - Installing plugins from a registry ([`marketplace`])
+//! - Suggesting unloaded plugins for files no editor handles
 //!
 //! ## Safety Model
 //!
@@ -52,19 +56,29 @@
 //!
 //! The only concern is **Arc cycles**, which can cause memory leaks.
 //! See `PLUGIN_ARCHITECTURE.md` for guidelines on preventing cycles with `Weak<T>`.
+//!
+//! ## Hot Reload
+//!
+//! [`PluginManager::reload_plugin`] swaps a rebuilt plugin in without a
+//! restart. It does not break the rule above: the old library stays mapped,
+//! its file types and editors are unregistered, and the rebuilt library is
+//! loaded alongside it from a fresh shadow copy. Editors opened before the
+//! reload keep running the old code until their tabs are closed.
 
 use once_cell::sync::OnceCell;
 use parking_lot::RwLock;
 use plugin_editor_api::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
 use ui::dock::PanelView;
 
 struct FileTypeDecoratedPanelView {
     inner: Arc<dyn PanelView>,
     file_path: PathBuf,
     icon: Option<ui::IconName>,
+    read_only: bool,
 }
 
 impl PanelView for FileTypeDecoratedPanelView {
@@ -77,7 +91,12 @@ impl PanelView for FileTypeDecoratedPanelView {
     }
 
     fn tab_name(&self, cx: &gpui::App) -> Option<gpui::SharedString> {
-        self.inner.tab_name(cx)
+        let name = self.inner.tab_name(cx);
+        if self.read_only {
+            name.map(|name| format!("{} (read-only)", name).into())
+        } else {
+            name
+        }
     }
 
     fn tab_icon(&self, cx: &gpui::App) -> Option<ui::IconName> {
@@ -173,13 +192,36 @@ impl PanelView for FileTypeDecoratedPanelView {
 }
 
 pub mod builtin;
+mod default_editors;
+pub mod embedded_viewport;
+mod events;
+mod file_icons;
+mod load_order;
+pub mod marketplace;
+mod new_file;
 mod permanent_library;
+mod plugin_settings;
+mod plugin_state;
+mod quarantine;
 mod registry;
+mod shadow_copy;
+mod sidecar;
+mod snapshot;
+mod suggestions;
 pub mod tool_bridge;
 
-pub use builtin::{BuiltinEditorProvider, BuiltinEditorRegistry, EditorContext};
+pub use builtin::{BuiltinEditorProvider, BuiltinEditorRegistry, EditorContext, EditorOpenMode};
+pub use embedded_viewport::EmbeddedViewportService;
+pub use events::PluginManagerEvent;
 pub use permanent_library::{IntegrityError, PermanentLibrary};
-pub use registry::{EditorRegistry, FileTypeRegistry};
+pub use plugin_settings::{schema_properties, PluginSettingsStore};
+pub use plugin_state::{PluginState, PluginStateEntry};
+pub use quarantine::QuarantinedPlugin;
+pub use registry::{CommandRegistry, EditorRegistry, FileTypeRegistry};
+pub use snapshot::{
+    LoadTimings, PendingUnload, PluginManagerSnapshot, PluginSnapshot, RetiredLibrary,
+};
+pub use suggestions::PluginSuggestion;
 pub use tool_bridge::PluginToolBridge;
 
 // ============================================================================
@@ -230,16 +272,85 @@ struct LoadedPlugin {
     ///
     /// SAFETY: PermanentLibrary ensures this is never unloaded.
     /// As long as this exists, all symbols from the library remain valid.
-    #[allow(dead_code)]
     library: PermanentLibrary,
 
+    /// Path the plugin was loaded from, before shadow copying. Reloads read
+    /// the rebuilt library from here.
+    source_path: PathBuf,
+
     /// Metadata for quick access (owned by main app)
     metadata: PluginMetadata,
 
+    /// Schema of the plugin's settings, if it has any
+    settings_schema: Option<serde_json::Value>,
+
+    /// When the plugin was registered
+    loaded_at: chrono::DateTime<chrono::Local>,
+
+    /// How long loading it took
+    load_timings: LoadTimings,
+
     /// Editor factories registered by this plugin (populated at load time).
     editor_factories: EditorFactoryRegistry,
 }
 
+/// A plugin created from its library and named by its metadata, before its
+/// `on_load` runs.
+struct CreatedPlugin {
+    plugin: &'static mut dyn EditorPluginFull,
+    library: PermanentLibrary,
+    metadata: PluginMetadata,
+    /// Time spent loading the library and creating the plugin
+    library_time: Duration,
+}
+
+/// The library of a plugin build retired by a reload or by disabling it.
+///
+/// The handle is kept rather than dropped so the libraries left mapped are
+/// accounted for in [`PluginManager::state_snapshot`].
+struct RetiredBuild {
+    plugin_id: PluginId,
+    library: PermanentLibrary,
+    retired_at: chrono::DateTime<chrono::Local>,
+}
+
+/// A created plugin and everything it registers, read from it before any of
+/// it is registered so a panic part way through leaves nothing behind.
+struct OpenedPlugin {
+    plugin: &'static dyn EditorPluginFull,
+    library: PermanentLibrary,
+    metadata: PluginMetadata,
+    dependencies: Vec<PluginDependency>,
+    file_types: Vec<FileTypeDefinition>,
+    file_type_icons: Vec<(FileTypeId, Arc<gpui::Image>)>,
+    editors: Vec<EditorMetadata>,
+    commands: Vec<CommandDefinition>,
+    statusbar_buttons: Vec<StatusbarButtonDefinition>,
+    settings_schema: Option<serde_json::Value>,
+    editor_factories: EditorFactoryRegistry,
+    /// Registration is completed by `register_plugin`
+    timings: LoadTimings,
+}
+
+/// A registered file type and where it comes from, from
+/// [`PluginManager::describe_file_type`].
+#[derive(Debug, Clone)]
+pub struct FileTypeDescriptor {
+    pub definition: FileTypeDefinition,
+    /// The plugin providing the file type; `builtin` for built-in ones
+    pub provider_id: PluginId,
+    /// Metadata of the providing plugin, `None` for built-in file types
+    pub provider: Option<PluginMetadata>,
+    /// Editors that can open the file type, in registration order
+    pub editors: Vec<EditorId>,
+    /// Whether one of `editors` can be used now, i.e. its plugin isn't
+    /// quarantined
+    pub editor_available: bool,
+    /// Other file types claiming the same extension, with their providers,
+    /// in registration order
+    pub shares_extension_with: Vec<(FileTypeId, PluginId)>,
+}
+
 // ============================================================================
 // Plugin Manager
 // ============================================================================
@@ -269,21 +380,42 @@ pub struct PluginManager {
     /// All loaded plugins, indexed by plugin ID
     plugins: HashMap<PluginId, LoadedPlugin>,
 
+    /// Plugins found disabled while loading or disabled since, by plugin ID
+    disabled_plugins: HashMap<PluginId, plugin_state::DisabledPlugin>,
+
+    /// Libraries that failed to load, by path
+    failed_plugins: HashMap<PathBuf, PluginStateEntry>,
+
+    /// Settings of plugins that declare a settings schema
+    plugin_settings: PluginSettingsStore,
+
     /// Registry of all file types
     file_type_registry: FileTypeRegistry,
 
     /// Registry of all editors
     editor_registry: EditorRegistry,
 
+    /// Registry of all command palette commands
+    command_registry: CommandRegistry,
+
     /// Built-in editor registry (no DLL loading)
     builtin_registry: BuiltinEditorRegistry,
 
     /// The version info for this engine build
     engine_version: VersionInfo,
 
+    /// Also require plugins to be built with the engine's rustc version
+    strict_rustc_check: bool,
+
     /// Project root path for editor context
     project_root: Option<PathBuf>,
 
+    /// Open mode passed to newly created editors
+    editor_open_mode: EditorOpenMode,
+
+    /// The project's default editor for file types more than one editor can open
+    default_editors: default_editors::DefaultEditors,
+
     /// Statusbar buttons registered by all plugins
     /// Stored with plugin ownership tracking for proper cleanup
     statusbar_buttons: Vec<(PluginId, StatusbarButtonDefinition)>,
@@ -299,6 +431,26 @@ pub struct PluginManager {
     /// Component definitions registered directly as built-ins (not from DLL plugins).
     /// These supplement definitions from `BuiltinEditorRegistry` and DLL plugins.
     builtin_component_definitions: Vec<ComponentDefinition>,
+
+    /// Editors created from DLL plugins, by plugin. Weak so closing a tab
+    /// isn't held up; dead entries are pruned when counted.
+    active_editors: HashMap<PluginId, Vec<Weak<dyn PanelView>>>,
+
+    /// Editors still open on a build of their plugin that a reload retired
+    retired_editors: HashMap<PluginId, Vec<Weak<dyn PanelView>>>,
+
+    /// Libraries of retired builds, oldest first
+    retired_builds: Vec<RetiredBuild>,
+
+    /// Subscribers to [`PluginManagerEvent`]s
+    events: events::EventBus,
+
+    /// Libraries read by [`suggest_plugins_for_path`](Self::suggest_plugins_for_path)
+    /// without being loaded as plugins, so each is opened only once.
+    probed_libraries: suggestions::ProbeCache,
+
+    /// Plugins that panicked and are no longer called into
+    quarantine: quarantine::Quarantine,
 }
 
 // SAFETY: PluginManager now contains only safe types:
@@ -310,6 +462,38 @@ pub struct PluginManager {
 unsafe impl Send for PluginManager {}
 unsafe impl Sync for PluginManager {}
 
+/// Dynamic libraries directly in `dir` (.dll on Windows, .so on Linux,
+/// .dylib on macOS), sorted by path.
+fn plugin_library_paths(dir: &Path) -> Vec<PathBuf> {
+    // Get the appropriate file extension for this platform
+    #[cfg(target_os = "windows")]
+    let extension = "dll";
+    #[cfg(target_os = "linux")]
+    let extension = "so";
+    #[cfg(target_os = "macos")]
+    let extension = "dylib";
+
+    let mut paths: Vec<PathBuf> = walkdir::WalkDir::new(dir)
+        .max_depth(1)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.extension().and_then(|s| s.to_str()) == Some(extension))
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Stand-in ID for the plugin in the library at `path`, used until the
+/// plugin names itself: the library's file stem.
+fn library_plugin_id(path: &Path) -> PluginId {
+    PluginId::new(
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    )
+}
+
 impl PluginManager {
     fn decorate_editor_panel_for_path(
         &self,
@@ -319,39 +503,116 @@ impl PluginManager {
         let icon = self
             .get_file_type_for_path(file_path)
             .map(|ft| ft.icon.clone());
+        let read_only = self.editor_open_mode == EditorOpenMode::ReadOnly;
 
-        if icon.is_none() && file_path.as_os_str().is_empty() {
+        if icon.is_none() && file_path.as_os_str().is_empty() && !read_only {
             panel
         } else {
             Arc::new(FileTypeDecoratedPanelView {
                 inner: panel,
                 file_path: file_path.to_path_buf(),
                 icon,
+                read_only,
             })
         }
     }
 
     /// Create a new plugin manager.
     pub fn new() -> Self {
+        let events = events::EventBus::default();
         Self {
             plugins: HashMap::new(),
+            disabled_plugins: HashMap::new(),
+            failed_plugins: HashMap::new(),
+            plugin_settings: PluginSettingsStore::new(PluginSettingsStore::default_config_dir()),
             file_type_registry: FileTypeRegistry::new(),
             editor_registry: EditorRegistry::new(),
+            command_registry: CommandRegistry::new(),
             builtin_registry: BuiltinEditorRegistry::new(),
             engine_version: VersionInfo::current(),
+            strict_rustc_check: false,
             project_root: None,
+            editor_open_mode: EditorOpenMode::default(),
+            default_editors: default_editors::DefaultEditors::default(),
             statusbar_buttons: Vec::new(),
             plugin_subsystems: Vec::new(),
             plugin_component_registrations: Vec::new(),
             builtin_component_definitions: Vec::new(),
+            active_editors: HashMap::new(),
+            retired_editors: HashMap::new(),
+            retired_builds: Vec::new(),
+            probed_libraries: suggestions::ProbeCache::default(),
+            quarantine: quarantine::Quarantine::new(events.clone()),
+            events,
         }
     }
 
+    /// Receive every [`PluginManagerEvent`] from now on.
+    ///
+    /// The channel is unbounded; drop the receiver to unsubscribe.
+    pub fn subscribe(&mut self) -> crossbeam_channel::Receiver<PluginManagerEvent> {
+        self.events.subscribe()
+    }
+
     /// Set the project root path for editor context.
+    ///
+    /// Also loads the project's default editors when the project changes.
     pub fn set_project_root(&mut self, project_root: Option<PathBuf>) {
+        if self.project_root != project_root {
+            self.default_editors = project_root
+                .as_deref()
+                .map(default_editors::DefaultEditors::load)
+                .unwrap_or_default();
+        }
         self.project_root = project_root;
     }
 
+    /// Open files of `file_type_id` with `editor_id` when several editors can
+    /// open them.
+    ///
+    /// The choice is saved to the project's `.pulsar/default_editors.json` if
+    /// a project is open; it applies for the session even if saving fails.
+    pub fn set_default_editor(
+        &mut self,
+        file_type_id: FileTypeId,
+        editor_id: EditorId,
+    ) -> Result<(), PluginManagerError> {
+        if !self
+            .editor_registry
+            .get_editors_for_file_type(&file_type_id)
+            .contains(&editor_id)
+        {
+            return Err(PluginManagerError::EditorNotFound { editor_id });
+        }
+        self.default_editors
+            .set(self.project_root.as_deref(), file_type_id, editor_id)
+    }
+
+    /// Set the open mode for editors created from now on.
+    ///
+    /// Editors that are already open keep the mode they were created with.
+    pub fn set_editor_open_mode(&mut self, open_mode: EditorOpenMode) {
+        self.editor_open_mode = open_mode;
+    }
+
+    pub fn editor_open_mode(&self) -> EditorOpenMode {
+        self.editor_open_mode
+    }
+
+    /// Reject plugins built with a different rustc version than the engine.
+    ///
+    /// Off by default: compatibility rests on the plugin API and engine
+    /// versions (see [`VersionInfo::check_compatible`]). Release builds that
+    /// ship plugins alongside the engine can turn it on for the extra
+    /// guarantee. Applies to plugins loaded from now on.
+    pub fn set_strict_rustc_check(&mut self, strict: bool) {
+        self.strict_rustc_check = strict;
+    }
+
+    pub fn strict_rustc_check(&self) -> bool {
+        self.strict_rustc_check
+    }
+
     /// Get a mutable reference to the built-in editor registry.
     ///
     /// This allows external code to register built-in editors during initialization.
@@ -396,6 +657,16 @@ impl PluginManager {
     /// Plugins that fail version checks or loading will be logged but won't
     /// prevent other plugins from loading.
     ///
+    /// Plugins disabled in the directory's `plugins_state.json` are skipped;
+    /// see [`set_plugin_enabled`](Self::set_plugin_enabled).
+    ///
+    /// Every library is opened before anything is registered, so plugins are
+    /// registered after the plugins they declare as
+    /// [`dependencies`](EditorPlugin::dependencies) regardless of directory
+    /// order. A plugin with a missing dependency, or on a dependency cycle,
+    /// fails with [`PluginManagerError::DependencyError`], as do the plugins
+    /// depending on it.
+    ///
     /// # Important
     ///
     /// Plugins are loaded ONCE and NEVER unloaded. This is intentional and
@@ -413,39 +684,98 @@ impl PluginManager {
         }
 
         tracing::info!("Loading plugins from: {:?}", dir);
+        shadow_copy::clean();
+
+        let states = plugin_state::PluginStateFile::load(dir);
+        let paths = plugin_library_paths(dir);
+
+        // Open every plugin before registering any, so dependencies can be ordered
+        let mut opened = Vec::new();
+        for path in paths {
+            // A sidecar can rule the library out without opening it
+            if let Some(sidecar) = sidecar::PluginSidecar::read_for(&path) {
+                let plugin_id = PluginId::new(sidecar.id.clone());
+                if !states.is_enabled(&plugin_id) {
+                    tracing::info!("⏸️ Skipping disabled plugin: {}", plugin_id);
+                    self.disabled_plugins.insert(
+                        plugin_id,
+                        plugin_state::DisabledPlugin {
+                            name: sidecar.display_name(),
+                            source_path: path,
+                        },
+                    );
+                    continue;
+                }
+                if let Some((version, component)) = sidecar.incompatibility(&self.engine_version) {
+                    let e = PluginManagerError::VersionMismatch {
+                        expected: self.engine_version,
+                        actual: version,
+                        component,
+                    };
+                    self.record_load_failure(&path, Some(plugin_id), &e);
+                    continue;
+                }
+            }
 
-        // Get the appropriate file extension for this platform
-        #[cfg(target_os = "windows")]
-        let extension = "dll";
-        #[cfg(target_os = "linux")]
-        let extension = "so";
-        #[cfg(target_os = "macos")]
-        let extension = "dylib";
-
-        // Scan directory for plugin libraries
-        for entry in walkdir::WalkDir::new(dir)
-            .max_depth(1)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
+            let created = match self.create_plugin(&path, cx) {
+                Ok(created) => created,
+                Err(e) => {
+                    self.record_load_failure(&path, None, &e);
+                    continue;
+                }
+            };
 
-            // Check if this is a dynamic library
-            if path.extension().and_then(|s| s.to_str()) != Some(extension) {
+            let plugin_id = created.metadata.id.clone();
+            if !states.is_enabled(&plugin_id) {
+                tracing::info!("⏸️ Skipping disabled plugin: {}", plugin_id);
+                self.disabled_plugins.insert(
+                    plugin_id,
+                    plugin_state::DisabledPlugin {
+                        name: created.metadata.name,
+                        source_path: path,
+                    },
+                );
                 continue;
             }
 
-            // Attempt to load the plugin
-            match self.load_plugin(path, cx) {
-                Ok(plugin_id) => {
-                    tracing::info!("✅ Successfully loaded plugin: {}", plugin_id);
-                }
-                Err(e) => {
-                    tracing::error!("❌ Failed to load plugin from {:?}: {}", path, e);
-                }
+            match self.open_created_plugin(created) {
+                Ok(plugin) => opened.push(Some((path, plugin))),
+                Err(e) => self.record_load_failure(&path, Some(plugin_id), &e),
             }
         }
 
+        let nodes: Vec<load_order::PluginNode> = opened
+            .iter()
+            .flatten()
+            .map(|(_, plugin)| load_order::PluginNode {
+                id: plugin.metadata.id.clone(),
+                dependencies: plugin.dependencies.clone(),
+            })
+            .collect();
+        let loaded: HashSet<PluginId> = self.plugins.keys().cloned().collect();
+        let load_order = load_order::resolve(&nodes, &loaded);
+
+        for (index, failure) in load_order.failures {
+            let Some((path, _)) = opened[index].take() else {
+                continue;
+            };
+            let e = PluginManagerError::DependencyError {
+                plugin_id: nodes[index].id.clone(),
+                missing: failure.missing,
+                cycle: failure.cycle,
+            };
+            self.record_load_failure(&path, Some(nodes[index].id.clone()), &e);
+        }
+
+        for index in load_order.order {
+            let Some((path, plugin)) = opened[index].take() else {
+                continue;
+            };
+            self.collect_plugin_extensions(&plugin);
+            let plugin_id = self.register_plugin(&path, plugin);
+            tracing::info!("✅ Successfully loaded plugin: {}", plugin_id);
+        }
+
         Ok(())
     }
 
@@ -458,7 +788,8 @@ impl PluginManager {
     /// as the current build. These are checked at runtime.
     ///
     /// The loaded library will NEVER be unloaded. This is intentional and necessary
-    /// to prevent undefined behavior.
+    /// to prevent undefined behavior. It is loaded from a shadow copy so the file
+    /// at `path` stays free to be overwritten by the next plugin build.
     ///
     /// # Returns
     ///
@@ -471,60 +802,391 @@ impl PluginManager {
     /// - Required symbols are missing
     /// - Version compatibility check fails
     /// - Plugin creation fails
+    /// - A declared dependency is not loaded yet
     pub fn load_plugin(
         &mut self,
         path: impl AsRef<Path>,
         cx: &gpui::App,
     ) -> Result<PluginId, PluginManagerError> {
         let path = path.as_ref();
+        let plugin = self.open_plugin(path, cx)?;
 
-        tracing::debug!("Loading plugin from: {:?}", path);
+        let missing: Vec<PluginId> = plugin
+            .dependencies
+            .iter()
+            .map(|dependency| dependency.id.clone())
+            .filter(|id| !self.plugins.contains_key(id))
+            .collect();
+        if !missing.is_empty() {
+            return Err(PluginManagerError::DependencyError {
+                plugin_id: plugin.metadata.id,
+                missing,
+                cycle: Vec::new(),
+            });
+        }
 
-        // Load the library permanently
-        let library =
-            PermanentLibrary::new(path).map_err(|e| PluginManagerError::LibraryLoadError {
-                path: path.to_path_buf(),
-                message: e.to_string(),
+        self.collect_plugin_extensions(&plugin);
+        Ok(self.register_plugin(path, plugin))
+    }
+
+    /// Collect a plugin's subsystems and component factories.
+    ///
+    /// These are drained into the engine at startup, so unlike file types and
+    /// editors they are not replaced on reload. A plugin that panics here
+    /// still gets its file types and editors registered, though quarantined.
+    fn collect_plugin_extensions(&mut self, opened: &OpenedPlugin) {
+        let plugin = opened.plugin;
+        let plugin_id = &opened.metadata.id;
+
+        // Collect plugin subsystems
+        let subsystems = self
+            .quarantine
+            .call(plugin_id, "subsystems", || plugin.subsystems())
+            .unwrap_or_default();
+        if !subsystems.is_empty() {
+            tracing::debug!(
+                "  🧩 Registering {} subsystem(s) from plugin",
+                subsystems.len()
+            );
+            for ss in &subsystems {
+                tracing::debug!("    - Subsystem: {}", ss.id());
+            }
+            self.plugin_subsystems.extend(subsystems);
+        }
+
+        // Collect plugin component registrations
+        let component_regs = self
+            .quarantine
+            .call(plugin_id, "component_factories", || {
+                plugin.component_factories()
+            })
+            .unwrap_or_default();
+        if !component_regs.is_empty() {
+            tracing::debug!(
+                "  🔧 Registering {} component(s) from plugin",
+                component_regs.len()
+            );
+            for (name, _) in &component_regs {
+                tracing::debug!("    - Component: {}", name);
+            }
+            self.plugin_component_registrations.extend(component_regs);
+        }
+    }
+
+    /// Reload a plugin from the library file it was originally loaded from.
+    ///
+    /// The rebuilt library is loaded and checked first; if loading, the
+    /// integrity manifest or the version check rejects it, the error is
+    /// returned and the current plugin stays registered. Otherwise the old
+    /// plugin's file types, editors and statusbar buttons are replaced by the
+    /// new one's.
+    ///
+    /// The old library is not unmapped (see the crate docs), so editors it
+    /// created keep working on the old code. Check
+    /// [`open_editor_count`](Self::open_editor_count) beforehand to offer
+    /// reopening them.
+    ///
+    /// Subsystems and component factories are collected at startup only and
+    /// are not reloaded.
+    ///
+    /// A successful reload lifts the plugin's quarantine, if it panicked
+    /// before. If the rebuild panics while loading, the plugin is quarantined.
+    pub fn reload_plugin(
+        &mut self,
+        plugin_id: &PluginId,
+        cx: &gpui::App,
+    ) -> Result<PluginId, PluginManagerError> {
+        let source_path = self
+            .plugins
+            .get(plugin_id)
+            .map(|p| p.source_path.clone())
+            .ok_or_else(|| PluginManagerError::PluginNotFound {
+                plugin_id: plugin_id.clone(),
             })?;
 
-        // Verify the plugin binary against the required integrity manifest.
-        // The manifest is a sibling JSON file named "plugin_integrity.json" that
-        // maps plugin filenames to their expected SHA-256 hex digests.
-        // If no manifest exists, plugins are rejected (manifest is mandatory).
-        let plugin_dir = path.parent().unwrap_or(Path::new("."));
-        let manifest = Self::load_plugin_manifest(plugin_dir)?;
-        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-        let expected_hex = manifest.get(file_name);
-        if let Some(expected_hex) = expected_hex {
-            let expected: [u8; 32] = Self::parse_hex_hash(expected_hex).map_err(|e| {
-                PluginManagerError::LibraryLoadError {
-                    path: path.to_path_buf(),
-                    message: format!("Invalid hash in manifest for '{}': {}", file_name, e),
+        tracing::info!("🔄 Reloading plugin {} from {:?}", plugin_id, source_path);
+
+        let plugin = self.open_plugin(&source_path, cx)?;
+        let new_id = plugin.metadata.id.clone();
+        if &new_id != plugin_id {
+            return Err(PluginManagerError::PluginIdChanged {
+                expected: plugin_id.clone(),
+                actual: new_id,
+            });
+        }
+
+        let open_editors = self.open_editor_count(plugin_id);
+        if open_editors > 0 {
+            tracing::warn!(
+                "{} editor(s) from {} stay on the previous build until reopened",
+                open_editors,
+                plugin_id
+            );
+        }
+
+        self.unregister_plugin(plugin_id);
+        self.retire_editors(plugin_id);
+        self.quarantine.release(plugin_id);
+        Ok(self.register_plugin(&source_path, plugin))
+    }
+
+    /// Enable or disable a plugin, remembering the choice in the
+    /// `plugins_state.json` of the directory it was loaded from.
+    ///
+    /// Disabling unregisters the plugin the way a reload retires a build:
+    /// its file types, editors, commands and statusbar buttons go at once,
+    /// while editors it created stay open until closed (see
+    /// [`PluginManagerEvent::PluginUnloadPending`]). Subsystems and component
+    /// factories collected at startup stay until the engine restarts.
+    ///
+    /// Enabling loads the plugin from its library again. That also retries a
+    /// plugin that failed to load.
+    pub fn set_plugin_enabled(
+        &mut self,
+        plugin_id: &PluginId,
+        enabled: bool,
+        cx: &gpui::App,
+    ) -> Result<(), PluginManagerError> {
+        let (name, source_path) = if let Some(loaded) = self.plugins.get(plugin_id) {
+            (loaded.metadata.name.clone(), loaded.source_path.clone())
+        } else if let Some(disabled) = self.disabled_plugins.get(plugin_id) {
+            (disabled.name.clone(), disabled.source_path.clone())
+        } else if let Some(failed) = self
+            .failed_plugins
+            .values()
+            .find(|failed| &failed.plugin_id == plugin_id)
+        {
+            (failed.name.clone(), failed.library_path.clone())
+        } else {
+            return Err(PluginManagerError::PluginNotFound {
+                plugin_id: plugin_id.clone(),
+            });
+        };
+
+        let dir = source_path.parent().unwrap_or(Path::new("."));
+        plugin_state::PluginStateFile::save_enabled(dir, plugin_id, enabled)?;
+
+        if enabled {
+            if self.plugins.contains_key(plugin_id) {
+                return Ok(());
+            }
+            self.disabled_plugins.remove(plugin_id);
+            tracing::info!("▶️ Enabling plugin {}", plugin_id);
+            return match self.load_plugin(&source_path, cx) {
+                Ok(_) => Ok(()),
+                Err(e) => {
+                    self.record_load_failure(&source_path, Some(plugin_id.clone()), &e);
+                    Err(e)
                 }
-            })?;
-            if library.sha256() != &expected {
-                return Err(PluginManagerError::IntegrityCheckFailed {
-                    path: path.to_path_buf(),
-                    expected: expected,
-                    actual: *library.sha256(),
-                });
+            };
+        }
+
+        tracing::info!("⏸️ Disabling plugin {}", plugin_id);
+        if self.plugins.contains_key(plugin_id) {
+            self.unregister_plugin(plugin_id);
+            self.retire_editors(plugin_id);
+        }
+        self.failed_plugins.remove(&source_path);
+        self.disabled_plugins.insert(
+            plugin_id.clone(),
+            plugin_state::DisabledPlugin { name, source_path },
+        );
+        Ok(())
+    }
+
+    /// Every plugin library found while loading, enabled, disabled or failed
+    /// to load, sorted by plugin ID.
+    pub fn get_plugin_states(&self) -> Vec<PluginStateEntry> {
+        let enabled = self
+            .plugins
+            .iter()
+            .map(|(plugin_id, loaded)| PluginStateEntry {
+                plugin_id: plugin_id.clone(),
+                name: loaded.metadata.name.clone(),
+                library_path: loaded.source_path.clone(),
+                state: PluginState::Enabled,
+            });
+        let disabled = self
+            .disabled_plugins
+            .iter()
+            .map(|(plugin_id, disabled)| PluginStateEntry {
+                plugin_id: plugin_id.clone(),
+                name: disabled.name.clone(),
+                library_path: disabled.source_path.clone(),
+                state: PluginState::Disabled,
+            });
+
+        let mut states: Vec<PluginStateEntry> = enabled
+            .chain(disabled)
+            .chain(self.failed_plugins.values().cloned())
+            .collect();
+        states.sort_by(|a, b| a.plugin_id.as_str().cmp(b.plugin_id.as_str()));
+        states
+    }
+
+    /// Schema of a loaded plugin's settings, for rendering a settings form.
+    /// `None` if the plugin isn't loaded or has no settings.
+    pub fn get_plugin_settings_schema(&self, plugin_id: &PluginId) -> Option<&serde_json::Value> {
+        self.plugins.get(plugin_id)?.settings_schema.as_ref()
+    }
+
+    /// Current settings of a loaded plugin, a JSON object with a value for
+    /// every key in its schema that has a default or was set.
+    pub fn get_plugin_settings(&self, plugin_id: &PluginId) -> Option<&serde_json::Value> {
+        self.plugins.get(plugin_id)?;
+        self.plugin_settings.get(plugin_id)
+    }
+
+    /// Validate and save new settings for a plugin, then pass them to its
+    /// [`on_settings_changed`](plugin_editor_api::EditorPlugin::on_settings_changed).
+    ///
+    /// `settings` replaces the current settings; keys left out go back to
+    /// their defaults.
+    pub fn update_plugin_settings(
+        &mut self,
+        plugin_id: &PluginId,
+        settings: serde_json::Value,
+    ) -> Result<(), PluginManagerError> {
+        let loaded =
+            self.plugins
+                .get(plugin_id)
+                .ok_or_else(|| PluginManagerError::PluginNotFound {
+                    plugin_id: plugin_id.clone(),
+                })?;
+        let schema = loaded.settings_schema.as_ref().ok_or_else(|| {
+            PluginManagerError::InvalidPluginSettings {
+                plugin_id: plugin_id.clone(),
+                message: "the plugin has no settings".to_string(),
             }
-            tracing::debug!("Integrity check passed for plugin: {:?}", path);
-        } else {
-            // Plugin not listed in manifest — reject unless manifest explicitly
-            // allows unlisted plugins via the special "allow_unlisted": true key.
-            if !manifest
-                .get("__allow_unlisted__")
-                .map_or(false, |v| v == "true")
-            {
-                return Err(PluginManagerError::IntegrityCheckFailed {
-                    path: path.to_path_buf(),
-                    expected: [0u8; 32],
-                    actual: *library.sha256(),
-                });
+        })?;
+
+        let settings = self
+            .plugin_settings
+            .update(plugin_id, schema, settings)?
+            .clone();
+        let plugin = loaded.plugin;
+        self.quarantine.call(plugin_id, "on_settings_changed", || {
+            plugin.on_settings_changed(&settings)
+        })
+    }
+
+    /// Log a library that failed to load and keep it for
+    /// [`get_plugin_states`](Self::get_plugin_states).
+    fn record_load_failure(
+        &mut self,
+        path: &Path,
+        plugin_id: Option<PluginId>,
+        error: &PluginManagerError,
+    ) {
+        tracing::error!("❌ Failed to load plugin from {:?}: {}", path, error);
+        let plugin_id = plugin_id.unwrap_or_else(|| library_plugin_id(path));
+        self.failed_plugins.insert(
+            path.to_path_buf(),
+            PluginStateEntry {
+                name: plugin_id.to_string(),
+                plugin_id,
+                library_path: path.to_path_buf(),
+                state: PluginState::FailedToLoad {
+                    message: error.to_string(),
+                },
+            },
+        );
+    }
+
+    /// Number of open editors created by a DLL plugin, on any of its builds.
+    pub fn open_editor_count(&mut self, plugin_id: &PluginId) -> usize {
+        self.on_editor_closed();
+        [&self.active_editors, &self.retired_editors]
+            .iter()
+            .filter_map(|editors| editors.get(plugin_id))
+            .map(Vec::len)
+            .sum()
+    }
+
+    /// Forget editors that have been closed, emitting
+    /// [`PluginManagerEvent::EditorClosed`] for each, and
+    /// [`PluginManagerEvent::PluginUnloaded`] for retired builds left without
+    /// editors.
+    ///
+    /// Call after an editor tab is closed and dropped.
+    pub fn on_editor_closed(&mut self) {
+        for editors in [&mut self.active_editors, &mut self.retired_editors] {
+            for (plugin_id, editors) in editors.iter_mut() {
+                let open = editors.len();
+                editors.retain(|editor| editor.strong_count() > 0);
+                for _ in editors.len()..open {
+                    self.events.emit(PluginManagerEvent::EditorClosed {
+                        plugin_id: plugin_id.clone(),
+                    });
+                }
             }
         }
 
+        let events = &self.events;
+        self.retired_editors.retain(|plugin_id, editors| {
+            if editors.is_empty() {
+                events.emit(PluginManagerEvent::PluginUnloaded(plugin_id.clone()));
+            }
+            !editors.is_empty()
+        });
+    }
+
+    /// Move the open editors of an unregistered plugin to the retired build.
+    fn retire_editors(&mut self, plugin_id: &PluginId) {
+        let open: Vec<_> = self
+            .active_editors
+            .remove(plugin_id)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|editor| editor.strong_count() > 0)
+            .collect();
+        if open.is_empty() && !self.retired_editors.contains_key(plugin_id) {
+            self.events
+                .emit(PluginManagerEvent::PluginUnloaded(plugin_id.clone()));
+        } else {
+            self.retired_editors
+                .entry(plugin_id.clone())
+                .or_default()
+                .extend(open);
+            self.events
+                .emit(PluginManagerEvent::PluginUnloadPending(plugin_id.clone()));
+        }
+    }
+
+    /// Load, verify and create the plugin in the library at `path`, and read
+    /// what it registers, without registering anything.
+    fn open_plugin(&self, path: &Path, cx: &gpui::App) -> Result<OpenedPlugin, PluginManagerError> {
+        let created = self.create_plugin(path, cx)?;
+        self.open_created_plugin(created)
+    }
+
+    /// Load, verify and create the plugin in the library at `path`, and read
+    /// its metadata. Its `on_load` isn't called yet.
+    fn create_plugin(
+        &self,
+        path: &Path,
+        cx: &gpui::App,
+    ) -> Result<CreatedPlugin, PluginManagerError> {
+        tracing::debug!("Loading plugin from: {:?}", path);
+        let started_at = Instant::now();
+
+        let shadow_path =
+            shadow_copy::copy(path).map_err(|e| PluginManagerError::LibraryLoadError {
+                path: path.to_path_buf(),
+                message: format!("cannot copy library to the shadow directory: {}", e),
+            })?;
+        tracing::debug!("Loading shadow copy: {:?}", shadow_path);
+
+        // Load the library permanently
+        let library = PermanentLibrary::new(&shadow_path).map_err(|e| {
+            PluginManagerError::LibraryLoadError {
+                path: path.to_path_buf(),
+                message: e.to_string(),
+            }
+        })?;
+
+        Self::verify_library_integrity(path, &library)?;
+
         // Get the version info function
         let version_fn: libloading::Symbol<extern "C" fn() -> VersionInfo> = unsafe {
             // SAFETY: We're loading a symbol from the permanently loaded library.
@@ -547,67 +1209,104 @@ impl PluginManager {
             plugin_version
         );
 
-        if !self.engine_version.is_compatible(&plugin_version) {
-            tracing::error!(
-                "Plugin version mismatch! Expected engine v{}.{}.{} (rustc hash {:#x}), got v{}.{}.{} (rustc hash {:#x})",
-                self.engine_version.engine_version.0,
-                self.engine_version.engine_version.1,
-                self.engine_version.engine_version.2,
-                self.engine_version.rustc_version_hash,
-                plugin_version.engine_version.0,
-                plugin_version.engine_version.1,
-                plugin_version.engine_version.2,
-                plugin_version.rustc_version_hash,
-            );
-
-            return Err(PluginManagerError::VersionMismatch {
+        if let Err(component) = self
+            .engine_version
+            .check_compatible(&plugin_version, self.strict_rustc_check)
+        {
+            let error = PluginManagerError::VersionMismatch {
                 expected: self.engine_version,
                 actual: plugin_version,
-            });
+                component,
+            };
+            tracing::error!("{}", error);
+            return Err(error);
         }
 
         tracing::debug!("✅ Version check passed for plugin at {:?}", path);
 
-        // Get the plugin constructor
-        let create_fn: libloading::Symbol<PluginCreate> = unsafe {
-            // SAFETY: Loading symbol from permanently loaded library.
-            // The returned function pointer remains valid forever because
-            // the library is never unloaded.
-            library
-                .get(b"_plugin_create")
-                .map_err(|e| PluginManagerError::MissingSymbol {
-                    symbol: "_plugin_create".to_string(),
-                    message: e.to_string(),
-                })?
-        };
+        // Theme pointer for cross-DLL global state sync. The Theme must remain
+        // valid for the process lifetime (guaranteed by engine).
+        let theme_ptr = ui::theme::Theme::global(cx) as *const _ as *const std::ffi::c_void;
+        if theme_ptr.is_null() {
+            return Err(PluginManagerError::PluginCreationFailed {
+                message: "Theme pointer is null (engine global state not initialized)".to_string(),
+            });
+        }
 
-        // Create the plugin instance with Theme pointer for cross-DLL global state sync
+        // Create the plugin instance
         let plugin = unsafe {
             // SAFETY: Calling the plugin constructor is safe because:
             // 1. We trust the plugin code (internal plugins only)
             // 2. We've verified version compatibility
-            // 3. The returned &'static mut reference is valid because the library never unloads
+            // 3. The returned instance stays valid because the library never unloads
             //
-            // We pass the Theme pointer to sync global state across the DLL boundary.
-            // The Theme must remain valid for the process lifetime (guaranteed by engine).
-            let theme_ptr = ui::theme::Theme::global(cx) as *const _ as *const std::ffi::c_void;
-
-            // Validate theme pointer before passing to plugin
-            if theme_ptr.is_null() {
-                return Err(PluginManagerError::PluginCreationFailed {
-                    message: "Theme pointer is null (engine global state not initialized)"
-                        .to_string(),
-                });
+            // The function pointers remain valid forever for the same reason.
+            match library.get::<PluginCreateWithGlobals>(b"_plugin_create_with_globals") {
+                Ok(create_fn) => {
+                    let globals = PluginGlobals::new(theme_ptr);
+                    let plugin = create_fn(&globals);
+                    if plugin.is_null() {
+                        return Err(PluginManagerError::PluginCreationFailed {
+                            message: "_plugin_create_with_globals returned null".to_string(),
+                        });
+                    }
+                    &mut *plugin
+                }
+                Err(e) => {
+                    #[allow(deprecated)]
+                    let create_fn =
+                        library
+                            .get::<PluginCreate>(b"_plugin_create")
+                            .map_err(|_| PluginManagerError::MissingSymbol {
+                                symbol: "_plugin_create_with_globals".to_string(),
+                                message: e.to_string(),
+                            })?;
+                    tracing::warn!(
+                        "Plugin at {:?} only exports the deprecated _plugin_create; \
+                         rebuild it against the current plugin_editor_api",
+                        path
+                    );
+                    create_fn(theme_ptr)
+                }
+            }
+        };
+
+        let plugin: &'static mut dyn EditorPluginFull = plugin;
+
+        // Share the host's UI scale cell so plugin-drawn pixel sizes follow zoom changes.
+        // Optional: plugins built before UI scaling existed don't export this symbol.
+        unsafe {
+            // SAFETY: The host cell is a static in this binary and outlives every plugin.
+            match library.get::<plugin_editor_api::PluginInitUiScale>(b"_plugin_init_ui_scale") {
+                Ok(init_fn) => init_fn(plugin_editor_api::ui_scale::host_cell()),
+                Err(_) => tracing::debug!(
+                    "Plugin at {:?} does not export _plugin_init_ui_scale; UI scale not shared",
+                    path
+                ),
             }
+        }
 
-            create_fn(theme_ptr)
-        };
-
-        let plugin: &'static mut dyn EditorPluginFull = plugin;
+        // Give the plugin access to embedded viewports. Optional for the same reason.
+        // `service()` installs the host implementation on first use.
+        embedded_viewport::service();
+        unsafe {
+            // SAFETY: The host cell is a static in this binary and outlives every plugin.
+            match library
+                .get::<plugin_editor_api::PluginInitViewportHost>(b"_plugin_init_viewport_host")
+            {
+                Ok(init_fn) => init_fn(plugin_editor_api::viewport_host_cell()),
+                Err(_) => tracing::debug!(
+                    "Plugin at {:?} does not export _plugin_init_viewport_host; embedded viewports unavailable",
+                    path
+                ),
+            }
+        }
 
-        // Get plugin metadata
-        let metadata = plugin.metadata();
-        let plugin_id = metadata.id.clone();
+        // Get plugin metadata. Until the plugin has named itself, a panic is
+        // filed under the library's name.
+        let metadata = self
+            .quarantine
+            .catch(&library_plugin_id(path), "metadata", || plugin.metadata())?;
 
         tracing::info!(
             "📦 Loaded plugin: {} v{} by {}",
@@ -616,14 +1315,125 @@ impl PluginManager {
             metadata.author
         );
 
+        if let Some(sidecar) = sidecar::PluginSidecar::read_for(path) {
+            for mismatch in sidecar.mismatches(&metadata) {
+                tracing::warn!("Plugin sidecar mismatch for {:?}: {}", path, mismatch);
+            }
+        }
+
+        Ok(CreatedPlugin {
+            plugin,
+            library,
+            metadata,
+            library_time: started_at.elapsed(),
+        })
+    }
+
+    /// Initialize a created plugin and read what it registers, without
+    /// registering anything.
+    fn open_created_plugin(
+        &self,
+        created: CreatedPlugin,
+    ) -> Result<OpenedPlugin, PluginManagerError> {
+        let CreatedPlugin {
+            plugin,
+            library,
+            metadata,
+            library_time,
+        } = created;
+        let plugin_id = metadata.id.clone();
+
         // Call on_load hook
-        plugin.on_load();
+        let started_at = Instant::now();
+        self.quarantine
+            .catch(&plugin_id, "on_load", || plugin.on_load())?;
+        let on_load_time = started_at.elapsed();
+        let started_at = Instant::now();
 
         // After load-time initialization we keep only an immutable static plugin ref.
         let plugin: &'static dyn EditorPluginFull = plugin;
 
+        let dependencies = self
+            .quarantine
+            .catch(&plugin_id, "dependencies", || plugin.dependencies())?;
+        let file_types = self
+            .quarantine
+            .catch(&plugin_id, "file_types", || plugin.file_types())?;
+        let file_type_icons = self
+            .quarantine
+            .catch(&plugin_id, "file_type_icon", || {
+                file_types
+                    .iter()
+                    .filter_map(|file_type| {
+                        Some((file_type.id.clone(), plugin.file_type_icon(&file_type.id)?))
+                    })
+                    .collect::<Vec<_>>()
+            })?
+            .into_iter()
+            .filter_map(|(file_type_id, payload)| {
+                let image = file_icons::decode_icon(&file_type_id, &payload)?;
+                Some((file_type_id, image))
+            })
+            .collect();
+        let editors = self
+            .quarantine
+            .catch(&plugin_id, "editors", || plugin.editors())?;
+        let commands = self
+            .quarantine
+            .catch(&plugin_id, "commands", || plugin.commands())?;
+        let statusbar_buttons = self.quarantine.catch(&plugin_id, "statusbar_buttons", || {
+            plugin.statusbar_buttons()
+        })?;
+        let settings_schema = self
+            .quarantine
+            .catch(&plugin_id, "settings_schema", || plugin.settings_schema())?;
+        let editor_factories = self.quarantine.catch(&plugin_id, "register_editors", || {
+            let mut editor_factories = EditorFactoryRegistry::new();
+            EditorPluginEditor::register_editors(plugin, &mut editor_factories);
+            editor_factories
+        })?;
+
+        Ok(OpenedPlugin {
+            plugin,
+            library,
+            metadata,
+            dependencies,
+            file_types,
+            file_type_icons,
+            editors,
+            commands,
+            statusbar_buttons,
+            settings_schema,
+            editor_factories,
+            timings: LoadTimings {
+                library: library_time,
+                on_load: on_load_time,
+                registration: started_at.elapsed(),
+            },
+        })
+    }
+
+    /// Register a created plugin's file types, editors, commands and
+    /// statusbar buttons.
+    fn register_plugin(&mut self, source_path: &Path, opened: OpenedPlugin) -> PluginId {
+        let OpenedPlugin {
+            plugin,
+            library,
+            metadata,
+            file_types,
+            file_type_icons,
+            editors,
+            commands,
+            statusbar_buttons,
+            settings_schema,
+            editor_factories,
+            mut timings,
+            ..
+        } = opened;
+        let started_at = Instant::now();
+        let plugin_id = metadata.id.clone();
+
         // Register file types
-        let file_types = plugin.file_types();
         for file_type in file_types {
             tracing::debug!(
                 "  📄 Registering file type: {} (.{})",
@@ -633,16 +1443,23 @@ impl PluginManager {
             self.file_type_registry
                 .register(file_type, plugin_id.clone());
         }
+        for (file_type_id, image) in file_type_icons {
+            self.file_type_registry.set_icon_image(&file_type_id, image);
+        }
 
         // Register editors
-        let editors = plugin.editors();
         for editor in editors {
             tracing::debug!("  📝 Registering editor: {}", editor.display_name);
             self.editor_registry.register(editor, plugin_id.clone());
         }
 
+        // Register command palette commands
+        for command in commands {
+            tracing::debug!("  ⌨️ Registering command: {}", command.display_name);
+            self.command_registry.register(command, plugin_id.clone());
+        }
+
         // Register statusbar buttons
-        let statusbar_buttons = plugin.statusbar_buttons();
         if !statusbar_buttons.is_empty() {
             tracing::debug!(
                 "  🔘 Registering {} statusbar buttons",
@@ -672,39 +1489,32 @@ impl PluginManager {
             });
         }
 
-        // Collect plugin subsystems
-        let subsystems = plugin.subsystems();
-        if !subsystems.is_empty() {
+        if !editor_factories.factories().is_empty() {
             tracing::debug!(
-                "  🧩 Registering {} subsystem(s) from plugin",
-                subsystems.len()
+                "  📝 Registering {} editor factories",
+                editor_factories.factories().len()
             );
-            for ss in &subsystems {
-                tracing::debug!("    - Subsystem: {}", ss.id());
-            }
-            self.plugin_subsystems.extend(subsystems);
         }
 
-        // Collect plugin component registrations
-        let component_regs = plugin.component_factories();
-        if !component_regs.is_empty() {
-            tracing::debug!(
-                "  🔧 Registering {} component(s) from plugin",
-                component_regs.len()
-            );
-            for (name, _) in &component_regs {
-                tracing::debug!("    - Component: {}", name);
+        // Hand the plugin its stored settings
+        if let Some(schema) = &settings_schema {
+            let settings = self.plugin_settings.load(&plugin_id, schema).clone();
+            if let Err(e) = self.quarantine.call(&plugin_id, "on_settings_changed", || {
+                plugin.on_settings_changed(&settings)
+            }) {
+                tracing::error!("❌ Plugin {} rejected its settings: {}", plugin_id, e);
             }
-            self.plugin_component_registrations.extend(component_regs);
         }
 
-        // Collect editor factories from the plugin
-        let mut editor_factories = EditorFactoryRegistry::new();
-        EditorPluginEditor::register_editors(plugin, &mut editor_factories);
-        if !editor_factories.factories().is_empty() {
-            tracing::debug!(
-                "  📝 Registering {} editor factories",
-                editor_factories.factories().len()
+        timings.registration += started_at.elapsed();
+        if timings.total() > snapshot::SLOW_LOAD {
+            tracing::warn!(
+                "🐢 Plugin {} took {:?} to load (library {:?}, on_load {:?}, registration {:?})",
+                plugin_id,
+                timings.total(),
+                timings.library,
+                timings.on_load,
+                timings.registration
             );
         }
 
@@ -714,13 +1524,49 @@ impl PluginManager {
         let loaded_plugin = LoadedPlugin {
             plugin,
             library,
+            source_path: source_path.to_path_buf(),
             metadata: metadata.clone(),
+            settings_schema,
+            loaded_at: chrono::Local::now(),
+            load_timings: timings,
             editor_factories,
         };
 
         self.plugins.insert(plugin_id.clone(), loaded_plugin);
+        self.failed_plugins.remove(source_path);
+        self.disabled_plugins.remove(&plugin_id);
+        self.events.emit(PluginManagerEvent::PluginLoaded(metadata));
+
+        plugin_id
+    }
+
+    /// Call a plugin's `on_unload` and remove it and everything it
+    /// registered.
+    ///
+    /// The library itself stays loaded; see the crate docs.
+    fn unregister_plugin(&mut self, plugin_id: &PluginId) {
+        if let Some(loaded) = self.plugins.get(plugin_id) {
+            let plugin = loaded.plugin;
+            if let Err(e) = self
+                .quarantine
+                .call(plugin_id, "on_unload", || plugin.on_unload())
+            {
+                tracing::error!("❌ Plugin {} failed to unload: {}", plugin_id, e);
+            }
+        }
 
-        Ok(plugin_id)
+        self.file_type_registry.unregister_by_plugin(plugin_id);
+        self.editor_registry.unregister_by_plugin(plugin_id);
+        self.command_registry.unregister_by_plugin(plugin_id);
+        self.statusbar_buttons
+            .retain(|(owner, _)| owner != plugin_id);
+        if let Some(loaded) = self.plugins.remove(plugin_id) {
+            self.retired_builds.push(RetiredBuild {
+                plugin_id: plugin_id.clone(),
+                library: loaded.library,
+                retired_at: chrono::Local::now(),
+            });
+        }
     }
 
     /// Get all loaded plugins.
@@ -728,6 +1574,127 @@ impl PluginManager {
         self.plugins.values().map(|p| &p.metadata).collect()
     }
 
+    /// Copy out the state of loaded plugins and retired builds, for Mission
+    /// Control and for bug reports.
+    ///
+    /// Editors closed since the last
+    /// [`on_editor_closed`](Self::on_editor_closed) aren't counted.
+    pub fn state_snapshot(&self) -> PluginManagerSnapshot {
+        let open_editors = |editors: &HashMap<PluginId, Vec<Weak<dyn PanelView>>>,
+                            plugin_id: &PluginId| {
+            editors.get(plugin_id).map_or(0, |editors| {
+                editors
+                    .iter()
+                    .filter(|editor| editor.strong_count() > 0)
+                    .count()
+            })
+        };
+
+        let mut plugins: Vec<PluginSnapshot> = self
+            .plugins
+            .values()
+            .map(|loaded| PluginSnapshot {
+                id: loaded.metadata.id.clone(),
+                name: loaded.metadata.name.clone(),
+                version: loaded.metadata.version.clone(),
+                author: loaded.metadata.author.clone(),
+                source_path: loaded.source_path.clone(),
+                library_path: loaded.library.path().to_path_buf(),
+                loaded_at: loaded.loaded_at,
+                load_timings: loaded.load_timings,
+                open_editors: open_editors(&self.active_editors, &loaded.metadata.id),
+                quarantined: self.quarantine.contains(&loaded.metadata.id),
+            })
+            .collect();
+        plugins.sort_by(|a, b| a.id.as_str().cmp(b.id.as_str()));
+
+        let mut pending_unloads: Vec<PendingUnload> = self
+            .retired_editors
+            .keys()
+            .map(|plugin_id| PendingUnload {
+                plugin_id: plugin_id.clone(),
+                open_editors: open_editors(&self.retired_editors, plugin_id),
+            })
+            .filter(|pending| pending.open_editors > 0)
+            .collect();
+        pending_unloads.sort_by(|a, b| a.plugin_id.as_str().cmp(b.plugin_id.as_str()));
+
+        let retired_libraries = self
+            .retired_builds
+            .iter()
+            .map(|retired| RetiredLibrary {
+                plugin_id: retired.plugin_id.clone(),
+                library_path: retired.library.path().to_path_buf(),
+                retired_at: retired.retired_at,
+            })
+            .collect();
+
+        PluginManagerSnapshot {
+            taken_at: chrono::Local::now(),
+            plugins,
+            pending_unloads,
+            retired_libraries,
+        }
+    }
+
+    /// [`state_snapshot`](Self::state_snapshot) as text, for logs.
+    pub fn debug_state(&self) -> String {
+        self.state_snapshot().to_string()
+    }
+
+    /// Plugins that panicked, loaded or not, sorted by ID.
+    ///
+    /// Loaded ones stay registered but every call into them fails with
+    /// [`PluginManagerError::PluginPanicked`] until they are reloaded.
+    pub fn get_quarantined_plugins(&self) -> Vec<QuarantinedPlugin> {
+        self.quarantine.list()
+    }
+
+    /// Verify a library against the integrity manifest next to `path`.
+    ///
+    /// The manifest is a sibling JSON file named "plugin_integrity.json" that
+    /// maps plugin filenames to their expected SHA-256 hex digests.
+    /// If no manifest exists, plugins are rejected (manifest is mandatory).
+    fn verify_library_integrity(
+        path: &Path,
+        library: &PermanentLibrary,
+    ) -> Result<(), PluginManagerError> {
+        let plugin_dir = path.parent().unwrap_or(Path::new("."));
+        let manifest = Self::load_plugin_manifest(plugin_dir)?;
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let expected_hex = manifest.get(file_name);
+        if let Some(expected_hex) = expected_hex {
+            let expected: [u8; 32] = Self::parse_hex_hash(expected_hex).map_err(|e| {
+                PluginManagerError::LibraryLoadError {
+                    path: path.to_path_buf(),
+                    message: format!("Invalid hash in manifest for '{}': {}", file_name, e),
+                }
+            })?;
+            if library.sha256() != &expected {
+                return Err(PluginManagerError::IntegrityCheckFailed {
+                    path: path.to_path_buf(),
+                    expected: expected,
+                    actual: *library.sha256(),
+                });
+            }
+            tracing::debug!("Integrity check passed for plugin: {:?}", path);
+        } else {
+            // Plugin not listed in manifest — reject unless manifest explicitly
+            // allows unlisted plugins via the special "allow_unlisted": true key.
+            if !manifest
+                .get("__allow_unlisted__")
+                .map_or(false, |v| v == "true")
+            {
+                return Err(PluginManagerError::IntegrityCheckFailed {
+                    path: path.to_path_buf(),
+                    expected: [0u8; 32],
+                    actual: *library.sha256(),
+                });
+            }
+        }
+        Ok(())
+    }
+
     /// Load the plugin integrity manifest from a JSON file in the plugin directory.
     ///
     /// The manifest file must be named `plugin_integrity.json` and contains a flat
@@ -787,6 +1754,9 @@ impl PluginManager {
         );
         let mut bridge = PluginToolBridge::new();
         for (plugin_id, loaded_plugin) in &self.plugins {
+            if self.quarantine.contains(plugin_id) {
+                continue;
+            }
             bridge.discover_plugin_tools(plugin_id.clone(), loaded_plugin.plugin);
         }
 
@@ -811,6 +1781,9 @@ impl PluginManager {
         tracing::debug!(file = %file_path.display(), plugin_count = self.plugins.len(), builtin_count = self.builtin_registry.providers().len(), "build_tool_bridge_for_file start");
         let mut bridge = PluginToolBridge::new();
         for (plugin_id, loaded_plugin) in &self.plugins {
+            if self.quarantine.contains(plugin_id) {
+                continue;
+            }
             bridge.discover_plugin_tools_for_file(
                 plugin_id.clone(),
                 loaded_plugin.plugin,
@@ -841,7 +1814,9 @@ impl PluginManager {
         plugin_id: &PluginId,
     ) -> Result<Vec<AiToolDefinition>, PluginManagerError> {
         if let Some(loaded_plugin) = self.plugins.get(plugin_id) {
-            return Ok(loaded_plugin.plugin.ai_tools());
+            return self
+                .quarantine
+                .call(plugin_id, "ai_tools", || loaded_plugin.plugin.ai_tools());
         }
 
         if let Some(provider) = self.builtin_registry.provider_by_id(plugin_id.as_str()) {
@@ -862,9 +1837,13 @@ impl PluginManager {
         tool_args: JsonValue,
     ) -> Result<JsonValue, PluginManagerError> {
         if let Some(loaded_plugin) = self.plugins.get(plugin_id) {
-            return loaded_plugin
-                .plugin
-                .execute_ai_tool(file_path, tool_name, tool_args)
+            return self
+                .quarantine
+                .call(plugin_id, "execute_ai_tool", || {
+                    loaded_plugin
+                        .plugin
+                        .execute_ai_tool(file_path, tool_name, tool_args)
+                })?
                 .map_err(|error| PluginManagerError::PluginError {
                     plugin_id: plugin_id.clone(),
                     error,
@@ -895,6 +1874,46 @@ impl PluginManager {
         &self.editor_registry
     }
 
+    /// Get a reference to the command registry.
+    pub fn command_registry(&self) -> &CommandRegistry {
+        &self.command_registry
+    }
+
+    /// Run a command palette command provided by a plugin.
+    ///
+    /// Fails without calling into the plugin if the command is no longer
+    /// registered, e.g. because a reload retired the build that listed it,
+    /// or if the plugin is quarantined.
+    pub fn execute_plugin_command(
+        &self,
+        plugin_id: &PluginId,
+        command_id: &str,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Result<(), PluginManagerError> {
+        let loaded_plugin = self
+            .plugins
+            .get(plugin_id)
+            .filter(|_| {
+                self.command_registry
+                    .get_command(plugin_id, command_id)
+                    .is_some()
+            })
+            .ok_or_else(|| PluginManagerError::CommandNotFound {
+                plugin_id: plugin_id.clone(),
+                command_id: command_id.to_string(),
+            })?;
+
+        self.quarantine
+            .call(plugin_id, "execute_command", || {
+                loaded_plugin.plugin.execute_command(command_id, window, cx)
+            })?
+            .map_err(|error| PluginManagerError::PluginError {
+                plugin_id: plugin_id.clone(),
+                error,
+            })
+    }
+
     /// Get all registered statusbar buttons from all plugins.
     ///
     /// Buttons are sorted by position (left/right) and priority within each position.
@@ -925,8 +1944,16 @@ impl PluginManager {
     pub fn get_all_component_definitions(&self) -> Vec<ComponentDefinition> {
         let mut all_defs = Vec::new();
 
-        for loaded in self.plugins.values() {
-            all_defs.extend(loaded.plugin.component_definitions());
+        for (plugin_id, loaded) in &self.plugins {
+            let defs = self
+                .quarantine
+                .call(plugin_id, "component_definitions", || {
+                    loaded.plugin.component_definitions()
+                });
+            match defs {
+                Ok(defs) => all_defs.extend(defs),
+                Err(e) => tracing::warn!("Skipping component definitions: {}", e),
+            }
         }
 
         for (_, def) in self.builtin_registry.get_all_components() {
@@ -961,7 +1988,8 @@ impl PluginManager {
     /// Create an editor instance for a file.
     ///
     /// This will:
-    /// 1. Determine the file type from the path
+    /// 1. Determine the file type from the path (a file inside a folder-based
+    ///    asset resolves to the asset's root folder)
     /// 2. Find an editor that supports that file type
     /// 3. Create an editor instance using the appropriate plugin or built-in editor
     ///
@@ -988,26 +2016,40 @@ impl PluginManager {
         window: &mut Window,
         cx: &mut App,
     ) -> Result<Arc<dyn PanelView>, PluginManagerError> {
-        // Determine file type
-        let file_type_id = self
+        // Determine file type. A file inside a folder-based asset opens the
+        // whole asset.
+        let (file_type_id, file_path) = self
             .file_type_registry
-            .get_file_type_for_path(file_path)
+            .resolve_file_type(file_path)
             .ok_or_else(|| PluginManagerError::NoFileTypeForPath {
                 path: file_path.to_path_buf(),
             })?;
 
-        // Find an editor for this file type
-        let editor_id = self
-            .editor_registry
-            .get_editor_for_file_type(&file_type_id)
-            .ok_or_else(|| PluginManagerError::NoEditorForFileType {
-                file_type_id: file_type_id.clone(),
-            })?;
+        // Find an editor for this file type, asking the caller to pick if
+        // there are several and no default
+        let editor_id = self.default_editors.choose(
+            &file_type_id,
+            self.editor_registry
+                .get_editors_for_file_type(&file_type_id),
+        )?;
+
+        self.create_editor_for_file_with(&file_path, &editor_id, window, cx)
+    }
 
+    /// Create an editor for a file with a specific editor, e.g. one picked
+    /// after [`create_editor_for_file`](Self::create_editor_for_file) failed
+    /// with [`PluginManagerError::MultipleEditorsAvailable`].
+    pub fn create_editor_for_file_with(
+        &mut self,
+        file_path: &Path,
+        editor_id: &EditorId,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Result<Arc<dyn PanelView>, PluginManagerError> {
         // Get the plugin that owns this editor
         let plugin_id = self
             .editor_registry
-            .get_plugin_for_editor(&editor_id)
+            .get_plugin_for_editor(editor_id)
             .ok_or_else(|| PluginManagerError::EditorNotFound {
                 editor_id: editor_id.clone(),
             })?
@@ -1016,13 +2058,14 @@ impl PluginManager {
         // Check if this is a built-in editor
         if plugin_id.as_str() == "builtin" {
             // Create editor context with project root
-            let editor_context = EditorContext::new(self.project_root.clone());
+            let editor_context =
+                EditorContext::new(self.project_root.clone()).with_open_mode(self.editor_open_mode);
 
             // Create the editor directly using the provider
             return self
                 .builtin_registry
                 .create_editor(
-                    &editor_id,
+                    editor_id,
                     file_path.to_path_buf(),
                     &editor_context,
                     window,
@@ -1036,7 +2079,7 @@ impl PluginManager {
         }
 
         // Fall back to DLL-based plugin
-        self.create_editor(&plugin_id, &editor_id, file_path.to_path_buf(), window, cx)
+        self.create_editor(&plugin_id, editor_id, file_path.to_path_buf(), window, cx)
     }
 
     /// Create an editor instance with a specific editor ID.
@@ -1054,6 +2097,7 @@ impl PluginManager {
         cx: &mut App,
     ) -> Result<Arc<dyn PanelView>, PluginManagerError> {
         let file_path_for_decoration = file_path.clone();
+        self.quarantine.check(plugin_id)?;
 
         let plugin =
             self.plugins
@@ -1096,12 +2140,26 @@ impl PluginManager {
             }
         })?;
 
-        (factory.create)(file_path, window, cx)
+        let panel = self
+            .quarantine
+            .call(plugin_id, "create_editor", || {
+                (factory.create)(file_path, window, cx)
+            })?
             .map(|panel| self.decorate_editor_panel_for_path(panel, &file_path_for_decoration))
             .map_err(|e| PluginManagerError::PluginError {
                 plugin_id: plugin_id.clone(),
                 error: e,
-            })
+            })?;
+
+        self.active_editors
+            .entry(plugin_id.clone())
+            .or_default()
+            .push(Arc::downgrade(&panel));
+        self.events.emit(PluginManagerEvent::EditorOpened {
+            plugin_id: plugin_id.clone(),
+            path: file_path_for_decoration,
+        });
+        Ok(panel)
     }
 
     /// Get the default content for a file type.
@@ -1124,9 +2182,49 @@ impl PluginManager {
             .and_then(|id| self.file_type_registry.get_file_type(&id))
     }
 
+    /// Describe a registered file type with the plugin providing it, for
+    /// showing "provided by" in the UI.
+    pub fn describe_file_type(&self, file_type_id: &FileTypeId) -> Option<FileTypeDescriptor> {
+        let definition = self.file_type_registry.get_file_type(file_type_id)?.clone();
+        let provider_id = self
+            .file_type_registry
+            .get_plugin_for_file_type(file_type_id)?
+            .clone();
+        let editors = self.editor_registry.get_editors_for_file_type(file_type_id);
+        let editor_available = editors.iter().any(|editor_id| {
+            self.editor_registry
+                .get_plugin_for_editor(editor_id)
+                .is_some_and(|plugin_id| !self.quarantine.contains(plugin_id))
+        });
+        let shares_extension_with = self
+            .file_type_registry
+            .get_providers_for_extension(&definition.extension)
+            .into_iter()
+            .filter(|(id, _)| *id != file_type_id)
+            .map(|(id, plugin_id)| (id.clone(), plugin_id.clone()))
+            .collect();
+
+        Some(FileTypeDescriptor {
+            provider: self
+                .plugins
+                .get(&provider_id)
+                .map(|loaded| loaded.metadata.clone()),
+            definition,
+            provider_id,
+            editors,
+            editor_available,
+            shares_extension_with,
+        })
+    }
+
     /// Create a new file of the given type.
     ///
-    /// This will create the file structure on disk with default content.
+    /// This will create the file structure on disk with default content:
+    /// what the owning plugin's
+    /// [`default_content_for`](plugin_editor_api::EditorPlugin::default_content_for)
+    /// returns for `path`, or else the file type's static default content.
+    /// `{{name}}` and `{{date}}` placeholders in the content and in template
+    /// files are expanded for `path`.
     pub fn create_new_file(
         &self,
         file_type_id: &FileTypeId,
@@ -1139,87 +2237,26 @@ impl PluginManager {
                 file_type_id: file_type_id.clone(),
             })?;
 
-        match &file_type.structure {
-            FileStructure::Standalone => {
-                // Create a simple file with default content
-                let content =
-                    serde_json::to_string_pretty(&file_type.default_content).map_err(|e| {
-                        PluginManagerError::FileCreationError {
-                            path: path.to_path_buf(),
-                            message: e.to_string(),
-                        }
-                    })?;
-
-                std::fs::write(path, content).map_err(|e| {
-                    PluginManagerError::FileCreationError {
-                        path: path.to_path_buf(),
-                        message: e.to_string(),
-                    }
-                })?;
-            }
-
-            FileStructure::FolderBased {
-                marker_file,
-                template_structure,
-            } => {
-                // Create the folder
-                std::fs::create_dir_all(path).map_err(|e| {
-                    PluginManagerError::FileCreationError {
-                        path: path.to_path_buf(),
-                        message: e.to_string(),
-                    }
-                })?;
-
-                // Create the marker file with default content
-                let marker_path = path.join(marker_file);
-                let content =
-                    serde_json::to_string_pretty(&file_type.default_content).map_err(|e| {
-                        PluginManagerError::FileCreationError {
-                            path: marker_path.clone(),
-                            message: e.to_string(),
-                        }
-                    })?;
-
-                std::fs::write(&marker_path, content).map_err(|e| {
-                    PluginManagerError::FileCreationError {
-                        path: marker_path,
-                        message: e.to_string(),
-                    }
-                })?;
-
-                // Create template structure
-                for template in template_structure {
-                    match template {
-                        PathTemplate::File {
-                            path: rel_path,
-                            content,
-                        } => {
-                            let file_path = path.join(rel_path);
-                            if let Some(parent) = file_path.parent() {
-                                std::fs::create_dir_all(parent);
-                            }
-                            std::fs::write(&file_path, content).map_err(|e| {
-                                PluginManagerError::FileCreationError {
-                                    path: file_path,
-                                    message: e.to_string(),
-                                }
-                            })?;
-                        }
-                        PathTemplate::Folder { path: rel_path } => {
-                            let folder_path = path.join(rel_path);
-                            std::fs::create_dir_all(&folder_path).map_err(|e| {
-                                PluginManagerError::FileCreationError {
-                                    path: folder_path,
-                                    message: e.to_string(),
-                                }
-                            })?;
-                        }
-                    }
-                }
+        let plugin_content = match self
+            .file_type_registry
+            .get_plugin_for_file_type(file_type_id)
+            .and_then(|plugin_id| Some((plugin_id, self.plugins.get(plugin_id)?)))
+        {
+            Some((plugin_id, loaded)) => {
+                self.quarantine.call(plugin_id, "default_content_for", || {
+                    loaded.plugin.default_content_for(file_type_id, path)
+                })?
             }
-        }
-
-        Ok(())
+            None => None,
+        };
+        let content = plugin_content.unwrap_or_else(|| file_type.default_content.clone());
+
+        new_file::write_new_file(
+            &file_type.structure,
+            content,
+            path,
+            &new_file::TemplateVars::for_path(path),
+        )
     }
 }
 
@@ -1269,32 +2306,73 @@ pub enum PluginManagerError {
     VersionMismatch {
         expected: VersionInfo,
         actual: VersionInfo,
+        /// The first component that didn't match
+        component: VersionComponent,
     },
 
     /// Failed to create plugin instance
     PluginCreationFailed { message: String },
 
+    /// A plugin's dependencies are missing or form a cycle
+    DependencyError {
+        plugin_id: PluginId,
+        missing: Vec<PluginId>,
+        cycle: Vec<PluginId>,
+    },
+
     /// Plugin not found
     PluginNotFound { plugin_id: PluginId },
 
+    /// A reloaded library reports a different plugin ID than the one it replaces
+    PluginIdChanged {
+        expected: PluginId,
+        actual: PluginId,
+    },
+
     /// File type not found
     FileTypeNotFound { file_type_id: FileTypeId },
 
     /// Editor not found
     EditorNotFound { editor_id: EditorId },
 
+    /// Command not registered, or its plugin is no longer loaded
+    CommandNotFound {
+        plugin_id: PluginId,
+        command_id: String,
+    },
+
     /// No file type for path
     NoFileTypeForPath { path: PathBuf },
 
     /// No editor for file type
     NoEditorForFileType { file_type_id: FileTypeId },
 
+    /// Several editors can open the file type and none is the default; open
+    /// it with [`PluginManager::create_editor_for_file_with`] instead
+    MultipleEditorsAvailable {
+        file_type_id: FileTypeId,
+        editors: Vec<EditorId>,
+    },
+
     /// Plugin error
     PluginError {
         plugin_id: PluginId,
         error: PluginError,
     },
 
+    /// Plugin code panicked, now or in an earlier call; the plugin is
+    /// quarantined
+    PluginPanicked {
+        plugin_id: PluginId,
+        context: String,
+    },
+
+    /// Plugin settings don't match the plugin's settings schema
+    InvalidPluginSettings {
+        plugin_id: PluginId,
+        message: String,
+    },
+
     /// Failed to create file
     FileCreationError { path: PathBuf, message: String },
 }
@@ -1330,42 +2408,112 @@ impl std::fmt::Display for PluginManagerError {
             Self::MissingSymbol { symbol, message } => {
                 write!(f, "Missing symbol '{}': {}", symbol, message)
             }
-            Self::VersionMismatch { expected, actual } => {
-                write!(
-                    f,
-                    "Plugin version mismatch: expected engine v{}.{}.{} with rustc hash {:#x}, got v{}.{}.{} with rustc hash {:#x}. \
-                    Plugin must be recompiled with the same Rust compiler version as the engine.",
-                    expected.engine_version.0,
-                    expected.engine_version.1,
-                    expected.engine_version.2,
-                    expected.rustc_version_hash,
-                    actual.engine_version.0,
-                    actual.engine_version.1,
-                    actual.engine_version.2,
-                    actual.rustc_version_hash,
-                )
+            Self::VersionMismatch {
+                expected,
+                actual,
+                component,
+            } => {
+                write!(f, "Plugin {} mismatch: ", component)?;
+                match component {
+                    VersionComponent::ApiVersion => write!(
+                        f,
+                        "engine uses plugin API v{}, plugin was built against v{}",
+                        expected.api_version, actual.api_version
+                    )?,
+                    VersionComponent::EngineMajor | VersionComponent::EngineMinor => write!(
+                        f,
+                        "engine is v{}.{}.{}, plugin was built against v{}.{}.{}",
+                        expected.engine_version.0,
+                        expected.engine_version.1,
+                        expected.engine_version.2,
+                        actual.engine_version.0,
+                        actual.engine_version.1,
+                        actual.engine_version.2,
+                    )?,
+                    VersionComponent::Rustc => write!(
+                        f,
+                        "engine rustc hash {:#x}, plugin rustc hash {:#x}",
+                        expected.rustc_version_hash, actual.rustc_version_hash
+                    )?,
+                }
+                write!(f, ". Plugin must be recompiled against this engine.")
             }
             Self::PluginCreationFailed { message } => {
                 write!(f, "Failed to create plugin: {}", message)
             }
+            Self::DependencyError {
+                plugin_id,
+                missing,
+                cycle,
+            } => {
+                write!(f, "Plugin {} has unresolved dependencies", plugin_id)?;
+                if !missing.is_empty() {
+                    let missing: Vec<&str> = missing.iter().map(|id| id.as_str()).collect();
+                    write!(f, "; missing or failed: {}", missing.join(", "))?;
+                }
+                if !cycle.is_empty() {
+                    let cycle: Vec<&str> = cycle.iter().map(|id| id.as_str()).collect();
+                    write!(f, "; cycle: {}", cycle.join(" -> "))?;
+                }
+                Ok(())
+            }
             Self::PluginNotFound { plugin_id } => {
                 write!(f, "Plugin not found: {}", plugin_id)
             }
+            Self::PluginIdChanged { expected, actual } => {
+                write!(
+                    f,
+                    "Reloaded plugin reports ID {} instead of {}",
+                    actual, expected
+                )
+            }
             Self::FileTypeNotFound { file_type_id } => {
                 write!(f, "File type not found: {}", file_type_id)
             }
             Self::EditorNotFound { editor_id } => {
                 write!(f, "Editor not found: {}", editor_id)
             }
+            Self::CommandNotFound {
+                plugin_id,
+                command_id,
+            } => {
+                write!(
+                    f,
+                    "Command not found: {} in plugin {}",
+                    command_id, plugin_id
+                )
+            }
             Self::NoFileTypeForPath { path } => {
                 write!(f, "No file type registered for path: {:?}", path)
             }
             Self::NoEditorForFileType { file_type_id } => {
                 write!(f, "No editor registered for file type: {}", file_type_id)
             }
+            Self::MultipleEditorsAvailable {
+                file_type_id,
+                editors,
+            } => {
+                let editors: Vec<&str> = editors.iter().map(|id| id.as_str()).collect();
+                write!(
+                    f,
+                    "Several editors can open file type {} and none is the default: {}",
+                    file_type_id,
+                    editors.join(", ")
+                )
+            }
             Self::PluginError { plugin_id, error } => {
                 write!(f, "Plugin error in {}: {}", plugin_id, error)
             }
+            Self::PluginPanicked { plugin_id, context } => {
+                write!(
+                    f,
+                    "Plugin {} panicked in {} and is disabled until it is reloaded",
+                    plugin_id, context
+                )
+            }
+            Self::InvalidPluginSettings { plugin_id, message } => {
+                write!(f, "Invalid settings for plugin {}: {}", plugin_id, message)
+            }
             Self::FileCreationError { path, message } => {
                 write!(f, "Failed to create file {:?}: {}", path, message)
             }
@@ -0,0 +1,231 @@
+//! Plugin load order from declared dependencies.
+//!
+//! [`resolve`] orders a batch of opened plugins so every plugin comes after
+//! the plugins it depends on. A plugin whose dependency is absent,
+//! failed itself, or sits on a dependency cycle is left out with a
+//! [`DependencyFailure`]; the rest of the batch is unaffected.
+
+use plugin_editor_api::{PluginDependency, PluginId};
+use std::collections::{HashMap, HashSet};
+
+/// A plugin awaiting registration.
+pub(crate) struct PluginNode {
+    pub id: PluginId,
+    pub dependencies: Vec<PluginDependency>,
+}
+
+/// Why a plugin cannot be registered.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct DependencyFailure {
+    /// Dependencies that are absent or failed to load.
+    pub missing: Vec<PluginId>,
+    /// The dependency cycle the plugin is part of, starting and ending with
+    /// the same ID.
+    pub cycle: Vec<PluginId>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct LoadOrder {
+    /// Indices into the resolved nodes, dependencies first.
+    pub order: Vec<usize>,
+    /// Nodes that can't be registered, by index.
+    pub failures: Vec<(usize, DependencyFailure)>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Visit {
+    Pending,
+    InProgress,
+    Loaded,
+    Failed,
+}
+
+struct Resolver<'a> {
+    nodes: &'a [PluginNode],
+    loaded: &'a HashSet<PluginId>,
+    by_id: HashMap<&'a PluginId, usize>,
+    visits: Vec<Visit>,
+    stack: Vec<usize>,
+    cycles: HashMap<usize, Vec<PluginId>>,
+    result: LoadOrder,
+}
+
+/// Order `nodes` for registration. Plugins in `loaded` are already
+/// registered and satisfy dependencies on them.
+pub(crate) fn resolve(nodes: &[PluginNode], loaded: &HashSet<PluginId>) -> LoadOrder {
+    let mut by_id = HashMap::new();
+    for (index, node) in nodes.iter().enumerate() {
+        by_id.entry(&node.id).or_insert(index);
+    }
+    let mut resolver = Resolver {
+        nodes,
+        loaded,
+        by_id,
+        visits: vec![Visit::Pending; nodes.len()],
+        stack: Vec::new(),
+        cycles: HashMap::new(),
+        result: LoadOrder::default(),
+    };
+    for index in 0..nodes.len() {
+        resolver.visit(index);
+    }
+    resolver.result.failures.sort_by_key(|(index, _)| *index);
+    resolver.result
+}
+
+impl Resolver<'_> {
+    fn visit(&mut self, index: usize) {
+        if self.visits[index] != Visit::Pending {
+            return;
+        }
+        self.visits[index] = Visit::InProgress;
+        self.stack.push(index);
+
+        let mut failure = DependencyFailure::default();
+        for dependency in &self.nodes[index].dependencies {
+            if self.loaded.contains(&dependency.id) {
+                continue;
+            }
+            let Some(&target) = self.by_id.get(&dependency.id) else {
+                failure.missing.push(dependency.id.clone());
+                continue;
+            };
+            match self.visits[target] {
+                // Back edge: everything on the stack from `target` up is a cycle
+                Visit::InProgress => {
+                    let start = self.stack.iter().position(|i| *i == target).unwrap_or(0);
+                    let mut cycle: Vec<PluginId> = self.stack[start..]
+                        .iter()
+                        .map(|i| self.nodes[*i].id.clone())
+                        .collect();
+                    cycle.push(self.nodes[target].id.clone());
+                    for member in &self.stack[start..] {
+                        self.cycles.entry(*member).or_insert_with(|| cycle.clone());
+                    }
+                }
+                Visit::Pending => {
+                    self.visit(target);
+                    if self.visits[target] == Visit::Failed {
+                        failure.missing.push(dependency.id.clone());
+                    }
+                }
+                Visit::Failed => failure.missing.push(dependency.id.clone()),
+                Visit::Loaded => {}
+            }
+        }
+
+        self.stack.pop();
+        if let Some(cycle) = self.cycles.remove(&index) {
+            // Fellow cycle members aren't reported as missing as well
+            failure.missing.retain(|id| !cycle.contains(id));
+            failure.cycle = cycle;
+        }
+        if failure.missing.is_empty() && failure.cycle.is_empty() {
+            self.visits[index] = Visit::Loaded;
+            self.result.order.push(index);
+        } else {
+            self.visits[index] = Visit::Failed;
+            self.result.failures.push((index, failure));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: &str, dependencies: &[&str]) -> PluginNode {
+        PluginNode {
+            id: PluginId::new(id),
+            dependencies: dependencies
+                .iter()
+                .map(|d| PluginDependency::new(*d))
+                .collect(),
+        }
+    }
+
+    fn ids(nodes: &[PluginNode], indices: &[usize]) -> Vec<String> {
+        indices
+            .iter()
+            .map(|i| nodes[*i].id.as_str().to_string())
+            .collect()
+    }
+
+    fn plugin_ids(ids: &[&str]) -> Vec<PluginId> {
+        ids.iter().map(|id| PluginId::new(*id)).collect()
+    }
+
+    #[test]
+    fn test_diamond_dependencies() {
+        // Listed dependents first, as a directory walk might return them
+        let nodes = vec![
+            node("app", &["left", "right"]),
+            node("left", &["base"]),
+            node("right", &["base"]),
+            node("base", &[]),
+        ];
+        let order = resolve(&nodes, &HashSet::new());
+        assert!(order.failures.is_empty());
+        assert_eq!(ids(&nodes, &order.order), ["base", "left", "right", "app"]);
+    }
+
+    #[test]
+    fn test_missing_dependency_fails_only_dependents() {
+        let nodes = vec![
+            node("extension", &["file-type"]),
+            node("extension-tools", &["extension"]),
+            node("standalone", &[]),
+        ];
+        let order = resolve(&nodes, &HashSet::new());
+        assert_eq!(ids(&nodes, &order.order), ["standalone"]);
+        assert_eq!(
+            order.failures,
+            vec![
+                (
+                    0,
+                    DependencyFailure {
+                        missing: plugin_ids(&["file-type"]),
+                        cycle: Vec::new(),
+                    }
+                ),
+                (
+                    1,
+                    DependencyFailure {
+                        missing: plugin_ids(&["extension"]),
+                        cycle: Vec::new(),
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_already_loaded_dependency() {
+        let nodes = vec![node("extension", &["file-type"])];
+        let loaded = plugin_ids(&["file-type"]).into_iter().collect();
+        let order = resolve(&nodes, &loaded);
+        assert_eq!(order.order, vec![0]);
+        assert!(order.failures.is_empty());
+    }
+
+    #[test]
+    fn test_cycle_fails_members_and_dependents() {
+        let nodes = vec![
+            node("a", &["b"]),
+            node("b", &["c"]),
+            node("c", &["a"]),
+            node("user", &["c"]),
+            node("free", &[]),
+        ];
+        let order = resolve(&nodes, &HashSet::new());
+        assert_eq!(ids(&nodes, &order.order), ["free"]);
+
+        let failures: HashMap<usize, DependencyFailure> = order.failures.into_iter().collect();
+        for member in 0..3 {
+            assert!(failures[&member].missing.is_empty());
+            assert_eq!(failures[&member].cycle, plugin_ids(&["a", "b", "c", "a"]));
+        }
+        assert_eq!(failures[&3].missing, plugin_ids(&["c"]));
+        assert!(failures[&3].cycle.is_empty());
+    }
+}
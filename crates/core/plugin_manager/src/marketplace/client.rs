@@ -0,0 +1,646 @@
+//! The registry client: fetching the index, downloading and installing.
+//!
+//! All network access goes through a [`RegistryTransport`];
+//! [`HttpTransport`] is the real one. Calls block, so UI code runs them on a
+//! background thread.
+
+use plugin_editor_api::VersionInfo;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::download::{Download, DownloadProgress, DownloadState, ResponseAction};
+use super::index::{encode_hex, parse_index, IndexPlugin, RegistryIndex};
+use super::install::{self, io_error, InstalledPlugin};
+use super::resolve::{available_update, current_platform, resolve};
+use super::verify::{hash_file, verify_artifact};
+use super::MarketplaceError;
+
+/// A response body, possibly starting partway into the resource.
+pub struct RangeResponse {
+    /// Offset of the first body byte in the resource
+    pub start: u64,
+    pub body: Box<dyn Read + Send>,
+}
+
+/// Network access for the registry client.
+pub trait RegistryTransport: Send + Sync {
+    /// Fetch a whole document.
+    fn get(&self, url: &str) -> Result<Vec<u8>, String>;
+
+    /// Fetch `url` from byte `offset` on. Servers may ignore the offset and
+    /// send the whole resource, which [`RangeResponse::start`] reports.
+    fn get_from(&self, url: &str, offset: u64) -> Result<RangeResponse, String>;
+}
+
+/// [`RegistryTransport`] over HTTP(S).
+pub struct HttpTransport {
+    client: reqwest::blocking::Client,
+}
+
+impl HttpTransport {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::blocking::Client::builder()
+                .connect_timeout(Duration::from_secs(15))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl Default for HttpTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegistryTransport for HttpTransport {
+    fn get(&self, url: &str) -> Result<Vec<u8>, String> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| e.to_string())?;
+        response
+            .bytes()
+            .map(|bytes| bytes.to_vec())
+            .map_err(|e| e.to_string())
+    }
+
+    fn get_from(&self, url: &str, offset: u64) -> Result<RangeResponse, String> {
+        let mut request = self.client.get(url);
+        if offset > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+        }
+        let response = request
+            .send()
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| e.to_string())?;
+        let start = if response.status() == reqwest::StatusCode::PARTIAL_CONTENT {
+            response
+                .headers()
+                .get(reqwest::header::CONTENT_RANGE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(content_range_start)
+                .ok_or_else(|| "partial response without a valid Content-Range".to_string())?
+        } else {
+            0
+        };
+        Ok(RangeResponse {
+            start,
+            body: Box::new(response),
+        })
+    }
+}
+
+/// First byte offset of a `Content-Range: bytes <start>-<end>/<size>` value.
+fn content_range_start(value: &str) -> Option<u64> {
+    let range = value.trim().strip_prefix("bytes ")?;
+    range.split('-').next()?.trim().parse().ok()
+}
+
+/// The index as last fetched.
+#[derive(Debug, Clone)]
+pub struct IndexSnapshot {
+    pub index: RegistryIndex,
+    pub fetched_at: SystemTime,
+    /// Why the registry couldn't be reached, when this is the cached copy
+    pub offline: Option<String>,
+}
+
+impl IndexSnapshot {
+    /// Whether this is an older copy shown because the registry is
+    /// unreachable.
+    pub fn is_stale(&self) -> bool {
+        self.offline.is_some()
+    }
+}
+
+/// A marketplace install that has a newer compatible build.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginUpdate {
+    pub id: String,
+    pub installed: String,
+    pub available: (u32, u32, u32),
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedIndex {
+    url: String,
+    fetched_at: u64,
+    document: serde_json::Value,
+}
+
+const INDEX_CACHE: &str = "index.json";
+
+/// Installs plugins from one registry.
+pub struct Marketplace {
+    transport: Arc<dyn RegistryTransport>,
+    index_url: String,
+    cache_dir: PathBuf,
+    engine: VersionInfo,
+    platform: String,
+}
+
+impl Marketplace {
+    /// A client for the index at `index_url`, keeping its cached index and
+    /// partial downloads in `cache_dir`.
+    pub fn new(
+        transport: Arc<dyn RegistryTransport>,
+        index_url: impl Into<String>,
+        cache_dir: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            transport,
+            index_url: index_url.into(),
+            cache_dir: cache_dir.into(),
+            engine: VersionInfo::current(),
+            platform: current_platform(),
+        }
+    }
+
+    /// Resolve builds for another engine or platform than this one.
+    pub fn with_target(mut self, engine: VersionInfo, platform: impl Into<String>) -> Self {
+        self.engine = engine;
+        self.platform = platform.into();
+        self
+    }
+
+    pub fn index_url(&self) -> &str {
+        &self.index_url
+    }
+
+    /// Fetch the index, falling back to the cached copy when the registry
+    /// can't be reached.
+    pub fn fetch_index(&self) -> Result<IndexSnapshot, MarketplaceError> {
+        let document = match self.transport.get(&self.index_url) {
+            Ok(document) => document,
+            Err(message) => {
+                return self
+                    .cached_index(message.clone())
+                    .ok_or(MarketplaceError::Transport {
+                        url: self.index_url.clone(),
+                        message,
+                    });
+            }
+        };
+        let index = parse_index(&document)?;
+        let fetched_at = SystemTime::now();
+        if let Ok(document) = serde_json::from_slice(&document) {
+            let cached = CachedIndex {
+                url: self.index_url.clone(),
+                fetched_at: fetched_at
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+                document,
+            };
+            if let Err(e) = std::fs::create_dir_all(&self.cache_dir).and_then(|_| {
+                std::fs::write(
+                    self.cache_dir.join(INDEX_CACHE),
+                    serde_json::to_vec(&cached).unwrap_or_default(),
+                )
+            }) {
+                tracing::warn!("Failed to cache plugin index: {}", e);
+            }
+        }
+        Ok(IndexSnapshot {
+            index,
+            fetched_at,
+            offline: None,
+        })
+    }
+
+    fn cached_index(&self, offline: String) -> Option<IndexSnapshot> {
+        let bytes = std::fs::read(self.cache_dir.join(INDEX_CACHE)).ok()?;
+        let cached: CachedIndex = serde_json::from_slice(&bytes).ok()?;
+        if cached.url != self.index_url {
+            return None;
+        }
+        let index = parse_index(cached.document.to_string().as_bytes()).ok()?;
+        Some(IndexSnapshot {
+            index,
+            fetched_at: UNIX_EPOCH + Duration::from_secs(cached.fetched_at),
+            offline: Some(offline),
+        })
+    }
+
+    /// Download, verify and install the best build of `plugin` into `dir`.
+    ///
+    /// Nothing reaches `dir` unless the download matches the index's size
+    /// and hash and carries a valid publisher signature. The library is
+    /// never loaded here.
+    pub fn install(
+        &self,
+        plugin: &IndexPlugin,
+        dir: &Path,
+        progress: &mut dyn FnMut(DownloadProgress),
+    ) -> Result<InstalledPlugin, MarketplaceError> {
+        let resolved = resolve(plugin, &self.engine, &self.platform).map_err(|reason| {
+            MarketplaceError::Unavailable {
+                plugin_id: plugin.id.clone(),
+                reason,
+            }
+        })?;
+        let artifact = resolved.artifact;
+
+        // Named by hash so index contents never form a path
+        let downloads = self.cache_dir.join("downloads");
+        std::fs::create_dir_all(&downloads).map_err(|e| io_error(&downloads, e))?;
+        let partial = downloads.join(format!("{}.part", encode_hex(&artifact.sha256)));
+        self.download(&artifact.url, &partial, artifact.size, progress)?;
+
+        let (size, sha256) = hash_file(&partial).map_err(|e| io_error(&partial, e))?;
+        if let Err(reason) = verify_artifact(artifact, &plugin.publisher_key, size, &sha256) {
+            let _ = std::fs::remove_file(&partial);
+            return Err(MarketplaceError::Verification {
+                plugin_id: plugin.id.clone(),
+                reason,
+            });
+        }
+
+        let (major, minor, patch) = resolved.version.version;
+        let version = format!("{}.{}.{}", major, minor, patch);
+        let installed = install::install(dir, &partial, &plugin.id, &version, artifact)?;
+        let _ = std::fs::remove_file(&partial);
+        tracing::info!("Installed plugin {} {} into {:?}", plugin.id, version, dir);
+        Ok(installed)
+    }
+
+    fn download(
+        &self,
+        url: &str,
+        partial: &Path,
+        size: u64,
+        progress: &mut dyn FnMut(DownloadProgress),
+    ) -> Result<(), MarketplaceError> {
+        let existing = std::fs::metadata(partial).map(|m| m.len()).unwrap_or(0);
+        let mut download = Download::resume(size, existing);
+        loop {
+            match download.state() {
+                DownloadState::Complete => return Ok(()),
+                DownloadState::Failed { reason } => {
+                    return Err(MarketplaceError::Transport {
+                        url: url.to_string(),
+                        message: reason.clone(),
+                    })
+                }
+                DownloadState::Requesting | DownloadState::Receiving => {}
+            }
+
+            let response = match self.transport.get_from(url, download.request_offset()) {
+                Ok(response) => response,
+                Err(message) => {
+                    download.on_interrupted(message);
+                    continue;
+                }
+            };
+            let mut file = match download.on_response(response.start) {
+                ResponseAction::Append => std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(partial),
+                ResponseAction::Restart => std::fs::File::create(partial),
+            }
+            .map_err(|e| io_error(partial, e))?;
+            progress(download.progress());
+
+            let mut body = response.body;
+            let mut buffer = [0u8; 64 * 1024];
+            loop {
+                match body.read(&mut buffer) {
+                    Ok(0) => {
+                        download.on_end();
+                        break;
+                    }
+                    Ok(count) => {
+                        file.write_all(&buffer[..count])
+                            .map_err(|e| io_error(partial, e))?;
+                        download.on_data(count as u64);
+                        progress(download.progress());
+                        if matches!(download.state(), DownloadState::Failed { .. }) {
+                            // Oversized; the partial file is useless
+                            let _ = std::fs::remove_file(partial);
+                            break;
+                        }
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                    Err(e) => {
+                        download.on_interrupted(e.to_string());
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Marketplace installs in `dir` with a newer compatible build in `index`.
+    pub fn check_updates(
+        &self,
+        index: &RegistryIndex,
+        dir: &Path,
+    ) -> Result<Vec<PluginUpdate>, MarketplaceError> {
+        let mut updates = Vec::new();
+        for installed in install::installed(dir)? {
+            let Some(plugin) = index.plugin(&installed.id) else {
+                continue;
+            };
+            let current = super::index::parse_version(&installed.version).unwrap_or_default();
+            if let Some(update) = available_update(plugin, current, &self.engine, &self.platform) {
+                updates.push(PluginUpdate {
+                    id: installed.id,
+                    installed: installed.version,
+                    available: update.version.version,
+                });
+            }
+        }
+        Ok(updates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::marketplace::index::{Artifact, IndexVersion};
+    use crate::marketplace::verify::VerifyError;
+    use ed25519_dalek::{Signer, SigningKey};
+    use parking_lot::Mutex;
+    use sha2::{Digest, Sha256};
+    use std::collections::HashMap;
+    use std::io::Cursor;
+
+    const PLATFORM: &str = "linux-x86_64";
+    const INDEX_URL: &str = "https://registry.example.com/index.json";
+    const ARTIFACT_URL: &str = "https://registry.example.com/terrain.so";
+
+    /// Serves fixed documents; can go offline, ignore ranges, or cut the
+    /// next artifact response short.
+    #[derive(Default)]
+    struct MockTransport {
+        documents: Mutex<HashMap<String, Vec<u8>>>,
+        offline: Mutex<bool>,
+        ignore_range: bool,
+        cut_next_after: Mutex<Option<usize>>,
+        requested_offsets: Mutex<Vec<u64>>,
+    }
+
+    struct CutReader {
+        data: Cursor<Vec<u8>>,
+        remaining: usize,
+    }
+
+    impl Read for CutReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.remaining == 0 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::ConnectionReset,
+                    "connection reset",
+                ));
+            }
+            let limit = buf.len().min(self.remaining);
+            let count = self.data.read(&mut buf[..limit])?;
+            self.remaining -= count;
+            Ok(count)
+        }
+    }
+
+    impl MockTransport {
+        fn document(&self, url: &str) -> Result<Vec<u8>, String> {
+            if *self.offline.lock() {
+                return Err("network unreachable".to_string());
+            }
+            self.documents
+                .lock()
+                .get(url)
+                .cloned()
+                .ok_or_else(|| "404 Not Found".to_string())
+        }
+    }
+
+    impl RegistryTransport for MockTransport {
+        fn get(&self, url: &str) -> Result<Vec<u8>, String> {
+            self.document(url)
+        }
+
+        fn get_from(&self, url: &str, offset: u64) -> Result<RangeResponse, String> {
+            self.requested_offsets.lock().push(offset);
+            let document = self.document(url)?;
+            let start = if self.ignore_range { 0 } else { offset };
+            let data = Cursor::new(document[start as usize..].to_vec());
+            let body: Box<dyn Read + Send> = match self.cut_next_after.lock().take() {
+                Some(remaining) => Box::new(CutReader { data, remaining }),
+                None => Box::new(data),
+            };
+            Ok(RangeResponse { start, body })
+        }
+    }
+
+    fn signed_plugin(key: &SigningKey, contents: &[u8], version: (u32, u32, u32)) -> IndexPlugin {
+        let sha256: [u8; 32] = Sha256::digest(contents).into();
+        IndexPlugin {
+            id: "com.example.terrain".to_string(),
+            name: "Terrain".to_string(),
+            description: String::new(),
+            publisher: "Example".to_string(),
+            publisher_key: key.verifying_key().to_bytes(),
+            versions: vec![IndexVersion {
+                version,
+                min_engine_version: VersionInfo::current().engine_version,
+                api_version: VersionInfo::current().api_version,
+                artifacts: vec![Artifact {
+                    platform: PLATFORM.to_string(),
+                    url: ARTIFACT_URL.to_string(),
+                    file_name: "terrain.so".to_string(),
+                    size: contents.len() as u64,
+                    sha256,
+                    signature: key.sign(&sha256).to_bytes(),
+                }],
+            }],
+        }
+    }
+
+    fn marketplace(transport: &Arc<MockTransport>, cache: &Path) -> Marketplace {
+        Marketplace::new(transport.clone(), INDEX_URL, cache)
+            .with_target(VersionInfo::current(), PLATFORM)
+    }
+
+    fn serve_artifact(transport: &MockTransport, contents: &[u8]) {
+        transport
+            .documents
+            .lock()
+            .insert(ARTIFACT_URL.to_string(), contents.to_vec());
+    }
+
+    #[test]
+    fn test_install_resumes_interrupted_download() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let contents: Vec<u8> = (0..200_000u32).map(|i| i as u8).collect();
+        let transport = Arc::new(MockTransport::default());
+        serve_artifact(&transport, &contents);
+        *transport.cut_next_after.lock() = Some(70_000);
+
+        let key = SigningKey::from_bytes(&[3; 32]);
+        let plugin = signed_plugin(&key, &contents, (1, 0, 0));
+        let mut reports = Vec::new();
+        let installed = marketplace(&transport, &root.join("cache"))
+            .install(&plugin, &root.join("plugins"), &mut |p| reports.push(p))
+            .unwrap();
+
+        assert_eq!(*transport.requested_offsets.lock(), vec![0, 70_000]);
+        assert_eq!(reports.last().unwrap().fraction(), 1.0);
+        assert_eq!(installed.version, "1.0.0");
+        assert_eq!(
+            std::fs::read(root.join("plugins/terrain.so")).unwrap(),
+            contents
+        );
+        // The partial download is cleaned up
+        assert_eq!(
+            std::fs::read_dir(root.join("cache/downloads"))
+                .unwrap()
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_restarts_when_server_ignores_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let contents = b"complete plugin library".to_vec();
+        let transport = Arc::new(MockTransport {
+            ignore_range: true,
+            ..Default::default()
+        });
+        serve_artifact(&transport, &contents);
+        *transport.cut_next_after.lock() = Some(5);
+
+        let key = SigningKey::from_bytes(&[3; 32]);
+        let plugin = signed_plugin(&key, &contents, (1, 0, 0));
+        marketplace(&transport, &root.join("cache"))
+            .install(&plugin, &root.join("plugins"), &mut |_| {})
+            .unwrap();
+        assert_eq!(
+            std::fs::read(root.join("plugins/terrain.so")).unwrap(),
+            contents
+        );
+    }
+
+    #[test]
+    fn test_tampered_artifact_never_reaches_plugins_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let key = SigningKey::from_bytes(&[3; 32]);
+        let plugin = signed_plugin(&key, b"genuine", (1, 0, 0));
+        let transport = Arc::new(MockTransport::default());
+        serve_artifact(&transport, b"evil!!!");
+
+        let error = marketplace(&transport, &root.join("cache"))
+            .install(&plugin, &root.join("plugins"), &mut |_| {})
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            MarketplaceError::Verification {
+                reason: VerifyError::HashMismatch { .. },
+                ..
+            }
+        ));
+        assert!(!root.join("plugins").exists());
+        // The bad download isn't resumed next time
+        assert_eq!(
+            std::fs::read_dir(root.join("cache/downloads"))
+                .unwrap()
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_wrong_platform_downloads_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let key = SigningKey::from_bytes(&[3; 32]);
+        let plugin = signed_plugin(&key, b"plugin", (1, 0, 0));
+        let transport = Arc::new(MockTransport::default());
+        serve_artifact(&transport, b"plugin");
+
+        let error = Marketplace::new(transport.clone(), INDEX_URL, root.join("cache"))
+            .with_target(VersionInfo::current(), "windows-x86_64")
+            .install(&plugin, &root.join("plugins"), &mut |_| {})
+            .unwrap_err();
+        assert!(matches!(error, MarketplaceError::Unavailable { .. }));
+        assert!(transport.requested_offsets.lock().is_empty());
+    }
+
+    #[test]
+    fn test_offline_index_falls_back_to_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let transport = Arc::new(MockTransport::default());
+        let marketplace = marketplace(&transport, root);
+        *transport.offline.lock() = true;
+        assert!(matches!(
+            marketplace.fetch_index(),
+            Err(MarketplaceError::Transport { .. })
+        ));
+
+        *transport.offline.lock() = false;
+        transport.documents.lock().insert(
+            INDEX_URL.to_string(),
+            br#"{"format": 1, "plugins": []}"#.to_vec(),
+        );
+        assert!(!marketplace.fetch_index().unwrap().is_stale());
+
+        *transport.offline.lock() = true;
+        let snapshot = marketplace.fetch_index().unwrap();
+        assert!(snapshot.is_stale());
+        assert_eq!(snapshot.offline.as_deref(), Some("network unreachable"));
+    }
+
+    #[test]
+    fn test_update_check_and_uninstall() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let plugins = root.join("plugins");
+        let key = SigningKey::from_bytes(&[3; 32]);
+        let transport = Arc::new(MockTransport::default());
+        serve_artifact(&transport, b"v1");
+        let marketplace = marketplace(&transport, &root.join("cache"));
+        marketplace
+            .install(
+                &signed_plugin(&key, b"v1", (1, 0, 0)),
+                &plugins,
+                &mut |_| {},
+            )
+            .unwrap();
+
+        let index = RegistryIndex {
+            plugins: vec![signed_plugin(&key, b"v2", (1, 1, 0))],
+        };
+        assert_eq!(
+            marketplace.check_updates(&index, &plugins).unwrap(),
+            vec![PluginUpdate {
+                id: "com.example.terrain".to_string(),
+                installed: "1.0.0".to_string(),
+                available: (1, 1, 0),
+            }]
+        );
+
+        install::uninstall(&plugins, "com.example.terrain").unwrap();
+        assert!(!plugins.join("terrain.so").exists());
+        assert!(marketplace
+            .check_updates(&index, &plugins)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_content_range_start() {
+        assert_eq!(content_range_start("bytes 100-999/1000"), Some(100));
+        assert_eq!(content_range_start("bytes */1000"), None);
+    }
+}
@@ -0,0 +1,224 @@
+//! Resumable downloads.
+//!
+//! [`Download`] tracks how much of an artifact sits in its partial file and
+//! decides what each response means for it. Requests ask for the bytes from
+//! [`request_offset`](Download::request_offset) on; a server that ignores
+//! the range and sends the whole body restarts the file instead of
+//! corrupting it. Interrupted transfers are retried from where they stopped
+//! until [`MAX_ATTEMPTS`] in a row make no progress. The partial file
+//! survives across sessions, so a download cut off by closing the editor
+//! also resumes.
+
+/// Consecutive attempts without progress before a download is abandoned.
+pub const MAX_ATTEMPTS: u32 = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownloadState {
+    /// Waiting for the next request to be sent
+    Requesting,
+    /// A response body is being written to the partial file
+    Receiving,
+    Complete,
+    Failed {
+        reason: String,
+    },
+}
+
+/// What to do with the partial file when a response starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseAction {
+    /// The body continues the partial file
+    Append,
+    /// The body starts from the beginning; truncate the partial file first
+    Restart,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DownloadProgress {
+    pub received: u64,
+    pub total: u64,
+}
+
+impl DownloadProgress {
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            (self.received as f64 / self.total as f64) as f32
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Download {
+    total: u64,
+    received: u64,
+    /// Bytes received when the current run of failed attempts started
+    received_at_failure: u64,
+    failed_attempts: u32,
+    state: DownloadState,
+}
+
+impl Download {
+    /// Start or resume a download of `total` bytes with `partial` bytes
+    /// already on disk.
+    ///
+    /// A partial file longer than the artifact can't be a prefix of it and
+    /// is restarted.
+    pub fn resume(total: u64, partial: u64) -> Self {
+        let received = if partial > total { 0 } else { partial };
+        let state = if partial == total {
+            DownloadState::Complete
+        } else {
+            DownloadState::Requesting
+        };
+        Self {
+            total,
+            received,
+            received_at_failure: received,
+            failed_attempts: 0,
+            state,
+        }
+    }
+
+    pub fn state(&self) -> &DownloadState {
+        &self.state
+    }
+
+    pub fn progress(&self) -> DownloadProgress {
+        DownloadProgress {
+            received: self.received,
+            total: self.total,
+        }
+    }
+
+    /// Offset to request the remaining bytes from.
+    pub fn request_offset(&self) -> u64 {
+        self.received
+    }
+
+    /// A response body starting at byte `start` of the artifact arrived.
+    pub fn on_response(&mut self, start: u64) -> ResponseAction {
+        self.state = DownloadState::Receiving;
+        if start == self.received {
+            ResponseAction::Append
+        } else {
+            // The server ignored the range (or sent a different one); only a
+            // body from the very start can be used
+            self.received = 0;
+            self.received_at_failure = 0;
+            ResponseAction::Restart
+        }
+    }
+
+    /// `len` more bytes were written to the partial file.
+    pub fn on_data(&mut self, len: u64) {
+        self.received += len;
+        if self.received > self.total {
+            self.state = DownloadState::Failed {
+                reason: format!(
+                    "server sent more than the {} bytes listed in the index",
+                    self.total
+                ),
+            };
+        }
+    }
+
+    /// The response body ended.
+    pub fn on_end(&mut self) {
+        if self.received == self.total {
+            self.state = DownloadState::Complete;
+        } else {
+            self.on_interrupted(format!(
+                "connection closed after {} of {} bytes",
+                self.received, self.total
+            ));
+        }
+    }
+
+    /// The request failed or the body stopped mid-transfer.
+    pub fn on_interrupted(&mut self, reason: String) {
+        if matches!(self.state, DownloadState::Failed { .. }) {
+            return;
+        }
+        if self.received > self.received_at_failure {
+            self.failed_attempts = 0;
+            self.received_at_failure = self.received;
+        }
+        self.failed_attempts += 1;
+        self.state = if self.failed_attempts >= MAX_ATTEMPTS {
+            DownloadState::Failed {
+                reason: format!("{} (gave up after {} attempts)", reason, MAX_ATTEMPTS),
+            }
+        } else {
+            DownloadState::Requesting
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resumes_after_interruption() {
+        let mut download = Download::resume(100, 0);
+        assert_eq!(download.request_offset(), 0);
+        assert_eq!(download.on_response(0), ResponseAction::Append);
+        download.on_data(40);
+        download.on_interrupted("reset".to_string());
+        assert_eq!(download.state(), &DownloadState::Requesting);
+
+        assert_eq!(download.request_offset(), 40);
+        assert_eq!(download.on_response(40), ResponseAction::Append);
+        download.on_data(60);
+        download.on_end();
+        assert_eq!(download.state(), &DownloadState::Complete);
+        assert_eq!(download.progress().fraction(), 1.0);
+    }
+
+    #[test]
+    fn test_restarts_when_range_ignored() {
+        // 30 bytes left over from an earlier session
+        let mut download = Download::resume(100, 30);
+        assert_eq!(download.request_offset(), 30);
+        assert_eq!(download.on_response(0), ResponseAction::Restart);
+        assert_eq!(download.progress().received, 0);
+        download.on_data(100);
+        download.on_end();
+        assert_eq!(download.state(), &DownloadState::Complete);
+
+        // A partial file longer than the artifact is discarded
+        assert_eq!(Download::resume(100, 120).request_offset(), 0);
+        assert_eq!(Download::resume(100, 100).state(), &DownloadState::Complete);
+    }
+
+    #[test]
+    fn test_gives_up_without_progress() {
+        let mut download = Download::resume(100, 0);
+        for _ in 0..MAX_ATTEMPTS - 1 {
+            download.on_interrupted("timeout".to_string());
+            assert_eq!(download.state(), &DownloadState::Requesting);
+        }
+        // Progress resets the count
+        download.on_response(0);
+        download.on_data(10);
+        download.on_end();
+        assert_eq!(download.state(), &DownloadState::Requesting);
+        for _ in 0..MAX_ATTEMPTS - 1 {
+            download.on_interrupted("timeout".to_string());
+        }
+        assert!(matches!(download.state(), DownloadState::Failed { .. }));
+    }
+
+    #[test]
+    fn test_oversized_body_fails() {
+        let mut download = Download::resume(10, 0);
+        download.on_response(0);
+        download.on_data(11);
+        assert!(matches!(download.state(), DownloadState::Failed { .. }));
+        // Later events don't revive it
+        download.on_interrupted("closed".to_string());
+        assert!(matches!(download.state(), DownloadState::Failed { .. }));
+    }
+}
@@ -0,0 +1,312 @@
+//! The registry index format.
+//!
+//! A registry publishes one JSON document listing every plugin it carries:
+//!
+//! ```json
+//! {
+//!   "format": 1,
+//!   "plugins": [{
+//!     "id": "com.example.terrain-tools",
+//!     "name": "Terrain Tools",
+//!     "description": "Sculpting and painting brushes",
+//!     "publisher": "Example Studio",
+//!     "publisher_key": "<32-byte ed25519 public key, hex>",
+//!     "versions": [{
+//!       "version": "1.2.0",
+//!       "min_engine_version": "0.4.0",
+//!       "api_version": 1,
+//!       "artifacts": [{
+//!         "platform": "windows-x86_64",
+//!         "url": "https://example.com/terrain_tools-1.2.0.dll",
+//!         "file_name": "terrain_tools.dll",
+//!         "size": 1048576,
+//!         "sha256": "<hex>",
+//!         "signature": "<64-byte ed25519 signature over the raw SHA-256, hex>"
+//!       }]
+//!     }]
+//!   }]
+//! }
+//! ```
+//!
+//! [`parse_index`] rejects a document with a malformed key, hash, signature,
+//! version or file name up front, so the rest of the pipeline works with
+//! decoded values only.
+
+use serde::Deserialize;
+
+use super::MarketplaceError;
+
+/// Index format version this client understands.
+pub const INDEX_FORMAT: u32 = 1;
+
+/// A parsed registry index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistryIndex {
+    pub plugins: Vec<IndexPlugin>,
+}
+
+impl RegistryIndex {
+    pub fn plugin(&self, id: &str) -> Option<&IndexPlugin> {
+        self.plugins.iter().find(|plugin| plugin.id == id)
+    }
+}
+
+/// A plugin listed in the index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexPlugin {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub publisher: String,
+    /// Ed25519 public key every artifact of this plugin must be signed with.
+    pub publisher_key: [u8; 32],
+    pub versions: Vec<IndexVersion>,
+}
+
+/// One published version of a plugin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexVersion {
+    pub version: (u32, u32, u32),
+    /// Engine version the plugin was built against
+    pub min_engine_version: (u32, u32, u32),
+    /// Plugin API version the plugin was built against
+    pub api_version: u32,
+    pub artifacts: Vec<Artifact>,
+}
+
+/// A downloadable build of a plugin version for one platform.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Artifact {
+    /// `<os>-<arch>`, as returned by [`current_platform`](super::current_platform)
+    pub platform: String,
+    pub url: String,
+    /// Name to install the library under in the plugins directory
+    pub file_name: String,
+    pub size: u64,
+    pub sha256: [u8; 32],
+    /// Publisher signature over the raw bytes of `sha256`
+    pub signature: [u8; 64],
+}
+
+#[derive(Deserialize)]
+struct RawIndex {
+    format: u32,
+    plugins: Vec<RawPlugin>,
+}
+
+#[derive(Deserialize)]
+struct RawPlugin {
+    id: String,
+    name: String,
+    #[serde(default)]
+    description: String,
+    publisher: String,
+    publisher_key: String,
+    versions: Vec<RawVersion>,
+}
+
+#[derive(Deserialize)]
+struct RawVersion {
+    version: String,
+    min_engine_version: String,
+    api_version: u32,
+    artifacts: Vec<RawArtifact>,
+}
+
+#[derive(Deserialize)]
+struct RawArtifact {
+    platform: String,
+    url: String,
+    file_name: String,
+    size: u64,
+    sha256: String,
+    signature: String,
+}
+
+/// Parse and validate an index document.
+pub fn parse_index(json: &[u8]) -> Result<RegistryIndex, MarketplaceError> {
+    let raw: RawIndex = serde_json::from_slice(json)
+        .map_err(|e| MarketplaceError::InvalidIndex(format!("invalid JSON: {}", e)))?;
+    if raw.format != INDEX_FORMAT {
+        return Err(MarketplaceError::InvalidIndex(format!(
+            "unsupported index format {} (expected {})",
+            raw.format, INDEX_FORMAT
+        )));
+    }
+    let plugins = raw
+        .plugins
+        .into_iter()
+        .map(parse_plugin)
+        .collect::<Result<_, _>>()?;
+    Ok(RegistryIndex { plugins })
+}
+
+fn parse_plugin(raw: RawPlugin) -> Result<IndexPlugin, MarketplaceError> {
+    let invalid =
+        |message: String| MarketplaceError::InvalidIndex(format!("{}: {}", raw.id, message));
+    let publisher_key = decode_hex::<32>(&raw.publisher_key)
+        .map_err(|e| invalid(format!("publisher_key {}", e)))?;
+    let mut versions = Vec::with_capacity(raw.versions.len());
+    for version in &raw.versions {
+        let parsed = parse_version(&version.version)
+            .ok_or_else(|| invalid(format!("invalid version '{}'", version.version)))?;
+        let min_engine_version = parse_version(&version.min_engine_version).ok_or_else(|| {
+            invalid(format!(
+                "invalid min_engine_version '{}'",
+                version.min_engine_version
+            ))
+        })?;
+        let mut artifacts = Vec::with_capacity(version.artifacts.len());
+        for artifact in &version.artifacts {
+            let context = format!("{} {}", version.version, artifact.platform);
+            if !is_plain_file_name(&artifact.file_name) {
+                return Err(invalid(format!(
+                    "{}: file_name '{}' is not a plain file name",
+                    context, artifact.file_name
+                )));
+            }
+            artifacts.push(Artifact {
+                platform: artifact.platform.clone(),
+                url: artifact.url.clone(),
+                file_name: artifact.file_name.clone(),
+                size: artifact.size,
+                sha256: decode_hex::<32>(&artifact.sha256)
+                    .map_err(|e| invalid(format!("{}: sha256 {}", context, e)))?,
+                signature: decode_hex::<64>(&artifact.signature)
+                    .map_err(|e| invalid(format!("{}: signature {}", context, e)))?,
+            });
+        }
+        versions.push(IndexVersion {
+            version: parsed,
+            min_engine_version,
+            api_version: version.api_version,
+            artifacts,
+        });
+    }
+    Ok(IndexPlugin {
+        id: raw.id,
+        name: raw.name,
+        description: raw.description,
+        publisher: raw.publisher,
+        publisher_key,
+        versions,
+    })
+}
+
+/// Parse `major.minor.patch`. Pre-release and build suffixes are not
+/// supported; the index lists release builds only.
+pub fn parse_version(version: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = version
+        .trim()
+        .split('.')
+        .map(|part| part.parse::<u32>().ok());
+    let version = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(version)
+}
+
+/// Whether `name` can be joined onto the plugins directory without leaving
+/// it or shadowing the integrity manifest's special keys.
+fn is_plain_file_name(name: &str) -> bool {
+    !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name.starts_with("__")
+        && !name.contains(['/', '\\', ':'])
+        && name != super::install::INTEGRITY_MANIFEST
+}
+
+/// Decode exactly `N` bytes of hex.
+pub(crate) fn decode_hex<const N: usize>(hex: &str) -> Result<[u8; N], String> {
+    let hex = hex.trim();
+    if hex.len() != N * 2 || !hex.is_ascii() {
+        return Err(format!("must be {} hex characters", N * 2));
+    }
+    let mut out = [0u8; N];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+            .map_err(|_| format!("has invalid hex at position {}", i * 2))?;
+    }
+    Ok(out)
+}
+
+pub(crate) fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn raw_index(sha256: &str, signature: &str, file_name: &str) -> serde_json::Value {
+        json!({
+            "format": 1,
+            "plugins": [{
+                "id": "com.example.terrain",
+                "name": "Terrain",
+                "publisher": "Example",
+                "publisher_key": "11".repeat(32),
+                "versions": [{
+                    "version": "1.2.0",
+                    "min_engine_version": "0.4.0",
+                    "api_version": 1,
+                    "artifacts": [{
+                        "platform": "linux-x86_64",
+                        "url": "https://example.com/terrain.so",
+                        "file_name": file_name,
+                        "size": 3,
+                        "sha256": sha256,
+                        "signature": signature,
+                    }]
+                }]
+            }]
+        })
+    }
+
+    fn parse(value: serde_json::Value) -> Result<RegistryIndex, MarketplaceError> {
+        parse_index(value.to_string().as_bytes())
+    }
+
+    #[test]
+    fn test_parse_index() {
+        let index = parse(raw_index(&"ab".repeat(32), &"cd".repeat(64), "terrain.so")).unwrap();
+        let plugin = index.plugin("com.example.terrain").unwrap();
+        assert_eq!(plugin.publisher_key, [0x11; 32]);
+        assert_eq!(plugin.description, "");
+        let version = &plugin.versions[0];
+        assert_eq!(version.version, (1, 2, 0));
+        assert_eq!(version.min_engine_version, (0, 4, 0));
+        assert_eq!(version.artifacts[0].sha256, [0xab; 32]);
+        assert_eq!(version.artifacts[0].signature, [0xcd; 64]);
+    }
+
+    #[test]
+    fn test_rejects_malformed_entries() {
+        let sha = "ab".repeat(32);
+        let sig = "cd".repeat(64);
+        // Short hash, bad hex in the signature
+        assert!(parse(raw_index("abcd", &sig, "terrain.so")).is_err());
+        assert!(parse(raw_index(&sha, &"zz".repeat(64), "terrain.so")).is_err());
+        // File names that would escape the plugins directory or replace the manifest
+        for name in [
+            "../terrain.so",
+            "sub/terrain.so",
+            "..",
+            "plugin_integrity.json",
+        ] {
+            assert!(parse(raw_index(&sha, &sig, name)).is_err(), "{}", name);
+        }
+
+        let mut future = raw_index(&sha, &sig, "terrain.so");
+        future["format"] = json!(2);
+        assert!(parse(future).is_err());
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("1.2.3"), Some((1, 2, 3)));
+        assert_eq!(parse_version("1.2"), None);
+        assert_eq!(parse_version("1.2.3.4"), None);
+        assert_eq!(parse_version("1.2.x"), None);
+    }
+}
@@ -0,0 +1,245 @@
+//! Installing verified artifacts into a plugins directory.
+//!
+//! Installing only moves files: the library is copied in under its index
+//! file name, its hash is added to the directory's integrity manifest (the
+//! same `plugin_integrity.json` the loader checks before opening a library),
+//! and the install is recorded so update checks and uninstall know which
+//! files the marketplace owns. Nothing is loaded; a newly installed plugin is
+//! picked up the next time its directory is loaded.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::index::{encode_hex, Artifact};
+use super::MarketplaceError;
+
+/// Integrity manifest file name, shared with the loader.
+pub const INTEGRITY_MANIFEST: &str = "plugin_integrity.json";
+
+/// Record of marketplace installs in a plugins directory.
+pub const INSTALL_RECORD: &str = "marketplace_installs.json";
+
+/// A plugin the marketplace installed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstalledPlugin {
+    pub id: String,
+    pub version: String,
+    pub platform: String,
+    pub file_name: String,
+    /// SHA-256 of the installed library, hex
+    pub sha256: String,
+}
+
+/// Marketplace installs in `dir`.
+pub fn installed(dir: &Path) -> Result<Vec<InstalledPlugin>, MarketplaceError> {
+    read_json(&dir.join(INSTALL_RECORD))
+}
+
+/// Move the verified artifact at `verified` into `dir`.
+///
+/// A file of the same name that the marketplace didn't install for this
+/// plugin is never overwritten. An earlier version of the plugin is replaced.
+pub fn install(
+    dir: &Path,
+    verified: &Path,
+    plugin_id: &str,
+    version: &str,
+    artifact: &Artifact,
+) -> Result<InstalledPlugin, MarketplaceError> {
+    std::fs::create_dir_all(dir).map_err(|e| io_error(dir, e))?;
+    let mut records = installed(dir)?;
+    let previous = records.iter().position(|record| record.id == plugin_id);
+
+    let target = dir.join(&artifact.file_name);
+    let owned_by_plugin = previous.is_some_and(|i| records[i].file_name == artifact.file_name);
+    if target.exists() && !owned_by_plugin {
+        return Err(MarketplaceError::FileConflict { path: target });
+    }
+
+    // Copy beside the target first so the rename into place is atomic
+    let staging = dir.join(format!(".{}.installing", artifact.file_name));
+    std::fs::copy(verified, &staging).map_err(|e| io_error(&staging, e))?;
+    std::fs::rename(&staging, &target).map_err(|e| {
+        let _ = std::fs::remove_file(&staging);
+        io_error(&target, e)
+    })?;
+
+    let sha256 = encode_hex(&artifact.sha256);
+    let mut manifest: BTreeMap<String, String> = read_json(&dir.join(INTEGRITY_MANIFEST))?;
+    if let Some(i) = previous {
+        let old = records.remove(i);
+        if old.file_name != artifact.file_name {
+            manifest.remove(&old.file_name);
+            remove_if_exists(&dir.join(&old.file_name))?;
+        }
+    }
+    manifest.insert(artifact.file_name.clone(), sha256.clone());
+    write_json(&dir.join(INTEGRITY_MANIFEST), &manifest)?;
+
+    let record = InstalledPlugin {
+        id: plugin_id.to_string(),
+        version: version.to_string(),
+        platform: artifact.platform.clone(),
+        file_name: artifact.file_name.clone(),
+        sha256,
+    };
+    records.push(record.clone());
+    write_json(&dir.join(INSTALL_RECORD), &records)?;
+    Ok(record)
+}
+
+/// Remove a marketplace-installed plugin from `dir`.
+///
+/// A plugin that is currently loaded stays loaded until the editor restarts;
+/// the running process has its own shadow copy of the library.
+pub fn uninstall(dir: &Path, plugin_id: &str) -> Result<InstalledPlugin, MarketplaceError> {
+    let mut records = installed(dir)?;
+    let index = records
+        .iter()
+        .position(|record| record.id == plugin_id)
+        .ok_or_else(|| MarketplaceError::NotInstalled {
+            plugin_id: plugin_id.to_string(),
+        })?;
+    let record = records.remove(index);
+
+    remove_if_exists(&dir.join(&record.file_name))?;
+    let mut manifest: BTreeMap<String, String> = read_json(&dir.join(INTEGRITY_MANIFEST))?;
+    manifest.remove(&record.file_name);
+    write_json(&dir.join(INTEGRITY_MANIFEST), &manifest)?;
+    write_json(&dir.join(INSTALL_RECORD), &records)?;
+    Ok(record)
+}
+
+fn read_json<T: Default + DeserializeOwned>(path: &Path) -> Result<T, MarketplaceError> {
+    match std::fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes).map_err(|e| MarketplaceError::Io {
+            path: path.to_path_buf(),
+            message: format!("invalid JSON: {}", e),
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(T::default()),
+        Err(e) => Err(io_error(path, e)),
+    }
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), MarketplaceError> {
+    let json = serde_json::to_vec_pretty(value).map_err(|e| MarketplaceError::Io {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+    let temp = path.with_extension("json.tmp");
+    std::fs::write(&temp, json).map_err(|e| io_error(&temp, e))?;
+    std::fs::rename(&temp, path).map_err(|e| io_error(path, e))
+}
+
+fn remove_if_exists(path: &Path) -> Result<(), MarketplaceError> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(io_error(path, e)),
+        _ => Ok(()),
+    }
+}
+
+pub(super) fn io_error(path: &Path, error: std::io::Error) -> MarketplaceError {
+    MarketplaceError::Io {
+        path: PathBuf::from(path),
+        message: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn artifact(file_name: &str, sha256: u8) -> Artifact {
+        Artifact {
+            platform: "linux-x86_64".to_string(),
+            url: String::new(),
+            file_name: file_name.to_string(),
+            size: 0,
+            sha256: [sha256; 32],
+            signature: [0; 64],
+        }
+    }
+
+    fn manifest(dir: &Path) -> BTreeMap<String, String> {
+        read_json(&dir.join(INTEGRITY_MANIFEST)).unwrap()
+    }
+
+    #[test]
+    fn test_install_update_uninstall() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let plugins = root.join("plugins");
+        let download = root.join("download");
+        std::fs::write(&download, b"v1").unwrap();
+        std::fs::create_dir_all(&plugins).unwrap();
+        // Hand-installed plugins already listed in the manifest are kept
+        std::fs::write(
+            plugins.join(INTEGRITY_MANIFEST),
+            r#"{"local.so": "00", "__allow_unlisted__": "true"}"#,
+        )
+        .unwrap();
+
+        install(
+            &plugins,
+            &download,
+            "terrain",
+            "1.0.0",
+            &artifact("terrain.so", 1),
+        )
+        .unwrap();
+        assert_eq!(std::fs::read(plugins.join("terrain.so")).unwrap(), b"v1");
+        assert_eq!(manifest(&plugins)["terrain.so"], "01".repeat(32));
+        assert_eq!(manifest(&plugins)["local.so"], "00");
+
+        // An update under a new file name replaces the old library
+        std::fs::write(&download, b"v2").unwrap();
+        install(
+            &plugins,
+            &download,
+            "terrain",
+            "1.1.0",
+            &artifact("terrain_v2.so", 2),
+        )
+        .unwrap();
+        assert!(!plugins.join("terrain.so").exists());
+        assert!(!manifest(&plugins).contains_key("terrain.so"));
+        assert_eq!(installed(&plugins).unwrap()[0].version, "1.1.0");
+
+        let removed = uninstall(&plugins, "terrain").unwrap();
+        assert_eq!(removed.file_name, "terrain_v2.so");
+        assert!(!plugins.join("terrain_v2.so").exists());
+        assert_eq!(manifest(&plugins).len(), 2);
+        assert!(installed(&plugins).unwrap().is_empty());
+        assert!(matches!(
+            uninstall(&plugins, "terrain"),
+            Err(MarketplaceError::NotInstalled { .. })
+        ));
+    }
+
+    #[test]
+    fn test_never_overwrites_foreign_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let download = root.join("download");
+        std::fs::write(&download, b"new").unwrap();
+        std::fs::write(root.join("terrain.so"), b"hand-built").unwrap();
+
+        assert!(matches!(
+            install(
+                root,
+                &download,
+                "terrain",
+                "1.0.0",
+                &artifact("terrain.so", 1)
+            ),
+            Err(MarketplaceError::FileConflict { .. })
+        ));
+        assert_eq!(
+            std::fs::read(root.join("terrain.so")).unwrap(),
+            b"hand-built"
+        );
+        assert!(!root.join(INTEGRITY_MANIFEST).exists());
+    }
+}
@@ -0,0 +1,122 @@
+//! Installing plugins from a registry.
+//!
+//! A registry publishes an index (see [`index`]) of plugins and signed
+//! per-platform builds. [`Marketplace`] fetches and caches it, resolves the
+//! build that suits this engine and platform, downloads it with resumption,
+//! verifies its hash and publisher signature, and only then copies it into
+//! the global or project plugins directory with its integrity manifest
+//! entry. Install never loads the library; the plugin is picked up the next
+//! time the editor loads that directory.
+
+mod client;
+pub mod download;
+pub mod index;
+mod install;
+pub mod resolve;
+pub mod verify;
+
+pub use client::{
+    HttpTransport, IndexSnapshot, Marketplace, PluginUpdate, RangeResponse, RegistryTransport,
+};
+pub use download::DownloadProgress;
+pub use index::{IndexPlugin, RegistryIndex};
+pub use install::{installed, uninstall, InstalledPlugin, INTEGRITY_MANIFEST};
+pub use resolve::{current_platform, ResolveError};
+pub use verify::VerifyError;
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Plugins directory the editor loads at startup, relative to the working
+/// directory.
+pub const GLOBAL_PLUGIN_DIR: &str = "plugins/editor";
+
+/// Where the cached index and partial downloads are kept by default.
+pub fn default_cache_dir() -> PathBuf {
+    std::env::temp_dir().join("pulsar_plugin_marketplace")
+}
+
+/// Where to install a plugin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InstallScope {
+    /// For every project, in [`GLOBAL_PLUGIN_DIR`]
+    #[default]
+    Global,
+    /// For one project, in its `plugins/editor` directory
+    Project,
+}
+
+impl InstallScope {
+    /// The directory to install into, or `None` for [`Project`](Self::Project)
+    /// without an open project.
+    pub fn dir(&self, project_root: Option<&Path>) -> Option<PathBuf> {
+        match self {
+            Self::Global => Some(PathBuf::from(GLOBAL_PLUGIN_DIR)),
+            Self::Project => project_root.map(project_plugin_dir),
+        }
+    }
+}
+
+/// Plugins directory of the project at `project_root`.
+pub fn project_plugin_dir(project_root: &Path) -> PathBuf {
+    project_root.join(GLOBAL_PLUGIN_DIR)
+}
+
+/// Errors from the registry client.
+#[derive(Debug, Clone)]
+pub enum MarketplaceError {
+    /// The registry or artifact host couldn't be reached
+    Transport { url: String, message: String },
+
+    /// The index document is malformed
+    InvalidIndex(String),
+
+    /// No build of the plugin suits this engine and platform
+    Unavailable {
+        plugin_id: String,
+        reason: ResolveError,
+    },
+
+    /// The downloaded artifact failed verification and was discarded
+    Verification {
+        plugin_id: String,
+        reason: VerifyError,
+    },
+
+    /// A file the marketplace doesn't own is in the way
+    FileConflict { path: PathBuf },
+
+    /// The plugin wasn't installed from the marketplace
+    NotInstalled { plugin_id: String },
+
+    /// Filesystem error
+    Io { path: PathBuf, message: String },
+}
+
+impl fmt::Display for MarketplaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transport { url, message } => {
+                write!(f, "Failed to fetch {}: {}", url, message)
+            }
+            Self::InvalidIndex(message) => write!(f, "Invalid plugin index: {}", message),
+            Self::Unavailable { plugin_id, reason } => {
+                write!(f, "Cannot install {}: {}", plugin_id, reason)
+            }
+            Self::Verification { plugin_id, reason } => {
+                write!(f, "Refusing to install {}: {}", plugin_id, reason)
+            }
+            Self::FileConflict { path } => write!(
+                f,
+                "{} already exists and was not installed by the marketplace",
+                path.display()
+            ),
+            Self::NotInstalled { plugin_id } => {
+                write!(f, "{} was not installed from the marketplace", plugin_id)
+            }
+            Self::Io { path, message } => write!(f, "{}: {}", path.display(), message),
+        }
+    }
+}
+
+impl std::error::Error for MarketplaceError {}
@@ -0,0 +1,247 @@
+//! Picking the build of a plugin to install.
+//!
+//! The newest version that has an artifact for this platform and passes the
+//! same compatibility rules the loader applies ([`VersionInfo::check_compatible`],
+//! without the strict rustc check) wins. An index version's
+//! `min_engine_version` and `api_version` describe the engine the plugin was
+//! built against, exactly like the `VersionInfo` a loaded plugin reports.
+
+use plugin_editor_api::{VersionComponent, VersionInfo};
+use std::fmt;
+
+use super::index::{Artifact, IndexPlugin, IndexVersion};
+
+/// This build's platform, in the index's `<os>-<arch>` form.
+pub fn current_platform() -> String {
+    format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+/// The version and artifact chosen for installation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Resolved<'a> {
+    pub version: &'a IndexVersion,
+    pub artifact: &'a Artifact,
+}
+
+/// Why no build of a plugin can be installed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveError {
+    /// No version has a build for this platform
+    NoArtifactForPlatform {
+        platform: String,
+        available: Vec<String>,
+    },
+    /// There are builds for this platform, but none is compatible with this
+    /// engine. Reports the newest one.
+    Incompatible {
+        version: (u32, u32, u32),
+        built_for: VersionInfo,
+        component: VersionComponent,
+    },
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoArtifactForPlatform {
+                platform,
+                available,
+            } => {
+                write!(f, "no build for {}", platform)?;
+                if !available.is_empty() {
+                    write!(f, " (available: {})", available.join(", "))?;
+                }
+                Ok(())
+            }
+            Self::Incompatible {
+                version: (major, minor, patch),
+                built_for,
+                component,
+            } => {
+                let (engine_major, engine_minor, engine_patch) = built_for.engine_version;
+                write!(
+                    f,
+                    "newest build {}.{}.{} targets engine v{}.{}.{} with plugin API v{}; \
+                     its {} is incompatible with this engine",
+                    major,
+                    minor,
+                    patch,
+                    engine_major,
+                    engine_minor,
+                    engine_patch,
+                    built_for.api_version,
+                    component
+                )
+            }
+        }
+    }
+}
+
+/// Choose the build of `plugin` to install on `platform` for `engine`.
+pub fn resolve<'a>(
+    plugin: &'a IndexPlugin,
+    engine: &VersionInfo,
+    platform: &str,
+) -> Result<Resolved<'a>, ResolveError> {
+    let mut versions: Vec<&IndexVersion> = plugin.versions.iter().collect();
+    versions.sort_by_key(|v| std::cmp::Reverse(v.version));
+
+    let mut newest_incompatible = None;
+    for version in versions {
+        let Some(artifact) = version.artifacts.iter().find(|a| a.platform == platform) else {
+            continue;
+        };
+        let built_for = VersionInfo {
+            engine_version: version.min_engine_version,
+            api_version: version.api_version,
+            rustc_version_hash: engine.rustc_version_hash,
+        };
+        match engine.check_compatible(&built_for, false) {
+            Ok(()) => return Ok(Resolved { version, artifact }),
+            Err(component) => {
+                newest_incompatible.get_or_insert(ResolveError::Incompatible {
+                    version: version.version,
+                    built_for,
+                    component,
+                });
+            }
+        }
+    }
+
+    Err(newest_incompatible.unwrap_or_else(|| {
+        let mut available: Vec<String> = plugin
+            .versions
+            .iter()
+            .flat_map(|v| v.artifacts.iter().map(|a| a.platform.clone()))
+            .collect();
+        available.sort();
+        available.dedup();
+        ResolveError::NoArtifactForPlatform {
+            platform: platform.to_string(),
+            available,
+        }
+    }))
+}
+
+/// The build to update to, if one newer than `installed` can be installed.
+pub fn available_update<'a>(
+    plugin: &'a IndexPlugin,
+    installed: (u32, u32, u32),
+    engine: &VersionInfo,
+    platform: &str,
+) -> Option<Resolved<'a>> {
+    resolve(plugin, engine, platform)
+        .ok()
+        .filter(|resolved| resolved.version.version > installed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use plugin_editor_api::PLUGIN_API_VERSION;
+
+    fn artifact(platform: &str) -> Artifact {
+        Artifact {
+            platform: platform.to_string(),
+            url: format!("https://example.com/{}", platform),
+            file_name: "terrain.so".to_string(),
+            size: 0,
+            sha256: [0; 32],
+            signature: [0; 64],
+        }
+    }
+
+    fn version(
+        version: (u32, u32, u32),
+        engine: (u32, u32, u32),
+        platforms: &[&str],
+    ) -> IndexVersion {
+        IndexVersion {
+            version,
+            min_engine_version: engine,
+            api_version: PLUGIN_API_VERSION,
+            artifacts: platforms.iter().map(|p| artifact(p)).collect(),
+        }
+    }
+
+    fn plugin(versions: Vec<IndexVersion>) -> IndexPlugin {
+        IndexPlugin {
+            id: "com.example.terrain".to_string(),
+            name: "Terrain".to_string(),
+            description: String::new(),
+            publisher: "Example".to_string(),
+            publisher_key: [0; 32],
+            versions,
+        }
+    }
+
+    fn engine(version: (u32, u32, u32)) -> VersionInfo {
+        VersionInfo {
+            engine_version: version,
+            ..VersionInfo::current()
+        }
+    }
+
+    #[test]
+    fn test_picks_newest_compatible_build() {
+        let plugin = plugin(vec![
+            version((1, 0, 0), (1, 1, 0), &["linux-x86_64", "windows-x86_64"]),
+            // Needs a newer engine than 1.2
+            version((1, 2, 0), (1, 3, 0), &["linux-x86_64"]),
+            version((1, 1, 0), (1, 2, 0), &["linux-x86_64"]),
+        ]);
+        let resolved = resolve(&plugin, &engine((1, 2, 5)), "linux-x86_64").unwrap();
+        assert_eq!(resolved.version.version, (1, 1, 0));
+        assert_eq!(resolved.artifact.platform, "linux-x86_64");
+
+        // Only the oldest version has a Windows build
+        let resolved = resolve(&plugin, &engine((1, 2, 5)), "windows-x86_64").unwrap();
+        assert_eq!(resolved.version.version, (1, 0, 0));
+    }
+
+    #[test]
+    fn test_wrong_platform() {
+        let plugin = plugin(vec![version(
+            (1, 0, 0),
+            (1, 0, 0),
+            &["windows-x86_64", "linux-x86_64"],
+        )]);
+        assert_eq!(
+            resolve(&plugin, &engine((1, 0, 0)), "macos-aarch64"),
+            Err(ResolveError::NoArtifactForPlatform {
+                platform: "macos-aarch64".to_string(),
+                available: vec!["linux-x86_64".to_string(), "windows-x86_64".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn test_incompatible_reports_newest_build() {
+        let mut newer_api = version((2, 0, 0), (1, 0, 0), &["linux-x86_64"]);
+        newer_api.api_version = PLUGIN_API_VERSION + 1;
+        let plugin = plugin(vec![
+            version((1, 0, 0), (2, 0, 0), &["linux-x86_64"]),
+            newer_api,
+        ]);
+        let Err(ResolveError::Incompatible {
+            version, component, ..
+        }) = resolve(&plugin, &engine((1, 4, 0)), "linux-x86_64")
+        else {
+            panic!("expected an incompatible build");
+        };
+        assert_eq!(version, (2, 0, 0));
+        assert_eq!(component, VersionComponent::ApiVersion);
+    }
+
+    #[test]
+    fn test_available_update() {
+        let plugin = plugin(vec![
+            version((1, 0, 0), (1, 0, 0), &["linux-x86_64"]),
+            version((1, 1, 0), (1, 0, 0), &["linux-x86_64"]),
+        ]);
+        let engine = engine((1, 0, 0));
+        let update = available_update(&plugin, (1, 0, 0), &engine, "linux-x86_64").unwrap();
+        assert_eq!(update.version.version, (1, 1, 0));
+        assert!(available_update(&plugin, (1, 1, 0), &engine, "linux-x86_64").is_none());
+    }
+}
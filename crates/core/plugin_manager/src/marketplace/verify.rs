@@ -0,0 +1,161 @@
+//! Artifact verification.
+//!
+//! A downloaded artifact is only installed once its size and SHA-256 match
+//! the index and the publisher's Ed25519 signature over that hash checks out
+//! against the key the index lists for the plugin.
+
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use super::index::{encode_hex, Artifact};
+
+/// Why a downloaded artifact was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyError {
+    SizeMismatch {
+        expected: u64,
+        actual: u64,
+    },
+    HashMismatch {
+        expected: [u8; 32],
+        actual: [u8; 32],
+    },
+    /// The index lists a key that isn't a valid Ed25519 point
+    InvalidPublisherKey,
+    /// The signature wasn't made by the publisher key over this hash
+    BadSignature,
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SizeMismatch { expected, actual } => write!(
+                f,
+                "downloaded {} bytes, the index lists {}",
+                actual, expected
+            ),
+            Self::HashMismatch { expected, actual } => write!(
+                f,
+                "sha256 is {}, the index lists {}; the download is corrupt or was tampered with",
+                encode_hex(actual),
+                encode_hex(expected)
+            ),
+            Self::InvalidPublisherKey => write!(f, "the index lists an invalid publisher key"),
+            Self::BadSignature => write!(
+                f,
+                "the artifact is not signed by the publisher key listed in the index"
+            ),
+        }
+    }
+}
+
+/// Check a downloaded artifact of `size` bytes hashing to `sha256` against
+/// its index entry.
+pub fn verify_artifact(
+    artifact: &Artifact,
+    publisher_key: &[u8; 32],
+    size: u64,
+    sha256: &[u8; 32],
+) -> Result<(), VerifyError> {
+    if size != artifact.size {
+        return Err(VerifyError::SizeMismatch {
+            expected: artifact.size,
+            actual: size,
+        });
+    }
+    if sha256 != &artifact.sha256 {
+        return Err(VerifyError::HashMismatch {
+            expected: artifact.sha256,
+            actual: *sha256,
+        });
+    }
+    let key =
+        VerifyingKey::from_bytes(publisher_key).map_err(|_| VerifyError::InvalidPublisherKey)?;
+    let signature = Signature::from_bytes(&artifact.signature);
+    key.verify(sha256, &signature)
+        .map_err(|_| VerifyError::BadSignature)
+}
+
+/// Size and SHA-256 digest of a file, streamed.
+pub fn hash_file(path: &Path) -> std::io::Result<(u64, [u8; 32])> {
+    let mut reader = BufReader::new(std::fs::File::open(path)?);
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 8192];
+    let mut size = 0u64;
+    loop {
+        let count = reader.read(&mut buffer)?;
+        if count == 0 {
+            break;
+        }
+        size += count as u64;
+        hasher.update(&buffer[..count]);
+    }
+    Ok((size, hasher.finalize().into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn signed_artifact(key: &SigningKey, contents: &[u8]) -> Artifact {
+        let sha256: [u8; 32] = Sha256::digest(contents).into();
+        Artifact {
+            platform: "linux-x86_64".to_string(),
+            url: "https://example.com/terrain.so".to_string(),
+            file_name: "terrain.so".to_string(),
+            size: contents.len() as u64,
+            sha256,
+            signature: key.sign(&sha256).to_bytes(),
+        }
+    }
+
+    fn check(artifact: &Artifact, key: &SigningKey, contents: &[u8]) -> Result<(), VerifyError> {
+        let sha256: [u8; 32] = Sha256::digest(contents).into();
+        verify_artifact(
+            artifact,
+            key.verifying_key().as_bytes(),
+            contents.len() as u64,
+            &sha256,
+        )
+    }
+
+    #[test]
+    fn test_accepts_signed_artifact() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let artifact = signed_artifact(&key, b"plugin");
+        assert_eq!(check(&artifact, &key, b"plugin"), Ok(()));
+    }
+
+    #[test]
+    fn test_rejects_tampered_artifact() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let artifact = signed_artifact(&key, b"plugin");
+        assert!(matches!(
+            check(&artifact, &key, b"plugiN"),
+            Err(VerifyError::HashMismatch { .. })
+        ));
+        assert!(matches!(
+            check(&artifact, &key, b"plugin!"),
+            Err(VerifyError::SizeMismatch {
+                expected: 6,
+                actual: 7
+            })
+        ));
+    }
+
+    #[test]
+    fn test_rejects_foreign_signature() {
+        let publisher = SigningKey::from_bytes(&[7; 32]);
+        let attacker = SigningKey::from_bytes(&[8; 32]);
+        // Hash updated to match a replaced artifact, but signed by someone else
+        let artifact = signed_artifact(&attacker, b"evil");
+        assert_eq!(
+            check(&artifact, &publisher, b"evil"),
+            Err(VerifyError::BadSignature)
+        );
+    }
+}
@@ -0,0 +1,189 @@
+//! Creating new files of a registered file type.
+//!
+//! Placeholders in a file type's default content and in the content of its
+//! template files are expanded for the file being created:
+//!
+//! - `{{name}}` — the new file's name without its extension
+//!   (`Player` for `Player.class`)
+//! - `{{date}}` — today's date, `YYYY-MM-DD`
+//!
+//! In JSON default content only string values are expanded, so names with
+//! quotes or backslashes can't break the document.
+
+use plugin_editor_api::{FileStructure, PathTemplate};
+use std::path::Path;
+
+use crate::PluginManagerError;
+
+/// Values substituted for template placeholders.
+#[derive(Debug, Clone)]
+pub(crate) struct TemplateVars {
+    name: String,
+    date: String,
+}
+
+impl TemplateVars {
+    /// Placeholder values for a file about to be created at `path`.
+    pub(crate) fn for_path(path: &Path) -> Self {
+        Self {
+            name: path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            date: chrono::Local::now().format("%Y-%m-%d").to_string(),
+        }
+    }
+
+    fn expand(&self, text: &str) -> String {
+        text.replace("{{name}}", &self.name)
+            .replace("{{date}}", &self.date)
+    }
+
+    fn expand_value(&self, value: serde_json::Value) -> serde_json::Value {
+        use serde_json::Value;
+        match value {
+            Value::String(text) => Value::String(self.expand(&text)),
+            Value::Array(items) => {
+                Value::Array(items.into_iter().map(|v| self.expand_value(v)).collect())
+            }
+            Value::Object(fields) => Value::Object(
+                fields
+                    .into_iter()
+                    .map(|(key, v)| (key, self.expand_value(v)))
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+}
+
+fn creation_error(path: &Path, e: impl ToString) -> PluginManagerError {
+    PluginManagerError::FileCreationError {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    }
+}
+
+fn write_json(path: &Path, content: &serde_json::Value) -> Result<(), PluginManagerError> {
+    let text = serde_json::to_string_pretty(content).map_err(|e| creation_error(path, e))?;
+    std::fs::write(path, text).map_err(|e| creation_error(path, e))
+}
+
+/// Create a file (or folder) of the given structure at `path`, with
+/// `content` as the file or marker file's contents.
+pub(crate) fn write_new_file(
+    structure: &FileStructure,
+    content: serde_json::Value,
+    path: &Path,
+    vars: &TemplateVars,
+) -> Result<(), PluginManagerError> {
+    let content = vars.expand_value(content);
+
+    match structure {
+        FileStructure::Standalone => write_json(path, &content),
+
+        FileStructure::FolderBased {
+            marker_file,
+            template_structure,
+        } => {
+            std::fs::create_dir_all(path).map_err(|e| creation_error(path, e))?;
+            write_json(&path.join(marker_file), &content)?;
+
+            for template in template_structure {
+                match template {
+                    PathTemplate::File {
+                        path: rel_path,
+                        content,
+                    } => {
+                        let file_path = path.join(rel_path);
+                        if let Some(parent) = file_path.parent() {
+                            std::fs::create_dir_all(parent)
+                                .map_err(|e| creation_error(parent, e))?;
+                        }
+                        std::fs::write(&file_path, vars.expand(content))
+                            .map_err(|e| creation_error(&file_path, e))?;
+                    }
+                    PathTemplate::Folder { path: rel_path } => {
+                        let folder_path = path.join(rel_path);
+                        std::fs::create_dir_all(&folder_path)
+                            .map_err(|e| creation_error(&folder_path, e))?;
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(name: &str) -> TemplateVars {
+        TemplateVars {
+            name: name.to_string(),
+            date: "2026-01-02".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_vars_for_path() {
+        let vars = TemplateVars::for_path(Path::new("scripts/Player.class"));
+        assert_eq!(vars.name, "Player");
+        assert_eq!(vars.date.len(), "YYYY-MM-DD".len());
+    }
+
+    #[test]
+    fn test_expand_json_strings_only() {
+        let content = serde_json::json!({
+            "class": "{{name}}",
+            "created": "{{date}}",
+            "tags": ["{{name}}-tag", 3],
+            "{{name}}": true,
+        });
+        let expanded = vars("Say \"hi\"").expand_value(content);
+        assert_eq!(
+            expanded,
+            serde_json::json!({
+                "class": "Say \"hi\"",
+                "created": "2026-01-02",
+                "tags": ["Say \"hi\"-tag", 3],
+                "{{name}}": true,
+            })
+        );
+    }
+
+    #[test]
+    fn test_folder_based_file_is_expanded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Player.class");
+
+        let structure = FileStructure::FolderBased {
+            marker_file: "class.json".to_string(),
+            template_structure: vec![
+                PathTemplate::Folder {
+                    path: "assets".to_string(),
+                },
+                PathTemplate::File {
+                    path: "src/main.rs".to_string(),
+                    content: "// {{name}}, created {{date}}\npub struct {{name}};\n".to_string(),
+                },
+            ],
+        };
+        let content = serde_json::json!({ "name": "{{name}}", "version": 1 });
+
+        write_new_file(&structure, content, &path, &vars("Player")).unwrap();
+
+        let marker: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(path.join("class.json")).unwrap()).unwrap();
+        assert_eq!(
+            marker,
+            serde_json::json!({ "name": "Player", "version": 1 })
+        );
+        assert_eq!(
+            std::fs::read_to_string(path.join("src/main.rs")).unwrap(),
+            "// Player, created 2026-01-02\npub struct Player;\n"
+        );
+        assert!(path.join("assets").is_dir());
+    }
+}
@@ -39,8 +39,9 @@
 //! let lib = PermanentLibrary::new("plugins/my_plugin.dll")?;
 //!
 //! // Get symbols - they remain valid forever
-//! let create_fn: Symbol<PluginCreate> = unsafe { lib.get(b"_plugin_create")? };
-//! let plugin = unsafe { create_fn() };
+//! let create_fn: Symbol<PluginCreateWithGlobals> =
+//!     unsafe { lib.get(b"_plugin_create_with_globals")? };
+//! let plugin = unsafe { &mut *create_fn(&PluginGlobals::new(theme_ptr)) };
 //!
 //! // Safe to share Arc across boundary because library never unloads
 //! let panel: Arc<dyn PanelView> = plugin.create_editor(...)?;
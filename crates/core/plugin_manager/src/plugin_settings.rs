@@ -0,0 +1,294 @@
+//! Per-plugin settings.
+//!
+//! Plugins that declare a [`settings_schema`](plugin_editor_api::EditorPlugin::settings_schema)
+//! get their settings kept in `{config_dir}/plugins/{plugin_id}.json`:
+//!
+//! ```json
+//! {
+//!   "properties": {
+//!     "auto_format": { "type": "boolean", "default": true, "title": "Format on Save" },
+//!     "indent": { "type": "integer", "default": 4, "minimum": 1, "maximum": 8 },
+//!     "style": { "type": "string", "enum": ["compact", "wide"], "default": "wide" }
+//!   }
+//! }
+//! ```
+//!
+//! Stored settings are checked against the schema when loaded: values that
+//! no longer fit (after a plugin update changed its schema, say) are dropped
+//! with a warning and replaced by their defaults. Updates are checked the
+//! same way but rejected as a whole.
+
+use plugin_editor_api::PluginId;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::PluginManagerError;
+
+/// Loads, validates and saves the settings of plugins with a settings
+/// schema.
+#[derive(Debug)]
+pub struct PluginSettingsStore {
+    /// `{config_dir}/plugins`
+    dir: PathBuf,
+    settings: HashMap<PluginId, Value>,
+}
+
+impl PluginSettingsStore {
+    /// Store settings under `{config_dir}/plugins`.
+    pub fn new(config_dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: config_dir.as_ref().join("plugins"),
+            settings: HashMap::new(),
+        }
+    }
+
+    /// The engine's config directory, next to `engine.toml`.
+    pub fn default_config_dir() -> PathBuf {
+        directories::ProjectDirs::from("com", "Pulsar", "Pulsar_Engine")
+            .map(|dirs| dirs.data_dir().join("configs"))
+            .unwrap_or_else(|| PathBuf::from("configs"))
+    }
+
+    /// The settings file of a plugin.
+    pub fn path_for(&self, plugin_id: &PluginId) -> PathBuf {
+        // Plugin IDs are reverse domain names, but don't let one escape the
+        // directory
+        let file_name: String = plugin_id
+            .as_str()
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' => c,
+                _ => '_',
+            })
+            .collect();
+        self.dir
+            .join(format!("{}.json", file_name.trim_start_matches('.')))
+    }
+
+    /// Read a plugin's settings file, keeping the values that fit `schema`
+    /// and filling in defaults for the rest.
+    pub fn load(&mut self, plugin_id: &PluginId, schema: &Value) -> &Value {
+        let path = self.path_for(plugin_id);
+        let stored = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid settings file {:?}: {}", path, e);
+                Value::Null
+            }),
+            Err(_) => Value::Null,
+        };
+
+        let mut settings = Map::new();
+        if let Value::Object(stored) = stored {
+            for (key, value) in stored {
+                match check_setting(schema, &key, &value) {
+                    Ok(()) => {
+                        settings.insert(key, value);
+                    }
+                    Err(reason) => {
+                        tracing::warn!("Dropping setting of plugin {}: {}", plugin_id, reason);
+                    }
+                }
+            }
+        }
+        fill_defaults(schema, &mut settings);
+
+        self.settings
+            .insert(plugin_id.clone(), Value::Object(settings));
+        &self.settings[plugin_id]
+    }
+
+    /// A plugin's settings, once [loaded](Self::load).
+    pub fn get(&self, plugin_id: &PluginId) -> Option<&Value> {
+        self.settings.get(plugin_id)
+    }
+
+    /// Replace a plugin's settings with `settings`, an object of values
+    /// declared in `schema`, and save them. Keys left out get their
+    /// defaults.
+    pub fn update(
+        &mut self,
+        plugin_id: &PluginId,
+        schema: &Value,
+        settings: Value,
+    ) -> Result<&Value, PluginManagerError> {
+        let invalid = |message: String| PluginManagerError::InvalidPluginSettings {
+            plugin_id: plugin_id.clone(),
+            message,
+        };
+
+        let Value::Object(mut settings) = settings else {
+            return Err(invalid("settings must be a JSON object".to_string()));
+        };
+        for (key, value) in &settings {
+            check_setting(schema, key, value).map_err(invalid)?;
+        }
+        fill_defaults(schema, &mut settings);
+        let settings = Value::Object(settings);
+
+        let path = self.path_for(plugin_id);
+        let write = || -> std::io::Result<()> {
+            std::fs::create_dir_all(&self.dir)?;
+            std::fs::write(&path, serde_json::to_vec_pretty(&settings)?)
+        };
+        write().map_err(|e| PluginManagerError::FileCreationError {
+            path: path.clone(),
+            message: e.to_string(),
+        })?;
+
+        self.settings.insert(plugin_id.clone(), settings);
+        Ok(&self.settings[plugin_id])
+    }
+}
+
+/// The declared settings of a schema, by key.
+pub fn schema_properties(schema: &Value) -> Option<&Map<String, Value>> {
+    schema.get("properties")?.as_object()
+}
+
+/// Check that `key` is declared in `schema` and `value` fits its declaration.
+fn check_setting(schema: &Value, key: &str, value: &Value) -> Result<(), String> {
+    let property = schema_properties(schema)
+        .and_then(|properties| properties.get(key))
+        .ok_or_else(|| format!("unknown setting `{}`", key))?;
+
+    if let Some(expected) = property.get("type").and_then(Value::as_str) {
+        let fits = match expected {
+            "boolean" => value.is_boolean(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            "string" => value.is_string(),
+            "array" => value.is_array(),
+            "object" => value.is_object(),
+            _ => true,
+        };
+        if !fits {
+            return Err(format!("`{}` must be of type {}", key, expected));
+        }
+    }
+
+    if let Some(allowed) = property.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            return Err(format!("`{}` is not one of the allowed values", key));
+        }
+    }
+
+    if let Some(number) = value.as_f64() {
+        if let Some(minimum) = property.get("minimum").and_then(Value::as_f64) {
+            if number < minimum {
+                return Err(format!("`{}` must be at least {}", key, minimum));
+            }
+        }
+        if let Some(maximum) = property.get("maximum").and_then(Value::as_f64) {
+            if number > maximum {
+                return Err(format!("`{}` must be at most {}", key, maximum));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn fill_defaults(schema: &Value, settings: &mut Map<String, Value>) {
+    for (key, property) in schema_properties(schema).into_iter().flatten() {
+        if let Some(default) = property.get("default") {
+            settings
+                .entry(key.clone())
+                .or_insert_with(|| default.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "properties": {
+                "auto_format": { "type": "boolean", "default": true },
+                "indent": { "type": "integer", "default": 4, "minimum": 1, "maximum": 8 },
+                "style": { "type": "string", "enum": ["compact", "wide"], "default": "wide" },
+                "extra_paths": { "type": "array" }
+            }
+        })
+    }
+
+    #[test]
+    fn test_check_setting() {
+        let schema = schema();
+        assert!(check_setting(&schema, "auto_format", &json!(false)).is_ok());
+        assert!(check_setting(&schema, "auto_format", &json!("yes")).is_err());
+        assert!(check_setting(&schema, "indent", &json!(2)).is_ok());
+        assert!(check_setting(&schema, "indent", &json!(2.5)).is_err());
+        assert!(check_setting(&schema, "indent", &json!(12)).is_err());
+        assert!(check_setting(&schema, "style", &json!("compact")).is_ok());
+        assert!(check_setting(&schema, "style", &json!("tiny")).is_err());
+        assert!(check_setting(&schema, "unknown", &json!(1)).is_err());
+    }
+
+    #[test]
+    fn test_update_and_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugin_id = PluginId::new("com.example.formatter");
+        let mut store = PluginSettingsStore::new(dir.path());
+
+        // Nothing stored yet: all defaults
+        assert_eq!(
+            store.load(&plugin_id, &schema()),
+            &json!({ "auto_format": true, "indent": 4, "style": "wide" })
+        );
+
+        store
+            .update(
+                &plugin_id,
+                &schema(),
+                json!({ "indent": 2, "style": "compact" }),
+            )
+            .unwrap();
+        assert!(matches!(
+            store.update(&plugin_id, &schema(), json!({ "indent": "two" })),
+            Err(PluginManagerError::InvalidPluginSettings { .. })
+        ));
+        assert!(store.update(&plugin_id, &schema(), json!([1, 2])).is_err());
+
+        let mut reloaded = PluginSettingsStore::new(dir.path());
+        assert_eq!(
+            reloaded.load(&plugin_id, &schema()),
+            &json!({ "auto_format": true, "indent": 2, "style": "compact" })
+        );
+    }
+
+    #[test]
+    fn test_load_drops_values_that_no_longer_fit() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugin_id = PluginId::new("com.example.formatter");
+        let store = PluginSettingsStore::new(dir.path());
+        std::fs::create_dir_all(dir.path().join("plugins")).unwrap();
+        std::fs::write(
+            store.path_for(&plugin_id),
+            r#"{ "indent": 3, "style": "tiny", "removed": true }"#,
+        )
+        .unwrap();
+
+        let mut store = store;
+        assert_eq!(
+            store.load(&plugin_id, &schema()),
+            &json!({ "auto_format": true, "indent": 3, "style": "wide" })
+        );
+    }
+
+    #[test]
+    fn test_path_stays_in_settings_dir() {
+        let store = PluginSettingsStore::new("configs");
+        assert_eq!(
+            store.path_for(&PluginId::new("com.example.formatter")),
+            Path::new("configs/plugins/com.example.formatter.json")
+        );
+        assert_eq!(
+            store.path_for(&PluginId::new("../../evil")),
+            Path::new("configs/plugins/_.._evil.json")
+        );
+    }
+}
@@ -0,0 +1,137 @@
+//! Enabling and disabling plugins.
+//!
+//! Which plugins are disabled is recorded per plugin directory in a
+//! `plugins_state.json` next to the libraries:
+//!
+//! ```json
+//! { "disabled": ["com.example.flaky_editor"] }
+//! ```
+//!
+//! [`PluginManager::load_plugins_from_dir`] reads it before loading anything.
+//! A disabled plugin's library is still loaded and its plugin created, since
+//! only the plugin knows its ID, but `on_load` isn't called and nothing it
+//! provides is registered.
+
+use plugin_editor_api::PluginId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use crate::PluginManagerError;
+
+const FILE: &str = "plugins_state.json";
+
+/// Whether a plugin found in a plugin directory is in use.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PluginState {
+    /// Loaded and registered
+    Enabled,
+    /// Turned off with [`PluginManager::set_plugin_enabled`](crate::PluginManager::set_plugin_enabled)
+    Disabled,
+    /// Enabled, but loading it failed
+    FailedToLoad { message: String },
+}
+
+/// A plugin library and its [`PluginState`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginStateEntry {
+    /// The plugin's ID, or the library's file name if loading failed before
+    /// the plugin named itself
+    pub plugin_id: PluginId,
+    pub name: String,
+    pub library_path: PathBuf,
+    pub state: PluginState,
+}
+
+/// A plugin found disabled while loading, or disabled since.
+#[derive(Debug, Clone)]
+pub(crate) struct DisabledPlugin {
+    pub(crate) name: String,
+    /// Loaded from here when the plugin is enabled again
+    pub(crate) source_path: PathBuf,
+}
+
+/// The contents of a directory's `plugins_state.json`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct PluginStateFile {
+    /// IDs of disabled plugins, kept sorted so the file diffs cleanly
+    #[serde(default)]
+    disabled: BTreeSet<String>,
+}
+
+impl PluginStateFile {
+    /// Read the state file in `dir`. Plugins are enabled unless the file
+    /// says otherwise, so a missing or invalid file disables nothing.
+    pub(crate) fn load(dir: &Path) -> Self {
+        let path = dir.join(FILE);
+        match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid plugin state in {:?}: {}", path, e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub(crate) fn is_enabled(&self, plugin_id: &PluginId) -> bool {
+        !self.disabled.contains(plugin_id.as_str())
+    }
+
+    /// Record whether `plugin_id` is enabled in the state file in `dir`.
+    pub(crate) fn save_enabled(
+        dir: &Path,
+        plugin_id: &PluginId,
+        enabled: bool,
+    ) -> Result<(), PluginManagerError> {
+        let mut state = Self::load(dir);
+        let changed = if enabled {
+            state.disabled.remove(plugin_id.as_str())
+        } else {
+            state.disabled.insert(plugin_id.to_string())
+        };
+        if !changed {
+            return Ok(());
+        }
+
+        let path = dir.join(FILE);
+        let bytes = serde_json::to_vec_pretty(&state).map_err(|e| {
+            PluginManagerError::FileCreationError {
+                path: path.clone(),
+                message: e.to_string(),
+            }
+        })?;
+        std::fs::write(&path, bytes).map_err(|e| PluginManagerError::FileCreationError {
+            path,
+            message: e.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_file_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let flaky = PluginId::new("com.example.flaky");
+        let other = PluginId::new("com.example.other");
+
+        // Everything is enabled without a state file
+        assert!(PluginStateFile::load(dir.path()).is_enabled(&flaky));
+
+        PluginStateFile::save_enabled(dir.path(), &flaky, false).unwrap();
+        PluginStateFile::save_enabled(dir.path(), &other, false).unwrap();
+        let state = PluginStateFile::load(dir.path());
+        assert!(!state.is_enabled(&flaky));
+        assert!(!state.is_enabled(&other));
+
+        PluginStateFile::save_enabled(dir.path(), &flaky, true).unwrap();
+        let state = PluginStateFile::load(dir.path());
+        assert!(state.is_enabled(&flaky));
+        assert!(!state.is_enabled(&other));
+
+        std::fs::write(dir.path().join(FILE), "{ not json").unwrap();
+        assert!(PluginStateFile::load(dir.path()).is_enabled(&other));
+    }
+}
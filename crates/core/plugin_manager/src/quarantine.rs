@@ -0,0 +1,224 @@
+//! Containing plugin panics.
+//!
+//! A panic unwinding out of plugin code would take the whole editor down.
+//! The manager makes every call into a plugin through a [`Quarantine`]: a
+//! plugin that panics is recorded as quarantined, and later calls into it
+//! fail with [`PluginManagerError::PluginPanicked`] without running its code
+//! again. The quarantine lasts until the plugin is reloaded successfully.
+//!
+//! This only helps with panics that unwind. A plugin built with
+//! `panic = "abort"`, or one panicking inside an `extern "C"` export, still
+//! aborts the process.
+
+use parking_lot::RwLock;
+use plugin_editor_api::PluginId;
+use std::any::Any;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::events::{EventBus, PluginManagerEvent};
+use crate::PluginManagerError;
+
+/// A plugin that panicked and is no longer called into.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuarantinedPlugin {
+    pub plugin_id: PluginId,
+    /// The call that panicked, e.g. `"create_editor"`
+    pub context: String,
+    /// The panic message, if it was a string
+    pub message: String,
+}
+
+/// Plugins that panicked, by ID.
+#[derive(Default)]
+pub(crate) struct Quarantine {
+    plugins: RwLock<HashMap<PluginId, QuarantinedPlugin>>,
+    /// Told about each plugin when it is quarantined
+    events: EventBus,
+}
+
+impl Quarantine {
+    pub(crate) fn new(events: EventBus) -> Self {
+        Self {
+            plugins: RwLock::default(),
+            events,
+        }
+    }
+
+    pub(crate) fn contains(&self, plugin_id: &PluginId) -> bool {
+        self.plugins.read().contains_key(plugin_id)
+    }
+
+    /// Fail if `plugin_id` is quarantined.
+    pub(crate) fn check(&self, plugin_id: &PluginId) -> Result<(), PluginManagerError> {
+        match self.plugins.read().get(plugin_id) {
+            Some(quarantined) => Err(PluginManagerError::PluginPanicked {
+                plugin_id: plugin_id.clone(),
+                context: quarantined.context.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Call into `plugin_id` unless it is quarantined, quarantining it if
+    /// `f` panics.
+    pub(crate) fn call<T>(
+        &self,
+        plugin_id: &PluginId,
+        context: &str,
+        f: impl FnOnce() -> T,
+    ) -> Result<T, PluginManagerError> {
+        self.check(plugin_id)?;
+        self.catch(plugin_id, context, f)
+    }
+
+    /// Like [`call`](Self::call), but runs `f` even if `plugin_id` is
+    /// already quarantined. Used while loading, when a rebuilt library
+    /// replaces the one that panicked.
+    pub(crate) fn catch<T>(
+        &self,
+        plugin_id: &PluginId,
+        context: &str,
+        f: impl FnOnce() -> T,
+    ) -> Result<T, PluginManagerError> {
+        // The plugin is never called again after a panic, so whatever state
+        // it left half-updated is never observed
+        catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+            let message = panic_message(payload.as_ref());
+            tracing::error!(
+                "Plugin {} panicked in {}: {}; it is quarantined",
+                plugin_id,
+                context,
+                message
+            );
+            let quarantined = QuarantinedPlugin {
+                plugin_id: plugin_id.clone(),
+                context: context.to_string(),
+                message,
+            };
+            let newly_quarantined = match self.plugins.write().entry(plugin_id.clone()) {
+                Entry::Occupied(_) => false,
+                Entry::Vacant(entry) => {
+                    entry.insert(quarantined.clone());
+                    true
+                }
+            };
+            if newly_quarantined {
+                self.events
+                    .emit(PluginManagerEvent::PluginQuarantined(quarantined));
+            }
+            PluginManagerError::PluginPanicked {
+                plugin_id: plugin_id.clone(),
+                context: context.to_string(),
+            }
+        })
+    }
+
+    /// Lift the quarantine, after the plugin was reloaded.
+    pub(crate) fn release(&self, plugin_id: &PluginId) {
+        self.plugins.write().remove(plugin_id);
+    }
+
+    /// All quarantined plugins, sorted by ID.
+    pub(crate) fn list(&self) -> Vec<QuarantinedPlugin> {
+        let mut plugins: Vec<QuarantinedPlugin> = self.plugins.read().values().cloned().collect();
+        plugins.sort_by(|a, b| a.plugin_id.as_str().cmp(b.plugin_id.as_str()));
+        plugins
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "non-string panic payload".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panic_quarantines_plugin() {
+        let quarantine = Quarantine::default();
+        let id = PluginId::new("com.example.flaky");
+
+        assert_eq!(quarantine.call(&id, "editors", || 3).unwrap(), 3);
+        assert!(!quarantine.contains(&id));
+
+        let result = quarantine.call(&id, "create_editor", || -> u32 {
+            panic!("index out of bounds")
+        });
+        assert!(matches!(
+            result,
+            Err(PluginManagerError::PluginPanicked { ref context, .. }) if context == "create_editor"
+        ));
+        assert_eq!(
+            quarantine.list(),
+            [QuarantinedPlugin {
+                plugin_id: id.clone(),
+                context: "create_editor".to_string(),
+                message: "index out of bounds".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_quarantined_calls_short_circuit() {
+        let quarantine = Quarantine::default();
+        let id = PluginId::new("com.example.flaky");
+        let _ = quarantine.call(&id, "on_load", || panic!("{} failed", "init"));
+        assert_eq!(quarantine.list()[0].message, "init failed");
+
+        let mut ran = false;
+        let result = quarantine.call(&id, "file_types", || ran = true);
+        assert!(!ran);
+        // Reported against the call that panicked first
+        assert!(matches!(
+            result,
+            Err(PluginManagerError::PluginPanicked { ref context, .. }) if context == "on_load"
+        ));
+
+        // Loading a rebuild still runs, and a clean reload lifts the quarantine
+        assert!(quarantine.catch(&id, "on_load", || ()).is_ok());
+        quarantine.release(&id);
+        assert!(quarantine.call(&id, "file_types", || ()).is_ok());
+        assert!(quarantine.list().is_empty());
+    }
+
+    #[test]
+    fn test_quarantine_is_announced_once() {
+        let events = EventBus::default();
+        let receiver = events.subscribe();
+        let quarantine = Quarantine::new(events);
+        let id = PluginId::new("com.example.flaky");
+        let _ = quarantine.call(&id, "editors", || panic!("first"));
+        let _ = quarantine.catch(&id, "on_load", || panic!("second"));
+
+        let announced: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(
+            announced,
+            [PluginManagerEvent::PluginQuarantined(QuarantinedPlugin {
+                plugin_id: id,
+                context: "editors".to_string(),
+                message: "first".to_string(),
+            })]
+        );
+    }
+
+    #[test]
+    fn test_other_plugins_unaffected() {
+        let quarantine = Quarantine::default();
+        let _ = quarantine.call(&PluginId::new("com.example.a"), "editors", || {
+            std::panic::panic_any(7_u32)
+        });
+        assert_eq!(quarantine.list()[0].message, "non-string panic payload");
+        assert!(quarantine
+            .call(&PluginId::new("com.example.b"), "editors", || ())
+            .is_ok());
+    }
+}
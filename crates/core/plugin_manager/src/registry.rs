@@ -1,10 +1,11 @@
-//! Registries for file types and editors.
+//! Registries for file types, editors and commands.
 //!
-//! These registries maintain the mapping between file types, editors, and plugins.
+//! These registries maintain the mapping between file types, editors, commands, and plugins.
 
 use plugin_editor_api::*;
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 // ============================================================================
 // File Type Registry
@@ -15,20 +16,25 @@ pub struct FileTypeRegistry {
     /// All registered file types, indexed by FileTypeId
     file_types: HashMap<FileTypeId, FileTypeDefinition>,
 
-    /// Map from file extension to FileTypeId
+    /// Map from file extension to the FileTypeIds claiming it, in
+    /// registration order. The last one is used for lookups.
     /// For folder-based files, this is the folder extension
-    extension_to_type: HashMap<String, FileTypeId>,
+    extension_to_types: HashMap<String, Vec<FileTypeId>>,
 
     /// Map from FileTypeId to PluginId (which plugin provides this type)
     type_to_plugin: HashMap<FileTypeId, PluginId>,
+
+    /// Decoded custom icons, for file types whose plugin provides one
+    icon_images: HashMap<FileTypeId, Arc<gpui::Image>>,
 }
 
 impl FileTypeRegistry {
     pub fn new() -> Self {
         Self {
             file_types: HashMap::new(),
-            extension_to_type: HashMap::new(),
+            extension_to_types: HashMap::new(),
             type_to_plugin: HashMap::new(),
+            icon_images: HashMap::new(),
         }
     }
 
@@ -37,12 +43,22 @@ impl FileTypeRegistry {
         let file_type_id = file_type.id.clone();
         let extension = file_type.extension.clone();
 
-        // Store the file type
-        self.file_types.insert(file_type_id.clone(), file_type);
-
-        // Map extension to type
-        self.extension_to_type
-            .insert(extension, file_type_id.clone());
+        // Store the file type, dropping any icon of the type it replaces
+        if let Some(replaced) = self.file_types.insert(file_type_id.clone(), file_type) {
+            self.remove_extension_claim(&replaced.extension, &file_type_id);
+        }
+        self.icon_images.remove(&file_type_id);
+
+        // Map extension to type, keeping earlier claims
+        let claims = self.extension_to_types.entry(extension).or_default();
+        if let Some(previous) = claims.last() {
+            tracing::warn!(
+                "File type {} takes over an extension already claimed by {}",
+                file_type_id,
+                previous
+            );
+        }
+        claims.push(file_type_id.clone());
 
         // Map type to plugin
         self.type_to_plugin.insert(file_type_id, plugin_id);
@@ -67,11 +83,41 @@ impl FileTypeRegistry {
     /// Unregister a specific file type.
     pub fn unregister(&mut self, file_type_id: &FileTypeId) {
         if let Some(file_type) = self.file_types.remove(file_type_id) {
-            self.extension_to_type.remove(&file_type.extension);
+            // The extension goes back to the type that claimed it before
+            self.remove_extension_claim(&file_type.extension, file_type_id);
             self.type_to_plugin.remove(file_type_id);
+            self.icon_images.remove(file_type_id);
+        }
+    }
+
+    fn remove_extension_claim(&mut self, extension: &str, file_type_id: &FileTypeId) {
+        if let Some(claims) = self.extension_to_types.get_mut(extension) {
+            claims.retain(|id| id != file_type_id);
+            if claims.is_empty() {
+                self.extension_to_types.remove(extension);
+            }
+        }
+    }
+
+    /// The file type used for an extension: the last one registered for it.
+    fn type_for_extension(&self, extension: &str) -> Option<&FileTypeId> {
+        self.extension_to_types.get(extension)?.last()
+    }
+
+    /// Set the custom icon of a registered file type.
+    pub fn set_icon_image(&mut self, file_type_id: &FileTypeId, image: Arc<gpui::Image>) {
+        if self.file_types.contains_key(file_type_id) {
+            self.icon_images.insert(file_type_id.clone(), image);
         }
     }
 
+    /// Get the custom icon of a file type, if its plugin provides one.
+    /// Without one, the file type's [`icon`](FileTypeDefinition::icon) is
+    /// used.
+    pub fn icon_image(&self, file_type_id: &FileTypeId) -> Option<Arc<gpui::Image>> {
+        self.icon_images.get(file_type_id).cloned()
+    }
+
     /// Get a file type by ID.
     pub fn get_file_type(&self, file_type_id: &FileTypeId) -> Option<&FileTypeDefinition> {
         self.file_types.get(file_type_id)
@@ -85,41 +131,37 @@ impl FileTypeRegistry {
     /// Get the file type for a path.
     ///
     /// This checks:
-    /// 1. If the path is a folder with an extension (folder-based file)
-    /// 2. If the path is a regular file with an extension
+    /// 1. If the path is a folder containing a folder-based type's marker file
+    /// 2. If the path is a folder with a folder-based type's extension
+    /// 3. If the path is a regular file with an extension
+    ///
+    /// A file inside a folder-based asset gets its own type here; use
+    /// [`resolve_file_type`](Self::resolve_file_type) to find the asset.
     pub fn get_file_type_for_path(&self, path: &Path) -> Option<FileTypeId> {
-        // Check if this is a folder with an extension
         if path.is_dir() {
-            if let Some(folder_name) = path.file_name().and_then(|s| s.to_str()) {
-                // Check if the folder name has an extension
-                if let Some(dot_pos) = folder_name.rfind('.') {
-                    let ext = &folder_name[dot_pos + 1..];
-                    if let Some(file_type_id) = self.extension_to_type.get(ext) {
-                        // Verify this is a folder-based file type
-                        if let Some(file_type) = self.file_types.get(file_type_id) {
-                            if matches!(file_type.structure, FileStructure::FolderBased { .. }) {
-                                return Some(file_type_id.clone());
-                            }
-                        }
-                    }
-                }
+            if let Some(file_type_id) = self.folder_type_by_marker(path) {
+                return Some(file_type_id);
             }
 
-            // If no extension found, check for marker files
-            // This handles folders like "ExampleClass/" that contain "graph_save.json"
-            for (file_type_id, file_type) in &self.file_types {
-                if let FileStructure::FolderBased { marker_file, .. } = &file_type.structure {
-                    let marker_path = path.join(marker_file);
-                    if marker_path.exists() {
-                        return Some(file_type_id.clone());
-                    }
+            // A folder-based asset whose marker isn't there (yet)
+            if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
+                let folder_type = self
+                    .file_types
+                    .values()
+                    .filter(|file_type| {
+                        file_type.extension == ext
+                            && matches!(file_type.structure, FileStructure::FolderBased { .. })
+                    })
+                    .min_by(|a, b| a.id.as_str().cmp(b.id.as_str()));
+                if let Some(file_type) = folder_type {
+                    return Some(file_type.id.clone());
                 }
             }
         }
 
         // Check if this is a regular file
         if let Some(extension) = path.extension().and_then(|s| s.to_str()) {
-            if let Some(file_type_id) = self.extension_to_type.get(extension) {
+            if let Some(file_type_id) = self.type_for_extension(extension) {
                 return Some(file_type_id.clone());
             }
         }
@@ -130,7 +172,7 @@ impl FileTypeRegistry {
             let parts: Vec<&str> = file_name.split('.').collect();
             for i in 1..parts.len() {
                 let compound_ext = parts[i..].join(".");
-                if let Some(file_type_id) = self.extension_to_type.get(&compound_ext) {
+                if let Some(file_type_id) = self.type_for_extension(&compound_ext) {
                     return Some(file_type_id.clone());
                 }
             }
@@ -139,11 +181,69 @@ impl FileTypeRegistry {
         None
     }
 
+    /// Find the file type to open `path` as, and the path to open.
+    ///
+    /// A path inside a folder-based asset, like a `.class` folder's
+    /// `graph_save.json`, resolves to the innermost asset containing it and
+    /// that asset's root folder. Any other path resolves to itself, with the
+    /// type [`get_file_type_for_path`](Self::get_file_type_for_path) gives it.
+    pub fn resolve_file_type(&self, path: &Path) -> Option<(FileTypeId, PathBuf)> {
+        if path.is_dir() {
+            if let Some(file_type_id) = self.folder_type_by_marker(path) {
+                return Some((file_type_id, path.to_path_buf()));
+            }
+        }
+
+        let owning_asset = path
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| !dir.as_os_str().is_empty())
+            .find_map(|dir| Some((self.folder_type_by_marker(dir)?, dir.to_path_buf())));
+        if owning_asset.is_some() {
+            return owning_asset;
+        }
+
+        let file_type_id = self.get_file_type_for_path(path)?;
+        Some((file_type_id, path.to_path_buf()))
+    }
+
+    /// The folder-based type of a directory containing its marker file. If
+    /// several types' markers are there, one whose extension the directory
+    /// has wins, then the first by ID.
+    fn folder_type_by_marker(&self, dir: &Path) -> Option<FileTypeId> {
+        let dir_ext = dir.extension().and_then(|s| s.to_str());
+        self.file_types
+            .values()
+            .filter(|file_type| match &file_type.structure {
+                FileStructure::FolderBased { marker_file, .. } => dir.join(marker_file).exists(),
+                FileStructure::Standalone => false,
+            })
+            .min_by_key(|file_type| {
+                (
+                    Some(file_type.extension.as_str()) != dir_ext,
+                    file_type.id.as_str(),
+                )
+            })
+            .map(|file_type| file_type.id.clone())
+    }
+
     /// Get the plugin that provides a file type.
     pub fn get_plugin_for_file_type(&self, file_type_id: &FileTypeId) -> Option<&PluginId> {
         self.type_to_plugin.get(file_type_id)
     }
 
+    /// Every file type claiming an extension, with the plugin providing it,
+    /// in registration order. The last one is the one used for the
+    /// extension.
+    pub fn get_providers_for_extension(&self, extension: &str) -> Vec<(&FileTypeId, &PluginId)> {
+        self.extension_to_types
+            .get(extension)
+            .into_iter()
+            .flatten()
+            .filter_map(|file_type_id| Some((file_type_id, self.type_to_plugin.get(file_type_id)?)))
+            .collect()
+    }
+
     /// Check if a path matches a folder-based file type.
     ///
     /// Returns the file type ID if the path is a folder containing the marker file.
@@ -265,8 +365,9 @@ impl EditorRegistry {
 
     /// Get the first editor that can open a file type.
     ///
-    /// If multiple editors support the same file type, this returns the first one.
-    /// In the future, we could add logic for user preferences or editor priorities.
+    /// If multiple editors support the same file type, this returns the first one
+    /// registered. [`PluginManager::create_editor_for_file`](crate::PluginManager::create_editor_for_file)
+    /// honours the project's default editor instead.
     pub fn get_editor_for_file_type(&self, file_type_id: &FileTypeId) -> Option<EditorId> {
         self.file_type_to_editors
             .get(file_type_id)
@@ -274,7 +375,7 @@ impl EditorRegistry {
             .cloned()
     }
 
-    /// Get all editors that can open a file type.
+    /// Get all editors that can open a file type, in registration order.
     pub fn get_editors_for_file_type(&self, file_type_id: &FileTypeId) -> Vec<EditorId> {
         self.file_type_to_editors
             .get(file_type_id)
@@ -286,6 +387,18 @@ impl EditorRegistry {
     pub fn get_plugin_for_editor(&self, editor_id: &EditorId) -> Option<&PluginId> {
         self.editor_to_plugin.get(editor_id)
     }
+
+    /// Get all editors a plugin provides, sorted by ID.
+    pub fn editors_by_plugin(&self, plugin_id: &PluginId) -> Vec<&EditorMetadata> {
+        let mut editors: Vec<&EditorMetadata> = self
+            .editor_to_plugin
+            .iter()
+            .filter(|(_, pid)| *pid == plugin_id)
+            .filter_map(|(editor_id, _)| self.editors.get(editor_id))
+            .collect();
+        editors.sort_by(|a, b| a.id.as_str().cmp(b.id.as_str()));
+        editors
+    }
 }
 
 impl Default for EditorRegistry {
@@ -294,6 +407,76 @@ impl Default for EditorRegistry {
     }
 }
 
+// ============================================================================
+// Command Registry
+// ============================================================================
+
+/// Registry for command palette commands provided by plugins.
+pub struct CommandRegistry {
+    /// Commands by the plugin that provides them, in the order it listed them.
+    /// Command IDs are only unique within a plugin.
+    commands: HashMap<PluginId, Vec<CommandDefinition>>,
+}
+
+impl CommandRegistry {
+    pub fn new() -> Self {
+        Self {
+            commands: HashMap::new(),
+        }
+    }
+
+    /// Register a command from a plugin, replacing one with the same ID.
+    pub fn register(&mut self, command: CommandDefinition, plugin_id: PluginId) {
+        let commands = self.commands.entry(plugin_id).or_default();
+        match commands
+            .iter_mut()
+            .find(|existing| existing.id == command.id)
+        {
+            Some(existing) => *existing = command,
+            None => commands.push(command),
+        }
+    }
+
+    /// Unregister all commands from a plugin.
+    pub fn unregister_by_plugin(&mut self, plugin_id: &PluginId) {
+        self.commands.remove(plugin_id);
+    }
+
+    /// Get a command by the plugin that provides it and its ID.
+    pub fn get_command(
+        &self,
+        plugin_id: &PluginId,
+        command_id: &str,
+    ) -> Option<&CommandDefinition> {
+        self.commands
+            .get(plugin_id)?
+            .iter()
+            .find(|command| command.id == command_id)
+    }
+
+    /// Get all registered commands with their plugins, sorted by category
+    /// and then by name.
+    pub fn get_all_commands(&self) -> Vec<(&PluginId, &CommandDefinition)> {
+        let mut commands: Vec<(&PluginId, &CommandDefinition)> = self
+            .commands
+            .iter()
+            .flat_map(|(plugin_id, commands)| {
+                commands.iter().map(move |command| (plugin_id, command))
+            })
+            .collect();
+        commands.sort_by(|(_, a), (_, b)| {
+            (&a.category, &a.display_name).cmp(&(&b.category, &b.display_name))
+        });
+        commands
+    }
+}
+
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -323,6 +506,154 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_shared_extension_keeps_all_providers() {
+        let mut registry = FileTypeRegistry::new();
+        let text_type = |id: &str| {
+            standalone_file_type(
+                id,
+                "txt",
+                id,
+                ui::IconName::Code,
+                gpui::rgb(0x00BCD4).into(),
+                serde_json::json!({}),
+            )
+        };
+        let (text, notes) = (FileTypeId::new("text"), FileTypeId::new("notes"));
+        let (builtin, notes_plugin) =
+            (PluginId::new("builtin"), PluginId::new("com.example.notes"));
+
+        registry.register(text_type("text"), builtin.clone());
+        registry.register(text_type("notes"), notes_plugin.clone());
+        assert_eq!(
+            registry.get_providers_for_extension("txt"),
+            [(&text, &builtin), (&notes, &notes_plugin)]
+        );
+        assert_eq!(
+            registry.get_file_type_for_path(Path::new("readme.txt")),
+            Some(notes.clone())
+        );
+
+        // Re-registering doesn't claim the extension twice
+        registry.register(text_type("notes"), notes_plugin.clone());
+        assert_eq!(registry.get_providers_for_extension("txt").len(), 2);
+
+        registry.unregister_by_plugin(&notes_plugin);
+        assert_eq!(
+            registry.get_providers_for_extension("txt"),
+            [(&text, &builtin)]
+        );
+        assert_eq!(registry.get_plugin_for_file_type(&notes), None);
+        assert_eq!(
+            registry.get_file_type_for_path(Path::new("readme.txt")),
+            Some(text)
+        );
+    }
+
+    fn folder_type(id: &str, extension: &str, marker_file: &str) -> FileTypeDefinition {
+        folder_file_type(
+            id,
+            extension,
+            id,
+            ui::IconName::Folder,
+            gpui::rgb(0x00BCD4).into(),
+            marker_file,
+            vec![],
+            serde_json::json!({}),
+        )
+    }
+
+    #[test]
+    fn test_folder_based_assets_by_marker() {
+        let root = tempfile::tempdir().unwrap();
+        let outer = root.path().join("Player.class");
+        let inner = outer.join("Weapon.class");
+        let material = root.path().join("Metal.class");
+        for dir in [&inner, &material.join("textures")] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        std::fs::write(outer.join("graph_save.json"), "{}").unwrap();
+        std::fs::write(outer.join("notes.txt"), "").unwrap();
+        std::fs::write(inner.join("graph_save.json"), "{}").unwrap();
+        std::fs::write(material.join("material.json"), "{}").unwrap();
+
+        let mut registry = FileTypeRegistry::new();
+        let plugin_id = PluginId::new("test.plugin");
+        registry.register(
+            folder_type("blueprint", "class", "graph_save.json"),
+            plugin_id.clone(),
+        );
+        // Shares the extension with blueprints, told apart by its marker
+        registry.register(
+            folder_type("material", "class", "material.json"),
+            plugin_id.clone(),
+        );
+        registry.register(
+            standalone_file_type(
+                "text",
+                "txt",
+                "Text",
+                ui::IconName::Code,
+                gpui::rgb(0x00BCD4).into(),
+                serde_json::json!({}),
+            ),
+            plugin_id,
+        );
+        let blueprint = FileTypeId::new("blueprint");
+        let material_type = FileTypeId::new("material");
+
+        assert_eq!(
+            registry.get_file_type_for_path(&outer),
+            Some(blueprint.clone())
+        );
+        assert_eq!(
+            registry.get_file_type_for_path(&material),
+            Some(material_type.clone())
+        );
+
+        // Inner files open as the asset owning them, the innermost one if nested
+        assert_eq!(
+            registry.resolve_file_type(&outer.join("graph_save.json")),
+            Some((blueprint.clone(), outer.clone()))
+        );
+        assert_eq!(
+            registry.resolve_file_type(&outer.join("notes.txt")),
+            Some((blueprint.clone(), outer.clone()))
+        );
+        assert_eq!(
+            registry.resolve_file_type(&inner.join("graph_save.json")),
+            Some((blueprint.clone(), inner.clone()))
+        );
+        assert_eq!(
+            registry.resolve_file_type(&inner),
+            Some((blueprint.clone(), inner.clone()))
+        );
+        assert_eq!(
+            registry.resolve_file_type(&material.join("textures")),
+            Some((material_type.clone(), material.clone()))
+        );
+
+        // Outside any asset a path resolves to itself
+        let loose = root.path().join("readme.txt");
+        assert_eq!(
+            registry.resolve_file_type(&loose),
+            Some((FileTypeId::new("text"), loose))
+        );
+
+        // A new asset without its marker yet goes by extension
+        let new_asset = root.path().join("New.class");
+        std::fs::create_dir_all(&new_asset).unwrap();
+        assert_eq!(
+            registry.get_file_type_for_path(&new_asset),
+            Some(blueprint.clone())
+        );
+
+        // Unregistering one type hands the shared extension to the other
+        registry.unregister(&material_type);
+        assert_eq!(registry.type_for_extension("class"), Some(&blueprint));
+        assert_eq!(registry.get_file_type_for_path(&material), Some(blueprint));
+    }
+
     #[test]
     fn test_editor_registry() {
         let mut registry = EditorRegistry::new();
@@ -342,4 +673,71 @@ mod tests {
             Some(EditorId::new("test-editor"))
         );
     }
+    #[test]
+    fn test_several_editors_for_file_type() {
+        let mut registry = EditorRegistry::new();
+        let editor = |id: &str| EditorMetadata {
+            id: EditorId::new(id),
+            display_name: id.to_string(),
+            supported_file_types: vec![FileTypeId::new("rust-script")],
+        };
+
+        registry.register(editor("code"), PluginId::new("com.example.code"));
+        registry.register(editor("graph"), PluginId::new("com.example.graph"));
+        assert_eq!(
+            registry.get_editors_for_file_type(&FileTypeId::new("rust-script")),
+            [EditorId::new("code"), EditorId::new("graph")]
+        );
+        let by_plugin: Vec<&EditorId> = registry
+            .editors_by_plugin(&PluginId::new("com.example.graph"))
+            .into_iter()
+            .map(|editor| &editor.id)
+            .collect();
+        assert_eq!(by_plugin, [&EditorId::new("graph")]);
+
+        registry.unregister_by_plugin(&PluginId::new("com.example.code"));
+        assert_eq!(
+            registry.get_editors_for_file_type(&FileTypeId::new("rust-script")),
+            [EditorId::new("graph")]
+        );
+    }
+    #[test]
+    fn test_command_registry() {
+        let mut registry = CommandRegistry::new();
+        let blueprint = PluginId::new("com.example.blueprint");
+        let shader = PluginId::new("com.example.shader");
+
+        registry.register(
+            CommandDefinition::new("format", "Format Blueprint", "Blueprint"),
+            blueprint.clone(),
+        );
+        registry.register(
+            CommandDefinition::new("format", "Format Shader", "Shader").with_keybinding("Ctrl+F"),
+            shader.clone(),
+        );
+        registry.register(
+            CommandDefinition::new("compile", "Compile Blueprint", "Blueprint"),
+            blueprint.clone(),
+        );
+
+        let names: Vec<&str> = registry
+            .get_all_commands()
+            .iter()
+            .map(|(_, command)| command.display_name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            ["Compile Blueprint", "Format Blueprint", "Format Shader"]
+        );
+        assert_eq!(
+            registry
+                .get_command(&shader, "format")
+                .and_then(|command| command.keybinding.as_deref()),
+            Some("Ctrl+F")
+        );
+
+        registry.unregister_by_plugin(&blueprint);
+        assert!(registry.get_command(&blueprint, "format").is_none());
+        assert!(registry.get_command(&shader, "format").is_some());
+    }
 }
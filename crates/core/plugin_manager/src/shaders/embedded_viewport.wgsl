@@ -0,0 +1,49 @@
+// Embedded plugin viewports: flat-coloured lit meshes and debug lines.
+
+struct Draw {
+    view_proj: mat4x4<f32>,
+    model: mat4x4<f32>,
+    color: vec4<f32>,
+};
+
+@group(0) @binding(0) var<uniform> draw: Draw;
+
+struct MeshOut {
+    @builtin(position) position: vec4<f32>,
+    @location(0) normal: vec3<f32>,
+};
+
+@vertex
+fn vs_mesh(@location(0) position: vec3<f32>, @location(1) normal: vec3<f32>) -> MeshOut {
+    var out: MeshOut;
+    out.position = draw.view_proj * draw.model * vec4<f32>(position, 1.0);
+    out.normal = (draw.model * vec4<f32>(normal, 0.0)).xyz;
+    return out;
+}
+
+@fragment
+fn fs_mesh(in: MeshOut) -> @location(0) vec4<f32> {
+    let light = normalize(vec3<f32>(0.4, 0.8, 0.5));
+    let n = normalize(in.normal);
+    // Two-sided so open meshes from arbitrary files still read well
+    let diffuse = abs(dot(n, light));
+    return vec4<f32>(draw.color.rgb * (0.25 + 0.75 * diffuse), draw.color.a);
+}
+
+struct LineOut {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+};
+
+@vertex
+fn vs_line(@location(0) position: vec3<f32>, @location(1) color: vec4<f32>) -> LineOut {
+    var out: LineOut;
+    out.position = draw.view_proj * vec4<f32>(position, 1.0);
+    out.color = color * draw.color;
+    return out;
+}
+
+@fragment
+fn fs_line(in: LineOut) -> @location(0) vec4<f32> {
+    return in.color;
+}
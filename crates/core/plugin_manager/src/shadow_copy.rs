@@ -0,0 +1,120 @@
+//! Shadow copies of plugin libraries.
+//!
+//! Plugins are loaded from a private copy in the temp directory rather than
+//! from `plugins/editor/` directly:
+//!
+//! - Windows locks a loaded DLL, so loading the original would make the next
+//!   plugin build fail to overwrite it.
+//! - The OS loader hands back the already-loaded image when asked for a path
+//!   it has seen before; a fresh file name per load is what lets
+//!   [`PluginManager::reload_plugin`](crate::PluginManager::reload_plugin)
+//!   actually pick up new code.
+//!
+//! Copies are never deleted while in use (libraries never unload); stale ones
+//! from earlier sessions are removed by [`clean`].
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Directory under the system temp dir holding the copies.
+const SHADOW_DIR: &str = "pulsar_plugin_shadow";
+
+static NEXT_COPY: AtomicU64 = AtomicU64::new(0);
+
+fn shadow_dir() -> PathBuf {
+    std::env::temp_dir().join(SHADOW_DIR)
+}
+
+/// Copy `library` to a uniquely named file in the shadow directory.
+pub(crate) fn copy(library: &Path) -> std::io::Result<PathBuf> {
+    copy_into(&shadow_dir(), library)
+}
+
+fn copy_into(dir: &Path, library: &Path) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let stem = library
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("plugin");
+    let mut name = format!(
+        "{}-{}-{}",
+        stem,
+        std::process::id(),
+        NEXT_COPY.fetch_add(1, Ordering::Relaxed)
+    );
+    if let Some(extension) = library.extension().and_then(|e| e.to_str()) {
+        name.push('.');
+        name.push_str(extension);
+    }
+    let shadow = dir.join(name);
+    std::fs::copy(library, &shadow)?;
+    Ok(shadow)
+}
+
+/// Remove shadow copies left by earlier sessions.
+///
+/// Copies still loaded by another running editor can't be removed on
+/// Windows and are skipped; on Unix removing them is harmless because the
+/// mapping outlives the file.
+pub(crate) fn clean() {
+    clean_dir(&shadow_dir());
+}
+
+fn clean_dir(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let own_prefix = format!("-{}-", std::process::id());
+    for entry in entries.filter_map(|e| e.ok()) {
+        let name = entry.file_name();
+        if name.to_string_lossy().contains(&own_prefix) {
+            continue;
+        }
+        if let Err(e) = std::fs::remove_file(entry.path()) {
+            tracing::debug!("Keeping shadow copy {:?}: {}", entry.path(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_each_copy_gets_a_new_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let library = dir.path().join("my_editor.dll");
+        std::fs::write(&library, b"v1").unwrap();
+
+        let shadows = dir.path().join("shadow");
+        let first = copy_into(&shadows, &library).unwrap();
+        std::fs::write(&library, b"v2").unwrap();
+        let second = copy_into(&shadows, &library).unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(first.extension().unwrap(), "dll");
+        assert!(first
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("my_editor-"));
+        assert_eq!(std::fs::read(&first).unwrap(), b"v1");
+        assert_eq!(std::fs::read(&second).unwrap(), b"v2");
+    }
+
+    #[test]
+    fn test_clean_keeps_this_sessions_copies() {
+        let dir = tempfile::tempdir().unwrap();
+        let library = dir.path().join("my_editor.so");
+        std::fs::write(&library, b"lib").unwrap();
+
+        let shadows = dir.path().join("shadow");
+        let ours = copy_into(&shadows, &library).unwrap();
+        let stale = shadows.join("my_editor-0-3.so");
+        std::fs::write(&stale, b"old").unwrap();
+
+        clean_dir(&shadows);
+        assert!(ours.exists());
+        assert!(!stale.exists());
+    }
+}
@@ -0,0 +1,225 @@
+//! Plugin sidecars: a `<library stem>.plugin.toml` next to a plugin library
+//! that describes the plugin without loading it.
+//!
+//! ```toml
+//! id = "com.example.obj_editor"
+//! name = "OBJ Editor"
+//! version = "1.2.0"
+//! description = "Edit Wavefront OBJ meshes"
+//! extensions = ["obj", "mtl"]
+//! # The plugin API and engine version the plugin is built against
+//! api_version = 3
+//! engine_version = "0.4.0"
+//! ```
+//!
+//! Only `id` is required. [`PluginManager::load_plugins_from_dir`](crate::PluginManager::load_plugins_from_dir)
+//! reads the sidecar before opening the library, and skips the library
+//! entirely when the sidecar names a disabled plugin or a version this engine
+//! can't load. Plugin suggestions use the claimed extensions. A sidecar is
+//! not trusted over the plugin itself: once loaded, the plugin's own
+//! metadata is used, and any disagreement is logged.
+
+use plugin_editor_api::{PluginMetadata, VersionComponent, VersionInfo};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Suffix of the sidecar next to a plugin library.
+const SIDECAR_SUFFIX: &str = "plugin.toml";
+
+/// Sidecar of the plugin library at `library`.
+pub(crate) fn sidecar_path(library: &Path) -> PathBuf {
+    library.with_extension(SIDECAR_SUFFIX)
+}
+
+/// Contents of a `<library stem>.plugin.toml`.
+#[derive(Debug, Deserialize)]
+pub(crate) struct PluginSidecar {
+    pub(crate) id: String,
+    #[serde(default)]
+    pub(crate) name: Option<String>,
+    /// The plugin's own version
+    #[serde(default)]
+    pub(crate) version: Option<String>,
+    #[serde(default)]
+    pub(crate) description: Option<String>,
+    #[serde(default)]
+    pub(crate) extensions: Vec<String>,
+    #[serde(default)]
+    api_version: Option<u32>,
+    /// `major.minor.patch` of the engine the plugin was built against
+    #[serde(default)]
+    engine_version: Option<String>,
+}
+
+impl PluginSidecar {
+    pub(crate) fn read(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        toml::from_str(&content).map_err(|e| e.to_string())
+    }
+
+    /// The sidecar of `library`, if it has one that can be read. An invalid
+    /// one is logged and ignored.
+    pub(crate) fn read_for(library: &Path) -> Option<Self> {
+        let path = sidecar_path(library);
+        if !path.exists() {
+            return None;
+        }
+        Self::read(&path)
+            .map_err(|e| tracing::warn!("Ignoring plugin sidecar {:?}: {}", path, e))
+            .ok()
+    }
+
+    /// The name to show, falling back to the ID.
+    pub(crate) fn display_name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| self.id.clone())
+    }
+
+    /// The declared version, with anything left out taken from `engine`.
+    /// `None` when the sidecar declares no version at all.
+    fn version_info(&self, engine: &VersionInfo) -> Result<Option<VersionInfo>, String> {
+        if self.api_version.is_none() && self.engine_version.is_none() {
+            return Ok(None);
+        }
+        let engine_version = match &self.engine_version {
+            Some(version) => parse_engine_version(version)
+                .ok_or_else(|| format!("invalid engine_version '{}'", version))?,
+            None => engine.engine_version,
+        };
+        Ok(Some(VersionInfo {
+            engine_version,
+            api_version: self.api_version.unwrap_or(engine.api_version),
+            rustc_version_hash: engine.rustc_version_hash,
+        }))
+    }
+
+    /// The declared version and why `engine` can't load it, if it can't.
+    ///
+    /// The sidecar can't say which rustc built the library, so that part of
+    /// the check is left to the load. An unparsable version is logged and
+    /// treated as compatible, leaving the decision to the load too.
+    pub(crate) fn incompatibility(
+        &self,
+        engine: &VersionInfo,
+    ) -> Option<(VersionInfo, VersionComponent)> {
+        match self.version_info(engine) {
+            Ok(Some(version)) => engine
+                .check_compatible(&version, false)
+                .err()
+                .map(|component| (version, component)),
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("Plugin sidecar for {}: {}", self.id, e);
+                None
+            }
+        }
+    }
+
+    /// Where the sidecar disagrees with the loaded plugin's metadata.
+    pub(crate) fn mismatches(&self, metadata: &PluginMetadata) -> Vec<String> {
+        let mut mismatches = Vec::new();
+        let mut compare = |field: &str, declared: Option<&str>, actual: &str| {
+            if let Some(declared) = declared {
+                if declared != actual {
+                    mismatches.push(format!(
+                        "{} is '{}' in the sidecar but '{}' in the plugin",
+                        field, declared, actual
+                    ));
+                }
+            }
+        };
+        compare("id", Some(&self.id), metadata.id.as_str());
+        compare("name", self.name.as_deref(), &metadata.name);
+        compare("version", self.version.as_deref(), &metadata.version);
+        compare(
+            "description",
+            self.description.as_deref(),
+            &metadata.description,
+        );
+        mismatches
+    }
+}
+
+/// Parse `major.minor.patch`, allowing the patch to be left out.
+fn parse_engine_version(version: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = version.trim().split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    let patch = match parts.next() {
+        Some(patch) => patch.parse().ok()?,
+        None => 0,
+    };
+    parts.next().is_none().then_some((major, minor, patch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use plugin_editor_api::PluginId;
+
+    #[test]
+    fn test_parse_engine_version() {
+        assert_eq!(parse_engine_version("0.4.1"), Some((0, 4, 1)));
+        assert_eq!(parse_engine_version("1.2"), Some((1, 2, 0)));
+        assert_eq!(parse_engine_version("1"), None);
+        assert_eq!(parse_engine_version("1.2.3.4"), None);
+        assert_eq!(parse_engine_version("one.two"), None);
+    }
+
+    #[test]
+    fn test_sidecar_path() {
+        assert_eq!(
+            sidecar_path(Path::new("plugins/editor/libobj_editor.so")),
+            Path::new("plugins/editor/libobj_editor.plugin.toml")
+        );
+    }
+
+    #[test]
+    fn test_incompatibility() {
+        let engine = VersionInfo::current();
+        let sidecar: PluginSidecar = toml::from_str("id = \"com.example.a\"").unwrap();
+        assert_eq!(sidecar.incompatibility(&engine), None);
+
+        let (major, minor, _) = engine.engine_version;
+        let sidecar: PluginSidecar = toml::from_str(&format!(
+            "id = \"com.example.a\"\nengine_version = \"{}.{}.99\"",
+            major, minor
+        ))
+        .unwrap();
+        assert_eq!(sidecar.incompatibility(&engine), None);
+
+        let sidecar: PluginSidecar = toml::from_str(&format!(
+            "id = \"com.example.a\"\nengine_version = \"{}.0.0\"",
+            major + 1
+        ))
+        .unwrap();
+        let (version, component) = sidecar.incompatibility(&engine).unwrap();
+        assert_eq!(version.engine_version, (major + 1, 0, 0));
+        assert_eq!(component, VersionComponent::EngineMajor);
+    }
+
+    #[test]
+    fn test_mismatches() {
+        let sidecar: PluginSidecar = toml::from_str(
+            r#"
+                id = "com.example.obj_editor"
+                name = "OBJ Editor"
+                version = "1.0.0"
+            "#,
+        )
+        .unwrap();
+        let mut metadata = PluginMetadata {
+            id: PluginId::new("com.example.obj_editor"),
+            name: "OBJ Editor".to_string(),
+            version: "1.0.0".to_string(),
+            author: "Example".to_string(),
+            description: "Anything, the sidecar leaves it out".to_string(),
+        };
+        assert!(sidecar.mismatches(&metadata).is_empty());
+
+        metadata.version = "1.1.0".to_string();
+        assert_eq!(
+            sidecar.mismatches(&metadata),
+            ["version is '1.0.0' in the sidecar but '1.1.0' in the plugin"]
+        );
+    }
+}
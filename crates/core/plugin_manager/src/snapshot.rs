@@ -0,0 +1,215 @@
+//! A serializable view of the plugin manager's state.
+//!
+//! [`PluginManager::state_snapshot`](crate::PluginManager::state_snapshot)
+//! copies out what is loaded, from where, since when and how long it took,
+//! for Mission Control and for bug reports. The [`Display`](std::fmt::Display)
+//! impl renders the same data as text for logs.
+
+use chrono::{DateTime, Local};
+use plugin_editor_api::PluginId;
+use serde::Serialize;
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Loading a plugin taking longer than this is logged as a warning.
+pub(crate) const SLOW_LOAD: Duration = Duration::from_millis(500);
+
+/// Time spent loading a plugin, by step.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct LoadTimings {
+    /// Copying, loading and verifying the library and creating the plugin
+    pub library: Duration,
+    /// The plugin's `on_load`
+    pub on_load: Duration,
+    /// Reading and registering its file types, editors and other extensions
+    pub registration: Duration,
+}
+
+impl LoadTimings {
+    pub fn total(&self) -> Duration {
+        self.library + self.on_load + self.registration
+    }
+}
+
+/// A loaded plugin.
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginSnapshot {
+    pub id: PluginId,
+    pub name: String,
+    pub version: String,
+    pub author: String,
+    /// The library the plugin was loaded from
+    pub source_path: PathBuf,
+    /// The shadow copy actually loaded
+    pub library_path: PathBuf,
+    pub loaded_at: DateTime<Local>,
+    pub load_timings: LoadTimings,
+    /// Editors open on the current build
+    pub open_editors: usize,
+    pub quarantined: bool,
+}
+
+/// A retired build of a plugin, unloaded once its editors are closed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PendingUnload {
+    pub plugin_id: PluginId,
+    pub open_editors: usize,
+}
+
+/// The library of a retired build. Libraries are never unmapped, so each
+/// reload or disable leaves one of these behind for the rest of the session.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RetiredLibrary {
+    pub plugin_id: PluginId,
+    pub library_path: PathBuf,
+    pub retired_at: DateTime<Local>,
+}
+
+/// The plugin manager's state at one point in time.
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginManagerSnapshot {
+    pub taken_at: DateTime<Local>,
+    /// Loaded plugins, sorted by ID
+    pub plugins: Vec<PluginSnapshot>,
+    /// Sorted by plugin ID
+    pub pending_unloads: Vec<PendingUnload>,
+    /// Oldest first
+    pub retired_libraries: Vec<RetiredLibrary>,
+}
+
+impl PluginManagerSnapshot {
+    /// Loaded plugins, slowest to load first.
+    pub fn slowest_plugins(&self) -> Vec<&PluginSnapshot> {
+        let mut plugins: Vec<&PluginSnapshot> = self.plugins.iter().collect();
+        plugins.sort_by_key(|p| std::cmp::Reverse(p.load_timings.total()));
+        plugins
+    }
+}
+
+impl fmt::Display for PluginManagerSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Plugin manager at {}: {} plugin(s) loaded",
+            self.taken_at.format("%Y-%m-%d %H:%M:%S"),
+            self.plugins.len()
+        )?;
+        for plugin in &self.plugins {
+            let timings = &plugin.load_timings;
+            writeln!(
+                f,
+                "  {} v{} ({}){}",
+                plugin.id,
+                plugin.version,
+                plugin.name,
+                if plugin.quarantined {
+                    " [quarantined]"
+                } else {
+                    ""
+                }
+            )?;
+            writeln!(f, "    library:  {}", plugin.source_path.display())?;
+            writeln!(
+                f,
+                "    loaded:   {} in {:?} (library {:?}, on_load {:?}, registration {:?})",
+                plugin.loaded_at.format("%H:%M:%S"),
+                timings.total(),
+                timings.library,
+                timings.on_load,
+                timings.registration
+            )?;
+            writeln!(f, "    editors:  {}", plugin.open_editors)?;
+        }
+        if !self.pending_unloads.is_empty() {
+            writeln!(f, "Pending unloads:")?;
+            for pending in &self.pending_unloads {
+                writeln!(
+                    f,
+                    "  {} ({} editor(s) open)",
+                    pending.plugin_id, pending.open_editors
+                )?;
+            }
+        }
+        if !self.retired_libraries.is_empty() {
+            writeln!(
+                f,
+                "Retired libraries still mapped: {}",
+                self.retired_libraries.len()
+            )?;
+            for retired in &self.retired_libraries {
+                writeln!(
+                    f,
+                    "  {} at {} ({})",
+                    retired.plugin_id,
+                    retired.retired_at.format("%H:%M:%S"),
+                    retired.library_path.display()
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plugin(id: &str, library_ms: u64, on_load_ms: u64) -> PluginSnapshot {
+        PluginSnapshot {
+            id: PluginId::new(id),
+            name: id.to_string(),
+            version: "1.0.0".to_string(),
+            author: "Example".to_string(),
+            source_path: PathBuf::from(format!("plugins/editor/{}.so", id)),
+            library_path: PathBuf::from(format!("shadow/{}.so", id)),
+            loaded_at: Local::now(),
+            load_timings: LoadTimings {
+                library: Duration::from_millis(library_ms),
+                on_load: Duration::from_millis(on_load_ms),
+                registration: Duration::from_millis(1),
+            },
+            open_editors: 2,
+            quarantined: false,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_serializes_and_formats() {
+        let snapshot = PluginManagerSnapshot {
+            taken_at: Local::now(),
+            plugins: vec![
+                plugin("com.example.fast", 5, 1),
+                plugin("com.example.slow", 20, 900),
+            ],
+            pending_unloads: vec![PendingUnload {
+                plugin_id: PluginId::new("com.example.fast"),
+                open_editors: 1,
+            }],
+            retired_libraries: vec![RetiredLibrary {
+                plugin_id: PluginId::new("com.example.fast"),
+                library_path: PathBuf::from("shadow/com.example.fast-1.so"),
+                retired_at: Local::now(),
+            }],
+        };
+
+        let json = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(json["plugins"][1]["id"], "com.example.slow");
+        assert_eq!(json["plugins"][0]["open_editors"], 2);
+        assert_eq!(json["pending_unloads"][0]["open_editors"], 1);
+
+        let slowest: Vec<&str> = snapshot
+            .slowest_plugins()
+            .iter()
+            .map(|p| p.id.as_str())
+            .collect();
+        assert_eq!(slowest, ["com.example.slow", "com.example.fast"]);
+
+        let text = snapshot.to_string();
+        assert!(text.contains("2 plugin(s) loaded"));
+        assert!(text.contains("com.example.slow v1.0.0"));
+        assert!(text.contains("on_load 900ms"));
+        assert!(text.contains("com.example.fast (1 editor(s) open)"));
+        assert!(text.contains("Retired libraries still mapped: 1"));
+    }
+}
@@ -0,0 +1,378 @@
+//! Suggesting plugins for files no loaded editor handles.
+//!
+//! When a file has no registered file type or editor,
+//! [`PluginManager::suggest_plugins_for_path`] looks through the plugin
+//! directories for libraries that claim its extension but aren't loaded, so
+//! the editor can name the plugin to enable instead of only failing.
+//!
+//! A library's claim is read from, in order:
+//!
+//! - its [sidecar](crate::sidecar), read without loading the library. A
+//!   sidecar without a version is assumed compatible.
+//!
+//! - the library's optional `_plugin_supported_extensions` export (see
+//!   [`PluginSupportedExtensions`]). The library is loaded to read it, after
+//!   the same integrity check as a plugin load, but its plugin is never
+//!   created. The ID and name are taken from the file name.
+//!
+//! Libraries with neither are not suggested.
+
+use parking_lot::Mutex;
+use plugin_editor_api::{PluginId, PluginSupportedExtensions, VersionComponent, VersionInfo};
+use std::collections::{HashMap, HashSet};
+use std::ffi::CStr;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::marketplace::{project_plugin_dir, GLOBAL_PLUGIN_DIR};
+use crate::sidecar::{sidecar_path, PluginSidecar};
+use crate::{
+    plugin_library_paths, shadow_copy, PermanentLibrary, PluginManager, PluginManagerError,
+};
+
+/// A plugin on disk that is not loaded but claims a file's extension.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginSuggestion {
+    pub plugin_id: PluginId,
+    pub name: String,
+    /// Extensions the plugin claims, lowercase and without the dot
+    pub extensions: Vec<String>,
+    pub library_path: PathBuf,
+    /// Why this engine can't load the plugin, when its version is known and
+    /// doesn't match
+    pub incompatible: Option<VersionComponent>,
+}
+
+impl PluginSuggestion {
+    /// Whether the plugin claims `extension` (with or without the dot, in
+    /// any case).
+    pub fn claims(&self, extension: &str) -> bool {
+        let extension = normalize_extension(extension);
+        self.extensions.contains(&extension)
+    }
+
+    /// Explanation for a user who tried to open `path`.
+    pub fn message(&self, path: &Path) -> String {
+        let file_type = match path.extension() {
+            Some(extension) => format!(".{}", extension.to_string_lossy()),
+            None => "this file".to_string(),
+        };
+        match self.incompatible {
+            None => format!(
+                "The file type {} is supported by plugin {}, which is not loaded",
+                file_type, self.name
+            ),
+            Some(component) => format!(
+                "The file type {} is supported by plugin {}, which is not loaded: \
+                 its {} is incompatible with this engine",
+                file_type, self.name, component
+            ),
+        }
+    }
+}
+
+impl PluginManager {
+    /// Plugins in the global and project plugin directories that claim
+    /// `path`'s extension but aren't loaded.
+    ///
+    /// Meant for when [`create_editor_for_file`](Self::create_editor_for_file)
+    /// fails with [`PluginManagerError::NoFileTypeForPath`] or
+    /// [`PluginManagerError::NoEditorForFileType`]. Plugins whose version
+    /// rules them out are still returned, with
+    /// [`incompatible`](PluginSuggestion::incompatible) set, after the
+    /// compatible ones.
+    pub fn suggest_plugins_for_path(&self, path: &Path) -> Vec<PluginSuggestion> {
+        let Some(extension) = path.extension().and_then(|e| e.to_str()) else {
+            return Vec::new();
+        };
+
+        let mut dirs = vec![PathBuf::from(GLOBAL_PLUGIN_DIR)];
+        if let Some(project_root) = &self.project_root {
+            let dir = project_plugin_dir(project_root);
+            if !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+
+        // The same plugin may be installed globally and in the project
+        let mut seen = HashSet::new();
+        let mut suggestions: Vec<PluginSuggestion> = dirs
+            .iter()
+            .flat_map(|dir| self.suggestions_in_dir(dir, extension))
+            .filter(|suggestion| seen.insert(suggestion.plugin_id.clone()))
+            .collect();
+        suggestions.sort_by_key(|suggestion| suggestion.incompatible.is_some());
+        suggestions
+    }
+
+    fn suggestions_in_dir(&self, dir: &Path, extension: &str) -> Vec<PluginSuggestion> {
+        let loaded: HashSet<&Path> = self
+            .plugins
+            .values()
+            .map(|plugin| plugin.source_path.as_path())
+            .collect();
+
+        plugin_library_paths(dir)
+            .into_iter()
+            .filter(|library| !loaded.contains(library.as_path()))
+            .filter_map(|library| self.library_suggestion(&library))
+            .filter(|suggestion| {
+                !self.plugins.contains_key(&suggestion.plugin_id) && suggestion.claims(extension)
+            })
+            .collect()
+    }
+
+    fn library_suggestion(&self, library: &Path) -> Option<PluginSuggestion> {
+        let sidecar_path = sidecar_path(library);
+        if sidecar_path.exists() {
+            return match PluginSidecar::read(&sidecar_path) {
+                Ok(sidecar) => Some(sidecar_suggestion(sidecar, library, &self.engine_version)),
+                Err(e) => {
+                    tracing::warn!("Ignoring plugin sidecar {:?}: {}", sidecar_path, e);
+                    None
+                }
+            };
+        }
+
+        let probe = self.probed_libraries.get(library)?;
+        let stem = library
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Some(PluginSuggestion {
+            plugin_id: PluginId::new(stem.clone()),
+            name: stem,
+            extensions: probe.extensions,
+            library_path: library.to_path_buf(),
+            incompatible: self
+                .engine_version
+                .check_compatible(&probe.version, self.strict_rustc_check)
+                .err(),
+        })
+    }
+}
+
+fn normalize_extension(extension: &str) -> String {
+    extension
+        .trim()
+        .trim_start_matches('.')
+        .to_ascii_lowercase()
+}
+
+/// Parse the `;`-separated list returned by `_plugin_supported_extensions`.
+fn parse_extension_list(list: &str) -> Vec<String> {
+    list.split(';')
+        .map(normalize_extension)
+        .filter(|extension| !extension.is_empty())
+        .collect()
+}
+
+fn sidecar_suggestion(
+    sidecar: PluginSidecar,
+    library: &Path,
+    engine: &VersionInfo,
+) -> PluginSuggestion {
+    PluginSuggestion {
+        name: sidecar.display_name(),
+        incompatible: sidecar
+            .incompatibility(engine)
+            .map(|(_, component)| component),
+        plugin_id: PluginId::new(sidecar.id),
+        extensions: sidecar
+            .extensions
+            .iter()
+            .map(|extension| normalize_extension(extension))
+            .filter(|extension| !extension.is_empty())
+            .collect(),
+        library_path: library.to_path_buf(),
+    }
+}
+
+/// What a library without a sidecar says about itself.
+#[derive(Debug, Clone)]
+struct LibraryProbe {
+    extensions: Vec<String>,
+    version: VersionInfo,
+}
+
+struct CachedProbe {
+    modified: Option<SystemTime>,
+    probe: Option<LibraryProbe>,
+}
+
+/// Probed libraries by path. Probing loads the library for good, so it is
+/// only repeated when the file changes.
+#[derive(Default)]
+pub(crate) struct ProbeCache(Mutex<HashMap<PathBuf, CachedProbe>>);
+
+impl ProbeCache {
+    fn get(&self, library: &Path) -> Option<LibraryProbe> {
+        let modified = std::fs::metadata(library)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        let mut cache = self.0.lock();
+        if let Some(cached) = cache.get(library) {
+            if cached.modified == modified {
+                return cached.probe.clone();
+            }
+        }
+
+        let probe = probe_library(library).unwrap_or_else(|e| {
+            tracing::debug!("Could not probe plugin library {:?}: {}", library, e);
+            None
+        });
+        cache.insert(
+            library.to_path_buf(),
+            CachedProbe {
+                modified,
+                probe: probe.clone(),
+            },
+        );
+        probe
+    }
+}
+
+/// Read the extensions and version a library exports, without creating its
+/// plugin. `Ok(None)` if it doesn't export `_plugin_supported_extensions`.
+fn probe_library(path: &Path) -> Result<Option<LibraryProbe>, PluginManagerError> {
+    let load_error = |message: String| PluginManagerError::LibraryLoadError {
+        path: path.to_path_buf(),
+        message,
+    };
+    let shadow_path = shadow_copy::copy(path).map_err(|e| {
+        load_error(format!(
+            "cannot copy library to the shadow directory: {}",
+            e
+        ))
+    })?;
+    let library = PermanentLibrary::new(&shadow_path).map_err(|e| load_error(e.to_string()))?;
+    PluginManager::verify_library_integrity(path, &library)?;
+
+    let list = unsafe {
+        // SAFETY: The library stays loaded for the process lifetime, and the
+        // export returns a static NUL-terminated string.
+        let Ok(supported_extensions) =
+            library.get::<PluginSupportedExtensions>(b"_plugin_supported_extensions")
+        else {
+            return Ok(None);
+        };
+        let list = supported_extensions();
+        if list.is_null() {
+            return Ok(None);
+        }
+        CStr::from_ptr(list).to_string_lossy().into_owned()
+    };
+
+    let version_fn = unsafe {
+        // SAFETY: Same contract as the version check in `open_plugin`.
+        library
+            .get::<extern "C" fn() -> VersionInfo>(b"_plugin_version")
+            .map_err(|e| PluginManagerError::MissingSymbol {
+                symbol: "_plugin_version".to_string(),
+                message: e.to_string(),
+            })?
+    };
+    let version = version_fn();
+
+    Ok(Some(LibraryProbe {
+        extensions: parse_extension_list(&list),
+        version,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An empty "library" with a sidecar; never loaded.
+    fn write_plugin(dir: &Path, stem: &str, sidecar: &str) -> PathBuf {
+        let library = dir
+            .join(stem)
+            .with_extension(std::env::consts::DLL_EXTENSION);
+        std::fs::write(&library, b"").unwrap();
+        std::fs::write(sidecar_path(&library), sidecar).unwrap();
+        library
+    }
+
+    #[test]
+    fn test_parse_extension_list() {
+        assert_eq!(
+            parse_extension_list("obj;.FBX;; mtl ;"),
+            ["obj", "fbx", "mtl"]
+        );
+        assert!(parse_extension_list("").is_empty());
+    }
+
+    #[test]
+    fn test_sidecar_suggestions() {
+        let dir = tempfile::tempdir().unwrap();
+        let engine = VersionInfo::current();
+        let obj = write_plugin(
+            dir.path(),
+            "obj_editor",
+            r#"
+                id = "com.example.obj_editor"
+                name = "OBJ Editor"
+                extensions = ["obj", ".MTL"]
+            "#,
+        );
+        write_plugin(
+            dir.path(),
+            "old_obj_editor",
+            &format!(
+                "id = \"com.example.old_obj\"\nextensions = [\"obj\"]\napi_version = {}\n",
+                engine.api_version + 1
+            ),
+        );
+        write_plugin(
+            dir.path(),
+            "png_viewer",
+            "id = \"com.example.png\"\nextensions = [\"png\"]\n",
+        );
+        write_plugin(dir.path(), "broken", "not = [valid");
+
+        let manager = PluginManager::new();
+        let suggestions = manager.suggestions_in_dir(dir.path(), "OBJ");
+        assert_eq!(suggestions.len(), 2);
+
+        let current = &suggestions[0];
+        assert_eq!(current.plugin_id.as_str(), "com.example.obj_editor");
+        assert_eq!(current.name, "OBJ Editor");
+        assert_eq!(current.extensions, ["obj", "mtl"]);
+        assert_eq!(current.library_path, obj);
+        assert_eq!(current.incompatible, None);
+        assert!(current.claims(".mtl"));
+
+        // Reported, but flagged; the name falls back to the ID
+        let old = &suggestions[1];
+        assert_eq!(old.name, "com.example.old_obj");
+        assert_eq!(old.incompatible, Some(VersionComponent::ApiVersion));
+
+        assert!(manager.suggestions_in_dir(dir.path(), "txt").is_empty());
+    }
+
+    #[test]
+    fn test_message() {
+        let suggestion = PluginSuggestion {
+            plugin_id: PluginId::new("com.example.foo"),
+            name: "Foo Editor".to_string(),
+            extensions: vec!["foo".to_string()],
+            library_path: PathBuf::from("plugins/editor/foo.so"),
+            incompatible: None,
+        };
+        assert_eq!(
+            suggestion.message(Path::new("assets/level.foo")),
+            "The file type .foo is supported by plugin Foo Editor, which is not loaded"
+        );
+
+        let incompatible = PluginSuggestion {
+            incompatible: Some(VersionComponent::EngineMajor),
+            ..suggestion
+        };
+        assert_eq!(
+            incompatible.message(Path::new("level.foo")),
+            "The file type .foo is supported by plugin Foo Editor, which is not loaded: \
+             its engine major version is incompatible with this engine"
+        );
+    }
+}
@@ -44,12 +44,10 @@ fn exports_required_symbols() {
     let _: libloading::Symbol<unsafe extern "C" fn() -> VersionInfo> =
         unsafe { lib.get(b"_plugin_version") }.expect("_plugin_version symbol");
 
-    // _plugin_create
-    let _: libloading::Symbol<
-        unsafe extern "C" fn(
-            *const std::ffi::c_void,
-        ) -> &'static mut dyn plugin_editor_api::EditorPluginFull,
-    > = unsafe { lib.get(b"_plugin_create") }.expect("_plugin_create symbol");
+    // _plugin_create_with_globals
+    let _: libloading::Symbol<plugin_editor_api::PluginCreateWithGlobals> =
+        unsafe { lib.get(b"_plugin_create_with_globals") }
+            .expect("_plugin_create_with_globals symbol");
 
     // _plugin_init_globals
     let _: libloading::Symbol<unsafe extern "C" fn(*const std::ffi::c_void)> =
@@ -57,3 +55,26 @@ fn exports_required_symbols() {
 
     eprintln!("✅ All 3 required FFI symbols found");
 }
+
+#[test]
+fn plugin_sees_host_ui_scale() {
+    use plugin_editor_api::ui_scale;
+
+    let lib = PermanentLibrary::new(PLUGIN_PATH).expect("failed to load plugin dylib");
+
+    let init_fn: libloading::Symbol<plugin_editor_api::PluginInitUiScale> =
+        unsafe { lib.get(b"_plugin_init_ui_scale") }.expect("_plugin_init_ui_scale symbol");
+    let scale_fn: libloading::Symbol<extern "C" fn() -> f32> =
+        unsafe { lib.get(b"_plugin_ui_scale") }.expect("_plugin_ui_scale symbol");
+
+    unsafe { init_fn(ui_scale::host_cell()) };
+
+    ui_scale::publish(1.5);
+    assert_eq!(scale_fn(), 1.5);
+
+    ui_scale::publish(0.75);
+    assert_eq!(scale_fn(), 0.75);
+
+    ui_scale::publish(ui_scale::DEFAULT_UI_SCALE);
+    eprintln!("✅ Plugin follows host UI scale");
+}
@@ -308,6 +308,33 @@ impl RendezvousCoordinator {
                 self.relay_file_chunk(&sid, &pid, file_path, offset, data, is_last)
                     .await?;
             }
+            ClientMessage::RoleAnnounce {
+                session_id: sid,
+                peer_id: pid,
+                protocol_version,
+            } => {
+                self.relay_role_announce(&sid, &pid, protocol_version)
+                    .await?;
+            }
+            ClientMessage::SetRole {
+                session_id: sid,
+                peer_id: pid,
+                target_peer_id,
+                role,
+            } => {
+                self.relay_role_changed(&sid, &pid, target_peer_id, role)
+                    .await?;
+            }
+            ClientMessage::PermissionDenied {
+                session_id: sid,
+                peer_id: pid,
+                target_peer_id,
+                operation,
+                reason,
+            } => {
+                self.relay_permission_denied(&sid, &pid, target_peer_id, operation, reason)
+                    .await?;
+            }
             ClientMessage::Ping => {
                 tx.send(ServerMessage::Pong).await?;
             }
@@ -408,6 +435,7 @@ impl RendezvousCoordinator {
             } else {
                 Some(participant_profiles)
             },
+            host_peer_id: Some(session.host_id.clone()),
         })
         .await?;
 
@@ -432,14 +460,8 @@ impl RendezvousCoordinator {
     async fn handle_kick(&self, sid: &str, pid: &str, target_peer_id: String) -> Result<()> {
         let session = self.sessions.get(sid).context("Session not found")?;
 
-        // Verify the kicker is the host (first participant)
-        let is_host = session
-            .list_peers()
-            .first()
-            .map(|p| p.peer_id == pid)
-            .unwrap_or(false);
-
-        if !is_host {
+        // Verify the kicker is the host
+        if session.host_id != pid {
             warn!(
                 session = %sid,
                 kicker = %pid,
@@ -620,6 +642,92 @@ impl RendezvousCoordinator {
         Ok(())
     }
 
+    async fn relay_role_announce(&self, sid: &str, pid: &str, protocol_version: u32) -> Result<()> {
+        let session = self.sessions.get(sid).context("Session not found")?;
+
+        let msg = ServerMessage::RoleAnnounce {
+            session_id: sid.to_string(),
+            from_peer_id: pid.to_string(),
+            protocol_version,
+        };
+
+        for peer in session.list_peers() {
+            if peer.peer_id != pid {
+                let _ = peer.tx.send(msg.clone()).await;
+            }
+        }
+
+        METRICS
+            .signaling_messages
+            .with_label_values(&["role_announce"])
+            .inc();
+
+        Ok(())
+    }
+
+    /// Roles are validated by every client against its own copy of the role
+    /// table, so the relay only fans the change out.
+    async fn relay_role_changed(
+        &self,
+        sid: &str,
+        pid: &str,
+        target_peer_id: String,
+        role: String,
+    ) -> Result<()> {
+        let session = self.sessions.get(sid).context("Session not found")?;
+
+        let msg = ServerMessage::RoleChanged {
+            session_id: sid.to_string(),
+            from_peer_id: pid.to_string(),
+            target_peer_id,
+            role,
+        };
+
+        for peer in session.list_peers() {
+            if peer.peer_id != pid {
+                let _ = peer.tx.send(msg.clone()).await;
+            }
+        }
+
+        METRICS
+            .signaling_messages
+            .with_label_values(&["role_changed"])
+            .inc();
+
+        Ok(())
+    }
+
+    async fn relay_permission_denied(
+        &self,
+        sid: &str,
+        pid: &str,
+        target_peer_id: String,
+        operation: String,
+        reason: String,
+    ) -> Result<()> {
+        let session = self.sessions.get(sid).context("Session not found")?;
+
+        if let Some(target_peer) = session.get_peer(&target_peer_id) {
+            let _ = target_peer
+                .tx
+                .send(ServerMessage::PermissionDenied {
+                    session_id: sid.to_string(),
+                    from_peer_id: pid.to_string(),
+                    target_peer_id,
+                    operation,
+                    reason,
+                })
+                .await;
+        }
+
+        METRICS
+            .signaling_messages
+            .with_label_values(&["permission_denied"])
+            .inc();
+
+        Ok(())
+    }
+
     async fn broadcast_peer_joined(
         &self,
         sid: &str,
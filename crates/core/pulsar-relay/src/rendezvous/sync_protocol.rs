@@ -104,6 +104,27 @@ pub enum ClientMessage {
         data: Vec<u8>,
         is_last: bool,
     },
+    /// Announce that this client understands session roles
+    RoleAnnounce {
+        session_id: String,
+        peer_id: String,
+        protocol_version: u32,
+    },
+    /// Change a participant's role (validated by every client)
+    SetRole {
+        session_id: String,
+        peer_id: String,
+        target_peer_id: String,
+        role: String,
+    },
+    /// Tell a participant their operation was rejected
+    PermissionDenied {
+        session_id: String,
+        peer_id: String,
+        target_peer_id: String,
+        operation: String,
+        reason: String,
+    },
     /// Heartbeat / keepalive
     Ping,
 }
@@ -121,6 +142,10 @@ pub enum ServerMessage {
         join_token: Option<String>,
         #[serde(default)]
         participant_profiles: Option<Vec<PeerProfile>>,
+        /// Peer that created the session. The participant list comes from an
+        /// unordered map, so its first entry is not the host.
+        #[serde(default)]
+        host_peer_id: Option<String>,
     },
     /// Another peer joined
     PeerJoined {
@@ -220,6 +245,27 @@ pub enum ServerMessage {
         data: Vec<u8>,
         is_last: bool,
     },
+    /// Role support announcement (relayed)
+    RoleAnnounce {
+        session_id: String,
+        from_peer_id: String,
+        protocol_version: u32,
+    },
+    /// Role change (relayed)
+    RoleChanged {
+        session_id: String,
+        from_peer_id: String,
+        target_peer_id: String,
+        role: String,
+    },
+    /// Rejected operation (relayed to the target peer)
+    PermissionDenied {
+        session_id: String,
+        from_peer_id: String,
+        target_peer_id: String,
+        operation: String,
+        reason: String,
+    },
     /// Heartbeat response
     Pong,
     /// Error message
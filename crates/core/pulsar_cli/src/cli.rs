@@ -0,0 +1,179 @@
+//! Argument definitions for the `pulsar` binary.
+
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+/// Headless Pulsar tools for CI and build machines.
+///
+/// Every subcommand runs without opening a window. Pass `--json` for a single
+/// machine-readable document on stdout; exit codes are 0 (success), 1 (the
+/// command ran and found problems), 2 (bad arguments) and 3 (the command could
+/// not run).
+#[derive(Debug, Parser)]
+#[command(name = "pulsar", version, propagate_version = true)]
+pub struct Cli {
+    /// Print the report as JSON on stdout
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Never show progress, even on a terminal
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// Log more to stderr (-v info, -vv debug); RUST_LOG overrides this
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Check the manifest, scenes and blueprints of a project
+    Validate(ValidateArgs),
+    /// Compile every blueprint class of a project to bytecode
+    CompileBlueprints(CompileBlueprintsArgs),
+    /// Stage a project for distribution
+    Export(ExportArgs),
+    /// Summarise a recorded profiler trace
+    ProfileReport(ProfileReportArgs),
+}
+
+impl Command {
+    /// Name used for the `command` field of JSON output.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Validate(_) => "validate",
+            Command::CompileBlueprints(_) => "compile-blueprints",
+            Command::Export(_) => "export",
+            Command::ProfileReport(_) => "profile-report",
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ValidateArgs {
+    /// Project directory (the one containing Pulsar.toml)
+    pub project: PathBuf,
+
+    /// Fail on warnings as well as errors
+    #[arg(long)]
+    pub strict: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct CompileBlueprintsArgs {
+    /// Project directory (the one containing Pulsar.toml)
+    pub project: PathBuf,
+
+    /// Recompile classes whose bytecode is already up to date
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// Project directory (the one containing Pulsar.toml)
+    pub project: PathBuf,
+
+    /// Build configuration to export
+    #[arg(long, value_enum)]
+    pub profile: ExportProfile,
+
+    /// Staging directory; defaults to the project's packaging settings
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+
+    /// Stage content only, without building the game executable
+    #[arg(long)]
+    pub skip_build: bool,
+}
+
+/// Build configurations, matching the project's `build/configuration` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportProfile {
+    Debug,
+    Development,
+    Release,
+    Shipping,
+}
+
+impl ExportProfile {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExportProfile::Debug => "debug",
+            ExportProfile::Development => "development",
+            ExportProfile::Release => "release",
+            ExportProfile::Shipping => "shipping",
+        }
+    }
+
+    /// Whether the game is built with cargo's release profile.
+    pub fn optimized(self) -> bool {
+        matches!(self, ExportProfile::Release | ExportProfile::Shipping)
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ProfileReportArgs {
+    /// Trace database recorded by the profiler (`.db`)
+    pub trace: PathBuf,
+
+    /// Print the per-scope table as CSV instead of a report
+    #[arg(long, conflicts_with = "json")]
+    pub csv: bool,
+
+    /// Only list the N scopes with the most total time
+    #[arg(long)]
+    pub top: Option<usize>,
+
+    /// Also write the trace as Chrome trace JSON, for chrome://tracing or Perfetto
+    #[arg(long, value_name = "PATH")]
+    pub chrome_trace: Option<PathBuf>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition_is_consistent() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_global_flags_follow_subcommand() {
+        let cli =
+            Cli::try_parse_from(["pulsar", "validate", "proj", "--json", "--strict"]).unwrap();
+        assert!(cli.json);
+        match cli.command {
+            Command::Validate(args) => {
+                assert!(args.strict);
+                assert_eq!(args.project, PathBuf::from("proj"));
+            }
+            other => panic!("unexpected command {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_export_requires_known_profile() {
+        assert!(Cli::try_parse_from(["pulsar", "export", "proj"]).is_err());
+        assert!(Cli::try_parse_from(["pulsar", "export", "proj", "--profile", "fast"]).is_err());
+        let cli =
+            Cli::try_parse_from(["pulsar", "export", "proj", "--profile", "shipping"]).unwrap();
+        match cli.command {
+            Command::Export(args) => assert_eq!(args.profile, ExportProfile::Shipping),
+            other => panic!("unexpected command {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_csv_conflicts_with_json() {
+        assert!(
+            Cli::try_parse_from(["pulsar", "profile-report", "t.db", "--csv", "--json"]).is_err()
+        );
+    }
+}
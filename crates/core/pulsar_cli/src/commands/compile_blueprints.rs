@@ -0,0 +1,234 @@
+//! `pulsar compile-blueprints`: batch-compile blueprint classes to bytecode.
+//!
+//! Each class folder (one holding `graph_save.json`) is compiled with the
+//! runtime's [`BytecodeCompiler`] and written to `events/.build/bytecode.json`,
+//! the artifact the game loads. Classes whose bytecode is newer than all of
+//! their sources are skipped unless `--force` is given.
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use engine_fs::derived::blueprints::{bytecode_path, class_inputs};
+use pulsar_game::blueprint_runtime::BytecodeCompiler;
+use serde::Serialize;
+
+use crate::cli::CompileBlueprintsArgs;
+use crate::output::{Output, Progress, Report};
+use crate::project::HeadlessProject;
+
+/// File marking a folder as a blueprint class.
+pub const CLASS_MARKER: &str = "graph_save.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompileStatus {
+    Compiled,
+    UpToDate,
+    Failed,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BlueprintResult {
+    /// Class folder, relative to the project root.
+    pub class: String,
+    pub status: CompileStatus,
+    /// Number of event programs in the bytecode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub events: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct CompileReport {
+    pub compiled: usize,
+    pub up_to_date: usize,
+    pub failed: usize,
+    pub blueprints: Vec<BlueprintResult>,
+}
+
+impl Report for CompileReport {
+    fn success(&self) -> bool {
+        self.failed == 0
+    }
+
+    fn write_text(&self, out: &mut dyn Write) -> io::Result<()> {
+        for result in &self.blueprints {
+            match result.status {
+                CompileStatus::Compiled => writeln!(out, "compiled    {}", result.class)?,
+                CompileStatus::UpToDate => writeln!(out, "up to date  {}", result.class)?,
+                CompileStatus::Failed => writeln!(
+                    out,
+                    "FAILED      {}: {}",
+                    result.class,
+                    result.error.as_deref().unwrap_or("unknown error")
+                )?,
+            }
+        }
+        writeln!(
+            out,
+            "{} compiled, {} up to date, {} failed",
+            self.compiled, self.up_to_date, self.failed
+        )
+    }
+}
+
+pub fn run(args: &CompileBlueprintsArgs, output: &Output) -> anyhow::Result<CompileReport> {
+    let project = HeadlessProject::open(&args.project)?;
+    let classes = class_dirs(&project.files());
+    let mut progress = output.progress(classes.len());
+    Ok(compile_classes(
+        &project,
+        &classes,
+        args.force,
+        &mut progress,
+    ))
+}
+
+/// Blueprint class folders among `files`.
+pub fn class_dirs(files: &[PathBuf]) -> Vec<PathBuf> {
+    files
+        .iter()
+        .filter(|path| path.file_name().is_some_and(|name| name == CLASS_MARKER))
+        .filter_map(|path| path.parent().map(Path::to_path_buf))
+        .collect()
+}
+
+pub fn compile_classes(
+    project: &HeadlessProject,
+    classes: &[PathBuf],
+    force: bool,
+    progress: &mut Progress,
+) -> CompileReport {
+    let compiler = BytecodeCompiler::new();
+    let mut report = CompileReport::default();
+
+    for class_dir in classes {
+        let class = project.display_path(class_dir);
+        progress.step(&class);
+
+        let result = if !force && freshness(class_dir) == Freshness::Fresh {
+            BlueprintResult {
+                class,
+                status: CompileStatus::UpToDate,
+                events: None,
+                error: None,
+            }
+        } else {
+            match compile_class(&compiler, class_dir) {
+                Ok(events) => BlueprintResult {
+                    class,
+                    status: CompileStatus::Compiled,
+                    events: Some(events),
+                    error: None,
+                },
+                Err(error) => BlueprintResult {
+                    class,
+                    status: CompileStatus::Failed,
+                    events: None,
+                    error: Some(error),
+                },
+            }
+        };
+
+        match result.status {
+            CompileStatus::Compiled => report.compiled += 1,
+            CompileStatus::UpToDate => report.up_to_date += 1,
+            CompileStatus::Failed => report.failed += 1,
+        }
+        report.blueprints.push(result);
+    }
+    report
+}
+
+/// Compile one class and write its bytecode; returns the number of events.
+fn compile_class(compiler: &BytecodeCompiler, class_dir: &Path) -> Result<usize, String> {
+    let bytecode = compiler
+        .compile_class(class_dir)
+        .map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(&bytecode).map_err(|e| e.to_string())?;
+
+    let path = bytecode_path(class_dir);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("cannot create {}: {}", parent.display(), e))?;
+    }
+    std::fs::write(&path, json).map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+    Ok(bytecode.event_programs.len())
+}
+
+/// State of a class's compiled bytecode relative to its sources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freshness {
+    Missing,
+    Stale,
+    Fresh,
+}
+
+pub fn freshness(class_dir: &Path) -> Freshness {
+    let Some(built) = modified(&bytecode_path(class_dir)) else {
+        return Freshness::Missing;
+    };
+    let newest_input = class_inputs(class_dir)
+        .iter()
+        .filter_map(|input| modified(input))
+        .max();
+    match newest_input {
+        Some(input) if input > built => Freshness::Stale,
+        _ => Freshness::Fresh,
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn touch(path: &Path, at: SystemTime) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "{}").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(at)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_freshness_compares_bytecode_with_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let class_dir = dir.path().join("Player.class");
+        let then = SystemTime::now() - Duration::from_secs(60);
+
+        touch(&class_dir.join(CLASS_MARKER), then);
+        assert_eq!(freshness(&class_dir), Freshness::Missing);
+
+        touch(&bytecode_path(&class_dir), then + Duration::from_secs(10));
+        assert_eq!(freshness(&class_dir), Freshness::Fresh);
+
+        touch(
+            &class_dir.join("events").join("tick.json"),
+            then + Duration::from_secs(20),
+        );
+        assert_eq!(freshness(&class_dir), Freshness::Stale);
+    }
+
+    #[test]
+    fn test_class_dirs_are_marker_parents() {
+        let files = vec![
+            PathBuf::from("p/src/classes/Player.class/graph_save.json"),
+            PathBuf::from("p/src/classes/Player.class/events/tick.json"),
+            PathBuf::from("p/scenes/main.scene"),
+        ];
+        assert_eq!(
+            class_dirs(&files),
+            vec![PathBuf::from("p/src/classes/Player.class")]
+        );
+    }
+}
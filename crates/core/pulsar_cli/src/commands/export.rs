@@ -0,0 +1,259 @@
+//! `pulsar export`: stage a project for distribution.
+//!
+//! 1. Compile blueprints whose bytecode is out of date
+//! 2. Build the game crate (when the project has one) for the chosen profile
+//! 3. Copy the project's content and the executable into the staging folder,
+//!    `<packaging/staging_dir>/<profile>/` unless `--out` is given
+//! 4. Write `export_manifest.json` listing what was staged
+//!
+//! Game sources (`src/`, `Cargo.*`) are compiled into the executable and are
+//! not staged. Archiving the staged folder is left to the packaging step.
+
+use std::io::{self, BufRead as _, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context as _};
+use engine_backend::services::ensure_core_bootstrap;
+use serde::Serialize;
+
+use super::compile_blueprints::{class_dirs, compile_classes, CompileReport};
+use crate::cli::{ExportArgs, ExportProfile};
+use crate::output::{Output, Report};
+use crate::project::HeadlessProject;
+
+/// Written into the staging folder; also marks it as safe to replace.
+pub const EXPORT_MANIFEST: &str = "export_manifest.json";
+
+/// How many lines of cargo's output to keep when a build fails.
+const BUILD_LOG_TAIL: usize = 30;
+
+#[derive(Debug, Serialize)]
+pub struct ExportReport {
+    pub profile: &'static str,
+    pub staging_dir: String,
+    pub blueprints: CompileReport,
+    /// Staged executable, relative to the staging folder.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub executable: Option<String>,
+    pub files: usize,
+    pub bytes: u64,
+    /// Why the export stopped, when it did.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ExportManifest<'a> {
+    profile: &'a str,
+    executable: Option<&'a str>,
+    files: &'a [StagedFile],
+}
+
+#[derive(Serialize)]
+struct StagedFile {
+    path: String,
+    bytes: u64,
+}
+
+impl Report for ExportReport {
+    fn success(&self) -> bool {
+        self.error.is_none() && self.blueprints.failed == 0
+    }
+
+    fn write_text(&self, out: &mut dyn Write) -> io::Result<()> {
+        if self.blueprints.failed > 0 {
+            self.blueprints.write_text(out)?;
+        }
+        if let Some(error) = &self.error {
+            return writeln!(out, "Export failed: {}", error);
+        }
+        writeln!(
+            out,
+            "Exported {} ({}) to {}: {} files, {} bytes",
+            self.executable.as_deref().unwrap_or("content only"),
+            self.profile,
+            self.staging_dir,
+            self.files,
+            self.bytes
+        )
+    }
+}
+
+pub fn run(args: &ExportArgs, output: &Output) -> anyhow::Result<ExportReport> {
+    let project = HeadlessProject::open(&args.project)?;
+    let root = project.root();
+    let staging_root = match &args.out {
+        Some(out) => std::path::absolute(out)
+            .with_context(|| format!("invalid output directory {}", out.display()))?,
+        None => root.join(project.setting_string("packaging", "staging_dir", "dist/staged/")),
+    };
+    let staging = staging_root.join(args.profile.as_str());
+
+    let files = project.files();
+    let classes = class_dirs(&files);
+    let mut progress = output.progress(classes.len());
+    let blueprints = compile_classes(&project, &classes, false, &mut progress);
+
+    let mut report = ExportReport {
+        profile: args.profile.as_str(),
+        staging_dir: staging.display().to_string(),
+        blueprints,
+        executable: None,
+        files: 0,
+        bytes: 0,
+        error: None,
+    };
+    if report.blueprints.failed > 0 {
+        report.error = Some(format!(
+            "{} blueprint(s) failed to compile",
+            report.blueprints.failed
+        ));
+        return Ok(report);
+    }
+
+    let executable = if !args.skip_build && root.join("Cargo.toml").is_file() {
+        progress.status(format!("Building game ({})", args.profile.as_str()));
+        ensure_core_bootstrap(root).map_err(anyhow::Error::msg)?;
+        match build_game(root, args.profile)? {
+            Ok(executable) => Some(executable),
+            Err(log) => {
+                report.error = Some(format!("game build failed:\n{}", log));
+                return Ok(report);
+            }
+        }
+    } else {
+        None
+    };
+
+    prepare_staging_dir(&staging)?;
+
+    // Never stage earlier exports, the packaging output or game sources
+    let output_dir = root.join(project.setting_string("packaging", "output_dir", "dist/"));
+    let excluded = [staging_root.as_path(), output_dir.as_path()];
+    let mut staged = Vec::new();
+    for file in &files {
+        let relative = project.display_path(file);
+        if excluded.iter().any(|dir| file.starts_with(dir))
+            || relative.starts_with("src/")
+            || relative == "Cargo.toml"
+            || relative == "Cargo.lock"
+        {
+            continue;
+        }
+        progress.status(format!("Staging {}", relative));
+        let bytes = copy_into(file, &staging.join(&relative))?;
+        staged.push(StagedFile {
+            path: relative,
+            bytes,
+        });
+    }
+
+    if let Some(executable) = executable {
+        let name = executable
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .context("cargo reported an executable without a file name")?;
+        copy_into(&executable, &staging.join(&name))?;
+        report.executable = Some(name);
+    }
+
+    report.files = staged.len();
+    report.bytes = staged.iter().map(|f| f.bytes).sum();
+    let manifest = ExportManifest {
+        profile: report.profile,
+        executable: report.executable.as_deref(),
+        files: &staged,
+    };
+    std::fs::write(
+        staging.join(EXPORT_MANIFEST),
+        serde_json::to_string_pretty(&manifest)?,
+    )
+    .with_context(|| format!("cannot write {}", EXPORT_MANIFEST))?;
+
+    Ok(report)
+}
+
+/// Empty `staging` for a new export, refusing to clear anything that isn't a
+/// previous export.
+fn prepare_staging_dir(staging: &Path) -> anyhow::Result<()> {
+    if staging.exists() {
+        let is_empty = std::fs::read_dir(staging)
+            .with_context(|| format!("cannot read {}", staging.display()))?
+            .next()
+            .is_none();
+        if !is_empty {
+            if !staging.join(EXPORT_MANIFEST).is_file() {
+                bail!(
+                    "{} is not empty and does not hold a previous export",
+                    staging.display()
+                );
+            }
+            std::fs::remove_dir_all(staging)
+                .with_context(|| format!("cannot clear {}", staging.display()))?;
+        }
+    }
+    std::fs::create_dir_all(staging).with_context(|| format!("cannot create {}", staging.display()))
+}
+
+fn copy_into(from: &Path, to: &Path) -> anyhow::Result<u64> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("cannot create {}", parent.display()))?;
+    }
+    std::fs::copy(from, to)
+        .with_context(|| format!("cannot copy {} to {}", from.display(), to.display()))
+}
+
+/// Run cargo for the game crate.
+///
+/// The outer error means cargo couldn't be run; the inner one is a failed
+/// build, carrying the end of its output.
+fn build_game(root: &Path, profile: ExportProfile) -> anyhow::Result<Result<PathBuf, String>> {
+    let mut command = Command::new("cargo");
+    command
+        .current_dir(root)
+        .args(["build", "--message-format=json-render-diagnostics"]);
+    if profile.optimized() {
+        command.arg("--release");
+    }
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("cannot run cargo")?;
+
+    // Diagnostics arrive on stderr; drain it on a thread so neither pipe fills
+    let stderr = child.stderr.take().context("cargo stderr not captured")?;
+    let log = std::thread::spawn(move || {
+        let mut tail = std::collections::VecDeque::with_capacity(BUILD_LOG_TAIL);
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            if tail.len() == BUILD_LOG_TAIL {
+                tail.pop_front();
+            }
+            tail.push_back(line);
+        }
+        Vec::from(tail).join("\n")
+    });
+
+    let stdout = child.stdout.take().context("cargo stdout not captured")?;
+    let mut executable = None;
+    for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+        let Ok(message) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+        if message["reason"] == "compiler-artifact" {
+            if let Some(path) = message["executable"].as_str() {
+                executable = Some(PathBuf::from(path));
+            }
+        }
+    }
+
+    let status = child.wait().context("cargo did not finish")?;
+    let log = log.join().unwrap_or_default();
+    Ok(match (status.success(), executable) {
+        (true, Some(executable)) => Ok(executable),
+        (true, None) => Err("cargo built no executable".to_string()),
+        (false, _) => Err(log),
+    })
+}
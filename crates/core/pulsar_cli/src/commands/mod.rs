@@ -0,0 +1,35 @@
+//! Subcommand implementations. Each `run` returns a [`Report`](crate::output::Report)
+//! or an error meaning the command could not run at all.
+
+pub mod compile_blueprints;
+pub mod export;
+pub mod profile_report;
+pub mod validate;
+
+use std::process::ExitCode;
+
+use crate::cli::{Cli, Command};
+use crate::output::{Output, EXIT_ERROR};
+
+pub fn dispatch(cli: &Cli) -> ExitCode {
+    let output = Output::new(cli.json, cli.quiet);
+    let name = cli.command.name();
+    match &cli.command {
+        Command::Validate(args) => output.finish(name, validate::run(args, &output)),
+        Command::CompileBlueprints(args) => {
+            output.finish(name, compile_blueprints::run(args, &output))
+        }
+        Command::Export(args) => output.finish(name, export::run(args, &output)),
+        Command::ProfileReport(args) if args.csv => match profile_report::run(args, &output) {
+            Ok(report) => match report.write_csv(&mut std::io::stdout().lock()) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(e) => {
+                    eprintln!("error: failed to write output: {}", e);
+                    ExitCode::from(EXIT_ERROR)
+                }
+            },
+            Err(e) => output.finish::<profile_report::ProfileReport>(name, Err(e)),
+        },
+        Command::ProfileReport(args) => output.finish(name, profile_report::run(args, &output)),
+    }
+}
@@ -0,0 +1,485 @@
+//! `pulsar profile-report`: aggregate a recorded profiler trace.
+//!
+//! Reads the trace database the profiler window writes and produces the same
+//! per-scope statistics as its statistics panel, plus frame-time percentiles
+//! and per-thread busy time. `--csv` prints only the per-scope table.
+//! `--chrome-trace` also converts the trace to the Trace Event Format, to open
+//! in chrome://tracing or Perfetto.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use anyhow::{bail, Context as _};
+use serde::Serialize;
+
+use crate::cli::ProfileReportArgs;
+use crate::output::{Output, Report};
+
+/// Span name the profiler uses to record frame times.
+const FRAME_MARKER: &str = "__FRAME_MARKER__";
+
+/// One recorded scope, independent of the profiler's own event type.
+#[derive(Debug, Clone)]
+pub struct TraceEvent {
+    pub name: String,
+    pub thread_id: u64,
+    pub thread_name: Option<String>,
+    pub start_ns: u64,
+    pub duration_ns: u64,
+    pub depth: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScopeStats {
+    pub name: String,
+    pub calls: usize,
+    pub total_ns: u64,
+    pub mean_ns: u64,
+    pub min_ns: u64,
+    pub max_ns: u64,
+    pub p95_ns: u64,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct FrameStats {
+    pub frames: usize,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ThreadStats {
+    pub thread_id: u64,
+    pub name: String,
+    pub spans: usize,
+    /// Time spent in top-level scopes.
+    pub busy_ns: u64,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ProfileReport {
+    pub trace: String,
+    pub events: usize,
+    /// From the first scope's start to the last scope's end.
+    pub duration_ns: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frames: Option<FrameStats>,
+    pub threads: Vec<ThreadStats>,
+    /// Sorted by total time, longest first.
+    pub scopes: Vec<ScopeStats>,
+    /// Where `--chrome-trace` wrote the converted trace.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chrome_trace: Option<String>,
+}
+
+impl Report for ProfileReport {
+    fn success(&self) -> bool {
+        true
+    }
+
+    fn write_text(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}: {} events over {}",
+            self.trace,
+            self.events,
+            format_duration(self.duration_ns)
+        )?;
+        if let Some(frames) = &self.frames {
+            writeln!(
+                out,
+                "Frames: {}  mean {:.2}ms  p50 {:.2}ms  p95 {:.2}ms  p99 {:.2}ms  max {:.2}ms",
+                frames.frames,
+                frames.mean_ms,
+                frames.p50_ms,
+                frames.p95_ms,
+                frames.p99_ms,
+                frames.max_ms
+            )?;
+        }
+        for thread in &self.threads {
+            writeln!(
+                out,
+                "Thread {:<20} {:>8} spans  {:>10} busy",
+                thread.name,
+                thread.spans,
+                format_duration(thread.busy_ns)
+            )?;
+        }
+        writeln!(
+            out,
+            "\n{:<40} {:>8} {:>10} {:>10} {:>10} {:>10}",
+            "Scope", "Calls", "Total", "Mean", "P95", "Max"
+        )?;
+        for scope in &self.scopes {
+            writeln!(
+                out,
+                "{:<40} {:>8} {:>10} {:>10} {:>10} {:>10}",
+                scope.name,
+                scope.calls,
+                format_duration(scope.total_ns),
+                format_duration(scope.mean_ns),
+                format_duration(scope.p95_ns),
+                format_duration(scope.max_ns)
+            )?;
+        }
+        if let Some(path) = &self.chrome_trace {
+            writeln!(out, "\nChrome trace written to {}", path)?;
+        }
+        Ok(())
+    }
+}
+
+impl ProfileReport {
+    pub fn write_csv(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "name,calls,total_ns,mean_ns,min_ns,max_ns,p95_ns")?;
+        for s in &self.scopes {
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{}",
+                csv_field(&s.name),
+                s.calls,
+                s.total_ns,
+                s.mean_ns,
+                s.min_ns,
+                s.max_ns,
+                s.p95_ns
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run(args: &ProfileReportArgs, _output: &Output) -> anyhow::Result<ProfileReport> {
+    if !args.trace.is_file() {
+        bail!("trace file {} not found", args.trace.display());
+    }
+    let conn = rusqlite::Connection::open(&args.trace)
+        .with_context(|| format!("cannot open trace {}", args.trace.display()))?;
+    let events: Vec<TraceEvent> = profiling::database::load_events(&conn)
+        .map_err(|e| anyhow::anyhow!("cannot read trace {}: {}", args.trace.display(), e))?
+        .into_iter()
+        .map(|e| TraceEvent {
+            name: e.name,
+            thread_id: e.thread_id,
+            thread_name: e.thread_name,
+            start_ns: e.start_ns,
+            duration_ns: e.duration_ns,
+            depth: e.depth,
+        })
+        .collect();
+
+    let mut report = aggregate(&events, args.top);
+    report.trace = args.trace.display().to_string();
+    if let Some(path) = &args.chrome_trace {
+        export_chrome_trace(&events, path)
+            .with_context(|| format!("cannot write Chrome trace {}", path.display()))?;
+        report.chrome_trace = Some(path.display().to_string());
+    }
+    Ok(report)
+}
+
+/// Writes `events` to `path` as Chrome trace JSON, see [`write_chrome_trace`].
+pub fn export_chrome_trace(events: &[TraceEvent], path: &Path) -> io::Result<()> {
+    let mut out = BufWriter::new(std::fs::File::create(path)?);
+    write_chrome_trace(events, &mut out)?;
+    out.flush()
+}
+
+/// Writes `events` in the Trace Event Format, one event at a time.
+///
+/// Scopes become complete (`X`) events and frame markers global instant
+/// events, with times in microseconds. Thread names become `thread_name`
+/// metadata events. Scopes are written by thread and start time, parents
+/// before the children starting with them, which is the order viewers nest
+/// them in.
+pub fn write_chrome_trace(events: &[TraceEvent], out: &mut dyn Write) -> io::Result<()> {
+    const PID: u32 = 1;
+    let us = |ns: u64| ns as f64 / 1_000.0;
+
+    let mut thread_names: BTreeMap<u64, &str> = BTreeMap::new();
+    for event in events {
+        if let Some(name) = &event.thread_name {
+            thread_names.entry(event.thread_id).or_insert(name);
+        }
+    }
+    let mut order: Vec<&TraceEvent> = events.iter().collect();
+    order.sort_by_key(|e| (e.thread_id, e.start_ns, e.depth));
+
+    let mut first = true;
+    let mut write_event = |out: &mut dyn Write, event: serde_json::Value| -> io::Result<()> {
+        if !first {
+            out.write_all(b",")?;
+        }
+        first = false;
+        out.write_all(b"\n")?;
+        serde_json::to_writer(&mut *out, &event).map_err(io::Error::from)
+    };
+
+    out.write_all(b"{\"displayTimeUnit\":\"ms\",\"traceEvents\":[")?;
+    write_event(
+        out,
+        serde_json::json!({
+            "name": "process_name", "ph": "M", "pid": PID, "tid": 0,
+            "args": { "name": "Pulsar" }
+        }),
+    )?;
+    for (&tid, name) in &thread_names {
+        write_event(
+            out,
+            serde_json::json!({
+                "name": "thread_name", "ph": "M", "pid": PID, "tid": tid,
+                "args": { "name": name }
+            }),
+        )?;
+    }
+    for event in order {
+        let json = if event.name == FRAME_MARKER {
+            serde_json::json!({
+                "name": "Frame", "ph": "i", "s": "g",
+                "pid": PID, "tid": event.thread_id, "ts": us(event.start_ns),
+                "args": { "frame_ms": event.duration_ns as f64 / 1_000_000.0 }
+            })
+        } else {
+            serde_json::json!({
+                "name": event.name, "cat": "scope", "ph": "X",
+                "pid": PID, "tid": event.thread_id,
+                "ts": us(event.start_ns), "dur": us(event.duration_ns)
+            })
+        };
+        write_event(out, json)?;
+    }
+    out.write_all(b"\n]}\n")
+}
+
+pub fn aggregate(events: &[TraceEvent], top: Option<usize>) -> ProfileReport {
+    let mut frame_ms = Vec::new();
+    let mut scopes: HashMap<&str, Vec<u64>> = HashMap::new();
+    let mut threads: HashMap<u64, ThreadStats> = HashMap::new();
+    let mut bounds: Option<(u64, u64)> = None;
+
+    for event in events {
+        if event.name == FRAME_MARKER {
+            frame_ms.push(event.duration_ns as f64 / 1_000_000.0);
+            continue;
+        }
+        let end = event.start_ns + event.duration_ns;
+        bounds = Some(match bounds {
+            Some((start, stop)) => (start.min(event.start_ns), stop.max(end)),
+            None => (event.start_ns, end),
+        });
+
+        scopes
+            .entry(event.name.as_str())
+            .or_default()
+            .push(event.duration_ns);
+
+        let thread = threads
+            .entry(event.thread_id)
+            .or_insert_with(|| ThreadStats {
+                thread_id: event.thread_id,
+                name: String::new(),
+                spans: 0,
+                busy_ns: 0,
+            });
+        if let Some(name) = &event.thread_name {
+            thread.name = name.clone();
+        }
+        thread.spans += 1;
+        if event.depth == 0 {
+            thread.busy_ns += event.duration_ns;
+        }
+    }
+
+    let mut scopes: Vec<ScopeStats> = scopes
+        .into_iter()
+        .map(|(name, mut durations)| {
+            durations.sort_unstable();
+            let total: u64 = durations.iter().sum();
+            ScopeStats {
+                name: name.to_string(),
+                calls: durations.len(),
+                total_ns: total,
+                mean_ns: total / durations.len() as u64,
+                min_ns: durations[0],
+                max_ns: durations[durations.len() - 1],
+                p95_ns: percentile(&durations, 95.0),
+            }
+        })
+        .collect();
+    scopes.sort_by(|a, b| b.total_ns.cmp(&a.total_ns).then(a.name.cmp(&b.name)));
+    if let Some(top) = top {
+        scopes.truncate(top);
+    }
+
+    let mut threads: Vec<ThreadStats> = threads
+        .into_values()
+        .map(|mut t| {
+            if t.name.is_empty() {
+                t.name = format!("Thread {}", t.thread_id);
+            }
+            t
+        })
+        .collect();
+    threads.sort_by_key(|t| t.thread_id);
+
+    ProfileReport {
+        trace: String::new(),
+        events: events.len(),
+        duration_ns: bounds.map_or(0, |(start, end)| end - start),
+        frames: frame_stats(frame_ms),
+        threads,
+        scopes,
+        chrome_trace: None,
+    }
+}
+
+fn frame_stats(mut frame_ms: Vec<f64>) -> Option<FrameStats> {
+    if frame_ms.is_empty() {
+        return None;
+    }
+    frame_ms.sort_by(f64::total_cmp);
+    Some(FrameStats {
+        frames: frame_ms.len(),
+        mean_ms: frame_ms.iter().sum::<f64>() / frame_ms.len() as f64,
+        p50_ms: percentile(&frame_ms, 50.0),
+        p95_ms: percentile(&frame_ms, 95.0),
+        p99_ms: percentile(&frame_ms, 99.0),
+        max_ms: frame_ms[frame_ms.len() - 1],
+    })
+}
+
+/// Nearest-rank percentile of sorted, non-empty `values`.
+fn percentile<T: Copy>(values: &[T], pct: f64) -> T {
+    let rank = (pct / 100.0 * values.len() as f64).ceil() as usize;
+    values[rank.clamp(1, values.len()) - 1]
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn format_duration(ns: u64) -> String {
+    if ns < 1_000 {
+        format!("{}ns", ns)
+    } else if ns < 1_000_000 {
+        format!("{:.2}µs", ns as f64 / 1_000.0)
+    } else if ns < 1_000_000_000 {
+        format!("{:.2}ms", ns as f64 / 1_000_000.0)
+    } else {
+        format!("{:.2}s", ns as f64 / 1_000_000_000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(
+        name: &str,
+        thread_id: u64,
+        start_ns: u64,
+        duration_ns: u64,
+        depth: u32,
+    ) -> TraceEvent {
+        TraceEvent {
+            name: name.to_string(),
+            thread_id,
+            thread_name: None,
+            start_ns,
+            duration_ns,
+            depth,
+        }
+    }
+
+    #[test]
+    fn test_aggregate_scopes_threads_and_frames() {
+        let events = vec![
+            event("tick", 1, 0, 100, 0),
+            event("physics", 1, 10, 40, 1),
+            event("tick", 1, 200, 300, 0),
+            event("render", 2, 50, 500, 0),
+            event(FRAME_MARKER, 1, 0, 16_000_000, 0),
+            event(FRAME_MARKER, 1, 0, 20_000_000, 0),
+        ];
+        let report = aggregate(&events, None);
+
+        assert_eq!(report.events, 6);
+        assert_eq!(report.duration_ns, 550);
+        let names: Vec<_> = report.scopes.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["render", "tick", "physics"]);
+        let tick = &report.scopes[1];
+        assert_eq!((tick.calls, tick.total_ns, tick.mean_ns), (2, 400, 200));
+        assert_eq!((tick.min_ns, tick.max_ns, tick.p95_ns), (100, 300, 300));
+
+        assert_eq!(report.threads.len(), 2);
+        assert_eq!(report.threads[0].busy_ns, 400);
+        assert_eq!(report.threads[0].spans, 3);
+        assert_eq!(report.threads[1].name, "Thread 2");
+
+        let frames = report.frames.unwrap();
+        assert_eq!(frames.frames, 2);
+        assert_eq!(frames.mean_ms, 18.0);
+        assert_eq!(frames.p50_ms, 16.0);
+        assert_eq!(frames.max_ms, 20.0);
+    }
+
+    #[test]
+    fn test_top_limits_scopes() {
+        let events = vec![event("a", 1, 0, 1, 0), event("b", 1, 0, 2, 0)];
+        let report = aggregate(&events, Some(1));
+        assert_eq!(report.scopes.len(), 1);
+        assert_eq!(report.scopes[0].name, "b");
+    }
+
+    #[test]
+    fn test_chrome_trace_round_trips_as_json() {
+        let mut main = event("tick", 1, 1_000, 5_000, 0);
+        main.thread_name = Some("Main Thread".to_string());
+        let events = vec![
+            event("physics", 1, 1_000, 2_000, 1),
+            main,
+            event("render", 2, 500, 1_500, 0),
+            event(FRAME_MARKER, 1, 6_000, 16_000_000, 0),
+        ];
+        let mut out = Vec::new();
+        write_chrome_trace(&events, &mut out).unwrap();
+
+        let trace: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let trace_events = trace["traceEvents"].as_array().unwrap();
+        // Process and thread names, then every event
+        assert_eq!(trace_events.len(), 2 + events.len());
+        assert_eq!(trace_events[1]["args"]["name"], "Main Thread");
+        assert_eq!(trace_events[1]["tid"], 1);
+
+        let names: Vec<_> = trace_events[2..]
+            .iter()
+            .map(|e| e["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["tick", "physics", "Frame", "render"]);
+        assert_eq!(trace_events[2]["ph"], "X");
+        assert_eq!(trace_events[2]["ts"], 1.0);
+        assert_eq!(trace_events[2]["dur"], 5.0);
+        assert_eq!(trace_events[4]["ph"], "i");
+        assert_eq!(trace_events[4]["args"]["frame_ms"], 16.0);
+    }
+
+    #[test]
+    fn test_csv_quotes_scope_names() {
+        let report = aggregate(&[event("load, \"big\"", 1, 0, 5, 0)], None);
+        let mut csv = Vec::new();
+        report.write_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "name,calls,total_ns,mean_ns,min_ns,max_ns,p95_ns\n\"load, \"\"big\"\"\",1,5,5,5,5,5\n"
+        );
+    }
+}
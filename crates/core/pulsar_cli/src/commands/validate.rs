@@ -0,0 +1,294 @@
+//! `pulsar validate`: check a project before it is built or shipped.
+//!
+//! - **manifest** — `Pulsar.toml` parses, names the project and points at a
+//!   default scene that exists
+//! - **scene** — every scene file loads and its hierarchy is sound (unique
+//!   ids, known parents, no cycles)
+//! - **blueprint** — class graphs parse and their bytecode is up to date
+//!
+//! Errors fail the command; warnings only do with `--strict`.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+use std::path::Path;
+
+use pulsar_scene::SceneFile;
+use serde::Serialize;
+
+use super::compile_blueprints::{class_dirs, freshness, Freshness, CLASS_MARKER};
+use crate::cli::ValidateArgs;
+use crate::output::{Output, Report};
+use crate::project::{HeadlessProject, MANIFEST};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Finding {
+    pub severity: Severity,
+    /// Which check produced this: `manifest`, `scene` or `blueprint`.
+    pub check: &'static str,
+    /// File or folder concerned, relative to the project root.
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ValidationReport {
+    pub files: usize,
+    pub scenes: usize,
+    pub blueprints: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    pub findings: Vec<Finding>,
+    #[serde(skip)]
+    strict: bool,
+}
+
+impl ValidationReport {
+    fn push(&mut self, severity: Severity, check: &'static str, path: String, message: String) {
+        match severity {
+            Severity::Error => self.errors += 1,
+            Severity::Warning => self.warnings += 1,
+        }
+        self.findings.push(Finding {
+            severity,
+            check,
+            path,
+            message,
+        });
+    }
+}
+
+impl Report for ValidationReport {
+    fn success(&self) -> bool {
+        self.errors == 0 && !(self.strict && self.warnings > 0)
+    }
+
+    fn write_text(&self, out: &mut dyn Write) -> io::Result<()> {
+        for finding in &self.findings {
+            let label = match finding.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            writeln!(
+                out,
+                "{}[{}] {}: {}",
+                label, finding.check, finding.path, finding.message
+            )?;
+        }
+        writeln!(
+            out,
+            "Checked {} files ({} scenes, {} blueprints): {} errors, {} warnings",
+            self.files, self.scenes, self.blueprints, self.errors, self.warnings
+        )
+    }
+}
+
+pub fn run(args: &ValidateArgs, output: &Output) -> anyhow::Result<ValidationReport> {
+    let project = HeadlessProject::open(&args.project)?;
+    let files = project.files();
+    let scenes: Vec<_> = files.iter().filter(|p| is_scene_file(p)).collect();
+    let classes = class_dirs(&files);
+
+    let mut report = ValidationReport {
+        files: files.len(),
+        scenes: scenes.len(),
+        blueprints: classes.len(),
+        strict: args.strict,
+        ..Default::default()
+    };
+    let mut progress = output.progress(1 + scenes.len() + classes.len());
+
+    progress.step(MANIFEST);
+    check_manifest(&project, &mut report);
+
+    for scene in scenes {
+        let path = project.display_path(scene);
+        progress.step(&path);
+        match SceneFile::load(scene) {
+            Ok(file) => {
+                let objects: Vec<_> = file
+                    .objects
+                    .iter()
+                    .map(|o| (o.id.as_str(), o.parent.as_deref()))
+                    .collect();
+                for problem in hierarchy_problems(&objects) {
+                    report.push(Severity::Error, "scene", path.clone(), problem);
+                }
+            }
+            Err(e) => report.push(Severity::Error, "scene", path, e.to_string()),
+        }
+    }
+
+    for class_dir in &classes {
+        let path = project.display_path(class_dir);
+        progress.step(&path);
+        check_blueprint(class_dir, path, &mut report);
+    }
+
+    Ok(report)
+}
+
+/// Scene files as saved by the level editor.
+pub fn is_scene_file(path: &Path) -> bool {
+    path.file_name()
+        .map(|name| name.to_string_lossy())
+        .is_some_and(|name| {
+            name.ends_with(".scene") || name.ends_with(".level") || name.ends_with(".level.json")
+        })
+}
+
+fn check_manifest(project: &HeadlessProject, report: &mut ValidationReport) {
+    let path = MANIFEST.to_string();
+    let manifest = match std::fs::read_to_string(project.root().join(MANIFEST))
+        .map_err(|e| e.to_string())
+        .and_then(|text| text.parse::<toml::Table>().map_err(|e| e.to_string()))
+    {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            report.push(Severity::Error, "manifest", path, e);
+            return;
+        }
+    };
+
+    let name = manifest
+        .get("project")
+        .and_then(|p| p.get("name"))
+        .and_then(|n| n.as_str());
+    if name.is_none_or(|n| n.trim().is_empty()) {
+        report.push(
+            Severity::Error,
+            "manifest",
+            path.clone(),
+            "[project] has no name".to_string(),
+        );
+    }
+
+    let default_scene = manifest
+        .get("settings")
+        .and_then(|s| s.get("default_scene"))
+        .and_then(|s| s.as_str());
+    if let Some(scene) = default_scene {
+        if !project.root().join(scene).is_file() {
+            report.push(
+                Severity::Error,
+                "manifest",
+                path,
+                format!("default scene {} does not exist", scene),
+            );
+        }
+    }
+}
+
+fn check_blueprint(class_dir: &Path, path: String, report: &mut ValidationReport) {
+    let graph = std::fs::read_to_string(class_dir.join(CLASS_MARKER))
+        .map_err(|e| e.to_string())
+        .and_then(|text| {
+            serde_json::from_str::<serde_json::Value>(&text).map_err(|e| e.to_string())
+        });
+    if let Err(e) = graph {
+        report.push(
+            Severity::Error,
+            "blueprint",
+            path,
+            format!("{} is unreadable: {}", CLASS_MARKER, e),
+        );
+        return;
+    }
+
+    let message = match freshness(class_dir) {
+        Freshness::Fresh => return,
+        Freshness::Missing => "not compiled; run `pulsar compile-blueprints`",
+        Freshness::Stale => "bytecode is older than its sources; run `pulsar compile-blueprints`",
+    };
+    report.push(Severity::Warning, "blueprint", path, message.to_string());
+}
+
+/// Problems in a scene hierarchy given `(id, parent)` for every object.
+pub fn hierarchy_problems(objects: &[(&str, Option<&str>)]) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut parents: HashMap<&str, Option<&str>> = HashMap::new();
+    for &(id, parent) in objects {
+        if parents.insert(id, parent).is_some() {
+            problems.push(format!("duplicate object id `{}`", id));
+        }
+    }
+
+    for &(id, parent) in objects {
+        if let Some(parent) = parent {
+            if !parents.contains_key(parent) {
+                problems.push(format!("object `{}` has unknown parent `{}`", id, parent));
+            }
+        }
+    }
+
+    // Walk up from each object; reaching an object twice means a cycle
+    let mut in_cycle: HashSet<&str> = HashSet::new();
+    for &(start, _) in objects {
+        if in_cycle.contains(start) {
+            continue;
+        }
+        let mut path = vec![start];
+        let mut current = start;
+        while let Some(Some(parent)) = parents.get(current) {
+            if let Some(pos) = path.iter().position(|id| id == parent) {
+                let cycle = &path[pos..];
+                if cycle.iter().all(|id| !in_cycle.contains(id)) {
+                    problems.push(format!("parent cycle: {}", cycle.join(" -> ")));
+                    in_cycle.extend(cycle.iter().copied());
+                }
+                break;
+            }
+            path.push(parent);
+            current = parent;
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sound_hierarchy_has_no_problems() {
+        let objects = [
+            ("root", None),
+            ("child", Some("root")),
+            ("leaf", Some("child")),
+        ];
+        assert!(hierarchy_problems(&objects).is_empty());
+    }
+
+    #[test]
+    fn test_hierarchy_problems_are_reported() {
+        let objects = [
+            ("a", None),
+            ("a", None),
+            ("orphan", Some("missing")),
+            ("x", Some("y")),
+            ("y", Some("x")),
+            ("below_cycle", Some("x")),
+        ];
+        assert_eq!(
+            hierarchy_problems(&objects),
+            vec![
+                "duplicate object id `a`".to_string(),
+                "object `orphan` has unknown parent `missing`".to_string(),
+                "parent cycle: x -> y".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scene_file_names() {
+        assert!(is_scene_file(Path::new("scenes/main.scene")));
+        assert!(is_scene_file(Path::new("levels/arena.level.json")));
+        assert!(!is_scene_file(Path::new("data/items.json")));
+    }
+}
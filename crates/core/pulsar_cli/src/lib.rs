@@ -0,0 +1,18 @@
+//! Headless Pulsar command line.
+//!
+//! The `pulsar` binary runs project tasks on CI and build machines without the
+//! editor: nothing here creates a window, a GPU device or a GPUI app.
+//!
+//! | Command | Module |
+//! |---|---|
+//! | `pulsar validate <project>` | [`commands::validate`] |
+//! | `pulsar compile-blueprints <project>` | [`commands::compile_blueprints`] |
+//! | `pulsar export <project> --profile <name>` | [`commands::export`] |
+//! | `pulsar profile-report <trace>` | [`commands::profile_report`] |
+//!
+//! All commands share `--json` output and exit codes, see [`output`].
+
+pub mod cli;
+pub mod commands;
+pub mod output;
+pub mod project;
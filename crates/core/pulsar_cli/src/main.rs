@@ -0,0 +1,26 @@
+//! `pulsar` — headless Pulsar tools. See the `pulsar_cli` crate docs.
+
+use std::process::ExitCode;
+
+use clap::Parser;
+use pulsar_cli::cli::Cli;
+use tracing_subscriber::EnvFilter;
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    // Logs go to stderr so stdout stays a clean report
+    let default_level = match cli.verbose {
+        0 => "warn",
+        1 => "info",
+        _ => "debug",
+    };
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_level)),
+        )
+        .with_writer(std::io::stderr)
+        .init();
+
+    pulsar_cli::commands::dispatch(&cli)
+}
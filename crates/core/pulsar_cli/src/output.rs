@@ -0,0 +1,172 @@
+//! Report output, progress and exit codes shared by every subcommand.
+//!
+//! Commands return a [`Report`]; [`Output`] prints it either as text or, with
+//! `--json`, as one document on stdout:
+//!
+//! ```text
+//! {"command": "validate", "success": false, "report": { ... }}
+//! {"command": "validate", "success": false, "error": {"message": "..."}}
+//! ```
+//!
+//! Progress goes to stderr, and only when stderr is a terminal.
+
+use std::io::{self, IsTerminal, Write};
+use std::process::ExitCode;
+
+use serde::Serialize;
+
+/// The command ran and everything passed.
+pub const EXIT_SUCCESS: u8 = 0;
+/// The command ran and found problems (validation errors, failed compiles).
+pub const EXIT_FAILURE: u8 = 1;
+/// The arguments were invalid; clap exits with this itself.
+pub const EXIT_USAGE: u8 = 2;
+/// The command could not run at all (missing project, unreadable trace).
+pub const EXIT_ERROR: u8 = 3;
+
+/// Result of a subcommand.
+pub trait Report: Serialize {
+    /// Decides between [`EXIT_SUCCESS`] and [`EXIT_FAILURE`].
+    fn success(&self) -> bool;
+
+    /// Human-readable rendering for terminals.
+    fn write_text(&self, out: &mut dyn Write) -> io::Result<()>;
+}
+
+#[derive(Serialize)]
+struct Envelope<'a, R: Serialize> {
+    command: &'a str,
+    success: bool,
+    report: &'a R,
+}
+
+#[derive(Serialize)]
+struct ErrorEnvelope<'a> {
+    command: &'a str,
+    success: bool,
+    error: ErrorBody,
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    message: String,
+    /// The error's causes, outermost first.
+    causes: Vec<String>,
+}
+
+pub struct Output {
+    json: bool,
+    show_progress: bool,
+}
+
+impl Output {
+    pub fn new(json: bool, quiet: bool) -> Self {
+        Self {
+            json,
+            show_progress: !quiet && io::stderr().is_terminal(),
+        }
+    }
+
+    pub fn progress(&self, total: usize) -> Progress {
+        Progress {
+            enabled: self.show_progress,
+            total,
+            current: 0,
+        }
+    }
+
+    /// Print the outcome of `command` and pick the exit code.
+    pub fn finish<R: Report>(&self, command: &str, result: anyhow::Result<R>) -> ExitCode {
+        match result {
+            Ok(report) => self.report(command, &report),
+            Err(e) => self.error(command, &e),
+        }
+    }
+
+    fn report<R: Report>(&self, command: &str, report: &R) -> ExitCode {
+        let success = report.success();
+        let mut stdout = io::stdout().lock();
+        let written = if self.json {
+            write_json(
+                &mut stdout,
+                &Envelope {
+                    command,
+                    success,
+                    report,
+                },
+            )
+        } else {
+            report.write_text(&mut stdout)
+        };
+        if let Err(e) = written.and_then(|_| stdout.flush()) {
+            // A closed pipe (`| head`) is not worth a second error message
+            if e.kind() != io::ErrorKind::BrokenPipe {
+                eprintln!("error: failed to write output: {}", e);
+            }
+            return ExitCode::from(EXIT_ERROR);
+        }
+        ExitCode::from(if success { EXIT_SUCCESS } else { EXIT_FAILURE })
+    }
+
+    fn error(&self, command: &str, error: &anyhow::Error) -> ExitCode {
+        if self.json {
+            let envelope = ErrorEnvelope {
+                command,
+                success: false,
+                error: ErrorBody {
+                    message: error.to_string(),
+                    causes: error.chain().skip(1).map(|c| c.to_string()).collect(),
+                },
+            };
+            let _ = write_json(&mut io::stdout().lock(), &envelope);
+        } else {
+            eprintln!("error: {:#}", error);
+        }
+        ExitCode::from(EXIT_ERROR)
+    }
+}
+
+fn write_json(out: &mut dyn Write, value: &impl Serialize) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, value)?;
+    writeln!(out)
+}
+
+/// A `[3/10] message` status line on stderr, redrawn in place.
+pub struct Progress {
+    enabled: bool,
+    total: usize,
+    current: usize,
+}
+
+impl Progress {
+    /// Advance to the next item.
+    pub fn step(&mut self, message: impl std::fmt::Display) {
+        self.current += 1;
+        if self.enabled {
+            let mut stderr = io::stderr().lock();
+            let _ = write!(
+                stderr,
+                "\r\x1b[2K[{}/{}] {}",
+                self.current, self.total, message
+            );
+            let _ = stderr.flush();
+        }
+    }
+
+    /// Show a message without advancing, for phases that aren't counted.
+    pub fn status(&mut self, message: impl std::fmt::Display) {
+        if self.enabled {
+            let mut stderr = io::stderr().lock();
+            let _ = write!(stderr, "\r\x1b[2K{}", message);
+            let _ = stderr.flush();
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if self.enabled {
+            let _ = write!(io::stderr(), "\r\x1b[2K");
+        }
+    }
+}
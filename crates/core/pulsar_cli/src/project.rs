@@ -0,0 +1,129 @@
+//! Opening a project without the editor.
+//!
+//! [`HeadlessProject::open`] does the parts of engine startup a CLI command
+//! needs: registers the settings schemas, loads the editor and project
+//! settings layers and publishes the project on a global [`EngineContext`].
+//! No window, GPU or async runtime is created.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context as _};
+use engine_state::{EngineContext, GlobalSettings, ProjectContext, ProjectSettings};
+
+/// Manifest every Pulsar project has at its root.
+pub const MANIFEST: &str = "Pulsar.toml";
+
+pub struct HeadlessProject {
+    root: PathBuf,
+    settings: Option<ProjectSettings>,
+}
+
+impl HeadlessProject {
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let root = path
+            .canonicalize()
+            .with_context(|| format!("cannot open project at {}", path.display()))?;
+        if !root.join(MANIFEST).is_file() {
+            bail!(
+                "{} is not a Pulsar project (no {} found)",
+                root.display(),
+                MANIFEST
+            );
+        }
+
+        // Same layering as the editor: schema defaults, then the user's editor
+        // settings, then the project's own overrides
+        engine_state::register_default_settings();
+        GlobalSettings::new().load_all();
+        let settings = ProjectSettings::new(&root);
+        if let Some(settings) = &settings {
+            settings.load_all();
+        }
+
+        let context = EngineContext::new();
+        context.set_project(ProjectContext::new(root.clone()));
+        context.set_global();
+
+        Ok(Self { root, settings })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Path relative to the project root, with `/` separators, for reports.
+    pub fn display_path(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+
+    pub fn setting_string(&self, owner: &str, key: &str, default: &str) -> String {
+        self.settings
+            .as_ref()
+            .and_then(|s| s.get(owner, key))
+            .and_then(|v| v.as_str().ok().map(str::to_string))
+            .unwrap_or_else(|| default.to_string())
+    }
+
+    /// Every file of the project, sorted. See [`project_files`].
+    pub fn files(&self) -> Vec<PathBuf> {
+        project_files(&self.root)
+    }
+}
+
+/// Files under `root` that belong to the project.
+///
+/// Follows the scanner's rules — hidden entries and `target/` are skipped —
+/// and additionally honours `.gitignore` files, with or without a repository.
+pub fn project_files(root: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = ignore::WalkBuilder::new(root)
+        .hidden(true)
+        .git_ignore(true)
+        .git_global(false)
+        .git_exclude(false)
+        .require_git(false)
+        .filter_entry(|entry| entry.depth() == 0 || entry.file_name() != "target")
+        .build()
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .map(ignore::DirEntry::into_path)
+        .collect();
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_files_honours_ignore_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for file in [
+            "Pulsar.toml",
+            "scenes/main.scene",
+            "scratch/notes.scene",
+            ".pulsar/project/build.toml",
+            "target/debug/game",
+        ] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+        std::fs::write(root.join(".gitignore"), "scratch/\n").unwrap();
+
+        let files: Vec<String> = project_files(root)
+            .iter()
+            .map(|p| {
+                p.strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        assert_eq!(files, vec!["Pulsar.toml", "scenes/main.scene"]);
+    }
+}
@@ -0,0 +1,294 @@
+//! Runs the `pulsar` binary against fixture projects built in a tempdir.
+
+use std::path::Path;
+use std::process::Command;
+
+use serde_json::Value;
+
+const MANIFEST: &str = r#"[project]
+name = "Fixture"
+version = "0.1.0"
+
+[settings]
+default_scene = "scenes/main.scene"
+"#;
+
+const SCENE: &str = r#"{
+  "version": "2.1",
+  "objects": [
+    { "id": "root", "name": "Root", "object_type": "Empty" },
+    { "id": "light", "name": "Sun", "object_type": "Empty", "parent": "root" }
+  ]
+}"#;
+
+fn write(root: &Path, file: &str, content: &str) {
+    let path = root.join(file);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+fn fixture_project() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "Pulsar.toml", MANIFEST);
+    write(dir.path(), "scenes/main.scene", SCENE);
+    write(dir.path(), "assets/readme.txt", "fixture asset");
+    dir
+}
+
+/// Run `pulsar` and return its exit code and parsed JSON stdout.
+fn pulsar_json(args: &[&str]) -> (i32, Value) {
+    let output = Command::new(env!("CARGO_BIN_EXE_pulsar"))
+        .args(args)
+        .arg("--json")
+        .output()
+        .expect("failed to run pulsar");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json = serde_json::from_str(&stdout).unwrap_or_else(|e| {
+        panic!(
+            "stdout is not JSON ({}):\n{}\nstderr:\n{}",
+            e,
+            stdout,
+            String::from_utf8_lossy(&output.stderr)
+        )
+    });
+    (output.status.code().unwrap(), json)
+}
+
+fn path_str(path: &Path) -> &str {
+    path.to_str().unwrap()
+}
+
+#[test]
+fn validate_clean_project_succeeds() {
+    let project = fixture_project();
+    let (code, json) = pulsar_json(&["validate", path_str(project.path())]);
+
+    assert_eq!(code, 0, "{json:#}");
+    assert_eq!(json["command"], "validate");
+    assert_eq!(json["success"], true);
+    assert_eq!(json["report"]["scenes"], 1);
+    assert_eq!(json["report"]["errors"], 0);
+}
+
+#[test]
+fn validate_reports_broken_scenes_and_manifest() {
+    let project = fixture_project();
+    write(project.path(), "scenes/main.scene", "{ not json");
+    write(
+        project.path(),
+        "scenes/loop.scene",
+        r#"{ "objects": [
+            { "id": "a", "name": "A", "object_type": "Empty", "parent": "b" },
+            { "id": "b", "name": "B", "object_type": "Empty", "parent": "a" }
+        ] }"#,
+    );
+    write(
+        project.path(),
+        "Pulsar.toml",
+        "[project]\nversion = \"0.1.0\"\n",
+    );
+
+    let (code, json) = pulsar_json(&["validate", path_str(project.path())]);
+
+    assert_eq!(code, 1, "{json:#}");
+    assert_eq!(json["success"], false);
+    let findings = json["report"]["findings"].as_array().unwrap();
+    let has = |check: &str, path: &str| {
+        findings
+            .iter()
+            .any(|f| f["check"] == check && f["path"] == path && f["severity"] == "error")
+    };
+    assert!(has("manifest", "Pulsar.toml"), "{json:#}");
+    assert!(has("scene", "scenes/main.scene"), "{json:#}");
+    assert!(has("scene", "scenes/loop.scene"), "{json:#}");
+}
+
+#[test]
+fn validate_skips_ignored_files() {
+    let project = fixture_project();
+    write(project.path(), "scratch/broken.scene", "{ not json");
+    write(project.path(), ".gitignore", "scratch/\n");
+
+    let (code, json) = pulsar_json(&["validate", path_str(project.path())]);
+
+    assert_eq!(code, 0, "{json:#}");
+    assert_eq!(json["report"]["scenes"], 1);
+}
+
+#[test]
+fn validate_strict_fails_on_uncompiled_blueprints() {
+    let project = fixture_project();
+    write(
+        project.path(),
+        "src/classes/Player.class/graph_save.json",
+        "{}",
+    );
+
+    let (code, json) = pulsar_json(&["validate", path_str(project.path())]);
+    assert_eq!(code, 0, "{json:#}");
+    assert_eq!(json["report"]["warnings"], 1);
+
+    let (code, json) = pulsar_json(&["validate", path_str(project.path()), "--strict"]);
+    assert_eq!(code, 1, "{json:#}");
+    assert_eq!(json["success"], false);
+}
+
+#[test]
+fn validate_missing_project_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let (code, json) = pulsar_json(&["validate", path_str(dir.path())]);
+
+    assert_eq!(code, 3, "{json:#}");
+    assert_eq!(json["command"], "validate");
+    assert_eq!(json["success"], false);
+    assert!(json["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("not a Pulsar project"));
+}
+
+#[test]
+fn compile_blueprints_reports_each_class() {
+    let project = fixture_project();
+    write(
+        project.path(),
+        "src/classes/Broken.class/graph_save.json",
+        "{ \"not\": \"a blueprint\" }",
+    );
+
+    let (code, json) = pulsar_json(&["compile-blueprints", path_str(project.path())]);
+
+    assert_eq!(code, 1, "{json:#}");
+    assert_eq!(json["command"], "compile-blueprints");
+    assert_eq!(json["report"]["failed"], 1);
+    let result = &json["report"]["blueprints"][0];
+    assert_eq!(result["class"], "src/classes/Broken.class");
+    assert_eq!(result["status"], "failed");
+    assert!(result["error"].is_string());
+}
+
+#[test]
+fn compile_blueprints_without_classes_succeeds() {
+    let project = fixture_project();
+    let (code, json) = pulsar_json(&["compile-blueprints", path_str(project.path())]);
+
+    assert_eq!(code, 0, "{json:#}");
+    assert_eq!(json["report"]["compiled"], 0);
+    assert_eq!(json["report"]["blueprints"], Value::Array(Vec::new()));
+}
+
+#[test]
+fn export_stages_content() {
+    let project = fixture_project();
+    write(project.path(), "src/main.rs", "fn main() {}");
+    let out = tempfile::tempdir().unwrap();
+
+    let args = [
+        "export",
+        path_str(project.path()),
+        "--profile",
+        "release",
+        "--skip-build",
+        "--out",
+        path_str(out.path()),
+    ];
+    let (code, json) = pulsar_json(&args);
+
+    assert_eq!(code, 0, "{json:#}");
+    assert_eq!(json["report"]["profile"], "release");
+    let staging = out.path().join("release");
+    assert!(staging.join("scenes/main.scene").is_file());
+    assert!(staging.join("Pulsar.toml").is_file());
+    assert!(!staging.join("src").exists());
+
+    let manifest: Value = serde_json::from_str(
+        &std::fs::read_to_string(staging.join("export_manifest.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        manifest["files"].as_array().unwrap().len(),
+        json["report"]["files"]
+    );
+
+    // A second export replaces the first
+    let (code, _) = pulsar_json(&args);
+    assert_eq!(code, 0);
+}
+
+#[test]
+fn export_refuses_to_clear_foreign_directory() {
+    let project = fixture_project();
+    let out = tempfile::tempdir().unwrap();
+    write(out.path(), "debug/important.txt", "keep me");
+
+    let (code, json) = pulsar_json(&[
+        "export",
+        path_str(project.path()),
+        "--profile",
+        "debug",
+        "--skip-build",
+        "--out",
+        path_str(out.path()),
+    ]);
+
+    assert_eq!(code, 3, "{json:#}");
+    assert!(out.path().join("debug/important.txt").is_file());
+}
+
+#[test]
+fn profile_report_reads_recorded_trace() {
+    let dir = tempfile::tempdir().unwrap();
+    let trace = dir.path().join("session.db");
+    let conn = profiling::database::create_database(&trace).unwrap();
+    profiling::database::save_events(&conn, &Vec::new()).unwrap();
+    drop(conn);
+
+    let (code, json) = pulsar_json(&["profile-report", path_str(&trace)]);
+
+    assert_eq!(code, 0, "{json:#}");
+    assert_eq!(json["command"], "profile-report");
+    assert_eq!(json["report"]["events"], 0);
+    assert!(json["report"]["scopes"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn profile_report_writes_chrome_trace() {
+    let dir = tempfile::tempdir().unwrap();
+    let trace = dir.path().join("session.db");
+    let conn = profiling::database::create_database(&trace).unwrap();
+    profiling::database::save_events(&conn, &Vec::new()).unwrap();
+    drop(conn);
+    let chrome_trace = dir.path().join("session.json");
+
+    let (code, json) = pulsar_json(&[
+        "profile-report",
+        path_str(&trace),
+        "--chrome-trace",
+        path_str(&chrome_trace),
+    ]);
+
+    assert_eq!(code, 0, "{json:#}");
+    assert_eq!(json["report"]["chrome_trace"], path_str(&chrome_trace));
+    let written: Value =
+        serde_json::from_str(&std::fs::read_to_string(&chrome_trace).unwrap()).unwrap();
+    // Only the process name
+    assert_eq!(written["traceEvents"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn profile_report_missing_trace_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let (code, json) = pulsar_json(&["profile-report", path_str(&dir.path().join("none.db"))]);
+
+    assert_eq!(code, 3, "{json:#}");
+    assert_eq!(json["success"], false);
+}
+
+#[test]
+fn bad_arguments_exit_with_usage_code() {
+    let output = Command::new(env!("CARGO_BIN_EXE_pulsar"))
+        .args(["export", "project", "--profile", "fastest"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -72,6 +72,9 @@ pub fn generate_hierarchical_docs(
     // Generate crate-level index.json
     generate_crate_json_index(&crate_dir, docs)?;
 
+    // Generate search_index.json from the pages written above
+    super::search_index::generate_search_index(&crate_dir, docs)?;
+
     Ok(())
 }
 
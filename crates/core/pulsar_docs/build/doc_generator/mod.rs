@@ -1,6 +1,7 @@
 pub mod extractor;
 pub mod markdown;
 pub mod parser;
+pub mod search_index;
 pub mod types;
 /// Main documentation generator module
 ///
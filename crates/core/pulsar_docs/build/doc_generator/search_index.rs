@@ -0,0 +1,105 @@
+use super::types::*;
+/// Search index generation
+///
+/// Writes a `search_index.json` per crate so the runtime can search item
+/// names, summaries and page headings without loading every page
+use serde::Serialize;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+/// JSON search index structures
+#[derive(Serialize)]
+struct SearchIndex {
+    name: String,
+    entries: Vec<SearchEntry>,
+}
+
+#[derive(Serialize)]
+struct SearchEntry {
+    name: String,
+    path: String,
+    section: String,
+    doc_summary: Option<String>,
+    headings: Vec<String>,
+}
+
+/// Generate `search_index.json` for a crate whose pages are already written
+pub fn generate_search_index(
+    crate_dir: &Path,
+    docs: &CrateDocumentation,
+) -> Result<(), Box<dyn Error>> {
+    let mut entries = Vec::new();
+
+    let public = |vis: &Visibility| *vis == Visibility::Public;
+    let mut push = |name: &str, section: &str, dir: &str, doc: &Option<String>| {
+        let path = format!("{}/{}.md", dir, name);
+        let headings = fs::read_to_string(crate_dir.join(&path))
+            .map(|md| page_headings(&md))
+            .unwrap_or_default();
+        entries.push(SearchEntry {
+            name: name.to_string(),
+            path,
+            section: section.to_string(),
+            doc_summary: doc
+                .as_ref()
+                .and_then(|d| d.lines().next().map(String::from)),
+            headings,
+        });
+    };
+
+    for s in docs.structs.iter().filter(|s| public(&s.visibility)) {
+        push(&s.name, "Structs", "structs", &s.doc_comment);
+    }
+    for e in docs.enums.iter().filter(|e| public(&e.visibility)) {
+        push(&e.name, "Enums", "enums", &e.doc_comment);
+    }
+    for t in docs.traits.iter().filter(|t| public(&t.visibility)) {
+        push(&t.name, "Traits", "traits", &t.doc_comment);
+    }
+    for f in docs.functions.iter().filter(|f| public(&f.visibility)) {
+        push(&f.name, "Functions", "functions", &f.doc_comment);
+    }
+    for m in &docs.macros {
+        push(&m.name, "Macros", "macros", &m.doc_comment);
+    }
+    for c in docs.constants.iter().filter(|c| public(&c.visibility)) {
+        push(&c.name, "Constants", "constants", &c.doc_comment);
+    }
+    for t in docs.type_aliases.iter().filter(|t| public(&t.visibility)) {
+        push(&t.name, "Type Aliases", "type_aliases", &t.doc_comment);
+    }
+
+    let index = SearchIndex {
+        name: docs.name.clone(),
+        entries,
+    };
+
+    let json = serde_json::to_string_pretty(&index)?;
+    fs::write(crate_dir.join("search_index.json"), json)?;
+
+    Ok(())
+}
+
+/// Section headings below the page title, without code spans
+fn page_headings(markdown: &str) -> Vec<String> {
+    let mut headings: Vec<String> = Vec::new();
+    let mut in_code = false;
+
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code || !line.starts_with("##") {
+            continue;
+        }
+
+        let text = line.trim_start_matches('#').trim().replace('`', "");
+        if !text.is_empty() && !headings.contains(&text) {
+            headings.push(text);
+        }
+    }
+
+    headings
+}
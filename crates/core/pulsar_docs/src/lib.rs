@@ -1,7 +1,14 @@
 use rust_embed::RustEmbed;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::RwLock;
 
 pub mod project_parser;
+mod search;
+
+pub use search::{SearchEntry, SearchHit, SearchIndex, get_search_index, search};
 
 // RustEmbed scans the doc folder at compile time
 // Uses a simple relative path (../../target/doc) from crates/pulsar_docs/ to workspace root
@@ -42,30 +49,75 @@ pub struct IndexItem {
     pub doc_summary: Option<String>,
 }
 
+/// Doc folder chosen at runtime, checked before the embedded assets
+static EXTERNAL_DOC_ROOT: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Load docs from `root` (a generated `target/doc` or a downloaded docs
+/// bundle) in preference to the embedded assets
+pub fn set_external_doc_root(root: PathBuf) {
+    let root = root.canonicalize().unwrap_or(root);
+    if let Ok(mut lock) = EXTERNAL_DOC_ROOT.write() {
+        *lock = Some(root);
+    }
+    search::clear_cache();
+}
+
+/// The folder set with [`set_external_doc_root`], if any
+pub fn external_doc_root() -> Option<PathBuf> {
+    EXTERNAL_DOC_ROOT.read().ok()?.clone()
+}
+
+/// Resolve a doc path inside `root`
+///
+/// Rejects absolute paths, `..` components, and symlinks leading outside `root`
+fn resolve_in_root(root: &Path, path: &str) -> Option<PathBuf> {
+    let relative = Path::new(path);
+    let is_plain = relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if !is_plain {
+        return None;
+    }
+
+    let resolved = root.join(relative).canonicalize().ok()?;
+    let root = root.canonicalize().ok()?;
+    resolved.starts_with(&root).then_some(resolved)
+}
+
+/// Read a doc file from the external root, falling back to the embedded assets
+fn load_asset(path: &str) -> Option<Cow<'static, [u8]>> {
+    let external = external_doc_root()
+        .and_then(|root| resolve_in_root(&root, path))
+        .and_then(|path| fs::read(path).ok());
+    if let Some(data) = external {
+        return Some(Cow::Owned(data));
+    }
+
+    DocAssets::get(path).map(|file| file.data)
+}
+
 /// Get markdown content for any doc page
 pub fn get_doc_content(path: &str) -> Option<String> {
-    if let Some(content) = DocAssets::get(path) {
-        std::str::from_utf8(&content.data).ok().map(String::from)
-    } else {
-        None
-    }
+    let content = load_asset(path)?;
+    std::str::from_utf8(&content).ok().map(String::from)
 }
 
 /// Get the index.json for a crate
 pub fn get_crate_index(crate_name: &str) -> Option<CrateIndex> {
     let index_path = format!("{}/index.json", crate_name);
 
-    if let Some(content) = DocAssets::get(&index_path) {
-        let json_str = std::str::from_utf8(&content.data).ok()?;
-        serde_json::from_str(json_str).ok()
-    } else {
-        None
-    }
+    let content = load_asset(&index_path)?;
+    let json_str = std::str::from_utf8(&content).ok()?;
+    serde_json::from_str(json_str).ok()
 }
 
 /// Get list of all documented crates by scanning for index.json files
+///
+/// Only `<crate>/index.json` counts; `search_index.json` sits beside it
 pub fn list_crates() -> Vec<String> {
-    let mut crates = Vec::new();
+    let mut crates = external_doc_root()
+        .map(|root| list_crates_in(&root))
+        .unwrap_or_default();
 
     for file_path in DocAssets::iter() {
         let path = file_path.as_ref();
@@ -76,9 +128,23 @@ pub fn list_crates() -> Vec<String> {
     }
 
     crates.sort();
+    crates.dedup();
     crates
 }
 
+/// Crates with an index.json directly under `root`
+fn list_crates_in(root: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().join("index.json").is_file())
+        .filter_map(|entry| entry.file_name().to_str().map(String::from))
+        .collect()
+}
+
 /// Check if docs are available
 pub fn docs_available() -> bool {
     !list_crates().is_empty()
@@ -110,6 +176,45 @@ mod tests {
         );
     }
 
+    /// A doc root holding one crate, plus a file outside it
+    fn external_root() -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("doc");
+        fs::create_dir_all(root.join("my_crate/structs")).unwrap();
+        fs::write(root.join("my_crate/index.json"), "{}").unwrap();
+        fs::write(root.join("my_crate/structs/World.md"), "# `World`").unwrap();
+        fs::write(dir.path().join("secret.md"), "secret").unwrap();
+        (dir, root)
+    }
+
+    #[test]
+    fn test_resolve_in_root() {
+        let (dir, root) = external_root();
+
+        let page = resolve_in_root(&root, "my_crate/structs/World.md").unwrap();
+        assert_eq!(fs::read_to_string(page).unwrap(), "# `World`");
+        assert!(resolve_in_root(&root, "my_crate/structs/Missing.md").is_none());
+        assert!(resolve_in_root(&root, "../secret.md").is_none());
+        assert!(resolve_in_root(&root, "my_crate/../../secret.md").is_none());
+        let absolute = dir.path().join("secret.md");
+        assert!(resolve_in_root(&root, absolute.to_str().unwrap()).is_none());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir.path(), root.join("escape")).unwrap();
+            assert!(resolve_in_root(&root, "escape/secret.md").is_none());
+        }
+    }
+
+    #[test]
+    fn test_list_crates_in_external_root() {
+        let (dir, root) = external_root();
+        fs::create_dir_all(root.join("not_a_crate")).unwrap();
+
+        assert_eq!(list_crates_in(&root), ["my_crate"]);
+        assert!(list_crates_in(&dir.path().join("missing")).is_empty());
+    }
+
     #[test]
     fn test_can_load_crate_index() {
         let crates = list_crates();
@@ -0,0 +1,262 @@
+use crate::{get_crate_index, list_crates, load_asset};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::sync::{Arc, RwLock};
+
+/// Search index structures matching the build script
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SearchIndex {
+    pub name: String,
+    pub entries: Vec<SearchEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SearchEntry {
+    pub name: String,
+    pub path: String,
+    pub section: String,
+    pub doc_summary: Option<String>,
+    #[serde(default)]
+    pub headings: Vec<String>,
+}
+
+/// A search result, best matches first
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub crate_name: String,
+    pub name: String,
+    pub section: String,
+    /// Page path as accepted by [`get_doc_content`](crate::get_doc_content)
+    pub path: String,
+    pub doc_summary: Option<String>,
+    pub score: u32,
+}
+
+// Name matches always outrank heading matches, which outrank summary matches
+const SCORE_EXACT: u32 = 1000;
+const SCORE_PREFIX: u32 = 800;
+const SCORE_CONTAINS: u32 = 600;
+const SCORE_FUZZY: u32 = 400;
+const SCORE_FUZZY_MIN: u32 = 300;
+const SCORE_HEADING: u32 = 200;
+const SCORE_SUMMARY: u32 = 100;
+
+/// Get the search_index.json for a crate
+///
+/// Falls back to the items in index.json, without headings, for docs
+/// generated before search indices existed
+pub fn get_search_index(crate_name: &str) -> Option<SearchIndex> {
+    let index_path = format!("{}/search_index.json", crate_name);
+
+    if let Some(content) = load_asset(&index_path) {
+        let json_str = std::str::from_utf8(&content).ok()?;
+        return serde_json::from_str(json_str).ok();
+    }
+
+    let index = get_crate_index(crate_name)?;
+    let entries = index
+        .sections
+        .into_iter()
+        .flat_map(|section| {
+            let section_name = section.name;
+            section.items.into_iter().map(move |item| SearchEntry {
+                name: item.name,
+                path: item.path,
+                section: section_name.clone(),
+                doc_summary: item.doc_summary,
+                headings: Vec::new(),
+            })
+        })
+        .collect();
+
+    Some(SearchIndex {
+        name: index.name,
+        entries,
+    })
+}
+
+/// Search every documented crate for `query`, returning at most `limit` hits
+///
+/// Returns nothing when the query is blank or no docs were built
+pub fn search(query: &str, limit: usize) -> Vec<SearchHit> {
+    rank(&loaded_indices(), query, limit)
+}
+
+/// Search indices of every crate, loaded on first search
+static INDICES: RwLock<Option<Arc<Vec<SearchIndex>>>> = RwLock::new(None);
+
+fn loaded_indices() -> Arc<Vec<SearchIndex>> {
+    if let Some(indices) = INDICES.read().ok().and_then(|lock| lock.clone()) {
+        return indices;
+    }
+
+    let indices: Arc<Vec<SearchIndex>> = Arc::new(
+        list_crates()
+            .iter()
+            .filter_map(|crate_name| get_search_index(crate_name))
+            .collect(),
+    );
+    if let Ok(mut lock) = INDICES.write() {
+        *lock = Some(indices.clone());
+    }
+    indices
+}
+
+/// Reload the indices on the next search, e.g. after the doc root changes
+pub(crate) fn clear_cache() {
+    if let Ok(mut lock) = INDICES.write() {
+        *lock = None;
+    }
+}
+
+fn rank(indices: &[SearchIndex], query: &str, limit: usize) -> Vec<SearchHit> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut hits: Vec<SearchHit> = indices
+        .iter()
+        .flat_map(|index| {
+            index.entries.iter().filter_map(|entry| {
+                let score = score_entry(&query, entry)?;
+                Some(SearchHit {
+                    crate_name: index.name.clone(),
+                    name: entry.name.clone(),
+                    section: entry.section.clone(),
+                    path: format!("{}/{}", index.name, entry.path),
+                    doc_summary: entry.doc_summary.clone(),
+                    score,
+                })
+            })
+        })
+        .collect();
+
+    hits.sort_by(|a, b| {
+        (Reverse(a.score), a.name.len(), &a.name, &a.crate_name).cmp(&(
+            Reverse(b.score),
+            b.name.len(),
+            &b.name,
+            &b.crate_name,
+        ))
+    });
+    hits.truncate(limit);
+    hits
+}
+
+/// Score `entry` against an already lowercased query
+fn score_entry(query: &str, entry: &SearchEntry) -> Option<u32> {
+    let name = entry.name.to_lowercase();
+
+    if name == query {
+        return Some(SCORE_EXACT);
+    }
+    if name.starts_with(query) {
+        return Some(SCORE_PREFIX);
+    }
+    if name.contains(query) {
+        return Some(SCORE_CONTAINS);
+    }
+    if let Some(gaps) = fuzzy_gaps(query, &name) {
+        return Some(SCORE_FUZZY.saturating_sub(gaps * 10).max(SCORE_FUZZY_MIN));
+    }
+    if entry
+        .headings
+        .iter()
+        .any(|heading| heading.to_lowercase().contains(query))
+    {
+        return Some(SCORE_HEADING);
+    }
+
+    let summary = entry.doc_summary.as_deref()?.to_lowercase();
+    query
+        .split_whitespace()
+        .all(|word| summary.contains(word))
+        .then_some(SCORE_SUMMARY)
+}
+
+/// Characters skipped when matching `query` as a subsequence of `name`
+fn fuzzy_gaps(query: &str, name: &str) -> Option<u32> {
+    if query.chars().count() < 2 {
+        return None;
+    }
+
+    let mut gaps = 0;
+    let mut name_chars = name.chars();
+    for q in query.chars() {
+        loop {
+            let c = name_chars.next()?;
+            if c == q {
+                break;
+            }
+            gaps += 1;
+        }
+    }
+    Some(gaps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, summary: Option<&str>, headings: &[&str]) -> SearchEntry {
+        SearchEntry {
+            name: name.to_string(),
+            path: format!("structs/{}.md", name),
+            section: "Structs".to_string(),
+            doc_summary: summary.map(String::from),
+            headings: headings.iter().map(|h| h.to_string()).collect(),
+        }
+    }
+
+    fn names(hits: &[SearchHit]) -> Vec<&str> {
+        hits.iter().map(|h| h.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_name_matches_rank_above_summary_matches() {
+        let indices = vec![SearchIndex {
+            name: "engine_physics".to_string(),
+            entries: vec![
+                entry("World", Some("Holds every rigid body"), &[]),
+                entry("RigidBodyHandle", None, &[]),
+                entry("Collider", None, &["body"]),
+                entry("Body", None, &[]),
+                entry("Gravity", None, &[]),
+            ],
+        }];
+
+        let hits = rank(&indices, "Body", 10);
+        assert_eq!(
+            names(&hits),
+            ["Body", "RigidBodyHandle", "Collider", "World"]
+        );
+        assert_eq!(hits[0].path, "engine_physics/structs/Body.md");
+        assert_eq!(hits[0].crate_name, "engine_physics");
+    }
+
+    #[test]
+    fn test_fuzzy_and_limit() {
+        let indices = vec![SearchIndex {
+            name: "engine_state".to_string(),
+            entries: vec![
+                entry("GlobalConfig", None, &[]),
+                entry("GameConfig", None, &[]),
+                entry("Registry", None, &[]),
+            ],
+        }];
+
+        assert_eq!(
+            names(&rank(&indices, "gcfg", 10)),
+            ["GameConfig", "GlobalConfig"]
+        );
+        assert_eq!(rank(&indices, "config", 1).len(), 1);
+        assert!(rank(&indices, "   ", 10).is_empty());
+        assert!(rank(&indices, "zzz", 10).is_empty());
+    }
+
+    #[test]
+    fn test_missing_index_is_empty() {
+        assert!(rank(&[], "world", 10).is_empty());
+    }
+}
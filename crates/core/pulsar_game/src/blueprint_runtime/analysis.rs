@@ -0,0 +1,289 @@
+//! Dead node and redundant evaluation analysis of blueprint graphs.
+//!
+//! Graphs accumulate nodes that no longer do anything: exec nodes left
+//! unconnected after an edit, and pure nodes whose results nothing reads.
+//! They still end up in the generated code. [`analyze_graph`] finds them so
+//! the editor can dim them, and the compiler leaves them out when
+//! [`CompilerOptions::strip_dead_nodes`](super::bytecode_compiler::CompilerOptions::strip_dead_nodes)
+//! is set.
+
+use super::diagnostics::GraphView;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use ui::graph::GraphDescription;
+
+/// What [`analyze_graph`] found in a graph. Node IDs are sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphAnalysis {
+    /// Nodes with exec pins that no event's execution flow reaches
+    pub unreachable: Vec<String>,
+    /// Pure nodes whose outputs never reach a node that runs, directly or
+    /// through other pure nodes
+    pub unused: Vec<String>,
+    /// Pure nodes evaluated in more than one place, once for each input of a
+    /// live node that reads them, with the number of places. Their result
+    /// could be computed once and reused.
+    pub repeated_pure: Vec<(String, usize)>,
+}
+
+impl GraphAnalysis {
+    /// Whether the node can be removed without changing what the graph does.
+    pub fn is_dead(&self, node_id: &str) -> bool {
+        self.unreachable.iter().any(|id| id == node_id)
+            || self.unused.iter().any(|id| id == node_id)
+    }
+
+    /// Every node that can be removed without changing what the graph does.
+    pub fn dead_nodes(&self) -> impl Iterator<Item = &str> {
+        self.unreachable
+            .iter()
+            .chain(&self.unused)
+            .map(String::as_str)
+    }
+
+    pub fn has_dead_nodes(&self) -> bool {
+        !self.unreachable.is_empty() || !self.unused.is_empty()
+    }
+}
+
+/// Finds dead nodes and repeatedly evaluated pure nodes in a graph.
+pub fn analyze_graph(graph: &GraphDescription) -> GraphAnalysis {
+    serde_json::to_value(graph)
+        .map(|json| analyze_json(&json))
+        .unwrap_or_default()
+}
+
+/// [`analyze_graph`] on the serialized graph.
+pub(crate) fn analyze_json(graph: &Value) -> GraphAnalysis {
+    let graph = GraphView::read(graph);
+    let reached = graph.reached_by_exec();
+
+    // Data flows from pure nodes into the nodes that read them
+    let mut readers: HashMap<&str, Vec<&str>> = HashMap::new();
+    for &[source_node, _, target_node, _] in &graph.connections {
+        let from_pure = graph
+            .nodes
+            .get(source_node)
+            .is_some_and(|node| node.is_pure());
+        if from_pure && graph.nodes.contains_key(target_node) {
+            readers.entry(source_node).or_default().push(target_node);
+        }
+    }
+
+    // Pure nodes are live when a reached node reads them, directly or
+    // through other live pure nodes
+    let mut evaluations: HashMap<&str, usize> = HashMap::new();
+    for (&id, node) in &graph.nodes {
+        if node.is_pure() {
+            count_evaluations(id, &graph, &reached, &readers, &mut evaluations);
+        }
+    }
+
+    let unreachable = graph
+        .nodes
+        .iter()
+        .filter(|(id, node)| !node.is_pure() && !reached.contains(*id))
+        .map(|(&id, _)| id.to_string())
+        .collect();
+    let pure = || {
+        graph
+            .nodes
+            .iter()
+            .filter(|(_, node)| node.is_pure())
+            .map(|(&id, _)| (id, evaluations.get(id).copied().unwrap_or(0)))
+    };
+    let unused = pure()
+        .filter(|&(_, count)| count == 0)
+        .map(|(id, _)| id.to_string())
+        .collect();
+    let repeated_pure = pure()
+        .filter(|&(_, count)| count > 1)
+        .map(|(id, count)| (id.to_string(), count))
+        .collect();
+
+    GraphAnalysis {
+        unreachable,
+        unused,
+        repeated_pure,
+    }
+}
+
+/// How often pure node `id` is evaluated per run: once for each connection
+/// into a reached node, plus the evaluations of each pure node reading it.
+/// Pure nodes in a cycle, which PBGC rejects, count as evaluated once.
+fn count_evaluations<'a>(
+    id: &'a str,
+    graph: &GraphView<'a>,
+    reached: &HashSet<&'a str>,
+    readers: &HashMap<&'a str, Vec<&'a str>>,
+    evaluations: &mut HashMap<&'a str, usize>,
+) -> usize {
+    if let Some(&count) = evaluations.get(id) {
+        return count;
+    }
+    // Placeholder while the readers are counted, ending cycles
+    evaluations.insert(id, 1);
+
+    let mut count = 0;
+    for &reader in readers.get(id).into_iter().flatten() {
+        let reader_is_pure = graph.nodes.get(reader).is_some_and(|node| node.is_pure());
+        count += if reader_is_pure {
+            count_evaluations(reader, graph, reached, readers, evaluations)
+        } else {
+            usize::from(reached.contains(reader))
+        };
+    }
+    evaluations.insert(id, count);
+    count
+}
+
+/// Removes the dead nodes [`analyze_json`] finds, and their connections,
+/// from a serialized graph. Returns the IDs removed.
+pub(crate) fn strip_dead_nodes(graph: &mut Value) -> BTreeSet<String> {
+    let dead: BTreeSet<String> = analyze_json(graph)
+        .dead_nodes()
+        .map(str::to_string)
+        .collect();
+    if dead.is_empty() {
+        return dead;
+    }
+
+    match graph.get_mut("nodes") {
+        Some(Value::Object(nodes)) => nodes.retain(|id, _| !dead.contains(id)),
+        Some(Value::Array(nodes)) => nodes.retain(|node| {
+            node.get("id")
+                .and_then(Value::as_str)
+                .is_none_or(|id| !dead.contains(id))
+        }),
+        _ => {}
+    }
+    if let Some(Value::Array(connections)) = graph.get_mut("connections") {
+        connections.retain(|connection| {
+            ["source_node", "target_node"].iter().all(|key| {
+                connection
+                    .get(key)
+                    .and_then(Value::as_str)
+                    .is_none_or(|id| !dead.contains(id))
+            })
+        });
+    }
+    dead
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn node(node_type: &str, inputs: &[(&str, &str)], outputs: &[(&str, &str)]) -> Value {
+        let pins = |pins: &[(&str, &str)]| -> Vec<Value> {
+            pins.iter()
+                .map(|&(id, data_type)| {
+                    let data_type = match data_type {
+                        "exec" => json!("Execution"),
+                        base_type => json!({ "Data": { "base_type": base_type, "wrappers": [] } }),
+                    };
+                    json!({ "id": id, "pin": { "id": id, "name": id, "data_type": data_type } })
+                })
+                .collect()
+        };
+        json!({ "node_type": node_type, "inputs": pins(inputs), "outputs": pins(outputs) })
+    }
+
+    fn connect(source: &str, source_pin: &str, target: &str, target_pin: &str) -> Value {
+        json!({
+            "source_node": source, "source_pin": source_pin,
+            "target_node": target, "target_pin": target_pin
+        })
+    }
+
+    /// An event branching on a condition: the true branch prints a sum, the
+    /// false branch prints it twice. Some nodes hang off neither branch.
+    fn branching_graph() -> Value {
+        json!({
+            "nodes": {
+                "begin": node("begin_play", &[], &[("begin_exec", "exec")]),
+                "cond": node("greater_than", &[], &[("cond_r", "bool")]),
+                "branch": node(
+                    "branch",
+                    &[("branch_exec", "exec"), ("branch_cond", "bool")],
+                    &[("branch_true", "exec"), ("branch_false", "exec")]
+                ),
+                "sum": node("add", &[], &[("sum_r", "i64")]),
+                "print_true": node("print_number", &[("pt_exec", "exec"), ("pt_n", "i64")], &[]),
+                "print_false": node(
+                    "print_number",
+                    &[("pf_exec", "exec"), ("pf_n", "i64")],
+                    &[("pf_then", "exec")]
+                ),
+                "print_again": node("print_number", &[("pa_exec", "exec"), ("pa_n", "i64")], &[]),
+                "orphan_print": node("print_number", &[("op_exec", "exec"), ("op_n", "i64")], &[]),
+                "orphan_sum": node("add", &[], &[("os_r", "i64")]),
+                "feeds_orphan": node("multiply", &[], &[("fo_r", "i64")]),
+                "unread": node("subtract", &[], &[("unread_r", "i64")])
+            },
+            "connections": [
+                connect("begin", "begin_exec", "branch", "branch_exec"),
+                connect("cond", "cond_r", "branch", "branch_cond"),
+                connect("branch", "branch_true", "print_true", "pt_exec"),
+                connect("branch", "branch_false", "print_false", "pf_exec"),
+                connect("print_false", "pf_then", "print_again", "pa_exec"),
+                connect("sum", "sum_r", "print_true", "pt_n"),
+                connect("sum", "sum_r", "print_false", "pf_n"),
+                connect("sum", "sum_r", "print_again", "pa_n"),
+                connect("orphan_sum", "os_r", "orphan_print", "op_n"),
+                connect("feeds_orphan", "fo_r", "orphan_sum", "os_a")
+            ]
+        })
+    }
+
+    #[test]
+    fn test_nodes_reachable_through_one_branch_are_live() {
+        let analysis = analyze_json(&branching_graph());
+
+        assert_eq!(analysis.unreachable, ["orphan_print"]);
+        assert_eq!(analysis.unused, ["feeds_orphan", "orphan_sum", "unread"]);
+        assert_eq!(analysis.repeated_pure, [("sum".to_string(), 3)]);
+        for live in ["print_true", "print_false", "print_again", "cond", "sum"] {
+            assert!(!analysis.is_dead(live), "{} is live", live);
+        }
+        assert_eq!(analysis.dead_nodes().count(), 4);
+    }
+
+    #[test]
+    fn test_pure_chains_multiply_evaluations() {
+        let mut graph = branching_graph();
+        graph["nodes"]["double"] = node("multiply", &[("double_a", "i64")], &[("double_r", "i64")]);
+        graph["connections"].as_array_mut().unwrap().extend([
+            connect("cond", "cond_r", "double", "double_a"),
+            connect("double", "double_r", "print_true", "pt_n"),
+            connect("double", "double_r", "print_again", "pa_n"),
+        ]);
+
+        let analysis = analyze_json(&graph);
+        let count = |id: &str| {
+            analysis
+                .repeated_pure
+                .iter()
+                .find(|(node, _)| node == id)
+                .map(|&(_, count)| count)
+        };
+        assert_eq!(count("double"), Some(2));
+        // Once for the branch, twice through `double`
+        assert_eq!(count("cond"), Some(3));
+    }
+
+    #[test]
+    fn test_strip_dead_nodes() {
+        let mut graph = branching_graph();
+        let removed = strip_dead_nodes(&mut graph);
+
+        assert_eq!(removed.len(), 4);
+        let nodes = graph["nodes"].as_object().unwrap();
+        assert_eq!(nodes.len(), 7);
+        assert!(!nodes.contains_key("orphan_print"));
+        assert_eq!(graph["connections"].as_array().unwrap().len(), 8);
+        assert!(!analyze_json(&graph).has_dead_nodes());
+        assert!(strip_dead_nodes(&mut graph).is_empty());
+    }
+}
@@ -3,16 +3,25 @@
 //! Compiles blueprint graphs from Plugin_Blueprints `.class` folders into
 //! executable bytecode using PBGC (Pulsar Blueprint Graph Compiler).
 
+use super::analysis;
+use super::codegen::{self, CodegenOptions};
+use super::compile_cache::{BlueprintCompileCache, CacheStatus};
 use super::compiled_bytecode::{CompiledBytecode, VariableDescriptor};
-use pbgc::{compile_graph_to_bytecode, BpProgram, GraphDescription as PbgcGraphDescription};
+use super::diagnostics::{self, BlueprintDiagnostic, CompileResult};
+use super::migration::{GraphMigrator, MigrationError};
+use super::source_map::SourceMap;
+use pbgc::{
+    compile_graph, compile_graph_to_bytecode, BpProgram, GraphDescription as PbgcGraphDescription,
+};
+use serde_json::{json, Value};
 use ui::graph::{BlueprintAsset, ClassVariable, GraphDescription};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::Path;
 
 /// Bytecode compiler for blueprint classes.
 pub struct BytecodeCompiler {
     /// Compiler options and state
-    _options: CompilerOptions,
+    options: CompilerOptions,
 }
 
 /// Compiler options and settings.
@@ -23,6 +32,19 @@ pub struct CompilerOptions {
 
     /// Generate debug symbols
     pub debug_symbols: bool,
+
+    /// Node types graphs may use, including event nodes. When set, nodes of
+    /// any other type are reported as unknown before PBGC runs.
+    pub known_node_types: Option<HashSet<String>>,
+
+    /// Enum types pins may take, with their variants in declaration order.
+    /// Literal values on pins of these types are checked against the
+    /// variants, given by name or index.
+    pub enum_types: HashMap<String, Vec<String>>,
+
+    /// Leave nodes [`analyze_graph`](super::analysis::analyze_graph) finds
+    /// dead out of the compiled program.
+    pub strip_dead_nodes: bool,
 }
 
 impl Default for CompilerOptions {
@@ -30,10 +52,31 @@ impl Default for CompilerOptions {
         Self {
             optimize: true,
             debug_symbols: true,
+            known_node_types: None,
+            enum_types: HashMap::new(),
+            strip_dead_nodes: false,
         }
     }
 }
 
+impl CompilerOptions {
+    /// The options as canonical JSON, with sets and maps sorted, for
+    /// keying compile caches.
+    pub fn cache_fingerprint(&self) -> Value {
+        let known_node_types = self
+            .known_node_types
+            .as_ref()
+            .map(|types| types.iter().collect::<BTreeSet<_>>());
+        json!({
+            "optimize": self.optimize,
+            "debug_symbols": self.debug_symbols,
+            "known_node_types": known_node_types,
+            "enum_types": self.enum_types.iter().collect::<BTreeMap<_, _>>(),
+            "strip_dead_nodes": self.strip_dead_nodes,
+        })
+    }
+}
+
 /// Error type for bytecode compilation.
 #[derive(Debug)]
 pub enum CompilerError {
@@ -48,6 +91,9 @@ pub enum CompilerError {
 
     /// Invalid blueprint structure
     Invalid(String),
+
+    /// Saved graph in a format this engine can't migrate
+    Migration(MigrationError),
 }
 
 impl std::fmt::Display for CompilerError {
@@ -57,6 +103,7 @@ impl std::fmt::Display for CompilerError {
             CompilerError::Json(e) => write!(f, "JSON error: {}", e),
             CompilerError::Compilation(e) => write!(f, "Compilation error: {}", e),
             CompilerError::Invalid(e) => write!(f, "Invalid blueprint: {}", e),
+            CompilerError::Migration(e) => write!(f, "{}", e),
         }
     }
 }
@@ -75,17 +122,28 @@ impl From<serde_json::Error> for CompilerError {
     }
 }
 
+impl From<MigrationError> for CompilerError {
+    fn from(e: MigrationError) -> Self {
+        CompilerError::Migration(e)
+    }
+}
+
 impl BytecodeCompiler {
     /// Create a new bytecode compiler with default options.
     pub fn new() -> Self {
         Self {
-            _options: CompilerOptions::default(),
+            options: CompilerOptions::default(),
         }
     }
 
     /// Create a new bytecode compiler with custom options.
     pub fn with_options(options: CompilerOptions) -> Self {
-        Self { _options: options }
+        Self { options }
+    }
+
+    /// The options this compiler compiles with.
+    pub fn options(&self) -> &CompilerOptions {
+        &self.options
     }
 
     /// Compile a blueprint class from a `.class` folder to bytecode.
@@ -107,7 +165,7 @@ impl BytecodeCompiler {
         }
 
         let json = std::fs::read_to_string(&graph_save_path)?;
-        let blueprint: BlueprintAsset = serde_json::from_str(&json)?;
+        let blueprint = GraphMigrator::new().migrate_asset(serde_json::from_str(&json)?)?;
 
         self.compile_blueprint(&blueprint)
     }
@@ -142,6 +200,95 @@ impl BytecodeCompiler {
         Ok(compiled)
     }
 
+    /// Compile a graph, reporting every problem found as a diagnostic tied
+    /// to the node and pin at fault rather than failing on the first one.
+    ///
+    /// PBGC only runs when the graph has no errors of its own. An error it
+    /// still reports is tied to the node its message names, if any.
+    pub fn compile_blueprint_with_diagnostics(&self, graph: &GraphDescription) -> CompileResult {
+        let json = match serde_json::to_value(graph) {
+            Ok(json) => json,
+            Err(e) => {
+                return CompileResult::failed(BlueprintDiagnostic::error(format!(
+                    "Graph conversion failed: {}",
+                    e
+                )))
+            }
+        };
+
+        let mut diagnostics = diagnostics::validate_graph(&json, &self.options);
+        if diagnostics.iter().any(BlueprintDiagnostic::is_error) {
+            return CompileResult {
+                program: None,
+                diagnostics,
+            };
+        }
+
+        let program = match self.compile_event_graph(graph) {
+            Ok(program) => Some(program),
+            Err(CompilerError::Compilation(message)) => {
+                diagnostics.push(diagnostics::from_compiler_message(message, &json));
+                None
+            }
+            Err(e) => {
+                diagnostics.push(BlueprintDiagnostic::error(e.to_string()));
+                None
+            }
+        };
+        CompileResult {
+            program,
+            diagnostics,
+        }
+    }
+
+    /// Compile a graph like
+    /// [`compile_blueprint_with_diagnostics`](Self::compile_blueprint_with_diagnostics),
+    /// also mapping `generated_rust`, the Rust PBGC generated for the same
+    /// graph, back to its nodes so rustc errors in it can be shown on them.
+    pub fn compile_blueprint_with_sourcemap(
+        &self,
+        graph: &GraphDescription,
+        generated_rust: &str,
+    ) -> (CompileResult, SourceMap) {
+        let source_map = SourceMap::build(generated_rust, graph.nodes.keys().map(String::as_str));
+        (self.compile_blueprint_with_diagnostics(graph), source_map)
+    }
+
+    /// Compile a graph, reusing the program from `cache` if the same graph
+    /// was compiled with the same options against the same node set before.
+    pub fn compile_blueprint_cached(
+        &self,
+        graph: &GraphDescription,
+        cache: &mut BlueprintCompileCache,
+    ) -> Result<(BpProgram, CacheStatus), CompilerError> {
+        let key = cache.key(
+            &serde_json::to_value(graph)?,
+            &self.options.cache_fingerprint(),
+        );
+        if let Some(program) = cache.get(key) {
+            return Ok((program, CacheStatus::Hit));
+        }
+
+        let program = self.compile_event_graph(graph)?;
+        cache.insert(key, program.clone());
+        Ok((program, CacheStatus::Miss))
+    }
+
+    /// Check a graph for problems without compiling it, fast enough to run
+    /// on every edit: node types (when
+    /// [`known_node_types`](CompilerOptions::known_node_types) is set),
+    /// connection types, unset inputs, duplicate pin IDs, and nodes no
+    /// event's execution flow reaches.
+    pub fn validate_blueprint(&self, graph: &GraphDescription) -> Vec<BlueprintDiagnostic> {
+        match serde_json::to_value(graph) {
+            Ok(json) => diagnostics::validate_graph(&json, &self.options),
+            Err(e) => vec![BlueprintDiagnostic::error(format!(
+                "Graph conversion failed: {}",
+                e
+            ))],
+        }
+    }
+
     /// Compile blueprint variables to descriptors.
     fn compile_variables(
         &self,
@@ -346,11 +493,50 @@ impl BytecodeCompiler {
         Ok(graph.clone())
     }
 
+    /// Generate the Rust source for a graph with PBGC, as written to a
+    /// class's `events.rs`.
+    pub fn generate_rust(&self, graph: &GraphDescription) -> Result<String, CompilerError> {
+        self.generate_rust_with_options(graph, &CodegenOptions::default())
+    }
+
+    /// Generate the Rust source for a graph with PBGC, shaped as `options`
+    /// ask: the event functions alone, a module, or a standalone program.
+    pub fn generate_rust_with_options(
+        &self,
+        graph: &GraphDescription,
+        options: &CodegenOptions,
+    ) -> Result<String, CompilerError> {
+        let pbgc_graph = self.to_pbgc_graph(graph)?;
+        let functions = compile_graph(&pbgc_graph)
+            .map_err(|e| CompilerError::Compilation(format!("{:?}", e)))?;
+        codegen::shape(&functions, options)
+    }
+
+    /// Convert a graph to PBGC's graph type, stripping dead nodes first if
+    /// the options ask for it, and coercing literal values to their pins'
+    /// types.
+    fn to_pbgc_graph(
+        &self,
+        graph: &GraphDescription,
+    ) -> Result<PbgcGraphDescription, CompilerError> {
+        let mut bridge_json = serde_json::to_value(graph)?;
+        if self.options.strip_dead_nodes {
+            let removed = analysis::strip_dead_nodes(&mut bridge_json);
+            if !removed.is_empty() {
+                tracing::debug!("Stripped {} dead blueprint nodes", removed.len());
+            }
+        }
+        diagnostics::coerce_properties(&mut bridge_json, &self.options).map_err(|mismatches| {
+            let mismatches: Vec<String> = mismatches.iter().map(ToString::to_string).collect();
+            CompilerError::Invalid(mismatches.join("; "))
+        })?;
+        serde_json::from_value(bridge_json)
+            .map_err(|e| CompilerError::Compilation(format!("Graph conversion failed: {}", e)))
+    }
+
     /// Compile an event graph to bytecode.
     fn compile_event_graph(&self, graph: &GraphDescription) -> Result<BpProgram, CompilerError> {
-        let bridge_json = serde_json::to_value(graph)?;
-        let pbgc_graph: PbgcGraphDescription = serde_json::from_value(bridge_json)
-            .map_err(|e| CompilerError::Compilation(format!("Graph conversion failed: {}", e)))?;
+        let pbgc_graph = self.to_pbgc_graph(graph)?;
 
         // Use PBGC to compile the graph
         let programs = compile_graph_to_bytecode(&pbgc_graph)
@@ -380,8 +566,40 @@ mod tests {
     #[test]
     fn test_compiler_creation() {
         let compiler = BytecodeCompiler::new();
-        assert!(compiler._options.optimize);
-        assert!(compiler._options.debug_symbols);
+        assert!(compiler.options.optimize);
+        assert!(compiler.options.debug_symbols);
+    }
+
+    #[test]
+    fn test_cache_fingerprint_covers_options() {
+        let with_types = |types: &[&str]| CompilerOptions {
+            known_node_types: Some(types.iter().map(ToString::to_string).collect()),
+            ..CompilerOptions::default()
+        };
+        assert_eq!(
+            with_types(&["add", "print", "begin_play"]).cache_fingerprint(),
+            with_types(&["begin_play", "add", "print"]).cache_fingerprint()
+        );
+
+        let default = CompilerOptions::default().cache_fingerprint();
+        let stripping = CompilerOptions {
+            strip_dead_nodes: true,
+            ..CompilerOptions::default()
+        };
+        let with_enum = CompilerOptions {
+            enum_types: HashMap::from([("Mode".to_string(), vec!["On".to_string()])]),
+            ..CompilerOptions::default()
+        };
+        assert_ne!(default, stripping.cache_fingerprint());
+        assert_ne!(default, with_enum.cache_fingerprint());
+        assert_ne!(default, with_types(&["add"]).cache_fingerprint());
+
+        let cache = BlueprintCompileCache::<String>::new(4).with_node_set_version(1);
+        let graph = json!({ "nodes": {}, "connections": [] });
+        assert_ne!(
+            cache.key(&graph, &default),
+            cache.key(&graph, &stripping.cache_fingerprint())
+        );
     }
 
     #[test]
@@ -0,0 +1,250 @@
+//! Shapes of the Rust generated for a blueprint graph.
+//!
+//! PBGC generates a graph's event functions on their own, ready to be
+//! spliced into a class's `events.rs`. [`CodegenTarget`] can instead wrap
+//! them in a module to include in the game crate, or in a standalone
+//! program that runs the graph's `begin_play` event, e.g. to test a graph
+//! outside the engine.
+
+use super::bytecode_compiler::CompilerError;
+use serde_json::{json, Value};
+
+/// What to generate for a graph.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CodegenTarget {
+    /// The event functions alone, as PBGC generates them
+    #[default]
+    Functions,
+    /// A module named `name` with the event functions made `pub`
+    Module { name: String },
+    /// A program whose `main` runs the `begin_play` event
+    Binary,
+}
+
+/// Options for generating Rust from a graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodegenOptions {
+    pub target: CodegenTarget,
+
+    /// Keep comments in the output, and start wrapped targets with a
+    /// header saying the file is generated
+    pub emit_comments: bool,
+
+    /// Evaluate pure nodes where their results are used, which is how PBGC
+    /// generates them. PBGC can't evaluate them once into locals yet, so
+    /// turning this off fails generation.
+    pub inline_pure_nodes: bool,
+}
+
+impl Default for CodegenOptions {
+    fn default() -> Self {
+        Self {
+            target: CodegenTarget::Functions,
+            emit_comments: true,
+            inline_pure_nodes: true,
+        }
+    }
+}
+
+impl CodegenOptions {
+    /// The options as canonical JSON, for keying compile caches.
+    pub fn cache_fingerprint(&self) -> Value {
+        let target = match &self.target {
+            CodegenTarget::Functions => json!("functions"),
+            CodegenTarget::Module { name } => json!({ "module": name }),
+            CodegenTarget::Binary => json!("binary"),
+        };
+        json!({
+            "target": target,
+            "emit_comments": self.emit_comments,
+            "inline_pure_nodes": self.inline_pure_nodes,
+        })
+    }
+}
+
+const HEADER: &str = "// Generated from a blueprint graph by PBGC. Do not edit.";
+
+/// Shapes the event functions PBGC generated as `options` ask.
+pub(crate) fn shape(functions: &str, options: &CodegenOptions) -> Result<String, CompilerError> {
+    if !options.inline_pure_nodes {
+        return Err(CompilerError::Invalid(
+            "PBGC only generates inlined pure nodes".to_string(),
+        ));
+    }
+
+    let functions = if options.emit_comments {
+        functions.to_string()
+    } else {
+        functions
+            .lines()
+            .filter(|line| !is_comment(line))
+            .map(|line| format!("{}\n", line))
+            .collect()
+    };
+
+    let mut out = String::new();
+    if options.emit_comments && options.target != CodegenTarget::Functions {
+        out.push_str(HEADER);
+        out.push_str("\n\n");
+    }
+    match &options.target {
+        CodegenTarget::Functions => out.push_str(&functions),
+        CodegenTarget::Module { name } => {
+            if !is_identifier(name) {
+                return Err(CompilerError::Invalid(format!(
+                    "'{}' is not a valid module name",
+                    name
+                )));
+            }
+            out.push_str(&format!("pub mod {} {{\n    use pulsar_std::*;\n\n", name));
+            for line in functions.lines() {
+                if line.trim().is_empty() {
+                    out.push('\n');
+                    continue;
+                }
+                let line = match line.strip_prefix("fn ") {
+                    Some(rest) => format!("pub fn {}", rest),
+                    None => line.to_string(),
+                };
+                out.push_str(&format!("    {}\n", line));
+            }
+            out.push_str("}\n");
+        }
+        CodegenTarget::Binary => {
+            if !defines_function(&functions, "begin_play") {
+                return Err(CompilerError::Invalid(
+                    "A standalone program needs a begin_play event".to_string(),
+                ));
+            }
+            out.push_str("use pulsar_std::*;\n\n");
+            out.push_str(&functions);
+            if !functions.ends_with('\n') {
+                out.push('\n');
+            }
+            out.push_str("\nfn main() {\n    begin_play();\n}\n");
+        }
+    }
+    Ok(out)
+}
+
+/// Whether a line holds only a comment. Doc comments are kept.
+fn is_comment(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("//") && !line.starts_with("///") && !line.starts_with("//!")
+}
+
+/// Whether `code` defines a top-level function called `name`.
+fn defines_function(code: &str, name: &str) -> bool {
+    code.lines().any(|line| {
+        line.strip_prefix("pub ")
+            .unwrap_or(line)
+            .strip_prefix("fn ")
+            .and_then(|rest| rest.strip_prefix(name))
+            .is_some_and(|rest| rest.starts_with('('))
+    })
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && name != "_"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FUNCTIONS: &str = "\
+// Event: begin_play
+fn begin_play() {
+    // print_number
+    let node_a_result = add(1, 2);
+    print_number(node_a_result);
+}
+
+fn on_tick() {
+}
+";
+
+    fn options(target: CodegenTarget, emit_comments: bool) -> CodegenOptions {
+        CodegenOptions {
+            target,
+            emit_comments,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cache_fingerprint_covers_target() {
+        let functions = options(CodegenTarget::Functions, true).cache_fingerprint();
+        assert_eq!(functions, CodegenOptions::default().cache_fingerprint());
+        assert_ne!(
+            functions,
+            options(CodegenTarget::Binary, true).cache_fingerprint()
+        );
+        assert_ne!(
+            functions,
+            options(CodegenTarget::Functions, false).cache_fingerprint()
+        );
+        assert_ne!(
+            options(CodegenTarget::Module { name: "a".into() }, true).cache_fingerprint(),
+            options(CodegenTarget::Module { name: "b".into() }, true).cache_fingerprint()
+        );
+    }
+
+    #[test]
+    fn test_default_keeps_pbgc_output() {
+        assert_eq!(
+            shape(FUNCTIONS, &CodegenOptions::default()).unwrap(),
+            FUNCTIONS
+        );
+    }
+
+    #[test]
+    fn test_module_target() {
+        let target = CodegenTarget::Module {
+            name: "player_events".to_string(),
+        };
+        let code = shape(FUNCTIONS, &options(target, false)).unwrap();
+        assert_eq!(
+            code,
+            "\
+pub mod player_events {
+    use pulsar_std::*;
+
+    pub fn begin_play() {
+        let node_a_result = add(1, 2);
+        print_number(node_a_result);
+    }
+
+    pub fn on_tick() {
+    }
+}
+"
+        );
+
+        let target = CodegenTarget::Module {
+            name: "player-events".to_string(),
+        };
+        assert!(shape(FUNCTIONS, &options(target, false)).is_err());
+    }
+
+    #[test]
+    fn test_binary_target() {
+        let code = shape(FUNCTIONS, &options(CodegenTarget::Binary, true)).unwrap();
+        assert!(code.starts_with(HEADER));
+        assert!(code.contains("// print_number"));
+        assert!(code.ends_with("fn main() {\n    begin_play();\n}\n"));
+
+        let without_begin_play = "fn on_tick() {\n}\n";
+        assert!(shape(without_begin_play, &options(CodegenTarget::Binary, true)).is_err());
+        let inlining_off = CodegenOptions {
+            inline_pure_nodes: false,
+            ..Default::default()
+        };
+        assert!(shape(FUNCTIONS, &inlining_off).is_err());
+    }
+}
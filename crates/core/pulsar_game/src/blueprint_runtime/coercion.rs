@@ -0,0 +1,273 @@
+//! Type checking and coercion of literal pin values.
+//!
+//! Literal values on unconnected inputs are stored untyped in a node's
+//! `properties`, as JSON. Before PBGC splices them into generated code, each
+//! is checked against its pin's data type: lossless coercions are applied
+//! (`5.0` into an `i64` pin becomes `5`) and anything else is a type
+//! mismatch, rather than invalid Rust.
+//!
+//! Enum pins take a variant either by name or by index, and both are
+//! normalized to the variant name.
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Largest integer every `f64` below it represents exactly.
+const F64_EXACT_INT: u64 = 1 << 53;
+/// Largest integer every `f32` below it represents exactly.
+const F32_EXACT_INT: u64 = 1 << 24;
+
+/// Checks a literal against a pin data type in its serialized form,
+/// returning the value coerced to that type, or a description of what the
+/// value was if it doesn't fit.
+///
+/// `enum_types` maps enum type names to their variants in declaration
+/// order. Wildcard pins, exec pins, containers other than `Option` and
+/// types that are neither primitives nor known enums aren't checked.
+pub(crate) fn coerce_literal(
+    value: &Value,
+    data_type: &Value,
+    enum_types: &HashMap<String, Vec<String>>,
+) -> Result<Value, String> {
+    let Some(data) = data_type.get("Data") else {
+        return Ok(value.clone());
+    };
+    if data.get("is_wildcard").and_then(Value::as_bool) == Some(true) {
+        return Ok(value.clone());
+    }
+    let base_type = data.get("base_type").and_then(Value::as_str).unwrap_or("");
+    let wrappers = data
+        .get("wrappers")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    coerce(value, base_type, wrappers, enum_types)
+}
+
+fn coerce(
+    value: &Value,
+    base_type: &str,
+    wrappers: &[Value],
+    enum_types: &HashMap<String, Vec<String>>,
+) -> Result<Value, String> {
+    match wrappers.split_first() {
+        Some((outer, inner)) if outer.as_str() == Some("Option") => {
+            return match value {
+                Value::Null => Ok(Value::Null),
+                value => coerce(value, base_type, inner, enum_types),
+            };
+        }
+        Some(_) => return Ok(value.clone()),
+        None => {}
+    }
+
+    let coerced = match base_type {
+        "i8" => integer(value, i8::MIN.into(), i8::MAX.into()),
+        "i16" => integer(value, i16::MIN.into(), i16::MAX.into()),
+        "i32" => integer(value, i32::MIN.into(), i32::MAX.into()),
+        "i64" | "isize" => integer(value, i64::MIN.into(), i64::MAX.into()),
+        "u8" => integer(value, 0, u8::MAX.into()),
+        "u16" => integer(value, 0, u16::MAX.into()),
+        "u32" => integer(value, 0, u32::MAX.into()),
+        "u64" | "usize" => integer(value, 0, u64::MAX.into()),
+        "f32" => float(value, f32::MAX.into(), F32_EXACT_INT),
+        "f64" => float(value, f64::MAX, F64_EXACT_INT),
+        "bool" => value.as_bool().map(Value::Bool),
+        "String" | "str" | "&str" => value.as_str().map(|s| Value::String(s.to_string())),
+        "char" => value
+            .as_str()
+            .filter(|s| s.chars().count() == 1)
+            .map(|s| Value::String(s.to_string())),
+        enum_type => match enum_types.get(enum_type) {
+            Some(variants) => variant(value, variants),
+            None => return Ok(value.clone()),
+        },
+    };
+    coerced.ok_or_else(|| describe(value))
+}
+
+/// An integer in `min..=max`, from an integer or an integer-valued float.
+fn integer(value: &Value, min: i128, max: i128) -> Option<Value> {
+    let Value::Number(number) = value else {
+        return None;
+    };
+    let n = if let Some(n) = number.as_i64() {
+        i128::from(n)
+    } else if let Some(n) = number.as_u64() {
+        i128::from(n)
+    } else {
+        let f = number.as_f64()?;
+        // Casting saturates, so out of range floats fail the range check
+        if f.fract() != 0.0 || f < min as f64 || f > max as f64 {
+            return None;
+        }
+        f as i128
+    };
+    if n < min || n > max {
+        return None;
+    }
+    Some(match i64::try_from(n) {
+        Ok(n) => Value::from(n),
+        Err(_) => Value::from(u64::try_from(n).ok()?),
+    })
+}
+
+/// A float no larger than `max`, from a float or an integer small enough
+/// to convert exactly.
+fn float(value: &Value, max: f64, exact_int: u64) -> Option<Value> {
+    let Value::Number(number) = value else {
+        return None;
+    };
+    let exact = |magnitude: u64| magnitude <= exact_int;
+    if let Some(n) = number.as_i64() {
+        return exact(n.unsigned_abs()).then(|| Value::from(n as f64));
+    }
+    if let Some(n) = number.as_u64() {
+        return exact(n).then(|| Value::from(n as f64));
+    }
+    let f = number.as_f64()?;
+    (f.abs() <= max).then(|| Value::from(f))
+}
+
+/// The name of a variant given by name or by index.
+fn variant(value: &Value, variants: &[String]) -> Option<Value> {
+    let name = match value {
+        Value::String(name) => variants.iter().find(|variant| *variant == name)?,
+        Value::Number(_) => {
+            let index = integer(value, 0, variants.len() as i128 - 1)?;
+            &variants[index.as_u64()? as usize]
+        }
+        _ => return None,
+    };
+    Some(Value::String(name.clone()))
+}
+
+/// A literal as shown in a type mismatch, e.g. `number 2.5`.
+fn describe(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => format!("bool {}", b),
+        Value::Number(n) => format!("number {}", n),
+        Value::String(s) => format!("string {:?}", s),
+        Value::Array(_) => "array".to_string(),
+        Value::Object(_) => "object".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn data(base_type: &str, wrappers: &[&str]) -> Value {
+        json!({ "Data": { "base_type": base_type, "wrappers": wrappers, "is_wildcard": false } })
+    }
+
+    fn enum_types() -> HashMap<String, Vec<String>> {
+        HashMap::from([(
+            "Direction".to_string(),
+            vec!["North".to_string(), "East".to_string(), "South".to_string()],
+        )])
+    }
+
+    #[test]
+    fn test_every_literal_against_every_type() {
+        let values = [
+            json!(null),
+            json!(true),
+            json!(2),
+            json!(-3),
+            json!(5.0),
+            json!(2.5),
+            json!("East"),
+            json!("x"),
+            json!([1]),
+            json!({ "a": 1 }),
+        ];
+        // The outcome of each value above, in order: the coerced value as
+        // JSON, or X for a mismatch
+        const X: &str = "";
+        let cases = [
+            (data("bool", &[]), [X, "true", X, X, X, X, X, X, X, X]),
+            (data("i64", &[]), [X, X, "2", "-3", "5", X, X, X, X, X]),
+            (data("u8", &[]), [X, X, "2", X, "5", X, X, X, X, X]),
+            (
+                data("f32", &[]),
+                [X, X, "2.0", "-3.0", "5.0", "2.5", X, X, X, X],
+            ),
+            (
+                data("f64", &[]),
+                [X, X, "2.0", "-3.0", "5.0", "2.5", X, X, X, X],
+            ),
+            (
+                data("String", &[]),
+                [X, X, X, X, X, X, "\"East\"", "\"x\"", X, X],
+            ),
+            (data("char", &[]), [X, X, X, X, X, X, X, "\"x\"", X, X]),
+            (
+                data("Direction", &[]),
+                [X, X, "\"South\"", X, X, X, "\"East\"", X, X, X],
+            ),
+            (
+                data("i64", &["Option"]),
+                ["null", X, "2", "-3", "5", X, X, X, X, X],
+            ),
+        ];
+
+        for (data_type, expected) in cases {
+            for (value, expected) in values.iter().zip(expected) {
+                let expected = (expected != X).then(|| serde_json::from_str(expected).unwrap());
+                let coerced = coerce_literal(value, &data_type, &enum_types()).ok();
+                assert_eq!(coerced, expected, "{} into {}", value, data_type);
+            }
+        }
+    }
+
+    #[test]
+    fn test_unchecked_types_pass_through() {
+        let wildcard = json!({ "Data": { "base_type": "T", "wrappers": [], "is_wildcard": true } });
+        for data_type in [
+            wildcard,
+            data("i64", &["Vec"]),
+            data("Transform", &[]),
+            json!("Execution"),
+        ] {
+            for value in [json!(null), json!(2.5), json!("x"), json!([1])] {
+                assert_eq!(
+                    coerce_literal(&value, &data_type, &enum_types()),
+                    Ok(value.clone())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_ranges_and_precision() {
+        let enums = HashMap::new();
+        let check = |value: Value, base_type: &str| {
+            coerce_literal(&value, &data(base_type, &[]), &enums).ok()
+        };
+
+        assert_eq!(check(json!(255), "u8"), Some(json!(255)));
+        assert_eq!(check(json!(256), "u8"), None);
+        assert_eq!(check(json!(-129), "i8"), None);
+        assert_eq!(check(json!(u64::MAX), "u64"), Some(json!(u64::MAX)));
+        assert_eq!(check(json!(u64::MAX), "i64"), None);
+        assert_eq!(check(json!(1e20), "i64"), None);
+        assert_eq!(check(json!(16_777_216), "f32"), Some(json!(16_777_216.0)));
+        assert_eq!(check(json!(16_777_217), "f32"), None);
+        assert_eq!(check(json!(1e300), "f32"), None);
+        assert_eq!(check(json!(9_007_199_254_740_993_u64), "f64"), None);
+        assert_eq!(check(json!("é"), "char"), Some(json!("é")));
+        assert_eq!(check(json!(""), "char"), None);
+
+        assert_eq!(
+            coerce_literal(&json!(3), &data("Direction", &[]), &enum_types()),
+            Err("number 3".to_string())
+        );
+        assert_eq!(
+            coerce_literal(&json!("north"), &data("Direction", &[]), &enum_types()),
+            Err("string \"north\"".to_string())
+        );
+    }
+}
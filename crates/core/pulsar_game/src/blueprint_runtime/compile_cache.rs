@@ -0,0 +1,271 @@
+//! Cache of compiled blueprint graphs, keyed by a hash of the graph.
+//!
+//! [`BytecodeCompiler::compile_blueprint_cached`](super::BytecodeCompiler::compile_blueprint_cached)
+//! skips PBGC for a graph that was compiled before. The key covers the whole
+//! serialized graph (nodes, connections, properties and metadata), the
+//! options it was compiled with and the version of the node set, so
+//! rebuilding `pulsar_std` invalidates every entry. The cache holds at most a fixed number of entries, evicting the
+//! least recently used, and can be kept on disk under [`CACHE_DIR`], one
+//! file per entry.
+
+use pbgc::BpProgram;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+
+/// Project-relative directory of the on-disk cache.
+pub const CACHE_DIR: &str = "target/blueprint_cache";
+
+/// Entries kept by default.
+pub const DEFAULT_CAPACITY: usize = 256;
+
+/// Whether a cached compile found its result in the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    Hit,
+    Miss,
+}
+
+/// Bounded LRU cache of compiled graphs.
+pub struct BlueprintCompileCache<T = BpProgram> {
+    capacity: usize,
+    node_set_version: u64,
+    entries: HashMap<u64, T>,
+    /// Keys from least to most recently used
+    order: VecDeque<u64>,
+    /// Where entries are persisted, if anywhere
+    dir: Option<PathBuf>,
+}
+
+impl<T: Clone + Serialize + DeserializeOwned> BlueprintCompileCache<T> {
+    /// An in-memory cache.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            node_set_version: pulsar_std_node_set_version(),
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            dir: None,
+        }
+    }
+
+    /// A cache persisted in `dir`, starting with the entries already there.
+    /// Entries that can't be read are deleted.
+    pub fn open(dir: &Path, capacity: usize) -> Self {
+        let mut cache = Self::new(capacity);
+        cache.dir = Some(dir.to_path_buf());
+
+        let mut files: Vec<(std::time::SystemTime, u64, PathBuf)> = std::fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let key = u64::from_str_radix(path.file_stem()?.to_str()?, 16).ok()?;
+                let modified = path.metadata().and_then(|m| m.modified()).ok()?;
+                Some((modified, key, path))
+            })
+            .collect();
+        // Oldest first, so the least recently written are evicted first
+        files.sort();
+
+        for (_, key, path) in files {
+            let entry = std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()));
+            match entry {
+                Ok(entry) => cache.insert_loaded(key, entry),
+                Err(e) => {
+                    tracing::warn!("Discarding blueprint cache entry {:?}: {}", path, e);
+                    let _ = std::fs::remove_file(&path);
+                }
+            }
+        }
+        cache
+    }
+
+    /// The cache of the project at `project_root`, in [`CACHE_DIR`].
+    pub fn for_project(project_root: &Path) -> Self {
+        Self::open(&project_root.join(CACHE_DIR), DEFAULT_CAPACITY)
+    }
+
+    /// Keys entries to another node set version than the one of the
+    /// embedded `pulsar_std`.
+    pub fn with_node_set_version(mut self, node_set_version: u64) -> Self {
+        self.node_set_version = node_set_version;
+        self
+    }
+
+    /// The key of a serialized graph compiled with `options`, given as
+    /// canonical JSON. Keys name the files entries are persisted in, so
+    /// they're SHA-256 based to stay the same across builds and toolchains.
+    pub fn key(&self, graph: &Value, options: &Value) -> u64 {
+        let mut hasher = Sha256::new();
+        hasher.update(self.node_set_version.to_le_bytes());
+        // serde_json's maps are sorted, so equal values serialize the same.
+        // The newline keeps the options from running into the graph.
+        hasher.update(options.to_string().as_bytes());
+        hasher.update(b"\n");
+        hasher.update(graph.to_string().as_bytes());
+        leading_u64(&hasher.finalize())
+    }
+
+    /// The entry for `key`, marking it most recently used.
+    pub fn get(&mut self, key: u64) -> Option<T> {
+        let entry = self.entries.get(&key)?.clone();
+        self.touch(key);
+        Some(entry)
+    }
+
+    /// Adds an entry, persisting it and evicting the least recently used
+    /// one if the cache is full.
+    pub fn insert(&mut self, key: u64, entry: T) {
+        if let Some(path) = self.path_of(key) {
+            let written = std::fs::create_dir_all(path.parent().unwrap_or(Path::new(".")))
+                .map_err(|e| e.to_string())
+                .and_then(|_| serde_json::to_string(&entry).map_err(|e| e.to_string()))
+                .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+            if let Err(e) = written {
+                tracing::warn!("Failed to write blueprint cache entry {:?}: {}", path, e);
+            }
+        }
+        self.insert_loaded(key, entry);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes every entry, from disk too.
+    pub fn clear(&mut self) {
+        for key in std::mem::take(&mut self.order) {
+            self.remove_file(key);
+        }
+        self.entries.clear();
+    }
+
+    fn insert_loaded(&mut self, key: u64, entry: T) {
+        self.entries.insert(key, entry);
+        self.touch(key);
+        while self.entries.len() > self.capacity {
+            let Some(lru) = self.order.pop_front() else {
+                break;
+            };
+            self.entries.remove(&lru);
+            self.remove_file(lru);
+        }
+    }
+
+    fn touch(&mut self, key: u64) {
+        // O(n) scan, n is at most the capacity
+        self.order.retain(|&k| k != key);
+        self.order.push_back(key);
+    }
+
+    fn path_of(&self, key: u64) -> Option<PathBuf> {
+        Some(self.dir.as_ref()?.join(format!("{:016x}.json", key)))
+    }
+
+    fn remove_file(&self, key: u64) {
+        if let Some(path) = self.path_of(key) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Version of the node set the embedded `pulsar_std` provides: a hash of
+/// the library, so any change to it changes the version.
+fn pulsar_std_node_set_version() -> u64 {
+    leading_u64(pulsar_std_bundle::expected_sha256())
+}
+
+/// The first eight bytes of a digest, as a key.
+fn leading_u64(digest: &[u8]) -> u64 {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    u64::from_be_bytes(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn cache(capacity: usize) -> BlueprintCompileCache<String> {
+        BlueprintCompileCache::new(capacity).with_node_set_version(1)
+    }
+
+    #[test]
+    fn test_key_covers_graph_options_and_node_set() {
+        let cache = cache(4);
+        let options = json!({ "strip_dead_nodes": false });
+        let graph = json!({ "nodes": { "a": { "node_type": "add" } }, "connections": [] });
+        let same = json!({ "connections": [], "nodes": { "a": { "node_type": "add" } } });
+        let edited = json!({ "nodes": { "a": { "node_type": "subtract" } }, "connections": [] });
+
+        assert_eq!(cache.key(&graph, &options), cache.key(&same, &options));
+        assert_ne!(cache.key(&graph, &options), cache.key(&edited, &options));
+        assert_ne!(
+            cache.key(&graph, &options),
+            cache.key(&graph, &json!({ "strip_dead_nodes": true }))
+        );
+        assert_ne!(
+            cache.key(&graph, &options),
+            BlueprintCompileCache::<String>::new(4)
+                .with_node_set_version(2)
+                .key(&graph, &options)
+        );
+    }
+
+    #[test]
+    fn test_key_is_stable() {
+        // Persisted entries are found by key, so it must not change between
+        // runs or toolchains
+        let graph = json!({ "nodes": {}, "connections": [] });
+        let options = json!({});
+        assert_eq!(
+            cache(4).key(&graph, &options),
+            cache(4).key(&graph, &options)
+        );
+        assert_eq!(cache(4).key(&graph, &options), 0xb32b_1ff5_5c29_5de7);
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut cache = cache(2);
+        cache.insert(1, "one".to_string());
+        cache.insert(2, "two".to_string());
+        assert_eq!(cache.get(1).as_deref(), Some("one"));
+
+        cache.insert(3, "three".to_string());
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(2), None);
+        assert!(cache.get(1).is_some());
+        assert!(cache.get(3).is_some());
+    }
+
+    #[test]
+    fn test_persists_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cache = BlueprintCompileCache::<String>::open(dir.path(), 2);
+        cache.insert(1, "one".to_string());
+        cache.insert(2, "two".to_string());
+        cache.insert(3, "three".to_string());
+        assert!(!dir.path().join(format!("{:016x}.json", 1)).exists());
+        std::fs::write(dir.path().join(format!("{:016x}.json", 4)), "not json").unwrap();
+
+        let mut reopened = BlueprintCompileCache::<String>::open(dir.path(), 2);
+        assert_eq!(reopened.len(), 2);
+        assert_eq!(reopened.get(3).as_deref(), Some("three"));
+        assert!(!dir.path().join(format!("{:016x}.json", 4)).exists());
+
+        reopened.clear();
+        assert!(BlueprintCompileCache::<String>::open(dir.path(), 2).is_empty());
+    }
+}
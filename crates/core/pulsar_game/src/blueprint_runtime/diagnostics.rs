@@ -0,0 +1,903 @@
+//! Structured diagnostics for blueprint compilation.
+//!
+//! [`BytecodeCompiler::compile_blueprint_with_diagnostics`](super::BytecodeCompiler::compile_blueprint_with_diagnostics)
+//! reports every problem it finds in a graph as a [`BlueprintDiagnostic`]
+//! naming the node and pin at fault, so the blueprint editor can highlight
+//! them and list them in the problems drawer.
+//! [`BytecodeCompiler::validate_blueprint`](super::BytecodeCompiler::validate_blueprint)
+//! runs the same checks without compiling, cheaply enough to call on every
+//! edit of the graph.
+//!
+//! The graph is checked in its serialized form, the same JSON PBGC reads:
+//!
+//! ```json
+//! {
+//!   "nodes": { "<id>": { "node_type": "add", "inputs": [...], "outputs": [...], "properties": {...} } },
+//!   "connections": [ { "source_node": "...", "source_pin": "...", "target_node": "...", "target_pin": "..." } ]
+//! }
+//! ```
+
+use super::bytecode_compiler::CompilerOptions;
+use super::coercion::coerce_literal;
+use pbgc::BpProgram;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
+
+/// How serious a [`BlueprintDiagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagnosticSeverity {
+    /// The graph can't be compiled
+    Error,
+    /// The graph compiles, but probably doesn't do what was meant
+    Warning,
+}
+
+/// What kind of problem a [`BlueprintDiagnostic`] reports, for problems
+/// the editor can do more with than show the message.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    #[default]
+    General,
+    /// A connection or literal value of type `got` on a pin of type
+    /// `expected`
+    TypeMismatch { expected: String, got: String },
+}
+
+/// A problem found while compiling a blueprint graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlueprintDiagnostic {
+    pub severity: DiagnosticSeverity,
+    pub kind: DiagnosticKind,
+    pub message: String,
+    /// The node at fault, if the problem is tied to one
+    pub node_id: Option<String>,
+    /// The pin at fault on `node_id`, if the problem is tied to one
+    pub pin_id: Option<String>,
+}
+
+impl BlueprintDiagnostic {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            severity: DiagnosticSeverity::Error,
+            kind: DiagnosticKind::General,
+            message: message.into(),
+            node_id: None,
+            pin_id: None,
+        }
+    }
+
+    /// An error for a value of type `got` on pin `pin_id` of `node_id`,
+    /// which takes `expected`.
+    pub fn type_mismatch(
+        message: impl Into<String>,
+        node_id: impl Into<String>,
+        pin_id: impl Into<String>,
+        expected: impl Into<String>,
+        got: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind: DiagnosticKind::TypeMismatch {
+                expected: expected.into(),
+                got: got.into(),
+            },
+            ..Self::error(message).at_pin(node_id, pin_id)
+        }
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self {
+            severity: DiagnosticSeverity::Warning,
+            ..Self::error(message)
+        }
+    }
+
+    pub fn at_node(mut self, node_id: impl Into<String>) -> Self {
+        self.node_id = Some(node_id.into());
+        self
+    }
+
+    pub fn at_pin(mut self, node_id: impl Into<String>, pin_id: impl Into<String>) -> Self {
+        self.pin_id = Some(pin_id.into());
+        self.at_node(node_id)
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == DiagnosticSeverity::Error
+    }
+}
+
+impl fmt::Display for BlueprintDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.severity {
+            DiagnosticSeverity::Error => write!(f, "error")?,
+            DiagnosticSeverity::Warning => write!(f, "warning")?,
+        }
+        match (&self.node_id, &self.pin_id) {
+            (Some(node), Some(pin)) => write!(f, " [{}.{}]", node, pin)?,
+            (Some(node), None) => write!(f, " [{}]", node)?,
+            _ => {}
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Outcome of compiling a graph with diagnostics.
+#[derive(Debug, Clone, Default)]
+pub struct CompileResult {
+    /// The compiled program, if there were no errors
+    pub program: Option<BpProgram>,
+    /// Errors and warnings, in node order
+    pub diagnostics: Vec<BlueprintDiagnostic>,
+}
+
+impl CompileResult {
+    pub fn failed(diagnostic: BlueprintDiagnostic) -> Self {
+        Self {
+            program: None,
+            diagnostics: vec![diagnostic],
+        }
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(BlueprintDiagnostic::is_error)
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &BlueprintDiagnostic> {
+        self.diagnostics.iter().filter(|d| d.is_error())
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &BlueprintDiagnostic> {
+        self.diagnostics.iter().filter(|d| !d.is_error())
+    }
+}
+
+/// A pin of a node in the serialized graph.
+struct PinView<'a> {
+    id: &'a str,
+    name: Option<&'a str>,
+    data_type: Option<&'a Value>,
+}
+
+impl<'a> PinView<'a> {
+    fn read(pin: &'a Value) -> Option<Self> {
+        let inner = pin.get("pin");
+        let field = |key: &str| {
+            pin.get(key)
+                .or_else(|| inner.and_then(|inner| inner.get(key)))
+        };
+        Some(Self {
+            id: field("id")?.as_str()?,
+            name: field("name").and_then(Value::as_str),
+            data_type: field("data_type"),
+        })
+    }
+
+    fn is_exec(&self) -> bool {
+        self.data_type
+            .and_then(Value::as_str)
+            .is_some_and(|t| t == "Execution" || t == "Exec")
+    }
+}
+
+/// A node in the serialized graph.
+pub(super) struct NodeView<'a> {
+    node_type: &'a str,
+    inputs: Vec<PinView<'a>>,
+    outputs: Vec<PinView<'a>>,
+    properties: Option<&'a serde_json::Map<String, Value>>,
+}
+
+impl<'a> NodeView<'a> {
+    fn read(node: &'a Value) -> Self {
+        let pins = |key: &str| {
+            node.get(key)
+                .and_then(Value::as_array)
+                .map(|pins| pins.iter().filter_map(PinView::read).collect())
+                .unwrap_or_default()
+        };
+        Self {
+            node_type: node.get("node_type").and_then(Value::as_str).unwrap_or(""),
+            inputs: pins("inputs"),
+            outputs: pins("outputs"),
+            properties: node.get("properties").and_then(Value::as_object),
+        }
+    }
+
+    fn pin(&self, id: &str) -> Option<&PinView<'a>> {
+        self.inputs.iter().chain(&self.outputs).find(|p| p.id == id)
+    }
+
+    /// Whether the node has no exec pins, so it only runs to produce its
+    /// outputs.
+    pub(super) fn is_pure(&self) -> bool {
+        !self
+            .inputs
+            .iter()
+            .chain(&self.outputs)
+            .any(PinView::is_exec)
+    }
+
+    /// The literal value of an unconnected input, with the property key it
+    /// is stored under: the pin's ID or, failing that, its name.
+    fn literal(&self, pin: &PinView) -> Option<(&'a str, &'a Value)> {
+        let properties = self.properties?;
+        [Some(pin.id), pin.name]
+            .into_iter()
+            .flatten()
+            .find_map(|key| properties.get_key_value(key))
+            .map(|(key, value)| (key.as_str(), value))
+    }
+}
+
+/// Checks a serialized graph for problems PBGC would otherwise report as a
+/// single error string, or not at all: missing nodes and pins, type
+/// mismatches of connections and literal values, unset inputs, duplicate
+/// pin IDs, and nodes no event's execution flow reaches.
+///
+/// Node types are only checked when
+/// [`known_node_types`](CompilerOptions::known_node_types) is set.
+pub(crate) fn validate_graph(graph: &Value, options: &CompilerOptions) -> Vec<BlueprintDiagnostic> {
+    let graph = GraphView::read(graph);
+    let mut diagnostics = graph.check_connections_and_inputs(options);
+    diagnostics.extend(graph.duplicate_pins());
+    diagnostics.extend(graph.unreachable_nodes());
+    diagnostics
+}
+
+/// The serialized graph, with nodes sorted by ID so diagnostics come out in
+/// a stable order.
+pub(super) struct GraphView<'a> {
+    pub(super) nodes: BTreeMap<&'a str, NodeView<'a>>,
+    /// `[source_node, source_pin, target_node, target_pin]`
+    pub(super) connections: Vec<[&'a str; 4]>,
+}
+
+impl<'a> GraphView<'a> {
+    pub(super) fn read(graph: &'a Value) -> Self {
+        let nodes = match graph.get("nodes") {
+            Some(Value::Object(nodes)) => nodes
+                .iter()
+                .map(|(id, node)| (id.as_str(), NodeView::read(node)))
+                .collect(),
+            Some(Value::Array(nodes)) => nodes
+                .iter()
+                .filter_map(|node| Some((node.get("id")?.as_str()?, NodeView::read(node))))
+                .collect(),
+            _ => BTreeMap::new(),
+        };
+        let connections = graph
+            .get("connections")
+            .and_then(Value::as_array)
+            .map(|connections| {
+                connections
+                    .iter()
+                    .filter_map(|c| {
+                        let field = |key: &str| c.get(key).and_then(Value::as_str);
+                        Some([
+                            field("source_node")?,
+                            field("source_pin")?,
+                            field("target_node")?,
+                            field("target_pin")?,
+                        ])
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { nodes, connections }
+    }
+
+    fn check_connections_and_inputs(&self, options: &CompilerOptions) -> Vec<BlueprintDiagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut connected_inputs = HashSet::new();
+        let mut connected_outputs = HashSet::new();
+
+        for &[source_node, source_pin, target_node, target_pin] in &self.connections {
+            connected_outputs.insert((source_node, source_pin));
+            connected_inputs.insert((target_node, target_pin));
+
+            let Some(source) = self.nodes.get(source_node) else {
+                diagnostics.push(
+                    BlueprintDiagnostic::error(format!(
+                        "Connection from missing node '{}'",
+                        source_node
+                    ))
+                    .at_pin(target_node, target_pin),
+                );
+                continue;
+            };
+            let Some(target) = self.nodes.get(target_node) else {
+                diagnostics.push(
+                    BlueprintDiagnostic::error(format!(
+                        "Connection to missing node '{}'",
+                        target_node
+                    ))
+                    .at_pin(source_node, source_pin),
+                );
+                continue;
+            };
+            let Some(from) = source.pin(source_pin) else {
+                diagnostics.push(
+                    BlueprintDiagnostic::error(format!(
+                        "Node '{}' has no pin '{}'",
+                        source.node_type, source_pin
+                    ))
+                    .at_pin(source_node, source_pin),
+                );
+                continue;
+            };
+            let Some(to) = target.pin(target_pin) else {
+                diagnostics.push(
+                    BlueprintDiagnostic::error(format!(
+                        "Node '{}' has no pin '{}'",
+                        target.node_type, target_pin
+                    ))
+                    .at_pin(target_node, target_pin),
+                );
+                continue;
+            };
+            if let (Some(from_type), Some(to_type)) = (from.data_type, to.data_type) {
+                if !types_compatible(from_type, to_type) {
+                    let (from_type, to_type) = (render_type(from_type), render_type(to_type));
+                    diagnostics.push(BlueprintDiagnostic::type_mismatch(
+                        format!(
+                            "Type mismatch: {} output '{}' of '{}' is connected to {} input '{}'",
+                            from_type,
+                            from.name.unwrap_or(from.id),
+                            source.node_type,
+                            to_type,
+                            to.name.unwrap_or(to.id),
+                        ),
+                        target_node,
+                        target_pin,
+                        to_type,
+                        from_type,
+                    ));
+                }
+            }
+        }
+
+        for (&id, node) in &self.nodes {
+            if let Some(known) = &options.known_node_types {
+                if !known.contains(node.node_type) {
+                    diagnostics.push(
+                        BlueprintDiagnostic::error(format!(
+                            "Unknown node type '{}'",
+                            node.node_type
+                        ))
+                        .at_node(id),
+                    );
+                    continue;
+                }
+            }
+
+            for input in node.inputs.iter().filter(|pin| !pin.is_exec()) {
+                if connected_inputs.contains(&(id, input.id)) {
+                    continue;
+                }
+                let Some((_, value)) = node.literal(input) else {
+                    diagnostics.push(
+                        BlueprintDiagnostic::error(format!(
+                            "Input '{}' of '{}' is not connected and has no value",
+                            input.name.unwrap_or(input.id),
+                            node.node_type
+                        ))
+                        .at_pin(id, input.id),
+                    );
+                    continue;
+                };
+                let Some(data_type) = input.data_type else {
+                    continue;
+                };
+                if let Err(got) = coerce_literal(value, data_type, &options.enum_types) {
+                    diagnostics.push(literal_mismatch(id, node, input, data_type, got));
+                }
+            }
+
+            // A node with exec pins runs for its side effects, so leaving its
+            // outputs unused is normal
+            if node.is_pure() {
+                for output in &node.outputs {
+                    if !connected_outputs.contains(&(id, output.id)) {
+                        diagnostics.push(
+                            BlueprintDiagnostic::warning(format!(
+                                "Output '{}' of '{}' is never used",
+                                output.name.unwrap_or(output.id),
+                                node.node_type
+                            ))
+                            .at_pin(id, output.id),
+                        );
+                    }
+                }
+            }
+        }
+
+        diagnostics
+    }
+
+    /// Pins that share an ID with another pin of the same node, which makes
+    /// connections to them ambiguous.
+    fn duplicate_pins(&self) -> Vec<BlueprintDiagnostic> {
+        let mut diagnostics = Vec::new();
+        for (&id, node) in &self.nodes {
+            let mut seen = HashSet::new();
+            for pin in node.inputs.iter().chain(&node.outputs) {
+                if !seen.insert(pin.id) {
+                    diagnostics.push(
+                        BlueprintDiagnostic::error(format!(
+                            "'{}' has more than one pin with ID '{}'",
+                            node.node_type, pin.id
+                        ))
+                        .at_pin(id, pin.id),
+                    );
+                }
+            }
+        }
+        diagnostics
+    }
+
+    /// Nodes with exec pins that no event's execution flow reaches, so they
+    /// never run.
+    fn unreachable_nodes(&self) -> Vec<BlueprintDiagnostic> {
+        let reached = self.reached_by_exec();
+        self.nodes
+            .iter()
+            .filter(|(id, node)| !node.is_pure() && !reached.contains(*id))
+            .map(|(&id, node)| {
+                BlueprintDiagnostic::warning(format!(
+                    "'{}' never runs: no event's execution flow reaches it",
+                    node.node_type
+                ))
+                .at_node(id)
+            })
+            .collect()
+    }
+
+    /// Nodes some event's execution flow reaches. Events are the nodes with
+    /// exec outputs but no exec inputs.
+    pub(super) fn reached_by_exec(&self) -> HashSet<&'a str> {
+        let mut next: HashMap<&str, Vec<&'a str>> = HashMap::new();
+        for &[source_node, source_pin, target_node, _] in &self.connections {
+            let is_exec = self
+                .nodes
+                .get(source_node)
+                .and_then(|node| node.pin(source_pin))
+                .is_some_and(PinView::is_exec);
+            if is_exec {
+                next.entry(source_node).or_default().push(target_node);
+            }
+        }
+
+        let mut reached: HashSet<&'a str> = HashSet::new();
+        let mut queue: VecDeque<&'a str> = self
+            .nodes
+            .iter()
+            .filter(|(_, node)| {
+                !node.inputs.iter().any(PinView::is_exec)
+                    && node.outputs.iter().any(PinView::is_exec)
+            })
+            .map(|(&id, _)| id)
+            .collect();
+        while let Some(id) = queue.pop_front() {
+            if reached.insert(id) {
+                queue.extend(next.get(id).into_iter().flatten());
+            }
+        }
+        reached
+    }
+}
+
+/// Replaces the literal values of unconnected inputs in a serialized graph
+/// with their values coerced to the pins' types, or returns the type
+/// mismatches if any value doesn't fit its pin.
+pub(crate) fn coerce_properties(
+    graph: &mut Value,
+    options: &CompilerOptions,
+) -> Result<(), Vec<BlueprintDiagnostic>> {
+    let mut coerced = Vec::new();
+    let mut mismatches = Vec::new();
+    {
+        let view = GraphView::read(graph);
+        let connected: HashSet<(&str, &str)> = view
+            .connections
+            .iter()
+            .map(|&[_, _, target_node, target_pin]| (target_node, target_pin))
+            .collect();
+        for (&id, node) in &view.nodes {
+            for input in &node.inputs {
+                let (Some((key, value)), Some(data_type)) = (node.literal(input), input.data_type)
+                else {
+                    continue;
+                };
+                if connected.contains(&(id, input.id)) {
+                    continue;
+                }
+                match coerce_literal(value, data_type, &options.enum_types) {
+                    Ok(new_value) if new_value != *value => {
+                        coerced.push((id.to_string(), key.to_string(), new_value));
+                    }
+                    Ok(_) => {}
+                    Err(got) => mismatches.push(literal_mismatch(id, node, input, data_type, got)),
+                }
+            }
+        }
+    }
+    if !mismatches.is_empty() {
+        return Err(mismatches);
+    }
+
+    for (node_id, key, value) in coerced {
+        let node = match graph.get_mut("nodes") {
+            Some(Value::Object(nodes)) => nodes.get_mut(&node_id),
+            Some(Value::Array(nodes)) => nodes
+                .iter_mut()
+                .find(|node| node.get("id").and_then(Value::as_str) == Some(&node_id)),
+            _ => None,
+        };
+        if let Some(properties) = node
+            .and_then(|node| node.get_mut("properties"))
+            .and_then(Value::as_object_mut)
+        {
+            properties.insert(key, value);
+        }
+    }
+    Ok(())
+}
+
+/// The type mismatch of a literal value of type `got` on `input`.
+fn literal_mismatch(
+    node_id: &str,
+    node: &NodeView,
+    input: &PinView,
+    data_type: &Value,
+    got: String,
+) -> BlueprintDiagnostic {
+    let expected = render_type(data_type);
+    BlueprintDiagnostic::type_mismatch(
+        format!(
+            "Type mismatch: input '{}' of '{}' takes {}, but its value is {}",
+            input.name.unwrap_or(input.id),
+            node.node_type,
+            expected,
+            got
+        ),
+        node_id,
+        input.id,
+        expected,
+        got,
+    )
+}
+
+/// A diagnostic for an error PBGC reported as text, tied to the node whose
+/// ID it mentions, if any.
+pub(crate) fn from_compiler_message(message: String, graph: &Value) -> BlueprintDiagnostic {
+    let node_id = graph
+        .get("nodes")
+        .and_then(Value::as_object)
+        .and_then(|nodes| {
+            nodes
+                .keys()
+                .filter(|id| message.contains(id.as_str()))
+                // The longest match, in case one ID contains another
+                .max_by_key(|id| id.len())
+                .cloned()
+        });
+    let diagnostic = BlueprintDiagnostic::error(message);
+    match node_id {
+        Some(node_id) => diagnostic.at_node(node_id),
+        None => diagnostic,
+    }
+}
+
+/// Whether a value of type `from` can flow into a pin of type `to`.
+fn types_compatible(from: &Value, to: &Value) -> bool {
+    let is_wildcard = |t: &Value| {
+        t.pointer("/Data/is_wildcard")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    };
+    from == to || is_wildcard(from) || is_wildcard(to)
+}
+
+/// A pin data type as shown to the user, e.g. `Vec<i64>` or `exec`.
+fn render_type(data_type: &Value) -> String {
+    if data_type
+        .as_str()
+        .is_some_and(|t| t == "Execution" || t == "Exec")
+    {
+        return "exec".to_string();
+    }
+    let Some(base) = data_type.pointer("/Data/base_type").and_then(Value::as_str) else {
+        return data_type.to_string();
+    };
+    let wrappers = data_type
+        .pointer("/Data/wrappers")
+        .and_then(Value::as_array)
+        .map(|wrappers| {
+            wrappers
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    wrappers
+        .iter()
+        .rev()
+        .fold(base.to_string(), |inner, wrapper| {
+            format!("{}<{}>", wrapper, inner)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn data(base_type: &str) -> Value {
+        json!({ "Data": { "base_type": base_type, "wrappers": [], "is_wildcard": false } })
+    }
+
+    fn pin(id: &str, data_type: Value) -> Value {
+        json!({ "id": id, "pin": { "id": id, "name": id, "data_type": data_type } })
+    }
+
+    fn graph() -> Value {
+        json!({
+            "nodes": {
+                "begin": {
+                    "node_type": "begin_play",
+                    "inputs": [],
+                    "outputs": [pin("begin_exec", json!("Execution"))]
+                },
+                "add": {
+                    "node_type": "add",
+                    "inputs": [pin("add_a", data("i64")), pin("add_b", data("i64"))],
+                    "outputs": [pin("add_r", data("i64"))],
+                    "properties": { "add_a": 1.0 }
+                },
+                "print": {
+                    "node_type": "print_string",
+                    "inputs": [pin("print_exec", json!("Execution")), pin("print_s", data("String"))],
+                    "outputs": []
+                }
+            },
+            "connections": [
+                { "source_node": "begin", "source_pin": "begin_exec", "target_node": "print", "target_pin": "print_exec" },
+                { "source_node": "add", "source_pin": "add_r", "target_node": "print", "target_pin": "print_s" }
+            ]
+        })
+    }
+
+    #[test]
+    fn test_reports_nodes_and_pins() {
+        let options = CompilerOptions {
+            known_node_types: Some(["begin_play", "add"].map(String::from).into()),
+            ..Default::default()
+        };
+        let diagnostics = validate_graph(&graph(), &options);
+
+        let at = |node: &str, pin: Option<&str>| {
+            diagnostics
+                .iter()
+                .find(|d| d.node_id.as_deref() == Some(node) && d.pin_id.as_deref() == pin)
+                .unwrap_or_else(|| panic!("no diagnostic at {}.{:?}: {:?}", node, pin, diagnostics))
+        };
+        assert!(at("print", Some("print_s"))
+            .message
+            .starts_with("Type mismatch: i64"));
+        assert!(at("add", Some("add_b")).message.contains("not connected"));
+        assert_eq!(
+            at("print", None).message,
+            "Unknown node type 'print_string'"
+        );
+        assert_eq!(diagnostics.len(), 3);
+        assert!(diagnostics.iter().all(BlueprintDiagnostic::is_error));
+        assert_eq!(
+            at("add", Some("add_b")).to_string(),
+            "error [add.add_b]: Input 'add_b' of 'add' is not connected and has no value"
+        );
+    }
+
+    #[test]
+    fn test_warns_about_unused_outputs() {
+        let mut graph = graph();
+        graph["connections"].as_array_mut().unwrap().pop();
+        graph["nodes"]["add"]["properties"]["add_b"] = json!(2.0);
+
+        let diagnostics = validate_graph(&graph, &CompilerOptions::default());
+        let warnings: Vec<_> = diagnostics.iter().filter(|d| !d.is_error()).collect();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].pin_id.as_deref(), Some("add_r"));
+        // The print node's input is now unconnected
+        assert_eq!(diagnostics.len(), 2);
+
+        let diagnostic = from_compiler_message("No function for node add".to_string(), &graph);
+        assert_eq!(diagnostic.node_id.as_deref(), Some("add"));
+    }
+
+    #[test]
+    fn test_checks_and_coerces_literal_values() {
+        let mut graph = graph();
+        graph["connections"].as_array_mut().unwrap().pop();
+        graph["nodes"]["add"]["properties"]["add_b"] = json!("2");
+        graph["nodes"]["print"]["properties"] = json!({ "print_s": "sum" });
+
+        let options = CompilerOptions::default();
+        let diagnostics = validate_graph(&graph, &options);
+        let mismatch = diagnostics
+            .iter()
+            .find(|d| d.pin_id.as_deref() == Some("add_b"))
+            .unwrap();
+        assert_eq!(
+            mismatch.kind,
+            DiagnosticKind::TypeMismatch {
+                expected: "i64".to_string(),
+                got: "string \"2\"".to_string()
+            }
+        );
+        assert_eq!(
+            mismatch.to_string(),
+            "error [add.add_b]: Type mismatch: input 'add_b' of 'add' takes i64, but its value is string \"2\""
+        );
+        assert_eq!(
+            coerce_properties(&mut graph.clone(), &options).unwrap_err(),
+            std::slice::from_ref(mismatch)
+        );
+
+        graph["nodes"]["add"]["properties"]["add_b"] = json!(2.0);
+        coerce_properties(&mut graph, &options).unwrap();
+        assert_eq!(
+            graph["nodes"]["add"]["properties"],
+            json!({ "add_a": 1, "add_b": 2 })
+        );
+        assert_eq!(graph["nodes"]["print"]["properties"]["print_s"], "sum");
+    }
+
+    #[test]
+    fn test_duplicate_pins_and_unreachable_nodes() {
+        let mut graph = graph();
+        graph["nodes"]["add"]["outputs"]
+            .as_array_mut()
+            .unwrap()
+            .push(pin("add_b", data("i64")));
+        graph["nodes"]["orphan"] = json!({
+            "node_type": "print_string",
+            "inputs": [pin("orphan_exec", json!("Execution"))],
+            "outputs": []
+        });
+
+        let diagnostics = validate_graph(&graph, &CompilerOptions::default());
+        let duplicate = diagnostics
+            .iter()
+            .find(|d| d.message.contains("more than one pin"))
+            .unwrap();
+        assert_eq!(duplicate.node_id.as_deref(), Some("add"));
+        assert_eq!(duplicate.pin_id.as_deref(), Some("add_b"));
+
+        let unreachable: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| d.message.contains("never runs"))
+            .collect();
+        assert_eq!(unreachable.len(), 1);
+        assert_eq!(unreachable[0].node_id.as_deref(), Some("orphan"));
+        assert!(!unreachable[0].is_error());
+    }
+
+    /// An event followed by a chain of `len - 1` print nodes, each fed by
+    /// the previous node's output.
+    fn chain(len: usize) -> Value {
+        let mut nodes = serde_json::Map::new();
+        let mut connections = Vec::new();
+        nodes.insert(
+            "n0".to_string(),
+            json!({
+                "node_type": "begin_play",
+                "inputs": [],
+                "outputs": [pin("n0_exec_out", json!("Execution")), pin("n0_out", data("f64"))]
+            }),
+        );
+        for i in 1..len {
+            let id = format!("n{}", i);
+            nodes.insert(
+                id.clone(),
+                json!({
+                    "node_type": "print_number",
+                    "inputs": [pin(&format!("{}_exec_in", id), json!("Execution")), pin(&format!("{}_in", id), data("f64"))],
+                    "outputs": [pin(&format!("{}_exec_out", id), json!("Execution")), pin(&format!("{}_out", id), data("f64"))]
+                }),
+            );
+            let prev = format!("n{}", i - 1);
+            for (from, to) in [("exec_out", "exec_in"), ("out", "in")] {
+                connections.push(json!({
+                    "source_node": prev,
+                    "source_pin": format!("{}_{}", prev, from),
+                    "target_node": id,
+                    "target_pin": format!("{}_{}", id, to),
+                }));
+            }
+        }
+        json!({ "nodes": nodes, "connections": connections })
+    }
+
+    fn chain_options() -> CompilerOptions {
+        CompilerOptions {
+            known_node_types: Some(["begin_play", "print_number"].map(String::from).into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_validates_large_graph() {
+        let options = chain_options();
+        let diagnostics = validate_graph(&chain(500), &options);
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+        // Break the chain in three places far from either end
+        let mut graph = chain(500);
+        graph["nodes"]["n400"]["node_type"] = json!("print_text");
+        graph["connections"]
+            .as_array_mut()
+            .unwrap()
+            .retain(|c| c["target_pin"] != "n250_in" && c["target_pin"] != "n300_exec_in");
+
+        let diagnostics = validate_graph(&graph, &options);
+        let errors: Vec<&BlueprintDiagnostic> =
+            diagnostics.iter().filter(|d| d.is_error()).collect();
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors.iter().any(|d| {
+            d.node_id.as_deref() == Some("n250")
+                && d.pin_id.as_deref() == Some("n250_in")
+                && d.message.contains("not connected")
+        }));
+        assert!(errors.iter().any(|d| {
+            d.node_id.as_deref() == Some("n400") && d.message == "Unknown node type 'print_text'"
+        }));
+
+        let unreachable: Vec<&str> = diagnostics
+            .iter()
+            .filter(|d| d.message.contains("never runs"))
+            .filter_map(|d| d.node_id.as_deref())
+            .collect();
+        assert_eq!(unreachable.len(), 200);
+        assert_eq!(unreachable.first(), Some(&"n300"));
+        assert!(unreachable.contains(&"n499"));
+        assert_eq!(diagnostics.len(), 202);
+    }
+
+    #[test]
+    #[ignore = "timing, run with --ignored --release"]
+    fn test_validating_large_graph_is_fast() {
+        let graph = chain(500);
+        let options = chain_options();
+
+        // Best of a few runs, so a busy machine doesn't fail the test
+        let fastest = (0..3)
+            .map(|_| {
+                let start = std::time::Instant::now();
+                validate_graph(&graph, &options);
+                start.elapsed()
+            })
+            .min()
+            .unwrap();
+        // About 2ms in release builds; unoptimized builds are several times
+        // slower
+        let budget = if cfg!(debug_assertions) { 50 } else { 10 };
+        assert!(
+            fastest < std::time::Duration::from_millis(budget),
+            "validating a 500-node graph took {:?}",
+            fastest
+        );
+    }
+
+    #[test]
+    fn test_render_type() {
+        assert_eq!(render_type(&data("f32")), "f32");
+        assert_eq!(render_type(&json!("Execution")), "exec");
+        assert_eq!(
+            render_type(
+                &json!({ "Data": { "base_type": "i64", "wrappers": ["Vec", "Option"], "is_wildcard": false } })
+            ),
+            "Vec<Option<i64>>"
+        );
+    }
+}
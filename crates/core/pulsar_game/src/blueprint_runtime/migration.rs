@@ -0,0 +1,295 @@
+//! Versioning and migration of saved blueprint graphs.
+//!
+//! A graph records the format it was saved in as `metadata.version`. When
+//! the format changes, a [`MigrationStep`] rewriting graphs from the previous
+//! version is registered here, so older `graph_save.json` files keep loading:
+//! [`GraphMigrator`] runs the steps from a graph's version up to
+//! [`CURRENT_GRAPH_VERSION`] on the raw JSON before it is deserialized.
+//!
+//! Steps are plain functions from JSON to JSON, so each can be tested on its
+//! own.
+
+use serde_json::Value;
+use std::fmt;
+use ui::graph::{BlueprintAsset, GraphDescription};
+
+/// Format version of graphs saved by this engine.
+pub const CURRENT_GRAPH_VERSION: &str = "1.0.0";
+
+/// Version assumed for graphs saved before versions were recorded.
+const UNVERSIONED: &str = "0.1.0";
+
+/// Rewrites a graph saved in format `from` into format `to`.
+#[derive(Debug, Clone, Copy)]
+pub struct MigrationStep {
+    pub from: &'static str,
+    pub to: &'static str,
+    /// Takes the whole graph, returning it rewritten or why it can't be
+    pub migrate: fn(Value) -> Result<Value, String>,
+}
+
+/// Error migrating a saved graph.
+#[derive(Debug)]
+pub enum MigrationError {
+    /// The graph was saved by a newer engine than this one
+    NewerVersion { found: String },
+
+    /// `metadata.version` isn't a `major.minor.patch` version
+    InvalidVersion(String),
+
+    /// No registered step migrates from this version
+    NoMigration { from: String },
+
+    /// A step failed on this graph
+    StepFailed {
+        from: &'static str,
+        to: &'static str,
+        message: String,
+    },
+
+    /// The migrated graph doesn't deserialize
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::NewerVersion { found } => write!(
+                f,
+                "Blueprint was created with a newer engine (graph format {}, this engine supports up to {})",
+                found, CURRENT_GRAPH_VERSION
+            ),
+            MigrationError::InvalidVersion(version) => {
+                write!(f, "Invalid graph format version '{}'", version)
+            }
+            MigrationError::NoMigration { from } => write!(
+                f,
+                "No migration from graph format {} to {}",
+                from, CURRENT_GRAPH_VERSION
+            ),
+            MigrationError::StepFailed { from, to, message } => {
+                write!(f, "Migrating graph from {} to {} failed: {}", from, to, message)
+            }
+            MigrationError::Json(e) => write!(f, "Migrated graph is invalid: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+impl From<serde_json::Error> for MigrationError {
+    fn from(e: serde_json::Error) -> Self {
+        MigrationError::Json(e)
+    }
+}
+
+/// Migrates saved graphs to [`CURRENT_GRAPH_VERSION`].
+#[derive(Debug, Clone)]
+pub struct GraphMigrator {
+    steps: Vec<MigrationStep>,
+}
+
+impl Default for GraphMigrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GraphMigrator {
+    /// A migrator with the engine's migration steps.
+    pub fn new() -> Self {
+        Self {
+            steps: vec![MigrationStep {
+                from: "0.1.0",
+                to: "1.0.0",
+                migrate: nodes_keyed_by_id,
+            }],
+        }
+    }
+
+    /// Adds a migration step, replacing any registered from the same version.
+    pub fn register(&mut self, step: MigrationStep) {
+        self.steps.retain(|existing| existing.from != step.from);
+        self.steps.push(step);
+    }
+
+    /// Migrates a serialized graph to the current format and deserializes it.
+    pub fn migrate_to_current(&self, graph: Value) -> Result<GraphDescription, MigrationError> {
+        Ok(serde_json::from_value(self.migrate(graph)?)?)
+    }
+
+    /// Migrates the main graph of a serialized `graph_save.json` and
+    /// deserializes the asset.
+    pub fn migrate_asset(&self, mut asset: Value) -> Result<BlueprintAsset, MigrationError> {
+        if let Some(graph) = asset.get_mut("main_graph") {
+            *graph = self.migrate(graph.take())?;
+        }
+        Ok(serde_json::from_value(asset)?)
+    }
+
+    /// Runs the steps from the graph's version up to the current format,
+    /// returning the migrated JSON. Current graphs are returned unchanged.
+    pub fn migrate(&self, mut graph: Value) -> Result<Value, MigrationError> {
+        let current = parse_version(CURRENT_GRAPH_VERSION).expect("current version is valid");
+        let mut version = graph
+            .pointer("/metadata/version")
+            .and_then(Value::as_str)
+            .unwrap_or(UNVERSIONED)
+            .to_string();
+
+        loop {
+            let parsed = parse_version(&version)
+                .ok_or_else(|| MigrationError::InvalidVersion(version.clone()))?;
+            if parsed == current {
+                return Ok(graph);
+            }
+            if parsed > current {
+                return Err(MigrationError::NewerVersion { found: version });
+            }
+
+            let step = self
+                .steps
+                .iter()
+                .find(|step| parse_version(step.from) == Some(parsed))
+                .ok_or_else(|| MigrationError::NoMigration {
+                    from: version.clone(),
+                })?;
+            graph = (step.migrate)(graph).map_err(|message| MigrationError::StepFailed {
+                from: step.from,
+                to: step.to,
+                message,
+            })?;
+            set_version(&mut graph, step.to);
+            version = step.to.to_string();
+        }
+    }
+}
+
+/// Parses a `major.minor.patch` version.
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = version.trim().split('.').map(str::parse::<u64>);
+    let version = (
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+    );
+    parts.next().is_none().then_some(version)
+}
+
+fn set_version(graph: &mut Value, version: &str) {
+    let Value::Object(graph) = graph else {
+        return;
+    };
+    let metadata = graph
+        .entry("metadata")
+        .or_insert_with(|| Value::Object(Default::default()));
+    if let Value::Object(metadata) = metadata {
+        metadata.insert("version".to_string(), Value::from(version));
+    }
+}
+
+// ── Migration steps ──────────────────────────────────────────────────────────
+
+/// 0.1.0 → 1.0.0: nodes were an array of nodes carrying their IDs, and are
+/// now an object keyed by node ID. Graphs without comments get an empty list.
+fn nodes_keyed_by_id(mut graph: Value) -> Result<Value, String> {
+    let Value::Object(fields) = &mut graph else {
+        return Err("graph is not an object".to_string());
+    };
+    if let Some(Value::Array(nodes)) = fields.get_mut("nodes") {
+        let mut keyed = serde_json::Map::new();
+        for node in nodes.drain(..) {
+            let id = node
+                .get("id")
+                .and_then(Value::as_str)
+                .ok_or("node without an id")?
+                .to_string();
+            if keyed.insert(id.clone(), node).is_some() {
+                return Err(format!("duplicate node id '{}'", id));
+            }
+        }
+        fields.insert("nodes".to_string(), Value::Object(keyed));
+    }
+    fields
+        .entry("comments")
+        .or_insert_with(|| Value::Array(Vec::new()));
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn graph(version: &str) -> Value {
+        json!({ "nodes": {}, "connections": [], "metadata": { "version": version } })
+    }
+
+    #[test]
+    fn test_nodes_keyed_by_id() {
+        let old = json!({
+            "nodes": [
+                { "id": "a", "node_type": "begin_play" },
+                { "id": "b", "node_type": "print_string" }
+            ],
+            "connections": []
+        });
+        let migrated = nodes_keyed_by_id(old).unwrap();
+        assert_eq!(migrated["nodes"]["b"]["node_type"], "print_string");
+        assert_eq!(migrated["comments"], json!([]));
+
+        let duplicate = json!({ "nodes": [{ "id": "a" }, { "id": "a" }] });
+        assert!(nodes_keyed_by_id(duplicate).is_err());
+        assert!(nodes_keyed_by_id(json!({ "nodes": [{}] })).is_err());
+    }
+
+    #[test]
+    fn test_migrate_runs_steps_in_order() {
+        fn rename_connections(mut graph: Value) -> Result<Value, String> {
+            graph["links"] = graph["connections"].take();
+            Ok(graph)
+        }
+        let mut migrator = GraphMigrator::new();
+        migrator.register(MigrationStep {
+            from: "0.0.1",
+            to: "0.1.0",
+            migrate: rename_connections,
+        });
+
+        let mut old = graph("0.0.1");
+        old["nodes"] = json!([{ "id": "a" }]);
+        let migrated = migrator.migrate(old).unwrap();
+        assert_eq!(migrated["metadata"]["version"], CURRENT_GRAPH_VERSION);
+        assert!(migrated["nodes"]["a"].is_object());
+        assert_eq!(migrated["links"], json!([]));
+
+        // Graphs without a version are the oldest format
+        let unversioned = json!({ "nodes": [] });
+        let migrated = migrator.migrate(unversioned).unwrap();
+        assert_eq!(migrated["metadata"]["version"], CURRENT_GRAPH_VERSION);
+
+        let current = graph(CURRENT_GRAPH_VERSION);
+        assert_eq!(migrator.migrate(current.clone()).unwrap(), current);
+    }
+
+    #[test]
+    fn test_migrate_errors() {
+        let migrator = GraphMigrator::new();
+
+        let error = migrator.migrate(graph("2.0.0")).unwrap_err();
+        assert!(matches!(error, MigrationError::NewerVersion { .. }));
+        assert!(error.to_string().contains("newer engine"));
+        assert!(matches!(
+            migrator.migrate(graph("1.0")),
+            Err(MigrationError::InvalidVersion(_))
+        ));
+        assert!(matches!(
+            migrator.migrate(graph("0.0.1")),
+            Err(MigrationError::NoMigration { .. })
+        ));
+        assert!(matches!(
+            migrator.migrate(json!({ "nodes": [{}] })),
+            Err(MigrationError::StepFailed { .. })
+        ));
+    }
+}
@@ -3,16 +3,33 @@
 //! This module provides bytecode-based blueprint execution for development workflow,
 //! with support for hot-reload, in-editor playtesting, and visual debugging.
 
+pub mod analysis;
 pub mod byte_arena;
 pub mod bytecode_compiler;
+pub mod codegen;
+mod coercion;
+pub mod compile_cache;
 pub mod compiled_bytecode;
+pub mod diagnostics;
 pub mod dispatcher;
 pub mod executor;
 pub mod instance;
+pub mod migration;
+pub mod project_build;
+pub mod source_map;
 
+pub use analysis::{analyze_graph, GraphAnalysis};
 pub use byte_arena::ByteArena;
 pub use bytecode_compiler::BytecodeCompiler;
+pub use codegen::{CodegenOptions, CodegenTarget};
+pub use compile_cache::{BlueprintCompileCache, CacheStatus};
 pub use compiled_bytecode::{CompiledBytecode, VariableDescriptor};
+pub use diagnostics::{BlueprintDiagnostic, CompileResult, DiagnosticKind, DiagnosticSeverity};
 pub use dispatcher::{BlueprintDispatcher, BlueprintEvent, ExecutionMode};
 pub use executor::BlueprintExecutor;
 pub use instance::{BlueprintExecutionMode, BlueprintInstance};
+pub use migration::{GraphMigrator, MigrationError, CURRENT_GRAPH_VERSION};
+pub use project_build::{
+    compile_project_blueprints, BlueprintBuildSummary, ClassBuild, ProjectBuild,
+};
+pub use source_map::SourceMap;
@@ -0,0 +1,375 @@
+//! Compiles every blueprint class in a project for a build.
+//!
+//! Classes are the folder-based `.class` assets, found by their
+//! `graph_save.json` marker anywhere under the project root outside
+//! `target` and hidden folders. Each class's main graph is migrated to the
+//! current format, validated and turned into Rust by PBGC, in parallel, and
+//! written to [`OUTPUT_DIR`]`/{class_name}.rs`. Generated code is cached
+//! under [`RUST_CACHE_DIR`], so classes whose graph and compile options
+//! didn't change skip PBGC.
+
+use super::bytecode_compiler::{BytecodeCompiler, CompilerError};
+use super::codegen::CodegenOptions;
+use super::compile_cache::{BlueprintCompileCache, DEFAULT_CAPACITY};
+use super::diagnostics::{self, BlueprintDiagnostic};
+use super::migration::GraphMigrator;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use ui::graph::BlueprintAsset;
+
+/// Project-relative directory the generated Rust is written to.
+pub const OUTPUT_DIR: &str = "target/blueprints";
+
+/// Project-relative directory of the generated Rust cache.
+pub const RUST_CACHE_DIR: &str = "target/blueprint_cache/rust";
+
+/// File marking a folder as a blueprint class.
+const MARKER_FILE: &str = "graph_save.json";
+
+/// Outcome of compiling one class.
+#[derive(Debug)]
+pub struct ClassBuild {
+    /// The `.class` folder
+    pub class_path: PathBuf,
+    pub class_name: String,
+    /// The generated Rust
+    pub output: Result<String, CompilerError>,
+    /// Where the generated Rust was written, if it was
+    pub output_path: Option<PathBuf>,
+    /// Everything found wrong with the graph, including what made it fail
+    pub diagnostics: Vec<BlueprintDiagnostic>,
+    /// Whether the output came from the cache
+    pub cached: bool,
+}
+
+/// Counts over a project build, for the build UI.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlueprintBuildSummary {
+    /// Classes compiled, including those served from the cache
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Classes whose output came from the cache
+    pub cached: usize,
+}
+
+impl BlueprintBuildSummary {
+    pub fn total(&self) -> usize {
+        self.succeeded + self.failed
+    }
+}
+
+/// Outcome of compiling every class in a project, sorted by class path.
+#[derive(Debug)]
+pub struct ProjectBuild {
+    pub classes: Vec<ClassBuild>,
+    pub summary: BlueprintBuildSummary,
+}
+
+/// Compiles every blueprint class in the project with the default options,
+/// returning each class folder with its generated Rust.
+pub fn compile_project_blueprints(
+    project_root: &Path,
+) -> Vec<(PathBuf, Result<String, CompilerError>)> {
+    BytecodeCompiler::new()
+        .build_project_blueprints(project_root)
+        .classes
+        .into_iter()
+        .map(|class| (class.class_path, class.output))
+        .collect()
+}
+
+/// A class's graph, loaded and checked, waiting for codegen.
+struct Prepared {
+    class_path: PathBuf,
+    class_name: String,
+    asset: BlueprintAsset,
+    key: u64,
+    diagnostics: Vec<BlueprintDiagnostic>,
+}
+
+impl BytecodeCompiler {
+    /// Compiles every blueprint class in the project to Rust, reusing the
+    /// project's cache, and writes the results to [`OUTPUT_DIR`].
+    pub fn build_project_blueprints(&self, project_root: &Path) -> ProjectBuild {
+        let mut cache = BlueprintCompileCache::<String>::open(
+            &project_root.join(RUST_CACHE_DIR),
+            DEFAULT_CAPACITY,
+        );
+        let output_dir = project_root.join(OUTPUT_DIR);
+        let options = serde_json::json!({
+            "compiler": self.options().cache_fingerprint(),
+            "codegen": CodegenOptions::default().cache_fingerprint(),
+        });
+
+        let mut class_paths = Vec::new();
+        find_classes(project_root, &mut class_paths);
+        class_paths.sort();
+
+        // Load and validate in parallel; a class failing here is done
+        let prepared: Vec<Result<Prepared, Box<ClassBuild>>> = class_paths
+            .into_par_iter()
+            .map(|class_path| self.prepare_class(class_path, &cache, &options))
+            .collect();
+
+        // Cache lookups need the cache mutably, so they run in order
+        let mut classes = Vec::with_capacity(prepared.len());
+        let mut misses = Vec::new();
+        for class in prepared {
+            match class {
+                Err(failed) => classes.push(*failed),
+                Ok(class) => match cache.get(class.key) {
+                    Some(code) => classes.push(class.into_build(Ok(code), true)),
+                    None => misses.push(class),
+                },
+            }
+        }
+
+        let generated: Vec<(u64, ClassBuild)> = misses
+            .into_par_iter()
+            .map(|class| (class.key, self.generate_class(class)))
+            .collect();
+        for (key, class) in generated {
+            if let Ok(code) = &class.output {
+                cache.insert(key, code.clone());
+            }
+            classes.push(class);
+        }
+
+        classes.sort_by(|a, b| a.class_path.cmp(&b.class_path));
+        let mut summary = BlueprintBuildSummary::default();
+        for class in &mut classes {
+            write_output(class, &output_dir);
+            match class.output {
+                Ok(_) => summary.succeeded += 1,
+                Err(_) => summary.failed += 1,
+            }
+            if class.cached {
+                summary.cached += 1;
+            }
+        }
+
+        tracing::info!(
+            "Compiled {} blueprint classes: {} succeeded ({} cached), {} failed",
+            summary.total(),
+            summary.succeeded,
+            summary.cached,
+            summary.failed
+        );
+        ProjectBuild { classes, summary }
+    }
+
+    fn prepare_class(
+        &self,
+        class_path: PathBuf,
+        cache: &BlueprintCompileCache<String>,
+        options: &serde_json::Value,
+    ) -> Result<Prepared, Box<ClassBuild>> {
+        let class_name = class_name(&class_path);
+        let failed = |error: CompilerError, diagnostics: Vec<BlueprintDiagnostic>| {
+            Box::new(ClassBuild {
+                class_path: class_path.clone(),
+                class_name: class_name.clone(),
+                output: Err(error),
+                output_path: None,
+                diagnostics,
+                cached: false,
+            })
+        };
+
+        let asset: BlueprintAsset = match std::fs::read_to_string(class_path.join(MARKER_FILE))
+            .map_err(CompilerError::from)
+            .and_then(|json| serde_json::from_str(&json).map_err(CompilerError::from))
+            .and_then(|json| Ok(GraphMigrator::new().migrate_asset(json)?))
+        {
+            Ok(asset) => asset,
+            Err(e) => {
+                let diagnostic = BlueprintDiagnostic::error(e.to_string());
+                return Err(failed(e, vec![diagnostic]));
+            }
+        };
+
+        let diagnostics = self.validate_blueprint(&asset.main_graph);
+        if diagnostics.iter().any(BlueprintDiagnostic::is_error) {
+            let errors: Vec<String> = diagnostics
+                .iter()
+                .filter(|d| d.is_error())
+                .map(ToString::to_string)
+                .collect();
+            return Err(failed(
+                CompilerError::Invalid(errors.join("; ")),
+                diagnostics,
+            ));
+        }
+
+        let key = match serde_json::to_value(&asset.main_graph) {
+            Ok(json) => cache.key(&json, options),
+            Err(e) => return Err(failed(e.into(), diagnostics)),
+        };
+        Ok(Prepared {
+            class_path,
+            class_name,
+            asset,
+            key,
+            diagnostics,
+        })
+    }
+
+    fn generate_class(&self, class: Prepared) -> ClassBuild {
+        let output = self.generate_rust(&class.asset.main_graph);
+        let diagnostic = match &output {
+            Ok(_) => None,
+            Err(CompilerError::Compilation(message)) => {
+                let json = serde_json::to_value(&class.asset.main_graph).unwrap_or_default();
+                Some(diagnostics::from_compiler_message(message.clone(), &json))
+            }
+            Err(e) => Some(BlueprintDiagnostic::error(e.to_string())),
+        };
+        let mut build = class.into_build(output, false);
+        build.diagnostics.extend(diagnostic);
+        build
+    }
+}
+
+impl Prepared {
+    fn into_build(self, output: Result<String, CompilerError>, cached: bool) -> ClassBuild {
+        ClassBuild {
+            class_path: self.class_path,
+            class_name: self.class_name,
+            output,
+            output_path: None,
+            diagnostics: self.diagnostics,
+            cached,
+        }
+    }
+}
+
+/// Collects the class folders under `dir`, skipping `target` and hidden
+/// folders. Classes aren't searched for nested classes.
+fn find_classes(dir: &Path, classes: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || name == "target" {
+            continue;
+        }
+        if path.join(MARKER_FILE).is_file() {
+            classes.push(path);
+        } else {
+            find_classes(&path, classes);
+        }
+    }
+}
+
+/// The class name of a class folder: its name without the `.class`
+/// extension.
+fn class_name(class_path: &Path) -> String {
+    class_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Writes a successful class's Rust to `output_dir`. A failed write fails
+/// the class.
+fn write_output(class: &mut ClassBuild, output_dir: &Path) {
+    let Ok(code) = &class.output else {
+        return;
+    };
+    let path = output_dir.join(format!("{}.rs", class.class_name));
+    let written = std::fs::create_dir_all(output_dir).and_then(|_| std::fs::write(&path, code));
+    match written {
+        Ok(()) => class.output_path = Some(path),
+        Err(e) => {
+            class.diagnostics.push(BlueprintDiagnostic::error(format!(
+                "Failed to write {:?}: {}",
+                path, e
+            )));
+            class.output = Err(e.into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn class(root: &Path, path: &str, graph_save: &str) {
+        let dir = root.join(path);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(MARKER_FILE), graph_save).unwrap();
+    }
+
+    #[test]
+    fn test_find_classes() {
+        let root = tempfile::tempdir().unwrap();
+        class(root.path(), "src/classes/Player.class", "{}");
+        class(
+            root.path(),
+            "src/classes/Player.class/events/Inner.class",
+            "{}",
+        );
+        class(root.path(), "content/enemies/Enemy", "{}");
+        class(root.path(), "target/blueprints/Stale.class", "{}");
+        class(root.path(), ".git/Hidden.class", "{}");
+        std::fs::create_dir_all(root.path().join("src/empty")).unwrap();
+
+        let mut classes = Vec::new();
+        find_classes(root.path(), &mut classes);
+        classes.sort();
+        let names: Vec<String> = classes.iter().map(|path| class_name(path)).collect();
+        assert_eq!(names, ["Enemy", "Player"]);
+    }
+
+    #[test]
+    fn test_failed_classes_are_reported_per_file() {
+        let root = tempfile::tempdir().unwrap();
+        class(root.path(), "src/classes/Broken.class", "not json");
+        class(
+            root.path(),
+            "src/classes/Dangling.class",
+            r#"{
+                "format_version": 1,
+                "main_graph": {
+                    "nodes": {},
+                    "connections": [{
+                        "id": "c1",
+                        "source_node": "missing",
+                        "source_pin": "out",
+                        "target_node": "gone",
+                        "target_pin": "in",
+                        "connection_type": "Execution"
+                    }],
+                    "metadata": { "name": "EventGraph", "description": "", "version": "1.0.0", "created_at": "", "modified_at": "" }
+                }
+            }"#,
+        );
+
+        let build = BytecodeCompiler::new().build_project_blueprints(root.path());
+        assert_eq!(
+            build.summary,
+            BlueprintBuildSummary {
+                succeeded: 0,
+                failed: 2,
+                cached: 0
+            }
+        );
+        assert_eq!(build.classes[0].class_name, "Broken");
+        assert!(matches!(
+            build.classes[0].output,
+            Err(CompilerError::Json(_))
+        ));
+        assert_eq!(build.classes[0].diagnostics.len(), 1);
+        assert!(build
+            .classes
+            .iter()
+            .all(|class| class.output_path.is_none()));
+        assert!(!root.path().join(OUTPUT_DIR).exists());
+    }
+}
@@ -0,0 +1,232 @@
+//! Maps lines of the Rust generated for a blueprint back to its nodes.
+//!
+//! PBGC names everything it generates for a node after the node's ID
+//! (`let node_<id>_result = ...`), so the lines of the generated code can be
+//! traced back to nodes without help from the code generator. With a
+//! [`SourceMap`], rustc errors in the generated code can be shown on the
+//! blueprint nodes they come from: see
+//! [`SourceMap::translate_cargo_diagnostics`].
+
+use super::diagnostics::{BlueprintDiagnostic, DiagnosticSeverity};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::Path;
+
+/// Line ranges of generated code and the nodes they were generated for.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    /// Sorted, non-overlapping 1-based line ranges
+    ranges: Vec<(Range<usize>, String)>,
+}
+
+impl SourceMap {
+    /// Builds the map of `code`, generated from a graph with the given node
+    /// IDs. A line belongs to the first node it names; consecutive lines of
+    /// the same node form one range.
+    pub fn build<'a>(code: &str, node_ids: impl IntoIterator<Item = &'a str>) -> Self {
+        let identifiers: HashMap<String, &str> = node_ids
+            .into_iter()
+            .map(|id| (identifier_of(id), id))
+            .collect();
+
+        let mut ranges: Vec<(Range<usize>, String)> = Vec::new();
+        for (index, line) in code.lines().enumerate() {
+            let number = index + 1;
+            let Some(node_id) = first_node_named(line, &identifiers) else {
+                continue;
+            };
+            match ranges.last_mut() {
+                Some((range, last)) if last == node_id && range.end == number => {
+                    range.end = number + 1;
+                }
+                _ => ranges.push((number..number + 1, node_id.to_string())),
+            }
+        }
+        Self { ranges }
+    }
+
+    /// The node line `line` (1-based) was generated for, if any.
+    pub fn node_for_line(&self, line: usize) -> Option<&str> {
+        let index = self.ranges.partition_point(|(range, _)| range.end <= line);
+        self.ranges
+            .get(index)
+            .filter(|(range, _)| range.contains(&line))
+            .map(|(_, node_id)| node_id.as_str())
+    }
+
+    /// Line ranges generated for `node_id`.
+    pub fn lines_of(&self, node_id: &str) -> Vec<Range<usize>> {
+        self.ranges
+            .iter()
+            .filter(|(_, id)| id == node_id)
+            .map(|(range, _)| range.clone())
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Turns the errors and warnings in `cargo build --message-format=json`
+    /// output (or rustc's `--error-format=json`) that point into
+    /// `generated_file` into blueprint diagnostics on the nodes the lines
+    /// came from. Messages about other files are skipped.
+    pub fn translate_cargo_diagnostics(
+        &self,
+        output: &str,
+        generated_file: &Path,
+    ) -> Vec<BlueprintDiagnostic> {
+        output
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Value>(line.trim()).ok())
+            .filter_map(
+                |message| match message.get("reason").and_then(Value::as_str) {
+                    Some("compiler-message") => message.get("message").cloned(),
+                    Some(_) => None,
+                    None => Some(message),
+                },
+            )
+            .filter_map(|message| self.translate(&message, generated_file))
+            .collect()
+    }
+
+    fn translate(&self, message: &Value, generated_file: &Path) -> Option<BlueprintDiagnostic> {
+        let severity = match message.get("level").and_then(Value::as_str)? {
+            "error" | "error: internal compiler error" => DiagnosticSeverity::Error,
+            "warning" => DiagnosticSeverity::Warning,
+            _ => return None,
+        };
+        let spans = message.get("spans").and_then(Value::as_array)?;
+        let span = spans
+            .iter()
+            .filter(|span| {
+                span.get("file_name")
+                    .and_then(Value::as_str)
+                    .is_some_and(|file| same_file(Path::new(file), generated_file))
+            })
+            .max_by_key(|span| span.get("is_primary").and_then(Value::as_bool) == Some(true))?;
+
+        let line = span.get("line_start").and_then(Value::as_u64)? as usize;
+        let mut text = message
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        if let Some(label) = span.get("label").and_then(Value::as_str) {
+            text = format!("{}: {}", text, label);
+        }
+
+        let message = format!("{} (generated line {})", text, line);
+        let diagnostic = match severity {
+            DiagnosticSeverity::Error => BlueprintDiagnostic::error(message),
+            DiagnosticSeverity::Warning => BlueprintDiagnostic::warning(message),
+        };
+        Some(match self.node_for_line(line) {
+            Some(node_id) => diagnostic.at_node(node_id),
+            None => diagnostic,
+        })
+    }
+}
+
+/// The identifier a node ID takes in generated code.
+fn identifier_of(node_id: &str) -> String {
+    node_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// The first node named as `node_<id>` on `line`. Where IDs share a prefix
+/// the longest match wins, so `node_ab_result` is `ab` rather than `a`.
+fn first_node_named<'a>(line: &str, identifiers: &HashMap<String, &'a str>) -> Option<&'a str> {
+    let mut rest = line;
+    while let Some(start) = rest.find("node_") {
+        let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+        let preceded_by_ident = rest[..start].chars().next_back().is_some_and(is_ident);
+        let tail = &rest[start + "node_".len()..];
+        let ident_len = tail.find(|c: char| !is_ident(c)).unwrap_or(tail.len());
+        let ident = &tail[..ident_len];
+
+        if !preceded_by_ident {
+            // Every prefix of the identifier ending before an `_` or at its end
+            let longest = ident
+                .match_indices('_')
+                .map(|(i, _)| i)
+                .chain(std::iter::once(ident.len()))
+                .rev()
+                .find_map(|end| identifiers.get(&ident[..end]));
+            if let Some(&node_id) = longest {
+                return Some(node_id);
+            }
+        }
+        rest = &tail[ident_len..];
+    }
+    None
+}
+
+/// Whether rustc's `file_name`, which is relative to the workspace root,
+/// names the same file as `generated_file`.
+fn same_file(file: &Path, generated_file: &Path) -> bool {
+    file == generated_file || generated_file.ends_with(file) || file.ends_with(generated_file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GENERATED: &str = "\
+pub fn begin_play() {
+    let node_a_result = add(1, 2);
+    let node_ab_result = multiply(node_a_result, 3);
+    {
+        print_number(node_ab_result);
+        print_number(node_ab_result);
+    }
+    let node_3f2a_9c_result = sqrt(node_a_result);
+}";
+
+    fn map() -> SourceMap {
+        SourceMap::build(GENERATED, ["a", "ab", "3f2a-9c", "unused"])
+    }
+
+    #[test]
+    fn test_node_for_line() {
+        let map = map();
+        assert_eq!(map.node_for_line(1), None);
+        assert_eq!(map.node_for_line(2), Some("a"));
+        assert_eq!(map.node_for_line(3), Some("ab"));
+        assert_eq!(map.node_for_line(4), None);
+        assert_eq!(map.node_for_line(6), Some("ab"));
+        assert_eq!(map.node_for_line(8), Some("3f2a-9c"));
+        assert_eq!(map.node_for_line(99), None);
+        assert_eq!(map.lines_of("ab"), [3..4, 5..7]);
+        assert!(map.lines_of("unused").is_empty());
+    }
+
+    #[test]
+    fn test_translate_cargo_diagnostics() {
+        let output = [
+            r#"{"reason":"compiler-artifact","target":{"name":"pulsar_std"}}"#,
+            r#"{"reason":"compiler-message","message":{"level":"error","message":"mismatched types","spans":[{"file_name":"src/classes/Player/events/events.rs","line_start":3,"is_primary":true,"label":"expected `f64`, found `String`"}]}}"#,
+            r#"{"reason":"compiler-message","message":{"level":"warning","message":"unused variable","spans":[{"file_name":"src/main.rs","line_start":3,"is_primary":true}]}}"#,
+            r#"{"level":"warning","message":"unused block","spans":[{"file_name":"src/classes/Player/events/events.rs","line_start":4,"is_primary":true}]}"#,
+            "not json",
+        ]
+        .join("\n");
+
+        let diagnostics = map().translate_cargo_diagnostics(
+            &output,
+            Path::new("/projects/game/src/classes/Player/events/events.rs"),
+        );
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics[0].is_error());
+        assert_eq!(diagnostics[0].node_id.as_deref(), Some("ab"));
+        assert_eq!(
+            diagnostics[0].message,
+            "mismatched types: expected `f64`, found `String` (generated line 3)"
+        );
+        assert!(!diagnostics[1].is_error());
+        assert_eq!(diagnostics[1].node_id, None);
+    }
+}
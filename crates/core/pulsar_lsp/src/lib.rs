@@ -5,8 +5,10 @@
 //! - [`traits::LspBackend`] — generic backend trait for any LSP server
 //! - [`traits::DefinitionProvider`] and [`traits::HoverProvider`] — GPUI-compatible LSP traits
 //! - [`rust_analyzer::path_utils`] — path/URI helpers shared by LSP clients
+//! - [`structure`] — fold regions, sticky scroll headers and indent guides for the script editor
 
 pub mod rust_analyzer;
+pub mod structure;
 pub mod traits;
 
 pub use rust_analyzer::{AnalyzerEvent, AnalyzerStatus, RustAnalyzerManager};
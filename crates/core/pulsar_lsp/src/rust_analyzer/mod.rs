@@ -1818,6 +1818,18 @@ impl RustAnalyzerManager {
         self.send_request("textDocument/definition", params)
     }
 
+    /// Request folding ranges for a file; returns an async receiver.
+    ///
+    /// Parse the response with [`crate::structure::folding::fold_regions_from_lsp`].
+    pub fn folding_ranges_async(&self, file_path: &PathBuf) -> Result<flume::Receiver<Value>> {
+        let uri = self.path_to_uri(file_path);
+        let params = json!({
+            "textDocument": { "uri": uri }
+        });
+
+        self.send_request_async("textDocument/foldingRange", params)
+    }
+
     /// Request code actions for a range; returns an async receiver.
     pub fn request_code_actions_async(
         &self,
@@ -0,0 +1,508 @@
+//! Fold regions and per-file fold state.
+//!
+//! Regions come from the brace scanner, or from rust-analyzer's
+//! `textDocument/foldingRange` once it has answered; both use the same
+//! line-only convention (`lineFoldingOnly`): folding a region keeps
+//! `start_line` visible and hides `start_line + 1 ..= end_line`, so the line
+//! holding a leading `}` stays on screen.
+//!
+//! Folded regions are remembered by [`FoldAnchor`]s — the header line's
+//! number and text — rather than by region index, so they survive edits and
+//! re-scans the same way breakpoints do.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::scanner::{BlockKind, Scan};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FoldKind {
+    Block,
+    Comment,
+    Imports,
+    Region,
+}
+
+/// A foldable line range. Lines are 0-based.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FoldRegion {
+    /// Line that stays visible, with a chevron in the gutter.
+    pub start_line: usize,
+    /// Last hidden line.
+    pub end_line: usize,
+    pub kind: FoldKind,
+    /// Number of regions enclosing this one; "fold level N" folds depth N-1.
+    pub depth: usize,
+}
+
+impl FoldRegion {
+    /// Whether folding this region hides `line`.
+    pub fn hides(&self, line: usize) -> bool {
+        line > self.start_line && line <= self.end_line
+    }
+}
+
+/// Fold regions of a scanned file.
+///
+/// Blocks that are never closed can't be folded and are left out.
+pub fn fold_regions(scan: &Scan) -> Vec<FoldRegion> {
+    let regions = scan.blocks.iter().filter_map(|block| {
+        let close = block.close_line?;
+        let (end_line, kind) = match block.kind {
+            BlockKind::Braces if block.close_leads_line => {
+                (close.saturating_sub(1), FoldKind::Block)
+            }
+            BlockKind::Braces => (close, FoldKind::Block),
+            BlockKind::Comment => (close, FoldKind::Comment),
+        };
+        Some(FoldRegion {
+            start_line: block.open_line,
+            end_line,
+            kind,
+            depth: 0,
+        })
+    });
+    normalize(regions.collect())
+}
+
+/// Fold regions from a `textDocument/foldingRange` response.
+pub fn fold_regions_from_lsp(response: &Value) -> Vec<FoldRegion> {
+    let Some(ranges) = response.as_array() else {
+        return Vec::new();
+    };
+    let regions = ranges.iter().filter_map(|range| {
+        let start_line = range.get("startLine")?.as_u64()? as usize;
+        let end_line = range.get("endLine")?.as_u64()? as usize;
+        let kind = match range.get("kind").and_then(Value::as_str) {
+            Some("comment") => FoldKind::Comment,
+            Some("imports") => FoldKind::Imports,
+            Some("region") => FoldKind::Region,
+            _ => FoldKind::Block,
+        };
+        Some(FoldRegion {
+            start_line,
+            end_line,
+            kind,
+            depth: 0,
+        })
+    });
+    normalize(regions.collect())
+}
+
+/// Drop empty regions, keep the largest of several starting on one line
+/// (only one chevron fits) and compute nesting depths.
+fn normalize(mut regions: Vec<FoldRegion>) -> Vec<FoldRegion> {
+    regions.retain(|r| r.end_line > r.start_line);
+    regions.sort_by(|a, b| {
+        a.start_line
+            .cmp(&b.start_line)
+            .then(b.end_line.cmp(&a.end_line))
+    });
+    regions.dedup_by_key(|r| r.start_line);
+
+    let mut open_ends: Vec<usize> = Vec::new();
+    for region in &mut regions {
+        while open_ends.last().is_some_and(|end| *end < region.start_line) {
+            open_ends.pop();
+        }
+        region.depth = open_ends.len();
+        open_ends.push(region.end_line);
+    }
+    regions
+}
+
+/// Innermost region starting at or enclosing `line`, for fold/unfold at the
+/// cursor.
+pub fn region_at(regions: &[FoldRegion], line: usize) -> Option<&FoldRegion> {
+    regions
+        .iter()
+        .filter(|r| r.start_line == line || r.hides(line))
+        .max_by_key(|r| r.depth)
+}
+
+/// A folded region, remembered by its header line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FoldAnchor {
+    pub line: usize,
+    /// Trimmed text of the header line when it was folded.
+    pub header: String,
+}
+
+/// Lines replaced by an edit: `removed` lines from `start_line` became
+/// `inserted` lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineEdit {
+    pub start_line: usize,
+    pub removed: usize,
+    pub inserted: usize,
+}
+
+/// Folded regions of one file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FoldState {
+    anchors: Vec<FoldAnchor>,
+}
+
+impl FoldState {
+    pub fn anchors(&self) -> &[FoldAnchor] {
+        &self.anchors
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.anchors.is_empty()
+    }
+
+    pub fn is_folded(&self, region: &FoldRegion) -> bool {
+        self.anchors.iter().any(|a| a.line == region.start_line)
+    }
+
+    pub fn fold(&mut self, region: &FoldRegion, lines: &[&str]) {
+        if !self.is_folded(region) {
+            self.anchors.push(FoldAnchor {
+                line: region.start_line,
+                header: header_text(lines, region.start_line),
+            });
+        }
+    }
+
+    /// Returns whether the region was folded.
+    pub fn unfold(&mut self, region: &FoldRegion) -> bool {
+        let before = self.anchors.len();
+        self.anchors.retain(|a| a.line != region.start_line);
+        self.anchors.len() != before
+    }
+
+    pub fn toggle(&mut self, region: &FoldRegion, lines: &[&str]) {
+        if !self.unfold(region) {
+            self.fold(region, lines);
+        }
+    }
+
+    /// Fold every region at `depth`, or every region when `depth` is `None`.
+    pub fn fold_all(&mut self, regions: &[FoldRegion], depth: Option<usize>, lines: &[&str]) {
+        for region in regions {
+            if depth.is_none_or(|d| d == region.depth) {
+                self.fold(region, lines);
+            }
+        }
+    }
+
+    pub fn unfold_all(&mut self) {
+        self.anchors.clear();
+    }
+
+    /// Move anchors for an edit, before the file is re-scanned.
+    ///
+    /// Anchors below the edit shift with it; anchors inside it stay on the
+    /// edit's first line and are matched again by [`resolve`](Self::resolve).
+    pub fn apply_edit(&mut self, edit: LineEdit) {
+        let edit_end = edit.start_line + edit.removed;
+        for anchor in &mut self.anchors {
+            if anchor.line >= edit_end {
+                anchor.line = anchor.line + edit.inserted - edit.removed;
+            } else if anchor.line > edit.start_line {
+                anchor.line = edit.start_line;
+            }
+        }
+    }
+
+    /// Re-attach anchors to freshly computed `regions`.
+    ///
+    /// An anchor keeps a region that starts on its line with the same text;
+    /// otherwise it moves to the nearest region whose header text matches
+    /// (the file was changed without [`apply_edit`](Self::apply_edit)), then
+    /// to whatever region starts on its line (the header itself was edited).
+    /// Anchors matching nothing are dropped.
+    pub fn resolve(&mut self, regions: &[FoldRegion], lines: &[&str]) {
+        let mut resolved: Vec<FoldAnchor> = Vec::new();
+        for anchor in &self.anchors {
+            let same_line = regions.iter().find(|r| r.start_line == anchor.line);
+            let same_text = regions
+                .iter()
+                .filter(|r| header_text(lines, r.start_line) == anchor.header)
+                .min_by_key(|r| r.start_line.abs_diff(anchor.line));
+            let region = match (same_line, same_text) {
+                (Some(line), Some(text)) if line.start_line == text.start_line => Some(line),
+                (_, Some(text)) => Some(text),
+                (line, None) => line,
+            };
+            if let Some(region) = region {
+                if !resolved.iter().any(|a| a.line == region.start_line) {
+                    resolved.push(FoldAnchor {
+                        line: region.start_line,
+                        header: header_text(lines, region.start_line),
+                    });
+                }
+            }
+        }
+        self.anchors = resolved;
+    }
+
+    /// Unfold every folded region hiding `line`, e.g. before jumping to a
+    /// search match there. Returns whether anything was unfolded.
+    pub fn reveal(&mut self, line: usize, regions: &[FoldRegion]) -> bool {
+        let before = self.anchors.len();
+        self.anchors.retain(|anchor| {
+            !regions
+                .iter()
+                .any(|r| r.start_line == anchor.line && r.hides(line))
+        });
+        self.anchors.len() != before
+    }
+
+    /// The line `line` is drawn on: itself, or the header of the outermost
+    /// folded region hiding it. Gutter markers for hidden lines go here.
+    pub fn display_line(&self, line: usize, regions: &[FoldRegion]) -> usize {
+        self.folded(regions)
+            .filter(|r| r.hides(line))
+            .map(|r| r.start_line)
+            .min()
+            .unwrap_or(line)
+    }
+
+    /// Whether `line` is hidden by a folded region.
+    pub fn is_hidden(&self, line: usize, regions: &[FoldRegion]) -> bool {
+        self.display_line(line, regions) != line
+    }
+
+    fn folded<'a>(&'a self, regions: &'a [FoldRegion]) -> impl Iterator<Item = &'a FoldRegion> {
+        regions.iter().filter(|r| self.is_folded(r))
+    }
+}
+
+fn header_text(lines: &[&str], line: usize) -> String {
+    lines
+        .get(line)
+        .map_or_else(String::new, |l| l.trim().to_string())
+}
+
+/// Fold state of every file opened this session, so reopening a file
+/// restores its folds.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FoldSessions {
+    files: HashMap<PathBuf, FoldState>,
+}
+
+impl FoldSessions {
+    pub fn get(&self, path: &Path) -> Option<&FoldState> {
+        self.files.get(path)
+    }
+
+    pub fn state_mut(&mut self, path: &Path) -> &mut FoldState {
+        self.files.entry(path.to_path_buf()).or_default()
+    }
+
+    /// Remember `state` for `path`; an empty state forgets the file.
+    pub fn store(&mut self, path: &Path, state: FoldState) {
+        if state.is_empty() {
+            self.files.remove(path);
+        } else {
+            self.files.insert(path.to_path_buf(), state);
+        }
+    }
+
+    /// Follow a rename.
+    pub fn rename(&mut self, from: &Path, to: &Path) {
+        if let Some(state) = self.files.remove(from) {
+            self.files.insert(to.to_path_buf(), state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::scanner::scan;
+
+    const IMPL: &str = include_str!("fixtures/nested_impl.rs.txt");
+
+    fn regions(source: &str) -> Vec<(usize, usize, FoldKind, usize)> {
+        fold_regions(&scan(source))
+            .iter()
+            .map(|r| (r.start_line, r.end_line, r.kind, r.depth))
+            .collect()
+    }
+
+    #[test]
+    fn test_regions_for_nested_impl() {
+        assert_eq!(
+            regions(IMPL),
+            vec![
+                (0, 2, FoldKind::Comment, 0),
+                (3, 4, FoldKind::Block, 0),
+                (7, 27, FoldKind::Block, 0),
+                (8, 10, FoldKind::Comment, 1),
+                (11, 18, FoldKind::Block, 1),
+                (13, 16, FoldKind::Block, 2),
+                (14, 15, FoldKind::Block, 3),
+                (21, 26, FoldKind::Block, 1),
+                (22, 24, FoldKind::Comment, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_one_region_per_start_line() {
+        // Two blocks open on line 0; the `fn` body is folded, hiding `} }`
+        // along with it. `} else {` closes one block and opens another.
+        let source = "fn f() { match x {\n    _ => {}\n} }\nif a {\n    b\n} else {\n    c\n}\n";
+        assert_eq!(
+            regions(source),
+            vec![
+                (0, 2, FoldKind::Block, 0),
+                (3, 4, FoldKind::Block, 0),
+                (5, 6, FoldKind::Block, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unclosed_blocks_are_not_folded() {
+        let source = include_str!("fixtures/unbalanced.rs.txt");
+        let scan = scan(source);
+        assert_eq!(scan.unmatched_closes, vec![0]);
+        assert_eq!(scan.unterminated_comment, Some(10));
+        let regions = fold_regions(&scan);
+        assert_eq!(
+            regions
+                .iter()
+                .map(|r| (r.start_line, r.end_line))
+                .collect::<Vec<_>>(),
+            vec![(2, 4), (6, 8)]
+        );
+    }
+
+    #[test]
+    fn test_pathological_nesting() {
+        let depth = 10_000;
+        let source = format!("{}\n{}\n", "{\n".repeat(depth), "}\n".repeat(depth));
+        let regions = fold_regions(&scan(&source));
+        assert_eq!(regions.len(), depth);
+        assert_eq!(regions[depth - 1].depth, depth - 1);
+        assert_eq!(region_at(&regions, depth).unwrap().depth, depth - 1);
+    }
+
+    #[test]
+    fn test_lsp_ranges() {
+        let response = serde_json::json!([
+            { "startLine": 0, "endLine": 2, "kind": "imports" },
+            { "startLine": 4, "endLine": 9 },
+            { "startLine": 5, "endLine": 5 },
+            { "startLine": 6, "endLine": 8, "kind": "comment" }
+        ]);
+        let regions = fold_regions_from_lsp(&response);
+        assert_eq!(regions.len(), 3);
+        assert_eq!(regions[0].kind, FoldKind::Imports);
+        assert_eq!((regions[2].kind, regions[2].depth), (FoldKind::Comment, 1));
+        assert!(fold_regions_from_lsp(&Value::Null).is_empty());
+    }
+
+    #[test]
+    fn test_fold_state_follows_edits() {
+        let lines: Vec<&str> = IMPL.lines().collect();
+        let regions = fold_regions(&scan(IMPL));
+        let mut state = FoldState::default();
+        let method = *region_at(&regions, 13).unwrap();
+        state.fold(&method, &lines);
+        assert_eq!(state.anchors()[0].header, "for entity in &self.entities {");
+
+        // Three lines inserted above the impl
+        let mut edited = lines.clone();
+        for _ in 0..3 {
+            edited.insert(6, "");
+        }
+        state.apply_edit(LineEdit {
+            start_line: 6,
+            removed: 0,
+            inserted: 3,
+        });
+        let edited_regions = fold_regions(&scan(&edited.join("\n")));
+        state.resolve(&edited_regions, &edited);
+        assert_eq!(state.anchors()[0].line, method.start_line + 3);
+
+        // Reloaded from disk without edit tracking: matched by text
+        let mut state = FoldState::default();
+        state.fold(&method, &lines);
+        state.resolve(&edited_regions, &edited);
+        assert_eq!(state.anchors()[0].line, method.start_line + 3);
+
+        // The folded block was deleted
+        state.apply_edit(LineEdit {
+            start_line: 16,
+            removed: 5,
+            inserted: 0,
+        });
+        let mut trimmed = edited.clone();
+        trimmed.drain(16..21);
+        state.resolve(&fold_regions(&scan(&trimmed.join("\n"))), &trimmed);
+        assert!(state.is_empty());
+    }
+
+    #[test]
+    fn test_fold_state_keeps_fold_when_header_is_edited() {
+        let lines: Vec<&str> = IMPL.lines().collect();
+        let regions = fold_regions(&scan(IMPL));
+        let mut state = FoldState::default();
+        state.fold(region_at(&regions, 11).unwrap(), &lines);
+
+        let mut edited = lines.clone();
+        edited[11] = "    pub fn tick_all(&mut self, dt: f32) {";
+        state.apply_edit(LineEdit {
+            start_line: 11,
+            removed: 1,
+            inserted: 1,
+        });
+        state.resolve(&fold_regions(&scan(&edited.join("\n"))), &edited);
+        assert_eq!(state.anchors()[0].line, 11);
+        assert_eq!(state.anchors()[0].header, edited[11].trim());
+    }
+
+    #[test]
+    fn test_reveal_and_display_line() {
+        let lines: Vec<&str> = IMPL.lines().collect();
+        let regions = fold_regions(&scan(IMPL));
+        let mut state = FoldState::default();
+        state.fold_all(&regions, Some(1), &lines);
+        state.fold_all(&regions, Some(2), &lines);
+        assert_eq!(state.anchors().len(), 5);
+
+        // A diagnostic on line 15 is drawn on the outermost folded header
+        assert!(state.is_hidden(15, &regions));
+        assert_eq!(state.display_line(15, &regions), 11);
+        assert_eq!(state.display_line(12, &regions), 11);
+        assert_eq!(state.display_line(11, &regions), 11);
+
+        // Navigating to a search match on line 15 unfolds what hides it
+        assert!(state.reveal(15, &regions));
+        assert!(!state.is_hidden(15, &regions));
+        assert!(state.is_hidden(22, &regions));
+        assert!(!state.reveal(15, &regions));
+    }
+
+    #[test]
+    fn test_sessions_remember_per_file() {
+        let regions = fold_regions(&scan(IMPL));
+        let lines: Vec<&str> = IMPL.lines().collect();
+        let mut sessions = FoldSessions::default();
+        sessions
+            .state_mut(Path::new("src/world.rs"))
+            .fold(&regions[2], &lines);
+        sessions.rename(Path::new("src/world.rs"), Path::new("src/ecs.rs"));
+        assert!(sessions.get(Path::new("src/world.rs")).is_none());
+        assert_eq!(
+            sessions
+                .get(Path::new("src/ecs.rs"))
+                .unwrap()
+                .anchors()
+                .len(),
+            1
+        );
+
+        sessions.store(Path::new("src/ecs.rs"), FoldState::default());
+        assert!(sessions.get(Path::new("src/ecs.rs")).is_none());
+    }
+}
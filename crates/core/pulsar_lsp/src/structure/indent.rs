@@ -0,0 +1,128 @@
+//! Indentation guides.
+//!
+//! A line indented `n` levels draws guides `0..n`, guide `k` sitting at
+//! column `k * tab_size`. Blank lines take the smaller level of their
+//! non-blank neighbours so guides run through gaps inside a block without
+//! dangling past its end.
+
+/// Indent level of every line.
+pub fn indent_levels(lines: &[&str], tab_size: usize) -> Vec<usize> {
+    let tab_size = tab_size.max(1);
+    let raw: Vec<Option<usize>> = lines
+        .iter()
+        .map(|line| {
+            if line.trim().is_empty() {
+                return None;
+            }
+            let mut width = 0;
+            for c in line.chars() {
+                match c {
+                    ' ' => width += 1,
+                    '\t' => width += tab_size - width % tab_size,
+                    _ => break,
+                }
+            }
+            Some(width / tab_size)
+        })
+        .collect();
+
+    let mut levels = vec![0; lines.len()];
+    let mut previous = 0;
+    for (i, level) in raw.iter().enumerate() {
+        levels[i] = match level {
+            Some(level) => {
+                previous = *level;
+                *level
+            }
+            None => {
+                let next = raw[i..].iter().flatten().next().copied().unwrap_or(0);
+                previous.min(next)
+            }
+        };
+    }
+    levels
+}
+
+/// The guide to highlight for the cursor, and the lines it spans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActiveGuide {
+    pub guide: usize,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// Guide of the indented block containing `cursor_line`.
+///
+/// On a line that opens a block (the next line is indented deeper) that
+/// block's guide is active, so the cursor on `fn f() {` highlights the body.
+pub fn active_guide(levels: &[usize], cursor_line: usize) -> Option<ActiveGuide> {
+    let here = *levels.get(cursor_line)?;
+    let below = levels.get(cursor_line + 1).copied().unwrap_or(0);
+    let level = here.max(below);
+    let guide = level.checked_sub(1)?;
+
+    let inside = |line: &usize| levels[*line] > guide;
+    let start_line = (0..=cursor_line)
+        .rev()
+        .take_while(inside)
+        .last()
+        .unwrap_or(cursor_line + 1);
+    let end_line = (start_line..levels.len())
+        .take_while(inside)
+        .last()
+        .unwrap_or(start_line);
+    Some(ActiveGuide {
+        guide,
+        start_line,
+        end_line,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IMPL: &str = include_str!("fixtures/nested_impl.rs.txt");
+
+    #[test]
+    fn test_levels_with_tabs_and_blank_lines() {
+        let lines = ["fn f() {", "\tif x {", "  \t\ty();", "", "\t}", "", "}"];
+        assert_eq!(indent_levels(&lines, 4), vec![0, 1, 2, 1, 1, 0, 0]);
+    }
+
+    #[test]
+    fn test_active_guide() {
+        let lines: Vec<&str> = IMPL.lines().collect();
+        let levels = indent_levels(&lines, 4);
+
+        // Inside the `if` body
+        assert_eq!(
+            active_guide(&levels, 15),
+            Some(ActiveGuide {
+                guide: 3,
+                start_line: 15,
+                end_line: 15
+            })
+        );
+        // On the `for` header: its body's guide
+        assert_eq!(
+            active_guide(&levels, 13),
+            Some(ActiveGuide {
+                guide: 2,
+                start_line: 14,
+                end_line: 16
+            })
+        );
+        // A blank line between methods belongs to the impl
+        assert_eq!(
+            active_guide(&levels, 20),
+            Some(ActiveGuide {
+                guide: 0,
+                start_line: 8,
+                end_line: 27
+            })
+        );
+        assert_eq!(active_guide(&levels, 28), None);
+        assert_eq!(active_guide(&levels, 100), None);
+    }
+}
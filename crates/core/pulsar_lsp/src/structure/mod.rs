@@ -0,0 +1,18 @@
+//! Source structure for the script editor's navigation aids.
+//!
+//! - [`scanner`] — brace/comment scanner that tolerates unbalanced input
+//! - [`folding`] — fold regions (scanned, or from rust-analyzer) and
+//!   anchor-based fold state per file
+//! - [`sticky`] — enclosing declarations for sticky scroll headers
+//! - [`indent`] — indentation guide levels and the active guide
+//!
+//! Everything here is plain computation over text, independent of GPUI.
+
+pub mod folding;
+pub mod indent;
+pub mod scanner;
+pub mod sticky;
+
+pub use folding::{FoldKind, FoldRegion, FoldSessions, FoldState, LineEdit};
+pub use scanner::{scan, Scan};
+pub use sticky::{sticky_headers, DeclKind, Declaration};
@@ -0,0 +1,462 @@
+//! Lightweight brace/comment scanner for Rust source.
+//!
+//! Not a parser: it skips strings, char literals and comments, matches `{}`
+//! pairs with a stack and remembers where the statement owning each brace
+//! began. That is enough to fold blocks and name the declaration a line sits
+//! in while the file is being edited and may not parse at all. Unmatched
+//! braces are reported instead of aborting the scan.
+
+/// What a [`Block`] spans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockKind {
+    /// A `{ … }` pair.
+    Braces,
+    /// A `/* … */` comment or a run of `//` comment lines.
+    Comment,
+}
+
+/// One block found by [`scan`]. Lines are 0-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    pub kind: BlockKind,
+    /// Byte offset of the first token of the statement owning the block,
+    /// after any attributes (`pub fn`, `impl`, `match`, …).
+    pub header_offset: usize,
+    pub header_line: usize,
+    /// Byte offset and line of the opening `{` (or comment start).
+    pub open_offset: usize,
+    pub open_line: usize,
+    /// Line of the closing `}`; `None` if the block is never closed.
+    pub close_line: Option<usize>,
+    /// Whether the closing `}` is the first token on its line.
+    pub close_leads_line: bool,
+    /// Number of brace blocks enclosing this one.
+    pub depth: usize,
+}
+
+/// Result of [`scan`].
+#[derive(Debug, Clone, Default)]
+pub struct Scan {
+    /// Brace and comment blocks, ordered by where they open.
+    pub blocks: Vec<Block>,
+    /// Lines of `}` with no matching `{`.
+    pub unmatched_closes: Vec<usize>,
+    /// Line of a `/* … */` comment that runs to the end of the file.
+    pub unterminated_comment: Option<usize>,
+    pub line_count: usize,
+}
+
+impl Scan {
+    /// Whether every brace and comment was closed.
+    pub fn is_balanced(&self) -> bool {
+        self.unmatched_closes.is_empty()
+            && self.unterminated_comment.is_none()
+            && self.blocks.iter().all(|b| b.close_line.is_some())
+    }
+
+    /// Last line of `block`; an unclosed block runs to the end of the file.
+    pub fn end_line(&self, block: &Block) -> usize {
+        block
+            .close_line
+            .unwrap_or(self.line_count.saturating_sub(1))
+    }
+}
+
+struct Open {
+    header: (usize, usize),
+    offset: usize,
+    line: usize,
+    /// Paren/bracket nesting of the enclosing block, restored on close.
+    outer_nesting: usize,
+}
+
+/// Scan `source` for brace blocks and multi-line comments.
+pub fn scan(source: &str) -> Scan {
+    let bytes = source.as_bytes();
+    let mut scan = Scan {
+        line_count: source.lines().count().max(1),
+        ..Scan::default()
+    };
+
+    let mut stack: Vec<Open> = Vec::new();
+    // (offset, line) of the first token of the current statement
+    let mut statement: Option<(usize, usize)> = None;
+    // Paren and bracket depth inside the current brace block
+    let mut nesting = 0usize;
+    // Bracket depth inside `#[…]`, which never starts a statement
+    let mut attribute: Option<usize> = None;
+    let mut line = 0usize;
+    let mut line_has_code = false;
+    // (offset, line, depth) of each line holding only a `//` comment
+    let mut comment_lines: Vec<(usize, usize, usize)> = Vec::new();
+    let mut i = 0usize;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+        let next = bytes.get(i + 1).copied();
+        match b {
+            b'\n' => {
+                line += 1;
+                line_has_code = false;
+                i += 1;
+                continue;
+            }
+            b' ' | b'\t' | b'\r' => {
+                i += 1;
+                continue;
+            }
+            b'/' if next == Some(b'/') => {
+                if !line_has_code {
+                    comment_lines.push((i, line, stack.len()));
+                }
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            b'/' if next == Some(b'*') => {
+                let (start_offset, start_line) = (i, line);
+                let mut depth = 0usize;
+                let mut closed = false;
+                while i < bytes.len() {
+                    if bytes[i] == b'/' && bytes.get(i + 1) == Some(&b'*') {
+                        depth += 1;
+                        i += 2;
+                    } else if bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/') {
+                        depth -= 1;
+                        i += 2;
+                        if depth == 0 {
+                            closed = true;
+                            break;
+                        }
+                    } else {
+                        if bytes[i] == b'\n' {
+                            line += 1;
+                        }
+                        i += 1;
+                    }
+                }
+                if !closed {
+                    scan.unterminated_comment = Some(start_line);
+                }
+                if line > start_line || !closed {
+                    scan.blocks.push(Block {
+                        kind: BlockKind::Comment,
+                        header_offset: start_offset,
+                        header_line: start_line,
+                        open_offset: start_offset,
+                        open_line: start_line,
+                        close_line: closed.then_some(line),
+                        close_leads_line: false,
+                        depth: stack.len(),
+                    });
+                }
+                // Code may follow on the comment's last line
+                line_has_code = line == start_line && line_has_code;
+                continue;
+            }
+            _ => {}
+        }
+
+        line_has_code = true;
+        let token = (i, line);
+        if attribute.is_none() && statement.is_none() && !matches!(b, b';' | b',' | b'{' | b'}') {
+            statement = Some(token);
+        }
+        match b {
+            b'"' => {
+                i = skip_string(bytes, i + 1, &mut line);
+            }
+            b'r' | b'b' if raw_string_start(bytes, i).is_some() => {
+                let (hashes, quote) = raw_string_start(bytes, i).unwrap_or((0, i));
+                i = skip_raw_string(bytes, quote + 1, hashes, &mut line);
+            }
+            b'\'' => {
+                i = skip_char_or_lifetime(source, i);
+            }
+            b'#' if next == Some(b'[')
+                || (next == Some(b'!') && bytes.get(i + 2) == Some(&b'[')) =>
+            {
+                if attribute.is_none() {
+                    attribute = Some(0);
+                    // An attribute never starts the statement it decorates
+                    if statement == Some(token) {
+                        statement = None;
+                    }
+                }
+                i += 1;
+            }
+            b'[' if attribute.is_some() => {
+                attribute = attribute.map(|d| d + 1);
+                i += 1;
+            }
+            b']' if attribute.is_some() => {
+                attribute = attribute.and_then(|d| d.checked_sub(1)).filter(|d| *d > 0);
+                i += 1;
+            }
+            b'{' => {
+                let header = statement.unwrap_or((i, line));
+                stack.push(Open {
+                    header,
+                    offset: i,
+                    line,
+                    outer_nesting: nesting,
+                });
+                statement = None;
+                nesting = 0;
+                attribute = None;
+                i += 1;
+            }
+            b'}' => {
+                let leads = !line_has_code_before(bytes, i);
+                match stack.pop() {
+                    Some(open) => {
+                        nesting = open.outer_nesting;
+                        scan.blocks.push(Block {
+                            kind: BlockKind::Braces,
+                            header_offset: open.header.0,
+                            header_line: open.header.1,
+                            open_offset: open.offset,
+                            open_line: open.line,
+                            close_line: Some(line),
+                            close_leads_line: leads,
+                            depth: stack.len(),
+                        });
+                    }
+                    None => scan.unmatched_closes.push(line),
+                }
+                statement = None;
+                attribute = None;
+                i += 1;
+            }
+            _ => {
+                if attribute.is_none() {
+                    match b {
+                        b';' => statement = None,
+                        b',' if nesting == 0 => statement = None,
+                        b'(' | b'[' => nesting += 1,
+                        b')' | b']' => nesting = nesting.saturating_sub(1),
+                        _ => {}
+                    }
+                }
+                i += 1;
+            }
+        }
+    }
+
+    for open in stack.into_iter().rev() {
+        scan.blocks.push(Block {
+            kind: BlockKind::Braces,
+            header_offset: open.header.0,
+            header_line: open.header.1,
+            open_offset: open.offset,
+            open_line: open.line,
+            close_line: None,
+            close_leads_line: false,
+            depth: 0,
+        });
+    }
+
+    push_comment_runs(&mut scan, &comment_lines);
+    scan.blocks
+        .sort_by_key(|b| (b.open_offset, b.kind == BlockKind::Comment));
+    fix_unclosed_depths(&mut scan.blocks);
+    scan
+}
+
+/// Group consecutive comment-only lines into comment blocks.
+fn push_comment_runs(scan: &mut Scan, lines: &[(usize, usize, usize)]) {
+    let mut start = 0;
+    for end in 0..lines.len() {
+        let run_ends = lines.get(end + 1).map(|l| l.1) != Some(lines[end].1 + 1);
+        if run_ends {
+            if end > start {
+                let (offset, line, depth) = lines[start];
+                scan.blocks.push(Block {
+                    kind: BlockKind::Comment,
+                    header_offset: offset,
+                    header_line: line,
+                    open_offset: offset,
+                    open_line: line,
+                    close_line: Some(lines[end].1),
+                    close_leads_line: false,
+                    depth,
+                });
+            }
+            start = end + 1;
+        }
+    }
+}
+
+/// Unclosed blocks are popped outermost-last; derive their depth from the
+/// unclosed blocks that open before them.
+fn fix_unclosed_depths(blocks: &mut [Block]) {
+    let mut unclosed = 0;
+    for block in blocks.iter_mut() {
+        if block.kind == BlockKind::Braces && block.close_line.is_none() {
+            block.depth = unclosed;
+            unclosed += 1;
+        }
+    }
+}
+
+fn line_has_code_before(bytes: &[u8], i: usize) -> bool {
+    bytes[..i]
+        .iter()
+        .rev()
+        .take_while(|b| **b != b'\n')
+        .any(|b| !b.is_ascii_whitespace())
+}
+
+fn is_ident_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// Skip a string body starting after its opening quote; returns the offset
+/// after the closing quote.
+fn skip_string(bytes: &[u8], mut i: usize, line: &mut usize) -> usize {
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            b'\n' => {
+                *line += 1;
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// `(hashes, offset of the opening quote)` if `r"`, `r#"`, `br"`… starts at `i`.
+fn raw_string_start(bytes: &[u8], i: usize) -> Option<(usize, usize)> {
+    if i > 0 && is_ident_byte(bytes[i - 1]) {
+        return None;
+    }
+    let mut j = i;
+    if bytes[j] == b'b' {
+        j += 1;
+    }
+    if bytes.get(j) != Some(&b'r') {
+        return None;
+    }
+    j += 1;
+    let hashes = bytes[j..].iter().take_while(|b| **b == b'#').count();
+    (bytes.get(j + hashes) == Some(&b'"')).then_some((hashes, j + hashes))
+}
+
+fn skip_raw_string(bytes: &[u8], mut i: usize, hashes: usize, line: &mut usize) -> usize {
+    while i < bytes.len() {
+        if bytes[i] == b'"'
+            && bytes[i + 1..]
+                .iter()
+                .take(hashes)
+                .filter(|b| **b == b'#')
+                .count()
+                == hashes
+        {
+            return i + 1 + hashes;
+        }
+        if bytes[i] == b'\n' {
+            *line += 1;
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// Skip a char literal (`'x'`, `'\n'`, `'é'`) or just the quote of a lifetime.
+fn skip_char_or_lifetime(source: &str, i: usize) -> usize {
+    let bytes = source.as_bytes();
+    if bytes.get(i + 1) == Some(&b'\\') {
+        let mut j = i + 2;
+        while j < bytes.len() && bytes[j] != b'\'' && bytes[j] != b'\n' {
+            j += 1;
+        }
+        return if bytes.get(j) == Some(&b'\'') {
+            j + 1
+        } else {
+            i + 1
+        };
+    }
+    if let Some(c) = source.get(i + 1..).and_then(|rest| rest.chars().next()) {
+        let after = i + 1 + c.len_utf8();
+        if c != '\'' && bytes.get(after) == Some(&b'\'') {
+            return after + 1;
+        }
+    }
+    i + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn braces(scan: &Scan) -> Vec<(usize, usize, Option<usize>)> {
+        scan.blocks
+            .iter()
+            .filter(|b| b.kind == BlockKind::Braces)
+            .map(|b| (b.header_line, b.open_line, b.close_line))
+            .collect()
+    }
+
+    #[test]
+    fn test_header_skips_attributes_and_spans_signature() {
+        let source = "#[derive(Debug)]\npub struct A {\n    x: u32,\n}\n\nfn f(\n    a: u32,\n) -> u32 {\n    a\n}\n";
+        let scan = scan(source);
+        assert!(scan.is_balanced());
+        assert_eq!(braces(&scan), vec![(1, 1, Some(3)), (5, 7, Some(9))]);
+        assert_eq!(&source[scan.blocks[0].header_offset..][..10], "pub struct");
+        assert!(scan.blocks[0].close_leads_line);
+    }
+
+    #[test]
+    fn test_braces_in_strings_chars_and_comments_are_ignored() {
+        let source = concat!(
+            "fn f<'a>(s: &'a str) {\n",
+            "    let _ = \"{ \\\" {\";\n",
+            "    let _ = r#\"}\"# ;\n",
+            "    let _ = '{';\n",
+            "    // }\n",
+            "    /* { /* } */ */\n",
+            "}\n",
+        );
+        let scan = scan(source);
+        assert!(scan.is_balanced());
+        assert_eq!(braces(&scan), vec![(0, 0, Some(6))]);
+    }
+
+    #[test]
+    fn test_unbalanced_braces_are_reported() {
+        let scan = scan("}\nfn f() {\n    if x {\n}\n");
+        assert_eq!(scan.unmatched_closes, vec![0]);
+        assert!(!scan.is_balanced());
+        // The `}` on line 3 closes the innermost block; `fn f` stays open
+        assert_eq!(braces(&scan), vec![(1, 1, None), (2, 2, Some(3))]);
+        assert_eq!(scan.blocks[0].depth, 0);
+        assert_eq!(scan.blocks[1].depth, 1);
+        assert_eq!(scan.end_line(&scan.blocks[0]), 3);
+    }
+
+    #[test]
+    fn test_unterminated_comment_runs_to_end() {
+        let scan = scan("fn f() {}\n/* never\nclosed {\n");
+        assert_eq!(scan.unterminated_comment, Some(1));
+        let comment = &scan.blocks[1];
+        assert_eq!(comment.kind, BlockKind::Comment);
+        assert_eq!(comment.close_line, None);
+    }
+
+    #[test]
+    fn test_comment_runs_need_two_lines() {
+        let scan = scan("// one\nfn f() {} // trailing\n/// a\n/// b\nstruct S;\n");
+        let comments: Vec<_> = scan
+            .blocks
+            .iter()
+            .filter(|b| b.kind == BlockKind::Comment)
+            .map(|b| (b.open_line, b.close_line))
+            .collect();
+        assert_eq!(comments, vec![(2, Some(3))]);
+    }
+}
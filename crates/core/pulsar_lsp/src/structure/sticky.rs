@@ -0,0 +1,215 @@
+//! Declarations enclosing a line, for sticky scroll headers.
+//!
+//! While the viewport's first line is inside a function, impl or module
+//! whose header has scrolled away, that header is pinned above the text.
+//! Control flow blocks (`if`, `match`, closures) are not declarations and
+//! never stick.
+
+use super::scanner::{BlockKind, Scan};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeclKind {
+    Fn,
+    Impl,
+    Trait,
+    Mod,
+    Struct,
+    Enum,
+    Union,
+    Macro,
+}
+
+/// A declaration with a braced body. Lines are 0-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Declaration {
+    pub kind: DeclKind,
+    /// First line of the declaration; clicking a sticky header jumps here.
+    pub header_line: usize,
+    /// Line of the body's closing `}`, or the last line if it never closes.
+    pub end_line: usize,
+    /// Number of declarations enclosing this one.
+    pub depth: usize,
+}
+
+/// Declarations in a scanned file, outermost first at each position.
+pub fn declarations(scan: &Scan, source: &str) -> Vec<Declaration> {
+    let mut declarations: Vec<Declaration> = Vec::new();
+    let mut open_ends: Vec<usize> = Vec::new();
+    for block in &scan.blocks {
+        if block.kind != BlockKind::Braces {
+            continue;
+        }
+        let Some(kind) = source
+            .get(block.header_offset..block.open_offset)
+            .and_then(declaration_kind)
+        else {
+            continue;
+        };
+        while open_ends.last().is_some_and(|end| *end < block.open_line) {
+            open_ends.pop();
+        }
+        let end_line = scan.end_line(block);
+        declarations.push(Declaration {
+            kind,
+            header_line: block.header_line,
+            end_line,
+            depth: open_ends.len(),
+        });
+        open_ends.push(end_line);
+    }
+    declarations
+}
+
+/// Kind of the declaration whose header (up to its `{`) is `header`.
+fn declaration_kind(header: &str) -> Option<DeclKind> {
+    let mut words = header
+        .split(|c: char| c.is_whitespace() || c == '<' || c == '(')
+        .filter(|w| !w.is_empty());
+    loop {
+        let word = words.next()?;
+        let kind = match word {
+            "fn" => DeclKind::Fn,
+            "impl" => DeclKind::Impl,
+            "trait" => DeclKind::Trait,
+            "mod" => DeclKind::Mod,
+            "struct" => DeclKind::Struct,
+            "enum" => DeclKind::Enum,
+            "union" => DeclKind::Union,
+            "macro_rules!" => DeclKind::Macro,
+            // Qualifiers that may precede the keyword
+            "pub" | "in" | "async" | "const" | "unsafe" | "default" | "extern" | "auto" => continue,
+            // `pub(crate)`, `pub(in path)`, `extern "C"`
+            w if w.ends_with(')') || w.starts_with('"') => continue,
+            _ => return None,
+        };
+        return Some(kind);
+    }
+}
+
+/// Declarations enclosing `line` whose header is above it, outermost first.
+pub fn enclosing(declarations: &[Declaration], line: usize) -> Vec<&Declaration> {
+    declarations
+        .iter()
+        .filter(|d| d.header_line < line && line <= d.end_line)
+        .collect()
+}
+
+/// Headers to pin above a viewport whose first line is `top_line`: the
+/// innermost `max` enclosing declarations, outermost first.
+///
+/// Pinned headers cover the lines below them, so a declaration only sticks
+/// while its body reaches past them. Bodies nest, so the innermost
+/// declaration always ends first.
+pub fn sticky_headers(
+    declarations: &[Declaration],
+    top_line: usize,
+    max: usize,
+) -> Vec<&Declaration> {
+    let mut headers = enclosing(declarations, top_line);
+    while headers
+        .last()
+        .is_some_and(|d| d.end_line < top_line + headers.len().min(max))
+    {
+        headers.pop();
+    }
+    if headers.len() > max {
+        headers.drain(..headers.len() - max);
+    }
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::scanner::scan;
+
+    const IMPL: &str = include_str!("fixtures/nested_impl.rs.txt");
+    const GENERATED: &str = include_str!("fixtures/generated_module.rs.txt");
+
+    fn declarations_of(source: &str) -> Vec<Declaration> {
+        declarations(&scan(source), source)
+    }
+
+    fn headers(source: &str, top_line: usize, max: usize) -> Vec<usize> {
+        let declarations = declarations_of(source);
+        sticky_headers(&declarations, top_line, max)
+            .iter()
+            .map(|d| d.header_line)
+            .collect()
+    }
+
+    #[test]
+    fn test_declarations_skip_control_flow() {
+        let kinds: Vec<_> = declarations_of(IMPL)
+            .iter()
+            .map(|d| (d.kind, d.header_line, d.depth))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (DeclKind::Impl, 7, 0),
+                (DeclKind::Fn, 11, 1),
+                (DeclKind::Fn, 21, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_declaration_kinds() {
+        assert_eq!(
+            declaration_kind("pub(crate) async fn f()"),
+            Some(DeclKind::Fn)
+        );
+        assert_eq!(
+            declaration_kind("pub(in crate::a) struct S"),
+            Some(DeclKind::Struct)
+        );
+        assert_eq!(
+            declaration_kind("unsafe impl<T> Send for S<T>"),
+            Some(DeclKind::Impl)
+        );
+        assert_eq!(declaration_kind("extern \"C\" fn f()"), Some(DeclKind::Fn));
+        assert_eq!(declaration_kind("macro_rules! m"), Some(DeclKind::Macro));
+        assert_eq!(declaration_kind("if x"), None);
+        assert_eq!(declaration_kind("let s = S"), None);
+        assert_eq!(declaration_kind("const X: S = S"), None);
+    }
+
+    #[test]
+    fn test_sticky_headers_in_generated_module() {
+        let declarations = declarations_of(GENERATED);
+        assert_eq!(declarations.len(), 4);
+        // Signature spans three lines; the header is where it starts
+        assert_eq!(declarations[2].header_line, 5);
+
+        assert!(headers(GENERATED, 0, 3).is_empty());
+        assert_eq!(headers(GENERATED, 1, 3), vec![0]);
+        assert_eq!(headers(GENERATED, 10, 3), vec![0, 3, 5]);
+        assert_eq!(headers(GENERATED, 10, 2), vec![3, 5]);
+        // `on_tick` ends under the pinned headers
+        assert_eq!(headers(GENERATED, 14, 3), vec![0, 3]);
+    }
+
+    #[test]
+    fn test_unclosed_declaration_sticks_to_end() {
+        let source = include_str!("fixtures/unbalanced.rs.txt");
+        let declarations = declarations_of(source);
+        assert_eq!(declarations.len(), 1);
+        assert_eq!(declarations[0].end_line, 11);
+        assert_eq!(headers(source, 8, 3), vec![1]);
+    }
+
+    #[test]
+    fn test_pathological_nesting() {
+        let depth = 5_000;
+        let source = format!("{}{}", "mod m {\n".repeat(depth), "}\n".repeat(depth));
+        let declarations = declarations_of(&source);
+        assert_eq!(declarations.len(), depth);
+        assert_eq!(declarations[depth - 1].depth, depth - 1);
+        // The innermost modules end within five lines and would be covered
+        assert_eq!(
+            headers(&source, depth, 5),
+            (depth - 10..depth - 5).collect::<Vec<_>>()
+        );
+    }
+}
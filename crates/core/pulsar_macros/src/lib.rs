@@ -114,7 +114,10 @@ pub fn generate_icon_enum(input: TokenStream) -> TokenStream {
 ///
 /// - `type`: Node type - `NodeTypes::pure`, `NodeTypes::fn_`, `NodeTypes::control_flow`, or `NodeTypes::event`
 /// - `color`: Optional hex color for the node in the UI (e.g., `"#ff0000"`)
-/// - `category`: Optional category for grouping nodes (e.g., `"Math"`)
+/// - `category`: Optional category for grouping nodes (e.g., `"Math"`); `/` nests
+///   categories (e.g., `"String/Conversion"`)
+/// - `deprecated`: Optional note marking the node as deprecated, saying what to use
+///   instead (e.g., `"use lerp instead"`)
 ///
 /// # Examples
 ///
@@ -193,6 +196,14 @@ pub fn blueprint(args: TokenStream, input: TokenStream) -> TokenStream {
         quote! { None }
     };
 
+    // Extract deprecation note
+    let deprecated = extract_string_value(&args_str, "deprecated");
+    let deprecated_opt = if let Some(note) = deprecated {
+        quote! { Some(#note) }
+    } else {
+        quote! { None }
+    };
+
     // Extract output data pins
     let outputs_str = extract_string_value(&args_str, "outputs");
     let output_params: Vec<proc_macro2::TokenStream> = if let Some(out_str) = outputs_str {
@@ -521,6 +532,7 @@ pub fn blueprint(args: TokenStream, input: TokenStream) -> TokenStream {
             documentation: #docs_array,
             category: #category_str,
             color: #color_opt,
+            deprecated: #deprecated_opt,
             imports: #imports_array,
             conversion: #conversion_expr,
         };
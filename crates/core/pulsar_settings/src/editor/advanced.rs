@@ -36,6 +36,24 @@ pub fn register(cfg: &'static ConfigManager) {
                 .label("Max Log Files").page("Advanced")
                 .field_type(FieldType::NumberInput { min: Some(1.0), max: Some(50.0), step: Some(1.0) })
                 .validator(Validator::int_range(1, 50)))
+        .setting("log_viewer_max_lines",
+            SchemaEntry::new("Maximum number of lines kept in the logs panel before the oldest are dropped", 100_000.0_f64)
+                .label("Log Viewer Max Lines").page("Advanced")
+                .field_type(FieldType::NumberInput { min: Some(1000.0), max: Some(1_000_000.0), step: Some(1000.0) })
+                .validator(Validator::float_range(1000.0, 1_000_000.0)))
+        .setting("log_follow_paths",
+            SchemaEntry::new("External log files tailed into the logs panel, separated by ';'", "")
+                .label("Follow Log Files").page("Advanced")
+                .field_type(FieldType::TextInput { placeholder: Some("logs/server.log;logs/asset_worker.log".into()), multiline: false }))
+        .setting("metrics_history_secs",
+            SchemaEntry::new("Seconds of CPU, memory and GPU history kept for the resource monitor charts", 1800.0_f64)
+                .label("Metrics History (s)").page("Advanced")
+                .field_type(FieldType::NumberInput { min: Some(60.0), max: Some(7200.0), step: Some(60.0) })
+                .validator(Validator::float_range(60.0, 7200.0)))
+        .setting("mission_control_alerts",
+            SchemaEntry::new("Mission Control alert rules as metric>threshold@seconds entries separated by ';'", "memory_mb>8192@5;frame_time_ms>33@3")
+                .label("Mission Control Alerts").page("Advanced")
+                .field_type(FieldType::TextInput { placeholder: Some("memory_mb>8192@5;frame_time_ms>33@3".into()), multiline: false }))
         .setting("experimental_features",
             SchemaEntry::new("Enable experimental in-development features (may be unstable)", false)
                 .label("Experimental Features").page("Advanced")
@@ -5,6 +5,10 @@ use pulsar_config::{
 pub const NS: &str = "editor";
 pub const OWNER: &str = "appearance";
 
+/// Smallest and largest `ui_scale`, the range window zoom is clamped to.
+pub const MIN_UI_SCALE: f64 = 11.0 / 16.0;
+pub const MAX_UI_SCALE: f64 = 2.0;
+
 pub fn register(cfg: &'static ConfigManager) {
     let schema = NamespaceSchema::new("Appearance", "Visual appearance and theme settings")
         // ── Theme ──────────────────────────────────────────────────────────
@@ -31,15 +35,31 @@ pub fn register(cfg: &'static ConfigManager) {
         )
         .setting(
             "ui_scale",
-            SchemaEntry::new("Scale factor for all UI elements", 1.0_f64)
-                .label("UI Scale")
-                .page("Appearance")
-                .field_type(FieldType::Slider {
-                    min: 0.5,
-                    max: 3.0,
-                    step: 0.05,
-                })
-                .validator(Validator::float_range(0.5, 3.0)),
+            SchemaEntry::new(
+                "Default scale factor for all UI elements (Ctrl+= / Ctrl+- / Ctrl+0 zoom individual windows)",
+                1.0_f64,
+            )
+            .label("UI Scale")
+            .page("Appearance")
+            .field_type(FieldType::Slider {
+                min: MIN_UI_SCALE,
+                max: MAX_UI_SCALE,
+                step: 0.05,
+            })
+            .validator(Validator::float_range(MIN_UI_SCALE, MAX_UI_SCALE)),
+        )
+        .setting(
+            "window_ui_scales",
+            SchemaEntry::new(
+                "Per-window-type zoom overrides, written when a window is zoomed (Type=scale;...)",
+                "",
+            )
+            .label("Window Zoom Overrides")
+            .page("Appearance")
+            .field_type(FieldType::TextInput {
+                placeholder: Some("ProjectEditor=1.25".into()),
+                multiline: false,
+            }),
         )
         .setting(
             "accent_color",
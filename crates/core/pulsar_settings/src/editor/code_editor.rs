@@ -193,6 +193,28 @@ pub fn register(cfg: &'static ConfigManager) {
                 .page("Code Editor")
                 .field_type(FieldType::Checkbox),
         )
+        .setting(
+            "sticky_scroll",
+            SchemaEntry::new(
+                "Pin the headers of enclosing functions, impls and modules at the top while scrolling",
+                true,
+            )
+            .label("Sticky Scroll")
+            .page("Code Editor")
+            .field_type(FieldType::Checkbox),
+        )
+        .setting(
+            "sticky_scroll_max_lines",
+            SchemaEntry::new("Maximum number of pinned sticky scroll headers", 5_i64)
+                .label("Sticky Scroll Lines")
+                .page("Code Editor")
+                .field_type(FieldType::NumberInput {
+                    min: Some(1.0),
+                    max: Some(10.0),
+                    step: Some(1.0),
+                })
+                .validator(Validator::int_range(1, 10)),
+        )
         .setting(
             "show_minimap",
             SchemaEntry::new("Display a minimap overview of the file", false)
@@ -0,0 +1,35 @@
+use pulsar_config::{ConfigManager, FieldType, NamespaceSchema, SchemaEntry, Validator};
+
+pub const NS: &str = "editor";
+pub const OWNER: &str = "documentation";
+
+pub fn register(cfg: &'static ConfigManager) {
+    let schema = NamespaceSchema::new(
+        "Documentation",
+        "Settings for the documentation window and its manual docs editor",
+    )
+    .setting(
+        "manual_autosave",
+        SchemaEntry::new(
+            "Periodically save manual docs that have unsaved changes",
+            false,
+        )
+        .label("Autosave Manual Docs")
+        .page("Documentation")
+        .field_type(FieldType::Checkbox),
+    )
+    .setting(
+        "manual_autosave_interval_secs",
+        SchemaEntry::new("Seconds between manual docs autosaves", 30.0_f64)
+            .label("Autosave Interval (s)")
+            .page("Documentation")
+            .field_type(FieldType::NumberInput {
+                min: Some(5.0),
+                max: Some(600.0),
+                step: Some(5.0),
+            })
+            .validator(Validator::float_range(5.0, 600.0)),
+    );
+
+    let _ = cfg.register(NS, OWNER, schema);
+}
@@ -2,6 +2,7 @@ pub mod advanced;
 pub mod appearance;
 pub mod code_editor;
 pub mod debugger;
+pub mod documentation;
 pub mod extensions;
 pub mod keybindings;
 pub mod localization;
@@ -24,6 +25,7 @@ pub fn register_all(cfg: &'static ConfigManager) {
     keybindings::register(cfg);
     terminal::register(cfg);
     debugger::register(cfg);
+    documentation::register(cfg);
     extensions::register(cfg);
     localization::register(cfg);
 }
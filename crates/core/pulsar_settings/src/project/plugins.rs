@@ -121,6 +121,35 @@ pub fn register(cfg: &'static ConfigManager) {
             .page("Plugins")
             .field_type(FieldType::Checkbox),
         )
+        .setting(
+            "registry_url",
+            SchemaEntry::new(
+                "Index URL of the registry the plugin manager installs plugins from",
+                "",
+            )
+            .label("Plugin Registry URL")
+            .page("Plugins")
+            .field_type(FieldType::TextInput {
+                placeholder: Some("https://plugins.example.com/index.json".into()),
+                multiline: false,
+            }),
+        )
+        .setting(
+            "install_scope",
+            SchemaEntry::new(
+                "Install registry plugins for every project or for this project only",
+                "global",
+            )
+            .label("Plugin Install Location")
+            .page("Plugins")
+            .field_type(FieldType::Dropdown {
+                options: vec![
+                    DropdownOption::new("All Projects", "global"),
+                    DropdownOption::new("This Project", "project"),
+                ],
+            })
+            .validator(Validator::string_one_of(["global", "project"])),
+        )
         .setting(
             "plugin_update_channel",
             SchemaEntry::new(
@@ -5,6 +5,7 @@
 //! When building as a cdylib, the registry is not used — __bp_dispatch_* symbols are the interface.
 
 use crate::NodeTypes;
+use std::sync::OnceLock;
 
 /// Parameter metadata — sizes baked in at compile time by the `#[blueprint]` macro.
 #[derive(Debug, Clone)]
@@ -74,6 +75,9 @@ pub struct NodeMetadata {
     pub documentation: &'static [&'static str],
     pub category: &'static str,
     pub color: Option<&'static str>,
+    /// Why the node is deprecated and what to use instead, if it is.
+    /// Set via `deprecated: "..."` in the `#[blueprint]` attribute.
+    pub deprecated: Option<&'static str>,
     pub imports: &'static [NodeImport],
     /// Optional conversion metadata for PBGC codegen.
     /// Set via `conversion: "from_type -> to_type"` in the `#[blueprint]` attribute.
@@ -81,6 +85,10 @@ pub struct NodeMetadata {
 }
 
 impl NodeMetadata {
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated.is_some()
+    }
+
     /// Get runtime type information for the return type by looking up in the registry.
     ///
     /// First tries the direct return_type_info_fn accessor (for Reflectable types),
@@ -166,6 +174,173 @@ pub fn get_all_categories() -> Vec<&'static str> {
     vec![]
 }
 
+// ── Node search and category tree ────────────────────────────────────────────
+
+/// A category in the node category tree. Categories nest on `/`, so a node in
+/// `"String/Conversion"` sits in `Conversion` under `String`.
+#[derive(Debug, Clone)]
+pub struct CategoryNode {
+    /// The last segment of the category, e.g. `"Conversion"`
+    pub name: &'static str,
+    /// The full category, e.g. `"String/Conversion"`
+    pub path: &'static str,
+    /// Nodes directly in this category, sorted by name
+    pub nodes: Vec<&'static NodeMetadata>,
+    /// Subcategories, sorted by name
+    pub children: Vec<CategoryNode>,
+}
+
+impl CategoryNode {
+    /// Number of nodes in this category and its subcategories
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+            + self
+                .children
+                .iter()
+                .map(CategoryNode::node_count)
+                .sum::<usize>()
+    }
+}
+
+/// Get the categories of all registered nodes as a tree, sorted by name.
+///
+/// The tree is built on first use and shared afterwards.
+pub fn get_category_tree() -> &'static [CategoryNode] {
+    static TREE: OnceLock<Vec<CategoryNode>> = OnceLock::new();
+    TREE.get_or_init(|| build_category_tree(get_all_nodes()))
+}
+
+/// Search registered nodes by name, best match first.
+///
+/// Matches are fuzzy: the query's characters must appear in order in the
+/// node name, with exact names, prefixes and matches at word starts ranked
+/// higher. Nodes whose category matches but name doesn't come after name
+/// matches, and deprecated nodes come after everything else. An empty query
+/// returns every node.
+pub fn search_nodes(query: &str) -> Vec<&'static NodeMetadata> {
+    rank_nodes(get_all_nodes(), query)
+}
+
+fn build_category_tree(
+    nodes: impl IntoIterator<Item = &'static NodeMetadata>,
+) -> Vec<CategoryNode> {
+    let mut roots: Vec<CategoryNode> = Vec::new();
+    for node in nodes {
+        let mut level = &mut roots;
+        let mut end = 0;
+        let mut segments = node.category.split('/').peekable();
+        while let Some(segment) = segments.next() {
+            end += segment.len();
+            let path = node.category[..end].trim();
+            end += 1;
+            let name = segment.trim();
+            if name.is_empty() {
+                continue;
+            }
+            let index = match level.iter().position(|category| category.name == name) {
+                Some(index) => index,
+                None => {
+                    level.push(CategoryNode {
+                        name,
+                        path,
+                        nodes: Vec::new(),
+                        children: Vec::new(),
+                    });
+                    level.len() - 1
+                }
+            };
+            if segments.peek().is_none() {
+                level[index].nodes.push(node);
+            }
+            level = &mut level[index].children;
+        }
+    }
+    sort_categories(&mut roots);
+    roots
+}
+
+fn sort_categories(categories: &mut [CategoryNode]) {
+    categories.sort_by(|a, b| a.name.cmp(b.name));
+    for category in categories {
+        category.nodes.sort_by(|a, b| a.name.cmp(b.name));
+        sort_categories(&mut category.children);
+    }
+}
+
+fn rank_nodes(
+    nodes: impl IntoIterator<Item = &'static NodeMetadata>,
+    query: &str,
+) -> Vec<&'static NodeMetadata> {
+    // Node names are snake_case, so "to string" finds `to_string`
+    let query = query.trim().to_lowercase().replace(' ', "_");
+    let mut ranked: Vec<(i32, &'static NodeMetadata)> = nodes
+        .into_iter()
+        .filter_map(|node| Some((match_score(node, &query)?, node)))
+        .collect();
+    ranked.sort_by(|(a_score, a), (b_score, b)| {
+        a.is_deprecated()
+            .cmp(&b.is_deprecated())
+            .then(b_score.cmp(a_score))
+            .then(a.name.cmp(b.name))
+    });
+    ranked.into_iter().map(|(_, node)| node).collect()
+}
+
+/// How well a lowercase query matches a node, if it does at all.
+fn match_score(node: &NodeMetadata, query: &str) -> Option<i32> {
+    if query.is_empty() {
+        return Some(0);
+    }
+    let name = node.name.to_lowercase();
+    let score = if name == query {
+        3000
+    } else if name.starts_with(query) {
+        2000 + fuzzy_score(query, &name)
+    } else {
+        let score = fuzzy_score(query, &name);
+        if score > 0 {
+            1000 + score
+        } else {
+            fuzzy_score(query, &node.category.to_lowercase())
+        }
+    };
+    (score > 0).then_some(score)
+}
+
+/// Scores `text` containing the characters of `pattern` in order, with
+/// bonuses for consecutive characters and for characters at word starts.
+/// 0 if it doesn't contain them.
+fn fuzzy_score(pattern: &str, text: &str) -> i32 {
+    let mut pattern = pattern.chars().peekable();
+    let mut score = 0;
+    let mut prev_match = false;
+    let mut prev_char = None;
+    for c in text.chars() {
+        let Some(&wanted) = pattern.peek() else {
+            break;
+        };
+        if c == wanted {
+            pattern.next();
+            score += 1;
+            if prev_match {
+                score += 2;
+            }
+            if matches!(prev_char, None | Some('_' | ' ' | '/')) {
+                score += 3;
+            }
+            prev_match = true;
+        } else {
+            prev_match = false;
+        }
+        prev_char = Some(c);
+    }
+    if pattern.peek().is_some() {
+        0
+    } else {
+        score
+    }
+}
+
 // ── Type constructor registry ────────────────────────────────────────────────
 
 #[derive(Debug, Clone)]
@@ -304,4 +479,77 @@ mod tests {
         let categories = get_all_categories();
         assert!(!categories.is_empty(), "Should have at least one category");
     }
+
+    fn node(
+        name: &'static str,
+        category: &'static str,
+        deprecated: Option<&'static str>,
+    ) -> &'static NodeMetadata {
+        Box::leak(Box::new(NodeMetadata {
+            name,
+            node_type: NodeTypes::pure,
+            params: &[],
+            output_params: &[],
+            return_type: None,
+            return_size: 0,
+            return_align: 1,
+            return_type_info_fn: None,
+            exec_inputs: &[],
+            exec_outputs: &[],
+            function_source: "",
+            documentation: &[],
+            category,
+            color: None,
+            deprecated,
+            imports: &[],
+            conversion: None,
+        }))
+    }
+
+    fn names(nodes: &[&NodeMetadata]) -> Vec<&'static str> {
+        nodes.iter().map(|node| node.name).collect()
+    }
+
+    #[test]
+    fn test_category_tree() {
+        let tree = build_category_tree([
+            node("to_upper", "String", None),
+            node("to_string", "String/Conversion", None),
+            node("parse_int", "String/Conversion", None),
+            node("add", "Math", None),
+            node("concat", "String", None),
+        ]);
+
+        let roots: Vec<&str> = tree.iter().map(|category| category.name).collect();
+        assert_eq!(roots, ["Math", "String"]);
+        let string = &tree[1];
+        assert_eq!(names(&string.nodes), ["concat", "to_upper"]);
+        assert_eq!(string.node_count(), 4);
+        let conversion = &string.children[0];
+        assert_eq!(conversion.name, "Conversion");
+        assert_eq!(conversion.path, "String/Conversion");
+        assert_eq!(names(&conversion.nodes), ["parse_int", "to_string"]);
+        assert!(conversion.children.is_empty());
+    }
+
+    #[test]
+    fn test_search_ranking() {
+        let nodes = [
+            node("string_to_int", "String", None),
+            node("to_string", "String/Conversion", None),
+            node("to_string_old", "String/Conversion", Some("use to_string")),
+            node("int_to_string", "Math", None),
+            node("add", "Math", None),
+        ];
+
+        assert_eq!(
+            names(&rank_nodes(nodes, "to string")),
+            ["to_string", "int_to_string", "to_string_old"]
+        );
+        assert_eq!(names(&rank_nodes(nodes, "ad")), ["add"]);
+        // Category matches come after name matches
+        assert_eq!(names(&rank_nodes(nodes, "math")), ["add", "int_to_string"]);
+        assert!(rank_nodes(nodes, "xyz").is_empty());
+        assert_eq!(rank_nodes(nodes, "").len(), nodes.len());
+    }
 }
@@ -12,6 +12,7 @@ pub mod pulsar_window;
 pub mod registry;
 pub mod state;
 pub mod telemetry;
+pub mod ui_scale;
 pub mod validation;
 pub mod wrappers;
 
@@ -21,7 +22,8 @@ pub use hooks::{HookContext, HookRegistry, HookType, WindowHook};
 pub use pulsar_window::{default_window_options, PulsarWindow};
 pub use state::{WindowInfo, WindowState};
 pub use telemetry::TelemetrySender;
-pub use ui_types_common::window_types::WindowRequest;
+pub use ui_scale::{WindowScaleSettings, ZoomStep};
+pub use ui_types_common::window_types::{WindowId, WindowRequest};
 pub use validation::{ValidationRule, WindowError, WindowResult, WindowValidator};
 
 pub use inventory;
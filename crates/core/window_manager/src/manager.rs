@@ -4,7 +4,7 @@ use crate::commands::{
     WindowCommand, WindowCommandResult,
 };
 use crate::hooks::{HookContext, HookRegistry, HookType, LoggingHook, TelemetryHook, WindowHook};
-use crate::state::WindowState;
+use crate::state::{WindowInfo, WindowState};
 use crate::telemetry::TelemetrySender;
 use crate::validation::{ValidationRule, WindowError, WindowResult, WindowValidator};
 use gpui::{
@@ -98,6 +98,13 @@ impl WindowManager {
 
         let handle: AnyWindowHandle = handle.into();
 
+        if let Some(settings) = cx.try_global::<crate::ui_scale::WindowScaleSettings>() {
+            let scale = settings.scale_for(wtype.kind_name());
+            let _ = handle.update(cx, |_, window, _| {
+                crate::ui_scale::apply_to_window(window, scale);
+            });
+        }
+
         self.state
             .register_window(window_id, wtype.clone(), None, handle);
         let result = WindowCommandResult::Created { window_id };
@@ -227,25 +234,41 @@ impl WindowManager {
         })
     }
 
+    /// Look up the manager's record for a GPUI window handle.
+    pub fn window_info_for_handle(&self, handle: AnyWindowHandle) -> Option<WindowInfo> {
+        self.state.find_by_handle(handle)
+    }
+
     pub fn window_count(&self) -> usize {
         self.state.window_count()
     }
 
+    /// Every open window whose type has the given
+    /// [`kind_name`](WindowRequest::kind_name).
+    pub fn windows_of_kind(&self, kind_name: &str) -> Vec<WindowInfo> {
+        self.state
+            .all_windows()
+            .into_iter()
+            .filter(|info| info.window_type.kind_name() == kind_name)
+            .collect()
+    }
+
     pub fn window_exists(&self, window_id: WindowId) -> bool {
         self.state.window_exists(window_id)
     }
 
     /// Focus an existing window matching the given request, if one is open.
-    /// Returns `true` if a matching window was found and focused.
-    pub fn focus_window_by_request(&self, request: &WindowRequest, cx: &mut App) -> bool {
-        if let Some(info) = self.state.find_by_request(request) {
-            let _ = info.handle.update(cx, |_, window, _| {
-                window.activate_window();
-            });
-            true
-        } else {
-            false
-        }
+    /// Returns the ID of the window found and focused.
+    pub fn focus_window_by_request(
+        &self,
+        request: &WindowRequest,
+        cx: &mut App,
+    ) -> Option<WindowId> {
+        let info = self.state.find_by_request(request)?;
+        let _ = info.handle.update(cx, |_, window, _| {
+            window.activate_window();
+        });
+        Some(info.window_id)
     }
 }
 
@@ -260,3 +283,59 @@ pub enum WindowManagerEvent {
 }
 
 impl EventEmitter<WindowManagerEvent> for WindowManager {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, IntoElement, TestAppContext, UpdateGlobal as _};
+
+    struct TestView;
+
+    impl Render for TestView {
+        fn render(
+            &mut self,
+            _window: &mut gpui::Window,
+            _cx: &mut gpui::Context<Self>,
+        ) -> impl IntoElement {
+            gpui::Empty
+        }
+    }
+
+    fn open(cx: &mut TestAppContext, type_name: &'static str) -> WindowId {
+        cx.update(|cx| {
+            WindowManager::update_global(cx, |wm, cx| {
+                wm.create_window(
+                    WindowRequest::Custom { type_name },
+                    WindowOptions::default(),
+                    |_, cx| cx.new(|_| TestView),
+                    cx,
+                )
+            })
+            .unwrap()
+            .0
+        })
+    }
+
+    #[gpui::test]
+    fn test_windows_of_kind(cx: &mut TestAppContext) {
+        cx.update(|cx| cx.set_global(WindowManager::new()));
+        let first = open(cx, "Graph");
+        let second = open(cx, "Graph");
+        open(cx, "Settings");
+
+        let graphs: Vec<WindowId> = cx.update(|cx| {
+            cx.global::<WindowManager>()
+                .windows_of_kind("Graph")
+                .iter()
+                .map(|info| info.window_id)
+                .collect()
+        });
+        assert_eq!(graphs.len(), 2);
+        assert!(graphs.contains(&first) && graphs.contains(&second));
+        assert!(cx.update(|cx| {
+            cx.global::<WindowManager>()
+                .windows_of_kind("About")
+                .is_empty()
+        }));
+    }
+}
@@ -1,6 +1,9 @@
 use dashmap::DashMap;
 use gpui::{App, Global};
 use std::sync::Arc;
+use ui_types_common::window_types::WindowId;
+
+use crate::validation::{WindowError, WindowResult};
 
 /// A zero-param window that can register itself in the [`WindowRegistry`].
 ///
@@ -12,7 +15,7 @@ pub struct WindowRegistrant {
 }
 inventory::collect!(WindowRegistrant);
 
-type Opener = Arc<dyn Fn(&mut App) + Send + Sync>;
+type Opener = Arc<dyn Fn(&mut App) -> WindowResult<WindowId> + Send + Sync>;
 
 /// Global registry mapping window names to their openers.
 ///
@@ -23,6 +26,9 @@ type Opener = Arc<dyn Fn(&mut App) + Send + Sync>;
 /// ```ignore
 /// WindowRegistry::update_global(cx, |reg, cx| reg.open("SettingsWindow", cx));
 /// ```
+///
+/// Callers that need the resulting window, e.g. to switch it to a tab, use
+/// [`try_open`](Self::try_open) instead.
 pub struct WindowRegistry {
     openers: DashMap<&'static str, Opener>,
 }
@@ -35,31 +41,49 @@ impl WindowRegistry {
     }
 
     /// Register an opener for `name`. Overwrites any previous registration.
-    pub fn register(&self, name: &'static str, opener: impl Fn(&mut App) + Send + Sync + 'static) {
+    /// The opener returns the ID of the window it created.
+    pub fn register(
+        &self,
+        name: &'static str,
+        opener: impl Fn(&mut App) -> WindowResult<WindowId> + Send + Sync + 'static,
+    ) {
         self.openers.insert(name, Arc::new(opener));
         tracing::debug!("[WindowRegistry] registered '{}'", name);
     }
 
-    /// Open the window registered under `name`. Logs timing at INFO level.
+    /// Open the window registered under `name`, logging any failure.
     pub fn open(&self, name: &'static str, cx: &mut App) {
-        match self.openers.get(name) {
-            Some(opener) => {
-                let t0 = std::time::Instant::now();
-                tracing::info!("[WindowRegistry] opening '{}'", name);
-                opener(cx);
-                let elapsed = t0.elapsed();
-                if elapsed.as_millis() > 50 {
-                    tracing::warn!(
-                        "[WindowRegistry] '{}' opener took {:?} (slow)",
-                        name,
-                        elapsed
-                    );
-                } else {
-                    tracing::info!("[WindowRegistry] '{}' opened in {:?}", name, elapsed);
-                }
-            }
-            None => tracing::warn!("[WindowRegistry] no opener registered for '{}'", name),
+        match self.try_open(name, cx) {
+            Ok(_) | Err(WindowError::AlreadyOpen(_)) => {}
+            Err(e) => tracing::warn!("[WindowRegistry] failed to open '{}': {}", name, e),
+        }
+    }
+
+    /// Open the window registered under `name`, returning its ID. A window
+    /// type that is already open is focused and reported as
+    /// [`WindowError::AlreadyOpen`] with the open window's ID. Logs timing
+    /// at INFO level.
+    pub fn try_open(&self, name: &'static str, cx: &mut App) -> WindowResult<WindowId> {
+        let Some(opener) = self.openers.get(name).map(|opener| opener.clone()) else {
+            return Err(WindowError::Internal(format!(
+                "no opener registered for '{}'",
+                name
+            )));
+        };
+        let t0 = std::time::Instant::now();
+        tracing::info!("[WindowRegistry] opening '{}'", name);
+        let result = opener(cx);
+        let elapsed = t0.elapsed();
+        if elapsed.as_millis() > 50 {
+            tracing::warn!(
+                "[WindowRegistry] '{}' opener took {:?} (slow)",
+                name,
+                elapsed
+            );
+        } else {
+            tracing::info!("[WindowRegistry] '{}' opened in {:?}", name, elapsed);
         }
+        result
     }
 
     pub fn is_registered(&self, name: &'static str) -> bool {
@@ -79,3 +103,121 @@ impl Default for WindowRegistry {
 }
 
 impl Global for WindowRegistry {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manager::WindowManager;
+    use gpui::{AppContext as _, IntoElement, Render, TestAppContext, UpdateGlobal as _};
+    use ui_types_common::window_types::WindowRequest;
+
+    const NAME: &str = "RegistryTestWindow";
+
+    struct TestView;
+
+    impl Render for TestView {
+        fn render(
+            &mut self,
+            _window: &mut gpui::Window,
+            _cx: &mut gpui::Context<Self>,
+        ) -> impl IntoElement {
+            gpui::Empty
+        }
+    }
+
+    /// Opens a single instance window the way `PulsarWindowExt::try_open` does
+    fn open_single(cx: &mut App) -> WindowResult<WindowId> {
+        let request = WindowRequest::Custom { type_name: NAME };
+        if let Some(window_id) =
+            WindowManager::update_global(cx, |wm, cx| wm.focus_window_by_request(&request, cx))
+        {
+            return Err(WindowError::AlreadyOpen(window_id));
+        }
+        let (window_id, _) = WindowManager::update_global(cx, |wm, cx| {
+            wm.create_window(
+                request,
+                gpui::WindowOptions::default(),
+                |_, cx| cx.new(|_| TestView),
+                cx,
+            )
+        })?;
+        Ok(window_id)
+    }
+
+    fn setup(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            cx.set_global(WindowManager::new());
+            let registry = WindowRegistry::new();
+            registry.register(NAME, open_single);
+            cx.set_global(registry);
+        });
+    }
+
+    fn try_open(cx: &mut TestAppContext) -> WindowResult<WindowId> {
+        cx.update(|cx| {
+            WindowRegistry::update_global(cx, |registry, cx| registry.try_open(NAME, cx))
+        })
+    }
+
+    fn close(cx: &mut TestAppContext, window_id: WindowId) {
+        cx.update(|cx| {
+            let handle = cx
+                .windows()
+                .into_iter()
+                .find(|handle| {
+                    cx.global::<WindowManager>()
+                        .window_info_for_handle(*handle)
+                        .is_some_and(|info| info.window_id == window_id)
+                })
+                .unwrap();
+            handle
+                .update(cx, |_, window, cx| {
+                    cx.global::<WindowManager>().close_window(window_id, window)
+                })
+                .unwrap()
+                .unwrap();
+        });
+    }
+
+    #[gpui::test]
+    fn test_opening_twice_returns_the_open_window(cx: &mut TestAppContext) {
+        setup(cx);
+        let window_id = try_open(cx).unwrap();
+
+        assert!(matches!(
+            try_open(cx),
+            Err(WindowError::AlreadyOpen(id)) if id == window_id
+        ));
+        assert_eq!(
+            cx.update(|cx| cx.global::<WindowManager>().window_count()),
+            1
+        );
+    }
+
+    #[gpui::test]
+    fn test_reopen_after_close(cx: &mut TestAppContext) {
+        setup(cx);
+        let first = try_open(cx).unwrap();
+        close(cx, first);
+        assert_eq!(
+            cx.update(|cx| cx.global::<WindowManager>().window_count()),
+            0
+        );
+
+        let second = try_open(cx).unwrap();
+        assert_ne!(second, first);
+        assert!(matches!(
+            try_open(cx),
+            Err(WindowError::AlreadyOpen(id)) if id == second
+        ));
+    }
+
+    #[gpui::test]
+    fn test_unregistered_name_fails(cx: &mut TestAppContext) {
+        setup(cx);
+        let result = cx.update(|cx| {
+            WindowRegistry::update_global(cx, |registry, cx| registry.try_open("Missing", cx))
+        });
+        assert!(matches!(result, Err(WindowError::Internal(_))));
+    }
+}
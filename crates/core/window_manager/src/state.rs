@@ -77,6 +77,14 @@ impl WindowState {
         }).map(|entry| entry.value().clone())
     }
 
+    /// Find the window registered with the given GPUI handle.
+    pub fn find_by_handle(&self, handle: AnyWindowHandle) -> Option<WindowInfo> {
+        self.windows
+            .iter()
+            .find(|entry| entry.handle == handle)
+            .map(|entry| entry.value().clone())
+    }
+
     pub fn window_count(&self) -> usize {
         self.windows.len()
     }
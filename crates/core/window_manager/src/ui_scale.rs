@@ -0,0 +1,330 @@
+//! Per-window UI scale (zoom).
+//!
+//! Scaling is applied through the window's rem size, so anything laid out in
+//! rems — every panel, plugin-hosted editors included — scales together without
+//! per-component changes. Scales are snapped so the rem size is a whole number of
+//! pixels, which keeps glyphs on the pixel grid and text sharp.
+//!
+//! Plugins are separate dynamic libraries with their own copy of this module's
+//! statics. The host therefore owns a single [`UiScaleCell`] and hands its
+//! address to each plugin through the `_plugin_init_ui_scale` export generated by
+//! `plugin_editor_api::export_plugin!`; [`current`] reads through that pointer.
+
+use gpui::{px, Global, Window};
+use pulsar_settings::editor::appearance;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicPtr, AtomicU32, AtomicU64, Ordering};
+
+/// Rem size (in pixels) at 100% scale.
+pub const BASE_REM_PX: f32 = 16.0;
+
+/// Available zoom levels expressed as rem sizes in whole pixels (≈69% – 200%).
+pub const REM_STEPS_PX: &[u32] = &[11, 12, 13, 14, 15, 16, 18, 20, 22, 24, 28, 32];
+
+/// Bounds of the zoom range, shared with the `ui_scale` setting's slider and
+/// validator. They're the first and last of [`REM_STEPS_PX`].
+pub const MIN_UI_SCALE: f32 = appearance::MIN_UI_SCALE as f32;
+pub const MAX_UI_SCALE: f32 = appearance::MAX_UI_SCALE as f32;
+pub const DEFAULT_UI_SCALE: f32 = 1.0;
+
+/// Direction of a zoom shortcut.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZoomStep {
+    In,
+    Out,
+    Reset,
+}
+
+/// Clamps a scale into the supported range and snaps it to a whole-pixel rem size.
+pub fn clamp_scale(scale: f32) -> f32 {
+    if !scale.is_finite() {
+        return DEFAULT_UI_SCALE;
+    }
+    let rem = (scale * BASE_REM_PX).round();
+    (rem / BASE_REM_PX).clamp(MIN_UI_SCALE, MAX_UI_SCALE)
+}
+
+/// Applies a zoom step to `scale`, moving to the next/previous entry in [`REM_STEPS_PX`].
+///
+/// Scales between two steps move to the nearest step in the requested direction.
+pub fn step_scale(scale: f32, step: ZoomStep) -> f32 {
+    let rem = clamp_scale(scale) * BASE_REM_PX;
+    let next = match step {
+        ZoomStep::Reset => return DEFAULT_UI_SCALE,
+        ZoomStep::In => REM_STEPS_PX
+            .iter()
+            .copied()
+            .find(|&step| step as f32 > rem)
+            .unwrap_or(*REM_STEPS_PX.last().expect("steps are not empty")),
+        ZoomStep::Out => REM_STEPS_PX
+            .iter()
+            .rev()
+            .copied()
+            .find(|&step| (step as f32) < rem)
+            .unwrap_or(REM_STEPS_PX[0]),
+    };
+    next as f32 / BASE_REM_PX
+}
+
+/// Scale as a whole percentage for display (e.g. `125`).
+pub fn scale_percent(scale: f32) -> u32 {
+    (scale * 100.0).round() as u32
+}
+
+/// Applies `scale` to a window by setting its rem size.
+pub fn apply_to_window(window: &mut Window, scale: f32) {
+    window.set_rem_size(px(clamp_scale(scale) * BASE_REM_PX));
+    window.refresh();
+}
+
+// ============================================================================
+// Persistence
+// ============================================================================
+
+/// Global default scale plus per-window-type overrides.
+///
+/// Window types are keyed by [`WindowRequest::kind_name`](crate::WindowRequest::kind_name).
+/// Installed as a GPUI global so [`WindowManager`](crate::WindowManager) can apply the
+/// stored scale to every window it opens.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowScaleSettings {
+    pub default_scale: f32,
+    per_window: BTreeMap<String, f32>,
+}
+
+impl Default for WindowScaleSettings {
+    fn default() -> Self {
+        Self::new(DEFAULT_UI_SCALE)
+    }
+}
+
+impl Global for WindowScaleSettings {}
+
+impl WindowScaleSettings {
+    pub fn new(default_scale: f32) -> Self {
+        Self {
+            default_scale: clamp_scale(default_scale),
+            per_window: BTreeMap::new(),
+        }
+    }
+
+    /// Scale for a window type, falling back to the global default.
+    pub fn scale_for(&self, window_type: &str) -> f32 {
+        self.per_window
+            .get(window_type)
+            .copied()
+            .unwrap_or(self.default_scale)
+    }
+
+    /// Stores a scale for a window type. Setting it back to the default removes the override.
+    pub fn set_scale_for(&mut self, window_type: &str, scale: f32) {
+        let scale = clamp_scale(scale);
+        if scale == self.default_scale {
+            self.per_window.remove(window_type);
+        } else {
+            self.per_window.insert(window_type.to_string(), scale);
+        }
+    }
+
+    /// Serializes the per-window overrides as `Type=1.25;Other=0.875`.
+    pub fn overrides_to_string(&self) -> String {
+        self.per_window
+            .iter()
+            .map(|(window_type, scale)| format!("{}={}", window_type, scale))
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+
+    /// Parses overrides written by [`Self::overrides_to_string`], skipping malformed entries.
+    pub fn with_overrides_from_str(mut self, overrides: &str) -> Self {
+        for entry in overrides.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+            let Some((window_type, scale)) = entry.split_once('=') else {
+                tracing::warn!("Ignoring malformed UI scale override '{}'", entry);
+                continue;
+            };
+            match scale.trim().parse::<f32>() {
+                Ok(scale) => self.set_scale_for(window_type.trim(), scale),
+                Err(_) => tracing::warn!("Ignoring malformed UI scale override '{}'", entry),
+            }
+        }
+        self
+    }
+}
+
+// ============================================================================
+// Cross-DLL propagation
+// ============================================================================
+
+/// Shared scale storage, owned by the host and read by plugins through a raw pointer.
+///
+/// Holds the scale of the most recently zoomed/focused window; `generation` bumps on
+/// every change so plugins can cheaply detect updates.
+#[repr(C)]
+pub struct UiScaleCell {
+    bits: AtomicU32,
+    generation: AtomicU64,
+}
+
+impl UiScaleCell {
+    pub const fn new() -> Self {
+        Self {
+            bits: AtomicU32::new(0x3f80_0000), // 1.0_f32
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.bits.load(Ordering::Acquire))
+    }
+
+    pub fn set(&self, scale: f32) {
+        self.bits
+            .store(clamp_scale(scale).to_bits(), Ordering::Release);
+        self.generation.fetch_add(1, Ordering::AcqRel);
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+}
+
+impl Default for UiScaleCell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static HOST_CELL: UiScaleCell = UiScaleCell::new();
+static SHARED_CELL: AtomicPtr<UiScaleCell> = AtomicPtr::new(std::ptr::null_mut());
+
+/// The host's scale cell; pass this to plugins via `_plugin_init_ui_scale`.
+pub fn host_cell() -> *const UiScaleCell {
+    &HOST_CELL
+}
+
+/// Points this copy of the module at the host's cell. Called from the plugin side.
+///
+/// # Safety
+///
+/// `cell` must be null or point to a [`UiScaleCell`] that lives for the rest of the
+/// process (the host's static cell satisfies this).
+pub unsafe fn install_shared(cell: *const UiScaleCell) {
+    SHARED_CELL.store(cell as *mut UiScaleCell, Ordering::Release);
+}
+
+fn cell() -> &'static UiScaleCell {
+    let shared = SHARED_CELL.load(Ordering::Acquire);
+    if shared.is_null() {
+        &HOST_CELL
+    } else {
+        // SAFETY: install_shared's contract guarantees the pointer outlives the process.
+        unsafe { &*shared }
+    }
+}
+
+/// Current UI scale as seen by this module (host or plugin).
+pub fn current() -> f32 {
+    cell().get()
+}
+
+/// Generation counter of the shared scale; changes whenever the scale does.
+pub fn generation() -> u64 {
+    cell().generation()
+}
+
+/// Publishes a new scale to the host cell, and therefore to every plugin.
+pub fn publish(scale: f32) {
+    HOST_CELL.set(scale);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clamp_snaps_and_bounds() {
+        assert_eq!(clamp_scale(1.0), 1.0);
+        assert_eq!(clamp_scale(0.1), MIN_UI_SCALE);
+        assert_eq!(clamp_scale(5.0), MAX_UI_SCALE);
+        assert_eq!(clamp_scale(f32::NAN), DEFAULT_UI_SCALE);
+        // 1.1 * 16 = 17.6 -> 18px rem
+        assert_eq!(clamp_scale(1.1), 1.125);
+        assert_eq!((clamp_scale(1.33) * BASE_REM_PX).fract(), 0.0);
+    }
+
+    #[test]
+    fn test_step_walks_the_table_and_stops_at_ends() {
+        assert_eq!(step_scale(1.0, ZoomStep::In), 1.125);
+        assert_eq!(step_scale(1.0, ZoomStep::Out), 15.0 / 16.0);
+        assert_eq!(step_scale(MAX_UI_SCALE, ZoomStep::In), MAX_UI_SCALE);
+        assert_eq!(step_scale(MIN_UI_SCALE, ZoomStep::Out), MIN_UI_SCALE);
+        assert_eq!(step_scale(1.75, ZoomStep::Reset), DEFAULT_UI_SCALE);
+
+        let mut scale = MIN_UI_SCALE;
+        for _ in 0..REM_STEPS_PX.len() * 2 {
+            scale = step_scale(scale, ZoomStep::In);
+        }
+        assert_eq!(scale, MAX_UI_SCALE);
+    }
+
+    #[test]
+    fn test_step_from_between_steps() {
+        // 17px rem is not a step; zooming in goes to 18, out goes to 16
+        let scale = 17.0 / BASE_REM_PX;
+        assert_eq!(step_scale(scale, ZoomStep::In), 18.0 / BASE_REM_PX);
+        assert_eq!(step_scale(scale, ZoomStep::Out), 1.0);
+    }
+
+    #[test]
+    fn test_bounds_match_the_steps() {
+        assert_eq!(REM_STEPS_PX[0] as f32 / BASE_REM_PX, MIN_UI_SCALE);
+        assert_eq!(
+            *REM_STEPS_PX.last().unwrap() as f32 / BASE_REM_PX,
+            MAX_UI_SCALE
+        );
+    }
+
+    #[test]
+    fn test_settings_round_trip() {
+        let mut settings = WindowScaleSettings::new(1.25);
+        settings.set_scale_for("PulsarEditorWindow", 1.5);
+        settings.set_scale_for("SettingsWindow", 0.875);
+        // Equal to the default: not stored as an override
+        settings.set_scale_for("AboutWindow", 1.25);
+
+        let serialized = settings.overrides_to_string();
+        assert_eq!(serialized, "PulsarEditorWindow=1.5;SettingsWindow=0.875");
+
+        let restored = WindowScaleSettings::new(1.25).with_overrides_from_str(&serialized);
+        assert_eq!(restored, settings);
+        assert_eq!(restored.scale_for("AboutWindow"), 1.25);
+        assert_eq!(restored.scale_for("SettingsWindow"), 0.875);
+    }
+
+    #[test]
+    fn test_settings_parse_skips_garbage() {
+        let settings =
+            WindowScaleSettings::default().with_overrides_from_str("A=1.5;;nonsense;B=abc; C = 9 ");
+        assert_eq!(settings.scale_for("A"), 1.5);
+        assert_eq!(settings.scale_for("B"), DEFAULT_UI_SCALE);
+        assert_eq!(settings.scale_for("C"), MAX_UI_SCALE);
+    }
+
+    #[test]
+    fn test_shared_cell_propagation() {
+        // Stand-in for a plugin's view of the host cell: a separate, leaked cell
+        // installed the same way `_plugin_init_ui_scale` does it.
+        let plugin_side: &'static UiScaleCell = Box::leak(Box::new(UiScaleCell::new()));
+        unsafe { install_shared(plugin_side) };
+
+        let before = generation();
+        plugin_side.set(1.5);
+        assert_eq!(current(), 1.5);
+        assert!(generation() > before);
+
+        unsafe { install_shared(std::ptr::null()) };
+        publish(1.25);
+        assert_eq!(current(), 1.25);
+        publish(DEFAULT_UI_SCALE);
+    }
+}
@@ -5,6 +5,10 @@ pub enum WindowError {
     #[error("Window {0} does not exist")]
     WindowNotFound(WindowId),
 
+    /// The window type only has one window, which is open and was focused
+    #[error("Window {0} is already open")]
+    AlreadyOpen(WindowId),
+
     #[error("Cannot close window {0}: {1}")]
     CannotClose(WindowId, String),
 
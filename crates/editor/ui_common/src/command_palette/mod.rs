@@ -10,6 +10,9 @@
 //! 2. **Palette** - Data container holding items with rebindable callbacks
 //! 3. **GenericPalette** - UI rendering component (via PaletteViewDelegate)
 //!
+//! Commands that plugins register with the plugin manager are added to a
+//! palette with `Palette::sync_plugin_commands`.
+//!
 //! ## Usage Example
 //!
 //! ```rust
@@ -56,6 +59,7 @@ pub mod generic_palette;
 pub mod palette_data;
 pub mod palette_delegate;
 pub mod palette_manager;
+mod plugin_commands;
 
 // Public API exports
 pub use generic_palette::GenericPalette;
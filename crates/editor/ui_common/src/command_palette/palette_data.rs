@@ -88,6 +88,8 @@ pub struct Palette {
     name: String,
     items: HashMap<ItemId, PaletteItemData>,
     next_item_id: usize,
+    /// Items added by `sync_plugin_commands`, replaced on each sync
+    pub(super) plugin_command_items: Vec<ItemId>,
 }
 
 impl Palette {
@@ -100,6 +102,7 @@ impl Palette {
             name,
             items: HashMap::new(),
             next_item_id: 0,
+            plugin_command_items: Vec::new(),
         }
     }
 
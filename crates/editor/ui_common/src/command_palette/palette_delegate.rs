@@ -58,6 +58,12 @@ impl PaletteViewDelegate {
         self.selected_item_id.take()
     }
 
+    /// Re-read the palette's items, e.g. after items were added or removed
+    /// since the delegate was created
+    pub fn refresh(&mut self, cx: &App) {
+        self.categories = self.palette.read(cx).categorized_items();
+    }
+
     /// Reset selection state for a fresh palette open
     pub fn reset_selection(&mut self) {
         self.selected_item_id = None;
@@ -0,0 +1,69 @@
+//! Plugin Commands
+//!
+//! Palette items for the commands plugins register with the plugin manager.
+//! The items are replaced whenever the palette is synced, so commands from a
+//! plugin build that a reload retired disappear. A stale item picked before
+//! the next sync is still safe: the plugin manager refuses to run commands
+//! that are no longer registered.
+
+use gpui::Context;
+use ui::{notification::Notification, ContextModal as _, IconName};
+
+use super::palette_data::Palette;
+
+impl Palette {
+    /// Replace this palette's plugin command items with the commands
+    /// currently registered with the global plugin manager.
+    pub fn sync_plugin_commands(&mut self, cx: &mut Context<Self>) {
+        for item_id in std::mem::take(&mut self.plugin_command_items) {
+            self.remove_item(item_id, cx);
+        }
+
+        let Some(pm) = plugin_manager::global() else {
+            return;
+        };
+        // Collect first so the lock isn't held while the palette changes
+        let commands: Vec<_> = {
+            let pm = pm.read();
+            pm.command_registry()
+                .get_all_commands()
+                .into_iter()
+                .map(|(plugin_id, command)| (plugin_id.clone(), command.clone()))
+                .collect()
+        };
+
+        for (plugin_id, command) in commands {
+            let description = match &command.keybinding {
+                Some(keybinding) => format!("{} ({})", plugin_id, keybinding),
+                None => plugin_id.to_string(),
+            };
+            let keywords = vec![command.id.clone(), plugin_id.to_string()];
+            let command_id = command.id;
+
+            let item_id = self.add_item_with_keywords(
+                command.display_name,
+                description,
+                IconName::Puzzle,
+                command.category,
+                keywords,
+                move |window, cx| {
+                    let Some(pm) = plugin_manager::global() else {
+                        return;
+                    };
+                    let result =
+                        pm.read()
+                            .execute_plugin_command(&plugin_id, &command_id, window, cx);
+                    if let Err(e) = result {
+                        tracing::error!("Plugin command {} failed: {}", command_id, e);
+                        window.push_notification(
+                            Notification::error("Command Failed").message(e.to_string()),
+                            cx,
+                        );
+                    }
+                },
+                cx,
+            );
+            self.plugin_command_items.push(item_id);
+        }
+    }
+}
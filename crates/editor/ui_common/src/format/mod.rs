@@ -0,0 +1,646 @@
+//! Locale-aware formatting for timestamps, durations and sizes
+//!
+//! Every unit name and word order comes from the `Format.*` translation keys,
+//! so panels don't format times themselves and pick up the editor language
+//! like the rest of the UI. Plural forms are chosen per locale by
+//! [`plural_category`] and looked up as `<key>.<category>`.
+//!
+//! [`RelativeTime`] wraps [`format_relative`] in an element that keeps itself
+//! current.
+
+mod relative_time;
+
+pub use relative_time::{next_refresh, RefreshSchedule, RelativeTime};
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SECS_PER_MINUTE: u64 = 60;
+const SECS_PER_HOUR: u64 = 60 * SECS_PER_MINUTE;
+const SECS_PER_DAY: u64 = 24 * SECS_PER_HOUR;
+
+/// How clock times are written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HourCycle {
+    /// `14:05`
+    #[default]
+    H24,
+    /// `2:05 PM`
+    H12,
+}
+
+impl HourCycle {
+    /// The cycle chosen in the editor's localization settings.
+    pub fn from_settings() -> Self {
+        let value = engine_state::global_config()
+            .get(engine_state::NS_EDITOR, "localization", "time_format")
+            .ok();
+        match value.as_ref().and_then(|v| v.as_str().ok()) {
+            Some("12h") => Self::H12,
+            _ => Self::H24,
+        }
+    }
+}
+
+/// Plural category of `n` in `locale`: `one`, `few`, `many` or `other`.
+pub fn plural_category(locale: &str, n: u64) -> &'static str {
+    let language = locale.split(['-', '_']).next().unwrap_or(locale);
+    match language {
+        "ru" | "uk" | "be" => {
+            let (rem10, rem100) = (n % 10, n % 100);
+            if rem10 == 1 && rem100 != 11 {
+                "one"
+            } else if (2..=4).contains(&rem10) && !(12..=14).contains(&rem100) {
+                "few"
+            } else {
+                "many"
+            }
+        }
+        "zh" | "ja" | "ko" => "other",
+        "pt" | "fr" if n <= 1 => "one",
+        _ if n == 1 => "one",
+        _ => "other",
+    }
+}
+
+/// How long ago `time` was, relative to `now`: "just now", "3 min ago",
+/// "5 hours ago", "yesterday 14:32", "Mar 4", "Mar 4, 2023".
+///
+/// Minutes are used for the first hour, hours for the rest of the same
+/// calendar day, then the day itself. Times slightly in the future (clock
+/// skew) count as just now; further ahead the date is shown.
+pub fn format_relative(time: SystemTime, now: SystemTime) -> String {
+    let locale = rust_i18n::locale();
+    relative(&Locale::current(&locale), time, now)
+}
+
+/// A duration in the largest unit that keeps it under 1000: "12 µs",
+/// "340 ms", "1.2 s". From a minute up it is written as two units:
+/// "4 m 10 s", "2 h 15 m", "3 d 4 h".
+///
+/// `precision` is the number of decimals shown for values below 10; larger
+/// values are shown whole.
+pub fn format_duration(duration: Duration, precision: usize) -> String {
+    let locale = rust_i18n::locale();
+    self::duration(&Locale::current(&locale), duration, precision)
+}
+
+/// A size in bytes using 1024-based units: "512 B", "1.50 KB", "12.3 MB",
+/// "640 GB".
+pub fn format_bytes(bytes: u64) -> String {
+    let locale = rust_i18n::locale();
+    self::bytes(&Locale::current(&locale), bytes)
+}
+
+/// Date and clock time of `time`: "Mar 4, 2024 14:32" or
+/// "Mar 4, 2024 2:32 PM".
+pub fn format_timestamp_absolute(time: SystemTime, hour_cycle: HourCycle) -> String {
+    let locale = rust_i18n::locale();
+    let locale = Locale {
+        hour_cycle,
+        ..Locale::current(&locale)
+    };
+    absolute(&locale, time)
+}
+
+/// Everything the formatters need to know about where they're displayed.
+#[derive(Clone, Copy)]
+struct Locale<'a> {
+    name: &'a str,
+    hour_cycle: HourCycle,
+    /// Seconds east of UTC at a given moment.
+    utc_offset: fn(SystemTime) -> i32,
+}
+
+impl<'a> Locale<'a> {
+    fn current(name: &'a str) -> Self {
+        Self {
+            name,
+            hour_cycle: HourCycle::from_settings(),
+            utc_offset: local_utc_offset,
+        }
+    }
+
+    fn text(&self, key: &str) -> String {
+        rust_i18n::t!(key, locale = self.name).into_owned()
+    }
+
+    fn plural(&self, key: &str, count: u64) -> String {
+        let key = format!("{}.{}", key, plural_category(self.name, count));
+        rust_i18n::t!(key.as_str(), locale = self.name, count = count).into_owned()
+    }
+
+    fn number(&self, value: f64, decimals: usize) -> String {
+        let text = format!("{:.*}", decimals, value);
+        if decimals == 0 {
+            return text;
+        }
+        text.replace('.', &self.text("Format.DecimalSeparator"))
+    }
+
+    fn quantity(&self, value: &str, unit_key: &str) -> String {
+        let unit = self.text(unit_key);
+        rust_i18n::t!(
+            "Format.Quantity",
+            locale = self.name,
+            value = value,
+            unit = unit
+        )
+        .into_owned()
+    }
+}
+
+fn local_utc_offset(time: SystemTime) -> i32 {
+    use chrono::{Offset, TimeZone};
+    let secs = unix_seconds(time);
+    chrono::Local
+        .timestamp_opt(secs, 0)
+        .single()
+        .map(|t| t.offset().fix().local_minus_utc())
+        .unwrap_or(0)
+}
+
+fn unix_seconds(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(since) => since.as_secs() as i64,
+        Err(before) => -(before.duration().as_secs() as i64),
+    }
+}
+
+/// Wall-clock date and time in some time zone.
+#[derive(Debug, PartialEq, Eq)]
+struct CivilTime {
+    /// Days since 1970-01-01 in that zone.
+    days: i64,
+    year: i64,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+}
+
+impl CivilTime {
+    fn of(time: SystemTime, locale: &Locale) -> Self {
+        let secs = unix_seconds(time) + i64::from((locale.utc_offset)(time));
+        let days = secs.div_euclid(SECS_PER_DAY as i64);
+        let secs_of_day = secs.rem_euclid(SECS_PER_DAY as i64) as u32;
+
+        // Days to proleptic Gregorian date, counting years from March so the
+        // leap day is last
+        let shifted = days + 719_468;
+        let era = shifted.div_euclid(146_097);
+        let day_of_era = shifted.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_from_march = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u32;
+        let month = if month_from_march < 10 {
+            month_from_march + 3
+        } else {
+            month_from_march - 9
+        } as u32;
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+        Self {
+            days,
+            year,
+            month,
+            day,
+            hour: secs_of_day / SECS_PER_HOUR as u32,
+            minute: secs_of_day % SECS_PER_HOUR as u32 / SECS_PER_MINUTE as u32,
+        }
+    }
+}
+
+fn date(locale: &Locale, time: &CivilTime, with_year: bool) -> String {
+    let month = locale.text(&format!("Format.Month.{}", time.month));
+    if with_year {
+        rust_i18n::t!(
+            "Format.Date.Full",
+            locale = locale.name,
+            month = month,
+            day = time.day,
+            year = time.year
+        )
+        .into_owned()
+    } else {
+        rust_i18n::t!(
+            "Format.Date.MonthDay",
+            locale = locale.name,
+            month = month,
+            day = time.day
+        )
+        .into_owned()
+    }
+}
+
+fn clock(locale: &Locale, time: &CivilTime) -> String {
+    let minute = format!("{:02}", time.minute);
+    match locale.hour_cycle {
+        HourCycle::H24 => rust_i18n::t!(
+            "Format.Time.H24",
+            locale = locale.name,
+            hour = format!("{:02}", time.hour),
+            minute = minute
+        )
+        .into_owned(),
+        HourCycle::H12 => {
+            let hour = match time.hour % 12 {
+                0 => 12,
+                hour => hour,
+            };
+            let period = locale.text(if time.hour < 12 {
+                "Format.Time.AM"
+            } else {
+                "Format.Time.PM"
+            });
+            rust_i18n::t!(
+                "Format.Time.H12",
+                locale = locale.name,
+                hour = hour,
+                minute = minute,
+                period = period
+            )
+            .into_owned()
+        }
+    }
+}
+
+fn relative(locale: &Locale, time: SystemTime, now: SystemTime) -> String {
+    let age = match now.duration_since(time) {
+        Ok(age) => age.as_secs(),
+        Err(ahead) if ahead.duration().as_secs() <= SECS_PER_MINUTE => 0,
+        Err(_) => return date(locale, &CivilTime::of(time, locale), true),
+    };
+    if age < SECS_PER_MINUTE {
+        return locale.text("Format.Relative.JustNow");
+    }
+    if age < SECS_PER_HOUR {
+        return locale.plural("Format.Relative.Minutes", age / SECS_PER_MINUTE);
+    }
+
+    let then = CivilTime::of(time, locale);
+    let today = CivilTime::of(now, locale);
+    if then.days == today.days {
+        locale.plural("Format.Relative.Hours", age / SECS_PER_HOUR)
+    } else if then.days + 1 == today.days {
+        let time = clock(locale, &then);
+        rust_i18n::t!(
+            "Format.Relative.Yesterday",
+            locale = locale.name,
+            time = time
+        )
+        .into_owned()
+    } else {
+        date(locale, &then, then.year != today.year)
+    }
+}
+
+fn absolute(locale: &Locale, time: SystemTime) -> String {
+    let civil = CivilTime::of(time, locale);
+    rust_i18n::t!(
+        "Format.DateTime",
+        locale = locale.name,
+        date = date(locale, &civil, true),
+        time = clock(locale, &civil)
+    )
+    .into_owned()
+}
+
+/// `value` rounded to the number of decimals `decimals_for` picks for it.
+///
+/// Rounding can carry a value into a range that wants fewer decimals
+/// (9.996 → 10.00), so the choice is repeated on the rounded value.
+fn round_for(value: f64, decimals_for: impl Fn(f64) -> usize) -> (f64, usize) {
+    let mut decimals = decimals_for(value);
+    loop {
+        let scale = 10f64.powi(decimals as i32);
+        let rounded = (value * scale).round() / scale;
+        let next = decimals_for(rounded);
+        if next >= decimals {
+            return (rounded, decimals);
+        }
+        decimals = next;
+    }
+}
+
+fn duration(locale: &Locale, duration: Duration, precision: usize) -> String {
+    // Unit, nanoseconds per unit, and the value that rolls over to the next
+    const UNITS: [(&str, f64, f64); 4] = [
+        ("Format.Duration.Nanoseconds", 1.0, 1000.0),
+        ("Format.Duration.Microseconds", 1e3, 1000.0),
+        ("Format.Duration.Milliseconds", 1e6, 1000.0),
+        ("Format.Duration.Seconds", 1e9, 60.0),
+    ];
+    let nanos = duration.as_nanos() as f64;
+
+    for (index, (unit, scale, limit)) in UNITS.into_iter().enumerate() {
+        // Nanoseconds are whole
+        let unit_precision = if index == 0 { 0 } else { precision };
+        let (value, decimals) =
+            round_for(
+                nanos / scale,
+                |value| {
+                    if value < 10.0 {
+                        unit_precision
+                    } else {
+                        0
+                    }
+                },
+            );
+        if value < limit {
+            return locale.quantity(&locale.number(value, decimals), unit);
+        }
+    }
+
+    let secs = (nanos / 1e9).round() as u64;
+    let (major, major_unit, minor, minor_unit) = if secs < SECS_PER_HOUR {
+        (
+            secs / SECS_PER_MINUTE,
+            "Format.Duration.Minutes",
+            secs % SECS_PER_MINUTE,
+            "Format.Duration.Seconds",
+        )
+    } else if secs < SECS_PER_DAY {
+        (
+            secs / SECS_PER_HOUR,
+            "Format.Duration.Hours",
+            secs % SECS_PER_HOUR / SECS_PER_MINUTE,
+            "Format.Duration.Minutes",
+        )
+    } else {
+        (
+            secs / SECS_PER_DAY,
+            "Format.Duration.Days",
+            secs % SECS_PER_DAY / SECS_PER_HOUR,
+            "Format.Duration.Hours",
+        )
+    };
+    rust_i18n::t!(
+        "Format.Duration.Compound",
+        locale = locale.name,
+        major = locale.quantity(&major.to_string(), major_unit),
+        minor = locale.quantity(&minor.to_string(), minor_unit)
+    )
+    .into_owned()
+}
+
+fn bytes(locale: &Locale, bytes: u64) -> String {
+    const UNITS: [&str; 4] = [
+        "Format.Bytes.KB",
+        "Format.Bytes.MB",
+        "Format.Bytes.GB",
+        "Format.Bytes.TB",
+    ];
+    if bytes < 1024 {
+        return locale.quantity(&bytes.to_string(), "Format.Bytes.B");
+    }
+    let decimals_for = |value: f64| match value {
+        v if v < 10.0 => 2,
+        v if v < 100.0 => 1,
+        _ => 0,
+    };
+
+    let mut value = bytes as f64;
+    for (index, unit) in UNITS.iter().enumerate() {
+        value /= 1024.0;
+        let (rounded, decimals) = round_for(value, decimals_for);
+        if rounded < 1024.0 || index == UNITS.len() - 1 {
+            return locale.quantity(&locale.number(rounded, decimals), unit);
+        }
+    }
+    unreachable!("the last unit always formats")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: u64 = SECS_PER_MINUTE;
+    const HOUR: u64 = SECS_PER_HOUR;
+    const DAY: u64 = SECS_PER_DAY;
+
+    fn locale(name: &str) -> Locale<'_> {
+        Locale {
+            name,
+            hour_cycle: HourCycle::H24,
+            utc_offset: |_| 0,
+        }
+    }
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    /// 2024-03-15 12:00 UTC
+    const NOON: u64 = 1_710_504_000;
+
+    #[test]
+    fn test_civil_time() {
+        let en = locale("en");
+        let civil = CivilTime::of(at(NOON + 2 * HOUR + 5 * MINUTE), &en);
+        assert_eq!(
+            (civil.year, civil.month, civil.day, civil.hour, civil.minute),
+            (2024, 3, 15, 14, 5)
+        );
+        // Leap day, and the first instant of a year
+        let leap = CivilTime::of(at(1_709_164_800), &en);
+        assert_eq!((leap.year, leap.month, leap.day), (2024, 2, 29));
+        let new_year = CivilTime::of(at(1_704_067_200), &en);
+        assert_eq!((new_year.year, new_year.month, new_year.day), (2024, 1, 1));
+
+        // Offsets move the wall clock across midnight
+        let tokyo = Locale {
+            utc_offset: |_| 9 * 3600,
+            ..en
+        };
+        let civil = CivilTime::of(at(NOON + 12 * HOUR), &tokyo);
+        assert_eq!((civil.day, civil.hour), (16, 9));
+    }
+
+    #[test]
+    fn test_relative_breakpoints() {
+        let en = locale("en");
+        let now = at(NOON);
+        let ago = |secs: u64| relative(&en, at(NOON - secs), now);
+
+        assert_eq!(ago(0), "just now");
+        assert_eq!(ago(59), "just now");
+        assert_eq!(ago(60), "1 min ago");
+        assert_eq!(ago(119), "1 min ago");
+        assert_eq!(ago(HOUR - 1), "59 min ago");
+        assert_eq!(ago(HOUR), "1 hour ago");
+        assert_eq!(ago(2 * HOUR), "2 hours ago");
+        // Midnight separates today from yesterday
+        assert_eq!(ago(12 * HOUR), "12 hours ago");
+        assert_eq!(ago(12 * HOUR + 1), "yesterday 23:59");
+        assert_eq!(ago(DAY + 12 * HOUR), "yesterday 00:00");
+        assert_eq!(ago(DAY + 12 * HOUR + 1), "Mar 13");
+        // Earlier in the year, then the year before
+        assert_eq!(ago(74 * DAY), "Jan 1");
+        assert_eq!(ago(75 * DAY), "Dec 31, 2023");
+
+        assert_eq!(relative(&en, at(NOON + 30), now), "just now");
+        assert_eq!(relative(&en, at(NOON + 3 * DAY), now), "Mar 18, 2024");
+    }
+
+    #[test]
+    fn test_relative_uses_hour_cycle() {
+        let en = Locale {
+            hour_cycle: HourCycle::H12,
+            ..locale("en")
+        };
+        let now = at(NOON);
+        assert_eq!(
+            relative(&en, at(NOON - 14 * HOUR), now),
+            "yesterday 10:00 PM"
+        );
+        assert_eq!(
+            relative(&en, at(NOON - 23 * HOUR - 30 * MINUTE), now),
+            "yesterday 12:30 PM"
+        );
+        assert_eq!(absolute(&en, at(NOON - 12 * HOUR)), "Mar 15, 2024 12:00 AM");
+        assert_eq!(
+            absolute(&locale("en"), at(NOON + 2 * HOUR + 5 * MINUTE)),
+            "Mar 15, 2024 14:05"
+        );
+    }
+
+    #[test]
+    fn test_plural_categories() {
+        let categories = |locale: &str| -> Vec<&str> {
+            [0, 1, 2, 4, 5, 11, 12, 21, 22, 25, 101, 111]
+                .iter()
+                .map(|n| plural_category(locale, *n))
+                .collect()
+        };
+        assert_eq!(
+            categories("en"),
+            [
+                "other", "one", "other", "other", "other", "other", "other", "other", "other",
+                "other", "other", "other"
+            ]
+        );
+        assert_eq!(
+            categories("ru"),
+            [
+                "many", "one", "few", "few", "many", "many", "many", "one", "few", "many", "one",
+                "many"
+            ]
+        );
+        assert_eq!(plural_category("pt-BR", 0), "one");
+        assert_eq!(plural_category("zh-CN", 1), "other");
+    }
+
+    #[test]
+    fn test_relative_in_russian() {
+        let ru = locale("ru");
+        let now = at(NOON);
+        let ago = |secs: u64| relative(&ru, at(NOON - secs), now);
+
+        assert_eq!(ago(30), "только что");
+        assert_eq!(ago(MINUTE), "1 минуту назад");
+        assert_eq!(ago(3 * MINUTE), "3 минуты назад");
+        assert_eq!(ago(5 * MINUTE), "5 минут назад");
+        assert_eq!(ago(11 * MINUTE), "11 минут назад");
+        assert_eq!(ago(21 * MINUTE), "21 минуту назад");
+        assert_eq!(ago(HOUR), "1 час назад");
+        assert_eq!(ago(2 * HOUR), "2 часа назад");
+        assert_eq!(ago(5 * HOUR), "5 часов назад");
+        assert_eq!(ago(13 * HOUR), "вчера в 23:00");
+        // Day before month
+        assert_eq!(ago(4 * DAY), "11 мар.");
+        assert_eq!(ago(75 * DAY), "31 дек. 2023");
+    }
+
+    #[test]
+    fn test_duration_units() {
+        let en = locale("en");
+        let format = |d: Duration, precision: usize| duration(&en, d, precision);
+
+        assert_eq!(format(Duration::ZERO, 1), "0 ns");
+        assert_eq!(format(Duration::from_nanos(999), 1), "999 ns");
+        assert_eq!(format(Duration::from_micros(12), 1), "12 µs");
+        assert_eq!(format(Duration::from_micros(340), 0), "340 µs");
+        assert_eq!(format(Duration::from_millis(340), 0), "340 ms");
+        assert_eq!(format(Duration::from_millis(1200), 1), "1.2 s");
+        assert_eq!(format(Duration::from_millis(1234), 2), "1.23 s");
+        assert_eq!(format(Duration::from_millis(12_345), 2), "12 s");
+        assert_eq!(format(Duration::from_secs(250), 1), "4 m 10 s");
+        assert_eq!(
+            format(Duration::from_secs(2 * HOUR + 15 * MINUTE), 1),
+            "2 h 15 m"
+        );
+        assert_eq!(
+            format(Duration::from_secs(3 * DAY + 4 * HOUR), 1),
+            "3 d 4 h"
+        );
+    }
+
+    #[test]
+    fn test_duration_rolls_over_when_rounding() {
+        let en = locale("en");
+        let format = |d: Duration, precision: usize| duration(&en, d, precision);
+
+        // 9.96 µs would show as "10.0 µs"
+        assert_eq!(format(Duration::from_nanos(9_960), 1), "10 µs");
+        assert_eq!(format(Duration::from_nanos(999_499), 0), "999 µs");
+        assert_eq!(format(Duration::from_nanos(999_500), 0), "1 ms");
+        assert_eq!(format(Duration::from_nanos(999_960), 1), "1.0 ms");
+        assert_eq!(format(Duration::from_millis(59_499), 0), "59 s");
+        assert_eq!(format(Duration::from_millis(59_500), 0), "1 m 0 s");
+        assert_eq!(format(Duration::from_secs(HOUR), 0), "1 h 0 m");
+        assert_eq!(format(Duration::from_secs(DAY), 0), "1 d 0 h");
+    }
+
+    #[test]
+    fn test_duration_in_russian() {
+        let ru = locale("ru");
+        assert_eq!(duration(&ru, Duration::from_millis(1200), 1), "1,2 с");
+        assert_eq!(duration(&ru, Duration::from_micros(12), 1), "12 мкс");
+        assert_eq!(
+            duration(&ru, Duration::from_secs(2 * HOUR + 15 * MINUTE), 1),
+            "2 ч 15 мин"
+        );
+    }
+
+    #[test]
+    fn test_byte_units() {
+        let en = locale("en");
+        let format = |n: u64| bytes(&en, n);
+
+        assert_eq!(format(0), "0 B");
+        assert_eq!(format(1023), "1023 B");
+        assert_eq!(format(1024), "1.00 KB");
+        assert_eq!(format(1536), "1.50 KB");
+        assert_eq!(format(10 * 1024), "10.0 KB");
+        assert_eq!(format(12_900_000), "12.3 MB");
+        assert_eq!(format(640 << 30), "640 GB");
+        // Rounds up into the next unit
+        assert_eq!(format(1024 * 1024 - 1), "1.00 MB");
+        assert_eq!(format(u64::MAX), "16777216 TB");
+
+        assert_eq!(bytes(&locale("ru"), 1536), "1,50 КБ");
+    }
+
+    #[test]
+    fn test_locale_switch_mid_session() {
+        let previous = rust_i18n::locale().to_string();
+        let now = SystemTime::now();
+        let five_minutes_ago = now - Duration::from_secs(5 * MINUTE);
+
+        rust_i18n::set_locale("en");
+        assert_eq!(format_relative(five_minutes_ago, now), "5 min ago");
+        assert_eq!(format_bytes(2048), "2.00 KB");
+
+        rust_i18n::set_locale("ru");
+        assert_eq!(format_relative(five_minutes_ago, now), "5 минут назад");
+        assert_eq!(format_bytes(2048), "2,00 КБ");
+
+        // Locales without their own strings fall back to English
+        rust_i18n::set_locale("it");
+        assert_eq!(format_relative(five_minutes_ago, now), "5 min ago");
+
+        rust_i18n::set_locale(&previous);
+    }
+}
@@ -0,0 +1,184 @@
+//! Live relative timestamps
+//!
+//! A [`RelativeTime`] label has to re-render as its text goes stale, but a
+//! timer per label adds up quickly in a log with thousands of rows. Instead,
+//! each render records when the label's text next changes against the view
+//! it was drawn in, and one shared task sleeps until the earliest of those
+//! and notifies the views that are due.
+
+use gpui::{App, EntityId, Global, IntoElement, RenderOnce, SharedString, Task, Window};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::{Duration, Instant, SystemTime};
+
+use super::format_relative;
+
+/// Text of [`format_relative`] for `time`, kept current while on screen.
+#[derive(IntoElement)]
+pub struct RelativeTime {
+    time: SystemTime,
+}
+
+impl RelativeTime {
+    pub fn new(time: SystemTime) -> Self {
+        Self { time }
+    }
+}
+
+impl RenderOnce for RelativeTime {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let now = SystemTime::now();
+        let age = now.duration_since(self.time).unwrap_or_default();
+        request_refresh(
+            window.current_view(),
+            Instant::now() + next_refresh(age),
+            cx,
+        );
+        SharedString::from(format_relative(self.time, now))
+    }
+}
+
+/// How long until a label for something `age` old should be redrawn: every
+/// second for the first minute, then on each whole minute of age.
+pub fn next_refresh(age: Duration) -> Duration {
+    const MINUTE: Duration = Duration::from_secs(60);
+    if age < MINUTE {
+        return Duration::from_secs(1);
+    }
+    let into_minute = Duration::from_nanos((age.as_nanos() % MINUTE.as_nanos()) as u64);
+    MINUTE - into_minute
+}
+
+/// Pending redraws, keyed by whatever has to be redrawn.
+pub struct RefreshSchedule<K> {
+    due: HashMap<K, Instant>,
+}
+
+impl<K> Default for RefreshSchedule<K> {
+    fn default() -> Self {
+        Self {
+            due: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Copy + Eq + Hash> RefreshSchedule<K> {
+    /// Redraw `key` by `due`. A key drawn several times keeps its earliest
+    /// deadline.
+    ///
+    /// Returns true when this moved the schedule's earliest deadline forward,
+    /// meaning a timer waiting for [`next_due`](Self::next_due) must be
+    /// re-armed.
+    pub fn request(&mut self, key: K, due: Instant) -> bool {
+        let earliest = self.next_due();
+        let entry = self.due.entry(key).or_insert(due);
+        *entry = (*entry).min(due);
+        earliest.is_none_or(|earliest| due < earliest)
+    }
+
+    /// The earliest pending deadline.
+    pub fn next_due(&self) -> Option<Instant> {
+        self.due.values().min().copied()
+    }
+
+    /// Remove and return every key due by `now`.
+    pub fn take_due(&mut self, now: Instant) -> Vec<K> {
+        let keys: Vec<K> = self
+            .due
+            .iter()
+            .filter(|(_, due)| **due <= now)
+            .map(|(key, _)| *key)
+            .collect();
+        for key in &keys {
+            self.due.remove(key);
+        }
+        keys
+    }
+}
+
+#[derive(Default)]
+struct RelativeTimeRefresh {
+    schedule: RefreshSchedule<EntityId>,
+    timer: Option<Task<()>>,
+}
+
+impl Global for RelativeTimeRefresh {}
+
+fn request_refresh(view: EntityId, due: Instant, cx: &mut App) {
+    let rearm = cx
+        .default_global::<RelativeTimeRefresh>()
+        .schedule
+        .request(view, due);
+    if rearm {
+        arm_timer(cx);
+    }
+}
+
+/// Wait for the earliest deadline, replacing (and so cancelling) any timer
+/// waiting for a later one.
+fn arm_timer(cx: &mut App) {
+    let Some(due) = cx.global::<RelativeTimeRefresh>().schedule.next_due() else {
+        return;
+    };
+    let timer = cx.spawn(async move |cx| {
+        cx.background_executor()
+            .timer(due.saturating_duration_since(Instant::now()))
+            .await;
+        let _ = cx.update(|cx| {
+            let views = cx
+                .global_mut::<RelativeTimeRefresh>()
+                .schedule
+                .take_due(Instant::now());
+            for view in views {
+                cx.notify(view);
+            }
+            arm_timer(cx);
+        });
+    });
+    cx.global_mut::<RelativeTimeRefresh>().timer = Some(timer);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refresh_cadence() {
+        let secs = Duration::from_secs;
+        assert_eq!(next_refresh(Duration::ZERO), secs(1));
+        assert_eq!(next_refresh(Duration::from_millis(59_999)), secs(1));
+        // From a minute on, wait for the next whole minute of age
+        assert_eq!(next_refresh(secs(60)), secs(60));
+        assert_eq!(next_refresh(secs(61)), secs(59));
+        assert_eq!(
+            next_refresh(Duration::from_millis(119_500)),
+            Duration::from_millis(500)
+        );
+        assert_eq!(next_refresh(secs(120)), secs(60));
+        assert_eq!(next_refresh(secs(3 * 3600 + 15)), secs(45));
+    }
+
+    #[test]
+    fn test_schedule_keeps_earliest_deadline() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let mut schedule = RefreshSchedule::default();
+
+        assert!(schedule.request("log", at(60)));
+        // A later deadline doesn't need the timer re-armed
+        assert!(!schedule.request("files", at(90)));
+        assert!(!schedule.request("log", at(75)));
+        assert_eq!(schedule.next_due(), Some(at(60)));
+        // A view showing a fresh entry now needs a redraw within a second
+        assert!(schedule.request("files", at(1)));
+        assert_eq!(schedule.next_due(), Some(at(1)));
+
+        assert!(schedule.take_due(at(0)).is_empty());
+        assert_eq!(schedule.take_due(at(1)), vec!["files"]);
+        assert_eq!(schedule.next_due(), Some(at(60)));
+        assert_eq!(schedule.take_due(at(100)), vec!["log"]);
+        assert_eq!(schedule.next_due(), None);
+        // Once empty, the next request starts the timer again
+        assert!(schedule.request("log", at(200)));
+    }
+}
@@ -26,6 +26,7 @@ pub fn set_locale(locale: &str) {
 pub mod asset_picker;
 pub mod command_palette;
 pub mod file_utils;
+pub mod format;
 pub mod generic_window;
 pub mod menu;
 pub mod open_window;
@@ -48,6 +49,10 @@ pub use shared_state::SharedState;
 
 // Re-export commonly used types
 pub use file_utils::{find_openable_files, FileInfo, FileType};
+pub use format::{
+    format_bytes, format_duration, format_relative, format_timestamp_absolute, HourCycle,
+    RelativeTime,
+};
 pub use menu::{AppTitleBar, AppTitleBarEvent};
 pub use panel::{PanelBase, PanelEvent};
 pub use profile_dropdown::{ProfileDropdown, ProfileDropdownEvent};
@@ -5,7 +5,10 @@
 
 use gpui::{App, AppContext as _, Bounds, UpdateGlobal as _, WindowBounds, WindowOptions};
 use ui::Root;
-use window_manager::{apply_window_wrapper, PulsarWindow, WindowManager, WindowRegistry};
+use window_manager::{
+    apply_window_wrapper, PulsarWindow, WindowError, WindowId, WindowManager, WindowRegistry,
+    WindowResult,
+};
 
 /// Extends every [`PulsarWindow`] with an `open` method that routes through
 /// the [`WindowManager`] and wraps the entity in [`Root`] for theming.
@@ -22,11 +25,23 @@ pub trait PulsarWindowExt: PulsarWindow {
     /// Open this window through the [`WindowManager`], wrapped in [`Root`] for theming.
     /// If a window with the same `window_name()` already exists, it is focused instead.
     fn open(params: Self::Params, cx: &mut App) {
+        match Self::try_open(params, cx) {
+            Ok(_) | Err(WindowError::AlreadyOpen(_)) => {}
+            Err(e) => tracing::warn!("Failed to open {}: {}", Self::window_name(), e),
+        }
+    }
+
+    /// Like [`open`](Self::open), returning the ID of the new window, or
+    /// [`WindowError::AlreadyOpen`] with the ID of the existing window it
+    /// focused instead.
+    fn try_open(params: Self::Params, cx: &mut App) -> WindowResult<WindowId> {
         let request = Self::window_request(&params);
 
         // Dedup: focus existing window if one is already open
-        if WindowManager::update_global(cx, |wm, cx| wm.focus_window_by_request(&request, cx)) {
-            return;
+        if let Some(window_id) =
+            WindowManager::update_global(cx, |wm, cx| wm.focus_window_by_request(&request, cx))
+        {
+            return Err(WindowError::AlreadyOpen(window_id));
         }
 
         let profile = Self::window_profile(&params);
@@ -36,7 +51,7 @@ pub trait PulsarWindowExt: PulsarWindow {
             WindowBounds::Windowed(bounds) => WindowBounds::centered(bounds.size, cx),
             other => other,
         });
-        let _ = WindowManager::update_global(cx, |wm, cx| {
+        let (window_id, _) = WindowManager::update_global(cx, |wm, cx| {
             if let Some(profile) = profile {
                 let wrapper_kind = profile.wrapper();
                 let profile_options = profile.options();
@@ -61,7 +76,8 @@ pub trait PulsarWindowExt: PulsarWindow {
                     cx,
                 )
             }
-        });
+        })?;
+        Ok(window_id)
     }
 
     /// Register this window in the [`WindowRegistry`] so it can be opened by name.
@@ -74,7 +90,7 @@ pub trait PulsarWindowExt: PulsarWindow {
     {
         WindowRegistry::update_global(cx, |reg, _| {
             reg.register(Self::window_name(), |cx| {
-                Self::open(Self::Params::default(), cx);
+                Self::try_open(Self::Params::default(), cx)
             });
         });
     }
@@ -45,6 +45,21 @@ pub struct ToggleFlamegraph;
 #[action(namespace = pulsar_app)]
 pub struct ToggleCommandPalette;
 
+/// Action to zoom the active window in
+#[derive(Action, Clone, Debug, PartialEq, Eq, Deserialize, JsonSchema)]
+#[action(namespace = pulsar_app)]
+pub struct ZoomIn;
+
+/// Action to zoom the active window out
+#[derive(Action, Clone, Debug, PartialEq, Eq, Deserialize, JsonSchema)]
+#[action(namespace = pulsar_app)]
+pub struct ZoomOut;
+
+/// Action to reset the active window's zoom to the default UI scale
+#[derive(Action, Clone, Debug, PartialEq, Eq, Deserialize, JsonSchema)]
+#[action(namespace = pulsar_app)]
+pub struct ResetZoom;
+
 /// Action to open a file at a specific path
 #[derive(Action, Clone, Debug, PartialEq, Eq, Deserialize, JsonSchema)]
 #[action(namespace = pulsar_app)]
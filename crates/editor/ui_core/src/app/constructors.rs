@@ -197,14 +197,14 @@ impl PulsarApp {
             let pd = problems_drawer.clone();
             window_manager::WindowRegistry::update_global(cx, move |reg, _| {
                 reg.register("ProblemsWindow", move |cx| {
-                    ui_problems::ProblemsWindow::open(pd.clone(), cx);
+                    ui_problems::ProblemsWindow::try_open(pd.clone(), cx)
                 });
             });
 
             let td = type_debugger_drawer.clone();
             window_manager::WindowRegistry::update_global(cx, move |reg, _| {
                 reg.register("TypeDebuggerWindow", move |cx| {
-                    ui_type_debugger::TypeDebuggerWindow::open(td.clone(), cx);
+                    ui_type_debugger::TypeDebuggerWindow::try_open(td.clone(), cx)
                 });
             });
         }
@@ -346,6 +346,26 @@ impl PulsarApp {
             }
         }
 
+        // Plugins installed for this project only
+        if let Some(project) = project_path.as_deref() {
+            let project_plugins = plugin_manager::marketplace::project_plugin_dir(project);
+            if project_plugins.is_dir() {
+                if let Err(e) = plugin_manager.load_plugins_from_dir(&project_plugins, &*cx) {
+                    tracing::error!("[PulsarApp] failed to load project plugins: {}", e);
+                }
+            }
+        }
+
+        if let Some(engine) = engine_state::EngineContext::global() {
+            for plugin in plugin_manager.get_plugins() {
+                engine.publish(engine_state::PluginLoaded {
+                    id: plugin.id.to_string(),
+                    name: plugin.name.clone(),
+                    version: plugin.version.clone(),
+                });
+            }
+        }
+
         // Drain plugin subsystems and inject into the engine backend
         // before the plugin manager becomes globally accessible.
         let plugin_subsystems = plugin_manager.drain_subsystems();
@@ -381,6 +401,14 @@ impl PulsarApp {
             }
         }
 
+        // Embedded plugin viewports follow the same frame cap as the level editor viewport
+        let max_viewport_fps = ui::settings::EngineSettings::default_path()
+            .map(|path| ui::settings::EngineSettings::load(&path))
+            .unwrap_or_default()
+            .advanced
+            .max_viewport_fps;
+        plugin_manager::embedded_viewport::service().set_max_fps(max_viewport_fps);
+
         // Initialize global plugin manager
         tracing::debug!("🌍 Initializing global plugin manager");
         plugin_manager::initialize_global(plugin_manager);
@@ -440,32 +468,38 @@ impl PulsarApp {
         };
 
         // Update file manager drawer with registered file types from plugin manager
-        let file_types: Vec<plugin_editor_api::FileTypeDefinition> =
-            if let Some(pm_lock) = plugin_manager::global() {
-                pm_lock
-                    .read()
-                    .file_type_registry()
-                    .get_all_file_types()
-                    .into_iter()
-                    .cloned()
-                    .collect()
-            } else {
-                Vec::new()
-            };
+        let (file_types, file_type_icons) = if let Some(pm_lock) = plugin_manager::global() {
+            let pm = pm_lock.read();
+            let registry = pm.file_type_registry();
+            let file_types: Vec<plugin_editor_api::FileTypeDefinition> =
+                registry.get_all_file_types().into_iter().cloned().collect();
+            let file_type_icons = file_types
+                .iter()
+                .filter_map(|file_type| {
+                    Some((file_type.id.clone(), registry.icon_image(&file_type.id)?))
+                })
+                .collect();
+            (file_types, file_type_icons)
+        } else {
+            (Vec::new(), std::collections::HashMap::new())
+        };
 
         app.state.file_manager_drawer.update(cx, |drawer, cx| {
             drawer.update_file_types(file_types);
+            drawer.update_file_type_icons(file_type_icons);
             cx.notify();
         });
 
-        // Sync UserTypeRegistry to UI if we have a project
+        // Keep the type debugger in sync with the UserTypeRegistry if we have a project
         if has_project {
             if let Some(engine_state) = engine_state::EngineContext::global() {
                 if let Some(user_types) = engine_state.user_types() {
-                    let types = user_types.all();
-                    tracing::debug!("📊 Syncing {} types to TypeDebuggerDrawer", types.len());
+                    tracing::debug!(
+                        "📊 Syncing {} types to TypeDebuggerDrawer",
+                        user_types.len()
+                    );
                     app.state.type_debugger_drawer.update(cx, |drawer, cx| {
-                        drawer.set_types(types, cx);
+                        drawer.watch_user_types(user_types, cx);
                     });
                 }
             }
@@ -589,6 +623,9 @@ impl PulsarApp {
                     cx,
                 );
 
+                // Commands contributed by plugins
+                palette.sync_plugin_commands(cx);
+
                 // Fast path: use the file list that the loading-screen background
                 // thread already scanned — no disk I/O on the main thread.
                 for entry in preloaded_files {
@@ -667,6 +667,12 @@ pub fn on_tab_panel_event(
             app.refresh_open_editor_snapshot(cx);
             // Update Discord presence when tab is closed
             app.update_discord_presence(cx);
+            // Let plugin state subscribers know once the editor is dropped
+            cx.defer(|_| {
+                if let Some(pm) = plugin_manager::global() {
+                    pm.write().on_editor_closed();
+                }
+            });
         }
         PanelEvent::TabChanged { active_index: _ } => {
             // Keep engine-level open-editor snapshot in sync.
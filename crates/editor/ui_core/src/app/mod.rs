@@ -98,6 +98,28 @@ impl PulsarApp {
         self.open_path(action.path.clone(), window, cx);
     }
 
+    fn on_open_recent(
+        &mut self,
+        _: &ui_common::menu::OpenRecent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.state.project_switcher_open {
+            self.toggle_project_switcher(window, cx);
+        }
+    }
+
+    fn on_clear_recent(
+        &mut self,
+        _: &ui_common::menu::ClearRecent,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) {
+        if let Some(ctx) = engine_state::EngineContext::global() {
+            ctx.clear_recent_projects();
+        }
+    }
+
     fn on_activate_open_editor(
         &mut self,
         action: &ActivateOpenEditor,
@@ -131,10 +153,14 @@ impl PulsarApp {
                 .clone()
                 .expect("Palette not initialized");
 
+            // Plugins may have been loaded or reloaded since the last open
+            palette.update(cx, |palette, cx| palette.sync_plugin_commands(cx));
+
             // Create or reuse view
             if let Some(view) = &self.state.command_palette_view {
                 // Reuse existing view - reset search state for fresh open
-                view.update(cx, |palette_view, _cx| {
+                view.update(cx, |palette_view, cx| {
+                    palette_view.delegate_mut().refresh(cx);
                     palette_view.reset_filter();
                 });
                 let input_handle = view.read(cx).search_input.read(cx).focus_handle(cx);
@@ -3,9 +3,14 @@
 //! This module uses the plugin system exclusively - NO match statements.
 //! All editors (built-in and plugin-based) are handled through the trait system.
 
-use gpui::{App, Context, Entity, Window};
+use gpui::{App, Context, Entity, ParentElement, Styled, Window};
+use plugin_editor_api::{EditorId, FileTypeId};
 use std::path::{Path, PathBuf};
-use ui::dock::{DockItem, TabPanel};
+use ui::{
+    button::{Button, ButtonVariants as _},
+    dock::{DockItem, TabPanel},
+    h_flex, v_flex, ContextModal, Sizable as _,
+};
 use ui_file_manager::FileSelected;
 
 use super::{open_editors::OpenEditorInfo, PulsarApp};
@@ -153,6 +158,12 @@ impl PulsarApp {
     pub fn open_path(&mut self, path: PathBuf, window: &mut Window, cx: &mut Context<Self>) {
         tracing::debug!("Opening path: {:?}", path);
 
+        // A file inside a folder-based asset opens (or finds) the whole asset
+        let path = plugin_manager::global()
+            .and_then(|pm| pm.read().file_type_registry().resolve_file_type(&path))
+            .map(|(_, root)| root)
+            .unwrap_or(path);
+
         if self.activate_open_editor_by_path(&path, window, cx) {
             tracing::debug!("Activated existing editor for: {:?}", path);
             self.refresh_open_editor_snapshot(cx);
@@ -162,7 +173,7 @@ impl PulsarApp {
         // Update plugin manager with current project root
         if let Some(pm_lock) = plugin_manager::global() {
             let mut pm = pm_lock.write();
-            pm.set_project_root(self.state.project_path.clone());
+            self.prepare_plugin_manager(&mut pm);
 
             // Let the plugin system handle everything - no match statements needed!
             match pm.create_editor_for_file(&path, window, cx) {
@@ -173,13 +184,160 @@ impl PulsarApp {
                     });
                     self.refresh_open_editor_snapshot(cx);
                 }
+                Err(plugin_manager::PluginManagerError::MultipleEditorsAvailable {
+                    file_type_id,
+                    editors,
+                }) => {
+                    let choices = editors
+                        .into_iter()
+                        .map(|editor_id| {
+                            let name = pm
+                                .editor_registry()
+                                .get_editor(&editor_id)
+                                .map(|editor| editor.display_name.clone())
+                                .unwrap_or_else(|| editor_id.to_string());
+                            (editor_id, name)
+                        })
+                        .collect();
+                    drop(pm);
+                    self.show_open_with_picker(path, file_type_id, choices, window, cx);
+                }
                 Err(e) => {
                     tracing::error!("Failed to open file {:?}: {}", path, e);
+
+                    // Point at a plugin that would handle the file if it were loaded
+                    if matches!(
+                        e,
+                        plugin_manager::PluginManagerError::NoFileTypeForPath { .. }
+                            | plugin_manager::PluginManagerError::NoEditorForFileType { .. }
+                    ) {
+                        if let Some(suggestion) = pm.suggest_plugins_for_path(&path).first() {
+                            window.push_notification(
+                                ui::notification::Notification::warning("Plugin Not Loaded")
+                                    .message(suggestion.message(&path)),
+                                cx,
+                            );
+                        }
+                    }
                 }
             }
         }
     }
 
+    /// Open a path with a specific editor, optionally making it the default
+    /// for the file's type.
+    pub fn open_path_with(
+        &mut self,
+        path: PathBuf,
+        editor_id: &EditorId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(pm_lock) = plugin_manager::global() else {
+            return;
+        };
+        let mut pm = pm_lock.write();
+        self.prepare_plugin_manager(&mut pm);
+
+        match pm.create_editor_for_file_with(&path, editor_id, window, cx) {
+            Ok(panel) => {
+                self.state.center_tabs.update(cx, |tabs, cx| {
+                    tabs.add_panel(panel, window, cx);
+                });
+                self.refresh_open_editor_snapshot(cx);
+            }
+            Err(e) => {
+                tracing::error!("Failed to open file {:?} with {}: {}", path, editor_id, e);
+            }
+        }
+    }
+
+    /// Update the plugin manager with the project root and the open mode
+    /// before creating an editor.
+    fn prepare_plugin_manager(&self, pm: &mut plugin_manager::PluginManager) {
+        pm.set_project_root(self.state.project_path.clone());
+
+        // Reviewers in a multiuser session get read-only editors
+        let read_only = engine_state::EngineContext::global()
+            .and_then(|ctx| ctx.multiuser())
+            .is_some_and(|multiuser| multiuser.is_read_only());
+        pm.set_editor_open_mode(if read_only {
+            plugin_manager::EditorOpenMode::ReadOnly
+        } else {
+            plugin_manager::EditorOpenMode::Editable
+        });
+    }
+
+    /// Ask which of several editors should open `path`.
+    ///
+    /// Each choice can be used once or made the default for the file type.
+    fn show_open_with_picker(
+        &mut self,
+        path: PathBuf,
+        file_type_id: FileTypeId,
+        choices: Vec<(EditorId, String)>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let app = cx.entity().downgrade();
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string());
+
+        window.open_modal(cx, move |modal, _window, _cx| {
+            let rows = choices.iter().enumerate().map(|(ix, (editor_id, name))| {
+                let open = |make_default: bool| {
+                    let app = app.clone();
+                    let path = path.clone();
+                    let file_type_id = file_type_id.clone();
+                    let editor_id = editor_id.clone();
+                    move |_: &gpui::ClickEvent, window: &mut Window, cx: &mut App| {
+                        window.close_modal(cx);
+                        if make_default {
+                            if let Some(pm) = plugin_manager::global() {
+                                if let Err(e) = pm
+                                    .write()
+                                    .set_default_editor(file_type_id.clone(), editor_id.clone())
+                                {
+                                    tracing::error!("Failed to save default editor: {}", e);
+                                }
+                            }
+                        }
+                        let _ = app.update(cx, |app, cx| {
+                            app.open_path_with(path.clone(), &editor_id, window, cx);
+                        });
+                    }
+                };
+
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .justify_between()
+                    .child(
+                        Button::new(("open-with", ix))
+                            .ghost()
+                            .justify_start()
+                            .label(name.clone())
+                            .on_click(open(false)),
+                    )
+                    .child(
+                        Button::new(("open-with-default", ix))
+                            .xsmall()
+                            .ghost()
+                            .label("Always Use")
+                            .tooltip("Open these files with this editor from now on")
+                            .on_click(open(true)),
+                    )
+            });
+
+            modal
+                .title(format!("Open {} With", file_name))
+                .show_close(true)
+                .child(v_flex().w_full().gap_1().children(rows))
+        });
+    }
+
     /// Handle file selected events from the file manager.
     pub fn handle_file_selected_from_external_window(
         &mut self,
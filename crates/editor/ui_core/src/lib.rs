@@ -23,6 +23,7 @@ pub mod builtin_editors;
 pub mod custom_providers;
 pub mod project_switcher;
 pub mod root;
+pub mod ui_scale;
 
 // Re-export main types
 pub use app::PulsarApp;
@@ -30,8 +31,8 @@ pub use root::PulsarRoot;
 
 // Re-export actions
 pub use actions::{
-    ActivateOpenEditor, OpenFile, ToggleAgentChat, ToggleCommandPalette, ToggleFileManager,
-    ToggleMultiplayer, ToggleProblems,
+    ActivateOpenEditor, OpenFile, ResetZoom, ToggleAgentChat, ToggleCommandPalette,
+    ToggleFileManager, ToggleMultiplayer, ToggleProblems, ZoomIn, ZoomOut,
 };
 
 // Re-export file_utils from ui_common
@@ -63,10 +64,28 @@ pub fn init(cx: &mut gpui::App) {
     cx.bind_keys([
         gpui::KeyBinding::new::<ToggleCommandPalette>("alt-space", ToggleCommandPalette {}, None),
         gpui::KeyBinding::new::<ToggleFileManager>("ctrl-space", ToggleFileManager {}, None),
+        gpui::KeyBinding::new::<ZoomIn>("ctrl-=", ZoomIn {}, None),
+        gpui::KeyBinding::new::<ZoomIn>("cmd-=", ZoomIn {}, None),
+        gpui::KeyBinding::new::<ZoomOut>("ctrl--", ZoomOut {}, None),
+        gpui::KeyBinding::new::<ZoomOut>("cmd--", ZoomOut {}, None),
+        gpui::KeyBinding::new::<ResetZoom>("ctrl-0", ResetZoom {}, None),
+        gpui::KeyBinding::new::<ResetZoom>("cmd-0", ResetZoom {}, None),
     ]);
 
+    // Per-window zoom, restored from settings for every window the WindowManager opens.
+    ui_scale::init(cx);
+    cx.on_action(|_: &ZoomIn, cx| ui_scale::zoom_active_window(window_manager::ZoomStep::In, cx));
+    cx.on_action(|_: &ZoomOut, cx| {
+        ui_scale::zoom_active_window(window_manager::ZoomStep::Out, cx)
+    });
+    cx.on_action(|_: &ResetZoom, cx| {
+        ui_scale::zoom_active_window(window_manager::ZoomStep::Reset, cx)
+    });
+
     // File-browser shortcuts (Ctrl/Cmd + C/X/V/A), scoped to the file manager focus.
     ui_file_manager::init(cx);
+    // Documentation back/forward (Alt + Left/Right), scoped to the docs window.
+    ui_documentation::init(cx);
 
     cx.on_action(|_: &Settings, cx| {
         tracing::debug!("[MENU] Settings");
@@ -1,25 +1,31 @@
 //! Project Switcher - uses GenericPalette for searchable project selection
 
-use directories::ProjectDirs;
+use engine_state::EngineContext;
 use gpui::{Context, DismissEvent, EventEmitter};
-use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
 use ui::IconName;
 use ui_common::command_palette::{GenericPalette, PaletteDelegate, PaletteItem};
 
-/// Recent project metadata
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A recent project as listed by the switcher
+#[derive(Debug, Clone)]
 pub struct RecentProject {
     pub name: String,
     pub path: String,
-    pub last_opened: Option<String>,
     pub is_git: bool,
+    pub pinned: bool,
+    /// The project directory no longer exists
+    pub missing: bool,
 }
 
-/// Recent projects list from disk
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct RecentProjectsList {
-    pub projects: Vec<RecentProject>,
+impl From<engine_state::RecentProject> for RecentProject {
+    fn from(project: engine_state::RecentProject) -> Self {
+        Self {
+            name: project.name,
+            path: project.path.to_string_lossy().into_owned(),
+            is_git: project.is_git,
+            pinned: project.pinned,
+            missing: project.missing,
+        }
+    }
 }
 
 impl PaletteItem for RecentProject {
@@ -32,7 +38,9 @@ impl PaletteItem for RecentProject {
     }
 
     fn icon(&self) -> IconName {
-        if self.is_git {
+        if self.missing {
+            IconName::WarningTriangle
+        } else if self.is_git {
             IconName::GitBranch
         } else {
             IconName::Folder
@@ -44,25 +52,6 @@ impl PaletteItem for RecentProject {
     }
 }
 
-impl RecentProjectsList {
-    pub fn load(path: &std::path::Path) -> Self {
-        use ui_common::file_utils;
-        file_utils::read_json(path).unwrap_or_default()
-    }
-
-    pub fn get_recent_projects_path() -> Option<PathBuf> {
-        ProjectDirs::from("com", "Pulsar", "Pulsar_Engine")
-            .map(|proj| proj.data_dir().join("recent_projects.json"))
-    }
-
-    pub fn load_from_default_location() -> Self {
-        Self::get_recent_projects_path()
-            .as_ref()
-            .map(|p| Self::load(p.as_path()))
-            .unwrap_or_default()
-    }
-}
-
 /// Event emitted when a project is selected
 #[derive(Clone)]
 pub struct ProjectSelected {
@@ -77,9 +66,11 @@ pub struct ProjectSwitcherDelegate {
 
 impl ProjectSwitcherDelegate {
     pub fn new() -> Self {
-        let list = RecentProjectsList::load_from_default_location();
+        let projects = EngineContext::global()
+            .map(|ctx| ctx.recent_projects())
+            .unwrap_or_default();
         Self {
-            projects: list.projects,
+            projects: projects.into_iter().map(RecentProject::from).collect(),
             selected_project: None,
         }
     }
@@ -93,12 +84,21 @@ impl PaletteDelegate for ProjectSwitcherDelegate {
     }
 
     fn categories(&self) -> Vec<(String, Vec<Self::Item>)> {
-        // All projects in one "Recent Projects" category
-        vec![("Recent Projects".to_string(), self.projects.clone())]
+        let (pinned, recent): (Vec<_>, Vec<_>) =
+            self.projects.iter().cloned().partition(|p| p.pinned);
+        [("Pinned", pinned), ("Recent Projects", recent)]
+            .into_iter()
+            .filter(|(_, projects)| !projects.is_empty())
+            .map(|(name, projects)| (name.to_string(), projects))
+            .collect()
     }
 
     fn confirm(&mut self, item: &Self::Item) {
-        self.selected_project = Some(item.clone());
+        // A missing project can't be opened; leave it for the entry screen
+        // to remove.
+        if !item.missing {
+            self.selected_project = Some(item.clone());
+        }
     }
 
     fn categories_collapsed_by_default(&self) -> bool {
@@ -236,6 +236,7 @@ impl Render for PulsarRoot {
 impl Render for EditorWindowShell {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let vp = window.viewport_size();
+        let scale = crate::ui_scale::scale_for_window(window, cx);
 
         div()
             .size_full()
@@ -245,6 +246,27 @@ impl Render for EditorWindowShell {
                     .child(self.title_bar.clone())
                     .child(div().flex_1().overflow_hidden().child(self.content.clone())),
             )
+            .when(scale != window_manager::ui_scale::DEFAULT_UI_SCALE, |this| {
+                // Zoom indicator; clicking resets to the default scale
+                this.child(
+                    div()
+                        .id("ui-scale-indicator")
+                        .absolute()
+                        .top(px(6.))
+                        .right(px(140.))
+                        .px_2()
+                        .py_0p5()
+                        .rounded(px(4.))
+                        .text_xs()
+                        .bg(cx.theme().secondary)
+                        .text_color(cx.theme().muted_foreground)
+                        .cursor_pointer()
+                        .child(format!("{}%", window_manager::ui_scale::scale_percent(scale)))
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(Box::new(crate::actions::ResetZoom), cx);
+                        }),
+                )
+            })
             .when(self.show_multiplayer, |this| {
                 this.child(
                     deferred(
@@ -0,0 +1,112 @@
+//! Window zoom: keyboard shortcuts, persistence and live application.
+//!
+//! The scale math and cross-DLL sharing live in [`window_manager::ui_scale`]; this
+//! module connects them to the editor settings and the active window.
+
+use engine_state::{ConfigValue, GlobalSettings};
+use gpui::{App, UpdateGlobal as _};
+use window_manager::ui_scale::{self, WindowScaleSettings, ZoomStep};
+use window_manager::WindowManager;
+
+const OWNER: &str = "appearance";
+const DEFAULT_KEY: &str = "ui_scale";
+const OVERRIDES_KEY: &str = "window_ui_scales";
+
+/// Load the stored scales and install them as a global, so every window opened
+/// through the [`WindowManager`] starts at its saved zoom.
+pub fn init(cx: &mut App) {
+    let settings = GlobalSettings::new();
+    let default_scale = settings
+        .get(OWNER, DEFAULT_KEY)
+        .and_then(|v| v.as_float().ok())
+        .unwrap_or(ui_scale::DEFAULT_UI_SCALE as f64) as f32;
+    let overrides = settings
+        .get(OWNER, OVERRIDES_KEY)
+        .and_then(|v| v.as_str().ok().map(str::to_owned))
+        .unwrap_or_default();
+
+    let scales = WindowScaleSettings::new(default_scale).with_overrides_from_str(&overrides);
+    ui_scale::publish(scales.default_scale);
+    cx.set_global(scales);
+}
+
+/// Zoom the active window in, out, or back to the default, and remember it for
+/// that window type. Scales are stored per type, so every open window of the
+/// type is zoomed with it.
+pub fn zoom_active_window(step: ZoomStep, cx: &mut App) {
+    let Some(handle) = cx.active_window() else {
+        return;
+    };
+    let window_type = window_type_for(handle, cx);
+
+    let scales = cx.default_global::<WindowScaleSettings>();
+    let old_scale = scales.scale_for(window_type);
+    let new_scale = match step {
+        ZoomStep::Reset => scales.default_scale,
+        step => ui_scale::step_scale(old_scale, step),
+    };
+    if new_scale == old_scale {
+        return;
+    }
+
+    WindowScaleSettings::update_global(cx, |scales, _| {
+        scales.set_scale_for(window_type, new_scale);
+    });
+    let mut handles: Vec<_> = cx
+        .try_global::<WindowManager>()
+        .map(|wm| wm.windows_of_kind(window_type))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|info| info.handle)
+        .collect();
+    if !handles.contains(&handle) {
+        handles.push(handle);
+    }
+    for handle in handles {
+        let _ = handle.update(cx, |_, window, _| {
+            ui_scale::apply_to_window(window, new_scale);
+        });
+    }
+    ui_scale::publish(new_scale);
+
+    tracing::debug!(
+        "UI scale for {} window: {}% -> {}%",
+        window_type,
+        ui_scale::scale_percent(old_scale),
+        ui_scale::scale_percent(new_scale)
+    );
+    save(cx);
+}
+
+/// Current scale of `window`, for display in the window chrome.
+pub fn scale_for_window(window: &gpui::Window, cx: &App) -> f32 {
+    let window_type = window_type_for(window.window_handle(), cx);
+    cx.try_global::<WindowScaleSettings>()
+        .map(|scales| scales.scale_for(window_type))
+        .unwrap_or(ui_scale::DEFAULT_UI_SCALE)
+}
+
+fn window_type_for(handle: gpui::AnyWindowHandle, cx: &App) -> &'static str {
+    cx.try_global::<WindowManager>()
+        .and_then(|wm| wm.window_info_for_handle(handle))
+        .map(|info| info.window_type.kind_name())
+        .unwrap_or("Main")
+}
+
+fn save(cx: &App) {
+    let Some(scales) = cx.try_global::<WindowScaleSettings>() else {
+        return;
+    };
+    let settings = GlobalSettings::new();
+    if let Err(e) = settings.set(
+        OWNER,
+        OVERRIDES_KEY,
+        ConfigValue::String(scales.overrides_to_string()),
+    ) {
+        tracing::warn!("Failed to store window zoom levels: {e:?}");
+        return;
+    }
+    if let Err(e) = settings.save_all() {
+        tracing::warn!("Failed to save window zoom levels: {e:?}");
+    }
+}
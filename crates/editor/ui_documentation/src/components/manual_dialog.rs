@@ -0,0 +1,414 @@
+use gpui::{prelude::*, *};
+use std::path::Path;
+use ui::{
+    ActiveTheme, Icon, IconName, Sizable, StyledExt,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    input::TextInput,
+    scroll::ScrollbarAxis,
+    v_flex,
+};
+
+use crate::DocumentationWindow;
+use crate::handlers;
+use crate::utils::{ManualDocsDialog, PendingSwitch, UnsavedChoice};
+
+pub fn render_manual_dialog(
+    window: &DocumentationWindow,
+    dialog: &ManualDocsDialog,
+    theme: &ui::ThemeColor,
+    cx: &mut Context<DocumentationWindow>,
+) -> impl IntoElement {
+    let (title, icon, body, footer) = match dialog {
+        ManualDocsDialog::NewFolder { parent } => (
+            "New Folder".to_string(),
+            IconName::FolderPlus,
+            new_folder_body(window, parent, theme).into_any_element(),
+            new_folder_footer(window, theme, cx).into_any_element(),
+        ),
+        ManualDocsDialog::Move { path } => (
+            format!("Move {}", entry_name(path)),
+            IconName::Folder,
+            move_body(window, path, theme, cx).into_any_element(),
+            cancel_footer(theme, cx).into_any_element(),
+        ),
+        ManualDocsDialog::Delete { path } => (
+            format!("Delete {}", entry_name(path)),
+            IconName::Trash,
+            delete_body(path, theme).into_any_element(),
+            delete_footer(theme, cx).into_any_element(),
+        ),
+        ManualDocsDialog::UnsavedChanges { pending } => (
+            "Unsaved Changes".to_string(),
+            IconName::TriangleAlert,
+            unsaved_body(window, pending, theme).into_any_element(),
+            unsaved_footer(theme, cx).into_any_element(),
+        ),
+    };
+
+    div()
+        .absolute()
+        .inset_0()
+        .flex()
+        .items_center()
+        .justify_center()
+        .bg(gpui::black().opacity(0.6))
+        .on_mouse_down(
+            gpui::MouseButton::Left,
+            cx.listener(|this, _, _, cx| {
+                handlers::close_manual_dialog(this);
+                cx.notify();
+            }),
+        )
+        .child(
+            div()
+                .w(px(480.0))
+                .bg(theme.background)
+                .border_1()
+                .border_color(theme.border)
+                .rounded_xl()
+                .shadow_2xl()
+                .overflow_hidden()
+                .on_mouse_down(gpui::MouseButton::Left, |_event, _phase, cx| {
+                    cx.stop_propagation();
+                })
+                .child(
+                    v_flex()
+                        .child(dialog_header(title, icon, theme, cx))
+                        .child(body)
+                        .child(footer),
+                ),
+        )
+}
+
+fn entry_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// `path` as shown to the user: relative to the docs folder's parent
+fn display_folder(window: &DocumentationWindow, path: &Path) -> String {
+    window
+        .manual_docs
+        .docs_folder
+        .as_ref()
+        .and_then(|docs| docs.parent())
+        .and_then(|root| path.strip_prefix(root).ok())
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn dialog_header(
+    title: String,
+    icon: IconName,
+    theme: &ui::ThemeColor,
+    cx: &mut Context<DocumentationWindow>,
+) -> impl IntoElement {
+    h_flex()
+        .w_full()
+        .h(px(56.0))
+        .px_6()
+        .items_center()
+        .justify_between()
+        .bg(theme.sidebar)
+        .border_b_1()
+        .border_color(theme.border)
+        .child(
+            h_flex()
+                .gap_3()
+                .items_center()
+                .child(Icon::new(icon).size_4())
+                .child(
+                    div()
+                        .text_base()
+                        .font_weight(gpui::FontWeight::SEMIBOLD)
+                        .text_color(theme.foreground)
+                        .child(title),
+                ),
+        )
+        .child(
+            Button::new("close-manual-dialog")
+                .icon(IconName::Close)
+                .ghost()
+                .xsmall()
+                .on_click(cx.listener(|this, _, _, cx| {
+                    handlers::close_manual_dialog(this);
+                    cx.notify();
+                })),
+        )
+}
+
+fn new_folder_body(
+    window: &DocumentationWindow,
+    parent: &Path,
+    theme: &ui::ThemeColor,
+) -> impl IntoElement {
+    let error = window.manual_docs.entry_name_error.clone();
+
+    v_flex().w_full().p_6().gap_2().child(
+        v_flex()
+            .gap_2()
+            .child(
+                div()
+                    .text_sm()
+                    .font_weight(gpui::FontWeight::MEDIUM)
+                    .text_color(theme.foreground)
+                    .child("Folder Name"),
+            )
+            .child(
+                TextInput::new(&window.manual_docs.entry_name_input_state)
+                    .w_full()
+                    .appearance(true)
+                    .bordered(true),
+            )
+            .child(match error {
+                Some(error) => div().text_xs().text_color(theme.danger).child(error),
+                None => div()
+                    .text_xs()
+                    .text_color(theme.muted_foreground)
+                    .child(format!("Created in {}", display_folder(window, parent))),
+            }),
+    )
+}
+
+fn new_folder_footer(
+    window: &DocumentationWindow,
+    theme: &ui::ThemeColor,
+    cx: &mut Context<DocumentationWindow>,
+) -> impl IntoElement {
+    let can_create = window.manual_docs.entry_name_error.is_none();
+
+    footer(theme).child(cancel_button(cx)).child(
+        Button::new("create-folder")
+            .label("Create Folder")
+            .icon(IconName::FolderPlus)
+            .primary()
+            .disabled(!can_create)
+            .on_click(cx.listener(|this, _, _, cx| {
+                handlers::create_folder(this, cx);
+                cx.notify();
+            })),
+    )
+}
+
+fn move_body(
+    window: &DocumentationWindow,
+    path: &Path,
+    theme: &ui::ThemeColor,
+    cx: &mut Context<DocumentationWindow>,
+) -> impl IntoElement {
+    let current_parent = path.parent();
+    let rows: Vec<AnyElement> = window
+        .manual_docs
+        .all_folders()
+        .into_iter()
+        .enumerate()
+        .map(|(index, folder)| {
+            let label = display_folder(window, &folder);
+            let is_current = current_parent == Some(folder.as_path());
+            let problem = match window.manual_docs.validate_move(path, &folder) {
+                _ if is_current => Some("Current folder".to_string()),
+                Ok(_) => None,
+                Err(e) => Some(e.to_string()),
+            };
+            let enabled = problem.is_none();
+            let source = path.to_path_buf();
+
+            h_flex()
+                .id(("move-target", index))
+                .w_full()
+                .px_3()
+                .py_2()
+                .gap_2()
+                .items_center()
+                .rounded(px(6.0))
+                .when(enabled, |row| {
+                    row.cursor_pointer()
+                        .hover(|s| s.bg(theme.accent.opacity(0.1)))
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            handlers::move_entry(this, source.clone(), folder.clone());
+                            cx.notify();
+                        }))
+                })
+                .child(Icon::new(IconName::Folder).size_4().text_color(if enabled {
+                    theme.foreground
+                } else {
+                    theme.muted_foreground
+                }))
+                .child(
+                    div()
+                        .flex_1()
+                        .text_sm()
+                        .text_color(if enabled {
+                            theme.foreground
+                        } else {
+                            theme.muted_foreground
+                        })
+                        .child(label),
+                )
+                .when_some(problem, |row, problem| {
+                    row.child(
+                        div()
+                            .text_xs()
+                            .text_color(theme.muted_foreground)
+                            .child(problem),
+                    )
+                })
+                .into_any_element()
+        })
+        .collect();
+
+    v_flex()
+        .w_full()
+        .p_4()
+        .gap_2()
+        .child(
+            div()
+                .px_2()
+                .text_sm()
+                .text_color(theme.muted_foreground)
+                .child("Choose a folder, or drag the file onto one in the sidebar."),
+        )
+        .child(
+            div().h(px(280.0)).child(
+                v_flex()
+                    .size_full()
+                    .gap_px()
+                    .scrollable(ScrollbarAxis::Vertical)
+                    .children(rows),
+            ),
+        )
+}
+
+fn delete_body(path: &Path, theme: &ui::ThemeColor) -> impl IntoElement {
+    let name = entry_name(path);
+    let message = if path.is_dir() {
+        format!(
+            "'{}' and everything in it will be permanently deleted.",
+            name
+        )
+    } else {
+        format!("'{}' will be permanently deleted.", name)
+    };
+
+    v_flex()
+        .w_full()
+        .p_6()
+        .gap_2()
+        .child(div().text_sm().text_color(theme.foreground).child(message))
+        .child(
+            div()
+                .text_xs()
+                .text_color(theme.muted_foreground)
+                .child("Open files are closed, including unsaved changes."),
+        )
+}
+
+fn delete_footer(
+    theme: &ui::ThemeColor,
+    cx: &mut Context<DocumentationWindow>,
+) -> impl IntoElement {
+    footer(theme).child(cancel_button(cx)).child(
+        Button::new("confirm-delete")
+            .label("Delete")
+            .icon(IconName::Trash)
+            .danger()
+            .on_click(cx.listener(|this, _, window, cx| {
+                handlers::confirm_delete(this, window, cx);
+                cx.notify();
+            })),
+    )
+}
+
+fn unsaved_body(
+    window: &DocumentationWindow,
+    pending: &PendingSwitch,
+    theme: &ui::ThemeColor,
+) -> impl IntoElement {
+    let name = window
+        .manual_docs
+        .selected_file
+        .as_deref()
+        .map(entry_name)
+        .unwrap_or_default();
+    let next = match pending {
+        PendingSwitch::File(path) => format!("opening '{}'", entry_name(path)),
+        PendingSwitch::Category(category) => format!("switching to {}", category.label()),
+    };
+
+    v_flex()
+        .w_full()
+        .p_6()
+        .gap_2()
+        .child(
+            div()
+                .text_sm()
+                .text_color(theme.foreground)
+                .child(format!("'{}' has unsaved changes.", name)),
+        )
+        .child(
+            div()
+                .text_xs()
+                .text_color(theme.muted_foreground)
+                .child(format!("Save them before {}?", next)),
+        )
+}
+
+fn unsaved_footer(
+    theme: &ui::ThemeColor,
+    cx: &mut Context<DocumentationWindow>,
+) -> impl IntoElement {
+    footer(theme)
+        .child(cancel_button(cx))
+        .child(
+            Button::new("discard-changes")
+                .label("Discard")
+                .danger()
+                .on_click(cx.listener(|this, _, window, cx| {
+                    handlers::resolve_unsaved_changes(this, UnsavedChoice::Discard, window, cx);
+                    cx.notify();
+                })),
+        )
+        .child(
+            Button::new("save-changes")
+                .label("Save")
+                .icon(IconName::Check)
+                .primary()
+                .on_click(cx.listener(|this, _, window, cx| {
+                    handlers::resolve_unsaved_changes(this, UnsavedChoice::Save, window, cx);
+                    cx.notify();
+                })),
+        )
+}
+
+fn cancel_footer(
+    theme: &ui::ThemeColor,
+    cx: &mut Context<DocumentationWindow>,
+) -> impl IntoElement {
+    footer(theme).child(cancel_button(cx))
+}
+
+fn footer(theme: &ui::ThemeColor) -> Div {
+    h_flex()
+        .w_full()
+        .h(px(64.0))
+        .px_6()
+        .items_center()
+        .gap_3()
+        .justify_end()
+        .bg(theme.sidebar.opacity(0.5))
+        .border_t_1()
+        .border_color(theme.border)
+}
+
+fn cancel_button(cx: &mut Context<DocumentationWindow>) -> Button {
+    Button::new("cancel-manual-dialog")
+        .label("Cancel")
+        .ghost()
+        .on_click(cx.listener(|this, _, _, cx| {
+            handlers::close_manual_dialog(this);
+            cx.notify();
+        }))
+}
@@ -1,5 +1,11 @@
+pub(crate) mod manual_dialog;
 pub(crate) mod new_file_dialog;
+pub(crate) mod outline;
 pub(crate) mod panels;
+pub(crate) mod search_results;
 
+pub use manual_dialog::render_manual_dialog;
 pub use new_file_dialog::render_new_file_dialog;
+pub use outline::{render_outline, render_sectioned_markdown};
 pub use panels::*;
+pub use search_results::render_search_results;
@@ -0,0 +1,159 @@
+use crate::utils::DocOutlineState;
+use gpui::{prelude::*, *};
+use ui::{
+    ActiveTheme, IconName, Sizable, StyledExt,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    scroll::{Scrollbar, ScrollbarAxis},
+    text::TextView,
+    v_flex,
+};
+
+/// Markdown rendered one heading section per child of the `id` scroll
+/// container, so the outline can scroll straight to a section.
+/// `configure` customises each section's [`TextView`].
+pub fn render_sectioned_markdown(
+    id: &'static str,
+    outline: &DocOutlineState,
+    max_width: Pixels,
+    configure: impl Fn(TextView) -> TextView,
+    window: &mut Window,
+    cx: &mut App,
+) -> impl IntoElement {
+    let sections: Vec<AnyElement> = outline
+        .outline
+        .sections
+        .iter()
+        .enumerate()
+        .map(|(index, markdown)| {
+            div()
+                .w_full()
+                .max_w(max_width)
+                .mx_auto()
+                .px_8()
+                .child(configure(
+                    TextView::markdown((id, index), markdown.clone(), window, cx).selectable(),
+                ))
+                .into_any_element()
+        })
+        .collect();
+
+    div()
+        .relative()
+        .size_full()
+        .child(
+            div()
+                .id(id)
+                .size_full()
+                .py_8()
+                .overflow_y_scroll()
+                .track_scroll(&outline.scroll_handle)
+                .children(sections),
+        )
+        .child(div().absolute().inset_0().child(Scrollbar::vertical(
+            &outline.scroll_state,
+            &outline.scroll_handle,
+        )))
+}
+
+/// "On this page" gutter listing the page's headings. Nothing is shown for
+/// pages without headings.
+pub fn render_outline(
+    outline: &DocOutlineState,
+    on_toggle: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static,
+    cx: &App,
+) -> Option<AnyElement> {
+    let headings = &outline.outline.headings;
+    let top_level = headings.iter().map(|h| h.level).min()?;
+    let theme = cx.theme();
+    let collapsed = outline.collapsed;
+
+    let rows: Vec<AnyElement> = headings
+        .iter()
+        .map(|heading| {
+            let handle = outline.scroll_handle.clone();
+            let section = heading.section;
+            let indent = (heading.level - top_level) as f32 * 12.0;
+
+            div()
+                .id(SharedString::from(format!("outline-{}", heading.anchor)))
+                .w_full()
+                .pl(px(8.0 + indent))
+                .pr_2()
+                .py_1()
+                .rounded(px(4.0))
+                .cursor_pointer()
+                .hover(|style| style.bg(theme.accent.opacity(0.1)))
+                .text_xs()
+                .text_color(if heading.level == top_level {
+                    theme.foreground
+                } else {
+                    theme.muted_foreground
+                })
+                .truncate()
+                .child(heading.text.clone())
+                .on_click(move |_event, window, _cx| {
+                    handle.scroll_to_item(section);
+                    window.refresh();
+                })
+                .into_any_element()
+        })
+        .collect();
+
+    Some(
+        v_flex()
+            .h_full()
+            .flex_none()
+            .w(if collapsed { px(36.0) } else { px(220.0) })
+            .border_l_1()
+            .border_color(theme.border)
+            .bg(theme.sidebar.opacity(0.4))
+            .child(
+                h_flex()
+                    .w_full()
+                    .h(px(40.0))
+                    .px_2()
+                    .items_center()
+                    .justify_between()
+                    .when(!collapsed, |this| {
+                        this.child(
+                            div()
+                                .pl_2()
+                                .text_xs()
+                                .font_weight(gpui::FontWeight::SEMIBOLD)
+                                .text_color(theme.muted_foreground)
+                                .child("On this page"),
+                        )
+                    })
+                    .child(
+                        Button::new("outline-toggle")
+                            .icon(if collapsed {
+                                IconName::ChevronLeft
+                            } else {
+                                IconName::ChevronRight
+                            })
+                            .ghost()
+                            .xsmall()
+                            .tooltip(if collapsed {
+                                "Show Outline"
+                            } else {
+                                "Hide Outline"
+                            })
+                            .on_click(on_toggle),
+                    ),
+            )
+            .when(!collapsed, |this| {
+                this.child(
+                    div().flex_1().overflow_hidden().child(
+                        v_flex()
+                            .size_full()
+                            .px_1()
+                            .pb_4()
+                            .scrollable(ScrollbarAxis::Vertical)
+                            .children(rows),
+                    ),
+                )
+            })
+            .into_any_element(),
+    )
+}
@@ -1,12 +1,16 @@
+use crate::components::{render_outline, render_sectioned_markdown};
 use crate::utils::{EngineDocsState, TreeNode};
 use gpui::{prelude::*, *};
+use pulsar_docs::SearchHit;
+use std::rc::Rc;
 use ui::{
-    ActiveTheme, Icon, IconName, StyledExt, h_flex,
+    ActiveTheme, Icon, IconName, Sizable, StyledExt,
+    button::Button,
+    h_flex,
     hierarchical_tree::{render_tree_category, render_tree_folder, render_tree_item, tree_colors},
     input::TextInput,
     resizable::{ResizableState, h_resizable, resizable_panel},
     scroll::ScrollbarAxis,
-    text::TextView,
     v_flex,
 };
 
@@ -23,6 +27,9 @@ impl EngineDocsPanel {
         sidebar_resizable: Entity<ResizableState>,
         on_toggle_expansion: impl Fn(&mut V, String, &mut Window, &mut Context<V>) + 'static + Clone,
         on_load_content: impl Fn(&mut V, String, &mut Window, &mut Context<V>) + 'static + Clone,
+        on_link_click: impl Fn(&mut V, String, &mut Window, &mut Context<V>) + 'static,
+        on_toggle_outline: impl Fn(&mut V, &mut Window, &mut Context<V>) + 'static,
+        on_locate_docs: impl Fn(&mut V, &mut Window, &mut Context<V>) + 'static,
         window: &mut Window,
         cx: &mut Context<V>,
     ) -> impl IntoElement
@@ -30,50 +37,75 @@ impl EngineDocsPanel {
         V: 'static + Render,
     {
         let breadcrumb_parts = Self::render_breadcrumbs(state);
-        let markdown = state.markdown_content.clone();
 
         let theme = cx.theme().clone();
 
-        let visible_items: Vec<_> = state
-            .flat_visible_items
-            .iter()
-            .map(|&idx| state.tree_items[idx].clone())
-            .collect();
+        let tree_nodes: Vec<AnyElement> = if state.is_searching() {
+            state
+                .search_results
+                .iter()
+                .enumerate()
+                .map(|(index, hit)| {
+                    Self::render_search_hit(index, hit, state, on_load_content.clone(), &theme, cx)
+                })
+                .collect()
+        } else {
+            let visible_items: Vec<_> = state
+                .flat_visible_items
+                .iter()
+                .map(|&idx| state.tree_items[idx].clone())
+                .collect();
 
-        let tree_nodes: Vec<AnyElement> = visible_items
-            .iter()
-            .map(|node| {
-                Self::render_tree_node(
-                    node,
-                    state,
-                    on_toggle_expansion.clone(),
-                    on_load_content.clone(),
-                    cx,
-                )
-            })
-            .collect();
+            visible_items
+                .iter()
+                .map(|node| {
+                    Self::render_tree_node(
+                        node,
+                        state,
+                        on_toggle_expansion.clone(),
+                        on_load_content.clone(),
+                        cx,
+                    )
+                })
+                .collect()
+        };
 
         h_resizable("docs-horizontal")
             .state(sidebar_resizable)
             .child(
                 resizable_panel()
                     .size(px(280.0))
-                    .child(Self::render_sidebar(state, tree_nodes, &theme)),
+                    .child(Self::render_sidebar(
+                        state,
+                        tree_nodes,
+                        on_locate_docs,
+                        &theme,
+                        cx,
+                    )),
             )
             .child(resizable_panel().child(Self::render_content(
+                state,
                 breadcrumb_parts,
-                markdown,
+                on_link_click,
+                on_toggle_outline,
                 window,
                 cx,
                 &theme,
             )))
     }
 
-    fn render_sidebar(
+    fn render_sidebar<V>(
         state: &EngineDocsState,
         tree_nodes: Vec<AnyElement>,
+        on_locate_docs: impl Fn(&mut V, &mut Window, &mut Context<V>) + 'static,
         theme: &ui::ThemeColor,
-    ) -> impl IntoElement {
+        cx: &mut Context<V>,
+    ) -> impl IntoElement
+    where
+        V: 'static + Render,
+    {
+        let docs_missing = state.tree_items.is_empty();
+
         v_flex()
             .size_full()
             .bg(theme.sidebar.opacity(0.95))
@@ -145,18 +177,126 @@ impl EngineDocsPanel {
                         .gap_px()
                         .font_family("monospace")
                         .scrollable(ScrollbarAxis::Vertical)
+                        .when(state.is_searching() && tree_nodes.is_empty(), |this| {
+                            this.child(
+                                div()
+                                    .px_2()
+                                    .py_3()
+                                    .text_sm()
+                                    .text_color(theme.muted_foreground)
+                                    .child(format!(
+                                        "No documentation found matching \"{}\"",
+                                        state.search_query.trim()
+                                    )),
+                            )
+                        })
                         .children(tree_nodes),
                 ),
             )
+            .when(docs_missing, |this| {
+                this.child(
+                    v_flex()
+                        .w_full()
+                        .p_3()
+                        .gap_2()
+                        .border_t_1()
+                        .border_color(theme.border)
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(theme.muted_foreground)
+                                .child("No engine documentation is available in this build."),
+                        )
+                        .child(
+                            Button::new("locate-docs")
+                                .label("Locate documentation folder...")
+                                .icon(IconName::FolderOpen)
+                                .small()
+                                .w_full()
+                                .on_click(cx.listener(move |view, _event, window, cx| {
+                                    on_locate_docs(view, window, cx);
+                                })),
+                        ),
+                )
+            })
     }
 
-    fn render_content(
+    fn render_search_hit<V>(
+        index: usize,
+        hit: &SearchHit,
+        state: &EngineDocsState,
+        on_load_content: impl Fn(&mut V, String, &mut Window, &mut Context<V>) + 'static,
+        theme: &ui::ThemeColor,
+        cx: &mut Context<V>,
+    ) -> AnyElement
+    where
+        V: 'static + Render,
+    {
+        let is_selected = state.current_path.as_deref() == Some(hit.path.as_str());
+        let path = hit.path.clone();
+
+        v_flex()
+            .id(("engine-search-hit", index))
+            .w_full()
+            .px_2()
+            .py_1()
+            .rounded(px(4.0))
+            .cursor_pointer()
+            .when(is_selected, |this| this.bg(theme.accent.opacity(0.15)))
+            .hover(|style| style.bg(theme.accent.opacity(0.08)))
+            .on_click(cx.listener(move |view, _event, window, cx| {
+                on_load_content(view, path.clone(), window, cx);
+            }))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(theme.foreground)
+                            .child(hit.name.clone()),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(theme.muted_foreground)
+                            .truncate()
+                            .child(format!("{} · {}", hit.crate_name, hit.section)),
+                    ),
+            )
+            .when_some(hit.doc_summary.clone(), |this, summary| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(theme.muted_foreground)
+                        .truncate()
+                        .child(summary),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn render_content<V>(
+        state: &EngineDocsState,
         breadcrumb_parts: Option<Vec<String>>,
-        markdown: String,
+        on_link_click: impl Fn(&mut V, String, &mut Window, &mut Context<V>) + 'static,
+        on_toggle_outline: impl Fn(&mut V, &mut Window, &mut Context<V>) + 'static,
         window: &mut Window,
-        cx: &mut App,
+        cx: &mut Context<V>,
         theme: &ui::ThemeColor,
-    ) -> impl IntoElement {
+    ) -> impl IntoElement
+    where
+        V: 'static + Render,
+    {
+        // Links resolve through the view so intra-doc paths stay in-window
+        let view = cx.entity().downgrade();
+        let on_link_click = Rc::new(on_link_click);
+        let outline = render_outline(
+            &state.outline,
+            cx.listener(move |view, _event, window, cx| on_toggle_outline(view, window, cx)),
+            cx,
+        );
         div().size_full().bg(theme.background).child(
             v_flex()
                 .size_full()
@@ -167,20 +307,31 @@ impl EngineDocsPanel {
                     ))
                 })
                 .child(
-                    div().flex_1().overflow_hidden().child(
-                        div().size_full().scrollable(ScrollbarAxis::Vertical).child(
-                            div()
-                                .w_full()
-                                .max_w(px(1200.0))
-                                .mx_auto()
-                                .px_8()
-                                .py_8()
-                                .child(
-                                    TextView::markdown("docs-markdown", markdown, window, cx)
-                                        .selectable(),
-                                ),
-                        ),
-                    ),
+                    h_flex()
+                        .flex_1()
+                        .w_full()
+                        .overflow_hidden()
+                        .child(div().flex_1().h_full().overflow_hidden().child(
+                            render_sectioned_markdown(
+                                "engine-content-scroll",
+                                &state.outline,
+                                px(1200.0),
+                                |text| {
+                                    let view = view.clone();
+                                    let on_link_click = on_link_click.clone();
+                                    text.on_link_click(move |href, window, cx| {
+                                        if let Some(view) = view.upgrade() {
+                                            view.update(cx, |view, cx| {
+                                                on_link_click(view, href.to_string(), window, cx);
+                                            });
+                                        }
+                                    })
+                                },
+                                window,
+                                cx,
+                            ),
+                        ))
+                        .children(outline),
                 ),
         )
     }
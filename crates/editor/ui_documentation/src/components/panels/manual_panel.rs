@@ -1,5 +1,8 @@
+use crate::components::{render_outline, render_sectioned_markdown};
+use crate::utils::actions::{DeleteDocEntry, MoveDocEntry, NewDocFolder, RenameDocEntry};
 use crate::utils::{FileEntry, ManualDocsState, ViewMode};
 use gpui::{prelude::*, *};
+use std::path::PathBuf;
 use ui::render_tree_folder;
 use ui::{
     ActiveTheme, Icon, IconName, Sizable, StyledExt,
@@ -7,12 +10,42 @@ use ui::{
     h_flex,
     hierarchical_tree::tree_colors,
     input::TextInput,
+    menu::context_menu::ContextMenuExt,
+    popup_menu::PopupMenu,
     resizable::{ResizableState, h_resizable, resizable_panel},
     scroll::ScrollbarAxis,
-    text::TextView,
     v_flex,
 };
 
+/// Drag payload for moving manual docs between folders
+#[derive(Clone)]
+pub struct DraggedDocEntry {
+    pub path: PathBuf,
+    pub name: String,
+}
+
+impl Render for DraggedDocEntry {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        h_flex()
+            .gap_2()
+            .px_3()
+            .py_1()
+            .rounded(px(6.0))
+            .bg(theme.sidebar)
+            .border_1()
+            .border_color(theme.accent)
+            .shadow_sm()
+            .child(Icon::new(IconName::BookOpen).size_4())
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(theme.foreground)
+                    .child(self.name.clone()),
+            )
+    }
+}
+
 pub struct ManualDocsPanel;
 
 impl ManualDocsPanel {
@@ -26,7 +59,10 @@ impl ManualDocsPanel {
         sidebar_resizable: Entity<ResizableState>,
         on_new_file: impl Fn(&mut V, &gpui::ClickEvent, &mut Window, &mut Context<V>) + 'static,
         on_save_file: impl Fn(&mut V, &gpui::ClickEvent, &mut Window, &mut Context<V>) + 'static,
+        on_select_file: impl Fn(&mut V, PathBuf, &mut Window, &mut Context<V>) + 'static + Clone,
         on_mode_change: impl Fn(&mut V, ViewMode, &mut Window, &mut Context<V>) + 'static + Clone,
+        on_move_entry: impl Fn(&mut V, PathBuf, PathBuf, &mut Window, &mut Context<V>) + 'static + Clone,
+        on_toggle_outline: impl Fn(&mut V, &mut Window, &mut Context<V>) + 'static,
         window: &mut Window,
         cx: &mut Context<V>,
     ) -> impl IntoElement
@@ -41,7 +77,15 @@ impl ManualDocsPanel {
 
         let file_entries: Vec<AnyElement> = visible_files
             .into_iter()
-            .map(|entry| Self::render_file_entry(&entry, state, cx))
+            .map(|entry| {
+                Self::render_file_entry(
+                    &entry,
+                    state,
+                    on_select_file.clone(),
+                    on_move_entry.clone(),
+                    cx,
+                )
+            })
             .collect();
 
         let theme = cx.theme().clone();
@@ -58,7 +102,14 @@ impl ManualDocsPanel {
             .child(
                 resizable_panel()
                     .size(px(260.0))
-                    .child(Self::render_sidebar(file_entries, &theme, cx, on_new_file)),
+                    .child(Self::render_sidebar(
+                        file_entries,
+                        state.docs_folder.clone(),
+                        &theme,
+                        cx,
+                        on_new_file,
+                        on_move_entry,
+                    )),
             )
             .child(resizable_panel().child(Self::render_editor_area(
                 state,
@@ -66,6 +117,7 @@ impl ManualDocsPanel {
                 file_name,
                 on_save_file,
                 on_mode_change,
+                on_toggle_outline,
                 window,
                 &theme,
                 cx,
@@ -74,9 +126,11 @@ impl ManualDocsPanel {
 
     fn render_sidebar<V>(
         file_entries: Vec<AnyElement>,
+        docs_folder: Option<PathBuf>,
         theme: &ui::ThemeColor,
         cx: &mut Context<V>,
         on_new_file: impl Fn(&mut V, &gpui::ClickEvent, &mut Window, &mut Context<V>) + 'static,
+        on_move_entry: impl Fn(&mut V, PathBuf, PathBuf, &mut Window, &mut Context<V>) + 'static,
     ) -> impl IntoElement
     where
         V: 'static + Render,
@@ -136,13 +190,35 @@ impl ManualDocsPanel {
                     ),
             )
             .child(
-                div().flex_1().overflow_hidden().child(
-                    v_flex()
-                        .size_full()
-                        .py_2()
-                        .scrollable(ScrollbarAxis::Vertical)
-                        .children(file_entries),
-                ),
+                div()
+                    .id("manual-docs-files")
+                    .flex_1()
+                    .overflow_hidden()
+                    .drag_over::<DraggedDocEntry>(|style, _, _, cx| {
+                        style.bg(cx.theme().accent.opacity(0.06))
+                    })
+                    .on_drop(
+                        cx.listener(move |view, drag: &DraggedDocEntry, window, cx| {
+                            // Dropped between entries: move to the top level
+                            if let Some(docs_folder) = docs_folder.clone() {
+                                on_move_entry(view, drag.path.clone(), docs_folder, window, cx);
+                            }
+                        }),
+                    )
+                    .child(
+                        v_flex()
+                            .size_full()
+                            .py_2()
+                            .scrollable(ScrollbarAxis::Vertical)
+                            .children(file_entries),
+                    )
+                    .context_menu(|menu, _window, _cx| {
+                        menu.menu_with_icon(
+                            "New Folder".to_string(),
+                            Icon::new(IconName::FolderPlus),
+                            Box::new(NewDocFolder::default()),
+                        )
+                    }),
             )
     }
 
@@ -152,6 +228,7 @@ impl ManualDocsPanel {
         file_name: Option<String>,
         on_save_file: impl Fn(&mut V, &gpui::ClickEvent, &mut Window, &mut Context<V>) + 'static,
         on_mode_change: impl Fn(&mut V, ViewMode, &mut Window, &mut Context<V>) + 'static + Clone,
+        on_toggle_outline: impl Fn(&mut V, &mut Window, &mut Context<V>) + 'static,
         window: &mut Window,
         theme: &ui::ThemeColor,
         cx: &mut Context<V>,
@@ -199,7 +276,15 @@ impl ManualDocsPanel {
                                     .child(
                                         file_name.unwrap_or_else(|| "No file selected".to_string()),
                                     ),
-                            ),
+                            )
+                            .when(state.is_dirty(), |this| {
+                                this.child(
+                                    div()
+                                        .size_2()
+                                        .rounded_full()
+                                        .bg(theme.foreground.opacity(0.7)),
+                                )
+                            }),
                     )
                     .child(
                         h_flex()
@@ -287,16 +372,27 @@ impl ManualDocsPanel {
                                     .icon(IconName::Check)
                                     .ghost()
                                     .xsmall()
-                                    .tooltip("Save File")
+                                    .tooltip(if state.is_dirty() {
+                                        "Save File (unsaved changes)"
+                                    } else {
+                                        "Save File"
+                                    })
                                     .on_click(cx.listener(on_save_file)),
                             ),
                     ),
             )
-            .child(Self::render_view_mode_content(state, window, cx, theme))
+            .child(Self::render_view_mode_content(
+                state,
+                on_toggle_outline,
+                window,
+                cx,
+                theme,
+            ))
     }
 
     fn render_view_mode_content<V>(
         state: &ManualDocsState,
+        on_toggle_outline: impl Fn(&mut V, &mut Window, &mut Context<V>) + 'static,
         window: &mut Window,
         cx: &mut Context<V>,
         theme: &ui::ThemeColor,
@@ -305,8 +401,15 @@ impl ManualDocsPanel {
         V: 'static + Render,
     {
         let view_mode = state.view_mode;
-        let markdown = state.markdown_preview.clone();
         let editor_state = state.editor_input_state.clone();
+        let outline = match view_mode {
+            ViewMode::Editor => None,
+            ViewMode::Preview | ViewMode::Split => render_outline(
+                &state.outline,
+                cx.listener(move |view, _event, window, cx| on_toggle_outline(view, window, cx)),
+                cx,
+            ),
+        };
 
         div().flex_1().overflow_hidden().child(match view_mode {
             ViewMode::Editor => div()
@@ -321,31 +424,24 @@ impl ManualDocsPanel {
                         .bordered(true),
                 )
                 .into_any_element(),
-            ViewMode::Preview => div()
-                .size_full()
-                .bg(theme.background)
-                .overflow_hidden()
-                .child(
-                    div().size_full().scrollable(ScrollbarAxis::Vertical).child(
-                        div()
-                            .w_full()
-                            .max_w(px(1200.0))
-                            .mx_auto()
-                            .px_8()
-                            .py_8()
-                            .child(
-                                TextView::markdown(
-                                    "manual-docs-preview",
-                                    markdown.clone(),
-                                    window,
-                                    cx,
-                                )
-                                .debounce_ms(30)
-                                .selectable(),
-                            ),
-                    ),
-                )
-                .into_any_element(),
+            ViewMode::Preview => {
+                h_flex()
+                    .size_full()
+                    .bg(theme.background)
+                    .overflow_hidden()
+                    .child(div().flex_1().h_full().overflow_hidden().child(
+                        render_sectioned_markdown(
+                            "manual-preview-scroll",
+                            &state.outline,
+                            px(1200.0),
+                            |text| text.debounce_ms(30),
+                            window,
+                            cx,
+                        ),
+                    ))
+                    .children(outline)
+                    .into_any_element()
+            }
             ViewMode::Split => h_flex()
                 .size_full()
                 .child(
@@ -365,37 +461,161 @@ impl ManualDocsPanel {
                         ),
                 )
                 .child(
-                    div()
+                    h_flex()
                         .flex_1()
                         .size_full()
                         .bg(theme.background)
                         .overflow_hidden()
-                        .child(
-                            div().size_full().scrollable(ScrollbarAxis::Vertical).child(
-                                div()
-                                    .w_full()
-                                    .max_w(px(900.0))
-                                    .mx_auto()
-                                    .px_6()
-                                    .py_6()
-                                    .child(
-                                        TextView::markdown(
-                                            "manual-docs-preview-split",
-                                            markdown,
-                                            window,
-                                            cx,
-                                        )
-                                        .debounce_ms(30)
-                                        .selectable(),
-                                    ),
+                        .child(div().flex_1().h_full().overflow_hidden().child(
+                            render_sectioned_markdown(
+                                "manual-preview-split-scroll",
+                                &state.outline,
+                                px(900.0),
+                                |text| text.debounce_ms(30),
+                                window,
+                                cx,
                             ),
-                        ),
+                        ))
+                        .children(outline),
                 )
                 .into_any_element(),
         })
     }
 
     fn render_file_entry<V>(
+        entry: &FileEntry,
+        state: &ManualDocsState,
+        on_select_file: impl Fn(&mut V, PathBuf, &mut Window, &mut Context<V>) + 'static,
+        on_move_entry: impl Fn(&mut V, PathBuf, PathBuf, &mut Window, &mut Context<V>) + 'static,
+        cx: &mut Context<V>,
+    ) -> AnyElement
+    where
+        V: 'static + Render,
+    {
+        let is_renaming = state.renaming_entry.as_ref() == Some(&entry.path);
+        let row = if is_renaming {
+            Self::render_rename_row(entry, state, cx)
+        } else {
+            Self::render_entry_row(entry, state, cx)
+        };
+
+        let drag = DraggedDocEntry {
+            path: entry.path.clone(),
+            name: entry.name.clone(),
+        };
+        let mut container = div()
+            .id(SharedString::from(format!(
+                "doc-entry-{}",
+                entry.path.display()
+            )))
+            .on_drag(drag, |drag, _, _, cx| {
+                cx.stop_propagation();
+                cx.new(|_| drag.clone())
+            })
+            .child(row);
+        if !entry.is_directory && !is_renaming {
+            let path = entry.path.clone();
+            container = container.on_click(cx.listener(move |view, _event, window, cx| {
+                on_select_file(view, path.clone(), window, cx);
+            }));
+        }
+        if entry.is_directory {
+            let destination = entry.path.clone();
+            container = container
+                .drag_over::<DraggedDocEntry>(|style, _, _, cx| {
+                    style.bg(cx.theme().accent.opacity(0.2)).rounded(px(6.0))
+                })
+                .on_drop(
+                    cx.listener(move |view, drag: &DraggedDocEntry, window, cx| {
+                        cx.stop_propagation();
+                        on_move_entry(view, drag.path.clone(), destination.clone(), window, cx);
+                    }),
+                );
+        }
+
+        container
+            .context_menu(Self::entry_context_menu(entry))
+            .into_any_element()
+    }
+
+    fn entry_context_menu(
+        entry: &FileEntry,
+    ) -> impl Fn(PopupMenu, &mut Window, &mut Context<PopupMenu>) -> PopupMenu + 'static {
+        let item_path = entry.path.to_string_lossy().to_string();
+        // New folders from a file's menu go next to the file
+        let folder_path = if entry.is_directory {
+            item_path.clone()
+        } else {
+            entry
+                .path
+                .parent()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default()
+        };
+
+        move |menu, _window, _cx| {
+            menu.menu_with_icon(
+                "New Folder".to_string(),
+                Icon::new(IconName::FolderPlus),
+                Box::new(NewDocFolder {
+                    folder_path: folder_path.clone(),
+                }),
+            )
+            .separator()
+            .menu_with_icon(
+                "Rename".to_string(),
+                Icon::new(IconName::EditPencil),
+                Box::new(RenameDocEntry {
+                    item_path: item_path.clone(),
+                }),
+            )
+            .menu_with_icon(
+                "Move To...".to_string(),
+                Icon::new(IconName::Folder),
+                Box::new(MoveDocEntry {
+                    item_path: item_path.clone(),
+                }),
+            )
+            .separator()
+            .menu_with_icon(
+                "Delete".to_string(),
+                Icon::new(IconName::Trash),
+                Box::new(DeleteDocEntry {
+                    item_path: item_path.clone(),
+                }),
+            )
+        }
+    }
+
+    fn render_rename_row<V>(
+        entry: &FileEntry,
+        state: &ManualDocsState,
+        cx: &mut Context<V>,
+    ) -> AnyElement
+    where
+        V: 'static + Render,
+    {
+        let theme = cx.theme();
+        let indent = px(entry.depth as f32 * 16.0);
+
+        v_flex()
+            .gap_1()
+            .py_1()
+            .pl(indent + px(12.0))
+            .pr_3()
+            .mx_2()
+            .child(
+                TextInput::new(&state.entry_name_input_state)
+                    .xsmall()
+                    .w_full(),
+            )
+            .when_some(state.entry_name_error.clone(), |this, error| {
+                this.child(div().text_xs().text_color(theme.danger).child(error))
+            })
+            .into_any_element()
+    }
+
+    fn render_entry_row<V>(
         entry: &FileEntry,
         state: &ManualDocsState,
         cx: &mut Context<V>,
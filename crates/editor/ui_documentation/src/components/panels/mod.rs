@@ -3,5 +3,5 @@ mod manual_panel;
 mod project_panel;
 
 pub use engine_panel::EngineDocsPanel;
-pub use manual_panel::ManualDocsPanel;
+pub use manual_panel::{DraggedDocEntry, ManualDocsPanel};
 pub use project_panel::ProjectDocsPanel;
@@ -1,3 +1,4 @@
+use crate::components::{render_outline, render_sectioned_markdown};
 use crate::utils::{ProjectDocsState, ProjectTreeNode};
 use gpui::{prelude::*, *};
 use ui::{
@@ -6,7 +7,6 @@ use ui::{
     input::TextInput,
     resizable::{ResizableState, h_resizable, resizable_panel},
     scroll::ScrollbarAxis,
-    text::TextView,
     v_flex,
 };
 
@@ -23,14 +23,13 @@ impl ProjectDocsPanel {
         sidebar_resizable: Entity<ResizableState>,
         on_toggle_expansion: impl Fn(&mut V, String, &mut Window, &mut Context<V>) + 'static + Clone,
         on_load_content: impl Fn(&mut V, String, &mut Window, &mut Context<V>) + 'static + Clone,
+        on_toggle_outline: impl Fn(&mut V, &mut Window, &mut Context<V>) + 'static,
         window: &mut Window,
         cx: &mut Context<V>,
     ) -> impl IntoElement
     where
         V: 'static + Render,
     {
-        let markdown = state.markdown_content.clone();
-
         let theme = cx.theme().clone();
 
         let visible_items: Vec<_> = state
@@ -59,7 +58,13 @@ impl ProjectDocsPanel {
                     .size(px(280.0))
                     .child(Self::render_sidebar(state, tree_nodes, &theme)),
             )
-            .child(resizable_panel().child(Self::render_content(markdown, window, cx, &theme)))
+            .child(resizable_panel().child(Self::render_content(
+                state,
+                on_toggle_outline,
+                window,
+                cx,
+                &theme,
+            )))
     }
 
     fn render_sidebar(
@@ -143,26 +148,40 @@ impl ProjectDocsPanel {
             )
     }
 
-    fn render_content(
-        markdown: String,
+    fn render_content<V>(
+        state: &ProjectDocsState,
+        on_toggle_outline: impl Fn(&mut V, &mut Window, &mut Context<V>) + 'static,
         window: &mut Window,
-        cx: &mut App,
+        cx: &mut Context<V>,
         theme: &ui::ThemeColor,
-    ) -> impl IntoElement {
-        div().size_full().bg(theme.background).child(
-            div().size_full().scrollable(ScrollbarAxis::Vertical).child(
+    ) -> impl IntoElement
+    where
+        V: 'static + Render,
+    {
+        let outline = render_outline(
+            &state.outline,
+            cx.listener(move |view, _event, window, cx| on_toggle_outline(view, window, cx)),
+            cx,
+        );
+
+        h_flex()
+            .size_full()
+            .bg(theme.background)
+            .child(
                 div()
-                    .w_full()
-                    .max_w(px(1200.0))
-                    .mx_auto()
-                    .px_8()
-                    .py_8()
-                    .child(
-                        TextView::markdown("project-docs-markdown", markdown, window, cx)
-                            .selectable(),
-                    ),
-            ),
-        )
+                    .flex_1()
+                    .h_full()
+                    .overflow_hidden()
+                    .child(render_sectioned_markdown(
+                        "project-content-scroll",
+                        &state.outline,
+                        px(1200.0),
+                        |text| text,
+                        window,
+                        cx,
+                    )),
+            )
+            .children(outline)
     }
 
     fn render_tree_node<V>(
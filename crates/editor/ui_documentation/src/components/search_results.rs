@@ -0,0 +1,115 @@
+use gpui::{prelude::*, *};
+use ui::{ActiveTheme, Icon, IconName, StyledExt, h_flex, scroll::ScrollbarAxis, v_flex};
+
+use crate::DocumentationWindow;
+use crate::handlers;
+use crate::utils::DocCategory;
+
+pub fn render_search_results(
+    window: &DocumentationWindow,
+    theme: &ui::ThemeColor,
+    cx: &mut Context<DocumentationWindow>,
+) -> impl IntoElement {
+    let rows: Vec<AnyElement> = window
+        .search
+        .results
+        .iter()
+        .enumerate()
+        .filter_map(|(index, hit)| {
+            let doc = window.search.hit_doc(hit)?;
+            let icon = match doc.category {
+                DocCategory::Engine => IconName::Code,
+                DocCategory::Project => IconName::Folder,
+                DocCategory::Manual => IconName::BookOpen,
+            };
+            Some(
+                v_flex()
+                    .id(("search-result", index))
+                    .w_full()
+                    .px_4()
+                    .py_3()
+                    .gap_1()
+                    .rounded_lg()
+                    .cursor_pointer()
+                    .hover(|style| style.bg(theme.accent.opacity(0.08)))
+                    .on_click(cx.listener(move |this, _event, window, cx| {
+                        handlers::open_search_result(this, index, window, cx);
+                        cx.notify();
+                    }))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .items_center()
+                            .child(
+                                h_flex()
+                                    .gap_1()
+                                    .items_center()
+                                    .px_2()
+                                    .py(px(2.0))
+                                    .rounded(px(6.0))
+                                    .bg(theme.accent.opacity(0.12))
+                                    .text_xs()
+                                    .text_color(theme.foreground)
+                                    .child(Icon::new(icon).size_3())
+                                    .child(doc.category.label()),
+                            )
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .font_weight(gpui::FontWeight::SEMIBOLD)
+                                    .text_color(theme.foreground)
+                                    .child(doc.title.clone()),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(theme.muted_foreground)
+                                    .truncate()
+                                    .child(doc.path.clone()),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(theme.muted_foreground)
+                            .child(hit.snippet.clone()),
+                    )
+                    .into_any_element(),
+            )
+        })
+        .collect();
+
+    let summary = match rows.len() {
+        0 => format!(
+            "No documentation found matching \"{}\"",
+            window.search.query
+        ),
+        1 => "1 result".to_string(),
+        n => format!("{} results", n),
+    };
+
+    v_flex()
+        .size_full()
+        .bg(theme.background)
+        .child(
+            div()
+                .w_full()
+                .px_6()
+                .py_3()
+                .border_b_1()
+                .border_color(theme.border)
+                .text_sm()
+                .text_color(theme.muted_foreground)
+                .child(summary),
+        )
+        .child(
+            div().flex_1().overflow_hidden().child(
+                v_flex()
+                    .size_full()
+                    .p_2()
+                    .gap_px()
+                    .scrollable(ScrollbarAxis::Vertical)
+                    .children(rows),
+            ),
+        )
+}
@@ -1,6 +1,13 @@
 use crate::DocumentationWindow;
-use crate::utils::{DocCategory, ViewMode};
+use crate::utils::actions::{
+    DeleteDocEntry, MoveDocEntry, NavigateBack, NavigateForward, NewDocFolder, RenameDocEntry,
+};
+use crate::utils::{
+    DocCategory, DocLink, EngineDocsState, ManualDocsDialog, PendingSwitch, ProjectTreeNode,
+    TreeNode, UnsavedChoice, ViewMode, resolve_doc_link, resolve_unsaved, validate_entry_name,
+};
 use gpui::*;
+use std::path::PathBuf;
 
 pub fn refresh_current_category(window: &mut DocumentationWindow) {
     match window.current_category {
@@ -9,13 +16,185 @@ pub fn refresh_current_category(window: &mut DocumentationWindow) {
             window.engine_docs.expanded_paths.clear();
             window.engine_docs.load_documentation();
         }
+        DocCategory::Project => {
+            window.project_docs.refresh();
+        }
         DocCategory::Manual => {
             window.manual_docs.load_file_tree();
         }
-        _ => {}
+    }
+    invalidate_search(window);
+}
+
+/// Ask for a generated documentation folder and load the engine docs from it.
+pub fn locate_doc_folder(cx: &mut Context<DocumentationWindow>) {
+    cx.spawn(async move |this, cx| {
+        let Some(folder) = rfd::AsyncFileDialog::new()
+            .set_title("Locate documentation folder")
+            .pick_folder()
+            .await
+        else {
+            return;
+        };
+        let root = folder.path().to_path_buf();
+
+        let _ = cx.update(|cx| {
+            if let Some(this) = this.upgrade() {
+                this.update(cx, |this, cx| {
+                    this.engine_docs.set_doc_root(root);
+                    invalidate_search(this);
+                    cx.notify();
+                });
+            }
+        });
+    })
+    .detach();
+}
+
+/// Rebuild the search index on next use, refreshing any results on screen.
+pub fn invalidate_search(window: &mut DocumentationWindow) {
+    window.search.invalidate();
+    run_search(window);
+}
+
+pub fn run_search(window: &mut DocumentationWindow) {
+    window.search.run(
+        &window.engine_docs,
+        &window.project_docs,
+        &window.manual_docs,
+    );
+}
+
+/// Leave search, switch to the result's category and show it in the tree.
+pub fn open_search_result(
+    window: &mut DocumentationWindow,
+    result: usize,
+    window_handle: &mut Window,
+    cx: &mut App,
+) {
+    let Some(doc) = window
+        .search
+        .results
+        .get(result)
+        .and_then(|hit| window.search.hit_doc(hit))
+        .cloned()
+    else {
+        return;
+    };
+
+    window.search.query.clear();
+    window.search.results.clear();
+    window.search.input_state.update(cx, |input, cx| {
+        input.set_value("", window_handle, cx);
+    });
+    window.current_category = doc.category;
+
+    match doc.category {
+        DocCategory::Engine => open_engine_doc(window, doc.path),
+        DocCategory::Project => {
+            let project = &mut window.project_docs;
+            let category = project.tree_items.iter().find_map(|node| match node {
+                ProjectTreeNode::Item { category, path, .. } if *path == doc.path => {
+                    Some(category.clone())
+                }
+                _ => None,
+            });
+            if let Some(category) = category {
+                project.expanded_paths.insert(category);
+            }
+            project.rebuild_visible_list();
+            project.load_content(&doc.path);
+        }
+        DocCategory::Manual => {
+            let path = PathBuf::from(&doc.path);
+            let manual = &mut window.manual_docs;
+            if let Some(docs_folder) = manual.docs_folder.clone() {
+                for folder in path.ancestors().skip(1) {
+                    if folder == docs_folder || !folder.starts_with(&docs_folder) {
+                        break;
+                    }
+                    manual.expanded_folders.insert(folder.to_path_buf());
+                }
+            }
+            manual.load_file_tree();
+            request_switch(window, PendingSwitch::File(path), window_handle, cx);
+        }
+    }
+}
+
+/// Show an engine doc page and record it in the navigation history.
+pub fn open_engine_doc(window: &mut DocumentationWindow, path: String) {
+    window.history.push(path.clone());
+    show_engine_doc(window, &path);
+}
+
+fn show_engine_doc(window: &mut DocumentationWindow, path: &str) {
+    window.current_category = DocCategory::Engine;
+    reveal_engine_item(&mut window.engine_docs, path);
+    window.engine_docs.load_content(path);
+}
+
+/// Expand the sidebar down to the item at `path`.
+fn reveal_engine_item(engine: &mut EngineDocsState, path: &str) {
+    let location = engine.tree_items.iter().find_map(|node| match node {
+        TreeNode::Item {
+            crate_name,
+            section_name,
+            path: item_path,
+            ..
+        } if item_path == path => Some((crate_name.clone(), section_name.clone())),
+        _ => None,
+    });
+    if let Some((crate_name, section_name)) = location {
+        engine
+            .expanded_paths
+            .insert(format!("{}/{}", crate_name, section_name));
+        engine.expanded_paths.insert(crate_name);
+        engine.rebuild_visible_list();
+    }
+}
+
+/// Follow a link clicked in rendered engine docs.
+pub fn open_doc_link(window: &mut DocumentationWindow, href: &str, cx: &mut App) {
+    match resolve_doc_link(window.engine_docs.current_path.as_deref(), href) {
+        Some(DocLink::Page(path)) => open_engine_doc(window, path),
+        Some(DocLink::External(url)) => cx.open_url(&url),
+        None => {}
     }
 }
 
+pub fn navigate_back(window: &mut DocumentationWindow) {
+    if let Some(path) = window.history.back().map(str::to_string) {
+        show_engine_doc(window, &path);
+    }
+}
+
+pub fn navigate_forward(window: &mut DocumentationWindow) {
+    if let Some(path) = window.history.forward().map(str::to_string) {
+        show_engine_doc(window, &path);
+    }
+}
+
+pub fn on_navigate_back(
+    window: &mut DocumentationWindow,
+    _: &NavigateBack,
+    _: &mut Window,
+    cx: &mut Context<DocumentationWindow>,
+) {
+    navigate_back(window);
+    cx.notify();
+}
+
+pub fn on_navigate_forward(
+    window: &mut DocumentationWindow,
+    _: &NavigateForward,
+    _: &mut Window,
+    cx: &mut Context<DocumentationWindow>,
+) {
+    navigate_forward(window);
+    cx.notify();
+}
+
 pub fn open_new_file_dialog(window: &mut DocumentationWindow) {
     window.show_new_file_dialog = true;
 }
@@ -32,22 +211,264 @@ pub fn create_new_file(
 ) {
     if !window.new_file_name.is_empty() {
         let file_name = window.new_file_name.clone();
-        if let Err(e) = window.manual_docs.create_new_file(file_name, window_handle, cx) {
-            tracing::error!("Failed to create file: {}", e);
+        match window.manual_docs.create_new_file(file_name) {
+            Ok(Some(path)) => request_switch(window, PendingSwitch::File(path), window_handle, cx),
+            Ok(None) => {}
+            Err(e) => tracing::error!("Failed to create file: {}", e),
         }
         window.show_new_file_dialog = false;
         window.new_file_name.clear();
     }
 }
 
-pub fn save_current_file(window: &mut DocumentationWindow, window_handle: &mut Window, cx: &App) {
-    let _ = window.manual_docs.save_current_file(window_handle, cx);
+pub fn save_current_file(window: &mut DocumentationWindow, cx: &App) {
+    match window.manual_docs.save_current_file(cx) {
+        Ok(()) => invalidate_search(window),
+        Err(e) => tracing::error!("Failed to save file: {}", e),
+    }
+}
+
+/// Save the open manual doc if it has unsaved changes, unless the user is
+/// still deciding what to do with them. Returns whether anything was saved.
+pub fn autosave(window: &mut DocumentationWindow, cx: &App) -> bool {
+    let prompting = matches!(
+        window.manual_dialog,
+        Some(ManualDocsDialog::UnsavedChanges { .. })
+    );
+    if prompting || !window.manual_docs.is_dirty() {
+        return false;
+    }
+    save_current_file(window, cx);
+    true
+}
+
+/// Open a manual doc or change tab, first asking what to do with unsaved
+/// changes if that would leave the open doc.
+pub fn request_switch(
+    window: &mut DocumentationWindow,
+    switch: PendingSwitch,
+    window_handle: &mut Window,
+    cx: &mut App,
+) {
+    let manual = &window.manual_docs;
+    if manual.is_dirty() && switch.leaves(window.current_category, manual.selected_file.as_deref())
+    {
+        window.manual_docs.cancel_rename();
+        window.manual_dialog = Some(ManualDocsDialog::UnsavedChanges { pending: switch });
+        return;
+    }
+    apply_switch(window, switch, window_handle, cx);
+}
+
+fn apply_switch(
+    window: &mut DocumentationWindow,
+    switch: PendingSwitch,
+    window_handle: &mut Window,
+    cx: &mut App,
+) {
+    match switch {
+        PendingSwitch::File(path) => {
+            window.current_category = DocCategory::Manual;
+            window.manual_docs.select_file(path, window_handle, cx);
+        }
+        PendingSwitch::Category(category) => window.current_category = category,
+    }
+}
+
+/// Answer the unsaved changes prompt. A failed save leaves it open.
+pub fn resolve_unsaved_changes(
+    window: &mut DocumentationWindow,
+    choice: UnsavedChoice,
+    window_handle: &mut Window,
+    cx: &mut App,
+) {
+    let Some(ManualDocsDialog::UnsavedChanges { pending }) = window.manual_dialog.clone() else {
+        return;
+    };
+    let manual = &mut window.manual_docs;
+    match resolve_unsaved(pending, choice, || manual.save_current_file(cx)) {
+        Ok(switch) => {
+            match choice {
+                UnsavedChoice::Save => invalidate_search(window),
+                UnsavedChoice::Discard => window.manual_docs.revert_changes(window_handle, cx),
+            }
+            close_manual_dialog(window);
+            apply_switch(window, switch, window_handle, cx);
+        }
+        Err(e) => tracing::error!("Failed to save file: {}", e),
+    }
+}
+
+pub fn on_new_doc_folder(
+    window: &mut DocumentationWindow,
+    action: &NewDocFolder,
+    window_handle: &mut Window,
+    cx: &mut Context<DocumentationWindow>,
+) {
+    let parent = if action.folder_path.is_empty() {
+        window.manual_docs.docs_folder.clone()
+    } else {
+        Some(PathBuf::from(&action.folder_path))
+    };
+    let Some(parent) = parent else {
+        return;
+    };
+
+    window.manual_docs.cancel_rename();
+    window
+        .manual_docs
+        .entry_name_input_state
+        .update(cx, |input, cx| {
+            input.set_value("", window_handle, cx);
+        });
+    window.manual_dialog = Some(ManualDocsDialog::NewFolder { parent });
+    cx.notify();
+}
+
+pub fn on_rename_doc_entry(
+    window: &mut DocumentationWindow,
+    action: &RenameDocEntry,
+    window_handle: &mut Window,
+    cx: &mut Context<DocumentationWindow>,
+) {
+    window.manual_dialog = None;
+    window
+        .manual_docs
+        .begin_rename(PathBuf::from(&action.item_path), window_handle, cx);
+    cx.notify();
+}
+
+pub fn on_move_doc_entry(
+    window: &mut DocumentationWindow,
+    action: &MoveDocEntry,
+    _: &mut Window,
+    cx: &mut Context<DocumentationWindow>,
+) {
+    window.manual_docs.cancel_rename();
+    window.manual_dialog = Some(ManualDocsDialog::Move {
+        path: PathBuf::from(&action.item_path),
+    });
+    cx.notify();
+}
+
+pub fn on_delete_doc_entry(
+    window: &mut DocumentationWindow,
+    action: &DeleteDocEntry,
+    _: &mut Window,
+    cx: &mut Context<DocumentationWindow>,
+) {
+    window.manual_docs.cancel_rename();
+    window.manual_dialog = Some(ManualDocsDialog::Delete {
+        path: PathBuf::from(&action.item_path),
+    });
+    cx.notify();
+}
+
+/// Re-check the name typed for an inline rename or a new folder.
+pub fn validate_entry_name_input(window: &mut DocumentationWindow, cx: &App) {
+    let manual = &window.manual_docs;
+    let name = manual.entry_name_input_state.read(cx).value().to_string();
+    // Don't flag an empty name before anything has been typed
+    let result = if name.trim().is_empty() {
+        None
+    } else if let Some(path) = &manual.renaming_entry {
+        path.parent()
+            .map(|parent| validate_entry_name(parent, &name, path.is_dir(), Some(path)))
+    } else if let Some(ManualDocsDialog::NewFolder { parent }) = &window.manual_dialog {
+        Some(validate_entry_name(parent, &name, true, None))
+    } else {
+        None
+    };
+    window.manual_docs.entry_name_error = result.and_then(Result::err).map(|e| e.to_string());
+}
+
+/// Enter in the name input finishes whichever edit it belongs to.
+pub fn confirm_entry_name(window: &mut DocumentationWindow, cx: &App) {
+    if window.manual_docs.renaming_entry.is_some() {
+        commit_rename(window, cx);
+    } else if let Some(ManualDocsDialog::NewFolder { .. }) = window.manual_dialog {
+        create_folder(window, cx);
+    }
+}
+
+pub fn commit_rename(window: &mut DocumentationWindow, cx: &App) {
+    let Some(path) = window.manual_docs.renaming_entry.clone() else {
+        return;
+    };
+    let name = window
+        .manual_docs
+        .entry_name_input_state
+        .read(cx)
+        .value()
+        .to_string();
+    match window.manual_docs.rename_entry(&path, &name) {
+        Ok(_) => {
+            window.manual_docs.cancel_rename();
+            invalidate_search(window);
+        }
+        Err(e) => window.manual_docs.entry_name_error = Some(e.to_string()),
+    }
+}
+
+pub fn create_folder(window: &mut DocumentationWindow, cx: &App) {
+    let Some(ManualDocsDialog::NewFolder { parent }) = window.manual_dialog.clone() else {
+        return;
+    };
+    let name = window
+        .manual_docs
+        .entry_name_input_state
+        .read(cx)
+        .value()
+        .to_string();
+    match window.manual_docs.create_folder(&parent, &name) {
+        Ok(_) => close_manual_dialog(window),
+        Err(e) => window.manual_docs.entry_name_error = Some(e.to_string()),
+    }
+}
+
+pub fn move_entry(window: &mut DocumentationWindow, path: PathBuf, destination: PathBuf) {
+    match window.manual_docs.move_entry(&path, &destination) {
+        Ok(_) => {
+            close_manual_dialog(window);
+            invalidate_search(window);
+        }
+        Err(e) => tracing::error!("Failed to move {}: {}", path.display(), e),
+    }
+}
+
+pub fn confirm_delete(window: &mut DocumentationWindow, window_handle: &mut Window, cx: &mut App) {
+    let Some(ManualDocsDialog::Delete { path }) = window.manual_dialog.take() else {
+        return;
+    };
+    match window
+        .manual_docs
+        .delete_file(path.clone(), window_handle, cx)
+    {
+        Ok(()) => invalidate_search(window),
+        Err(e) => tracing::error!("Failed to delete {}: {}", path.display(), e),
+    }
+}
+
+pub fn close_manual_dialog(window: &mut DocumentationWindow) {
+    window.manual_dialog = None;
+    window.manual_docs.entry_name_error = None;
+}
+
+/// Escape abandons an inline rename or an open manual docs dialog.
+pub fn cancel_entry_edit(window: &mut DocumentationWindow) {
+    window.manual_docs.cancel_rename();
+    close_manual_dialog(window);
 }
 
 pub fn set_view_mode(window: &mut DocumentationWindow, mode: ViewMode) {
     window.manual_docs.set_view_mode(mode);
 }
 
-pub fn set_category(window: &mut DocumentationWindow, category: DocCategory) {
-    window.current_category = category;
+pub fn set_category(
+    window: &mut DocumentationWindow,
+    category: DocCategory,
+    window_handle: &mut Window,
+    cx: &mut App,
+) {
+    request_switch(window, PendingSwitch::Category(category), window_handle, cx);
 }
@@ -7,3 +7,14 @@ pub use screen::{
     DocumentationWindow, create_documentation_window, create_documentation_window_with_project,
 };
 pub use utils::doc_source::DocSource;
+
+/// Register the documentation window's back/forward shortcuts, scoped to the
+/// `DocumentationWindow` key context. Call once during app init.
+pub fn init(cx: &mut gpui::App) {
+    use crate::utils::actions::{NavigateBack, NavigateForward};
+    const CTX: Option<&str> = Some("DocumentationWindow");
+    cx.bind_keys([
+        gpui::KeyBinding::new("alt-left", NavigateBack, CTX),
+        gpui::KeyBinding::new("alt-right", NavigateForward, CTX),
+    ]);
+}
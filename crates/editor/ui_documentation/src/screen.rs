@@ -1,4 +1,5 @@
 use std::path::PathBuf;
+use std::time::Duration;
 
 use gpui::{prelude::*, *};
 use ui::{
@@ -14,7 +15,13 @@ use ui_common::translate;
 use crate::components;
 use crate::handlers;
 use crate::components::{EngineDocsPanel, ManualDocsPanel, ProjectDocsPanel};
-use crate::utils::{DocCategory, EngineDocsState, ManualDocsState, ProjectDocsState};
+use crate::utils::{
+    DocCategory, DocSearchState, EngineDocsState, ManualDocsDialog, ManualDocsState,
+    NavigationHistory, PendingSwitch, ProjectDocsState, autosave_interval,
+};
+
+/// How often to re-check the autosave setting while autosave is off
+const AUTOSAVE_POLL: Duration = Duration::from_secs(5);
 
 pub struct DocumentationWindow {
     pub(crate) focus_handle: FocusHandle,
@@ -25,6 +32,8 @@ pub struct DocumentationWindow {
     pub(crate) engine_docs: EngineDocsState,
     pub(crate) project_docs: ProjectDocsState,
     pub(crate) manual_docs: ManualDocsState,
+    pub(crate) search: DocSearchState,
+    pub(crate) history: NavigationHistory,
 
     pub(crate) engine_panel: EngineDocsPanel,
     pub(crate) project_panel: ProjectDocsPanel,
@@ -33,6 +42,8 @@ pub struct DocumentationWindow {
     pub(crate) new_file_name: String,
     pub(crate) new_file_input_state: Entity<InputState>,
     pub(crate) show_new_file_dialog: bool,
+    pub(crate) manual_dialog: Option<ManualDocsDialog>,
+    _autosave_task: Task<()>,
 }
 
 impl DocumentationWindow {
@@ -51,6 +62,7 @@ impl DocumentationWindow {
         let engine_docs = EngineDocsState::new(window, cx);
         let project_docs = ProjectDocsState::new(window, cx, project_root.clone());
         let manual_docs = ManualDocsState::new(window, cx, project_root.clone());
+        let search = DocSearchState::new(window, cx);
 
         let new_file_input_state = cx.new(|cx| {
             let mut state = InputState::new(window, cx);
@@ -63,7 +75,18 @@ impl DocumentationWindow {
             &engine_search_state,
             |this: &mut Self, state, _event: &ui::input::InputEvent, cx| {
                 this.engine_docs.search_query = state.read(cx).value().to_string();
-                this.engine_docs.rebuild_visible_list();
+                this.engine_docs.update_search();
+                cx.notify();
+            },
+        )
+        .detach();
+
+        let global_search_state = search.input_state.clone();
+        cx.subscribe(
+            &global_search_state,
+            |this: &mut Self, state, _event: &ui::input::InputEvent, cx| {
+                this.search.query = state.read(cx).value().to_string();
+                handlers::run_search(this);
                 cx.notify();
             },
         )
@@ -79,6 +102,42 @@ impl DocumentationWindow {
         )
         .detach();
 
+        let entry_name_state = manual_docs.entry_name_input_state.clone();
+        cx.subscribe(
+            &entry_name_state,
+            |this: &mut Self, _state, event: &ui::input::InputEvent, cx| {
+                match event {
+                    ui::input::InputEvent::PressEnter { .. } => {
+                        handlers::confirm_entry_name(this, cx)
+                    }
+                    _ => handlers::validate_entry_name_input(this, cx),
+                }
+                cx.notify();
+            },
+        )
+        .detach();
+
+        // Settings are re-read every tick, so changes apply without a restart
+        let autosave_task = cx.spawn(async move |this, cx| {
+            loop {
+                let interval = autosave_interval();
+                smol::Timer::after(interval.unwrap_or(AUTOSAVE_POLL)).await;
+                if interval.is_none() {
+                    continue;
+                }
+
+                let _ = cx.update(|cx| {
+                    if let Some(this) = this.upgrade() {
+                        this.update(cx, |this, cx| {
+                            if handlers::autosave(this, cx) {
+                                cx.notify();
+                            }
+                        });
+                    }
+                });
+            }
+        });
+
         let new_file_state = new_file_input_state.clone();
         cx.subscribe(
             &new_file_state,
@@ -97,12 +156,16 @@ impl DocumentationWindow {
             engine_docs,
             project_docs,
             manual_docs,
+            search,
+            history: NavigationHistory::default(),
             engine_panel: EngineDocsPanel::new(),
             project_panel: ProjectDocsPanel::new(),
             manual_panel: ManualDocsPanel::new(),
             new_file_name: String::new(),
             new_file_input_state,
             show_new_file_dialog: false,
+            manual_dialog: None,
+            _autosave_task: autosave_task,
         }
     }
 }
@@ -119,7 +182,18 @@ impl Render for DocumentationWindow {
         let current_category = self.current_category;
 
         v_flex()
+            .key_context("DocumentationWindow")
             .track_focus(&self.focus_handle)
+            .on_action(cx.listener(handlers::on_navigate_back))
+            .on_action(cx.listener(handlers::on_navigate_forward))
+            .on_action(cx.listener(handlers::on_new_doc_folder))
+            .on_action(cx.listener(handlers::on_rename_doc_entry))
+            .on_action(cx.listener(handlers::on_move_doc_entry))
+            .on_action(cx.listener(handlers::on_delete_doc_entry))
+            .on_action(cx.listener(|this, _: &ui::input::Escape, _window, cx| {
+                handlers::cancel_entry_edit(this);
+                cx.notify();
+            }))
             .size_full()
             .bg(theme.background)
             .child(TitleBar::new().child(translate("Window.Title.Documentation")))
@@ -134,6 +208,9 @@ impl Render for DocumentationWindow {
                     cx,
                 ))
             })
+            .when_some(self.manual_dialog.clone(), |this, dialog| {
+                this.child(components::render_manual_dialog(self, &dialog, &theme, cx))
+            })
     }
 }
 
@@ -157,6 +234,34 @@ impl DocumentationWindow {
                 h_flex()
                     .gap_4()
                     .items_center()
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                Button::new("docs-back")
+                                    .icon(IconName::ArrowLeft)
+                                    .ghost()
+                                    .xsmall()
+                                    .disabled(!self.history.can_go_back())
+                                    .tooltip("Back (Alt+Left)")
+                                    .on_click(cx.listener(|this, _event, _window, cx| {
+                                        handlers::navigate_back(this);
+                                        cx.notify();
+                                    })),
+                            )
+                            .child(
+                                Button::new("docs-forward")
+                                    .icon(IconName::ArrowRight)
+                                    .ghost()
+                                    .xsmall()
+                                    .disabled(!self.history.can_go_forward())
+                                    .tooltip("Forward (Alt+Right)")
+                                    .on_click(cx.listener(|this, _event, _window, cx| {
+                                        handlers::navigate_forward(this);
+                                        cx.notify();
+                                    })),
+                            ),
+                    )
                     .child(
                         div()
                             .w(px(36.0))
@@ -181,6 +286,19 @@ impl DocumentationWindow {
                             .child("Documentation"),
                     ),
             )
+            .child(
+                div().w(px(360.0)).child(
+                    TextInput::new(&self.search.input_state)
+                        .w_full()
+                        .prefix(
+                            Icon::new(IconName::Search)
+                                .size_4()
+                                .text_color(theme.secondary_foreground),
+                        )
+                        .appearance(true)
+                        .bordered(true),
+                ),
+            )
             .child(
                 Button::new("refresh-docs")
                     .icon(IconName::Refresh)
@@ -224,8 +342,8 @@ impl DocumentationWindow {
                                 btn.bg(theme.accent).text_color(theme.accent_foreground)
                             })
                             .when(current_category != DocCategory::Engine, |btn| btn.ghost())
-                            .on_click(cx.listener(|this, _event, _window, cx| {
-                                handlers::set_category(this, DocCategory::Engine);
+                            .on_click(cx.listener(|this, _event, window, cx| {
+                                handlers::set_category(this, DocCategory::Engine, window, cx);
                                 cx.notify();
                             })),
                     )
@@ -238,8 +356,8 @@ impl DocumentationWindow {
                                 btn.bg(theme.accent).text_color(theme.accent_foreground)
                             })
                             .when(current_category != DocCategory::Project, |btn| btn.ghost())
-                            .on_click(cx.listener(|this, _event, _window, cx| {
-                                handlers::set_category(this, DocCategory::Project);
+                            .on_click(cx.listener(|this, _event, window, cx| {
+                                handlers::set_category(this, DocCategory::Project, window, cx);
                                 cx.notify();
                             })),
                     )
@@ -252,8 +370,8 @@ impl DocumentationWindow {
                                 btn.bg(theme.accent).text_color(theme.accent_foreground)
                             })
                             .when(current_category != DocCategory::Manual, |btn| btn.ghost())
-                            .on_click(cx.listener(|this, _event, _window, cx| {
-                                handlers::set_category(this, DocCategory::Manual);
+                            .on_click(cx.listener(|this, _event, window, cx| {
+                                handlers::set_category(this, DocCategory::Manual, window, cx);
                                 cx.notify();
                             })),
                     ),
@@ -266,6 +384,29 @@ impl DocumentationWindow {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> impl IntoElement {
+        if self.search.is_active() {
+            let theme = cx.theme().clone();
+            return div()
+                .flex_1()
+                .overflow_hidden()
+                .child(components::render_search_results(self, &theme, cx));
+        }
+
+        match current_category {
+            DocCategory::Engine => {
+                let engine = &mut self.engine_docs;
+                engine.outline.sync(&engine.markdown_content);
+            }
+            DocCategory::Project => {
+                let project = &mut self.project_docs;
+                project.outline.sync(&project.markdown_content);
+            }
+            DocCategory::Manual => {
+                let manual = &mut self.manual_docs;
+                manual.outline.sync(&manual.markdown_preview);
+            }
+        }
+
         div()
             .flex_1()
             .overflow_hidden()
@@ -280,9 +421,20 @@ impl DocumentationWindow {
                             cx.notify();
                         },
                         |this: &mut Self, path, _window, cx| {
-                            this.engine_docs.load_content(&path);
+                            handlers::open_engine_doc(this, path);
                             cx.notify();
                         },
+                        |this: &mut Self, href, _window, cx| {
+                            handlers::open_doc_link(this, &href, cx);
+                            cx.notify();
+                        },
+                        |this: &mut Self, _window, cx| {
+                            this.engine_docs.outline.toggle_collapsed();
+                            cx.notify();
+                        },
+                        |_this: &mut Self, _window, cx| {
+                            handlers::locate_doc_folder(cx);
+                        },
                         window,
                         cx,
                     )
@@ -300,6 +452,10 @@ impl DocumentationWindow {
                             this.project_docs.load_content(&path);
                             cx.notify();
                         },
+                        |this: &mut Self, _window, cx| {
+                            this.project_docs.outline.toggle_collapsed();
+                            cx.notify();
+                        },
                         window,
                         cx,
                     )
@@ -313,14 +469,26 @@ impl DocumentationWindow {
                             handlers::open_new_file_dialog(this);
                             cx.notify();
                         },
-                        |this: &mut Self, _event, window, cx| {
-                            handlers::save_current_file(this, window, cx);
+                        |this: &mut Self, _event, _window, cx| {
+                            handlers::save_current_file(this, cx);
+                            cx.notify();
+                        },
+                        |this: &mut Self, path, window, cx| {
+                            handlers::request_switch(this, PendingSwitch::File(path), window, cx);
                             cx.notify();
                         },
                         |this: &mut Self, mode, _window, cx| {
                             handlers::set_view_mode(this, mode);
                             cx.notify();
                         },
+                        |this: &mut Self, path, destination, _window, cx| {
+                            handlers::move_entry(this, path, destination);
+                            cx.notify();
+                        },
+                        |this: &mut Self, _window, cx| {
+                            this.manual_docs.outline.toggle_collapsed();
+                            cx.notify();
+                        },
                         window,
                         cx,
                     )
@@ -0,0 +1,33 @@
+use gpui::*;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+actions!(documentation, [NavigateBack, NavigateForward]);
+
+#[derive(Action, Clone, Debug, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[action(namespace = documentation)]
+pub struct NewDocFolder {
+    #[serde(default)]
+    pub folder_path: String,
+}
+
+#[derive(Action, Clone, Debug, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[action(namespace = documentation)]
+pub struct RenameDocEntry {
+    #[serde(default)]
+    pub item_path: String,
+}
+
+#[derive(Action, Clone, Debug, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[action(namespace = documentation)]
+pub struct MoveDocEntry {
+    #[serde(default)]
+    pub item_path: String,
+}
+
+#[derive(Action, Clone, Debug, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[action(namespace = documentation)]
+pub struct DeleteDocEntry {
+    #[serde(default)]
+    pub item_path: String,
+}
@@ -1,9 +1,17 @@
 use crate::utils::doc_source::{DocSource, make_search_input};
+use crate::utils::outline::DocOutlineState;
 use gpui::*;
-use pulsar_docs::{CrateIndex, get_crate_index, get_doc_content, list_crates};
+use pulsar_docs::{
+    CrateIndex, SearchHit, get_crate_index, get_doc_content, list_crates, search,
+    set_external_doc_root,
+};
 use std::collections::HashSet;
+use std::path::PathBuf;
 use ui::input::InputState;
 
+/// Most hits listed for a sidebar search
+const SEARCH_LIMIT: usize = 100;
+
 #[derive(Clone, Debug)]
 pub enum TreeNode {
     Crate {
@@ -34,7 +42,10 @@ pub struct EngineDocsState {
     pub current_path: Option<String>,
     pub markdown_content: String,
     pub search_query: String,
+    /// Hits for `search_query`, listed in place of the tree while it is set
+    pub search_results: Vec<SearchHit>,
     pub search_input_state: Entity<InputState>,
+    pub outline: DocOutlineState,
 }
 
 impl DocSource for EngineDocsState {
@@ -59,7 +70,9 @@ impl EngineDocsState {
             current_path: None,
             markdown_content: Self::initial_content(),
             search_query: String::new(),
+            search_results: Vec::new(),
             search_input_state,
+            outline: DocOutlineState::default(),
         };
 
         state.load_documentation();
@@ -69,12 +82,18 @@ impl EngineDocsState {
     pub fn load_documentation(&mut self) {
         use pulsar_docs::docs_available;
 
+        self.tree_items.clear();
+
         if !docs_available() {
-            self.markdown_content = "# No Documentation Available\n\nDocumentation has not been generated yet. Build in release mode to generate docs.".to_string();
+            self.markdown_content = "# No Documentation Available\n\nDocumentation has not been generated yet. Build in release mode to generate docs, or use **Locate documentation folder...** to load a generated `target/doc` folder.".to_string();
+            self.rebuild_visible_list();
             return;
         }
 
-        self.tree_items.clear();
+        if self.current_path.is_none() {
+            self.markdown_content = Self::initial_content();
+        }
+
         let mut crates = list_crates();
         crates.sort();
 
@@ -119,63 +138,49 @@ impl EngineDocsState {
 
     pub fn rebuild_visible_list(&mut self) {
         self.flat_visible_items.clear();
-        let query = self.search_query.to_lowercase();
-        let is_searching = !query.is_empty();
 
         for (idx, node) in self.tree_items.iter().enumerate() {
-            match node {
-                TreeNode::Crate { name, .. } => {
-                    let matches = name.to_lowercase().contains(&query);
-                    if !is_searching || matches {
-                        self.flat_visible_items.push(idx);
-                    }
-                }
-                TreeNode::Section {
-                    crate_name,
-                    section_name,
-                    ..
-                } => {
-                    let matches = section_name.to_lowercase().contains(&query);
-                    let parent_expanded = self.expanded_paths.contains(crate_name);
-
-                    if is_searching && matches {
-                        self.expanded_paths.insert(crate_name.clone());
-                    }
-
-                    if parent_expanded || (is_searching && matches) {
-                        self.flat_visible_items.push(idx);
-                    }
-                }
+            let visible = match node {
+                TreeNode::Crate { .. } => true,
+                TreeNode::Section { crate_name, .. } => self.expanded_paths.contains(crate_name),
                 TreeNode::Item {
                     crate_name,
                     section_name,
-                    item_name,
                     ..
-                } => {
-                    let section_path = format!("{}/{}", crate_name, section_name);
-                    let matches = item_name.to_lowercase().contains(&query);
-                    let section_expanded = self.expanded_paths.contains(&section_path);
-
-                    if is_searching && matches {
-                        self.expanded_paths.insert(crate_name.clone());
-                        self.expanded_paths.insert(section_path.clone());
-                    }
-
-                    if section_expanded || (is_searching && matches) {
-                        self.flat_visible_items.push(idx);
-                    }
-                }
+                } => self
+                    .expanded_paths
+                    .contains(&format!("{}/{}", crate_name, section_name)),
+            };
+            if visible {
+                self.flat_visible_items.push(idx);
             }
         }
+    }
+
+    /// Load docs from a folder the user located, such as a local `target/doc`.
+    pub fn set_doc_root(&mut self, root: PathBuf) {
+        set_external_doc_root(root.clone());
+        self.expanded_paths.clear();
+        self.load_documentation();
+        self.update_search();
 
-        if is_searching && self.flat_visible_items.is_empty() {
+        if self.tree_items.is_empty() {
             self.markdown_content = format!(
-                "# No Results\n\nNo documentation found matching \"{}\".\n\nTry a different search term.",
-                self.search_query
+                "# No Documentation Found\n\nNo documentation was found in `{}`.\n\nChoose a folder with one subfolder per crate, each holding an `index.json`.",
+                root.display()
             );
         }
     }
 
+    pub fn is_searching(&self) -> bool {
+        !self.search_query.trim().is_empty()
+    }
+
+    /// Re-run the sidebar search for the current `search_query`.
+    pub fn update_search(&mut self) {
+        self.search_results = search(&self.search_query, SEARCH_LIMIT);
+    }
+
     pub fn toggle_expansion(&mut self, path: String) {
         if self.expanded_paths.contains(&path) {
             self.expanded_paths.remove(&path);
@@ -1,11 +1,138 @@
 use crate::utils::doc_source::DocSource;
+use crate::utils::outline::DocOutlineState;
 use gpui::{prelude::*, *};
 use regex::Regex;
 use std::collections::HashSet;
+use std::fmt;
 use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use ui::input::{InputState, TabSize};
 
+const INVALID_NAME_CHARS: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+const SETTINGS_OWNER: &str = "documentation";
+const DEFAULT_AUTOSAVE_SECS: f64 = 30.0;
+const MIN_AUTOSAVE_SECS: f64 = 5.0;
+
+/// How often manual docs autosave, or `None` when autosave is off
+pub fn autosave_interval() -> Option<Duration> {
+    let config = engine_state::global_config();
+    let enabled = config
+        .get(engine_state::NS_EDITOR, SETTINGS_OWNER, "manual_autosave")
+        .ok()
+        .and_then(|v| v.as_bool().ok())
+        .unwrap_or(false);
+    if !enabled {
+        return None;
+    }
+    let secs = config
+        .get(
+            engine_state::NS_EDITOR,
+            SETTINGS_OWNER,
+            "manual_autosave_interval_secs",
+        )
+        .ok()
+        .and_then(|v| v.as_float().ok())
+        .unwrap_or(DEFAULT_AUTOSAVE_SECS);
+    Some(Duration::from_secs_f64(secs.max(MIN_AUTOSAVE_SECS)))
+}
+
+/// Why an entry can't take a name or move to a folder
+#[derive(Clone, Debug, PartialEq)]
+pub enum EntryNameError {
+    Empty,
+    InvalidCharacter(char),
+    Hidden,
+    Reserved,
+    AlreadyExists(String),
+    IntoItself,
+}
+
+impl fmt::Display for EntryNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntryNameError::Empty => write!(f, "Name cannot be empty"),
+            EntryNameError::InvalidCharacter(c) => {
+                write!(f, "Name cannot contain '{}'", c.escape_default())
+            }
+            EntryNameError::Hidden => write!(f, "Name cannot start with '.'"),
+            EntryNameError::Reserved => write!(f, "Name is reserved by the operating system"),
+            EntryNameError::AlreadyExists(name) => write!(f, "'{}' already exists here", name),
+            EntryNameError::IntoItself => write!(f, "A folder cannot be moved into itself"),
+        }
+    }
+}
+
+impl std::error::Error for EntryNameError {}
+
+impl From<EntryNameError> for io::Error {
+    fn from(err: EntryNameError) -> Self {
+        let kind = match err {
+            EntryNameError::AlreadyExists(_) => io::ErrorKind::AlreadyExists,
+            _ => io::ErrorKind::InvalidInput,
+        };
+        io::Error::new(kind, err)
+    }
+}
+
+/// Path `name` takes inside `parent`. Files always get a `.md` extension.
+/// `existing` is the entry being renamed, which may keep its own name.
+pub fn validate_entry_name(
+    parent: &Path,
+    name: &str,
+    is_directory: bool,
+    existing: Option<&Path>,
+) -> Result<PathBuf, EntryNameError> {
+    let name = name.trim();
+    if name.is_empty() || name == "." || name == ".." {
+        return Err(EntryNameError::Empty);
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| INVALID_NAME_CHARS.contains(c) || c.is_control())
+    {
+        return Err(EntryNameError::InvalidCharacter(c));
+    }
+    if name.starts_with('.') {
+        return Err(EntryNameError::Hidden);
+    }
+    let stem = name.split('.').next().unwrap_or(name);
+    if RESERVED_NAMES
+        .iter()
+        .any(|reserved| stem.eq_ignore_ascii_case(reserved))
+    {
+        return Err(EntryNameError::Reserved);
+    }
+
+    let file_name = if is_directory || name.ends_with(".md") {
+        name.to_string()
+    } else {
+        format!("{}.md", name)
+    };
+    let path = parent.join(&file_name);
+    if path.exists() && existing != Some(path.as_path()) {
+        return Err(EntryNameError::AlreadyExists(file_name));
+    }
+    Ok(path)
+}
+
+/// Where `path` ends up once `from` is moved to `to`
+fn relocated_path(path: &Path, from: &Path, to: &Path) -> Option<PathBuf> {
+    path.strip_prefix(from).ok().map(|rest| {
+        if rest.as_os_str().is_empty() {
+            to.to_path_buf()
+        } else {
+            to.join(rest)
+        }
+    })
+}
+
 #[derive(Clone, Debug)]
 pub struct FileEntry {
     pub name: String,
@@ -29,9 +156,18 @@ pub struct ManualDocsState {
     pub expanded_folders: HashSet<PathBuf>,
     pub selected_file: Option<PathBuf>,
     pub current_markdown: String,
+    /// Contents of the selected file as last loaded or saved
+    pub saved_markdown: String,
     pub markdown_preview: String,
     pub editor_input_state: Entity<InputState>,
     pub view_mode: ViewMode,
+    /// Entry being renamed inline in the sidebar
+    pub renaming_entry: Option<PathBuf>,
+    /// Name input shared by inline rename and the new-folder dialog
+    pub entry_name_input_state: Entity<InputState>,
+    pub entry_name_error: Option<String>,
+    /// Outline of the rendered preview
+    pub outline: DocOutlineState,
 }
 
 impl DocSource for ManualDocsState {
@@ -64,6 +200,7 @@ impl ManualDocsState {
                 })
                 .soft_wrap(true)
         });
+        let entry_name_input_state = cx.new(|cx| InputState::new(window, cx));
 
         let mut state = Self {
             project_root: project_root.clone(),
@@ -73,9 +210,14 @@ impl ManualDocsState {
             expanded_folders: HashSet::new(),
             selected_file: None,
             current_markdown: String::new(),
+            saved_markdown: String::new(),
             markdown_preview: String::new(),
             editor_input_state,
             view_mode: ViewMode::Split,
+            renaming_entry: None,
+            entry_name_input_state,
+            entry_name_error: None,
+            outline: DocOutlineState::default(),
         };
 
         state.load_file_tree();
@@ -171,9 +313,25 @@ impl ManualDocsState {
             });
             self.current_markdown = content.clone();
             self.markdown_preview = self.resolve_image_urls(&content);
+            self.saved_markdown = content;
         }
     }
 
+    /// Whether the editor holds changes that aren't on disk yet
+    pub fn is_dirty(&self) -> bool {
+        self.selected_file.is_some() && self.current_markdown != self.saved_markdown
+    }
+
+    /// Throw away unsaved edits, going back to the last saved contents.
+    pub fn revert_changes(&mut self, window: &mut Window, cx: &mut App) {
+        let content = self.saved_markdown.clone();
+        self.editor_input_state.update(cx, |editor, cx| {
+            editor.set_value(content.clone(), window, cx);
+        });
+        self.markdown_preview = self.resolve_image_urls(&content);
+        self.current_markdown = content;
+    }
+
     pub fn update_preview(&mut self, cx: &App) {
         let content = self.editor_input_state.read(cx).value().to_string();
         self.current_markdown = content.clone();
@@ -220,11 +378,7 @@ impl ManualDocsState {
         .to_string()
     }
 
-    pub fn save_current_file(
-        &mut self,
-        _window: &mut Window,
-        cx: &App,
-    ) -> Result<(), std::io::Error> {
+    pub fn save_current_file(&mut self, cx: &App) -> Result<(), std::io::Error> {
         let Some(path) = &self.selected_file else {
             return Ok(());
         };
@@ -234,19 +388,17 @@ impl ManualDocsState {
 
         fs::write(path, &self.current_markdown)?;
 
-        self.markdown_preview = self.current_markdown.clone();
+        self.markdown_preview = self.resolve_image_urls(&content);
+        self.saved_markdown = content;
 
         Ok(())
     }
 
-    pub fn create_new_file(
-        &mut self,
-        name: String,
-        window: &mut Window,
-        cx: &mut App,
-    ) -> Result<(), std::io::Error> {
+    /// Create a doc in the docs folder, returning its path so the caller
+    /// can open it.
+    pub fn create_new_file(&mut self, name: String) -> Result<Option<PathBuf>, std::io::Error> {
         let Some(docs_folder) = &self.docs_folder else {
-            return Ok(());
+            return Ok(None);
         };
 
         let file_name = if name.ends_with(".md") {
@@ -269,29 +421,227 @@ impl ManualDocsState {
             format!("# {}\n\n", name.trim_end_matches(".md")),
         )?;
         self.load_file_tree();
-        self.select_file(file_path, window, cx);
 
-        Ok(())
+        Ok(Some(file_path))
     }
 
-    pub fn delete_file(&mut self, path: PathBuf) -> Result<(), std::io::Error> {
+    /// Delete a file or folder. If the open file goes with it, the editor
+    /// is cleared.
+    pub fn delete_file(
+        &mut self,
+        path: PathBuf,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Result<(), std::io::Error> {
         if path.is_dir() {
             fs::remove_dir_all(&path)?;
         } else {
             fs::remove_file(&path)?;
         }
 
-        if self.selected_file.as_ref() == Some(&path) {
-            self.selected_file = None;
-            self.current_markdown.clear();
-            self.markdown_preview.clear();
+        if self
+            .selected_file
+            .as_ref()
+            .is_some_and(|selected| selected.starts_with(&path))
+        {
+            self.clear_selection(window, cx);
+        }
+        self.expanded_folders
+            .retain(|folder| !folder.starts_with(&path));
+        if self
+            .renaming_entry
+            .as_ref()
+            .is_some_and(|entry| entry.starts_with(&path))
+        {
+            self.cancel_rename();
+        }
+
+        self.load_file_tree();
+        Ok(())
+    }
+
+    fn clear_selection(&mut self, window: &mut Window, cx: &mut App) {
+        self.selected_file = None;
+        self.current_markdown.clear();
+        self.saved_markdown.clear();
+        self.markdown_preview.clear();
+        self.editor_input_state.update(cx, |editor, cx| {
+            editor.set_value("", window, cx);
+        });
+    }
+
+    /// Create a folder inside `parent` and expand down to it.
+    pub fn create_folder(&mut self, parent: &Path, name: &str) -> Result<PathBuf, std::io::Error> {
+        let path = validate_entry_name(parent, name, true, None)?;
+        fs::create_dir(&path)?;
+        self.expanded_folders.insert(parent.to_path_buf());
+        self.load_file_tree();
+        Ok(path)
+    }
+
+    pub fn rename_entry(&mut self, path: &Path, name: &str) -> Result<PathBuf, std::io::Error> {
+        let parent = path.parent().ok_or(io::ErrorKind::InvalidInput)?;
+        let target = validate_entry_name(parent, name, path.is_dir(), Some(path))?;
+        self.relocate(path, &target)?;
+        Ok(target)
+    }
+
+    /// Where `path` would land in `destination`, or why it can't move there
+    pub fn validate_move(
+        &self,
+        path: &Path,
+        destination: &Path,
+    ) -> Result<PathBuf, EntryNameError> {
+        if destination.starts_with(path) {
+            return Err(EntryNameError::IntoItself);
+        }
+        let name = path
+            .file_name()
+            .ok_or(EntryNameError::Empty)?
+            .to_string_lossy();
+        validate_entry_name(destination, &name, path.is_dir(), Some(path))
+    }
+
+    pub fn move_entry(
+        &mut self,
+        path: &Path,
+        destination: &Path,
+    ) -> Result<PathBuf, std::io::Error> {
+        let target = self.validate_move(path, destination)?;
+        if target != path {
+            self.expanded_folders.insert(destination.to_path_buf());
+            self.relocate(path, &target)?;
+        }
+        Ok(target)
+    }
+
+    /// Rename on disk, keeping the open file and expanded folders pointing
+    /// at their new locations.
+    fn relocate(&mut self, from: &Path, to: &Path) -> Result<(), std::io::Error> {
+        fs::rename(from, to)?;
+
+        if let Some(path) = self
+            .selected_file
+            .as_deref()
+            .and_then(|selected| relocated_path(selected, from, to))
+        {
+            self.selected_file = Some(path);
         }
+        self.expanded_folders = self
+            .expanded_folders
+            .drain()
+            .map(|folder| relocated_path(&folder, from, to).unwrap_or(folder))
+            .collect();
 
         self.load_file_tree();
         Ok(())
     }
 
+    /// Every folder a file could be moved to, the docs folder first
+    pub fn all_folders(&self) -> Vec<PathBuf> {
+        fn collect(dir: &Path, folders: &mut Vec<PathBuf>) {
+            folders.push(dir.to_path_buf());
+            let Ok(entries) = fs::read_dir(dir) else {
+                return;
+            };
+            let mut entries: Vec<_> = entries
+                .filter_map(|e| e.ok())
+                .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
+                .map(|e| e.path())
+                .filter(|path| path.is_dir())
+                .collect();
+            entries.sort();
+            for entry in entries {
+                collect(&entry, folders);
+            }
+        }
+
+        let mut folders = Vec::new();
+        if let Some(docs_folder) = &self.docs_folder {
+            collect(docs_folder, &mut folders);
+        }
+        folders
+    }
+
+    pub fn begin_rename(&mut self, path: PathBuf, window: &mut Window, cx: &mut App) {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.entry_name_error = None;
+        self.renaming_entry = Some(path);
+        self.entry_name_input_state.update(cx, |input, cx| {
+            input.set_value(name, window, cx);
+        });
+    }
+
+    pub fn cancel_rename(&mut self) {
+        self.renaming_entry = None;
+        self.entry_name_error = None;
+    }
+
     pub fn set_view_mode(&mut self, mode: ViewMode) {
         self.view_mode = mode;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_entry_name() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("Guide.md"), "# Guide").unwrap();
+
+        assert_eq!(
+            validate_entry_name(dir.path(), "Setup", false, None),
+            Ok(dir.path().join("Setup.md"))
+        );
+        assert_eq!(
+            validate_entry_name(dir.path(), "Setup", true, None),
+            Ok(dir.path().join("Setup"))
+        );
+        assert_eq!(
+            validate_entry_name(dir.path(), "Guide", false, None),
+            Err(EntryNameError::AlreadyExists("Guide.md".to_string()))
+        );
+        // Renaming an entry to its own name is allowed
+        let guide = dir.path().join("Guide.md");
+        assert_eq!(
+            validate_entry_name(dir.path(), "Guide.md", false, Some(&guide)),
+            Ok(guide.clone())
+        );
+        assert_eq!(
+            validate_entry_name(dir.path(), "  ", false, None),
+            Err(EntryNameError::Empty)
+        );
+        assert_eq!(
+            validate_entry_name(dir.path(), "a/b", false, None),
+            Err(EntryNameError::InvalidCharacter('/'))
+        );
+        assert_eq!(
+            validate_entry_name(dir.path(), ".notes", true, None),
+            Err(EntryNameError::Hidden)
+        );
+        assert_eq!(
+            validate_entry_name(dir.path(), "con.md", false, None),
+            Err(EntryNameError::Reserved)
+        );
+    }
+
+    #[test]
+    fn test_relocated_path() {
+        let from = Path::new("/docs/guides");
+        let to = Path::new("/docs/archive/guides");
+        assert_eq!(
+            relocated_path(Path::new("/docs/guides/intro.md"), from, to),
+            Some(to.join("intro.md"))
+        );
+        assert_eq!(relocated_path(from, from, to), Some(to.to_path_buf()));
+        assert_eq!(
+            relocated_path(Path::new("/docs/guides-old/a.md"), from, to),
+            None
+        );
+    }
+}
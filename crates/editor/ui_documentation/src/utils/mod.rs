@@ -1,11 +1,22 @@
+pub mod actions;
 pub mod doc_source;
 pub mod engine_docs;
 pub mod manual_docs;
+pub mod navigation;
+pub mod outline;
 pub mod project_docs;
+pub mod search;
 pub mod types;
+pub mod unsaved;
 
 pub use doc_source::{DocSource, make_search_input};
 pub use engine_docs::{EngineDocsState, TreeNode};
-pub use manual_docs::{FileEntry, ManualDocsState, ViewMode};
+pub use manual_docs::{
+    EntryNameError, FileEntry, ManualDocsState, ViewMode, autosave_interval, validate_entry_name,
+};
+pub use navigation::{DocLink, NavigationHistory, resolve_doc_link};
+pub use outline::{DocOutline, DocOutlineState, OutlineHeading};
 pub use project_docs::{ProjectDocsState, ProjectTreeNode};
-pub use types::DocCategory;
+pub use search::{DocSearchIndex, DocSearchState, SearchDoc, SearchHit};
+pub use types::{DocCategory, ManualDocsDialog};
+pub use unsaved::{PendingSwitch, UnsavedChoice, resolve_unsaved};
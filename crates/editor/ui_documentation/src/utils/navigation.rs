@@ -0,0 +1,160 @@
+/// Where a link in rendered engine docs points
+#[derive(Clone, Debug, PartialEq)]
+pub enum DocLink {
+    /// Engine doc path, e.g. `engine_state/structs/EngineContext.md`
+    Page(String),
+    /// Anything with a URL scheme; opened in the browser
+    External(String),
+}
+
+/// Resolve `href` from the page at `current`. Relative links are resolved
+/// against the page's directory; directory links open that directory's
+/// `index.md`. Returns `None` for in-page anchors.
+pub fn resolve_doc_link(current: Option<&str>, href: &str) -> Option<DocLink> {
+    let href = href.trim();
+    if href.contains("://") || href.starts_with("mailto:") {
+        return Some(DocLink::External(href.to_string()));
+    }
+
+    let target = href.split(['#', '?']).next().unwrap_or_default();
+    if target.is_empty() {
+        return None;
+    }
+
+    let mut parts: Vec<&str> = Vec::new();
+    if let Some(current) = current.filter(|_| !target.starts_with('/')) {
+        parts.extend(current.split('/'));
+        // Drop the file name, keeping its directory
+        parts.pop();
+    }
+    for part in target.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+
+    let mut path = parts.join("/");
+    if target.ends_with('/') || path.is_empty() {
+        if !path.is_empty() {
+            path.push('/');
+        }
+        path.push_str("index.md");
+    } else if !path.ends_with(".md") {
+        path.push_str(".md");
+    }
+    Some(DocLink::Page(path))
+}
+
+/// Back/forward history of visited engine doc pages
+#[derive(Clone, Debug, Default)]
+pub struct NavigationHistory {
+    entries: Vec<String>,
+    position: usize,
+}
+
+impl NavigationHistory {
+    pub fn current(&self) -> Option<&str> {
+        self.entries.get(self.position).map(String::as_str)
+    }
+
+    /// Visit `path`, dropping any forward history. Revisiting the current
+    /// page is a no-op.
+    pub fn push(&mut self, path: String) {
+        if self.current() == Some(path.as_str()) {
+            return;
+        }
+        if !self.entries.is_empty() {
+            self.entries.truncate(self.position + 1);
+            self.position += 1;
+        }
+        self.entries.push(path);
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        self.position > 0
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        self.position + 1 < self.entries.len()
+    }
+
+    pub fn back(&mut self) -> Option<&str> {
+        if !self.can_go_back() {
+            return None;
+        }
+        self.position -= 1;
+        self.current()
+    }
+
+    pub fn forward(&mut self) -> Option<&str> {
+        if !self.can_go_forward() {
+            return None;
+        }
+        self.position += 1;
+        self.current()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(path: &str) -> Option<DocLink> {
+        Some(DocLink::Page(path.to_string()))
+    }
+
+    #[test]
+    fn test_resolve_doc_link() {
+        let current = Some("engine_state/structs/EngineContext.md");
+        assert_eq!(
+            resolve_doc_link(current, "WindowContext.md"),
+            page("engine_state/structs/WindowContext.md")
+        );
+        assert_eq!(
+            resolve_doc_link(current, "../enums/MultiuserStatus.md#variants"),
+            page("engine_state/enums/MultiuserStatus.md")
+        );
+        assert_eq!(
+            resolve_doc_link(current, "../"),
+            page("engine_state/index.md")
+        );
+        assert_eq!(
+            resolve_doc_link(Some("engine_state/index.md"), "structs/EngineContext"),
+            page("engine_state/structs/EngineContext.md")
+        );
+        assert_eq!(
+            resolve_doc_link(current, "/engine_fs/index.md"),
+            page("engine_fs/index.md")
+        );
+        assert_eq!(
+            resolve_doc_link(current, "https://docs.rs/gpui"),
+            Some(DocLink::External("https://docs.rs/gpui".to_string()))
+        );
+        assert_eq!(resolve_doc_link(current, "#fields"), None);
+    }
+
+    #[test]
+    fn test_history_back_and_forward() {
+        let mut history = NavigationHistory::default();
+        assert!(!history.can_go_back() && !history.can_go_forward());
+
+        history.push("a.md".to_string());
+        history.push("b.md".to_string());
+        history.push("b.md".to_string());
+        history.push("c.md".to_string());
+        assert_eq!(history.back(), Some("b.md"));
+        assert_eq!(history.back(), Some("a.md"));
+        assert_eq!(history.back(), None);
+        assert_eq!(history.forward(), Some("b.md"));
+
+        // Visiting a page from the middle drops the forward entries
+        history.push("d.md".to_string());
+        assert!(!history.can_go_forward());
+        assert_eq!(history.back(), Some("b.md"));
+        assert_eq!(history.back(), Some("a.md"));
+    }
+}
@@ -0,0 +1,224 @@
+use gpui::ScrollHandle;
+use std::collections::HashMap;
+use ui::scroll::ScrollbarState;
+
+/// Deepest heading level listed in the outline
+const MAX_OUTLINE_LEVEL: usize = 4;
+
+/// A heading listed in the "On this page" outline
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutlineHeading {
+    pub level: usize,
+    pub text: String,
+    /// Unique within the page; duplicates get a `-1`, `-2`, ... suffix
+    pub anchor: String,
+    /// Index of the section this heading starts
+    pub section: usize,
+}
+
+/// Markdown split at its headings, so each section can be scrolled to
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DocOutline {
+    pub headings: Vec<OutlineHeading>,
+    /// The page's markdown, one entry per section. The first holds anything
+    /// before the first heading and may be empty.
+    pub sections: Vec<String>,
+}
+
+impl DocOutline {
+    pub fn parse(markdown: &str) -> Self {
+        let mut headings = Vec::new();
+        let mut sections = vec![String::new()];
+        let mut anchors: HashMap<String, usize> = HashMap::new();
+        let mut fence: Option<&str> = None;
+
+        for line in markdown.lines() {
+            let trimmed = line.trim_start();
+            if let Some(marker) = fence {
+                if trimmed.starts_with(marker) {
+                    fence = None;
+                }
+            } else if trimmed.starts_with("```") {
+                fence = Some("```");
+            } else if trimmed.starts_with("~~~") {
+                fence = Some("~~~");
+            } else if let Some((level, text)) = parse_heading(line) {
+                let slug = slugify(&text);
+                let seen = anchors.entry(slug.clone()).or_insert(0);
+                let anchor = if *seen == 0 {
+                    slug
+                } else {
+                    format!("{}-{}", slug, seen)
+                };
+                *seen += 1;
+
+                sections.push(String::new());
+                headings.push(OutlineHeading {
+                    level,
+                    text,
+                    anchor,
+                    section: sections.len() - 1,
+                });
+            }
+
+            let section = sections.last_mut().expect("sections start non-empty");
+            section.push_str(line);
+            section.push('\n');
+        }
+
+        Self { headings, sections }
+    }
+}
+
+/// Level and plain text of an ATX heading from `#` to `####`
+fn parse_heading(line: &str) -> Option<(usize, String)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+    let line = &line[indent..];
+    let level = line.len() - line.trim_start_matches('#').len();
+    if level == 0 || level > MAX_OUTLINE_LEVEL {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    // A closing run of #s is not part of the text
+    let rest = rest.trim();
+    let rest = match rest.trim_end_matches('#') {
+        stripped if stripped.is_empty() || stripped.ends_with([' ', '\t']) => stripped.trim_end(),
+        _ => rest,
+    };
+    let text = plain_text(rest);
+    if text.is_empty() {
+        return None;
+    }
+    Some((level, text))
+}
+
+/// Heading text without inline code, emphasis or link markup
+fn plain_text(text: &str) -> String {
+    let mut out = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '`' | '*' | '[' => {}
+            ']' => {
+                // Keep a link's text, drop its target
+                if chars.peek() == Some(&'(') {
+                    for c in chars.by_ref() {
+                        if c == ')' {
+                            break;
+                        }
+                    }
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    out.trim().to_string()
+}
+
+fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() || c == '_' || c == '-' {
+            slug.push(c);
+        } else if c.is_whitespace() {
+            slug.push('-');
+        }
+    }
+    if slug.is_empty() {
+        slug.push_str("section");
+    }
+    slug
+}
+
+/// Outline of the markdown on screen and the scroll position it drives
+#[derive(Default)]
+pub struct DocOutlineState {
+    pub outline: DocOutline,
+    pub scroll_handle: ScrollHandle,
+    pub scroll_state: ScrollbarState,
+    pub collapsed: bool,
+    source: String,
+}
+
+impl DocOutlineState {
+    /// Re-parse if `markdown` is not what the outline was built from.
+    pub fn sync(&mut self, markdown: &str) {
+        if self.source != markdown {
+            self.source = markdown.to_string();
+            self.outline = DocOutline::parse(markdown);
+        }
+    }
+
+    pub fn toggle_collapsed(&mut self) {
+        self.collapsed = !self.collapsed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(outline: &DocOutline) -> Vec<(usize, &str, &str, usize)> {
+        outline
+            .headings
+            .iter()
+            .map(|h| (h.level, h.text.as_str(), h.anchor.as_str(), h.section))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_headings_and_sections() {
+        let markdown = "Intro text\n\n# Physics\n\nBodies.\n\n## Rigid Bodies ##\n\n```rust\n# not_a_heading();\n```\n\n##### Too deep\n\n#### `Collider::new`\n";
+        let outline = DocOutline::parse(markdown);
+
+        assert_eq!(
+            summary(&outline),
+            [
+                (1, "Physics", "physics", 1),
+                (2, "Rigid Bodies", "rigid-bodies", 2),
+                (4, "Collider::new", "collidernew", 3),
+            ]
+        );
+        assert_eq!(outline.sections.len(), 4);
+        assert_eq!(outline.sections[0], "Intro text\n\n");
+        assert!(outline.sections[2].contains("# not_a_heading();"));
+        assert!(outline.sections[2].contains("##### Too deep"));
+        assert_eq!(outline.sections.concat(), markdown);
+    }
+
+    #[test]
+    fn test_duplicate_headings_get_unique_anchors() {
+        let outline = DocOutline::parse("## Examples\n\n## Examples\n\n### Examples\n");
+        let anchors: Vec<&str> = outline.headings.iter().map(|h| h.anchor.as_str()).collect();
+        assert_eq!(anchors, ["examples", "examples-1", "examples-2"]);
+    }
+
+    #[test]
+    fn test_heading_text_is_plain() {
+        assert_eq!(
+            parse_heading("### See [`World`](structs/World.md) *now*"),
+            Some((3, "See World now".to_string()))
+        );
+        assert_eq!(parse_heading("#hashtag"), None);
+        assert_eq!(parse_heading("    # indented code"), None);
+        assert_eq!(parse_heading("## C#"), Some((2, "C#".to_string())));
+    }
+
+    #[test]
+    fn test_sync_only_reparses_on_change() {
+        let mut state = DocOutlineState::default();
+        state.sync("# One\n");
+        assert_eq!(state.outline.headings.len(), 1);
+        state.outline.headings.clear();
+        state.sync("# One\n");
+        assert!(state.outline.headings.is_empty());
+        state.sync("# One\n## Two\n");
+        assert_eq!(state.outline.headings.len(), 2);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::utils::doc_source::{DocSource, make_search_input};
+use crate::utils::outline::DocOutlineState;
 use gpui::*;
 use std::collections::HashSet;
 use std::path::PathBuf;
@@ -31,6 +32,7 @@ pub struct ProjectDocsState {
     pub expanded_paths: HashSet<String>,
     pub current_path: Option<String>,
     pub full_docs: Option<pulsar_docs::project_parser::ProjectDocumentation>,
+    pub outline: DocOutlineState,
 }
 
 impl DocSource for ProjectDocsState {
@@ -59,6 +61,7 @@ impl ProjectDocsState {
             expanded_paths: HashSet::new(),
             current_path: None,
             full_docs: None,
+            outline: DocOutlineState::default(),
         };
 
         let project_path = project_root.or_else(|| {
@@ -258,159 +261,162 @@ impl ProjectDocsState {
     pub fn load_content(&mut self, path: &str) {
         self.current_path = Some(path.to_string());
 
-        if let Some(docs) = &self.full_docs {
-            for struct_doc in &docs.structs {
-                let item_path = if struct_doc.path.is_empty() {
-                    struct_doc.name.clone()
-                } else {
-                    format!("{}::{}", struct_doc.path.join("::"), struct_doc.name)
-                };
-
-                if item_path == path {
-                    let mut md = format!("# `{}`\n\n", item_path);
-                    md.push_str("**Type:** Struct\n\n");
-                    md.push_str(&format!("**Visibility:** `{}`\n\n", struct_doc.visibility));
-
-                    if let Some(doc) = &struct_doc.doc_comment {
-                        md.push_str("## Documentation\n\n");
-                        md.push_str(doc);
-                        md.push_str("\n\n");
-                    }
+        if let Some(markdown) = self.item_markdown(path) {
+            self.markdown_content = markdown;
+        }
+    }
 
-                    if !struct_doc.fields.is_empty() {
-                        md.push_str("## Fields\n\n");
-                        for field in &struct_doc.fields {
-                            md.push_str(&format!("### `{}`: `{}`\n\n", field.name, field.ty));
-                            md.push_str(&format!("**Visibility:** `{}`\n\n", field.visibility));
-                            if let Some(field_doc) = &field.doc_comment {
-                                md.push_str(field_doc);
-                                md.push_str("\n\n");
-                            }
+    /// Markdown page for the item at `path`, as shown when it's selected
+    pub fn item_markdown(&self, path: &str) -> Option<String> {
+        let docs = self.full_docs.as_ref()?;
+
+        for struct_doc in &docs.structs {
+            let item_path = if struct_doc.path.is_empty() {
+                struct_doc.name.clone()
+            } else {
+                format!("{}::{}", struct_doc.path.join("::"), struct_doc.name)
+            };
+
+            if item_path == path {
+                let mut md = format!("# `{}`\n\n", item_path);
+                md.push_str("**Type:** Struct\n\n");
+                md.push_str(&format!("**Visibility:** `{}`\n\n", struct_doc.visibility));
+
+                if let Some(doc) = &struct_doc.doc_comment {
+                    md.push_str("## Documentation\n\n");
+                    md.push_str(doc);
+                    md.push_str("\n\n");
+                }
+
+                if !struct_doc.fields.is_empty() {
+                    md.push_str("## Fields\n\n");
+                    for field in &struct_doc.fields {
+                        md.push_str(&format!("### `{}`: `{}`\n\n", field.name, field.ty));
+                        md.push_str(&format!("**Visibility:** `{}`\n\n", field.visibility));
+                        if let Some(field_doc) = &field.doc_comment {
+                            md.push_str(field_doc);
+                            md.push_str("\n\n");
                         }
                     }
-
-                    self.markdown_content = md;
-                    return;
                 }
+
+                return Some(md);
             }
+        }
 
-            for enum_doc in &docs.enums {
-                let item_path = if enum_doc.path.is_empty() {
-                    enum_doc.name.clone()
-                } else {
-                    format!("{}::{}", enum_doc.path.join("::"), enum_doc.name)
-                };
-
-                if item_path == path {
-                    let mut md = format!("# `{}`\n\n", item_path);
-                    md.push_str("**Type:** Enum\n\n");
-                    md.push_str(&format!("**Visibility:** `{}`\n\n", enum_doc.visibility));
-
-                    if let Some(doc) = &enum_doc.doc_comment {
-                        md.push_str("## Documentation\n\n");
-                        md.push_str(doc);
-                        md.push_str("\n\n");
-                    }
+        for enum_doc in &docs.enums {
+            let item_path = if enum_doc.path.is_empty() {
+                enum_doc.name.clone()
+            } else {
+                format!("{}::{}", enum_doc.path.join("::"), enum_doc.name)
+            };
+
+            if item_path == path {
+                let mut md = format!("# `{}`\n\n", item_path);
+                md.push_str("**Type:** Enum\n\n");
+                md.push_str(&format!("**Visibility:** `{}`\n\n", enum_doc.visibility));
+
+                if let Some(doc) = &enum_doc.doc_comment {
+                    md.push_str("## Documentation\n\n");
+                    md.push_str(doc);
+                    md.push_str("\n\n");
+                }
 
-                    if !enum_doc.variants.is_empty() {
-                        md.push_str("## Variants\n\n");
-                        for variant in &enum_doc.variants {
-                            md.push_str(&format!("### `{}`\n\n", variant.name));
-                            if let Some(variant_doc) = &variant.doc_comment {
-                                md.push_str(variant_doc);
-                                md.push_str("\n\n");
-                            }
+                if !enum_doc.variants.is_empty() {
+                    md.push_str("## Variants\n\n");
+                    for variant in &enum_doc.variants {
+                        md.push_str(&format!("### `{}`\n\n", variant.name));
+                        if let Some(variant_doc) = &variant.doc_comment {
+                            md.push_str(variant_doc);
+                            md.push_str("\n\n");
                         }
                     }
-
-                    self.markdown_content = md;
-                    return;
                 }
+
+                return Some(md);
             }
+        }
 
-            for trait_doc in &docs.traits {
-                let item_path = if trait_doc.path.is_empty() {
-                    trait_doc.name.clone()
-                } else {
-                    format!("{}::{}", trait_doc.path.join("::"), trait_doc.name)
-                };
-
-                if item_path == path {
-                    let mut md = format!("# `{}`\n\n", item_path);
-                    md.push_str("**Type:** Trait\n\n");
-                    md.push_str(&format!("**Visibility:** `{}`\n\n", trait_doc.visibility));
-
-                    if let Some(doc) = &trait_doc.doc_comment {
-                        md.push_str("## Documentation\n\n");
-                        md.push_str(doc);
-                        md.push_str("\n\n");
-                    }
+        for trait_doc in &docs.traits {
+            let item_path = if trait_doc.path.is_empty() {
+                trait_doc.name.clone()
+            } else {
+                format!("{}::{}", trait_doc.path.join("::"), trait_doc.name)
+            };
+
+            if item_path == path {
+                let mut md = format!("# `{}`\n\n", item_path);
+                md.push_str("**Type:** Trait\n\n");
+                md.push_str(&format!("**Visibility:** `{}`\n\n", trait_doc.visibility));
+
+                if let Some(doc) = &trait_doc.doc_comment {
+                    md.push_str("## Documentation\n\n");
+                    md.push_str(doc);
+                    md.push_str("\n\n");
+                }
 
-                    if !trait_doc.methods.is_empty() {
-                        md.push_str("## Methods\n\n");
-                        for method in &trait_doc.methods {
-                            md.push_str(&format!("### `{}`\n\n", method.name));
-                            md.push_str(&format!("**Signature:** `{}`\n\n", method.signature));
-                            if let Some(method_doc) = &method.doc_comment {
-                                md.push_str(method_doc);
-                                md.push_str("\n\n");
-                            }
+                if !trait_doc.methods.is_empty() {
+                    md.push_str("## Methods\n\n");
+                    for method in &trait_doc.methods {
+                        md.push_str(&format!("### `{}`\n\n", method.name));
+                        md.push_str(&format!("**Signature:** `{}`\n\n", method.signature));
+                        if let Some(method_doc) = &method.doc_comment {
+                            md.push_str(method_doc);
+                            md.push_str("\n\n");
                         }
                     }
-
-                    self.markdown_content = md;
-                    return;
                 }
-            }
 
-            for fn_doc in &docs.functions {
-                let item_path = if fn_doc.path.is_empty() {
-                    fn_doc.name.clone()
-                } else {
-                    format!("{}::{}", fn_doc.path.join("::"), fn_doc.name)
-                };
-
-                if item_path == path {
-                    let mut md = format!("# `{}`\n\n", item_path);
-                    md.push_str("**Type:** Function\n\n");
-                    md.push_str(&format!("**Visibility:** `{}`\n\n", fn_doc.visibility));
-                    md.push_str(&format!("**Signature:** `{}`\n\n", fn_doc.signature));
-
-                    if let Some(doc) = &fn_doc.doc_comment {
-                        md.push_str("## Documentation\n\n");
-                        md.push_str(doc);
-                        md.push_str("\n\n");
-                    }
+                return Some(md);
+            }
+        }
 
-                    self.markdown_content = md;
-                    return;
+        for fn_doc in &docs.functions {
+            let item_path = if fn_doc.path.is_empty() {
+                fn_doc.name.clone()
+            } else {
+                format!("{}::{}", fn_doc.path.join("::"), fn_doc.name)
+            };
+
+            if item_path == path {
+                let mut md = format!("# `{}`\n\n", item_path);
+                md.push_str("**Type:** Function\n\n");
+                md.push_str(&format!("**Visibility:** `{}`\n\n", fn_doc.visibility));
+                md.push_str(&format!("**Signature:** `{}`\n\n", fn_doc.signature));
+
+                if let Some(doc) = &fn_doc.doc_comment {
+                    md.push_str("## Documentation\n\n");
+                    md.push_str(doc);
+                    md.push_str("\n\n");
                 }
-            }
 
-            for const_doc in &docs.constants {
-                let item_path = if const_doc.path.is_empty() {
-                    const_doc.name.clone()
-                } else {
-                    format!("{}::{}", const_doc.path.join("::"), const_doc.name)
-                };
-
-                if item_path == path {
-                    let mut md = format!("# `{}`\n\n", item_path);
-                    md.push_str("**Type:** Constant\n\n");
-                    md.push_str(&format!("**Visibility:** `{}`\n\n", const_doc.visibility));
-                    md.push_str(&format!("**Type:** `{}`\n\n", const_doc.ty));
-
-                    if let Some(doc) = &const_doc.doc_comment {
-                        md.push_str("## Documentation\n\n");
-                        md.push_str(doc);
-                        md.push_str("\n\n");
-                    }
+                return Some(md);
+            }
+        }
 
-                    self.markdown_content = md;
-                    return;
+        for const_doc in &docs.constants {
+            let item_path = if const_doc.path.is_empty() {
+                const_doc.name.clone()
+            } else {
+                format!("{}::{}", const_doc.path.join("::"), const_doc.name)
+            };
+
+            if item_path == path {
+                let mut md = format!("# `{}`\n\n", item_path);
+                md.push_str("**Type:** Constant\n\n");
+                md.push_str(&format!("**Visibility:** `{}`\n\n", const_doc.visibility));
+                md.push_str(&format!("**Type:** `{}`\n\n", const_doc.ty));
+
+                if let Some(doc) = &const_doc.doc_comment {
+                    md.push_str("## Documentation\n\n");
+                    md.push_str(doc);
+                    md.push_str("\n\n");
                 }
+
+                return Some(md);
             }
         }
+        None
     }
 
     pub fn refresh(&mut self) {
@@ -0,0 +1,346 @@
+use crate::utils::{DocCategory, EngineDocsState, ManualDocsState, ProjectDocsState};
+use crate::utils::{ProjectTreeNode, TreeNode};
+use gpui::*;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use ui::input::InputState;
+
+const MAX_RESULTS: usize = 50;
+const SNIPPET_RADIUS: usize = 80;
+const TITLE_BOOST: f32 = 3.0;
+
+/// One searchable page
+#[derive(Clone, Debug)]
+pub struct SearchDoc {
+    pub category: DocCategory,
+    /// Engine doc path, project item path, or manual file path
+    pub path: String,
+    pub title: String,
+    pub body: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct SearchHit {
+    pub doc: usize,
+    pub score: f32,
+    pub snippet: String,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Posting {
+    doc: usize,
+    count: u32,
+    in_title: bool,
+}
+
+/// Inverted index over the markdown of every doc category
+#[derive(Default)]
+pub struct DocSearchIndex {
+    pub docs: Vec<SearchDoc>,
+    // Sorted, so a query term can match every word it prefixes
+    postings: BTreeMap<String, Vec<Posting>>,
+}
+
+impl DocSearchIndex {
+    pub fn build(docs: Vec<SearchDoc>) -> Self {
+        let mut postings: BTreeMap<String, Vec<Posting>> = BTreeMap::new();
+        for (doc, entry) in docs.iter().enumerate() {
+            let mut counts: HashMap<String, Posting> = HashMap::new();
+            for token in tokenize(&entry.title) {
+                counts
+                    .entry(token)
+                    .or_insert(Posting {
+                        doc,
+                        count: 0,
+                        in_title: false,
+                    })
+                    .in_title = true;
+            }
+            for token in tokenize(&entry.body) {
+                counts
+                    .entry(token)
+                    .or_insert(Posting {
+                        doc,
+                        count: 0,
+                        in_title: false,
+                    })
+                    .count += 1;
+            }
+            for (token, posting) in counts {
+                postings.entry(token).or_default().push(posting);
+            }
+        }
+        Self { docs, postings }
+    }
+
+    /// Pages containing every term of `query`, best first. Each term also
+    /// matches longer words it is a prefix of, so results follow typing.
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        let terms = tokenize(query);
+        if terms.is_empty() || self.docs.is_empty() {
+            return Vec::new();
+        }
+
+        let total = self.docs.len() as f32;
+        let mut scores: HashMap<usize, (usize, f32)> = HashMap::new();
+        for term in &terms {
+            let mut term_scores: HashMap<usize, f32> = HashMap::new();
+            for (word, postings) in self
+                .postings
+                .range(term.clone()..)
+                .take_while(|(word, _)| word.starts_with(term.as_str()))
+            {
+                let idf = (total / postings.len() as f32).ln() + 1.0;
+                // Prefix matches count for less than the exact word
+                let exactness = if word == term { 1.0 } else { 0.5 };
+                for posting in postings {
+                    let tf = (posting.count as f32).sqrt();
+                    let title = if posting.in_title { TITLE_BOOST } else { 0.0 };
+                    *term_scores.entry(posting.doc).or_default() += (tf + title) * idf * exactness;
+                }
+            }
+            for (doc, score) in term_scores {
+                let entry = scores.entry(doc).or_default();
+                entry.0 += 1;
+                entry.1 += score;
+            }
+        }
+
+        let mut hits: Vec<SearchHit> = scores
+            .into_iter()
+            .filter(|(_, (matched, _))| *matched == terms.len())
+            .map(|(doc, (_, score))| SearchHit {
+                doc,
+                score,
+                snippet: snippet(&self.docs[doc].body, &terms),
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| self.docs[a.doc].title.cmp(&self.docs[b.doc].title))
+        });
+        hits.truncate(MAX_RESULTS);
+        hits
+    }
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| word.chars().count() >= 2)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// The text around the first match of any term, on one line
+fn snippet(body: &str, terms: &[String]) -> String {
+    let lower = body.to_lowercase();
+    // Lowercasing can change byte lengths; only trust positions when it didn't
+    let position = if lower.len() == body.len() {
+        terms.iter().filter_map(|t| lower.find(t.as_str())).min()
+    } else {
+        None
+    }
+    .unwrap_or(0);
+
+    let mut start = position.saturating_sub(SNIPPET_RADIUS);
+    while !body.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (position + SNIPPET_RADIUS).min(body.len());
+    while !body.is_char_boundary(end) {
+        end += 1;
+    }
+
+    let text = body[start..end]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if end < body.len() { "…" } else { "" };
+    format!("{prefix}{text}{suffix}")
+}
+
+/// The documentation window's search box and its lazily built index
+pub struct DocSearchState {
+    pub input_state: Entity<InputState>,
+    pub query: String,
+    pub index: Option<DocSearchIndex>,
+    pub results: Vec<SearchHit>,
+}
+
+impl DocSearchState {
+    pub fn new(window: &mut Window, cx: &mut App) -> Self {
+        let input_state = cx.new(|cx| {
+            let mut state = InputState::new(window, cx);
+            state.set_placeholder("Search all documentation...", window, cx);
+            state
+        });
+        Self {
+            input_state,
+            query: String::new(),
+            index: None,
+            results: Vec::new(),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.query.trim().is_empty()
+    }
+
+    pub fn hit_doc(&self, hit: &SearchHit) -> Option<&SearchDoc> {
+        self.index.as_ref()?.docs.get(hit.doc)
+    }
+
+    /// Drop the index; it is rebuilt on the next search.
+    pub fn invalidate(&mut self) {
+        self.index = None;
+        self.results.clear();
+    }
+
+    pub fn run(
+        &mut self,
+        engine: &EngineDocsState,
+        project: &ProjectDocsState,
+        manual: &ManualDocsState,
+    ) {
+        if !self.is_active() {
+            self.results.clear();
+            return;
+        }
+        let index = self.index.get_or_insert_with(|| {
+            let mut docs = engine_search_docs(engine);
+            docs.extend(project_search_docs(project));
+            docs.extend(manual_search_docs(manual));
+            DocSearchIndex::build(docs)
+        });
+        self.results = index.search(&self.query);
+    }
+}
+
+fn engine_search_docs(state: &EngineDocsState) -> Vec<SearchDoc> {
+    state
+        .tree_items
+        .iter()
+        .filter_map(|node| match node {
+            TreeNode::Item {
+                item_name, path, ..
+            } => Some(SearchDoc {
+                category: DocCategory::Engine,
+                path: path.clone(),
+                title: item_name.clone(),
+                body: pulsar_docs::get_doc_content(path).unwrap_or_default(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+fn project_search_docs(state: &ProjectDocsState) -> Vec<SearchDoc> {
+    state
+        .tree_items
+        .iter()
+        .filter_map(|node| match node {
+            ProjectTreeNode::Item {
+                item_name, path, ..
+            } => Some(SearchDoc {
+                category: DocCategory::Project,
+                path: path.clone(),
+                title: item_name.clone(),
+                body: state.item_markdown(path).unwrap_or_default(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+fn manual_search_docs(state: &ManualDocsState) -> Vec<SearchDoc> {
+    let mut files = Vec::new();
+    if let Some(docs_folder) = &state.docs_folder {
+        collect_markdown_files(docs_folder, &mut files);
+    }
+    files
+        .into_iter()
+        .filter_map(|path| {
+            let body = std::fs::read_to_string(&path).ok()?;
+            let title = path.file_stem()?.to_string_lossy().into_owned();
+            Some(SearchDoc {
+                category: DocCategory::Manual,
+                path: path.to_string_lossy().into_owned(),
+                title,
+                body,
+            })
+        })
+        .collect()
+}
+
+fn collect_markdown_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            collect_markdown_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "md") {
+            files.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(title: &str, body: &str) -> SearchDoc {
+        SearchDoc {
+            category: DocCategory::Manual,
+            path: format!("{title}.md"),
+            title: title.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_search_ranks_and_requires_all_terms() {
+        let index = DocSearchIndex::build(vec![
+            doc(
+                "Physics",
+                "Rigid bodies collide. Collision layers filter contacts.",
+            ),
+            doc(
+                "Rendering",
+                "The renderer draws meshes. Collision shapes are not drawn.",
+            ),
+            doc("Audio", "Sounds play on audio buses."),
+        ]);
+
+        let hits = index.search("collision");
+        let titles: Vec<&str> = hits
+            .iter()
+            .map(|h| index.docs[h.doc].title.as_str())
+            .collect();
+        assert_eq!(titles, ["Physics", "Rendering"]);
+
+        let hits = index.search("collision draw");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(index.docs[hits[0].doc].title, "Rendering");
+
+        // Titles outrank body text; prefixes match while typing
+        let hits = index.search("aud");
+        assert_eq!(index.docs[hits[0].doc].title, "Audio");
+        assert!(index.search("").is_empty());
+    }
+
+    #[test]
+    fn test_snippet_surrounds_match() {
+        let body = format!("{} needle {}", "a ".repeat(100), "b ".repeat(100));
+        let text = snippet(&body, &["needle".to_string()]);
+        assert!(text.starts_with('…') && text.ends_with('…'));
+        assert!(text.contains("needle"));
+        assert!(!text.contains("  "));
+    }
+}
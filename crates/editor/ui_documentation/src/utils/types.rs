@@ -1,6 +1,29 @@
+use crate::utils::unsaved::PendingSwitch;
+use std::path::PathBuf;
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum DocCategory {
     Engine,
     Project,
     Manual,
 }
+
+impl DocCategory {
+    /// Name of the category's tab
+    pub fn label(&self) -> &'static str {
+        match self {
+            DocCategory::Engine => "Engine API",
+            DocCategory::Project => "Project API",
+            DocCategory::Manual => "Documentation",
+        }
+    }
+}
+
+/// Manual docs dialog waiting on the user
+#[derive(Clone, Debug, PartialEq)]
+pub enum ManualDocsDialog {
+    NewFolder { parent: PathBuf },
+    Move { path: PathBuf },
+    Delete { path: PathBuf },
+    UnsavedChanges { pending: PendingSwitch },
+}
@@ -0,0 +1,117 @@
+use crate::utils::DocCategory;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Where the user asked to go while the open manual doc had unsaved changes
+#[derive(Clone, Debug, PartialEq)]
+pub enum PendingSwitch {
+    File(PathBuf),
+    Category(DocCategory),
+}
+
+impl PendingSwitch {
+    /// Whether carrying this out takes the user away from the open manual doc
+    pub fn leaves(&self, current_category: DocCategory, selected_file: Option<&Path>) -> bool {
+        match self {
+            PendingSwitch::File(path) => selected_file != Some(path.as_path()),
+            PendingSwitch::Category(category) => {
+                current_category == DocCategory::Manual && *category != DocCategory::Manual
+            }
+        }
+    }
+}
+
+/// The user's answer to the unsaved changes prompt. Cancelling just closes it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnsavedChoice {
+    Save,
+    Discard,
+}
+
+/// Settle unsaved changes so `switch` can go ahead. `Save` runs `save`
+/// first; if that fails the error is returned and the switch is dropped,
+/// so the edits stay open.
+pub fn resolve_unsaved(
+    switch: PendingSwitch,
+    choice: UnsavedChoice,
+    save: impl FnOnce() -> io::Result<()>,
+) -> io::Result<PendingSwitch> {
+    if choice == UnsavedChoice::Save {
+        save()?;
+    }
+    Ok(switch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Two docs on disk, `a.md` open with edits in the buffer
+    fn setup() -> (tempfile::TempDir, PathBuf, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.md");
+        let b = dir.path().join("b.md");
+        fs::write(&a, "# A").unwrap();
+        fs::write(&b, "# B").unwrap();
+        (dir, a, b)
+    }
+
+    #[test]
+    fn test_switch_file_while_dirty_save() {
+        let (_dir, a, b) = setup();
+        let buffer = "# A\n\nEdited";
+
+        let switch = PendingSwitch::File(b.clone());
+        assert!(switch.leaves(DocCategory::Manual, Some(&a)));
+        let next = resolve_unsaved(switch, UnsavedChoice::Save, || fs::write(&a, buffer)).unwrap();
+
+        assert_eq!(next, PendingSwitch::File(b));
+        assert_eq!(fs::read_to_string(&a).unwrap(), buffer);
+    }
+
+    #[test]
+    fn test_switch_file_while_dirty_discard() {
+        let (_dir, a, b) = setup();
+        let buffer = "# A\n\nEdited";
+
+        let next = resolve_unsaved(
+            PendingSwitch::File(b.clone()),
+            UnsavedChoice::Discard,
+            || fs::write(&a, buffer),
+        )
+        .unwrap();
+
+        assert_eq!(next, PendingSwitch::File(b));
+        assert_eq!(fs::read_to_string(&a).unwrap(), "# A");
+    }
+
+    #[test]
+    fn test_failed_save_keeps_edits_open() {
+        let (dir, a, b) = setup();
+        let missing = dir.path().join("gone").join("a.md");
+
+        let result = resolve_unsaved(PendingSwitch::File(b), UnsavedChoice::Save, || {
+            fs::write(&missing, "# A\n\nEdited")
+        });
+
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&a).unwrap(), "# A");
+    }
+
+    #[test]
+    fn test_leaves() {
+        let open = Path::new("/docs/a.md");
+        assert!(!PendingSwitch::File(open.to_path_buf()).leaves(DocCategory::Manual, Some(open)));
+        assert!(
+            PendingSwitch::Category(DocCategory::Engine).leaves(DocCategory::Manual, Some(open))
+        );
+        assert!(
+            !PendingSwitch::Category(DocCategory::Manual).leaves(DocCategory::Manual, Some(open))
+        );
+        // The buffer survives switching between the other tabs
+        assert!(
+            !PendingSwitch::Category(DocCategory::Project).leaves(DocCategory::Engine, Some(open))
+        );
+    }
+}
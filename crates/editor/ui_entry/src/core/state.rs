@@ -321,8 +321,7 @@ impl AuthState {
 /// Top-level application state (replaces the 50+ field EntryScreen)
 pub struct AppState {
     pub logo: Option<Arc<RenderImage>>,
-    pub recent_projects: crate::service::project_service::RecentProjectsList,
-    pub recent_projects_path: PathBuf,
+    pub recent_projects: Vec<engine_state::RecentProject>,
     pub templates: Vec<Template>,
 
     pub ui: UiState,
@@ -364,11 +363,9 @@ pub struct AppState {
 
 impl AppState {
     pub fn new(window: &mut Window, cx: &mut App) -> Self {
-        let recent_projects_path = directories::ProjectDirs::from("com", "Pulsar", "Pulsar_Engine")
-            .map(|d| d.data_dir().join("recent_projects.json"))
-            .unwrap_or_else(|| PathBuf::from("recent_projects.json"));
-        let recent_projects =
-            crate::service::project_service::RecentProjectsList::load(&recent_projects_path);
+        let recent_projects = engine_state::EngineContext::global()
+            .map(|ctx| ctx.recent_projects())
+            .unwrap_or_default();
         let templates = get_default_templates();
 
         let cloud_servers_path = directories::ProjectDirs::from("com", "Pulsar", "Pulsar_Engine")
@@ -412,7 +409,6 @@ impl AppState {
         Self {
             logo,
             recent_projects,
-            recent_projects_path,
             templates,
             ui: UiState::new(),
             input: InputValues::new(),
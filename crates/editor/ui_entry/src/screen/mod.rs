@@ -1,6 +1,6 @@
 pub mod views;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
@@ -34,8 +34,10 @@ impl EntryScreen {
         let status = DependencyService::check();
         state.dependency_status = Some(status);
 
-        for proj in &state.recent_projects.projects {
-            state.project_thumbnail_queue.push_back(proj.path.clone());
+        for proj in &state.recent_projects {
+            state
+                .project_thumbnail_queue
+                .push_back(proj.path.to_string_lossy().into_owned());
         }
         for tmpl in &state.templates {
             state.template_thumbnail_queue.push_back(tmpl.clone());
@@ -85,10 +87,9 @@ impl EntryScreen {
         let paths: Vec<(String, PathBuf)> = self
             .state
             .recent_projects
-            .projects
             .iter()
-            .filter(|p| p.is_git)
-            .map(|p| (p.name.clone(), PathBuf::from(&p.path)))
+            .filter(|p| p.is_git && !p.missing)
+            .map(|p| (p.name.clone(), p.path.clone()))
             .collect();
         let statuses = self.state.git_fetch_statuses.clone();
         for (name, path) in paths {
@@ -135,29 +136,15 @@ impl EntryScreen {
 
     pub(crate) fn open_folder_dialog(&self, cx: &mut Context<Self>) {
         let entity = self.entity.clone().unwrap();
-        let recent_projects_path = self.state.recent_projects_path.clone();
         cx.spawn(async move |_handle, cx| {
             if let Some(folder) = rfd::AsyncFileDialog::new().pick_folder().await {
                 let path = folder.path().to_path_buf();
                 if !ProjectService::validate_project(&path) {
                     return;
                 }
-                let name = path
-                    .file_name()
-                    .map(|n| n.to_string_lossy().to_string())
-                    .unwrap_or_default();
-                let path_str = path.to_string_lossy().to_string();
-                let is_git = ProjectService::is_git_repo(&path);
-                let project = crate::service::project_service::RecentProject {
-                    name,
-                    path: path_str,
-                    last_opened: Some(chrono::Local::now().format("%Y-%m-%d %H:%M").to_string()),
-                    is_git,
-                };
                 cx.update(|cx| {
                     entity.update(cx, |this, cx| {
-                        this.state.recent_projects.add_or_update(project);
-                        this.state.recent_projects.save(&recent_projects_path);
+                        this.record_recent_project(&path);
                         cx.emit(ProjectSelected { path });
                         cx.notify();
                     });
@@ -173,7 +160,6 @@ impl EntryScreen {
             return;
         }
         let entity = self.entity.clone().unwrap();
-        let recent_projects_path = self.state.recent_projects_path.clone();
         cx.spawn(async move |_handle, cx| {
             if let Some(folder) = rfd::AsyncFileDialog::new().pick_folder().await {
                 let parent = folder.path().to_path_buf();
@@ -211,22 +197,7 @@ impl EntryScreen {
                                 .unwrap_or_default();
                             this.state.ui.show_git_upstream_prompt = Some((target.clone(), n));
                         } else {
-                            let ps = target.to_string_lossy().to_string();
-                            let n = target
-                                .file_name()
-                                .map(|n| n.to_string_lossy().to_string())
-                                .unwrap_or_default();
-                            this.state.recent_projects.add_or_update(
-                                crate::service::project_service::RecentProject {
-                                    name: n,
-                                    path: ps,
-                                    last_opened: Some(
-                                        chrono::Local::now().format("%Y-%m-%d %H:%M").to_string(),
-                                    ),
-                                    is_git: true,
-                                },
-                            );
-                            this.state.recent_projects.save(&recent_projects_path);
+                            this.record_recent_project(&target);
                             cx.emit(ProjectSelected { path: target });
                         }
                         cx.notify();
@@ -248,7 +219,6 @@ impl EntryScreen {
         };
         let url = self.state.input.git_upstream_url_text.clone();
         let entity = self.entity.clone().unwrap();
-        let recent_projects_path = self.state.recent_projects_path.clone();
         cx.spawn(async move |_handle, cx| {
             if !url.is_empty() {
                 let p = path.clone();
@@ -260,22 +230,7 @@ impl EntryScreen {
             }
             cx.update(|cx| {
                 entity.update(cx, |this, cx| {
-                    let ps = path.to_string_lossy().to_string();
-                    let n = path
-                        .file_name()
-                        .map(|n| n.to_string_lossy().to_string())
-                        .unwrap_or_default();
-                    this.state.recent_projects.add_or_update(
-                        crate::service::project_service::RecentProject {
-                            name: n,
-                            path: ps,
-                            last_opened: Some(
-                                chrono::Local::now().format("%Y-%m-%d %H:%M").to_string(),
-                            ),
-                            is_git: true,
-                        },
-                    );
-                    this.state.recent_projects.save(&recent_projects_path);
+                    this.record_recent_project(&path);
                     cx.emit(ProjectSelected { path });
                     cx.notify();
                 });
@@ -285,29 +240,40 @@ impl EntryScreen {
     }
 
     pub(crate) fn launch_project(&mut self, path: PathBuf, cx: &mut Context<Self>) {
-        let name = path
-            .file_name()
-            .map(|n| n.to_string_lossy().to_string())
-            .unwrap_or_default();
-        let is_git = ProjectService::is_git_repo(&path);
-        let project = crate::service::project_service::RecentProject {
-            name,
-            path: path.to_string_lossy().to_string(),
-            last_opened: Some(chrono::Local::now().format("%Y-%m-%d %H:%M").to_string()),
-            is_git,
-        };
-        self.state.recent_projects.add_or_update(project);
-        self.state
-            .recent_projects
-            .save(&self.state.recent_projects_path);
+        self.record_recent_project(&path);
         cx.emit(ProjectSelected { path });
     }
 
-    pub(crate) fn remove_recent_project(&mut self, path: &str, cx: &mut Context<Self>) {
-        self.state.recent_projects.remove(path);
-        self.state
-            .recent_projects
-            .save(&self.state.recent_projects_path);
+    /// Move `path` to the top of the engine's recent projects list
+    fn record_recent_project(&mut self, path: &Path) {
+        if let Some(ctx) = engine_state::EngineContext::global() {
+            ctx.record_project_opened(path);
+            self.state.recent_projects = ctx.recent_projects();
+        }
+    }
+
+    pub(crate) fn remove_recent_project(&mut self, path: &Path, cx: &mut Context<Self>) {
+        if let Some(ctx) = engine_state::EngineContext::global() {
+            ctx.forget_recent_project(path);
+            self.state.recent_projects = ctx.recent_projects();
+        }
+        cx.notify();
+    }
+
+    pub(crate) fn toggle_recent_project_pin(&mut self, path: &Path, cx: &mut Context<Self>) {
+        if let Some(ctx) = engine_state::EngineContext::global() {
+            let pinned = self
+                .state
+                .recent_projects
+                .iter()
+                .any(|p| p.pinned && p.path == path);
+            if pinned {
+                ctx.unpin_project(path);
+            } else {
+                ctx.pin_project(path);
+            }
+            self.state.recent_projects = ctx.recent_projects();
+        }
         cx.notify();
     }
 
@@ -397,7 +363,6 @@ impl EntryScreen {
             .unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
         let project_path = base_path.join(&name);
         let entity = self.entity.clone().unwrap();
-        let recent_projects_path = self.state.recent_projects_path.clone();
         let n = name.clone();
         let pp = project_path.clone();
         cx.spawn(async move |_handle, cx| {
@@ -410,17 +375,9 @@ impl EntryScreen {
                     let _ = ProjectService::init_repository(&pp);
                 })
                 .await;
-            let pstr = project_path.to_string_lossy().to_string();
-            let project = crate::service::project_service::RecentProject {
-                name,
-                path: pstr,
-                last_opened: Some(chrono::Local::now().format("%Y-%m-%d %H:%M").to_string()),
-                is_git: true,
-            };
             cx.update(|cx| {
                 entity.update(cx, |this, cx| {
-                    this.state.recent_projects.add_or_update(project);
-                    this.state.recent_projects.save(&recent_projects_path);
+                    this.record_recent_project(&project_path);
                     cx.emit(ProjectSelected { path: project_path });
                     cx.notify();
                 });
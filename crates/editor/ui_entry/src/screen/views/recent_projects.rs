@@ -19,7 +19,7 @@ pub fn render_recent_projects(
     let theme = cx.theme();
     let columns = screen.calculate_columns(px(available_width + 220.0 + 64.0));
 
-    let project_count = screen.state.recent_projects.projects.len();
+    let project_count = screen.state.recent_projects.len();
     let is_empty = project_count == 0;
 
     v_flex()
@@ -113,7 +113,7 @@ pub fn render_recent_projects(
                         .px_8()
                         .pb_6()
                             .child(h_flex().flex_wrap().gap_6().children(
-                                screen.state.recent_projects.projects.clone().iter().map(
+                                screen.state.recent_projects.clone().iter().map(
                                     |project| render_project_card(screen, project, columns, cx),
                                 ),
                             )),
@@ -124,21 +124,31 @@ pub fn render_recent_projects(
 
 fn render_project_card(
     screen: &mut EntryScreen,
-    project: &crate::service::project_service::RecentProject,
+    project: &engine_state::RecentProject,
     _columns: usize,
     cx: &mut Context<EntryScreen>,
 ) -> impl IntoElement {
     let theme = cx.theme();
-    let path = project.path.clone();
-    let path_open = path.clone();
-    let path_git = path.clone();
-    let path_settings = path.clone();
-    let path_remove = path.clone();
+    let path = project.path.to_string_lossy().into_owned();
+    let path_open = project.path.clone();
+    let path_git = project.path.clone();
+    let path_settings = project.path.clone();
+    let path_pin = project.path.clone();
+    let path_remove = project.path.clone();
     let name = project.name.clone();
     let normalized = normalize_project_path(&path);
-    let timestamp = project.last_opened.as_deref().unwrap_or("").to_string();
-    let formatted_time = format_timestamp(&timestamp);
-    let is_git = project.is_git;
+    let formatted_time = if project.missing {
+        "Folder not found".to_string()
+    } else {
+        let timestamp = project
+            .last_opened
+            .map(|t| t.with_timezone(&chrono::Local).to_rfc3339())
+            .unwrap_or_default();
+        format_timestamp(&timestamp)
+    };
+    let is_git = project.is_git && !project.missing;
+    let missing = project.missing;
+    let pinned = project.pinned;
 
     let fetch_status = {
         let statuses = screen.state.git_fetch_statuses.lock();
@@ -168,8 +178,8 @@ fn render_project_card(
                 .border_color(theme.accent.opacity(0.4))
         })
         .on_click(cx.listener(move |this, _, window, cx| {
-            if !window.default_prevented() {
-                this.launch_project(std::path::PathBuf::from(&path_open), cx);
+            if !window.default_prevented() && !missing {
+                this.launch_project(path_open.clone(), cx);
             }
         }))
         .child(
@@ -198,9 +208,13 @@ fn render_project_card(
                             .items_center()
                             .justify_center()
                             .child(
-                                Icon::new(IconName::Folder)
-                                    .size(px(36.))
-                                    .text_color(theme.muted_foreground.opacity(0.3)),
+                                Icon::new(if missing {
+                                    IconName::WarningTriangle
+                                } else {
+                                    IconName::Folder
+                                })
+                                .size(px(36.))
+                                .text_color(theme.muted_foreground.opacity(0.3)),
                             ),
                     )
                 })
@@ -222,7 +236,7 @@ fn render_project_card(
                                 .ghost()
                                 .tooltip("Git Manager")
                                 .on_click(cx.listener(move |this, _, _, cx| {
-                                    this.open_git_manager(std::path::PathBuf::from(&path_git), cx);
+                                    this.open_git_manager(path_git.clone(), cx);
                                 })),
                         )
                         .child(
@@ -232,10 +246,17 @@ fn render_project_card(
                                 .ghost()
                                 .tooltip("Project Settings")
                                 .on_click(cx.listener(move |this, _, _, cx| {
-                                    this.open_project_settings(
-                                        std::path::PathBuf::from(&path_settings),
-                                        cx,
-                                    );
+                                    this.open_project_settings(path_settings.clone(), cx);
+                                })),
+                        )
+                        .child(
+                            Button::new(SharedString::from(format!("pin-{}", path)))
+                                .icon(IconName::Star)
+                                .compact()
+                                .when(!pinned, |this| this.ghost())
+                                .tooltip(if pinned { "Unpin" } else { "Pin to top" })
+                                .on_click(cx.listener(move |this, _, _, cx| {
+                                    this.toggle_recent_project_pin(&path_pin, cx);
                                 })),
                         )
                         .child(
@@ -1,45 +1,5 @@
-use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
-/// A single recent project entry
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RecentProject {
-    pub name: String,
-    pub path: String,
-    pub last_opened: Option<String>,
-    pub is_git: bool,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct RecentProjectsList {
-    pub projects: Vec<RecentProject>,
-}
-
-impl RecentProjectsList {
-    pub fn load(path: &Path) -> Self {
-        ui_common::file_utils::read_json(path).unwrap_or_default()
-    }
-
-    pub fn save(&self, path: &Path) {
-        let _ = ui_common::file_utils::write_json(path, self);
-    }
-
-    pub fn add_or_update(&mut self, project: RecentProject) {
-        if let Some(existing) = self.projects.iter_mut().find(|p| p.path == project.path) {
-            *existing = project;
-        } else {
-            self.projects.insert(0, project);
-        }
-        if self.projects.len() > 20 {
-            self.projects.truncate(20);
-        }
-    }
-
-    pub fn remove(&mut self, path: &str) {
-        self.projects.retain(|p| p.path != path);
-    }
-}
-
 /// Pure functions for project lifecycle
 pub struct ProjectService;
 
@@ -16,12 +16,6 @@ pub fn appdata_dir() -> std::path::PathBuf {
         .unwrap_or_else(|| std::path::PathBuf::from("."))
 }
 
-pub fn recent_projects_path() -> std::path::PathBuf {
-    directories::ProjectDirs::from("com", "Pulsar", "Pulsar_Engine")
-        .map(|d| d.data_dir().join("recent_projects.json"))
-        .unwrap_or_else(|| std::path::PathBuf::from("recent_projects.json"))
-}
-
 pub fn cloud_servers_path() -> std::path::PathBuf {
     directories::ProjectDirs::from("com", "Pulsar", "Pulsar_Engine")
         .map(|d| d.data_dir().join("cloud_servers.json"))
@@ -216,6 +216,11 @@ pub fn folder_context_menu(
                 t!("FileManager.Refresh").to_string(),
                 ui::Icon::new(ui::IconName::Refresh),
                 Box::new(RefreshFileManager),
+            )
+            .menu_with_icon(
+                t!("FileManager.RebuildDerived").to_string(),
+                ui::Icon::new(ui::IconName::Refresh),
+                Box::new(RebuildDerivedData),
             );
 
         menu
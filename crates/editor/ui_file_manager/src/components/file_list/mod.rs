@@ -1,7 +1,7 @@
 use gpui::prelude::*;
 use gpui::*;
 use rust_i18n::t;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use ui::{
@@ -18,12 +18,13 @@ use ui::{
 use crate::utils::{
     actions::*,
     fs_metadata::FsMetadataManager,
-    helpers::{format_file_size, get_icon_color_for_file_type, get_icon_for_file_type},
+    helpers::{get_icon_color_for_file_type, get_icon_for_file_type, get_icon_image_for_file_type},
     operations::FileOperations,
     tree::FolderNode,
     types::*,
 };
 use crate::components::sidebar;
+use ui_common::{format_bytes, RelativeTime};
 
 pub struct FileManagerDrawer {
     pub project_path: Option<PathBuf>,
@@ -50,6 +51,7 @@ pub struct FileManagerDrawer {
     pub(crate) renaming_item: Option<PathBuf>,
     pub(crate) rename_input_state: Entity<InputState>,
     pub(crate) registered_file_types: Vec<plugin_editor_api::FileTypeDefinition>,
+    pub(crate) file_type_icons: HashMap<plugin_editor_api::FileTypeId, std::sync::Arc<gpui::Image>>,
     pub(crate) search_query: String,
     pub(crate) folder_search_state: Entity<InputState>,
     pub(crate) file_filter_query: String,
@@ -143,6 +145,7 @@ impl FileManagerDrawer {
             show_hidden_files: false,
             clipboard: None,
             registered_file_types: Vec::new(),
+            file_type_icons: HashMap::new(),
             grid_scroll_handle: VirtualListScrollHandle::new(),
             list_scroll_handle: VirtualListScrollHandle::new(),
             grid_scrollbar_state: ScrollbarState::default(),
@@ -192,6 +195,15 @@ impl FileManagerDrawer {
         self.registered_file_types = file_types;
     }
 
+    /// Set the custom icons plugins provide for their file types, shown
+    /// instead of the file types' built-in icons.
+    pub fn update_file_type_icons(
+        &mut self,
+        icons: HashMap<plugin_editor_api::FileTypeId, std::sync::Arc<gpui::Image>>,
+    ) {
+        self.file_type_icons = icons;
+    }
+
     pub(crate) fn copy_dir_recursive(src: &PathBuf, dst: &PathBuf) -> std::io::Result<()> {
         std::fs::create_dir_all(dst)?;
         for entry in std::fs::read_dir(src)? {
@@ -230,6 +242,10 @@ impl Render for FileManagerDrawer {
                 this.mark_directory_cache_dirty();
                 cx.notify();
             }))
+            .on_action(cx.listener(|_this, _: &RebuildDerivedData, _w, cx| {
+                engine_fs::derived::global().request_rebuild_all();
+                cx.notify();
+            }))
             .on_action(cx.listener(|this, a: &CreateAsset, _w, cx| {
                 crate::handlers::handle_create_asset(this, a, cx)
             }))
@@ -541,6 +557,7 @@ pub fn render_grid_item(
     let sel = d.selected_items.contains(&item.path);
     let ren = d.renaming_item.as_ref() == Some(&item.path);
     let icon = get_icon_for_file_type(item);
+    let icon_image = get_icon_image_for_file_type(item, &d.file_type_icons);
     let ic = get_icon_color_for_file_type(item, cx.theme(), &mut d.fs_metadata);
     let icl = item.clone();
     let idc = item.clone();
@@ -554,6 +571,7 @@ pub fn render_grid_item(
         d.ensure_thumbnail(&item.path, cx);
     }
     let thumb = d.thumbnails.get(&item.path).and_then(|t| t.clone());
+    let stale = !fld && engine_fs::derived::global().has_stale_dependents(&item.path);
     let paths = if sel {
         d.selected_items.iter().cloned().collect()
     } else {
@@ -648,6 +666,7 @@ pub fn render_grid_item(
             }));
     }
     div()
+        .relative()
         .w(px(cw))
         .h(px(110.0))
         .rounded_lg()
@@ -689,7 +708,14 @@ pub fn render_grid_item(
                                     .h(px(48.0))
                                     .object_fit(gpui::ObjectFit::Cover),
                             ),
-                            None => e.child(Icon::new(icon).size(px(24.0)).text_color(ic)),
+                            None => match icon_image {
+                                Some(image) => e.child(
+                                    gpui::img(gpui::ImageSource::Image(image))
+                                        .w(px(24.0))
+                                        .h(px(24.0)),
+                                ),
+                                None => e.child(Icon::new(icon).size(px(24.0)).text_color(ic)),
+                            },
                         }),
                 )
                 .child(if ren {
@@ -754,6 +780,18 @@ pub fn render_grid_item(
                     )
                 }),
         )
+        .when(stale, |e| {
+            e.child(
+                // Something built from this file (thumbnail, bytecode, ...) is out of date
+                div()
+                    .absolute()
+                    .top_1()
+                    .right_1()
+                    .size(px(8.0))
+                    .rounded_full()
+                    .bg(cx.theme().warning),
+            )
+        })
 }
 
 pub fn render_list_view(
@@ -812,6 +850,7 @@ pub fn render_list_item(
     let sel = d.selected_items.contains(&item.path);
     let ren = d.renaming_item.as_ref() == Some(&item.path);
     let icon = get_icon_for_file_type(item);
+    let icon_image = get_icon_image_for_file_type(item, &d.file_type_icons);
     let ic = get_icon_color_for_file_type(item, cx.theme(), &mut d.fs_metadata);
     let icl = item.clone();
     let idc = item.clone();
@@ -932,7 +971,10 @@ pub fn render_list_item(
             .justify_center()
             .rounded_sm()
             .bg(ic.opacity(0.15))
-            .child(Icon::new(icon).size_4().text_color(ic)),
+            .map(|e| match icon_image {
+                Some(image) => e.child(gpui::img(gpui::ImageSource::Image(image)).size_4()),
+                None => e.child(Icon::new(icon).size_4().text_color(ic)),
+            }),
     )
     .child(if ren {
         div()
@@ -953,6 +995,15 @@ pub fn render_list_item(
             .child(item.name.clone())
             .into_any_element()
     })
+    .when_some(item.modified, |e, modified| {
+        e.child(
+            div()
+                .w(px(96.0))
+                .text_xs()
+                .text_color(cx.theme().muted_foreground)
+                .child(RelativeTime::new(modified)),
+        )
+    })
     .when(!item.is_folder, |e| {
         e.child(
             div()
@@ -963,7 +1014,7 @@ pub fn render_list_item(
                 .text_xs()
                 .font_family("monospace")
                 .text_color(cx.theme().muted_foreground)
-                .child(format_file_size(item.size)),
+                .child(format_bytes(item.size)),
         )
     })
     .on_mouse_down(
@@ -69,6 +69,10 @@ pub struct CancelRename;
 #[action(namespace = file_manager, no_json)]
 pub struct RefreshFileManager;
 
+#[derive(Action, Clone, Debug, PartialEq, Eq, Deserialize, JsonSchema)]
+#[action(namespace = file_manager, no_json)]
+pub struct RebuildDerivedData;
+
 #[derive(Action, Clone, Debug, PartialEq, Eq, Deserialize, JsonSchema)]
 #[action(namespace = file_manager, no_json)]
 pub struct CollapseAllFolders;
@@ -1,34 +1,10 @@
 use crate::utils::fs_metadata::FsMetadataManager;
 use crate::utils::types::FileItem;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use ui::IconName;
 
-pub fn format_file_size(size: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-    if size >= GB {
-        format!("{:.2} GB", size as f64 / GB as f64)
-    } else if size >= MB {
-        format!("{:.2} MB", size as f64 / MB as f64)
-    } else if size >= KB {
-        format!("{:.2} KB", size as f64 / KB as f64)
-    } else {
-        format!("{} B", size)
-    }
-}
-
-pub fn format_modified_time(time: Option<std::time::SystemTime>) -> String {
-    time.and_then(|t| {
-        t.duration_since(std::time::UNIX_EPOCH).ok().map(|d| {
-            chrono::DateTime::<chrono::Local>::from(std::time::UNIX_EPOCH + d)
-                .format("%b %d, %Y %H:%M")
-                .to_string()
-        })
-    })
-    .unwrap_or_else(|| "Unknown".to_string())
-}
-
 pub fn get_icon_color_for_file_type(
     item: &FileItem,
     theme: &ui::Theme,
@@ -54,6 +30,16 @@ pub fn get_icon_for_file_type(item: &FileItem) -> IconName {
         })
 }
 
+/// The plugin-provided image icon for an item's file type, if there is one.
+pub fn get_icon_image_for_file_type(
+    item: &FileItem,
+    icons: &HashMap<plugin_editor_api::FileTypeId, Arc<gpui::Image>>,
+) -> Option<Arc<gpui::Image>> {
+    item.file_type_def
+        .as_ref()
+        .and_then(|d| icons.get(&d.id).cloned())
+}
+
 pub fn copy_dir_all(src: &Path, dst: &Path) -> std::io::Result<()> {
     std::fs::create_dir_all(dst)?;
     for entry in std::fs::read_dir(src)? {
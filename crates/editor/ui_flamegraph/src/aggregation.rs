@@ -0,0 +1,237 @@
+//! Aggregation of collected spans, for the statistics panel.
+//!
+//! Both functions work on a [`TraceFrame`]'s spans where they are, sorting
+//! indices into them rather than copying them.
+
+use crate::trace_data::{TraceFrame, TraceSpan};
+use std::collections::HashMap;
+
+/// Calls and durations of every span with one name
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScopeStats {
+    pub name: String,
+    pub call_count: usize,
+    pub total_ns: u64,
+    pub min_ns: u64,
+    pub max_ns: u64,
+    pub avg_ns: u64,
+}
+
+/// Totals over the spans of one frame
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FrameStats {
+    pub start_ns: u64,
+    pub duration_ns: u64,
+    /// Scopes by time including their children, longest first
+    pub top_inclusive: Vec<(String, u64)>,
+    /// Scopes by time excluding their children, longest first
+    pub top_exclusive: Vec<(String, u64)>,
+    /// Time in top-level scopes per thread, by thread ID
+    pub thread_busy_ns: Vec<(u64, u64)>,
+}
+
+/// Aggregates spans by name, longest total first. With a range, only spans
+/// starting in `start..end` are counted.
+pub fn aggregate_by_scope(spans: &[TraceSpan], range: Option<(u64, u64)>) -> Vec<ScopeStats> {
+    let mut scopes: HashMap<&str, ScopeStats> = HashMap::new();
+    let in_range = |span: &TraceSpan| {
+        range.is_none_or(|(start, end)| span.start_ns >= start && span.start_ns < end)
+    };
+    for span in spans.iter().filter(|span| in_range(span)) {
+        let stats = scopes.entry(&span.name).or_insert_with(|| ScopeStats {
+            name: span.name.clone(),
+            call_count: 0,
+            total_ns: 0,
+            min_ns: u64::MAX,
+            max_ns: 0,
+            avg_ns: 0,
+        });
+        stats.call_count += 1;
+        stats.total_ns += span.duration_ns;
+        stats.min_ns = stats.min_ns.min(span.duration_ns);
+        stats.max_ns = stats.max_ns.max(span.duration_ns);
+    }
+
+    let mut scopes: Vec<ScopeStats> = scopes
+        .into_values()
+        .map(|mut stats| {
+            stats.avg_ns = stats.total_ns / stats.call_count as u64;
+            stats
+        })
+        .collect();
+    scopes.sort_by(|a, b| b.total_ns.cmp(&a.total_ns).then(a.name.cmp(&b.name)));
+    scopes
+}
+
+/// Aggregates the spans between each pair of consecutive frame markers,
+/// keeping the `top_n` longest scopes of each frame. A span belongs to the
+/// frame it starts in; spans after the last marker aren't in a frame yet.
+pub fn aggregate_frames(frame: &TraceFrame, top_n: usize) -> Vec<FrameStats> {
+    let spans = &frame.spans;
+    let mut markers = frame.frame_markers_ns.clone();
+    markers.sort_unstable();
+    let mut by_start: Vec<usize> = (0..spans.len()).collect();
+    by_start.sort_by_key(|&i| spans[i].start_ns);
+
+    markers
+        .windows(2)
+        .map(|bounds| {
+            let (start, end) = (bounds[0], bounds[1]);
+            let first = by_start.partition_point(|&i| spans[i].start_ns < start);
+            let last = by_start.partition_point(|&i| spans[i].start_ns < end);
+            frame_stats(spans, &by_start[first..last], start, end, top_n)
+        })
+        .collect()
+}
+
+fn frame_stats(
+    spans: &[TraceSpan],
+    indices: &[usize],
+    start: u64,
+    end: u64,
+    top_n: usize,
+) -> FrameStats {
+    // Parents come before the children starting with them
+    let mut order = indices.to_vec();
+    order.sort_by_key(|&i| (spans[i].thread_id, spans[i].start_ns, spans[i].depth));
+
+    // Exclusive time is a span's duration less that of its direct children,
+    // found by keeping the spans still open on a stack
+    let mut exclusive: Vec<u64> = order.iter().map(|&i| spans[i].duration_ns).collect();
+    let mut open: Vec<usize> = Vec::new();
+    for (position, &i) in order.iter().enumerate() {
+        let span = &spans[i];
+        while let Some(&top) = open.last() {
+            let parent = &spans[order[top]];
+            let encloses = parent.thread_id == span.thread_id
+                && parent.depth < span.depth
+                && parent.end_ns() > span.start_ns;
+            if encloses {
+                break;
+            }
+            open.pop();
+        }
+        if let Some(&top) = open.last() {
+            if spans[order[top]].depth + 1 == span.depth {
+                exclusive[top] = exclusive[top].saturating_sub(span.duration_ns);
+            }
+        }
+        open.push(position);
+    }
+
+    let mut inclusive: HashMap<&str, u64> = HashMap::new();
+    let mut self_time: HashMap<&str, u64> = HashMap::new();
+    let mut busy: HashMap<u64, u64> = HashMap::new();
+    for (position, &i) in order.iter().enumerate() {
+        let span = &spans[i];
+        *inclusive.entry(&span.name).or_default() += span.duration_ns;
+        *self_time.entry(&span.name).or_default() += exclusive[position];
+        if span.depth == 0 {
+            *busy.entry(span.thread_id).or_default() += span.duration_ns;
+        }
+    }
+
+    let mut thread_busy_ns: Vec<(u64, u64)> = busy.into_iter().collect();
+    thread_busy_ns.sort_unstable();
+    FrameStats {
+        start_ns: start,
+        duration_ns: end - start,
+        top_inclusive: top_scopes(inclusive, top_n),
+        top_exclusive: top_scopes(self_time, top_n),
+        thread_busy_ns,
+    }
+}
+
+fn top_scopes(times: HashMap<&str, u64>, top_n: usize) -> Vec<(String, u64)> {
+    let mut times: Vec<(&str, u64)> = times.into_iter().collect();
+    times.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    times.truncate(top_n);
+    times
+        .into_iter()
+        .map(|(name, ns)| (name.to_string(), ns))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(name: &str, thread_id: u64, start_ns: u64, duration_ns: u64, depth: u32) -> TraceSpan {
+        TraceSpan {
+            name: name.to_string(),
+            start_ns,
+            duration_ns,
+            depth,
+            thread_id,
+            color_index: 0,
+        }
+    }
+
+    /// Two frames of 100ns each, plus a span after the last marker
+    fn trace() -> TraceFrame {
+        let mut frame = TraceFrame::new();
+        for span in [
+            span("physics", 1, 10, 20, 1),
+            span("tick", 1, 0, 60, 0),
+            span("collide", 1, 15, 5, 2),
+            span("render", 2, 5, 80, 0),
+            span("tick", 1, 100, 40, 0),
+            span("render", 2, 120, 50, 0),
+            span("tick", 1, 200, 30, 0),
+        ] {
+            frame.add_span(span);
+        }
+        frame.frame_markers_ns = vec![0, 100, 200];
+        frame
+    }
+
+    #[test]
+    fn test_aggregate_by_scope() {
+        let frame = trace();
+        let stats = aggregate_by_scope(&frame.spans, None);
+        let names: Vec<&str> = stats.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["render", "tick", "physics", "collide"]);
+        assert_eq!(
+            stats[1],
+            ScopeStats {
+                name: "tick".to_string(),
+                call_count: 3,
+                total_ns: 130,
+                min_ns: 30,
+                max_ns: 60,
+                avg_ns: 43,
+            }
+        );
+
+        let second_frame = aggregate_by_scope(&frame.spans, Some((100, 200)));
+        assert_eq!(second_frame.len(), 2);
+        assert_eq!(second_frame[0].total_ns, 50);
+    }
+
+    #[test]
+    fn test_aggregate_frames() {
+        let frames = aggregate_frames(&trace(), 2);
+        assert_eq!(frames.len(), 2);
+
+        let first = &frames[0];
+        assert_eq!((first.start_ns, first.duration_ns), (0, 100));
+        assert_eq!(
+            first.top_inclusive,
+            [("render".to_string(), 80), ("tick".to_string(), 60)]
+        );
+        // tick less physics, physics less collide
+        assert_eq!(
+            first.top_exclusive,
+            [("render".to_string(), 80), ("tick".to_string(), 40)]
+        );
+        assert_eq!(first.thread_busy_ns, [(1, 60), (2, 80)]);
+
+        let second = &frames[1];
+        assert_eq!(second.top_exclusive.len(), 2);
+        assert_eq!(second.thread_busy_ns, [(1, 40), (2, 50)]);
+
+        let mut unmarked = trace();
+        unmarked.frame_markers_ns.truncate(1);
+        assert!(aggregate_frames(&unmarked, 2).is_empty());
+    }
+}
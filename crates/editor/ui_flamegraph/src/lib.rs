@@ -11,6 +11,7 @@ mod trace_data;
 pub mod window;
 
 // Core modules
+mod aggregation;
 mod colors;
 mod components;
 mod constants;
@@ -21,10 +22,16 @@ mod state;
 
 // Profiling module
 mod profiler;
+mod session;
 
+pub use aggregation::{aggregate_by_scope, aggregate_frames, FrameStats, ScopeStats};
 pub use flamegraph_view::FlamegraphView;
 pub use panels::{FlamegraphPanel, StatisticsPanel};
-pub use profiler::{convert_profile_events_to_trace, InstrumentationCollector};
+pub use profiler::{
+    convert_profile_events_to_trace, InstrumentationCollector, ProfilerStats, ScopeFilter,
+    DEFAULT_MAX_EVENTS,
+};
+pub use session::LoadedSession;
 pub use trace_data::{ThreadInfo, TraceData, TraceFrame, TraceSpan};
 pub use window::FlamegraphWindow;
 
@@ -1,26 +1,16 @@
+use crate::aggregation::{aggregate_by_scope, ScopeStats};
 use crate::trace_data::TraceData;
 use gpui::prelude::FluentBuilder;
 use gpui::*;
-use std::collections::HashMap;
 use std::sync::Arc;
 use ui::{
     dock::{Panel, PanelEvent},
     h_flex, v_flex, ActiveTheme,
 };
 
-#[derive(Clone, Debug)]
-pub struct FunctionStats {
-    pub name: String,
-    pub call_count: usize,
-    pub total_duration_ns: u64,
-    pub avg_duration_ns: u64,
-    pub min_duration_ns: u64,
-    pub max_duration_ns: u64,
-}
-
 pub struct StatisticsPanel {
     trace_data: Arc<TraceData>,
-    stats: Vec<FunctionStats>,
+    stats: Vec<ScopeStats>,
     sort_by: SortColumn,
     sort_ascending: bool,
     focus_handle: FocusHandle,
@@ -59,32 +49,7 @@ impl StatisticsPanel {
 
         self.last_span_count = frame.spans.len();
         self.stats_dirty = false;
-        let mut function_map: HashMap<String, (usize, u64, u64, u64)> = HashMap::new();
-
-        // Aggregate statistics by function name
-        for span in &frame.spans {
-            let entry = function_map
-                .entry(span.name.clone())
-                .or_insert((0, 0, u64::MAX, 0));
-
-            entry.0 += 1; // call count
-            entry.1 += span.duration_ns; // total duration
-            entry.2 = entry.2.min(span.duration_ns); // min duration
-            entry.3 = entry.3.max(span.duration_ns); // max duration
-        }
-
-        // Convert to FunctionStats vec
-        self.stats = function_map
-            .into_iter()
-            .map(|(name, (count, total, min, max))| FunctionStats {
-                name,
-                call_count: count,
-                total_duration_ns: total,
-                avg_duration_ns: total / count as u64,
-                min_duration_ns: min,
-                max_duration_ns: max,
-            })
-            .collect();
+        self.stats = aggregate_by_scope(&frame.spans, None);
 
         // Sort by current column
         self.sort_statistics();
@@ -95,8 +60,8 @@ impl StatisticsPanel {
             let cmp = match self.sort_by {
                 SortColumn::Name => a.name.cmp(&b.name),
                 SortColumn::Calls => a.call_count.cmp(&b.call_count),
-                SortColumn::TotalTime => a.total_duration_ns.cmp(&b.total_duration_ns),
-                SortColumn::AvgTime => a.avg_duration_ns.cmp(&b.avg_duration_ns),
+                SortColumn::TotalTime => a.total_ns.cmp(&b.total_ns),
+                SortColumn::AvgTime => a.avg_ns.cmp(&b.avg_ns),
             };
 
             if self.sort_ascending {
@@ -222,7 +187,7 @@ impl StatisticsPanel {
                     .text_sm()
                     .text_color(theme.muted_foreground)
                     .font_family("monospace")
-                    .child(Self::format_duration(stats.total_duration_ns)),
+                    .child(Self::format_duration(stats.total_ns)),
             )
             .child(
                 div()
@@ -230,7 +195,7 @@ impl StatisticsPanel {
                     .text_sm()
                     .text_color(theme.muted_foreground)
                     .font_family("monospace")
-                    .child(Self::format_duration(stats.avg_duration_ns)),
+                    .child(Self::format_duration(stats.avg_ns)),
             )
     }
 }
@@ -1,17 +1,68 @@
 //! Real-time profiler using instrumentation for cross-platform profiling
 
 use crate::trace_data::{ThreadInfo, TraceData, TraceFrame, TraceSpan};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 
+/// Default number of spans a collector keeps before evicting the oldest
+pub const DEFAULT_MAX_EVENTS: usize = 1_000_000;
+
+/// Counts over the spans a collector keeps
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProfilerStats {
+    /// Spans currently kept
+    pub events_stored: usize,
+    /// Oldest spans evicted to stay within `max_events`
+    pub events_dropped: u64,
+    /// Scopes the collector's [`ScopeFilter`] left out
+    pub events_filtered: u64,
+    pub max_events: usize,
+}
+
+/// Which scopes a collector keeps. The default keeps every scope; frame
+/// markers are always kept.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScopeFilter {
+    /// Leave out scopes shorter than this
+    pub min_duration_ns: u64,
+    /// If not empty, keep only scopes whose name starts with one of these
+    pub name_prefixes: Vec<String>,
+    /// If not empty, keep only scopes on threads with one of these names
+    pub thread_names: Vec<String>,
+}
+
+impl ScopeFilter {
+    pub fn keeps(&self, name: &str, thread_name: Option<&str>, duration_ns: u64) -> bool {
+        duration_ns >= self.min_duration_ns
+            && (self.name_prefixes.is_empty()
+                || self
+                    .name_prefixes
+                    .iter()
+                    .any(|prefix| name.starts_with(prefix.as_str())))
+            && (self.thread_names.is_empty()
+                || thread_name.is_some_and(|thread| self.thread_names.iter().any(|t| t == thread)))
+    }
+}
+
+/// Settings a collector's background thread reads on every update
+struct CollectorLimits {
+    max_events: AtomicUsize,
+    filter: parking_lot::RwLock<ScopeFilter>,
+    events_filtered: AtomicU64,
+}
+
 /// Background collector that periodically grabs instrumentation events
 pub struct InstrumentationCollector {
     trace_data: Arc<TraceData>,
     running: Arc<parking_lot::RwLock<bool>>,
     frame_time_running: Arc<parking_lot::RwLock<bool>>,
     update_interval_ms: u64,
+    limits: Arc<CollectorLimits>,
+    /// Spans collected so far, shared with the collector thread
+    accumulator: Arc<parking_lot::Mutex<TraceAccumulator>>,
 }
 
 impl InstrumentationCollector {
@@ -26,6 +77,52 @@ impl InstrumentationCollector {
             running: Arc::new(parking_lot::RwLock::new(false)),
             frame_time_running: Arc::new(parking_lot::RwLock::new(false)),
             update_interval_ms,
+            limits: Arc::new(CollectorLimits {
+                max_events: AtomicUsize::new(DEFAULT_MAX_EVENTS),
+                filter: parking_lot::RwLock::new(ScopeFilter::default()),
+                events_filtered: AtomicU64::new(0),
+            }),
+            accumulator: Arc::new(parking_lot::Mutex::new(TraceAccumulator::default())),
+        }
+    }
+
+    /// Limit how many spans are kept; past it, the oldest are evicted.
+    /// Lowering the limit trims the spans already kept right away.
+    pub fn set_max_events(&self, max_events: usize) {
+        let max_events = max_events.max(1);
+        self.limits.max_events.store(max_events, Ordering::Relaxed);
+
+        let mut accumulator = self.accumulator.lock();
+        // Only the collector thread keeps the accumulator up to date
+        if !self.is_running() {
+            *accumulator = TraceAccumulator::from_frame(&self.trace_data.get_frame());
+        }
+        if accumulator.spans.len() > max_events {
+            accumulator.evict_to(max_events);
+            if let Err(e) = accumulator.publish(&self.trace_data) {
+                tracing::error!("[PROFILER] Failed to publish trimmed spans: {}", e);
+            }
+        }
+    }
+
+    /// Keep only the scopes `filter` keeps, from the next update on. Scopes
+    /// already collected stay.
+    pub fn set_filter(&self, filter: ScopeFilter) {
+        *self.limits.filter.write() = filter;
+    }
+
+    pub fn filter(&self) -> ScopeFilter {
+        self.limits.filter.read().clone()
+    }
+
+    /// How many spans are kept, evicted and filtered out
+    pub fn profiler_stats(&self) -> ProfilerStats {
+        let frame = self.trace_data.get_frame();
+        ProfilerStats {
+            events_stored: frame.spans.len(),
+            events_dropped: frame.dropped_spans,
+            events_filtered: self.limits.events_filtered.load(Ordering::Relaxed),
+            max_events: self.limits.max_events.load(Ordering::Relaxed),
         }
     }
 
@@ -76,13 +173,22 @@ impl InstrumentationCollector {
         let trace_data = Arc::clone(&self.trace_data);
         let running_flag = Arc::clone(&self.running);
         let update_interval = self.update_interval_ms;
+        let limits = Arc::clone(&self.limits);
+        *self.accumulator.lock() = TraceAccumulator::from_frame(&trace_data.get_frame());
+        let accumulator = Arc::clone(&self.accumulator);
 
         // NOTE: Don't enable/disable profiling here!
         // Profiling is enabled globally at engine startup
         // We just collect the events that are already being recorded
 
         thread::spawn(move || {
-            collector_loop(trace_data, running_flag, update_interval);
+            collector_loop(
+                trace_data,
+                running_flag,
+                update_interval,
+                limits,
+                accumulator,
+            );
         });
     }
 
@@ -103,10 +209,11 @@ fn collector_loop(
     trace_data: Arc<TraceData>,
     running: Arc<parking_lot::RwLock<bool>>,
     update_interval_ms: u64,
+    limits: Arc<CollectorLimits>,
+    accumulator: Arc<parking_lot::Mutex<TraceAccumulator>>,
 ) {
     tracing::trace!("[PROFILER] Starting instrumentation collector");
 
-    let mut accumulator = TraceAccumulator::from_frame(&trace_data.get_frame());
     let mut last_event_count = 0;
 
     while *running.read() {
@@ -118,6 +225,11 @@ fn collector_loop(
         // NOW get all events from storage
         let all_events = profiling::get_all_events();
 
+        // The store was cleared, so everything in it is new
+        if all_events.len() < last_event_count {
+            last_event_count = 0;
+        }
+
         // Only process new events since last update
         if all_events.len() <= last_event_count {
             continue;
@@ -133,9 +245,23 @@ fn collector_loop(
         );
 
         // Convert ONLY new events to TraceData format
+        let mut accumulator = accumulator.lock();
+        let filter = limits.filter.read();
+        let mut filtered = 0;
         for event in new_events {
-            accumulator.apply_event(event);
+            let kept = event.name == "__FRAME_MARKER__"
+                || filter.keeps(&event.name, event.thread_name.as_deref(), event.duration_ns);
+            if kept {
+                accumulator.apply_event(event);
+            } else {
+                filtered += 1;
+            }
         }
+        drop(filter);
+        limits
+            .events_filtered
+            .fetch_add(filtered, Ordering::Relaxed);
+        accumulator.evict_to(limits.max_events.load(Ordering::Relaxed));
 
         if let Err(e) = accumulator.publish(&trace_data) {
             tracing::error!("[PROFILER] Failed to convert events: {}", e);
@@ -177,17 +303,38 @@ fn sample_renderer_frame_time(
 
 #[derive(Default)]
 struct TraceAccumulator {
-    spans: Vec<TraceSpan>,
+    /// Oldest first, so eviction pops from the front
+    spans: VecDeque<TraceSpan>,
+    dropped_spans: u64,
     thread_names: HashMap<u64, ThreadInfo>,
     frame_times: Vec<f32>,
+    frame_markers: Vec<u64>,
 }
 
 impl TraceAccumulator {
     fn from_frame(frame: &TraceFrame) -> Self {
         Self {
-            spans: frame.spans.clone(),
+            spans: frame.spans.iter().cloned().collect(),
+            dropped_spans: frame.dropped_spans,
             thread_names: frame.threads.clone(),
             frame_times: frame.frame_times_ms.clone(),
+            frame_markers: frame.frame_markers_ns.clone(),
+        }
+    }
+
+    /// Evicts the oldest spans until at most `max_spans` are left, along with
+    /// the markers of frames that no longer have spans
+    fn evict_to(&mut self, max_spans: usize) {
+        let excess = self.spans.len().saturating_sub(max_spans);
+        if excess > 0 {
+            self.spans.drain(..excess);
+            self.dropped_spans += excess as u64;
+            if let Some(oldest) = self.spans.front() {
+                let stale = self
+                    .frame_markers
+                    .partition_point(|&marker| marker < oldest.start_ns);
+                self.frame_markers.drain(..stale);
+            }
         }
     }
 
@@ -195,6 +342,7 @@ impl TraceAccumulator {
         if event.name == "__FRAME_MARKER__" {
             self.frame_times
                 .push(event.duration_ns as f32 / 1_000_000.0);
+            self.frame_markers.push(event.start_ns);
             return;
         }
 
@@ -211,7 +359,7 @@ impl TraceAccumulator {
             },
         );
 
-        self.spans.push(TraceSpan {
+        self.spans.push_back(TraceSpan {
             name: event.name.clone(),
             start_ns: event.start_ns,
             duration_ns: event.duration_ns,
@@ -228,8 +376,10 @@ impl TraceAccumulator {
             .iter()
             .map(|(id, info)| (*id, info.name.clone()))
             .collect();
-        let mut frame = TraceFrame::with_data(self.spans.clone(), thread_names);
+        let mut frame = TraceFrame::with_data(self.spans.iter().cloned().collect(), thread_names);
         frame.frame_times_ms = self.frame_times.clone();
+        frame.frame_markers_ns = self.frame_markers.clone();
+        frame.dropped_spans = self.dropped_spans;
         trace_data.set_frame(frame);
         Ok(())
     }
@@ -252,6 +402,7 @@ pub fn convert_profile_events_to_trace(
         .map(|(id, info)| (*id, info.name.clone()))
         .collect();
     let mut frame_times = current_frame.frame_times_ms.clone();
+    let mut frame_markers = current_frame.frame_markers_ns.clone();
 
     tracing::trace!("[PROFILER] BEFORE: {} existing spans", existing_span_count);
 
@@ -264,6 +415,7 @@ pub fn convert_profile_events_to_trace(
             // Extract frame time from duration field (stored in nanoseconds)
             let frame_time_ms = event.duration_ns as f32 / 1_000_000.0;
             frame_times.push(frame_time_ms);
+            frame_markers.push(event.start_ns);
             tracing::trace!(
                 "[PROFILER] Frame marker: {:.2}ms ({:.1} FPS)",
                 frame_time_ms,
@@ -313,6 +465,8 @@ pub fn convert_profile_events_to_trace(
     // Update the trace data with accumulated spans and frame times
     let mut frame = TraceFrame::with_data(spans.clone(), thread_names.clone());
     frame.frame_times_ms = frame_times;
+    frame.frame_markers_ns = frame_markers;
+    frame.dropped_spans = current_frame.dropped_spans;
     trace_data.set_frame(frame);
 
     // Verify it was set correctly
@@ -326,3 +480,123 @@ pub fn convert_profile_events_to_trace(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_filter() {
+        assert!(ScopeFilter::default().keeps("ui::paint", None, 0));
+
+        let filter = ScopeFilter {
+            min_duration_ns: 1_000,
+            name_prefixes: vec!["physics".to_string(), "render".to_string()],
+            thread_names: vec!["Main Thread".to_string()],
+        };
+        assert!(filter.keeps("physics::step", Some("Main Thread"), 1_000));
+        assert!(!filter.keeps("physics::step", Some("Main Thread"), 999));
+        assert!(!filter.keeps("ui::paint", Some("Main Thread"), 5_000));
+        assert!(!filter.keeps("render", Some("Render Thread"), 5_000));
+        assert!(!filter.keeps("render", None, 5_000));
+    }
+
+    fn span(start_ns: u64) -> TraceSpan {
+        TraceSpan {
+            name: format!("span {}", start_ns),
+            start_ns,
+            duration_ns: 5,
+            depth: 0,
+            thread_id: 1,
+            color_index: 0,
+        }
+    }
+
+    /// Trace data holding spans starting at 0, 10, 20, ...
+    fn trace_with_spans(count: u64) -> Arc<TraceData> {
+        let trace_data = Arc::new(TraceData::new());
+        let spans = (0..count).map(|i| span(i * 10)).collect();
+        let threads = HashMap::from([(1, "Main Thread".to_string())]);
+        trace_data.set_frame(TraceFrame::with_data(spans, threads));
+        trace_data
+    }
+
+    #[test]
+    fn test_evict_to_drops_oldest_spans_and_their_frame_markers() {
+        let mut accumulator = TraceAccumulator {
+            spans: (0..10).map(|i| span(i * 10)).collect(),
+            frame_markers: vec![0, 40, 60, 90],
+            ..Default::default()
+        };
+
+        accumulator.evict_to(10);
+        assert_eq!(accumulator.spans.len(), 10);
+        assert_eq!(accumulator.dropped_spans, 0);
+
+        accumulator.evict_to(4);
+        let starts: Vec<u64> = accumulator.spans.iter().map(|s| s.start_ns).collect();
+        assert_eq!(starts, [60, 70, 80, 90]);
+        assert_eq!(accumulator.dropped_spans, 6);
+        assert_eq!(accumulator.frame_markers, [60, 90]);
+
+        // Later evictions add to the count
+        accumulator.spans.push_back(span(100));
+        accumulator.evict_to(4);
+        assert_eq!(accumulator.spans.front().unwrap().start_ns, 70);
+        assert_eq!(accumulator.dropped_spans, 7);
+    }
+
+    #[test]
+    fn test_lowering_max_events_trims_right_away() {
+        let trace_data = trace_with_spans(10);
+        let collector = InstrumentationCollector::new(Arc::clone(&trace_data), 100);
+        assert_eq!(
+            collector.profiler_stats(),
+            ProfilerStats {
+                events_stored: 10,
+                events_dropped: 0,
+                events_filtered: 0,
+                max_events: DEFAULT_MAX_EVENTS,
+            }
+        );
+
+        collector.set_max_events(3);
+        let frame = trace_data.get_frame();
+        let starts: Vec<u64> = frame.spans.iter().map(|s| s.start_ns).collect();
+        assert_eq!(starts, [70, 80, 90]);
+        assert_eq!(frame.min_time_ns, 70);
+        assert_eq!(
+            collector.profiler_stats(),
+            ProfilerStats {
+                events_stored: 3,
+                events_dropped: 7,
+                events_filtered: 0,
+                max_events: 3,
+            }
+        );
+
+        // Raising the limit again brings nothing back
+        collector.set_max_events(100);
+        assert_eq!(collector.profiler_stats().events_stored, 3);
+        assert_eq!(collector.profiler_stats().events_dropped, 7);
+    }
+
+    #[test]
+    fn test_stats_reset_when_trace_is_cleared_after_eviction() {
+        let trace_data = trace_with_spans(5);
+        let collector = InstrumentationCollector::new(Arc::clone(&trace_data), 100);
+        collector.set_max_events(2);
+        assert_eq!(collector.profiler_stats().events_dropped, 3);
+
+        trace_data.clear();
+        let stats = collector.profiler_stats();
+        assert_eq!(stats.events_stored, 0);
+        assert_eq!(stats.events_dropped, 0);
+        assert_eq!(stats.max_events, 2);
+
+        // A cleared trace starts counting evictions from zero
+        trace_data.set_frame(trace_with_spans(4).get_frame().as_ref().clone());
+        collector.set_max_events(1);
+        assert_eq!(collector.profiler_stats().events_dropped, 3);
+    }
+}
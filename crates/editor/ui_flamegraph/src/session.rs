@@ -0,0 +1,115 @@
+//! Profiling sessions saved to disk.
+//!
+//! When recording stops, the flamegraph window writes the session's events
+//! to a database under the project's `.pulsar/profiling` folder.
+//! [`LoadedSession`] reads one back, so a trace can be browsed long after it
+//! was recorded, by frame, time window and thread.
+
+use crate::profiler::convert_profile_events_to_trace;
+use crate::trace_data::{ThreadInfo, TraceData, TraceFrame, TraceSpan};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A profiling session read back from its database
+pub struct LoadedSession {
+    path: PathBuf,
+    frame: Arc<TraceFrame>,
+}
+
+impl LoadedSession {
+    /// Read the session saved at `path`
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let conn = rusqlite::Connection::open(path)?;
+        let events = profiling::database::load_events(&conn)?;
+        tracing::trace!(
+            "[PROFILER] Loaded {} events from {}",
+            events.len(),
+            path.display()
+        );
+
+        let trace_data = TraceData::new();
+        convert_profile_events_to_trace(&events, &trace_data)?;
+        Ok(Self::from_frame(path.to_path_buf(), trace_data.get_frame()))
+    }
+
+    fn from_frame(path: PathBuf, frame: Arc<TraceFrame>) -> Self {
+        Self { path, frame }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The whole session, as shown by the flamegraph
+    pub fn frame(&self) -> &Arc<TraceFrame> {
+        &self.frame
+    }
+
+    pub fn threads(&self) -> Vec<ThreadInfo> {
+        self.frame.get_sorted_threads()
+    }
+
+    /// Start and end of each frame, from consecutive frame markers
+    pub fn frame_ranges(&self) -> Vec<(u64, u64)> {
+        let mut markers = self.frame.frame_markers_ns.clone();
+        markers.sort_unstable();
+        markers
+            .windows(2)
+            .map(|bounds| (bounds[0], bounds[1]))
+            .collect()
+    }
+
+    /// Spans overlapping `start..end`, on one thread or all of them
+    pub fn spans_in(
+        &self,
+        start: u64,
+        end: u64,
+        thread_id: Option<u64>,
+    ) -> impl Iterator<Item = &TraceSpan> {
+        self.frame.spans.iter().filter(move |span| {
+            span.start_ns < end
+                && span.end_ns() > start
+                && thread_id.is_none_or(|id| span.thread_id == id)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(name: &str, thread_id: u64, start_ns: u64, duration_ns: u64) -> TraceSpan {
+        TraceSpan {
+            name: name.to_string(),
+            start_ns,
+            duration_ns,
+            depth: 0,
+            thread_id,
+            color_index: 0,
+        }
+    }
+
+    #[test]
+    fn test_frames_and_windows() {
+        let mut frame = TraceFrame::new();
+        frame.add_span(span("tick", 1, 0, 60));
+        frame.add_span(span("render", 2, 50, 80));
+        frame.add_span(span("tick", 1, 100, 40));
+        frame.frame_markers_ns = vec![100, 0, 200];
+        let session = LoadedSession::from_frame(PathBuf::from("session.db"), Arc::new(frame));
+
+        assert_eq!(session.frame_ranges(), [(0, 100), (100, 200)]);
+        let names = |spans: Vec<&TraceSpan>| -> Vec<String> {
+            spans.into_iter().map(|s| s.name.clone()).collect()
+        };
+        assert_eq!(
+            names(session.spans_in(100, 200, None).collect()),
+            ["render", "tick"]
+        );
+        assert_eq!(
+            names(session.spans_in(100, 200, Some(1)).collect()),
+            ["tick"]
+        );
+        assert!(session.spans_in(60, 100, Some(1)).next().is_none());
+    }
+}
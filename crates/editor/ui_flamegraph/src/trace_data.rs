@@ -46,6 +46,10 @@ pub struct TraceFrame {
     pub max_depth: u32,
     pub threads: HashMap<u64, ThreadInfo>,
     pub frame_times_ms: Vec<f32>, // History of frame times
+    /// Start of each frame marker, in the order recorded
+    pub frame_markers_ns: Vec<u64>,
+    /// Oldest spans evicted while collecting; non-zero means the trace is truncated
+    pub dropped_spans: u64,
 }
 
 impl TraceFrame {
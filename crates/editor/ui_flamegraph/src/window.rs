@@ -1,5 +1,6 @@
 use crate::{
-    FlamegraphPanel, FlamegraphView, InstrumentationCollector, StatisticsPanel, TraceData,
+    FlamegraphPanel, FlamegraphView, InstrumentationCollector, LoadedSession, StatisticsPanel,
+    TraceData,
 };
 use gpui::prelude::FluentBuilder;
 use gpui::*;
@@ -156,34 +157,19 @@ impl FlamegraphWindow {
     }
 
     fn load_from_database(&mut self, db_path: std::path::PathBuf, _cx: &mut Context<Self>) {
-        match rusqlite::Connection::open(&db_path) {
-            Ok(conn) => {
-                match profiling::database::load_events(&conn) {
-                    Ok(events) => {
-                        tracing::trace!(
-                            "[PROFILER] Loaded {} events from {}",
-                            events.len(),
-                            db_path.display()
-                        );
-
-                        // Convert to TraceData format
-                        if let Err(e) = crate::profiler::convert_profile_events_to_trace(
-                            &events,
-                            &self.trace_data,
-                        ) {
-                            tracing::error!("[PROFILER] Failed to convert events: {}", e);
-                        }
-
-                        self.current_db_path = Some(db_path);
-                        _cx.notify();
-                    }
-                    Err(e) => {
-                        tracing::error!("[PROFILER] Failed to load events from database: {}", e);
-                    }
-                }
+        match LoadedSession::load(&db_path) {
+            Ok(session) => {
+                // Show the recorded session in place of whatever was shown
+                self.trace_data.set_frame((**session.frame()).clone());
+                self.current_db_path = Some(db_path);
+                _cx.notify();
             }
             Err(e) => {
-                tracing::error!("[PROFILER] Failed to open database: {}", e);
+                tracing::error!(
+                    "[PROFILER] Failed to load session {}: {}",
+                    db_path.display(),
+                    e
+                );
             }
         }
     }
@@ -528,6 +514,7 @@ impl FlamegraphWindow {
         let frame = self.trace_data.get_frame();
         let span_count = frame.spans.len();
         let thread_count = frame.threads.len();
+        let dropped_spans = frame.dropped_spans;
 
         div()
             .absolute()
@@ -607,7 +594,28 @@ impl FlamegraphWindow {
                                                 .text_color(theme.foreground)
                                                 .child(format!("{}", thread_count)),
                                         ),
-                                ),
+                                )
+                                .when(dropped_spans > 0, |this| {
+                                    this.child(
+                                        h_flex()
+                                            .justify_between()
+                                            .child(
+                                                div()
+                                                    .text_base()
+                                                    .text_color(theme.muted_foreground)
+                                                    .child(
+                                                        t!("Flamegraph.SpansDropped").to_string(),
+                                                    ),
+                                            )
+                                            .child(
+                                                div()
+                                                    .text_base()
+                                                    .font_weight(gpui::FontWeight::SEMIBOLD)
+                                                    .text_color(theme.warning)
+                                                    .child(format!("{}", dropped_spans)),
+                                            ),
+                                    )
+                                }),
                         )
                         .child(
                             Button::new("stop-recording-btn")
@@ -708,6 +716,14 @@ impl Render for FlamegraphWindow {
                         theme.foreground,
                         &theme,
                     ))
+                    .when(frame.dropped_spans > 0, |this| {
+                        this.child(self.summary_chip(
+                            "Dropped",
+                            format!("{}", frame.dropped_spans),
+                            theme.warning,
+                            &theme,
+                        ))
+                    })
                     .child(self.summary_chip(
                         "Duration",
                         format!("{:.2}ms", frame.duration_ns() as f64 / 1_000_000.0),
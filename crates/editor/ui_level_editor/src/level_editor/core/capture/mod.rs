@@ -0,0 +1,213 @@
+//! Viewport capture settings - screenshots and turntables
+//!
+//! A capture re-renders the editor camera offscreen at its own resolution,
+//! so the shot doesn't depend on the viewport panel's size. Editor overlays
+//! are hidden unless asked for. Captures are always lit: the viewport's
+//! wireframe and lighting toggles only affect the panel.
+
+pub mod sidecar;
+pub mod turntable;
+
+use engine_backend::subsystems::render::{CaptureView, EditorCameraState};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+
+/// Largest width or height a capture may request, regardless of what the
+/// GPU supports. An 8K RGBA frame plus its readback buffer is ~256 MB.
+pub const MAX_CAPTURE_DIMENSION: u32 = 8192;
+
+/// Output size of a capture.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CaptureResolution {
+    /// Current size of the viewport panel
+    Viewport,
+    Hd720,
+    #[default]
+    Hd1080,
+    Qhd1440,
+    Uhd4k,
+    Custom {
+        width: u32,
+        height: u32,
+    },
+}
+
+impl CaptureResolution {
+    /// Fixed choices offered next to the custom size.
+    pub const PRESETS: [CaptureResolution; 5] = [
+        Self::Viewport,
+        Self::Hd720,
+        Self::Hd1080,
+        Self::Qhd1440,
+        Self::Uhd4k,
+    ];
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::Viewport => "Viewport".to_string(),
+            Self::Hd720 => "720p".to_string(),
+            Self::Hd1080 => "1080p".to_string(),
+            Self::Qhd1440 => "1440p".to_string(),
+            Self::Uhd4k => "4K".to_string(),
+            Self::Custom { width, height } => format!("{}×{}", width, height),
+        }
+    }
+
+    /// Pixel size, given the viewport panel's current size.
+    pub fn size(&self, viewport: (u32, u32)) -> (u32, u32) {
+        match *self {
+            Self::Viewport => viewport,
+            Self::Hd720 => (1280, 720),
+            Self::Hd1080 => (1920, 1080),
+            Self::Qhd1440 => (2560, 1440),
+            Self::Uhd4k => (3840, 2160),
+            Self::Custom { width, height } => (width, height),
+        }
+    }
+}
+
+/// Errors from capturing the viewport.
+#[derive(Debug, Clone)]
+pub enum CaptureError {
+    /// A width or height of zero
+    EmptySize,
+
+    /// Larger than the GPU or [`MAX_CAPTURE_DIMENSION`] allows
+    TooLarge { width: u32, height: u32, max: u32 },
+
+    /// The viewport hasn't rendered its first frame yet
+    RendererNotReady,
+
+    /// Copying the frame back from the GPU failed
+    Readback(String),
+
+    /// Filesystem error
+    Io { path: PathBuf, message: String },
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptySize => write!(f, "Capture size must be at least 1×1"),
+            Self::TooLarge { width, height, max } => write!(
+                f,
+                "{}×{} is larger than the {}×{} this GPU can capture",
+                width, height, max, max
+            ),
+            Self::RendererNotReady => write!(f, "The viewport hasn't rendered yet"),
+            Self::Readback(message) => write!(f, "Failed to read back the frame: {}", message),
+            Self::Io { path, message } => write!(f, "{}: {}", path.display(), message),
+        }
+    }
+}
+
+impl std::error::Error for CaptureError {}
+
+/// Check a capture size against the device's largest texture
+/// (`max_texture_dimension_2d`) and [`MAX_CAPTURE_DIMENSION`].
+pub fn validate_size(width: u32, height: u32, device_max: u32) -> Result<(), CaptureError> {
+    if width == 0 || height == 0 {
+        return Err(CaptureError::EmptySize);
+    }
+    let max = device_max.min(MAX_CAPTURE_DIMENSION);
+    if width > max || height > max {
+        return Err(CaptureError::TooLarge { width, height, max });
+    }
+    Ok(())
+}
+
+/// Editor overlays to keep in a capture. All hidden by default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureOverlays {
+    pub grid: bool,
+    /// Light and transform gizmos
+    pub gizmos: bool,
+    /// The selected actor's highlight; drawn with the gizmos
+    pub selection: bool,
+}
+
+/// Everything about a capture except the camera.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CaptureSettings {
+    pub resolution: CaptureResolution,
+    pub overlays: CaptureOverlays,
+    /// Clear to transparent instead of the editor background
+    pub transparent_background: bool,
+}
+
+impl CaptureSettings {
+    /// What the renderer needs to capture from `camera`.
+    pub fn view(&self, camera: EditorCameraState) -> CaptureView {
+        CaptureView {
+            camera,
+            show_grid: self.overlays.grid,
+            show_gizmos: self.overlays.gizmos,
+            show_selection: self.overlays.gizmos && self.overlays.selection,
+            transparent_background: self.transparent_background,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolution_sizes() {
+        assert_eq!(CaptureResolution::Viewport.size((800, 600)), (800, 600));
+        assert_eq!(CaptureResolution::Uhd4k.size((800, 600)), (3840, 2160));
+        assert_eq!(
+            CaptureResolution::Custom {
+                width: 1000,
+                height: 1000
+            }
+            .size((800, 600)),
+            (1000, 1000)
+        );
+        assert_eq!(CaptureResolution::default().size((0, 0)), (1920, 1080));
+    }
+
+    #[test]
+    fn test_validate_size() {
+        assert!(validate_size(3840, 2160, 16384).is_ok());
+        assert!(matches!(
+            validate_size(0, 1080, 16384),
+            Err(CaptureError::EmptySize)
+        ));
+        // The device limit wins when it's lower
+        assert!(matches!(
+            validate_size(3840, 2160, 2048),
+            Err(CaptureError::TooLarge { max: 2048, .. })
+        ));
+        assert!(matches!(
+            validate_size(MAX_CAPTURE_DIMENSION + 1, 100, 32768),
+            Err(CaptureError::TooLarge {
+                max: MAX_CAPTURE_DIMENSION,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_overlays_hidden_by_default() {
+        let view = CaptureSettings::default().view(EditorCameraState::default());
+        assert!(!view.show_grid);
+        assert!(!view.show_gizmos);
+        assert!(!view.show_selection);
+        assert!(!view.transparent_background);
+    }
+
+    #[test]
+    fn test_selection_needs_gizmos() {
+        let mut settings = CaptureSettings::default();
+        settings.overlays.selection = true;
+        assert!(!settings.view(EditorCameraState::default()).show_selection);
+
+        settings.overlays.gizmos = true;
+        settings.transparent_background = true;
+        let view = settings.view(EditorCameraState::default());
+        assert!(view.show_gizmos && view.show_selection && view.transparent_background);
+        assert!(!view.show_grid);
+    }
+}
@@ -0,0 +1,161 @@
+//! Capture metadata sidecars
+//!
+//! Every screenshot gets a `<name>.capture.json` next to it recording the
+//! camera and settings it was taken with, so the shot can be retaken after
+//! the scene changes. A turntable writes one sidecar for its whole frame
+//! directory.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::turntable::TurntableSettings;
+use super::{CaptureError, CaptureSettings};
+use crate::level_editor::scene_database::LevelEditorCameraState;
+
+/// Current sidecar layout.
+pub const SIDECAR_FORMAT: u32 = 1;
+
+const SIDECAR_EXTENSION: &str = "capture.json";
+
+/// Name of a turntable's sidecar inside its frame directory.
+const TURNTABLE_SIDECAR: &str = "turntable.capture.json";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CaptureMetadata {
+    pub format: u32,
+    /// The camera of the shot; for a turntable, of its first frame
+    pub camera: LevelEditorCameraState,
+    pub width: u32,
+    pub height: u32,
+    pub settings: CaptureSettings,
+    /// Scene file open at the time, if it had been saved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scene: Option<PathBuf>,
+    pub captured_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turntable: Option<TurntableMetadata>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TurntableMetadata {
+    pub settings: TurntableSettings,
+    pub pivot: [f32; 3],
+}
+
+/// Sidecar of the screenshot at `image`.
+pub fn sidecar_path(image: &Path) -> PathBuf {
+    image.with_extension(SIDECAR_EXTENSION)
+}
+
+/// Sidecar of the turntable whose frames are in `dir`.
+pub fn turntable_sidecar_path(dir: &Path) -> PathBuf {
+    dir.join(TURNTABLE_SIDECAR)
+}
+
+pub fn write(path: &Path, metadata: &CaptureMetadata) -> Result<(), CaptureError> {
+    let io_error = |message: String| CaptureError::Io {
+        path: path.to_path_buf(),
+        message,
+    };
+    let json = serde_json::to_string_pretty(metadata).map_err(|e| io_error(e.to_string()))?;
+    std::fs::write(path, json).map_err(|e| io_error(e.to_string()))
+}
+
+/// Read the metadata for `path`, which may be the sidecar itself or the
+/// screenshot it belongs to.
+pub fn read(path: &Path) -> Result<CaptureMetadata, CaptureError> {
+    let is_image = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("png"));
+    let path = if is_image {
+        sidecar_path(path)
+    } else {
+        path.to_path_buf()
+    };
+    let io_error = |message: String| CaptureError::Io {
+        path: path.clone(),
+        message,
+    };
+    let json = std::fs::read_to_string(&path).map_err(|e| io_error(e.to_string()))?;
+    let metadata: CaptureMetadata =
+        serde_json::from_str(&json).map_err(|e| io_error(e.to_string()))?;
+    if metadata.format > SIDECAR_FORMAT {
+        return Err(io_error(format!(
+            "written by a newer editor (format {})",
+            metadata.format
+        )));
+    }
+    Ok(metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level_editor::core::capture::CaptureResolution;
+
+    fn metadata() -> CaptureMetadata {
+        let mut settings = CaptureSettings {
+            resolution: CaptureResolution::Custom {
+                width: 1000,
+                height: 500,
+            },
+            transparent_background: true,
+            ..Default::default()
+        };
+        settings.overlays.grid = true;
+        CaptureMetadata {
+            format: SIDECAR_FORMAT,
+            camera: LevelEditorCameraState {
+                position: [1.0, 2.5, -3.0],
+                yaw: 0.75,
+                pitch: -0.25,
+            },
+            width: 1000,
+            height: 500,
+            settings,
+            scene: Some(PathBuf::from("scenes/level.pscene")),
+            captured_at: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            turntable: Some(TurntableMetadata {
+                settings: TurntableSettings::default(),
+                pivot: [0.0, 1.0, 0.0],
+            }),
+        }
+    }
+
+    #[test]
+    fn test_sidecar_paths() {
+        assert_eq!(
+            sidecar_path(Path::new("shots/hero.png")),
+            Path::new("shots/hero.capture.json")
+        );
+        assert_eq!(
+            turntable_sidecar_path(Path::new("shots/spin")),
+            Path::new("shots/spin/turntable.capture.json")
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let image = dir.path().join("shot.png");
+        let metadata = metadata();
+
+        write(&sidecar_path(&image), &metadata).unwrap();
+        // Readable from either the screenshot or the sidecar
+        assert_eq!(read(&image).unwrap(), metadata);
+        assert_eq!(read(&sidecar_path(&image)).unwrap(), metadata);
+    }
+
+    #[test]
+    fn test_rejects_newer_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shot.capture.json");
+        let metadata = CaptureMetadata {
+            format: SIDECAR_FORMAT + 1,
+            ..metadata()
+        };
+        write(&path, &metadata).unwrap();
+        assert!(matches!(read(&path), Err(CaptureError::Io { .. })));
+    }
+}
@@ -0,0 +1,210 @@
+//! Turntable captures - orbiting the camera around a pivot
+//!
+//! The camera keeps its distance and height relative to the pivot and is
+//! turned about the world Y axis, always facing the pivot. Each step is
+//! written as a numbered PNG that video tools can pick up as a sequence.
+
+use engine_backend::subsystems::render::EditorCameraState;
+use glam::{Quat, Vec3};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Distance in front of the camera to orbit around when nothing is selected.
+pub const DEFAULT_PIVOT_DISTANCE: f32 = 10.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TurntableSettings {
+    /// Length of the resulting clip
+    pub duration_secs: f32,
+    /// How far to orbit; negative turns clockwise seen from above
+    pub angle_degrees: f32,
+    pub fps: u32,
+}
+
+impl Default for TurntableSettings {
+    fn default() -> Self {
+        Self {
+            duration_secs: 6.0,
+            angle_degrees: 360.0,
+            fps: 30,
+        }
+    }
+}
+
+impl TurntableSettings {
+    /// Number of frames to write; at least one.
+    pub fn frame_count(&self) -> u32 {
+        ((self.duration_secs.max(0.0) * self.fps as f32).round() as u32).max(1)
+    }
+
+    /// Orbit angle of `frame`, in radians. A full turn leaves out the frame
+    /// at 360° so the clip loops without a repeated frame; a partial one ends
+    /// exactly on `angle_degrees`.
+    pub fn frame_angle(&self, frame: u32) -> f32 {
+        let count = self.frame_count();
+        let steps = if self.angle_degrees.abs() >= 360.0 || count == 1 {
+            count
+        } else {
+            count - 1
+        };
+        self.angle_degrees.to_radians() * frame as f32 / steps as f32
+    }
+}
+
+/// The camera `start` orbited by `angle` radians about the vertical axis
+/// through `pivot`, looking at the pivot.
+pub fn orbit_camera(start: &EditorCameraState, pivot: Vec3, angle: f32) -> EditorCameraState {
+    let offset = Quat::from_rotation_y(angle) * (Vec3::from_array(start.position) - pivot);
+    let position = pivot + offset;
+    let Some(dir) = (-offset).try_normalize() else {
+        // Sitting on the pivot: turn in place instead
+        return EditorCameraState {
+            yaw: start.yaw - angle,
+            ..*start
+        };
+    };
+    EditorCameraState {
+        position: position.to_array(),
+        yaw: dir.x.atan2(-dir.z),
+        pitch: dir.y.clamp(-1.0, 1.0).asin(),
+    }
+}
+
+/// What to orbit around: the selected object, or a point in front of the
+/// camera.
+pub fn turntable_pivot(camera: &EditorCameraState, selected: Option<[f32; 3]>) -> Vec3 {
+    selected.map(Vec3::from_array).unwrap_or_else(|| {
+        Vec3::from_array(camera.position) + camera.forward() * DEFAULT_PIVOT_DISTANCE
+    })
+}
+
+/// File name of frame `index` in `dir`.
+pub fn frame_path(dir: &Path, index: u32) -> PathBuf {
+    dir.join(format!("frame_{:05}.png", index))
+}
+
+/// The frames of a turntable still to be rendered.
+#[derive(Clone, Debug)]
+pub struct TurntableJob {
+    pub settings: TurntableSettings,
+    pub start: EditorCameraState,
+    pub pivot: Vec3,
+    next: u32,
+}
+
+impl TurntableJob {
+    pub fn new(settings: TurntableSettings, start: EditorCameraState, pivot: Vec3) -> Self {
+        Self {
+            settings,
+            start,
+            pivot,
+            next: 0,
+        }
+    }
+
+    /// The next frame's index and camera, or `None` once all are handed out.
+    pub fn next_frame(&mut self) -> Option<(u32, EditorCameraState)> {
+        let frame = self.next;
+        if frame >= self.settings.frame_count() {
+            return None;
+        }
+        self.next += 1;
+        let angle = self.settings.frame_angle(frame);
+        Some((frame, orbit_camera(&self.start, self.pivot, angle)))
+    }
+
+    /// Fraction of frames handed out, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        self.next as f32 / self.settings.frame_count() as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-4;
+
+    fn camera_at(position: [f32; 3]) -> EditorCameraState {
+        EditorCameraState {
+            position,
+            yaw: 0.0,
+            pitch: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_frame_count_and_angles() {
+        let settings = TurntableSettings::default();
+        assert_eq!(settings.frame_count(), 180);
+        assert_eq!(settings.frame_angle(0), 0.0);
+        // The last frame of a full turn stops one step short of 360°
+        let last = settings.frame_angle(179).to_degrees();
+        assert!((last - 358.0).abs() < EPSILON);
+
+        let partial = TurntableSettings {
+            duration_secs: 1.0,
+            angle_degrees: 90.0,
+            fps: 5,
+        };
+        assert!((partial.frame_angle(4).to_degrees() - 90.0).abs() < EPSILON);
+
+        let empty = TurntableSettings {
+            duration_secs: 0.0,
+            ..settings
+        };
+        assert_eq!(empty.frame_count(), 1);
+    }
+
+    #[test]
+    fn test_orbit_keeps_distance_and_faces_pivot() {
+        let pivot = Vec3::new(1.0, 0.5, -2.0);
+        let start = camera_at([1.0, 3.5, 2.0]);
+        for degrees in [0.0_f32, 45.0, 90.0, 180.0, 270.0] {
+            let camera = orbit_camera(&start, pivot, degrees.to_radians());
+            let position = Vec3::from_array(camera.position);
+            assert!((position.distance(pivot) - 5.0).abs() < EPSILON);
+            assert!((position.y - 3.5).abs() < EPSILON);
+            let to_pivot = (pivot - position).normalize();
+            assert!(camera.forward().distance(to_pivot) < EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_orbit_quarter_turn() {
+        // Looking down -Z at the origin; a quarter turn about +Y moves the
+        // camera from +Z to +X, now looking down -X
+        let camera = orbit_camera(&camera_at([0.0, 0.0, 4.0]), Vec3::ZERO, 90f32.to_radians());
+        assert!(Vec3::from_array(camera.position).distance(Vec3::new(4.0, 0.0, 0.0)) < EPSILON);
+        assert!(camera.forward().distance(Vec3::NEG_X) < EPSILON);
+        assert!(camera.pitch.abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_pivot_fallback() {
+        let camera = camera_at([0.0, 1.0, 0.0]);
+        assert_eq!(
+            turntable_pivot(&camera, Some([3.0, 0.0, 3.0])),
+            Vec3::new(3.0, 0.0, 3.0)
+        );
+        let pivot = turntable_pivot(&camera, None);
+        assert!(pivot.distance(Vec3::new(0.0, 1.0, -DEFAULT_PIVOT_DISTANCE)) < EPSILON);
+    }
+
+    #[test]
+    fn test_job_yields_every_frame() {
+        let settings = TurntableSettings {
+            duration_secs: 1.0,
+            angle_degrees: 360.0,
+            fps: 4,
+        };
+        let mut job = TurntableJob::new(settings, camera_at([0.0, 0.0, 4.0]), Vec3::ZERO);
+        let frames: Vec<u32> = std::iter::from_fn(|| job.next_frame().map(|(i, _)| i)).collect();
+        assert_eq!(frames, vec![0, 1, 2, 3]);
+        assert_eq!(job.progress(), 1.0);
+        assert_eq!(
+            frame_path(Path::new("shots"), 7),
+            Path::new("shots").join("frame_00007.png")
+        );
+    }
+}
@@ -1,3 +1,4 @@
+pub mod capture;
 pub mod commands;
 pub mod scene_database;
 pub mod world_settings_data;
@@ -781,7 +781,7 @@ pub struct LevelEditorFileState {
     pub camera: Option<LevelEditorCameraState>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct LevelEditorCameraState {
     pub position: [f32; 3],
     pub yaw: f32,
@@ -17,6 +17,7 @@ mod ui;
 pub mod workspace;
 
 // Module aliases so existing `crate::level_editor::X::Y` paths still compile
+pub use core::capture;
 pub use core::commands;
 pub use core::scene_database;
 pub use core::world_settings_data;
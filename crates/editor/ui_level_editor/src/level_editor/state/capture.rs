@@ -0,0 +1,84 @@
+//! Capture Domain — screenshot and turntable settings, and the requests the
+//! viewport picks up.
+//!
+//! The capture panel writes the settings and queues a request; the viewport
+//! renders it after its next frame and reports progress and the outcome back
+//! here.
+
+use std::path::PathBuf;
+
+use crate::level_editor::capture::turntable::TurntableSettings;
+use crate::level_editor::capture::CaptureSettings;
+
+/// Folder in the project that captures are saved to.
+pub const SCREENSHOT_DIR: &str = "Screenshots";
+
+/// Outcome of the last capture, shown in the capture panel.
+#[derive(Clone, Debug)]
+pub enum CaptureStatus {
+    /// Written to this file (screenshot) or directory (turntable).
+    Saved(PathBuf),
+    Failed(String),
+    Cancelled,
+}
+
+/// Capture domain.
+#[derive(Clone, Default)]
+pub struct CaptureDomain {
+    /// Whether the capture panel is open over the viewport.
+    pub show_panel: bool,
+    pub settings: CaptureSettings,
+    pub turntable: TurntableSettings,
+    /// Screenshot to take after the next frame. Taken by the viewport.
+    pub pending_screenshot: Option<PathBuf>,
+    /// Directory to start a turntable into. Taken by the viewport.
+    pub pending_turntable: Option<PathBuf>,
+    /// Fraction of the running turntable rendered, `None` when idle.
+    pub turntable_progress: Option<f32>,
+    /// Set to stop the running turntable; cleared by the viewport.
+    pub cancel_requested: bool,
+    pub status: Option<CaptureStatus>,
+}
+
+impl CaptureDomain {
+    pub fn is_busy(&self) -> bool {
+        self.pending_screenshot.is_some()
+            || self.pending_turntable.is_some()
+            || self.turntable_progress.is_some()
+    }
+
+    /// Queue a screenshot into the project's [`SCREENSHOT_DIR`].
+    pub fn request_screenshot(&mut self) {
+        if let Some(dir) = self.output_dir() {
+            self.pending_screenshot = Some(dir.join(format!("{}.png", timestamped("screenshot"))));
+        }
+    }
+
+    /// Queue a turntable into a new directory in the project's
+    /// [`SCREENSHOT_DIR`].
+    pub fn request_turntable(&mut self) {
+        if let Some(dir) = self.output_dir() {
+            self.pending_turntable = Some(dir.join(timestamped("turntable")));
+        }
+    }
+
+    fn output_dir(&mut self) -> Option<PathBuf> {
+        match engine_state::get_project_path() {
+            Some(project) => Some(PathBuf::from(project).join(SCREENSHOT_DIR)),
+            None => {
+                self.status = Some(CaptureStatus::Failed(
+                    "Open a project to save captures".to_string(),
+                ));
+                None
+            }
+        }
+    }
+}
+
+fn timestamped(prefix: &str) -> String {
+    format!(
+        "{}_{}",
+        prefix,
+        chrono::Local::now().format("%Y%m%d_%H%M%S")
+    )
+}
@@ -14,6 +14,7 @@
 //! | [`HierarchyDomain`](hierarchy::HierarchyDomain) | Hierarchy expand/collapse state, drag-and-drop state |
 //! | [`BuildDomain`](build::BuildDomain) | Build configuration, platform target, game process |
 //! | [`PlayDomain`](play::PlayDomain) | Play-mode parameters: time scale, target FPS, multiplayer mode |
+//! | [`CaptureDomain`](capture::CaptureDomain) | Screenshot and turntable settings, pending captures, progress |
 //!
 //! All mutation goes through [`execute_command`](super::commands::execute_command) or
 //! domain-specific methods. Direct field mutation outside of these paths is discouraged
@@ -26,6 +27,7 @@
 //! `SceneDomain` provides lock-free concurrent reads for the renderer.
 
 pub mod build;
+pub mod capture;
 pub mod editor;
 pub mod hierarchy;
 pub mod overlays;
@@ -33,6 +35,7 @@ pub mod play;
 pub mod scene;
 
 pub use build::BuildDomain;
+pub use capture::{CaptureDomain, CaptureStatus};
 pub use editor::EditorDomain;
 pub use hierarchy::HierarchyDomain;
 pub use overlays::OverlayDomain;
@@ -70,6 +73,9 @@ pub struct LevelEditorState {
 
     /// Play-mode state — time scale, target FPS, multiplayer.
     pub play: PlayDomain,
+
+    /// Viewport captures — screenshot and turntable settings and requests.
+    pub capture: CaptureDomain,
 }
 
 impl Default for LevelEditorState {
@@ -81,6 +87,7 @@ impl Default for LevelEditorState {
             hierarchy: HierarchyDomain::default(),
             build: BuildDomain::default(),
             play: PlayDomain::default(),
+            capture: CaptureDomain::default(),
         }
     }
 }
@@ -677,7 +677,19 @@ fn run_build_pipeline(
 
     let project_root_thread = project_root.clone();
     std::thread::spawn(move || {
+        let engine = engine_state::EngineContext::global();
+        if let Some(engine) = engine {
+            engine.publish(engine_state::BuildStarted {
+                project_root: project_root_thread.clone(),
+            });
+        }
         let result = run_cargo_build(&project_root_thread, progress_for_thread, status_for_thread);
+        if let Some(engine) = engine {
+            engine.publish(engine_state::BuildFinished {
+                project_root: project_root_thread.clone(),
+                succeeded: result.is_ok(),
+            });
+        }
         smol::block_on(result_tx.send(result));
     });
 
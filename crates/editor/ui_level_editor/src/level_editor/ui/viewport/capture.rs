@@ -0,0 +1,573 @@
+//! Offscreen viewport captures — project thumbnails, screenshots and
+//! turntables.
+//!
+//! Everything renders through [`render_image`]: draw into an offscreen
+//! texture, copy it back and decode it to what the viewport shows. GPU work
+//! stays on the main thread between viewport frames; PNG encoding runs on a
+//! writer thread. Turntable frames are spread over viewport frames within
+//! [`TURNTABLE_FRAME_BUDGET`], and stop being rendered while
+//! [`MAX_QUEUED_IMAGES`] are waiting to be written so a long turntable never
+//! holds more than a few frames in memory.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use engine_backend::services::gpu_renderer::GpuRenderer;
+use engine_backend::subsystems::render::CaptureView;
+use image::RgbaImage;
+
+use crate::level_editor::capture::sidecar::{self, CaptureMetadata, TurntableMetadata};
+use crate::level_editor::capture::turntable::{self, TurntableJob};
+use crate::level_editor::capture::{validate_size, CaptureError};
+use crate::level_editor::scene_database::LevelEditorCameraState;
+use crate::level_editor::state::{CaptureStatus, LevelEditorState};
+
+/// Time per viewport frame spent rendering turntable frames. At least one
+/// frame is always rendered.
+const TURNTABLE_FRAME_BUDGET: Duration = Duration::from_millis(12);
+
+/// Images rendered but not yet written before rendering pauses.
+const MAX_QUEUED_IMAGES: usize = 4;
+
+/// Render the scene into a `width`×`height` image. With `capture`, renders
+/// from its camera and overlays; without, exactly what the viewport shows.
+pub(super) fn render_image(
+    engine: &mut GpuRenderer,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    width: u32,
+    height: u32,
+    format: wgpu::TextureFormat,
+    capture: Option<&CaptureView>,
+) -> Result<RgbaImage, CaptureError> {
+    validate_size(width, height, device.limits().max_texture_dimension_2d)?;
+
+    let size = wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("viewport-capture"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    match capture {
+        Some(capture) => {
+            if !engine.render_capture(&view, width, height, capture) {
+                return Err(CaptureError::RendererNotReady);
+            }
+        }
+        None => engine.render_frame_to_surface(device, queue, &view, width, height, format),
+    }
+
+    let bytes_per_row = align_up(width * 4, 256);
+    let staging = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("viewport-capture-staging"),
+        size: (bytes_per_row * height) as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("viewport-capture-readback"),
+    });
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::TexelCopyBufferInfo {
+            buffer: &staging,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: None,
+            },
+        },
+        size,
+    );
+    queue.submit([encoder.finish()]);
+
+    let slice = staging.slice(..);
+    let (tx, rx) = mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |r| {
+        let _ = tx.send(r);
+    });
+    let _ = device.poll(wgpu::PollType::wait_indefinitely());
+
+    match rx.recv() {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => return Err(CaptureError::Readback(e.to_string())),
+        Err(_) => {
+            return Err(CaptureError::Readback(
+                "mapping never completed".to_string(),
+            ))
+        }
+    }
+
+    let data = slice
+        .get_mapped_range()
+        .map_err(|e| CaptureError::Readback(format!("{:?}", e)))?;
+    let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+    for row in 0..height {
+        let start = (row * bytes_per_row) as usize;
+        let end = start + (width * 4) as usize;
+        pixels.extend_from_slice(&data[start..end]);
+    }
+    drop(data);
+    staging.unmap();
+
+    // The captured texture stores correctly sRGB-encoded bytes, but the live editor
+    // viewport is composited via a shader that samples this `_Srgb` texture (auto
+    // decoding sRGB -> linear) and writes the result directly into a non-sRGB
+    // swapchain target (no re-encode). That makes the on-screen viewport appear
+    // darker than the raw captured bytes. Apply the same sRGB -> linear decode here
+    // so the saved image matches what the user actually sees in the editor.
+    let srgb_to_linear_lut = srgb_to_linear_lut();
+    for px in pixels.chunks_exact_mut(4) {
+        px[0] = srgb_to_linear_lut[px[0] as usize];
+        px[1] = srgb_to_linear_lut[px[1] as usize];
+        px[2] = srgb_to_linear_lut[px[2] as usize];
+    }
+
+    RgbaImage::from_raw(width, height, pixels).ok_or_else(|| {
+        CaptureError::Readback(format!(
+            "pixel buffer size mismatch for {}x{}",
+            width, height
+        ))
+    })
+}
+
+fn align_up(n: u32, align: u32) -> u32 {
+    (n + align - 1) & !(align - 1)
+}
+
+/// Builds an 8-bit sRGB-decode (EOTF) lookup table, mapping each sRGB-encoded
+/// byte value to its linear-light equivalent (also expressed as a byte 0-255).
+fn srgb_to_linear_lut() -> [u8; 256] {
+    let mut lut = [0u8; 256];
+    for (i, entry) in lut.iter_mut().enumerate() {
+        let c = i as f32 / 255.0;
+        let linear = if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        };
+        *entry = (linear * 255.0).round().clamp(0.0, 255.0) as u8;
+    }
+    lut
+}
+
+fn save_png(image: &RgbaImage, path: &Path) -> Result<(), CaptureError> {
+    let io_error = |message: String| CaptureError::Io {
+        path: path.to_path_buf(),
+        message,
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| io_error(e.to_string()))?;
+    }
+    image.save(path).map_err(|e| io_error(e.to_string()))
+}
+
+/// Renders the current Helio scene into an offscreen texture and writes it to
+/// `out_path` as a PNG. Used to capture project thumbnails on scene save.
+pub(super) fn capture_viewport_thumbnail(
+    engine: &mut GpuRenderer,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    width: u32,
+    height: u32,
+    format: wgpu::TextureFormat,
+    out_path: &Path,
+) {
+    let result = render_image(engine, device, queue, width, height, format, None)
+        .and_then(|image| save_png(&image, out_path));
+    match result {
+        Ok(()) => tracing::info!(
+            "[THUMBNAIL] Saved viewport thumbnail to {}",
+            out_path.display()
+        ),
+        Err(e) => tracing::warn!("[THUMBNAIL] {}", e),
+    }
+}
+
+// ── Screenshots & turntables ────────────────────────────────────────────────
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum WriteKind {
+    Screenshot,
+    TurntableFrame,
+}
+
+struct WriteJob {
+    kind: WriteKind,
+    image: RgbaImage,
+    path: PathBuf,
+    sidecar: Option<(PathBuf, CaptureMetadata)>,
+}
+
+struct WriteResult {
+    kind: WriteKind,
+    path: PathBuf,
+    result: Result<(), CaptureError>,
+}
+
+/// Writes captured images on a background thread.
+struct CaptureWriter {
+    jobs: mpsc::SyncSender<WriteJob>,
+    results: mpsc::Receiver<WriteResult>,
+}
+
+impl CaptureWriter {
+    fn spawn() -> Self {
+        let (jobs, job_rx) = mpsc::sync_channel::<WriteJob>(MAX_QUEUED_IMAGES);
+        let (result_tx, results) = mpsc::channel();
+        std::thread::spawn(move || {
+            for job in job_rx {
+                let mut result = save_png(&job.image, &job.path);
+                if let (Ok(()), Some((path, metadata))) = (&result, &job.sidecar) {
+                    result = sidecar::write(path, metadata);
+                }
+                let _ = result_tx.send(WriteResult {
+                    kind: job.kind,
+                    path: job.path,
+                    result,
+                });
+            }
+        });
+        Self { jobs, results }
+    }
+}
+
+struct RunningTurntable {
+    job: TurntableJob,
+    dir: PathBuf,
+    view: CaptureView,
+    width: u32,
+    height: u32,
+    metadata: CaptureMetadata,
+    /// Frames handed to the writer and not yet reported back
+    in_flight: usize,
+    error: Option<CaptureError>,
+}
+
+/// Carries out the screenshots and turntables queued in the capture domain.
+/// Owned by the viewport, which calls [`run`](Self::run) after each frame.
+#[derive(Default)]
+pub(super) struct CaptureRunner {
+    writer: Option<CaptureWriter>,
+    turntable: Option<RunningTurntable>,
+}
+
+impl CaptureRunner {
+    /// Start queued captures, render turntable frames within budget and
+    /// report finished writes. `viewport` is the panel's current size.
+    pub(super) fn run(
+        &mut self,
+        engine: &mut GpuRenderer,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        format: wgpu::TextureFormat,
+        viewport: (u32, u32),
+        shared_state: &Arc<parking_lot::RwLock<LevelEditorState>>,
+    ) {
+        self.collect_results(shared_state);
+
+        let (screenshot, turntable_dir, cancel, settings, turntable_settings, scene, selected) = {
+            let mut state = shared_state.write();
+            let capture = &mut state.capture;
+            (
+                capture.pending_screenshot.take(),
+                capture.pending_turntable.take(),
+                std::mem::take(&mut capture.cancel_requested),
+                capture.settings,
+                capture.turntable,
+                state.scene.current_scene.clone(),
+                state
+                    .scene
+                    .get_selected_object()
+                    .map(|object| object.transform.position),
+            )
+        };
+        let Some(camera) = engine.editor_camera_state() else {
+            if screenshot.is_some() || turntable_dir.is_some() {
+                set_status(
+                    shared_state,
+                    CaptureStatus::Failed(CaptureError::RendererNotReady.to_string()),
+                );
+            }
+            return;
+        };
+        let (width, height) = settings.resolution.size(viewport);
+        let view = settings.view(camera);
+        let metadata = CaptureMetadata {
+            format: sidecar::SIDECAR_FORMAT,
+            camera: LevelEditorCameraState {
+                position: camera.position,
+                yaw: camera.yaw,
+                pitch: camera.pitch,
+            },
+            width,
+            height,
+            settings,
+            scene,
+            captured_at: Utc::now(),
+            turntable: None,
+        };
+
+        if cancel && self.turntable.take().is_some() {
+            let mut state = shared_state.write();
+            state.capture.turntable_progress = None;
+            state.capture.status = Some(CaptureStatus::Cancelled);
+        }
+
+        if let Some(path) = screenshot {
+            let result = render_image(engine, device, queue, width, height, format, Some(&view))
+                .and_then(|image| {
+                    let sidecar = (sidecar::sidecar_path(&path), metadata.clone());
+                    self.submit(WriteJob {
+                        kind: WriteKind::Screenshot,
+                        image,
+                        path: path.clone(),
+                        sidecar: Some(sidecar),
+                    })
+                });
+            if let Err(e) = result {
+                set_status(shared_state, CaptureStatus::Failed(e.to_string()));
+            }
+        }
+
+        if let Some(dir) = turntable_dir {
+            match validate_size(width, height, device.limits().max_texture_dimension_2d) {
+                Ok(()) => {
+                    let pivot = turntable::turntable_pivot(&camera, selected);
+                    self.turntable = Some(RunningTurntable {
+                        job: TurntableJob::new(turntable_settings, camera, pivot),
+                        dir,
+                        view,
+                        width,
+                        height,
+                        metadata: CaptureMetadata {
+                            turntable: Some(TurntableMetadata {
+                                settings: turntable_settings,
+                                pivot: pivot.to_array(),
+                            }),
+                            ..metadata
+                        },
+                        in_flight: 0,
+                        error: None,
+                    });
+                    shared_state.write().capture.turntable_progress = Some(0.0);
+                }
+                Err(e) => set_status(shared_state, CaptureStatus::Failed(e.to_string())),
+            }
+        }
+
+        self.render_turntable_frames(engine, device, queue, format, shared_state);
+    }
+
+    fn render_turntable_frames(
+        &mut self,
+        engine: &mut GpuRenderer,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        format: wgpu::TextureFormat,
+        shared_state: &Arc<parking_lot::RwLock<LevelEditorState>>,
+    ) {
+        let Some(running) = self.turntable.as_mut() else {
+            return;
+        };
+        let start = Instant::now();
+        while running.error.is_none()
+            && running.in_flight < MAX_QUEUED_IMAGES
+            && (running.in_flight == 0 || start.elapsed() < TURNTABLE_FRAME_BUDGET)
+        {
+            let Some((index, camera)) = running.job.next_frame() else {
+                break;
+            };
+            let view = CaptureView {
+                camera,
+                ..running.view
+            };
+            let writer = self.writer.get_or_insert_with(CaptureWriter::spawn);
+            let result = render_image(
+                engine,
+                device,
+                queue,
+                running.width,
+                running.height,
+                format,
+                Some(&view),
+            )
+            .and_then(|image| {
+                submit(
+                    writer,
+                    WriteJob {
+                        kind: WriteKind::TurntableFrame,
+                        image,
+                        path: turntable::frame_path(&running.dir, index),
+                        sidecar: None,
+                    },
+                )
+            });
+            match result {
+                Ok(()) => running.in_flight += 1,
+                Err(e) => running.error = Some(e),
+            }
+        }
+        shared_state.write().capture.turntable_progress = Some(running.job.progress());
+        self.finish_turntable(shared_state);
+    }
+
+    /// Report the running turntable once every frame has been written, or
+    /// as soon as one failed.
+    fn finish_turntable(&mut self, shared_state: &Arc<parking_lot::RwLock<LevelEditorState>>) {
+        let Some(running) = self.turntable.as_ref() else {
+            return;
+        };
+        let status = if let Some(e) = &running.error {
+            CaptureStatus::Failed(e.to_string())
+        } else if running.in_flight == 0 && running.job.progress() >= 1.0 {
+            let path = sidecar::turntable_sidecar_path(&running.dir);
+            match sidecar::write(&path, &running.metadata) {
+                Ok(()) => CaptureStatus::Saved(running.dir.clone()),
+                Err(e) => CaptureStatus::Failed(e.to_string()),
+            }
+        } else {
+            return;
+        };
+        self.turntable = None;
+        let mut state = shared_state.write();
+        state.capture.turntable_progress = None;
+        state.capture.status = Some(status);
+    }
+
+    fn submit(&mut self, job: WriteJob) -> Result<(), CaptureError> {
+        submit(self.writer.get_or_insert_with(CaptureWriter::spawn), job)
+    }
+
+    fn collect_results(&mut self, shared_state: &Arc<parking_lot::RwLock<LevelEditorState>>) {
+        let Some(writer) = &self.writer else {
+            return;
+        };
+        for WriteResult { kind, path, result } in writer.results.try_iter() {
+            match kind {
+                WriteKind::Screenshot => match result {
+                    Ok(()) => {
+                        tracing::info!("[CAPTURE] Saved screenshot to {}", path.display());
+                        set_status(shared_state, CaptureStatus::Saved(path));
+                    }
+                    Err(e) => set_status(shared_state, CaptureStatus::Failed(e.to_string())),
+                },
+                WriteKind::TurntableFrame => {
+                    // Frames of a cancelled turntable still report in
+                    let Some(running) = self
+                        .turntable
+                        .as_mut()
+                        .filter(|running| path.parent() == Some(running.dir.as_path()))
+                    else {
+                        continue;
+                    };
+                    running.in_flight = running.in_flight.saturating_sub(1);
+                    if let Err(e) = result {
+                        running.error.get_or_insert(e);
+                    }
+                }
+            }
+        }
+        self.finish_turntable(shared_state);
+    }
+}
+
+fn submit(writer: &CaptureWriter, job: WriteJob) -> Result<(), CaptureError> {
+    let path = job.path.clone();
+    writer.jobs.send(job).map_err(|_| CaptureError::Io {
+        path,
+        message: "capture writer stopped".to_string(),
+    })
+}
+
+fn set_status(shared_state: &Arc<parking_lot::RwLock<LevelEditorState>>, status: CaptureStatus) {
+    if let CaptureStatus::Failed(message) = &status {
+        tracing::error!("[CAPTURE] {}", message);
+    }
+    shared_state.write().capture.status = Some(status);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level_editor::capture::CaptureSettings;
+    use engine_backend::services::gpu_renderer::GpuRendererBuilder;
+    use engine_backend::subsystems::render::EditorCameraState;
+
+    #[test]
+    #[ignore = "needs a GPU adapter, run with --ignored"]
+    fn test_capture_renders_requested_size() {
+        pollster::block_on(async {
+            let instance =
+                wgpu::Instance::new(wgpu::InstanceDescriptor::new_without_display_handle());
+            let mut adapter = None;
+            for force_fallback_adapter in [false, true] {
+                if let Ok(found) = instance
+                    .request_adapter(&wgpu::RequestAdapterOptions {
+                        power_preference: wgpu::PowerPreference::HighPerformance,
+                        compatible_surface: None,
+                        force_fallback_adapter,
+                        apply_limit_buckets: false,
+                    })
+                    .await
+                {
+                    adapter = Some(found);
+                    break;
+                }
+            }
+            let gpu = adapter.expect("no GPU adapter, not even a fallback one");
+            let (device, queue) = gpu
+                .request_device(&wgpu::DeviceDescriptor {
+                    label: Some("Pulsar viewport capture test device"),
+                    required_features: gpu.features(),
+                    required_limits: gpu.limits(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            let format = wgpu::TextureFormat::Rgba8UnormSrgb;
+            let mut engine = GpuRendererBuilder::new(64, 64).build();
+
+            // Before the first viewport frame there is no renderer to capture with
+            let view = CaptureSettings::default().view(EditorCameraState::default());
+            assert!(matches!(
+                render_image(&mut engine, &device, &queue, 32, 32, format, Some(&view)),
+                Err(CaptureError::RendererNotReady)
+            ));
+
+            // The viewport's own frame, which initialises the renderer
+            let thumbnail =
+                render_image(&mut engine, &device, &queue, 64, 48, format, None).unwrap();
+            assert_eq!(thumbnail.dimensions(), (64, 48));
+
+            // A capture larger than, and in a different aspect from, the viewport
+            let camera = engine.editor_camera_state().unwrap();
+            let view = CaptureSettings::default().view(camera);
+            let image =
+                render_image(&mut engine, &device, &queue, 320, 180, format, Some(&view)).unwrap();
+            assert_eq!(image.dimensions(), (320, 180));
+            // The editor background at least, not an empty buffer
+            assert!(image.pixels().any(|px| px.0 != [0; 4]));
+
+            assert!(matches!(
+                render_image(&mut engine, &device, &queue, 0, 180, format, Some(&view)),
+                Err(CaptureError::EmptySize)
+            ));
+        });
+    }
+}
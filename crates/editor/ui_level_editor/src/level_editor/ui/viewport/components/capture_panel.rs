@@ -0,0 +1,492 @@
+//! Capture panel component.
+//!
+//! A floating panel for taking screenshots and turntables of the viewport:
+//! output resolution, which editor overlays to keep, turntable timing, and
+//! restoring the camera a screenshot was taken with.
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use engine_backend::services::gpu_renderer::GpuRenderer;
+use engine_backend::subsystems::render::EditorCameraState;
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use ui::{
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    input::{InputState, NumberInput},
+    switch::Switch,
+    v_flex, ActiveTheme, Disableable as _, IconName, Selectable, Sizable,
+};
+
+use crate::level_editor::capture::{sidecar, CaptureResolution};
+use crate::level_editor::state::capture::SCREENSHOT_DIR;
+use crate::level_editor::state::{CaptureStatus, LevelEditorState};
+
+/// Frame rates offered for turntables.
+const TURNTABLE_FPS: [u32; 3] = [24, 30, 60];
+
+/// Width and height typed into the custom size inputs, if both are numbers.
+fn custom_size(
+    width_input: &Entity<InputState>,
+    height_input: &Entity<InputState>,
+    cx: &App,
+) -> Option<CaptureResolution> {
+    let width = width_input
+        .read(cx)
+        .text()
+        .to_string()
+        .trim()
+        .parse()
+        .ok()?;
+    let height = height_input
+        .read(cx)
+        .text()
+        .to_string()
+        .trim()
+        .parse()
+        .ok()?;
+    Some(CaptureResolution::Custom { width, height })
+}
+
+fn section_label<V: 'static + Render>(
+    label: impl Into<SharedString>,
+    cx: &Context<V>,
+) -> impl IntoElement {
+    div()
+        .text_xs()
+        .text_color(cx.theme().muted_foreground)
+        .child(label.into())
+}
+
+/// Labelled switch writing one capture setting.
+fn setting_switch<V: 'static + Render>(
+    id: &'static str,
+    label: &'static str,
+    checked: bool,
+    state_arc: Arc<parking_lot::RwLock<LevelEditorState>>,
+    set: fn(&mut LevelEditorState, bool),
+    cx: &Context<V>,
+) -> impl IntoElement {
+    h_flex()
+        .gap_1()
+        .items_center()
+        .child(section_label(label, cx))
+        .child(
+            Switch::new(id)
+                .checked(checked)
+                .on_click(move |checked, _, _| set(&mut state_arc.write(), *checked)),
+        )
+}
+
+/// Output size: presets, or a custom size from the two inputs.
+fn resolution_row<V: 'static + Render>(
+    state: &LevelEditorState,
+    state_arc: Arc<parking_lot::RwLock<LevelEditorState>>,
+    width_input: &Entity<InputState>,
+    height_input: &Entity<InputState>,
+    cx: &Context<V>,
+) -> impl IntoElement {
+    let current = state.capture.settings.resolution;
+    let is_custom = matches!(current, CaptureResolution::Custom { .. });
+
+    v_flex()
+        .gap_1()
+        .child(section_label("Resolution", cx))
+        .child(
+            h_flex()
+                .gap_1()
+                .children(CaptureResolution::PRESETS.iter().map(|&resolution| {
+                    let state_clone = state_arc.clone();
+                    Button::new(SharedString::from(format!(
+                        "capture_res_{}",
+                        resolution.label()
+                    )))
+                    .label(resolution.label())
+                    .xsmall()
+                    .ghost()
+                    .selected(current == resolution)
+                    .on_click(move |_, _, _| {
+                        state_clone.write().capture.settings.resolution = resolution;
+                    })
+                }))
+                .child({
+                    let state_clone = state_arc.clone();
+                    let width_input = width_input.clone();
+                    let height_input = height_input.clone();
+                    Button::new("capture_res_custom")
+                        .label("Custom")
+                        .xsmall()
+                        .ghost()
+                        .selected(is_custom)
+                        .on_click(move |_, _, cx| {
+                            if let Some(resolution) = custom_size(&width_input, &height_input, cx) {
+                                state_clone.write().capture.settings.resolution = resolution;
+                            }
+                        })
+                }),
+        )
+        .child(
+            h_flex()
+                .gap_1()
+                .items_center()
+                .child(
+                    div()
+                        .w(px(80.0))
+                        .child(NumberInput::new(width_input).xsmall()),
+                )
+                .child(section_label("×", cx))
+                .child(
+                    div()
+                        .w(px(80.0))
+                        .child(NumberInput::new(height_input).xsmall()),
+                ),
+        )
+}
+
+fn overlay_row<V: 'static + Render>(
+    state: &LevelEditorState,
+    state_arc: Arc<parking_lot::RwLock<LevelEditorState>>,
+    cx: &Context<V>,
+) -> impl IntoElement {
+    let settings = state.capture.settings;
+    h_flex()
+        .gap_2()
+        .items_center()
+        .child(setting_switch(
+            "capture_grid",
+            "Grid",
+            settings.overlays.grid,
+            state_arc.clone(),
+            |s, on| s.capture.settings.overlays.grid = on,
+            cx,
+        ))
+        .child(setting_switch(
+            "capture_gizmos",
+            "Gizmos",
+            settings.overlays.gizmos,
+            state_arc.clone(),
+            |s, on| s.capture.settings.overlays.gizmos = on,
+            cx,
+        ))
+        .child(setting_switch(
+            "capture_selection",
+            "Selection",
+            settings.overlays.selection,
+            state_arc.clone(),
+            |s, on| s.capture.settings.overlays.selection = on,
+            cx,
+        ))
+        .child(setting_switch(
+            "capture_transparent",
+            "Transparent",
+            settings.transparent_background,
+            state_arc,
+            |s, on| s.capture.settings.transparent_background = on,
+            cx,
+        ))
+}
+
+/// A value with -/+ buttons.
+fn stepper<V: 'static + Render>(
+    id: &'static str,
+    value: String,
+    state_arc: Arc<parking_lot::RwLock<LevelEditorState>>,
+    step: fn(&mut LevelEditorState, f32),
+    cx: &Context<V>,
+) -> impl IntoElement {
+    let down_state = state_arc.clone();
+    h_flex()
+        .gap_1()
+        .items_center()
+        .child(
+            Button::new(SharedString::from(format!("{}_down", id)))
+                .icon(IconName::Minus)
+                .xsmall()
+                .ghost()
+                .on_click(move |_, _, _| step(&mut down_state.write(), -1.0)),
+        )
+        .child(
+            div()
+                .text_xs()
+                .min_w(px(36.0))
+                .text_center()
+                .text_color(cx.theme().foreground)
+                .child(value),
+        )
+        .child(
+            Button::new(SharedString::from(format!("{}_up", id)))
+                .icon(IconName::Plus)
+                .xsmall()
+                .ghost()
+                .on_click(move |_, _, _| step(&mut state_arc.write(), 1.0)),
+        )
+}
+
+fn turntable_row<V: 'static + Render>(
+    state: &LevelEditorState,
+    state_arc: Arc<parking_lot::RwLock<LevelEditorState>>,
+    cx: &Context<V>,
+) -> impl IntoElement {
+    let turntable = state.capture.turntable;
+    v_flex()
+        .gap_1()
+        .child(section_label("Turntable", cx))
+        .child(
+            h_flex()
+                .gap_2()
+                .items_center()
+                .child(stepper(
+                    "turntable_duration",
+                    format!("{:.0}s", turntable.duration_secs),
+                    state_arc.clone(),
+                    |s, dir| {
+                        let duration = &mut s.capture.turntable.duration_secs;
+                        *duration = (*duration + dir).clamp(1.0, 120.0);
+                    },
+                    cx,
+                ))
+                .child(stepper(
+                    "turntable_angle",
+                    format!("{:.0}°", turntable.angle_degrees),
+                    state_arc.clone(),
+                    |s, dir| {
+                        let angle = &mut s.capture.turntable.angle_degrees;
+                        *angle = (*angle + dir * 45.0).clamp(-720.0, 720.0);
+                    },
+                    cx,
+                ))
+                .children(TURNTABLE_FPS.iter().map(|&fps| {
+                    let state_clone = state_arc.clone();
+                    Button::new(SharedString::from(format!("turntable_fps_{}", fps)))
+                        .label(format!("{} fps", fps))
+                        .xsmall()
+                        .ghost()
+                        .selected(turntable.fps == fps)
+                        .on_click(move |_, _, _| {
+                            state_clone.write().capture.turntable.fps = fps;
+                        })
+                })),
+        )
+}
+
+/// Capture buttons, turntable progress and the last capture's outcome.
+fn action_row<V: 'static + Render>(
+    state: &LevelEditorState,
+    state_arc: Arc<parking_lot::RwLock<LevelEditorState>>,
+    width_input: &Entity<InputState>,
+    height_input: &Entity<InputState>,
+    gpu_engine: &Arc<Mutex<GpuRenderer>>,
+    cx: &Context<V>,
+) -> impl IntoElement {
+    let busy = state.capture.is_busy();
+    let progress = state.capture.turntable_progress;
+
+    // A custom size is read again when capturing, so edits made after
+    // pressing "Custom" still count.
+    let refresh_custom = {
+        let width_input = width_input.clone();
+        let height_input = height_input.clone();
+        move |state: &mut LevelEditorState, cx: &App| {
+            if matches!(
+                state.capture.settings.resolution,
+                CaptureResolution::Custom { .. }
+            ) {
+                if let Some(resolution) = custom_size(&width_input, &height_input, cx) {
+                    state.capture.settings.resolution = resolution;
+                }
+            }
+        }
+    };
+
+    v_flex()
+        .gap_1()
+        .child(
+            h_flex()
+                .gap_1()
+                .items_center()
+                .child({
+                    let state_clone = state_arc.clone();
+                    let refresh_custom = refresh_custom.clone();
+                    Button::new("capture_screenshot")
+                        .icon(IconName::Camera)
+                        .label("Screenshot")
+                        .xsmall()
+                        .primary()
+                        .disabled(busy)
+                        .on_click(move |_, _, cx| {
+                            let mut state = state_clone.write();
+                            refresh_custom(&mut state, cx);
+                            state.capture.request_screenshot();
+                        })
+                })
+                .child({
+                    let state_clone = state_arc.clone();
+                    Button::new("capture_turntable")
+                        .icon(IconName::Refresh)
+                        .label("Turntable")
+                        .xsmall()
+                        .disabled(busy)
+                        .on_click(move |_, _, cx| {
+                            let mut state = state_clone.write();
+                            refresh_custom(&mut state, cx);
+                            state.capture.request_turntable();
+                        })
+                })
+                .child({
+                    let gpu_engine = gpu_engine.clone();
+                    let state_clone = state_arc.clone();
+                    Button::new("capture_restore_camera")
+                        .icon(IconName::Undo)
+                        .label("Restore Camera…")
+                        .xsmall()
+                        .ghost()
+                        .tooltip("Move the camera to where a screenshot was taken")
+                        .on_click(move |_, _, cx| {
+                            restore_camera_from_capture(gpu_engine.clone(), state_clone.clone(), cx)
+                        })
+                }),
+        )
+        .when_some(progress, |this, progress| {
+            this.child(
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .child(
+                        div()
+                            .flex_1()
+                            .h(px(4.0))
+                            .rounded_full()
+                            .bg(cx.theme().muted.opacity(0.3))
+                            .child(
+                                div()
+                                    .h_full()
+                                    .rounded_full()
+                                    .bg(cx.theme().primary)
+                                    .w(relative(progress)),
+                            ),
+                    )
+                    .child(section_label(format!("{:.0}%", progress * 100.0), cx))
+                    .child({
+                        let state_clone = state_arc.clone();
+                        Button::new("capture_cancel")
+                            .label("Cancel")
+                            .xsmall()
+                            .ghost()
+                            .on_click(move |_, _, _| {
+                                state_clone.write().capture.cancel_requested = true;
+                            })
+                    }),
+            )
+        })
+        .when_some(state.capture.status.clone(), |this, status| {
+            let (text, color) = match status {
+                CaptureStatus::Saved(path) => (
+                    format!("Saved {}", path.display()),
+                    cx.theme().muted_foreground,
+                ),
+                CaptureStatus::Failed(message) => (message, cx.theme().danger),
+                CaptureStatus::Cancelled => (
+                    "Turntable cancelled".to_string(),
+                    cx.theme().muted_foreground,
+                ),
+            };
+            this.child(div().text_xs().text_color(color).child(text))
+        })
+}
+
+/// Pick a screenshot or its sidecar and move the editor camera to where it
+/// was taken.
+fn restore_camera_from_capture(
+    gpu_engine: Arc<Mutex<GpuRenderer>>,
+    state_arc: Arc<parking_lot::RwLock<LevelEditorState>>,
+    cx: &mut App,
+) {
+    let mut dialog = rfd::AsyncFileDialog::new()
+        .set_title("Restore Camera from Screenshot")
+        .add_filter("Screenshot", &["png", "json"]);
+    if let Some(project) = engine_state::get_project_path() {
+        dialog = dialog.set_directory(PathBuf::from(project).join(SCREENSHOT_DIR));
+    }
+    cx.spawn(async move |_cx| {
+        let Some(handle) = dialog.pick_file().await else {
+            return;
+        };
+        match sidecar::read(handle.path()) {
+            Ok(metadata) => {
+                if let Ok(mut engine) = gpu_engine.lock() {
+                    engine.set_editor_camera_state(EditorCameraState {
+                        position: metadata.camera.position,
+                        yaw: metadata.camera.yaw,
+                        pitch: metadata.camera.pitch,
+                    });
+                }
+            }
+            Err(e) => {
+                state_arc.write().capture.status = Some(CaptureStatus::Failed(e.to_string()));
+            }
+        }
+    })
+    .detach();
+}
+
+/// Render the capture panel.
+pub fn render_capture_panel<V>(
+    state: &LevelEditorState,
+    state_arc: Arc<parking_lot::RwLock<LevelEditorState>>,
+    width_input: &Entity<InputState>,
+    height_input: &Entity<InputState>,
+    gpu_engine: &Arc<Mutex<GpuRenderer>>,
+    cx: &mut Context<V>,
+) -> impl IntoElement
+where
+    V: 'static + Render,
+{
+    v_flex()
+        .gap_2()
+        .p_2()
+        .w(px(420.0))
+        .bg(cx.theme().background.opacity(0.95))
+        .rounded(cx.theme().radius)
+        .border_1()
+        .border_color(cx.theme().border)
+        .child(
+            h_flex()
+                .items_center()
+                .child(
+                    div()
+                        .flex_1()
+                        .text_sm()
+                        .font_weight(FontWeight::MEDIUM)
+                        .text_color(cx.theme().foreground)
+                        .child("Capture"),
+                )
+                .child({
+                    let state_clone = state_arc.clone();
+                    Button::new("close_capture_panel")
+                        .icon(IconName::Close)
+                        .xsmall()
+                        .ghost()
+                        .on_click(move |_, _, _| {
+                            state_clone.write().capture.show_panel = false;
+                        })
+                }),
+        )
+        .child(resolution_row(
+            state,
+            state_arc.clone(),
+            width_input,
+            height_input,
+            cx,
+        ))
+        .child(overlay_row(state, state_arc.clone(), cx))
+        .child(turntable_row(state, state_arc.clone(), cx))
+        .child(action_row(
+            state,
+            state_arc,
+            width_input,
+            height_input,
+            gpu_engine,
+            cx,
+        ))
+}
@@ -1,6 +1,7 @@
 //! Reusable UI components for the viewport panel.
 
 pub mod camera_selector;
+pub mod capture_panel;
 pub mod floating_toolbar;
 pub mod gpu_pipeline_overlay;
 pub mod graph_panel;
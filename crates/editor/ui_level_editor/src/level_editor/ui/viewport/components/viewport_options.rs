@@ -277,6 +277,19 @@ where
         .child(gizmo_tool_buttons(state_arc.clone(), state))
         .child(div().h(px(20.0)).w_px().bg(cx.theme().border))
         .child(overlay_toggles(state_arc.clone(), state, cx))
+        .child(div().h(px(20.0)).w_px().bg(cx.theme().border))
+        .child({
+            let state_clone = state_arc.clone();
+            Button::new("toggle_capture_panel")
+                .icon(IconName::Camera)
+                .ghost()
+                .tooltip("Screenshot & Turntable")
+                .selected(state.capture.show_panel)
+                .on_click(move |_, _, _| {
+                    let capture = &mut state_clone.write().capture;
+                    capture.show_panel = !capture.show_panel;
+                })
+        })
         .child(
             Button::new("collapse_viewport_options")
                 .icon(IconName::Close)
@@ -15,6 +15,7 @@ use gpui::*;
 use plugin_editor_api::{AssetKind, AssetPayload};
 use ui::{ActiveTheme as _, ContextModal, notification::Notification};
 
+use super::capture::{CaptureRunner, capture_viewport_thumbnail};
 use crate::level_editor::commands::{SceneCommand, execute_command};
 use crate::level_editor::scene_database::{MeshType, ObjectType, SceneObjectData, Transform};
 use crate::level_editor::state::LevelEditorState;
@@ -41,6 +42,9 @@ pub struct HelioViewport {
     pie_blit: Option<PieBlit>,
     /// Timestamp of the previous PiE frame, for the game's delta time.
     pie_last_frame: Instant,
+
+    /// Screenshots and turntables queued from the capture panel.
+    captures: CaptureRunner,
 }
 
 impl HelioViewport {
@@ -63,6 +67,7 @@ impl HelioViewport {
             pie_host: None,
             pie_blit: None,
             pie_last_frame: Instant::now(),
+            captures: CaptureRunner::default(),
         }
     }
 
@@ -366,152 +371,6 @@ impl HelioViewport {
     }
 }
 
-/// Renders the current Helio scene into an offscreen texture, reads it back
-/// from the GPU, and writes it to `out_path` as a PNG. Used to capture
-/// project thumbnails on scene save.
-fn capture_viewport_thumbnail(
-    engine: &mut GpuRenderer,
-    surface: &WgpuSurfaceHandle,
-    width: u32,
-    height: u32,
-    format: wgpu::TextureFormat,
-    out_path: &std::path::Path,
-) {
-    let device = surface.device();
-    let queue = surface.queue();
-
-    let texture = device.create_texture(&wgpu::TextureDescriptor {
-        label: Some("thumbnail-capture"),
-        size: wgpu::Extent3d {
-            width,
-            height,
-            depth_or_array_layers: 1,
-        },
-        mip_level_count: 1,
-        sample_count: 1,
-        dimension: wgpu::TextureDimension::D2,
-        format,
-        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
-        view_formats: &[],
-    });
-    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-
-    engine.render_frame_to_surface(device, queue, &view, width, height, format);
-
-    let bytes_per_row = align_up(width * 4, 256);
-    let staging = device.create_buffer(&wgpu::BufferDescriptor {
-        label: Some("thumbnail-staging"),
-        size: (bytes_per_row * height) as u64,
-        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
-        mapped_at_creation: false,
-    });
-
-    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
-        label: Some("thumbnail-readback"),
-    });
-    encoder.copy_texture_to_buffer(
-        texture.as_image_copy(),
-        wgpu::TexelCopyBufferInfo {
-            buffer: &staging,
-            layout: wgpu::TexelCopyBufferLayout {
-                offset: 0,
-                bytes_per_row: Some(bytes_per_row),
-                rows_per_image: None,
-            },
-        },
-        wgpu::Extent3d {
-            width,
-            height,
-            depth_or_array_layers: 1,
-        },
-    );
-    queue.submit([encoder.finish()]);
-
-    let slice = staging.slice(..);
-    let (tx, rx) = std::sync::mpsc::channel();
-    slice.map_async(wgpu::MapMode::Read, move |r| {
-        let _ = tx.send(r);
-    });
-    let _ = device.poll(wgpu::PollType::wait_indefinitely());
-
-    match rx.recv() {
-        Ok(Ok(())) => {}
-        _ => {
-            tracing::warn!("[THUMBNAIL] Failed to map readback buffer");
-            return;
-        }
-    }
-
-    let data = match slice.get_mapped_range() {
-        Ok(d) => d,
-        Err(e) => {
-            tracing::warn!("[THUMBNAIL] Failed to get mapped range: {:?}", e);
-            return;
-        }
-    };
-    let mut pixels = Vec::with_capacity((width * height * 4) as usize);
-    for row in 0..height {
-        let start = (row * bytes_per_row) as usize;
-        let end = start + (width * 4) as usize;
-        pixels.extend_from_slice(&data[start..end]);
-    }
-    drop(data);
-    staging.unmap();
-
-    // The captured texture stores correctly sRGB-encoded bytes, but the live editor
-    // viewport is composited via a shader that samples this `_Srgb` texture (auto
-    // decoding sRGB -> linear) and writes the result directly into a non-sRGB
-    // swapchain target (no re-encode). That makes the on-screen viewport appear
-    // darker than the raw captured bytes. Apply the same sRGB -> linear decode here
-    // so the saved thumbnail matches what the user actually sees in the editor.
-    let srgb_to_linear_lut = srgb_to_linear_lut();
-    for px in pixels.chunks_exact_mut(4) {
-        px[0] = srgb_to_linear_lut[px[0] as usize];
-        px[1] = srgb_to_linear_lut[px[1] as usize];
-        px[2] = srgb_to_linear_lut[px[2] as usize];
-    }
-
-    let Some(rgba) = image::RgbaImage::from_raw(width, height, pixels) else {
-        tracing::warn!(
-            "[THUMBNAIL] Pixel buffer size mismatch for {}x{}",
-            width,
-            height
-        );
-        return;
-    };
-
-    if let Some(parent) = out_path.parent() {
-        let _ = std::fs::create_dir_all(parent);
-    }
-    match rgba.save(out_path) {
-        Ok(()) => tracing::info!(
-            "[THUMBNAIL] Saved viewport thumbnail to {}",
-            out_path.display()
-        ),
-        Err(e) => tracing::warn!("[THUMBNAIL] Failed to save {}: {}", out_path.display(), e),
-    }
-}
-
-fn align_up(n: u32, align: u32) -> u32 {
-    (n + align - 1) & !(align - 1)
-}
-
-/// Builds an 8-bit sRGB-decode (EOTF) lookup table, mapping each sRGB-encoded
-/// byte value to its linear-light equivalent (also expressed as a byte 0-255).
-fn srgb_to_linear_lut() -> [u8; 256] {
-    let mut lut = [0u8; 256];
-    for (i, entry) in lut.iter_mut().enumerate() {
-        let c = i as f32 / 255.0;
-        let linear = if c <= 0.04045 {
-            c / 12.92
-        } else {
-            ((c + 0.055) / 1.055).powf(2.4)
-        };
-        *entry = (linear * 255.0).round().clamp(0.0, 255.0) as u8;
-    }
-    lut
-}
-
 impl Focusable for HelioViewport {
     fn focus_handle(&self, _cx: &App) -> FocusHandle {
         self.focus_handle.clone()
@@ -613,7 +472,30 @@ impl Render for HelioViewport {
                         .take();
                     if let Some(path) = capture_path {
                         if let Ok(mut engine) = self.gpu_engine.try_lock() {
-                            capture_viewport_thumbnail(&mut engine, &surface, w, h, format, &path);
+                            capture_viewport_thumbnail(
+                                &mut engine,
+                                surface.device(),
+                                surface.queue(),
+                                w,
+                                h,
+                                format,
+                                &path,
+                            );
+                        }
+                    }
+
+                    // Screenshots and turntables render the editor scene, so
+                    // they wait while a game is embedded.
+                    if !pie_handled {
+                        if let Ok(mut engine) = self.gpu_engine.try_lock() {
+                            self.captures.run(
+                                &mut engine,
+                                surface.device(),
+                                surface.queue(),
+                                surface.format(),
+                                (w, h),
+                                &self.shared_state,
+                            );
                         }
                     }
                     frame_diagnostics = Some((
@@ -8,6 +8,7 @@
 //!
 //! The viewport has been refactored into focused, reusable components for maintainability.
 
+mod capture;
 pub mod components;
 pub mod helio_viewport;
 pub mod input_state;
@@ -32,6 +33,7 @@ use ui_common::ViewportControls;
 use crate::level_editor::state::LevelEditorState;
 use crate::level_editor::ui::viewport::components::camera_selector::CameraSpeedControl;
 use components::camera_selector::render_camera_selector;
+use components::capture_panel::render_capture_panel;
 use components::gpu_pipeline_overlay::render_gpu_pipeline_overlay;
 use components::performance_overlay::render_performance_overlay;
 use components::viewport_options::render_viewport_options;
@@ -116,6 +118,10 @@ pub struct ViewportPanel {
     keys_pressed: Rc<RefCell<HashSet<String>>>,
     alt_pressed: Rc<RefCell<bool>>,
 
+    /// Custom capture size inputs for the capture panel
+    capture_width_input: Entity<ui::input::InputState>,
+    capture_height_input: Entity<ui::input::InputState>,
+
     /// Focus handle
     focus_handle: FocusHandle,
 }
@@ -125,7 +131,7 @@ impl ViewportPanel {
     pub fn new<V>(
         viewport: Entity<HelioViewport>,
         render_enabled: Arc<AtomicBool>,
-        _window: &mut Window,
+        window: &mut Window,
         cx: &mut Context<V>,
     ) -> Self
     where
@@ -133,6 +139,10 @@ impl ViewportPanel {
     {
         let input_state = Arc::new(InputState::new());
         let focus_handle = cx.focus_handle();
+        let capture_width_input = cx.new(|cx| ui::input::InputState::new(window, cx));
+        let capture_height_input = cx.new(|cx| ui::input::InputState::new(window, cx));
+        capture_width_input.update(cx, |input, cx| input.set_value("1920", window, cx));
+        capture_height_input.update(cx, |input, cx| input.set_value("1080", window, cx));
 
         Self {
             viewport,
@@ -153,6 +163,8 @@ impl ViewportPanel {
             locked_cursor_screen_y: Arc::new(AtomicI32::new(0)),
             keys_pressed: Rc::new(RefCell::new(HashSet::new())),
             alt_pressed: Rc::new(RefCell::new(false)),
+            capture_width_input,
+            capture_height_input,
             focus_handle,
         }
     }
@@ -1040,6 +1052,24 @@ impl ViewportPanel {
                     )),
             );
 
+        // Below the viewport options: Capture panel
+        if state.capture.show_panel {
+            overlays = overlays.child(
+                div()
+                    .absolute()
+                    .top(px(state.overlays.positions.viewport.1 + 50.0))
+                    .left(px(state.overlays.positions.viewport.0))
+                    .child(render_capture_panel(
+                        state,
+                        state_arc.clone(),
+                        &self.capture_width_input,
+                        &self.capture_height_input,
+                        gpu_engine,
+                        cx,
+                    )),
+            );
+        }
+
         // Top-right: Camera selector
         if state.overlays.state.show_camera_mode_selector {
             overlays = overlays.child(
@@ -1,7 +1,6 @@
 //! Loading screen — runs background tasks, shows progress, then opens the editor.
 
 mod preload;
-mod recent_projects;
 mod screen;
 mod tasks;
 
@@ -5,8 +5,7 @@ use std::time::{Duration, Instant};
 use engine_backend::services::RustAnalyzerManager;
 use gpui::*;
 
-use crate::recent_projects::update_recent_projects;
-use crate::tasks::{LoadingEvent, TaskStatus, TASKS};
+use crate::tasks::{LoadingEvent, TaskProgress, TaskStatus, TASKS};
 
 mod components;
 use components::*;
@@ -52,7 +51,7 @@ impl LoadingScreen {
         std::thread::spawn(move || {
             let project = project_path_for_thread.as_path();
             for (idx, (label, task_fn)) in TASKS.iter().enumerate() {
-                let result = task_fn(project);
+                let result = task_fn(project, &TaskProgress::new(idx, &tx));
                 tracing::info!(
                     "[Loading] {:>3}ms  {}{}",
                     result.elapsed.as_millis(),
@@ -121,17 +120,33 @@ impl LoadingScreen {
         }
         self.progress = (idx + 1) as f32 / TASKS.len() as f32;
     }
+
+    /// Moves the bar within the share of the running task.
+    fn advance_within(&mut self, idx: usize, fraction: f32, detail: String) {
+        if self.statuses.get(idx) != Some(&TaskStatus::Running) {
+            return;
+        }
+        let progress = (idx as f32 + fraction.clamp(0.0, 1.0)) / TASKS.len() as f32;
+        self.progress = self.progress.max(progress);
+        self.message = format!("{} — {detail}", TASKS[idx].0);
+    }
 }
 
 impl Render for LoadingScreen {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        while let Ok(LoadingEvent::TaskDone {
-            idx,
-            elapsed,
-            detail,
-        }) = self.rx.try_recv()
-        {
-            self.advance(idx, elapsed, detail);
+        while let Ok(event) = self.rx.try_recv() {
+            match event {
+                LoadingEvent::TaskDone {
+                    idx,
+                    elapsed,
+                    detail,
+                } => self.advance(idx, elapsed, detail),
+                LoadingEvent::TaskProgress {
+                    idx,
+                    fraction,
+                    detail,
+                } => self.advance_within(idx, fraction, detail),
+            }
             cx.notify();
         }
 
@@ -146,7 +161,6 @@ impl Render for LoadingScreen {
                 window.request_animation_frame();
             } else {
                 self.opened_editor = true;
-                update_recent_projects(&self.project_path);
                 let path = self.project_path.clone();
                 let on_complete = self.on_complete.clone();
                 let handle = window.window_handle();
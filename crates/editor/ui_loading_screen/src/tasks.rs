@@ -4,10 +4,12 @@
 //! took plus an optional human-readable detail string.  The loading-screen
 //! background thread runs them sequentially, measures each one, and sends a
 //! `LoadingEvent::TaskDone` so the UI advances its progress indicator in
-//! real-time.  There are no artificial `sleep` calls — the loading screen
-//! completes as fast as the actual work does.
+//! real-time.  Long tasks also report partial progress through
+//! [`TaskProgress`].  There are no artificial `sleep` calls — the loading
+//! screen completes as fast as the actual work does.
 
 use std::path::Path;
+use std::sync::mpsc::Sender;
 use std::time::{Duration, Instant};
 
 // ── Task result ────────────────────────────────────────────────────────────
@@ -21,7 +23,29 @@ pub(crate) struct TaskResult {
 
 // ── Task function type ─────────────────────────────────────────────────────
 
-pub(crate) type TaskFn = fn(&Path) -> TaskResult;
+pub(crate) type TaskFn = fn(&Path, &TaskProgress) -> TaskResult;
+
+/// Lets a running task move the progress bar before it finishes.
+pub(crate) struct TaskProgress<'a> {
+    idx: usize,
+    tx: &'a Sender<LoadingEvent>,
+}
+
+impl<'a> TaskProgress<'a> {
+    pub fn new(idx: usize, tx: &'a Sender<LoadingEvent>) -> Self {
+        Self { idx, tx }
+    }
+
+    /// Report `fraction` (0 to 1) of the task done, with a detail for the
+    /// status line.
+    pub fn report(&self, fraction: f32, detail: String) {
+        let _ = self.tx.send(LoadingEvent::TaskProgress {
+            idx: self.idx,
+            fraction,
+            detail,
+        });
+    }
+}
 
 // ── Task list ──────────────────────────────────────────────────────────────
 
@@ -33,7 +57,7 @@ pub(crate) const TASKS: &[(&str, TaskFn)] = &[
     ("Reading project configuration", task_read_config),
     ("Scanning workspace packages", task_scan_packages),
     ("Indexing source files", task_index_files),
-    ("Scanning asset pipeline", task_scan_assets),
+    ("Indexing project assets", task_scan_assets),
     ("Building file tree", task_scan_folder_tree),
     ("Warming scene cache", task_warm_scene),
     ("Loading engine settings", task_load_settings),
@@ -59,11 +83,17 @@ pub(crate) enum LoadingEvent {
         elapsed: Duration,
         detail: Option<String>,
     },
+    /// Emitted by long tasks while they run.
+    TaskProgress {
+        idx: usize,
+        fraction: f32,
+        detail: String,
+    },
 }
 
 // ── Task implementations ───────────────────────────────────────────────────
 
-fn task_cargo_check(project: &Path) -> TaskResult {
+fn task_cargo_check(project: &Path, _progress: &TaskProgress) -> TaskResult {
     let t = Instant::now();
     let output = std::process::Command::new("cargo")
         .args(["check", "--quiet"])
@@ -91,7 +121,7 @@ fn task_cargo_check(project: &Path) -> TaskResult {
     }
 }
 
-fn task_verify_project(project: &Path) -> TaskResult {
+fn task_verify_project(project: &Path, _progress: &TaskProgress) -> TaskResult {
     let t = Instant::now();
     let exists = project.exists();
     let has_cargo = project.join("Cargo.toml").exists();
@@ -112,7 +142,7 @@ fn task_verify_project(project: &Path) -> TaskResult {
     }
 }
 
-fn task_read_config(project: &Path) -> TaskResult {
+fn task_read_config(project: &Path, _progress: &TaskProgress) -> TaskResult {
     let t = Instant::now();
     let path = project.join("Cargo.toml");
     let detail = std::fs::read_to_string(&path)
@@ -124,7 +154,7 @@ fn task_read_config(project: &Path) -> TaskResult {
     }
 }
 
-fn task_scan_packages(project: &Path) -> TaskResult {
+fn task_scan_packages(project: &Path, _progress: &TaskProgress) -> TaskResult {
     let t = Instant::now();
     let content = std::fs::read_to_string(project.join("Cargo.toml")).unwrap_or_default();
     // Count quoted workspace member entries inside the [workspace] section.
@@ -149,7 +179,7 @@ fn task_scan_packages(project: &Path) -> TaskResult {
     }
 }
 
-fn task_index_files(project: &Path) -> TaskResult {
+fn task_index_files(project: &Path, _progress: &TaskProgress) -> TaskResult {
     use crate::preload::{store_preloaded_files, PreloadedFileEntry};
     use ui_common::file_utils::find_openable_files;
 
@@ -171,14 +201,35 @@ fn task_index_files(project: &Path) -> TaskResult {
     }
 }
 
-fn task_scan_assets(project: &Path) -> TaskResult {
+fn task_scan_assets(project: &Path, progress: &TaskProgress) -> TaskResult {
     let t = Instant::now();
-    let assets_dir = project.join("assets");
-    let count = count_files_recursive(&assets_dir, 4);
-    let detail = if assets_dir.is_dir() {
-        format!("{count} assets")
-    } else {
-        "no assets/ dir".to_string()
+    let (tx, rx) = std::sync::mpsc::channel::<engine_fs::ScanProgress>();
+    let project_root = project.to_path_buf();
+    let scan = std::thread::spawn(move || engine_fs::EngineFs::new_with_progress(project_root, tx));
+
+    // Ends with the finished update, or when a failed scan drops the sender
+    for update in rx {
+        if update.finished {
+            break;
+        }
+        if update.files_processed == 0 {
+            progress.report(0.0, format!("{} files found", update.files_discovered));
+        } else {
+            let fraction = update.files_processed as f32 / update.files_discovered.max(1) as f32;
+            progress.report(
+                fraction,
+                format!(
+                    "{} / {} files",
+                    update.files_processed, update.files_discovered
+                ),
+            );
+        }
+    }
+
+    let detail = match scan.join() {
+        Ok(Ok(fs)) => format!("{} assets indexed", fs.asset_index().len()),
+        Ok(Err(e)) => format!("scan failed: {e}"),
+        Err(_) => "scan panicked".to_string(),
     };
     TaskResult {
         elapsed: t.elapsed(),
@@ -186,7 +237,7 @@ fn task_scan_assets(project: &Path) -> TaskResult {
     }
 }
 
-fn task_warm_scene(project: &Path) -> TaskResult {
+fn task_warm_scene(project: &Path, _progress: &TaskProgress) -> TaskResult {
     let t = Instant::now();
     let scene_dir = project.join("scene");
     let _ = std::fs::create_dir_all(&scene_dir);
@@ -206,7 +257,7 @@ fn task_warm_scene(project: &Path) -> TaskResult {
     }
 }
 
-fn task_load_settings(_project: &Path) -> TaskResult {
+fn task_load_settings(_project: &Path, _progress: &TaskProgress) -> TaskResult {
     let t = Instant::now();
     // Best-effort: check whether a settings file exists at the standard path.
     let detail = directories::ProjectDirs::from("dev", "Pulsar", "Pulsar Engine")
@@ -223,7 +274,7 @@ fn task_load_settings(_project: &Path) -> TaskResult {
     }
 }
 
-fn task_check_lsp(_project: &Path) -> TaskResult {
+fn task_check_lsp(_project: &Path, _progress: &TaskProgress) -> TaskResult {
     let t = Instant::now();
     let sep = if cfg!(windows) { ';' } else { ':' };
     let found = std::env::var("PATH")
@@ -243,7 +294,7 @@ fn task_check_lsp(_project: &Path) -> TaskResult {
     }
 }
 
-fn task_scan_folder_tree(project: &Path) -> TaskResult {
+fn task_scan_folder_tree(project: &Path, _progress: &TaskProgress) -> TaskResult {
     use ui_file_manager::{store_preloaded_tree, FolderNode};
     let t = Instant::now();
     let tree = FolderNode::from_path(project);
@@ -255,7 +306,7 @@ fn task_scan_folder_tree(project: &Path) -> TaskResult {
     }
 }
 
-fn task_finalize(_project: &Path) -> TaskResult {
+fn task_finalize(_project: &Path, _progress: &TaskProgress) -> TaskResult {
     // Logical fence: all earlier tasks have completed, pre-loaded data is ready.
     TaskResult {
         elapsed: Duration::ZERO,
@@ -268,22 +319,3 @@ fn task_finalize(_project: &Path) -> TaskResult {
 fn count_tree_nodes(node: &ui_file_manager::FolderNode) -> usize {
     1 + node.children.iter().map(count_tree_nodes).sum::<usize>()
 }
-
-fn count_files_recursive(dir: &Path, max_depth: usize) -> usize {
-    if max_depth == 0 || !dir.is_dir() {
-        return 0;
-    }
-    let Ok(entries) = std::fs::read_dir(dir) else {
-        return 0;
-    };
-    let mut n = 0usize;
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if path.is_file() {
-            n += 1;
-        } else if path.is_dir() {
-            n += count_files_recursive(&path, max_depth - 1);
-        }
-    }
-    n
-}
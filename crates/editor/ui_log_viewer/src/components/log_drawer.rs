@@ -1,46 +1,24 @@
+use crate::utils::{
+    log_filter::{LogFilter, LogLevel, SearchMode},
+    log_reader::LogFollower,
+    log_store::{LogEntryDetails, LogStore, DEFAULT_MAX_LINES, ENGINE_SOURCE},
+};
 use gpui::{prelude::*, *};
-use std::{cell::RefCell, collections::VecDeque, ops::Range, rc::Rc, time::Duration};
+use std::{ops::RangeInclusive, path::PathBuf, rc::Rc, time::Duration};
 use ui::{
     button::{Button, ButtonVariants as _},
     h_flex,
     input::{InputState, TextInput},
-    table::{Column, Table, TableDelegate},
-    v_flex, ActiveTheme as _, ContextModal, IconName,
+    v_flex, v_virtual_list, ActiveTheme as _, ContextModal, IconName, VirtualListScrollHandle,
 };
+use ui_common::{format_timestamp_absolute, HourCycle, RelativeTime};
 
-const MAX_BUFFERED_LINES: usize = 250_000;
-const TRIM_CHUNK_LINES: usize = 10_000;
 const LIVE_BATCH_MAX_LINES: usize = 2_048;
 const INGEST_FLUSH_INTERVAL_MS: u64 = 100;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum LogLevel {
-    Error,
-    Warn,
-    Info,
-    Debug,
-    Trace,
-    Unknown,
-}
+const FOLLOW_POLL_INTERVAL_MS: u64 = 250;
+const ROW_HEIGHT: f32 = 28.0;
 
 impl LogLevel {
-    fn from_line(line: &str) -> Self {
-        let upper = line.to_ascii_uppercase();
-        if upper.contains("ERROR") || upper.contains(" ERR ") || upper.starts_with("ERR") {
-            LogLevel::Error
-        } else if upper.contains("WARN") {
-            LogLevel::Warn
-        } else if upper.contains("INFO") {
-            LogLevel::Info
-        } else if upper.contains("DEBUG") {
-            LogLevel::Debug
-        } else if upper.contains("TRACE") {
-            LogLevel::Trace
-        } else {
-            LogLevel::Unknown
-        }
-    }
-
     fn color(&self, theme: &ui::Theme) -> Hsla {
         match self {
             LogLevel::Error => theme.danger,
@@ -68,33 +46,6 @@ impl LogLevel {
     }
 }
 
-#[derive(Clone)]
-struct LogRow {
-    abs_line: usize,
-    level: LogLevel,
-    text: String,
-}
-
-struct LogStore {
-    rows: VecDeque<LogRow>,
-    filtered_indices: Vec<usize>,
-    total_seen: usize,
-    dropped_total: usize,
-    level_filter: Option<LogLevel>,
-    search_query: String,
-}
-
-#[derive(Clone)]
-struct LogEntryDetails {
-    abs_line: usize,
-    level: LogLevel,
-    text: String,
-    chars: usize,
-    bytes: usize,
-    buffered_rows: usize,
-    filtered_active: bool,
-}
-
 fn open_log_entry_modal(details: LogEntryDetails, window: &mut Window, cx: &mut App) {
     window.open_modal(cx, move |modal, _w, cx| {
         let theme = cx.theme();
@@ -173,6 +124,17 @@ fn open_log_entry_modal(details: LogEntryDetails, window: &mut Window, cx: &mut
                                 "Level".to_string(),
                                 details.level.label().to_string(),
                             ))
+                            .child(metadata_row(
+                                "Source".to_string(),
+                                details.source.to_string(),
+                            ))
+                            .child(metadata_row(
+                                "Received".to_string(),
+                                format_timestamp_absolute(
+                                    details.received_at,
+                                    HourCycle::from_settings(),
+                                ),
+                            ))
                             .child(metadata_row(
                                 "Characters".to_string(),
                                 details.chars.to_string(),
@@ -221,380 +183,76 @@ fn open_log_entry_modal(details: LogEntryDetails, window: &mut Window, cx: &mut
     });
 }
 
-impl LogStore {
-    fn new() -> Self {
-        Self {
-            rows: VecDeque::new(),
-            filtered_indices: Vec::new(),
-            total_seen: 0,
-            dropped_total: 0,
-            level_filter: None,
-            search_query: String::new(),
-        }
-    }
-
-    fn clear(&mut self) {
-        self.rows.clear();
-        self.filtered_indices.clear();
-        self.total_seen = 0;
-        self.dropped_total = 0;
-    }
-
-    fn has_active_filter(&self) -> bool {
-        self.level_filter.is_some() || !self.search_query.is_empty()
-    }
-
-    fn visible_count(&self) -> usize {
-        if self.has_active_filter() {
-            self.filtered_indices.len()
-        } else {
-            self.rows.len()
-        }
-    }
-
-    fn matches_filters(&self, row: &LogRow) -> bool {
-        if let Some(level) = self.level_filter {
-            if row.level != level {
-                return false;
-            }
-        }
-
-        if self.search_query.is_empty() {
-            return true;
-        }
-
-        row.text
-            .to_ascii_lowercase()
-            .contains(&self.search_query.to_ascii_lowercase())
-    }
-
-    fn refilter_all(&mut self) {
-        self.filtered_indices.clear();
-        if !self.has_active_filter() {
-            return;
-        }
-
-        let query = self.search_query.to_ascii_lowercase();
-        let has_query = !query.is_empty();
-        for (ix, row) in self.rows.iter().enumerate() {
-            if let Some(level) = self.level_filter {
-                if row.level != level {
-                    continue;
-                }
-            }
-
-            if has_query && !row.text.to_ascii_lowercase().contains(&query) {
-                continue;
-            }
-
-            self.filtered_indices.push(ix);
-        }
-    }
-
-    fn append_batch(&mut self, lines: Vec<String>) {
-        if lines.is_empty() {
-            return;
-        }
-
-        let query = self.search_query.to_ascii_lowercase();
-        let has_query = !query.is_empty();
-        let level_filter = self.level_filter;
-
-        for line in lines {
-            self.total_seen += 1;
-            let row = LogRow {
-                abs_line: self.total_seen,
-                level: LogLevel::from_line(&line),
-                text: line,
-            };
-
-            let row_ix = self.rows.len();
-            let matches = if let Some(level) = level_filter {
-                if row.level != level {
-                    false
-                } else if has_query {
-                    row.text.to_ascii_lowercase().contains(&query)
-                } else {
-                    true
-                }
-            } else if has_query {
-                row.text.to_ascii_lowercase().contains(&query)
-            } else {
-                false
-            };
-
-            self.rows.push_back(row);
-
-            if self.has_active_filter() && matches {
-                self.filtered_indices.push(row_ix);
-            }
-        }
-
-        self.trim_if_needed();
-    }
-
-    fn trim_if_needed(&mut self) {
-        if self.rows.len() <= MAX_BUFFERED_LINES {
-            return;
-        }
-
-        let drop_count = TRIM_CHUNK_LINES.min(self.rows.len());
-        for _ in 0..drop_count {
-            let _ = self.rows.pop_front();
-        }
-        self.dropped_total += drop_count;
-
-        if self.has_active_filter() {
-            self.filtered_indices.retain(|ix| *ix >= drop_count);
-            for ix in &mut self.filtered_indices {
-                *ix -= drop_count;
-            }
-        }
-    }
-
-    fn set_level_filter(&mut self, level: Option<LogLevel>) {
-        self.level_filter = level;
-        self.refilter_all();
-    }
-
-    fn set_search_query(&mut self, query: String) {
-        let next = query.trim().to_ascii_lowercase();
-        if self.search_query == next {
-            return;
-        }
-        self.search_query = next;
-        self.refilter_all();
-    }
-
-    fn row_for_visible(&self, visible_row: usize) -> Option<&LogRow> {
-        if self.has_active_filter() {
-            let base_ix = *self.filtered_indices.get(visible_row)?;
-            self.rows.get(base_ix)
-        } else {
-            self.rows.get(visible_row)
-        }
-    }
-
-    fn entry_details_for_visible(&self, visible_row: usize) -> Option<LogEntryDetails> {
-        let row = self.row_for_visible(visible_row)?;
-        Some(LogEntryDetails {
-            abs_line: row.abs_line,
-            level: row.level,
-            text: row.text.clone(),
-            chars: row.text.chars().count(),
-            bytes: row.text.len(),
-            buffered_rows: self.rows.len(),
-            filtered_active: self.has_active_filter(),
-        })
-    }
-}
-
-#[derive(Clone)]
-struct LogTableDelegate {
-    store: Rc<RefCell<LogStore>>,
-    columns: Vec<Column>,
-}
-
-impl LogTableDelegate {
-    fn new(store: Rc<RefCell<LogStore>>) -> Self {
-        Self {
-            store,
-            columns: vec![
-                Column::new("line", "Line").width(px(90.0)).resizable(false),
-                Column::new("level", "Level")
-                    .width(px(88.0))
-                    .resizable(false),
-                Column::new("message", "Message")
-                    .width(px(1600.0))
-                    .resizable(false),
-            ],
-        }
-    }
-}
-
-impl TableDelegate for LogTableDelegate {
-    fn columns_count(&self, _cx: &App) -> usize {
-        self.columns.len()
-    }
-
-    fn rows_count(&self, _cx: &App) -> usize {
-        self.store.borrow().visible_count()
-    }
-
-    fn column(&self, col_ix: usize, _cx: &App) -> &Column {
-        &self.columns[col_ix]
-    }
-
-    fn render_tr(
-        &self,
-        row_ix: usize,
-        _window: &mut Window,
-        cx: &mut Context<Table<Self>>,
-    ) -> Stateful<Div> {
-        let theme = cx.theme();
-        let base_bg = if row_ix % 2 == 0 {
-            theme.background
-        } else {
-            theme.background.opacity(0.96)
-        };
-
-        h_flex()
-            .id(("row", row_ix))
-            .cursor_pointer()
-            .hover(|s| s.bg(theme.accent.opacity(0.08)))
-            .bg(base_bg)
-            .border_b_1()
-            .border_color(theme.border.opacity(0.25))
-    }
-
-    fn render_td(
-        &self,
-        row_ix: usize,
-        col_ix: usize,
-        _window: &mut Window,
-        cx: &mut Context<Table<Self>>,
-    ) -> impl IntoElement {
-        let theme = cx.theme().clone();
-        let store = self.store.clone();
-        let borrowed = self.store.borrow();
-        let Some(row) = borrowed.row_for_visible(row_ix) else {
-            return div().into_any_element();
-        };
-
-        match col_ix {
-            0 => div()
-                .w_full()
-                .px_2()
-                .on_mouse_down(MouseButton::Left, move |_, window, cx| {
-                    let details = {
-                        let borrowed = store.borrow();
-                        borrowed.entry_details_for_visible(row_ix)
-                    };
-
-                    if let Some(details) = details {
-                        open_log_entry_modal(details, window, cx);
-                    }
-                })
-                .text_color(theme.muted_foreground)
-                .child(format!("{}", row.abs_line))
-                .into_any_element(),
-            1 => {
-                let level_color = row.level.color(&theme);
-                let store = self.store.clone();
-                div()
-                    .w_full()
-                    .px_2()
-                    .on_mouse_down(MouseButton::Left, move |_, window, cx| {
-                        let details = {
-                            let borrowed = store.borrow();
-                            borrowed.entry_details_for_visible(row_ix)
-                        };
-
-                        if let Some(details) = details {
-                            open_log_entry_modal(details, window, cx);
-                        }
-                    })
-                    .child(
-                        h_flex()
-                            .h(px(22.0))
-                            .px_2()
-                            .items_center()
-                            .justify_center()
-                            .rounded(px(999.0))
-                            .bg(row.level.tint(&theme).opacity(0.95))
-                            .border_1()
-                            .border_color(level_color.opacity(0.55))
-                            .text_color(level_color)
-                            .font_weight(FontWeight::SEMIBOLD)
-                            .child(row.level.label()),
-                    )
-                    .into_any_element()
-            }
-            _ => {
-                let level_color = row.level.color(&theme);
-                let store = self.store.clone();
-                div()
-                    .w_full()
-                    .px_2()
-                    .py_1()
-                    .on_mouse_down(MouseButton::Left, move |_, window, cx| {
-                        let details = {
-                            let borrowed = store.borrow();
-                            borrowed.entry_details_for_visible(row_ix)
-                        };
-
-                        if let Some(details) = details {
-                            open_log_entry_modal(details, window, cx);
-                        }
-                    })
-                    .rounded(px(4.0))
-                    .bg(row.level.tint(&theme).opacity(0.45))
-                    .text_color(level_color)
-                    .child(row.text.clone())
-                    .into_any_element()
-            }
-        }
-    }
-
-    fn load_more(&mut self, _window: &mut Window, _cx: &mut Context<Table<Self>>) {}
-
-    fn is_eof(&self, _cx: &App) -> bool {
-        true
-    }
-
-    fn visible_rows_changed(
-        &mut self,
-        _visible_range: Range<usize>,
-        _window: &mut Window,
-        _cx: &mut Context<Table<Self>>,
-    ) {
-    }
-}
-
 pub struct LogDrawer {
-    store: Rc<RefCell<LogStore>>,
-    table: Option<Entity<Table<LogTableDelegate>>>,
+    store: LogStore,
+    scroll_handle: VirtualListScrollHandle,
+    /// Row sizes handed to the virtual list, rebuilt when the row count changes
+    item_sizes: Rc<Vec<Size<Pixels>>>,
+    /// Anchor and head line of the selected rows, as `abs_line` numbers
+    selection: Option<(usize, usize)>,
+    focus_handle: FocusHandle,
     search_input: Option<Entity<InputState>>,
+    target_input: Option<Entity<InputState>>,
+    show_targets: bool,
     locked_to_bottom: bool,
     error_message: Option<String>,
     _background_task: Option<Task<()>>,
+    _follow_task: Option<Task<()>>,
+}
+
+/// The `log_viewer_max_lines` setting, or [`DEFAULT_MAX_LINES`]
+fn configured_max_lines() -> usize {
+    engine_state::global_config()
+        .get(engine_state::NS_EDITOR, "advanced", "log_viewer_max_lines")
+        .ok()
+        .and_then(|v| v.as_float().ok())
+        .map(|lines| lines as usize)
+        .unwrap_or(DEFAULT_MAX_LINES)
+}
+
+/// Extra log files from the `log_follow_paths` setting, shown alongside the
+/// engine's own output
+fn configured_follow_paths() -> Vec<PathBuf> {
+    engine_state::global_config()
+        .get(engine_state::NS_EDITOR, "advanced", "log_follow_paths")
+        .ok()
+        .and_then(|v| v.as_str().ok().map(str::to_owned))
+        .unwrap_or_default()
+        .split(';')
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .collect()
 }
 
 impl LogDrawer {
-    pub fn new(_cx: &mut Context<Self>) -> Self {
+    pub fn new(cx: &mut Context<Self>) -> Self {
         Self {
-            store: Rc::new(RefCell::new(LogStore::new())),
-            table: None,
+            store: LogStore::new(configured_max_lines()),
+            scroll_handle: VirtualListScrollHandle::new(),
+            item_sizes: Rc::new(Vec::new()),
+            selection: None,
+            focus_handle: cx.focus_handle(),
             search_input: None,
+            target_input: None,
+            show_targets: false,
             locked_to_bottom: true,
             error_message: None,
             _background_task: None,
+            _follow_task: None,
         }
     }
 
-    fn ensure_table(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+    fn ensure_inputs(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if self.search_input.is_none() {
             let input = cx.new(|cx| InputState::new(window, cx).placeholder("Search logs..."));
             self.search_input = Some(input);
         }
 
-        if self.table.is_some() {
-            return;
+        if self.target_input.is_none() {
+            let input = cx.new(|cx| InputState::new(window, cx).placeholder("Module prefix..."));
+            self.target_input = Some(input);
         }
-
-        let delegate = LogTableDelegate::new(self.store.clone());
-        let table = cx.new(|cx| {
-            Table::new(delegate, window, cx)
-                .sortable(false)
-                .col_movable(false)
-                .col_resizable(false)
-                .row_selectable(false)
-                .col_selectable(false)
-                .loop_selection(false)
-                .stripe(true)
-        });
-
-        self.table = Some(table);
     }
 
     pub fn start_monitoring(&mut self, cx: &mut Context<Self>) {
@@ -602,6 +260,9 @@ impl LogDrawer {
             return;
         }
 
+        // Pick up changes to the line limit made while the panel was closed
+        self.store.set_max_lines(configured_max_lines());
+
         let rx = crate::subscribe_live_logs();
 
         let task = cx.spawn(async move |this, cx| {
@@ -641,7 +302,7 @@ impl LogDrawer {
                 let _ = cx.update(|cx| {
                     if let Some(this) = this.upgrade() {
                         this.update(cx, |drawer, cx| {
-                            drawer.ingest_lines(batch, cx);
+                            drawer.ingest_lines(ENGINE_SOURCE, batch, cx);
                         });
                     }
                 });
@@ -649,76 +310,321 @@ impl LogDrawer {
         });
 
         self._background_task = Some(task);
+        self.start_following(configured_follow_paths(), cx);
         self.error_message = None;
         cx.notify();
     }
 
+    /// Tail `paths` on the background executor, tagging their lines with
+    /// each file's name
+    fn start_following(&mut self, paths: Vec<PathBuf>, cx: &mut Context<Self>) {
+        let mut follower = LogFollower::new(paths);
+        if follower.is_empty() {
+            self._follow_task = None;
+            return;
+        }
+
+        let task = cx.spawn(async move |this, cx| loop {
+            let (returned, batches) = cx
+                .background_executor()
+                .spawn(async move {
+                    let batches = follower.poll();
+                    (follower, batches)
+                })
+                .await;
+            follower = returned;
+
+            if !batches.is_empty() {
+                let _ = cx.update(|cx| {
+                    if let Some(this) = this.upgrade() {
+                        this.update(cx, |drawer, cx| {
+                            for (source, lines) in batches {
+                                drawer.ingest_lines(&source, lines, cx);
+                            }
+                        });
+                    }
+                });
+            }
+
+            smol::Timer::after(Duration::from_millis(FOLLOW_POLL_INTERVAL_MS)).await;
+        });
+
+        self._follow_task = Some(task);
+    }
+
     pub fn stop_monitoring(&mut self) {
         self._background_task = None;
+        self._follow_task = None;
     }
 
-    fn ingest_lines(&mut self, lines: Vec<String>, cx: &mut Context<Self>) {
+    pub fn has_active_filter(&self) -> bool {
+        self.store.has_active_filter()
+    }
+
+    /// Every buffered line, or only those passing the current filter
+    pub fn buffered_lines(&self, filtered: bool) -> Vec<String> {
+        self.store.lines(filtered)
+    }
+
+    fn ingest_lines(&mut self, source: &str, lines: Vec<String>, cx: &mut Context<Self>) {
         if lines.is_empty() {
             return;
         }
 
-        self.store.borrow_mut().append_batch(lines);
+        self.store.append_batch(source, lines);
 
-        self.refresh_table(cx);
         if self.locked_to_bottom {
-            self.scroll_to_bottom(cx);
+            self.scroll_to_bottom();
         }
 
         cx.notify();
     }
 
-    fn refresh_table(&mut self, cx: &mut Context<Self>) {
-        if let Some(table) = self.table.clone() {
-            table.update(cx, |_, cx| {
-                cx.notify();
-            });
+    fn scroll_to_bottom(&self) {
+        if self.store.visible_count() > 0 {
+            self.scroll_handle.scroll_to_bottom();
         }
     }
 
-    fn scroll_to_bottom(&mut self, cx: &mut Context<Self>) {
-        let visible_count = self.store.borrow().visible_count();
-        if visible_count == 0 {
-            return;
-        }
+    fn clear_logs(&mut self, cx: &mut Context<Self>) {
+        self.store.clear();
+        self.selection = None;
+        cx.notify();
+    }
 
-        if let Some(table) = self.table.clone() {
-            table.update(cx, |table, cx| {
-                table.scroll_to_row(visible_count - 1, cx);
-            });
+    fn update_filter(
+        &mut self,
+        update: impl FnOnce(&mut LogFilter) -> bool,
+        cx: &mut Context<Self>,
+    ) {
+        self.store.update_filter(update);
+
+        if self.locked_to_bottom {
+            self.scroll_to_bottom();
         }
+
+        cx.notify();
     }
 
-    fn clear_logs(&mut self, cx: &mut Context<Self>) {
-        self.store.borrow_mut().clear();
-        self.refresh_table(cx);
+    /// Select `abs_line`, or extend the selection to it when `extend` is set
+    fn select_line(&mut self, abs_line: usize, extend: bool, cx: &mut Context<Self>) {
+        self.selection = match self.selection {
+            Some((anchor, _)) if extend => Some((anchor, abs_line)),
+            _ => Some((abs_line, abs_line)),
+        };
         cx.notify();
     }
 
-    fn set_level_filter(&mut self, level: Option<LogLevel>, cx: &mut Context<Self>) {
-        self.store.borrow_mut().set_level_filter(level);
-        self.refresh_table(cx);
+    fn selected_lines(&self) -> Option<RangeInclusive<usize>> {
+        self.selection
+            .map(|(anchor, head)| anchor.min(head)..=anchor.max(head))
+    }
 
-        if self.locked_to_bottom {
-            self.scroll_to_bottom(cx);
+    /// Copy the selected rows that pass the current filter
+    fn copy_selection(&mut self, cx: &mut Context<Self>) {
+        let Some((anchor, head)) = self.selection else {
+            return;
+        };
+        let text = self.store.visible_text_between(anchor, head);
+        if !text.is_empty() {
+            cx.write_to_clipboard(ClipboardItem::new_string(text));
         }
+    }
 
-        cx.notify();
+    fn handle_key_down(
+        &mut self,
+        event: &KeyDownEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let modifiers = &event.keystroke.modifiers;
+        match event.keystroke.key.as_str() {
+            "c" if modifiers.control || modifiers.platform => {
+                self.copy_selection(cx);
+                cx.stop_propagation();
+            }
+            "escape" if self.selection.is_some() => {
+                self.selection = None;
+                cx.notify();
+                cx.stop_propagation();
+            }
+            _ => {}
+        }
     }
 
-    fn set_search_query(&mut self, query: String, cx: &mut Context<Self>) {
-        self.store.borrow_mut().set_search_query(query);
-        self.refresh_table(cx);
+    fn render_row(
+        &self,
+        ix: usize,
+        selected: Option<&RangeInclusive<usize>>,
+        show_source: bool,
+        theme: &ui::Theme,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let Some(row) = self.store.row_for_visible(ix) else {
+            return div().h(px(ROW_HEIGHT)).into_any_element();
+        };
 
-        if self.locked_to_bottom {
-            self.scroll_to_bottom(cx);
+        let abs_line = row.abs_line;
+        let level_color = row.level.color(theme);
+        let bg = if selected.is_some_and(|lines| lines.contains(&abs_line)) {
+            theme.accent.opacity(0.18)
+        } else if ix % 2 == 0 {
+            theme.background
+        } else {
+            theme.background.opacity(0.96)
+        };
+
+        h_flex()
+            .id(("log-row", abs_line))
+            .w_full()
+            .h(px(ROW_HEIGHT))
+            .items_center()
+            .cursor_pointer()
+            .bg(bg)
+            .hover(|s| s.bg(theme.accent.opacity(0.08)))
+            .border_b_1()
+            .border_color(theme.border.opacity(0.25))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, event: &MouseDownEvent, window, cx| {
+                    this.focus_handle.focus(window, cx);
+                    if event.click_count == 2 {
+                        if let Some(details) = this.store.entry_details(abs_line) {
+                            open_log_entry_modal(details, window, cx);
+                        }
+                    } else {
+                        this.select_line(abs_line, event.modifiers.shift, cx);
+                    }
+                }),
+            )
+            .child(
+                div()
+                    .w(px(90.0))
+                    .flex_shrink_0()
+                    .px_2()
+                    .text_color(theme.muted_foreground)
+                    .child(abs_line.to_string()),
+            )
+            .child(
+                div()
+                    .w(px(120.0))
+                    .flex_shrink_0()
+                    .px_2()
+                    .text_color(theme.muted_foreground)
+                    .child(RelativeTime::new(row.received_at)),
+            )
+            .when(show_source, |this| {
+                this.child(
+                    div()
+                        .w(px(120.0))
+                        .flex_shrink_0()
+                        .px_2()
+                        .truncate()
+                        .text_color(theme.muted_foreground)
+                        .child(row.source.to_string()),
+                )
+            })
+            .child(
+                div().w(px(88.0)).flex_shrink_0().px_2().child(
+                    h_flex()
+                        .h(px(22.0))
+                        .px_2()
+                        .items_center()
+                        .justify_center()
+                        .rounded(px(999.0))
+                        .bg(row.level.tint(theme).opacity(0.95))
+                        .border_1()
+                        .border_color(level_color.opacity(0.55))
+                        .text_color(level_color)
+                        .font_weight(FontWeight::SEMIBOLD)
+                        .child(row.level.label()),
+                ),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .px_2()
+                    .truncate()
+                    .rounded(px(4.0))
+                    .bg(row.level.tint(theme).opacity(0.45))
+                    .text_color(level_color)
+                    .child(row.text.clone()),
+            )
+            .into_any_element()
+    }
+
+    fn set_input_value(
+        input: Option<&Entity<InputState>>,
+        value: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(input) = input {
+            input.update(cx, |input, cx| {
+                input.set_value(value.to_string(), window, cx);
+            });
         }
+    }
 
-        cx.notify();
+    /// Pick up edits to the search and module inputs
+    fn sync_inputs(&mut self, cx: &mut Context<Self>) {
+        if let Some(search_input) = self.search_input.as_ref() {
+            let query = search_input.read(cx).value().to_string();
+            let filter = &self.store.filter;
+            let (changed, mode) = (filter.query() != query.trim(), filter.mode());
+            if changed {
+                self.update_filter(|filter| filter.set_query(&query, mode), cx);
+            }
+        }
+
+        if let Some(target_input) = self.target_input.as_ref() {
+            let prefix = target_input.read(cx).value().to_string();
+            if self.store.filter.target_prefix() != prefix.trim() {
+                self.update_filter(|filter| filter.set_target_prefix(&prefix), cx);
+            }
+        }
+    }
+
+    fn render_level_toggle(
+        &self,
+        level: LogLevel,
+        label: &'static str,
+        cx: &mut Context<Self>,
+    ) -> Button {
+        let filter = &self.store.filter;
+        let enabled = !filter.all_levels_enabled() && filter.level_enabled(level);
+
+        Button::new(SharedString::from(format!(
+            "filter-{}",
+            label.to_ascii_lowercase()
+        )))
+        .label(label)
+        .when(enabled, |btn| btn.primary())
+        .on_click(cx.listener(move |this, _event, _window, cx| {
+            this.update_filter(
+                |filter| {
+                    if filter.all_levels_enabled() {
+                        // Starting from "All", a toggle isolates that level
+                        for other in LogLevel::FILTERABLE {
+                            if other != level {
+                                filter.toggle_level(other);
+                            }
+                        }
+                    } else {
+                        filter.toggle_level(level);
+                        if LogLevel::FILTERABLE
+                            .iter()
+                            .all(|l| !filter.level_enabled(*l))
+                        {
+                            filter.enable_all_levels();
+                        }
+                    }
+                    true
+                },
+                cx,
+            );
+        }))
     }
 
     fn jump_to_latest(
@@ -728,7 +634,7 @@ impl LogDrawer {
         cx: &mut Context<Self>,
     ) {
         self.locked_to_bottom = true;
-        self.scroll_to_bottom(cx);
+        self.scroll_to_bottom();
         cx.notify();
     }
 
@@ -752,27 +658,68 @@ impl LogDrawer {
 
 impl Render for LogDrawer {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        self.ensure_table(window, cx);
+        self.ensure_inputs(window, cx);
         let theme = cx.theme().clone();
 
-        if let Some(search_input) = self.search_input.as_ref() {
-            let search_query = search_input.read(cx).value().to_string();
-            if self.store.borrow().search_query != search_query.trim().to_ascii_lowercase() {
-                self.set_search_query(search_query, cx);
-            }
-        }
+        self.sync_inputs(cx);
 
-        let store = self.store.borrow();
+        let store = &self.store;
         let visible_count = store.visible_count();
-        let buffered_count = store.rows.len();
+        let buffered_count = store.buffered_count();
         let total_seen = store.total_seen;
         let dropped_total = store.dropped_total;
-        let active_search = store.search_query.clone();
-        drop(store);
+        let filter_active = store.has_active_filter();
+        let active_search = store.filter.query().to_string();
+        let active_target = store.filter.target_prefix().to_string();
+        let regex_mode = store.filter.mode() == SearchMode::Regex;
+        let regex_error = store.filter.regex_error().map(String::from);
+        let all_levels = store.filter.all_levels_enabled();
+        let active_source = store.filter.source().map(String::from);
+        // Only worth a filter row once files are followed next to the engine log
+        let sources: Vec<(String, usize)> = if store.sources.len() > 1 {
+            store
+                .sources
+                .iter()
+                .map(|(source, count)| (source.to_string(), *count))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let targets: Vec<(String, usize)> = if self.show_targets {
+            store
+                .targets
+                .iter()
+                .map(|(target, count)| (target.clone(), *count))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let selected_count = self
+            .selection
+            .map(|(anchor, head)| store.visible_count_between(anchor, head))
+            .unwrap_or(0);
+
+        if self.item_sizes.len() != visible_count {
+            self.item_sizes = Rc::new(vec![size(px(0.0), px(ROW_HEIGHT)); visible_count]);
+        }
+
+        let status = if filter_active {
+            format!(
+                "{} matches | {} buffered | {} seen | {} dropped",
+                visible_count, buffered_count, total_seen, dropped_total
+            )
+        } else {
+            format!(
+                "{} shown | {} buffered | {} seen | {} dropped",
+                visible_count, buffered_count, total_seen, dropped_total
+            )
+        };
 
         v_flex()
             .size_full()
             .bg(theme.background)
+            .track_focus(&self.focus_handle)
+            .on_key_down(cx.listener(Self::handle_key_down))
             .child(
                 h_flex()
                     .w_full()
@@ -783,10 +730,7 @@ impl Render for LogDrawer {
                     .bg(theme.background.opacity(0.98))
                     .border_b_1()
                     .border_color(theme.border.opacity(0.4))
-                    .child(div().text_color(theme.muted_foreground).child(format!(
-                        "{} shown | {} buffered | {} seen | {} dropped",
-                        visible_count, buffered_count, total_seen, dropped_total
-                    )))
+                    .child(div().text_color(theme.muted_foreground).child(status))
                     .child(
                         h_flex()
                             .gap_2()
@@ -799,6 +743,21 @@ impl Render for LogDrawer {
                                         this.clear_logs(cx);
                                     })),
                             )
+                            .when(selected_count > 0, |this| {
+                                this.child(
+                                    Button::new("copy-selection")
+                                        .label(if selected_count == 1 {
+                                            "Copy Line".to_string()
+                                        } else {
+                                            format!("Copy {} Lines", selected_count)
+                                        })
+                                        .icon(IconName::Copy)
+                                        .tooltip("Copy the selected rows (Ctrl+C)")
+                                        .on_click(cx.listener(|this, _event, _window, cx| {
+                                            this.copy_selection(cx);
+                                        })),
+                                )
+                            })
                             .when(!self.locked_to_bottom, |this| {
                                 this.child(
                                     Button::new("jump-to-latest")
@@ -827,84 +786,193 @@ impl Render for LogDrawer {
                             .into_any_element(),
                         None => div().flex_1().into_any_element(),
                     })
+                    .child(
+                        Button::new("search-regex")
+                            .label(".*")
+                            .tooltip("Match with a regular expression")
+                            .when(regex_mode, |btn| btn.primary())
+                            .on_click(cx.listener(move |this, _event, _window, cx| {
+                                let mode = if regex_mode {
+                                    SearchMode::Substring
+                                } else {
+                                    SearchMode::Regex
+                                };
+                                let query = this.store.filter.query().to_string();
+                                this.update_filter(|filter| filter.set_query(&query, mode), cx);
+                            })),
+                    )
+                    .when_some(regex_error, |this, error| {
+                        this.child(
+                            div()
+                                .flex_shrink()
+                                .min_w_0()
+                                .truncate()
+                                .text_sm()
+                                .text_color(theme.danger)
+                                .child(format!("Invalid regex: {}", error)),
+                        )
+                    })
                     .when(!active_search.is_empty(), |this| {
                         this.child(Button::new("clear-search").label("Clear Search").on_click(
                             cx.listener(|this, _event, window, cx| {
-                                if let Some(search_input) = this.search_input.as_ref() {
-                                    search_input.update(cx, |input, cx| {
-                                        input.set_value("", window, cx);
-                                    });
-                                }
-                                this.set_search_query(String::new(), cx);
+                                Self::set_input_value(this.search_input.as_ref(), "", window, cx);
+                                let mode = this.store.filter.mode();
+                                this.update_filter(|filter| filter.set_query("", mode), cx);
                             }),
                         ))
-                    })
+                    }),
+            )
+            .child(
+                h_flex()
+                    .w_full()
+                    .h(px(44.0))
+                    .px_4()
+                    .items_center()
+                    .gap_2()
+                    .bg(theme.background.opacity(0.94))
+                    .border_b_1()
+                    .border_color(theme.border.opacity(0.35))
                     .child(
                         Button::new("filter-all")
                             .label("All")
-                            .when(self.store.borrow().level_filter.is_none(), |btn| {
-                                btn.primary()
-                            })
-                            .on_click(cx.listener(|this, _event, _window, cx| {
-                                this.set_level_filter(None, cx);
-                            })),
-                    )
-                    .child(
-                        Button::new("filter-error")
-                            .label("Errors")
-                            .when(
-                                self.store.borrow().level_filter == Some(LogLevel::Error),
-                                |btn| btn.primary(),
-                            )
-                            .on_click(cx.listener(|this, _event, _window, cx| {
-                                this.set_level_filter(Some(LogLevel::Error), cx);
-                            })),
-                    )
-                    .child(
-                        Button::new("filter-warn")
-                            .label("Warnings")
-                            .when(
-                                self.store.borrow().level_filter == Some(LogLevel::Warn),
-                                |btn| btn.primary(),
-                            )
+                            .when(all_levels, |btn| btn.primary())
                             .on_click(cx.listener(|this, _event, _window, cx| {
-                                this.set_level_filter(Some(LogLevel::Warn), cx);
-                            })),
-                    )
-                    .child(
-                        Button::new("filter-info")
-                            .label("Info")
-                            .when(
-                                self.store.borrow().level_filter == Some(LogLevel::Info),
-                                |btn| btn.primary(),
-                            )
-                            .on_click(cx.listener(|this, _event, _window, cx| {
-                                this.set_level_filter(Some(LogLevel::Info), cx);
+                                this.update_filter(
+                                    |filter| {
+                                        let changed = !filter.all_levels_enabled();
+                                        filter.enable_all_levels();
+                                        changed
+                                    },
+                                    cx,
+                                );
                             })),
                     )
+                    .child(self.render_level_toggle(LogLevel::Error, "Errors", cx))
+                    .child(self.render_level_toggle(LogLevel::Warn, "Warnings", cx))
+                    .child(self.render_level_toggle(LogLevel::Info, "Info", cx))
+                    .child(self.render_level_toggle(LogLevel::Debug, "Debug", cx))
+                    .child(self.render_level_toggle(LogLevel::Trace, "Trace", cx))
+                    .child(match self.target_input.as_ref() {
+                        Some(target_input) => div()
+                            .flex_1()
+                            .max_w(px(280.0))
+                            .child(TextInput::new(target_input))
+                            .into_any_element(),
+                        None => div().flex_1().into_any_element(),
+                    })
                     .child(
-                        Button::new("filter-debug")
-                            .label("Debug")
-                            .when(
-                                self.store.borrow().level_filter == Some(LogLevel::Debug),
-                                |btn| btn.primary(),
-                            )
+                        Button::new("toggle-targets")
+                            .label("Modules")
+                            .icon(if self.show_targets {
+                                IconName::ChevronUp
+                            } else {
+                                IconName::ChevronDown
+                            })
+                            .when(self.show_targets, |btn| btn.primary())
                             .on_click(cx.listener(|this, _event, _window, cx| {
-                                this.set_level_filter(Some(LogLevel::Debug), cx);
+                                this.show_targets = !this.show_targets;
+                                cx.notify();
                             })),
                     )
-                    .child(
-                        Button::new("filter-trace")
-                            .label("Trace")
-                            .when(
-                                self.store.borrow().level_filter == Some(LogLevel::Trace),
-                                |btn| btn.primary(),
-                            )
-                            .on_click(cx.listener(|this, _event, _window, cx| {
-                                this.set_level_filter(Some(LogLevel::Trace), cx);
-                            })),
-                    ),
+                    .when(!active_target.is_empty(), |this| {
+                        this.child(Button::new("clear-target").label("Clear Module").on_click(
+                            cx.listener(|this, _event, window, cx| {
+                                Self::set_input_value(this.target_input.as_ref(), "", window, cx);
+                                this.update_filter(|filter| filter.set_target_prefix(""), cx);
+                            }),
+                        ))
+                    }),
             )
+            .when(!sources.is_empty(), |this| {
+                this.child(
+                    h_flex()
+                        .w_full()
+                        .px_4()
+                        .py_2()
+                        .flex_wrap()
+                        .items_center()
+                        .gap_1()
+                        .bg(theme.background.opacity(0.94))
+                        .border_b_1()
+                        .border_color(theme.border.opacity(0.35))
+                        .child(
+                            Button::new("source-all")
+                                .label("All Sources")
+                                .when(active_source.is_none(), |btn| btn.primary())
+                                .on_click(cx.listener(|this, _event, _window, cx| {
+                                    this.update_filter(|filter| filter.set_source(None), cx);
+                                })),
+                        )
+                        .children(
+                            sources
+                                .into_iter()
+                                .enumerate()
+                                .map(|(ix, (source, count))| {
+                                    let selected =
+                                        active_source.as_deref() == Some(source.as_str());
+                                    Button::new(("log-source", ix))
+                                        .label(format!("{} ({})", source, count))
+                                        .when(selected, |btn| btn.primary())
+                                        .on_click(cx.listener(move |this, _event, _window, cx| {
+                                            // Clicking the selected source shows every source again
+                                            let source = (!selected).then_some(source.as_str());
+                                            this.update_filter(
+                                                |filter| filter.set_source(source),
+                                                cx,
+                                            );
+                                        }))
+                                }),
+                        ),
+                )
+            })
+            .when(self.show_targets, |this| {
+                this.child(
+                    div()
+                        .id("log-targets")
+                        .w_full()
+                        .max_h(px(132.0))
+                        .px_4()
+                        .py_2()
+                        .overflow_y_scroll()
+                        .bg(theme.background.opacity(0.94))
+                        .border_b_1()
+                        .border_color(theme.border.opacity(0.35))
+                        .child(if targets.is_empty() {
+                            div()
+                                .text_sm()
+                                .text_color(theme.muted_foreground)
+                                .child("No modules seen yet")
+                                .into_any_element()
+                        } else {
+                            h_flex()
+                                .flex_wrap()
+                                .gap_1()
+                                .children(targets.into_iter().enumerate().map(
+                                    |(ix, (target, count))| {
+                                        let selected = target == active_target;
+                                        Button::new(("log-target", ix))
+                                            .label(format!("{} ({})", target, count))
+                                            .when(selected, |btn| btn.primary())
+                                            .on_click(cx.listener(
+                                                move |this, _event, window, cx| {
+                                                    Self::set_input_value(
+                                                        this.target_input.as_ref(),
+                                                        &target,
+                                                        window,
+                                                        cx,
+                                                    );
+                                                    this.update_filter(
+                                                        |filter| filter.set_target_prefix(&target),
+                                                        cx,
+                                                    );
+                                                },
+                                            ))
+                                    },
+                                ))
+                                .into_any_element()
+                        }),
+                )
+            })
             .child(
                 div()
                     .flex_1()
@@ -919,15 +987,30 @@ impl Render for LogDrawer {
                                         .child(error.clone()),
                                 ),
                             )
-                        } else if let Some(table) = self.table.clone() {
-                            this.child(table)
                         } else {
                             this.child(
-                                v_flex().size_full().items_center().justify_center().child(
-                                    div()
-                                        .text_color(theme.muted_foreground)
-                                        .child("Loading table..."),
-                                ),
+                                v_virtual_list(
+                                    cx.entity().clone(),
+                                    "log-rows",
+                                    self.item_sizes.clone(),
+                                    |this, range, _window, cx| {
+                                        let theme = cx.theme().clone();
+                                        let selected = this.selected_lines();
+                                        let show_source = this.store.sources.len() > 1;
+                                        range
+                                            .map(|ix| {
+                                                this.render_row(
+                                                    ix,
+                                                    selected.as_ref(),
+                                                    show_source,
+                                                    &theme,
+                                                    cx,
+                                                )
+                                            })
+                                            .collect()
+                                    },
+                                )
+                                .track_scroll(&self.scroll_handle),
                             )
                         }
                     }),
@@ -0,0 +1,420 @@
+//! Alerts panel — alert rule status, trigger/recovery history and the rule editor dialog.
+
+use crate::utils::alerts::{
+    save_rules, AlertEventKind, AlertMetric, AlertRule, Comparison, SharedAlertMonitor,
+};
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use std::time::Duration;
+use ui::{
+    button::{Button, ButtonVariants as _},
+    dock::{Panel, PanelEvent},
+    h_flex,
+    input::{InputState, TextInput},
+    v_flex, ActiveTheme, ContextModal as _, Disableable as _, Icon, IconName, StyledExt,
+};
+
+pub struct AlertsPanel {
+    focus_handle: FocusHandle,
+    alerts: SharedAlertMonitor,
+}
+
+impl AlertsPanel {
+    pub fn new(alerts: SharedAlertMonitor, cx: &mut Context<Self>) -> Self {
+        Self {
+            focus_handle: cx.focus_handle(),
+            alerts,
+        }
+    }
+
+    fn open_rules_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let alerts = self.alerts.clone();
+        let editor = cx.new(|cx| AlertRulesEditor::new(alerts, window, cx));
+        window.open_modal(cx, move |modal, _window, _cx| {
+            modal
+                .width(px(640.0))
+                .show_close(true)
+                .title("Alert Rules")
+                .child(editor.clone())
+        });
+    }
+}
+
+impl EventEmitter<PanelEvent> for AlertsPanel {}
+
+ui_common::panel_boilerplate!(AlertsPanel);
+
+impl Render for AlertsPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        use ui::scroll::ScrollbarAxis;
+        let theme = cx.theme().clone();
+        let alerts = self.alerts.read();
+
+        let rules: Vec<(AlertRule, bool)> = alerts
+            .rules()
+            .iter()
+            .enumerate()
+            .map(|(ix, rule)| (*rule, alerts.is_firing(ix)))
+            .collect();
+        let history: Vec<_> = alerts.history().cloned().collect();
+        drop(alerts);
+
+        cx.notify();
+
+        let section_header = |title: &'static str| {
+            div()
+                .text_size(px(12.0))
+                .font_weight(gpui::FontWeight::SEMIBOLD)
+                .text_color(theme.foreground)
+                .child(title)
+        };
+
+        v_flex()
+            .size_full()
+            .bg(theme.sidebar)
+            .p_4()
+            .gap_3()
+            .scrollable(ScrollbarAxis::Vertical)
+            .child(
+                h_flex()
+                    .w_full()
+                    .items_center()
+                    .justify_between()
+                    .child(section_header("Rules"))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(
+                                Button::new("clear-alert-history")
+                                    .xsmall()
+                                    .ghost()
+                                    .label("Clear History")
+                                    .disabled(history.is_empty())
+                                    .on_click(cx.listener(|this, _event, _window, cx| {
+                                        this.alerts.write().clear_history();
+                                        cx.notify();
+                                    })),
+                            )
+                            .child(
+                                Button::new("edit-alert-rules")
+                                    .xsmall()
+                                    .icon(IconName::Settings)
+                                    .label("Edit Rules")
+                                    .on_click(cx.listener(|this, _event, window, cx| {
+                                        this.open_rules_dialog(window, cx);
+                                    })),
+                            ),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .w_full()
+                    .p_2()
+                    .gap_1()
+                    .bg(theme.background)
+                    .border_1()
+                    .border_color(theme.border)
+                    .rounded(px(6.0))
+                    .when(rules.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .text_size(px(11.0))
+                                .text_color(theme.muted_foreground)
+                                .child("No alert rules configured"),
+                        )
+                    })
+                    .children(rules.into_iter().map(|(rule, firing)| {
+                        let color = if firing { theme.danger } else { theme.success };
+                        h_flex()
+                            .w_full()
+                            .gap_2()
+                            .items_center()
+                            .text_size(px(11.0))
+                            .child(div().size(px(8.0)).rounded_full().bg(color))
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .text_color(theme.foreground)
+                                    .child(rule.describe()),
+                            )
+                            .child(div().text_color(color).child(if firing {
+                                "Firing"
+                            } else {
+                                "OK"
+                            }))
+                    })),
+            )
+            .child(section_header("History"))
+            .when(history.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_size(px(11.0))
+                        .text_color(theme.muted_foreground)
+                        .child("No alerts yet"),
+                )
+            })
+            .children(history.into_iter().map(|event| {
+                let (icon, color) = match event.kind {
+                    AlertEventKind::Triggered => (IconName::TriangleAlert, theme.danger),
+                    AlertEventKind::Recovered => (IconName::CircleCheck, theme.success),
+                };
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .p_2()
+                    .items_center()
+                    .bg(theme.background)
+                    .border_1()
+                    .border_color(color.opacity(0.35))
+                    .rounded(px(6.0))
+                    .child(Icon::new(icon).size_4().text_color(color))
+                    .child(
+                        v_flex()
+                            .flex_1()
+                            .child(
+                                div()
+                                    .text_size(px(12.0))
+                                    .font_weight(gpui::FontWeight::MEDIUM)
+                                    .text_color(theme.foreground)
+                                    .child(event.title()),
+                            )
+                            .child(
+                                div()
+                                    .text_size(px(11.0))
+                                    .text_color(theme.muted_foreground)
+                                    .child(event.message()),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .text_size(px(11.0))
+                            .text_color(theme.muted_foreground)
+                            .child(event.at.format("%H:%M:%S").to_string()),
+                    )
+            }))
+    }
+}
+
+impl Panel for AlertsPanel {
+    fn panel_name(&self) -> &'static str {
+        "alerts"
+    }
+    fn title(&self, _window: &Window, _cx: &App) -> AnyElement {
+        "Alerts".into_any_element()
+    }
+}
+
+/// One editable rule in [`AlertRulesEditor`]
+struct RuleRow {
+    metric: AlertMetric,
+    comparison: Comparison,
+    threshold: Entity<InputState>,
+    sustained_secs: Entity<InputState>,
+}
+
+impl RuleRow {
+    fn new(rule: AlertRule, window: &mut Window, cx: &mut App) -> Self {
+        let input = |value: String, window: &mut Window, cx: &mut App| {
+            cx.new(|cx| {
+                let mut input = InputState::new(window, cx);
+                input.set_value(value, window, cx);
+                input
+            })
+        };
+        Self {
+            metric: rule.metric,
+            comparison: rule.comparison,
+            threshold: input(rule.threshold.to_string(), window, cx),
+            sustained_secs: input(rule.sustained_for.as_secs().to_string(), window, cx),
+        }
+    }
+
+    fn to_rule(&self, cx: &App) -> Result<AlertRule, String> {
+        let threshold = self.threshold.read(cx).value().trim().to_string();
+        let threshold = threshold
+            .parse::<f64>()
+            .ok()
+            .filter(|t| t.is_finite())
+            .ok_or_else(|| format!("invalid threshold '{}'", threshold))?;
+        let secs = self.sustained_secs.read(cx).value().trim().to_string();
+        let secs = secs
+            .parse::<u64>()
+            .map_err(|_| format!("invalid duration '{}'", secs))?;
+        Ok(AlertRule {
+            metric: self.metric,
+            comparison: self.comparison,
+            threshold,
+            sustained_for: Duration::from_secs(secs),
+        })
+    }
+}
+
+/// Modal content for adding, changing and removing alert rules
+pub struct AlertRulesEditor {
+    alerts: SharedAlertMonitor,
+    rows: Vec<RuleRow>,
+    error: Option<String>,
+}
+
+impl AlertRulesEditor {
+    fn new(alerts: SharedAlertMonitor, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let rules = alerts.read().rules().to_vec();
+        Self {
+            rows: rules
+                .into_iter()
+                .map(|rule| RuleRow::new(rule, window, cx))
+                .collect(),
+            alerts,
+            error: None,
+        }
+    }
+
+    fn add_rule(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let rule = AlertRule {
+            metric: AlertMetric::MemoryMb,
+            comparison: Comparison::Above,
+            threshold: 8192.0,
+            sustained_for: Duration::from_secs(5),
+        };
+        self.rows.push(RuleRow::new(rule, window, cx));
+        cx.notify();
+    }
+
+    /// Apply the rules to the running monitor and persist them, or show
+    /// the first invalid row
+    fn save(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let rules: Result<Vec<AlertRule>, String> = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(ix, row)| {
+                row.to_rule(cx)
+                    .map_err(|e| format!("Rule {}: {}", ix + 1, e))
+            })
+            .collect();
+        match rules {
+            Ok(rules) => {
+                save_rules(&rules);
+                self.alerts.write().set_rules(rules);
+                window.close_modal(cx);
+            }
+            Err(err) => {
+                self.error = Some(err);
+                cx.notify();
+            }
+        }
+    }
+}
+
+impl Render for AlertRulesEditor {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme().clone();
+
+        let rows = self.rows.iter().enumerate().map(|(ix, row)| {
+            h_flex()
+                .w_full()
+                .gap_2()
+                .items_center()
+                .child(
+                    Button::new(("alert-metric", ix))
+                        .small()
+                        .label(row.metric.label())
+                        .tooltip("Click to change the metric")
+                        .on_click(cx.listener(move |this, _event, _window, cx| {
+                            this.rows[ix].metric = this.rows[ix].metric.next();
+                            cx.notify();
+                        })),
+                )
+                .child(
+                    Button::new(("alert-comparison", ix))
+                        .small()
+                        .label(row.comparison.symbol())
+                        .on_click(cx.listener(move |this, _event, _window, cx| {
+                            this.rows[ix].comparison = this.rows[ix].comparison.toggled();
+                            cx.notify();
+                        })),
+                )
+                .child(div().w(px(110.0)).child(TextInput::new(&row.threshold)))
+                .child(
+                    div()
+                        .w(px(32.0))
+                        .text_size(px(12.0))
+                        .text_color(theme.muted_foreground)
+                        .child(row.metric.unit().trim().to_string()),
+                )
+                .child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(theme.muted_foreground)
+                        .child("for"),
+                )
+                .child(div().w(px(64.0)).child(TextInput::new(&row.sustained_secs)))
+                .child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(theme.muted_foreground)
+                        .child("s"),
+                )
+                .child(
+                    Button::new(("alert-remove", ix))
+                        .xsmall()
+                        .ghost()
+                        .icon(IconName::Trash)
+                        .tooltip("Remove rule")
+                        .on_click(cx.listener(move |this, _event, _window, cx| {
+                            this.rows.remove(ix);
+                            this.error = None;
+                            cx.notify();
+                        })),
+                )
+        });
+
+        v_flex()
+            .w_full()
+            .gap_2()
+            .child(
+                div()
+                    .text_size(px(12.0))
+                    .text_color(theme.muted_foreground)
+                    .child(
+                        "A rule fires once its metric stays past the threshold for the given \
+                         time, and recovers once it is back inside the threshold (with a small \
+                         margin) for as long.",
+                    ),
+            )
+            .children(rows)
+            .when_some(self.error.clone(), |this, error| {
+                this.child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(theme.danger)
+                        .child(error),
+                )
+            })
+            .child(
+                h_flex()
+                    .w_full()
+                    .pt_2()
+                    .justify_between()
+                    .child(
+                        Button::new("add-alert-rule")
+                            .small()
+                            .ghost()
+                            .icon(IconName::Plus)
+                            .label("Add Rule")
+                            .on_click(cx.listener(|this, _event, window, cx| {
+                                this.add_rule(window, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("save-alert-rules")
+                            .small()
+                            .primary()
+                            .label("Save")
+                            .on_click(cx.listener(|this, _event, window, cx| {
+                                this.save(window, cx);
+                            })),
+                    ),
+            )
+    }
+}
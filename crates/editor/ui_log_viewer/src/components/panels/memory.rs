@@ -1,5 +1,6 @@
 //! Memory panel — system memory stats (cache, pools, committed) + engine allocation breakdown.
 
+use crate::utils::atomic_memory_tracking::AllocationEntry;
 use crate::utils::memory_tracking::SharedMemoryTracker;
 use crate::utils::performance_metrics::SharedPerformanceMetrics;
 use gpui::prelude::FluentBuilder;
@@ -8,14 +9,35 @@ use ui::{
     dock::{Panel, PanelEvent},
     v_flex, ActiveTheme,
 };
+use ui_common::format_bytes;
+
+/// Column the engine allocation table is sorted by
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SortColumn {
+    Name,
+    Current,
+    Peak,
+}
+
+impl SortColumn {
+    fn label(self) -> &'static str {
+        match self {
+            SortColumn::Name => "Category",
+            SortColumn::Current => "Current",
+            SortColumn::Peak => "Peak",
+        }
+    }
+}
 
 pub struct MemoryBreakdownPanel {
     focus_handle: FocusHandle,
     scroll_handle: ui::VirtualListScrollHandle,
-    cached_entries: Vec<crate::utils::atomic_memory_tracking::AllocationEntry>,
+    cached_entries: Vec<AllocationEntry>,
     cached_total: usize,
     last_update: std::time::Instant,
     metrics: SharedPerformanceMetrics,
+    sort_column: SortColumn,
+    sort_descending: bool,
 }
 
 impl MemoryBreakdownPanel {
@@ -31,7 +53,64 @@ impl MemoryBreakdownPanel {
             cached_total: 0,
             last_update: std::time::Instant::now(),
             metrics,
+            sort_column: SortColumn::Current,
+            sort_descending: true,
+        }
+    }
+
+    /// Sort by `column`, flipping the direction if it already is
+    fn toggle_sort(&mut self, column: SortColumn, cx: &mut Context<Self>) {
+        if self.sort_column == column {
+            self.sort_descending = !self.sort_descending;
+        } else {
+            self.sort_column = column;
+            // Names read best A-Z, sizes largest first
+            self.sort_descending = column != SortColumn::Name;
         }
+        self.sort_entries();
+        cx.notify();
+    }
+
+    fn sort_entries(&mut self) {
+        let column = self.sort_column;
+        self.cached_entries.sort_by(|a, b| {
+            let ordering = match column {
+                SortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                SortColumn::Current => a.size.cmp(&b.size),
+                SortColumn::Peak => a.peak.cmp(&b.peak),
+            };
+            if self.sort_descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+
+    fn sort_header(&self, column: SortColumn, cx: &Context<Self>) -> Stateful<Div> {
+        let theme = cx.theme();
+        let arrow = match (self.sort_column == column, self.sort_descending) {
+            (false, _) => "",
+            (true, true) => " ▼",
+            (true, false) => " ▲",
+        };
+        div()
+            .id(SharedString::from(format!(
+                "memory-sort-{}",
+                column.label()
+            )))
+            .cursor_pointer()
+            .text_size(px(10.0))
+            .font_weight(gpui::FontWeight::SEMIBOLD)
+            .text_color(if self.sort_column == column {
+                theme.foreground
+            } else {
+                theme.muted_foreground
+            })
+            .child(format!("{}{}", column.label(), arrow))
+            .on_click(cx.listener(move |this, _event, _window, cx| {
+                this.toggle_sort(column, cx);
+            }))
     }
 }
 
@@ -52,6 +131,7 @@ impl Render for MemoryBreakdownPanel {
             use crate::utils::atomic_memory_tracking::ATOMIC_MEMORY_COUNTERS;
             self.cached_total = ATOMIC_MEMORY_COUNTERS.total();
             self.cached_entries = ATOMIC_MEMORY_COUNTERS.get_all_entries();
+            self.sort_entries();
         }
 
         let snap = self.metrics.read().mem_snapshot.clone();
@@ -107,11 +187,8 @@ impl Render for MemoryBreakdownPanel {
                 )
         };
 
-        let mb_str = |v: u64| format!("{} MiB", v);
-        let opt_mb = |v: Option<u64>| {
-            v.map(|n| format!("{} MiB", n))
-                .unwrap_or_else(|| "N/A".to_string())
-        };
+        let mb_str = |v: u64| format_bytes(v * 1024 * 1024);
+        let opt_mb = |v: Option<u64>| v.map(mb_str).unwrap_or_else(|| "N/A".to_string());
 
         #[derive(Clone)]
         struct Pt {
@@ -144,7 +221,7 @@ impl Render for MemoryBreakdownPanel {
                                 .text_color(theme.foreground).child("System Memory"))
                             .child(div().text_size(px(18.0)).font_weight(gpui::FontWeight::BOLD)
                                 .text_color(use_color)
-                                .child(format!("{} / {} MiB  ({:.0}%)", snap.in_use_mb, snap.total_mb, in_use_pct)))
+                                .child(format!("{} / {}  ({:.0}%)", mb_str(snap.in_use_mb), mb_str(snap.total_mb), in_use_pct)))
                     )
                     // Usage bar
                     .child(
@@ -161,14 +238,14 @@ impl Render for MemoryBreakdownPanel {
                             .child(stat_card("Total RAM",      mb_str(snap.total_mb),     theme.muted_foreground))
                             .child(stat_card("Committed", {
                                 if let (Some(c), Some(l)) = (snap.committed_mb, snap.committed_limit_mb) {
-                                    format!("{} / {} MiB", c, l)
+                                    format!("{} / {}", mb_str(c), mb_str(l))
                                 } else { opt_mb(snap.committed_mb) }
                             }, theme.warning))
                             .child(stat_card("Paged Pool",     opt_mb(snap.paged_pool_mb),     theme.accent))
                             .child(stat_card("Non-Paged Pool", opt_mb(snap.non_paged_pool_mb), theme.accent))
                             .child(stat_card("Page File", {
                                 if snap.swap_total_mb > 0 {
-                                    format!("{} / {} MiB", snap.swap_used_mb, snap.swap_total_mb)
+                                    format!("{} / {}", mb_str(snap.swap_used_mb), mb_str(snap.swap_total_mb))
                                 } else { "N/A".to_string() }
                             }, theme.muted_foreground))
                     )
@@ -218,7 +295,14 @@ impl Render for MemoryBreakdownPanel {
                             .child(div().text_size(px(11.0)).font_weight(gpui::FontWeight::SEMIBOLD)
                                 .text_color(theme.foreground).child("Engine Allocations"))
                             .child(div().text_size(px(11.0)).text_color(theme.foreground)
-                                .child(format!("{:.2} MB", cached_alloc as f64 / 1024.0 / 1024.0)))
+                                .child(format_bytes(cached_alloc as u64)))
+                    )
+                    .child(
+                        h_flex().w_full().px_3().gap_2()
+                            .child(div().flex_1().child(self.sort_header(SortColumn::Name, cx)))
+                            .child(div().w(px(90.0)).child(self.sort_header(SortColumn::Current, cx)))
+                            .child(div().w(px(90.0)).child(self.sort_header(SortColumn::Peak, cx)))
+                            .child(div().w(px(50.0)).text_size(px(10.0)).text_color(theme.muted_foreground).child("Share"))
                     )
             )
             .child(
@@ -237,19 +321,18 @@ impl Render for MemoryBreakdownPanel {
                             if let Some(entry) = entries.get(ix) {
                                 let pct     = if total > 0 { entry.size as f64 / total as f64 * 100.0 } else { 0.0 };
                                 let color   = colors[ix % colors.len()];
-                                let size_mb = entry.size as f64 / 1024.0 / 1024.0;
                                 use ui::h_flex;
                                 v_flex().w_full().p_3().gap_1()
                                     .child(
-                                        h_flex().w_full().justify_between().items_center()
-                                            .child(div().text_size(px(12.0)).font_weight(gpui::FontWeight::MEDIUM)
+                                        h_flex().w_full().gap_2().items_center()
+                                            .child(div().flex_1().min_w_0().truncate().text_size(px(12.0)).font_weight(gpui::FontWeight::MEDIUM)
                                                 .text_color(theme.foreground).child(entry.name.clone()))
-                                            .child(h_flex().gap_2().items_center()
-                                                .child(div().text_size(px(11.0)).text_color(theme.muted_foreground)
-                                                    .child(format!("{:.2} MB", size_mb)))
-                                                .child(div().text_size(px(11.0)).font_weight(gpui::FontWeight::SEMIBOLD)
-                                                    .text_color(color).child(format!("{:.1}%", pct)))
-                                            )
+                                            .child(div().w(px(90.0)).text_size(px(11.0)).text_color(theme.muted_foreground)
+                                                .child(format_bytes(entry.size as u64)))
+                                            .child(div().w(px(90.0)).text_size(px(11.0)).text_color(theme.muted_foreground)
+                                                .child(entry.peak.map(|peak| format_bytes(peak as u64)).unwrap_or_else(|| "—".to_string())))
+                                            .child(div().w(px(50.0)).text_size(px(11.0)).font_weight(gpui::FontWeight::SEMIBOLD)
+                                                .text_color(color).child(format!("{:.1}%", pct)))
                                     )
                                     .child(
                                         div().w_full().h(px(6.0)).bg(theme.border).rounded(px(3.0))
@@ -1,5 +1,6 @@
 //! Mission Control workspace panels — one file per panel.
 
+pub mod alerts;
 pub mod callers;
 pub mod cpu;
 pub mod gpu;
@@ -8,6 +9,7 @@ pub mod memory;
 pub mod resource_monitor;
 pub mod system_info;
 
+pub use alerts::AlertsPanel;
 pub use callers::CallerSitesPanel;
 pub use cpu::AdvancedMetricsPanel;
 pub use gpu::GpuMetricsPanel;
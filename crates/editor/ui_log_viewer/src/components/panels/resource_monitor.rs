@@ -1,16 +1,54 @@
 //! Resource Monitor panel — CPU, memory, GPU, FPS, network, disk charts.
 
+use crate::utils::metric_history::{downsample, MetricSample};
 use crate::utils::performance_metrics::SharedPerformanceMetrics;
 use gpui::prelude::FluentBuilder;
 use gpui::*;
+use std::time::Duration;
 use ui::{
+    button::{Button, ButtonVariants as _},
     dock::{Panel, PanelEvent},
     v_flex, ActiveTheme, StyledExt,
 };
 
+/// Points drawn per long-range chart after downsampling
+const CHART_POINTS: usize = 120;
+/// Threads listed in the per-thread CPU card
+const TOP_THREADS: usize = 8;
+
+/// How far back the CPU, memory and GPU charts look
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+enum ChartRange {
+    #[default]
+    Last30s,
+    Last5m,
+    Last30m,
+}
+
+impl ChartRange {
+    const ALL: [ChartRange; 3] = [ChartRange::Last30s, ChartRange::Last5m, ChartRange::Last30m];
+
+    fn duration(self) -> Duration {
+        match self {
+            ChartRange::Last30s => Duration::from_secs(30),
+            ChartRange::Last5m => Duration::from_secs(5 * 60),
+            ChartRange::Last30m => Duration::from_secs(30 * 60),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ChartRange::Last30s => "30s",
+            ChartRange::Last5m => "5m",
+            ChartRange::Last30m => "30m",
+        }
+    }
+}
+
 pub struct ResourceMonitorPanel {
     focus_handle: FocusHandle,
     metrics: SharedPerformanceMetrics,
+    range: ChartRange,
 }
 
 impl ResourceMonitorPanel {
@@ -18,6 +56,7 @@ impl ResourceMonitorPanel {
         Self {
             focus_handle: cx.focus_handle(),
             metrics,
+            range: ChartRange::default(),
         }
     }
 
@@ -74,6 +113,60 @@ impl ResourceMonitorPanel {
                 )
             })
     }
+
+    /// A labelled long-range chart inside the renderer card
+    fn render_chart(
+        label: &'static str,
+        value_str: String,
+        data: Vec<MetricSample>,
+        color: gpui::Hsla,
+        cx: &App,
+    ) -> impl IntoElement {
+        use ui::chart::AreaChart;
+        use ui::h_flex;
+        let theme = cx.theme();
+        v_flex()
+            .w_full()
+            .gap_1()
+            .child(
+                h_flex()
+                    .w_full()
+                    .justify_between()
+                    .text_size(px(12.0))
+                    .child(div().text_color(theme.muted_foreground).child(label))
+                    .child(
+                        div()
+                            .font_weight(gpui::FontWeight::BOLD)
+                            .text_color(color)
+                            .child(value_str),
+                    ),
+            )
+            .when(!data.is_empty(), |this| {
+                this.child(
+                    div().h(px(40.0)).w_full().child(
+                        AreaChart::<_, SharedString, f64>::new(data)
+                            .x(|_s: &MetricSample| "".into())
+                            .y(|s: &MetricSample| s.value)
+                            .stroke(color)
+                            .fill(color.opacity(0.15))
+                            .linear()
+                            .tick_margin(0)
+                            .max_points(CHART_POINTS),
+                    ),
+                )
+            })
+    }
+}
+
+/// e.g. `512 MB / 8.0 GB`, or just the used amount when the total is unknown
+fn format_vram(used_bytes: u64, total_bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    let used = format!("{:.0} MB", used_bytes as f64 / MB);
+    if total_bytes == 0 {
+        used
+    } else {
+        format!("{} / {:.1} GB", used, total_bytes as f64 / MB / 1024.0)
+    }
 }
 
 impl EventEmitter<PanelEvent> for ResourceMonitorPanel {}
@@ -96,9 +189,28 @@ impl Render for ResourceMonitorPanel {
         let current_disk_read_kbps = metrics.current_disk_read_kbps;
         let current_disk_write_kbps = metrics.current_disk_write_kbps;
 
-        let cpu_data: Vec<_> = metrics.cpu_history.iter().cloned().collect();
-        let memory_data: Vec<_> = metrics.memory_history.iter().cloned().collect();
-        let gpu_data: Vec<_> = metrics.gpu_history.iter().cloned().collect();
+        let window = self.range.duration();
+        let cpu_data = downsample(&metrics.cpu_samples.window(window), CHART_POINTS);
+        let memory_data = downsample(&metrics.memory_samples.window(window), CHART_POINTS);
+        let gpu_data = downsample(&metrics.vram_samples.window(window), CHART_POINTS);
+        let mut threads: Vec<(String, f64)> = metrics
+            .thread_cpu
+            .iter()
+            .filter_map(|(tid, thread)| {
+                let average = thread.history.window_average(window)?;
+                let name = if thread.name.is_empty() {
+                    format!("thread {}", tid)
+                } else {
+                    format!("{} ({})", thread.name, tid)
+                };
+                Some((name, average))
+            })
+            .collect();
+        threads.sort_by(|a, b| b.1.total_cmp(&a.1));
+        threads.truncate(TOP_THREADS);
+        let render = metrics.render;
+        let frame_time_data = downsample(&metrics.frame_time_samples.window(window), CHART_POINTS);
+        let render_mem_data = downsample(&metrics.render_mem_samples.window(window), CHART_POINTS);
         let fps_data: Vec<_> = metrics.fps_history.iter().cloned().collect();
         let net_rx_data: Vec<_> = metrics.net_rx_history.iter().cloned().collect();
         let net_tx_data: Vec<_> = metrics.net_tx_history.iter().cloned().collect();
@@ -115,13 +227,30 @@ impl Render for ResourceMonitorPanel {
             .gap_4()
             .scrollable(ScrollbarAxis::Vertical)
             .child(
-                h_flex().items_center().gap_2().child(
-                    div()
-                        .text_size(px(14.0))
-                        .font_weight(gpui::FontWeight::SEMIBOLD)
-                        .text_color(theme.foreground)
-                        .child("System Resources"),
-                ),
+                h_flex()
+                    .items_center()
+                    .justify_between()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_size(px(14.0))
+                            .font_weight(gpui::FontWeight::SEMIBOLD)
+                            .text_color(theme.foreground)
+                            .child("System Resources"),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .children(ChartRange::ALL.into_iter().map(|range| {
+                                Button::new(range.label())
+                                    .label(range.label())
+                                    .when(range == self.range, |btn| btn.primary())
+                                    .on_click(cx.listener(move |this, _event, _window, cx| {
+                                        this.range = range;
+                                        cx.notify();
+                                    }))
+                            })),
+                    ),
             )
             // CPU
             .child(
@@ -156,14 +285,14 @@ impl Render for ResourceMonitorPanel {
                         this.child(
                             div().h(px(60.0)).w_full().child(
                                 AreaChart::<_, SharedString, f64>::new(cpu_data)
-                                    .x(|d| format!("{}", d.index).into())
-                                    .y(|d| d.usage)
+                                    .x(|_s: &MetricSample| "".into())
+                                    .y(|s: &MetricSample| s.value)
                                     .stroke(theme.info)
                                     .fill(theme.info.opacity(0.15))
                                     .linear()
                                     .tick_margin(0)
                                     .max_y_range(100.0)
-                                    .max_points(crate::utils::performance_metrics::MAX_HISTORY_SIZE),
+                                    .max_points(CHART_POINTS),
                             ),
                         )
                     }),
@@ -201,13 +330,13 @@ impl Render for ResourceMonitorPanel {
                         this.child(
                             div().h(px(60.0)).w_full().child(
                                 AreaChart::<_, SharedString, f64>::new(memory_data)
-                                    .x(|d| format!("{}", d.index).into())
-                                    .y(|d| d.memory_mb)
+                                    .x(|_s: &MetricSample| "".into())
+                                    .y(|s: &MetricSample| s.value)
                                     .stroke(theme.warning)
                                     .fill(theme.warning.opacity(0.15))
                                     .linear()
                                     .tick_margin(0)
-                                    .max_points(crate::utils::performance_metrics::MAX_HISTORY_SIZE),
+                                    .max_points(CHART_POINTS),
                             ),
                         )
                     }),
@@ -249,17 +378,68 @@ impl Render for ResourceMonitorPanel {
                         this.child(
                             div().h(px(60.0)).w_full().child(
                                 AreaChart::<_, SharedString, f64>::new(gpu_data)
-                                    .x(|d| format!("{}", d.index).into())
-                                    .y(|d| d.vram_used_mb)
+                                    .x(|_s: &MetricSample| "".into())
+                                    .y(|s: &MetricSample| s.value)
                                     .stroke(theme.success)
                                     .fill(theme.success.opacity(0.15))
                                     .linear()
                                     .tick_margin(0)
-                                    .max_points(crate::utils::performance_metrics::MAX_HISTORY_SIZE),
+                                    .max_points(CHART_POINTS),
                             ),
                         )
                     }),
             )
+            // Per-thread CPU
+            .child(
+                v_flex()
+                    .w_full()
+                    .p_3()
+                    .gap_1()
+                    .bg(theme.background)
+                    .border_1()
+                    .border_color(theme.border)
+                    .rounded(px(6.0))
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .font_weight(gpui::FontWeight::MEDIUM)
+                            .text_color(theme.muted_foreground)
+                            .child(format!("Busiest Threads (avg over {})", self.range.label())),
+                    )
+                    .when(threads.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .text_size(px(12.0))
+                                .text_color(theme.muted_foreground)
+                                .child(if cfg!(target_os = "linux") {
+                                    "Collecting thread samples…"
+                                } else {
+                                    "Per-thread CPU is only available on Linux"
+                                }),
+                        )
+                    })
+                    .children(threads.into_iter().map(|(name, average)| {
+                        h_flex()
+                            .w_full()
+                            .justify_between()
+                            .gap_2()
+                            .text_size(px(12.0))
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .min_w_0()
+                                    .truncate()
+                                    .text_color(theme.foreground)
+                                    .child(name),
+                            )
+                            .child(
+                                div()
+                                    .text_color(theme.info)
+                                    .font_weight(gpui::FontWeight::SEMIBOLD)
+                                    .child(format!("{:.1}%", average)),
+                            )
+                    })),
+            )
             // FPS
             .child(
                 v_flex()
@@ -286,7 +466,9 @@ impl Render for ResourceMonitorPanel {
                                     .text_size(px(18.0))
                                     .font_weight(gpui::FontWeight::BOLD)
                                     .text_color(theme.foreground)
-                                    .child(if current_fps > 0.0 {
+                                    .child(if render.is_none() {
+                                        "No active renderer".to_string()
+                                    } else if current_fps > 0.0 {
                                         format!("{:.0} FPS", current_fps)
                                     } else {
                                         "N/A".to_string()
@@ -308,6 +490,56 @@ impl Render for ResourceMonitorPanel {
                         )
                     }),
             )
+            // Renderer
+            .child(
+                v_flex()
+                    .w_full()
+                    .p_3()
+                    .gap_2()
+                    .bg(theme.background)
+                    .border_1()
+                    .border_color(theme.border)
+                    .rounded(px(6.0))
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .font_weight(gpui::FontWeight::MEDIUM)
+                            .text_color(theme.muted_foreground)
+                            .child("Renderer"),
+                    )
+                    .map(|this| match render {
+                        None => this.child(
+                            div()
+                                .text_size(px(12.0))
+                                .text_color(theme.muted_foreground)
+                                .child("No active renderer"),
+                        ),
+                        Some(render) => this
+                            .child(
+                                h_flex()
+                                    .w_full()
+                                    .justify_between()
+                                    .text_size(px(12.0))
+                                    .text_color(theme.foreground)
+                                    .child(format!("{} draw calls", render.draw_calls))
+                                    .child(format!("{} triangles", render.triangles)),
+                            )
+                            .child(Self::render_chart(
+                                "Frame Time",
+                                format!("{:.2} ms", render.frame_time_ms),
+                                frame_time_data,
+                                theme.accent,
+                                cx,
+                            ))
+                            .child(Self::render_chart(
+                                "Renderer VRAM",
+                                format_vram(render.gpu_mem_used, render.vram_total),
+                                render_mem_data,
+                                theme.success,
+                                cx,
+                            )),
+                    }),
+            )
             // Network / Disk I/O
             .child(Self::io_chart_card(
                 "Network In",
@@ -7,13 +7,21 @@ mod utils;
 pub use screen::MissionControlPanel;
 pub use components::log_drawer::LogDrawer;
 pub use components::panels::{
-    AdvancedMetricsPanel, CallerSitesPanel, GpuMetricsPanel, LogsPanel, MemoryBreakdownPanel,
-    ResourceMonitorPanel, SystemInfoPanel,
+    AdvancedMetricsPanel, AlertsPanel, CallerSitesPanel, GpuMetricsPanel, LogsPanel,
+    MemoryBreakdownPanel, ResourceMonitorPanel, SystemInfoPanel,
+};
+pub use screen::MissionControlPanel;
+pub use utils::alerts::{
+    create_shared_alerts, AlertEvent, AlertEventKind, AlertMetric, AlertMonitor, AlertRule,
+    Comparison, SharedAlertMonitor,
+};
+pub use utils::atomic_memory_tracking::{
+    AllocationEntry, SizeBucket, ATOMIC_MEMORY_COUNTERS, MAX_MEMORY_CATEGORIES,
 };
-pub use utils::atomic_memory_tracking::{AllocationEntry, SizeBucket, ATOMIC_MEMORY_COUNTERS};
 pub use utils::live_logs::{publish_live_log, subscribe_live_logs};
 pub use utils::memory_tracking::{
-    create_memory_tracker, MemoryCategory, MemoryStatsSnapshot, MemoryTracker, SharedMemoryTracker,
+    create_memory_tracker, CategoryUsage, MemoryCategory, MemoryCategoryId, MemoryStatsSnapshot,
+    MemoryTracker, SharedMemoryTracker,
 };
 pub use utils::performance_metrics::{
     create_shared_metrics, PerformanceMetrics, SharedPerformanceMetrics,
@@ -1,13 +1,27 @@
 //! Mission Control - Main screen component
 
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+use std::time::{Duration, Instant};
+
 use gpui::*;
-use ui::{dock::DockItem, h_flex, v_flex, workspace::Workspace, ActiveTheme, TitleBar};
+use ui::{
+    button::Button, checkbox::Checkbox, dock::DockItem, h_flex, notification::Notification, v_flex,
+    workspace::Workspace, ActiveTheme, ContextModal as _, Disableable as _, IconName, TitleBar,
+};
 
 use crate::components::{log_drawer, panels};
+use crate::utils::alerts::{create_shared_alerts, AlertEvent, AlertEventKind, SharedAlertMonitor};
+use crate::utils::atomic_memory_tracking::ATOMIC_MEMORY_COUNTERS;
+use crate::utils::diagnostics_export::{self, DiagnosticsSnapshot};
 use crate::utils::memory_tracking::{create_memory_tracker, SharedMemoryTracker};
 use crate::utils::performance_metrics::{create_shared_metrics, SharedPerformanceMetrics};
 use crate::utils::system_info::{create_shared_info, SharedSystemInfo};
 
+struct DiagnosticsExportNotification;
+
 /// Mission Control - Main panel with workspace layout
 pub struct MissionControlPanel {
     pub(crate) focus_handle: FocusHandle,
@@ -16,6 +30,10 @@ pub struct MissionControlPanel {
     pub(crate) metrics: SharedPerformanceMetrics,
     pub(crate) system_info: SharedSystemInfo,
     pub(crate) memory_tracker: SharedMemoryTracker,
+    pub(crate) alerts: SharedAlertMonitor,
+    /// Export only the log lines passing the logs panel filter
+    pub(crate) export_filtered_logs: bool,
+    pub(crate) exporting: bool,
     pub(crate) _metrics_task: Option<Task<()>>,
 }
 
@@ -28,6 +46,7 @@ impl MissionControlPanel {
         let metrics = create_shared_metrics();
         let system_info = create_shared_info();
         let memory_tracker = create_memory_tracker();
+        let alerts = create_shared_alerts();
 
         // Populate sysinfo data on the background executor so it never blocks the UI.
         let metrics_bg = metrics.clone();
@@ -35,10 +54,15 @@ impl MissionControlPanel {
         cx.background_executor()
             .spawn(async move {
                 let t = std::time::Instant::now();
-                let full_metrics = crate::utils::performance_metrics::PerformanceMetrics::new();
+                let mut full_metrics = crate::utils::performance_metrics::PerformanceMetrics::new();
                 let full_sysinfo = crate::utils::system_info::SystemInfo::gather();
                 tracing::info!("[MissionControlPanel] sysinfo init took {:?}", t.elapsed());
-                *metrics_bg.write() = full_metrics;
+                {
+                    // Keep any samples taken while sysinfo was initializing
+                    let mut metrics = metrics_bg.write();
+                    full_metrics.inherit_history(&mut metrics);
+                    *metrics = full_metrics;
+                }
                 *sysinfo_bg.write() = full_sysinfo;
             })
             .detach();
@@ -55,12 +79,16 @@ impl MissionControlPanel {
             metrics,
             system_info,
             memory_tracker,
+            alerts,
+            export_filtered_logs: false,
+            exporting: false,
             _metrics_task: None,
         }
     }
 
-    /// Start monitoring the log file and metrics
-    pub fn start_monitoring(&mut self, cx: &mut Context<Self>) {
+    /// Start monitoring the log file and metrics. Alert rules are evaluated
+    /// on every metrics tick and notify through `window`.
+    pub fn start_monitoring(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if self._metrics_task.is_some() {
             return;
         }
@@ -71,20 +99,167 @@ impl MissionControlPanel {
 
         // Standard UI reactivity pattern used elsewhere: an entity-owned task
         // updates state through `cx.update` and then notifies that entity.
+        let window_handle = window.window_handle();
         let task = cx.spawn(async move |this, cx| loop {
             smol::Timer::after(std::time::Duration::from_secs(1)).await;
 
+            let events = cx
+                .update(|cx| {
+                    this.update(cx, |panel, cx| {
+                        let render = panel.render_metrics();
+                        let mut metrics = panel.metrics.write();
+                        metrics.update_system_metrics();
+                        metrics.update_from_render_metrics(render);
+                        let events = panel
+                            .alerts
+                            .write()
+                            .evaluate(Instant::now(), |metric| metric.read(&metrics));
+                        drop(metrics);
+                        cx.notify();
+                        events
+                    })
+                })
+                .unwrap_or_default();
+
+            if !events.is_empty() {
+                let _ = cx.update_window(window_handle, |_, window, cx| {
+                    for event in &events {
+                        window.push_notification(alert_notification(event), cx);
+                    }
+                });
+            }
+        });
+
+        self._metrics_task = Some(task);
+    }
+
+    /// Frame statistics of the active renderer, if any. Renderers that
+    /// can't report VRAM fall back to the adapter total from system info.
+    fn render_metrics(&self) -> Option<engine_state::RenderMetrics> {
+        let mut render = engine_state::EngineContext::global()?.render_metrics()?;
+        if render.vram_total == 0 {
+            let total_mb = self.system_info.read().gpu_vram_total_mb.unwrap_or(0);
+            render.vram_total = total_mb * 1024 * 1024;
+        }
+        Some(render)
+    }
+
+    fn diagnostics_snapshot(&self, cx: &App) -> DiagnosticsSnapshot {
+        let log_drawer = self.log_drawer.read(cx);
+        let filtered = self.export_filtered_logs && log_drawer.has_active_filter();
+        let metrics = self.metrics.read();
+
+        DiagnosticsSnapshot {
+            engine_version: env!("CARGO_PKG_VERSION"),
+            created_at: chrono::Local::now(),
+            log_lines: log_drawer.buffered_lines(filtered),
+            logs_filtered: filtered,
+            metrics_csv: diagnostics_export::metrics_csv(&metrics),
+            system_info: diagnostics_export::system_info_json(&self.system_info.read()),
+            memory: diagnostics_export::memory_json(
+                &metrics.mem_snapshot,
+                &ATOMIC_MEMORY_COUNTERS.snapshot(),
+                ATOMIC_MEMORY_COUNTERS.total(),
+            ),
+        }
+    }
+
+    /// Save logs, metrics, system info and memory stats to a zip the user
+    /// picks. The zip is written on the background executor with progress
+    /// reported through a notification.
+    fn export_diagnostics(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.exporting {
+            return;
+        }
+
+        let snapshot = self.diagnostics_snapshot(cx);
+        let dialog = rfd::AsyncFileDialog::new()
+            .set_title("Export Diagnostics")
+            .add_filter("Zip archive", &["zip"])
+            .set_file_name(snapshot.file_name());
+        let window_handle = window.window_handle();
+
+        self.exporting = true;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            if let Some(handle) = dialog.save_file().await {
+                let path = handle.path().to_path_buf();
+                let _ = cx.update_window(window_handle, |_, window, cx| {
+                    window.push_notification(
+                        Notification::info("Writing diagnostics bundle…")
+                            .id::<DiagnosticsExportNotification>()
+                            .title("Export Diagnostics")
+                            .progress(0.0)
+                            .autohide(false),
+                        cx,
+                    );
+                });
+
+                let progress = Arc::new(AtomicU32::new(0));
+                let progress_for_task = Arc::clone(&progress);
+                let (result_tx, result_rx) = smol::channel::bounded(1);
+                cx.background_executor()
+                    .spawn(async move {
+                        let result = diagnostics_export::write_bundle(&path, &snapshot, |p| {
+                            progress_for_task.store((p * 100.0) as u32, Ordering::Relaxed);
+                        })
+                        .map(|()| path);
+                        let _ = result_tx.send(result).await;
+                    })
+                    .detach();
+
+                let mut last_pct = 0;
+                let result = loop {
+                    match result_rx.try_recv() {
+                        Ok(result) => break result,
+                        Err(smol::channel::TryRecvError::Closed) => {
+                            break Err(anyhow::anyhow!("export task stopped unexpectedly"))
+                        }
+                        Err(smol::channel::TryRecvError::Empty) => {
+                            let pct = progress.load(Ordering::Relaxed);
+                            if pct != last_pct {
+                                last_pct = pct;
+                                let _ = cx.update_window(window_handle, |_, window, cx| {
+                                    window.update_notification::<DiagnosticsExportNotification>(
+                                        format!("Writing diagnostics bundle… ({pct}%)"),
+                                        pct as f32 / 100.0,
+                                        cx,
+                                    );
+                                });
+                            }
+                            cx.background_executor()
+                                .timer(Duration::from_millis(250))
+                                .await;
+                        }
+                    }
+                };
+
+                let _ = cx.update_window(window_handle, |_, window, cx| {
+                    let notification = match &result {
+                        Ok(path) => Notification::success(format!("Saved to {}", path.display()))
+                            .id::<DiagnosticsExportNotification>()
+                            .title("Export Diagnostics")
+                            .progress(1.0)
+                            .autohide_delay(Duration::from_secs(5)),
+                        Err(err) => Notification::error(format!("Export failed: {:#}", err))
+                            .id::<DiagnosticsExportNotification>()
+                            .title("Export Diagnostics"),
+                    };
+                    window.push_notification(notification, cx);
+                });
+            }
+
             let _ = cx.update(|cx| {
                 if let Some(this) = this.upgrade() {
                     this.update(cx, |panel, cx| {
-                        panel.metrics.write().update_system_metrics();
+                        panel.exporting = false;
                         cx.notify();
                     });
                 }
             });
-        });
-
-        self._metrics_task = Some(task);
+        })
+        .detach();
     }
 
     fn initialize_workspace(&mut self, window: &mut Window, cx: &mut Context<Self>) {
@@ -105,6 +280,7 @@ impl MissionControlPanel {
         let metrics = self.metrics.clone();
         let system_info = self.system_info.clone();
         let memory_tracker = self.memory_tracker.clone();
+        let alerts = self.alerts.clone();
 
         workspace.update(cx, |workspace, cx| {
             let dock_area = workspace.dock_area().downgrade();
@@ -133,6 +309,9 @@ impl MissionControlPanel {
             let callers_panel = cx.new(|cx| {
                 panels::CallerSitesPanel::new(window, cx)
             });
+            let alerts_panel = cx.new(|cx| {
+                panels::AlertsPanel::new(alerts.clone(), cx)
+            });
             let resource_panel = cx.new(|cx| {
                 panels::ResourceMonitorPanel::new(metrics.clone(), cx)
             });
@@ -142,7 +321,7 @@ impl MissionControlPanel {
                 panels::SystemInfoPanel::new(system_info.clone(), cx)
             });
 
-            // Center: Logs | Memory | CPU | GPU | Callers tabs
+            // Center: Logs | Memory | CPU | GPU | Callers | Alerts tabs
             let center_tabs = DockItem::tabs(
                 vec![
                     std::sync::Arc::new(logs_panel) as std::sync::Arc<dyn ui::dock::PanelView>,
@@ -150,6 +329,7 @@ impl MissionControlPanel {
                     std::sync::Arc::new(advanced_panel) as std::sync::Arc<dyn ui::dock::PanelView>,
                     std::sync::Arc::new(gpu_panel) as std::sync::Arc<dyn ui::dock::PanelView>,
                     std::sync::Arc::new(callers_panel) as std::sync::Arc<dyn ui::dock::PanelView>,
+                    std::sync::Arc::new(alerts_panel) as std::sync::Arc<dyn ui::dock::PanelView>,
                 ],
                 Some(0), // Default to logs tab
                 &dock_area,
@@ -208,7 +388,7 @@ impl Render for MissionControlPanel {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         self.initialize_workspace(window, cx);
 
-        let theme = cx.theme();
+        let theme = cx.theme().clone();
 
         v_flex()
             .size_full()
@@ -216,13 +396,42 @@ impl Render for MissionControlPanel {
             .child(
                 // Title bar
                 TitleBar::new().child(
-                    h_flex().flex_1().items_center().px_4().child(
-                        div()
-                            .text_size(px(14.0))
-                            .font_weight(gpui::FontWeight::SEMIBOLD)
-                            .text_color(theme.foreground)
-                            .child("Mission Control"),
-                    ),
+                    h_flex()
+                        .flex_1()
+                        .items_center()
+                        .justify_between()
+                        .px_4()
+                        .child(
+                            div()
+                                .text_size(px(14.0))
+                                .font_weight(gpui::FontWeight::SEMIBOLD)
+                                .text_color(theme.foreground)
+                                .child("Mission Control"),
+                        )
+                        .child(
+                            h_flex()
+                                .gap_3()
+                                .items_center()
+                                .child(
+                                    Checkbox::new("export-filtered-logs")
+                                        .label("Filtered logs only")
+                                        .checked(self.export_filtered_logs)
+                                        .on_click(cx.listener(|this, _event, _window, cx| {
+                                            this.export_filtered_logs = !this.export_filtered_logs;
+                                            cx.notify();
+                                        })),
+                                )
+                                .child(
+                                    Button::new("export-diagnostics")
+                                        .label("Export Diagnostics")
+                                        .icon(IconName::Download)
+                                        .tooltip("Save logs, metrics and system info to a zip")
+                                        .disabled(self.exporting)
+                                        .on_click(cx.listener(|this, _event, window, cx| {
+                                            this.export_diagnostics(window, cx);
+                                        })),
+                                ),
+                        ),
                 ),
             )
             .child(if let Some(ref workspace) = self.workspace {
@@ -233,6 +442,16 @@ impl Render for MissionControlPanel {
     }
 }
 
+/// Toast for an alert; triggered alerts stay up until dismissed
+fn alert_notification(event: &AlertEvent) -> Notification {
+    match event.kind {
+        AlertEventKind::Triggered => Notification::warning(event.message())
+            .title(event.title())
+            .autohide(false),
+        AlertEventKind::Recovered => Notification::success(event.message()).title(event.title()),
+    }
+}
+
 #[window_manager::register_window]
 impl window_manager::PulsarWindow for MissionControlPanel {
     type Params = ();
@@ -245,9 +464,9 @@ impl window_manager::PulsarWindow for MissionControlPanel {
         window_manager::default_window_options(1920.0, 1080.0)
     }
 
-    fn build(_: (), _window: &mut gpui::Window, cx: &mut gpui::App) -> gpui::Entity<Self> {
+    fn build(_: (), window: &mut gpui::Window, cx: &mut gpui::App) -> gpui::Entity<Self> {
         let panel = cx.new(MissionControlPanel::new);
-        panel.update(cx, |p, cx| p.start_monitoring(cx));
+        panel.update(cx, |p, cx| p.start_monitoring(window, cx));
         panel
     }
 }
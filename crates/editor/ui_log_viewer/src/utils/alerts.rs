@@ -0,0 +1,533 @@
+//! Threshold alerts evaluated against the 1Hz metric samples
+//!
+//! Rules live in the `mission_control_alerts` setting as `;`-separated
+//! `metric>threshold@seconds` entries, e.g. `memory_mb>8192@5`.
+
+use crate::utils::performance_metrics::PerformanceMetrics;
+use anyhow::{anyhow, Context as _};
+use chrono::{DateTime, Local};
+use engine_state::{ConfigValue, GlobalSettings};
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use ui_common::SharedState;
+
+/// Alert events kept for the alerts panel
+pub const MAX_ALERT_HISTORY: usize = 200;
+
+/// A firing alert only recovers once the value is back past the threshold
+/// by this fraction of it, so values hovering at the limit don't flap
+pub const HYSTERESIS: f64 = 0.05;
+
+/// Rules used while the `mission_control_alerts` setting is unset
+pub const DEFAULT_ALERT_RULES: &str = "memory_mb>8192@5;frame_time_ms>33@3";
+
+const SETTINGS_OWNER: &str = "advanced";
+const SETTINGS_KEY: &str = "mission_control_alerts";
+
+/// Metric an [`AlertRule`] watches
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AlertMetric {
+    CpuPercent,
+    MemoryMb,
+    VramMb,
+    FrameTimeMs,
+    Fps,
+}
+
+impl AlertMetric {
+    pub const ALL: [AlertMetric; 5] = [
+        AlertMetric::CpuPercent,
+        AlertMetric::MemoryMb,
+        AlertMetric::VramMb,
+        AlertMetric::FrameTimeMs,
+        AlertMetric::Fps,
+    ];
+
+    /// Name used in the settings string
+    pub fn key(self) -> &'static str {
+        match self {
+            AlertMetric::CpuPercent => "cpu_percent",
+            AlertMetric::MemoryMb => "memory_mb",
+            AlertMetric::VramMb => "vram_mb",
+            AlertMetric::FrameTimeMs => "frame_time_ms",
+            AlertMetric::Fps => "fps",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|metric| metric.key() == key)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AlertMetric::CpuPercent => "CPU",
+            AlertMetric::MemoryMb => "Memory",
+            AlertMetric::VramMb => "VRAM",
+            AlertMetric::FrameTimeMs => "Frame Time",
+            AlertMetric::Fps => "FPS",
+        }
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            AlertMetric::CpuPercent => "%",
+            AlertMetric::MemoryMb | AlertMetric::VramMb => " MB",
+            AlertMetric::FrameTimeMs => " ms",
+            AlertMetric::Fps => " FPS",
+        }
+    }
+
+    /// The metric after this one in [`Self::ALL`], wrapping around
+    pub fn next(self) -> Self {
+        let ix = Self::ALL.iter().position(|m| *m == self).unwrap_or(0);
+        Self::ALL[(ix + 1) % Self::ALL.len()]
+    }
+
+    /// Latest reading, or `None` while the metric isn't being reported
+    pub fn read(self, metrics: &PerformanceMetrics) -> Option<f64> {
+        match self {
+            AlertMetric::CpuPercent => metrics.cpu_samples.latest().map(|s| s.value),
+            AlertMetric::MemoryMb => metrics.memory_samples.latest().map(|s| s.value),
+            AlertMetric::VramMb => metrics.vram_samples.latest().map(|s| s.value),
+            AlertMetric::FrameTimeMs => metrics.render.map(|r| r.frame_time_ms as f64),
+            AlertMetric::Fps => metrics.render.map(|_| metrics.current_fps),
+        }
+    }
+
+    pub fn format_value(self, value: f64) -> String {
+        format!("{:.0}{}", value, self.unit())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Above,
+    Below,
+}
+
+impl Comparison {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Comparison::Above => ">",
+            Comparison::Below => "<",
+        }
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            Comparison::Above => Comparison::Below,
+            Comparison::Below => Comparison::Above,
+        }
+    }
+
+    fn breached(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparison::Above => value > threshold,
+            Comparison::Below => value < threshold,
+        }
+    }
+
+    /// Whether `value` is back inside the limit, past the hysteresis band
+    fn recovered(self, value: f64, threshold: f64) -> bool {
+        let band = threshold.abs() * HYSTERESIS;
+        match self {
+            Comparison::Above => value < threshold - band,
+            Comparison::Below => value > threshold + band,
+        }
+    }
+}
+
+/// Fire when `metric` stays beyond `threshold` for `sustained_for`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AlertRule {
+    pub metric: AlertMetric,
+    pub comparison: Comparison,
+    pub threshold: f64,
+    pub sustained_for: Duration,
+}
+
+impl AlertRule {
+    /// e.g. `Memory > 8192 MB for 5s`
+    pub fn describe(&self) -> String {
+        format!(
+            "{} {} {} for {}s",
+            self.metric.label(),
+            self.comparison.symbol(),
+            self.metric.format_value(self.threshold),
+            self.sustained_for.as_secs()
+        )
+    }
+}
+
+/// The settings form, e.g. `memory_mb>8192@5`
+impl fmt::Display for AlertRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}@{}",
+            self.metric.key(),
+            self.comparison.symbol(),
+            self.threshold,
+            self.sustained_for.as_secs()
+        )
+    }
+}
+
+impl FromStr for AlertRule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let (condition, secs) = s.split_once('@').unwrap_or((s, "0"));
+        let (metric, comparison, threshold) = if let Some((m, t)) = condition.split_once('>') {
+            (m, Comparison::Above, t)
+        } else if let Some((m, t)) = condition.split_once('<') {
+            (m, Comparison::Below, t)
+        } else {
+            return Err(anyhow!("missing '>' or '<'"));
+        };
+
+        let metric = AlertMetric::from_key(metric.trim())
+            .ok_or_else(|| anyhow!("unknown metric '{}'", metric.trim()))?;
+        let threshold: f64 = threshold
+            .trim()
+            .parse()
+            .with_context(|| format!("invalid threshold '{}'", threshold.trim()))?;
+        if !threshold.is_finite() {
+            return Err(anyhow!("invalid threshold '{}'", threshold));
+        }
+        let secs: u64 = secs
+            .trim()
+            .parse()
+            .with_context(|| format!("invalid duration '{}'", secs.trim()))?;
+
+        Ok(Self {
+            metric,
+            comparison,
+            threshold,
+            sustained_for: Duration::from_secs(secs),
+        })
+    }
+}
+
+/// Parses rules written by [`rules_to_string`], skipping malformed entries
+pub fn parse_rules(rules: &str) -> Vec<AlertRule> {
+    rules
+        .split(';')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| match entry.parse() {
+            Ok(rule) => Some(rule),
+            Err(err) => {
+                tracing::warn!("Ignoring malformed alert rule '{}': {:#}", entry, err);
+                None
+            }
+        })
+        .collect()
+}
+
+pub fn rules_to_string(rules: &[AlertRule]) -> String {
+    rules
+        .iter()
+        .map(AlertRule::to_string)
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// The `mission_control_alerts` setting, or [`DEFAULT_ALERT_RULES`]
+pub fn configured_rules() -> Vec<AlertRule> {
+    let rules = engine_state::global_config()
+        .get(engine_state::NS_EDITOR, SETTINGS_OWNER, SETTINGS_KEY)
+        .ok()
+        .and_then(|v| v.as_str().ok().map(str::to_owned))
+        .unwrap_or_else(|| DEFAULT_ALERT_RULES.to_owned());
+    parse_rules(&rules)
+}
+
+/// Store `rules` in the editor settings and write them to disk
+pub fn save_rules(rules: &[AlertRule]) {
+    let settings = GlobalSettings::new();
+    if let Err(e) = settings.set(
+        SETTINGS_OWNER,
+        SETTINGS_KEY,
+        ConfigValue::String(rules_to_string(rules)),
+    ) {
+        tracing::warn!("Failed to store alert rules: {e:?}");
+        return;
+    }
+    if let Err(e) = settings.save_all() {
+        tracing::warn!("Failed to save alert rules: {e:?}");
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlertEventKind {
+    Triggered,
+    Recovered,
+}
+
+#[derive(Clone, Debug)]
+pub struct AlertEvent {
+    pub rule: AlertRule,
+    pub kind: AlertEventKind,
+    /// Reading that completed the transition
+    pub value: f64,
+    pub at: DateTime<Local>,
+}
+
+impl AlertEvent {
+    pub fn title(&self) -> String {
+        match self.kind {
+            AlertEventKind::Triggered => format!("{} alert", self.rule.metric.label()),
+            AlertEventKind::Recovered => format!("{} recovered", self.rule.metric.label()),
+        }
+    }
+
+    pub fn message(&self) -> String {
+        let value = self.rule.metric.format_value(self.value);
+        match self.kind {
+            AlertEventKind::Triggered => format!(
+                "{} is {} ({})",
+                self.rule.metric.label(),
+                value,
+                self.rule.describe()
+            ),
+            AlertEventKind::Recovered => format!("{} back to {}", self.rule.metric.label(), value),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RuleState {
+    /// Within limits; `breached_since` is set while a breach is building up
+    Normal { breached_since: Option<Instant> },
+    /// Fired; `recovered_since` is set while the value is back inside the band
+    Firing { recovered_since: Option<Instant> },
+}
+
+impl Default for RuleState {
+    fn default() -> Self {
+        RuleState::Normal {
+            breached_since: None,
+        }
+    }
+}
+
+/// Tracks every rule's state and the recent trigger/recovery events
+pub struct AlertMonitor {
+    rules: Vec<AlertRule>,
+    states: Vec<RuleState>,
+    history: VecDeque<AlertEvent>,
+}
+
+impl AlertMonitor {
+    pub fn new(rules: Vec<AlertRule>) -> Self {
+        Self {
+            states: vec![RuleState::default(); rules.len()],
+            rules,
+            history: VecDeque::new(),
+        }
+    }
+
+    pub fn rules(&self) -> &[AlertRule] {
+        &self.rules
+    }
+
+    /// Replace the rules, forgetting any pending or firing state
+    pub fn set_rules(&mut self, rules: Vec<AlertRule>) {
+        self.states = vec![RuleState::default(); rules.len()];
+        self.rules = rules;
+    }
+
+    pub fn is_firing(&self, rule_ix: usize) -> bool {
+        matches!(self.states.get(rule_ix), Some(RuleState::Firing { .. }))
+    }
+
+    /// Events newest first
+    pub fn history(&self) -> impl Iterator<Item = &AlertEvent> {
+        self.history.iter().rev()
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    /// Advance every rule with the readings from `read` and return the
+    /// events this produced. A rule fires after breaching for its
+    /// `sustained_for` and recovers after being back past the hysteresis
+    /// band for as long; rules whose metric reads `None` restart their wait.
+    pub fn evaluate(
+        &mut self,
+        now: Instant,
+        read: impl Fn(AlertMetric) -> Option<f64>,
+    ) -> Vec<AlertEvent> {
+        let mut events = Vec::new();
+        for (rule, state) in self.rules.iter().zip(&mut self.states) {
+            let Some(value) = read(rule.metric) else {
+                *state = match state {
+                    RuleState::Normal { .. } => RuleState::default(),
+                    RuleState::Firing { .. } => RuleState::Firing {
+                        recovered_since: None,
+                    },
+                };
+                continue;
+            };
+
+            let kind = match state {
+                RuleState::Normal { breached_since } => {
+                    if !rule.comparison.breached(value, rule.threshold) {
+                        *breached_since = None;
+                        continue;
+                    }
+                    let since = *breached_since.get_or_insert(now);
+                    if now.duration_since(since) < rule.sustained_for {
+                        continue;
+                    }
+                    *state = RuleState::Firing {
+                        recovered_since: None,
+                    };
+                    AlertEventKind::Triggered
+                }
+                RuleState::Firing { recovered_since } => {
+                    if !rule.comparison.recovered(value, rule.threshold) {
+                        *recovered_since = None;
+                        continue;
+                    }
+                    let since = *recovered_since.get_or_insert(now);
+                    if now.duration_since(since) < rule.sustained_for {
+                        continue;
+                    }
+                    *state = RuleState::default();
+                    AlertEventKind::Recovered
+                }
+            };
+
+            events.push(AlertEvent {
+                rule: *rule,
+                kind,
+                value,
+                at: Local::now(),
+            });
+        }
+
+        for event in &events {
+            if self.history.len() >= MAX_ALERT_HISTORY {
+                self.history.pop_front();
+            }
+            self.history.push_back(event.clone());
+        }
+        events
+    }
+}
+
+pub type SharedAlertMonitor = SharedState<AlertMonitor>;
+
+/// Create a monitor loaded with the configured rules
+pub fn create_shared_alerts() -> SharedAlertMonitor {
+    SharedState::new(AlertMonitor::new(configured_rules()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(comparison: Comparison, threshold: f64, secs: u64) -> AlertRule {
+        AlertRule {
+            metric: AlertMetric::MemoryMb,
+            comparison,
+            threshold,
+            sustained_for: Duration::from_secs(secs),
+        }
+    }
+
+    /// Feed one reading per second and collect the event kinds
+    fn run(rule: AlertRule, values: &[f64]) -> Vec<(usize, AlertEventKind)> {
+        let mut monitor = AlertMonitor::new(vec![rule]);
+        let start = Instant::now();
+        let mut kinds = Vec::new();
+        for (second, value) in values.iter().enumerate() {
+            let now = start + Duration::from_secs(second as u64);
+            for event in monitor.evaluate(now, |_| Some(*value)) {
+                kinds.push((second, event.kind));
+            }
+        }
+        assert_eq!(monitor.history().count(), kinds.len());
+        kinds
+    }
+
+    #[test]
+    fn test_rules_round_trip_through_settings_string() {
+        let rules = parse_rules(DEFAULT_ALERT_RULES);
+        assert_eq!(
+            rules,
+            [
+                AlertRule {
+                    metric: AlertMetric::MemoryMb,
+                    comparison: Comparison::Above,
+                    threshold: 8192.0,
+                    sustained_for: Duration::from_secs(5),
+                },
+                AlertRule {
+                    metric: AlertMetric::FrameTimeMs,
+                    comparison: Comparison::Above,
+                    threshold: 33.0,
+                    sustained_for: Duration::from_secs(3),
+                },
+            ]
+        );
+        assert_eq!(rules_to_string(&rules), DEFAULT_ALERT_RULES);
+
+        let parsed =
+            parse_rules(" fps < 29.5 ;;bogus>1@1;cpu_percent=90;vram_mb>abc;cpu_percent>90");
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].comparison, Comparison::Below);
+        assert_eq!(parsed[0].threshold, 29.5);
+        assert_eq!(parsed[1].sustained_for, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_alert_fires_only_after_sustained_breach() {
+        // A one-second spike is ignored, three seconds over the limit fire
+        let kinds = run(
+            rule(Comparison::Above, 100.0, 2),
+            &[50.0, 150.0, 50.0, 150.0, 150.0, 150.0, 150.0],
+        );
+        assert_eq!(kinds, [(5, AlertEventKind::Triggered)]);
+    }
+
+    #[test]
+    fn test_hysteresis_suppresses_flapping() {
+        // Values bouncing around the threshold never leave the band, so the
+        // alert fires once and stays firing
+        let kinds = run(
+            rule(Comparison::Above, 100.0, 0),
+            &[101.0, 99.0, 101.0, 97.0, 102.0, 96.0],
+        );
+        assert_eq!(kinds, [(0, AlertEventKind::Triggered)]);
+
+        let kinds = run(
+            rule(Comparison::Below, 30.0, 1),
+            &[20.0, 20.0, 40.0, 20.0, 40.0, 40.0],
+        );
+        assert_eq!(
+            kinds,
+            [
+                (1, AlertEventKind::Triggered),
+                (5, AlertEventKind::Recovered)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_metric_restarts_wait() {
+        let mut monitor = AlertMonitor::new(vec![rule(Comparison::Above, 100.0, 2)]);
+        let start = Instant::now();
+        let readings = [Some(150.0), Some(150.0), None, Some(150.0), Some(150.0)];
+        for (second, reading) in readings.into_iter().enumerate() {
+            let now = start + Duration::from_secs(second as u64);
+            assert!(monitor.evaluate(now, |_| reading).is_empty());
+        }
+        assert!(!monitor.is_firing(0));
+    }
+}
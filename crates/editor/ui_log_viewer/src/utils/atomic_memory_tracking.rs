@@ -1,8 +1,11 @@
 //! Lock-free atomic memory tracking - zero overhead, no locks
 //!
 //! Uses atomic counters for each category to track allocations without any locking.
+//! Categories registered at runtime get their own counters; only registering
+//! and naming them takes a lock.
 
-use crate::utils::memory_tracking::MemoryCategory;
+use crate::utils::memory_tracking::{CategoryUsage, MemoryCategory, MemoryCategoryId};
+use parking_lot::RwLock;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// Allocation size bucket for detailed tracking
@@ -46,22 +49,23 @@ impl SizeBucket {
 pub struct AllocationEntry {
     pub name: String,
     pub size: usize,
-    pub category: crate::utils::memory_tracking::MemoryCategory,
+    /// High-water mark; `None` for size bucket entries
+    pub peak: Option<usize>,
+    pub category: MemoryCategoryId,
     pub bucket: SizeBucket,
 }
 
-/// Lock-free atomic memory counters (one per category)
+/// Most categories the counters can hold, built-in ones included
+pub const MAX_MEMORY_CATEGORIES: usize = 64;
+
+/// Lock-free atomic memory counters (current and peak bytes per category)
 pub struct AtomicMemoryCounters {
-    // Per-category counters
-    unknown: AtomicUsize,
-    engine: AtomicUsize,
-    renderer: AtomicUsize,
-    ui: AtomicUsize,
-    physics: AtomicUsize,
-    audio: AtomicUsize,
-    assets: AtomicUsize,
-    scripts: AtomicUsize,
-    network: AtomicUsize,
+    // Per-category counters, indexed by `MemoryCategoryId`
+    current: [AtomicUsize; MAX_MEMORY_CATEGORIES],
+    peak: [AtomicUsize; MAX_MEMORY_CATEGORIES],
+    /// Names of registered categories, in id order after the built-in ones.
+    /// Only touched when registering or reading names, never while recording.
+    custom_names: RwLock<Vec<&'static str>>,
 
     // Per-size bucket counters
     tiny_count: AtomicUsize,
@@ -77,18 +81,18 @@ pub struct AtomicMemoryCounters {
     huge_bytes: AtomicUsize,
 }
 
+impl Default for AtomicMemoryCounters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AtomicMemoryCounters {
     pub const fn new() -> Self {
         Self {
-            unknown: AtomicUsize::new(0),
-            engine: AtomicUsize::new(0),
-            renderer: AtomicUsize::new(0),
-            ui: AtomicUsize::new(0),
-            physics: AtomicUsize::new(0),
-            audio: AtomicUsize::new(0),
-            assets: AtomicUsize::new(0),
-            scripts: AtomicUsize::new(0),
-            network: AtomicUsize::new(0),
+            current: [const { AtomicUsize::new(0) }; MAX_MEMORY_CATEGORIES],
+            peak: [const { AtomicUsize::new(0) }; MAX_MEMORY_CATEGORIES],
+            custom_names: RwLock::new(Vec::new()),
             tiny_count: AtomicUsize::new(0),
             small_count: AtomicUsize::new(0),
             medium_count: AtomicUsize::new(0),
@@ -102,12 +106,60 @@ impl AtomicMemoryCounters {
         }
     }
 
+    /// Add a named category, or return the existing one with that name.
+    /// Falls back to [`MemoryCategoryId::UNKNOWN`] once
+    /// [`MAX_MEMORY_CATEGORIES`] are in use.
+    pub fn register_category(&self, name: &str) -> MemoryCategoryId {
+        if let Some(category) = MemoryCategory::ALL.iter().find(|c| c.as_str() == name) {
+            return (*category).into();
+        }
+
+        let mut names = self.custom_names.write();
+        if let Some(ix) = names.iter().position(|existing| *existing == name) {
+            return MemoryCategoryId(MemoryCategory::COUNT + ix);
+        }
+        if MemoryCategory::COUNT + names.len() >= MAX_MEMORY_CATEGORIES {
+            tracing::warn!(
+                "Memory category limit reached; tracking '{}' as Unknown",
+                name
+            );
+            return MemoryCategoryId::UNKNOWN;
+        }
+        // Categories live for the whole process, so leaking the name is fine
+        names.push(Box::leak(name.to_owned().into_boxed_str()));
+        MemoryCategoryId(MemoryCategory::COUNT + names.len() - 1)
+    }
+
+    pub fn category_name(&self, category: MemoryCategoryId) -> &'static str {
+        match category.builtin() {
+            Some(builtin) => builtin.as_str(),
+            None => self
+                .custom_names
+                .read()
+                .get(category.0 - MemoryCategory::COUNT)
+                .copied()
+                .unwrap_or("Unknown"),
+        }
+    }
+
+    /// Number of built-in plus registered categories
+    pub fn category_count(&self) -> usize {
+        MemoryCategory::COUNT + self.custom_names.read().len()
+    }
+
     /// Record allocation (lock-free, atomic)
     #[inline]
-    pub fn record_alloc(&self, size: usize, category: MemoryCategory) {
-        // Update category counter
-        let counter = self.get_counter(category);
-        counter.fetch_add(size, Ordering::Relaxed);
+    pub fn record_alloc(&self, size: usize, category: MemoryCategoryId) {
+        // Update category counters
+        let ix = Self::slot(category);
+        let now = self.current[ix]
+            .fetch_add(size, Ordering::Relaxed)
+            .wrapping_add(size);
+        // Memory freed under another category than it was allocated in can
+        // take a counter "below zero"; don't let that wrap into the peak
+        if now <= isize::MAX as usize {
+            self.peak[ix].fetch_max(now, Ordering::Relaxed);
+        }
 
         // Update size bucket counters
         let bucket = SizeBucket::from_size(size);
@@ -116,10 +168,9 @@ impl AtomicMemoryCounters {
 
     /// Record deallocation (lock-free, atomic)
     #[inline]
-    pub fn record_dealloc(&self, size: usize, category: MemoryCategory) {
+    pub fn record_dealloc(&self, size: usize, category: MemoryCategoryId) {
         // Update category counter
-        let counter = self.get_counter(category);
-        counter.fetch_sub(size, Ordering::Relaxed);
+        self.current[Self::slot(category)].fetch_sub(size, Ordering::Relaxed);
 
         // Update size bucket counters
         let bucket = SizeBucket::from_size(size);
@@ -180,92 +231,67 @@ impl AtomicMemoryCounters {
         }
     }
 
-    /// Get the atomic counter for a category
+    /// Counter index for `category`; ids from elsewhere count as Unknown
     #[inline]
-    fn get_counter(&self, category: MemoryCategory) -> &AtomicUsize {
-        match category {
-            MemoryCategory::Unknown => &self.unknown,
-            MemoryCategory::Engine => &self.engine,
-            MemoryCategory::Renderer => &self.renderer,
-            MemoryCategory::UI => &self.ui,
-            MemoryCategory::Physics => &self.physics,
-            MemoryCategory::Audio => &self.audio,
-            MemoryCategory::Assets => &self.assets,
-            MemoryCategory::Scripts => &self.scripts,
-            MemoryCategory::Network => &self.network,
+    fn slot(category: MemoryCategoryId) -> usize {
+        if category.0 < MAX_MEMORY_CATEGORIES {
+            category.0
+        } else {
+            MemoryCategoryId::UNKNOWN.0
         }
     }
 
     /// Get current value for a category
-    pub fn get(&self, category: MemoryCategory) -> usize {
-        self.get_counter(category).load(Ordering::Relaxed)
+    pub fn get(&self, category: impl Into<MemoryCategoryId>) -> usize {
+        not_negative(self.current[Self::slot(category.into())].load(Ordering::Relaxed))
+    }
+
+    /// Highest value a category has reached
+    pub fn peak(&self, category: impl Into<MemoryCategoryId>) -> usize {
+        self.peak[Self::slot(category.into())].load(Ordering::Relaxed)
     }
 
     /// Get total current usage across all categories
     pub fn total(&self) -> usize {
-        self.unknown.load(Ordering::Relaxed)
-            + self.engine.load(Ordering::Relaxed)
-            + self.renderer.load(Ordering::Relaxed)
-            + self.ui.load(Ordering::Relaxed)
-            + self.physics.load(Ordering::Relaxed)
-            + self.audio.load(Ordering::Relaxed)
-            + self.assets.load(Ordering::Relaxed)
-            + self.scripts.load(Ordering::Relaxed)
-            + self.network.load(Ordering::Relaxed)
+        not_negative(
+            self.current
+                .iter()
+                .fold(0usize, |sum, c| sum.wrapping_add(c.load(Ordering::Relaxed))),
+        )
     }
 
-    /// Get snapshot of all categories (for UI rendering)
-    pub fn snapshot(&self) -> Vec<(MemoryCategory, usize)> {
-        let mut result = Vec::with_capacity(9);
-
-        for category in [
-            MemoryCategory::Unknown,
-            MemoryCategory::Engine,
-            MemoryCategory::Renderer,
-            MemoryCategory::UI,
-            MemoryCategory::Physics,
-            MemoryCategory::Audio,
-            MemoryCategory::Assets,
-            MemoryCategory::Scripts,
-            MemoryCategory::Network,
-        ] {
-            let value = self.get(category);
-            if value > 0 {
-                result.push((category, value));
-            }
-        }
+    /// Current and peak usage of every category that has seen allocations,
+    /// largest current usage first (for UI rendering)
+    pub fn snapshot(&self) -> Vec<CategoryUsage> {
+        let mut result: Vec<CategoryUsage> = (0..self.category_count())
+            .map(MemoryCategoryId)
+            .map(|id| CategoryUsage {
+                id,
+                name: self.category_name(id),
+                current: self.get(id),
+                peak: self.peak(id),
+            })
+            .filter(|usage| usage.peak > 0)
+            .collect();
 
         // Sort by size descending
-        result.sort_by(|a, b| b.1.cmp(&a.1));
+        result.sort_by(|a, b| b.current.cmp(&a.current).then(a.id.cmp(&b.id)));
         result
     }
 
     /// Get detailed allocation entries for virtual list display
     pub fn get_all_entries(&self) -> Vec<AllocationEntry> {
-        let mut entries = Vec::with_capacity(14); // 9 categories + 5 size buckets
-
-        // Add category entries
-        for category in [
-            MemoryCategory::Unknown,
-            MemoryCategory::Engine,
-            MemoryCategory::Renderer,
-            MemoryCategory::UI,
-            MemoryCategory::Physics,
-            MemoryCategory::Audio,
-            MemoryCategory::Assets,
-            MemoryCategory::Scripts,
-            MemoryCategory::Network,
-        ] {
-            let size = self.get(category);
-            if size > 0 {
-                entries.push(AllocationEntry {
-                    name: category.as_str().to_string(),
-                    size,
-                    category,
-                    bucket: SizeBucket::Tiny, // Not applicable for category entries
-                });
-            }
-        }
+        let mut entries: Vec<AllocationEntry> = self
+            .snapshot()
+            .into_iter()
+            .map(|usage| AllocationEntry {
+                name: usage.name.to_string(),
+                size: usage.current,
+                peak: Some(usage.peak),
+                category: usage.id,
+                bucket: SizeBucket::Tiny, // Not applicable for category entries
+            })
+            .collect();
 
         // Add size bucket entries
         let tiny_bytes = self.tiny_bytes.load(Ordering::Relaxed);
@@ -274,7 +300,8 @@ impl AtomicMemoryCounters {
             entries.push(AllocationEntry {
                 name: format!("{} ({} allocs)", SizeBucket::Tiny.name(), tiny_count),
                 size: tiny_bytes,
-                category: MemoryCategory::Unknown,
+                peak: None,
+                category: MemoryCategoryId::UNKNOWN,
                 bucket: SizeBucket::Tiny,
             });
         }
@@ -285,7 +312,8 @@ impl AtomicMemoryCounters {
             entries.push(AllocationEntry {
                 name: format!("{} ({} allocs)", SizeBucket::Small.name(), small_count),
                 size: small_bytes,
-                category: MemoryCategory::Unknown,
+                peak: None,
+                category: MemoryCategoryId::UNKNOWN,
                 bucket: SizeBucket::Small,
             });
         }
@@ -296,7 +324,8 @@ impl AtomicMemoryCounters {
             entries.push(AllocationEntry {
                 name: format!("{} ({} allocs)", SizeBucket::Medium.name(), medium_count),
                 size: medium_bytes,
-                category: MemoryCategory::Unknown,
+                peak: None,
+                category: MemoryCategoryId::UNKNOWN,
                 bucket: SizeBucket::Medium,
             });
         }
@@ -307,7 +336,8 @@ impl AtomicMemoryCounters {
             entries.push(AllocationEntry {
                 name: format!("{} ({} allocs)", SizeBucket::Large.name(), large_count),
                 size: large_bytes,
-                category: MemoryCategory::Unknown,
+                peak: None,
+                category: MemoryCategoryId::UNKNOWN,
                 bucket: SizeBucket::Large,
             });
         }
@@ -318,7 +348,8 @@ impl AtomicMemoryCounters {
             entries.push(AllocationEntry {
                 name: format!("{} ({} allocs)", SizeBucket::Huge.name(), huge_count),
                 size: huge_bytes,
-                category: MemoryCategory::Unknown,
+                peak: None,
+                category: MemoryCategoryId::UNKNOWN,
                 bucket: SizeBucket::Huge,
             });
         }
@@ -329,5 +360,127 @@ impl AtomicMemoryCounters {
     }
 }
 
+/// A counter that went "below zero" (see [`AtomicMemoryCounters::record_alloc`])
+/// reads as empty
+fn not_negative(bytes: usize) -> usize {
+    if bytes > isize::MAX as usize {
+        0
+    } else {
+        bytes
+    }
+}
+
 /// Global atomic counters instance
 pub static ATOMIC_MEMORY_COUNTERS: AtomicMemoryCounters = AtomicMemoryCounters::new();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::tracking_allocator::{current_category, MemoryCategoryGuard};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_register_category_is_idempotent() {
+        let counters = AtomicMemoryCounters::new();
+        let foliage = counters.register_category("Foliage");
+        let plugin = counters.register_category("MyPlugin");
+
+        assert_eq!(foliage.index(), MemoryCategory::COUNT);
+        assert_eq!(counters.register_category("Foliage"), foliage);
+        assert_ne!(foliage, plugin);
+        assert_eq!(counters.category_name(plugin), "MyPlugin");
+        assert_eq!(
+            counters.register_category("Renderer"),
+            MemoryCategory::Renderer.into()
+        );
+        assert_eq!(counters.category_count(), MemoryCategory::COUNT + 2);
+    }
+
+    #[test]
+    fn test_register_category_falls_back_to_unknown_when_full() {
+        let counters = AtomicMemoryCounters::new();
+        for i in MemoryCategory::COUNT..MAX_MEMORY_CATEGORIES {
+            assert_eq!(counters.register_category(&format!("cat{i}")).index(), i);
+        }
+        assert_eq!(
+            counters.register_category("one too many"),
+            MemoryCategoryId::UNKNOWN
+        );
+    }
+
+    #[test]
+    fn test_peak_tracks_high_water_mark() {
+        let counters = AtomicMemoryCounters::new();
+        let audio = MemoryCategoryId::from(MemoryCategory::Audio);
+
+        counters.record_alloc(1000, audio);
+        counters.record_alloc(500, audio);
+        counters.record_dealloc(1000, audio);
+        counters.record_alloc(200, audio);
+
+        assert_eq!(counters.get(audio), 700);
+        assert_eq!(counters.peak(audio), 1500);
+
+        // Freed under a category it wasn't allocated in: reads as empty
+        // instead of wrapping, and leaves the peak alone
+        counters.record_dealloc(300, MemoryCategoryId::UNKNOWN);
+        assert_eq!(counters.get(MemoryCategoryId::UNKNOWN), 0);
+        assert_eq!(counters.peak(MemoryCategoryId::UNKNOWN), 0);
+        assert_eq!(counters.total(), 400);
+    }
+
+    #[test]
+    fn test_parallel_guards_keep_counters_consistent() {
+        const THREADS: usize = 8;
+        const ROUNDS: usize = 2_000;
+
+        let counters = Arc::new(AtomicMemoryCounters::new());
+        let categories = [
+            counters.register_category("Gameplay"),
+            counters.register_category("Plugin"),
+            MemoryCategory::Physics.into(),
+        ];
+
+        let handles: Vec<_> = (0..THREADS)
+            .map(|thread| {
+                let counters = Arc::clone(&counters);
+                std::thread::spawn(move || {
+                    let category = categories[thread % categories.len()];
+                    let _guard = MemoryCategoryGuard::new(category);
+                    let size = 64 * (thread + 1);
+                    for round in 0..ROUNDS {
+                        counters.record_alloc(size, current_category());
+                        // Free every other block so each thread ends half full
+                        if round % 2 == 1 {
+                            counters.record_dealloc(size, current_category());
+                        }
+                    }
+                    // Nested guard on a different category, fully released
+                    let _inner = MemoryCategoryGuard::new(MemoryCategory::Scripts);
+                    counters.record_alloc(10, current_category());
+                    counters.record_dealloc(10, current_category());
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let mut expected = [0usize; 3];
+        for thread in 0..THREADS {
+            expected[thread % categories.len()] += 64 * (thread + 1) * ROUNDS / 2;
+        }
+        for (category, expected) in categories.iter().zip(expected) {
+            assert_eq!(counters.get(*category), expected);
+            assert!(counters.peak(*category) >= expected);
+        }
+        assert_eq!(counters.get(MemoryCategory::Scripts), 0);
+        assert!(counters.peak(MemoryCategory::Scripts) >= 10);
+        assert_eq!(counters.total(), expected.iter().sum::<usize>());
+        assert_eq!(current_category(), MemoryCategoryId::UNKNOWN);
+
+        let snapshot = counters.snapshot();
+        assert_eq!(snapshot.len(), 4);
+        assert!(snapshot.windows(2).all(|w| w[0].current >= w[1].current));
+    }
+}
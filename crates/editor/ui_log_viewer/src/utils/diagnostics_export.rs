@@ -0,0 +1,287 @@
+//! Support bundle export for Mission Control
+//!
+//! Everything is captured into a [`DiagnosticsSnapshot`] on the UI thread and
+//! then serialized and zipped by [`write_bundle`] on a background task.
+
+use crate::utils::{
+    mem_details::MemorySnapshot, memory_tracking::CategoryUsage,
+    performance_metrics::PerformanceMetrics, system_info::SystemInfo,
+};
+use anyhow::Context as _;
+use chrono::{DateTime, Local};
+use serde_json::json;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+/// Log lines written between progress reports
+const LOG_CHUNK_LINES: usize = 10_000;
+
+pub(crate) struct DiagnosticsSnapshot {
+    pub(crate) engine_version: &'static str,
+    pub(crate) created_at: DateTime<Local>,
+    pub(crate) log_lines: Vec<String>,
+    /// Whether `log_lines` only holds the lines passing the logs panel filter
+    pub(crate) logs_filtered: bool,
+    pub(crate) metrics_csv: String,
+    pub(crate) system_info: serde_json::Value,
+    pub(crate) memory: serde_json::Value,
+}
+
+impl DiagnosticsSnapshot {
+    /// e.g. `pulsar-diagnostics-v0.2.42-20250101-120000.zip`
+    pub(crate) fn file_name(&self) -> String {
+        format!(
+            "pulsar-diagnostics-v{}-{}.zip",
+            self.engine_version,
+            self.created_at.format("%Y%m%d-%H%M%S")
+        )
+    }
+
+    fn manifest(&self) -> serde_json::Value {
+        json!({
+            "engine_version": self.engine_version,
+            "created_at": self.created_at.to_rfc3339(),
+            "log_lines": self.log_lines.len(),
+            "logs_filtered": self.logs_filtered,
+        })
+    }
+}
+
+/// Metric histories as CSV, one row per sample, oldest first
+pub(crate) fn metrics_csv(metrics: &PerformanceMetrics) -> String {
+    let columns: [(&str, Vec<f64>); 10] = [
+        (
+            "timestamp",
+            metrics.cpu_samples.iter().map(|s| s.timestamp).collect(),
+        ),
+        (
+            "cpu_percent",
+            metrics.cpu_samples.iter().map(|s| s.value).collect(),
+        ),
+        (
+            "memory_mb",
+            metrics.memory_samples.iter().map(|s| s.value).collect(),
+        ),
+        (
+            "vram_used_mb",
+            metrics.vram_samples.iter().map(|s| s.value).collect(),
+        ),
+        ("fps", metrics.fps_history.iter().map(|p| p.fps).collect()),
+        (
+            "frame_time_ms",
+            metrics
+                .frame_time_history
+                .iter()
+                .map(|p| p.frame_time_ms)
+                .collect(),
+        ),
+        (
+            "net_rx_kbps",
+            metrics.net_rx_history.iter().map(|p| p.kbps).collect(),
+        ),
+        (
+            "net_tx_kbps",
+            metrics.net_tx_history.iter().map(|p| p.kbps).collect(),
+        ),
+        (
+            "disk_read_kbps",
+            metrics.disk_read_history.iter().map(|p| p.kbps).collect(),
+        ),
+        (
+            "disk_write_kbps",
+            metrics.disk_write_history.iter().map(|p| p.kbps).collect(),
+        ),
+    ];
+    csv_table(&columns)
+}
+
+/// Columns of unequal length are aligned on their newest sample, leaving
+/// older cells empty
+fn csv_table(columns: &[(&str, Vec<f64>)]) -> String {
+    let rows = columns
+        .iter()
+        .map(|(_, values)| values.len())
+        .max()
+        .unwrap_or(0);
+
+    let mut csv = String::from("sample");
+    for (name, _) in columns {
+        csv.push(',');
+        csv.push_str(name);
+    }
+    csv.push('\n');
+
+    for row in 0..rows {
+        csv.push_str(&row.to_string());
+        for (_, values) in columns {
+            csv.push(',');
+            if let Some(value) = (row + values.len())
+                .checked_sub(rows)
+                .and_then(|ix| values.get(ix))
+            {
+                csv.push_str(&format!("{:.3}", value));
+            }
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+pub(crate) fn system_info_json(info: &SystemInfo) -> serde_json::Value {
+    json!({
+        "os_name": info.os_name,
+        "os_version": info.os_version,
+        "kernel_version": info.kernel_version,
+        "host_name": info.host_name,
+        "cpu_brand": info.cpu_brand,
+        "cpu_vendor": info.cpu_vendor,
+        "cpu_cores": info.cpu_cores,
+        "cpu_frequency_mhz": info.cpu_frequency,
+        "total_memory_bytes": info.total_memory,
+        "total_swap_bytes": info.total_swap,
+        "gpu_name": info.gpu_name,
+        "gpu_vendor": info.gpu_vendor,
+        "gpu_driver_version": info.gpu_driver_version,
+        "gpu_vram_total_mb": info.gpu_vram_total_mb,
+        "uptime_secs": info.uptime,
+    })
+}
+
+/// System memory state plus the engine's tracked allocations per category
+pub(crate) fn memory_json(
+    system: &MemorySnapshot,
+    categories: &[CategoryUsage],
+    tracked_total: usize,
+) -> serde_json::Value {
+    let categories: serde_json::Map<String, serde_json::Value> = categories
+        .iter()
+        .map(|usage| {
+            (
+                usage.name.to_string(),
+                json!({ "current_bytes": usage.current, "peak_bytes": usage.peak }),
+            )
+        })
+        .collect();
+
+    json!({
+        "system": {
+            "total_mb": system.total_mb,
+            "available_mb": system.available_mb,
+            "in_use_mb": system.in_use_mb,
+            "cached_mb": system.cached_mb,
+            "committed_mb": system.committed_mb,
+            "committed_limit_mb": system.committed_limit_mb,
+            "paged_pool_mb": system.paged_pool_mb,
+            "non_paged_pool_mb": system.non_paged_pool_mb,
+            "swap_total_mb": system.swap_total_mb,
+            "swap_used_mb": system.swap_used_mb,
+        },
+        "tracked_total_bytes": tracked_total,
+        "tracked_categories": categories,
+    })
+}
+
+/// Write `snapshot` as a zip at `path`, reporting progress from 0.0 to 1.0
+pub(crate) fn write_bundle(
+    path: &Path,
+    snapshot: &DiagnosticsSnapshot,
+    mut progress: impl FnMut(f32),
+) -> anyhow::Result<()> {
+    let file =
+        File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    // Logs dominate the bundle, so they get most of the progress range
+    zip.start_file("logs.txt", options)?;
+    let chunks = snapshot.log_lines.len().div_ceil(LOG_CHUNK_LINES).max(1);
+    for (ix, chunk) in snapshot.log_lines.chunks(LOG_CHUNK_LINES).enumerate() {
+        for line in chunk {
+            zip.write_all(line.as_bytes())?;
+            zip.write_all(b"\n")?;
+        }
+        progress(0.8 * (ix + 1) as f32 / chunks as f32);
+    }
+
+    let files = [
+        ("metrics.csv", snapshot.metrics_csv.clone()),
+        (
+            "system_info.json",
+            serde_json::to_string_pretty(&snapshot.system_info)?,
+        ),
+        (
+            "memory.json",
+            serde_json::to_string_pretty(&snapshot.memory)?,
+        ),
+        (
+            "manifest.json",
+            serde_json::to_string_pretty(&snapshot.manifest())?,
+        ),
+    ];
+    for (ix, (name, contents)) in files.iter().enumerate() {
+        zip.start_file(*name, options)?;
+        zip.write_all(contents.as_bytes())?;
+        progress(0.8 + 0.2 * (ix + 1) as f32 / files.len() as f32);
+    }
+
+    zip.finish()?.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_csv_aligns_columns_on_newest_sample() {
+        let csv = csv_table(&[("cpu", vec![1.0, 2.0, 3.0]), ("fps", vec![60.0])]);
+        assert_eq!(csv, "sample,cpu,fps\n0,1.000,\n1,2.000,\n2,3.000,60.000\n");
+        assert_eq!(csv_table(&[("cpu", Vec::new())]), "sample,cpu\n");
+    }
+
+    #[test]
+    fn test_bundle_contains_every_file() {
+        let snapshot = DiagnosticsSnapshot {
+            engine_version: "1.2.3",
+            created_at: Local::now(),
+            log_lines: (0..25_000).map(|i| format!("line {}", i)).collect(),
+            logs_filtered: true,
+            metrics_csv: "sample,cpu\n0,1.000\n".to_string(),
+            system_info: json!({ "os_name": "TestOS" }),
+            memory: memory_json(&MemorySnapshot::default(), &[], 0),
+        };
+        assert!(snapshot
+            .file_name()
+            .starts_with("pulsar-diagnostics-v1.2.3-"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(snapshot.file_name());
+        let mut reported = Vec::new();
+        write_bundle(&path, &snapshot, |p| reported.push(p)).unwrap();
+        assert_eq!(reported.last(), Some(&1.0));
+        assert!(reported.windows(2).all(|w| w[0] <= w[1]));
+
+        let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut read = |name: &str| {
+            let mut contents = String::new();
+            archive
+                .by_name(name)
+                .unwrap()
+                .read_to_string(&mut contents)
+                .unwrap();
+            contents
+        };
+        let logs = read("logs.txt");
+        assert_eq!(logs.lines().count(), 25_000);
+        assert_eq!(logs.lines().last(), Some("line 24999"));
+        assert_eq!(read("metrics.csv"), snapshot.metrics_csv);
+        assert!(read("system_info.json").contains("TestOS"));
+        assert!(read("manifest.json").contains("\"logs_filtered\": true"));
+        assert!(read("memory.json").contains("tracked_total_bytes"));
+    }
+}
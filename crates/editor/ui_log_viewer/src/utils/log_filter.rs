@@ -0,0 +1,312 @@
+//! Log line parsing and the filters applied by the logs panel
+
+use regex::{Regex, RegexBuilder};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+    Unknown,
+}
+
+impl LogLevel {
+    /// Levels that can be toggled in the filter toolbar, most severe first
+    pub(crate) const FILTERABLE: [LogLevel; 5] = [
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+        LogLevel::Trace,
+    ];
+
+    fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "ERROR" => Some(LogLevel::Error),
+            "WARN" => Some(LogLevel::Warn),
+            "INFO" => Some(LogLevel::Info),
+            "DEBUG" => Some(LogLevel::Debug),
+            "TRACE" => Some(LogLevel::Trace),
+            _ => None,
+        }
+    }
+
+    /// Guess the level of a line that has no level column
+    fn from_text(line: &str) -> Self {
+        let upper = line.to_ascii_uppercase();
+        if upper.contains("ERROR") || upper.contains(" ERR ") || upper.starts_with("ERR") {
+            LogLevel::Error
+        } else if upper.contains("WARN") {
+            LogLevel::Warn
+        } else if upper.contains("INFO") {
+            LogLevel::Info
+        } else if upper.contains("DEBUG") {
+            LogLevel::Debug
+        } else if upper.contains("TRACE") {
+            LogLevel::Trace
+        } else {
+            LogLevel::Unknown
+        }
+    }
+
+    fn bit(self) -> u8 {
+        match self {
+            LogLevel::Error => 1 << 0,
+            LogLevel::Warn => 1 << 1,
+            LogLevel::Info => 1 << 2,
+            LogLevel::Debug => 1 << 3,
+            LogLevel::Trace => 1 << 4,
+            LogLevel::Unknown => 0,
+        }
+    }
+}
+
+/// Level and target of a tracing line, e.g.
+/// `2025-01-01 12:00:00.000 INFO engine_backend::scene: loaded` or the file
+/// log's `2025-01-01T12:00:00Z  INFO ThreadId(01) engine_backend::scene: loaded`
+pub(crate) fn parse_line(line: &str) -> (LogLevel, Option<String>) {
+    let mut tokens = line.split_whitespace().take(6);
+    let Some(level) = tokens.by_ref().find_map(LogLevel::from_token) else {
+        return (LogLevel::from_text(line), None);
+    };
+
+    let target = tokens
+        .find(|token| !token.starts_with("ThreadId("))
+        .and_then(|token| token.strip_suffix(':'))
+        .filter(|target| !target.is_empty())
+        .map(String::from);
+
+    (level, target)
+}
+
+/// How the search box text is matched against log lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum SearchMode {
+    #[default]
+    Substring,
+    Regex,
+}
+
+/// Severity, target and text filters for the logs panel
+pub(crate) struct LogFilter {
+    levels: u8,
+    target_prefix: String,
+    /// Only show lines from this source
+    source: Option<String>,
+    query: String,
+    /// `query` lowercased, for case-insensitive substring matching
+    needle: String,
+    mode: SearchMode,
+    regex: Option<Regex>,
+    regex_error: Option<String>,
+}
+
+impl Default for LogFilter {
+    fn default() -> Self {
+        Self {
+            levels: Self::ALL_LEVELS,
+            target_prefix: String::new(),
+            source: None,
+            query: String::new(),
+            needle: String::new(),
+            mode: SearchMode::default(),
+            regex: None,
+            regex_error: None,
+        }
+    }
+}
+
+impl LogFilter {
+    const ALL_LEVELS: u8 = 0b1_1111;
+
+    pub(crate) fn is_active(&self) -> bool {
+        self.levels != Self::ALL_LEVELS
+            || !self.target_prefix.is_empty()
+            || self.source.is_some()
+            || self.text_active()
+    }
+
+    fn text_active(&self) -> bool {
+        match self.mode {
+            SearchMode::Substring => !self.query.is_empty(),
+            SearchMode::Regex => self.regex.is_some(),
+        }
+    }
+
+    pub(crate) fn level_enabled(&self, level: LogLevel) -> bool {
+        self.levels & level.bit() != 0
+    }
+
+    pub(crate) fn all_levels_enabled(&self) -> bool {
+        self.levels == Self::ALL_LEVELS
+    }
+
+    pub(crate) fn toggle_level(&mut self, level: LogLevel) {
+        self.levels ^= level.bit();
+    }
+
+    pub(crate) fn enable_all_levels(&mut self) {
+        self.levels = Self::ALL_LEVELS;
+    }
+
+    pub(crate) fn target_prefix(&self) -> &str {
+        &self.target_prefix
+    }
+
+    /// Returns whether the prefix changed
+    pub(crate) fn set_target_prefix(&mut self, prefix: &str) -> bool {
+        let prefix = prefix.trim();
+        if self.target_prefix == prefix {
+            return false;
+        }
+        self.target_prefix = prefix.to_string();
+        true
+    }
+
+    pub(crate) fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Returns whether the source changed
+    pub(crate) fn set_source(&mut self, source: Option<&str>) -> bool {
+        if self.source.as_deref() == source {
+            return false;
+        }
+        self.source = source.map(String::from);
+        true
+    }
+
+    pub(crate) fn matches_source(&self, source: &str) -> bool {
+        self.source.as_deref().is_none_or(|wanted| wanted == source)
+    }
+
+    pub(crate) fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub(crate) fn mode(&self) -> SearchMode {
+        self.mode
+    }
+
+    /// Why the regex in the search box could not be used, if it can't
+    pub(crate) fn regex_error(&self) -> Option<&str> {
+        self.regex_error.as_deref()
+    }
+
+    /// Returns whether the text filter changed. An invalid regex sets
+    /// [`Self::regex_error`] and filters no text rather than hiding every line.
+    pub(crate) fn set_query(&mut self, query: &str, mode: SearchMode) -> bool {
+        let query = query.trim();
+        if self.query == query && self.mode == mode {
+            return false;
+        }
+        self.query = query.to_string();
+        self.needle = query.to_ascii_lowercase();
+        self.mode = mode;
+        self.regex = None;
+        self.regex_error = None;
+
+        if mode == SearchMode::Regex && !query.is_empty() {
+            match RegexBuilder::new(query).case_insensitive(true).build() {
+                Ok(regex) => self.regex = Some(regex),
+                Err(err) => self.regex_error = Some(err.to_string()),
+            }
+        }
+        true
+    }
+
+    /// Lines without a level only pass while every severity is shown, and
+    /// lines without a target only while no target prefix is set
+    pub(crate) fn matches(&self, level: LogLevel, target: Option<&str>, text: &str) -> bool {
+        if !self.all_levels_enabled() && !self.level_enabled(level) {
+            return false;
+        }
+
+        if !self.target_prefix.is_empty()
+            && !target.is_some_and(|target| target.starts_with(&self.target_prefix))
+        {
+            return false;
+        }
+
+        match self.mode {
+            SearchMode::Substring if !self.needle.is_empty() => {
+                text.to_ascii_lowercase().contains(&self.needle)
+            }
+            SearchMode::Regex => self.regex.as_ref().is_none_or(|regex| regex.is_match(text)),
+            SearchMode::Substring => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_live_and_file_lines() {
+        assert_eq!(
+            parse_line("2025-01-01 12:00:00.000 WARN engine_backend::scene: slow frame"),
+            (LogLevel::Warn, Some("engine_backend::scene".to_string()))
+        );
+        assert_eq!(
+            parse_line("2025-01-01T12:00:00.000Z  INFO ThreadId(01) ui_core: ready"),
+            (LogLevel::Info, Some("ui_core".to_string()))
+        );
+        assert_eq!(
+            parse_line("thread panicked: ERROR"),
+            (LogLevel::Error, None)
+        );
+        assert_eq!(
+            parse_line("   at src/main.rs:10"),
+            (LogLevel::Unknown, None)
+        );
+    }
+
+    #[test]
+    fn test_severity_and_target_filters() {
+        let mut filter = LogFilter::default();
+        assert!(!filter.is_active());
+        assert!(filter.matches(LogLevel::Unknown, None, "continuation"));
+
+        filter.toggle_level(LogLevel::Info);
+        filter.toggle_level(LogLevel::Debug);
+        assert!(filter.is_active());
+        assert!(filter.matches(LogLevel::Error, None, "boom"));
+        assert!(!filter.matches(LogLevel::Info, None, "hello"));
+        assert!(!filter.matches(LogLevel::Unknown, None, "continuation"));
+
+        filter.enable_all_levels();
+        assert!(filter.set_target_prefix("engine_backend"));
+        assert!(filter.matches(LogLevel::Info, Some("engine_backend::scene"), "x"));
+        assert!(!filter.matches(LogLevel::Info, Some("ui_core"), "x"));
+        assert!(!filter.matches(LogLevel::Info, None, "x"));
+    }
+
+    #[test]
+    fn test_substring_and_regex_search() {
+        let mut filter = LogFilter::default();
+        filter.set_query("Frame", SearchMode::Substring);
+        assert!(filter.matches(LogLevel::Info, None, "slow frame took 40ms"));
+        assert!(!filter.matches(LogLevel::Info, None, "loaded scene"));
+
+        filter.set_query(r"took \d+ms", SearchMode::Regex);
+        assert!(filter.regex_error().is_none());
+        assert!(filter.matches(LogLevel::Info, None, "slow frame TOOK 40ms"));
+        assert!(!filter.matches(LogLevel::Info, None, "took a while"));
+    }
+
+    #[test]
+    fn test_invalid_regex_reports_error_and_filters_nothing() {
+        let mut filter = LogFilter::default();
+        assert!(filter.set_query("frame(", SearchMode::Regex));
+        assert!(filter.regex_error().is_some());
+        assert!(!filter.is_active());
+        assert!(filter.matches(LogLevel::Info, None, "anything"));
+
+        filter.set_query("frame(", SearchMode::Substring);
+        assert!(filter.regex_error().is_none());
+        assert!(filter.matches(LogLevel::Info, None, "bad frame(1)"));
+    }
+}
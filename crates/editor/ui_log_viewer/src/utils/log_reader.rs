@@ -2,7 +2,7 @@
 
 use anyhow::{Context, Result};
 use std::fs::File;
-use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
@@ -195,3 +195,267 @@ pub fn find_latest_log_dir() -> Result<PathBuf> {
 
     Ok(latest_dir)
 }
+
+/// Bytes read from a followed file per poll, so a large backlog streams in
+/// over several polls instead of stalling one
+const FOLLOW_READ_CHUNK: u64 = 4 * 1024 * 1024;
+
+/// Which file a path pointed at when it was opened
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct FileId(u64, u64);
+
+impl FileId {
+    #[cfg(unix)]
+    fn of(meta: &std::fs::Metadata) -> Option<Self> {
+        use std::os::unix::fs::MetadataExt;
+        Some(Self(meta.dev(), meta.ino()))
+    }
+
+    /// Without inodes, a recreated file is told apart by its creation time
+    #[cfg(not(unix))]
+    fn of(meta: &std::fs::Metadata) -> Option<Self> {
+        let created = meta
+            .created()
+            .ok()?
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?;
+        Some(Self(created.as_secs(), created.subsec_nanos() as u64))
+    }
+}
+
+/// Follows one log file as it grows, reading only what was appended since
+/// the last poll. The file is reopened from the start when the path points
+/// at a different file (rotation by rename) or shrinks below the read
+/// offset (rotation by truncation).
+pub struct FileTail {
+    path: PathBuf,
+    source: String,
+    file: Option<File>,
+    id: Option<FileId>,
+    offset: u64,
+    /// Bytes after the last newline, held until the line is complete
+    partial: Vec<u8>,
+}
+
+impl FileTail {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let source = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        Self {
+            path,
+            source,
+            file: None,
+            id: None,
+            offset: 0,
+            partial: Vec::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Name the file's lines are tagged with
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Complete lines appended since the last poll. A missing file is not an
+    /// error; it is picked up once it appears.
+    pub fn poll(&mut self) -> Result<Vec<String>> {
+        let mut lines = Vec::new();
+
+        // Finish the file already open first, so lines written just before a
+        // rotation aren't lost. Rotation is only checked once it is drained.
+        if self.read_available(&mut lines)? {
+            return Ok(lines);
+        }
+
+        let meta = match std::fs::metadata(&self.path) {
+            Ok(meta) => meta,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(lines),
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to read {}", self.path.display()))
+            }
+        };
+
+        let replaced = self.file.is_some() && FileId::of(&meta) != self.id;
+        let truncated = self.file.is_some() && meta.len() < self.offset;
+        if self.file.is_none() || replaced || truncated {
+            self.flush_partial(&mut lines);
+            self.open()?;
+            self.read_available(&mut lines)?;
+        }
+
+        Ok(lines)
+    }
+
+    fn open(&mut self) -> Result<()> {
+        let file = File::open(&self.path)
+            .with_context(|| format!("Failed to open log file: {}", self.path.display()))?;
+        self.id = FileId::of(&file.metadata()?);
+        self.file = Some(file);
+        self.offset = 0;
+        Ok(())
+    }
+
+    /// Read up to [`FOLLOW_READ_CHUNK`] bytes from the open file, returning
+    /// whether more are waiting
+    fn read_available(&mut self, lines: &mut Vec<String>) -> Result<bool> {
+        let Some(file) = self.file.as_mut() else {
+            return Ok(false);
+        };
+        let read = file
+            .take(FOLLOW_READ_CHUNK)
+            .read_to_end(&mut self.partial)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        self.offset += read as u64;
+
+        if let Some(end) = self.partial.iter().rposition(|b| *b == b'\n') {
+            let rest = self.partial.split_off(end + 1);
+            let complete = std::mem::replace(&mut self.partial, rest);
+            lines.extend(complete[..end].split(|b| *b == b'\n').map(decode_line));
+        }
+
+        Ok(read as u64 == FOLLOW_READ_CHUNK)
+    }
+
+    /// Emit the last line of a file that ended without a newline
+    fn flush_partial(&mut self, lines: &mut Vec<String>) {
+        if !self.partial.is_empty() {
+            lines.push(decode_line(&self.partial));
+            self.partial.clear();
+        }
+    }
+}
+
+fn decode_line(line: &[u8]) -> String {
+    String::from_utf8_lossy(line).trim_end().to_string()
+}
+
+/// Follows a list of log files, tagging lines with the file they came from
+#[derive(Default)]
+pub struct LogFollower {
+    tails: Vec<FileTail>,
+}
+
+impl LogFollower {
+    pub fn new(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        Self {
+            tails: paths.into_iter().map(FileTail::new).collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tails.is_empty()
+    }
+
+    /// New lines of every followed file as `(source, lines)`. A file that
+    /// can't be read is skipped and retried on the next poll.
+    pub fn poll(&mut self) -> Vec<(String, Vec<String>)> {
+        self.tails
+            .iter_mut()
+            .filter_map(|tail| match tail.poll() {
+                Ok(lines) if !lines.is_empty() => Some((tail.source().to_string(), lines)),
+                Ok(_) => None,
+                Err(err) => {
+                    tracing::debug!("Skipping followed log: {:#}", err);
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+
+    fn append(path: &Path, text: &str) {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_tail_reads_only_appended_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("runtime.log");
+        append(&path, "one\ntw");
+
+        let mut tail = FileTail::new(&path);
+        assert_eq!(tail.source(), "runtime.log");
+        assert_eq!(tail.poll().unwrap(), ["one"]);
+
+        append(&path, "o\r\n\nthree\n");
+        assert_eq!(tail.poll().unwrap(), ["two", "", "three"]);
+        assert!(tail.poll().unwrap().is_empty());
+        assert_eq!(tail.offset, fs::metadata(&path).unwrap().len());
+    }
+
+    #[test]
+    fn test_tail_follows_file_replaced_mid_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("runtime.log");
+        append(&path, "old 1\nold 2\n");
+
+        let mut tail = FileTail::new(&path);
+        assert_eq!(tail.poll().unwrap(), ["old 1", "old 2"]);
+
+        // The runtime writes a last, unterminated line and rotates
+        append(&path, "old 3\nold 4");
+        fs::rename(&path, dir.path().join("runtime.log.1")).unwrap();
+        assert_eq!(tail.poll().unwrap(), ["old 3"]);
+
+        append(&path, "new 1\n");
+        assert_eq!(tail.poll().unwrap(), ["old 4", "new 1"]);
+        append(&path, "new 2\n");
+        assert_eq!(tail.poll().unwrap(), ["new 2"]);
+    }
+
+    #[test]
+    fn test_tail_reopens_truncated_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("runtime.log");
+        append(&path, "a fairly long line\nanother long line\n");
+
+        let mut tail = FileTail::new(&path);
+        assert_eq!(tail.poll().unwrap().len(), 2);
+
+        fs::write(&path, "short\n").unwrap();
+        assert_eq!(tail.poll().unwrap(), ["short"]);
+        assert!(tail.poll().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_follower_waits_for_files_and_tags_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let game = dir.path().join("game.log");
+        let server = dir.path().join("server.log");
+
+        let mut follower = LogFollower::new([game.clone(), server.clone()]);
+        assert!(follower.poll().is_empty());
+
+        append(&server, "listening\n");
+        append(&game, "started\nloaded level\n");
+        assert_eq!(
+            follower.poll(),
+            [
+                (
+                    "game.log".to_string(),
+                    vec!["started".to_string(), "loaded level".to_string()]
+                ),
+                ("server.log".to_string(), vec!["listening".to_string()]),
+            ]
+        );
+        assert!(follower.poll().is_empty());
+    }
+}
@@ -0,0 +1,359 @@
+//! Bounded line buffer behind the logs panel
+
+use crate::utils::log_filter::{parse_line, LogFilter, LogLevel};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    ops::Range,
+    sync::Arc,
+    time::SystemTime,
+};
+
+/// Lines kept when the `log_viewer_max_lines` setting is unset
+pub(crate) const DEFAULT_MAX_LINES: usize = 100_000;
+
+/// Source of lines published by this process's own tracing subscriber
+pub(crate) const ENGINE_SOURCE: &str = "engine";
+
+#[derive(Clone)]
+pub(crate) struct LogRow {
+    /// 1-based position in everything the viewer has received
+    pub(crate) abs_line: usize,
+    pub(crate) level: LogLevel,
+    pub(crate) target: Option<String>,
+    /// [`ENGINE_SOURCE`] or the name of the followed file the line came from
+    pub(crate) source: Arc<str>,
+    pub(crate) text: String,
+    /// When the line reached the viewer; log lines carry no parsed timestamp.
+    pub(crate) received_at: SystemTime,
+}
+
+#[derive(Clone)]
+pub(crate) struct LogEntryDetails {
+    pub(crate) abs_line: usize,
+    pub(crate) level: LogLevel,
+    pub(crate) source: Arc<str>,
+    pub(crate) text: String,
+    pub(crate) received_at: SystemTime,
+    pub(crate) chars: usize,
+    pub(crate) bytes: usize,
+    pub(crate) buffered_rows: usize,
+    pub(crate) filtered_active: bool,
+}
+
+/// Ring of the most recent `max_lines` rows; the oldest row is dropped for
+/// every row pushed past the limit
+pub(crate) struct LogStore {
+    rows: VecDeque<LogRow>,
+    /// `abs_line` of every buffered row passing the filter, oldest first
+    filtered_lines: VecDeque<usize>,
+    max_lines: usize,
+    pub(crate) total_seen: usize,
+    pub(crate) dropped_total: usize,
+    pub(crate) filter: LogFilter,
+    /// Every target seen so far, with how many lines it logged
+    pub(crate) targets: BTreeMap<String, usize>,
+    /// Every source seen so far, with how many lines it produced
+    pub(crate) sources: BTreeMap<Arc<str>, usize>,
+}
+
+impl LogStore {
+    pub(crate) fn new(max_lines: usize) -> Self {
+        Self {
+            rows: VecDeque::new(),
+            filtered_lines: VecDeque::new(),
+            max_lines: max_lines.max(1),
+            total_seen: 0,
+            dropped_total: 0,
+            filter: LogFilter::default(),
+            targets: BTreeMap::new(),
+            sources: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.rows.clear();
+        self.filtered_lines.clear();
+        self.total_seen = 0;
+        self.dropped_total = 0;
+        self.targets.clear();
+        self.sources.clear();
+    }
+
+    pub(crate) fn buffered_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub(crate) fn has_active_filter(&self) -> bool {
+        self.filter.is_active()
+    }
+
+    pub(crate) fn visible_count(&self) -> usize {
+        if self.has_active_filter() {
+            self.filtered_lines.len()
+        } else {
+            self.rows.len()
+        }
+    }
+
+    fn matches_filters(&self, row: &LogRow) -> bool {
+        self.filter.matches_source(&row.source)
+            && self
+                .filter
+                .matches(row.level, row.target.as_deref(), &row.text)
+    }
+
+    fn refilter_all(&mut self) {
+        self.filtered_lines.clear();
+        if !self.has_active_filter() {
+            return;
+        }
+
+        let filtered: VecDeque<usize> = self
+            .rows
+            .iter()
+            .filter(|row| self.matches_filters(row))
+            .map(|row| row.abs_line)
+            .collect();
+        self.filtered_lines = filtered;
+    }
+
+    /// Append lines that all came from `source`
+    pub(crate) fn append_batch(&mut self, source: &str, lines: Vec<String>) {
+        if lines.is_empty() {
+            return;
+        }
+
+        let received_at = SystemTime::now();
+        let source = match self.sources.get_key_value(source) {
+            Some((source, _)) => Arc::clone(source),
+            None => Arc::from(source),
+        };
+        *self.sources.entry(Arc::clone(&source)).or_default() += lines.len();
+
+        for line in lines {
+            self.total_seen += 1;
+            let (level, target) = parse_line(&line);
+            if let Some(target) = &target {
+                *self.targets.entry(target.clone()).or_default() += 1;
+            }
+            let row = LogRow {
+                abs_line: self.total_seen,
+                level,
+                target,
+                source: Arc::clone(&source),
+                text: line,
+                received_at,
+            };
+
+            if self.rows.len() >= self.max_lines {
+                self.drop_oldest();
+            }
+            if self.has_active_filter() && self.matches_filters(&row) {
+                self.filtered_lines.push_back(row.abs_line);
+            }
+            self.rows.push_back(row);
+        }
+    }
+
+    fn drop_oldest(&mut self) {
+        let Some(row) = self.rows.pop_front() else {
+            return;
+        };
+        self.dropped_total += 1;
+        if self.filtered_lines.front() == Some(&row.abs_line) {
+            self.filtered_lines.pop_front();
+        }
+    }
+
+    /// Change the line limit, dropping the oldest rows if it shrank
+    pub(crate) fn set_max_lines(&mut self, max_lines: usize) {
+        self.max_lines = max_lines.max(1);
+        while self.rows.len() > self.max_lines {
+            self.drop_oldest();
+        }
+        self.rows.shrink_to(self.max_lines);
+    }
+
+    /// Apply a filter change; `update` returns whether anything changed
+    pub(crate) fn update_filter(&mut self, update: impl FnOnce(&mut LogFilter) -> bool) {
+        if update(&mut self.filter) {
+            self.refilter_all();
+        }
+    }
+
+    fn row_for_line(&self, abs_line: usize) -> Option<&LogRow> {
+        let first = self.rows.front()?.abs_line;
+        self.rows.get(abs_line.checked_sub(first)?)
+    }
+
+    pub(crate) fn row_for_visible(&self, visible_row: usize) -> Option<&LogRow> {
+        if self.has_active_filter() {
+            self.row_for_line(*self.filtered_lines.get(visible_row)?)
+        } else {
+            self.rows.get(visible_row)
+        }
+    }
+
+    /// Index of the first visible row at or after `abs_line`
+    fn visible_ix_for_line(&self, abs_line: usize) -> usize {
+        if self.has_active_filter() {
+            self.filtered_lines.partition_point(|line| *line < abs_line)
+        } else {
+            self.rows.partition_point(|row| row.abs_line < abs_line)
+        }
+    }
+
+    /// Text of every buffered row, or only of those passing the filter
+    pub(crate) fn lines(&self, filtered: bool) -> Vec<String> {
+        if filtered && self.has_active_filter() {
+            (0..self.visible_count())
+                .filter_map(|ix| self.row_for_visible(ix))
+                .map(|row| row.text.clone())
+                .collect()
+        } else {
+            self.rows.iter().map(|row| row.text.clone()).collect()
+        }
+    }
+
+    /// Visible rows between two line numbers, inclusive. Rows that were
+    /// dropped or filtered out are skipped.
+    fn visible_range_between(&self, from: usize, to: usize) -> Range<usize> {
+        let start = self.visible_ix_for_line(from.min(to));
+        let end = self.visible_ix_for_line(from.max(to).saturating_add(1));
+        start..end
+    }
+
+    pub(crate) fn visible_count_between(&self, from: usize, to: usize) -> usize {
+        self.visible_range_between(from, to).len()
+    }
+
+    /// Text of the visible rows between two line numbers, one row per line
+    pub(crate) fn visible_text_between(&self, from: usize, to: usize) -> String {
+        self.visible_range_between(from, to)
+            .filter_map(|ix| self.row_for_visible(ix))
+            .map(|row| row.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub(crate) fn entry_details(&self, abs_line: usize) -> Option<LogEntryDetails> {
+        let row = self.row_for_line(abs_line)?;
+        Some(LogEntryDetails {
+            abs_line: row.abs_line,
+            level: row.level,
+            source: Arc::clone(&row.source),
+            text: row.text.clone(),
+            received_at: row.received_at,
+            chars: row.text.chars().count(),
+            bytes: row.text.len(),
+            buffered_rows: self.rows.len(),
+            filtered_active: self.has_active_filter(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::log_filter::SearchMode;
+
+    fn lines(range: Range<usize>) -> Vec<String> {
+        range
+            .map(|i| {
+                let level = if i % 10 == 0 { "WARN" } else { "INFO" };
+                format!(
+                    "2025-01-01 12:00:00.000 {} engine::stress: line {}",
+                    level, i
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_stress_memory_stays_bounded() {
+        let mut store = LogStore::new(DEFAULT_MAX_LINES);
+        store.update_filter(|filter| {
+            filter.toggle_level(LogLevel::Info);
+            true
+        });
+
+        for start in (0..200_000).step_by(2_048) {
+            store.append_batch(ENGINE_SOURCE, lines(start..(start + 2_048).min(200_000)));
+            assert!(store.buffered_count() <= DEFAULT_MAX_LINES);
+            assert!(store.filtered_lines.len() <= store.buffered_count());
+        }
+
+        assert_eq!(store.total_seen, 200_000);
+        assert_eq!(store.buffered_count(), DEFAULT_MAX_LINES);
+        assert_eq!(store.dropped_total, 100_000);
+        assert!(store.rows.capacity() < 2 * DEFAULT_MAX_LINES);
+        assert!(store.filtered_lines.capacity() < 2 * DEFAULT_MAX_LINES);
+
+        // Every tenth line is a warning, and only the newest 100k survive
+        assert_eq!(store.visible_count(), 10_000);
+        assert_eq!(store.row_for_visible(0).unwrap().abs_line, 100_001);
+        assert_eq!(store.row_for_visible(9_999).unwrap().abs_line, 199_991);
+    }
+
+    #[test]
+    fn test_ring_drops_oldest_and_keeps_filter_in_sync() {
+        let mut store = LogStore::new(5);
+        store.update_filter(|filter| filter.set_query("line 1", SearchMode::Substring));
+        store.append_batch(ENGINE_SOURCE, lines(0..12));
+
+        assert_eq!(store.buffered_count(), 5);
+        // "line 1", "line 10" and "line 11" matched; "line 1" was dropped
+        assert_eq!(store.visible_count(), 2);
+        assert_eq!(store.row_for_visible(0).map(|row| row.abs_line), Some(11));
+
+        store.set_max_lines(1);
+        assert_eq!(store.buffered_count(), 1);
+        assert_eq!(store.visible_count(), 1);
+        assert_eq!(store.dropped_total, 11);
+    }
+
+    #[test]
+    fn test_visible_text_between() {
+        let mut store = LogStore::new(100);
+        store.append_batch(
+            ENGINE_SOURCE,
+            vec!["a".into(), "b WARN x".into(), "c".into(), "d".into()],
+        );
+
+        assert_eq!(store.visible_text_between(3, 2), "b WARN x\nc");
+        assert_eq!(store.visible_count_between(2, 3), 2);
+        assert_eq!(store.visible_text_between(4, 9), "d");
+
+        store.update_filter(|filter| filter.set_query("WARN", SearchMode::Substring));
+        assert_eq!(store.visible_text_between(1, 4), "b WARN x");
+        assert_eq!(store.visible_text_between(3, 4), "");
+        assert_eq!(store.visible_count_between(3, 4), 0);
+    }
+    #[test]
+    fn test_source_filter_and_counts() {
+        let mut store = LogStore::new(100);
+        store.append_batch(ENGINE_SOURCE, lines(0..3));
+        store.append_batch("game.log", vec!["INFO game: ready".into()]);
+        store.append_batch("game.log", vec!["WARN game: slow".into()]);
+
+        assert_eq!(store.sources.len(), 2);
+        assert_eq!(store.sources.get("game.log"), Some(&2));
+        assert_eq!(
+            store.row_for_visible(4).map(|row| &*row.source),
+            Some("game.log")
+        );
+
+        store.update_filter(|filter| filter.set_source(Some("game.log")));
+        assert_eq!(store.visible_count(), 2);
+        store.append_batch(ENGINE_SOURCE, lines(3..5));
+        store.append_batch("game.log", vec!["INFO game: done".into()]);
+        assert_eq!(store.visible_count(), 3);
+        assert_eq!(
+            store.lines(true).last().map(String::as_str),
+            Some("INFO game: done")
+        );
+
+        store.update_filter(|filter| filter.set_source(None));
+        assert_eq!(store.visible_count(), 8);
+    }
+}
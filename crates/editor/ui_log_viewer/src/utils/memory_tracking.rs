@@ -1,5 +1,6 @@
 //! Memory tracking and allocation monitoring
 
+use crate::utils::atomic_memory_tracking::ATOMIC_MEMORY_COUNTERS;
 use parking_lot::RwLock;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -20,6 +21,21 @@ pub enum MemoryCategory {
 }
 
 impl MemoryCategory {
+    /// Number of built-in categories
+    pub const COUNT: usize = 9;
+
+    pub const ALL: [MemoryCategory; Self::COUNT] = [
+        MemoryCategory::Unknown,
+        MemoryCategory::Engine,
+        MemoryCategory::Renderer,
+        MemoryCategory::UI,
+        MemoryCategory::Physics,
+        MemoryCategory::Audio,
+        MemoryCategory::Assets,
+        MemoryCategory::Scripts,
+        MemoryCategory::Network,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             MemoryCategory::Unknown => "Unknown",
@@ -35,10 +51,47 @@ impl MemoryCategory {
     }
 }
 
+/// A built-in [`MemoryCategory`] or one registered at runtime through
+/// [`MemoryTracker::register_category`]
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct MemoryCategoryId(pub(crate) usize);
+
+impl MemoryCategoryId {
+    pub const UNKNOWN: MemoryCategoryId = MemoryCategoryId(MemoryCategory::Unknown as usize);
+
+    pub fn index(self) -> usize {
+        self.0
+    }
+
+    /// The built-in category this id refers to, if it isn't a registered one
+    pub fn builtin(self) -> Option<MemoryCategory> {
+        MemoryCategory::ALL.get(self.0).copied()
+    }
+
+    pub fn name(self) -> &'static str {
+        ATOMIC_MEMORY_COUNTERS.category_name(self)
+    }
+}
+
+impl From<MemoryCategory> for MemoryCategoryId {
+    fn from(category: MemoryCategory) -> Self {
+        MemoryCategoryId(category as usize)
+    }
+}
+
+/// Current and peak bytes of one category
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryUsage {
+    pub id: MemoryCategoryId,
+    pub name: &'static str,
+    pub current: usize,
+    pub peak: usize,
+}
+
 /// Detailed memory allocation entry
 #[derive(Debug, Clone)]
 pub struct MemoryAllocation {
-    pub category: MemoryCategory,
+    pub category: MemoryCategoryId,
     pub size: usize,
     pub count: usize,
     pub description: String,
@@ -53,12 +106,14 @@ pub struct MemoryStats {
     pub peak_usage: usize,
     pub allocation_count: usize,
     pub deallocation_count: usize,
-    pub by_category: HashMap<MemoryCategory, usize>,
+    pub by_category: HashMap<MemoryCategoryId, usize>,
+    pub peak_by_category: HashMap<MemoryCategoryId, usize>,
 }
 
 impl MemoryStats {
     /// Record an allocation
-    pub fn record_allocation(&mut self, size: usize, category: MemoryCategory) {
+    pub fn record_allocation(&mut self, size: usize, category: impl Into<MemoryCategoryId>) {
+        let category = category.into();
         self.total_allocated += size;
         self.current_usage += size;
         self.allocation_count += 1;
@@ -67,11 +122,15 @@ impl MemoryStats {
             self.peak_usage = self.current_usage;
         }
 
-        *self.by_category.entry(category).or_insert(0) += size;
+        let current = self.by_category.entry(category).or_insert(0);
+        *current += size;
+        let peak = self.peak_by_category.entry(category).or_insert(0);
+        *peak = (*peak).max(*current);
     }
 
     /// Record a deallocation
-    pub fn record_deallocation(&mut self, size: usize, category: MemoryCategory) {
+    pub fn record_deallocation(&mut self, size: usize, category: impl Into<MemoryCategoryId>) {
+        let category = category.into();
         self.total_deallocated += size;
         if self.current_usage >= size {
             self.current_usage -= size;
@@ -101,9 +160,18 @@ impl MemoryStats {
     }
 
     /// Get category breakdown sorted by size
-    pub fn category_breakdown(&self) -> Vec<(MemoryCategory, usize)> {
-        let mut categories: Vec<_> = self.by_category.iter().map(|(k, v)| (*k, *v)).collect();
-        categories.sort_by(|a, b| b.1.cmp(&a.1));
+    pub fn category_breakdown(&self) -> Vec<CategoryUsage> {
+        let mut categories: Vec<_> = self
+            .peak_by_category
+            .iter()
+            .map(|(&id, &peak)| CategoryUsage {
+                id,
+                name: id.name(),
+                current: self.by_category.get(&id).copied().unwrap_or(0),
+                peak,
+            })
+            .collect();
+        categories.sort_by(|a, b| b.current.cmp(&a.current).then(a.id.cmp(&b.id)));
         categories
     }
 
@@ -122,7 +190,8 @@ impl MemoryStats {
 pub struct MemoryStatsSnapshot {
     pub current_usage: usize,
     pub peak_usage: usize,
-    pub category_breakdown: Vec<(MemoryCategory, usize)>,
+    /// Current and peak bytes per category, largest current usage first
+    pub category_breakdown: Vec<CategoryUsage>,
 }
 
 /// Global memory tracker
@@ -137,6 +206,14 @@ impl MemoryTracker {
         }
     }
 
+    /// Add a named category for allocations that don't fit the built-in
+    /// ones, e.g. a plugin or gameplay system. Registering the same name
+    /// again returns the same id. Use the id with
+    /// [`crate::MemoryCategoryGuard`] to attribute allocations to it.
+    pub fn register_category(name: &str) -> MemoryCategoryId {
+        ATOMIC_MEMORY_COUNTERS.register_category(name)
+    }
+
     pub fn stats(&self) -> Arc<RwLock<MemoryStats>> {
         self.stats.clone()
     }
@@ -147,12 +224,12 @@ impl MemoryTracker {
     }
 
     /// Record an allocation
-    pub fn allocate(&self, size: usize, category: MemoryCategory) {
+    pub fn allocate(&self, size: usize, category: impl Into<MemoryCategoryId>) {
         self.stats.write().record_allocation(size, category);
     }
 
     /// Record a deallocation
-    pub fn deallocate(&self, size: usize, category: MemoryCategory) {
+    pub fn deallocate(&self, size: usize, category: impl Into<MemoryCategoryId>) {
         self.stats.write().record_deallocation(size, category);
     }
 
@@ -0,0 +1,183 @@
+//! Timestamped ring buffers for long-range metric charts
+
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Samples kept when the `metrics_history_secs` setting is unset; 30 minutes at 1Hz
+pub const DEFAULT_HISTORY_LEN: usize = 1800;
+
+/// A single metric reading
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MetricSample {
+    /// Unix time in seconds
+    pub timestamp: f64,
+    pub value: f64,
+}
+
+/// Fixed-capacity history of one metric, oldest sample first
+#[derive(Clone, Debug)]
+pub struct MetricHistory {
+    samples: VecDeque<MetricSample>,
+    capacity: usize,
+}
+
+impl MetricHistory {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Change how many samples are kept, dropping the oldest if it shrank
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        while self.samples.len() > self.capacity {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn push(&mut self, timestamp: f64, value: f64) {
+        if self.samples.len() >= self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(MetricSample { timestamp, value });
+    }
+
+    /// Record `value` as of now
+    pub fn push_now(&mut self, value: f64) {
+        self.push(unix_now(), value);
+    }
+
+    pub fn latest(&self) -> Option<MetricSample> {
+        self.samples.back().copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &MetricSample> {
+        self.samples.iter()
+    }
+
+    /// Samples no older than `window` before the newest one, oldest first
+    pub fn window(&self, window: Duration) -> Vec<MetricSample> {
+        let Some(newest) = self.samples.back() else {
+            return Vec::new();
+        };
+        let start = newest.timestamp - window.as_secs_f64();
+        let first = self.samples.partition_point(|s| s.timestamp < start);
+        self.samples.range(first..).copied().collect()
+    }
+
+    /// Mean of the samples in [`Self::window`]
+    pub fn window_average(&self, window: Duration) -> Option<f64> {
+        let samples = self.window(window);
+        if samples.is_empty() {
+            return None;
+        }
+        Some(samples.iter().map(|s| s.value).sum::<f64>() / samples.len() as f64)
+    }
+}
+
+/// Average runs of consecutive samples so at most `max_points` remain. Each
+/// point takes the timestamp of the newest sample it covers.
+pub fn downsample(samples: &[MetricSample], max_points: usize) -> Vec<MetricSample> {
+    if max_points == 0 {
+        return Vec::new();
+    }
+    if samples.len() <= max_points {
+        return samples.to_vec();
+    }
+
+    let bucket = samples.len().div_ceil(max_points);
+    samples
+        .chunks(bucket)
+        .map(|chunk| MetricSample {
+            timestamp: chunk[chunk.len() - 1].timestamp,
+            value: chunk.iter().map(|s| s.value).sum::<f64>() / chunk.len() as f64,
+        })
+        .collect()
+}
+
+fn unix_now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(values: impl IntoIterator<Item = f64>, capacity: usize) -> MetricHistory {
+        let mut history = MetricHistory::new(capacity);
+        for (i, value) in values.into_iter().enumerate() {
+            history.push(1000.0 + i as f64, value);
+        }
+        history
+    }
+
+    #[test]
+    fn test_ring_keeps_newest_samples() {
+        let mut history = history((0..10).map(f64::from), 4);
+        assert_eq!(history.len(), 4);
+        assert_eq!(history.iter().next().map(|s| s.value), Some(6.0));
+        assert_eq!(history.latest().map(|s| s.value), Some(9.0));
+
+        history.set_capacity(2);
+        assert_eq!(
+            history.iter().map(|s| s.value).collect::<Vec<_>>(),
+            [8.0, 9.0]
+        );
+    }
+
+    #[test]
+    fn test_window_is_relative_to_newest_sample() {
+        let history = history((0..60).map(f64::from), 1800);
+
+        let last_ten = history.window(Duration::from_secs(9));
+        assert_eq!(last_ten.len(), 10);
+        assert_eq!(last_ten[0].value, 50.0);
+        assert_eq!(history.window(Duration::from_secs(300)).len(), 60);
+        assert_eq!(history.window_average(Duration::from_secs(1)), Some(58.5));
+        assert!(MetricHistory::new(10)
+            .window(Duration::from_secs(30))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_downsample_averages_buckets() {
+        let samples = history((0..10).map(f64::from), 100).window(Duration::from_secs(60));
+
+        let points = downsample(&samples, 4);
+        assert_eq!(points.len(), 4);
+        assert_eq!(
+            points[0],
+            MetricSample {
+                timestamp: 1002.0,
+                value: 1.0
+            }
+        );
+        assert_eq!(
+            points[3],
+            MetricSample {
+                timestamp: 1009.0,
+                value: 9.0
+            }
+        );
+        assert_eq!(downsample(&samples, 20), samples);
+        assert!(downsample(&samples, 0).is_empty());
+    }
+}
@@ -1,12 +1,17 @@
+pub mod alerts;
 pub mod atomic_memory_tracking;
 pub mod caller_tracking;
+pub mod diagnostics_export;
 pub mod gpu_engines;
 pub mod gpu_info;
 pub mod live_logs;
+pub mod log_filter;
 pub mod log_reader;
+pub mod log_store;
 pub mod mem_details;
 pub mod memory_database;
 pub mod memory_tracking;
+pub mod metric_history;
 pub mod performance_metrics;
 pub mod system_info;
 pub mod tracking_allocator;
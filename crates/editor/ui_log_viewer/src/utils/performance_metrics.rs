@@ -1,8 +1,9 @@
 //! Performance metrics tracking for Mission Control
 
 use crate::utils::gpu_info;
-use std::collections::VecDeque;
-use sysinfo::{Components, Networks, ProcessesToUpdate, System};
+use crate::utils::metric_history::{MetricHistory, DEFAULT_HISTORY_LEN};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use sysinfo::{Components, Networks, Pid, ProcessesToUpdate, System};
 use ui_common::SharedState;
 
 /// Maximum number of data points to keep in history
@@ -58,6 +59,23 @@ pub struct DiskDataPoint {
     pub kbps: f64,
 }
 
+/// CPU history of one thread of the editor process
+#[derive(Clone)]
+pub struct ThreadCpuHistory {
+    pub name: String,
+    pub history: MetricHistory,
+}
+
+/// The `metrics_history_secs` setting as a sample count, or [`DEFAULT_HISTORY_LEN`]
+fn configured_history_len() -> usize {
+    engine_state::global_config()
+        .get(engine_state::NS_EDITOR, "advanced", "metrics_history_secs")
+        .ok()
+        .and_then(|v| v.as_float().ok())
+        .map(|secs| secs as usize)
+        .unwrap_or(DEFAULT_HISTORY_LEN)
+}
+
 /// Container for all performance metrics
 pub struct PerformanceMetrics {
     pub cpu_history: VecDeque<CpuDataPoint>,
@@ -114,6 +132,19 @@ pub struct PerformanceMetrics {
     /// Cached memory history for chart (MiB).
     pub cached_history: VecDeque<f64>,
 
+    // Long-range 1Hz histories for the resource monitor charts
+    pub cpu_samples: MetricHistory,
+    pub memory_samples: MetricHistory,
+    pub vram_samples: MetricHistory,
+    /// Per-thread CPU % keyed by thread id. Linux only; empty elsewhere.
+    pub thread_cpu: BTreeMap<u32, ThreadCpuHistory>,
+
+    /// Latest renderer statistics; `None` while no renderer is active
+    pub render: Option<engine_state::RenderMetrics>,
+    pub frame_time_samples: MetricHistory,
+    /// GPU memory allocated by the renderer, in MiB
+    pub render_mem_samples: MetricHistory,
+
     // System info
     system: System,
     networks: Networks,
@@ -147,6 +178,7 @@ impl PerformanceMetrics {
         components: Components,
         current_pid: sysinfo::Pid,
     ) -> Self {
+        let history_len = configured_history_len();
         Self {
             cpu_history: VecDeque::with_capacity(MAX_HISTORY_SIZE),
             cpu_counter: 0,
@@ -188,6 +220,15 @@ impl PerformanceMetrics {
             committed_history: VecDeque::with_capacity(MAX_HISTORY_SIZE),
             cached_history: VecDeque::with_capacity(MAX_HISTORY_SIZE),
 
+            cpu_samples: MetricHistory::new(history_len),
+            memory_samples: MetricHistory::new(history_len),
+            vram_samples: MetricHistory::new(history_len),
+            thread_cpu: BTreeMap::new(),
+
+            render: None,
+            frame_time_samples: MetricHistory::new(history_len),
+            render_mem_samples: MetricHistory::new(history_len),
+
             system,
             networks,
             components,
@@ -338,21 +379,109 @@ impl PerformanceMetrics {
         self.current_disk_read_kbps = disk_r;
         self.current_disk_write_kbps = disk_w;
 
+        // ── Per-thread CPU (Linux lists threads as tasks of the process) ─────
+        self.update_thread_cpu();
+
         // ── Push to histories ─────────────────────────────────────────────────
         self.add_cpu(cpu_usage);
         self.add_memory(memory_mb);
         self.add_gpu(self.current_vram_used_mb);
         self.add_net(self.current_net_rx_kbps, self.current_net_tx_kbps);
         self.add_disk(self.current_disk_read_kbps, self.current_disk_write_kbps);
+
+        self.cpu_samples.push_now(cpu_usage);
+        self.memory_samples.push_now(memory_mb);
+        self.vram_samples.push_now(self.current_vram_used_mb);
     }
 
-    /// Update from render metrics (FPS, Frame Time)
-    pub fn update_from_render_metrics(&mut self, fps: f32, frame_time_ms: f32, _memory_mb: f32) {
-        self.current_fps = fps as f64;
-        self.current_frame_time_ms = frame_time_ms as f64;
+    /// Carry the long-range histories over from the instance this replaces
+    pub fn inherit_history(&mut self, previous: &mut PerformanceMetrics) {
+        std::mem::swap(&mut self.cpu_samples, &mut previous.cpu_samples);
+        std::mem::swap(&mut self.memory_samples, &mut previous.memory_samples);
+        std::mem::swap(&mut self.vram_samples, &mut previous.vram_samples);
+        std::mem::swap(&mut self.thread_cpu, &mut previous.thread_cpu);
+        std::mem::swap(
+            &mut self.frame_time_samples,
+            &mut previous.frame_time_samples,
+        );
+        std::mem::swap(
+            &mut self.render_mem_samples,
+            &mut previous.render_mem_samples,
+        );
+        self.render = previous.render;
+    }
 
-        self.add_fps(fps as f64);
-        self.add_frame_time(frame_time_ms as f64);
+    /// Change how many samples the long-range histories keep
+    pub fn set_history_len(&mut self, len: usize) {
+        self.cpu_samples.set_capacity(len);
+        self.memory_samples.set_capacity(len);
+        self.vram_samples.set_capacity(len);
+        self.frame_time_samples.set_capacity(len);
+        self.render_mem_samples.set_capacity(len);
+        for thread in self.thread_cpu.values_mut() {
+            thread.history.set_capacity(len);
+        }
+    }
+
+    fn update_thread_cpu(&mut self) {
+        let thread_ids: HashSet<Pid> = self
+            .system
+            .process(self.current_pid)
+            .and_then(|p| p.tasks())
+            .cloned()
+            .unwrap_or_default();
+        if thread_ids.is_empty() {
+            self.thread_cpu.clear();
+            return;
+        }
+
+        let to_refresh: Vec<Pid> = thread_ids.iter().copied().collect();
+        self.system
+            .refresh_processes(ProcessesToUpdate::Some(&to_refresh), true);
+
+        // Forget threads that have exited
+        self.thread_cpu
+            .retain(|tid, _| thread_ids.contains(&Pid::from_u32(*tid)));
+
+        let history_len = self.cpu_samples.capacity();
+        for tid in &thread_ids {
+            let Some(thread) = self.system.process(*tid) else {
+                continue;
+            };
+            self.thread_cpu
+                .entry(tid.as_u32())
+                .or_insert_with(|| ThreadCpuHistory {
+                    name: thread.name().to_string_lossy().into_owned(),
+                    history: MetricHistory::new(history_len),
+                })
+                .history
+                .push_now(thread.cpu_usage() as f64);
+        }
+    }
+
+    /// Record the active renderer's latest frame, or note that there is none
+    pub fn update_from_render_metrics(&mut self, render: Option<engine_state::RenderMetrics>) {
+        self.render = render;
+        let Some(render) = render else {
+            self.current_fps = 0.0;
+            self.current_frame_time_ms = 0.0;
+            return;
+        };
+
+        let frame_time_ms = render.frame_time_ms as f64;
+        let fps = if frame_time_ms > 0.0 {
+            1000.0 / frame_time_ms
+        } else {
+            0.0
+        };
+        self.current_fps = fps;
+        self.current_frame_time_ms = frame_time_ms;
+
+        self.add_fps(fps);
+        self.add_frame_time(frame_time_ms);
+        self.frame_time_samples.push_now(frame_time_ms);
+        self.render_mem_samples
+            .push_now(render.gpu_mem_used as f64 / 1024.0 / 1024.0);
     }
 
     fn add_cpu(&mut self, usage: f64) {
@@ -6,7 +6,7 @@
 
 use crate::utils::atomic_memory_tracking::ATOMIC_MEMORY_COUNTERS;
 use crate::utils::caller_tracking;
-use crate::utils::memory_tracking::MemoryCategory;
+use crate::utils::memory_tracking::{MemoryCategory, MemoryCategoryId};
 use std::alloc::{GlobalAlloc, Layout, System};
 use std::cell::Cell;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
@@ -46,10 +46,6 @@ impl TrackingAllocator {
         Self
     }
 
-    fn categorize_allocation() -> MemoryCategory {
-        CURRENT_CATEGORY.with(|cat| cat.load(Ordering::Relaxed).into())
-    }
-
     #[inline]
     fn record_alloc(&self, ptr: *mut u8, layout: Layout) {
         // Early exit if tracking is disabled — zero overhead, just like System allocator.
@@ -73,7 +69,7 @@ impl TrackingAllocator {
             return;
         }
 
-        ATOMIC_MEMORY_COUNTERS.record_alloc(layout.size(), Self::categorize_allocation());
+        ATOMIC_MEMORY_COUNTERS.record_alloc(layout.size(), current_category());
 
         // Capture raw return addresses only — no symbol resolution, no heap alloc.
         let mut frames = [0usize; 8];
@@ -117,7 +113,7 @@ impl TrackingAllocator {
             return;
         }
 
-        ATOMIC_MEMORY_COUNTERS.record_dealloc(layout.size(), Self::categorize_allocation());
+        ATOMIC_MEMORY_COUNTERS.record_dealloc(layout.size(), current_category());
         caller_tracking::record_dealloc(ptr as usize, layout.size());
 
         TRACKING_ENABLED.with(|e| e.set(true));
@@ -161,13 +157,22 @@ thread_local! {
     static CURRENT_CATEGORY: AtomicUsize = const { AtomicUsize::new(0) };
 }
 
+/// Category that allocations on this thread are currently attributed to
+pub(crate) fn current_category() -> MemoryCategoryId {
+    MemoryCategoryId(CURRENT_CATEGORY.with(|c| c.load(Ordering::Relaxed)))
+}
+
+/// Attributes this thread's allocations to a category until dropped.
+/// Accepts a built-in [`MemoryCategory`] or an id from
+/// [`crate::MemoryTracker::register_category`].
 pub struct MemoryCategoryGuard {
     previous: usize,
 }
 
 impl MemoryCategoryGuard {
-    pub fn new(category: MemoryCategory) -> Self {
-        let previous = CURRENT_CATEGORY.with(|c| c.swap(category as usize, Ordering::Relaxed));
+    pub fn new(category: impl Into<MemoryCategoryId>) -> Self {
+        let category = category.into();
+        let previous = CURRENT_CATEGORY.with(|c| c.swap(category.index(), Ordering::Relaxed));
         Self { previous }
     }
 }
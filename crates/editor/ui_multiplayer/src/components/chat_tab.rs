@@ -1,5 +1,6 @@
 use gpui::prelude::FluentBuilder;
 use gpui::*;
+use std::time::{Duration, UNIX_EPOCH};
 use ui::{
     button::Button,
     h_flex,
@@ -8,7 +9,7 @@ use ui::{
 };
 
 use crate::screen::MultiplayerWindow;
-use crate::utils::format::format_timestamp;
+use ui_common::RelativeTime;
 
 pub fn render_chat_tab(
     this: &MultiplayerWindow,
@@ -47,6 +48,18 @@ pub fn render_chat_tab(
                         )
                     })
                     .children(this.chat_messages.iter().map(|msg| {
+                        let sent_at = UNIX_EPOCH + Duration::from_secs(msg.timestamp);
+                        if msg.is_system {
+                            return h_flex()
+                                .justify_center()
+                                .gap_2()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(msg.message.clone())
+                                .child(RelativeTime::new(sent_at))
+                                .into_any_element();
+                        }
+
                         let peer_name = if msg.is_self {
                             "You".to_string()
                         } else {
@@ -57,8 +70,6 @@ pub fn render_chat_tab(
                             }
                         };
 
-                        let timestamp_str = format_timestamp(msg.timestamp);
-
                         v_flex()
                             .gap_1()
                             .when(msg.is_self, |this| this.items_end())
@@ -77,7 +88,7 @@ pub fn render_chat_tab(
                                         div()
                                             .text_xs()
                                             .text_color(cx.theme().muted_foreground)
-                                            .child(timestamp_str),
+                                            .child(RelativeTime::new(sent_at)),
                                     ),
                             )
                             .child(
@@ -7,17 +7,13 @@ use ui::{
 };
 
 use crate::screen::MultiplayerWindow;
+use engine_state::session_roles::SessionAction;
 
 pub fn render_presence_tab(
     this: &MultiplayerWindow,
     cx: &mut Context<MultiplayerWindow>,
 ) -> impl IntoElement {
-    let is_host = this
-        .active_session
-        .as_ref()
-        .and_then(|s| s.connected_users.first())
-        .map(|first_peer| Some(first_peer) == this.current_peer_id.as_ref())
-        .unwrap_or(false);
+    let is_host = this.is_local_host();
 
     v_flex()
         .size_full()
@@ -86,7 +82,9 @@ pub fn render_presence_tab(
                     .children(this.user_presences.iter().map(|presence| {
                         let is_self =
                             Some(&presence.peer_id) == this.current_peer_id.as_ref();
-                        let short_id = if presence.peer_id.len() > 8 {
+                        let name = if presence.display_name != presence.peer_id {
+                            presence.display_name.clone()
+                        } else if presence.peer_id.len() > 8 {
                             format!("{}...", &presence.peer_id[..8])
                         } else {
                             presence.peer_id.clone()
@@ -104,6 +102,20 @@ pub fn render_presence_tab(
                             SharedString::from(format!("kick-{}", presence.peer_id));
                         let peer_id_for_jump = presence.peer_id.clone();
                         let peer_id_for_kick = presence.peer_id.clone();
+                        let can_kick = this
+                            .session_roles
+                            .as_ref()
+                            .zip(this.current_peer_id.as_ref())
+                            .is_some_and(|(roles, our_peer_id)| {
+                                roles
+                                    .check(
+                                        our_peer_id,
+                                        &SessionAction::Kick {
+                                            target_role: roles.role_of(&presence.peer_id),
+                                        },
+                                    )
+                                    .is_ok()
+                            });
 
                         v_flex()
                             .gap_3()
@@ -131,9 +143,9 @@ pub fn render_presence_tab(
                                             .font_bold()
                                             .text_color(cx.theme().foreground)
                                             .child(if is_self {
-                                                format!("{} (You)", short_id)
+                                                format!("{} (You)", name)
                                             } else {
-                                                short_id
+                                                name
                                             }),
                                     )
                                     .child(
@@ -230,7 +242,7 @@ pub fn render_presence_tab(
                                                     },
                                                 )),
                                         )
-                                        .when(is_host, |this| {
+                                        .when(can_kick, |this| {
                                             this.child(
                                                 Button::new(kick_id)
                                                     .label("Kick")
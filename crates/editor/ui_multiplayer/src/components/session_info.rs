@@ -1,14 +1,15 @@
 use gpui::prelude::FluentBuilder;
 use gpui::*;
 use ui::{
-    button::Button,
+    button::{Button, ButtonVariants as _},
     clipboard::Clipboard,
     h_flex,
-    v_flex, ActiveTheme as _, Icon, IconName, StyledExt,
+    v_flex, ActiveTheme as _, Icon, IconName, Sizable as _, StyledExt,
 };
 
 use crate::screen::MultiplayerWindow;
 use crate::utils::types::ActiveSession;
+use engine_state::session_roles::{Capability, SessionRole};
 
 pub fn render_session_info_tab(
     this: &MultiplayerWindow,
@@ -18,6 +19,11 @@ pub fn render_session_info_tab(
     let session_id = session.session_id.clone();
     let join_token = session.join_token.clone();
     let server_address = session.server_address.clone();
+    let legacy_peers = this
+        .session_roles
+        .as_ref()
+        .map(|roles| roles.legacy_participants())
+        .unwrap_or_default();
 
     v_flex()
         .gap_3()
@@ -119,6 +125,21 @@ pub fn render_session_info_tab(
                 ),
         )
         .child(div().h(px(1.)).w_full().bg(cx.theme().border))
+        .when(!legacy_peers.is_empty(), |this| {
+            let message = if legacy_peers.len() == 1 {
+                "1 participant uses an older client without role support and is treated as an Editor"
+                    .to_string()
+            } else {
+                format!(
+                    "{} participants use older clients without role support and are treated as Editors",
+                    legacy_peers.len()
+                )
+            };
+            this.child(render_banner(message, cx))
+        })
+        .when_some(this.role_notice.clone(), |this, notice| {
+            this.child(render_banner(notice, cx))
+        })
         .child(
             v_flex()
                 .gap_3()
@@ -165,44 +186,14 @@ pub fn render_session_info_tab(
                     v_flex()
                         .gap_1()
                     .children(
-                        this.format_participants(&session.connected_users)
+                        session
+                            .connected_users
                             .iter()
-                                .map(|user| {
-                                    h_flex()
-                                        .items_center()
-                                        .gap_2()
-                                        .px_3()
-                                        .py_2()
-                                        .rounded(px(6.))
-                                        .bg(cx.theme().secondary)
-                                        .child(
-                                            Icon::new(IconName::User)
-                                                .size(px(14.))
-                                                .text_color(cx.theme().muted_foreground),
-                                        )
-                                        .child(
-                                            div()
-                                                .text_sm()
-                                                .text_color(cx.theme().foreground)
-                                                .child(user.clone()),
-                                        )
-                                        .when(user.contains("(Host)"), |this| {
-                                            this.child(
-                                                div()
-                                                    .ml_auto()
-                                                    .px_2()
-                                                    .py_0p5()
-                                                    .rounded(px(4.))
-                                                    .bg(cx.theme().primary)
-                                                    .text_xs()
-                                                    .font_bold()
-                                                    .text_color(cx.theme().primary_foreground)
-                                                    .child("HOST"),
-                                            )
-                                        })
-                                        .into_any_element()
-                                }),
-                        )
+                            .zip(this.format_participants(&session.connected_users))
+                            .map(|(peer_id, user)| {
+                                render_participant_row(this, peer_id, user, cx)
+                            }),
+                    )
                         .when(session.connected_users.is_empty(), |this| {
                             this.child(
                                 div()
@@ -226,3 +217,117 @@ pub fn render_session_info_tab(
             ),
         )
 }
+
+fn render_banner(message: String, cx: &mut Context<MultiplayerWindow>) -> impl IntoElement {
+    h_flex()
+        .items_center()
+        .gap_2()
+        .px_3()
+        .py_2()
+        .rounded(px(6.))
+        .bg(cx.theme().warning.opacity(0.15))
+        .child(
+            Icon::new(IconName::TriangleAlert)
+                .size(px(14.))
+                .text_color(cx.theme().warning),
+        )
+        .child(
+            div()
+                .text_xs()
+                .text_color(cx.theme().foreground)
+                .child(message),
+        )
+}
+
+fn render_participant_row(
+    this: &MultiplayerWindow,
+    peer_id: &str,
+    user: String,
+    cx: &mut Context<MultiplayerWindow>,
+) -> AnyElement {
+    let roles = this.session_roles.as_ref();
+    let role = roles.map(|roles| roles.role_of(peer_id));
+    let is_self = this.current_peer_id.as_deref() == Some(peer_id);
+    let can_manage = !is_self
+        && role.as_ref().is_some_and(|role| *role != SessionRole::Host)
+        && this.local_can(Capability::ChangeRoles);
+
+    // Managers can hand out any role they hold the capabilities for; only the
+    // host can pass on the host role
+    let mut choices = roles
+        .map(|roles| roles.table.assignable_roles())
+        .unwrap_or_default();
+    if this.is_local_host() {
+        choices.push(SessionRole::Host);
+    }
+
+    v_flex()
+        .gap_2()
+        .px_3()
+        .py_2()
+        .rounded(px(6.))
+        .bg(cx.theme().secondary)
+        .child(
+            h_flex()
+                .items_center()
+                .gap_2()
+                .child(
+                    Icon::new(IconName::User)
+                        .size(px(14.))
+                        .text_color(cx.theme().muted_foreground),
+                )
+                .child(
+                    div()
+                        .text_sm()
+                        .text_color(cx.theme().foreground)
+                        .child(user),
+                )
+                .when_some(role.clone(), |this, role| {
+                    let is_host = role == SessionRole::Host;
+                    this.child(
+                        div()
+                            .ml_auto()
+                            .px_2()
+                            .py_0p5()
+                            .rounded(px(4.))
+                            .bg(if is_host {
+                                cx.theme().primary
+                            } else {
+                                cx.theme().muted
+                            })
+                            .text_xs()
+                            .font_bold()
+                            .text_color(if is_host {
+                                cx.theme().primary_foreground
+                            } else {
+                                cx.theme().muted_foreground
+                            })
+                            .child(role.to_string().to_uppercase()),
+                    )
+                }),
+        )
+        .when(can_manage, |this| {
+            let current = role.clone().unwrap_or_default();
+            this.child(h_flex().gap_1().flex_wrap().children(choices.into_iter().map(
+                |choice| {
+                    let target = peer_id.to_string();
+                    let id = SharedString::from(format!("role-{}-{}", peer_id, choice.as_str()));
+                    let label = if choice == SessionRole::Host {
+                        "Make Host".to_string()
+                    } else {
+                        choice.to_string()
+                    };
+                    let button = Button::new(id).label(label).xsmall();
+                    let button = if choice == current {
+                        button.primary()
+                    } else {
+                        button.ghost()
+                    };
+                    button.on_click(cx.listener(move |this, _, _window, cx| {
+                        this.request_role_change(target.clone(), choice.clone(), cx);
+                    }))
+                },
+            )))
+        })
+        .into_any_element()
+}
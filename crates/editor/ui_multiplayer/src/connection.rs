@@ -2,6 +2,7 @@
 
 //! Session creation (host path) for multiplayer sessions
 
+use engine_state::EngineContext;
 use gpui::*;
 
 use crate::screen::MultiplayerWindow;
@@ -25,6 +26,7 @@ impl MultiplayerWindow {
                         this.client = None;
                         this.current_peer_id = None;
                         this.chat_messages.clear();
+                        this.clear_session_roles();
                         this.current_tab = SessionTab::Info;
                         this.sync_engine_multiuser_disconnected();
                         cx.notify();
@@ -38,6 +40,7 @@ impl MultiplayerWindow {
             self.client = None;
             self.current_peer_id = None;
             self.chat_messages.clear();
+            self.clear_session_roles();
             self.current_tab = SessionTab::Info;
             self.sync_engine_multiuser_disconnected();
             cx.notify();
@@ -50,6 +53,9 @@ impl MultiplayerWindow {
         let peer_id = self.current_peer_id.clone();
         self.connection_status = ConnectionStatus::Error(reason.clone());
         self.sync_engine_multiuser_error(reason.clone());
+        if let Some(ctx) = EngineContext::global() {
+            ctx.update_multiuser(|mu| mu.merge_participants(&[]));
+        }
 
         cx.spawn(async move |this, cx| {
             if let Some(client) = client {
@@ -63,7 +69,7 @@ impl MultiplayerWindow {
                     this.client = None;
                     this.current_peer_id = None;
                     this.chat_messages.clear();
-                    this.user_presences.clear();
+                    this.clear_session_roles();
                     this.current_tab = SessionTab::Info;
                     this.file_sync_in_progress = false;
                     this.sync_progress_message = None;
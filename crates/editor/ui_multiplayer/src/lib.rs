@@ -12,6 +12,7 @@ mod file_sync_ui;
 mod handlers;
 mod peer_state;
 mod presence;
+mod roles;
 pub mod screen;
 mod session;
 mod sync_protocol;
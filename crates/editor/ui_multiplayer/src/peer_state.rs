@@ -63,6 +63,7 @@ impl MultiplayerWindow {
                             participants,
                             join_token: server_join_token,
                             participant_profiles,
+                            host_peer_id,
                             ..
                         }) => {
                             cx.update(|cx| {
@@ -87,9 +88,6 @@ impl MultiplayerWindow {
                                         connected_users: participants.clone(),
                                     });
 
-                                    // Initialize presence for all participants
-                                    this.update_presence_from_participants(cx);
-
                                     // Log project root
                                     if let Some(project_root) = &this.project_root {
                                         tracing::debug!("JOIN_SESSION: Project root at {:?}", project_root);
@@ -100,7 +98,6 @@ impl MultiplayerWindow {
 
                                     // Set up replication bridge
                                     let integration = ui::replication::MultiuserIntegration::new(cx);
-                                    let host_peer_id = participants.first().cloned().unwrap_or_else(|| peer_id.clone());
 
                                     // Set up message sender
                                     let client_clone = client.clone();
@@ -108,7 +105,7 @@ impl MultiplayerWindow {
                                     let peer_id_clone = peer_id.clone();
                                     integration.start_session(
                                         peer_id.clone(),
-                                        host_peer_id,
+                                        host_peer_id.clone().unwrap_or_default(),
                                         move |replication_msg| {
                                             let client = client_clone.clone();
                                             let session_id = session_id_clone.clone();
@@ -139,6 +136,7 @@ impl MultiplayerWindow {
                                     }
 
                                     tracing::debug!("JOIN_SESSION: Initialized replication bridge");
+                                    this.init_session_roles(&peer_id, host_peer_id.as_deref(), &participants);
                                     this.sync_engine_multiuser_connected(
                                         &server_address,
                                         &session_id,
@@ -151,6 +149,7 @@ impl MultiplayerWindow {
                                         peer_id.clone(),
                                         cx,
                                     );
+                                    this.announce_role_support(cx);
                                     if let Some(profiles) = participant_profiles {
                                         this.sync_engine_multiuser_profiles(profiles);
                                     }
@@ -159,8 +158,8 @@ impl MultiplayerWindow {
                                 });
                             });
 
-                            // Request file manifest from host (first participant)
-                            if let Some(host_peer_id) = participants.first() {
+                            // Request file manifest from host
+                            if let Some(host_peer_id) = &host_peer_id {
                                 tracing::debug!("JOIN_SESSION: Requesting file manifest from host {}", host_peer_id);
 
                                 let our_peer_id = cx.update(|cx| {
@@ -213,6 +212,8 @@ impl MultiplayerWindow {
                                                     return;
                                                 }
 
+                                                this.on_role_peer_joined(&joined_peer_id, profile.as_ref(), cx);
+
                                                 if let Some(session) = &mut this.active_session {
                                                     // Add raw peer_id if not already present
                                                     if !session.connected_users.contains(&joined_peer_id) {
@@ -261,23 +262,13 @@ impl MultiplayerWindow {
                                                 if let Some(profile) = profile {
                                                     if let Some(engine) = EngineContext::global() {
                                                         let _ = engine.update_multiuser(|mu| {
-                                                            if let Some(existing) = mu
-                                                                .participant_profiles
-                                                                .iter_mut()
-                                                                .find(|p| p.peer_id == profile.peer_id)
-                                                            {
-                                                                existing.display_name = profile.display_name.clone();
-                                                                existing.avatar_url = profile.avatar_url.clone();
-                                                                existing.github_login = profile.github_login.clone();
-                                                            } else {
-                                                                mu.participant_profiles.push(MultiuserParticipant {
-                                                                    peer_id: profile.peer_id.clone(),
-                                                                    display_name: profile.display_name.clone(),
-                                                                    avatar_url: profile.avatar_url.clone(),
-                                                                    github_login: profile.github_login.clone(),
-                                                                    ping_ms: None,
-                                                                });
-                                                            }
+                                                            mu.upsert_participant_profile(MultiuserParticipant {
+                                                                peer_id: profile.peer_id.clone(),
+                                                                display_name: profile.display_name.clone(),
+                                                                avatar_url: profile.avatar_url.clone(),
+                                                                github_login: profile.github_login.clone(),
+                                                                ping_ms: None,
+                                                            });
                                                         });
                                                         engine.notify_multiuser_changed();
                                                     }
@@ -288,9 +279,9 @@ impl MultiplayerWindow {
                                     ServerMessage::PeerLeft { peer_id: left_peer_id, .. } => {
                                         cx.update(|cx| {
                                             this.update(cx, |this, cx| {
+                                                this.on_role_peer_left(&left_peer_id);
                                                 if let Some(session) = &mut this.active_session {
                                                     session.connected_users.retain(|p| p != &left_peer_id);
-                                                    this.user_presences.retain(|p| p.peer_id != left_peer_id);
                                                     // Remove from replication system
                                                     let integration = ui::replication::MultiuserIntegration::new(cx);
                                                     integration.remove_user(&left_peer_id, cx);
@@ -356,6 +347,7 @@ impl MultiplayerWindow {
                                                     message,
                                                     timestamp,
                                                     is_self,
+                                                    is_system: false,
                                                 });
                                                 tracing::debug!("JOIN_SESSION: Chat messages now: {}", this.chat_messages.len());
                                                 cx.notify();
@@ -596,7 +588,15 @@ impl MultiplayerWindow {
                                             }
                                         }
                                     }
-                                    ServerMessage::FileChanged { path, kind, .. } => {
+                                    ServerMessage::FileChanged { from_peer_id, path, kind, .. } => {
+                                        let accepted = cx.update(|cx| {
+                                            this.update(cx, |this, cx| {
+                                                this.accept_remote_file_change(&from_peer_id, &path, cx)
+                                            })
+                                        }).unwrap_or(false);
+                                        if !accepted {
+                                            continue;
+                                        }
                                         let change_kind = match kind.as_str() {
                                             "created" => engine_fs::events::FsChangeKind::Created,
                                             "deleted" => engine_fs::events::FsChangeKind::Deleted,
@@ -774,6 +774,28 @@ impl MultiplayerWindow {
                                             }
                                         }
                                     }
+                                    ServerMessage::RoleAnnounce { from_peer_id, protocol_version, .. } => {
+                                        cx.update(|cx| {
+                                            this.update(cx, |this, cx| {
+                                                this.on_role_announce(&from_peer_id, protocol_version);
+                                                cx.notify();
+                                            });
+                                        });
+                                    }
+                                    ServerMessage::RoleChanged { from_peer_id, target_peer_id, role, .. } => {
+                                        cx.update(|cx| {
+                                            this.update(cx, |this, cx| {
+                                                this.on_role_changed(&from_peer_id, &target_peer_id, &role, cx);
+                                            });
+                                        });
+                                    }
+                                    ServerMessage::PermissionDenied { target_peer_id, operation, reason, .. } => {
+                                        cx.update(|cx| {
+                                            this.update(cx, |this, cx| {
+                                                this.on_permission_denied(&target_peer_id, &operation, &reason, cx);
+                                            });
+                                        });
+                                    }
                                     _ => {}
                                 }
 
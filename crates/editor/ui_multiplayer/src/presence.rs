@@ -3,6 +3,8 @@
 use crate::screen::MultiplayerWindow;
 use crate::utils::types::*;
 use engine_backend::subsystems::networking::multiuser::ClientMessage;
+use engine_state::session_roles::SessionAction;
+use engine_state::EngineContext;
 use gpui::*;
 
 impl MultiplayerWindow {
@@ -13,16 +15,17 @@ impl MultiplayerWindow {
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        // Check if we're the host
-        let is_host = self
-            .active_session
-            .as_ref()
-            .and_then(|s| s.connected_users.first())
-            .map(|first_peer| Some(first_peer) == self.current_peer_id.as_ref())
-            .unwrap_or(false);
-
-        if !is_host {
-            tracing::warn!("Only host can kick users");
+        // Check our role allows kicking this participant
+        if let (Some(roles), Some(our_peer_id)) = (&self.session_roles, &self.current_peer_id) {
+            let action = SessionAction::Kick {
+                target_role: roles.role_of(&peer_id),
+            };
+            if let Err(e) = roles.check(our_peer_id, &action) {
+                tracing::warn!("Cannot kick {}: {}", peer_id, e);
+                return;
+            }
+        } else {
+            tracing::warn!("Cannot kick users outside a session");
             return;
         }
 
@@ -60,7 +63,9 @@ impl MultiplayerWindow {
                             session.connected_users.retain(|p| p != &peer_id);
                         }
                         // Remove presence
-                        this.user_presences.retain(|p| p.peer_id != peer_id);
+                        if let Some(ctx) = EngineContext::global() {
+                            ctx.update_multiuser(|mu| mu.remove_participant(&peer_id));
+                        }
                         cx.notify();
                     });
                 });
@@ -112,7 +117,8 @@ impl MultiplayerWindow {
         }
     }
 
-    /// Update our own presence to broadcast to others
+    /// Update our own presence in the engine's participant list. This is
+    /// local to this editor; peers aren't sent it.
     pub(super) fn update_own_presence(
         &mut self,
         tab: Option<String>,
@@ -120,17 +126,16 @@ impl MultiplayerWindow {
         cx: &mut Context<Self>,
     ) {
         if let Some(our_peer_id) = self.current_peer_id.clone() {
+            // The tab is local UI state; the edited file is engine presence
+            // and comes back as a participant event
             if let Some(presence) = self.get_presence_mut(&our_peer_id) {
                 presence.current_tab = tab;
-                presence.editing_file = editing_file;
-                presence.last_activity = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs();
                 presence.is_idle = false;
-
-                // TODO: Send presence update to server
-                // This would broadcast our current state to all peers
+            }
+            if let Some(ctx) = EngineContext::global() {
+                ctx.update_participant(&our_peer_id, |info| {
+                    info.focused_path = editing_file.map(Into::into);
+                });
             }
         }
         cx.notify();
@@ -0,0 +1,416 @@
+//! Session roles: assignment, enforcement and the session timeline
+//!
+//! The decision logic lives in `engine_state::session_roles`; this module wires
+//! it to the relay protocol. Every client keeps its own [`SessionRoles`] and
+//! validates role changes it receives, so a client can't grant itself rights.
+
+use gpui::*;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::screen::MultiplayerWindow;
+use crate::utils::types::*;
+use engine_backend::subsystems::networking::multiuser::{ClientMessage, PeerProfile};
+use engine_state::session_roles::{
+    Capability, RoleChange, SessionAction, SessionRole, SessionRoles, TeamRoster,
+    ROLE_PROTOCOL_VERSION,
+};
+use engine_state::EngineContext;
+
+impl MultiplayerWindow {
+    /// Set up role tracking once the server has confirmed our join.
+    ///
+    /// The host is whoever the relay's session record names, never whoever
+    /// happens to be first in the participant list. Everyone else starts with
+    /// the default role until the host broadcasts their actual one.
+    pub(super) fn init_session_roles(
+        &mut self,
+        our_peer_id: &str,
+        host_peer_id: Option<&str>,
+        participants: &[String],
+    ) {
+        let is_host = host_peer_id == Some(our_peer_id);
+
+        self.team_roster = if is_host {
+            self.project_root
+                .as_deref()
+                .map(|root| {
+                    TeamRoster::load(root).unwrap_or_else(|e| {
+                        tracing::warn!("Failed to load team roster: {}", e);
+                        TeamRoster::default()
+                    })
+                })
+                .unwrap_or_default()
+        } else {
+            TeamRoster::default()
+        };
+
+        let mut roles = match host_peer_id {
+            Some(host_peer_id) => SessionRoles::new(self.team_roster.role_table(), host_peer_id),
+            None => SessionRoles::without_host(self.team_roster.role_table()),
+        };
+        for peer_id in participants {
+            roles.join(peer_id.clone(), SessionRole::Editor);
+        }
+        roles.mark_role_aware(our_peer_id);
+        self.session_roles = Some(roles);
+        self.role_notice = None;
+    }
+
+    pub(super) fn clear_session_roles(&mut self) {
+        self.session_roles = None;
+        self.role_notice = None;
+    }
+
+    pub(crate) fn local_can(&self, capability: Capability) -> bool {
+        match (&self.session_roles, &self.current_peer_id) {
+            (Some(roles), Some(peer_id)) => roles.can(peer_id, capability),
+            _ => false,
+        }
+    }
+
+    pub(crate) fn is_local_host(&self) -> bool {
+        match (&self.session_roles, &self.current_peer_id) {
+            (Some(roles), Some(peer_id)) => roles.host_peer_id() == Some(peer_id.as_str()),
+            _ => false,
+        }
+    }
+
+    /// Tell the other participants that this client enforces roles.
+    pub(super) fn announce_role_support(&self, cx: &mut Context<Self>) {
+        let (Some(session), Some(peer_id)) = (&self.active_session, &self.current_peer_id) else {
+            return;
+        };
+        self.send_role_message(
+            ClientMessage::RoleAnnounce {
+                session_id: session.session_id.clone(),
+                peer_id: peer_id.clone(),
+                protocol_version: ROLE_PROTOCOL_VERSION,
+            },
+            cx,
+        );
+    }
+
+    pub(super) fn on_role_peer_joined(
+        &mut self,
+        peer_id: &str,
+        profile: Option<&PeerProfile>,
+        cx: &mut Context<Self>,
+    ) {
+        let is_host = self.is_local_host();
+        let default_role = if is_host {
+            self.team_roster.resolve(
+                profile.and_then(|p| p.github_login.as_deref()),
+                profile.and_then(|p| p.display_name.as_deref()),
+            )
+        } else {
+            SessionRole::Editor
+        };
+        let Some(roles) = self.session_roles.as_mut() else {
+            return;
+        };
+        roles.join(peer_id.to_string(), default_role);
+
+        // The newcomer only learns who is role-aware from announcements sent
+        // after it joined
+        self.announce_role_support(cx);
+
+        if is_host {
+            self.broadcast_role_snapshot(peer_id, cx);
+        }
+    }
+
+    pub(super) fn on_role_peer_left(&mut self, peer_id: &str) {
+        if let Some(roles) = self.session_roles.as_mut() {
+            roles.leave(peer_id);
+        }
+    }
+
+    pub(super) fn on_role_announce(&mut self, from_peer_id: &str, protocol_version: u32) {
+        tracing::debug!(
+            "Peer {} supports roles (protocol v{})",
+            from_peer_id,
+            protocol_version
+        );
+        if let Some(roles) = self.session_roles.as_mut() {
+            roles.mark_role_aware(from_peer_id);
+        }
+    }
+
+    /// Change `target`'s role from the participant list.
+    pub(crate) fn request_role_change(
+        &mut self,
+        target: String,
+        role: SessionRole,
+        cx: &mut Context<Self>,
+    ) {
+        let (Some(session), Some(our_peer_id)) = (&self.active_session, &self.current_peer_id)
+        else {
+            return;
+        };
+        let session_id = session.session_id.clone();
+        let our_peer_id = our_peer_id.clone();
+        let Some(roles) = self.session_roles.as_mut() else {
+            return;
+        };
+
+        let result = if role == SessionRole::Host {
+            roles.transfer_host(&our_peer_id, &target)
+        } else {
+            roles
+                .assign(&our_peer_id, &target, role.clone())
+                .map(|c| vec![c])
+        };
+        let changes = match result {
+            Ok(changes) => changes,
+            Err(e) => {
+                tracing::warn!("Role change rejected: {}", e);
+                self.role_notice = Some(e.to_string());
+                cx.notify();
+                return;
+            }
+        };
+
+        self.send_role_message(
+            ClientMessage::SetRole {
+                session_id,
+                peer_id: our_peer_id,
+                target_peer_id: target,
+                role: role.as_str().to_string(),
+            },
+            cx,
+        );
+        for change in &changes {
+            self.remember_role(change);
+            self.log_role_change(change);
+        }
+        self.sync_engine_multiuser_role();
+        cx.notify();
+    }
+
+    pub(super) fn on_role_changed(
+        &mut self,
+        from_peer_id: &str,
+        target_peer_id: &str,
+        role: &str,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(roles) = self.session_roles.as_mut() else {
+            return;
+        };
+        let previous_host = roles.host_peer_id().map(str::to_owned);
+        let role = SessionRole::parse(role);
+        match roles.apply_remote(from_peer_id, target_peer_id, role) {
+            Ok(change) => {
+                if change.from == change.to {
+                    return;
+                }
+                if change.to == SessionRole::Host {
+                    if let Some(previous_host) = previous_host {
+                        self.log_role_change(&RoleChange {
+                            peer_id: previous_host,
+                            from: SessionRole::Host,
+                            to: SessionRole::Editor,
+                            changed_by: from_peer_id.to_string(),
+                        });
+                    }
+                }
+                self.log_role_change(&change);
+                self.sync_engine_multiuser_role();
+                cx.notify();
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Ignoring role change for {} from {}: {}",
+                    target_peer_id,
+                    from_peer_id,
+                    e
+                );
+            }
+        }
+    }
+
+    pub(super) fn on_permission_denied(
+        &mut self,
+        target_peer_id: &str,
+        operation: &str,
+        reason: &str,
+        cx: &mut Context<Self>,
+    ) {
+        if self.current_peer_id.as_deref() != Some(target_peer_id) {
+            return;
+        }
+        tracing::warn!("Host rejected {}: {}", operation, reason);
+        self.role_notice = Some(format!("Host rejected {}: {}", operation, reason));
+        cx.notify();
+    }
+
+    /// Whether a file change relayed from `from_peer_id` should be applied.
+    ///
+    /// Every client drops changes from participants without edit rights; the
+    /// host also tells the sender so a misbehaving client notices.
+    pub(super) fn accept_remote_file_change(
+        &mut self,
+        from_peer_id: &str,
+        path: &str,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.current_peer_id.as_deref() == Some(from_peer_id) {
+            return true;
+        }
+        let Some(roles) = self.session_roles.as_ref() else {
+            return true;
+        };
+        let Err(e) = roles.check(from_peer_id, &SessionAction::EditFiles) else {
+            return true;
+        };
+
+        tracing::warn!("Rejected change to {} from {}: {}", path, from_peer_id, e);
+        if self.is_local_host() {
+            if let (Some(session), Some(our_peer_id)) =
+                (&self.active_session, &self.current_peer_id)
+            {
+                self.send_role_message(
+                    ClientMessage::PermissionDenied {
+                        session_id: session.session_id.clone(),
+                        peer_id: our_peer_id.clone(),
+                        target_peer_id: from_peer_id.to_string(),
+                        operation: format!("change to {}", path),
+                        reason: e.to_string(),
+                    },
+                    cx,
+                );
+            }
+            self.push_timeline_entry(format!(
+                "Rejected change to {} from {}: {}",
+                path,
+                Self::shorten_peer_id(from_peer_id),
+                e
+            ));
+            cx.notify();
+        }
+        false
+    }
+
+    /// Send every non-default role so `newcomer` sees the same assignments,
+    /// including its own.
+    fn broadcast_role_snapshot(&self, newcomer: &str, cx: &mut Context<Self>) {
+        let (Some(session), Some(our_peer_id), Some(roles)) = (
+            &self.active_session,
+            &self.current_peer_id,
+            &self.session_roles,
+        ) else {
+            return;
+        };
+        let mut peers = session.connected_users.clone();
+        if !peers.iter().any(|p| p == newcomer) {
+            peers.push(newcomer.to_string());
+        }
+        for peer_id in &peers {
+            let role = roles.role_of(peer_id);
+            if role == SessionRole::Host || role == SessionRole::Editor {
+                continue;
+            }
+            self.send_role_message(
+                ClientMessage::SetRole {
+                    session_id: session.session_id.clone(),
+                    peer_id: our_peer_id.clone(),
+                    target_peer_id: peer_id.clone(),
+                    role: role.as_str().to_string(),
+                },
+                cx,
+            );
+        }
+    }
+
+    /// Store a host's role decision as the participant's default for this project.
+    fn remember_role(&mut self, change: &RoleChange) {
+        if !self.is_local_host() || change.to == SessionRole::Host {
+            return;
+        }
+        let Some(project_root) = self.project_root.clone() else {
+            return;
+        };
+        let profile = EngineContext::global()
+            .and_then(|ctx| ctx.multiuser())
+            .and_then(|mu| {
+                mu.participant_profiles
+                    .into_iter()
+                    .find(|p| p.peer_id == change.peer_id)
+            });
+        let Some(profile) = profile else {
+            return;
+        };
+        self.team_roster.remember(
+            profile.github_login.as_deref(),
+            profile.display_name.as_deref(),
+            change.to.clone(),
+        );
+        if let Err(e) = self.team_roster.save(&project_root) {
+            tracing::warn!("Failed to save team roster: {}", e);
+        }
+    }
+
+    fn log_role_change(&mut self, change: &RoleChange) {
+        let name = |peer_id: &str| {
+            if self.current_peer_id.as_deref() == Some(peer_id) {
+                "You".to_string()
+            } else {
+                Self::shorten_peer_id(peer_id)
+            }
+        };
+        let entry = format!(
+            "{} changed {} from {} to {}",
+            name(&change.changed_by),
+            name(&change.peer_id),
+            change.from,
+            change.to
+        );
+        self.push_timeline_entry(entry);
+    }
+
+    fn push_timeline_entry(&mut self, message: String) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self.chat_messages.push(ChatMessage {
+            peer_id: String::new(),
+            message,
+            timestamp,
+            is_self: false,
+            is_system: true,
+        });
+    }
+
+    /// Publish our role so editors open read-only when we can't edit.
+    fn sync_engine_multiuser_role(&self) {
+        let (Some(ctx), Some(roles), Some(our_peer_id)) = (
+            EngineContext::global(),
+            &self.session_roles,
+            &self.current_peer_id,
+        ) else {
+            return;
+        };
+        let local_role = roles.role_of(our_peer_id);
+        let table = roles.table.clone();
+        if ctx.update_multiuser(|mu| {
+            mu.local_role = local_role;
+            mu.role_table = table;
+        }) {
+            ctx.notify_multiuser_changed();
+        }
+    }
+
+    fn send_role_message(&self, message: ClientMessage, cx: &mut Context<Self>) {
+        let Some(client) = self.client.clone() else {
+            return;
+        };
+        cx.spawn(async move |_this, _cx| {
+            let client_guard = client.read().await;
+            if let Err(e) = client_guard.send(message).await {
+                tracing::error!("Failed to send role message: {}", e);
+            }
+        })
+        .detach();
+    }
+}
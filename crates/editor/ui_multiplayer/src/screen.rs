@@ -16,7 +16,10 @@ use engine_fs::{
     events::{FsChangeKind, FsEventSource},
     subscribe,
 };
-use engine_state::{EngineContext, MultiuserContext, MultiuserParticipant, MultiuserStatus};
+use engine_state::{
+    EngineContext, MultiuserContext, MultiuserParticipant, MultiuserStatus, ParticipantEvent,
+    SessionRoles, TeamRoster,
+};
 
 use crate::components::{render_active_session, render_chat_tab, render_connection_form};
 
@@ -32,7 +35,10 @@ pub struct MultiplayerWindow {
     pub(crate) current_tab: SessionTab,
     pub(crate) chat_messages: Vec<ChatMessage>,
     pub(crate) file_assets: Vec<FileAssetStatus>,
+    /// Presence indicators, kept in step with the engine's participant
+    /// events by `_participant_events`
     pub(crate) user_presences: Vec<UserPresence>,
+    pub(crate) _participant_events: Option<Task<()>>,
     pub(crate) focus_handle: FocusHandle,
     pub(crate) project_root: Option<PathBuf>,
     pub(crate) pending_file_sync: Option<(SyncDiff, String)>,
@@ -43,6 +49,10 @@ pub struct MultiplayerWindow {
     pub(crate) pending_diff_populate: Option<SyncDiff>,
     pub(crate) pending_file_updates: Vec<(String, String)>,
     pub(crate) fs_event_forwarder: Option<gpui::Task<()>>,
+    pub(crate) session_roles: Option<SessionRoles>,
+    pub(crate) team_roster: TeamRoster,
+    /// Last role-related rejection shown to the user (e.g. from the host).
+    pub(crate) role_notice: Option<String>,
 }
 
 impl MultiplayerWindow {
@@ -77,6 +87,17 @@ impl MultiplayerWindow {
 
         let project_root = project_path;
         let diff_viewer = cx.new(DiffViewer::new);
+        let participant_events = Self::watch_participants(cx);
+        // Participants who joined before this window opened
+        let user_presences = EngineContext::global()
+            .and_then(|ctx| ctx.multiuser())
+            .map(|mu| {
+                mu.participant_info
+                    .iter()
+                    .map(UserPresence::from_participant)
+                    .collect()
+            })
+            .unwrap_or_default();
 
         Self {
             server_address_input,
@@ -90,7 +111,8 @@ impl MultiplayerWindow {
             current_tab: SessionTab::Info,
             chat_messages: Vec::new(),
             file_assets: Vec::new(),
-            user_presences: Vec::new(),
+            user_presences,
+            _participant_events: participant_events,
             focus_handle: cx.focus_handle(),
             project_root,
             pending_file_sync: None,
@@ -101,6 +123,9 @@ impl MultiplayerWindow {
             pending_diff_populate: None,
             pending_file_updates: Vec::new(),
             fs_event_forwarder: None,
+            session_roles: None,
+            team_roster: TeamRoster::default(),
+            role_notice: None,
         }
     }
 
@@ -136,6 +161,9 @@ impl MultiplayerWindow {
         ctx.notify_multiuser_changed();
     }
 
+    /// Publish the connected session to the engine context. An existing
+    /// context for the same session is updated in place, so participant
+    /// metadata and presence survive roster changes.
     pub(crate) fn sync_engine_multiuser_connected(
         &self,
         server_url: &str,
@@ -147,10 +175,40 @@ impl MultiplayerWindow {
             return;
         };
 
-        let host_peer_id = participants
-            .first()
-            .cloned()
-            .unwrap_or_else(|| our_peer_id.to_string());
+        // The roles track the host across transfers; participant order says
+        // nothing about who hosts
+        let host_peer_id = self
+            .session_roles
+            .as_ref()
+            .and_then(|roles| roles.host_peer_id())
+            .unwrap_or_default()
+            .to_string();
+
+        let same_session = ctx
+            .multiuser
+            .read()
+            .as_ref()
+            .is_some_and(|mu| mu.session_id == session_id && mu.peer_id == our_peer_id);
+        if same_session {
+            ctx.update_multiuser(|mu| {
+                if !mu.is_connected() {
+                    mu.set_status(MultiuserStatus::Connected { relay_mode: None });
+                }
+                mu.server_url = server_url.to_string();
+                mu.is_host = host_peer_id == our_peer_id;
+                mu.host_peer_id = host_peer_id;
+                mu.merge_participants(participants);
+                if let Some(active_session) = &self.active_session {
+                    mu.join_token = Some(active_session.join_token.clone());
+                }
+                if let Some(roles) = &self.session_roles {
+                    mu.local_role = roles.role_of(our_peer_id);
+                    mu.role_table = roles.table.clone();
+                }
+            });
+            ctx.notify_multiuser_changed();
+            return;
+        }
 
         let mut session = MultiuserContext::new_peer_to_peer(
             server_url.to_string(),
@@ -166,6 +224,10 @@ impl MultiplayerWindow {
         if let Some(active_session) = &self.active_session {
             session = session.with_join_token(active_session.join_token.clone());
         }
+        if let Some(roles) = &self.session_roles {
+            session.local_role = roles.role_of(our_peer_id);
+            session.role_table = roles.table.clone();
+        }
 
         ctx.set_multiuser(session);
         ctx.notify_multiuser_changed();
@@ -237,6 +299,14 @@ impl MultiplayerWindow {
                     continue;
                 }
 
+                // Reviewers don't push changes; the host would reject them anyway
+                let read_only = EngineContext::global()
+                    .and_then(|ctx| ctx.multiuser())
+                    .is_some_and(|mu| mu.is_read_only());
+                if read_only {
+                    continue;
+                }
+
                 let kind = match event.kind {
                     FsChangeKind::Created => "created",
                     FsChangeKind::Modified => "modified",
@@ -365,16 +435,34 @@ impl MultiplayerWindow {
         }
     }
 
-    pub(crate) fn update_presence_from_participants(&mut self, cx: &mut Context<Self>) {
-        if let Some(session) = &self.active_session {
-            for peer_id in &session.connected_users {
-                if !self.user_presences.iter().any(|p| &p.peer_id == peer_id) {
-                    self.user_presences.push(UserPresence::new(peer_id.clone()));
-                }
+    /// Drive `user_presences` from the engine's participant events.
+    fn watch_participants(cx: &mut Context<Self>) -> Option<Task<()>> {
+        let events = EngineContext::global()?.subscribe::<ParticipantEvent>();
+        Some(cx.spawn(async move |this, cx| {
+            while let Ok(event) = events.recv().await {
+                let Some(view) = this.upgrade() else {
+                    break;
+                };
+                let _ = cx.update(|cx| {
+                    view.update(cx, |view, cx| view.apply_participant_event(event, cx));
+                });
             }
+        }))
+    }
 
-            self.user_presences
-                .retain(|p| session.connected_users.contains(&p.peer_id));
+    fn apply_participant_event(&mut self, event: ParticipantEvent, cx: &mut Context<Self>) {
+        match event {
+            ParticipantEvent::Joined(info) | ParticipantEvent::Updated(info) => {
+                if let Some(presence) = self.get_presence_mut(&info.peer_id) {
+                    presence.apply(&info);
+                } else {
+                    let presence = UserPresence::from_participant(&info);
+                    self.user_presences.push(presence);
+                }
+            }
+            ParticipantEvent::Left { peer_id } => {
+                self.user_presences.retain(|p| p.peer_id != peer_id);
+            }
         }
         cx.notify();
     }
@@ -15,39 +15,25 @@ impl MultiplayerWindow {
             }
         };
 
-        let is_host = self
-            .active_session
-            .as_ref()
-            .map(|_s| participants.first() == Some(our_peer_id))
-            .unwrap_or(false);
-
         participants
             .iter()
-            .enumerate()
-            .map(|(index, p)| {
+            .map(|p| {
+                let role = match &self.session_roles {
+                    Some(roles) => roles.role_of(p).to_string(),
+                    None => "Guest".to_string(),
+                };
                 if p == our_peer_id {
                     // Current user
-                    if is_host {
-                        "You (Host)".to_string()
-                    } else {
-                        "You (Guest)".to_string()
-                    }
+                    format!("You ({})", role)
                 } else {
-                    // Other users - show their role and shortened ID
-                    let short_id = Self::shorten_peer_id(p);
-                    if index == 0 {
-                        // First participant is always the host
-                        format!("{} (Host)", short_id)
-                    } else {
-                        format!("{} (Guest)", short_id)
-                    }
+                    format!("{} ({})", Self::shorten_peer_id(p), role)
                 }
             })
             .collect()
     }
 
     /// Shorten a peer ID for display (show first 8 characters)
-    fn shorten_peer_id(peer_id: &str) -> String {
+    pub(super) fn shorten_peer_id(peer_id: &str) -> String {
         if peer_id.len() <= 8 {
             peer_id.to_string()
         } else {
@@ -85,6 +85,7 @@ impl MultiplayerWindow {
                                     participants,
                                     join_token,
                                     participant_profiles,
+                                    host_peer_id,
                                     ..
                                 }) => {
                                     cx.update(|cx| {
@@ -106,9 +107,6 @@ impl MultiplayerWindow {
                                                 }
                                             }
 
-                                            // Initialize presence for all participants
-                                            this.update_presence_from_participants(cx);
-
                                             // Log project root for host
                                             if let Some(project_root) = &this.project_root {
                                                 tracing::debug!("CREATE_SESSION: Project root at {:?}", project_root);
@@ -119,7 +117,8 @@ impl MultiplayerWindow {
 
                                             // Set up replication bridge
                                             let integration = ui::replication::MultiuserIntegration::new(cx);
-                                            let host_peer_id = participants.first().cloned().unwrap_or_else(|| peer_id.clone());
+                                            // We created the session, so we host it unless the relay says otherwise
+                                            let host_peer_id = host_peer_id.clone().unwrap_or_else(|| peer_id.clone());
 
                                             // Set up message sender
                                             let client_clone = client.clone();
@@ -127,7 +126,7 @@ impl MultiplayerWindow {
                                             let peer_id_clone = peer_id.clone();
                                             integration.start_session(
                                                 peer_id.clone(),
-                                                host_peer_id,
+                                                host_peer_id.clone(),
                                                 move |replication_msg| {
                                                     let client = client_clone.clone();
                                                     let session_id = session_id_clone.clone();
@@ -158,6 +157,7 @@ impl MultiplayerWindow {
                                             }
 
                                             tracing::debug!("CREATE_SESSION: Initialized replication bridge");
+                                            this.init_session_roles(&peer_id, Some(&host_peer_id), &participants);
                                             this.sync_engine_multiuser_connected(
                                                 &server_address,
                                                 &session_id,
@@ -170,6 +170,7 @@ impl MultiplayerWindow {
                                                 peer_id.clone(),
                                                 cx,
                                             );
+                                            this.announce_role_support(cx);
                                             if let Some(profiles) = participant_profiles {
                                                 this.sync_engine_multiuser_profiles(profiles);
                                             }
@@ -212,6 +213,8 @@ impl MultiplayerWindow {
                                                             return;
                                                         }
 
+                                                        this.on_role_peer_joined(&joined_peer_id, profile.as_ref(), cx);
+
                                                         if let Some(session) = &mut this.active_session {
                                                             // Add raw peer_id if not already present
                                                             if !session.connected_users.contains(&joined_peer_id) {
@@ -229,8 +232,6 @@ impl MultiplayerWindow {
                                                                 return;
                                                             }
 
-                                                            // Update presence
-                                                                this.update_presence_from_participants(cx);
                                                                 // Add to replication system
                                                                 let integration = ui::replication::MultiuserIntegration::new(cx);
                                                                 let color = Self::generate_user_color(&joined_peer_id);
@@ -268,23 +269,13 @@ impl MultiplayerWindow {
                                                         if let Some(profile) = profile {
                                                             if let Some(engine) = EngineContext::global() {
                                                                 let _ = engine.update_multiuser(|mu| {
-                                                                    if let Some(existing) = mu
-                                                                        .participant_profiles
-                                                                        .iter_mut()
-                                                                        .find(|p| p.peer_id == profile.peer_id)
-                                                                    {
-                                                                        existing.display_name = profile.display_name.clone();
-                                                                        existing.avatar_url = profile.avatar_url.clone();
-                                                                        existing.github_login = profile.github_login.clone();
-                                                                    } else {
-                                                                        mu.participant_profiles.push(MultiuserParticipant {
-                                                                            peer_id: profile.peer_id.clone(),
-                                                                            display_name: profile.display_name.clone(),
-                                                                            avatar_url: profile.avatar_url.clone(),
-                                                                            github_login: profile.github_login.clone(),
-                                                                            ping_ms: None,
-                                                                        });
-                                                                    }
+                                                                    mu.upsert_participant_profile(MultiuserParticipant {
+                                                                        peer_id: profile.peer_id.clone(),
+                                                                        display_name: profile.display_name.clone(),
+                                                                        avatar_url: profile.avatar_url.clone(),
+                                                                        github_login: profile.github_login.clone(),
+                                                                        ping_ms: None,
+                                                                    });
                                                                 });
                                                                 engine.notify_multiuser_changed();
                                                             }
@@ -295,9 +286,9 @@ impl MultiplayerWindow {
                                             ServerMessage::PeerLeft { peer_id: left_peer_id, .. } => {
                                                 cx.update(|cx| {
                                                     this.update(cx, |this, cx| {
+                                                        this.on_role_peer_left(&left_peer_id);
                                                         if let Some(session) = &mut this.active_session {
                                                             session.connected_users.retain(|p| p != &left_peer_id);
-                                                            this.user_presences.retain(|p| p.peer_id != left_peer_id);
                                                             // Remove from replication system
                                                             let integration = ui::replication::MultiuserIntegration::new(cx);
                                                             integration.remove_user(&left_peer_id, cx);
@@ -363,6 +354,7 @@ impl MultiplayerWindow {
                                                             message,
                                                             timestamp,
                                                             is_self,
+                                                            is_system: false,
                                                         });
                                                         tracing::debug!("CREATE_SESSION: Chat messages now: {}", this.chat_messages.len());
                                                         cx.notify();
@@ -382,7 +374,15 @@ impl MultiplayerWindow {
                                                     });
                                                 });
                                             }
-                                            ServerMessage::FileChanged { path, kind, .. } => {
+                                            ServerMessage::FileChanged { from_peer_id, path, kind, .. } => {
+                                                let accepted = cx.update(|cx| {
+                                                    this.update(cx, |this, cx| {
+                                                        this.accept_remote_file_change(&from_peer_id, &path, cx)
+                                                    })
+                                                }).unwrap_or(false);
+                                                if !accepted {
+                                                    continue;
+                                                }
                                                 let change_kind = match kind.as_str() {
                                                     "created" => engine_fs::events::FsChangeKind::Created,
                                                     "deleted" => engine_fs::events::FsChangeKind::Deleted,
@@ -569,6 +569,28 @@ impl MultiplayerWindow {
                                                     }
                                                 }
                                             }
+                                            ServerMessage::RoleAnnounce { from_peer_id, protocol_version, .. } => {
+                                                cx.update(|cx| {
+                                                    this.update(cx, |this, cx| {
+                                                        this.on_role_announce(&from_peer_id, protocol_version);
+                                                        cx.notify();
+                                                    });
+                                                });
+                                            }
+                                            ServerMessage::RoleChanged { from_peer_id, target_peer_id, role, .. } => {
+                                                cx.update(|cx| {
+                                                    this.update(cx, |this, cx| {
+                                                        this.on_role_changed(&from_peer_id, &target_peer_id, &role, cx);
+                                                    });
+                                                });
+                                            }
+                                            ServerMessage::PermissionDenied { target_peer_id, operation, reason, .. } => {
+                                                cx.update(|cx| {
+                                                    this.update(cx, |this, cx| {
+                                                        this.on_permission_denied(&target_peer_id, &operation, &reason, cx);
+                                                    });
+                                                });
+                                            }
                                             _ => {}
                                         }
 
@@ -1,20 +0,0 @@
-use std::time::{SystemTime, UNIX_EPOCH};
-
-pub fn format_timestamp(timestamp: u64) -> String {
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
-
-    let diff = now.saturating_sub(timestamp);
-
-    if diff < 60 {
-        "Just now".to_string()
-    } else if diff < 3600 {
-        format!("{}m ago", diff / 60)
-    } else if diff < 86400 {
-        format!("{}h ago", diff / 3600)
-    } else {
-        format!("{}d ago", diff / 86400)
-    }
-}
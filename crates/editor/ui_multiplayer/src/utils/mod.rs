@@ -1,2 +1 @@
-pub mod format;
 pub mod types;
@@ -1,4 +1,5 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use engine_state::ParticipantInfo;
+use std::time::UNIX_EPOCH;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum ConnectionStatus {
@@ -30,6 +31,8 @@ pub struct ChatMessage {
     pub message: String,
     pub timestamp: u64,
     pub is_self: bool,
+    /// Session timeline entry (role changes, rejected operations) rather than chat.
+    pub is_system: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -58,6 +61,7 @@ pub struct FileAssetStatus {
 #[derive(Clone, Debug)]
 pub struct UserPresence {
     pub peer_id: String,
+    pub display_name: String,
     pub current_tab: Option<String>,
     pub editing_file: Option<String>,
     pub selected_object: Option<String>,
@@ -68,29 +72,35 @@ pub struct UserPresence {
 }
 
 impl UserPresence {
-    pub fn new(peer_id: String) -> Self {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-
-        let hash = peer_id
-            .bytes()
-            .fold(0u32, |acc, b| acc.wrapping_mul(31).wrapping_add(b as u32));
-        let r = ((hash & 0xFF) as f32) / 255.0;
-        let g = (((hash >> 8) & 0xFF) as f32) / 255.0;
-        let b = (((hash >> 16) & 0xFF) as f32) / 255.0;
-
-        Self {
-            peer_id,
+    pub fn from_participant(info: &ParticipantInfo) -> Self {
+        let mut presence = Self {
+            peer_id: info.peer_id.clone(),
+            display_name: String::new(),
             current_tab: None,
             editing_file: None,
             selected_object: None,
             cursor_position: None,
-            last_activity: now,
+            last_activity: 0,
             is_idle: false,
-            color: [r, g, b],
-        }
+            color: [0.0; 3],
+        };
+        presence.apply(info);
+        presence
+    }
+
+    /// Take the participant's latest engine-side presence
+    pub fn apply(&mut self, info: &ParticipantInfo) {
+        self.display_name = info.display_name.clone();
+        self.color = info.color;
+        self.editing_file = info
+            .focused_path
+            .as_ref()
+            .map(|path| path.to_string_lossy().replace('\\', "/"));
+        self.last_activity = info
+            .last_seen
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
     }
 
     pub fn activity_status(&self) -> &str {
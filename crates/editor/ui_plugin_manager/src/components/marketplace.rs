@@ -0,0 +1,296 @@
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use plugin_manager::marketplace::{IndexPlugin, InstallScope};
+use ui::Sizable;
+use ui::{
+    button::{Button, ButtonVariants as _},
+    h_flex, v_flex, ActiveTheme as _, Icon, IconName, StyledExt,
+};
+use ui_common::{format_bytes, RelativeTime};
+
+use crate::marketplace::{IndexStatus, InstallState, MarketplaceState};
+use crate::screen::PluginManagerWindow;
+
+pub fn render_marketplace_section(
+    state: &MarketplaceState,
+    cx: &mut Context<PluginManagerWindow>,
+) -> impl IntoElement {
+    let scope = state.scope;
+
+    v_flex()
+        .w_full()
+        .gap_3()
+        .child(
+            h_flex()
+                .w_full()
+                .items_center()
+                .gap_2()
+                .child(
+                    div()
+                        .text_base()
+                        .font_semibold()
+                        .text_color(cx.theme().foreground)
+                        .child("Marketplace"),
+                )
+                .child(div().flex_1())
+                .child(
+                    Button::new("scope-global")
+                        .label("All Projects")
+                        .xsmall()
+                        .ghost()
+                        .selected(scope == InstallScope::Global)
+                        .on_click(cx.listener(|this, _, _window, cx| {
+                            this.set_install_scope(InstallScope::Global, cx);
+                        })),
+                )
+                .child(
+                    Button::new("scope-project")
+                        .label("This Project")
+                        .xsmall()
+                        .ghost()
+                        .selected(scope == InstallScope::Project)
+                        .on_click(cx.listener(|this, _, _window, cx| {
+                            this.set_install_scope(InstallScope::Project, cx);
+                        })),
+                )
+                .child(
+                    Button::new("refresh-marketplace")
+                        .icon(IconName::Refresh)
+                        .ghost()
+                        .xsmall()
+                        .tooltip("Refresh Registry")
+                        .on_click(cx.listener(|this, _, _window, cx| {
+                            this.refresh_marketplace(cx);
+                        })),
+                ),
+        )
+        .when(state.restart_needed, |this| {
+            this.child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("Restart the editor to load installed plugins or unload removed ones."),
+            )
+        })
+        .child(match &state.index {
+            IndexStatus::NotConfigured => render_note(
+                "Set a plugin registry URL in Project Settings > Plugins to browse plugins.",
+                cx,
+            )
+            .into_any_element(),
+            IndexStatus::Loading => render_note("Loading plugin registry…", cx).into_any_element(),
+            IndexStatus::Failed(message) => h_flex()
+                .gap_2()
+                .items_center()
+                .child(
+                    Icon::new(IconName::TriangleAlert)
+                        .size(px(14.))
+                        .text_color(cx.theme().danger),
+                )
+                .child(
+                    div()
+                        .text_sm()
+                        .text_color(cx.theme().danger)
+                        .child(message.clone()),
+                )
+                .into_any_element(),
+            IndexStatus::Loaded(snapshot) => v_flex()
+                .w_full()
+                .gap_3()
+                .when_some(snapshot.offline.clone(), |this, reason| {
+                    this.child(
+                        h_flex()
+                            .gap_1()
+                            .items_center()
+                            .text_sm()
+                            .text_color(cx.theme().warning)
+                            .child(Icon::new(IconName::TriangleAlert).size(px(14.)))
+                            .child(format!("Registry unreachable ({}); list from", reason))
+                            .child(RelativeTime::new(snapshot.fetched_at)),
+                    )
+                })
+                .when(snapshot.index.plugins.is_empty(), |this| {
+                    this.child(render_note("The registry lists no plugins.", cx))
+                })
+                .children(
+                    snapshot
+                        .index
+                        .plugins
+                        .iter()
+                        .map(|plugin| render_marketplace_item(plugin, state, cx)),
+                )
+                .into_any_element(),
+        })
+}
+
+fn render_note(text: &'static str, cx: &mut Context<PluginManagerWindow>) -> impl IntoElement {
+    div()
+        .text_sm()
+        .text_color(cx.theme().muted_foreground)
+        .child(text)
+}
+
+fn render_marketplace_item(
+    plugin: &IndexPlugin,
+    state: &MarketplaceState,
+    cx: &mut Context<PluginManagerWindow>,
+) -> impl IntoElement {
+    let installed = state.installed.iter().find(|i| i.id == plugin.id);
+    let update = state.updates.iter().find(|u| u.id == plugin.id);
+    let install_state = state.installs.get(&plugin.id);
+    let downloading = matches!(install_state, Some(InstallState::Downloading(_)));
+    let latest = plugin
+        .versions
+        .iter()
+        .map(|v| v.version)
+        .max()
+        .map(|(major, minor, patch)| format!("{}.{}.{}", major, minor, patch))
+        .unwrap_or_default();
+    let version_label = match installed {
+        Some(installed) => format!("{} installed", installed.version),
+        None => latest,
+    };
+
+    let plugin_id = plugin.id.clone();
+    let actions =
+        h_flex()
+            .flex_shrink_0()
+            .gap_2()
+            .when(!downloading, |this| match (installed, update) {
+                (Some(_), update) => this
+                    .when_some(update, |this, update| {
+                        let (major, minor, patch) = update.available;
+                        let id = plugin_id.clone();
+                        this.child(
+                            Button::new(SharedString::from(format!("update-{}", plugin_id)))
+                                .label(format!("Update to {}.{}.{}", major, minor, patch))
+                                .icon(IconName::Download)
+                                .primary()
+                                .small()
+                                .on_click(cx.listener(move |this, _, _window, cx| {
+                                    this.install_plugin(id.clone(), cx);
+                                })),
+                        )
+                    })
+                    .child({
+                        let id = plugin_id.clone();
+                        Button::new(SharedString::from(format!("uninstall-{}", plugin_id)))
+                            .label("Uninstall")
+                            .icon(IconName::Trash)
+                            .danger()
+                            .small()
+                            .on_click(cx.listener(move |this, _, _window, cx| {
+                                this.uninstall_plugin(id.clone(), cx);
+                            }))
+                    }),
+                (None, _) => this.child({
+                    let id = plugin_id.clone();
+                    Button::new(SharedString::from(format!("install-{}", plugin_id)))
+                        .label("Install")
+                        .icon(IconName::Download)
+                        .primary()
+                        .small()
+                        .on_click(cx.listener(move |this, _, _window, cx| {
+                            this.install_plugin(id.clone(), cx);
+                        }))
+                }),
+            });
+
+    v_flex()
+        .w_full()
+        .p_4()
+        .gap_2()
+        .rounded_lg()
+        .border_1()
+        .border_color(cx.theme().border)
+        .bg(cx.theme().sidebar.opacity(0.5))
+        .child(
+            h_flex()
+                .w_full()
+                .gap_4()
+                .child(
+                    v_flex()
+                        .flex_1()
+                        .gap_1()
+                        .child(
+                            h_flex()
+                                .items_center()
+                                .gap_2()
+                                .child(
+                                    div()
+                                        .text_base()
+                                        .font_semibold()
+                                        .text_color(cx.theme().foreground)
+                                        .child(plugin.name.clone()),
+                                )
+                                .child(
+                                    div()
+                                        .px_2()
+                                        .py_px()
+                                        .rounded(px(4.))
+                                        .bg(cx.theme().muted.opacity(0.3))
+                                        .text_xs()
+                                        .font_family("monospace")
+                                        .text_color(cx.theme().muted_foreground)
+                                        .child(version_label),
+                                ),
+                        )
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(format!("by {}", plugin.publisher)),
+                        )
+                        .when(!plugin.description.is_empty(), |this| {
+                            this.child(
+                                div()
+                                    .text_sm()
+                                    .text_color(cx.theme().muted_foreground.opacity(0.8))
+                                    .child(plugin.description.clone()),
+                            )
+                        }),
+                )
+                .child(actions),
+        )
+        .when_some(install_state, |this, install_state| match install_state {
+            InstallState::Downloading(progress) => this.child(
+                v_flex()
+                    .w_full()
+                    .gap_1()
+                    .child(
+                        div()
+                            .w_full()
+                            .h(px(4.))
+                            .rounded_full()
+                            .bg(cx.theme().muted.opacity(0.3))
+                            .child(
+                                div()
+                                    .h_full()
+                                    .rounded_full()
+                                    .bg(cx.theme().primary)
+                                    .w(relative(progress.fraction())),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(if progress.total == 0 {
+                                "Starting download…".to_string()
+                            } else {
+                                format!(
+                                    "{} of {}",
+                                    format_bytes(progress.received),
+                                    format_bytes(progress.total)
+                                )
+                            }),
+                    ),
+            ),
+            InstallState::Failed(message) => this.child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().danger)
+                    .child(message.clone()),
+            ),
+        })
+}
@@ -1,5 +1,7 @@
 pub mod empty_state;
+pub mod marketplace;
 pub mod plugin_card;
 
 pub use empty_state::render_empty_state;
-pub use plugin_card::render_plugin_item;
+pub use marketplace::render_marketplace_section;
+pub use plugin_card::{render_inactive_plugin_item, render_plugin_item};
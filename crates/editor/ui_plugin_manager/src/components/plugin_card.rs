@@ -1,11 +1,14 @@
 use gpui::prelude::FluentBuilder;
 use gpui::*;
 use plugin_editor_api::PluginMetadata;
+use plugin_manager::{PluginState, PluginStateEntry};
 use ui::{
     button::{Button, ButtonVariants as _},
     h_flex, v_flex, ActiveTheme as _, Icon, IconName, StyledExt,
 };
 
+use crate::handlers;
+
 pub fn render_plugin_item(
     plugin: &PluginMetadata,
     cx: &mut Context<crate::screen::PluginManagerWindow>,
@@ -83,23 +86,86 @@ pub fn render_plugin_item(
                     )
                 }),
         )
+        .child(v_flex().flex_shrink_0().gap_2().justify_center().child({
+            let id_for_button = plugin_id.clone();
+            Button::new(SharedString::from(format!("disable-{}", plugin_id)))
+                .label("Disable")
+                .icon(IconName::CircleX)
+                .danger()
+                .on_click(cx.listener(move |this, _, window, cx| {
+                    handlers::set_plugin_enabled(this, &id_for_button, false, window, cx);
+                }))
+        }))
+}
+
+/// A plugin that is disabled or failed to load, with a button to enable it
+/// (or retry loading it).
+pub fn render_inactive_plugin_item(
+    entry: &PluginStateEntry,
+    cx: &mut Context<crate::screen::PluginManagerWindow>,
+) -> impl IntoElement {
+    let plugin_id = entry.plugin_id.clone();
+    let (status, button_label) = match &entry.state {
+        PluginState::FailedToLoad { message } => (format!("Failed to load: {}", message), "Retry"),
+        _ => ("Disabled".to_string(), "Enable"),
+    };
+    let failed = matches!(entry.state, PluginState::FailedToLoad { .. });
+
+    h_flex()
+        .w_full()
+        .p_4()
+        .gap_4()
+        .rounded_lg()
+        .border_1()
+        .border_color(cx.theme().border)
+        .bg(cx.theme().sidebar.opacity(0.2))
         .child(
-            v_flex()
+            div()
                 .flex_shrink_0()
-                .gap_2()
+                .size(px(48.))
+                .rounded_lg()
+                .bg(cx.theme().muted.opacity(0.1))
+                .border_1()
+                .border_color(cx.theme().border)
+                .flex()
+                .items_center()
                 .justify_center()
-                .child({
-                    let id_for_button = plugin_id.clone();
-                    Button::new(SharedString::from(format!("unload-{}", plugin_id)))
-                        .label("Unload")
-                        .icon(IconName::Trash)
-                        .danger()
-                        .on_click(cx.listener(move |_this, _, _window, _cx| {
-                            todo!(
-                                "Implement plugin unloading in PluginManager and call it here (plugin_id: {})",
-                                id_for_button
-                            );
-                        }))
-                }),
+                .child(
+                    Icon::new(IconName::Puzzle)
+                        .size(px(24.))
+                        .text_color(cx.theme().muted_foreground),
+                ),
+        )
+        .child(
+            v_flex()
+                .flex_1()
+                .gap_1()
+                .child(
+                    div()
+                        .text_base()
+                        .font_semibold()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(entry.name.clone()),
+                )
+                .child(
+                    div()
+                        .text_sm()
+                        .text_color(if failed {
+                            cx.theme().danger
+                        } else {
+                            cx.theme().muted_foreground
+                        })
+                        .child(status),
+                ),
         )
+        .child(v_flex().flex_shrink_0().gap_2().justify_center().child({
+            let id_for_button = plugin_id.clone();
+            Button::new(SharedString::from(format!("enable-{}", plugin_id)))
+                .label(button_label)
+                .icon(IconName::Check)
+                .primary()
+                .on_click(cx.listener(move |this, _, window, cx| {
+                    handlers::set_plugin_enabled(this, &id_for_button, true, window, cx);
+                }))
+        }))
 }
@@ -1,4 +1,6 @@
 use gpui::*;
+use plugin_editor_api::PluginId;
+use ui::{notification::Notification, ContextModal as _};
 
 use crate::screen::PluginManagerWindow;
 
@@ -10,3 +12,27 @@ pub fn on_refresh(
 ) {
     this.refresh(cx);
 }
+
+/// Enable or disable a plugin, then refresh the list.
+pub fn set_plugin_enabled(
+    this: &mut PluginManagerWindow,
+    plugin_id: &PluginId,
+    enabled: bool,
+    window: &mut Window,
+    cx: &mut Context<PluginManagerWindow>,
+) {
+    let Some(pm_lock) = plugin_manager::global() else {
+        return;
+    };
+    let result = pm_lock.write().set_plugin_enabled(plugin_id, enabled, cx);
+    if let Err(e) = result {
+        tracing::error!("Failed to change plugin {}: {}", plugin_id, e);
+        let title = if enabled {
+            "Failed to Enable Plugin"
+        } else {
+            "Failed to Disable Plugin"
+        };
+        window.push_notification(Notification::error(title).message(e.to_string()), cx);
+    }
+    this.refresh(cx);
+}
@@ -1,5 +1,6 @@
 mod components;
 mod handlers;
+mod marketplace;
 mod screen;
 
 pub use screen::PluginManagerWindow;
@@ -0,0 +1,248 @@
+//! Registry browsing for the plugin manager window.
+//!
+//! Registry calls block, so each one runs on its own thread and reports
+//! back over a channel. The registry URL and install location come from the
+//! project's Plugins settings.
+
+use gpui::*;
+use plugin_manager::marketplace::{
+    self, DownloadProgress, HttpTransport, IndexSnapshot, InstallScope, InstalledPlugin,
+    Marketplace, MarketplaceError, PluginUpdate,
+};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::screen::PluginManagerWindow;
+
+const SETTINGS_OWNER: &str = "plugins";
+
+/// Minimum time between progress updates sent to the UI.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+pub(crate) enum IndexStatus {
+    /// No registry URL is set
+    NotConfigured,
+    Loading,
+    Loaded(IndexSnapshot),
+    Failed(String),
+}
+
+pub(crate) enum InstallState {
+    Downloading(DownloadProgress),
+    Failed(String),
+}
+
+enum InstallMsg {
+    Progress(DownloadProgress),
+    Done(Result<InstalledPlugin, MarketplaceError>),
+}
+
+pub(crate) struct MarketplaceState {
+    pub(crate) index: IndexStatus,
+    pub(crate) scope: InstallScope,
+    /// Marketplace installs in the directory for `scope`
+    pub(crate) installed: Vec<InstalledPlugin>,
+    pub(crate) updates: Vec<PluginUpdate>,
+    /// Installs in flight or failed, by plugin ID
+    pub(crate) installs: HashMap<String, InstallState>,
+    /// Plugins were installed or removed since the editor started
+    pub(crate) restart_needed: bool,
+}
+
+impl MarketplaceState {
+    pub(crate) fn new() -> Self {
+        let scope = match setting("install_scope").as_deref() {
+            Some("project") => InstallScope::Project,
+            _ => InstallScope::Global,
+        };
+        Self {
+            index: IndexStatus::NotConfigured,
+            scope,
+            installed: Vec::new(),
+            updates: Vec::new(),
+            installs: HashMap::new(),
+            restart_needed: false,
+        }
+    }
+
+    /// Directory to install into, if the scope has one.
+    pub(crate) fn install_dir(&self) -> Option<PathBuf> {
+        let project = engine_state::get_project_path().map(PathBuf::from);
+        self.scope.dir(project.as_deref())
+    }
+}
+
+fn setting(key: &str) -> Option<String> {
+    engine_state::global_config()
+        .get(engine_state::NS_PROJECT, SETTINGS_OWNER, key)
+        .ok()
+        .and_then(|value| value.as_str().ok().map(str::to_owned))
+}
+
+fn registry() -> Option<Marketplace> {
+    let url = setting("registry_url").filter(|url| !url.trim().is_empty())?;
+    Some(Marketplace::new(
+        Arc::new(HttpTransport::new()),
+        url.trim(),
+        marketplace::default_cache_dir(),
+    ))
+}
+
+impl PluginManagerWindow {
+    /// Fetch the registry index in the background.
+    pub(crate) fn refresh_marketplace(&mut self, cx: &mut Context<Self>) {
+        let Some(registry) = registry() else {
+            self.marketplace.index = IndexStatus::NotConfigured;
+            self.reload_installed();
+            cx.notify();
+            return;
+        };
+        self.marketplace.index = IndexStatus::Loading;
+        cx.notify();
+
+        let (tx, rx) = smol::channel::bounded(1);
+        std::thread::spawn(move || {
+            let _ = tx.send_blocking(registry.fetch_index());
+        });
+        cx.spawn(async move |this, cx| {
+            let Ok(result) = rx.recv().await else {
+                return;
+            };
+            let _ = cx.update(|cx| {
+                let _ = this.update(cx, |view, cx| {
+                    view.marketplace.index = match result {
+                        Ok(snapshot) => IndexStatus::Loaded(snapshot),
+                        Err(e) => {
+                            tracing::warn!("Plugin registry unavailable: {}", e);
+                            IndexStatus::Failed(e.to_string())
+                        }
+                    };
+                    view.reload_installed();
+                    cx.notify();
+                });
+            });
+        })
+        .detach();
+    }
+
+    /// Re-read the installs in the current scope's directory and check them
+    /// for updates.
+    pub(crate) fn reload_installed(&mut self) {
+        let state = &mut self.marketplace;
+        state.installed.clear();
+        state.updates.clear();
+        let Some(dir) = state.install_dir() else {
+            return;
+        };
+        match marketplace::installed(&dir) {
+            Ok(installed) => state.installed = installed,
+            Err(e) => tracing::warn!("Failed to read installed plugins: {}", e),
+        }
+        if let (IndexStatus::Loaded(snapshot), Some(registry)) = (&state.index, registry()) {
+            match registry.check_updates(&snapshot.index, &dir) {
+                Ok(updates) => state.updates = updates,
+                Err(e) => tracing::warn!("Plugin update check failed: {}", e),
+            }
+        }
+    }
+
+    pub(crate) fn set_install_scope(&mut self, scope: InstallScope, cx: &mut Context<Self>) {
+        self.marketplace.scope = scope;
+        self.reload_installed();
+        cx.notify();
+    }
+
+    /// Install, or update to, the best build of `plugin_id`.
+    pub(crate) fn install_plugin(&mut self, plugin_id: String, cx: &mut Context<Self>) {
+        let IndexStatus::Loaded(snapshot) = &self.marketplace.index else {
+            return;
+        };
+        let Some(plugin) = snapshot.index.plugin(&plugin_id).cloned() else {
+            return;
+        };
+        let (Some(registry), Some(dir)) = (registry(), self.marketplace.install_dir()) else {
+            self.marketplace.installs.insert(
+                plugin_id,
+                InstallState::Failed("Open a project to install plugins into it".to_string()),
+            );
+            cx.notify();
+            return;
+        };
+        self.marketplace.installs.insert(
+            plugin_id.clone(),
+            InstallState::Downloading(DownloadProgress {
+                received: 0,
+                total: 0,
+            }),
+        );
+        cx.notify();
+
+        let (tx, rx) = smol::channel::unbounded::<InstallMsg>();
+        std::thread::spawn(move || {
+            let mut last_sent = Instant::now();
+            let result = registry.install(&plugin, &dir, &mut |progress| {
+                if last_sent.elapsed() >= PROGRESS_INTERVAL {
+                    last_sent = Instant::now();
+                    let _ = tx.send_blocking(InstallMsg::Progress(progress));
+                }
+            });
+            let _ = tx.send_blocking(InstallMsg::Done(result));
+        });
+
+        cx.spawn(async move |this, cx| {
+            while let Ok(msg) = rx.recv().await {
+                let done = matches!(msg, InstallMsg::Done(_));
+                let _ = cx.update(|cx| {
+                    let _ = this.update(cx, |view, cx| {
+                        let state = &mut view.marketplace;
+                        match msg {
+                            InstallMsg::Progress(progress) => {
+                                state
+                                    .installs
+                                    .insert(plugin_id.clone(), InstallState::Downloading(progress));
+                            }
+                            InstallMsg::Done(Ok(_)) => {
+                                state.installs.remove(&plugin_id);
+                                state.restart_needed = true;
+                                view.reload_installed();
+                            }
+                            InstallMsg::Done(Err(e)) => {
+                                tracing::error!("{}", e);
+                                state
+                                    .installs
+                                    .insert(plugin_id.clone(), InstallState::Failed(e.to_string()));
+                            }
+                        }
+                        cx.notify();
+                    });
+                });
+                if done {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    pub(crate) fn uninstall_plugin(&mut self, plugin_id: String, cx: &mut Context<Self>) {
+        let Some(dir) = self.marketplace.install_dir() else {
+            return;
+        };
+        match marketplace::uninstall(&dir, &plugin_id) {
+            Ok(_) => {
+                self.marketplace.installs.remove(&plugin_id);
+                self.marketplace.restart_needed = true;
+            }
+            Err(e) => {
+                tracing::error!("{}", e);
+                self.marketplace
+                    .installs
+                    .insert(plugin_id, InstallState::Failed(e.to_string()));
+            }
+        }
+        self.reload_installed();
+        cx.notify();
+    }
+}
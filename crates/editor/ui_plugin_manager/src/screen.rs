@@ -1,37 +1,49 @@
 use gpui::prelude::FluentBuilder;
 use gpui::*;
 use plugin_editor_api::PluginMetadata;
+use plugin_manager::{PluginState, PluginStateEntry};
 use ui::Sizable;
 use ui::{
     button::{Button, ButtonVariants as _},
     h_flex, v_flex, ActiveTheme as _, Icon, IconName, StyledExt, TitleBar,
 };
 
-use crate::components::{render_empty_state, render_plugin_item};
+use crate::components::{
+    render_empty_state, render_inactive_plugin_item, render_marketplace_section, render_plugin_item,
+};
 use crate::handlers;
+use crate::marketplace::MarketplaceState;
 
 pub struct PluginManagerWindow {
     pub(crate) plugins: Vec<PluginMetadata>,
+    /// Plugins that are disabled or failed to load
+    pub(crate) inactive_plugins: Vec<PluginStateEntry>,
     pub(crate) focus_handle: FocusHandle,
+    pub(crate) marketplace: MarketplaceState,
 }
 
 impl PluginManagerWindow {
     pub fn new_global(cx: &mut Context<Self>) -> Self {
-        let plugins = if let Some(pm_lock) = plugin_manager::global() {
-            pm_lock.read().get_plugins().into_iter().cloned().collect()
-        } else {
-            Vec::new()
-        };
-
-        Self {
-            plugins,
+        let mut this = Self {
+            plugins: Vec::new(),
+            inactive_plugins: Vec::new(),
             focus_handle: cx.focus_handle(),
-        }
+            marketplace: MarketplaceState::new(),
+        };
+        this.refresh(cx);
+        this.refresh_marketplace(cx);
+        this
     }
 
     pub fn refresh(&mut self, cx: &mut Context<Self>) {
         if let Some(pm_lock) = plugin_manager::global() {
-            self.plugins = pm_lock.read().get_plugins().into_iter().cloned().collect();
+            let pm = pm_lock.read();
+            self.plugins = pm.get_plugins().into_iter().cloned().collect();
+            self.inactive_plugins = pm
+                .get_plugin_states()
+                .into_iter()
+                .filter(|entry| entry.state != PluginState::Enabled)
+                .collect();
         }
         cx.notify();
     }
@@ -45,7 +57,7 @@ impl Focusable for PluginManagerWindow {
 
 impl Render for PluginManagerWindow {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let has_plugins = !self.plugins.is_empty();
+        let has_plugins = !self.plugins.is_empty() || !self.inactive_plugins.is_empty();
 
         v_flex()
             .size_full()
@@ -79,21 +91,34 @@ impl Render for PluginManagerWindow {
                         ),
                 ),
             )
-            .child(if has_plugins {
+            .child(
                 v_flex()
+                    .id("plugin-manager-body")
                     .flex_1()
                     .w_full()
+                    .overflow_y_scroll()
                     .p_6()
-                    .gap_3()
-                    .children(
-                        self.plugins
-                            .iter()
-                            .map(|plugin| render_plugin_item(plugin, cx)),
-                    )
-                    .into_any_element()
-            } else {
-                render_empty_state(cx).into_any_element()
-            })
+                    .gap_6()
+                    .child(if has_plugins {
+                        v_flex()
+                            .w_full()
+                            .gap_3()
+                            .children(
+                                self.plugins
+                                    .iter()
+                                    .map(|plugin| render_plugin_item(plugin, cx)),
+                            )
+                            .children(
+                                self.inactive_plugins
+                                    .iter()
+                                    .map(|entry| render_inactive_plugin_item(entry, cx)),
+                            )
+                            .into_any_element()
+                    } else {
+                        render_empty_state(cx).into_any_element()
+                    })
+                    .child(render_marketplace_section(&self.marketplace, cx)),
+            )
     }
 }
 
@@ -15,7 +15,7 @@ use ui::{
     v_flex, ActiveTheme, Icon, IconName, Sizable, Theme, ThemeMode,
 };
 
-use crate::utils::config;
+use crate::utils::{config, plugin_settings};
 
 pub struct ModernSettingsScreen {
     pub(crate) focus_handle: FocusHandle,
@@ -135,7 +135,11 @@ impl ModernSettingsScreen {
             .icon(Icon::new(IconName::Folder))
             .groups(project_groups);
 
-        vec![ui_controls_page, editor_page, project_page]
+        let plugins_page = SettingPage::new("Plugins")
+            .icon(Icon::new(IconName::Puzzle))
+            .groups(plugin_settings::groups_for_plugins());
+
+        vec![ui_controls_page, editor_page, project_page, plugins_page]
     }
 }
 
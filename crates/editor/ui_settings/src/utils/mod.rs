@@ -1,2 +1,3 @@
 pub mod actions;
 pub mod config;
+pub mod plugin_settings;
@@ -0,0 +1,163 @@
+use gpui::{App, SharedString};
+use plugin_editor_api::PluginId;
+use serde_json::Value;
+use ui::setting::{NumberFieldOptions, SettingField, SettingGroup, SettingItem};
+
+/// One group per loaded plugin that declares a settings schema, sorted by
+/// plugin name.
+pub fn groups_for_plugins() -> Vec<SettingGroup> {
+    let Some(pm_lock) = plugin_manager::global() else {
+        return Vec::new();
+    };
+    let pm = pm_lock.read();
+
+    let mut plugins = pm.get_plugins();
+    plugins.sort_by(|a, b| a.name.cmp(&b.name));
+
+    plugins
+        .into_iter()
+        .filter_map(|metadata| {
+            let schema = pm.get_plugin_settings_schema(&metadata.id)?;
+            let items: Vec<SettingItem> = plugin_manager::schema_properties(schema)?
+                .iter()
+                .filter_map(|(key, property)| item_from_property(&metadata.id, key, property))
+                .collect();
+
+            if items.is_empty() {
+                return None;
+            }
+
+            Some(
+                SettingGroup::new()
+                    .title(metadata.name.clone())
+                    .items(items),
+            )
+        })
+        .collect()
+}
+
+fn item_from_property(plugin_id: &PluginId, key: &str, property: &Value) -> Option<SettingItem> {
+    let label: SharedString = property
+        .get("title")
+        .and_then(Value::as_str)
+        .unwrap_or(key)
+        .to_owned()
+        .into();
+    let desc: SharedString = property
+        .get("description")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_owned()
+        .into();
+
+    let (id, key) = (plugin_id.clone(), key.to_owned());
+    let (id2, key2) = (id.clone(), key.clone());
+
+    let field = match property.get("type").and_then(Value::as_str)? {
+        "boolean" => SettingField::checkbox(
+            move |_cx: &App| {
+                current_value(&id, &key)
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false)
+            },
+            move |val: bool, _cx: &mut App| set_value(&id2, &key2, Value::Bool(val)),
+        ),
+        "string" => match property.get("enum").and_then(Value::as_array) {
+            Some(options) => {
+                let opts: Vec<(SharedString, SharedString)> = options
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(|o| {
+                        (
+                            SharedString::from(o.to_owned()),
+                            SharedString::from(o.to_owned()),
+                        )
+                    })
+                    .collect();
+                SettingField::dropdown(
+                    opts,
+                    move |_cx: &App| {
+                        current_value(&id, &key)
+                            .and_then(|v| v.as_str().map(|s| SharedString::from(s.to_owned())))
+                            .unwrap_or_default()
+                    },
+                    move |val: SharedString, _cx: &mut App| {
+                        set_value(&id2, &key2, Value::String(val.to_string()))
+                    },
+                )
+            }
+            None => SettingField::input(
+                move |_cx: &App| {
+                    current_value(&id, &key)
+                        .and_then(|v| v.as_str().map(|s| SharedString::from(s.to_owned())))
+                        .unwrap_or_default()
+                },
+                move |val: SharedString, _cx: &mut App| {
+                    set_value(&id2, &key2, Value::String(val.to_string()))
+                },
+            ),
+        },
+        ty @ ("integer" | "number") => {
+            let integer = ty == "integer";
+            let opts = NumberFieldOptions {
+                min: property
+                    .get("minimum")
+                    .and_then(Value::as_f64)
+                    .unwrap_or(f64::MIN),
+                max: property
+                    .get("maximum")
+                    .and_then(Value::as_f64)
+                    .unwrap_or(f64::MAX),
+                step: property
+                    .get("multipleOf")
+                    .and_then(Value::as_f64)
+                    .unwrap_or(1.0),
+            };
+            SettingField::number_input(
+                opts,
+                move |_cx: &App| {
+                    current_value(&id, &key)
+                        .and_then(|v| v.as_f64())
+                        .unwrap_or(0.0)
+                },
+                move |val: f64, _cx: &mut App| {
+                    let value = if integer {
+                        Value::from(val.round() as i64)
+                    } else {
+                        Value::from(val)
+                    };
+                    set_value(&id2, &key2, value)
+                },
+            )
+        }
+        _ => return None,
+    };
+
+    Some(SettingItem::new(label, field).description(desc))
+}
+
+fn current_value(plugin_id: &PluginId, key: &str) -> Option<Value> {
+    let pm = plugin_manager::global()?.read();
+    pm.get_plugin_settings(plugin_id)?.get(key).cloned()
+}
+
+/// Plugin settings are saved as soon as they change, not through the save
+/// bar: the plugin manager validates them against the schema and hands them
+/// to the plugin.
+fn set_value(plugin_id: &PluginId, key: &str, value: Value) {
+    let Some(pm_lock) = plugin_manager::global() else {
+        return;
+    };
+    let mut pm = pm_lock.write();
+
+    let mut settings = pm
+        .get_plugin_settings(plugin_id)
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    settings.insert(key.to_owned(), value);
+
+    if let Err(e) = pm.update_plugin_settings(plugin_id, Value::Object(settings)) {
+        tracing::error!("Failed to update settings of plugin {}: {}", plugin_id, e);
+    }
+}
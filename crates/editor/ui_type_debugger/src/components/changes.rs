@@ -0,0 +1,318 @@
+use engine_fs::type_history::{
+    MemberChange, TypeChange, TypeChangeKind, TypeDiff, DEFAULT_RENAME_THRESHOLD,
+};
+use gpui::{prelude::*, *};
+use rust_i18n::t;
+use std::path::Path;
+use std::time::SystemTime;
+use ui::StyledExt;
+use ui::{
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    scroll::ScrollbarAxis,
+    v_flex, ActiveTheme as _, Icon, IconName, Sizable as _,
+};
+
+use crate::screen::TypeDebuggerDrawer;
+
+fn format_time(time: SystemTime) -> String {
+    chrono::DateTime::<chrono::Local>::from(time)
+        .format("%H:%M:%S")
+        .to_string()
+}
+
+fn render_section_title(title: String, cx: &App) -> Div {
+    div()
+        .text_xs()
+        .font_weight(gpui::FontWeight::SEMIBOLD)
+        .text_color(cx.theme().muted_foreground)
+        .child(title)
+}
+
+fn render_change_row(marker: &'static str, color: Hsla, text: String, cx: &App) -> Div {
+    h_flex()
+        .w_full()
+        .gap_2()
+        .px_2()
+        .py_1()
+        .rounded_md()
+        .bg(color.opacity(0.08))
+        .child(
+            div()
+                .w(px(14.0))
+                .text_xs()
+                .font_weight(gpui::FontWeight::BOLD)
+                .text_color(color)
+                .child(marker),
+        )
+        .child(
+            div()
+                .flex_1()
+                .text_xs()
+                .font_family("monospace")
+                .text_color(cx.theme().foreground)
+                .child(text),
+        )
+}
+
+fn render_diff(drawer: &TypeDebuggerDrawer, diff: &TypeDiff, cx: &App) -> Div {
+    let added: Hsla = gpui::rgb(0x4CAF50).into();
+    let removed: Hsla = gpui::rgb(0xF44336).into();
+    let changed: Hsla = gpui::rgb(0xFF9800).into();
+
+    let mut rows: Vec<Div> = Vec::new();
+
+    if let Some((from, to)) = &diff.name {
+        rows.push(render_change_row("~", changed, format!("name: {} → {}", from, to), cx));
+    }
+    if let Some((from, to)) = &diff.display_name {
+        rows.push(render_change_row(
+            "~",
+            changed,
+            format!("display name: {} → {}", from, to),
+            cx,
+        ));
+    }
+    if let Some((from, to)) = &diff.category {
+        rows.push(render_change_row("~", changed, format!("category: {} → {}", from, to), cx));
+    }
+    if let Some((from, to)) = &diff.kind {
+        rows.push(render_change_row("~", changed, format!("kind: {} → {}", from, to), cx));
+    }
+    if let Some((from, to)) = &diff.file_path {
+        rows.push(render_change_row(
+            "~",
+            changed,
+            format!(
+                "path: {} → {}",
+                drawer.get_display_path(from),
+                drawer.get_display_path(to)
+            ),
+            cx,
+        ));
+    }
+
+    for change in &diff.members {
+        rows.push(match change {
+            MemberChange::Added { name, type_name } => {
+                render_change_row("+", added, format!("{}: {}", name, type_name), cx)
+            }
+            MemberChange::Removed { name, type_name } => {
+                render_change_row("-", removed, format!("{}: {}", name, type_name), cx)
+            }
+            MemberChange::Renamed {
+                from,
+                to,
+                confidence,
+            } => render_change_row(
+                "→",
+                changed,
+                format!("{} → {} ({:.0}%)", from, to, confidence * 100.0),
+                cx,
+            ),
+            MemberChange::TypeChanged { name, from, to } => render_change_row(
+                "~",
+                changed,
+                format!("{}: {} → {}", name, from, to),
+                cx,
+            ),
+        });
+    }
+
+    let header = match (diff.before_at, diff.after_at) {
+        (Some(before), Some(after)) => format!("{} → {}", format_time(before), format_time(after)),
+        _ => String::new(),
+    };
+
+    v_flex()
+        .w_full()
+        .gap_1()
+        .p_3()
+        .rounded_lg()
+        .border_1()
+        .border_color(cx.theme().border.opacity(0.5))
+        .bg(cx.theme().sidebar.opacity(0.5))
+        .child(render_section_title(header, cx))
+        .children(rows)
+}
+
+/// "What changed" view: every retained version of one type, newest diff first.
+pub fn render_change_inspector(
+    drawer: &TypeDebuggerDrawer,
+    file_path: &Path,
+    cx: &mut Context<TypeDebuggerDrawer>,
+) -> impl IntoElement {
+    let (diffs, versions) = drawer
+        .history
+        .as_ref()
+        .map(|history| {
+            (
+                history.diffs(file_path, DEFAULT_RENAME_THRESHOLD),
+                history.history(file_path),
+            )
+        })
+        .unwrap_or_default();
+    let title = versions
+        .last()
+        .map(|s| s.display_name.clone())
+        .unwrap_or_else(|| drawer.get_display_path(file_path));
+
+    v_flex()
+        .size_full()
+        .child(
+            h_flex()
+                .w_full()
+                .gap_2()
+                .px_4()
+                .py_2()
+                .items_center()
+                .border_b_1()
+                .border_color(cx.theme().border)
+                .child(
+                    Button::new("close-inspector")
+                        .ghost()
+                        .small()
+                        .icon(IconName::ArrowLeft)
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.close_inspector(cx);
+                        })),
+                )
+                .child(
+                    div()
+                        .flex_1()
+                        .text_sm()
+                        .font_weight(gpui::FontWeight::SEMIBOLD)
+                        .text_color(cx.theme().foreground)
+                        .child(format!("{} — {}", t!("TypeDebugger.Changes.WhatChanged"), title)),
+                ),
+        )
+        .child(
+            div()
+                .id("type-debugger-change-inspector")
+                .flex_1()
+                .scrollable(ScrollbarAxis::Vertical)
+                .child(
+                    v_flex()
+                        .w_full()
+                        .p_3()
+                        .gap_2()
+                        .when(diffs.is_empty(), |this| {
+                            this.child(
+                                div()
+                                    .text_sm()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(t!("TypeDebugger.Changes.NoChanges").to_string()),
+                            )
+                        })
+                        .children(diffs.iter().map(|diff| render_diff(drawer, diff, cx)))
+                        .child(render_section_title(
+                            t!("TypeDebugger.Changes.Versions").to_string(),
+                            cx,
+                        ))
+                        .children(versions.iter().rev().map(|snapshot| {
+                            div()
+                                .text_xs()
+                                .font_family("monospace")
+                                .text_color(cx.theme().muted_foreground)
+                                .child(format!(
+                                    "{}  {}",
+                                    format_time(snapshot.recorded_at),
+                                    snapshot.name
+                                ))
+                        })),
+                ),
+        )
+}
+
+/// Session-wide "Recent type changes" feed, newest first.
+pub fn render_changes_feed(
+    drawer: &TypeDebuggerDrawer,
+    cx: &mut Context<TypeDebuggerDrawer>,
+) -> impl IntoElement {
+    let changes = drawer.recent_changes();
+
+    div()
+        .id("type-debugger-changes-feed")
+        .size_full()
+        .scrollable(ScrollbarAxis::Vertical)
+        .child(
+            v_flex()
+                .w_full()
+                .p_2()
+                .gap_1()
+                .when(changes.is_empty(), |this| {
+                    this.child(
+                        div()
+                            .p_4()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(t!("TypeDebugger.Changes.FeedEmpty").to_string()),
+                    )
+                })
+                .children(
+                    changes
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, change)| render_feed_entry(drawer, index, change, cx)),
+                ),
+        )
+}
+
+fn render_feed_entry(
+    drawer: &TypeDebuggerDrawer,
+    index: usize,
+    change: TypeChange,
+    cx: &mut Context<TypeDebuggerDrawer>,
+) -> impl IntoElement {
+    let (icon, label) = match change.kind {
+        TypeChangeKind::Modified => (IconName::EditPencil, t!("TypeDebugger.Changes.Modified")),
+        TypeChangeKind::Removed => (IconName::Trash, t!("TypeDebugger.Changes.Removed")),
+    };
+    let display_path = drawer.get_display_path(&change.file_path);
+    let updates = if change.update_count > 1 {
+        format!(" ×{}", change.update_count)
+    } else {
+        String::new()
+    };
+    let change_clone = change.clone();
+
+    h_flex()
+        .id(("type-change", index))
+        .w_full()
+        .gap_3()
+        .px_3()
+        .py_2()
+        .rounded_md()
+        .items_center()
+        .cursor_pointer()
+        .hover(|this| this.bg(cx.theme().secondary.opacity(0.7)))
+        .on_click(cx.listener(move |this, _, _, cx| {
+            this.jump_to_change(&change_clone, cx);
+        }))
+        .child(
+            div()
+                .text_xs()
+                .font_family("monospace")
+                .text_color(cx.theme().muted_foreground)
+                .child(format_time(change.last_at)),
+        )
+        .child(Icon::new(icon).size_3().text_color(cx.theme().muted_foreground))
+        .child(
+            v_flex()
+                .flex_1()
+                .child(
+                    div()
+                        .text_sm()
+                        .font_weight(gpui::FontWeight::SEMIBOLD)
+                        .text_color(cx.theme().foreground)
+                        .child(format!("{} — {}{}", change.name, label, updates)),
+                )
+                .child(
+                    div()
+                        .text_xs()
+                        .font_family("monospace")
+                        .text_color(cx.theme().muted_foreground)
+                        .child(display_path),
+                ),
+        )
+}
@@ -101,6 +101,17 @@ pub fn render_header(
                 .child(
                     h_flex()
                         .gap_2()
+                        .child(
+                            Button::new("toggle-changes-feed")
+                                .ghost()
+                                .small()
+                                .icon(IconName::Clock)
+                                .selected(drawer.show_changes_feed)
+                                .tooltip(t!("TypeDebugger.Changes.RecentChanges").to_string())
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.toggle_changes_feed(cx);
+                                })),
+                        )
                         .child(
                             Button::new("toggle-grouping")
                                 .ghost()
@@ -356,7 +367,23 @@ pub fn render_type_item(
                                     .font_family("monospace")
                                     .text_color(cx.theme().muted_foreground)
                                     .child(format!("ID: {}", type_info.uuid)),
-                            ),
+                            )
+                            .when(drawer.has_changed(&type_info.file_path), |this| {
+                                let file_path = type_info.file_path.clone();
+                                this.child(
+                                    Button::new(SharedString::from(format!(
+                                        "what-changed-{}",
+                                        type_info.uuid
+                                    )))
+                                    .ghost()
+                                    .xsmall()
+                                    .icon(IconName::GitCommit)
+                                    .label(t!("TypeDebugger.Changes.WhatChanged").to_string())
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        this.inspect_changes(file_path.clone(), cx);
+                                    })),
+                                )
+                            }),
                     )
                     .child(
                         div()
@@ -1,7 +1,9 @@
 pub use crate::screen::TypeDebuggerDrawer;
 
+pub use changes::{render_change_inspector, render_changes_feed};
 pub use content::{render_flat_view, render_grouped_view, kind_icon, kind_color, kind_label};
 pub use header::{render_header, render_type_badge, render_empty_state, render_type_item};
 
+mod changes;
 mod content;
 mod header;
@@ -1,8 +1,11 @@
+use engine_fs::type_history::{TypeChange, TypeHistory};
 use engine_fs::UserTypeInfo as TypeInfo;
+use engine_fs::{UserTypeEvent, UserTypeRegistry};
 use gpui::{prelude::*, *};
 use plugin_editor_api::FileTypeId;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use ui::StyledExt;
 use ui::{
     h_flex,
@@ -21,6 +24,12 @@ pub struct TypeDebuggerDrawer {
     pub(crate) group_by_kind: bool,
     pub(crate) search_input: Entity<InputState>,
     pub(crate) project_root: Option<PathBuf>,
+    pub(crate) history: Option<Arc<TypeHistory>>,
+    /// Type whose "What changed" view is open
+    pub(crate) inspected_path: Option<PathBuf>,
+    pub(crate) show_changes_feed: bool,
+    /// Applies changes from the registry being watched
+    type_events: Option<Task<()>>,
 }
 
 impl TypeDebuggerDrawer {
@@ -37,9 +46,58 @@ impl TypeDebuggerDrawer {
             group_by_kind: true,
             search_input,
             project_root: None,
+            history: None,
+            inspected_path: None,
+            show_changes_feed: false,
+            type_events: None,
         }
     }
 
+    /// Shows the types in `user_types` and keeps them up to date as the
+    /// registry changes, replacing any registry watched before.
+    pub fn watch_user_types(
+        &mut self,
+        user_types: Arc<UserTypeRegistry>,
+        cx: &mut Context<Self>,
+    ) {
+        // Subscribe before reading, so nothing registered in between is missed
+        let mut rx = user_types.subscribe();
+        self.set_types(user_types.all(), cx);
+        self.set_history(Some(user_types.history()), cx);
+
+        self.type_events = Some(cx.spawn(async move |drawer, cx| loop {
+            // The registry outlives this task, so the only error is falling
+            // behind: start over from the full list
+            let event = rx.recv().await.ok();
+            let _ = cx.update(|cx| {
+                drawer.update(cx, |drawer, cx| match event {
+                    Some(event) => drawer.apply_type_event(event, cx),
+                    None => drawer.set_types(user_types.all(), cx),
+                })
+            });
+        }));
+    }
+
+    fn apply_type_event(&mut self, event: UserTypeEvent, cx: &mut Context<Self>) {
+        match event {
+            UserTypeEvent::Registered(info) | UserTypeEvent::Updated(info) => {
+                match self.types.iter_mut().find(|t| t.file_path == info.file_path) {
+                    Some(existing) => *existing = info,
+                    None => self.types.push(info),
+                }
+            }
+            UserTypeEvent::Unregistered(uuid) => {
+                self.types.retain(|t| t.uuid != uuid);
+                self.selected_index = None;
+            }
+            UserTypeEvent::Cleared => {
+                self.types.clear();
+                self.selected_index = None;
+            }
+        }
+        cx.notify();
+    }
+
     pub fn set_types(&mut self, types: Vec<TypeInfo>, cx: &mut Context<Self>) {
         self.types = types;
         self.selected_index = None;
@@ -57,6 +115,50 @@ impl TypeDebuggerDrawer {
         cx.notify();
     }
 
+    pub fn set_history(&mut self, history: Option<Arc<TypeHistory>>, cx: &mut Context<Self>) {
+        self.history = history;
+        self.inspected_path = None;
+        cx.notify();
+    }
+
+    /// Whether the type at `file_path` has more than one version this session.
+    pub(crate) fn has_changed(&self, file_path: &Path) -> bool {
+        self.history
+            .as_ref()
+            .is_some_and(|history| history.has_changed(file_path))
+    }
+
+    pub(crate) fn recent_changes(&self) -> Vec<TypeChange> {
+        self.history
+            .as_ref()
+            .map(|history| history.recent_changes())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn inspect_changes(&mut self, file_path: PathBuf, cx: &mut Context<Self>) {
+        self.inspected_path = Some(file_path);
+        cx.notify();
+    }
+
+    pub(crate) fn close_inspector(&mut self, cx: &mut Context<Self>) {
+        self.inspected_path = None;
+        cx.notify();
+    }
+
+    pub(crate) fn toggle_changes_feed(&mut self, cx: &mut Context<Self>) {
+        self.show_changes_feed = !self.show_changes_feed;
+        cx.notify();
+    }
+
+    /// Opens the type behind a feed entry and shows what changed.
+    pub(crate) fn jump_to_change(&mut self, change: &TypeChange, cx: &mut Context<Self>) {
+        cx.emit(NavigateToType {
+            file_path: Some(change.file_path.clone()),
+            type_name: change.name.clone(),
+        });
+        self.inspect_changes(change.file_path.clone(), cx);
+    }
+
     pub(crate) fn get_display_path(&self, absolute_path: &std::path::Path) -> String {
         if let Some(project_root) = &self.project_root {
             if let Ok(relative) = absolute_path.strip_prefix(project_root) {
@@ -178,7 +280,11 @@ impl Render for TypeDebuggerDrawer {
         let selected_index = self.selected_index;
         let group_by_kind = self.group_by_kind;
 
-        let content: AnyElement = if filtered_types.is_empty() {
+        let content: AnyElement = if let Some(path) = self.inspected_path.clone() {
+            crate::components::render_change_inspector(self, &path, cx).into_any_element()
+        } else if self.show_changes_feed {
+            crate::components::render_changes_feed(self, cx).into_any_element()
+        } else if filtered_types.is_empty() {
             crate::components::render_empty_state(self, cx).into_any_element()
         } else if group_by_kind {
             crate::components::render_grouped_view(self, selected_index, cx)
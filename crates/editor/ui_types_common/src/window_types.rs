@@ -27,4 +27,25 @@ pub enum WindowRequest {
     },
 }
 
+impl WindowRequest {
+    /// Stable name of the window kind, ignoring per-instance data such as paths.
+    ///
+    /// Used as the key for per-window-type preferences (e.g. UI scale).
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            WindowRequest::Entry => "Entry",
+            WindowRequest::About => "About",
+            WindowRequest::Documentation => "Documentation",
+            WindowRequest::ProjectEditor { .. } => "ProjectEditor",
+            WindowRequest::ProjectSplash { .. } => "ProjectSplash",
+            WindowRequest::FabSearch => "FabSearch",
+            WindowRequest::FileManager { .. } => "FileManager",
+            WindowRequest::DetachedPanel => "DetachedPanel",
+            WindowRequest::Component => "Component",
+            WindowRequest::CloseWindow { .. } => "CloseWindow",
+            WindowRequest::Custom { type_name } => type_name,
+        }
+    }
+}
+
 pub type WindowId = u64;
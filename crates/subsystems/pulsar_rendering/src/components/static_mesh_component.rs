@@ -13,7 +13,9 @@ use std::collections::HashMap;
 use std::sync::Mutex;
 
 use crate::asset_component::AssetComponentRegistration;
-use crate::subsystems::{MeshCache, SceneObjectCache, load_mesh_upload, resolve_asset_path};
+use crate::subsystems::{
+    MeshCache, MeshStats, SceneObjectCache, load_mesh_upload, resolve_asset_path,
+};
 
 pulsar_reflection::inventory::submit! {
     AssetComponentRegistration {
@@ -407,6 +409,7 @@ impl ComponentRuntimeBehavior for StaticMeshComponent {
                     return;
                 }
             };
+            let stats = MeshStats::of(&upload);
             let renderer = get_subsystem!(context, Renderer);
             let scene = renderer.scene_mut();
             let mid = match scene.insert_actor(SceneActor::mesh(upload)).as_mesh() {
@@ -435,7 +438,7 @@ impl ComponentRuntimeBehavior for StaticMeshComponent {
             let matid = renderer.scene_mut().insert_material(mat);
             // Store in cache
             let mc = get_subsystem!(context, MeshCache);
-            mc.insert(abs_path.clone(), (mid, matid));
+            mc.insert(abs_path.clone(), (mid, matid), stats);
             (mid, matid)
         };
 
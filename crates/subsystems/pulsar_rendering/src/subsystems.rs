@@ -3,18 +3,37 @@ use std::path::{Path, PathBuf};
 
 use helio::{MaterialId, MeshId, MeshUpload};
 
+/// Geometry size of an uploaded mesh
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MeshStats {
+    pub vertices: u64,
+    pub triangles: u64,
+}
+
+impl MeshStats {
+    pub fn of(upload: &MeshUpload) -> Self {
+        Self {
+            vertices: upload.vertices.len() as u64,
+            triangles: (upload.indices.len() / 3) as u64,
+        }
+    }
+}
+
 /// Cache of GPU-uploaded mesh geometry, keyed by the resolved asset path.
 ///
 /// Registered as a subsystem by both the game loader and editor contexts.
 /// Components check this cache before loading and uploading mesh files.
 pub struct MeshCache {
     pub upload_cache: HashMap<String, (MeshId, MaterialId)>,
+    /// Size of each cached mesh, for renderer statistics
+    pub stats: HashMap<String, MeshStats>,
 }
 
 impl MeshCache {
     pub fn new() -> Self {
         Self {
             upload_cache: HashMap::new(),
+            stats: HashMap::new(),
         }
     }
 
@@ -22,9 +41,15 @@ impl MeshCache {
         self.upload_cache.get(key).copied()
     }
 
-    pub fn insert(&mut self, key: String, ids: (MeshId, MaterialId)) {
+    pub fn insert(&mut self, key: String, ids: (MeshId, MaterialId), stats: MeshStats) {
+        self.stats.insert(key.clone(), stats);
         self.upload_cache.insert(key, ids);
     }
+
+    /// Size of the mesh cached under `key`, zero if it isn't cached
+    pub fn stats(&self, key: &str) -> MeshStats {
+        self.stats.get(key).copied().unwrap_or_default()
+    }
 }
 
 /// Per-object-instance scene cache, keyed by scene-object ID.